@@ -27,6 +27,7 @@ class Extractor : ModInitializer {
             Recipes(),
             Particles(),
             SyncedRegistries(),
+            Registries(),
             Packets(),
             Screens(),
             Tags(),
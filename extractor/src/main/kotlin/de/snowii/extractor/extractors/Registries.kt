@@ -0,0 +1,37 @@
+package de.snowii.extractor.extractors
+
+import com.google.gson.JsonElement
+import com.google.gson.JsonObject
+import de.snowii.extractor.Extractor
+import net.minecraft.registry.Registries
+import net.minecraft.registry.Registry
+import net.minecraft.server.MinecraftServer
+
+
+class Registries : Extractor.Extractor {
+    override fun fileName(): String {
+        return "registries.json"
+    }
+
+    override fun extract(server: MinecraftServer): JsonElement {
+        val registriesJson = JsonObject()
+        for (registry in arrayOf<Registry<*>>(
+            Registries.ENTITY_TYPE,
+            Registries.DATA_COMPONENT_TYPE,
+            Registries.ATTRIBUTE,
+            Registries.FLUID,
+        )) {
+            registriesJson.add(registry.key.value.toString(), mapJson(registry))
+        }
+
+        return registriesJson
+    }
+
+    private fun <T> mapJson(registry: Registry<T>): JsonObject {
+        val json = JsonObject()
+        for (entry in registry) {
+            json.addProperty(registry.getId(entry)!!.toString(), registry.getRawId(entry))
+        }
+        return json
+    }
+}
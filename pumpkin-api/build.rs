@@ -0,0 +1,14 @@
+use std::{env, process::Command};
+
+fn main() {
+    // plugins have to be built by the same compiler as the server, as Rust has no stable ABI
+    let rustc = env::var("RUSTC").unwrap_or("rustc".to_string());
+    let version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map_or("unknown".to_string(), |version| version.trim().to_string());
+    println!("cargo:rustc-env=PUMPKIN_API_RUSTC_VERSION={version}");
+    println!("cargo:rerun-if-env-changed=RUSTC");
+}
@@ -0,0 +1,388 @@
+//! Commands of plugins, built as trees like Brigadier's.
+//!
+//! ```ignore
+//! let command = Command::new("heal", "Heals a player")
+//!     .requires("myplugin.command.heal", 2)
+//!     .then(argument("target", ArgumentType::Players).executes(|context| {
+//!         let Some(ArgumentValue::Players(players)) = context.get("target") else {
+//!             return Err("No player was found".to_string());
+//!         };
+//!         context.reply(format!("Healed {} players", players.len()));
+//!         Ok(())
+//!     }));
+//! plugin_context.commands().register(command);
+//! ```
+//!
+//! Registering and unregistering takes effect on the next tick, when the command tree is sent to
+//! every online player again.
+
+use std::{collections::HashMap, fmt, sync::Arc};
+
+use pumpkin_core::math::position::WorldPosition;
+
+use crate::event::player::PlayerInfo;
+
+/// How an argument is parsed, which is also how the client checks it while it is typed
+#[derive(Clone, Debug, PartialEq)]
+pub enum ArgumentType {
+    Bool,
+    Integer {
+        min: Option<i32>,
+        max: Option<i32>,
+    },
+    Double {
+        min: Option<f64>,
+        max: Option<f64>,
+    },
+    /// A single word
+    Word,
+    /// Everything until the end of the command
+    GreedyString,
+    /// One or more online players, by name or selector
+    Players,
+    BlockPos,
+}
+
+/// A parsed argument, see [`ArgumentType`]
+#[derive(Clone, Debug, PartialEq)]
+pub enum ArgumentValue {
+    Bool(bool),
+    Integer(i32),
+    Double(f64),
+    /// A [`ArgumentType::Word`] or [`ArgumentType::GreedyString`]
+    String(String),
+    Players(Vec<PlayerInfo>),
+    BlockPos(WorldPosition),
+}
+
+/// Who runs a command
+#[derive(Clone, Debug)]
+pub struct CommandSource {
+    pub name: String,
+    /// `None` for the console and RCON
+    pub player: Option<PlayerInfo>,
+}
+
+/// A command being run
+pub struct CommandContext<'a> {
+    pub source: &'a CommandSource,
+    arguments: HashMap<String, ArgumentValue>,
+    replies: Vec<String>,
+}
+
+impl<'a> CommandContext<'a> {
+    #[must_use]
+    pub const fn new(source: &'a CommandSource, arguments: HashMap<String, ArgumentValue>) -> Self {
+        Self {
+            source,
+            arguments,
+            replies: Vec::new(),
+        }
+    }
+
+    /// The argument with the name, if the command got to it
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&ArgumentValue> {
+        self.arguments.get(name)
+    }
+
+    /// Sends the message to the source once the command finished
+    pub fn reply(&mut self, message: impl Into<String>) {
+        self.replies.push(message.into());
+    }
+
+    #[must_use]
+    pub fn into_replies(self) -> Vec<String> {
+        self.replies
+    }
+}
+
+/// Runs a command, an error is shown to the source
+pub type CommandHandler = Arc<dyn Fn(&mut CommandContext<'_>) -> Result<(), String> + Send + Sync>;
+
+/// Suggests values of an argument while it is typed
+pub trait SuggestionProvider: Send + Sync {
+    /// `input` is what was typed of the argument so far
+    fn suggest(&self, source: &CommandSource, input: &str) -> Vec<String>;
+}
+
+impl<F: Fn(&CommandSource, &str) -> Vec<String> + Send + Sync> SuggestionProvider for F {
+    fn suggest(&self, source: &CommandSource, input: &str) -> Vec<String> {
+        self(source, input)
+    }
+}
+
+/// The permission needed to use a command or one of its nodes
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Permission {
+    pub node: String,
+    /// The operator level which grants the permission, from 0 to 4
+    pub level: u8,
+}
+
+#[derive(Clone)]
+pub enum NodeKind {
+    Literal(String),
+    Argument {
+        name: String,
+        argument: ArgumentType,
+        suggestions: Option<Arc<dyn SuggestionProvider>>,
+    },
+}
+
+/// A literal or argument of a command
+#[derive(Clone)]
+pub struct CommandNode {
+    pub kind: NodeKind,
+    pub permission: Option<Permission>,
+    /// Runs if the command ends at this node
+    pub handler: Option<CommandHandler>,
+    pub children: Vec<CommandNode>,
+}
+
+/// Matches the word
+#[must_use]
+pub fn literal(name: impl Into<String>) -> CommandNode {
+    CommandNode::new(NodeKind::Literal(name.into()))
+}
+
+/// Parses an argument, which is available by its name in the [`CommandContext`]
+#[must_use]
+pub fn argument(name: impl Into<String>, argument: ArgumentType) -> CommandNode {
+    CommandNode::new(NodeKind::Argument {
+        name: name.into(),
+        argument,
+        suggestions: None,
+    })
+}
+
+impl CommandNode {
+    const fn new(kind: NodeKind) -> Self {
+        Self {
+            kind,
+            permission: None,
+            handler: None,
+            children: Vec::new(),
+        }
+    }
+
+    /// Only allows sources with the permission to use this node
+    #[must_use]
+    pub fn requires(mut self, node: impl Into<String>, level: u8) -> Self {
+        self.permission = Some(Permission {
+            node: node.into(),
+            level,
+        });
+        self
+    }
+
+    /// Suggests values while the argument is typed, does nothing on literals
+    #[must_use]
+    pub fn suggests(mut self, provider: impl SuggestionProvider + 'static) -> Self {
+        if let NodeKind::Argument { suggestions, .. } = &mut self.kind {
+            *suggestions = Some(Arc::new(provider));
+        }
+        self
+    }
+
+    #[must_use]
+    pub fn executes(
+        mut self,
+        handler: impl Fn(&mut CommandContext<'_>) -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        self.handler = Some(Arc::new(handler));
+        self
+    }
+
+    #[must_use]
+    pub fn then(mut self, child: Self) -> Self {
+        self.children.push(child);
+        self
+    }
+}
+
+/// A command with its nodes
+#[derive(Clone)]
+pub struct Command {
+    pub name: String,
+    pub aliases: Vec<String>,
+    pub description: String,
+    pub permission: Option<Permission>,
+    /// Runs if the command has no arguments
+    pub handler: Option<CommandHandler>,
+    pub children: Vec<CommandNode>,
+}
+
+impl Command {
+    #[must_use]
+    pub fn new(name: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            aliases: Vec::new(),
+            description: description.into(),
+            permission: None,
+            handler: None,
+            children: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn alias(mut self, alias: impl Into<String>) -> Self {
+        self.aliases.push(alias.into());
+        self
+    }
+
+    /// Only allows sources with the permission to use the command
+    #[must_use]
+    pub fn requires(mut self, node: impl Into<String>, level: u8) -> Self {
+        self.permission = Some(Permission {
+            node: node.into(),
+            level,
+        });
+        self
+    }
+
+    #[must_use]
+    pub fn executes(
+        mut self,
+        handler: impl Fn(&mut CommandContext<'_>) -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        self.handler = Some(Arc::new(handler));
+        self
+    }
+
+    #[must_use]
+    pub fn then(mut self, child: CommandNode) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// The names of the arguments on any path, each argument name may only be used for one type
+    #[must_use]
+    pub fn argument_types(&self) -> HashMap<&str, &ArgumentType> {
+        fn collect<'a>(nodes: &'a [CommandNode], types: &mut HashMap<&'a str, &'a ArgumentType>) {
+            for node in nodes {
+                if let NodeKind::Argument { name, argument, .. } = &node.kind {
+                    types.insert(name, argument);
+                }
+                collect(&node.children, types);
+            }
+        }
+        let mut types = HashMap::new();
+        collect(&self.children, &mut types);
+        types
+    }
+}
+
+impl fmt::Debug for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Command")
+            .field("name", &self.name)
+            .field("aliases", &self.aliases)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Where the commands of plugins are registered, implemented by the server
+pub trait CommandRegistry: Send + Sync {
+    /// Registers the command of the plugin `owner`, replacing one it registered before
+    fn register(&self, owner: &str, command: Command);
+
+    /// Unregisters the command, if the plugin `owner` registered it
+    fn unregister(&self, owner: &str, name: &str);
+}
+
+/// The commands of one plugin, which may be kept to change them later
+#[derive(Clone)]
+pub struct PluginCommands {
+    owner: String,
+    registry: Arc<dyn CommandRegistry>,
+}
+
+impl PluginCommands {
+    #[must_use]
+    pub fn new(owner: impl Into<String>, registry: Arc<dyn CommandRegistry>) -> Self {
+        Self {
+            owner: owner.into(),
+            registry,
+        }
+    }
+
+    pub fn register(&self, command: Command) {
+        self.registry.register(&self.owner, command);
+    }
+
+    pub fn unregister(&self, name: &str) {
+        self.registry.unregister(&self.owner, name);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::{
+        argument, literal, ArgumentType, ArgumentValue, Command, CommandContext, CommandSource,
+    };
+
+    #[test]
+    fn builds_tree() {
+        let command = Command::new("warp", "Teleports to a warp")
+            .alias("w")
+            .requires("warps.command.warp", 0)
+            .then(
+                literal("set")
+                    .requires("warps.command.warp.set", 2)
+                    .then(argument("name", ArgumentType::Word).executes(|_| Ok(()))),
+            )
+            .then(
+                argument("name", ArgumentType::Word)
+                    .suggests(|_: &CommandSource, input: &str| vec![format!("{input}spawn")])
+                    .executes(|_| Ok(())),
+            );
+
+        assert_eq!(command.aliases, ["w"]);
+        assert_eq!(command.children.len(), 2);
+        assert!(command.children[0].children[0].handler.is_some());
+        assert_eq!(
+            command.argument_types().get("name"),
+            Some(&&ArgumentType::Word)
+        );
+    }
+
+    #[test]
+    fn context_replies() {
+        let source = CommandSource {
+            name: "Server".to_string(),
+            player: None,
+        };
+        let command = Command::new("double", "Doubles a number").then(
+            argument(
+                "number",
+                ArgumentType::Integer {
+                    min: None,
+                    max: None,
+                },
+            )
+            .executes(|context| {
+                let Some(ArgumentValue::Integer(number)) = context.get("number") else {
+                    return Err("Missing number".to_string());
+                };
+                let doubled = number * 2;
+                context.reply(doubled.to_string());
+                Ok(())
+            }),
+        );
+        let handler = command.children[0].handler.clone().unwrap();
+
+        let mut context = CommandContext::new(
+            &source,
+            HashMap::from([("number".to_string(), ArgumentValue::Integer(21))]),
+        );
+        assert_eq!(handler(&mut context), Ok(()));
+        assert_eq!(context.into_replies(), ["42"]);
+
+        let mut context = CommandContext::new(&source, HashMap::new());
+        assert!(handler(&mut context).is_err());
+    }
+}
@@ -0,0 +1,235 @@
+//! Custom items and blocks, which vanilla clients see with the resource pack the server generates.
+//!
+//! ```ignore
+//! let content = plugin_context.content();
+//! content.register_block(
+//!     CustomBlock::new(NamespacedKey::new("gems", "ruby_ore").unwrap(), BlockAppearance::NoteBlock)
+//!         .on_interact(|player, _| {
+//!             println!("{} touched ruby ore", player.name);
+//!             true
+//!         }),
+//! )?;
+//! content.register_item(
+//!     CustomItem::new(NamespacedKey::new("gems", "ruby").unwrap(), "minecraft:paper")
+//!         .places(NamespacedKey::new("gems", "ruby_ore").unwrap()),
+//! )?;
+//! ```
+//!
+//! Custom items are vanilla items with custom model data and custom blocks are note block or
+//! mushroom block states, which the resource pack gives their own model. Textures are expected at
+//! `assets/<namespace>/textures/item/<key>.png` and `assets/<namespace>/textures/block/<key>.png`
+//! unless another texture is set, plugins add them to the pack themselves.
+
+use std::{fmt, sync::Arc};
+
+use pumpkin_core::math::position::WorldPosition;
+use uuid::Uuid;
+
+use crate::{event::player::PlayerInfo, persistent_data::NamespacedKey};
+
+/// Called when a player uses a custom item or interacts with a custom block, with the clicked block
+/// if there is one. Returning true stops what would happen otherwise, like placing a block.
+pub type InteractHandler = Arc<dyn Fn(&PlayerInfo, Option<WorldPosition>) -> bool + Send + Sync>;
+
+/// An item which is shown as a vanilla item with another model
+#[derive(Clone)]
+pub struct CustomItem {
+    pub id: NamespacedKey,
+    /// The vanilla item, e.g. `minecraft:paper`. Items without special behaviour work best, as the
+    /// client still treats it like the vanilla item
+    pub base: String,
+    /// Picked by the server if not set
+    pub custom_model_data: Option<i32>,
+    /// The texture in the resource pack, `<namespace>:item/<key>` if not set
+    pub texture: Option<String>,
+    /// The custom block the item places
+    pub block: Option<NamespacedKey>,
+    pub on_use: Option<InteractHandler>,
+}
+
+impl CustomItem {
+    #[must_use]
+    pub fn new(id: NamespacedKey, base: impl Into<String>) -> Self {
+        Self {
+            id,
+            base: base.into(),
+            custom_model_data: None,
+            texture: None,
+            block: None,
+            on_use: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn custom_model_data(mut self, custom_model_data: i32) -> Self {
+        self.custom_model_data = Some(custom_model_data);
+        self
+    }
+
+    #[must_use]
+    pub fn texture(mut self, texture: impl Into<String>) -> Self {
+        self.texture = Some(texture.into());
+        self
+    }
+
+    /// Places the custom block when used on a block
+    #[must_use]
+    pub fn places(mut self, block: NamespacedKey) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    #[must_use]
+    pub fn on_use(
+        mut self,
+        handler: impl Fn(&PlayerInfo, Option<WorldPosition>) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.on_use = Some(Arc::new(handler));
+        self
+    }
+}
+
+impl fmt::Debug for CustomItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomItem")
+            .field("id", &self.id)
+            .field("base", &self.base)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The vanilla blocks a custom block can be shown as
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlockAppearance {
+    /// Solid, breaks like wood. There are 350 of these
+    NoteBlock,
+    /// Solid, breaks fast. There are 189 of these
+    MushroomBlock,
+}
+
+/// A block which is shown as a state of a vanilla block with another model
+#[derive(Clone)]
+pub struct CustomBlock {
+    pub id: NamespacedKey,
+    pub appearance: BlockAppearance,
+    /// The texture of every face in the resource pack, `<namespace>:block/<key>` if not set
+    pub texture: Option<String>,
+    pub on_interact: Option<InteractHandler>,
+    /// Returning true keeps the block
+    pub on_break: Option<InteractHandler>,
+}
+
+impl CustomBlock {
+    #[must_use]
+    pub const fn new(id: NamespacedKey, appearance: BlockAppearance) -> Self {
+        Self {
+            id,
+            appearance,
+            texture: None,
+            on_interact: None,
+            on_break: None,
+        }
+    }
+
+    #[must_use]
+    pub fn texture(mut self, texture: impl Into<String>) -> Self {
+        self.texture = Some(texture.into());
+        self
+    }
+
+    #[must_use]
+    pub fn on_interact(
+        mut self,
+        handler: impl Fn(&PlayerInfo, Option<WorldPosition>) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.on_interact = Some(Arc::new(handler));
+        self
+    }
+
+    #[must_use]
+    pub fn on_break(
+        mut self,
+        handler: impl Fn(&PlayerInfo, Option<WorldPosition>) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.on_break = Some(Arc::new(handler));
+        self
+    }
+}
+
+impl fmt::Debug for CustomBlock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomBlock")
+            .field("id", &self.id)
+            .field("appearance", &self.appearance)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ContentError {
+    AlreadyRegistered(NamespacedKey),
+    UnknownItem(String),
+    UnknownBlock(NamespacedKey),
+    /// Another custom item of the base item uses the custom model data
+    CustomModelDataTaken(i32),
+    /// Every state of the appearance is used by other custom blocks
+    NoFreeState(BlockAppearance),
+}
+
+impl fmt::Display for ContentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AlreadyRegistered(id) => write!(f, "{id} is already registered"),
+            Self::UnknownItem(item) => write!(f, "{item} is not a vanilla item"),
+            Self::UnknownBlock(block) => write!(f, "{block} is not a registered custom block"),
+            Self::CustomModelDataTaken(data) => {
+                write!(f, "The custom model data {data} is already taken")
+            }
+            Self::NoFreeState(appearance) => {
+                write!(f, "Every state of {appearance:?} is already taken")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ContentError {}
+
+/// Where custom items and blocks are registered, implemented by the server
+pub trait ContentRegistry: Send + Sync {
+    fn register_item(&self, owner: &str, item: CustomItem) -> Result<(), ContentError>;
+
+    /// Blocks have to be registered before the items which place them
+    fn register_block(&self, owner: &str, block: CustomBlock) -> Result<(), ContentError>;
+
+    /// Gives the online player the custom item, returns false if the player or item does not exist
+    fn give(&self, player: Uuid, item: &NamespacedKey, count: u32) -> bool;
+}
+
+/// The custom content of one plugin
+#[derive(Clone)]
+pub struct PluginContent {
+    owner: String,
+    registry: Arc<dyn ContentRegistry>,
+}
+
+impl PluginContent {
+    #[must_use]
+    pub fn new(owner: impl Into<String>, registry: Arc<dyn ContentRegistry>) -> Self {
+        Self {
+            owner: owner.into(),
+            registry,
+        }
+    }
+
+    pub fn register_item(&self, item: CustomItem) -> Result<(), ContentError> {
+        self.registry.register_item(&self.owner, item)
+    }
+
+    pub fn register_block(&self, block: CustomBlock) -> Result<(), ContentError> {
+        self.registry.register_block(&self.owner, block)
+    }
+
+    pub fn give(&self, player: Uuid, item: &NamespacedKey, count: u32) -> bool {
+        self.registry.give(player, item, count)
+    }
+}
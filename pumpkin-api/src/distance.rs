@@ -0,0 +1,25 @@
+//! Changing how far single players see and how far around them the world is simulated, e.g. less
+//! for players on a slow connection.
+//!
+//! ```ignore
+//! let distances = plugin_context.distances();
+//! distances.set_view_distance(player, Some(6));
+//! ```
+//!
+//! An override replaces the server's distance for the player until it is reset with `None` or
+//! the player leaves. Clients never see more than they ask for, and the simulation distance is
+//! at most the view distance.
+
+use uuid::Uuid;
+
+pub trait DistanceRegistry: Send + Sync {
+    /// Overrides the server's view distance for the player, returns false if they are not online
+    fn set_view_distance(&self, player: Uuid, distance: Option<u8>) -> bool;
+
+    /// Overrides the server's simulation distance for the player, returns false if they are not
+    /// online
+    fn set_simulation_distance(&self, player: Uuid, distance: Option<u8>) -> bool;
+
+    /// The view and simulation distance the player has right now
+    fn distances(&self, player: Uuid) -> Option<(u8, u8)>;
+}
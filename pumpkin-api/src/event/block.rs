@@ -0,0 +1,48 @@
+use pumpkin_core::math::position::WorldPosition;
+
+use super::player::PlayerInfo;
+
+/// A block is about to be broken
+#[derive(Clone, Debug)]
+pub struct BlockBreakEvent {
+    /// The player breaking it, `None` if it was e.g. a command
+    pub player: Option<PlayerInfo>,
+    pub position: WorldPosition,
+    /// The state of the block before it breaks
+    pub state_id: u16,
+    pub(crate) cancelled: bool,
+}
+
+impl BlockBreakEvent {
+    #[must_use]
+    pub const fn new(player: Option<PlayerInfo>, position: WorldPosition, state_id: u16) -> Self {
+        Self {
+            player,
+            position,
+            state_id,
+            cancelled: false,
+        }
+    }
+}
+
+/// A player is about to place a block
+#[derive(Clone, Debug)]
+pub struct BlockPlaceEvent {
+    pub player: PlayerInfo,
+    pub position: WorldPosition,
+    /// The state the block is placed with
+    pub state_id: u16,
+    pub(crate) cancelled: bool,
+}
+
+impl BlockPlaceEvent {
+    #[must_use]
+    pub const fn new(player: PlayerInfo, position: WorldPosition, state_id: u16) -> Self {
+        Self {
+            player,
+            position,
+            state_id,
+            cancelled: false,
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use super::player::PlayerInfo;
+
+/// A living entity is about to take damage
+#[derive(Clone, Debug)]
+pub struct EntityDamageEvent {
+    pub entity_id: i32,
+    /// The player, if the entity is one
+    pub player: Option<PlayerInfo>,
+    /// In health points, two per heart. Can be changed
+    pub damage: f32,
+    pub(crate) cancelled: bool,
+}
+
+impl EntityDamageEvent {
+    #[must_use]
+    pub const fn new(entity_id: i32, player: Option<PlayerInfo>, damage: f32) -> Self {
+        Self {
+            entity_id,
+            player,
+            damage,
+            cancelled: false,
+        }
+    }
+}
+
+/// A living entity died
+#[derive(Clone, Debug)]
+pub struct EntityDeathEvent {
+    pub entity_id: i32,
+    /// The player, if the entity is one
+    pub player: Option<PlayerInfo>,
+}
+
+/// An entity is about to ride another one
+#[derive(Clone, Debug)]
+pub struct EntityMountEvent {
+    pub entity_id: i32,
+    /// The player, if the entity is one
+    pub player: Option<PlayerInfo>,
+    pub vehicle_id: i32,
+    pub(crate) cancelled: bool,
+}
+
+impl EntityMountEvent {
+    #[must_use]
+    pub const fn new(entity_id: i32, player: Option<PlayerInfo>, vehicle_id: i32) -> Self {
+        Self {
+            entity_id,
+            player,
+            vehicle_id,
+            cancelled: false,
+        }
+    }
+}
+
+/// An entity is about to get off the entity it rides. Entities which leave the world get off
+/// without this event
+#[derive(Clone, Debug)]
+pub struct EntityDismountEvent {
+    pub entity_id: i32,
+    /// The player, if the entity is one
+    pub player: Option<PlayerInfo>,
+    pub vehicle_id: i32,
+    pub(crate) cancelled: bool,
+}
+
+impl EntityDismountEvent {
+    #[must_use]
+    pub const fn new(entity_id: i32, player: Option<PlayerInfo>, vehicle_id: i32) -> Self {
+        Self {
+            entity_id,
+            player,
+            vehicle_id,
+            cancelled: false,
+        }
+    }
+}
@@ -0,0 +1,307 @@
+//! Events the server fires, which plugins listen to.
+//!
+//! Listeners are called by [`EventPriority`], the lowest first, so the highest priorities have
+//! the final say. [`Cancellable`] events which were cancelled still reach later listeners,
+//! unless they were registered to ignore cancelled events.
+
+use parking_lot::RwLock;
+
+pub mod block;
+pub mod entity;
+pub mod player;
+pub mod server;
+pub mod world;
+
+use block::{BlockBreakEvent, BlockPlaceEvent};
+use entity::{EntityDamageEvent, EntityDeathEvent, EntityDismountEvent, EntityMountEvent};
+use player::{PlayerChatEvent, PlayerInteractEvent, PlayerJoinEvent, PlayerQuitEvent};
+use server::ConfigReloadedEvent;
+use world::ChunkLoadEvent;
+
+/// The order listeners are called in
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EventPriority {
+    Lowest,
+    Low,
+    Normal,
+    High,
+    Highest,
+    /// Called last, only to observe the outcome. Should not change the event
+    Monitor,
+}
+
+/// Something that happened on the server
+pub trait Event: Send + Sized + 'static {
+    /// The listeners of this event on the bus
+    fn listeners(bus: &EventBus) -> &Listeners<Self>;
+
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+}
+
+/// An event which can be cancelled, so the server does not do what it is about
+pub trait Cancellable: Event {
+    fn set_cancelled(&mut self, cancelled: bool);
+}
+
+type Handler<E> = Box<dyn Fn(&mut E) + Send + Sync>;
+
+struct Listener<E> {
+    /// The name of the plugin which registered it
+    owner: String,
+    priority: EventPriority,
+    ignore_cancelled: bool,
+    handler: Handler<E>,
+}
+
+/// The listeners of one event, ordered by priority
+pub struct Listeners<E> {
+    listeners: RwLock<Vec<Listener<E>>>,
+}
+
+impl<E> Default for Listeners<E> {
+    fn default() -> Self {
+        Self {
+            listeners: RwLock::new(Vec::new()),
+        }
+    }
+}
+
+impl<E: Event> Listeners<E> {
+    fn register(&self, listener: Listener<E>) {
+        let mut listeners = self.listeners.write();
+        // after all listeners of the same priority, so they are called in the order they were registered
+        let index = listeners.partition_point(|other| other.priority <= listener.priority);
+        listeners.insert(index, listener);
+    }
+
+    fn unregister(&self, owner: &str) {
+        self.listeners
+            .write()
+            .retain(|listener| listener.owner != owner);
+    }
+
+    fn fire(&self, event: &mut E) {
+        // listeners may fire other events
+        for listener in self.listeners.read_recursive().iter() {
+            if listener.ignore_cancelled && event.is_cancelled() {
+                continue;
+            }
+            (listener.handler)(event);
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.listeners.read_recursive().is_empty()
+    }
+}
+
+macro_rules! event_bus {
+    (events { $($event_field:ident: $event:ty),* $(,)? }
+     cancellable { $($cancellable_field:ident: $cancellable:ty),* $(,)? }) => {
+        /// Calls the listeners of events.
+        ///
+        /// Each event has its own listeners, so plugins do not depend on how types are identified
+        /// by the compiler they were built with.
+        #[derive(Default)]
+        pub struct EventBus {
+            $($event_field: Listeners<$event>,)*
+            $($cancellable_field: Listeners<$cancellable>,)*
+        }
+
+        impl EventBus {
+            /// Removes every listener the plugin registered
+            pub fn unregister_all(&self, owner: &str) {
+                $(self.$event_field.unregister(owner);)*
+                $(self.$cancellable_field.unregister(owner);)*
+            }
+        }
+
+        $(impl Event for $event {
+            fn listeners(bus: &EventBus) -> &Listeners<Self> {
+                &bus.$event_field
+            }
+        })*
+
+        $(impl Event for $cancellable {
+            fn listeners(bus: &EventBus) -> &Listeners<Self> {
+                &bus.$cancellable_field
+            }
+
+            fn is_cancelled(&self) -> bool {
+                self.cancelled
+            }
+        }
+
+        impl Cancellable for $cancellable {
+            fn set_cancelled(&mut self, cancelled: bool) {
+                self.cancelled = cancelled;
+            }
+        })*
+    };
+}
+
+event_bus! {
+    events {
+        player_join: PlayerJoinEvent,
+        player_quit: PlayerQuitEvent,
+        entity_death: EntityDeathEvent,
+        chunk_load: ChunkLoadEvent,
+        config_reloaded: ConfigReloadedEvent,
+    }
+    cancellable {
+        player_chat: PlayerChatEvent,
+        player_interact: PlayerInteractEvent,
+        block_break: BlockBreakEvent,
+        block_place: BlockPlaceEvent,
+        entity_damage: EntityDamageEvent,
+        entity_mount: EntityMountEvent,
+        entity_dismount: EntityDismountEvent,
+    }
+}
+
+impl EventBus {
+    /// Registers a listener of the plugin `owner`.
+    ///
+    /// Listeners must not register other listeners, as the bus is locked while it calls them
+    pub fn register<E: Event>(
+        &self,
+        owner: &str,
+        priority: EventPriority,
+        ignore_cancelled: bool,
+        handler: impl Fn(&mut E) + Send + Sync + 'static,
+    ) {
+        E::listeners(self).register(Listener {
+            owner: owner.to_string(),
+            priority,
+            ignore_cancelled,
+            handler: Box::new(handler),
+        });
+    }
+
+    /// Calls every listener of the event
+    pub fn fire<E: Event>(&self, event: &mut E) {
+        E::listeners(self).fire(event);
+    }
+
+    /// Whether any plugin listens to the event, to skip building events nobody listens to
+    #[must_use]
+    pub fn has_listeners<E: Event>(&self) -> bool {
+        !E::listeners(self).is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+    use uuid::Uuid;
+
+    use super::{
+        block::BlockBreakEvent, player::PlayerInfo, world::ChunkLoadEvent, Cancellable, Event,
+        EventBus, EventPriority,
+    };
+    use pumpkin_core::math::vector2::Vector2;
+
+    fn break_event() -> BlockBreakEvent {
+        BlockBreakEvent::new(
+            Some(PlayerInfo {
+                uuid: Uuid::nil(),
+                name: "Steve".to_string(),
+            }),
+            WorldPosition(Vector3::new(0, 64, 0)),
+            1,
+        )
+    }
+
+    #[test]
+    fn priority_order() {
+        let bus = EventBus::default();
+        let order = Arc::new(Mutex::new(Vec::new()));
+        for (priority, name) in [
+            (EventPriority::High, "high"),
+            (EventPriority::Lowest, "lowest"),
+            (EventPriority::Monitor, "monitor"),
+            (EventPriority::High, "high 2"),
+        ] {
+            let order = order.clone();
+            bus.register("test", priority, false, move |_: &mut ChunkLoadEvent| {
+                order.lock().unwrap().push(name);
+            });
+        }
+        bus.fire(&mut ChunkLoadEvent {
+            world: "world".to_string(),
+            position: Vector2::new(0, 0),
+            generated: false,
+        });
+        assert_eq!(
+            *order.lock().unwrap(),
+            ["lowest", "high", "high 2", "monitor"]
+        );
+    }
+
+    #[test]
+    fn cancellation() {
+        let bus = EventBus::default();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        bus.register(
+            "test",
+            EventPriority::Low,
+            false,
+            |event: &mut BlockBreakEvent| {
+                event.set_cancelled(true);
+            },
+        );
+        let ignoring = seen.clone();
+        bus.register(
+            "test",
+            EventPriority::Normal,
+            true,
+            move |_: &mut BlockBreakEvent| ignoring.lock().unwrap().push("ignoring"),
+        );
+        let receiving = seen.clone();
+        bus.register(
+            "test",
+            EventPriority::High,
+            false,
+            move |event: &mut BlockBreakEvent| {
+                receiving.lock().unwrap().push("receiving");
+                // undo the cancellation
+                event.set_cancelled(false);
+            },
+        );
+
+        let mut event = break_event();
+        bus.fire(&mut event);
+        assert!(!event.is_cancelled());
+        assert_eq!(*seen.lock().unwrap(), ["receiving"]);
+    }
+
+    #[test]
+    fn unregister() {
+        let bus = EventBus::default();
+        bus.register(
+            "first",
+            EventPriority::Normal,
+            false,
+            |event: &mut BlockBreakEvent| event.set_cancelled(true),
+        );
+        bus.register(
+            "second",
+            EventPriority::Normal,
+            false,
+            |_: &mut BlockBreakEvent| {},
+        );
+        bus.unregister_all("first");
+        assert!(bus.has_listeners::<BlockBreakEvent>());
+
+        let mut event = break_event();
+        bus.fire(&mut event);
+        assert!(!event.is_cancelled());
+
+        bus.unregister_all("second");
+        assert!(!bus.has_listeners::<BlockBreakEvent>());
+    }
+}
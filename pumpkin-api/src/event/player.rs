@@ -0,0 +1,73 @@
+use pumpkin_core::math::position::WorldPosition;
+use uuid::Uuid;
+
+/// The player an event is about
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlayerInfo {
+    pub uuid: Uuid,
+    pub name: String,
+}
+
+/// A player joined and was spawned into their world
+#[derive(Clone, Debug)]
+pub struct PlayerJoinEvent {
+    pub player: PlayerInfo,
+}
+
+/// A player left the server
+#[derive(Clone, Debug)]
+pub struct PlayerQuitEvent {
+    pub player: PlayerInfo,
+}
+
+/// A player sent a chat message. Changing the message shows the new one unsigned
+#[derive(Clone, Debug)]
+pub struct PlayerChatEvent {
+    pub player: PlayerInfo,
+    pub message: String,
+    pub(crate) cancelled: bool,
+}
+
+impl PlayerChatEvent {
+    #[must_use]
+    pub const fn new(player: PlayerInfo, message: String) -> Self {
+        Self {
+            player,
+            message,
+            cancelled: false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InteractAction {
+    LeftClickBlock,
+    RightClickBlock,
+    RightClickAir,
+}
+
+/// A player clicked a block or into the air
+#[derive(Clone, Debug)]
+pub struct PlayerInteractEvent {
+    pub player: PlayerInfo,
+    pub action: InteractAction,
+    /// The clicked block, if any
+    pub position: Option<WorldPosition>,
+    pub(crate) cancelled: bool,
+}
+
+impl PlayerInteractEvent {
+    #[must_use]
+    pub const fn new(
+        player: PlayerInfo,
+        action: InteractAction,
+        position: Option<WorldPosition>,
+    ) -> Self {
+        Self {
+            player,
+            action,
+            position,
+            cancelled: false,
+        }
+    }
+}
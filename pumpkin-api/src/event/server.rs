@@ -0,0 +1,6 @@
+/// The configuration was reloaded, e.g. with `/pumpkin reload`
+#[derive(Clone, Debug)]
+pub struct ConfigReloadedEvent {
+    /// The names of the settings which changed, e.g. `view_distance`
+    pub changed: Vec<String>,
+}
@@ -0,0 +1,11 @@
+use pumpkin_core::math::vector2::Vector2;
+
+/// A chunk was read from disk or generated, it is not fired for chunks which already were loaded
+#[derive(Clone, Debug)]
+pub struct ChunkLoadEvent {
+    /// The name of the world's folder
+    pub world: String,
+    pub position: Vector2<i32>,
+    /// Whether the chunk did not exist yet
+    pub generated: bool,
+}
@@ -0,0 +1,39 @@
+//! The API native plugins are written against.
+//!
+//! A plugin is a dynamic library (`crate-type = ["cdylib"]`) which implements [`Plugin`] and
+//! exports it with [`declare_plugin!`]. When it is loaded, it registers listeners on the
+//! [`EventBus`] to react to what happens on the server, can register [`Command`]s, store
+//! [persistent data](persistent_data), edit [NBT](nbt), add [custom items and blocks](content), show
+//! [menus](menu), share [services](service) with other plugins, hide entities from players
+//! ([visibility]) and change how far players see ([distance]).
+//!
+//! Rust has no stable ABI, so the server only loads plugins built against the same
+//! [`API_VERSION`] with the same compiler, see [`PluginDeclaration`].
+
+pub mod command;
+pub mod content;
+pub mod distance;
+pub mod event;
+pub mod menu;
+pub mod nbt;
+pub mod persistent_data;
+pub mod plugin;
+pub mod service;
+pub mod visibility;
+
+pub use command::{Command, CommandRegistry, PluginCommands};
+pub use content::{ContentRegistry, CustomBlock, CustomItem, PluginContent};
+pub use distance::DistanceRegistry;
+pub use event::{Cancellable, Event, EventBus, EventPriority};
+pub use menu::{Menu, MenuAction, MenuItem, MenuRegistry, PluginMenus, TextPrompt};
+pub use nbt::NbtStore;
+pub use persistent_data::{NamespacedKey, PersistentDataContainer, PersistentDataStore};
+pub use plugin::{Plugin, PluginContext, PluginDeclaration, PluginMetadata};
+pub use service::{ServicePriority, ServiceRegistry};
+pub use visibility::{PluginVisibility, VisibilityRegistry};
+
+/// Increased whenever events or the plugin interface change in an incompatible way
+pub const API_VERSION: u32 = 10;
+
+/// The version of the compiler this crate was built with, e.g. `rustc 1.83.0 (90b35a623 2024-11-26)`
+pub const RUSTC_VERSION: &str = env!("PUMPKIN_API_RUSTC_VERSION");
@@ -0,0 +1,374 @@
+//! Menus, which are chest screens whose items run a handler when they are clicked, and text
+//! prompts shown as an anvil.
+//!
+//! ```ignore
+//! let menus = plugin_context.menus();
+//! let mut menu = Menu::new("Warps", 1);
+//! menu.set(
+//!     0,
+//!     MenuItem::new("minecraft:grass_block").on_click(|click| {
+//!         println!("{} wants to go to spawn", click.player.name);
+//!         MenuAction::Close
+//!     }),
+//! );
+//! menus.open(uuid, menu);
+//! ```
+//!
+//! Players can't take or put items while a menu is open, the server sends the whole screen again
+//! after every click so the client shows what the server has.
+
+use std::{fmt, sync::Arc};
+
+use uuid::Uuid;
+
+use crate::event::player::PlayerInfo;
+
+/// The slots of a chest row
+pub const ROW_SLOTS: usize = 9;
+
+/// How a slot was clicked
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClickKind {
+    Left,
+    Right,
+    ShiftLeft,
+    ShiftRight,
+    /// A hotbar key, 0 to 8, or 40 for the offhand key
+    Number(u8),
+    Drop,
+    DropStack,
+    /// Dragging, double clicks and the middle button
+    Other,
+}
+
+#[derive(Clone, Debug)]
+pub struct MenuClick {
+    pub player: PlayerInfo,
+    pub slot: usize,
+    pub kind: ClickKind,
+}
+
+/// What happens after a click or a submitted prompt
+pub enum MenuAction {
+    /// Keeps the menu open
+    Nothing,
+    Close,
+    /// Replaces the menu, e.g. with the next page
+    Open(Menu),
+    Prompt(TextPrompt),
+}
+
+pub type ClickHandler = Arc<dyn Fn(&MenuClick) -> MenuAction + Send + Sync>;
+
+/// An item in a menu, which can't be taken
+#[derive(Clone)]
+pub struct MenuItem {
+    /// The item, e.g. `minecraft:paper`
+    pub item: String,
+    pub count: u8,
+    pub custom_model_data: Option<i32>,
+    pub on_click: Option<ClickHandler>,
+}
+
+impl MenuItem {
+    #[must_use]
+    pub fn new(item: impl Into<String>) -> Self {
+        Self {
+            item: item.into(),
+            count: 1,
+            custom_model_data: None,
+            on_click: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn count(mut self, count: u8) -> Self {
+        self.count = count;
+        self
+    }
+
+    /// Shows the item with another model, see [`crate::content`]
+    #[must_use]
+    pub const fn custom_model_data(mut self, custom_model_data: i32) -> Self {
+        self.custom_model_data = Some(custom_model_data);
+        self
+    }
+
+    #[must_use]
+    pub fn on_click(
+        mut self,
+        handler: impl Fn(&MenuClick) -> MenuAction + Send + Sync + 'static,
+    ) -> Self {
+        self.on_click = Some(Arc::new(handler));
+        self
+    }
+}
+
+impl fmt::Debug for MenuItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MenuItem")
+            .field("item", &self.item)
+            .field("count", &self.count)
+            .finish_non_exhaustive()
+    }
+}
+
+pub type CloseHandler = Arc<dyn Fn(&PlayerInfo) + Send + Sync>;
+
+/// A chest screen with one to six rows
+#[derive(Clone)]
+pub struct Menu {
+    pub title: String,
+    items: Vec<Option<MenuItem>>,
+    pub on_close: Option<CloseHandler>,
+}
+
+impl Menu {
+    /// The rows are clamped to one to six
+    #[must_use]
+    pub fn new(title: impl Into<String>, rows: usize) -> Self {
+        Self {
+            title: title.into(),
+            items: vec![None; rows.clamp(1, 6) * ROW_SLOTS],
+            on_close: None,
+        }
+    }
+
+    #[must_use]
+    pub fn rows(&self) -> usize {
+        self.items.len() / ROW_SLOTS
+    }
+
+    /// Sets the item of the slot, slots outside of the menu are ignored
+    pub fn set(&mut self, slot: usize, item: MenuItem) -> &mut Self {
+        if let Some(menu_slot) = self.items.get_mut(slot) {
+            *menu_slot = Some(item);
+        }
+        self
+    }
+
+    /// Fills every empty slot, e.g. with glass panes as the background
+    pub fn fill(&mut self, item: &MenuItem) -> &mut Self {
+        for slot in self.items.iter_mut().filter(|slot| slot.is_none()) {
+            *slot = Some(item.clone());
+        }
+        self
+    }
+
+    #[must_use]
+    pub fn items(&self) -> &[Option<MenuItem>] {
+        &self.items
+    }
+
+    #[must_use]
+    pub fn on_close(mut self, handler: impl Fn(&PlayerInfo) + Send + Sync + 'static) -> Self {
+        self.on_close = Some(Arc::new(handler));
+        self
+    }
+}
+
+impl fmt::Debug for Menu {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Menu")
+            .field("title", &self.title)
+            .field("items", &self.items)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Splits items over menus, with buttons to the previous and next page in the last row
+pub struct Pages {
+    title: String,
+    rows: usize,
+    items: Vec<MenuItem>,
+    previous: MenuItem,
+    next: MenuItem,
+}
+
+impl Pages {
+    /// The rows are clamped to two to six, the last one holds the buttons
+    #[must_use]
+    pub fn new(title: impl Into<String>, rows: usize, items: Vec<MenuItem>) -> Arc<Self> {
+        Arc::new(Self {
+            title: title.into(),
+            rows: rows.clamp(2, 6),
+            items,
+            previous: MenuItem::new("minecraft:arrow"),
+            next: MenuItem::new("minecraft:arrow"),
+        })
+    }
+
+    const fn items_per_page(&self) -> usize {
+        (self.rows - 1) * ROW_SLOTS
+    }
+
+    #[must_use]
+    pub fn page_count(&self) -> usize {
+        self.items.len().div_ceil(self.items_per_page()).max(1)
+    }
+
+    /// The menu of the page, starting at 0. Pages after the last one show the last one
+    #[must_use]
+    pub fn page(self: &Arc<Self>, page: usize) -> Menu {
+        let page = page.min(self.page_count() - 1);
+        let mut menu = Menu::new(
+            format!("{} ({}/{})", self.title, page + 1, self.page_count()),
+            self.rows,
+        );
+        let items = self
+            .items
+            .iter()
+            .skip(page * self.items_per_page())
+            .take(self.items_per_page());
+        for (slot, item) in items.enumerate() {
+            menu.set(slot, item.clone());
+        }
+
+        let buttons = self.items_per_page();
+        if page > 0 {
+            let pages = self.clone();
+            menu.set(
+                buttons,
+                self.previous
+                    .clone()
+                    .on_click(move |_| MenuAction::Open(pages.page(page - 1))),
+            );
+        }
+        if page + 1 < self.page_count() {
+            let pages = self.clone();
+            menu.set(
+                buttons + ROW_SLOTS - 1,
+                self.next
+                    .clone()
+                    .on_click(move |_| MenuAction::Open(pages.page(page + 1))),
+            );
+        }
+        menu
+    }
+}
+
+pub type SubmitHandler = Arc<dyn Fn(&PlayerInfo, &str) -> MenuAction + Send + Sync>;
+
+/// Asks for a text, which is typed into the text field of an anvil and submitted by clicking the
+/// result
+#[derive(Clone)]
+pub struct TextPrompt {
+    pub title: String,
+    /// The item which is renamed, e.g. `minecraft:paper`
+    pub item: String,
+    pub on_submit: SubmitHandler,
+}
+
+impl TextPrompt {
+    #[must_use]
+    pub fn new(
+        title: impl Into<String>,
+        on_submit: impl Fn(&PlayerInfo, &str) -> MenuAction + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            item: "minecraft:paper".to_string(),
+            on_submit: Arc::new(on_submit),
+        }
+    }
+
+    #[must_use]
+    pub fn item(mut self, item: impl Into<String>) -> Self {
+        self.item = item.into();
+        self
+    }
+}
+
+impl fmt::Debug for TextPrompt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TextPrompt")
+            .field("title", &self.title)
+            .field("item", &self.item)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Where menus are shown, implemented by the server
+pub trait MenuRegistry: Send + Sync {
+    /// Shows the menu to the online player, replacing the menu they have open. Returns false if
+    /// the player is not online
+    fn open(&self, owner: &str, player: Uuid, menu: Menu) -> bool;
+
+    fn prompt(&self, owner: &str, player: Uuid, prompt: TextPrompt) -> bool;
+
+    /// Closes the menu or prompt the player has open
+    fn close(&self, player: Uuid) -> bool;
+}
+
+/// The menus of one plugin, which are closed when it is unloaded
+#[derive(Clone)]
+pub struct PluginMenus {
+    owner: String,
+    registry: Arc<dyn MenuRegistry>,
+}
+
+impl PluginMenus {
+    #[must_use]
+    pub fn new(owner: impl Into<String>, registry: Arc<dyn MenuRegistry>) -> Self {
+        Self {
+            owner: owner.into(),
+            registry,
+        }
+    }
+
+    pub fn open(&self, player: Uuid, menu: Menu) -> bool {
+        self.registry.open(&self.owner, player, menu)
+    }
+
+    pub fn prompt(&self, player: Uuid, prompt: TextPrompt) -> bool {
+        self.registry.prompt(&self.owner, player, prompt)
+    }
+
+    pub fn close(&self, player: Uuid) -> bool {
+        self.registry.close(player)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use uuid::Uuid;
+
+    use crate::event::player::PlayerInfo;
+
+    use super::{ClickKind, MenuAction, MenuClick, MenuItem, Pages};
+
+    #[test]
+    fn pages() {
+        let items = (0..20)
+            .map(|count| MenuItem::new("minecraft:stone").count(count))
+            .collect();
+        let pages = Pages::new("Stones", 2, items);
+        assert_eq!(pages.page_count(), 3);
+
+        let first = pages.page(0);
+        assert_eq!(first.title, "Stones (1/3)");
+        assert_eq!(first.items()[8].as_ref().unwrap().count, 8);
+        assert!(first.items()[9].is_none());
+        let next = first.items()[17].as_ref().unwrap();
+
+        let click = MenuClick {
+            player: PlayerInfo {
+                uuid: Uuid::nil(),
+                name: "Steve".to_string(),
+            },
+            slot: 17,
+            kind: ClickKind::Left,
+        };
+        let MenuAction::Open(second) = (next.on_click.as_ref().unwrap())(&click) else {
+            panic!("The next button opens the next page");
+        };
+        assert_eq!(second.items()[0].as_ref().unwrap().count, 9);
+        assert!(second.items()[9].is_some());
+
+        let last = pages.page(5);
+        assert_eq!(last.title, "Stones (3/3)");
+        assert_eq!(last.items()[1].as_ref().unwrap().count, 19);
+        assert!(last.items()[2].is_none());
+        assert!(last.items()[17].is_none());
+    }
+}
@@ -0,0 +1,29 @@
+//! Reading and editing the NBT of players, block entities and `/data` storages, with the same
+//! [paths](NbtPath) and [SNBT](snbt) commands use.
+//!
+//! ```ignore
+//! let path = NbtPath::parse("Inventory[{Slot:0b}].id").unwrap();
+//! if let Some(player) = plugin_context.nbt().player(uuid) {
+//!     let held = path.get(&player).map(|ids| snbt::to_snbt(&ids[0]));
+//! }
+//! plugin_context.nbt().storage("myplugin", "state", &mut |storage| {
+//!     storage.insert("started".to_string(), Value::Byte(1));
+//! });
+//! ```
+use pumpkin_core::math::position::WorldPosition;
+use uuid::Uuid;
+
+pub use pumpkin_core::nbt::{compound_matches, merge, snbt, Compound, NbtError, NbtPath, Value};
+
+/// Where plugins access NBT, implemented by the server
+pub trait NbtStore: Send + Sync {
+    /// The NBT of an online player like it is saved. It can't be changed, like with `/data`
+    fn player(&self, uuid: Uuid) -> Option<Compound>;
+    /// Runs `f` with the NBT of the block entity at the position, returns false if the block has
+    /// none. Only the custom data in `PublicBukkitValues` is kept, see
+    /// [`persistent_data`](crate::persistent_data)
+    fn block(&self, world: &str, position: WorldPosition, f: &mut dyn FnMut(&mut Compound))
+        -> bool;
+    /// Runs `f` with the `/data` storage `<namespace>:<path>`, which is saved with the first world
+    fn storage(&self, namespace: &str, path: &str, f: &mut dyn FnMut(&mut Compound));
+}
@@ -0,0 +1,45 @@
+//! Custom data of plugins on players, worlds, chunks and blocks, which is saved with them so
+//! plugins need no files of their own.
+//!
+//! ```ignore
+//! let kills = NamespacedKey::new("myplugin", "kills").unwrap();
+//! plugin_context.data().player(uuid, &mut |data| {
+//!     let count = data.get::<i32>(&kills).unwrap_or(0);
+//!     data.set(&kills, count + 1);
+//! });
+//! ```
+
+use pumpkin_core::math::{position::WorldPosition, vector2::Vector2};
+use uuid::Uuid;
+
+pub use pumpkin_core::persistent_data::{
+    NamespacedKey, PersistentDataContainer, PersistentDataType,
+};
+
+/// Where plugins access persistent data, implemented by the server.
+///
+/// Every method runs `f` with the data and returns false if there is nothing to attach it to.
+pub trait PersistentDataStore: Send + Sync {
+    /// The data of an online player, saved when they leave
+    fn player(&self, uuid: Uuid, f: &mut dyn FnMut(&mut PersistentDataContainer)) -> bool;
+
+    /// The data of the world with the folder name, saved when the server stops
+    fn world(&self, world: &str, f: &mut dyn FnMut(&mut PersistentDataContainer)) -> bool;
+
+    /// The data of a loaded chunk, saved with the chunk
+    fn chunk(
+        &self,
+        world: &str,
+        chunk: Vector2<i32>,
+        f: &mut dyn FnMut(&mut PersistentDataContainer),
+    ) -> bool;
+
+    /// The data of the block entity at the position in a loaded chunk, saved with the chunk and
+    /// removed when the block is replaced
+    fn block(
+        &self,
+        world: &str,
+        position: WorldPosition,
+        f: &mut dyn FnMut(&mut PersistentDataContainer),
+    ) -> bool;
+}
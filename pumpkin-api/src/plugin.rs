@@ -0,0 +1,216 @@
+use std::{
+    ffi::{c_char, CStr},
+    sync::Arc,
+};
+
+use crate::{
+    command::{CommandRegistry, PluginCommands},
+    content::{ContentRegistry, PluginContent},
+    distance::DistanceRegistry,
+    event::{Event, EventBus, EventPriority},
+    menu::{MenuRegistry, PluginMenus},
+    nbt::NbtStore,
+    persistent_data::PersistentDataStore,
+    service::{Service, ServicePriority, ServiceRegistry},
+    visibility::{PluginVisibility, VisibilityRegistry},
+};
+
+/// Describes a plugin
+#[derive(Clone, Debug)]
+pub struct PluginMetadata {
+    /// Unique between all loaded plugins
+    pub name: &'static str,
+    pub version: &'static str,
+    pub authors: &'static [&'static str],
+    pub description: &'static str,
+}
+
+/// A native plugin, exported with [`declare_plugin!`](crate::declare_plugin)
+pub trait Plugin: Send + Sync {
+    fn metadata(&self) -> PluginMetadata;
+
+    /// Called once the plugin was loaded, this is where listeners and commands are registered
+    fn on_load(&mut self, context: &PluginContext<'_>);
+
+    /// Called before the plugin is unloaded. Its listeners and commands are removed afterwards
+    fn on_unload(&mut self) {}
+}
+
+/// What a plugin can access while it is loaded
+pub struct PluginContext<'a> {
+    name: &'a str,
+    events: &'a EventBus,
+    commands: Arc<dyn CommandRegistry>,
+    data: Arc<dyn PersistentDataStore>,
+    nbt: Arc<dyn NbtStore>,
+    content: Arc<dyn ContentRegistry>,
+    menus: Arc<dyn MenuRegistry>,
+    services: Arc<ServiceRegistry>,
+    visibility: Arc<dyn VisibilityRegistry>,
+    distances: Arc<dyn DistanceRegistry>,
+}
+
+impl<'a> PluginContext<'a> {
+    #[must_use]
+    #[expect(clippy::too_many_arguments)]
+    pub fn new(
+        name: &'a str,
+        events: &'a EventBus,
+        commands: Arc<dyn CommandRegistry>,
+        data: Arc<dyn PersistentDataStore>,
+        nbt: Arc<dyn NbtStore>,
+        content: Arc<dyn ContentRegistry>,
+        menus: Arc<dyn MenuRegistry>,
+        services: Arc<ServiceRegistry>,
+        visibility: Arc<dyn VisibilityRegistry>,
+        distances: Arc<dyn DistanceRegistry>,
+    ) -> Self {
+        Self {
+            name,
+            events,
+            commands,
+            data,
+            nbt,
+            content,
+            menus,
+            services,
+            visibility,
+            distances,
+        }
+    }
+
+    /// The name of the plugin
+    #[must_use]
+    pub const fn name(&self) -> &str {
+        self.name
+    }
+
+    /// Registers and unregisters the plugin's commands, also after it was loaded
+    #[must_use]
+    pub fn commands(&self) -> PluginCommands {
+        PluginCommands::new(self.name, self.commands.clone())
+    }
+
+    /// Custom data on players, worlds, chunks and blocks, which may be kept to use it later
+    #[must_use]
+    pub fn data(&self) -> Arc<dyn PersistentDataStore> {
+        self.data.clone()
+    }
+
+    /// The NBT of players, block entities and storages, which may be kept to use it later
+    #[must_use]
+    pub fn nbt(&self) -> Arc<dyn NbtStore> {
+        self.nbt.clone()
+    }
+
+    /// Registers the plugin's custom items and blocks
+    #[must_use]
+    pub fn content(&self) -> PluginContent {
+        PluginContent::new(self.name, self.content.clone())
+    }
+
+    /// Shows the plugin's menus and prompts to players
+    #[must_use]
+    pub fn menus(&self) -> PluginMenus {
+        PluginMenus::new(self.name, self.menus.clone())
+    }
+
+    /// Hides entities from players
+    #[must_use]
+    pub fn visibility(&self) -> PluginVisibility {
+        PluginVisibility::new(self.name, self.visibility.clone())
+    }
+
+    /// Overrides how far players see and how far around them the world is simulated
+    #[must_use]
+    pub fn distances(&self) -> Arc<dyn DistanceRegistry> {
+        self.distances.clone()
+    }
+
+    /// The services of all plugins, which may be kept to look them up later
+    #[must_use]
+    pub fn services(&self) -> Arc<ServiceRegistry> {
+        self.services.clone()
+    }
+
+    /// Provides the service, it is removed when the plugin is unloaded
+    pub fn provide<S: Service + ?Sized>(&self, priority: ServicePriority, service: Arc<S>) {
+        self.services.register(self.name, priority, service);
+    }
+
+    /// Listens to the event, including when it was cancelled
+    pub fn listen<E: Event>(
+        &self,
+        priority: EventPriority,
+        handler: impl Fn(&mut E) + Send + Sync + 'static,
+    ) {
+        self.events.register(self.name, priority, false, handler);
+    }
+
+    /// Listens to the event, unless it was cancelled by a listener called before
+    pub fn listen_uncancelled<E: Event>(
+        &self,
+        priority: EventPriority,
+        handler: impl Fn(&mut E) + Send + Sync + 'static,
+    ) {
+        self.events.register(self.name, priority, true, handler);
+    }
+}
+
+/// The name of the static every plugin exports
+pub const DECLARATION_SYMBOL: &[u8] = b"pumpkin_plugin_declaration\0";
+
+/// [`RUSTC_VERSION`](crate::RUSTC_VERSION) as a C string
+pub const RUSTC_VERSION_C: &CStr =
+    match CStr::from_bytes_with_nul(concat!(env!("PUMPKIN_API_RUSTC_VERSION"), "\0").as_bytes()) {
+        Ok(version) => version,
+        Err(_) => panic!("The rustc version contains a nul byte"),
+    };
+
+/// Exported by every plugin as [`DECLARATION_SYMBOL`].
+///
+/// The versions come first and only use C types, so the server can check them before it touches
+/// anything which depends on the plugin being built like the server.
+#[repr(C)]
+pub struct PluginDeclaration {
+    pub api_version: u32,
+    /// A nul terminated [`RUSTC_VERSION`](crate::RUSTC_VERSION)
+    pub rustc_version: *const c_char,
+    /// Only called if both versions match the server's
+    pub create: fn() -> Box<dyn Plugin>,
+}
+
+// SAFETY: the pointer is to a static string, which is never written to
+unsafe impl Sync for PluginDeclaration {}
+
+impl PluginDeclaration {
+    /// Whether the plugin was built for this API version with this compiler
+    ///
+    /// # Safety
+    ///
+    /// `rustc_version` has to point to a nul terminated string, which is ensured by [`declare_plugin!`](crate::declare_plugin).
+    #[must_use]
+    pub unsafe fn is_compatible(&self) -> bool {
+        self.api_version == crate::API_VERSION
+            && CStr::from_ptr(self.rustc_version) == RUSTC_VERSION_C
+    }
+}
+
+/// Exports the plugin, so the server can load it.
+///
+/// ```ignore
+/// pumpkin_api::declare_plugin!(MyPlugin::default);
+/// ```
+#[macro_export]
+macro_rules! declare_plugin {
+    ($constructor:path) => {
+        #[no_mangle]
+        #[allow(non_upper_case_globals)]
+        pub static pumpkin_plugin_declaration: $crate::plugin::PluginDeclaration =
+            $crate::plugin::PluginDeclaration {
+                api_version: $crate::API_VERSION,
+                rustc_version: $crate::plugin::RUSTC_VERSION_C.as_ptr(),
+                create: || ::std::boxed::Box::new($constructor()),
+            };
+    };
+}
@@ -0,0 +1,277 @@
+//! Services plugins share, like an economy which other plugins charge players with.
+//!
+//! A service is a trait object, which any plugin can provide and every plugin can look up. The
+//! provider with the highest [`ServicePriority`] is used, so plugins can replace the built-in
+//! ones. Some services, like [`Protection`], ask every provider instead.
+//!
+//! ```ignore
+//! let services = plugin_context.services();
+//! if let Some(economy) = services.get::<dyn Economy>() {
+//!     economy.withdraw(uuid, 10.0)?;
+//! }
+//! ```
+
+use std::{fmt, sync::Arc};
+
+use parking_lot::RwLock;
+use pumpkin_core::math::position::WorldPosition;
+use uuid::Uuid;
+
+use crate::event::player::PlayerInfo;
+
+/// Which provider of a service is used, the highest one
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ServicePriority {
+    /// Used by the server's own providers
+    Lowest,
+    Low,
+    Normal,
+    High,
+    Highest,
+}
+
+/// A service trait, e.g. `dyn Economy`
+pub trait Service: Send + Sync + 'static {
+    /// The providers of this service in the registry
+    fn providers(registry: &ServiceRegistry) -> &Providers<Self>;
+}
+
+struct Provider<S: ?Sized> {
+    /// The name of the plugin which registered it
+    owner: String,
+    priority: ServicePriority,
+    service: Arc<S>,
+}
+
+/// The providers of one service, ordered by priority
+pub struct Providers<S: ?Sized> {
+    providers: RwLock<Vec<Provider<S>>>,
+}
+
+impl<S: ?Sized> Default for Providers<S> {
+    fn default() -> Self {
+        Self {
+            providers: RwLock::new(Vec::new()),
+        }
+    }
+}
+
+impl<S: ?Sized> Providers<S> {
+    fn register(&self, provider: Provider<S>) {
+        let mut providers = self.providers.write();
+        // after the providers of the same priority, so the latest one of a priority is used
+        let index = providers.partition_point(|other| other.priority <= provider.priority);
+        providers.insert(index, provider);
+    }
+
+    fn unregister(&self, owner: &str) {
+        self.providers
+            .write()
+            .retain(|provider| provider.owner != owner);
+    }
+
+    fn get(&self) -> Option<Arc<S>> {
+        self.providers
+            .read()
+            .last()
+            .map(|provider| provider.service.clone())
+    }
+
+    fn all(&self) -> Vec<Arc<S>> {
+        self.providers
+            .read()
+            .iter()
+            .rev()
+            .map(|provider| provider.service.clone())
+            .collect()
+    }
+}
+
+macro_rules! service_registry {
+    ($($field:ident: $service:ty),* $(,)?) => {
+        /// Where services are provided and looked up.
+        ///
+        /// Each service has its own providers, so plugins do not depend on how types are
+        /// identified by the compiler they were built with.
+        #[derive(Default)]
+        pub struct ServiceRegistry {
+            $($field: Providers<$service>,)*
+        }
+
+        impl ServiceRegistry {
+            /// Removes every provider the plugin registered
+            pub fn unregister_all(&self, owner: &str) {
+                $(self.$field.unregister(owner);)*
+            }
+        }
+
+        $(impl Service for $service {
+            fn providers(registry: &ServiceRegistry) -> &Providers<Self> {
+                &registry.$field
+            }
+        })*
+    };
+}
+
+service_registry! {
+    economy: dyn Economy,
+    permissions: dyn Permissions,
+    chat_format: dyn ChatFormat,
+    protection: dyn Protection,
+}
+
+impl ServiceRegistry {
+    /// Provides the service for the plugin `owner`
+    pub fn register<S: Service + ?Sized>(
+        &self,
+        owner: &str,
+        priority: ServicePriority,
+        service: Arc<S>,
+    ) {
+        S::providers(self).register(Provider {
+            owner: owner.to_string(),
+            priority,
+            service,
+        });
+    }
+
+    /// The provider of the service with the highest priority
+    #[must_use]
+    pub fn get<S: Service + ?Sized>(&self) -> Option<Arc<S>> {
+        S::providers(self).get()
+    }
+
+    /// Every provider of the service, the highest priority first
+    #[must_use]
+    pub fn all<S: Service + ?Sized>(&self) -> Vec<Arc<S>> {
+        S::providers(self).all()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EconomyError {
+    /// The amount is negative or not finite
+    InvalidAmount,
+    InsufficientFunds,
+}
+
+impl fmt::Display for EconomyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidAmount => f.write_str("The amount has to be positive"),
+            Self::InsufficientFunds => f.write_str("The balance is too low"),
+        }
+    }
+}
+
+impl std::error::Error for EconomyError {}
+
+/// The balances of players, also of offline ones
+pub trait Economy: Send + Sync {
+    fn balance(&self, player: Uuid) -> f64;
+
+    fn has(&self, player: Uuid, amount: f64) -> bool {
+        self.balance(player) >= amount
+    }
+
+    /// Adds the amount, returning the new balance
+    fn deposit(&self, player: Uuid, amount: f64) -> Result<f64, EconomyError>;
+
+    /// Takes the amount, returning the new balance
+    fn withdraw(&self, player: Uuid, amount: f64) -> Result<f64, EconomyError>;
+
+    /// The amount as shown to players, e.g. `$10.00`
+    fn format(&self, amount: f64) -> String {
+        format!("{amount:.2}")
+    }
+}
+
+/// Decides permissions before the server's own permissions
+pub trait Permissions: Send + Sync {
+    /// Whether the node is granted or denied, or None to leave it to the server
+    fn resolve(&self, player: Uuid, node: &str) -> Option<bool>;
+}
+
+/// Decides how players are shown in chat
+pub trait ChatFormat: Send + Sync {
+    /// The name chat messages are shown with, e.g. with a rank as prefix
+    fn display_name(&self, player: &PlayerInfo) -> String;
+}
+
+/// Protects regions of worlds, e.g. claims. Every provider is asked, any of them can deny
+pub trait Protection: Send + Sync {
+    /// Whether the player may place or break the block at the position, or use a bucket on it
+    fn can_build(&self, player: &PlayerInfo, position: WorldPosition) -> bool;
+
+    /// Whether explosions may destroy the block at the position
+    fn can_explode(&self, _position: WorldPosition) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+    use uuid::Uuid;
+
+    use super::{Permissions, Protection, ServicePriority, ServiceRegistry};
+    use crate::event::player::PlayerInfo;
+
+    struct Fixed(bool);
+
+    impl Permissions for Fixed {
+        fn resolve(&self, _player: Uuid, _node: &str) -> Option<bool> {
+            Some(self.0)
+        }
+    }
+
+    impl Protection for Fixed {
+        fn can_build(&self, _player: &PlayerInfo, _position: WorldPosition) -> bool {
+            self.0
+        }
+    }
+
+    fn resolve(registry: &ServiceRegistry) -> Option<bool> {
+        registry
+            .get::<dyn Permissions>()
+            .and_then(|permissions| permissions.resolve(Uuid::nil(), "test"))
+    }
+
+    #[test]
+    fn highest_priority_is_used() {
+        let registry = ServiceRegistry::default();
+        assert_eq!(resolve(&registry), None);
+
+        registry.register::<dyn Permissions>("a", ServicePriority::High, Arc::new(Fixed(true)));
+        registry.register::<dyn Permissions>("b", ServicePriority::Low, Arc::new(Fixed(false)));
+        assert_eq!(resolve(&registry), Some(true));
+
+        registry.unregister_all("a");
+        assert_eq!(resolve(&registry), Some(false));
+    }
+
+    #[test]
+    fn every_protection_is_asked() {
+        let registry = ServiceRegistry::default();
+        let can_build = || {
+            let player = PlayerInfo {
+                uuid: Uuid::nil(),
+                name: "test".to_string(),
+            };
+            registry.all::<dyn Protection>().iter().all(|protection| {
+                protection.can_build(&player, WorldPosition(Vector3::new(0, 0, 0)))
+            })
+        };
+        assert!(can_build());
+
+        registry.register::<dyn Protection>("a", ServicePriority::High, Arc::new(Fixed(true)));
+        registry.register::<dyn Protection>("b", ServicePriority::Low, Arc::new(Fixed(false)));
+        assert_eq!(registry.all::<dyn Protection>().len(), 2);
+        assert!(!can_build());
+
+        registry.unregister_all("b");
+        assert!(can_build());
+    }
+}
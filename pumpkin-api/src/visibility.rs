@@ -0,0 +1,54 @@
+//! Hiding entities from players, e.g. a vanished staff member or a character only one player
+//! talks to.
+//!
+//! ```ignore
+//! let visibility = plugin_context.visibility();
+//! visibility.hide(player, entity_id);
+//! ```
+//!
+//! The entity is removed from the player's client on the next tick and spawned again once every
+//! plugin which hid it shows it again. Hidden players stay in the tab list.
+
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+pub trait VisibilityRegistry: Send + Sync {
+    /// Hides the entity from the player, until the plugin `owner` shows it again
+    fn hide(&self, owner: &str, player: Uuid, entity_id: i32);
+
+    fn show(&self, owner: &str, player: Uuid, entity_id: i32);
+
+    /// Whether any plugin hides the entity from the player
+    fn is_hidden(&self, player: Uuid, entity_id: i32) -> bool;
+}
+
+/// What one plugin hides, which is shown again when it is unloaded
+#[derive(Clone)]
+pub struct PluginVisibility {
+    owner: String,
+    registry: Arc<dyn VisibilityRegistry>,
+}
+
+impl PluginVisibility {
+    #[must_use]
+    pub fn new(owner: impl Into<String>, registry: Arc<dyn VisibilityRegistry>) -> Self {
+        Self {
+            owner: owner.into(),
+            registry,
+        }
+    }
+
+    pub fn hide(&self, player: Uuid, entity_id: i32) {
+        self.registry.hide(&self.owner, player, entity_id);
+    }
+
+    pub fn show(&self, player: Uuid, entity_id: i32) {
+        self.registry.show(&self.owner, player, entity_id);
+    }
+
+    #[must_use]
+    pub fn is_hidden(&self, player: Uuid, entity_id: i32) -> bool {
+        self.registry.is_hidden(player, entity_id)
+    }
+}
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// Records what operators do, like running commands, changing gamemodes or banning players,
+/// one JSON object per line. Can be searched with `/auditlog`
+pub struct AuditLogConfig {
+    pub enabled: bool,
+    /// Record every command which is run, not only the ones changing players
+    pub commands: bool,
+    pub path: String,
+    /// Size in bytes after which the file is rotated, 0 never rotates it
+    pub max_file_size: u64,
+    /// How many rotated files are kept, as `<path>.1` being the newest
+    pub max_files: u32,
+}
+
+impl Default for AuditLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            commands: true,
+            path: "audit.jsonl".to_string(),
+            max_file_size: 10 * 1024 * 1024,
+            max_files: 5,
+        }
+    }
+}
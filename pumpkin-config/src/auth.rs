@@ -9,6 +9,16 @@ pub struct AuthenticationConfig {
     pub auth_url: String,
     pub prevent_proxy_connections: bool,
     pub prevent_proxy_connection_auth_url: String,
+    /// Used to find the UUID of players which are not online, e.g. for `/whitelist add`.
+    pub profile_lookup_url: String,
+    /// Mojang's public keys, which the profile keys players sign chat messages with are signed with.
+    pub public_keys_url: String,
+    /// Used to fetch the skins of players for offline skins.
+    pub profile_url: String,
+    /// Profiles of players which joined or were looked up, kept in `usercache.json`.
+    pub profile_cache: ProfileCacheConfig,
+    /// Where players get their skins from when the server is in offline mode.
+    pub offline_skins: OfflineSkinsConfig,
     /// Player profile handling.
     pub player_profile: PlayerProfileConfig,
     /// Texture handling.
@@ -24,10 +34,67 @@ impl Default for AuthenticationConfig {
             textures: Default::default(),
             auth_url: "https://sessionserver.mojang.com/session/minecraft/hasJoined?username={username}&serverId={server_hash}".to_string(),
             prevent_proxy_connection_auth_url: "https://sessionserver.mojang.com/session/minecraft/hasJoined?username={username}&serverId={server_hash}&ip={ip}".to_string(),
+            profile_lookup_url: "https://api.mojang.com/users/profiles/minecraft/{username}".to_string(),
+            public_keys_url: "https://api.minecraftservices.com/publickeys".to_string(),
+            profile_url: "https://sessionserver.mojang.com/session/minecraft/profile/{uuid}?unsigned=false".to_string(),
+            profile_cache: Default::default(),
+            offline_skins: Default::default(),
         }
     }
 }
 
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct ProfileCacheConfig {
+    pub enabled: bool,
+    /// Days after which a cached profile is looked up again.
+    pub expiry_days: u32,
+    /// The most profiles to keep, the ones used least recently are dropped first.
+    pub max_entries: usize,
+    /// Requests to Mojang's API per minute, which limits them per IP. 0 allows any amount.
+    pub lookups_per_minute: u32,
+}
+
+impl Default for ProfileCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            expiry_days: 30,
+            max_entries: 1000,
+            lookups_per_minute: 60,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct OfflineSkinsConfig {
+    pub source: SkinSource,
+    /// Used by the `url` source, `{username}` and `{uuid}` are replaced.
+    /// Has to answer with a profile like Mojang's session server.
+    pub url: String,
+}
+
+impl Default for OfflineSkinsConfig {
+    fn default() -> Self {
+        Self {
+            source: SkinSource::None,
+            url: String::new(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SkinSource {
+    /// Players have the default skins, like vanilla
+    None,
+    /// The skin of the premium account with the same name
+    Mojang,
+    /// The skin from `url`
+    Url,
+}
+
 #[derive(Deserialize, Serialize)]
 #[serde(default)]
 pub struct PlayerProfileConfig {
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// Archives the worlds as `<folder>/<world>-<time>.tar.zst` while the server runs. `/backup`
+/// makes one at any time, `enabled` also makes one every `interval_minutes`
+pub struct BackupConfig {
+    pub enabled: bool,
+    pub interval_minutes: u64,
+    pub folder: String,
+    /// Hard links the world's files instead of copying them before they are archived, which is
+    /// faster but only a snapshot if the files are replaced rather than written in place
+    pub hard_link: bool,
+    /// The zstd compression level, from 1 to 22
+    pub level: i32,
+    /// How many backups of each world are kept, 0 keeps all of them
+    pub keep_last: usize,
+    /// Days after which backups are deleted, 0 keeps them regardless of their age
+    pub max_age_days: u64,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_minutes: 60,
+            folder: "backups".to_string(),
+            hard_link: false,
+            level: 3,
+            keep_last: 24,
+            max_age_days: 0,
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// Blocks changed during a tick are sent together when the tick ends, one packet per chunk section
+pub struct BlockChangesConfig {
+    /// How many blocks of a chunk may change in one tick before the whole chunk is sent again
+    /// instead, as that is smaller than the changes by then
+    pub chunk_resend_threshold: usize,
+}
+
+impl Default for BlockChangesConfig {
+    fn default() -> Self {
+        Self {
+            chunk_resend_threshold: 4096,
+        }
+    }
+}
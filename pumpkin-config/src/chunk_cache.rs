@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// Chunks no player sees anymore stay loaded until the loaded chunks of a world take more memory
+/// than the budget, then the least recently used of them are saved and unloaded
+pub struct ChunkCacheConfig {
+    /// The memory the blocks of the loaded chunks of each world may take, chunks players see
+    /// are never unloaded even if they take more. 0 unloads chunks as soon as no player sees them
+    pub memory_budget_mb: usize,
+}
+
+impl Default for ChunkCacheConfig {
+    fn default() -> Self {
+        Self {
+            memory_budget_mb: 512,
+        }
+    }
+}
@@ -7,6 +7,10 @@ pub struct CommandsConfig {
     pub use_console: bool,
     /// Should be commands from players be logged in console?
     pub log_console: bool, // TODO: commands...
+    /// Whether operators see what commands from the console do, like vanilla's `broadcast-console-to-ops`
+    pub broadcast_console_to_ops: bool,
+    /// Whether operators see what commands from RCON do, like vanilla's `broadcast-rcon-to-ops`
+    pub broadcast_rcon_to_ops: bool,
 }
 
 impl Default for CommandsConfig {
@@ -14,6 +18,8 @@ impl Default for CommandsConfig {
         Self {
             use_console: true,
             log_console: true,
+            broadcast_console_to_ops: true,
+            broadcast_rcon_to_ops: true,
         }
     }
 }
@@ -29,6 +29,9 @@ pub struct CompressionInfo {
     /// 1 = Optimize for the best speed of encoding.
     /// 9 = Optimize for the size of data being encoded.
     pub level: u32,
+    /// Sends packets uncompressed when compressing them does not make them smaller,
+    /// e.g. already compressed data like map images
+    pub adaptive: bool,
 }
 
 impl Default for CompressionInfo {
@@ -36,6 +39,7 @@ impl Default for CompressionInfo {
         Self {
             threshold: 256,
             level: 4,
+            adaptive: false,
         }
     }
 }
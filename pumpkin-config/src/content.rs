@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// Custom items and blocks of plugins, which players only see with the generated resource pack.
+///
+/// The pack is written as a folder, which has to be zipped and served with the `resource_pack`
+/// settings.
+pub struct ContentConfig {
+    pub generate_resource_pack: bool,
+    /// The folder the resource pack is written to, its content is replaced
+    pub resource_pack_folder: String,
+    pub resource_pack_description: String,
+}
+
+impl Default for ContentConfig {
+    fn default() -> Self {
+        Self {
+            generate_resource_pack: true,
+            resource_pack_folder: "content_pack".to_string(),
+            resource_pack_description: "Custom content of the server's plugins".to_string(),
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Overrides of the dimension types and biomes synced to clients, e.g. for stylized lobby worlds.
+///
+/// Dimension types are keyed like `minecraft:overworld`, biomes like `minecraft:plains`. The
+/// registries are the same for the whole server, so the overrides apply to every world. Changes
+/// are applied by `/pumpkin reload`, which sends every player through the configuration again.
+#[derive(Deserialize, Serialize, Default, Clone, PartialEq)]
+#[serde(default)]
+pub struct DimensionEffectsConfig {
+    pub dimension_types: HashMap<String, DimensionTypeOverride>,
+    pub biomes: HashMap<String, BiomeEffectsOverride>,
+}
+
+#[derive(Deserialize, Serialize, Default, Clone, PartialEq)]
+#[serde(default)]
+pub struct DimensionTypeOverride {
+    /// The sky, fog and cloud height of `minecraft:overworld`, `minecraft:the_nether` or `minecraft:the_end`
+    pub effects: Option<String>,
+    pub ambient_light: Option<f32>,
+    /// Freezes the sky at this time of day
+    pub fixed_time: Option<i64>,
+    pub has_skylight: Option<bool>,
+    pub has_ceiling: Option<bool>,
+}
+
+/// Colors are RGB, e.g. `0x78A7FF`
+#[derive(Deserialize, Serialize, Default, Clone, PartialEq)]
+#[serde(default)]
+pub struct BiomeEffectsOverride {
+    pub fog_color: Option<i32>,
+    pub sky_color: Option<i32>,
+    pub water_color: Option<i32>,
+    pub water_fog_color: Option<i32>,
+    pub foliage_color: Option<i32>,
+    pub grass_color: Option<i32>,
+}
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// The built-in economy, which plugins use unless another plugin provides one.
+pub struct EconomyConfig {
+    pub enabled: bool,
+    /// The balance of players who never had one
+    pub starting_balance: f64,
+    /// Shown before amounts, e.g. `$10.00`
+    pub currency_symbol: String,
+    /// The file the balances are saved in
+    pub file: String,
+}
+
+impl Default for EconomyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            starting_balance: 0.0,
+            currency_symbol: "$".to_string(),
+            file: "balances.json".to_string(),
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct EntityPersistenceConfig {
+    /// Save every entity when its chunk is unloaded, like vanilla does.
+    /// If disabled only the entities enabled below are saved
+    pub persist_all: bool,
+    /// Save entities which are marked as persistence required (e.g. picked up items)
+    pub persist_persistence_required: bool,
+    /// Save entities which have a custom name (e.g. from a name tag)
+    pub persist_name_tagged: bool,
+    /// Save tamed entities
+    pub persist_tamed: bool,
+    /// The maximum number of entities loaded with a single chunk.
+    /// If 0 there is no limit
+    pub max_entities_per_chunk: u32,
+    /// The maximum number of entities of the same type loaded with a single chunk.
+    /// If 0 there is no limit
+    pub max_entities_per_type: u32,
+    /// What to do with the entities of a chunk which exceed the limits
+    pub overflow_strategy: EntityOverflowStrategy,
+    /// Log a warning when a chunk exceeds the limits
+    pub log_overflow: bool,
+}
+
+impl Default for EntityPersistenceConfig {
+    fn default() -> Self {
+        Self {
+            persist_all: true,
+            persist_persistence_required: true,
+            persist_name_tagged: true,
+            persist_tamed: true,
+            max_entities_per_chunk: 2048,
+            max_entities_per_type: 512,
+            overflow_strategy: EntityOverflowStrategy::Merge,
+            log_overflow: true,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityOverflowStrategy {
+    /// Merge entities of the same type standing in the same block, then remove what still exceeds the limits
+    Merge,
+    /// Remove every entity exceeding the limits
+    Cull,
+}
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[serde(default)]
+/// Validates which blocks and entities players break, place against or attack,
+/// against interacting from too far away, without looking at them or through blocks
+pub struct InteractionCheckConfig {
+    pub enabled: bool,
+    /// Interacting further away than the player's reach
+    pub reach: bool,
+    /// Interacting with something the player does not look at
+    pub angle: bool,
+    /// Interacting with something behind blocks
+    pub through_walls: bool,
+    /// Blocks allowed on top of the player's reach, like vanilla
+    pub additional_range: f64,
+    /// The largest angle in degrees between where the player looks and what they interact with.
+    /// Looking at anything is always within 90 degrees of it
+    pub max_angle: f64,
+    /// Up to how many milliseconds of the attacker's latency entities may have moved since the attacker saw them
+    pub latency_compensation: u32,
+}
+
+impl Default for InteractionCheckConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            reach: true,
+            angle: true,
+            through_walls: true,
+            additional_range: 1.0,
+            max_angle: 90.0,
+            latency_compensation: 300,
+        }
+    }
+}
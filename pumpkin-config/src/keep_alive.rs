@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// Keep alives check that players are still connected and measure their latency
+pub struct KeepAliveConfig {
+    /// Seconds between keep alives
+    pub interval: u64,
+    /// Seconds a player has to answer a keep alive before they are kicked
+    pub timeout: u64,
+    /// How many of the last answers the latency shown in the player list is averaged over
+    pub average_over: usize,
+}
+
+impl Default for KeepAliveConfig {
+    fn default() -> Self {
+        Self {
+            interval: 15,
+            timeout: 15,
+            average_over: 4,
+        }
+    }
+}
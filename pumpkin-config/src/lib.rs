@@ -1,38 +1,91 @@
 use log::warn;
 use logging::LoggingConfig;
-use pumpkin_core::{Difficulty, GameMode};
+use migration::Migration;
+use pumpkin_core::GameMode;
 use query::QueryConfig;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 use std::{
-    env,
-    fs,
+    env, fs,
     net::{Ipv4Addr, SocketAddr},
     path::Path,
     sync::LazyLock,
 };
 
 pub mod auth;
+pub mod dimension_effects;
 pub mod logging;
 pub mod proxy;
 pub mod query;
+pub mod report_details;
 pub mod resource_pack;
+pub mod server_links;
 
+pub use audit_log::AuditLogConfig;
 pub use auth::AuthenticationConfig;
+pub use backup::BackupConfig;
+pub use block_changes::BlockChangesConfig;
+pub use chunk_cache::ChunkCacheConfig;
 pub use commands::CommandsConfig;
 pub use compression::CompressionConfig;
+pub use content::ContentConfig;
+pub use economy::EconomyConfig;
+pub use entity_persistence::{EntityOverflowStrategy, EntityPersistenceConfig};
+pub use interaction_check::InteractionCheckConfig;
+pub use keep_alive::KeepAliveConfig;
 pub use lan_broadcast::LANBroadcastConfig;
-pub use pvp::PVPConfig;
+pub use metrics::MetricsConfig;
+pub use movement_check::{MovementCheckConfig, ViolationAction};
+pub use multi_protocol::MultiProtocolConfig;
+pub use packet_capture::PacketCaptureConfig;
+pub use plugins::{PluginsConfig, WasmCapability, WasmPluginsConfig};
+pub use pvp::{KnockbackConfig, PVPConfig};
+pub use rate_limit::{PacketRateLimits, RateLimitConfig};
 pub use rcon::RCONConfig;
+pub use runtime::{reload, runtime_config, ConfigReload, RuntimeConfig};
+pub use scripting::ScriptingConfig;
+pub use send_queue::SendQueueConfig;
+pub use server_list::ServerListConfig;
+pub use tab_list::TabListConfig;
+pub use watchdog::{StallAction, WatchdogConfig};
+pub use world::{
+    world_config, ChunkStorageKind, GameRuleSetting, GeneratorKind, SpawnersConfig, WorldConfig,
+};
 
+mod audit_log;
+mod backup;
+mod block_changes;
+mod chunk_cache;
 mod commands;
 pub mod compression;
+mod content;
+mod economy;
+mod entity_persistence;
+mod interaction_check;
+mod keep_alive;
 mod lan_broadcast;
+mod metrics;
+mod migration;
+mod movement_check;
+mod multi_protocol;
+mod packet_capture;
+mod plugins;
 mod pvp;
+mod rate_limit;
 mod rcon;
+mod runtime;
+mod scripting;
+mod send_queue;
+mod server_list;
+mod tab_list;
+mod watchdog;
+mod world;
 
+use dimension_effects::DimensionEffectsConfig;
 use proxy::ProxyConfig;
+use report_details::ReportDetailsConfig;
 use resource_pack::ResourcePackConfig;
+use server_links::ServerLinksConfig;
 
 pub static ADVANCED_CONFIG: LazyLock<AdvancedConfiguration> =
     LazyLock::new(AdvancedConfiguration::load);
@@ -51,12 +104,35 @@ pub struct AdvancedConfiguration {
     pub authentication: AuthenticationConfig,
     pub packet_compression: CompressionConfig,
     pub resource_pack: ResourcePackConfig,
+    pub server_links: ServerLinksConfig,
+    pub report_details: ReportDetailsConfig,
     pub commands: CommandsConfig,
     pub rcon: RCONConfig,
     pub pvp: PVPConfig,
     pub logging: LoggingConfig,
     pub query: QueryConfig,
     pub lan_broadcast: LANBroadcastConfig,
+    pub entity_persistence: EntityPersistenceConfig,
+    pub dimension_effects: DimensionEffectsConfig,
+    pub send_queue: SendQueueConfig,
+    pub multi_protocol: MultiProtocolConfig,
+    pub tab_list: TabListConfig,
+    pub server_list: ServerListConfig,
+    pub rate_limit: RateLimitConfig,
+    pub packet_capture: PacketCaptureConfig,
+    pub movement_check: MovementCheckConfig,
+    pub interaction_check: InteractionCheckConfig,
+    pub keep_alive: KeepAliveConfig,
+    pub audit_log: AuditLogConfig,
+    pub plugins: PluginsConfig,
+    pub scripting: ScriptingConfig,
+    pub content: ContentConfig,
+    pub economy: EconomyConfig,
+    pub metrics: MetricsConfig,
+    pub watchdog: WatchdogConfig,
+    pub backup: BackupConfig,
+    pub chunk_cache: ChunkCacheConfig,
+    pub block_changes: BlockChangesConfig,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -64,16 +140,12 @@ pub struct AdvancedConfiguration {
 pub struct BasicConfiguration {
     /// The address to bind the server to.
     pub server_address: SocketAddr,
-    /// The seed for world generation.
-    pub seed: String,
     /// The maximum number of players allowed on the server. Specifying `0` disables the limit.
     pub max_players: u32,
     /// The maximum view distance for players.
     pub view_distance: u8,
     /// The maximum simulated view distance.
     pub simulation_distance: u8,
-    /// The default game difficulty.
-    pub default_difficulty: Difficulty,
     /// Whether the Nether dimension is enabled.
     pub allow_nether: bool,
     /// Whether the server is in hardcore mode.
@@ -82,28 +154,41 @@ pub struct BasicConfiguration {
     pub online_mode: bool,
     /// Whether packet encryption is enabled. Required when online mode is enabled.
     pub encryption: bool,
-    /// The server's description displayed on the status screen.
+    /// The server's description displayed on the status screen, MiniMessage tags and `§` codes may be used.
     pub motd: String,
     pub tps: f32,
     /// The default game mode for players.
     pub default_gamemode: GameMode,
+    /// Whether players always join in the default game mode instead of the one they left in.
+    pub force_gamemode: bool,
     /// Whether to remove IPs from logs or not
     pub scrub_ips: bool,
     /// Whether to use a server favicon
     pub use_favicon: bool,
-    /// Path to server favicon
+    /// Path to the server favicon, a 64x64 PNG
     pub favicon_path: String,
+    /// Whether only players in the `whitelist.json` may join.
+    pub white_list: bool,
+    /// Whether players are kicked when they are removed from the whitelist.
+    pub enforce_whitelist: bool,
+    /// The permission level given to players made operator with `/op`.
+    pub op_permission_level: u8,
+    /// The radius around the spawn in which only operators may build, `0` disables it like vanilla.
+    /// The spawn is not protected while there are no operators.
+    pub spawn_protection: u32,
+    /// Whether chat messages have to be signed with the player's Mojang key, only used in online mode.
+    pub enforce_secure_profile: bool,
+    /// The settings of every world, which a world's own file can override.
+    pub world: WorldConfig,
 }
 
 impl Default for BasicConfiguration {
     fn default() -> Self {
         Self {
             server_address: SocketAddr::new(Ipv4Addr::new(0, 0, 0, 0).into(), 25565),
-            seed: "".to_string(),
             max_players: 100000,
             view_distance: 10,
             simulation_distance: 10,
-            default_difficulty: Difficulty::Normal,
             allow_nether: true,
             hardcore: false,
             online_mode: true,
@@ -111,15 +196,30 @@ impl Default for BasicConfiguration {
             motd: "A Blazing fast Pumpkin Server!".to_string(),
             tps: 20.0,
             default_gamemode: GameMode::Survival,
+            force_gamemode: false,
             scrub_ips: true,
             use_favicon: true,
-            favicon_path: "icon.png".to_string(),
+            favicon_path: "server-icon.png".to_string(),
+            white_list: false,
+            enforce_whitelist: false,
+            op_permission_level: 4,
+            spawn_protection: 16,
+            enforce_secure_profile: true,
+            world: WorldConfig::default(),
         }
     }
 }
 
 trait LoadConfiguration {
     fn load() -> Self
+    where
+        Self: Sized + Default + Serialize + DeserializeOwned,
+    {
+        Self::try_load().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Reads the configuration, writing the default one if there is none
+    fn try_load() -> Result<Self, String>
     where
         Self: Sized + Default + Serialize + DeserializeOwned,
     {
@@ -128,19 +228,21 @@ trait LoadConfiguration {
 
         let config = if path.exists() {
             let file_content = fs::read_to_string(path)
-                .unwrap_or_else(|_| panic!("Couldn't read configuration file at {:?}", path));
-
-            toml::from_str(&file_content).unwrap_or_else(|err| {
-                panic!(
-                    "Couldn't parse config at {:?}. Reason: {}. This is probably caused by a Config update, just delete the old Config and start Pumpkin again",
-                    path,
-                    err.message()
-                )
-            })
+                .map_err(|_| format!("Couldn't read configuration file at {:?}", path))?;
+            let file_content = migration::migrate(path, file_content, Self::migrations())?;
+
+            toml::from_str(&file_content)
+                .map_err(|err| format!("Couldn't parse config at {path:?}:\n{err}"))?
         } else {
             let content = Self::default();
+            let file_content = format!(
+                "{} = {}\n\n{}",
+                migration::VERSION_KEY,
+                Self::migrations().len(),
+                toml::to_string(&content).unwrap()
+            );
 
-            if let Err(err) = fs::write(path, toml::to_string(&content).unwrap()) {
+            if let Err(err) = fs::write(path, file_content) {
                 warn!(
                     "Couldn't write default config to {:?}. Reason: {}. This is probably caused by a Config update, just delete the old Config and start Pumpkin again",
                     path, err
@@ -150,13 +252,18 @@ trait LoadConfiguration {
             content
         };
 
-        config.validate();
-        config
+        config.validate()?;
+        Ok(config)
     }
 
     fn get_path() -> String;
 
-    fn validate(&self);
+    /// Upgrades files of older versions, the index being the version a migration upgrades from
+    fn migrations() -> &'static [Migration] {
+        &[]
+    }
+
+    fn validate(&self) -> Result<(), String>;
 }
 
 impl LoadConfiguration for AdvancedConfiguration {
@@ -164,8 +271,9 @@ impl LoadConfiguration for AdvancedConfiguration {
         env::var("FEATURES_PATH").unwrap_or(String::from("features.toml"))
     }
 
-    fn validate(&self) {
-        self.resource_pack.validate()
+    fn validate(&self) -> Result<(), String> {
+        self.resource_pack.validate()?;
+        self.report_details.validate()
     }
 }
 
@@ -174,17 +282,39 @@ impl LoadConfiguration for BasicConfiguration {
         env::var("CONFIGURATION_PATH").unwrap_or(String::from("configuration.toml"))
     }
 
-    fn validate(&self) {
-        assert!(self.view_distance >= 2, "View distance must be at least 2");
-        assert!(
-            self.view_distance <= 32,
-            "View distance must be less than 32"
-        );
-        if self.online_mode {
-            assert!(
-                self.encryption,
-                "When Online Mode is enabled, Encryption must be enabled"
-            )
+    fn migrations() -> &'static [Migration] {
+        &[migration::world_table]
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.view_distance < 2 {
+            return Err("View distance must be at least 2".to_string());
+        }
+        if self.view_distance > 32 {
+            return Err("View distance must be less than 32".to_string());
         }
+        if !(1..=4).contains(&self.op_permission_level) {
+            return Err("Op permission level must be between 1 and 4".to_string());
+        }
+        if self.online_mode && !self.encryption {
+            return Err("When Online Mode is enabled, Encryption must be enabled".to_string());
+        }
+        self.world.validate()
     }
 }
+
+/// Writes a top level setting back to the file of the basic configuration, so changes made while
+/// the server runs like `/whitelist off` are kept after restarting. The loaded configuration stays
+/// the same
+pub fn save_basic_setting(key: &str, value: toml::Value) -> Result<(), String> {
+    let path_string = BasicConfiguration::get_path();
+    let path = Path::new(&path_string);
+    let content = fs::read_to_string(path)
+        .map_err(|err| format!("Couldn't read configuration file at {path:?}: {err}"))?;
+    let mut config: toml::Table = toml::from_str(&content)
+        .map_err(|err| format!("Couldn't parse config at {path:?}:\n{err}"))?;
+    config.insert(key.to_string(), value);
+    let content = toml::to_string(&config).map_err(|err| err.to_string())?;
+    fs::write(path, content)
+        .map_err(|err| format!("Couldn't write configuration file at {path:?}: {err}"))
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use std::net::{Ipv4Addr, SocketAddr};
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// An HTTP endpoint serving the server's health in the Prometheus text format, e.g. for Grafana.
+pub struct MetricsConfig {
+    pub enabled: bool,
+    /// The address the endpoint listens on
+    pub address: SocketAddr,
+    /// The path the metrics are served at, other paths are not found
+    pub path: String,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: SocketAddr::new(Ipv4Addr::new(0, 0, 0, 0).into(), 9225),
+            path: "/metrics".to_string(),
+        }
+    }
+}
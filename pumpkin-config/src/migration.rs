@@ -0,0 +1,87 @@
+//! Upgrades configuration files written by older versions of Pumpkin.
+
+use std::{fs, path::Path};
+
+use toml::{Table, Value};
+
+/// The key holding the version of a configuration file, files without one are version 0
+pub(crate) const VERSION_KEY: &str = "config_version";
+
+/// Upgrades a file from the version of its index in the migrations to the next one
+pub(crate) type Migration = fn(&mut Table);
+
+/// Moves the world settings of the basic configuration into its `[world]` table
+pub(crate) fn world_table(config: &mut Table) {
+    let mut world = match config.remove("world") {
+        Some(Value::Table(world)) => world,
+        _ => Table::new(),
+    };
+    for (old, new) in [("seed", "seed"), ("default_difficulty", "difficulty")] {
+        if let Some(value) = config.remove(old) {
+            world.insert(new.to_string(), value);
+        }
+    }
+    config.insert("world".to_string(), Value::Table(world));
+}
+
+/// Applies the migrations the file needs and writes it again, keeping the old one as
+/// `<file>.bak`. Returns the content to read the configuration from
+pub(crate) fn migrate(
+    path: &Path,
+    content: String,
+    migrations: &[Migration],
+) -> Result<String, String> {
+    let mut config: Table = toml::from_str(&content)
+        .map_err(|err| format!("Couldn't parse config at {path:?}:\n{err}"))?;
+    let current = migrations.len();
+    let version = match config.get(VERSION_KEY) {
+        None => 0,
+        Some(Value::Integer(version)) => usize::try_from(*version)
+            .map_err(|_| format!("Invalid {VERSION_KEY} {version} in {path:?}"))?,
+        Some(_) => return Err(format!("{VERSION_KEY} in {path:?} has to be a number")),
+    };
+    if version > current {
+        return Err(format!(
+            "{path:?} was written by a newer version of Pumpkin ({VERSION_KEY} {version}, this one reads up to {current})"
+        ));
+    }
+    if version == current {
+        return Ok(content);
+    }
+
+    for migration in &migrations[version..] {
+        migration(&mut config);
+    }
+    config.insert(VERSION_KEY.to_string(), Value::Integer(current as i64));
+    let migrated = toml::to_string(&config).expect("A TOML table can be serialized");
+
+    let backup = path.with_extension("toml.bak");
+    if let Err(err) = fs::copy(path, &backup).and_then(|_| fs::write(path, &migrated)) {
+        log::warn!("Couldn't save the migrated config to {path:?}: {err}");
+    } else {
+        log::info!(
+            "Migrated {path:?} from {VERSION_KEY} {version} to {current}, the old file was kept as {backup:?}"
+        );
+    }
+    Ok(migrated)
+}
+
+#[cfg(test)]
+mod test {
+    use toml::{Table, Value};
+
+    use super::world_table;
+
+    #[test]
+    fn world_settings_move_into_world_table() {
+        let mut config: Table =
+            toml::from_str("seed = \"pumpkin\"\ndefault_difficulty = \"Hard\"\nmotd = \"hi\"\n")
+                .unwrap();
+        world_table(&mut config);
+        assert!(config.get("seed").is_none());
+        assert_eq!(config["motd"], Value::String("hi".to_string()));
+        let world = config["world"].as_table().unwrap();
+        assert_eq!(world["seed"], Value::String("pumpkin".to_string()));
+        assert_eq!(world["difficulty"], Value::String("Hard".to_string()));
+    }
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[serde(default)]
+/// Validates the movement players send, against moving faster than possible, flying or walking through blocks.
+/// Players in creative and spectator mode or with levitation or slow falling are not checked for flying,
+/// spectators neither for walking through blocks. Speed and jump boost raise the limits
+pub struct MovementCheckConfig {
+    pub enabled: bool,
+    /// Moving further than the player's speed, knockback and elytra allow
+    pub speed: bool,
+    /// Rising higher than a jump or staying in the air without being allowed to fly
+    pub fly: bool,
+    /// Moving into blocks
+    pub noclip: bool,
+    /// Ignore movement until the client confirmed a teleport, like vanilla
+    pub teleport_confirm: bool,
+    /// Blocks per tick allowed on top of the maximum speed, as tolerance for lag
+    pub speed_tolerance: f64,
+    /// Ticks a player may stay in the air without falling before it counts as flying
+    pub max_air_ticks: u32,
+    /// What happens to a player failing a check
+    pub action: ViolationAction,
+    /// Failed checks within a minute after which the player is kicked even when rubber banding.
+    /// If 0 players are never kicked for it
+    pub kick_after: u32,
+}
+
+impl Default for MovementCheckConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            speed: true,
+            fly: true,
+            noclip: true,
+            teleport_confirm: true,
+            speed_tolerance: 0.1,
+            max_air_ticks: 40,
+            action: ViolationAction::Rubberband,
+            kick_after: 60,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ViolationAction {
+    /// Teleport the player back to where they were last
+    Rubberband,
+    /// Kick the player right away
+    Kick,
+}
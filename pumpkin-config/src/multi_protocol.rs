@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// Letting clients on older protocol versions join
+pub struct MultiProtocolConfig {
+    pub enabled: bool,
+    /// The older protocol versions clients may join with
+    pub allowed_versions: Vec<u32>,
+    /// The folder with the packet tables of other allowed versions, named `<version>.json` and in
+    /// the format of Pumpkin's `assets/packets.json`. Pumpkin comes with 767 (1.21 and 1.21.1),
+    /// tables of other versions have to be generated from their packet report and put here
+    pub mappings_folder: String,
+}
+
+impl Default for MultiProtocolConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            allowed_versions: vec![767],
+            mappings_folder: "protocol_mappings".to_string(),
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// Records every packet of every connection into a file, to debug protocol issues.
+/// Captures contain everything sent, including chat messages, so only enable this while debugging
+pub struct PacketCaptureConfig {
+    pub enabled: bool,
+    /// Where the captures are written, one file per connection
+    pub directory: String,
+}
+
+impl Default for PacketCaptureConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: "captures".to_string(),
+        }
+    }
+}
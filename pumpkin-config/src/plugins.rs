@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// Native plugins, dynamic libraries which are loaded on startup
+pub struct PluginsConfig {
+    pub enabled: bool,
+    /// The folder plugins are loaded from
+    pub folder: String,
+    pub wasm: WasmPluginsConfig,
+}
+
+impl Default for PluginsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            folder: "plugins".to_string(),
+            wasm: WasmPluginsConfig::default(),
+        }
+    }
+}
+
+/// WebAssembly plugins, `.wasm` files in the plugin folder which run sandboxed.
+///
+/// They may only use the capabilities they are granted.
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct WasmPluginsConfig {
+    pub enabled: bool,
+    /// Reloads plugins whose file changed
+    pub hot_reload: bool,
+    /// How much work a plugin may do per call, roughly in instructions
+    pub fuel: u64,
+    /// Bytes of memory a plugin may use
+    pub max_memory: usize,
+    pub capabilities: Vec<WasmCapability>,
+    /// Overrides by plugin, named like its file without `.wasm`
+    pub plugins: HashMap<String, WasmPluginOverride>,
+}
+
+impl Default for WasmPluginsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            hot_reload: true,
+            fuel: 10_000_000,
+            max_memory: 16 * 1024 * 1024,
+            capabilities: vec![
+                WasmCapability::Events,
+                WasmCapability::Commands,
+                WasmCapability::Scheduler,
+            ],
+            plugins: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Default, Clone)]
+#[serde(default)]
+pub struct WasmPluginOverride {
+    pub fuel: Option<u64>,
+    pub max_memory: Option<usize>,
+    pub capabilities: Option<Vec<WasmCapability>>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WasmCapability {
+    /// Listening to events and cancelling them
+    Events,
+    /// Registering commands and replying to them
+    Commands,
+    /// Reading blocks
+    WorldRead,
+    /// Changing blocks
+    WorldWrite,
+    /// Running tasks after a delay or repeatedly
+    Scheduler,
+}
+
+impl WasmCapability {
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Events => "events",
+            Self::Commands => "commands",
+            Self::WorldRead => "world_read",
+            Self::WorldWrite => "world_write",
+            Self::Scheduler => "scheduler",
+        }
+    }
+}
+
+impl WasmPluginsConfig {
+    #[must_use]
+    pub fn fuel(&self, plugin: &str) -> u64 {
+        self.plugins
+            .get(plugin)
+            .and_then(|plugin| plugin.fuel)
+            .unwrap_or(self.fuel)
+    }
+
+    #[must_use]
+    pub fn max_memory(&self, plugin: &str) -> usize {
+        self.plugins
+            .get(plugin)
+            .and_then(|plugin| plugin.max_memory)
+            .unwrap_or(self.max_memory)
+    }
+
+    #[must_use]
+    pub fn capabilities(&self, plugin: &str) -> &[WasmCapability] {
+        self.plugins
+            .get(plugin)
+            .and_then(|plugin| plugin.capabilities.as_deref())
+            .unwrap_or(&self.capabilities)
+    }
+}
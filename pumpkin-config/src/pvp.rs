@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, Clone, PartialEq)]
 #[serde(default)]
 pub struct PVPConfig {
     /// Is PVP enabled ?
@@ -13,6 +13,11 @@ pub struct PVPConfig {
     pub knockback: bool,
     /// Should player swing when attacking?
     pub swing: bool,
+    /// How far players are pushed when being hit
+    pub knockback_profile: KnockbackConfig,
+    /// Combat like before 1.9: attacks always deal their full damage without waiting for the
+    /// weapon to recharge, and swords don't sweep
+    pub legacy_combat: bool,
 }
 
 impl Default for PVPConfig {
@@ -23,6 +28,43 @@ impl Default for PVPConfig {
             protect_creative: true,
             knockback: true,
             swing: true,
+            knockback_profile: Default::default(),
+            legacy_combat: false,
+        }
+    }
+}
+
+/// The defaults match vanilla
+#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct KnockbackConfig {
+    /// Horizontal knockback of a normal hit
+    pub horizontal: f64,
+    /// Vertical knockback, only applied to victims standing on the ground
+    pub vertical: f64,
+    /// The highest upwards velocity knockback can cause
+    pub vertical_limit: f64,
+    /// Added to the horizontal and vertical knockback of sprint hits, and for each level of the
+    /// knockback enchantment
+    pub sprint_bonus: f64,
+    /// How much of the victim's own velocity is kept
+    pub friction: f64,
+    /// Whether knockback resistance of armor, e.g. netherite, reduces knockback
+    pub apply_knockback_resistance: bool,
+    /// How much of the attacker's horizontal velocity is kept after hitting
+    pub attacker_slowdown: f64,
+}
+
+impl Default for KnockbackConfig {
+    fn default() -> Self {
+        Self {
+            horizontal: 0.5,
+            vertical: 0.5,
+            vertical_limit: 0.4,
+            sprint_bonus: 0.5,
+            friction: 0.5,
+            apply_knockback_resistance: true,
+            attacker_slowdown: 0.6,
         }
     }
 }
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[serde(default)]
+/// Limits against clients flooding the server, an IP breaking one of them is blocked for a while.
+/// Behind a proxy they apply to the IP it forwards, connections are counted once it forwarded it.
+/// A limit of 0 disables it
+pub struct RateLimitConfig {
+    pub enabled: bool,
+    /// How many connections an IP may open per minute
+    pub connections_per_minute: u32,
+    /// How many times an IP may try to log in per minute
+    pub logins_per_minute: u32,
+    /// How many packets a client may send per second
+    pub packets_per_second: PacketRateLimits,
+    /// The largest packet in bytes a client may send before playing, while playing only the protocol's limit applies
+    pub max_packet_size_before_play: u32,
+    /// How long an IP breaking a limit is blocked, in seconds
+    pub block_duration: u64,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            connections_per_minute: 30,
+            logins_per_minute: 10,
+            packets_per_second: PacketRateLimits::default(),
+            max_packet_size_before_play: 64 * 1024,
+            block_duration: 300,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[serde(default)]
+/// Packets per second by connection state
+pub struct PacketRateLimits {
+    /// The handshake and server list ping
+    pub status: u32,
+    pub login: u32,
+    pub config: u32,
+    pub play: u32,
+}
+
+impl Default for PacketRateLimits {
+    fn default() -> Self {
+        Self {
+            status: 20,
+            login: 50,
+            config: 200,
+            play: 500,
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Extra details added to the client's crash reports, e.g. where to report them
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct ReportDetailsConfig {
+    pub enabled: bool,
+    /// The details keyed by their title
+    pub details: BTreeMap<String, String>,
+}
+
+impl Default for ReportDetailsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            details: BTreeMap::new(),
+        }
+    }
+}
+
+impl ReportDetailsConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.details.len() > 32 {
+            return Err("Too many custom report details (max. 32)".to_string());
+        }
+        for (title, description) in &self.details {
+            if title.chars().count() > 128 {
+                return Err(format!(
+                    "Custom report detail title {title:?} is too long (max. 128)"
+                ));
+            }
+            if description.chars().count() > 4096 {
+                return Err(format!(
+                    "Custom report detail {title:?} is too long (max. 4096)"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
@@ -15,15 +15,13 @@ pub struct ResourcePackConfig {
 }
 
 impl ResourcePackConfig {
-    pub fn validate(&self) {
-        assert_eq!(
-            !self.resource_pack_url.is_empty(),
-            !self.resource_pack_sha1.is_empty(),
-            "Resource Pack path or Sha1 hash is missing"
-        );
-        assert!(
-            self.resource_pack_sha1.len() <= 40,
-            "Resource pack sha1 hash is too long (max. 40)"
-        )
+    pub fn validate(&self) -> Result<(), String> {
+        if self.resource_pack_url.is_empty() != self.resource_pack_sha1.is_empty() {
+            return Err("Resource Pack path or Sha1 hash is missing".to_string());
+        }
+        if self.resource_pack_sha1.len() > 40 {
+            return Err("Resource pack sha1 hash is too long (max. 40)".to_string());
+        }
+        Ok(())
     }
 }
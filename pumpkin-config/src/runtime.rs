@@ -0,0 +1,101 @@
+use std::sync::{Arc, LazyLock, RwLock};
+
+use crate::{
+    dimension_effects::DimensionEffectsConfig, AdvancedConfiguration, BasicConfiguration,
+    InteractionCheckConfig, LoadConfiguration, MovementCheckConfig, PVPConfig, RateLimitConfig,
+    ADVANCED_CONFIG, BASIC_CONFIG,
+};
+
+macro_rules! runtime_config {
+    (basic { $($basic:ident: $basic_type:ty),* $(,)? }
+     advanced { $($advanced:ident: $advanced_type:ty),* $(,)? }) => {
+        /// The settings which can change while the server runs, see [`reload`].
+        ///
+        /// Every other setting is only read when the server starts.
+        #[derive(Clone, PartialEq)]
+        pub struct RuntimeConfig {
+            $(pub $basic: $basic_type,)*
+            $(pub $advanced: $advanced_type,)*
+        }
+
+        impl RuntimeConfig {
+            fn new(basic: &BasicConfiguration, advanced: &AdvancedConfiguration) -> Self {
+                Self {
+                    $($basic: basic.$basic.clone(),)*
+                    $($advanced: advanced.$advanced.clone(),)*
+                }
+            }
+
+            /// The names of the settings which are different in `other`
+            #[must_use]
+            pub fn changes(&self, other: &Self) -> Vec<&'static str> {
+                let mut changes = Vec::new();
+                $(if self.$basic != other.$basic {
+                    changes.push(stringify!($basic));
+                })*
+                $(if self.$advanced != other.$advanced {
+                    changes.push(stringify!($advanced));
+                })*
+                changes
+            }
+        }
+    };
+}
+
+runtime_config! {
+    basic {
+        motd: String,
+        max_players: u32,
+        view_distance: u8,
+    }
+    advanced {
+        rate_limit: RateLimitConfig,
+        pvp: PVPConfig,
+        movement_check: MovementCheckConfig,
+        interaction_check: InteractionCheckConfig,
+        dimension_effects: DimensionEffectsConfig,
+    }
+}
+
+static RUNTIME_CONFIG: LazyLock<RwLock<Arc<RuntimeConfig>>> = LazyLock::new(|| {
+    RwLock::new(Arc::new(RuntimeConfig::new(
+        &BASIC_CONFIG,
+        &ADVANCED_CONFIG,
+    )))
+});
+
+/// The current settings which can change while the server runs
+pub fn runtime_config() -> Arc<RuntimeConfig> {
+    RUNTIME_CONFIG
+        .read()
+        .expect("The runtime config is never poisoned")
+        .clone()
+}
+
+/// The settings before and after a [`reload`]
+pub struct ConfigReload {
+    pub old: Arc<RuntimeConfig>,
+    pub new: Arc<RuntimeConfig>,
+}
+
+impl ConfigReload {
+    #[must_use]
+    pub fn changes(&self) -> Vec<&'static str> {
+        self.old.changes(&self.new)
+    }
+}
+
+/// Reads the configuration files again and applies the settings of [`RuntimeConfig`], keeping the
+/// current ones if a file is invalid
+pub fn reload() -> Result<ConfigReload, String> {
+    let basic = BasicConfiguration::try_load()?;
+    let advanced = AdvancedConfiguration::try_load()?;
+    let new = Arc::new(RuntimeConfig::new(&basic, &advanced));
+    let old = std::mem::replace(
+        &mut *RUNTIME_CONFIG
+            .write()
+            .expect("The runtime config is never poisoned"),
+        new.clone(),
+    );
+    Ok(ConfigReload { old, new })
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// Rhai scripts, `.rhai` files which can register commands, listen to events and change the world
+/// without compiling a plugin. Reloaded with `/script reload`
+pub struct ScriptingConfig {
+    pub enabled: bool,
+    /// The folder scripts are loaded from
+    pub folder: String,
+    /// How many operations a script may run per call, 0 is unlimited
+    pub max_operations: u64,
+}
+
+impl Default for ScriptingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            folder: "scripts".to_string(),
+            max_operations: 1_000_000,
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// Limits for the packets waiting to be written to a player's connection
+pub struct SendQueueConfig {
+    /// How many chunks are sent to a player per tick at most, fewer if the client asks for fewer
+    pub chunks_per_tick: u32,
+    /// How many chunks may wait to be sent before loading more chunks for the player is paused
+    pub max_queued_chunks: usize,
+    /// How many bytes of other packets may wait to be sent before the player is disconnected
+    pub max_queued_bytes: usize,
+}
+
+impl Default for SendQueueConfig {
+    fn default() -> Self {
+        Self {
+            chunks_per_tick: 16,
+            max_queued_chunks: 512,
+            max_queued_bytes: 8 * 1024 * 1024,
+        }
+    }
+}
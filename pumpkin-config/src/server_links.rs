@@ -0,0 +1,43 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Links shown in the client's pause menu, empty links are not sent
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct ServerLinksConfig {
+    pub enabled: bool,
+    pub bug_report: String,
+    pub community_guidelines: String,
+    pub support: String,
+    pub status: String,
+    pub feedback: String,
+    pub community: String,
+    pub website: String,
+    pub forums: String,
+    pub news: String,
+    pub announcements: String,
+    /// Links with a custom label, keyed by the label.
+    ///
+    /// A label may be a translation key, clients which know the key show it in their language.
+    pub custom: BTreeMap<String, String>,
+}
+
+impl Default for ServerLinksConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            bug_report: String::new(),
+            community_guidelines: String::new(),
+            support: String::new(),
+            status: String::new(),
+            feedback: String::new(),
+            community: String::new(),
+            website: String::new(),
+            forums: String::new(),
+            news: String::new(),
+            announcements: String::new(),
+            custom: BTreeMap::new(),
+        }
+    }
+}
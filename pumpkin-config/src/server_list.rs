@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// What the server list shows next to the MOTD
+pub struct ServerListConfig {
+    /// Hides how many players are online, the client shows `???` instead
+    pub hide_player_count: bool,
+    /// How many online players are shown when hovering over the player count
+    pub sample_size: usize,
+    /// Lines in MiniMessage shown when hovering over the player count instead of the online players
+    pub custom_sample: Vec<String>,
+}
+
+impl Default for ServerListConfig {
+    fn default() -> Self {
+        Self {
+            hide_player_count: false,
+            sample_size: 12,
+            custom_sample: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// The text shown above and below the player list, in MiniMessage
+pub struct TabListConfig {
+    /// Empty for no header
+    pub header: String,
+    /// Empty for no footer
+    pub footer: String,
+    /// Whether the players' ping is shown, otherwise everyone has full bars
+    pub show_latency: bool,
+}
+
+impl Default for TabListConfig {
+    fn default() -> Self {
+        Self {
+            header: String::new(),
+            footer: String::new(),
+            show_latency: true,
+        }
+    }
+}
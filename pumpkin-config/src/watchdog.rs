@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// Watches the tick loop from its own thread, to find out why the server stopped ticking.
+pub struct WatchdogConfig {
+    pub enabled: bool,
+    /// Seconds a tick can take before a warning with the stuck subsystem is logged
+    pub warning_seconds: u64,
+    /// Seconds a tick can take before a crash report is written
+    pub timeout_seconds: u64,
+    /// What is done after the crash report was written
+    pub action: StallAction,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            warning_seconds: 10,
+            timeout_seconds: 60,
+            action: StallAction::Nothing,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StallAction {
+    /// Keep waiting for the tick, it may still finish
+    Nothing,
+    /// Save what can be saved, then keep waiting
+    Save,
+    /// Save what can be saved, then exit so the server can be restarted, e.g. by a systemd or
+    /// Docker restart policy
+    Restart,
+}
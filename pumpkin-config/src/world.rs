@@ -0,0 +1,210 @@
+use std::{collections::BTreeMap, env, fmt, fs, path::Path};
+
+use pumpkin_core::Difficulty;
+use serde::{Deserialize, Serialize};
+
+use crate::BASIC_CONFIG;
+
+/// How new chunks are generated
+#[derive(Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum GeneratorKind {
+    #[default]
+    Plains,
+    Superflat,
+}
+
+/// How the chunks of a world are saved
+#[derive(Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ChunkStorageKind {
+    /// Region files like vanilla, in the `region` folder
+    #[default]
+    Anvil,
+    /// Experimental, every written chunk is appended to `chunks.kv`, which writes faster than
+    /// region files but only Pumpkin can read. `pumpkin convert` converts existing chunks
+    KeyValue,
+}
+
+/// The value of a game rule, e.g. `keepInventory = true` or `randomTickSpeed = 3`
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(untagged)]
+pub enum GameRuleSetting {
+    Bool(bool),
+    Int(i32),
+}
+
+impl fmt::Display for GameRuleSetting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool(value) => write!(f, "{value}"),
+            Self::Int(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+/// The settings of a world. The `[world]` table of the basic configuration is used for every
+/// world, `worlds/<world folder>.toml` overrides any of them for one world.
+pub struct WorldConfig {
+    /// The seed for world generation.
+    pub seed: String,
+    pub generator: GeneratorKind,
+    /// Changing it doesn't move the saved chunks, convert them first
+    pub storage: ChunkStorageKind,
+    pub difficulty: Difficulty,
+    /// The view distance in the world, it can't be more than the server's. `0` uses the server's.
+    pub view_distance: u8,
+    /// The game rules of new worlds, by their name
+    pub game_rules: BTreeMap<String, GameRuleSetting>,
+    /// Patrols, sieges, wandering traders, cats and phantoms
+    pub spawners: SpawnersConfig,
+}
+
+/// Which of the mobs vanilla spawns by itself besides natural spawning come to the world. The
+/// game rules like `doPatrolSpawning` turn them off too
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct SpawnersConfig {
+    /// Pillager patrols roaming the world by day, from the fifth day on
+    pub patrols: bool,
+    /// Zombies besieging villages at night
+    pub sieges: bool,
+    /// The wandering trader visiting players with its llamas
+    pub wandering_traders: bool,
+    /// Stray cats moving into villages
+    pub cats: bool,
+    /// Phantoms haunting players who didn't rest
+    pub phantoms: bool,
+}
+
+impl Default for SpawnersConfig {
+    fn default() -> Self {
+        Self {
+            patrols: true,
+            sieges: true,
+            wandering_traders: true,
+            cats: true,
+            phantoms: true,
+        }
+    }
+}
+
+impl Default for WorldConfig {
+    fn default() -> Self {
+        Self {
+            seed: String::new(),
+            generator: GeneratorKind::default(),
+            storage: ChunkStorageKind::default(),
+            difficulty: Difficulty::Normal,
+            view_distance: 0,
+            game_rules: BTreeMap::new(),
+            spawners: SpawnersConfig::default(),
+        }
+    }
+}
+
+impl WorldConfig {
+    /// The view distance in the world, `max` being the server's
+    #[must_use]
+    pub fn view_distance(&self, max: u8) -> u8 {
+        match self.view_distance {
+            0 => max,
+            view_distance => view_distance.min(max),
+        }
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.view_distance != 0 && !(2..=32).contains(&self.view_distance) {
+            return Err("The view distance of a world must be 0 or between 2 and 32".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// The settings of the world in the folder `name`
+pub fn world_config(name: &str) -> Result<WorldConfig, String> {
+    let folder = env::var("WORLDS_CONFIG_PATH").unwrap_or(String::from("worlds"));
+    let path = Path::new(&folder).join(format!("{name}.toml"));
+    if !path.exists() {
+        return Ok(BASIC_CONFIG.world.clone());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|err| format!("Couldn't read the world config at {path:?}: {err}"))?;
+    layer(&BASIC_CONFIG.world, &content)
+        .map_err(|err| format!("Invalid world config at {path:?}:\n{err}"))
+}
+
+/// Applies the settings of an override file to the global ones
+fn layer(global: &WorldConfig, overrides: &str) -> Result<WorldConfig, String> {
+    // checked on its own first, so errors point into the file
+    toml::from_str::<WorldConfig>(overrides).map_err(|err| err.to_string())?;
+    let overrides: toml::Table = toml::from_str(overrides).map_err(|err| err.to_string())?;
+
+    let toml::Value::Table(mut config) =
+        toml::Value::try_from(global).expect("The world config can be serialized")
+    else {
+        unreachable!("The world config is a table");
+    };
+    merge(&mut config, overrides);
+    let config: WorldConfig = toml::Value::Table(config)
+        .try_into()
+        .map_err(|err: toml::de::Error| err.to_string())?;
+    config.validate()?;
+    Ok(config)
+}
+
+/// Replaces the values of `base` with the ones of `overrides`, merging tables
+fn merge(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(table)), toml::Value::Table(value)) => merge(table, value),
+            (Some(current), value) => *current = value,
+            (None, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_core::Difficulty;
+
+    use super::{layer, GameRuleSetting, GeneratorKind, WorldConfig};
+
+    #[test]
+    fn overrides() {
+        let mut global = WorldConfig::default();
+        global.seed = "global".to_string();
+        global
+            .game_rules
+            .insert("keepInventory".to_string(), GameRuleSetting::Bool(true));
+
+        let config = layer(
+            &global,
+            "generator = \"superflat\"\nview_distance = 6\n[game_rules]\nrandomTickSpeed = 0\n",
+        )
+        .unwrap();
+        assert_eq!(config.seed, "global");
+        assert_eq!(config.generator, GeneratorKind::Superflat);
+        assert!(config.difficulty == Difficulty::Normal);
+        assert_eq!(config.view_distance(10), 6);
+        assert_eq!(config.view_distance(4), 4);
+        assert_eq!(config.game_rules.len(), 2);
+        assert_eq!(
+            config.game_rules["randomTickSpeed"],
+            GameRuleSetting::Int(0)
+        );
+
+        let config = layer(&global, "[spawners]\nphantoms = false\n").unwrap();
+        assert!(!config.spawners.phantoms);
+        assert!(config.spawners.patrols);
+
+        let err = layer(&global, "seed = 1\n").err().unwrap();
+        assert!(err.contains("line 1"), "{err}");
+        assert!(layer(&global, "sed = \"typo\"\n").is_err());
+        assert!(layer(&global, "view_distance = 64\n").is_err());
+    }
+}
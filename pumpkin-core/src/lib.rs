@@ -1,5 +1,8 @@
 pub mod gamemode;
 pub mod math;
+pub mod nbt;
+pub mod permission;
+pub mod persistent_data;
 pub mod random;
 pub mod text;
 
@@ -7,7 +10,7 @@ pub use gamemode::GameMode;
 
 use serde::{Deserialize, Serialize};
 
-#[derive(PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Difficulty {
     Peaceful,
     Easy,
@@ -50,6 +50,30 @@ impl BoundingBox {
         }
     }
 
+    /// The box moved by the offset
+    pub fn offset(&self, x: f64, y: f64, z: f64) -> Self {
+        Self {
+            min_x: self.min_x + x,
+            min_y: self.min_y + y,
+            min_z: self.min_z + z,
+            max_x: self.max_x + x,
+            max_y: self.max_y + y,
+            max_z: self.max_z + z,
+        }
+    }
+
+    /// The box grown by the amount on every side, negative amounts shrink it
+    pub fn expand(&self, amount: f64) -> Self {
+        Self {
+            min_x: self.min_x - amount,
+            min_y: self.min_y - amount,
+            min_z: self.min_z - amount,
+            max_x: self.max_x + amount,
+            max_y: self.max_y + amount,
+            max_z: self.max_z + amount,
+        }
+    }
+
     pub fn intersects(&self, other: &BoundingBox) -> bool {
         self.min_x < other.max_x
             && self.max_x > other.min_x
@@ -59,6 +83,49 @@ impl BoundingBox {
             && self.max_z > other.min_z
     }
 
+    /// The point of the box closest to the position
+    pub fn closest_point(&self, pos: Vector3<f64>) -> Vector3<f64> {
+        Vector3::new(
+            pos.x.clamp(self.min_x, self.max_x),
+            pos.y.clamp(self.min_y, self.max_y),
+            pos.z.clamp(self.min_z, self.max_z),
+        )
+    }
+
+    pub fn center(&self) -> Vector3<f64> {
+        Vector3::new(
+            (self.min_x + self.max_x) / 2.0,
+            (self.min_y + self.max_y) / 2.0,
+            (self.min_z + self.max_z) / 2.0,
+        )
+    }
+
+    /// Where the line from one point to the other enters the box, as a fraction of the way
+    pub fn ray_intersection(&self, from: Vector3<f64>, to: Vector3<f64>) -> Option<f64> {
+        let mut enter: f64 = 0.0;
+        let mut exit: f64 = 1.0;
+        for (start, end, min, max) in [
+            (from.x, to.x, self.min_x, self.max_x),
+            (from.y, to.y, self.min_y, self.max_y),
+            (from.z, to.z, self.min_z, self.max_z),
+        ] {
+            let delta = end - start;
+            if delta == 0.0 {
+                if start < min || start > max {
+                    return None;
+                }
+                continue;
+            }
+            let (a, b) = ((min - start) / delta, (max - start) / delta);
+            enter = enter.max(a.min(b));
+            exit = exit.min(a.max(b));
+            if enter > exit {
+                return None;
+            }
+        }
+        Some(enter)
+    }
+
     pub fn squared_magnitude(&self, pos: Vector3<f64>) -> f64 {
         let d = f64::max(f64::max(self.min_x - pos.x, pos.x - self.max_x), 0.0);
         let e = f64::max(f64::max(self.min_y - pos.y, pos.y - self.max_y), 0.0);
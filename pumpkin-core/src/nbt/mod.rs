@@ -0,0 +1,100 @@
+//! Working with NBT as commands do: [SNBT](snbt), the text form of NBT like `{Count:1b}`, and
+//! [paths](NbtPath) like `Inventory[0].id` which select values inside it.
+
+use std::{collections::HashMap, fmt};
+
+pub use fastnbt::{ByteArray, IntArray, LongArray, Value};
+
+pub mod path;
+pub mod snbt;
+
+pub use path::NbtPath;
+
+/// An NBT compound, what entities and block entities are saved as
+pub type Compound = HashMap<String, Value>;
+
+/// Why SNBT or a path couldn't be parsed, or a path couldn't be applied
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NbtError {
+    message: String,
+}
+
+impl NbtError {
+    pub(crate) fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for NbtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for NbtError {}
+
+/// The name vanilla uses for the type of a value in its errors, e.g. `TAG_Compound`
+#[must_use]
+pub fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Byte(_) => "TAG_Byte",
+        Value::Short(_) => "TAG_Short",
+        Value::Int(_) => "TAG_Int",
+        Value::Long(_) => "TAG_Long",
+        Value::Float(_) => "TAG_Float",
+        Value::Double(_) => "TAG_Double",
+        Value::String(_) => "TAG_String",
+        Value::ByteArray(_) => "TAG_Byte_Array",
+        Value::IntArray(_) => "TAG_Int_Array",
+        Value::LongArray(_) => "TAG_Long_Array",
+        Value::List(_) => "TAG_List",
+        Value::Compound(_) => "TAG_Compound",
+    }
+}
+
+/// Whether `value` contains everything in `pattern`. Compounds may have more entries and lists
+/// more elements, the way `{Tags:["a"]}` matches an entity with the tags `a` and `b`
+#[must_use]
+pub fn matches(pattern: &Value, value: &Value) -> bool {
+    match (pattern, value) {
+        (Value::Compound(pattern), Value::Compound(value)) => compound_matches(pattern, value),
+        (Value::List(pattern), Value::List(value)) => {
+            if pattern.is_empty() {
+                return value.is_empty();
+            }
+            pattern
+                .iter()
+                .all(|pattern| value.iter().any(|value| matches(pattern, value)))
+        }
+        _ => pattern == value,
+    }
+}
+
+/// See [`matches`]
+#[must_use]
+pub fn compound_matches(pattern: &Compound, value: &Compound) -> bool {
+    pattern
+        .iter()
+        .all(|(key, pattern)| value.get(key).is_some_and(|value| matches(pattern, value)))
+}
+
+/// Merges `other` into `compound` like `/data merge` does, nested compounds are merged too and
+/// everything else is replaced. Returns whether something changed
+pub fn merge(compound: &mut Compound, other: &Compound) -> bool {
+    let mut changed = false;
+    for (key, value) in other {
+        match (compound.get_mut(key), value) {
+            (Some(Value::Compound(existing)), Value::Compound(value)) => {
+                changed |= merge(existing, value);
+            }
+            (Some(existing), value) if existing == value => {}
+            _ => {
+                compound.insert(key.clone(), value.clone());
+                changed = true;
+            }
+        }
+    }
+    changed
+}
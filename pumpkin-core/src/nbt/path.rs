@@ -0,0 +1,675 @@
+//! Paths which select values inside NBT, as used by `/data`:
+//!
+//! - `foo` the entry `foo` of a compound, `"a key"` if it has special characters
+//! - `foo{bar:1b}` the entry `foo` if it is a compound matching `{bar:1b}`
+//! - `foo[0]` the first element of a list or array, `foo[-1]` the last one
+//! - `foo[]` all elements, `foo[{bar:1b}]` all compounds in the list matching `{bar:1b}`
+//! - `{bar:1b}` at the start, the root compound if it matches
+//!
+//! Nodes are separated by `.`, e.g. `Inventory[{Slot:0b}].components."minecraft:custom_name"`.
+
+use std::{fmt, mem};
+
+use fastnbt::Value;
+
+use super::{compound_matches, matches, merge, snbt::Reader, type_name, Compound, NbtError};
+
+#[derive(Clone, Debug, PartialEq)]
+enum Node {
+    MatchRoot(Compound),
+    Child(String),
+    MatchChild(String, Compound),
+    Index(i32),
+    All,
+    MatchElement(Compound),
+}
+
+/// A parsed path, see the [module](self) for the syntax
+#[derive(Clone, Debug, PartialEq)]
+pub struct NbtPath {
+    source: String,
+    nodes: Vec<Node>,
+}
+
+impl fmt::Display for NbtPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl NbtPath {
+    pub fn parse(input: &str) -> Result<Self, NbtError> {
+        let mut reader = Reader::new(input);
+        let mut nodes = Vec::new();
+        while reader.peek().is_some() {
+            nodes.push(parse_node(&mut reader, nodes.is_empty())?);
+            match reader.peek() {
+                None | Some('[' | '{') => {}
+                Some(_) => reader.expect('.')?,
+            }
+        }
+        if nodes.is_empty() {
+            return Err(reader.error("Expected path"));
+        }
+        Ok(Self {
+            source: input.to_string(),
+            nodes,
+        })
+    }
+
+    fn not_found(&self) -> NbtError {
+        NbtError::new(format!("Found no elements matching {self}"))
+    }
+
+    /// Everything the path selects, fails if that is nothing
+    pub fn get(&self, root: &Compound) -> Result<Vec<Value>, NbtError> {
+        let (last, parents) = self.nodes.split_last().expect("Paths have nodes");
+        let root = Value::Compound(root.clone());
+        let mut current = vec![&root];
+        for node in parents {
+            current = current
+                .into_iter()
+                .flat_map(|value| node.get(value))
+                .collect();
+        }
+        let values: Vec<Value> = current
+            .into_iter()
+            .flat_map(|value| last.get_owned(value))
+            .collect();
+        if values.is_empty() {
+            return Err(self.not_found());
+        }
+        Ok(values)
+    }
+
+    /// How many values the path selects
+    #[must_use]
+    pub fn count(&self, root: &Compound) -> usize {
+        self.get(root).map_or(0, |values| values.len())
+    }
+
+    /// Replaces everything the path selects, the compounds on the way are created if they are
+    /// missing. Returns how many values changed
+    pub fn set(&self, root: &mut Compound, value: &Value) -> Result<usize, NbtError> {
+        with_value(root, |root| {
+            let (last, parents) = self.nodes.split_last().expect("Paths have nodes");
+            let parents = navigate_mut(root, parents, Some(&Value::Compound(Compound::new())));
+            if parents.is_empty() {
+                return Err(self.not_found());
+            }
+            Ok(parents
+                .into_iter()
+                .map(|parent| last.set(parent, value))
+                .sum())
+        })
+    }
+
+    /// Removes everything the path selects, returns how many values were removed
+    pub fn remove(&self, root: &mut Compound) -> Result<usize, NbtError> {
+        with_value(root, |root| {
+            let (last, parents) = self.nodes.split_last().expect("Paths have nodes");
+            let removed: usize = navigate_mut(root, parents, None)
+                .into_iter()
+                .map(|parent| last.remove(parent))
+                .sum();
+            if removed == 0 {
+                return Err(self.not_found());
+            }
+            Ok(removed)
+        })
+    }
+
+    /// Inserts the values into every list the path selects, at the index or from the end if it is
+    /// negative, `-1` appends. A missing list is created. Returns how many lists changed
+    pub fn insert(
+        &self,
+        root: &mut Compound,
+        index: i32,
+        values: &[Value],
+    ) -> Result<usize, NbtError> {
+        with_value(root, |root| {
+            let targets = navigate_mut(root, &self.nodes, Some(&Value::List(Vec::new())));
+            if targets.is_empty() {
+                return Err(self.not_found());
+            }
+            let mut changed = 0;
+            for target in targets {
+                if insert_into(target, index, values)? {
+                    changed += 1;
+                }
+            }
+            Ok(changed)
+        })
+    }
+
+    /// Merges the compound into every compound the path selects, a missing one is created.
+    /// Returns how many compounds changed
+    pub fn merge(&self, root: &mut Compound, compound: &Compound) -> Result<usize, NbtError> {
+        with_value(root, |root| {
+            let missing = Value::Compound(Compound::new());
+            let targets = navigate_mut(root, &self.nodes, Some(&missing));
+            if targets.is_empty() {
+                return Err(self.not_found());
+            }
+            let mut changed = 0;
+            for target in targets {
+                let Value::Compound(target) = target else {
+                    return Err(NbtError::new(format!(
+                        "Expected compound, got: {}",
+                        type_name(target)
+                    )));
+                };
+                if merge(target, compound) {
+                    changed += 1;
+                }
+            }
+            Ok(changed)
+        })
+    }
+}
+
+/// Runs `f` with the compound as a value, the way the nodes work on it
+fn with_value<T>(root: &mut Compound, f: impl FnOnce(&mut Value) -> T) -> T {
+    let mut value = Value::Compound(mem::take(root));
+    let result = f(&mut value);
+    if let Value::Compound(compound) = value {
+        *root = compound;
+    }
+    result
+}
+
+/// The values the nodes select. With `missing` the compounds on the way are created and
+/// `missing` is added if the last node selects no entry
+fn navigate_mut<'a>(
+    root: &'a mut Value,
+    nodes: &[Node],
+    missing: Option<&Value>,
+) -> Vec<&'a mut Value> {
+    let parent = Value::Compound(Compound::new());
+    let mut current = vec![root];
+    for (i, node) in nodes.iter().enumerate() {
+        let missing = missing.map(|missing| {
+            if i + 1 == nodes.len() {
+                missing
+            } else {
+                &parent
+            }
+        });
+        let mut next = Vec::new();
+        for value in current {
+            node.get_mut(value, missing, &mut next);
+        }
+        current = next;
+    }
+    current
+}
+
+fn parse_node(reader: &mut Reader, first: bool) -> Result<Node, NbtError> {
+    match reader.peek() {
+        Some('{') => {
+            if !first {
+                return Err(reader.error("Unexpected compound in path"));
+            }
+            Ok(Node::MatchRoot(reader.read_compound()?))
+        }
+        Some('[') => {
+            reader.skip();
+            let node = match reader.peek() {
+                Some('{') => Node::MatchElement(reader.read_compound()?),
+                Some(']') => Node::All,
+                _ => {
+                    let index = reader.read_unquoted();
+                    Node::Index(index.parse().map_err(|_| reader.error("Expected index"))?)
+                }
+            };
+            reader.expect(']')?;
+            Ok(node)
+        }
+        Some('"' | '\'') => {
+            let name = reader.read_quoted()?;
+            parse_child(reader, name)
+        }
+        _ => {
+            let name = read_unquoted_name(reader);
+            if name.is_empty() {
+                return Err(reader.error("Expected path node"));
+            }
+            parse_child(reader, name)
+        }
+    }
+}
+
+fn parse_child(reader: &mut Reader, name: String) -> Result<Node, NbtError> {
+    if reader.peek() == Some('{') {
+        return Ok(Node::MatchChild(name, reader.read_compound()?));
+    }
+    Ok(Node::Child(name))
+}
+
+/// Names in paths may contain more characters than unquoted SNBT strings, like `:`
+fn read_unquoted_name(reader: &mut Reader) -> String {
+    let mut name = String::new();
+    while let Some(c) = reader.peek() {
+        if matches!(c, ' ' | '"' | '\'' | '[' | ']' | '.' | '{' | '}') {
+            break;
+        }
+        name.push(c);
+        reader.skip();
+    }
+    name
+}
+
+/// The position in a list of the length, negative indices count from the end
+fn resolve_index(index: i32, len: usize) -> Option<usize> {
+    let index = if index < 0 {
+        len.checked_sub(index.unsigned_abs() as usize)?
+    } else {
+        index as usize
+    };
+    (index < len).then_some(index)
+}
+
+fn array_element(value: &Value, index: usize) -> Option<Value> {
+    match value {
+        Value::ByteArray(array) => array.get(index).copied().map(Value::Byte),
+        Value::IntArray(array) => array.get(index).copied().map(Value::Int),
+        Value::LongArray(array) => array.get(index).copied().map(Value::Long),
+        _ => None,
+    }
+}
+
+fn array_len(value: &Value) -> Option<usize> {
+    match value {
+        Value::ByteArray(array) => Some(array.len()),
+        Value::IntArray(array) => Some(array.len()),
+        Value::LongArray(array) => Some(array.len()),
+        _ => None,
+    }
+}
+
+fn as_integer(value: &Value) -> Option<i64> {
+    match value {
+        Value::Byte(value) => Some(i64::from(*value)),
+        Value::Short(value) => Some(i64::from(*value)),
+        Value::Int(value) => Some(i64::from(*value)),
+        Value::Long(value) => Some(*value),
+        _ => None,
+    }
+}
+
+/// Sets the element of an array, returns whether it changed
+fn set_array_element(array: &mut Value, index: usize, value: &Value) -> bool {
+    let Some(new) = as_integer(value) else {
+        return false;
+    };
+    match array {
+        Value::ByteArray(array) => replace(&mut array[index], new as i8),
+        Value::IntArray(array) => replace(&mut array[index], new as i32),
+        Value::LongArray(array) => replace(&mut array[index], new),
+        _ => false,
+    }
+}
+
+/// Edits the elements of an array as longs, they are cast back to the type of the array
+fn edit_array(array: &mut Value, edit: impl FnOnce(&mut Vec<i64>)) {
+    let mut data: Vec<i64> = match array {
+        Value::ByteArray(array) => array.iter().map(|n| i64::from(*n)).collect(),
+        Value::IntArray(array) => array.iter().map(|n| i64::from(*n)).collect(),
+        Value::LongArray(array) => array.to_vec(),
+        _ => return,
+    };
+    edit(&mut data);
+    match array {
+        Value::ByteArray(array) => {
+            *array = fastnbt::ByteArray::new(data.into_iter().map(|n| n as i8).collect());
+        }
+        Value::IntArray(array) => {
+            *array = fastnbt::IntArray::new(data.into_iter().map(|n| n as i32).collect());
+        }
+        Value::LongArray(array) => *array = fastnbt::LongArray::new(data),
+        _ => {}
+    }
+}
+
+fn replace<T: PartialEq>(old: &mut T, new: T) -> bool {
+    if *old == new {
+        return false;
+    }
+    *old = new;
+    true
+}
+
+/// Whether the value may be in the list, lists only have values of one type
+fn fits_list(list: &[Value], value: &Value) -> bool {
+    list.iter()
+        .all(|element| mem::discriminant(element) == mem::discriminant(value))
+}
+
+fn insert_into(target: &mut Value, index: i32, values: &[Value]) -> Result<bool, NbtError> {
+    let len = match &*target {
+        Value::List(list) => list.len(),
+        target => array_len(target)
+            .ok_or_else(|| NbtError::new(format!("Expected list, got: {}", type_name(target))))?,
+    };
+    let index = if index < 0 {
+        len.checked_sub(index.unsigned_abs() as usize - 1)
+    } else {
+        Some(index as usize).filter(|index| *index <= len)
+    }
+    .ok_or_else(|| NbtError::new(format!("Index out of bounds: {index}")))?;
+
+    match target {
+        Value::List(list) => {
+            for value in values {
+                if !fits_list(list, value) {
+                    return Err(NbtError::new(format!(
+                        "Can't insert {} into list of {}",
+                        type_name(value),
+                        type_name(&list[0])
+                    )));
+                }
+            }
+            list.splice(index..index, values.iter().cloned());
+        }
+        array => {
+            let numbers: Vec<i64> = values
+                .iter()
+                .map(|value| {
+                    as_integer(value).ok_or_else(|| {
+                        NbtError::new(format!(
+                            "Can't insert {} into {}",
+                            type_name(value),
+                            type_name(array)
+                        ))
+                    })
+                })
+                .collect::<Result<_, _>>()?;
+            edit_array(array, |data| {
+                data.splice(index..index, numbers);
+            });
+        }
+    }
+    Ok(!values.is_empty())
+}
+
+impl Node {
+    /// The values selected in `value`, without array elements which are no values
+    fn get<'a>(&self, value: &'a Value) -> Vec<&'a Value> {
+        match (self, value) {
+            (Self::MatchRoot(pattern), Value::Compound(compound))
+                if compound_matches(pattern, compound) =>
+            {
+                vec![value]
+            }
+            (Self::Child(name), Value::Compound(compound)) => {
+                compound.get(name).into_iter().collect()
+            }
+            (Self::MatchChild(name, pattern), Value::Compound(compound)) => compound
+                .get(name)
+                .filter(|child| matches!(child, Value::Compound(child) if compound_matches(pattern, child)))
+                .into_iter()
+                .collect(),
+            (Self::Index(index), Value::List(list)) => resolve_index(*index, list.len())
+                .map(|index| &list[index])
+                .into_iter()
+                .collect(),
+            (Self::All, Value::List(list)) => list.iter().collect(),
+            (Self::MatchElement(pattern), Value::List(list)) => list
+                .iter()
+                .filter(|element| matches(&Value::Compound(pattern.clone()), element))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Like [`Self::get`], but with the elements of arrays
+    fn get_owned(&self, value: &Value) -> Vec<Value> {
+        match (self, array_len(value)) {
+            (Self::Index(index), Some(len)) => resolve_index(*index, len)
+                .and_then(|index| array_element(value, index))
+                .into_iter()
+                .collect(),
+            (Self::All, Some(len)) => (0..len)
+                .filter_map(|index| array_element(value, index))
+                .collect(),
+            _ => self.get(value).into_iter().cloned().collect(),
+        }
+    }
+
+    /// Adds the values selected in `value` to `out`. With `missing` entries which don't exist yet
+    /// are added, like vanilla does
+    fn get_mut<'a>(
+        &self,
+        value: &'a mut Value,
+        missing: Option<&Value>,
+        out: &mut Vec<&'a mut Value>,
+    ) {
+        match (self, value) {
+            (Self::MatchRoot(pattern), value) => {
+                if matches!(value, Value::Compound(compound) if compound_matches(pattern, compound))
+                {
+                    out.push(value);
+                }
+            }
+            (Self::Child(name), Value::Compound(compound)) => {
+                if let Some(missing) = missing {
+                    compound
+                        .entry(name.clone())
+                        .or_insert_with(|| missing.clone());
+                }
+                out.extend(compound.get_mut(name));
+            }
+            (Self::MatchChild(name, pattern), Value::Compound(compound)) => {
+                if missing.is_some() {
+                    compound
+                        .entry(name.clone())
+                        .or_insert_with(|| Value::Compound(pattern.clone()));
+                }
+                out.extend(compound.get_mut(name).filter(
+                    |child| matches!(child, Value::Compound(child) if compound_matches(pattern, child)),
+                ));
+            }
+            (Self::Index(index), Value::List(list)) => {
+                if let Some(index) = resolve_index(*index, list.len()) {
+                    out.push(&mut list[index]);
+                }
+            }
+            (Self::All, Value::List(list)) => out.extend(list.iter_mut()),
+            (Self::MatchElement(pattern), Value::List(list)) => {
+                let pattern = Value::Compound(pattern.clone());
+                if missing.is_some()
+                    && !list.iter().any(|element| matches(&pattern, element))
+                    && fits_list(list, &pattern)
+                {
+                    list.push(pattern.clone());
+                }
+                out.extend(list.iter_mut().filter(|element| matches(&pattern, element)));
+            }
+            _ => {}
+        }
+    }
+
+    /// Replaces what the node selects in `parent`, returns how many values changed
+    fn set(&self, parent: &mut Value, value: &Value) -> usize {
+        match (self, parent) {
+            (Self::Child(name), Value::Compound(compound)) => {
+                usize::from(compound.insert(name.clone(), value.clone()).as_ref() != Some(value))
+            }
+            (Self::MatchChild(name, pattern), Value::Compound(compound)) => {
+                match compound.get_mut(name) {
+                    Some(child @ Value::Compound(_))
+                        if matches(&Value::Compound(pattern.clone()), child) =>
+                    {
+                        usize::from(replace(child, value.clone()))
+                    }
+                    _ => 0,
+                }
+            }
+            (Self::Index(index), Value::List(list)) => {
+                let others_fit = list.len() <= 1 || fits_list(list, value);
+                match resolve_index(*index, list.len()) {
+                    Some(index) if others_fit => {
+                        usize::from(replace(&mut list[index], value.clone()))
+                    }
+                    _ => 0,
+                }
+            }
+            (Self::Index(index), array) => {
+                match array_len(array).and_then(|len| resolve_index(*index, len)) {
+                    Some(index) => usize::from(set_array_element(array, index, value)),
+                    None => 0,
+                }
+            }
+            (Self::All, Value::List(list)) => {
+                if !fits_list(list, value) {
+                    return 0;
+                }
+                list.iter_mut()
+                    .map(|element| usize::from(replace(element, value.clone())))
+                    .sum()
+            }
+            (Self::All, array) => {
+                let len = array_len(array).unwrap_or_default();
+                (0..len)
+                    .map(|index| usize::from(set_array_element(array, index, value)))
+                    .sum()
+            }
+            (Self::MatchElement(pattern), Value::List(list)) => {
+                let pattern = Value::Compound(pattern.clone());
+                if !fits_list(list, value) {
+                    return 0;
+                }
+                list.iter_mut()
+                    .filter(|element| matches(&pattern, element))
+                    .map(|element| usize::from(replace(element, value.clone())))
+                    .sum()
+            }
+            _ => 0,
+        }
+    }
+
+    /// Removes what the node selects in `parent`, returns how many values were removed
+    fn remove(&self, parent: &mut Value) -> usize {
+        match (self, parent) {
+            (Self::Child(name), Value::Compound(compound)) => {
+                usize::from(compound.remove(name).is_some())
+            }
+            (Self::MatchChild(name, pattern), Value::Compound(compound)) => {
+                let matching = compound.get(name).is_some_and(
+                    |child| matches!(child, Value::Compound(child) if compound_matches(pattern, child)),
+                );
+                if matching {
+                    compound.remove(name);
+                }
+                usize::from(matching)
+            }
+            (Self::Index(index), Value::List(list)) => match resolve_index(*index, list.len()) {
+                Some(index) => {
+                    list.remove(index);
+                    1
+                }
+                None => 0,
+            },
+            (Self::Index(index), array) => {
+                let Some(index) = array_len(array).and_then(|len| resolve_index(*index, len))
+                else {
+                    return 0;
+                };
+                edit_array(array, |data| {
+                    data.remove(index);
+                });
+                1
+            }
+            (Self::All, Value::List(list)) => mem::take(list).len(),
+            (Self::All, array) => {
+                let len = array_len(array).unwrap_or_default();
+                edit_array(array, Vec::clear);
+                len
+            }
+            (Self::MatchElement(pattern), Value::List(list)) => {
+                let pattern = Value::Compound(pattern.clone());
+                let len = list.len();
+                list.retain(|element| !matches(&pattern, element));
+                len - list.len()
+            }
+            _ => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use fastnbt::Value;
+
+    use super::NbtPath;
+    use crate::nbt::{
+        snbt::{parse, parse_compound},
+        Compound,
+    };
+
+    fn data() -> Compound {
+        parse_compound(
+            r#"{Inventory:[{Slot:0b,id:"minecraft:stone"},{Slot:1b,id:"minecraft:dirt"}],Pos:[1.0d,2.0d,3.0d],UUID:[I;1,2,3,4],"minecraft:custom":{a:1}}"#,
+        )
+        .unwrap()
+    }
+
+    fn get(path: &str, data: &Compound) -> Vec<Value> {
+        NbtPath::parse(path).unwrap().get(data).unwrap()
+    }
+
+    #[test]
+    fn parsing() {
+        assert!(NbtPath::parse("a.b[0].c").is_ok());
+        assert!(NbtPath::parse(r#"a{b:1}.c[{d:"e"}][]."f g""#).is_ok());
+        assert!(NbtPath::parse("{a:1b}.b").is_ok());
+        assert!(NbtPath::parse("a.{b:1}").is_err());
+        assert!(NbtPath::parse("a[b]").is_err());
+        assert!(NbtPath::parse("").is_err());
+    }
+
+    #[test]
+    fn get_values() {
+        let data = data();
+        assert_eq!(get("Pos[-1]", &data), vec![Value::Double(3.0)]);
+        assert_eq!(get("UUID[1]", &data), vec![Value::Int(2)]);
+        assert_eq!(
+            get("Inventory[{Slot:1b}].id", &data),
+            vec![Value::String("minecraft:dirt".to_string())]
+        );
+        assert_eq!(get("Inventory[].Slot", &data).len(), 2);
+        assert_eq!(get("minecraft:custom.a", &data), vec![Value::Int(1)]);
+        assert!(NbtPath::parse("Pos[3]").unwrap().get(&data).is_err());
+    }
+
+    #[test]
+    fn modify_values() {
+        let mut data = data();
+        let path = NbtPath::parse("Inventory[].count").unwrap();
+        assert_eq!(path.set(&mut data, &Value::Int(5)), Ok(2));
+        assert_eq!(path.set(&mut data, &Value::Int(5)), Ok(0));
+
+        let created = NbtPath::parse("a.b.c").unwrap();
+        assert_eq!(created.set(&mut data, &Value::Byte(1)), Ok(1));
+        assert_eq!(get("a.b.c", &data), vec![Value::Byte(1)]);
+
+        let tags = NbtPath::parse("Tags").unwrap();
+        let values = [parse(r#""x""#).unwrap(), parse(r#""y""#).unwrap()];
+        assert_eq!(tags.insert(&mut data, -1, &values), Ok(1));
+        assert_eq!(
+            tags.insert(&mut data, 0, &[parse(r#""w""#).unwrap()]),
+            Ok(1)
+        );
+        assert_eq!(get("Tags[0]", &data), vec![parse(r#""w""#).unwrap()]);
+        assert!(tags.insert(&mut data, 0, &[Value::Int(1)]).is_err());
+
+        let inventory = NbtPath::parse("Inventory[{Slot:0b}]").unwrap();
+        assert_eq!(inventory.remove(&mut data), Ok(1));
+        assert_eq!(get("Inventory[]", &data).len(), 1);
+
+        let merged = parse_compound("{b:2}").unwrap();
+        let custom = NbtPath::parse("minecraft:custom").unwrap();
+        assert_eq!(custom.merge(&mut data, &merged), Ok(1));
+        assert_eq!(get("minecraft:custom.b", &data), vec![Value::Int(2)]);
+    }
+}
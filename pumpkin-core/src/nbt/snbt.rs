@@ -0,0 +1,442 @@
+//! SNBT, the text form of NBT used in commands, e.g. `{id:"minecraft:stone",count:2}`.
+//!
+//! Numbers get their type from a suffix: `1b` is a byte, `1s` a short, `1` an int, `1L` a long,
+//! `1.5f` a float and `1.5` or `1d` a double. `true` and `false` are bytes. Arrays are written
+//! like `[I;1,2,3]`.
+
+use std::fmt::Write;
+
+use fastnbt::{ByteArray, IntArray, LongArray, Value};
+
+use super::{Compound, NbtError};
+
+/// Parses a whole value, e.g. `{a:1b}` or `"text"`
+pub fn parse(input: &str) -> Result<Value, NbtError> {
+    let mut reader = Reader::new(input);
+    let value = reader.read_value()?;
+    reader.expect_end()?;
+    Ok(value)
+}
+
+/// Parses a compound, e.g. `{a:1b}`
+pub fn parse_compound(input: &str) -> Result<Compound, NbtError> {
+    let mut reader = Reader::new(input);
+    let compound = reader.read_compound()?;
+    reader.expect_end()?;
+    Ok(compound)
+}
+
+/// Writes the value as SNBT. Keys of compounds are sorted so the output is always the same
+#[must_use]
+pub fn to_snbt(value: &Value) -> String {
+    let mut out = String::new();
+    write_value(&mut out, value);
+    out
+}
+
+pub(crate) struct Reader<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) const fn new(input: &'a str) -> Self {
+        Self { input, position: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    pub(crate) fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    pub(crate) fn skip(&mut self) {
+        if let Some(c) = self.peek() {
+            self.position += c.len_utf8();
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.skip();
+        }
+    }
+
+    pub(crate) fn error(&self, message: &str) -> NbtError {
+        NbtError::new(format!("{message} at position {}", self.position))
+    }
+
+    pub(crate) fn expect(&mut self, expected: char) -> Result<(), NbtError> {
+        self.skip_whitespace();
+        if self.peek() == Some(expected) {
+            self.skip();
+            Ok(())
+        } else {
+            Err(self.error(&format!("Expected '{expected}'")))
+        }
+    }
+
+    pub(crate) fn expect_end(&mut self) -> Result<(), NbtError> {
+        self.skip_whitespace();
+        if self.peek().is_some() {
+            return Err(self.error("Unexpected trailing data"));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn read_value(&mut self) -> Result<Value, NbtError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => self.read_compound().map(Value::Compound),
+            Some('[') => self.read_list_or_array(),
+            Some('"' | '\'') => self.read_quoted().map(Value::String),
+            Some(_) => {
+                let token = self.read_unquoted();
+                if token.is_empty() {
+                    return Err(self.error("Expected value"));
+                }
+                Ok(parse_unquoted(token))
+            }
+            None => Err(self.error("Expected value")),
+        }
+    }
+
+    pub(crate) fn read_compound(&mut self) -> Result<Compound, NbtError> {
+        self.expect('{')?;
+        let mut compound = Compound::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.skip();
+            return Ok(compound);
+        }
+        loop {
+            let key = self.read_key()?;
+            self.expect(':')?;
+            let value = self.read_value()?;
+            compound.insert(key, value);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.skip(),
+                Some('}') => {
+                    self.skip();
+                    return Ok(compound);
+                }
+                _ => return Err(self.error("Expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn read_key(&mut self) -> Result<String, NbtError> {
+        self.skip_whitespace();
+        if matches!(self.peek(), Some('"' | '\'')) {
+            return self.read_quoted();
+        }
+        let key = self.read_unquoted();
+        if key.is_empty() {
+            return Err(self.error("Expected key"));
+        }
+        Ok(key.to_string())
+    }
+
+    fn read_list_or_array(&mut self) -> Result<Value, NbtError> {
+        self.expect('[')?;
+        let rest = self.rest();
+        let array = match (rest.chars().next(), rest.chars().nth(1)) {
+            (Some(kind @ ('B' | 'I' | 'L')), Some(';')) => Some(kind),
+            _ => None,
+        };
+        let mut values = Vec::new();
+        if array.is_some() {
+            self.position += 2;
+        }
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.skip();
+        } else {
+            loop {
+                let value = self.read_value()?;
+                if let Some(first) = values.first() {
+                    if array.is_none()
+                        && std::mem::discriminant(first) != std::mem::discriminant(&value)
+                    {
+                        return Err(self.error(&format!(
+                            "Can't insert {} into list of {}",
+                            super::type_name(&value),
+                            super::type_name(first)
+                        )));
+                    }
+                }
+                values.push(value);
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(',') => self.skip(),
+                    Some(']') => {
+                        self.skip();
+                        break;
+                    }
+                    _ => return Err(self.error("Expected ',' or ']'")),
+                }
+            }
+        }
+        let Some(kind) = array else {
+            return Ok(Value::List(values));
+        };
+        let invalid = || self.error(&format!("Invalid value in [{kind};...] array"));
+        Ok(match kind {
+            'B' => Value::ByteArray(ByteArray::new(
+                values
+                    .iter()
+                    .map(|value| match value {
+                        Value::Byte(value) => Ok(*value),
+                        _ => Err(invalid()),
+                    })
+                    .collect::<Result<_, _>>()?,
+            )),
+            'I' => Value::IntArray(IntArray::new(
+                values
+                    .iter()
+                    .map(|value| match value {
+                        Value::Byte(value) => Ok(i32::from(*value)),
+                        Value::Short(value) => Ok(i32::from(*value)),
+                        Value::Int(value) => Ok(*value),
+                        _ => Err(invalid()),
+                    })
+                    .collect::<Result<_, _>>()?,
+            )),
+            _ => Value::LongArray(LongArray::new(
+                values
+                    .iter()
+                    .map(|value| match value {
+                        Value::Byte(value) => Ok(i64::from(*value)),
+                        Value::Short(value) => Ok(i64::from(*value)),
+                        Value::Int(value) => Ok(i64::from(*value)),
+                        Value::Long(value) => Ok(*value),
+                        _ => Err(invalid()),
+                    })
+                    .collect::<Result<_, _>>()?,
+            )),
+        })
+    }
+
+    pub(crate) fn read_quoted(&mut self) -> Result<String, NbtError> {
+        let Some(quote) = self.peek() else {
+            return Err(self.error("Expected quote"));
+        };
+        self.skip();
+        let mut string = String::new();
+        loop {
+            let Some(c) = self.peek() else {
+                return Err(self.error("Unclosed quoted string"));
+            };
+            self.skip();
+            match c {
+                '\\' => {
+                    let Some(escaped) = self.peek() else {
+                        return Err(self.error("Unclosed quoted string"));
+                    };
+                    self.skip();
+                    string.push(match escaped {
+                        'n' => '\n',
+                        't' => '\t',
+                        '\\' | '"' | '\'' => escaped,
+                        _ => {
+                            return Err(self.error(&format!("Invalid escape sequence \\{escaped}")))
+                        }
+                    });
+                }
+                c if c == quote => return Ok(string),
+                c => string.push(c),
+            }
+        }
+    }
+
+    /// Reads characters which are allowed outside quotes
+    pub(crate) fn read_unquoted(&mut self) -> &'a str {
+        let start = self.position;
+        while self.peek().is_some_and(is_unquoted_char) {
+            self.skip();
+        }
+        &self.input[start..self.position]
+    }
+}
+
+const fn is_unquoted_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '+')
+}
+
+fn is_integer(token: &str) -> bool {
+    let digits = token.strip_prefix(['+', '-']).unwrap_or(token);
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Like vanilla a decimal number has to have a `.`, unless it has a suffix
+fn is_decimal(token: &str, needs_point: bool) -> bool {
+    let digits = token.strip_prefix(['+', '-']).unwrap_or(token);
+    let (mantissa, exponent) = match digits.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, Some(exponent)),
+        None => (digits, None),
+    };
+    let mantissa_valid = match mantissa.split_once('.') {
+        Some((whole, fraction)) => {
+            (!whole.is_empty() || !fraction.is_empty())
+                && whole.bytes().all(|b| b.is_ascii_digit())
+                && fraction.bytes().all(|b| b.is_ascii_digit())
+        }
+        None => {
+            !needs_point && !mantissa.is_empty() && mantissa.bytes().all(|b| b.is_ascii_digit())
+        }
+    };
+    mantissa_valid && exponent.is_none_or(is_integer)
+}
+
+/// A number if it looks like one, otherwise a string
+fn parse_unquoted(token: &str) -> Value {
+    let number = || {
+        let (body, suffix) = token.split_at(token.len() - 1);
+        match suffix {
+            "b" | "B" if is_integer(body) => body.parse().ok().map(Value::Byte),
+            "s" | "S" if is_integer(body) => body.parse().ok().map(Value::Short),
+            "l" | "L" if is_integer(body) => body.parse().ok().map(Value::Long),
+            "f" | "F" if is_decimal(body, false) => body.parse().ok().map(Value::Float),
+            "d" | "D" if is_decimal(body, false) => body.parse().ok().map(Value::Double),
+            _ if is_integer(token) => token.parse().ok().map(Value::Int),
+            _ if is_decimal(token, true) => token.parse().ok().map(Value::Double),
+            _ => None,
+        }
+    };
+    match token {
+        "true" => Value::Byte(1),
+        "false" => Value::Byte(0),
+        _ => number().unwrap_or_else(|| Value::String(token.to_string())),
+    }
+}
+
+fn write_value(out: &mut String, value: &Value) {
+    match value {
+        Value::Byte(value) => {
+            let _ = write!(out, "{value}b");
+        }
+        Value::Short(value) => {
+            let _ = write!(out, "{value}s");
+        }
+        Value::Int(value) => {
+            let _ = write!(out, "{value}");
+        }
+        Value::Long(value) => {
+            let _ = write!(out, "{value}L");
+        }
+        Value::Float(value) => {
+            let _ = write!(out, "{value:?}f");
+        }
+        Value::Double(value) => {
+            let _ = write!(out, "{value:?}d");
+        }
+        Value::String(value) => write_string(out, value),
+        Value::ByteArray(values) => write_array(out, 'B', values.iter().map(|v| format!("{v}B"))),
+        Value::IntArray(values) => write_array(out, 'I', values.iter().map(ToString::to_string)),
+        Value::LongArray(values) => write_array(out, 'L', values.iter().map(|v| format!("{v}L"))),
+        Value::List(values) => {
+            out.push('[');
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_value(out, value);
+            }
+            out.push(']');
+        }
+        Value::Compound(compound) => {
+            let mut entries: Vec<_> = compound.iter().collect();
+            entries.sort_unstable_by_key(|(key, _)| *key);
+            out.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                if !key.is_empty() && key.chars().all(is_unquoted_char) {
+                    out.push_str(key);
+                } else {
+                    write_string(out, key);
+                }
+                out.push(':');
+                write_value(out, value);
+            }
+            out.push('}');
+        }
+    }
+}
+
+fn write_array(out: &mut String, kind: char, values: impl Iterator<Item = String>) {
+    let _ = write!(out, "[{kind};");
+    for (i, value) in values.enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(&value);
+    }
+    out.push(']');
+}
+
+/// Quotes with `"`, unless the string contains only that quote
+fn write_string(out: &mut String, value: &str) {
+    let quote = if value.contains('"') && !value.contains('\'') {
+        '\''
+    } else {
+        '"'
+    };
+    out.push(quote);
+    for c in value.chars() {
+        if c == quote || c == '\\' {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out.push(quote);
+}
+
+#[cfg(test)]
+mod test {
+    use fastnbt::{IntArray, Value};
+
+    use super::{parse, parse_compound, to_snbt};
+
+    #[test]
+    fn numbers_get_their_type_from_the_suffix() {
+        assert_eq!(parse("1b"), Ok(Value::Byte(1)));
+        assert_eq!(parse("-3s"), Ok(Value::Short(-3)));
+        assert_eq!(parse("7"), Ok(Value::Int(7)));
+        assert_eq!(parse("7L"), Ok(Value::Long(7)));
+        assert_eq!(parse("1.5f"), Ok(Value::Float(1.5)));
+        assert_eq!(parse("1.5"), Ok(Value::Double(1.5)));
+        assert_eq!(parse("2d"), Ok(Value::Double(2.0)));
+        assert_eq!(parse("true"), Ok(Value::Byte(1)));
+        // too big for a byte, and no number at all
+        assert_eq!(parse("300b"), Ok(Value::String("300b".to_string())));
+        assert_eq!(parse("abc"), Ok(Value::String("abc".to_string())));
+    }
+
+    #[test]
+    fn round_trip() {
+        let input = r#"{Items:[{Slot:0b,count:2,id:"minecraft:stone"}],Name:'say "hi"',Pos:[1.0d,2.5d,-3.0d],UUID:[I;1,2,3,4],"with space":1.0f}"#;
+        let value = parse(input).unwrap();
+        let Value::Compound(compound) = &value else {
+            panic!("Expected a compound");
+        };
+        assert_eq!(
+            compound.get("UUID"),
+            Some(&Value::IntArray(IntArray::new(vec![1, 2, 3, 4])))
+        );
+        assert_eq!(to_snbt(&value), input);
+    }
+
+    #[test]
+    fn errors() {
+        assert!(parse("[1,2b]").is_err());
+        assert!(parse("{a:1").is_err());
+        assert!(parse("{a:1} b").is_err());
+        assert!(parse_compound("[1]").is_err());
+        assert!(parse(r#""unclosed"#).is_err());
+    }
+}
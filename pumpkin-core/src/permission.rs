@@ -0,0 +1,123 @@
+//! Resolution of hierarchical permission nodes like `pumpkin.command.gamemode.other`.
+//!
+//! A permission entry is a node, optionally ending in `*` to match all nodes below it,
+//! and optionally prefixed with `-` to deny instead of grant.
+
+/// A single granted or denied node pattern.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PermissionEntry {
+    pattern: String,
+    granted: bool,
+}
+
+impl PermissionEntry {
+    /// Parses an entry like `pumpkin.command.*` or `-pumpkin.command.stop`.
+    #[must_use]
+    pub fn parse(entry: &str) -> Self {
+        let entry = entry.trim();
+        match entry.strip_prefix('-') {
+            Some(pattern) => Self {
+                pattern: pattern.to_string(),
+                granted: false,
+            },
+            None => Self {
+                pattern: entry.to_string(),
+                granted: true,
+            },
+        }
+    }
+
+    /// Returns how specific the match is, or None if the pattern does not match the node.
+    ///
+    /// An exact match is more specific than any wildcard, longer wildcards are more specific than shorter ones.
+    fn specificity(&self, node: &str) -> Option<usize> {
+        if self.pattern == node {
+            return Some(usize::MAX);
+        }
+        let prefix = self.pattern.strip_suffix('*')?;
+        if prefix.is_empty() {
+            return Some(0);
+        }
+        // `a.b.*` matches `a.b` itself and everything below it, but not `a.bc`
+        let parent = prefix.strip_suffix('.').unwrap_or(prefix);
+        if node == parent || (prefix.ends_with('.') && node.starts_with(prefix)) {
+            Some(prefix.len())
+        } else {
+            None
+        }
+    }
+}
+
+/// A set of permission entries, e.g. those of a group or a user.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PermissionSet {
+    entries: Vec<PermissionEntry>,
+}
+
+impl PermissionSet {
+    #[must_use]
+    pub fn new<'a>(entries: impl IntoIterator<Item = &'a str>) -> Self {
+        Self {
+            entries: entries.into_iter().map(PermissionEntry::parse).collect(),
+        }
+    }
+
+    /// Returns whether the node is granted or denied, or None if no entry matches it.
+    ///
+    /// The most specific matching entry decides, a denial wins over a grant of the same specificity.
+    #[must_use]
+    pub fn resolve(&self, node: &str) -> Option<bool> {
+        let mut best: Option<(usize, bool)> = None;
+        for entry in &self.entries {
+            let Some(specificity) = entry.specificity(node) else {
+                continue;
+            };
+            best = match best {
+                Some((best_specificity, granted))
+                    if best_specificity > specificity
+                        || (best_specificity == specificity && !granted) =>
+                {
+                    best
+                }
+                _ => Some((specificity, entry.granted)),
+            };
+        }
+        best.map(|(_, granted)| granted)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PermissionSet;
+
+    #[test]
+    fn wildcards() {
+        let set = PermissionSet::new(["pumpkin.command.*"]);
+        assert_eq!(set.resolve("pumpkin.command.gamemode"), Some(true));
+        assert_eq!(set.resolve("pumpkin.command.gamemode.other"), Some(true));
+        assert_eq!(set.resolve("pumpkin.command"), Some(true));
+        assert_eq!(set.resolve("pumpkin.commands"), None);
+        assert_eq!(set.resolve("minecraft.command.stop"), None);
+
+        let set = PermissionSet::new(["*"]);
+        assert_eq!(set.resolve("anything.at.all"), Some(true));
+    }
+
+    #[test]
+    fn negation() {
+        let set = PermissionSet::new([
+            "pumpkin.command.*",
+            "-pumpkin.command.stop",
+            "-pumpkin.command.gamemode.*",
+            "pumpkin.command.gamemode",
+        ]);
+        assert_eq!(set.resolve("pumpkin.command.stop"), Some(false));
+        assert_eq!(set.resolve("pumpkin.command.say"), Some(true));
+        assert_eq!(set.resolve("pumpkin.command.gamemode"), Some(true));
+        assert_eq!(set.resolve("pumpkin.command.gamemode.other"), Some(false));
+
+        // denial wins over a grant of the same node
+        let set = PermissionSet::new(["pumpkin.command.stop", "-pumpkin.command.stop"]);
+        assert_eq!(set.resolve("pumpkin.command.stop"), Some(false));
+    }
+}
@@ -0,0 +1,278 @@
+//! Custom data which plugins attach to players, entities, block entities, chunks and worlds.
+//!
+//! A [`PersistentDataContainer`] maps [`NamespacedKey`]s like `myplugin:kills` to NBT values and
+//! is saved together with what it is attached to, in the same format as Bukkit's
+//! `PublicBukkitValues`.
+
+use std::{collections::HashMap, fmt};
+
+use fastnbt::{ByteArray, IntArray, LongArray, Value};
+use serde::{Deserialize, Serialize};
+
+/// The entry custom data is saved in, in the NBT of what it is attached to
+pub const NBT_KEY: &str = "PublicBukkitValues";
+
+/// A key like `myplugin:kills`, the namespace is usually the name of the plugin so plugins do not
+/// overwrite each other's data.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NamespacedKey {
+    namespace: String,
+    key: String,
+}
+
+impl NamespacedKey {
+    /// Returns None if the namespace or key contain characters other than `a-z0-9._-`, the key may
+    /// also contain `/`
+    #[must_use]
+    pub fn new(namespace: &str, key: &str) -> Option<Self> {
+        let valid = |part: &str, extra: &[char]| {
+            !part.is_empty()
+                && part.chars().all(|c| {
+                    c.is_ascii_lowercase()
+                        || c.is_ascii_digit()
+                        || matches!(c, '.' | '_' | '-')
+                        || extra.contains(&c)
+                })
+        };
+        (valid(namespace, &[]) && valid(key, &['/'])).then(|| Self {
+            namespace: namespace.to_string(),
+            key: key.to_string(),
+        })
+    }
+
+    /// Parses a key like `myplugin:kills`
+    #[must_use]
+    pub fn parse(key: &str) -> Option<Self> {
+        let (namespace, key) = key.split_once(':')?;
+        Self::new(namespace, key)
+    }
+
+    #[must_use]
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    #[must_use]
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+}
+
+impl fmt::Display for NamespacedKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.namespace, self.key)
+    }
+}
+
+/// A type which can be stored in a [`PersistentDataContainer`]
+pub trait PersistentDataType: Sized {
+    fn to_nbt(self) -> Value;
+
+    /// Returns None if the value was stored as another type
+    fn from_nbt(value: &Value) -> Option<Self>;
+}
+
+macro_rules! persistent_data_type {
+    ($type:ty, $variant:ident) => {
+        impl PersistentDataType for $type {
+            fn to_nbt(self) -> Value {
+                Value::$variant(self)
+            }
+
+            fn from_nbt(value: &Value) -> Option<Self> {
+                match value {
+                    Value::$variant(value) => Some(*value),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+persistent_data_type!(i8, Byte);
+persistent_data_type!(i16, Short);
+persistent_data_type!(i32, Int);
+persistent_data_type!(i64, Long);
+persistent_data_type!(f32, Float);
+persistent_data_type!(f64, Double);
+
+impl PersistentDataType for String {
+    fn to_nbt(self) -> Value {
+        Value::String(self)
+    }
+
+    fn from_nbt(value: &Value) -> Option<Self> {
+        match value {
+            Value::String(value) => Some(value.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Stored as a byte like NBT does
+impl PersistentDataType for bool {
+    fn to_nbt(self) -> Value {
+        Value::Byte(self.into())
+    }
+
+    fn from_nbt(value: &Value) -> Option<Self> {
+        match value {
+            Value::Byte(value) => Some(*value != 0),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! persistent_data_array {
+    ($type:ty, $array:ident) => {
+        impl PersistentDataType for Vec<$type> {
+            fn to_nbt(self) -> Value {
+                Value::$array($array::new(self))
+            }
+
+            fn from_nbt(value: &Value) -> Option<Self> {
+                match value {
+                    Value::$array(array) => Some(array.to_vec()),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+persistent_data_array!(i8, ByteArray);
+persistent_data_array!(i32, IntArray);
+persistent_data_array!(i64, LongArray);
+
+/// Nested containers are stored as compounds
+impl PersistentDataType for PersistentDataContainer {
+    fn to_nbt(self) -> Value {
+        Value::Compound(self.values)
+    }
+
+    fn from_nbt(value: &Value) -> Option<Self> {
+        match value {
+            Value::Compound(values) => Some(Self {
+                values: values.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Values by [`NamespacedKey`]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PersistentDataContainer {
+    values: HashMap<String, Value>,
+}
+
+impl PersistentDataContainer {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns None if there is no value or it has another type
+    #[must_use]
+    pub fn get<T: PersistentDataType>(&self, key: &NamespacedKey) -> Option<T> {
+        T::from_nbt(self.values.get(&key.to_string())?)
+    }
+
+    /// Sets the value, replacing the previous one even if it had another type
+    pub fn set<T: PersistentDataType>(&mut self, key: &NamespacedKey, value: T) {
+        self.values.insert(key.to_string(), value.to_nbt());
+    }
+
+    /// Returns whether there was a value
+    pub fn remove(&mut self, key: &NamespacedKey) -> bool {
+        self.values.remove(&key.to_string()).is_some()
+    }
+
+    #[must_use]
+    pub fn has(&self, key: &NamespacedKey) -> bool {
+        self.values.contains_key(&key.to_string())
+    }
+
+    /// The keys of every value, keys which are not namespaced are skipped
+    pub fn keys(&self) -> impl Iterator<Item = NamespacedKey> + '_ {
+        self.values
+            .keys()
+            .filter_map(|key| NamespacedKey::parse(key))
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Removes every value of the namespace, e.g. when a plugin resets its data
+    pub fn clear_namespace(&mut self, namespace: &str) {
+        self.values.retain(|key, _| {
+            key.split_once(':')
+                .is_none_or(|(key_namespace, _)| key_namespace != namespace)
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{NamespacedKey, PersistentDataContainer};
+
+    fn key(key: &str) -> NamespacedKey {
+        NamespacedKey::parse(key).unwrap()
+    }
+
+    #[test]
+    fn keys() {
+        assert_eq!(key("myplugin:kills").to_string(), "myplugin:kills");
+        assert_eq!(key("my_plugin:stats/kills").key(), "stats/kills");
+        assert!(NamespacedKey::parse("kills").is_none());
+        assert!(NamespacedKey::parse("MyPlugin:kills").is_none());
+        assert!(NamespacedKey::parse("my/plugin:kills").is_none());
+        assert!(NamespacedKey::parse("myplugin:").is_none());
+    }
+
+    #[test]
+    fn typed_values() {
+        let mut container = PersistentDataContainer::new();
+        container.set(&key("test:kills"), 3i32);
+        container.set(&key("test:name"), "Steve".to_string());
+        container.set(&key("test:vip"), true);
+        container.set(&key("test:history"), vec![1i64, 2, 3]);
+
+        assert_eq!(container.get::<i32>(&key("test:kills")), Some(3));
+        // stored as another type
+        assert_eq!(container.get::<i64>(&key("test:kills")), None);
+        assert_eq!(
+            container.get::<String>(&key("test:name")).as_deref(),
+            Some("Steve")
+        );
+        assert_eq!(container.get::<bool>(&key("test:vip")), Some(true));
+        assert_eq!(
+            container.get::<Vec<i64>>(&key("test:history")),
+            Some(vec![1, 2, 3])
+        );
+
+        assert!(container.remove(&key("test:kills")));
+        assert!(!container.has(&key("test:kills")));
+        assert!(!container.remove(&key("test:kills")));
+    }
+
+    #[test]
+    fn nested_containers() {
+        let mut home = PersistentDataContainer::new();
+        home.set(&key("homes:x"), 10.5f64);
+        let mut container = PersistentDataContainer::new();
+        container.set(&key("homes:home"), home.clone());
+        container.set(&key("other:value"), 1i8);
+
+        assert_eq!(
+            container.get::<PersistentDataContainer>(&key("homes:home")),
+            Some(home)
+        );
+
+        container.clear_namespace("homes");
+        assert_eq!(container.keys().collect::<Vec<_>>(), [key("other:value")]);
+    }
+}
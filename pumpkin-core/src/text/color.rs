@@ -87,6 +87,43 @@ impl RGBColor {
     pub fn new(red: u8, green: u8, blue: u8) -> Self {
         RGBColor { red, green, blue }
     }
+
+    /// Parses `RRGGBB`, without the leading `#`
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let component = |range| u8::from_str_radix(&hex[range], 16).ok();
+        Some(Self::new(
+            component(0..2)?,
+            component(2..4)?,
+            component(4..6)?,
+        ))
+    }
+
+    /// The color as `0xRRGGBB`, like particles and other places outside of text use it
+    pub fn to_int(self) -> i32 {
+        (i32::from(self.red) << 16) | (i32::from(self.green) << 8) | i32::from(self.blue)
+    }
+
+    /// The named color which looks the most alike, for places which only support those
+    pub fn nearest_named(self) -> NamedColor {
+        let distance = |color: NamedColor| {
+            let other = color.rgb();
+            [
+                (self.red, other.red),
+                (self.green, other.green),
+                (self.blue, other.blue),
+            ]
+            .iter()
+            .map(|(a, b)| (i32::from(*a) - i32::from(*b)).pow(2))
+            .sum::<i32>()
+        };
+        NamedColor::ALL
+            .into_iter()
+            .min_by_key(|color| distance(*color))
+            .unwrap_or(NamedColor::White)
+    }
 }
 
 impl Serialize for RGBColor {
@@ -123,6 +160,60 @@ pub enum NamedColor {
     White,
 }
 
+impl NamedColor {
+    pub const ALL: [Self; 16] = [
+        Self::Black,
+        Self::DarkBlue,
+        Self::DarkGreen,
+        Self::DarkAqua,
+        Self::DarkRed,
+        Self::DarkPurple,
+        Self::Gold,
+        Self::Gray,
+        Self::DarkGray,
+        Self::Blue,
+        Self::Green,
+        Self::Aqua,
+        Self::Red,
+        Self::LightPurple,
+        Self::Yellow,
+        Self::White,
+    ];
+
+    /// The character of the color in legacy formatting codes like `§c`
+    pub fn code(self) -> char {
+        char::from_digit(self as u32, 16).unwrap_or('f')
+    }
+
+    pub fn from_code(code: char) -> Option<Self> {
+        let index = code.to_ascii_lowercase().to_digit(16)?;
+        Some(Self::ALL[index as usize])
+    }
+
+    /// The color the client renders the text in
+    pub fn rgb(self) -> RGBColor {
+        let (red, green, blue) = match self {
+            Self::Black => (0x00, 0x00, 0x00),
+            Self::DarkBlue => (0x00, 0x00, 0xAA),
+            Self::DarkGreen => (0x00, 0xAA, 0x00),
+            Self::DarkAqua => (0x00, 0xAA, 0xAA),
+            Self::DarkRed => (0xAA, 0x00, 0x00),
+            Self::DarkPurple => (0xAA, 0x00, 0xAA),
+            Self::Gold => (0xFF, 0xAA, 0x00),
+            Self::Gray => (0xAA, 0xAA, 0xAA),
+            Self::DarkGray => (0x55, 0x55, 0x55),
+            Self::Blue => (0x55, 0x55, 0xFF),
+            Self::Green => (0x55, 0xFF, 0x55),
+            Self::Aqua => (0x55, 0xFF, 0xFF),
+            Self::Red => (0xFF, 0x55, 0x55),
+            Self::LightPurple => (0xFF, 0x55, 0xFF),
+            Self::Yellow => (0xFF, 0xFF, 0x55),
+            Self::White => (0xFF, 0xFF, 0xFF),
+        };
+        RGBColor::new(red, green, blue)
+    }
+}
+
 impl TryFrom<&str> for NamedColor {
     type Error = ();
 
@@ -0,0 +1,262 @@
+//! Legacy formatting codes like `§c` or `&l`, which are still common in configs and older plugins.
+//!
+//! A color code resets the formatting before it, like in the old client. Hex colors are written as
+//! `§x§R§R§G§G§B§B` or `&#RRGGBB`.
+
+use super::{
+    color::{Color, NamedColor, RGBColor},
+    style::Style,
+    TextComponent, TextContent,
+};
+
+/// The character the client uses for formatting codes
+pub const SECTION_SIGN: char = '§';
+
+/// Parses text with formatting codes starting with `code_char`, usually [`SECTION_SIGN`] or `&`.
+///
+/// Unknown codes are kept as text.
+pub fn parse(input: &str, code_char: char) -> TextComponent<'static> {
+    let mut root = TextComponent::text("");
+    let mut style = Style::default();
+    let mut text = String::new();
+    let chars: Vec<char> = input.chars().collect();
+
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let Some(&code) = chars.get(i + 1).filter(|_| c == code_char) else {
+            text.push(c);
+            i += 1;
+            continue;
+        };
+
+        let mut next_style = style.clone();
+        let mut consumed = 2;
+        match code.to_ascii_lowercase() {
+            'k' => next_style.obfuscated = Some(1),
+            'l' => next_style.bold = Some(1),
+            'm' => next_style.strikethrough = Some(1),
+            'n' => next_style.underlined = Some(1),
+            'o' => next_style.italic = Some(1),
+            'r' => next_style = Style::default(),
+            'x' => match bungee_hex(&chars[i + 2..], code_char) {
+                Some(color) => {
+                    next_style = Style::default().color(Color::Rgb(color));
+                    consumed += 12;
+                }
+                None => consumed = 0,
+            },
+            '#' => match chars
+                .get(i + 2..i + 8)
+                .and_then(|hex| RGBColor::from_hex(&hex.iter().collect::<String>()))
+            {
+                Some(color) => {
+                    next_style = Style::default().color(Color::Rgb(color));
+                    consumed += 6;
+                }
+                None => consumed = 0,
+            },
+            code => match NamedColor::from_code(code) {
+                Some(color) => next_style = Style::default().color_named(color),
+                None => consumed = 0,
+            },
+        }
+        if consumed == 0 {
+            text.push(c);
+            i += 1;
+            continue;
+        }
+
+        if !text.is_empty() {
+            root.extra.push(styled(std::mem::take(&mut text), style));
+        }
+        style = next_style;
+        i += consumed;
+    }
+    if !text.is_empty() {
+        root.extra.push(styled(text, style));
+    }
+    root
+}
+
+/// Reads the `§R§R§G§G§B§B` after a `§x`
+fn bungee_hex(chars: &[char], code_char: char) -> Option<RGBColor> {
+    let pairs = chars.get(..12)?;
+    let mut hex = String::with_capacity(6);
+    for pair in pairs.chunks(2) {
+        if pair[0] != code_char {
+            return None;
+        }
+        hex.push(pair[1]);
+    }
+    RGBColor::from_hex(&hex)
+}
+
+fn styled(text: String, style: Style<'static>) -> TextComponent<'static> {
+    let mut component = TextComponent::text_string(text);
+    component.style = style;
+    component
+}
+
+/// Writes the component with `§` codes, for places which only take a string like the server list.
+///
+/// Events are lost, and RGB colors become the nearest named color.
+pub fn serialize(component: &TextComponent) -> String {
+    let mut output = String::new();
+    let mut current = Style::default();
+    write_component(component, &Style::default(), &mut current, &mut output);
+    output
+}
+
+fn write_component<'a>(
+    component: &TextComponent<'a>,
+    parent: &Style<'a>,
+    current: &mut Style<'a>,
+    output: &mut String,
+) {
+    let style = inherit(parent, &component.style);
+    let text = match &component.content {
+        TextContent::Text { text } => text.to_string(),
+        TextContent::Translate { translate, with } => super::translation::translate(
+            translate,
+            with.iter().map(|arg| arg.0.to_plain_text()).collect(),
+        ),
+        TextContent::EntityNames { selector, .. } => selector.to_string(),
+        TextContent::Keybind { keybind } => keybind.to_string(),
+    };
+    if !text.is_empty() {
+        if !same_formatting(&style, current) {
+            write_codes(&style, current, output);
+            *current = style.clone();
+        }
+        output.push_str(&text);
+    }
+    for child in &component.extra {
+        write_component(child, &style, current, output);
+    }
+}
+
+/// The style of a child, which inherits everything it does not set itself
+pub(crate) fn inherit<'a>(parent: &Style<'a>, child: &Style<'a>) -> Style<'a> {
+    Style {
+        color: child.color.or(parent.color),
+        bold: child.bold.or(parent.bold),
+        italic: child.italic.or(parent.italic),
+        underlined: child.underlined.or(parent.underlined),
+        strikethrough: child.strikethrough.or(parent.strikethrough),
+        obfuscated: child.obfuscated.or(parent.obfuscated),
+        insertion: child.insertion.clone().or_else(|| parent.insertion.clone()),
+        click_event: child
+            .click_event
+            .clone()
+            .or_else(|| parent.click_event.clone()),
+        hover_event: child
+            .hover_event
+            .clone()
+            .or_else(|| parent.hover_event.clone()),
+    }
+}
+
+fn same_formatting(a: &Style, b: &Style) -> bool {
+    named_color(a) == named_color(b) && flags(a) == flags(b)
+}
+
+fn named_color(style: &Style) -> Option<NamedColor> {
+    match style.color? {
+        Color::Reset => None,
+        Color::Named(color) => Some(color),
+        Color::Rgb(color) => Some(color.nearest_named()),
+    }
+}
+
+/// The codes of the formatting which is on
+fn flags(style: &Style) -> Vec<char> {
+    [
+        (style.obfuscated, 'k'),
+        (style.bold, 'l'),
+        (style.strikethrough, 'm'),
+        (style.underlined, 'n'),
+        (style.italic, 'o'),
+    ]
+    .into_iter()
+    .filter(|(flag, _)| *flag == Some(1))
+    .map(|(_, code)| code)
+    .collect()
+}
+
+fn write_codes(style: &Style, previous: &Style, output: &mut String) {
+    let color = named_color(style);
+    let codes = flags(style);
+    let previous_codes = flags(previous);
+    if color == named_color(previous) && previous_codes.iter().all(|code| codes.contains(code)) {
+        // only formatting was added
+        for code in codes.iter().filter(|code| !previous_codes.contains(code)) {
+            output.push(SECTION_SIGN);
+            output.push(*code);
+        }
+        return;
+    }
+    // formatting can only be turned off with a reset, which a color code does as well
+    match color {
+        Some(color) => {
+            output.push(SECTION_SIGN);
+            output.push(color.code());
+        }
+        None => {
+            output.push(SECTION_SIGN);
+            output.push('r');
+        }
+    }
+    for code in codes {
+        output.push(SECTION_SIGN);
+        output.push(code);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::text::{
+        color::{NamedColor, RGBColor},
+        TextComponent,
+    };
+
+    use super::{parse, serialize};
+
+    #[test]
+    fn parses_codes() {
+        let component = parse("&aHello &lworld&r!", '&');
+        assert_eq!(
+            component,
+            TextComponent::text("")
+                .add_child(TextComponent::text("Hello ").color_named(NamedColor::Green))
+                .add_child(
+                    TextComponent::text("world")
+                        .color_named(NamedColor::Green)
+                        .bold()
+                )
+                .add_child(TextComponent::text("!"))
+        );
+    }
+
+    #[test]
+    fn parses_hex_colors() {
+        let expected = TextComponent::text("")
+            .add_child(TextComponent::text("hex").color_rgb(RGBColor::new(0x12, 0xAB, 0xEF)));
+        assert_eq!(parse("§x§1§2§a§b§e§fhex", '§'), expected);
+        assert_eq!(parse("&#12ABEFhex", '&'), expected);
+    }
+
+    #[test]
+    fn keeps_unknown_codes() {
+        assert_eq!(
+            parse("100&z &", '&'),
+            TextComponent::text("").add_child(TextComponent::text("100&z &"))
+        );
+    }
+
+    #[test]
+    fn round_trips() {
+        let text = "§cred §lbold§r plain";
+        assert_eq!(serialize(&parse(text, '§')), text);
+    }
+}
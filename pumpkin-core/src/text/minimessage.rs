@@ -0,0 +1,345 @@
+//! MiniMessage, a markup for formatted text like `<red>Hello <bold>world</bold>!</red>`.
+//!
+//! Supported are colors (`<red>`, `<#ff5555>`, `<color:gold>`), decorations (`<bold>`, `<!italic>`),
+//! `<click:action:value>`, `<hover:show_text:text>`, `<insert:text>`, `<key:key.jump>`,
+//! `<lang:key:args..>`, `<newline>` and `<reset>`. A closing tag closes everything opened after it,
+//! `</>` only the last tag. Unknown tags are kept as text and `\<` escapes a tag.
+
+use std::borrow::Cow;
+
+use super::{
+    click::ClickEvent,
+    color::{Color, NamedColor, RGBColor},
+    hover::HoverEvent,
+    style::Style,
+    Text, TextComponent, TextContent,
+};
+
+/// Parses the MiniMessage, never failing as anything not understood is kept as text.
+pub fn parse(input: &str) -> TextComponent<'static> {
+    let mut parser = Parser {
+        root: TextComponent::text(""),
+        open: Vec::new(),
+        text: String::new(),
+    };
+    let chars: Vec<char> = input.chars().collect();
+
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' if matches!(chars.get(i + 1), Some('<' | '\\')) => {
+                parser.text.push(chars[i + 1]);
+                i += 2;
+            }
+            '<' => match tag_end(&chars[i + 1..]) {
+                Some(length) => {
+                    let tag: String = chars[i + 1..i + 1 + length].iter().collect();
+                    if !parser.apply_tag(&tag) {
+                        parser.text.push('<');
+                        parser.text.push_str(&tag);
+                        parser.text.push('>');
+                    }
+                    i += length + 2;
+                }
+                None => {
+                    parser.text.push('<');
+                    i += 1;
+                }
+            },
+            c => {
+                parser.text.push(c);
+                i += 1;
+            }
+        }
+    }
+    parser.flush();
+    parser.root
+}
+
+/// Writes the text so [`parse`] reads it as it is.
+pub fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('<', "\\<")
+}
+
+struct Parser {
+    root: TextComponent<'static>,
+    /// The open tags by name, with the style inside of them
+    open: Vec<(String, Style<'static>)>,
+    text: String,
+}
+
+impl Parser {
+    fn style(&self) -> Style<'static> {
+        self.open
+            .last()
+            .map(|(_, style)| style.clone())
+            .unwrap_or_default()
+    }
+
+    fn flush(&mut self) {
+        if self.text.is_empty() {
+            return;
+        }
+        let text = std::mem::take(&mut self.text);
+        self.push(TextContent::Text { text: text.into() });
+    }
+
+    fn push(&mut self, content: TextContent<'static>) {
+        self.root.extra.push(TextComponent {
+            content,
+            style: self.style(),
+            extra: Vec::new(),
+        });
+    }
+
+    /// Returns false if the tag is not known, so it is kept as text
+    fn apply_tag(&mut self, tag: &str) -> bool {
+        if let Some(name) = tag.strip_prefix('/') {
+            return self.close(name);
+        }
+
+        let args = split_args(tag);
+        let name = args[0].to_ascii_lowercase();
+        let args = &args[1..];
+        match name.as_str() {
+            "newline" | "br" => {
+                self.text.push('\n');
+                return true;
+            }
+            "reset" => {
+                self.flush();
+                self.open.clear();
+                return true;
+            }
+            "key" => {
+                let [keybind] = args else {
+                    return false;
+                };
+                self.flush();
+                self.push(TextContent::Keybind {
+                    keybind: keybind.clone().into(),
+                });
+                return true;
+            }
+            "lang" | "tr" | "translate" => {
+                let Some((key, with)) = args.split_first() else {
+                    return false;
+                };
+                self.flush();
+                self.push(TextContent::Translate {
+                    translate: key.clone().into(),
+                    with: with.iter().map(|arg| Text(Box::new(parse(arg)))).collect(),
+                });
+                return true;
+            }
+            _ => {}
+        }
+
+        let Some(style) = self.tag_style(&name, args) else {
+            return false;
+        };
+        self.flush();
+        self.open.push((name, style));
+        true
+    }
+
+    fn close(&mut self, name: &str) -> bool {
+        let name = name
+            .split(':')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let position = if name.is_empty() {
+            self.open.len().checked_sub(1)
+        } else {
+            self.open.iter().rposition(|(open, _)| *open == name)
+        };
+        let Some(position) = position else {
+            return false;
+        };
+        self.flush();
+        self.open.truncate(position);
+        true
+    }
+
+    /// The style inside an opening tag
+    fn tag_style(&self, name: &str, args: &[String]) -> Option<Style<'static>> {
+        let mut style = self.style();
+        if let Some(color) = parse_color(name) {
+            style.color = Some(color);
+            return Some(style);
+        }
+
+        let (decoration, on) = match name.strip_prefix('!') {
+            Some(decoration) => (decoration, false),
+            None => (name, args.first().is_none_or(|arg| arg != "false")),
+        };
+        let on = Some(u8::from(on));
+        match decoration {
+            "bold" | "b" => style.bold = on,
+            "italic" | "i" | "em" => style.italic = on,
+            "underlined" | "u" => style.underlined = on,
+            "strikethrough" | "st" => style.strikethrough = on,
+            "obfuscated" | "obf" => style.obfuscated = on,
+            "color" | "colour" | "c" => style.color = Some(parse_color(args.first()?)?),
+            "click" => style.click_event = Some(parse_click(args)?),
+            "hover" => {
+                let [action, value] = args else {
+                    return None;
+                };
+                if action != "show_text" {
+                    return None;
+                }
+                // the tooltip is a string, which the client still renders legacy codes in
+                let text = parse(value).to_legacy();
+                style.hover_event = Some(HoverEvent::ShowText(text.into()));
+            }
+            "insert" | "insertion" => style.insertion = Some(args.first()?.clone()),
+            _ => return None,
+        }
+        Some(style)
+    }
+}
+
+fn parse_color(name: &str) -> Option<Color> {
+    if let Some(hex) = name.strip_prefix('#') {
+        return RGBColor::from_hex(hex).map(Color::Rgb);
+    }
+    let name = name.replace("grey", "gray");
+    NamedColor::try_from(name.as_str()).ok().map(Color::Named)
+}
+
+fn parse_click(args: &[String]) -> Option<ClickEvent<'static>> {
+    let [action, value] = args else {
+        return None;
+    };
+    let value: Cow<'static, str> = value.clone().into();
+    Some(match action.as_str() {
+        "open_url" => ClickEvent::OpenUrl(value),
+        "run_command" => ClickEvent::RunCommand(value),
+        "suggest_command" => ClickEvent::SuggestCommand(value),
+        "change_page" => ClickEvent::ChangePage(value.parse().ok()?),
+        "copy_to_clipboard" => ClickEvent::CopyToClipboard(value),
+        _ => return None,
+    })
+}
+
+/// Returns the length of the tag starting after its `<`, skipping quoted arguments
+fn tag_end(chars: &[char]) -> Option<usize> {
+    let mut quote = None;
+    for (i, &c) in chars.iter().enumerate() {
+        match (quote, c) {
+            (None, '>') => return Some(i),
+            // a tag can not span another one
+            (None, '<') => return None,
+            (None, '\'' | '"') => quote = Some(c),
+            (Some(open), c) if c == open => quote = None,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Splits the tag at `:`, removing the quotes around arguments
+fn split_args(tag: &str) -> Vec<String> {
+    let mut args = vec![String::new()];
+    let mut quote = None;
+    let mut chars = tag.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(open), c) if c == open => quote = None,
+            (Some(_), '\\') => {
+                if let Some(escaped) = chars.next() {
+                    args.last_mut().unwrap().push(escaped);
+                }
+            }
+            (None, '\'' | '"') => quote = Some(c),
+            (None, ':') => args.push(String::new()),
+            _ => args.last_mut().unwrap().push(c),
+        }
+    }
+    args
+}
+
+#[cfg(test)]
+mod test {
+    use crate::text::{
+        click::ClickEvent,
+        color::{NamedColor, RGBColor},
+        hover::HoverEvent,
+        TextComponent,
+    };
+
+    use super::{escape, parse};
+
+    #[test]
+    fn colors_and_decorations() {
+        assert_eq!(
+            parse("<red>Hello <bold>world</bold>!</red> <#12abef>hex"),
+            TextComponent::text("")
+                .add_child(TextComponent::text("Hello ").color_named(NamedColor::Red))
+                .add_child(
+                    TextComponent::text("world")
+                        .color_named(NamedColor::Red)
+                        .bold()
+                )
+                .add_child(TextComponent::text("!").color_named(NamedColor::Red))
+                .add_child(TextComponent::text(" "))
+                .add_child(TextComponent::text("hex").color_rgb(RGBColor::new(0x12, 0xAB, 0xEF)))
+        );
+    }
+
+    #[test]
+    fn closing_tag_closes_the_ones_inside() {
+        assert_eq!(
+            parse("<green><italic>a</green>b"),
+            TextComponent::text("")
+                .add_child(
+                    TextComponent::text("a")
+                        .color_named(NamedColor::Green)
+                        .italic()
+                )
+                .add_child(TextComponent::text("b"))
+        );
+    }
+
+    #[test]
+    fn events() {
+        assert_eq!(
+            parse("<click:run_command:'/tp 0 64 0'><hover:show_text:'<gold>Go'>spawn"),
+            TextComponent::text("").add_child(
+                TextComponent::text("spawn")
+                    .click_event(ClickEvent::RunCommand("/tp 0 64 0".into()))
+                    .hover_event(HoverEvent::ShowText("§6Go".into()))
+            )
+        );
+    }
+
+    #[test]
+    fn keeps_unknown_tags() {
+        assert_eq!(
+            parse("a <unknown> b </red> 1 < 2 \\<red>"),
+            TextComponent::text("")
+                .add_child(TextComponent::text("a <unknown> b </red> 1 < 2 <red>"))
+        );
+        assert_eq!(
+            parse(&escape("<red>")),
+            TextComponent::text("").add_child(TextComponent::text("<red>"))
+        );
+    }
+
+    #[test]
+    fn translations() {
+        assert_eq!(
+            parse("<lang:chat.type.text:Steve:'<red>hi'>"),
+            TextComponent::text("").add_child(TextComponent::translate(
+                "chat.type.text",
+                vec![
+                    TextComponent::text("").add_child(TextComponent::text("Steve")),
+                    TextComponent::text("")
+                        .add_child(TextComponent::text("hi").color_named(NamedColor::Red)),
+                ]
+            ))
+        );
+    }
+}
@@ -12,7 +12,10 @@ use style::Style;
 pub mod click;
 pub mod color;
 pub mod hover;
+pub mod legacy;
+pub mod minimessage;
 pub mod style;
+pub mod translation;
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(transparent)]
@@ -32,6 +35,9 @@ pub struct TextComponent<'a> {
     /// Also has `ClickEvent
     #[serde(flatten)]
     pub style: Style<'a>,
+    /// Components appended to this one, they inherit its style
+    #[serde(default)]
+    pub extra: Vec<TextComponent<'a>>,
 }
 
 impl<'a> TextComponent<'a> {
@@ -39,6 +45,7 @@ impl<'a> TextComponent<'a> {
         Self {
             content: TextContent::Text { text: text.into() },
             style: Style::default(),
+            extra: Vec::new(),
         }
     }
 
@@ -46,15 +53,82 @@ impl<'a> TextComponent<'a> {
         Self {
             content: TextContent::Text { text: text.into() },
             style: Style::default(),
+            extra: Vec::new(),
         }
     }
 
+    /// Text which is translated by the client, `with` replaces the placeholders in the translation
+    pub fn translate(key: impl Into<Cow<'a, str>>, with: Vec<TextComponent<'a>>) -> Self {
+        Self {
+            content: TextContent::Translate {
+                translate: key.into(),
+                with: with.into_iter().map(|arg| Text(Box::new(arg))).collect(),
+            },
+            style: Style::default(),
+            extra: Vec::new(),
+        }
+    }
+
+    /// A key the player bound, shown as the key they bound it to, e.g. `key.jump`
+    pub fn keybind(key: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            content: TextContent::Keybind {
+                keybind: key.into(),
+            },
+            style: Style::default(),
+            extra: Vec::new(),
+        }
+    }
+
+    /// Parses text with `§` formatting codes, see [`legacy`]
+    pub fn from_legacy(text: &str) -> TextComponent<'static> {
+        legacy::parse(text, legacy::SECTION_SIGN)
+    }
+
+    /// Parses text with MiniMessage tags like `<red>`, see [`minimessage`]
+    pub fn from_mini_message(text: &str) -> TextComponent<'static> {
+        minimessage::parse(text)
+    }
+
+    /// The text with `§` formatting codes, for places which only take a string
+    pub fn to_legacy(&self) -> String {
+        legacy::serialize(self)
+    }
+
+    /// The text without any formatting, translations are in English
+    pub fn to_plain_text(&self) -> String {
+        let mut text = match &self.content {
+            TextContent::Text { text } => text.to_string(),
+            TextContent::Translate { translate, with } => translation::translate(
+                translate,
+                with.iter().map(|arg| arg.0.to_plain_text()).collect(),
+            ),
+            TextContent::EntityNames { selector, .. } => selector.to_string(),
+            TextContent::Keybind { keybind } => keybind.to_string(),
+        };
+        for child in &self.extra {
+            text.push_str(&child.to_plain_text());
+        }
+        text
+    }
+
+    /// Appends a component, which inherits the style of this one
+    pub fn add_child(mut self, child: TextComponent<'a>) -> Self {
+        self.extra.push(child);
+        self
+    }
+
     pub fn to_pretty_console(self) -> String {
         let style = self.style;
         let color = style.color;
         let mut text = match self.content {
             TextContent::Text { text } => text.into_owned(),
-            TextContent::Translate { translate, with: _ } => translate.into_owned(),
+            TextContent::Translate { translate, with } => translation::translate(
+                &translate,
+                with.into_iter()
+                    .map(|arg| arg.0.to_pretty_console())
+                    .collect(),
+            ),
             TextContent::EntityNames {
                 selector,
                 separator: _,
@@ -82,6 +156,9 @@ impl<'a> TextComponent<'a> {
                 text = format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text).to_string()
             }
         }
+        for child in self.extra {
+            text.push_str(&child.to_pretty_console());
+        }
         text
     }
 }
@@ -159,23 +236,55 @@ impl<'a> TextComponent<'a> {
         self
     }
 
+    /// The component in its JSON form, for the places which still use it like the server list
+    pub fn as_json(&self) -> impl Serialize + '_ {
+        NetworkComponent::new(self)
+    }
+
     pub fn encode(&self) -> Vec<u8> {
-        // TODO: Somehow fix this ugly mess
-        #[derive(serde::Serialize)]
-        #[serde(rename_all = "camelCase")]
-        struct TempStruct<'a> {
-            #[serde(flatten)]
-            text: &'a TextContent<'a>,
-            #[serde(flatten)]
-            style: &'a Style<'a>,
-        }
-        let astruct = TempStruct {
-            text: &self.content,
-            style: &self.style,
-        };
-        // dbg!(&serde_json::to_string(&astruct));
+        fastnbt::to_bytes_with_opts(&NetworkComponent::new(self), SerOpts::network_nbt()).unwrap()
+    }
+}
+
+// TODO: Somehow fix this ugly mess
+/// `TextComponent` serializes itself as bytes, so nested components (children and translation arguments)
+/// have to be serialized through this instead
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NetworkComponent<'a> {
+    #[serde(flatten)]
+    content: NetworkContent<'a>,
+    #[serde(flatten)]
+    style: &'a Style<'a>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    extra: Vec<NetworkComponent<'a>>,
+}
 
-        fastnbt::to_bytes_with_opts(&astruct, SerOpts::network_nbt()).unwrap()
+#[derive(Serialize)]
+#[serde(untagged)]
+enum NetworkContent<'a> {
+    Translate {
+        translate: &'a str,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        with: Vec<NetworkComponent<'a>>,
+    },
+    Other(&'a TextContent<'a>),
+}
+
+impl<'a> NetworkComponent<'a> {
+    fn new(component: &'a TextComponent<'a>) -> Self {
+        let content = match &component.content {
+            TextContent::Translate { translate, with } => NetworkContent::Translate {
+                translate,
+                with: with.iter().map(|arg| Self::new(&arg.0)).collect(),
+            },
+            content => NetworkContent::Other(content),
+        };
+        Self {
+            content,
+            style: &component.style,
+            extra: component.extra.iter().map(Self::new).collect(),
+        }
     }
 }
 
@@ -0,0 +1,152 @@
+//! English translations for the console, players get the translations of their own language from the client.
+
+// TODO: load the full en_us language file
+const EN_US: &[(&str, &str)] = &[
+    ("command.context.here", "<--[HERE]"),
+    (
+        "command.expected.separator",
+        "Expected whitespace to end one argument, but found trailing data",
+    ),
+    (
+        "command.failed",
+        "An unexpected error occurred trying to execute that command",
+    ),
+    ("command.unknown.argument", "Incorrect argument for command"),
+    (
+        "command.unknown.command",
+        "Unknown or incomplete command, see below for error",
+    ),
+    ("death.attack.cactus", "%1$s was pricked to death"),
+    (
+        "death.attack.cactus.player",
+        "%1$s walked into a cactus while trying to escape %2$s",
+    ),
+    ("death.attack.drown", "%1$s drowned"),
+    (
+        "death.attack.drown.player",
+        "%1$s drowned while trying to escape %2$s",
+    ),
+    ("death.attack.freeze", "%1$s froze to death"),
+    (
+        "death.attack.freeze.player",
+        "%1$s was frozen to death by %2$s",
+    ),
+    ("death.attack.generic", "%1$s died"),
+    ("death.attack.genericKill", "%1$s was killed"),
+    ("death.attack.inFire", "%1$s went up in flames"),
+    (
+        "death.attack.inFire.player",
+        "%1$s walked into fire while fighting %2$s",
+    ),
+    ("death.attack.indirectMagic", "%1$s was killed by %2$s"),
+    ("death.attack.lava", "%1$s tried to swim in lava"),
+    (
+        "death.attack.lava.player",
+        "%1$s tried to swim in lava to escape %2$s",
+    ),
+    ("death.attack.magic", "%1$s was killed by magic"),
+    (
+        "death.attack.magic.player",
+        "%1$s was killed by magic while trying to escape %2$s",
+    ),
+    ("death.attack.onFire", "%1$s burned to death"),
+    (
+        "death.attack.onFire.player",
+        "%1$s was burned to a crisp while fighting %2$s",
+    ),
+    ("death.attack.player", "%1$s was slain by %2$s"),
+    (
+        "death.attack.sweetBerryBush",
+        "%1$s was poked to death by a sweet berry bush",
+    ),
+    (
+        "death.attack.sweetBerryBush.player",
+        "%1$s was poked to death by a sweet berry bush while trying to escape %2$s",
+    ),
+    ("death.fell.accident.generic", "%1$s fell from a high place"),
+    ("death.fell.assist", "%1$s was doomed to fall by %2$s"),
+];
+
+/// Returns the English translation of a key, or the key itself if it is unknown.
+#[must_use]
+pub fn get_translation(key: &str) -> &str {
+    EN_US
+        .iter()
+        .find(|(translation_key, _)| *translation_key == key)
+        .map_or(key, |(_, translation)| translation)
+}
+
+/// Translates a key and fills in the arguments, like the client does with translatable components.
+///
+/// Supports `%s`, the indexed `%1$s` and the escaped `%%`.
+#[must_use]
+pub fn translate(key: &str, args: Vec<String>) -> String {
+    let translation = get_translation(key);
+    let mut result = String::with_capacity(translation.len());
+    let mut next_arg = 0;
+    let mut chars = translation.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            result.push(c);
+            continue;
+        }
+        match chars.peek() {
+            Some('%') => {
+                chars.next();
+                result.push('%');
+            }
+            Some('s') => {
+                chars.next();
+                result.push_str(args.get(next_arg).map_or("", String::as_str));
+                next_arg += 1;
+            }
+            Some(digit) if digit.is_ascii_digit() => {
+                let mut index = String::new();
+                while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                    index.push(digit);
+                }
+                if chars.next_if_eq(&'$').is_some() && chars.next_if_eq(&'s').is_some() {
+                    let arg = index
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|index| args.get(index.checked_sub(1)?));
+                    result.push_str(arg.map_or("", String::as_str));
+                } else {
+                    result.push('%');
+                    result.push_str(&index);
+                }
+            }
+            _ => result.push('%'),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::translate;
+
+    #[test]
+    fn placeholders() {
+        assert_eq!(
+            translate("command.unknown.argument", vec![]),
+            "Incorrect argument for command"
+        );
+        assert_eq!(translate("unknown.key", vec![]), "unknown.key");
+        assert_eq!(
+            translate("command.context.here", vec!["unused".into()]),
+            "<--[HERE]"
+        );
+    }
+
+    #[test]
+    fn indexed_placeholders() {
+        // unknown keys are returned as they are, so use them to test the formatting
+        assert_eq!(
+            translate("%2$s %1$s 100%%", vec!["a".into(), "b".into()]),
+            "b a 100%"
+        );
+        assert_eq!(translate("%s and %s", vec!["x".into()]), "x and ");
+    }
+}
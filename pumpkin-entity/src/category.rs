@@ -0,0 +1,51 @@
+use crate::entity_type::EntityType;
+
+impl EntityType {
+    /// Monsters, which are hostile to players. Conduits attack them
+    #[must_use]
+    pub const fn is_enemy(&self) -> bool {
+        matches!(
+            self,
+            Self::Blaze
+                | Self::Bogged
+                | Self::Breeze
+                | Self::CaveSpider
+                | Self::Creaking
+                | Self::Creeper
+                | Self::Drowned
+                | Self::ElderGuardian
+                | Self::EnderDragon
+                | Self::Enderman
+                | Self::Endermite
+                | Self::Evoker
+                | Self::Ghast
+                | Self::Giant
+                | Self::Guardian
+                | Self::Hoglin
+                | Self::Husk
+                | Self::Illusioner
+                | Self::MagmaCube
+                | Self::Phantom
+                | Self::Piglin
+                | Self::PiglinBrute
+                | Self::Pillager
+                | Self::Ravager
+                | Self::Shulker
+                | Self::Silverfish
+                | Self::Skeleton
+                | Self::Slime
+                | Self::Spider
+                | Self::Stray
+                | Self::Vex
+                | Self::Vindicator
+                | Self::Warden
+                | Self::Witch
+                | Self::Wither
+                | Self::WitherSkeleton
+                | Self::Zoglin
+                | Self::Zombie
+                | Self::ZombieVillager
+                | Self::ZombifiedPiglin
+        )
+    }
+}
@@ -0,0 +1,18 @@
+use pumpkin_macros::registry_enum;
+
+/// The status effects, like speed or regeneration
+#[registry_enum("minecraft:mob_effect")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum EffectType {}
+
+impl EffectType {
+    /// Effects which act once when they are applied instead of lasting
+    #[must_use]
+    pub const fn is_instant(self) -> bool {
+        matches!(
+            self,
+            Self::InstantHealth | Self::InstantDamage | Self::Saturation
+        )
+    }
+}
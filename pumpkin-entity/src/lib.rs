@@ -1,4 +1,7 @@
+pub mod category;
+pub mod effect_type;
 pub mod entity_type;
 pub mod pose;
+pub mod tracking;
 
 pub type EntityId = i32;
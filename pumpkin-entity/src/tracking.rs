@@ -0,0 +1,145 @@
+use crate::entity_type::EntityType;
+
+/// An update interval of entities which never move on their own
+pub const NEVER_UPDATED: u32 = u32::MAX;
+
+impl EntityType {
+    /// How far away players see the entity, in chunks. This is further limited by their view distance
+    #[must_use]
+    pub const fn tracking_range(&self) -> u8 {
+        match self {
+            Self::Player => 32,
+            Self::EndCrystal | Self::LightningBolt | Self::Warden => 16,
+            Self::EnderDragon
+            | Self::Wither
+            | Self::Ghast
+            | Self::ElderGuardian
+            | Self::Ravager
+            | Self::Shulker
+            | Self::Slime
+            | Self::Giant
+            | Self::ArmorStand
+            | Self::FallingBlock
+            | Self::Tnt
+            | Self::Painting
+            | Self::ItemFrame
+            | Self::GlowItemFrame
+            | Self::LeashKnot
+            | Self::AreaEffectCloud
+            | Self::BlockDisplay
+            | Self::ItemDisplay
+            | Self::TextDisplay
+            | Self::Interaction => 10,
+            Self::Blaze
+            | Self::Bogged
+            | Self::Breeze
+            | Self::CaveSpider
+            | Self::Creaking
+            | Self::CreakingTransient
+            | Self::Creeper
+            | Self::Drowned
+            | Self::Enderman
+            | Self::Endermite
+            | Self::Evoker
+            | Self::Guardian
+            | Self::Hoglin
+            | Self::Husk
+            | Self::Illusioner
+            | Self::MagmaCube
+            | Self::Phantom
+            | Self::Piglin
+            | Self::PiglinBrute
+            | Self::Pillager
+            | Self::Silverfish
+            | Self::Skeleton
+            | Self::Spider
+            | Self::Stray
+            | Self::Vex
+            | Self::Vindicator
+            | Self::Witch
+            | Self::WitherSkeleton
+            | Self::Zoglin
+            | Self::Zombie
+            | Self::ZombieVillager
+            | Self::ZombifiedPiglin
+            | Self::Minecart
+            | Self::ChestMinecart
+            | Self::CommandBlockMinecart
+            | Self::FurnaceMinecart
+            | Self::HopperMinecart
+            | Self::SpawnerMinecart
+            | Self::TntMinecart
+            | Self::ShulkerBullet => 8,
+            Self::Item | Self::ExperienceOrb | Self::EvokerFangs => 6,
+            Self::Bat => 5,
+            Self::Arrow
+            | Self::SpectralArrow
+            | Self::Trident
+            | Self::Egg
+            | Self::Snowball
+            | Self::EnderPearl
+            | Self::EyeOfEnder
+            | Self::Potion
+            | Self::ExperienceBottle
+            | Self::Fireball
+            | Self::SmallFireball
+            | Self::DragonFireball
+            | Self::WitherSkull
+            | Self::WindCharge
+            | Self::BreezeWindCharge
+            | Self::LlamaSpit
+            | Self::FireworkRocket
+            | Self::FishingBobber
+            | Self::Cod
+            | Self::Salmon
+            | Self::Pufferfish
+            | Self::TropicalFish
+            | Self::Tadpole => 4,
+            Self::Marker => 0,
+            // animals, villagers, golems and vehicles
+            _ => 10,
+        }
+    }
+
+    /// Every how many ticks movement of the entity is sent to the players seeing it
+    #[must_use]
+    pub const fn update_interval(&self) -> u32 {
+        match self {
+            Self::Player => 2,
+            Self::ShulkerBullet | Self::BlockDisplay | Self::ItemDisplay | Self::TextDisplay => 1,
+            Self::EvokerFangs => 2,
+            Self::EyeOfEnder => 4,
+            Self::FishingBobber => 5,
+            Self::Tnt
+            | Self::Egg
+            | Self::Snowball
+            | Self::EnderPearl
+            | Self::Potion
+            | Self::ExperienceBottle
+            | Self::Fireball
+            | Self::SmallFireball
+            | Self::DragonFireball
+            | Self::WitherSkull
+            | Self::WindCharge
+            | Self::BreezeWindCharge
+            | Self::LlamaSpit
+            | Self::FireworkRocket => 10,
+            Self::Item
+            | Self::ExperienceOrb
+            | Self::FallingBlock
+            | Self::Arrow
+            | Self::SpectralArrow
+            | Self::Trident => 20,
+            Self::Painting
+            | Self::ItemFrame
+            | Self::GlowItemFrame
+            | Self::LeashKnot
+            | Self::EndCrystal
+            | Self::LightningBolt
+            | Self::AreaEffectCloud
+            | Self::Interaction
+            | Self::Marker => NEVER_UPDATED,
+            _ => 3,
+        }
+    }
+}
@@ -0,0 +1,78 @@
+//! Beacons, which give the players around them the effects chosen in their menu.
+
+use pumpkin_world::{block::BlockFace, item::ItemStack};
+
+use crate::{
+    window_property::{self, WindowProperty},
+    Container, WindowType,
+};
+
+/// The slot of the item paid for changing the effects
+pub const PAYMENT_SLOT: usize = 0;
+
+#[derive(Default)]
+pub struct Beacon {
+    payment: Option<ItemStack>,
+    /// How many layers the pyramid below has, 0 to 4
+    levels: i16,
+    /// The ids of the effects in `minecraft:mob_effect`
+    primary: Option<i16>,
+    secondary: Option<i16>,
+}
+
+/// The client expects the effect id plus one, 0 is none
+fn encode(effect: Option<i16>) -> i16 {
+    effect.map_or(0, |effect| effect + 1)
+}
+
+fn decode(value: i16) -> Option<i16> {
+    (value > 0).then_some(value - 1)
+}
+
+impl Container for Beacon {
+    fn window_type(&self) -> &'static WindowType {
+        &WindowType::Beacon
+    }
+
+    fn window_name(&self) -> &'static str {
+        "Beacon"
+    }
+
+    fn all_slots(&mut self) -> Vec<&mut Option<ItemStack>> {
+        vec![&mut self.payment]
+    }
+
+    fn all_slots_ref(&self) -> Vec<Option<&ItemStack>> {
+        vec![self.payment.as_ref()]
+    }
+
+    fn properties(&self) -> Vec<(i16, i16)> {
+        vec![
+            WindowProperty::new(window_property::Beacon::PowerLevel, self.levels).into_tuple(),
+            WindowProperty::new(
+                window_property::Beacon::FirstPotionEffect,
+                encode(self.primary),
+            )
+            .into_tuple(),
+            WindowProperty::new(
+                window_property::Beacon::SecondPotionEffect,
+                encode(self.secondary),
+            )
+            .into_tuple(),
+        ]
+    }
+
+    fn set_property(&mut self, property: i16, value: i16) {
+        match property {
+            0 => self.levels = value,
+            1 => self.primary = decode(value),
+            2 => self.secondary = decode(value),
+            _ => {}
+        }
+    }
+
+    /// Hoppers can't pay for beacons
+    fn slots_for_face(&self, _face: BlockFace) -> Vec<usize> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,199 @@
+//! Brewing stands, which brew their ingredient into the potions in their bottle slots.
+
+use std::ops::Range;
+
+use pumpkin_world::{
+    block::BlockFace,
+    item::{
+        item_registry::{get_item, get_item_name},
+        potion::{BREW_TIME, FUEL_PER_BLAZE_POWDER},
+        ItemStack,
+    },
+};
+
+use crate::{
+    window_property::{self, WindowProperty},
+    Container, ContainerTick, WindowType,
+};
+
+/// The slots of the three potions
+pub const BOTTLE_SLOTS: Range<usize> = 0..3;
+pub const INGREDIENT_SLOT: usize = 3;
+pub const FUEL_SLOT: usize = 4;
+
+#[derive(Default)]
+pub struct BrewingStand {
+    slots: [Option<ItemStack>; 5],
+    /// Ticks until the potions are brewed, 0 if nothing is brewing
+    brew_time: i32,
+    /// How many more brews the blaze powder in the stand fuels
+    fuel: i32,
+    /// The item being brewed, brewing stops if it is taken out
+    ingredient: Option<u16>,
+}
+
+impl BrewingStand {
+    fn can_brew(&self) -> bool {
+        let Some(ingredient) = &self.slots[INGREDIENT_SLOT] else {
+            return false;
+        };
+        ingredient.is_brewing_ingredient()
+            && self.slots[BOTTLE_SLOTS]
+                .iter()
+                .flatten()
+                .any(|bottle| bottle.brew(ingredient).is_some())
+    }
+
+    fn brew(&mut self) {
+        let Some(ingredient) = self.slots[INGREDIENT_SLOT].clone() else {
+            return;
+        };
+        for slot in &mut self.slots[BOTTLE_SLOTS] {
+            if let Some(brewed) = slot.as_ref().and_then(|bottle| bottle.brew(&ingredient)) {
+                *slot = Some(brewed);
+            }
+        }
+        let slot = &mut self.slots[INGREDIENT_SLOT];
+        if let Some(stack) = slot {
+            stack.item_count -= 1;
+            if stack.item_count == 0 {
+                *slot = None;
+            }
+        }
+        // dragon's breath leaves its bottle behind, vanilla drops it if the slot isn't empty
+        if slot.is_none() && get_item_name(ingredient.item_id) == Some("minecraft:dragon_breath") {
+            *slot = get_item("minecraft:glass_bottle").map(|bottle| ItemStack::new(1, bottle.id));
+        }
+    }
+
+    /// Puts another blaze powder into the fuel once the last one is used up
+    fn refuel(&mut self) -> bool {
+        let slot = &mut self.slots[FUEL_SLOT];
+        let Some(fuel) = slot
+            .as_mut()
+            .filter(|fuel| get_item_name(fuel.item_id) == Some("minecraft:blaze_powder"))
+        else {
+            return false;
+        };
+        if self.fuel > 0 {
+            return false;
+        }
+        self.fuel = FUEL_PER_BLAZE_POWDER;
+        fuel.item_count -= 1;
+        if fuel.item_count == 0 {
+            *slot = None;
+        }
+        true
+    }
+}
+
+impl Container for BrewingStand {
+    fn window_type(&self) -> &'static WindowType {
+        &WindowType::BrewingStand
+    }
+
+    fn window_name(&self) -> &'static str {
+        "Brewing Stand"
+    }
+
+    fn all_slots(&mut self) -> Vec<&mut Option<ItemStack>> {
+        self.slots.iter_mut().collect()
+    }
+
+    fn all_slots_ref(&self) -> Vec<Option<&ItemStack>> {
+        self.slots.iter().map(Option::as_ref).collect()
+    }
+
+    /// Brews like vanilla: a brew takes [`BREW_TIME`] ticks and a bit of fuel, it stops if the
+    /// ingredient is taken out or nothing can be brewed anymore
+    fn tick(&mut self) -> ContainerTick {
+        let mut tick = ContainerTick {
+            slots_changed: self.refuel(),
+            finished: false,
+        };
+        let can_brew = self.can_brew();
+        if self.brew_time > 0 {
+            self.brew_time -= 1;
+            let ingredient = self.slots[INGREDIENT_SLOT]
+                .as_ref()
+                .map(|ingredient| ingredient.item_id);
+            if self.brew_time == 0 && can_brew {
+                self.brew();
+                tick.slots_changed = true;
+                tick.finished = true;
+            } else if !can_brew || ingredient != self.ingredient {
+                self.brew_time = 0;
+            }
+        } else if can_brew && self.fuel > 0 {
+            self.fuel -= 1;
+            self.brew_time = BREW_TIME;
+            self.ingredient = self.slots[INGREDIENT_SLOT]
+                .as_ref()
+                .map(|ingredient| ingredient.item_id);
+        }
+        tick
+    }
+
+    fn properties(&self) -> Vec<(i16, i16)> {
+        vec![
+            WindowProperty::new(
+                window_property::BrewingStand::BrewTime,
+                self.brew_time as i16,
+            )
+            .into_tuple(),
+            WindowProperty::new(window_property::BrewingStand::FuelTime, self.fuel as i16)
+                .into_tuple(),
+        ]
+    }
+
+    /// The brew goes on with the ingredient in the stand, like it does in vanilla after loading
+    fn set_property(&mut self, property: i16, value: i16) {
+        match property {
+            0 => {
+                self.brew_time = value.into();
+                self.ingredient = self.slots[INGREDIENT_SLOT]
+                    .as_ref()
+                    .map(|ingredient| ingredient.item_id);
+            }
+            1 => self.fuel = value.into(),
+            _ => {}
+        }
+    }
+
+    /// Hoppers fill in the ingredient from above and bottles and fuel from the sides, and take
+    /// the potions out from below
+    fn slots_for_face(&self, face: BlockFace) -> Vec<usize> {
+        match face {
+            BlockFace::Top => vec![INGREDIENT_SLOT],
+            BlockFace::Bottom => vec![0, 1, 2, INGREDIENT_SLOT],
+            _ => vec![0, 1, 2, FUEL_SLOT],
+        }
+    }
+
+    fn can_place_item(&self, slot: usize, stack: &ItemStack) -> bool {
+        let name = get_item_name(stack.item_id);
+        match slot {
+            INGREDIENT_SLOT => stack.is_brewing_ingredient(),
+            FUEL_SLOT => name == Some("minecraft:blaze_powder"),
+            _ => {
+                self.slots.get(slot).is_some_and(Option::is_none)
+                    && matches!(
+                        name,
+                        Some(
+                            "minecraft:potion"
+                                | "minecraft:splash_potion"
+                                | "minecraft:lingering_potion"
+                                | "minecraft:glass_bottle"
+                        )
+                    )
+            }
+        }
+    }
+
+    /// Only the bottle left behind by dragon's breath comes out of the ingredient slot
+    fn can_take_item(&self, slot: usize, stack: &ItemStack, face: BlockFace) -> bool {
+        face != BlockFace::Bottom
+            || slot != INGREDIENT_SLOT
+            || get_item_name(stack.item_id) == Some("minecraft:glass_bottle")
+    }
+}
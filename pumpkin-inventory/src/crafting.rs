@@ -1,30 +1,55 @@
 use itertools::Itertools;
 use pumpkin_registry::{
-    flatten_3x3, get_tag_values, IngredientSlot, IngredientType, RecipeResult, TagCategory, RECIPES,
+    flatten_3x3, is_in, recipes, IngredientSlot, IngredientType, RecipeResult, TagCategory,
 };
-use pumpkin_world::item::item_registry::get_item;
+use pumpkin_world::item::component::{ComponentPatch, DataComponent};
+use pumpkin_world::item::item_registry::{get_item, get_item_name};
 use pumpkin_world::item::ItemStack;
 use rayon::prelude::*;
 
 #[inline(always)]
-fn check_ingredient_type(ingredient_type: &IngredientType, input: ItemStack) -> bool {
+fn check_ingredient_type(ingredient_type: &IngredientType, input: &ItemStack) -> bool {
     match ingredient_type {
         IngredientType::Tag(tag) => {
-            let items = match get_tag_values(TagCategory::Item, tag) {
-                Some(items) => items,
-                None => return false,
-            };
-            items
-                .iter()
-                .any(|tag| check_ingredient_type(&tag.to_ingredient_type(), input))
+            get_item_name(input.item_id).is_some_and(|name| is_in(TagCategory::Item, tag, name))
         }
         IngredientType::Item(item) => get_item(item).is_some_and(|item| item.id == input.item_id),
     }
 }
 
+/// Eight arrows around a lingering potion make tipped arrows of the potion
+fn tipped_arrows(input: &[[Option<ItemStack>; 3]; 3]) -> Option<ItemStack> {
+    let potion = input[1][1]
+        .as_ref()
+        .filter(|item| get_item_name(item.item_id) == Some("minecraft:lingering_potion"))?;
+    let arrows = input
+        .iter()
+        .flatten()
+        .enumerate()
+        .filter(|(i, _)| *i != 4)
+        .all(|(_, item)| {
+            item.as_ref()
+                .is_some_and(|item| get_item_name(item.item_id) == Some("minecraft:arrow"))
+        });
+    if !arrows {
+        return None;
+    }
+    let mut tipped = ItemStack::new(8, get_item("minecraft:tipped_arrow")?.id);
+    if let Some(contents) = potion.potion_contents() {
+        tipped
+            .components
+            .set(DataComponent::PotionContents(contents.clone()));
+    }
+    Some(tipped)
+}
+
 pub fn check_if_matches_crafting(input: [[Option<ItemStack>; 3]; 3]) -> Option<ItemStack> {
+    // special recipes aren't data driven
+    if let Some(tipped) = tipped_arrows(&input) {
+        return Some(tipped);
+    }
     let input = flatten_3x3(input);
-    RECIPES
+    recipes()
         .par_iter()
         .find_any(|recipe| {
             let patterns = recipe.pattern();
@@ -36,13 +61,13 @@ pub fn check_if_matches_crafting(input: [[Option<ItemStack>; 3]; 3]) -> Option<I
             {
                 false
             } else if recipe.recipe_type.is_shapeless() {
-                shapeless_crafting_match(input, recipe.pattern())
+                shapeless_crafting_match(&input, recipe.pattern())
             } else {
                 patterns.par_iter().any(|pattern| {
                     pattern.iter().enumerate().all(|(i, row)| {
                         row.iter()
                             .enumerate()
-                            .all(|(j, item)| match (item, input[i][j]) {
+                            .all(|(j, item)| match (item, &input[i][j]) {
                                 (Some(item), Some(input)) => ingredient_slot_check(item, input),
                                 (None, None) => true,
                                 (Some(_), None) | (None, Some(_)) => false,
@@ -53,18 +78,20 @@ pub fn check_if_matches_crafting(input: [[Option<ItemStack>; 3]; 3]) -> Option<I
         })
         .map(|recipe| match recipe.result() {
             RecipeResult::Single { id, .. } => Some(ItemStack {
-                item_id: get_item(id).unwrap().id,
+                item_id: get_item(id)?.id,
                 item_count: 1,
+                components: ComponentPatch::default(),
             }),
             RecipeResult::Many { id, count, .. } => Some(ItemStack {
-                item_id: get_item(id).unwrap().id,
+                item_id: get_item(id)?.id,
                 item_count: *count,
+                components: ComponentPatch::default(),
             }),
             RecipeResult::Special => None,
         })?
 }
 
-fn ingredient_slot_check(recipe_item: &IngredientSlot, input: ItemStack) -> bool {
+fn ingredient_slot_check(recipe_item: &IngredientSlot, input: &ItemStack) -> bool {
     match recipe_item {
         IngredientSlot::Single(ingredient) => check_ingredient_type(ingredient, input),
         IngredientSlot::Many(ingredients) => ingredients
@@ -73,7 +100,7 @@ fn ingredient_slot_check(recipe_item: &IngredientSlot, input: ItemStack) -> bool
     }
 }
 fn shapeless_crafting_match(
-    input: [[Option<ItemStack>; 3]; 3],
+    input: &[[Option<ItemStack>; 3]; 3],
     pattern: &[[[Option<IngredientSlot>; 3]; 3]],
 ) -> bool {
     let mut pattern = pattern
@@ -83,7 +110,7 @@ fn shapeless_crafting_match(
         .flatten()
         .cloned()
         .collect_vec();
-    for item in input.into_iter().flatten().flatten() {
+    for item in input.iter().flatten().flatten() {
         if let Some(index) = pattern.iter().enumerate().find_map(|(i, recipe_item)| {
             if ingredient_slot_check(recipe_item, item) {
                 Some(i)
@@ -0,0 +1,44 @@
+//! Dispensers and droppers, which throw out an item of a random slot when they are powered.
+
+use pumpkin_world::item::ItemStack;
+
+use crate::{Container, WindowType};
+
+#[derive(Default)]
+pub struct Dispenser {
+    slots: [Option<ItemStack>; 9],
+    /// Droppers only drop their items, or put them into the container they face
+    dropper: bool,
+}
+
+impl Dispenser {
+    #[must_use]
+    pub fn dropper() -> Self {
+        Self {
+            dropper: true,
+            ..Self::default()
+        }
+    }
+}
+
+impl Container for Dispenser {
+    fn window_type(&self) -> &'static WindowType {
+        &WindowType::Generic3x3
+    }
+
+    fn window_name(&self) -> &'static str {
+        if self.dropper {
+            "Dropper"
+        } else {
+            "Dispenser"
+        }
+    }
+
+    fn all_slots(&mut self) -> Vec<&mut Option<ItemStack>> {
+        self.slots.iter_mut().collect()
+    }
+
+    fn all_slots_ref(&self) -> Vec<Option<&ItemStack>> {
+        self.slots.iter().map(Option::as_ref).collect()
+    }
+}
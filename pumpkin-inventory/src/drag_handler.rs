@@ -73,10 +73,7 @@ impl DragHandler {
             Err(InventoryError::MultiplePlayersDragging)?
         }
         let mut slots = container.all_slots();
-        let slots_cloned = slots
-            .iter()
-            .map(|stack| stack.map(|item| item.to_owned()))
-            .collect_vec();
+        let slots_cloned = slots.iter().map(|stack| Option::clone(stack)).collect_vec();
         let Some(carried_item) = maybe_carried_item else {
             return Ok(());
         };
@@ -85,15 +82,15 @@ impl DragHandler {
             // Checked in any function that uses this function.
             MouseDragType::Middle => {
                 for slot in &drag.slots {
-                    *slots[*slot] = *maybe_carried_item;
+                    slots[*slot].clone_from(maybe_carried_item);
                 }
             }
             MouseDragType::Right => {
-                let mut single_item = *carried_item;
+                let mut single_item = carried_item.clone();
                 single_item.item_count = 1;
 
                 let changing_slots =
-                    drag.possibly_changing_slots(&slots_cloned, carried_item.item_id);
+                    drag.possibly_changing_slots(&slots_cloned, carried_item.clone());
                 changing_slots.for_each(|slot| {
                     if carried_item.item_count != 0 {
                         carried_item.item_count -= 1;
@@ -105,7 +102,7 @@ impl DragHandler {
                                 carried_item.item_count += 1;
                             }
                         } else {
-                            *slots[slot] = Some(single_item)
+                            *slots[slot] = Some(single_item.clone())
                         }
                     }
                 });
@@ -119,13 +116,13 @@ impl DragHandler {
                 // In that specific case, follow MouseDragType::Right behaviours instead!
 
                 let changing_slots =
-                    drag.possibly_changing_slots(&slots_cloned, carried_item.item_id);
+                    drag.possibly_changing_slots(&slots_cloned, carried_item.clone());
                 let amount_of_slots = changing_slots.clone().count();
                 let (amount_per_slot, remainder) =
                     (carried_item.item_count as usize).div_rem_euclid(&amount_of_slots);
-                let mut item_in_each_slot = *carried_item;
+                let mut item_in_each_slot = carried_item.clone();
                 item_in_each_slot.item_count = amount_per_slot as u8;
-                changing_slots.for_each(|slot| *slots[slot] = Some(item_in_each_slot));
+                changing_slots.for_each(|slot| *slots[slot] = Some(item_in_each_slot.clone()));
 
                 if remainder > 0 {
                     carried_item.item_count = remainder as u8;
@@ -148,14 +145,14 @@ impl Drag {
     fn possibly_changing_slots<'a>(
         &'a self,
         slots: &'a [Option<ItemStack>],
-        carried_item_id: u16,
+        carried_item: ItemStack,
     ) -> impl Iterator<Item = usize> + 'a + Clone {
         self.slots.iter().filter_map(move |slot_index| {
             let slot = &slots[*slot_index];
 
             match slot {
                 Some(item_slot) => {
-                    if item_slot.item_id == carried_item_id {
+                    if *item_slot == carried_item {
                         Some(*slot_index)
                     } else {
                         None
@@ -0,0 +1,28 @@
+//! Hoppers, which pass items from the container above them into the one they face.
+
+use pumpkin_world::item::ItemStack;
+
+use crate::{Container, WindowType};
+
+#[derive(Default)]
+pub struct Hopper {
+    slots: [Option<ItemStack>; 5],
+}
+
+impl Container for Hopper {
+    fn window_type(&self) -> &'static WindowType {
+        &WindowType::Hopper
+    }
+
+    fn window_name(&self) -> &'static str {
+        "Item Hopper"
+    }
+
+    fn all_slots(&mut self) -> Vec<&mut Option<ItemStack>> {
+        self.slots.iter_mut().collect()
+    }
+
+    fn all_slots_ref(&self) -> Vec<Option<&ItemStack>> {
+        self.slots.iter().map(Option::as_ref).collect()
+    }
+}
@@ -0,0 +1,68 @@
+//! The inventory of horses, donkeys, llamas and camels: a saddle, what they wear on their body
+//! and the chest some of them carry.
+
+use pumpkin_world::item::{item_registry::get_item_name, ItemStack};
+
+use crate::{Container, WindowType};
+
+pub const SADDLE_SLOT: usize = 0;
+/// Horse armor, or the carpet of llamas
+pub const BODY_SLOT: usize = 1;
+/// The chest slots follow the saddle and body slots
+pub const CHEST_SLOTS_START: usize = 2;
+
+pub struct HorseInventory {
+    saddle: Option<ItemStack>,
+    body: Option<ItemStack>,
+    chest: Vec<Option<ItemStack>>,
+    /// What fits into the body slot, nothing for mobs which wear nothing
+    body_items: &'static [&'static str],
+}
+
+impl HorseInventory {
+    #[must_use]
+    pub fn new(chest_slots: usize, body_items: &'static [&'static str]) -> Self {
+        Self {
+            saddle: None,
+            body: None,
+            chest: vec![None; chest_slots],
+            body_items,
+        }
+    }
+}
+
+impl Container for HorseInventory {
+    /// Horse screens are opened by their own packet, which has no window type
+    fn window_type(&self) -> &'static WindowType {
+        &WindowType::Generic9x1
+    }
+
+    fn window_name(&self) -> &'static str {
+        "Horse"
+    }
+
+    fn all_slots(&mut self) -> Vec<&mut Option<ItemStack>> {
+        [&mut self.saddle, &mut self.body]
+            .into_iter()
+            .chain(self.chest.iter_mut())
+            .collect()
+    }
+
+    fn all_slots_ref(&self) -> Vec<Option<&ItemStack>> {
+        [self.saddle.as_ref(), self.body.as_ref()]
+            .into_iter()
+            .chain(self.chest.iter().map(Option::as_ref))
+            .collect()
+    }
+
+    fn can_place_item(&self, slot: usize, stack: &ItemStack) -> bool {
+        let name = get_item_name(stack.item_id);
+        match slot {
+            SADDLE_SLOT => self.saddle.is_none() && name == Some("minecraft:saddle"),
+            BODY_SLOT => {
+                self.body.is_none() && name.is_some_and(|name| self.body_items.contains(&name))
+            }
+            _ => slot - CHEST_SLOTS_START < self.chest.len(),
+        }
+    }
+}
@@ -2,17 +2,27 @@ use crate::container_click::MouseClick;
 use crate::player::PlayerInventory;
 use num_derive::FromPrimitive;
 use pumpkin_macros::screen;
-use pumpkin_world::item::ItemStack;
+use pumpkin_world::{block::BlockFace, item::ItemStack};
 
+mod beacon;
+mod brewing_stand;
 pub mod container_click;
 mod crafting;
+mod dispenser;
 pub mod drag_handler;
 mod error;
+mod hopper;
+pub mod horse;
 mod open_container;
 pub mod player;
+pub mod transfer;
 pub mod window_property;
 
+pub use beacon::*;
+pub use brewing_stand::*;
+pub use dispenser::*;
 pub use error::InventoryError;
+pub use hopper::*;
 pub use open_container::*;
 
 /// https://wiki.vg/Inventory
@@ -55,6 +65,14 @@ pub enum WindowType {
     CartographyTable = screen!("minecraft:cartography_table"),
     Stonecutter = screen!("minecraft:stonecutter"),
 }
+/// What a container which works by itself, like a brewing stand, did in a tick
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ContainerTick {
+    pub slots_changed: bool,
+    /// It finished its work, e.g. brewed the potions
+    pub finished: bool,
+}
+
 pub struct ContainerStruct<const SLOTS: usize>([Option<ItemStack>; SLOTS]);
 
 // Container needs Sync + Send to be able to be in async Server
@@ -77,7 +95,7 @@ pub trait Container: Sync + Send {
         if taking_crafted {
             match (all_slots[slot].as_mut(), carried_item.as_mut()) {
                 (Some(s1), Some(s2)) => {
-                    if s1.item_id == s2.item_id {
+                    if s1 == s2 {
                         handle_item_change(all_slots[slot], carried_item, mouse_click);
                     }
                 }
@@ -122,10 +140,39 @@ pub trait Container: Sync + Send {
     fn crafted_item_slot(&self) -> Option<ItemStack> {
         self.all_slots_ref()
             .get(self.crafting_output_slot()?)?
-            .copied()
+            .cloned()
     }
 
     fn recipe_used(&mut self) {}
+
+    /// Called every tick for containers of blocks, which may change their items by themselves
+    fn tick(&mut self) -> ContainerTick {
+        ContainerTick::default()
+    }
+
+    /// The window properties players looking into the container are sent, e.g. the progress
+    fn properties(&self) -> Vec<(i16, i16)> {
+        Vec::new()
+    }
+
+    /// Changes one of the window properties, containers ignore the ones they don't have
+    fn set_property(&mut self, _property: i16, _value: i16) {}
+
+    /// The slots hoppers and droppers reach through the face of the container's block
+    fn slots_for_face(&self, _face: BlockFace) -> Vec<usize> {
+        (0..self.all_slots_ref().len()).collect()
+    }
+
+    /// Whether hoppers and droppers may put the stack into the slot, e.g. brewing stands only
+    /// take blaze powder as fuel
+    fn can_place_item(&self, _slot: usize, _stack: &ItemStack) -> bool {
+        true
+    }
+
+    /// Whether hoppers may take the stack out of the slot through the face
+    fn can_take_item(&self, _slot: usize, _stack: &ItemStack, _face: BlockFace) -> bool {
+        true
+    }
 }
 
 pub fn handle_item_take(
@@ -136,7 +183,7 @@ pub fn handle_item_take(
     let Some(item) = item_slot else {
         return;
     };
-    let mut new_item = *item;
+    let mut new_item = item.clone();
 
     match mouse_click {
         MouseClick::Left => {
@@ -158,10 +205,10 @@ pub fn handle_item_change(
     match (current_slot.as_mut(), carried_slot.as_mut()) {
         // Swap or combine current and carried
         (Some(current), Some(carried)) => {
-            if current.item_id == carried.item_id {
+            if current == carried {
                 combine_stacks(carried_slot, current, mouse_click);
             } else if mouse_click == MouseClick::Left {
-                let carried = *carried;
+                let carried = carried.clone();
                 *carried_slot = Some(current.to_owned());
                 *current_slot = Some(carried.to_owned());
             }
@@ -174,7 +221,7 @@ pub fn handle_item_change(
             }
             MouseClick::Right => {
                 carried.item_count -= 1;
-                let mut new = *carried;
+                let mut new = carried.clone();
                 new.item_count = 1;
                 *current_slot = Some(new);
             }
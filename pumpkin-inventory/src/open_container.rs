@@ -36,6 +36,14 @@ impl OpenContainer {
         }
     }
 
+    /// Lets players look into a container which outlives them, like the one of a block
+    pub fn new(container: Arc<Mutex<Box<dyn Container>>>) -> Self {
+        Self {
+            players: Vec::new(),
+            container,
+        }
+    }
+
     pub fn new_empty_container<C: Container + Default + 'static>(player_id: i32) -> Self {
         Self {
             players: vec![player_id],
@@ -52,7 +60,7 @@ pub struct Chest([Option<ItemStack>; 27]);
 
 impl Chest {
     pub fn new() -> Self {
-        Self([None; 27])
+        Self([const { None }; 27])
     }
 }
 impl Container for Chest {
@@ -113,8 +121,8 @@ impl Container for CraftingTable {
     }
 
     fn craft(&mut self) -> bool {
-        let old_output = self.output;
-        self.output = check_if_matches_crafting(self.input);
+        let old_output = self.output.take();
+        self.output = check_if_matches_crafting(self.input.clone());
         old_output != self.output
             || self.input.iter().flatten().any(|s| s.is_some())
             || self.output.is_some()
@@ -30,10 +30,10 @@ impl PlayerInventory {
 
     pub fn new() -> Self {
         Self {
-            crafting: [None; 4],
+            crafting: [const { None }; 4],
             crafting_output: None,
-            items: [None; 36],
-            armor: [None; 4],
+            items: [const { None }; 36],
+            armor: [const { None }; 4],
             offhand: None,
             // TODO: What when player spawns in with an different index ?
             selected: 0,
@@ -109,6 +109,34 @@ impl PlayerInventory {
         self.selected = slot;
     }
 
+    /// The selected hotbar slot, from 0 to 8
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// The number vanilla saves the slot as in `playerdata`, the crafting grid is not saved
+    pub fn saved_slot(slot: usize) -> Option<i8> {
+        match slot {
+            // Boots are saved first
+            5..=8 => Some(108 - slot as i8),
+            9..=35 => Some(slot as i8),
+            36..=44 => Some(slot as i8 - 36),
+            45 => Some(-106),
+            _ => None,
+        }
+    }
+
+    /// The slot of a number vanilla saves in `playerdata`, see [`Self::saved_slot`]
+    pub fn from_saved_slot(saved: i8) -> Option<usize> {
+        match saved {
+            0..=8 => Some(saved as usize + 36),
+            9..=35 => Some(saved as usize),
+            100..=103 => Some(108 - saved as usize),
+            -106 => Some(45),
+            _ => None,
+        }
+    }
+
     pub fn held_item(&self) -> Option<&ItemStack> {
         debug_assert!((0..9).contains(&self.selected));
         self.items[self.selected + 36 - 9].as_ref()
@@ -119,6 +147,15 @@ impl PlayerInventory {
         &mut self.items[self.selected + 36 - 9]
     }
 
+    pub fn offhand_item(&self) -> Option<&ItemStack> {
+        self.offhand.as_ref()
+    }
+
+    /// Helmet, chestplate, leggings and boots
+    pub fn armor(&self) -> &[Option<ItemStack>; 4] {
+        &self.armor
+    }
+
     pub fn slots(&self) -> Vec<Option<&ItemStack>> {
         let mut slots = vec![self.crafting_output.as_ref()];
         slots.extend(self.crafting.iter().map(|c| c.as_ref()));
@@ -203,9 +240,9 @@ impl Container for PlayerInventory {
     }
 
     fn craft(&mut self) -> bool {
-        let v1 = [self.crafting[0], self.crafting[1], None];
-        let v2 = [self.crafting[2], self.crafting[3], None];
-        let v3 = [None; 3];
+        let v1 = [self.crafting[0].clone(), self.crafting[1].clone(), None];
+        let v2 = [self.crafting[2].clone(), self.crafting[3].clone(), None];
+        let v3 = [const { None }; 3];
         let together = [v1, v2, v3];
 
         self.crafting_output = check_if_matches_crafting(together);
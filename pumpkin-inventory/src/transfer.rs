@@ -0,0 +1,95 @@
+//! Moving items between containers without a player, like hoppers and droppers do.
+
+use pumpkin_world::{
+    block::BlockFace,
+    item::{item_registry::get_item_by_id, ItemStack},
+};
+
+use crate::Container;
+
+/// How many of the stack's item fit into one slot
+#[must_use]
+pub fn max_stack_size(stack: &ItemStack) -> u8 {
+    get_item_by_id(stack.item_id).map_or(64, |item| item.components.max_stack_size)
+}
+
+/// Puts as much of the stack as fits into the container through the face, slot by slot like
+/// vanilla. Returns what is left
+pub fn insert_stack(
+    container: &mut dyn Container,
+    mut stack: ItemStack,
+    face: BlockFace,
+) -> Option<ItemStack> {
+    let max_stack = max_stack_size(&stack);
+    let slots: Vec<_> = container
+        .slots_for_face(face)
+        .into_iter()
+        .filter(|slot| container.can_place_item(*slot, &stack))
+        .collect();
+    let mut all_slots = container.all_slots();
+    for slot in slots {
+        let Some(slot) = all_slots.get_mut(slot) else {
+            continue;
+        };
+        match slot {
+            Some(item) if *item == stack => {
+                let moved = stack
+                    .item_count
+                    .min(max_stack.saturating_sub(item.item_count));
+                item.item_count += moved;
+                stack.item_count -= moved;
+            }
+            Some(_) => continue,
+            None => {
+                let moved = stack.item_count.min(max_stack);
+                let mut part = stack.clone();
+                part.item_count = moved;
+                **slot = Some(part);
+                stack.item_count -= moved;
+            }
+        }
+        if stack.item_count == 0 {
+            return None;
+        }
+    }
+    Some(stack)
+}
+
+/// Takes one item out of the slot
+pub fn remove_one(container: &mut dyn Container, slot: usize) -> Option<ItemStack> {
+    let mut all_slots = container.all_slots();
+    let slot = all_slots.get_mut(slot)?;
+    let stack = slot.as_mut()?;
+    let mut taken = stack.clone();
+    taken.item_count = 1;
+    stack.item_count -= 1;
+    if stack.item_count == 0 {
+        **slot = None;
+    }
+    Some(taken)
+}
+
+/// Moves one item out of the first slot it can be taken from through `from_face` into the other
+/// container through `to_face`. Returns whether an item moved
+pub fn move_one(
+    from: &mut dyn Container,
+    from_face: BlockFace,
+    to: &mut dyn Container,
+    to_face: BlockFace,
+) -> bool {
+    for slot in from.slots_for_face(from_face) {
+        let Some(stack) = from.all_slots_ref().get(slot).copied().flatten().cloned() else {
+            continue;
+        };
+        if !from.can_take_item(slot, &stack, from_face) {
+            continue;
+        }
+        let mut one = stack;
+        one.item_count = 1;
+        if insert_stack(to, one, to_face).is_none() {
+            remove_one(from, slot);
+            return true;
+        }
+    }
+    false
+}
@@ -58,3 +58,16 @@ mod particle;
 pub fn particle(item: TokenStream) -> TokenStream {
     particle::particle_impl(item)
 }
+
+mod registry;
+/// The names of a registry's entries in the order of their ids, as an array
+#[proc_macro]
+pub fn registry_entries(item: TokenStream) -> TokenStream {
+    registry::registry_entries_impl(item)
+}
+
+/// Fills the enum with a variant for each entry of the registry, which is its id
+#[proc_macro_attribute]
+pub fn registry_enum(input: TokenStream, item: TokenStream) -> TokenStream {
+    registry::registry_enum_impl(input, item)
+}
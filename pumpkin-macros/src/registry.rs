@@ -0,0 +1,108 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::LazyLock,
+};
+
+use proc_macro::TokenStream;
+use proc_macro2::{Ident, Span};
+use quote::quote;
+
+/// The ids of the registries built into the game, by registry and entry
+static REGISTRIES: LazyLock<HashMap<String, HashMap<String, i32>>> = LazyLock::new(|| {
+    serde_json::from_str(include_str!("../../assets/registries.json"))
+        .expect("Could not parse registries.json registry.")
+});
+
+/// The registries sent to clients, which are sorted by name like they are sent
+static SYNCED_REGISTRIES: LazyLock<HashMap<String, BTreeMap<String, serde_json::Value>>> =
+    LazyLock::new(|| {
+        serde_json::from_str(include_str!("../../assets/synced_registries.json"))
+            .expect("Could not parse synced_registries.json registry.")
+    });
+
+/// The names of the entries with their protocol ids, in the order of the ids. Synced registries
+/// are numbered in the order they are sent
+fn entries(registry: &str) -> Vec<(String, i32)> {
+    if let Some(entries) = REGISTRIES.get(registry) {
+        let mut entries: Vec<_> = entries
+            .iter()
+            .map(|(name, id)| (name.clone(), *id))
+            .collect();
+        entries.sort_by_key(|(_, id)| *id);
+        return entries;
+    }
+    SYNCED_REGISTRIES
+        .get(registry)
+        .expect("Invalid registry")
+        .keys()
+        .zip(0..)
+        .map(|(name, id)| (name.clone(), id))
+        .collect()
+}
+
+fn registry_name(item: TokenStream) -> String {
+    item.to_string().trim_matches('"').to_string()
+}
+
+pub(crate) fn registry_entries_impl(item: TokenStream) -> TokenStream {
+    let names = entries(&registry_name(item))
+        .into_iter()
+        .map(|(name, _)| name);
+    quote! { [#(#names),*] }.into()
+}
+
+pub(crate) fn registry_enum_impl(input: TokenStream, item: TokenStream) -> TokenStream {
+    let mut ast: syn::DeriveInput = syn::parse(item).expect("Expected an enum");
+    let syn::Data::Enum(data) = &mut ast.data else {
+        panic!("Expected an enum");
+    };
+    let (entries, ids): (Vec<_>, Vec<_>) = entries(&registry_name(input)).into_iter().unzip();
+    let variants: Vec<_> = entries
+        .iter()
+        .map(|entry| {
+            let path = entry
+                .split_once(':')
+                .map_or(entry.as_str(), |(_, path)| path);
+            let variant: String = path
+                .split('_')
+                .map(|word| {
+                    let mut chars = word.chars();
+                    chars.next().map_or_else(String::new, |first| {
+                        first.to_uppercase().chain(chars).collect()
+                    })
+                })
+                .collect();
+            Ident::new(&variant, Span::call_site())
+        })
+        .collect();
+    data.variants = syn::parse_quote! { #(#variants = #ids),* };
+    let name = &ast.ident;
+
+    quote! {
+        #ast
+
+        impl #name {
+            pub fn from_name(name: &str) -> Option<Self> {
+                match name {
+                    #(#entries => Some(Self::#variants),)*
+                    _ => None,
+                }
+            }
+
+            /// The entry with the network id
+            pub const fn from_id(id: i32) -> Option<Self> {
+                match id {
+                    #(#ids => Some(Self::#variants),)*
+                    _ => None,
+                }
+            }
+
+            pub const fn name(&self) -> &'static str {
+                match self {
+                    #(Self::#variants => #entries,)*
+                }
+            }
+        }
+    }
+    .into()
+}
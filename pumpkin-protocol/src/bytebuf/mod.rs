@@ -1,11 +1,11 @@
-use crate::{BitSet, FixedBitSet, VarInt, VarLongType};
+use crate::{BitSet, FixedBitSet, VarInt, VarLong, VarLongType};
 use bytes::{Buf, BufMut, BytesMut};
 use core::str;
 
-mod deserializer;
+pub(crate) mod deserializer;
 pub use deserializer::DeserializerError;
 pub mod packet_id;
-mod serializer;
+pub(crate) mod serializer;
 
 const SEGMENT_BITS: u8 = 0x7F;
 const CONTINUE_BIT: u8 = 0x80;
@@ -158,6 +158,19 @@ impl ByteBuffer {
         }
     }
 
+    pub fn put_var_long(&mut self, value: &VarLong) {
+        let mut val = value.0 as u64;
+        loop {
+            let b = val as u8 & SEGMENT_BITS;
+            val >>= 7;
+            if val == 0 {
+                self.buffer.put_u8(b);
+                break;
+            }
+            self.buffer.put_u8(b | CONTINUE_BIT);
+        }
+    }
+
     pub fn put_bit_set(&mut self, set: &BitSet) {
         self.put_var_int(&set.0);
         for b in set.1 {
@@ -413,9 +426,18 @@ mod test {
 
     use crate::{
         bytebuf::{deserializer, serializer, ByteBuffer},
-        VarInt,
+        VarInt, VarLong,
     };
 
+    #[test]
+    fn test_varlong_roundtrip() {
+        for value in [0, 1, 127, 128, 25565, i64::from(i32::MAX) + 1, -1, i64::MIN] {
+            let mut buffer = ByteBuffer::empty();
+            buffer.put_var_long(&VarLong(value));
+            assert_eq!(buffer.get_var_long().unwrap(), value);
+        }
+    }
+
     #[test]
     fn test_i32_reserialize() {
         #[derive(serde::Serialize, serde::Deserialize, PartialEq, Eq, Debug)]
@@ -0,0 +1,305 @@
+//! Recording the packets of a connection to debug protocol desyncs.
+//!
+//! A capture starts with [`MAGIC`], followed by one record per packet:
+//! the [`Direction`] as a byte, the time since the capture started in microseconds as a big endian `u64`,
+//! the length of the packet as a VarInt and the uncompressed packet id and data.
+//! Captures can be read with [`CaptureReader`] and fed back into a decoder with [`replay`].
+
+use std::{
+    io::{self, Read, Write},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    packet_decoder::{PacketDecodeError, PacketDecoder},
+    RawPacket, VarInt,
+};
+
+/// The start of every capture, the last byte is the version of the format
+pub const MAGIC: [u8; 6] = *b"PKCAP\x01";
+
+/// A capture shared by the encoder and decoder of a connection
+pub type SharedCapture = Arc<Mutex<PacketCapture>>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Direction {
+    Serverbound = 0,
+    Clientbound = 1,
+}
+
+impl TryFrom<u8> for Direction {
+    type Error = io::Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Serverbound),
+            1 => Ok(Self::Clientbound),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unknown packet direction {value}"),
+            )),
+        }
+    }
+}
+
+/// Writes packets to a capture
+pub struct PacketCapture {
+    writer: Box<dyn Write + Send>,
+    start: Instant,
+}
+
+impl PacketCapture {
+    pub fn new(mut writer: Box<dyn Write + Send>) -> io::Result<Self> {
+        writer.write_all(&MAGIC)?;
+        Ok(Self {
+            writer,
+            start: Instant::now(),
+        })
+    }
+
+    #[must_use]
+    pub fn shared(self) -> SharedCapture {
+        Arc::new(Mutex::new(self))
+    }
+
+    /// Records the packet id followed by the packet data.
+    pub fn record(&mut self, direction: Direction, packet: &[u8]) -> io::Result<()> {
+        let time = self.start.elapsed().as_micros() as u64;
+        let mut header = Vec::with_capacity(14);
+        header.push(direction as u8);
+        header.extend_from_slice(&time.to_be_bytes());
+        VarInt(packet.len() as i32).encode(&mut header)?;
+        self.writer.write_all(&header)?;
+        self.writer.write_all(packet)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Records the packet, the capture is stopped if that fails.
+pub(crate) fn record(capture: &mut Option<SharedCapture>, direction: Direction, packet: &[u8]) {
+    with_capture(capture, |writer| writer.record(direction, packet));
+}
+
+/// Writes the buffered packets to the capture, called once a batch of packets is sent or read so
+/// the capture is complete up to the last batch if the server crashes.
+pub(crate) fn flush(capture: &mut Option<SharedCapture>) {
+    with_capture(capture, PacketCapture::flush);
+}
+
+fn with_capture(
+    capture: &mut Option<SharedCapture>,
+    f: impl FnOnce(&mut PacketCapture) -> io::Result<()>,
+) {
+    let Some(shared) = capture else {
+        return;
+    };
+    let Ok(mut writer) = shared.lock() else {
+        return;
+    };
+    if let Err(error) = f(&mut writer) {
+        log::warn!("Stopped packet capture: {error}");
+        drop(writer);
+        *capture = None;
+    }
+}
+
+/// A packet read from a capture
+#[derive(Clone, Debug)]
+pub struct CapturedPacket {
+    pub direction: Direction,
+    /// Since the capture started
+    pub time: Duration,
+    /// The packet id followed by the packet data
+    pub data: Vec<u8>,
+}
+
+impl CapturedPacket {
+    /// The packet framed like on a connection without compression and encryption
+    #[must_use]
+    pub fn framed(&self) -> Vec<u8> {
+        let mut framed = Vec::with_capacity(self.data.len() + 5);
+        VarInt(self.data.len() as i32)
+            .encode(&mut framed)
+            .expect("Writing to a Vec can not fail");
+        framed.extend_from_slice(&self.data);
+        framed
+    }
+}
+
+/// Reads the packets of a capture in order
+pub struct CaptureReader<R> {
+    reader: R,
+}
+
+impl<R: Read> CaptureReader<R> {
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0; MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a packet capture",
+            ));
+        }
+        Ok(Self { reader })
+    }
+
+    fn read_packet(&mut self, direction: u8) -> io::Result<CapturedPacket> {
+        let direction = Direction::try_from(direction)?;
+        let mut time = [0; 8];
+        self.reader.read_exact(&mut time)?;
+        let len = self.read_var_int()?;
+        let mut data = vec![0; len];
+        self.reader.read_exact(&mut data)?;
+        Ok(CapturedPacket {
+            direction,
+            time: Duration::from_micros(u64::from_be_bytes(time)),
+            data,
+        })
+    }
+
+    fn read_var_int(&mut self) -> io::Result<usize> {
+        let mut value = 0;
+        for i in 0..VarInt::MAX_SIZE {
+            let mut byte = [0];
+            self.reader.read_exact(&mut byte)?;
+            value |= usize::from(byte[0] & 0x7F) << (i * 7);
+            if byte[0] & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "packet length is too long",
+        ))
+    }
+}
+
+impl<R: Read> Iterator for CaptureReader<R> {
+    type Item = io::Result<CapturedPacket>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut direction = [0];
+        match self.reader.read(&mut direction) {
+            // the capture ended
+            Ok(0) => None,
+            Ok(_) => Some(self.read_packet(direction[0])),
+            Err(error) => Some(Err(error)),
+        }
+    }
+}
+
+/// Decodes the captured packets of the direction like they arrived on a connection.
+pub fn replay<'a>(
+    packets: impl IntoIterator<Item = &'a CapturedPacket>,
+    direction: Direction,
+) -> Result<Vec<RawPacket>, PacketDecodeError> {
+    let mut decoder = PacketDecoder::default();
+    let mut decoded = Vec::new();
+    for packet in packets {
+        if packet.direction != direction {
+            continue;
+        }
+        decoder.queue_slice(&packet.framed());
+        while let Some(packet) = decoder.decode()? {
+            decoded.push(packet);
+        }
+    }
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        io::{BufWriter, Write},
+        sync::{Arc, Mutex},
+    };
+
+    use crate::{
+        bytebuf::{packet_id::Packet, ByteBuffer},
+        packet_decoder::PacketDecoder,
+        packet_encoder::PacketEncoder,
+        ClientPacket,
+    };
+
+    use super::{replay, CaptureReader, Direction, PacketCapture};
+
+    /// Keeps what was written, so the test can read it after the capture is done
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    struct Payload(Vec<u8>);
+
+    impl Packet for Payload {
+        const PACKET_ID: i32 = 0x21;
+    }
+
+    impl ClientPacket for Payload {
+        fn write(&self, bytebuf: &mut ByteBuffer) {
+            bytebuf.put_slice(&self.0);
+        }
+    }
+
+    #[test]
+    fn records_and_replays_both_directions() {
+        let buf = SharedBuf::default();
+        // buffered like on a server, the batches are flushed by the encoder and decoder
+        let capture = PacketCapture::new(Box::new(BufWriter::new(buf.clone())))
+            .unwrap()
+            .shared();
+
+        // clientbound, compressed on the wire but captured uncompressed
+        let mut encoder = PacketEncoder::default();
+        encoder.set_capture(Some(capture.clone()));
+        encoder.set_compression(Some(pumpkin_config::compression::CompressionInfo {
+            threshold: 0,
+            level: 6,
+            adaptive: false,
+        }));
+        encoder.append_packet(&Payload(vec![1, 2, 3])).unwrap();
+        let sent = encoder.take();
+
+        // serverbound, the same bytes read back
+        let mut decoder = PacketDecoder::default();
+        decoder.set_capture(Some(capture));
+        decoder.set_compression(Some(0));
+        decoder.queue_slice(&sent);
+        decoder.decode().unwrap().unwrap();
+
+        let bytes = buf.0.lock().unwrap().clone();
+        let packets = CaptureReader::new(&bytes[..])
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(packets.len(), 2);
+        assert_eq!(packets[0].direction, Direction::Clientbound);
+        assert_eq!(packets[1].direction, Direction::Serverbound);
+        assert_eq!(packets[0].data, [0x21, 1, 2, 3]);
+        assert_eq!(packets[0].data, packets[1].data);
+
+        let mut replayed = replay(&packets, Direction::Clientbound).unwrap();
+        assert_eq!(replayed.len(), 1);
+        assert_eq!(replayed[0].id.0, 0x21);
+        assert_eq!(replayed[0].bytebuf.buf().to_vec(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert!(CaptureReader::new(&b"not a capture"[..]).is_err());
+    }
+}
@@ -0,0 +1,24 @@
+use pumpkin_macros::client_packet;
+
+use crate::{bytebuf::ByteBuffer, ClientPacket};
+
+/// Details the client adds to its crash reports, as title and description
+#[client_packet("config:custom_report_details")]
+pub struct CCustomReportDetails<'a> {
+    details: &'a [(&'a str, &'a str)],
+}
+
+impl<'a> CCustomReportDetails<'a> {
+    pub fn new(details: &'a [(&'a str, &'a str)]) -> Self {
+        Self { details }
+    }
+}
+
+impl ClientPacket for CCustomReportDetails<'_> {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        bytebuf.put_list(self.details, |bytebuf, (title, description)| {
+            bytebuf.put_string(title);
+            bytebuf.put_string(description);
+        });
+    }
+}
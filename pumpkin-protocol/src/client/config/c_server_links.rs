@@ -0,0 +1,59 @@
+use pumpkin_core::text::TextComponent;
+use pumpkin_macros::client_packet;
+
+use crate::{bytebuf::ByteBuffer, ClientPacket, VarInt};
+
+#[client_packet("config:server_links")]
+pub struct CServerLinks<'a> {
+    links: &'a [ServerLink<'a>],
+}
+
+impl<'a> CServerLinks<'a> {
+    pub fn new(links: &'a [ServerLink<'a>]) -> Self {
+        Self { links }
+    }
+}
+
+pub struct ServerLink<'a> {
+    pub label: Label<'a>,
+    pub url: &'a str,
+}
+
+pub enum Label<'a> {
+    /// Translated by the client
+    BuiltIn(LinkType),
+    Custom(Box<TextComponent<'a>>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(i32)]
+pub enum LinkType {
+    BugReport,
+    CommunityGuidelines,
+    Support,
+    Status,
+    Feedback,
+    Community,
+    Website,
+    Forums,
+    News,
+    Announcements,
+}
+
+impl ClientPacket for CServerLinks<'_> {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        bytebuf.put_list(self.links, |bytebuf, link| {
+            match &link.label {
+                Label::BuiltIn(link_type) => {
+                    bytebuf.put_bool(true);
+                    bytebuf.put_var_int(&VarInt(*link_type as i32));
+                }
+                Label::Custom(label) => {
+                    bytebuf.put_bool(false);
+                    bytebuf.put_slice(&label.encode());
+                }
+            }
+            bytebuf.put_string(link.url);
+        });
+    }
+}
@@ -0,0 +1,38 @@
+use pumpkin_macros::client_packet;
+
+use crate::{bytebuf::ByteBuffer, ClientPacket, VarInt};
+
+#[client_packet("config:update_tags")]
+pub struct CUpdateTags<'a> {
+    registries: &'a [RegistryTags],
+}
+
+impl<'a> CUpdateTags<'a> {
+    pub fn new(registries: &'a [RegistryTags]) -> Self {
+        Self { registries }
+    }
+}
+
+/// The tags of a registry, the values are the ids of the entries
+#[derive(Clone, Debug)]
+pub struct RegistryTags {
+    pub registry_id: &'static str,
+    pub tags: Vec<(String, Vec<VarInt>)>,
+}
+
+/// Writes the tags like both the configuration and the play packet send them
+pub(crate) fn write_registry_tags(bytebuf: &mut ByteBuffer, registries: &[RegistryTags]) {
+    bytebuf.put_list::<RegistryTags>(registries, |p, v| {
+        p.put_string(v.registry_id);
+        p.put_list::<(String, Vec<VarInt>)>(&v.tags, |p, (name, ids)| {
+            p.put_string(name);
+            p.put_list::<VarInt>(ids, |p, id| p.put_var_int(id));
+        });
+    });
+}
+
+impl<'a> ClientPacket for CUpdateTags<'a> {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        write_registry_tags(bytebuf, self.registries);
+    }
+}
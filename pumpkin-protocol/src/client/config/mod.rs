@@ -1,15 +1,21 @@
 mod c_add_resource_pack;
 mod c_config_disconnect;
 mod c_cookie_request;
+mod c_custom_report_details;
 mod c_finish_config;
 mod c_known_packs;
 mod c_plugin_message;
 mod c_registry_data;
+mod c_server_links;
+mod c_update_tags;
 
 pub use c_add_resource_pack::*;
 pub use c_config_disconnect::*;
 pub use c_cookie_request::*;
+pub use c_custom_report_details::*;
 pub use c_finish_config::*;
 pub use c_known_packs::*;
 pub use c_plugin_message::*;
 pub use c_registry_data::*;
+pub use c_server_links::*;
+pub use c_update_tags::*;
@@ -0,0 +1,33 @@
+use pumpkin_core::math::position::WorldPosition;
+
+use pumpkin_macros::client_packet;
+use serde::Serialize;
+
+use crate::VarInt;
+
+/// Makes the client animate a block, like a piston extending or a chest opening
+#[derive(Serialize)]
+#[client_packet("play:block_event")]
+pub struct CBlockEvent<'a> {
+    location: &'a WorldPosition,
+    action_id: u8,
+    action_parameter: u8,
+    /// The id of the block, not of its state
+    block_type: VarInt,
+}
+
+impl<'a> CBlockEvent<'a> {
+    pub fn new(
+        location: &'a WorldPosition,
+        action_id: u8,
+        action_parameter: u8,
+        block_type: VarInt,
+    ) -> Self {
+        Self {
+            location,
+            action_id,
+            action_parameter,
+            block_type,
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use pumpkin_core::text::TextComponent;
+use pumpkin_macros::client_packet;
+
+use crate::{bytebuf::ByteBuffer, ClientPacket, VarInt};
+
+#[client_packet("play:boss_event")]
+pub struct CBossEvent<'a> {
+    uuid: &'a uuid::Uuid,
+    action: BossEventAction<'a>,
+}
+
+impl<'a> CBossEvent<'a> {
+    pub fn new(uuid: &'a uuid::Uuid, action: BossEventAction<'a>) -> Self {
+        Self { uuid, action }
+    }
+}
+
+pub enum BossEventAction<'a> {
+    Add {
+        title: TextComponent<'a>,
+        /// From 0 to 1
+        health: f32,
+        color: BossBarColor,
+        division: BossBarDivision,
+        flags: u8,
+    },
+    Remove,
+    UpdateHealth(f32),
+    UpdateTitle(TextComponent<'a>),
+    UpdateStyle {
+        color: BossBarColor,
+        division: BossBarDivision,
+    },
+    UpdateFlags(u8),
+}
+
+impl<'a> ClientPacket for CBossEvent<'a> {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        bytebuf.put_uuid(self.uuid);
+        match &self.action {
+            BossEventAction::Add {
+                title,
+                health,
+                color,
+                division,
+                flags,
+            } => {
+                bytebuf.put_var_int(&VarInt(0));
+                bytebuf.put_slice(&title.encode());
+                bytebuf.put_f32(*health);
+                bytebuf.put_var_int(&VarInt(*color as i32));
+                bytebuf.put_var_int(&VarInt(*division as i32));
+                bytebuf.put_u8(*flags);
+            }
+            BossEventAction::Remove => bytebuf.put_var_int(&VarInt(1)),
+            BossEventAction::UpdateHealth(health) => {
+                bytebuf.put_var_int(&VarInt(2));
+                bytebuf.put_f32(*health);
+            }
+            BossEventAction::UpdateTitle(title) => {
+                bytebuf.put_var_int(&VarInt(3));
+                bytebuf.put_slice(&title.encode());
+            }
+            BossEventAction::UpdateStyle { color, division } => {
+                bytebuf.put_var_int(&VarInt(4));
+                bytebuf.put_var_int(&VarInt(*color as i32));
+                bytebuf.put_var_int(&VarInt(*division as i32));
+            }
+            BossEventAction::UpdateFlags(flags) => {
+                bytebuf.put_var_int(&VarInt(5));
+                bytebuf.put_u8(*flags);
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+pub enum BossBarColor {
+    Pink,
+    Blue,
+    Red,
+    Green,
+    Yellow,
+    Purple,
+    White,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+pub enum BossBarDivision {
+    NoDivision,
+    Notches6,
+    Notches10,
+    Notches12,
+    Notches20,
+}
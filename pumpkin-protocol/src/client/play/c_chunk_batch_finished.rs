@@ -0,0 +1,17 @@
+use pumpkin_macros::client_packet;
+use serde::Serialize;
+
+use crate::VarInt;
+
+/// Ends a batch of chunks, the client answers with how many chunks per tick it wants
+#[derive(Serialize)]
+#[client_packet("play:chunk_batch_finished")]
+pub struct CChunkBatchFinished {
+    batch_size: VarInt,
+}
+
+impl CChunkBatchFinished {
+    pub fn new(batch_size: VarInt) -> Self {
+        Self { batch_size }
+    }
+}
@@ -0,0 +1,18 @@
+use pumpkin_macros::client_packet;
+
+/// Sent before a batch of chunks, the client measures how long it takes to receive them
+#[derive(serde::Serialize)]
+#[client_packet("play:chunk_batch_start")]
+pub struct CChunkBatchStart {}
+
+impl Default for CChunkBatchStart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CChunkBatchStart {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
@@ -1,9 +1,11 @@
-use crate::{bytebuf::ByteBuffer, BitSet, ClientPacket, VarInt};
+use crate::{bytebuf::ByteBuffer, ClientPacket, VarInt};
 use itertools::Itertools;
 
 use pumpkin_macros::client_packet;
 use pumpkin_world::{chunk::ChunkData, DIRECT_PALETTE_BITS};
 
+use super::c_light_update::write_light;
+
 #[client_packet("play:level_chunk_with_light")]
 pub struct CChunkData<'a>(pub &'a ChunkData);
 
@@ -111,34 +113,6 @@ impl<'a> ClientPacket for CChunkData<'a> {
         // TODO: block entities
         buf.put_var_int(&VarInt(0));
 
-        // Sky Light Mask
-        // All of the chunks, this is not optimal and uses way more data than needed but will be
-        // overhauled with full lighting system.
-        buf.put_bit_set(&BitSet(VarInt(1), &[0b01111111111111111111111110]));
-        // Block Light Mask
-        buf.put_bit_set(&BitSet(VarInt(1), &[0]));
-        // Empty Sky Light Mask
-        buf.put_bit_set(&BitSet(VarInt(1), &[0b0]));
-        // Empty Block Light Mask
-        buf.put_bit_set(&BitSet(VarInt(1), &[0]));
-
-        buf.put_var_int(&VarInt(self.0.blocks.subchunks_len() as i32));
-        self.0.blocks.iter_subchunks().for_each(|chunk| {
-            let mut chunk_light = [0u8; 2048];
-            for (i, _) in chunk.iter().enumerate() {
-                // if !block .is_air() {
-                //     continue;
-                // }
-                let index = i / 2;
-                let mask = if i % 2 == 1 { 0xF0 } else { 0x0F };
-                chunk_light[index] |= mask;
-            }
-
-            buf.put_var_int(&VarInt(chunk_light.len() as i32));
-            buf.put_slice(&chunk_light);
-        });
-
-        // Block Lighting
-        buf.put_var_int(&VarInt(0));
+        write_light(buf, self.0.light.as_ref(), self.0.blocks.subchunks_len());
     }
 }
@@ -0,0 +1,15 @@
+use pumpkin_macros::client_packet;
+use serde::Serialize;
+
+/// Hides the current title, resetting also sets the fade timings back to the default
+#[derive(Serialize)]
+#[client_packet("play:clear_titles")]
+pub struct CClearTitle {
+    reset: bool,
+}
+
+impl CClearTitle {
+    pub fn new(reset: bool) -> Self {
+        Self { reset }
+    }
+}
@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use pumpkin_core::text::TextComponent;
 use pumpkin_macros::client_packet;
 
@@ -34,7 +36,7 @@ impl<'a> ClientPacket for CCommandSuggestions<'a> {
         bytebuf.put_var_int(&self.length);
 
         bytebuf.put_list(&self.matches, |bytebuf, suggestion| {
-            bytebuf.put_string(suggestion.suggestion);
+            bytebuf.put_string(&suggestion.suggestion);
             bytebuf.put_bool(suggestion.tooltip.is_some());
             if let Some(tooltip) = &suggestion.tooltip {
                 bytebuf.put_slice(&tooltip.encode());
@@ -45,14 +47,14 @@ impl<'a> ClientPacket for CCommandSuggestions<'a> {
 
 #[derive(PartialEq, Eq, Hash, Debug)]
 pub struct CommandSuggestion<'a> {
-    pub suggestion: &'a str,
+    pub suggestion: Cow<'a, str>,
     pub tooltip: Option<TextComponent<'a>>,
 }
 
 impl<'a> CommandSuggestion<'a> {
-    pub fn new(suggestion: &'a str, tooltip: Option<TextComponent<'a>>) -> Self {
+    pub fn new(suggestion: impl Into<Cow<'a, str>>, tooltip: Option<TextComponent<'a>>) -> Self {
         Self {
-            suggestion,
+            suggestion: suggestion.into(),
             tooltip,
         }
     }
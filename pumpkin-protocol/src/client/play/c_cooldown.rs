@@ -0,0 +1,22 @@
+use pumpkin_macros::client_packet;
+use serde::Serialize;
+
+use crate::VarInt;
+
+#[derive(Serialize)]
+#[client_packet("play:cooldown")]
+pub struct CCooldown<'a> {
+    /// The items sharing the cooldown, usually the item's name, e.g. `minecraft:shield`
+    cooldown_group: &'a str,
+    /// 0 ends the cooldown
+    ticks: VarInt,
+}
+
+impl<'a> CCooldown<'a> {
+    pub fn new(cooldown_group: &'a str, ticks: VarInt) -> Self {
+        Self {
+            cooldown_group,
+            ticks,
+        }
+    }
+}
@@ -1,35 +1,266 @@
+use pumpkin_core::{
+    math::{position::WorldPosition, vector3::Vector3},
+    text::TextComponent,
+};
 use pumpkin_macros::client_packet;
+use pumpkin_world::item::ItemStack;
 use serde::Serialize;
+use uuid::Uuid;
 
-use crate::VarInt;
+use crate::{
+    bytebuf::{serializer::Serializer, ByteBuffer},
+    slot::Slot,
+    ClientPacket, VarInt, VarLong,
+};
 
-#[derive(Serialize)]
+use super::Particle;
+
+/// Changes the metadata of an entity, only the given entries are changed.
 #[client_packet("play:set_entity_data")]
-pub struct CSetEntityMetadata<T> {
+pub struct CSetEntityMetadata<'a> {
     entity_id: VarInt,
-    metadata: Metadata<T>,
-    end: u8,
+    metadata: &'a [Metadata],
 }
 
-impl<T> CSetEntityMetadata<T> {
-    pub fn new(entity_id: VarInt, metadata: Metadata<T>) -> Self {
+impl<'a> CSetEntityMetadata<'a> {
+    pub fn new(entity_id: VarInt, metadata: &'a [Metadata]) -> Self {
         Self {
             entity_id,
             metadata,
-            end: 255,
         }
     }
 }
 
-#[derive(Serialize)]
-pub struct Metadata<T> {
-    index: u8,
-    typ: VarInt,
-    value: T,
+impl ClientPacket for CSetEntityMetadata<'_> {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        bytebuf.put_var_int(&self.entity_id);
+        for metadata in self.metadata {
+            bytebuf.put_u8(metadata.index);
+            bytebuf.put_var_int(&VarInt(metadata.value.serializer_id()));
+            metadata.value.write(bytebuf);
+        }
+        // marks the end of the list
+        bytebuf.put_u8(0xFF);
+    }
+}
+
+/// An entry of an entity's metadata, what the index means depends on the entity type
+#[derive(Clone, Debug, PartialEq)]
+pub struct Metadata {
+    pub index: u8,
+    pub value: MetadataValue,
+}
+
+impl Metadata {
+    pub fn new(index: u8, value: MetadataValue) -> Self {
+        Self { index, value }
+    }
+}
+
+/// A metadata value, the variant decides how the client reads it
+#[derive(Clone, Debug, PartialEq)]
+pub enum MetadataValue {
+    Byte(i8),
+    VarInt(i32),
+    VarLong(i64),
+    Float(f32),
+    String(String),
+    TextComponent(TextComponent<'static>),
+    OptionalTextComponent(Option<TextComponent<'static>>),
+    /// None is an empty slot
+    ItemStack(Option<ItemStack>),
+    Boolean(bool),
+    /// The rotation around each axis in degrees, used by armor stands
+    Rotations(Vector3<f32>),
+    Position(WorldPosition),
+    OptionalPosition(Option<WorldPosition>),
+    Direction(i32),
+    OptionalUuid(Option<Uuid>),
+    BlockState(i32),
+    /// None is air
+    OptionalBlockState(Option<i32>),
+    /// The particles of area effect clouds
+    Particle(Particle),
+    /// The biome type, profession and level of villagers and zombie villagers
+    VillagerData([i32; 3]),
+    OptionalVarInt(Option<i32>),
+    /// The id of an entity pose
+    Pose(i32),
+    Vector3(Vector3<f32>),
+    Quaternion([f32; 4]),
+}
+
+impl MetadataValue {
+    /// The id of the type in the `minecraft:entity_data_serializers` registry
+    #[must_use]
+    pub fn serializer_id(&self) -> i32 {
+        match self {
+            Self::Byte(_) => 0,
+            Self::VarInt(_) => 1,
+            Self::VarLong(_) => 2,
+            Self::Float(_) => 3,
+            Self::String(_) => 4,
+            Self::TextComponent(_) => 5,
+            Self::OptionalTextComponent(_) => 6,
+            Self::ItemStack(_) => 7,
+            Self::Boolean(_) => 8,
+            Self::Rotations(_) => 9,
+            Self::Position(_) => 10,
+            Self::OptionalPosition(_) => 11,
+            Self::Direction(_) => 12,
+            Self::OptionalUuid(_) => 13,
+            Self::BlockState(_) => 14,
+            Self::OptionalBlockState(_) => 15,
+            Self::Particle(_) => 17,
+            Self::VillagerData(_) => 19,
+            Self::OptionalVarInt(_) => 20,
+            Self::Pose(_) => 21,
+            Self::Vector3(_) => 29,
+            Self::Quaternion(_) => 30,
+        }
+    }
+
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        match self {
+            Self::Byte(value) => bytebuf.put_i8(*value),
+            Self::VarInt(value)
+            | Self::Direction(value)
+            | Self::BlockState(value)
+            | Self::Pose(value) => bytebuf.put_var_int(&VarInt(*value)),
+            Self::VarLong(value) => bytebuf.put_var_long(&VarLong(*value)),
+            Self::Float(value) => bytebuf.put_f32(*value),
+            Self::String(value) => bytebuf.put_string(value),
+            Self::TextComponent(text) => bytebuf.put_slice(&text.encode()),
+            Self::OptionalTextComponent(text) => {
+                bytebuf.put_option(text, |bytebuf, text| bytebuf.put_slice(&text.encode()));
+            }
+            Self::ItemStack(stack) => {
+                let mut serializer = Serializer::new(ByteBuffer::empty());
+                Slot::from(stack)
+                    .serialize(&mut serializer)
+                    .expect("Slots can always be serialized");
+                bytebuf.put(serializer.output.buf());
+            }
+            Self::Boolean(value) => bytebuf.put_bool(*value),
+            Self::Rotations(vector) | Self::Vector3(vector) => {
+                bytebuf.put_f32(vector.x);
+                bytebuf.put_f32(vector.y);
+                bytebuf.put_f32(vector.z);
+            }
+            Self::Position(position) => bytebuf.put_i64(position.as_long()),
+            Self::OptionalPosition(position) => {
+                bytebuf.put_option(position, |bytebuf, position| {
+                    bytebuf.put_i64(position.as_long());
+                });
+            }
+            Self::OptionalUuid(uuid) => bytebuf.put_option(uuid, ByteBuffer::put_uuid),
+            // 0 is air and means none
+            Self::OptionalBlockState(state) => {
+                bytebuf.put_var_int(&VarInt(state.unwrap_or(0)));
+            }
+            Self::Particle(particle) => particle.write(bytebuf),
+            Self::VillagerData(data) => {
+                for value in data {
+                    bytebuf.put_var_int(&VarInt(*value));
+                }
+            }
+            // 0 means none, so the value is sent plus one
+            Self::OptionalVarInt(value) => {
+                bytebuf.put_var_int(&VarInt(value.map_or(0, |value| value + 1)));
+            }
+            Self::Quaternion(quaternion) => {
+                for value in quaternion {
+                    bytebuf.put_f32(*value);
+                }
+            }
+        }
+    }
 }
 
-impl<T> Metadata<T> {
-    pub fn new(index: u8, typ: VarInt, value: T) -> Self {
-        Self { index, typ, value }
+#[cfg(test)]
+mod test {
+    use pumpkin_macros::particle;
+    use pumpkin_world::item::ItemStack;
+
+    use crate::{bytebuf::ByteBuffer, client::play::Particle, ClientPacket};
+
+    use super::{CSetEntityMetadata, Metadata, MetadataValue};
+
+    #[test]
+    fn writes_entries_and_end() {
+        let metadata = [
+            Metadata::new(0, MetadataValue::Byte(0x02)),
+            Metadata::new(6, MetadataValue::Pose(5)),
+            Metadata::new(9, MetadataValue::Float(20.0)),
+        ];
+        let mut bytebuf = ByteBuffer::empty();
+        CSetEntityMetadata::new(7.into(), &metadata).write(&mut bytebuf);
+
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 7);
+        assert_eq!(bytebuf.get_u8().unwrap(), 0);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 0);
+        assert_eq!(bytebuf.get_i8().unwrap(), 0x02);
+        assert_eq!(bytebuf.get_u8().unwrap(), 6);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 21);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 5);
+        assert_eq!(bytebuf.get_u8().unwrap(), 9);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 3);
+        assert_eq!(bytebuf.get_f32().unwrap(), 20.0);
+        assert_eq!(bytebuf.get_u8().unwrap(), 0xFF);
+    }
+
+    #[test]
+    fn writes_item_stacks_as_slots() {
+        let metadata = [
+            Metadata::new(8, MetadataValue::ItemStack(Some(ItemStack::new(3, 42)))),
+            Metadata::new(8, MetadataValue::ItemStack(None)),
+        ];
+        let mut bytebuf = ByteBuffer::empty();
+        CSetEntityMetadata::new(1.into(), &metadata).write(&mut bytebuf);
+
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 1);
+        assert_eq!(bytebuf.get_u8().unwrap(), 8);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 7);
+        // count, id and no added or removed components
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 3);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 42);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 0);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 0);
+        assert_eq!(bytebuf.get_u8().unwrap(), 8);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 7);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 0);
+        assert_eq!(bytebuf.get_u8().unwrap(), 0xFF);
+    }
+
+    #[test]
+    fn writes_particles() {
+        let flame = particle!("minecraft:flame");
+        let metadata = [Metadata::new(
+            10,
+            MetadataValue::Particle(Particle::simple(flame).unwrap()),
+        )];
+        let mut bytebuf = ByteBuffer::empty();
+        CSetEntityMetadata::new(1.into(), &metadata).write(&mut bytebuf);
+
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 1);
+        assert_eq!(bytebuf.get_u8().unwrap(), 10);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 17);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, i32::from(flame));
+        assert_eq!(bytebuf.get_u8().unwrap(), 0xFF);
+    }
+
+    #[test]
+    fn writes_villager_data() {
+        let metadata = [Metadata::new(18, MetadataValue::VillagerData([2, 5, 3]))];
+        let mut bytebuf = ByteBuffer::empty();
+        CSetEntityMetadata::new(1.into(), &metadata).write(&mut bytebuf);
+
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 1);
+        assert_eq!(bytebuf.get_u8().unwrap(), 18);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 19);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 2);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 5);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 3);
+        assert_eq!(bytebuf.get_u8().unwrap(), 0xFF);
     }
 }
@@ -1,35 +1,31 @@
 use pumpkin_macros::client_packet;
-use serde::Serialize;
 
-use crate::{SoundCategory, VarInt};
+use crate::{bytebuf::ByteBuffer, ClientPacket, SoundCategory, VarInt};
+
+use super::SoundEvent;
 
-#[derive(Serialize)]
 #[client_packet("play:sound_entity")]
-pub struct CEntitySoundEffect {
-    sound_id: VarInt,
-    // TODO: add sound from name
-    // sound_name: Option<&'a str>,
-    // has_fixed_range: Option<bool>,
-    // range: Option<f32>,
-    sound_category: VarInt,
+pub struct CEntitySoundEffect<'a> {
+    sound: SoundEvent<'a>,
+    sound_category: SoundCategory,
     entity_id: VarInt,
     volume: f32,
     pitch: f32,
-    seed: f64,
+    seed: i64,
 }
 
-impl CEntitySoundEffect {
+impl<'a> CEntitySoundEffect<'a> {
     pub fn new(
-        sound_id: VarInt,
+        sound: SoundEvent<'a>,
         sound_category: SoundCategory,
         entity_id: VarInt,
         volume: f32,
         pitch: f32,
-        seed: f64,
+        seed: i64,
     ) -> Self {
         Self {
-            sound_id: VarInt(sound_id.0 + 1),
-            sound_category: VarInt(sound_category as i32),
+            sound,
+            sound_category,
             entity_id,
             volume,
             pitch,
@@ -37,3 +33,14 @@ impl CEntitySoundEffect {
         }
     }
 }
+
+impl ClientPacket for CEntitySoundEffect<'_> {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        self.sound.write(bytebuf);
+        bytebuf.put_var_int(&VarInt(self.sound_category as i32));
+        bytebuf.put_var_int(&self.entity_id);
+        bytebuf.put_f32(self.volume);
+        bytebuf.put_f32(self.pitch);
+        bytebuf.put_i64(self.seed);
+    }
+}
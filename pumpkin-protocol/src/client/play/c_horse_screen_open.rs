@@ -0,0 +1,24 @@
+use pumpkin_macros::client_packet;
+use serde::Serialize;
+
+use crate::VarInt;
+
+/// Opens the inventory of a horse like mob, it has no window type like other screens
+#[derive(Serialize)]
+#[client_packet("play:horse_screen_open")]
+pub struct CHorseScreenOpen {
+    window_id: VarInt,
+    /// The columns of three slots the chest of donkeys and llamas has, 0 without a chest
+    inventory_columns: VarInt,
+    entity_id: i32,
+}
+
+impl CHorseScreenOpen {
+    pub const fn new(window_id: VarInt, inventory_columns: VarInt, entity_id: i32) -> Self {
+        Self {
+            window_id,
+            inventory_columns,
+            entity_id,
+        }
+    }
+}
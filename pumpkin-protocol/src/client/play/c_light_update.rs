@@ -0,0 +1,78 @@
+use pumpkin_core::math::vector2::Vector2;
+use pumpkin_macros::client_packet;
+use pumpkin_world::chunk::light::{ChunkLight, LightArray, LIGHT_ARRAY_LEN};
+
+use crate::{bytebuf::ByteBuffer, BitSet, ClientPacket, VarInt};
+
+/// Sends the new light of a chunk the client has already
+#[client_packet("play:light_update")]
+pub struct CLightUpdate<'a> {
+    position: Vector2<i32>,
+    light: &'a ChunkLight,
+}
+
+impl<'a> CLightUpdate<'a> {
+    pub fn new(position: Vector2<i32>, light: &'a ChunkLight) -> Self {
+        Self { position, light }
+    }
+}
+
+impl ClientPacket for CLightUpdate<'_> {
+    fn write(&self, buf: &mut ByteBuffer) {
+        buf.put_var_int(&VarInt(self.position.x));
+        buf.put_var_int(&VarInt(self.position.z));
+        write_light(buf, Some(self.light), self.light.sky_sections().len());
+    }
+}
+
+/// The sections which have light, the ones which have none and the light of the former
+fn light_masks<'a>(
+    sections: impl Iterator<Item = &'a LightArray>,
+) -> (i64, i64, Vec<&'a LightArray>) {
+    let (mut mask, mut empty_mask, mut arrays) = (0, 0, Vec::new());
+    for (index, section) in sections.enumerate() {
+        // the first bit is the section below the world
+        let bit = 1 << (index + 1);
+        if section.iter().all(|light| *light == 0) {
+            empty_mask |= bit;
+        } else {
+            mask |= bit;
+            arrays.push(section);
+        }
+    }
+    (mask, empty_mask, arrays)
+}
+
+/// Writes the light like the chunk data and light update packets have it, chunks which were not
+/// lit yet get full sky light
+pub(super) fn write_light(buf: &mut ByteBuffer, light: Option<&ChunkLight>, sections: usize) {
+    const FULL: LightArray = [0xFF; LIGHT_ARRAY_LEN];
+    const DARK: LightArray = [0; LIGHT_ARRAY_LEN];
+    let (sky, block) = match light {
+        Some(light) => (
+            light_masks(light.sky_sections().iter().map(|section| &**section)),
+            light_masks(light.block_sections().iter().map(|section| &**section)),
+        ),
+        None => (
+            light_masks(std::iter::repeat_n(&FULL, sections)),
+            light_masks(std::iter::repeat_n(&DARK, sections)),
+        ),
+    };
+
+    // Sky Light Mask
+    buf.put_bit_set(&BitSet(VarInt(1), &[sky.0]));
+    // Block Light Mask
+    buf.put_bit_set(&BitSet(VarInt(1), &[block.0]));
+    // Empty Sky Light Mask
+    buf.put_bit_set(&BitSet(VarInt(1), &[sky.1]));
+    // Empty Block Light Mask
+    buf.put_bit_set(&BitSet(VarInt(1), &[block.1]));
+
+    for arrays in [sky.2, block.2] {
+        buf.put_var_int(&VarInt(arrays.len() as i32));
+        for array in arrays {
+            buf.put_var_int(&VarInt(array.len() as i32));
+            buf.put_slice(array);
+        }
+    }
+}
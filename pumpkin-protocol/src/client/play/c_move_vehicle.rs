@@ -0,0 +1,25 @@
+use pumpkin_macros::client_packet;
+use serde::Serialize;
+
+/// Moves the vehicle the client is controlling, e.g. to revert a movement the server rejected
+#[derive(Serialize)]
+#[client_packet("play:move_vehicle")]
+pub struct CMoveVehicle {
+    x: f64,
+    y: f64,
+    z: f64,
+    yaw: f32,
+    pitch: f32,
+}
+
+impl CMoveVehicle {
+    pub fn new(x: f64, y: f64, z: f64, yaw: f32, pitch: f32) -> Self {
+        Self {
+            x,
+            y,
+            z,
+            yaw,
+            pitch,
+        }
+    }
+}
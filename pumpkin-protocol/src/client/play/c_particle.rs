@@ -1,52 +1,53 @@
+use pumpkin_core::math::vector3::Vector3;
 use pumpkin_macros::client_packet;
-use serde::Serialize;
 
-use crate::VarInt;
+use crate::{bytebuf::ByteBuffer, ClientPacket};
+
+use super::Particle;
 
-#[derive(Serialize)]
 #[client_packet("play:level_particles")]
 pub struct CParticle<'a> {
     /// If true, particle distance increases from 256 to 65536.
     long_distance: bool,
-    x: f64,
-    y: f64,
-    z: f64,
-    offset_x: f32,
-    offset_y: f32,
-    offset_z: f32,
+    position: Vector3<f64>,
+    /// The particles are spread randomly by up to this, or are the color or direction of some particles if the count is 0
+    offset: Vector3<f32>,
     max_speed: f32,
     particle_count: i32,
-    pariticle_id: VarInt,
-    data: &'a [u8],
+    particle: &'a Particle,
 }
 
 impl<'a> CParticle<'a> {
-    #[expect(clippy::too_many_arguments)]
     pub fn new(
         long_distance: bool,
-        x: f64,
-        y: f64,
-        z: f64,
-        offset_x: f32,
-        offset_y: f32,
-        offset_z: f32,
+        position: Vector3<f64>,
+        offset: Vector3<f32>,
         max_speed: f32,
         particle_count: i32,
-        pariticle_id: VarInt,
-        data: &'a [u8],
+        particle: &'a Particle,
     ) -> Self {
         Self {
             long_distance,
-            x,
-            y,
-            z,
-            offset_x,
-            offset_y,
-            offset_z,
+            position,
+            offset,
             max_speed,
             particle_count,
-            pariticle_id,
-            data,
+            particle,
         }
     }
 }
+
+impl ClientPacket for CParticle<'_> {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        bytebuf.put_bool(self.long_distance);
+        bytebuf.put_f64(self.position.x);
+        bytebuf.put_f64(self.position.y);
+        bytebuf.put_f64(self.position.z);
+        bytebuf.put_f32(self.offset.x);
+        bytebuf.put_f32(self.offset.y);
+        bytebuf.put_f32(self.offset.z);
+        bytebuf.put_f32(self.max_speed);
+        bytebuf.put_i32(self.particle_count);
+        self.particle.write(bytebuf);
+    }
+}
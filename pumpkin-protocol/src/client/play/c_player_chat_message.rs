@@ -1,25 +1,25 @@
 use pumpkin_core::text::TextComponent;
 
 use pumpkin_macros::client_packet;
-use serde::Serialize;
 
-use crate::{BitSet, VarInt};
+use crate::{bytebuf::ByteBuffer, BitSet, ClientPacket, VarInt};
+
+/// The most messages a chat message may acknowledge
+pub const MAX_PREVIOUS_MESSAGES: usize = 20;
 
-#[derive(Serialize)]
 #[client_packet("play:player_chat")]
 pub struct CPlayerChatMessage<'a> {
-    #[serde(with = "uuid::serde::compact")]
     sender: uuid::Uuid,
+    /// Counts the messages of the sender the receiver got
     index: VarInt,
     message_signature: Option<&'a [u8]>,
     message: &'a str,
     timestamp: i64,
     salt: i64,
-    previous_messages_count: VarInt,
     previous_messages: &'a [PreviousMessage<'a>], // max 20
     unsigned_content: Option<TextComponent<'a>>,
     filter_type: FilterType<'a>,
-    chat_type: VarInt,
+    chat_type: ChatType,
     sender_name: TextComponent<'a>,
     target_name: Option<TextComponent<'a>>,
 }
@@ -36,7 +36,7 @@ impl<'a> CPlayerChatMessage<'a> {
         previous_messages: &'a [PreviousMessage<'a>],
         unsigned_content: Option<TextComponent<'a>>,
         filter_type: FilterType<'a>,
-        chat_type: VarInt,
+        chat_type: ChatType,
         sender_name: TextComponent<'a>,
         target_name: Option<TextComponent<'a>>,
     ) -> Self {
@@ -47,7 +47,6 @@ impl<'a> CPlayerChatMessage<'a> {
             message,
             timestamp,
             salt,
-            previous_messages_count: previous_messages.len().into(),
             previous_messages,
             unsigned_content,
             filter_type,
@@ -58,19 +57,64 @@ impl<'a> CPlayerChatMessage<'a> {
     }
 }
 
-#[derive(Serialize)]
-pub struct PreviousMessage<'a> {
-    message_id: VarInt,
-    signature: Option<&'a [u8]>,
+impl<'a> ClientPacket for CPlayerChatMessage<'a> {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        bytebuf.put_uuid(&self.sender);
+        bytebuf.put_var_int(&self.index);
+        bytebuf.put_option(&self.message_signature, |p, v| p.put_slice(v));
+        bytebuf.put_string(self.message);
+        bytebuf.put_i64(self.timestamp);
+        bytebuf.put_i64(self.salt);
+        bytebuf.put_list(self.previous_messages, |p, v| match v {
+            // the id is sent plus one, as zero means a full signature follows
+            PreviousMessage::Cached(id) => p.put_var_int(&(id + 1).into()),
+            PreviousMessage::Signature(signature) => {
+                p.put_var_int(&0.into());
+                p.put_slice(signature);
+            }
+        });
+        bytebuf.put_option(&self.unsigned_content, |p, v| p.put_slice(&v.encode()));
+        match &self.filter_type {
+            FilterType::PassThrough => bytebuf.put_var_int(&0.into()),
+            FilterType::FullyFiltered => bytebuf.put_var_int(&1.into()),
+            FilterType::PartiallyFiltered(mask) => {
+                bytebuf.put_var_int(&2.into());
+                bytebuf.put_bit_set(mask);
+            }
+        }
+        // an id in the registry plus one, as zero means the chat type is sent inline
+        bytebuf.put_var_int(&(self.chat_type as i32 + 1).into());
+        bytebuf.put_slice(&self.sender_name.encode());
+        bytebuf.put_option(&self.target_name, |p, v| p.put_slice(&v.encode()));
+    }
+}
+
+/// A message the sender saw before sending this one
+pub enum PreviousMessage<'a> {
+    /// The id of a signature the receiver already has
+    Cached(i32),
+    /// The full signature, 256 bytes
+    Signature(&'a [u8]),
 }
 
-#[derive(Serialize)]
-#[repr(i32)]
 pub enum FilterType<'a> {
     /// Message is not filtered at all
-    PassThrough = 0,
+    PassThrough,
     /// Message is fully filtered
-    FullyFiltered = 1,
+    FullyFiltered,
     /// Only some characters in the message are filtered
-    PartiallyFiltered(BitSet<'a>) = 2,
+    PartiallyFiltered(BitSet<'a>),
+}
+
+/// The entries of the `minecraft:chat_type` registry, which is synced sorted by name
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(i32)]
+pub enum ChatType {
+    Chat,
+    EmoteCommand,
+    MsgCommandIncoming,
+    MsgCommandOutgoing,
+    SayCommand,
+    TeamMsgCommandIncoming,
+    TeamMsgCommandOutgoing,
 }
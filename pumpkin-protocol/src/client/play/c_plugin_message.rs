@@ -0,0 +1,15 @@
+use pumpkin_macros::client_packet;
+use serde::Serialize;
+
+#[derive(Serialize)]
+#[client_packet("play:custom_payload")]
+pub struct CPlayPluginMessage<'a> {
+    channel: &'a str,
+    data: &'a [u8],
+}
+
+impl<'a> CPlayPluginMessage<'a> {
+    pub fn new(channel: &'a str, data: &'a [u8]) -> Self {
+        Self { channel, data }
+    }
+}
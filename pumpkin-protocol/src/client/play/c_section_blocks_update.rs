@@ -0,0 +1,32 @@
+use pumpkin_core::math::vector3::Vector3;
+use pumpkin_macros::client_packet;
+
+use crate::{ClientPacket, VarLong};
+
+/// Updates multiple blocks inside of a single chunk section at once.
+#[client_packet("play:section_blocks_update")]
+pub struct CSectionBlocksUpdate<'a> {
+    section: Vector3<i32>,
+    /// Chunk section relative x, y, z and the new block state id
+    blocks: &'a [(u8, u8, u8, u16)],
+}
+
+impl<'a> CSectionBlocksUpdate<'a> {
+    pub fn new(section: Vector3<i32>, blocks: &'a [(u8, u8, u8, u16)]) -> Self {
+        Self { section, blocks }
+    }
+}
+
+impl<'a> ClientPacket for CSectionBlocksUpdate<'a> {
+    fn write(&self, bytebuf: &mut crate::bytebuf::ByteBuffer) {
+        bytebuf.put_i64(
+            ((i64::from(self.section.x) & 0x3F_FFFF) << 42)
+                | ((i64::from(self.section.z) & 0x3F_FFFF) << 20)
+                | (i64::from(self.section.y) & 0xF_FFFF),
+        );
+        bytebuf.put_list(self.blocks, |p, &(x, y, z, state_id)| {
+            let position = (i64::from(x) << 8) | (i64::from(z) << 4) | i64::from(y);
+            p.put_var_long(&VarLong((i64::from(state_id) << 12) | position));
+        });
+    }
+}
@@ -0,0 +1,17 @@
+use pumpkin_macros::client_packet;
+use serde::Serialize;
+
+use crate::VarInt;
+
+/// Makes a spectator see through the eyes of an entity, or of itself again
+#[derive(Serialize)]
+#[client_packet("play:set_camera")]
+pub struct CSetCamera {
+    camera_id: VarInt,
+}
+
+impl CSetCamera {
+    pub fn new(camera_id: VarInt) -> Self {
+        Self { camera_id }
+    }
+}
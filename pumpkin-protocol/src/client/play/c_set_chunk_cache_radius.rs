@@ -0,0 +1,9 @@
+use pumpkin_macros::client_packet;
+
+use crate::VarInt;
+
+#[derive(serde::Serialize)]
+#[client_packet("play:set_chunk_cache_radius")]
+pub struct CSetChunkCacheRadius {
+    pub view_distance: VarInt,
+}
@@ -0,0 +1,86 @@
+use pumpkin_macros::client_packet;
+use pumpkin_world::item::ItemStack;
+use serde::Serialize;
+
+use crate::{
+    bytebuf::{serializer::Serializer, ByteBuffer},
+    slot::Slot,
+    ClientPacket, VarInt,
+};
+
+/// Where an entity wears an item, `Body` is the armor of horses and wolves
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EquipmentSlot {
+    MainHand,
+    OffHand,
+    Feet,
+    Legs,
+    Chest,
+    Head,
+    Body,
+}
+
+/// Shows the items an entity wears, only the given slots are changed. `None` is an empty slot
+#[client_packet("play:set_equipment")]
+pub struct CSetEquipment<'a> {
+    entity_id: VarInt,
+    equipment: &'a [(EquipmentSlot, Option<ItemStack>)],
+}
+
+impl<'a> CSetEquipment<'a> {
+    pub fn new(entity_id: VarInt, equipment: &'a [(EquipmentSlot, Option<ItemStack>)]) -> Self {
+        Self {
+            entity_id,
+            equipment,
+        }
+    }
+}
+
+impl ClientPacket for CSetEquipment<'_> {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        bytebuf.put_var_int(&self.entity_id);
+        for (i, (slot, stack)) in self.equipment.iter().enumerate() {
+            // the top bit tells that another slot follows
+            let more = if i + 1 < self.equipment.len() {
+                0x80
+            } else {
+                0
+            };
+            bytebuf.put_u8(*slot as u8 | more);
+            let mut serializer = Serializer::new(ByteBuffer::empty());
+            Slot::from(stack)
+                .serialize(&mut serializer)
+                .expect("Slots can always be serialized");
+            bytebuf.put(serializer.output.buf());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_world::item::ItemStack;
+
+    use crate::{bytebuf::ByteBuffer, ClientPacket};
+
+    use super::{CSetEquipment, EquipmentSlot};
+
+    #[test]
+    fn marks_all_but_the_last_slot() {
+        let equipment = [
+            (EquipmentSlot::Body, Some(ItemStack::new(1, 42))),
+            (EquipmentSlot::MainHand, None),
+        ];
+        let mut bytebuf = ByteBuffer::empty();
+        CSetEquipment::new(5.into(), &equipment).write(&mut bytebuf);
+
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 5);
+        assert_eq!(bytebuf.get_u8().unwrap(), 0x86);
+        // count, id and no added or removed components
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 1);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 42);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 0);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 0);
+        assert_eq!(bytebuf.get_u8().unwrap(), 0);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 0);
+    }
+}
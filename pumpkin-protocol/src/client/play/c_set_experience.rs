@@ -0,0 +1,23 @@
+use pumpkin_macros::client_packet;
+use serde::Serialize;
+
+use crate::VarInt;
+
+#[derive(Serialize)]
+#[client_packet("play:set_experience")]
+pub struct CSetExperience {
+    /// How full the bar is, from 0 to 1
+    progress: f32,
+    level: VarInt,
+    total_experience: VarInt,
+}
+
+impl CSetExperience {
+    pub fn new(progress: f32, level: VarInt, total_experience: VarInt) -> Self {
+        Self {
+            progress,
+            level,
+            total_experience,
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use pumpkin_macros::client_packet;
+use serde::Serialize;
+
+use crate::VarInt;
+
+#[derive(Serialize)]
+#[client_packet("play:set_passengers")]
+pub struct CSetPassengers<'a> {
+    entity_id: VarInt,
+    count: VarInt,
+    passengers: &'a [VarInt],
+}
+
+impl<'a> CSetPassengers<'a> {
+    pub fn new(entity_id: VarInt, passengers: &'a [VarInt]) -> Self {
+        Self {
+            entity_id,
+            count: VarInt(passengers.len() as i32),
+            passengers,
+        }
+    }
+}
@@ -0,0 +1,9 @@
+use pumpkin_macros::client_packet;
+
+use crate::VarInt;
+
+#[derive(serde::Serialize)]
+#[client_packet("play:set_simulation_distance")]
+pub struct CSetSimulationDistance {
+    pub simulation_distance: VarInt,
+}
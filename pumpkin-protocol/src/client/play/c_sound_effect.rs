@@ -1,40 +1,60 @@
 use pumpkin_macros::client_packet;
-use serde::Serialize;
 
-use crate::{SoundCategory, VarInt};
+use crate::{bytebuf::ByteBuffer, ClientPacket, SoundCategory, VarInt};
+
+/// The sound to play, either from the sound registry or any sound of a resource pack
+#[derive(Clone, Copy)]
+pub enum SoundEvent<'a> {
+    /// An id in `minecraft:sound_event`
+    Registry(u16),
+    Custom {
+        name: &'a str,
+        /// The distance it can be heard from, by default it depends on the volume
+        fixed_range: Option<f32>,
+    },
+}
+
+impl SoundEvent<'_> {
+    pub(crate) fn write(&self, bytebuf: &mut ByteBuffer) {
+        match self {
+            // the id is sent plus one, as zero means the sound is sent inline
+            Self::Registry(id) => bytebuf.put_var_int(&(i32::from(*id) + 1).into()),
+            Self::Custom { name, fixed_range } => {
+                bytebuf.put_var_int(&0.into());
+                bytebuf.put_string(name);
+                bytebuf.put_option(fixed_range, |p, v| p.put_f32(*v));
+            }
+        }
+    }
+}
 
-#[derive(Serialize)]
 #[client_packet("play:sound")]
-pub struct CSoundEffect {
-    sound_id: VarInt,
-    // TODO: add sound from name
-    // sound_name: Option<&'a str>,
-    // has_fixed_range: Option<bool>,
-    // range: Option<f32>,
-    sound_category: VarInt,
+pub struct CSoundEffect<'a> {
+    sound: SoundEvent<'a>,
+    sound_category: SoundCategory,
     effect_position_x: i32,
     effect_position_y: i32,
     effect_position_z: i32,
     volume: f32,
     pitch: f32,
-    seed: f64,
+    seed: i64,
 }
 
-impl CSoundEffect {
+impl<'a> CSoundEffect<'a> {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
-        sound_id: VarInt,
+        sound: SoundEvent<'a>,
         sound_category: SoundCategory,
         effect_position_x: f64,
         effect_position_y: f64,
         effect_position_z: f64,
         volume: f32,
         pitch: f32,
-        seed: f64,
+        seed: i64,
     ) -> Self {
         Self {
-            sound_id: VarInt(sound_id.0 + 1),
-            sound_category: VarInt(sound_category as i32),
+            sound,
+            sound_category,
             effect_position_x: (effect_position_x * 8.0) as i32,
             effect_position_y: (effect_position_y * 8.0) as i32,
             effect_position_z: (effect_position_z * 8.0) as i32,
@@ -44,3 +64,16 @@ impl CSoundEffect {
         }
     }
 }
+
+impl ClientPacket for CSoundEffect<'_> {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        self.sound.write(bytebuf);
+        bytebuf.put_var_int(&VarInt(self.sound_category as i32));
+        bytebuf.put_i32(self.effect_position_x);
+        bytebuf.put_i32(self.effect_position_y);
+        bytebuf.put_i32(self.effect_position_z);
+        bytebuf.put_f32(self.volume);
+        bytebuf.put_f32(self.pitch);
+        bytebuf.put_i64(self.seed);
+    }
+}
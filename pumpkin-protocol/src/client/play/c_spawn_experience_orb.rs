@@ -0,0 +1,28 @@
+use pumpkin_macros::client_packet;
+use serde::Serialize;
+
+use crate::VarInt;
+
+/// Spawns an experience orb, which are not spawned with `CSpawnEntity`
+#[derive(Serialize)]
+#[client_packet("play:add_experience_orb")]
+pub struct CSpawnExperienceOrb {
+    entity_id: VarInt,
+    x: f64,
+    y: f64,
+    z: f64,
+    /// The experience points, the client only uses it to pick the orb's size
+    count: i16,
+}
+
+impl CSpawnExperienceOrb {
+    pub fn new(entity_id: VarInt, x: f64, y: f64, z: f64, count: i16) -> Self {
+        Self {
+            entity_id,
+            x,
+            y,
+            z,
+            count,
+        }
+    }
+}
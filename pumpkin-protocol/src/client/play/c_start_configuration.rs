@@ -0,0 +1,18 @@
+use pumpkin_macros::client_packet;
+
+/// Sends the client back into the configuration, e.g. to sync changed registries
+#[derive(serde::Serialize)]
+#[client_packet("play:start_configuration")]
+pub struct CStartConfiguration {}
+
+impl Default for CStartConfiguration {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CStartConfiguration {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
@@ -0,0 +1,35 @@
+use pumpkin_macros::client_packet;
+
+use crate::{bytebuf::ByteBuffer, ClientPacket, SoundCategory, VarInt};
+
+/// Stops sounds which are playing, all of them if neither a category nor a sound is given
+#[client_packet("play:stop_sound")]
+pub struct CStopSound<'a> {
+    category: Option<SoundCategory>,
+    sound: Option<&'a str>,
+}
+
+impl<'a> CStopSound<'a> {
+    pub fn new(category: Option<SoundCategory>, sound: Option<&'a str>) -> Self {
+        Self { category, sound }
+    }
+}
+
+impl ClientPacket for CStopSound<'_> {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        let mut flags = 0;
+        if self.category.is_some() {
+            flags |= 0x01;
+        }
+        if self.sound.is_some() {
+            flags |= 0x02;
+        }
+        bytebuf.put_i8(flags);
+        if let Some(category) = self.category {
+            bytebuf.put_var_int(&VarInt(category as i32));
+        }
+        if let Some(sound) = self.sound {
+            bytebuf.put_string(sound);
+        }
+    }
+}
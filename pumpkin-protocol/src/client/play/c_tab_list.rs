@@ -0,0 +1,18 @@
+use pumpkin_core::text::TextComponent;
+
+use pumpkin_macros::client_packet;
+use serde::Serialize;
+
+/// The text above and below the player list, an empty text removes it
+#[derive(Serialize)]
+#[client_packet("play:tab_list")]
+pub struct CTabList<'a> {
+    header: &'a TextComponent<'a>,
+    footer: &'a TextComponent<'a>,
+}
+
+impl<'a> CTabList<'a> {
+    pub fn new(header: &'a TextComponent<'a>, footer: &'a TextComponent<'a>) -> Self {
+        Self { header, footer }
+    }
+}
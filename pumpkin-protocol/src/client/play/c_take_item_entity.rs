@@ -0,0 +1,28 @@
+use pumpkin_macros::client_packet;
+use serde::Serialize;
+
+use crate::VarInt;
+
+/// Shows the item or experience orb flying to the entity which picked it up. The entity is not
+/// removed by this
+#[derive(Serialize)]
+#[client_packet("play:take_item_entity")]
+pub struct CTakeItemEntity {
+    collected_entity_id: VarInt,
+    collector_entity_id: VarInt,
+    item_count: VarInt,
+}
+
+impl CTakeItemEntity {
+    pub fn new(
+        collected_entity_id: VarInt,
+        collector_entity_id: VarInt,
+        item_count: VarInt,
+    ) -> Self {
+        Self {
+            collected_entity_id,
+            collector_entity_id,
+            item_count,
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use pumpkin_macros::client_packet;
+use serde::Serialize;
+
+/// The fade timings of titles, all in ticks
+#[derive(Serialize)]
+#[client_packet("play:set_titles_animation")]
+pub struct CTitleAnimation {
+    fade_in: i32,
+    stay: i32,
+    fade_out: i32,
+}
+
+impl CTitleAnimation {
+    pub fn new(fade_in: i32, stay: i32, fade_out: i32) -> Self {
+        Self {
+            fade_in,
+            stay,
+            fade_out,
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use pumpkin_macros::client_packet;
+use pumpkin_world::item::component::{network_id, ATTRIBUTES};
+
+use crate::{bytebuf::ByteBuffer, ClientPacket, VarInt};
+
+/// Sets the base values of an entity's attributes, like the speed of a horse. Attributes the
+/// registry doesn't know are left out
+#[client_packet("play:update_attributes")]
+pub struct CUpdateAttributes<'a> {
+    entity_id: VarInt,
+    attributes: &'a [(&'a str, f64)],
+}
+
+impl<'a> CUpdateAttributes<'a> {
+    pub fn new(entity_id: VarInt, attributes: &'a [(&'a str, f64)]) -> Self {
+        Self {
+            entity_id,
+            attributes,
+        }
+    }
+}
+
+impl ClientPacket for CUpdateAttributes<'_> {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        bytebuf.put_var_int(&self.entity_id);
+        let attributes: Vec<_> = self
+            .attributes
+            .iter()
+            .filter_map(|(name, value)| Some((network_id(ATTRIBUTES, name)?, *value)))
+            .collect();
+        bytebuf.put_list(&attributes, |bytebuf, (id, value)| {
+            bytebuf.put_var_int(&VarInt(*id));
+            bytebuf.put_f64(*value);
+            // no modifiers
+            bytebuf.put_var_int(&VarInt(0));
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_world::item::component::{network_id, ATTRIBUTES};
+
+    use crate::{bytebuf::ByteBuffer, ClientPacket};
+
+    use super::CUpdateAttributes;
+
+    #[test]
+    fn skips_unknown_attributes() {
+        let attributes = [
+            ("minecraft:movement_speed", 0.25),
+            ("minecraft:not_an_attribute", 1.0),
+        ];
+        let mut bytebuf = ByteBuffer::empty();
+        CUpdateAttributes::new(3.into(), &attributes).write(&mut bytebuf);
+
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 3);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 1);
+        assert_eq!(
+            Some(bytebuf.get_var_int().unwrap().0),
+            network_id(ATTRIBUTES, "minecraft:movement_speed")
+        );
+        assert!((bytebuf.get_f64().unwrap() - 0.25).abs() < f64::EPSILON);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 0);
+    }
+}
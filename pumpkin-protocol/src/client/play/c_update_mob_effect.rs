@@ -0,0 +1,34 @@
+use pumpkin_macros::client_packet;
+use serde::Serialize;
+
+use crate::VarInt;
+
+#[derive(Serialize)]
+#[client_packet("play:update_mob_effect")]
+pub struct CUpdateMobEffect {
+    entity_id: VarInt,
+    effect_id: VarInt,
+    amplifier: VarInt,
+    /// In ticks, -1 lasts forever
+    duration: VarInt,
+    /// 0x01 ambient, 0x02 show particles, 0x04 show icon, 0x08 blend
+    flags: i8,
+}
+
+impl CUpdateMobEffect {
+    pub fn new(
+        entity_id: VarInt,
+        effect_id: VarInt,
+        amplifier: VarInt,
+        duration: VarInt,
+        flags: i8,
+    ) -> Self {
+        Self {
+            entity_id,
+            effect_id,
+            amplifier,
+            duration,
+            flags,
+        }
+    }
+}
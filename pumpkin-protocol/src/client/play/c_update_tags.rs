@@ -0,0 +1,25 @@
+use pumpkin_macros::client_packet;
+
+use crate::{
+    bytebuf::ByteBuffer,
+    client::config::{write_registry_tags, RegistryTags},
+    ClientPacket,
+};
+
+/// Replaces the client's tags, after datapacks changed
+#[client_packet("play:update_tags")]
+pub struct CPlayUpdateTags<'a> {
+    registries: &'a [RegistryTags],
+}
+
+impl<'a> CPlayUpdateTags<'a> {
+    pub fn new(registries: &'a [RegistryTags]) -> Self {
+        Self { registries }
+    }
+}
+
+impl ClientPacket for CPlayUpdateTags<'_> {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        write_registry_tags(bytebuf, self.registries);
+    }
+}
@@ -0,0 +1,101 @@
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+use pumpkin_macros::client_packet;
+
+use crate::{bytebuf::ByteBuffer, ClientPacket, VarInt};
+
+/// The color clients reset to when a team has none
+const RESET_COLOR: i32 = 21;
+
+/// How the members of a team see each other. Players glow and show their name in the color
+#[derive(Clone)]
+pub struct TeamInfo<'a> {
+    pub display_name: TextComponent<'a>,
+    pub friendly_fire: bool,
+    /// Members see their invisible teammates as a ghost
+    pub see_friendly_invisibles: bool,
+    pub color: Option<NamedColor>,
+}
+
+pub enum TeamMethod<'a> {
+    Create(TeamInfo<'a>, &'a [&'a str]),
+    Remove,
+    Update(TeamInfo<'a>),
+    AddMembers(&'a [&'a str]),
+    RemoveMembers(&'a [&'a str]),
+}
+
+/// Creates, changes or removes a team. Members are player names or entity uuids
+#[client_packet("play:set_player_team")]
+pub struct CUpdateTeams<'a> {
+    name: &'a str,
+    method: TeamMethod<'a>,
+}
+
+impl<'a> CUpdateTeams<'a> {
+    pub fn new(name: &'a str, method: TeamMethod<'a>) -> Self {
+        Self { name, method }
+    }
+}
+
+fn put_info(bytebuf: &mut ByteBuffer, info: &TeamInfo) {
+    bytebuf.put_slice(&info.display_name.encode());
+    bytebuf.put_i8(i8::from(info.friendly_fire) | i8::from(info.see_friendly_invisibles) << 1);
+    // name tag visibility and collision rule
+    bytebuf.put_string("always");
+    bytebuf.put_string("always");
+    bytebuf.put_var_int(&VarInt(
+        info.color.map_or(RESET_COLOR, |color| color as i32),
+    ));
+    // prefix and suffix
+    bytebuf.put_slice(&TextComponent::text("").encode());
+    bytebuf.put_slice(&TextComponent::text("").encode());
+}
+
+fn put_members(bytebuf: &mut ByteBuffer, members: &[&str]) {
+    bytebuf.put_list(members, |bytebuf, member| bytebuf.put_string(member));
+}
+
+impl ClientPacket for CUpdateTeams<'_> {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        bytebuf.put_string(self.name);
+        match &self.method {
+            TeamMethod::Create(info, members) => {
+                bytebuf.put_u8(0);
+                put_info(bytebuf, info);
+                put_members(bytebuf, members);
+            }
+            TeamMethod::Remove => bytebuf.put_u8(1),
+            TeamMethod::Update(info) => {
+                bytebuf.put_u8(2);
+                put_info(bytebuf, info);
+            }
+            TeamMethod::AddMembers(members) => {
+                bytebuf.put_u8(3);
+                put_members(bytebuf, members);
+            }
+            TeamMethod::RemoveMembers(members) => {
+                bytebuf.put_u8(4);
+                put_members(bytebuf, members);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{bytebuf::ByteBuffer, ClientPacket};
+
+    use super::{CUpdateTeams, TeamMethod};
+
+    #[test]
+    fn writes_members() {
+        let mut bytebuf = ByteBuffer::empty();
+        CUpdateTeams::new("red", TeamMethod::AddMembers(&["Alex", "Steve"])).write(&mut bytebuf);
+
+        assert_eq!(bytebuf.get_string().unwrap(), "red");
+        assert_eq!(bytebuf.get_u8().unwrap(), 3);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 2);
+        assert_eq!(bytebuf.get_string().unwrap(), "Alex");
+        assert_eq!(bytebuf.get_string().unwrap(), "Steve");
+    }
+}
@@ -1,14 +1,20 @@
 mod c_acknowledge_block;
 mod c_actionbar;
 mod c_block_destroy_stage;
+mod c_block_event;
 mod c_block_update;
+mod c_boss_event;
 mod c_center_chunk;
 mod c_change_difficulty;
+mod c_chunk_batch_finished;
+mod c_chunk_batch_start;
 mod c_chunk_data;
+mod c_clear_title;
 mod c_close_container;
 mod c_combat_death;
 mod c_command_suggestions;
 mod c_commands;
+mod c_cooldown;
 mod c_damage_event;
 mod c_disguised_chat_message;
 mod c_display_objective;
@@ -19,10 +25,13 @@ mod c_entity_status;
 mod c_entity_velocity;
 mod c_game_event;
 mod c_head_rot;
+mod c_horse_screen_open;
 mod c_hurt_animation;
 mod c_initialize_world_border;
 mod c_keep_alive;
+mod c_light_update;
 mod c_login;
+mod c_move_vehicle;
 mod c_open_screen;
 mod c_particle;
 mod c_ping_response;
@@ -31,47 +40,74 @@ mod c_player_abilities;
 mod c_player_chat_message;
 mod c_player_info_update;
 mod c_player_remove;
+mod c_plugin_message;
 mod c_remove_entities;
+mod c_remove_mob_effect;
 mod c_reset_score;
 mod c_respawn;
+mod c_section_blocks_update;
 mod c_set_border_center;
 mod c_set_border_lerp_size;
 mod c_set_border_size;
 mod c_set_border_warning_delay;
 mod c_set_border_warning_distance;
+mod c_set_camera;
+mod c_set_chunk_cache_radius;
 mod c_set_container_content;
 mod c_set_container_property;
 mod c_set_container_slot;
+mod c_set_equipment;
+mod c_set_experience;
 mod c_set_health;
 mod c_set_held_item;
+mod c_set_passengers;
+mod c_set_simulation_distance;
 mod c_set_title;
 mod c_sound_effect;
 mod c_spawn_entity;
+mod c_spawn_experience_orb;
+mod c_start_configuration;
+mod c_stop_sound;
 mod c_subtitle;
 mod c_sync_player_position;
 mod c_system_chat_message;
+mod c_tab_list;
+mod c_take_item_entity;
 mod c_teleport_entity;
+mod c_title_animation;
 mod c_transfer;
 mod c_unload_chunk;
+mod c_update_attributes;
 mod c_update_entity_pos;
 mod c_update_entity_pos_rot;
 mod c_update_entity_rot;
+mod c_update_mob_effect;
 mod c_update_objectives;
 mod c_update_score;
+mod c_update_tags;
+mod c_update_teams;
+mod c_update_time;
 mod c_worldevent;
+mod particle;
 mod player_action;
 
 pub use c_acknowledge_block::*;
 pub use c_actionbar::*;
 pub use c_block_destroy_stage::*;
+pub use c_block_event::*;
 pub use c_block_update::*;
+pub use c_boss_event::*;
 pub use c_center_chunk::*;
 pub use c_change_difficulty::*;
+pub use c_chunk_batch_finished::*;
+pub use c_chunk_batch_start::*;
 pub use c_chunk_data::*;
+pub use c_clear_title::*;
 pub use c_close_container::*;
 pub use c_combat_death::*;
 pub use c_command_suggestions::*;
 pub use c_commands::*;
+pub use c_cooldown::*;
 pub use c_damage_event::*;
 pub use c_disguised_chat_message::*;
 pub use c_display_objective::*;
@@ -82,10 +118,13 @@ pub use c_entity_status::*;
 pub use c_entity_velocity::*;
 pub use c_game_event::*;
 pub use c_head_rot::*;
+pub use c_horse_screen_open::*;
 pub use c_hurt_animation::*;
 pub use c_initialize_world_border::*;
 pub use c_keep_alive::*;
+pub use c_light_update::*;
 pub use c_login::*;
+pub use c_move_vehicle::*;
 pub use c_open_screen::*;
 pub use c_particle::*;
 pub use c_ping_response::*;
@@ -94,32 +133,53 @@ pub use c_player_abilities::*;
 pub use c_player_chat_message::*;
 pub use c_player_info_update::*;
 pub use c_player_remove::*;
+pub use c_plugin_message::*;
 pub use c_remove_entities::*;
+pub use c_remove_mob_effect::*;
 pub use c_reset_score::*;
 pub use c_respawn::*;
+pub use c_section_blocks_update::*;
 pub use c_set_border_center::*;
 pub use c_set_border_lerp_size::*;
 pub use c_set_border_size::*;
 pub use c_set_border_warning_delay::*;
 pub use c_set_border_warning_distance::*;
+pub use c_set_camera::*;
+pub use c_set_chunk_cache_radius::*;
 pub use c_set_container_content::*;
 pub use c_set_container_property::*;
 pub use c_set_container_slot::*;
+pub use c_set_equipment::*;
+pub use c_set_experience::*;
 pub use c_set_health::*;
 pub use c_set_held_item::*;
+pub use c_set_passengers::*;
+pub use c_set_simulation_distance::*;
 pub use c_set_title::*;
 pub use c_sound_effect::*;
 pub use c_spawn_entity::*;
+pub use c_spawn_experience_orb::*;
+pub use c_start_configuration::*;
+pub use c_stop_sound::*;
 pub use c_subtitle::*;
 pub use c_sync_player_position::*;
 pub use c_system_chat_message::*;
+pub use c_tab_list::*;
+pub use c_take_item_entity::*;
 pub use c_teleport_entity::*;
+pub use c_title_animation::*;
 pub use c_transfer::*;
 pub use c_unload_chunk::*;
+pub use c_update_attributes::*;
 pub use c_update_entity_pos::*;
 pub use c_update_entity_pos_rot::*;
 pub use c_update_entity_rot::*;
+pub use c_update_mob_effect::*;
 pub use c_update_objectives::*;
 pub use c_update_score::*;
+pub use c_update_tags::*;
+pub use c_update_teams::*;
+pub use c_update_time::*;
 pub use c_worldevent::*;
+pub use particle::*;
 pub use player_action::*;
@@ -0,0 +1,306 @@
+use pumpkin_core::{
+    math::{position::WorldPosition, vector3::Vector3},
+    text::color::RGBColor,
+};
+use pumpkin_macros::particle;
+use pumpkin_world::item::ItemStack;
+use serde::Serialize;
+
+use crate::{
+    bytebuf::{serializer::Serializer, ByteBuffer},
+    slot::Slot,
+    VarInt,
+};
+
+/// A particle from `minecraft:particle_type` with the options it needs, e.g. the color of dust
+#[derive(Clone, Debug, PartialEq)]
+pub struct Particle {
+    id: u16,
+    options: ParticleOptions,
+}
+
+/// The options of a particle, most particles have none
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParticleOptions {
+    None,
+    /// A block state id, for `block`, `block_marker`, `falling_dust`, `dust_pillar` and `block_crumble`
+    Block(u16),
+    Dust {
+        color: RGBColor,
+        /// Between 0.01 and 4.0
+        scale: f32,
+    },
+    DustColorTransition {
+        from: RGBColor,
+        to: RGBColor,
+        scale: f32,
+    },
+    /// The color of `entity_effect`
+    Color {
+        color: RGBColor,
+        alpha: u8,
+    },
+    Item(ItemStack),
+    /// Flies from the particle's position to the destination
+    Vibration {
+        destination: PositionSource,
+        arrival_in_ticks: i32,
+    },
+    Trail {
+        target: Vector3<f64>,
+        color: RGBColor,
+    },
+    /// The rotation in radians
+    SculkCharge(f32),
+    /// The ticks until the particle appears
+    Shriek(i32),
+}
+
+/// The kinds of [`ParticleOptions`], to know which ones a particle needs
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParticleOptionsKind {
+    None,
+    Block,
+    Dust,
+    DustColorTransition,
+    Color,
+    Item,
+    Vibration,
+    Trail,
+    SculkCharge,
+    Shriek,
+}
+
+/// Where a vibration particle flies to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PositionSource {
+    Block(WorldPosition),
+    Entity { entity_id: i32, eye_height: f32 },
+}
+
+impl ParticleOptionsKind {
+    /// The options the particle needs
+    #[must_use]
+    pub fn of(particle_id: u16) -> Self {
+        const BLOCK: [u16; 5] = [
+            particle!("minecraft:block"),
+            particle!("minecraft:block_marker"),
+            particle!("minecraft:falling_dust"),
+            particle!("minecraft:dust_pillar"),
+            particle!("minecraft:block_crumble"),
+        ];
+        const DUST: u16 = particle!("minecraft:dust");
+        const DUST_COLOR_TRANSITION: u16 = particle!("minecraft:dust_color_transition");
+        const ENTITY_EFFECT: u16 = particle!("minecraft:entity_effect");
+        const ITEM: u16 = particle!("minecraft:item");
+        const VIBRATION: u16 = particle!("minecraft:vibration");
+        const TRAIL: u16 = particle!("minecraft:trail");
+        const SCULK_CHARGE: u16 = particle!("minecraft:sculk_charge");
+        const SHRIEK: u16 = particle!("minecraft:shriek");
+
+        match particle_id {
+            id if BLOCK.contains(&id) => Self::Block,
+            DUST => Self::Dust,
+            DUST_COLOR_TRANSITION => Self::DustColorTransition,
+            ENTITY_EFFECT => Self::Color,
+            ITEM => Self::Item,
+            VIBRATION => Self::Vibration,
+            TRAIL => Self::Trail,
+            SCULK_CHARGE => Self::SculkCharge,
+            SHRIEK => Self::Shriek,
+            _ => Self::None,
+        }
+    }
+}
+
+impl ParticleOptions {
+    #[must_use]
+    pub fn kind(&self) -> ParticleOptionsKind {
+        match self {
+            Self::None => ParticleOptionsKind::None,
+            Self::Block(_) => ParticleOptionsKind::Block,
+            Self::Dust { .. } => ParticleOptionsKind::Dust,
+            Self::DustColorTransition { .. } => ParticleOptionsKind::DustColorTransition,
+            Self::Color { .. } => ParticleOptionsKind::Color,
+            Self::Item(_) => ParticleOptionsKind::Item,
+            Self::Vibration { .. } => ParticleOptionsKind::Vibration,
+            Self::Trail { .. } => ParticleOptionsKind::Trail,
+            Self::SculkCharge(_) => ParticleOptionsKind::SculkCharge,
+            Self::Shriek(_) => ParticleOptionsKind::Shriek,
+        }
+    }
+
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        match self {
+            Self::None => {}
+            Self::Block(state_id) => bytebuf.put_var_int(&VarInt(i32::from(*state_id))),
+            Self::Dust { color, scale } => {
+                bytebuf.put_i32(color.to_int());
+                bytebuf.put_f32(*scale);
+            }
+            Self::DustColorTransition { from, to, scale } => {
+                bytebuf.put_i32(from.to_int());
+                bytebuf.put_i32(to.to_int());
+                bytebuf.put_f32(*scale);
+            }
+            Self::Color { color, alpha } => {
+                bytebuf.put_i32((i32::from(*alpha) << 24) | color.to_int());
+            }
+            Self::Item(stack) => put_serialized(bytebuf, &Slot::from(stack)),
+            Self::Vibration {
+                destination,
+                arrival_in_ticks,
+            } => {
+                match destination {
+                    PositionSource::Block(position) => {
+                        bytebuf.put_var_int(&0.into());
+                        put_serialized(bytebuf, position);
+                    }
+                    PositionSource::Entity {
+                        entity_id,
+                        eye_height,
+                    } => {
+                        bytebuf.put_var_int(&1.into());
+                        bytebuf.put_var_int(&VarInt(*entity_id));
+                        bytebuf.put_f32(*eye_height);
+                    }
+                }
+                bytebuf.put_var_int(&VarInt(*arrival_in_ticks));
+            }
+            Self::Trail { target, color } => {
+                bytebuf.put_f64(target.x);
+                bytebuf.put_f64(target.y);
+                bytebuf.put_f64(target.z);
+                bytebuf.put_i32(color.to_int());
+            }
+            Self::SculkCharge(roll) => bytebuf.put_f32(*roll),
+            Self::Shriek(delay) => bytebuf.put_var_int(&VarInt(*delay)),
+        }
+    }
+}
+
+fn put_serialized(bytebuf: &mut ByteBuffer, value: &impl Serialize) {
+    let mut serializer = Serializer::new(ByteBuffer::empty());
+    value
+        .serialize(&mut serializer)
+        .expect("Could not serialize particle options");
+    bytebuf.put(serializer.output.buf());
+}
+
+impl Particle {
+    /// Returns None if the particle needs other options, see [`ParticleOptionsKind::of`].
+    #[must_use]
+    pub fn new(id: u16, options: ParticleOptions) -> Option<Self> {
+        (ParticleOptionsKind::of(id) == options.kind()).then_some(Self { id, options })
+    }
+
+    /// A particle without options, like `minecraft:flame`.
+    #[must_use]
+    pub fn simple(id: u16) -> Option<Self> {
+        Self::new(id, ParticleOptions::None)
+    }
+
+    #[must_use]
+    pub fn dust(color: RGBColor, scale: f32) -> Self {
+        Self {
+            id: particle!("minecraft:dust"),
+            options: ParticleOptions::Dust {
+                color,
+                scale: scale.clamp(0.01, 4.0),
+            },
+        }
+    }
+
+    #[must_use]
+    pub fn dust_color_transition(from: RGBColor, to: RGBColor, scale: f32) -> Self {
+        Self {
+            id: particle!("minecraft:dust_color_transition"),
+            options: ParticleOptions::DustColorTransition {
+                from,
+                to,
+                scale: scale.clamp(0.01, 4.0),
+            },
+        }
+    }
+
+    /// The cracks of a block being broken
+    #[must_use]
+    pub fn block(state_id: u16) -> Self {
+        Self {
+            id: particle!("minecraft:block"),
+            options: ParticleOptions::Block(state_id),
+        }
+    }
+
+    #[must_use]
+    pub fn item(stack: ItemStack) -> Self {
+        Self {
+            id: particle!("minecraft:item"),
+            options: ParticleOptions::Item(stack),
+        }
+    }
+
+    #[must_use]
+    pub fn vibration(destination: PositionSource, arrival_in_ticks: i32) -> Self {
+        Self {
+            id: particle!("minecraft:vibration"),
+            options: ParticleOptions::Vibration {
+                destination,
+                arrival_in_ticks,
+            },
+        }
+    }
+
+    #[must_use]
+    pub fn shriek(delay: i32) -> Self {
+        Self {
+            id: particle!("minecraft:shriek"),
+            options: ParticleOptions::Shriek(delay),
+        }
+    }
+
+    #[must_use]
+    pub fn id(&self) -> u16 {
+        self.id
+    }
+
+    #[must_use]
+    pub fn options(&self) -> &ParticleOptions {
+        &self.options
+    }
+
+    pub(crate) fn write(&self, bytebuf: &mut ByteBuffer) {
+        bytebuf.put_var_int(&VarInt(i32::from(self.id)));
+        self.options.write(bytebuf);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_core::text::color::RGBColor;
+    use pumpkin_macros::particle;
+
+    use crate::bytebuf::ByteBuffer;
+
+    use super::{Particle, ParticleOptions, ParticleOptionsKind};
+
+    #[test]
+    fn options_must_match_the_particle() {
+        assert_eq!(
+            ParticleOptionsKind::of(particle!("minecraft:falling_dust")),
+            ParticleOptionsKind::Block
+        );
+        assert!(Particle::simple(particle!("minecraft:flame")).is_some());
+        assert!(Particle::simple(particle!("minecraft:dust")).is_none());
+        assert!(Particle::new(particle!("minecraft:shriek"), ParticleOptions::Shriek(5)).is_some());
+    }
+
+    #[test]
+    fn writes_dust() {
+        let mut bytebuf = ByteBuffer::empty();
+        Particle::dust(RGBColor::new(0xFF, 0x00, 0x80), 1.5).write(&mut bytebuf);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 13);
+        assert_eq!(bytebuf.get_i32().unwrap(), 0x00FF_0080);
+        assert_eq!(bytebuf.get_f32().unwrap(), 1.5);
+    }
+}
@@ -1,3 +1,5 @@
+use pumpkin_core::text::TextComponent;
+
 use crate::{Property, VarInt};
 
 pub enum PlayerAction<'a> {
@@ -5,12 +7,24 @@ pub enum PlayerAction<'a> {
         name: &'a str,
         properties: &'a [Property],
     },
-    InitializeChat(u8),
+    /// The session the player signs chat messages with, None if they don't sign them
+    InitializeChat(Option<ChatSession<'a>>),
     /// Gamemode ?
     UpdateGameMode(VarInt),
     /// Listed ?
     UpdateListed(bool),
-    UpdateLatency(u8),
-    UpdateDisplayName(u8),
-    UpdateListOrder,
+    /// Ping in milliseconds, shown as bars
+    UpdateLatency(VarInt),
+    /// Shown instead of the name, None shows the name
+    UpdateDisplayName(Option<TextComponent<'a>>),
+    /// Players with a higher order are listed first
+    UpdateListOrder(VarInt),
+}
+
+pub struct ChatSession<'a> {
+    pub session_id: uuid::Uuid,
+    /// Milliseconds since the unix epoch
+    pub expires_at: i64,
+    pub public_key: &'a [u8],
+    pub key_signature: &'a [u8],
 }
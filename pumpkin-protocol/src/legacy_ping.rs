@@ -0,0 +1,125 @@
+//! The server list ping from before the Netty rewrite (1.6 and older).
+//!
+//! These clients, and some server scanners, start the connection with `0xFE` instead of a handshake
+//! and expect a kick packet (`0xFF`) containing the status as a UTF-16 string.
+
+/// The first byte of a legacy ping
+pub const LEGACY_PING_ID: u8 = 0xFE;
+const KICK_ID: u8 = 0xFF;
+/// The plugin message 1.6 sends after the ping
+const PLUGIN_MESSAGE_ID: u8 = 0xFA;
+
+/// The protocol a modern server reports, old clients show the server as outdated
+pub const LEGACY_PROTOCOL: i32 = 127;
+
+/// Which response the client understands
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LegacyPing {
+    /// Beta 1.8 to 1.3, only sends `0xFE`
+    Beta,
+    /// 1.4 to 1.6, sends `0xFE 0x01` and since 1.6 a `MC|PingHost` plugin message
+    V1_4,
+}
+
+impl LegacyPing {
+    /// Detects a legacy ping from the first bytes of a connection.
+    ///
+    /// A modern handshake can also start with `0xFE` as part of its length,
+    /// it is followed by `0x01 0x00` though.
+    #[must_use]
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        match bytes {
+            [LEGACY_PING_ID] => Some(Self::Beta),
+            [LEGACY_PING_ID, 0x01] | [LEGACY_PING_ID, 0x01, PLUGIN_MESSAGE_ID, ..] => {
+                Some(Self::V1_4)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The status sent to a legacy ping
+pub struct CLegacyStatus<'a> {
+    pub version: &'a str,
+    /// Only plain text with legacy `§` codes, Beta clients do not support colors
+    pub motd: &'a str,
+    pub online: u32,
+    pub max: u32,
+}
+
+impl<'a> CLegacyStatus<'a> {
+    pub fn new(version: &'a str, motd: &'a str, online: u32, max: u32) -> Self {
+        Self {
+            version,
+            motd,
+            online,
+            max,
+        }
+    }
+
+    /// The kick packet with the status, in the format the client understands
+    #[must_use]
+    pub fn encode(&self, ping: LegacyPing) -> Vec<u8> {
+        let status = match ping {
+            // `§` is the separator here, so it can not be used in the motd
+            LegacyPing::Beta => format!(
+                "{}§{}§{}",
+                self.motd.replace('§', ""),
+                self.online,
+                self.max
+            ),
+            LegacyPing::V1_4 => format!(
+                "§1\0{LEGACY_PROTOCOL}\0{}\0{}\0{}\0{}",
+                self.version, self.motd, self.online, self.max
+            ),
+        };
+        let chars: Vec<u16> = status.encode_utf16().collect();
+
+        let mut buf = Vec::with_capacity(3 + chars.len() * 2);
+        buf.push(KICK_ID);
+        buf.extend_from_slice(&(chars.len() as u16).to_be_bytes());
+        for char in chars {
+            buf.extend_from_slice(&char.to_be_bytes());
+        }
+        buf
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CLegacyStatus, LegacyPing};
+
+    #[test]
+    fn detects_legacy_pings() {
+        assert_eq!(LegacyPing::parse(&[0xFE]), Some(LegacyPing::Beta));
+        assert_eq!(LegacyPing::parse(&[0xFE, 0x01]), Some(LegacyPing::V1_4));
+        assert_eq!(
+            LegacyPing::parse(&[0xFE, 0x01, 0xFA, 0x00, 0x0B]),
+            Some(LegacyPing::V1_4)
+        );
+        // a handshake with a length of 254
+        assert_eq!(LegacyPing::parse(&[0xFE, 0x01, 0x00]), None);
+        assert_eq!(LegacyPing::parse(&[0x10, 0x00]), None);
+    }
+
+    #[test]
+    fn encodes_status() {
+        let status = CLegacyStatus::new("1.21.3", "§aA", 1, 20);
+
+        let beta = status.encode(LegacyPing::Beta);
+        assert_eq!(beta[0], 0xFF);
+        // "aA§1§20"
+        assert_eq!(u16::from_be_bytes([beta[1], beta[2]]), 7);
+        assert_eq!(beta[3..5], [0x00, b'a']);
+
+        let new = status.encode(LegacyPing::V1_4);
+        let chars: Vec<u16> = new[3..]
+            .chunks(2)
+            .map(|char| u16::from_be_bytes([char[0], char[1]]))
+            .collect();
+        assert_eq!(
+            String::from_utf16(&chars).unwrap(),
+            "§1\0127\01.21.3\0§aA\01\020"
+        );
+    }
+}
@@ -3,12 +3,16 @@ use pumpkin_core::text::{style::Style, TextComponent};
 use serde::{Deserialize, Serialize};
 
 pub mod bytebuf;
+pub mod capture;
 pub mod client;
+pub mod legacy_ping;
+pub mod nbt;
 pub mod packet_decoder;
 pub mod packet_encoder;
 pub mod query;
 pub mod server;
 pub mod slot;
+pub mod translation;
 
 mod var_int;
 pub use var_int::*;
@@ -55,6 +59,7 @@ impl From<VarInt> for ConnectionState {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SoundCategory {
     Master,
     Music,
@@ -68,6 +73,45 @@ pub enum SoundCategory {
     Voice,
 }
 
+impl SoundCategory {
+    pub const ALL: [Self; 10] = [
+        Self::Master,
+        Self::Music,
+        Self::Records,
+        Self::Weather,
+        Self::Blocks,
+        Self::Hostile,
+        Self::Neutral,
+        Self::Players,
+        Self::Ambient,
+        Self::Voice,
+    ];
+
+    /// The name used in commands like `/playsound`
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Master => "master",
+            Self::Music => "music",
+            Self::Records => "record",
+            Self::Weather => "weather",
+            Self::Blocks => "block",
+            Self::Hostile => "hostile",
+            Self::Neutral => "neutral",
+            Self::Players => "player",
+            Self::Ambient => "ambient",
+            Self::Voice => "voice",
+        }
+    }
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|category| category.name() == name)
+    }
+}
+
 pub struct RawPacket {
     pub id: VarInt,
     pub bytebuf: ByteBuffer,
@@ -84,16 +128,28 @@ pub trait ServerPacket: Packet + Sized {
 #[derive(Serialize)]
 pub struct StatusResponse {
     /// The version on which the Server is running. Optional
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub version: Option<Version>,
     /// Information about currently connected Players. Optional
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub players: Option<Players>,
     /// The description displayed also called MOTD (Message of the day). Optional
-    pub description: String,
+    #[serde(serialize_with = "serialize_json_component")]
+    pub description: TextComponent<'static>,
     /// The icon displayed, Optional
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub favicon: Option<String>,
     /// Players are forced to use Secure chat
     pub enforce_secure_chat: bool,
 }
+
+fn serialize_json_component<S: serde::Serializer>(
+    component: &TextComponent,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    component.as_json().serialize(serializer)
+}
+
 #[derive(Serialize)]
 pub struct Version {
     /// The current name of the Version (e.g. 1.21.3)
@@ -113,7 +169,7 @@ pub struct Players {
     pub sample: Vec<Sample>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct Sample {
     /// Players Name
     pub name: String,
@@ -179,3 +235,28 @@ impl PositionFlag {
         flags.iter().fold(0, |acc, flag| acc | flag.get_mask())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use pumpkin_core::text::{color::NamedColor, TextComponent};
+
+    use crate::StatusResponse;
+
+    #[test]
+    fn status_description_is_a_json_component() {
+        let status = StatusResponse {
+            version: None,
+            players: None,
+            description: TextComponent::text("Hello").color_named(NamedColor::Gold),
+            favicon: None,
+            enforce_secure_chat: false,
+        };
+        let json: serde_json::Value = serde_json::to_value(&status).unwrap();
+        assert_eq!(
+            json["description"],
+            serde_json::json!({"text": "Hello", "color": "gold"})
+        );
+        // the client shows `???` when the players are left out
+        assert!(json.get("players").is_none());
+    }
+}
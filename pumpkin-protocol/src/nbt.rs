@@ -0,0 +1,248 @@
+//! NBT like it is sent in packets since 1.20.2, where the root tag has no name and may be any tag,
+//! not only a compound.
+
+use fastnbt::{ByteArray, IntArray, LongArray, Value};
+use pumpkin_core::nbt::Compound;
+use serde::{
+    de::{self, SeqAccess},
+    ser::SerializeSeq,
+    Deserialize, Serialize, Serializer,
+};
+
+/// Deeper NBT is rejected so clients can't overflow the stack
+const MAX_DEPTH: usize = 512;
+
+const END: u8 = 0;
+const BYTE: u8 = 1;
+const SHORT: u8 = 2;
+const INT: u8 = 3;
+const LONG: u8 = 4;
+const FLOAT: u8 = 5;
+const DOUBLE: u8 = 6;
+const BYTE_ARRAY: u8 = 7;
+const STRING: u8 = 8;
+const LIST: u8 = 9;
+const COMPOUND: u8 = 10;
+const INT_ARRAY: u8 = 11;
+const LONG_ARRAY: u8 = 12;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkNbt(pub Value);
+
+const fn tag_id(value: &Value) -> u8 {
+    match value {
+        Value::Byte(_) => BYTE,
+        Value::Short(_) => SHORT,
+        Value::Int(_) => INT,
+        Value::Long(_) => LONG,
+        Value::Float(_) => FLOAT,
+        Value::Double(_) => DOUBLE,
+        Value::ByteArray(_) => BYTE_ARRAY,
+        Value::String(_) => STRING,
+        Value::List(_) => LIST,
+        Value::Compound(_) => COMPOUND,
+        Value::IntArray(_) => INT_ARRAY,
+        Value::LongArray(_) => LONG_ARRAY,
+    }
+}
+
+fn write_string<S: SerializeSeq>(s: &mut S, string: &str) -> Result<(), S::Error> {
+    // strings are modified UTF-8, which only differs for null and characters outside the BMP
+    let length = u16::try_from(string.len()).unwrap_or(u16::MAX);
+    s.serialize_element(&length)?;
+    for byte in &string.as_bytes()[..usize::from(length)] {
+        s.serialize_element(byte)?;
+    }
+    Ok(())
+}
+
+fn write_payload<S: SerializeSeq>(s: &mut S, value: &Value) -> Result<(), S::Error> {
+    match value {
+        Value::Byte(value) => s.serialize_element(value),
+        Value::Short(value) => s.serialize_element(value),
+        Value::Int(value) => s.serialize_element(value),
+        Value::Long(value) => s.serialize_element(value),
+        Value::Float(value) => s.serialize_element(value),
+        Value::Double(value) => s.serialize_element(value),
+        Value::String(value) => write_string(s, value),
+        Value::ByteArray(array) => {
+            s.serialize_element(&(array.len() as i32))?;
+            array
+                .iter()
+                .try_for_each(|value| s.serialize_element(value))
+        }
+        Value::IntArray(array) => {
+            s.serialize_element(&(array.len() as i32))?;
+            array
+                .iter()
+                .try_for_each(|value| s.serialize_element(value))
+        }
+        Value::LongArray(array) => {
+            s.serialize_element(&(array.len() as i32))?;
+            array
+                .iter()
+                .try_for_each(|value| s.serialize_element(value))
+        }
+        Value::List(list) => {
+            s.serialize_element(&list.first().map_or(END, tag_id))?;
+            s.serialize_element(&(list.len() as i32))?;
+            list.iter().try_for_each(|value| write_payload(s, value))
+        }
+        Value::Compound(compound) => {
+            for (name, value) in compound {
+                s.serialize_element(&tag_id(value))?;
+                write_string(s, name)?;
+                write_payload(s, value)?;
+            }
+            s.serialize_element(&END)
+        }
+    }
+}
+
+impl Serialize for NetworkNbt {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut s = serializer.serialize_seq(None)?;
+        s.serialize_element(&tag_id(&self.0))?;
+        write_payload(&mut s, &self.0)?;
+        s.end()
+    }
+}
+
+struct Reader<'a, A>(&'a mut A);
+
+impl<'de, A: SeqAccess<'de>> Reader<'_, A> {
+    fn next<T: Deserialize<'de>>(&mut self) -> Result<T, A::Error> {
+        self.0
+            .next_element()?
+            .ok_or(de::Error::custom("NBT ended early"))
+    }
+
+    fn length(&mut self) -> Result<usize, A::Error> {
+        usize::try_from(self.next::<i32>()?)
+            .map_err(|_| de::Error::custom("NBT has a negative length"))
+    }
+
+    fn string(&mut self) -> Result<String, A::Error> {
+        let length = self.next::<u16>()?;
+        let bytes = (0..length)
+            .map(|_| self.next::<u8>())
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    fn payload(&mut self, id: u8, depth: usize) -> Result<Value, A::Error> {
+        if depth > MAX_DEPTH {
+            return Err(de::Error::custom("NBT is nested too deep"));
+        }
+        let value = match id {
+            BYTE => Value::Byte(self.next()?),
+            SHORT => Value::Short(self.next()?),
+            INT => Value::Int(self.next()?),
+            LONG => Value::Long(self.next()?),
+            FLOAT => Value::Float(self.next()?),
+            DOUBLE => Value::Double(self.next()?),
+            STRING => Value::String(self.string()?),
+            BYTE_ARRAY => {
+                let length = self.length()?;
+                let array = (0..length).map(|_| self.next()).collect::<Result<_, _>>()?;
+                Value::ByteArray(ByteArray::new(array))
+            }
+            INT_ARRAY => {
+                let length = self.length()?;
+                let array = (0..length).map(|_| self.next()).collect::<Result<_, _>>()?;
+                Value::IntArray(IntArray::new(array))
+            }
+            LONG_ARRAY => {
+                let length = self.length()?;
+                let array = (0..length).map(|_| self.next()).collect::<Result<_, _>>()?;
+                Value::LongArray(LongArray::new(array))
+            }
+            LIST => {
+                let element = self.next::<u8>()?;
+                let length = self.length()?;
+                let list = (0..length)
+                    .map(|_| self.payload(element, depth + 1))
+                    .collect::<Result<_, _>>()?;
+                Value::List(list)
+            }
+            COMPOUND => {
+                let mut compound = Compound::new();
+                loop {
+                    let id = self.next::<u8>()?;
+                    if id == END {
+                        break;
+                    }
+                    let name = self.string()?;
+                    compound.insert(name, self.payload(id, depth + 1)?);
+                }
+                Value::Compound(compound)
+            }
+            id => return Err(de::Error::custom(format!("Unknown NBT tag {id}"))),
+        };
+        Ok(value)
+    }
+}
+
+impl<'de> Deserialize<'de> for NetworkNbt {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct Visitor;
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = NetworkNbt;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a NBT tag without a name")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut reader = Reader(&mut seq);
+                let id = reader.next::<u8>()?;
+                reader.payload(id, 0).map(NetworkNbt)
+            }
+        }
+
+        deserializer.deserialize_seq(Visitor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use fastnbt::{IntArray, Value};
+    use pumpkin_core::nbt::Compound;
+    use serde::{Deserialize, Serialize};
+
+    use crate::bytebuf::{deserializer::Deserializer, serializer::Serializer, ByteBuffer};
+
+    use super::NetworkNbt;
+
+    #[test]
+    fn round_trip() {
+        let nbt = NetworkNbt(Value::Compound(Compound::from([
+            ("name".to_string(), Value::String("Steve".to_string())),
+            (
+                "pos".to_string(),
+                Value::IntArray(IntArray::new(vec![1, -2, 3])),
+            ),
+            (
+                "list".to_string(),
+                Value::List(vec![Value::Double(0.5), Value::Double(-1.0)]),
+            ),
+            ("empty".to_string(), Value::List(Vec::new())),
+        ])));
+        let mut serializer = Serializer::new(ByteBuffer::empty());
+        nbt.serialize(&mut serializer).unwrap();
+        let mut serialized: ByteBuffer = serializer.into();
+        assert_eq!(
+            NetworkNbt::deserialize(Deserializer::new(&mut serialized)).unwrap(),
+            nbt
+        );
+    }
+}
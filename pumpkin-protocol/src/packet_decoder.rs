@@ -7,7 +7,11 @@ use std::io::Write;
 use bytes::BufMut;
 use flate2::write::ZlibDecoder;
 
-use crate::{bytebuf::ByteBuffer, RawPacket, VarInt, VarIntDecodeError, MAX_PACKET_SIZE};
+use crate::{
+    bytebuf::ByteBuffer,
+    capture::{self, Direction, SharedCapture},
+    RawPacket, VarInt, VarIntDecodeError, MAX_PACKET_SIZE,
+};
 
 type Cipher = cfb8::Decryptor<aes::Aes128>;
 
@@ -18,8 +22,12 @@ type Cipher = cfb8::Decryptor<aes::Aes128>;
 pub struct PacketDecoder {
     buf: BytesMut,
     decompress_buf: BytesMut,
-    compression: bool,
+    /// The compression threshold, if compression is enabled
+    compression: Option<u32>,
+    /// A lower limit than [`MAX_PACKET_SIZE`], e.g. before the client is playing
+    max_packet_size: Option<i32>,
     cipher: Option<Cipher>,
+    capture: Option<SharedCapture>,
 }
 
 impl PacketDecoder {
@@ -32,7 +40,10 @@ impl PacketDecoder {
             Err(VarIntDecodeError::TooLarge) => Err(PacketDecodeError::MalformedLength)?,
         };
 
-        if !(0..=MAX_PACKET_SIZE).contains(&packet_len) {
+        let max_packet_size = self
+            .max_packet_size
+            .map_or(MAX_PACKET_SIZE, |size| size.min(MAX_PACKET_SIZE));
+        if !(0..=max_packet_size).contains(&packet_len) {
             Err(PacketDecodeError::OutOfBounds)?
         }
 
@@ -44,19 +55,23 @@ impl PacketDecoder {
         let packet_len_len = VarInt(packet_len).written_size();
 
         let mut data;
-        if self.compression {
+        if let Some(threshold) = self.compression {
             r = &r[..packet_len as usize];
 
             let data_len = VarInt::decode(&mut r)
                 .map_err(|_| PacketDecodeError::TooLong)?
                 .0;
 
-            if !(0..=MAX_PACKET_SIZE).contains(&data_len) {
+            // the uncompressed size is limited as well, so small packets can't inflate into huge ones
+            if !(0..=max_packet_size).contains(&data_len) {
                 Err(PacketDecodeError::OutOfBounds)?
             }
 
             // Is this packet compressed?
             if data_len > 0 {
+                if (data_len as u32) < threshold {
+                    Err(PacketDecodeError::BelowThreshold(data_len, threshold))?
+                }
                 debug_assert!(self.decompress_buf.is_empty());
 
                 self.decompress_buf.put_bytes(0, data_len as usize);
@@ -64,9 +79,22 @@ impl PacketDecoder {
                 // TODO: use libdeflater or zune-inflate?
                 let mut z = ZlibDecoder::new(&mut self.decompress_buf[..]);
 
-                z.write_all(r)
-                    .map_err(|e| PacketDecodeError::FailedWrite(e.to_string()))?;
-                z.finish().map_err(|_| PacketDecodeError::FailedFinish)?;
+                // writing more than the announced length fails, as the buffer is full
+                let result = z
+                    .write_all(r)
+                    .map_err(|e| PacketDecodeError::FailedWrite(e.to_string()))
+                    .and_then(|()| z.finish().map_err(|_| PacketDecodeError::FailedFinish))
+                    .and_then(|remaining| {
+                        if remaining.is_empty() {
+                            Ok(())
+                        } else {
+                            Err(PacketDecodeError::WrongDecompressedLength)
+                        }
+                    });
+                if let Err(error) = result {
+                    self.decompress_buf.clear();
+                    return Err(error);
+                }
 
                 let total_packet_len = VarInt(packet_len).written_size() + packet_len as usize;
 
@@ -88,6 +116,12 @@ impl PacketDecoder {
             data = self.buf.split_to(packet_len as usize);
         }
 
+        capture::record(&mut self.capture, Direction::Serverbound, &data);
+        if self.buf.is_empty() {
+            // everything which was read is decoded
+            capture::flush(&mut self.capture);
+        }
+
         r = &data[..];
         let packet_id = VarInt::decode(&mut r).map_err(|_| PacketDecodeError::DecodeID)?;
 
@@ -116,9 +150,19 @@ impl PacketDecoder {
         }
     }
 
-    /// Sets ZLib Deompression
-    pub fn set_compression(&mut self, compression: bool) {
-        self.compression = compression;
+    /// Sets ZLib Deompression, compressed packets smaller than the threshold are rejected like vanilla does
+    pub fn set_compression(&mut self, threshold: Option<u32>) {
+        self.compression = threshold;
+    }
+
+    /// Rejects packets larger than the size, None only rejects ones above [`MAX_PACKET_SIZE`]
+    pub fn set_max_packet_size(&mut self, size: Option<i32>) {
+        self.max_packet_size = size;
+    }
+
+    /// Records every packet after it is decompressed, see [`capture`](crate::capture)
+    pub fn set_capture(&mut self, capture: Option<SharedCapture>) {
+        self.capture = capture;
     }
 
     fn decrypt_bytes(cipher: &mut Cipher, bytes: &mut [u8]) {
@@ -174,4 +218,101 @@ pub enum PacketDecodeError {
     OutOfBounds,
     #[error("malformed packet length VarInt")]
     MalformedLength,
+    #[error("badly compressed packet, size of {0} is below the threshold of {1}")]
+    BelowThreshold(i32, u32),
+    #[error("decompressed packet does not match the announced length")]
+    WrongDecompressedLength,
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_config::compression::CompressionInfo;
+
+    use crate::{
+        bytebuf::{packet_id::Packet, ByteBuffer},
+        packet_encoder::PacketEncoder,
+        ClientPacket,
+    };
+
+    use super::{PacketDecodeError, PacketDecoder};
+
+    struct Payload(Vec<u8>);
+
+    impl Packet for Payload {
+        const PACKET_ID: i32 = 0x42;
+    }
+
+    impl ClientPacket for Payload {
+        fn write(&self, bytebuf: &mut ByteBuffer) {
+            bytebuf.put_slice(&self.0);
+        }
+    }
+
+    fn encode(packet: &Payload, threshold: u32, adaptive: bool) -> Vec<u8> {
+        let mut encoder = PacketEncoder::default();
+        encoder.set_compression(Some(CompressionInfo {
+            threshold,
+            level: 6,
+            adaptive,
+        }));
+        encoder.append_packet(packet).unwrap();
+        encoder.take().to_vec()
+    }
+
+    #[test]
+    fn adaptive_skips_incompressible() {
+        // noise from a linear congruential generator does not compress
+        let mut state: u32 = 1;
+        let data: Vec<u8> = (0..512)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (state >> 16) as u8
+            })
+            .collect();
+        let packet = Payload(data.clone());
+
+        let adaptive = encode(&packet, 0, true);
+        let compressed = encode(&packet, 0, false);
+        assert!(adaptive.len() < compressed.len());
+
+        for bytes in [adaptive, compressed] {
+            let mut decoder = PacketDecoder::default();
+            decoder.set_compression(Some(0));
+            decoder.queue_slice(&bytes);
+            let mut decoded = decoder.decode().unwrap().unwrap();
+            assert_eq!(decoded.id.0, Payload::PACKET_ID);
+            assert_eq!(decoded.bytebuf.buf().to_vec(), data);
+        }
+    }
+
+    #[test]
+    fn rejects_compressed_below_threshold() {
+        let bytes = encode(&Payload(vec![0; 16]), 0, false);
+
+        let mut decoder = PacketDecoder::default();
+        decoder.set_compression(Some(256));
+        decoder.queue_slice(&bytes);
+        assert!(matches!(
+            decoder.decode(),
+            Err(PacketDecodeError::BelowThreshold(17, 256))
+        ));
+    }
+
+    #[test]
+    fn rejects_oversized_packets() {
+        let mut encoder = PacketEncoder::default();
+        encoder.append_packet(&Payload(vec![0; 64])).unwrap();
+        let bytes = encoder.take().to_vec();
+
+        let mut decoder = PacketDecoder::default();
+        decoder.set_max_packet_size(Some(32));
+        decoder.queue_slice(&bytes);
+        assert!(matches!(
+            decoder.decode(),
+            Err(PacketDecodeError::OutOfBounds)
+        ));
+
+        decoder.set_max_packet_size(None);
+        assert!(decoder.decode().unwrap().is_some());
+    }
 }
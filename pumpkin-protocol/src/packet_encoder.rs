@@ -1,7 +1,7 @@
 use std::io::Write;
 
 use aes::cipher::{generic_array::GenericArray, BlockEncryptMut, BlockSizeUser, KeyIvInit};
-use bytes::{BufMut, BytesMut};
+use bytes::{BufMut, Bytes, BytesMut};
 use pumpkin_config::compression::CompressionInfo;
 use thiserror::Error;
 
@@ -10,7 +10,11 @@ use std::io::Read;
 use flate2::bufread::ZlibEncoder;
 use flate2::Compression;
 
-use crate::{bytebuf::ByteBuffer, ClientPacket, VarInt, MAX_PACKET_SIZE};
+use crate::{
+    bytebuf::ByteBuffer,
+    capture::{self, Direction, SharedCapture},
+    ClientPacket, VarInt, MAX_PACKET_SIZE,
+};
 
 type Cipher = cfb8::Encryptor<aes::Aes128>;
 
@@ -23,6 +27,7 @@ pub struct PacketEncoder {
     compress_buf: Vec<u8>,
     compression: Option<CompressionInfo>,
     cipher: Option<Cipher>,
+    capture: Option<SharedCapture>,
 }
 
 impl PacketEncoder {
@@ -40,7 +45,24 @@ impl PacketEncoder {
             .write(packet_buf.buf())
             .map_err(|_| PacketEncodeError::EncodeFailedWrite)?;
 
+        self.frame_packet(start_len)
+    }
+
+    /// Appends a packet which was already serialized, only compressing and framing it.
+    pub fn append_prepared(&mut self, packet: &PreparedPacket) -> Result<(), PacketEncodeError> {
+        let start_len = self.buf.len();
+        self.buf.extend_from_slice(&packet.data);
+        self.frame_packet(start_len)
+    }
+
+    /// Prefixes the packet data written since `start_len` with its length, compressing it if needed.
+    fn frame_packet(&mut self, start_len: usize) -> Result<(), PacketEncodeError> {
         let data_len = self.buf.len() - start_len;
+        capture::record(
+            &mut self.capture,
+            Direction::Clientbound,
+            &self.buf[start_len..],
+        );
 
         if let Some(compression) = &self.compression {
             if data_len > compression.threshold as usize {
@@ -51,14 +73,19 @@ impl PacketEncoder {
 
                 let data_len_size = VarInt(data_len as i32).written_size();
 
-                let packet_len = data_len_size + z.read_to_end(&mut self.compress_buf).unwrap();
+                let compressed_len = z.read_to_end(&mut self.compress_buf).unwrap();
+                drop(z);
+
+                if compression.adaptive && compressed_len >= data_len {
+                    return self.frame_uncompressed(start_len, data_len);
+                }
+
+                let packet_len = data_len_size + compressed_len;
 
                 if packet_len >= MAX_PACKET_SIZE as usize {
                     Err(PacketEncodeError::TooLong)?
                 }
 
-                drop(z);
-
                 self.buf.truncate(start_len);
 
                 let mut writer = (&mut self.buf).writer();
@@ -71,30 +98,7 @@ impl PacketEncoder {
                     .map_err(|_| PacketEncodeError::EncodeData)?;
                 self.buf.extend_from_slice(&self.compress_buf);
             } else {
-                let data_len_size = 1;
-                let packet_len = data_len_size + data_len;
-
-                if packet_len >= MAX_PACKET_SIZE as usize {
-                    Err(PacketEncodeError::TooLong)?
-                }
-
-                let packet_len_size = VarInt(packet_len as i32).written_size();
-
-                let data_prefix_len = packet_len_size + data_len_size;
-
-                self.buf.put_bytes(0, data_prefix_len);
-                self.buf
-                    .copy_within(start_len..start_len + data_len, start_len + data_prefix_len);
-
-                let mut front = &mut self.buf[start_len..];
-
-                VarInt(packet_len as i32)
-                    .encode(&mut front)
-                    .map_err(|_| PacketEncodeError::EncodeLength)?;
-                // Zero for no compression on this packet.
-                VarInt(0)
-                    .encode(front)
-                    .map_err(|_| PacketEncodeError::EncodeData)?;
+                self.frame_uncompressed(start_len, data_len)?;
             }
 
             return Ok(());
@@ -119,6 +123,39 @@ impl PacketEncoder {
         Ok(())
     }
 
+    /// Frames packet data as not compressed, which is marked by a data length of zero.
+    fn frame_uncompressed(
+        &mut self,
+        start_len: usize,
+        data_len: usize,
+    ) -> Result<(), PacketEncodeError> {
+        let data_len_size = 1;
+        let packet_len = data_len_size + data_len;
+
+        if packet_len >= MAX_PACKET_SIZE as usize {
+            Err(PacketEncodeError::TooLong)?
+        }
+
+        let packet_len_size = VarInt(packet_len as i32).written_size();
+
+        let data_prefix_len = packet_len_size + data_len_size;
+
+        self.buf.put_bytes(0, data_prefix_len);
+        self.buf
+            .copy_within(start_len..start_len + data_len, start_len + data_prefix_len);
+
+        let mut front = &mut self.buf[start_len..];
+
+        VarInt(packet_len as i32)
+            .encode(&mut front)
+            .map_err(|_| PacketEncodeError::EncodeLength)?;
+        // Zero for no compression on this packet.
+        VarInt(0)
+            .encode(front)
+            .map_err(|_| PacketEncodeError::EncodeData)?;
+        Ok(())
+    }
+
     pub fn set_encryption(&mut self, key: Option<&[u8; 16]>) {
         if let Some(key) = key {
             assert!(self.cipher.is_none(), "encryption is already enabled");
@@ -136,7 +173,13 @@ impl PacketEncoder {
         self.compression = compression;
     }
 
+    /// Records every packet before it is compressed, see [`capture`](crate::capture)
+    pub fn set_capture(&mut self, capture: Option<SharedCapture>) {
+        self.capture = capture;
+    }
+
     pub fn take(&mut self) -> BytesMut {
+        capture::flush(&mut self.capture);
         if let Some(cipher) = &mut self.cipher {
             for chunk in self.buf.chunks_mut(Cipher::block_size()) {
                 let gen_arr = GenericArray::from_mut_slice(chunk);
@@ -148,6 +191,52 @@ impl PacketEncoder {
     }
 }
 
+/// A packet serialized once, so the same packet can be sent to many clients
+/// without writing it again for each of them.
+#[derive(Clone)]
+pub struct PreparedPacket {
+    /// The packet id followed by the packet data
+    data: Bytes,
+}
+
+impl PreparedPacket {
+    pub fn new<P: ClientPacket>(packet: &P) -> Self {
+        let mut buf = ByteBuffer::empty();
+        buf.put_var_int(&VarInt(P::PACKET_ID));
+        packet.write(&mut buf);
+        Self {
+            data: buf.buf().split().freeze(),
+        }
+    }
+
+    /// Builds a packet from its id and already serialized data.
+    pub fn from_parts(id: i32, payload: &[u8]) -> Self {
+        let mut buf = ByteBuffer::empty();
+        buf.put_var_int(&VarInt(id));
+        buf.put_slice(payload);
+        Self {
+            data: buf.buf().split().freeze(),
+        }
+    }
+
+    /// Returns the packet id and the data following it.
+    pub fn parts(&self) -> (i32, &[u8]) {
+        let mut data = &self.data[..];
+        // always starts with an id we wrote
+        let id = VarInt::decode(&mut data).map_or(0, |id| id.0);
+        (id, data)
+    }
+
+    /// The size of the uncompressed packet id and data
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum PacketEncodeError {
     #[error("failed to encode packet ID")]
@@ -168,3 +257,37 @@ impl PacketEncodeError {
         !matches!(self, Self::EncodeData | Self::EncodeFailedWrite)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use pumpkin_config::compression::CompressionInfo;
+
+    use crate::client::play::CKeepAlive;
+
+    use super::{PacketEncoder, PreparedPacket};
+
+    #[test]
+    fn prepared_packet_matches_direct() {
+        let packet = CKeepAlive::new(42);
+        for compression in [
+            None,
+            Some(CompressionInfo {
+                threshold: 0,
+                level: 6,
+                adaptive: false,
+            }),
+        ] {
+            let mut direct = PacketEncoder::default();
+            direct.set_compression(compression.clone());
+            direct.append_packet(&packet).unwrap();
+
+            let mut prepared = PacketEncoder::default();
+            prepared.set_compression(compression);
+            prepared
+                .append_prepared(&PreparedPacket::new(&packet))
+                .unwrap();
+
+            assert_eq!(direct.take(), prepared.take());
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use pumpkin_config::ADVANCED_CONFIG;
 use pumpkin_macros::server_packet;
 
 use crate::{
@@ -5,19 +6,32 @@ use crate::{
     ConnectionState, ServerPacket, VarInt,
 };
 
+/// The longest server address vanilla accepts
+const MAX_ADDRESS_LEN: i32 = 255;
+/// The longest server address with the player info BungeeCord forwards in it, which may carry
+/// skin properties
+const MAX_FORWARDED_ADDRESS_LEN: i32 = i16::MAX as i32;
+
 #[server_packet("handshake:intention")]
 pub struct SHandShake {
     pub protocol_version: VarInt,
-    pub server_address: String, // 255
+    /// At most 255 characters, longer only when BungeeCord appends the forwarded player info
+    pub server_address: String,
     pub server_port: u16,
     pub next_state: ConnectionState,
 }
 
 impl ServerPacket for SHandShake {
     fn read(bytebuf: &mut ByteBuffer) -> Result<Self, DeserializerError> {
+        let max_address_len =
+            if ADVANCED_CONFIG.proxy.enabled && ADVANCED_CONFIG.proxy.bungeecord.enabled {
+                MAX_FORWARDED_ADDRESS_LEN
+            } else {
+                MAX_ADDRESS_LEN
+            };
         Ok(Self {
             protocol_version: bytebuf.get_var_int()?,
-            server_address: bytebuf.get_string_len(255)?,
+            server_address: bytebuf.get_string_len(max_address_len)?,
             server_port: bytebuf.get_u16()?,
             next_state: bytebuf.get_var_int()?.into(),
         })
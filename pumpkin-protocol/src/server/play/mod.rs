@@ -1,14 +1,20 @@
+mod s_chat_ack;
 mod s_chat_command;
 mod s_chat_message;
+mod s_chat_session_update;
+mod s_chunk_batch_received;
 mod s_click_container;
 mod s_client_command;
 mod s_client_information;
 mod s_client_tick_end;
 mod s_close_container;
 mod s_command_suggestion;
+mod s_configuration_acknowledged;
 mod s_confirm_teleport;
 mod s_interact;
 mod s_keep_alive;
+mod s_move_vehicle;
+mod s_paddle_boat;
 mod s_ping_request;
 mod s_player_abilities;
 mod s_player_action;
@@ -18,23 +24,32 @@ mod s_player_input;
 mod s_player_position;
 mod s_player_position_rotation;
 mod s_player_rotation;
+mod s_plugin_message;
+mod s_rename_item;
+mod s_set_beacon;
 mod s_set_creative_slot;
 mod s_set_held_item;
 mod s_swing_arm;
 mod s_use_item;
 mod s_use_item_on;
 
+pub use s_chat_ack::*;
 pub use s_chat_command::*;
 pub use s_chat_message::*;
+pub use s_chat_session_update::*;
+pub use s_chunk_batch_received::*;
 pub use s_click_container::*;
 pub use s_client_command::*;
 pub use s_client_information::*;
 pub use s_client_tick_end::*;
 pub use s_close_container::*;
 pub use s_command_suggestion::*;
+pub use s_configuration_acknowledged::*;
 pub use s_confirm_teleport::*;
 pub use s_interact::*;
 pub use s_keep_alive::*;
+pub use s_move_vehicle::*;
+pub use s_paddle_boat::*;
 pub use s_ping_request::*;
 pub use s_player_abilities::*;
 pub use s_player_action::*;
@@ -44,6 +59,9 @@ pub use s_player_input::*;
 pub use s_player_position::*;
 pub use s_player_position_rotation::*;
 pub use s_player_rotation::*;
+pub use s_plugin_message::*;
+pub use s_rename_item::*;
+pub use s_set_beacon::*;
 pub use s_set_creative_slot::*;
 pub use s_set_held_item::*;
 pub use s_swing_arm::*;
@@ -0,0 +1,12 @@
+use pumpkin_macros::server_packet;
+use serde::Deserialize;
+
+use crate::VarInt;
+
+/// Acknowledges the chat messages the client has seen, so the next message does not have to
+#[derive(Deserialize)]
+#[server_packet("play:chat_ack")]
+pub struct SChatAck {
+    /// How many messages were seen since the last acknowledgement
+    pub offset: VarInt,
+}
@@ -0,0 +1,44 @@
+use bytes::Bytes;
+use pumpkin_macros::server_packet;
+
+use crate::{
+    bytebuf::{ByteBuffer, DeserializerError},
+    ServerPacket,
+};
+
+/// The largest public key the client may send, in DER
+const MAX_KEY_LENGTH: usize = 512;
+/// Mojang signs the keys with a 4096 bit key
+const MAX_KEY_SIGNATURE_LENGTH: usize = 4096;
+
+/// The session the client signs its chat messages with
+#[server_packet("play:chat_session_update")]
+pub struct SChatSessionUpdate {
+    pub session_id: uuid::Uuid,
+    /// Milliseconds since the unix epoch
+    pub expires_at: i64,
+    pub public_key: Bytes,
+    /// Mojang's signature of the key
+    pub key_signature: Bytes,
+}
+
+fn get_prefixed_bytes(bytebuf: &mut ByteBuffer, max: usize) -> Result<Bytes, DeserializerError> {
+    let len = bytebuf.get_var_int()?.0;
+    if len < 0 || len as usize > max {
+        return Err(DeserializerError::Message(format!(
+            "Byte array of length {len} is longer than {max}"
+        )));
+    }
+    bytebuf.copy_to_bytes(len as usize)
+}
+
+impl ServerPacket for SChatSessionUpdate {
+    fn read(bytebuf: &mut ByteBuffer) -> Result<Self, DeserializerError> {
+        Ok(Self {
+            session_id: bytebuf.get_uuid()?,
+            expires_at: bytebuf.get_i64()?,
+            public_key: get_prefixed_bytes(bytebuf, MAX_KEY_LENGTH)?,
+            key_signature: get_prefixed_bytes(bytebuf, MAX_KEY_SIGNATURE_LENGTH)?,
+        })
+    }
+}
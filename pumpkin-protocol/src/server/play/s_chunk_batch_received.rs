@@ -0,0 +1,10 @@
+use pumpkin_macros::server_packet;
+use serde::Deserialize;
+
+/// Acknowledges a batch of chunks once the client received it
+#[derive(Deserialize)]
+#[server_packet("play:chunk_batch_received")]
+pub struct SChunkBatchReceived {
+    /// How many chunks per tick the client can take, measured from the batches it received
+    pub chunks_per_tick: f32,
+}
@@ -0,0 +1,5 @@
+use pumpkin_macros::server_packet;
+
+#[derive(serde::Deserialize)]
+#[server_packet("play:configuration_acknowledged")]
+pub struct SConfigurationAcknowledged {}
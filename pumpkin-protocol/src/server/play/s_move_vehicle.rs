@@ -0,0 +1,12 @@
+use pumpkin_macros::server_packet;
+
+/// Sent by the client while it controls the vehicle it is riding
+#[derive(serde::Deserialize)]
+#[server_packet("play:move_vehicle")]
+pub struct SMoveVehicle {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub yaw: f32,
+    pub pitch: f32,
+}
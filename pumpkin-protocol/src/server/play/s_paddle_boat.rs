@@ -0,0 +1,8 @@
+use pumpkin_macros::server_packet;
+
+#[derive(serde::Deserialize)]
+#[server_packet("play:paddle_boat")]
+pub struct SPaddleBoat {
+    pub left_paddle_turning: bool,
+    pub right_paddle_turning: bool,
+}
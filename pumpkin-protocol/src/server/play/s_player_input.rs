@@ -3,6 +3,20 @@ use pumpkin_macros::server_packet;
 #[derive(serde::Deserialize)]
 #[server_packet("play:player_input")]
 pub struct SPlayerInput {
-    // Yep exactly how it looks like
-    _input: i8,
+    /// Bit flags of the movement keys the player is holding
+    pub input: u8,
+}
+
+impl SPlayerInput {
+    pub const FORWARD: u8 = 0x01;
+    pub const BACKWARD: u8 = 0x02;
+    pub const LEFT: u8 = 0x04;
+    pub const RIGHT: u8 = 0x08;
+    pub const JUMP: u8 = 0x10;
+    pub const SNEAK: u8 = 0x20;
+    pub const SPRINT: u8 = 0x40;
+
+    pub const fn has(&self, flag: u8) -> bool {
+        self.input & flag != 0
+    }
 }
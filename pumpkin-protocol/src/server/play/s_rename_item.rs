@@ -0,0 +1,9 @@
+use pumpkin_macros::server_packet;
+use serde::Deserialize;
+
+/// Sent while the player types in the text field of an anvil
+#[derive(Deserialize)]
+#[server_packet("play:rename_item")]
+pub struct SRenameItem {
+    pub item_name: String,
+}
@@ -0,0 +1,23 @@
+use pumpkin_macros::server_packet;
+
+use crate::{
+    bytebuf::{ByteBuffer, DeserializerError},
+    ServerPacket, VarInt,
+};
+
+/// Sent when the player confirms the effects in a beacon's menu
+#[server_packet("play:set_beacon")]
+pub struct SSetBeacon {
+    /// The ids of the effects in `minecraft:mob_effect`
+    pub primary_effect: Option<VarInt>,
+    pub secondary_effect: Option<VarInt>,
+}
+
+impl ServerPacket for SSetBeacon {
+    fn read(bytebuf: &mut ByteBuffer) -> Result<Self, DeserializerError> {
+        Ok(Self {
+            primary_effect: bytebuf.get_option(ByteBuffer::get_var_int)?,
+            secondary_effect: bytebuf.get_option(ByteBuffer::get_var_int)?,
+        })
+    }
+}
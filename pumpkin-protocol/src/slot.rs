@@ -1,5 +1,18 @@
-use crate::VarInt;
-use pumpkin_world::item::ItemStack;
+use std::collections::BTreeMap;
+
+use crate::{nbt::NetworkNbt, VarInt};
+use fastnbt::Value;
+use pumpkin_core::nbt::Compound;
+use pumpkin_world::block::block_registry::{get_block, get_block_by_id};
+use pumpkin_world::item::{
+    component::{
+        network_id, AdventurePredicate, AttributeModifier, BlockPredicate, BlockSet,
+        ComponentPatch, DataComponent, Enchantments, Food, PotionContents, PotionEffect,
+        ATTRIBUTES, COMPONENTS, ENCHANTMENTS, MOB_EFFECTS, MODIFIER_OPERATIONS, POTIONS, RARITIES,
+        SLOT_GROUPS,
+    },
+    ItemStack,
+};
 use serde::ser::SerializeSeq;
 use serde::{
     de::{self, SeqAccess},
@@ -10,10 +23,464 @@ use serde::{
 pub struct Slot {
     item_count: VarInt,
     item_id: Option<VarInt>,
-    num_components_to_add: Option<VarInt>,
-    num_components_to_remove: Option<VarInt>,
-    components_to_add: Option<Vec<(VarInt, ())>>, // The second type depends on the varint
-    components_to_remove: Option<Vec<VarInt>>,
+    components: ComponentPatch,
+}
+
+/// Text is saved as JSON but sent as NBT
+fn text_to_nbt(text: &str) -> Value {
+    match serde_json::from_str(text) {
+        Ok(json) => json_to_nbt(&json),
+        Err(_) => Value::String(text.to_string()),
+    }
+}
+
+fn json_to_nbt(json: &serde_json::Value) -> Value {
+    match json {
+        serde_json::Value::Null => Value::String(String::new()),
+        serde_json::Value::Bool(value) => Value::Byte(i8::from(*value)),
+        serde_json::Value::Number(number) => match number.as_i64() {
+            Some(number) => i32::try_from(number).map_or(Value::Long(number), Value::Int),
+            None => Value::Double(number.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::String(value) => Value::String(value.clone()),
+        serde_json::Value::Array(list) => {
+            let mut list: Vec<_> = list.iter().map(json_to_nbt).collect();
+            // NBT lists only hold one type, text which isn't a compound is the same as {text:...}
+            if list.iter().any(|value| matches!(value, Value::Compound(_))) {
+                for value in &mut list {
+                    if !matches!(value, Value::Compound(_)) {
+                        let text = match std::mem::replace(value, Value::Byte(0)) {
+                            Value::String(text) => text,
+                            other => serde_json::to_string(&nbt_to_json(&other)).unwrap(),
+                        };
+                        *value = Value::Compound(Compound::from([(
+                            "text".to_string(),
+                            Value::String(text),
+                        )]));
+                    }
+                }
+            }
+            Value::List(list)
+        }
+        serde_json::Value::Object(object) => Value::Compound(
+            object
+                .iter()
+                .map(|(key, value)| (key.clone(), json_to_nbt(value)))
+                .collect(),
+        ),
+    }
+}
+
+fn nbt_to_json(nbt: &Value) -> serde_json::Value {
+    match nbt {
+        // bytes in text are always booleans like bold
+        Value::Byte(value) => serde_json::Value::Bool(*value != 0),
+        Value::Short(value) => (*value).into(),
+        Value::Int(value) => (*value).into(),
+        Value::Long(value) => (*value).into(),
+        Value::Float(value) => (*value).into(),
+        Value::Double(value) => (*value).into(),
+        Value::String(value) => value.clone().into(),
+        Value::ByteArray(array) => array.iter().copied().collect(),
+        Value::IntArray(array) => array.iter().copied().collect(),
+        Value::LongArray(array) => array.iter().copied().collect(),
+        Value::List(list) => list.iter().map(nbt_to_json).collect(),
+        Value::Compound(compound) => serde_json::Value::Object(
+            compound
+                .iter()
+                .map(|(key, value)| (key.clone(), nbt_to_json(value)))
+                .collect(),
+        ),
+    }
+}
+
+fn list_id(list: &[&str], name: &str) -> VarInt {
+    VarInt(network_id(list, name).unwrap_or_default())
+}
+
+fn write_enchantments<S: SerializeSeq>(
+    s: &mut S,
+    enchantments: &Enchantments,
+) -> Result<(), S::Error> {
+    // enchantments from datapacks aren't synced yet
+    let levels: Vec<_> = enchantments
+        .levels
+        .iter()
+        .filter_map(|(name, level)| Some((network_id(ENCHANTMENTS, name)?, *level)))
+        .collect();
+    s.serialize_element(&VarInt(levels.len() as i32))?;
+    for (id, level) in levels {
+        s.serialize_element(&VarInt(id))?;
+        s.serialize_element(&VarInt(level))?;
+    }
+    s.serialize_element(&enchantments.show_in_tooltip)
+}
+
+fn write_adventure_predicate<S: SerializeSeq>(
+    s: &mut S,
+    predicate: &AdventurePredicate,
+) -> Result<(), S::Error> {
+    s.serialize_element(&VarInt(predicate.predicates.len() as i32))?;
+    for predicate in &predicate.predicates {
+        s.serialize_element(&predicate.blocks.is_some())?;
+        match &predicate.blocks {
+            Some(BlockSet::Tag(tag)) => {
+                s.serialize_element(&VarInt(0))?;
+                s.serialize_element(tag)?;
+            }
+            Some(BlockSet::Blocks(names)) => {
+                let ids: Vec<_> = names
+                    .iter()
+                    .filter_map(|name| get_block(name))
+                    .map(|block| VarInt(i32::from(block.id)))
+                    .collect();
+                s.serialize_element(&VarInt(ids.len() as i32 + 1))?;
+                ids.iter().try_for_each(|id| s.serialize_element(id))?;
+            }
+            None => {}
+        }
+        s.serialize_element(&!predicate.state.is_empty())?;
+        if !predicate.state.is_empty() {
+            s.serialize_element(&VarInt(predicate.state.len() as i32))?;
+            for (name, value) in &predicate.state {
+                s.serialize_element(name)?;
+                // only exact values are kept
+                s.serialize_element(&true)?;
+                s.serialize_element(value)?;
+            }
+        }
+        // no NBT
+        s.serialize_element(&false)?;
+    }
+    s.serialize_element(&predicate.show_in_tooltip)
+}
+
+fn write_potion_contents<S: SerializeSeq>(
+    s: &mut S,
+    contents: &PotionContents,
+) -> Result<(), S::Error> {
+    // potions and effects from datapacks aren't synced yet
+    let potion = contents
+        .potion
+        .as_ref()
+        .and_then(|potion| network_id(POTIONS, potion));
+    s.serialize_element(&potion.is_some())?;
+    if let Some(potion) = potion {
+        s.serialize_element(&VarInt(potion))?;
+    }
+    s.serialize_element(&contents.custom_color.is_some())?;
+    if let Some(color) = contents.custom_color {
+        s.serialize_element(&color)?;
+    }
+    let effects: Vec<_> = contents
+        .custom_effects
+        .iter()
+        .filter_map(|effect| Some((network_id(MOB_EFFECTS, &effect.effect)?, effect)))
+        .collect();
+    s.serialize_element(&VarInt(effects.len() as i32))?;
+    for (id, effect) in effects {
+        s.serialize_element(&VarInt(id))?;
+        s.serialize_element(&VarInt(effect.amplifier))?;
+        s.serialize_element(&VarInt(effect.duration))?;
+        s.serialize_element(&effect.ambient)?;
+        s.serialize_element(&effect.show_particles)?;
+        s.serialize_element(&effect.show_icon)?;
+        // no hidden weaker effect
+        s.serialize_element(&false)?;
+    }
+    s.serialize_element(&contents.custom_name.is_some())?;
+    if let Some(name) = &contents.custom_name {
+        s.serialize_element(name)?;
+    }
+    Ok(())
+}
+
+fn write_component<S: SerializeSeq>(s: &mut S, component: &DataComponent) -> Result<(), S::Error> {
+    match component {
+        DataComponent::CustomData(data) => {
+            s.serialize_element(&NetworkNbt(Value::Compound(data.clone())))
+        }
+        DataComponent::MaxStackSize(value)
+        | DataComponent::MaxDamage(value)
+        | DataComponent::Damage(value)
+        | DataComponent::CustomModelData(value)
+        | DataComponent::RepairCost(value) => s.serialize_element(&VarInt(*value)),
+        DataComponent::Unbreakable { show_in_tooltip } => s.serialize_element(show_in_tooltip),
+        DataComponent::CustomName(text) | DataComponent::ItemName(text) => {
+            s.serialize_element(&NetworkNbt(text_to_nbt(text)))
+        }
+        DataComponent::ItemModel(model) => s.serialize_element(model),
+        DataComponent::Lore(lines) => {
+            s.serialize_element(&VarInt(lines.len() as i32))?;
+            lines
+                .iter()
+                .try_for_each(|line| s.serialize_element(&NetworkNbt(text_to_nbt(line))))
+        }
+        DataComponent::Rarity(rarity) => s.serialize_element(&VarInt(
+            RARITIES
+                .iter()
+                .position(|candidate| candidate == rarity)
+                .unwrap_or_default() as i32,
+        )),
+        DataComponent::Enchantments(enchantments)
+        | DataComponent::StoredEnchantments(enchantments) => write_enchantments(s, enchantments),
+        DataComponent::AttributeModifiers {
+            modifiers,
+            show_in_tooltip,
+        } => {
+            let modifiers: Vec<_> = modifiers
+                .iter()
+                .filter_map(|modifier| {
+                    Some((network_id(ATTRIBUTES, &modifier.attribute)?, modifier))
+                })
+                .collect();
+            s.serialize_element(&VarInt(modifiers.len() as i32))?;
+            for (attribute, modifier) in modifiers {
+                s.serialize_element(&VarInt(attribute))?;
+                s.serialize_element(&modifier.id)?;
+                s.serialize_element(&modifier.amount)?;
+                s.serialize_element(&list_id(&MODIFIER_OPERATIONS, &modifier.operation))?;
+                s.serialize_element(&list_id(&SLOT_GROUPS, &modifier.slot))?;
+            }
+            s.serialize_element(show_in_tooltip)
+        }
+        DataComponent::HideAdditionalTooltip | DataComponent::HideTooltip => Ok(()),
+        DataComponent::EnchantmentGlintOverride(glint) => s.serialize_element(glint),
+        DataComponent::Food(food) => {
+            s.serialize_element(&VarInt(food.nutrition))?;
+            s.serialize_element(&food.saturation)?;
+            s.serialize_element(&food.can_always_eat)
+        }
+        DataComponent::DyedColor {
+            rgb,
+            show_in_tooltip,
+        } => {
+            s.serialize_element(rgb)?;
+            s.serialize_element(show_in_tooltip)
+        }
+        DataComponent::CanPlaceOn(predicate) | DataComponent::CanBreak(predicate) => {
+            write_adventure_predicate(s, predicate)
+        }
+        DataComponent::PotionContents(contents) => write_potion_contents(s, contents),
+        DataComponent::Other(_) => unreachable!("Unknown components are not sent"),
+    }
+}
+
+struct Reader<'a, A>(&'a mut A);
+
+impl<'de, A: SeqAccess<'de>> Reader<'_, A> {
+    fn next<T: Deserialize<'de>>(&mut self) -> Result<T, A::Error> {
+        self.0
+            .next_element()?
+            .ok_or(de::Error::custom("Slot ended early"))
+    }
+
+    fn var_int(&mut self) -> Result<i32, A::Error> {
+        Ok(self.next::<VarInt>()?.0)
+    }
+
+    fn length(&mut self) -> Result<usize, A::Error> {
+        usize::try_from(self.var_int()?).map_err(|_| de::Error::custom("Negative length"))
+    }
+
+    fn text(&mut self) -> Result<String, A::Error> {
+        let NetworkNbt(nbt) = self.next()?;
+        Ok(serde_json::to_string(&nbt_to_json(&nbt)).unwrap())
+    }
+
+    fn name(&mut self, list: &[&str]) -> Result<String, A::Error> {
+        let id = self.var_int()?;
+        usize::try_from(id)
+            .ok()
+            .and_then(|id| list.get(id))
+            .map(|name| (*name).to_string())
+            .ok_or_else(|| de::Error::custom(format!("Unknown id {id}")))
+    }
+
+    fn enchantments(&mut self) -> Result<Enchantments, A::Error> {
+        let length = self.length()?;
+        let levels = (0..length)
+            .map(|_| Ok((self.name(ENCHANTMENTS)?, self.var_int()?)))
+            .collect::<Result<_, _>>()?;
+        Ok(Enchantments {
+            levels,
+            show_in_tooltip: self.next()?,
+        })
+    }
+
+    fn block_predicate(&mut self) -> Result<BlockPredicate, A::Error> {
+        let blocks = if self.next()? {
+            Some(match self.length()? {
+                0 => BlockSet::Tag(self.next()?),
+                length => BlockSet::Blocks(
+                    (1..length)
+                        .map(|_| {
+                            let id = self.var_int()?;
+                            u16::try_from(id)
+                                .ok()
+                                .and_then(get_block_by_id)
+                                .map(|block| block.name.clone())
+                                .ok_or_else(|| de::Error::custom(format!("Unknown block {id}")))
+                        })
+                        .collect::<Result<_, _>>()?,
+                ),
+            })
+        } else {
+            None
+        };
+        let mut state = BTreeMap::new();
+        if self.next()? {
+            for _ in 0..self.length()? {
+                let name: String = self.next()?;
+                if self.next()? {
+                    state.insert(name, self.next()?);
+                } else {
+                    // ranges aren't supported, they are read and dropped
+                    for _ in 0..2 {
+                        if self.next()? {
+                            self.next::<String>()?;
+                        }
+                    }
+                }
+            }
+        }
+        if self.next()? {
+            self.next::<NetworkNbt>()?;
+        }
+        Ok(BlockPredicate { blocks, state })
+    }
+
+    fn adventure_predicate(&mut self) -> Result<AdventurePredicate, A::Error> {
+        let length = self.length()?;
+        Ok(AdventurePredicate {
+            predicates: (0..length)
+                .map(|_| self.block_predicate())
+                .collect::<Result<_, _>>()?,
+            show_in_tooltip: self.next()?,
+        })
+    }
+
+    fn optional<T>(
+        &mut self,
+        read: impl FnOnce(&mut Self) -> Result<T, A::Error>,
+    ) -> Result<Option<T>, A::Error> {
+        if self.next()? {
+            Ok(Some(read(self)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn potion_effect(&mut self, effect: String) -> Result<PotionEffect, A::Error> {
+        let effect = PotionEffect {
+            effect,
+            amplifier: self.var_int()?,
+            duration: self.var_int()?,
+            ambient: self.next()?,
+            show_particles: self.next()?,
+            show_icon: self.next()?,
+        };
+        // the weaker effect which is hidden behind this one isn't kept
+        if self.next()? {
+            self.potion_effect(String::new())?;
+        }
+        Ok(effect)
+    }
+
+    fn potion_contents(&mut self) -> Result<PotionContents, A::Error> {
+        let potion = self.optional(|reader| reader.name(POTIONS))?;
+        let custom_color = self.optional(Self::next)?;
+        let length = self.length()?;
+        let custom_effects = (0..length)
+            .map(|_| {
+                let effect = self.name(MOB_EFFECTS)?;
+                self.potion_effect(effect)
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(PotionContents {
+            potion,
+            custom_color,
+            custom_effects,
+            custom_name: self.optional(Self::next)?,
+        })
+    }
+
+    fn component(&mut self, name: &str) -> Result<DataComponent, A::Error> {
+        let component = match name {
+            "minecraft:custom_data" => match self.next()? {
+                NetworkNbt(Value::Compound(data)) => DataComponent::CustomData(data),
+                _ => return Err(de::Error::custom("Custom data has to be a compound")),
+            },
+            "minecraft:max_stack_size" => DataComponent::MaxStackSize(self.var_int()?),
+            "minecraft:max_damage" => DataComponent::MaxDamage(self.var_int()?),
+            "minecraft:damage" => DataComponent::Damage(self.var_int()?),
+            "minecraft:unbreakable" => DataComponent::Unbreakable {
+                show_in_tooltip: self.next()?,
+            },
+            "minecraft:custom_name" => DataComponent::CustomName(self.text()?),
+            "minecraft:item_name" => DataComponent::ItemName(self.text()?),
+            "minecraft:item_model" => DataComponent::ItemModel(self.next()?),
+            "minecraft:lore" => {
+                let length = self.length()?;
+                DataComponent::Lore((0..length).map(|_| self.text()).collect::<Result<_, _>>()?)
+            }
+            "minecraft:rarity" => {
+                let id = self.length()?;
+                DataComponent::Rarity(
+                    *RARITIES
+                        .get(id)
+                        .ok_or(de::Error::custom("Unknown rarity"))?,
+                )
+            }
+            "minecraft:enchantments" => DataComponent::Enchantments(self.enchantments()?),
+            "minecraft:stored_enchantments" => {
+                DataComponent::StoredEnchantments(self.enchantments()?)
+            }
+            "minecraft:attribute_modifiers" => {
+                let length = self.length()?;
+                let modifiers = (0..length)
+                    .map(|_| {
+                        Ok(AttributeModifier {
+                            attribute: self.name(ATTRIBUTES)?,
+                            id: self.next()?,
+                            amount: self.next()?,
+                            operation: self.name(&MODIFIER_OPERATIONS)?,
+                            slot: self.name(&SLOT_GROUPS)?,
+                        })
+                    })
+                    .collect::<Result<_, _>>()?;
+                DataComponent::AttributeModifiers {
+                    modifiers,
+                    show_in_tooltip: self.next()?,
+                }
+            }
+            "minecraft:custom_model_data" => DataComponent::CustomModelData(self.var_int()?),
+            "minecraft:hide_additional_tooltip" => DataComponent::HideAdditionalTooltip,
+            "minecraft:hide_tooltip" => DataComponent::HideTooltip,
+            "minecraft:repair_cost" => DataComponent::RepairCost(self.var_int()?),
+            "minecraft:enchantment_glint_override" => {
+                DataComponent::EnchantmentGlintOverride(self.next()?)
+            }
+            "minecraft:food" => DataComponent::Food(Food {
+                nutrition: self.var_int()?,
+                saturation: self.next()?,
+                can_always_eat: self.next()?,
+            }),
+            "minecraft:dyed_color" => DataComponent::DyedColor {
+                rgb: self.next()?,
+                show_in_tooltip: self.next()?,
+            },
+            "minecraft:can_place_on" => DataComponent::CanPlaceOn(self.adventure_predicate()?),
+            "minecraft:can_break" => DataComponent::CanBreak(self.adventure_predicate()?),
+            "minecraft:potion_contents" => DataComponent::PotionContents(self.potion_contents()?),
+            name => {
+                return Err(de::Error::custom(format!(
+                    "The slot component {name} is currently unsupported"
+                )))
+            }
+        };
+        Ok(component)
+    }
 }
 
 impl<'de> Deserialize<'de> for Slot {
@@ -33,41 +500,29 @@ impl<'de> Deserialize<'de> for Slot {
             where
                 A: SeqAccess<'de>,
             {
-                let item_count = seq
-                    .next_element::<VarInt>()?
-                    .ok_or(de::Error::custom("Failed to decode VarInt"))?;
+                let mut reader = Reader(&mut seq);
+                let item_count = reader.next::<VarInt>()?;
                 if item_count.0 == 0 {
-                    return Ok(Slot {
-                        item_count: 0.into(),
-                        item_id: None,
-                        num_components_to_add: None,
-                        num_components_to_remove: None,
-                        components_to_add: None,
-                        components_to_remove: None,
-                    });
+                    return Ok(Slot::empty());
                 }
-                let item_id = seq
-                    .next_element::<VarInt>()?
-                    .ok_or(de::Error::custom("Failed to decode VarInt"))?;
-                let num_components_to_add = seq
-                    .next_element::<VarInt>()?
-                    .ok_or(de::Error::custom("Failed to decode VarInt"))?;
-                let num_components_to_remove = seq
-                    .next_element::<VarInt>()?
-                    .ok_or(de::Error::custom("Failed to decode VarInt"))?;
-                if num_components_to_add.0 != 0 || num_components_to_remove.0 != 0 {
-                    return Err(de::Error::custom(
-                        "Slot components are currently unsupported",
-                    ));
+                let item_id = reader.next::<VarInt>()?;
+                let num_components_to_add = reader.length()?;
+                let num_components_to_remove = reader.length()?;
+
+                let mut components = ComponentPatch::default();
+                for _ in 0..num_components_to_add {
+                    let name = reader.name(COMPONENTS)?;
+                    let component = reader.component(&name)?;
+                    components.set_named(&name, component);
+                }
+                for _ in 0..num_components_to_remove {
+                    components.remove(&reader.name(COMPONENTS)?);
                 }
 
                 Ok(Slot {
                     item_count,
                     item_id: Some(item_id),
-                    num_components_to_add: Some(num_components_to_add),
-                    num_components_to_remove: Some(num_components_to_remove),
-                    components_to_add: None,
-                    components_to_remove: None,
+                    components,
                 })
             }
         }
@@ -81,50 +536,41 @@ impl Serialize for Slot {
     where
         S: Serializer,
     {
-        if self.item_count == 0.into() {
+        let Some(item_id) = self
+            .item_id
+            .as_ref()
+            .filter(|_| self.item_count != 0.into())
+        else {
             let mut s = serializer.serialize_seq(Some(1))?;
-            s.serialize_element(&self.item_count)?;
-            s.end()
-        } else {
-            match (&self.num_components_to_add, &self.num_components_to_remove) {
-                (Some(to_add), Some(to_remove)) => {
-                    let mut s = serializer.serialize_seq(Some(6))?;
-                    s.serialize_element(&self.item_count)?;
-                    s.serialize_element(self.item_id.as_ref().unwrap())?;
-                    s.serialize_element(to_add)?;
-                    s.serialize_element(to_remove)?;
-                    s.serialize_element(self.components_to_add.as_ref().unwrap())?;
-                    s.serialize_element(self.components_to_remove.as_ref().unwrap())?;
-                    s.end()
-                }
-                (None, Some(to_remove)) => {
-                    let mut s = serializer.serialize_seq(Some(5))?;
-                    s.serialize_element(&self.item_count)?;
-                    s.serialize_element(self.item_id.as_ref().unwrap())?;
-                    s.serialize_element(&VarInt(0))?;
-                    s.serialize_element(to_remove)?;
-                    s.serialize_element(self.components_to_remove.as_ref().unwrap())?;
-                    s.end()
-                }
-                (Some(to_add), None) => {
-                    let mut s = serializer.serialize_seq(Some(5))?;
-                    s.serialize_element(&self.item_count)?;
-                    s.serialize_element(self.item_id.as_ref().unwrap())?;
-                    s.serialize_element(to_add)?;
-                    s.serialize_element(&VarInt(0))?;
-                    s.serialize_element(self.components_to_add.as_ref().unwrap())?;
-                    s.end()
-                }
-                (None, None) => {
-                    let mut s = serializer.serialize_seq(Some(4))?;
-                    s.serialize_element(&self.item_count)?;
-                    s.serialize_element(&self.item_id.as_ref().unwrap())?;
-                    s.serialize_element(&VarInt(0))?;
-                    s.serialize_element(&VarInt(0))?;
-                    s.end()
-                }
+            s.serialize_element(&VarInt(0))?;
+            return s.end();
+        };
+        // components Pumpkin doesn't know can't be sent
+        let mut added = Vec::new();
+        let mut removed = Vec::new();
+        for (name, component) in self.components.iter() {
+            let Some(id) = network_id(COMPONENTS, name) else {
+                continue;
+            };
+            match component {
+                Some(DataComponent::Other(_)) => {}
+                Some(component) => added.push((id, component)),
+                None => removed.push(id),
             }
         }
+        let mut s = serializer.serialize_seq(None)?;
+        s.serialize_element(&self.item_count)?;
+        s.serialize_element(item_id)?;
+        s.serialize_element(&VarInt(added.len() as i32))?;
+        s.serialize_element(&VarInt(removed.len() as i32))?;
+        for (id, component) in added {
+            s.serialize_element(&VarInt(id))?;
+            write_component(&mut s, component)?;
+        }
+        for id in removed {
+            s.serialize_element(&VarInt(id))?;
+        }
+        s.end()
     }
 }
 
@@ -134,6 +580,7 @@ impl Slot {
         Some(ItemStack {
             item_id,
             item_count: self.item_count.0.try_into().unwrap(),
+            components: self.components,
         })
     }
 
@@ -141,10 +588,7 @@ impl Slot {
         Slot {
             item_count: VarInt(0),
             item_id: None,
-            num_components_to_add: None,
-            num_components_to_remove: None,
-            components_to_add: None,
-            components_to_remove: None,
+            components: ComponentPatch::new(),
         }
     }
 }
@@ -154,11 +598,7 @@ impl From<&ItemStack> for Slot {
         Slot {
             item_count: item.item_count.into(),
             item_id: Some(VarInt(item.item_id as i32)),
-            // TODO: add these
-            num_components_to_add: None,
-            num_components_to_remove: None,
-            components_to_add: None,
-            components_to_remove: None,
+            components: item.components.clone(),
         }
     }
 }
@@ -171,7 +611,119 @@ impl From<Option<&ItemStack>> for Slot {
 
 impl From<&Option<ItemStack>> for Slot {
     fn from(item: &Option<ItemStack>) -> Self {
-        item.map(|stack| Self::from(&stack))
-            .unwrap_or(Slot::empty())
+        item.as_ref().map(Self::from).unwrap_or(Slot::empty())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_world::item::{
+        component::{
+            AdventurePredicate, BlockPredicate, BlockSet, DataComponent, Enchantments,
+            PotionContents, PotionEffect,
+        },
+        ItemStack,
+    };
+    use serde::{Deserialize, Serialize};
+
+    use crate::bytebuf::{deserializer::Deserializer, serializer::Serializer, ByteBuffer};
+
+    use super::Slot;
+
+    fn reserialize(slot: &Slot) -> Slot {
+        let mut serializer = Serializer::new(ByteBuffer::empty());
+        slot.serialize(&mut serializer).unwrap();
+        let mut serialized: ByteBuffer = serializer.into();
+        Slot::deserialize(Deserializer::new(&mut serialized)).unwrap()
+    }
+
+    #[test]
+    fn custom_model_data() {
+        let mut item = ItemStack::new(3, 42);
+        assert_eq!(
+            reserialize(&Slot::from(&item)).to_item(),
+            Some(item.clone())
+        );
+
+        item.set_custom_model_data(Some(1001));
+        let item_back = reserialize(&Slot::from(&item)).to_item().unwrap();
+        assert_eq!(item_back, item);
+        assert_eq!(item_back.item_count, 3);
+
+        assert_eq!(reserialize(&Slot::empty()).to_item(), None);
+    }
+
+    #[test]
+    fn components() {
+        let mut item = ItemStack::new(1, 42);
+        item.components.set(DataComponent::CustomName(
+            r#"{"text":"Blade","bold":true,"extra":["!",{"text":"?"}]}"#.to_string(),
+        ));
+        item.components.set(DataComponent::Damage(12));
+        item.components
+            .set(DataComponent::Enchantments(Enchantments {
+                levels: [("minecraft:sharpness".to_string(), 3)].into(),
+                show_in_tooltip: false,
+            }));
+        item.components.remove("minecraft:food");
+        let item_back = reserialize(&Slot::from(&item)).to_item().unwrap();
+        assert_eq!(
+            item_back.components.get("minecraft:damage"),
+            item.components.get("minecraft:damage")
+        );
+        assert_eq!(
+            item_back.components.get("minecraft:enchantments"),
+            item.components.get("minecraft:enchantments")
+        );
+        assert!(item_back.components.is_removed("minecraft:food"));
+        let Some(DataComponent::CustomName(name)) =
+            item_back.components.get("minecraft:custom_name")
+        else {
+            panic!("The name was not sent");
+        };
+        let name: serde_json::Value = serde_json::from_str(name).unwrap();
+        assert_eq!(name["bold"], true);
+        assert_eq!(name["extra"][0]["text"], "!");
+    }
+
+    #[test]
+    fn adventure_predicates() {
+        let mut item = ItemStack::new(1, 42);
+        let can_break = DataComponent::CanBreak(AdventurePredicate {
+            predicates: vec![
+                BlockPredicate {
+                    blocks: Some(BlockSet::Blocks(vec!["minecraft:oak_stairs".to_string()])),
+                    state: [("facing".to_string(), "east".to_string())].into(),
+                },
+                BlockPredicate {
+                    blocks: Some(BlockSet::Tag("minecraft:logs".to_string())),
+                    ..Default::default()
+                },
+                BlockPredicate::default(),
+            ],
+            show_in_tooltip: true,
+        });
+        item.components.set(can_break.clone());
+        let item_back = reserialize(&Slot::from(&item)).to_item().unwrap();
+        assert_eq!(
+            item_back.components.get("minecraft:can_break"),
+            Some(&can_break)
+        );
+    }
+
+    #[test]
+    fn potion_contents() {
+        let mut item = ItemStack::new(1, 42);
+        let contents = DataComponent::PotionContents(PotionContents {
+            custom_color: Some(0x00FF_00FF),
+            custom_effects: vec![PotionEffect::new("minecraft:haste", 2, 600)],
+            ..PotionContents::of("minecraft:long_swiftness")
+        });
+        item.components.set(contents.clone());
+        let item_back = reserialize(&Slot::from(&item)).to_item().unwrap();
+        assert_eq!(
+            item_back.components.get("minecraft:potion_contents"),
+            Some(&contents)
+        );
     }
 }
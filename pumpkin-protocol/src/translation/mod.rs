@@ -0,0 +1,319 @@
+//! Translating packets for clients on an older protocol version than [`crate::CURRENT_MC_PROTOCOL`].
+//!
+//! The ids of packets which exist in both versions are remapped by their name, packets the older
+//! version does not know are not sent at all. Packets whose data changed between the versions
+//! need a rewriter, which turns the data of the current version into the older one.
+//!
+//! Pumpkin comes with the tables and rewriters of 1.21 and 1.21.1, see [`v767`].
+
+use std::{collections::HashMap, sync::LazyLock};
+
+use bytes::BytesMut;
+use serde::Deserialize;
+
+use crate::{
+    bytebuf::ByteBuffer, packet_encoder::PreparedPacket, ConnectionState, RawPacket, VarInt,
+};
+
+pub mod v767;
+
+/// Lets clients on another protocol version connect.
+pub trait ProtocolTranslator: Send + Sync {
+    /// The protocol version of the clients this translates for
+    fn protocol_version(&self) -> u32;
+
+    /// Turns a packet we send into the client's version, returning None if it can not be sent.
+    fn clientbound(&self, state: ConnectionState, packet: PreparedPacket)
+        -> Option<PreparedPacket>;
+
+    /// Turns a packet from the client into our version, returning None if it should be ignored.
+    fn serverbound(&self, state: ConnectionState, packet: RawPacket) -> Option<RawPacket>;
+}
+
+/// Turns the data of a packet from one version into the other, returning None to drop it
+pub type PayloadRewriter = fn(&[u8]) -> Option<Vec<u8>>;
+
+/// The packet ids of a protocol version, in the format of `assets/packets.json`
+#[derive(Deserialize)]
+pub struct PacketTable {
+    serverbound: HashMap<String, HashMap<String, i32>>,
+    clientbound: HashMap<String, HashMap<String, i32>>,
+}
+
+impl PacketTable {
+    /// The table Pumpkin comes with for the older version, if any
+    #[must_use]
+    pub fn shipped(protocol_version: u32) -> Option<Self> {
+        let json = match protocol_version {
+            v767::PROTOCOL_VERSION => include_str!("../../../assets/protocol/767.json"),
+            _ => return None,
+        };
+        Some(serde_json::from_str(json).expect("Could not parse a shipped packet table."))
+    }
+
+    /// The table of [`crate::CURRENT_MC_PROTOCOL`]
+    #[must_use]
+    pub fn current() -> &'static Self {
+        &CURRENT_PACKETS
+    }
+
+    /// The id of a packet we send by its name, like `keep_alive`
+    #[must_use]
+    pub fn clientbound_id(&self, state: ConnectionState, name: &str) -> Option<i32> {
+        self.clientbound.get(state_name(state))?.get(name).copied()
+    }
+
+    /// The id of a packet the client sends by its name, like `keep_alive`
+    #[must_use]
+    pub fn serverbound_id(&self, state: ConnectionState, name: &str) -> Option<i32> {
+        self.serverbound.get(state_name(state))?.get(name).copied()
+    }
+
+    /// The name of a packet we send by its id
+    #[must_use]
+    pub fn clientbound_name(&self, state: ConnectionState, id: i32) -> Option<&str> {
+        name_of(self.clientbound.get(state_name(state))?, id)
+    }
+
+    /// The name of a packet the client sends by its id
+    #[must_use]
+    pub fn serverbound_name(&self, state: ConnectionState, id: i32) -> Option<&str> {
+        name_of(self.serverbound.get(state_name(state))?, id)
+    }
+}
+
+fn name_of(packets: &HashMap<String, i32>, id: i32) -> Option<&str> {
+    packets
+        .iter()
+        .find(|(_, packet_id)| **packet_id == id)
+        .map(|(name, _)| name.as_str())
+}
+
+static CURRENT_PACKETS: LazyLock<PacketTable> = LazyLock::new(|| {
+    serde_json::from_str(include_str!("../../../assets/packets.json"))
+        .expect("Could not parse packets.json registry.")
+});
+
+const fn state_name(state: ConnectionState) -> &'static str {
+    match state {
+        ConnectionState::HandShake => "handshake",
+        ConnectionState::Status => "status",
+        ConnectionState::Login | ConnectionState::Transfer => "login",
+        ConnectionState::Config => "config",
+        ConnectionState::Play => "play",
+    }
+}
+
+/// Maps `from` ids to `to` ids of the packets both tables have
+fn map_ids(
+    from: &HashMap<String, HashMap<String, i32>>,
+    to: &HashMap<String, HashMap<String, i32>>,
+) -> HashMap<(&'static str, i32), i32> {
+    let mut ids = HashMap::new();
+    for state in [
+        ConnectionState::HandShake,
+        ConnectionState::Status,
+        ConnectionState::Login,
+        ConnectionState::Config,
+        ConnectionState::Play,
+    ] {
+        let name = state_name(state);
+        let (Some(from), Some(to)) = (from.get(name), to.get(name)) else {
+            continue;
+        };
+        for (packet, from_id) in from {
+            if let Some(to_id) = to.get(packet) {
+                ids.insert((name, *from_id), *to_id);
+            }
+        }
+    }
+    ids
+}
+
+/// Translates by remapping packet ids using the packet tables of both versions
+pub struct MappingTranslator {
+    protocol_version: u32,
+    /// Our ids to the client's ids
+    clientbound: HashMap<(&'static str, i32), i32>,
+    /// The client's ids to ours
+    serverbound: HashMap<(&'static str, i32), i32>,
+    /// By state and our id
+    clientbound_rewriters: HashMap<(&'static str, i32), PayloadRewriter>,
+    /// By state and the client's id
+    serverbound_rewriters: HashMap<(&'static str, i32), PayloadRewriter>,
+}
+
+impl MappingTranslator {
+    #[must_use]
+    pub fn new(protocol_version: u32, packets: &PacketTable) -> Self {
+        Self {
+            protocol_version,
+            clientbound: map_ids(&CURRENT_PACKETS.clientbound, &packets.clientbound),
+            serverbound: map_ids(&packets.serverbound, &CURRENT_PACKETS.serverbound),
+            clientbound_rewriters: HashMap::new(),
+            serverbound_rewriters: HashMap::new(),
+        }
+    }
+
+    /// Reads the packet table of the older version, in the format of `assets/packets.json`.
+    pub fn from_json(protocol_version: u32, json: &str) -> serde_json::Result<Self> {
+        Ok(Self::new(protocol_version, &serde_json::from_str(json)?))
+    }
+
+    /// The translator Pumpkin comes with for the older version, with the rewriters of the packets
+    /// which changed. None if it comes with none for the version
+    #[must_use]
+    pub fn shipped(protocol_version: u32) -> Option<Self> {
+        match protocol_version {
+            v767::PROTOCOL_VERSION => Some(v767::translator()),
+            _ => None,
+        }
+    }
+
+    /// Rewrites the data of a packet we send, `packet_id` is our id for it.
+    #[must_use]
+    pub fn with_clientbound_rewriter(
+        mut self,
+        state: ConnectionState,
+        packet_id: i32,
+        rewriter: PayloadRewriter,
+    ) -> Self {
+        self.clientbound_rewriters
+            .insert((state_name(state), packet_id), rewriter);
+        self
+    }
+
+    /// Rewrites the data of a packet the client sends, `packet_id` is the client's id for it.
+    #[must_use]
+    pub fn with_serverbound_rewriter(
+        mut self,
+        state: ConnectionState,
+        packet_id: i32,
+        rewriter: PayloadRewriter,
+    ) -> Self {
+        self.serverbound_rewriters
+            .insert((state_name(state), packet_id), rewriter);
+        self
+    }
+}
+
+impl ProtocolTranslator for MappingTranslator {
+    fn protocol_version(&self) -> u32 {
+        self.protocol_version
+    }
+
+    fn clientbound(
+        &self,
+        state: ConnectionState,
+        packet: PreparedPacket,
+    ) -> Option<PreparedPacket> {
+        let state = state_name(state);
+        let (id, payload) = packet.parts();
+        let client_id = *self.clientbound.get(&(state, id))?;
+        if let Some(rewriter) = self.clientbound_rewriters.get(&(state, id)) {
+            return Some(PreparedPacket::from_parts(client_id, &rewriter(payload)?));
+        }
+        if client_id == id {
+            return Some(packet);
+        }
+        Some(PreparedPacket::from_parts(client_id, payload))
+    }
+
+    fn serverbound(&self, state: ConnectionState, mut packet: RawPacket) -> Option<RawPacket> {
+        let state = state_name(state);
+        let client_id = packet.id.0;
+        let id = *self.serverbound.get(&(state, client_id))?;
+        if let Some(rewriter) = self.serverbound_rewriters.get(&(state, client_id)) {
+            let payload = rewriter(packet.bytebuf.buf())?;
+            packet.bytebuf = ByteBuffer::new(BytesMut::from(payload.as_slice()));
+        }
+        packet.id = VarInt(id);
+        Some(packet)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bytes::BytesMut;
+
+    use crate::{
+        bytebuf::{packet_id::Packet, ByteBuffer},
+        client::play::{CKeepAlive, CPingResponse},
+        packet_encoder::PreparedPacket,
+        server::play::SKeepAlive,
+        ConnectionState, RawPacket, VarInt,
+    };
+
+    use super::{MappingTranslator, ProtocolTranslator};
+
+    /// An older version where keep alive moved and ping responses did not exist yet
+    fn translator() -> MappingTranslator {
+        MappingTranslator::from_json(
+            1,
+            r#"{
+                "serverbound": { "play": { "keep_alive": 3 } },
+                "clientbound": { "play": { "keep_alive": 7 } }
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn remaps_ids_by_name() {
+        let translator = translator();
+        let packet = translator
+            .clientbound(
+                ConnectionState::Play,
+                PreparedPacket::new(&CKeepAlive::new(5)),
+            )
+            .unwrap();
+        let (id, payload) = packet.parts();
+        assert_eq!(id, 7);
+        assert_eq!(payload, 5i64.to_be_bytes());
+
+        let packet = translator
+            .serverbound(
+                ConnectionState::Play,
+                RawPacket {
+                    id: VarInt(3),
+                    bytebuf: ByteBuffer::new(BytesMut::from(&5i64.to_be_bytes()[..])),
+                },
+            )
+            .unwrap();
+        assert_eq!(packet.id.0, SKeepAlive::PACKET_ID);
+    }
+
+    #[test]
+    fn drops_unknown_packets() {
+        let translator = translator();
+        assert!(translator
+            .clientbound(
+                ConnectionState::Play,
+                PreparedPacket::new(&CPingResponse::new(1))
+            )
+            .is_none());
+        // same name, but in another state
+        assert!(translator
+            .clientbound(
+                ConnectionState::Config,
+                PreparedPacket::new(&CKeepAlive::new(5))
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn rewrites_payloads() {
+        let translator = translator().with_clientbound_rewriter(
+            ConnectionState::Play,
+            CKeepAlive::PACKET_ID,
+            |payload| Some(payload[4..].to_vec()),
+        );
+        let packet = translator
+            .clientbound(
+                ConnectionState::Play,
+                PreparedPacket::new(&CKeepAlive::new(5)),
+            )
+            .unwrap();
+        assert_eq!(packet.parts(), (7, &5i32.to_be_bytes()[..]));
+    }
+}
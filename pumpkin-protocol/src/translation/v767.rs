@@ -0,0 +1,740 @@
+//! Clients of 1.21 and 1.21.1, which are on protocol 767.
+//!
+//! Besides moving packet ids, 1.21.2 added the sea level to joining and respawning, gave teleports
+//! a velocity, sends whether the time runs as a flag and the movement keys as flags. Its new blocks
+//! and entities shifted the ids of the others, those are remapped and the new ones are shown as the
+//! closest ones 1.21.1 has, e.g. pale oak as dark oak. Sounds are sent by name as their ids moved.
+//!
+//! Item ids are sent as they are, so items after the ones 1.21.2 added show up as others.
+//! Particles changed too much and are not sent.
+
+use std::{collections::HashMap, ops::Range, sync::LazyLock};
+
+use bytes::BytesMut;
+use pumpkin_macros::registry_entries;
+use pumpkin_world::block::block_registry::{get_block, Block, BLOCKS};
+use serde::Deserialize;
+
+use crate::{
+    bytebuf::{deserializer::Deserializer, packet_id::Packet, ByteBuffer, DeserializerError},
+    client::{
+        config::CUpdateTags,
+        login::CLoginSuccess,
+        play::{
+            CBlockUpdate, CChunkData, CEntitySoundEffect, CLogin, CParticle, CPlayUpdateTags,
+            CRespawn, CSectionBlocksUpdate, CSoundEffect, CSpawnEntity, CSyncPlayerPosition,
+            CUpdateTime, CWorldEvent,
+        },
+    },
+    nbt::NetworkNbt,
+    server::play::SPlayerInput,
+    ConnectionState, VarInt, VarLong,
+};
+
+use super::{MappingTranslator, PacketTable};
+
+pub const PROTOCOL_VERSION: u32 = 767;
+
+/// The level event of a block breaking, its data is the block state
+const BLOCK_BREAK_EVENT: i32 = 2001;
+
+const ENTITY_TYPES: &[&str] = &registry_entries!("minecraft:entity_type");
+
+type Result<T> = std::result::Result<T, DeserializerError>;
+
+/// The translator for 1.21 and 1.21.1 clients
+#[must_use]
+pub fn translator() -> MappingTranslator {
+    let packets = PacketTable::shipped(PROTOCOL_VERSION).expect("767 is shipped");
+    let serverbound = |state, name| {
+        packets
+            .serverbound_id(state, name)
+            .expect("The 767 packet table misses a packet")
+    };
+    MappingTranslator::new(PROTOCOL_VERSION, &packets)
+        // strict error handling, which 1.21.2 removed
+        .with_clientbound_rewriter(
+            ConnectionState::Login,
+            CLoginSuccess::PACKET_ID,
+            |payload| Some([payload, &[0]].concat()),
+        )
+        .with_clientbound_rewriter(ConnectionState::Config, CUpdateTags::PACKET_ID, |payload| {
+            tags(payload).ok()
+        })
+        .with_clientbound_rewriter(
+            ConnectionState::Play,
+            CPlayUpdateTags::PACKET_ID,
+            |payload| tags(payload).ok(),
+        )
+        .with_clientbound_rewriter(ConnectionState::Play, CLogin::PACKET_ID, |payload| {
+            login(payload).ok()
+        })
+        .with_clientbound_rewriter(ConnectionState::Play, CRespawn::PACKET_ID, |payload| {
+            respawn(payload).ok()
+        })
+        .with_clientbound_rewriter(
+            ConnectionState::Play,
+            CSyncPlayerPosition::PACKET_ID,
+            |payload| player_position(payload).ok(),
+        )
+        .with_clientbound_rewriter(ConnectionState::Play, CSpawnEntity::PACKET_ID, |payload| {
+            add_entity(payload).ok().flatten()
+        })
+        .with_clientbound_rewriter(ConnectionState::Play, CChunkData::PACKET_ID, |payload| {
+            chunk(payload).ok()
+        })
+        .with_clientbound_rewriter(ConnectionState::Play, CBlockUpdate::PACKET_ID, |payload| {
+            block_update(payload).ok()
+        })
+        .with_clientbound_rewriter(
+            ConnectionState::Play,
+            CSectionBlocksUpdate::PACKET_ID,
+            |payload| section_blocks_update(payload).ok(),
+        )
+        .with_clientbound_rewriter(ConnectionState::Play, CWorldEvent::PACKET_ID, level_event)
+        .with_clientbound_rewriter(ConnectionState::Play, CSoundEffect::PACKET_ID, |payload| {
+            sound(payload).ok().flatten()
+        })
+        .with_clientbound_rewriter(
+            ConnectionState::Play,
+            CEntitySoundEffect::PACKET_ID,
+            |payload| sound(payload).ok().flatten(),
+        )
+        .with_clientbound_rewriter(ConnectionState::Play, CUpdateTime::PACKET_ID, |payload| {
+            set_time(payload).ok()
+        })
+        .with_clientbound_rewriter(ConnectionState::Play, CParticle::PACKET_ID, |_| None)
+        // the particles the client shows, which 1.21.1 does not send
+        .with_serverbound_rewriter(
+            ConnectionState::Config,
+            serverbound(ConnectionState::Config, "client_information"),
+            |payload| Some([payload, &var_int(0)].concat()),
+        )
+        .with_serverbound_rewriter(
+            ConnectionState::Play,
+            serverbound(ConnectionState::Play, "client_information"),
+            |payload| Some([payload, &var_int(0)].concat()),
+        )
+        // the rotation of the player, which 1.21.1 does not send
+        .with_serverbound_rewriter(
+            ConnectionState::Play,
+            serverbound(ConnectionState::Play, "use_item"),
+            |payload| Some([payload, &[0; 8]].concat()),
+        )
+        .with_serverbound_rewriter(
+            ConnectionState::Play,
+            serverbound(ConnectionState::Play, "player_input"),
+            |payload| player_input(payload).ok(),
+        )
+        // recipes are sent by name instead of their display id
+        .with_serverbound_rewriter(
+            ConnectionState::Play,
+            serverbound(ConnectionState::Play, "place_recipe"),
+            |_| None,
+        )
+        .with_serverbound_rewriter(
+            ConnectionState::Play,
+            serverbound(ConnectionState::Play, "recipe_book_seen_recipe"),
+            |_| None,
+        )
+}
+
+/// The block shown to 1.21.1 instead of one added in 1.21.2, None for the blocks it has
+fn replacement(name: &str) -> Option<String> {
+    match name {
+        "minecraft:pale_moss_block" => Some("minecraft:moss_block".to_string()),
+        "minecraft:pale_moss_carpet" => Some("minecraft:moss_carpet".to_string()),
+        "minecraft:pale_hanging_moss" => Some("minecraft:hanging_roots".to_string()),
+        "minecraft:creaking_heart" => Some("minecraft:dark_oak_log".to_string()),
+        _ if name.contains("pale_oak") => Some(name.replace("pale_oak", "dark_oak")),
+        _ => None,
+    }
+}
+
+/// The state of the shown block with the property values both blocks have
+fn shown_state(block: &Block, shown: &Block, state_id: u16) -> u16 {
+    let properties = block
+        .properties_of_state(state_id)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(name, _)| {
+            shown
+                .properties
+                .iter()
+                .any(|property| property.name == *name)
+        })
+        .collect::<Vec<_>>();
+    shown
+        .state_id_with_properties(&properties)
+        .unwrap_or(shown.default_state_id)
+}
+
+struct OldBlocks {
+    /// Our block state ids to 1.21.1's
+    states: Vec<i32>,
+    /// Our block ids to 1.21.1's, None for the blocks added in 1.21.2
+    blocks: Vec<Option<i32>>,
+}
+
+static OLD_BLOCKS: LazyLock<OldBlocks> = LazyLock::new(|| {
+    // 1.21.2 only put its blocks in between, the others kept their order
+    let mut first_states = HashMap::new();
+    let mut blocks = vec![None; BLOCKS.blocks.len()];
+    let mut next_state = 0;
+    for (id, block) in BLOCKS
+        .blocks
+        .iter()
+        .filter(|block| replacement(&block.name).is_none())
+        .enumerate()
+    {
+        blocks[usize::from(block.id)] = i32::try_from(id).ok();
+        first_states.insert(block.name.as_str(), next_state);
+        next_state += block.states.len();
+    }
+
+    let mut states = vec![0; BLOCKS.blocks.iter().map(|block| block.states.len()).sum()];
+    for block in &BLOCKS.blocks {
+        let shown = replacement(&block.name).map_or(block, |name| {
+            get_block(&name).expect("The block shown to 1.21.1 does not exist")
+        });
+        let first_state = shown.states.first().map_or(0, |state| state.id);
+        for state in &block.states {
+            let index = usize::from(shown_state(block, shown, state.id) - first_state);
+            states[usize::from(state.id)] =
+                i32::try_from(first_states[shown.name.as_str()] + index).unwrap_or_default();
+        }
+    }
+    OldBlocks { states, blocks }
+});
+
+/// 1.21.1's id of the block state, air for ids which do not exist
+fn block_state(id: i32) -> i32 {
+    usize::try_from(id)
+        .ok()
+        .and_then(|id| OLD_BLOCKS.states.get(id))
+        .copied()
+        .unwrap_or_default()
+}
+
+/// 1.21.1's id of the block, None for the blocks it does not have
+fn block(id: i32) -> Option<i32> {
+    usize::try_from(id)
+        .ok()
+        .and_then(|id| OLD_BLOCKS.blocks.get(id))
+        .copied()
+        .flatten()
+}
+
+/// The entity type 1.21.1 has for ours, it had one type for all boats and one for all chest boats
+fn old_entity_type(name: &str) -> Option<&str> {
+    if name.ends_with("_chest_boat") || name.ends_with("_chest_raft") {
+        Some("minecraft:chest_boat")
+    } else if name.ends_with("_boat") || name.ends_with("_raft") {
+        Some("minecraft:boat")
+    } else if name.starts_with("minecraft:creaking") {
+        None
+    } else {
+        Some(name)
+    }
+}
+
+/// Our entity type ids to 1.21.1's
+static OLD_ENTITY_TYPES: LazyLock<Vec<Option<i32>>> = LazyLock::new(|| {
+    let mut old = Vec::new();
+    for &name in ENTITY_TYPES {
+        if old_entity_type(name) == Some(name) {
+            old.push(name);
+        }
+        // where the boats are when sorting by name, like most of the registry is
+        match name {
+            "minecraft:block_display" => old.push("minecraft:boat"),
+            "minecraft:cave_spider" => old.push("minecraft:chest_boat"),
+            _ => {}
+        }
+    }
+    ENTITY_TYPES
+        .iter()
+        .map(|name| {
+            let name = old_entity_type(name)?;
+            let id = old.iter().position(|old| *old == name)?;
+            i32::try_from(id).ok()
+        })
+        .collect()
+});
+
+/// 1.21.1's id of the entity type, None for the types it does not have
+fn entity_type(id: i32) -> Option<i32> {
+    usize::try_from(id)
+        .ok()
+        .and_then(|id| OLD_ENTITY_TYPES.get(id))
+        .copied()
+        .flatten()
+}
+
+/// The names of the sounds by our id
+static SOUND_NAMES: LazyLock<Vec<String>> = LazyLock::new(|| {
+    let sounds: HashMap<String, u16> =
+        serde_json::from_str(include_str!("../../../assets/sounds.json"))
+            .expect("Could not parse sounds.json registry.");
+    let mut names = vec![String::new(); sounds.len()];
+    for (name, id) in sounds {
+        if let Some(slot) = names.get_mut(usize::from(id)) {
+            *slot = name;
+        }
+    }
+    names
+});
+
+fn reader(payload: &[u8]) -> ByteBuffer {
+    ByteBuffer::new(BytesMut::from(payload))
+}
+
+/// How much of the payload was read
+fn offset(payload: &[u8], reader: &mut ByteBuffer) -> usize {
+    payload.len() - reader.buf().len()
+}
+
+/// Reads a VarInt, returning it with where it is in the payload
+fn var_int_at(payload: &[u8], reader: &mut ByteBuffer) -> Result<(i32, Range<usize>)> {
+    let start = offset(payload, reader);
+    let value = reader.get_var_int()?.0;
+    Ok((value, start..offset(payload, reader)))
+}
+
+fn var_int(value: i32) -> Vec<u8> {
+    let mut buf = ByteBuffer::empty();
+    buf.put_var_int(&VarInt(value));
+    buf.buf().to_vec()
+}
+
+/// The payload with the bytes in the range replaced
+fn splice(payload: &[u8], range: Range<usize>, replacement: &[u8]) -> Vec<u8> {
+    [&payload[..range.start], replacement, &payload[range.end..]].concat()
+}
+
+fn invalid(message: &str) -> DeserializerError {
+    DeserializerError::Message(message.to_string())
+}
+
+/// Reads up to the sea level, which follows the spawn info of joining and respawning
+fn skip_spawn_info(reader: &mut ByteBuffer) -> Result<()> {
+    // dimension type and name
+    reader.get_var_int()?;
+    reader.get_string()?;
+    // hashed seed, game mode, previous game mode, debug and flat
+    reader.get_i64()?;
+    reader.get_u8()?;
+    reader.get_i8()?;
+    reader.get_bool()?;
+    reader.get_bool()?;
+    // death location
+    if reader.get_bool()? {
+        reader.get_string()?;
+        reader.get_i64()?;
+    }
+    // portal cooldown
+    reader.get_var_int()?;
+    Ok(())
+}
+
+fn login(payload: &[u8]) -> Result<Vec<u8>> {
+    let mut reader = reader(payload);
+    // entity id and hardcore
+    reader.get_i32()?;
+    reader.get_bool()?;
+    for _ in 0..reader.get_var_int()?.0 {
+        reader.get_string()?;
+    }
+    // max players, view and simulation distance
+    for _ in 0..3 {
+        reader.get_var_int()?;
+    }
+    // reduced debug info, respawn screen and limited crafting
+    for _ in 0..3 {
+        reader.get_bool()?;
+    }
+    skip_spawn_info(&mut reader)?;
+    let (_, sea_level) = var_int_at(payload, &mut reader)?;
+    Ok(splice(payload, sea_level, &[]))
+}
+
+fn respawn(payload: &[u8]) -> Result<Vec<u8>> {
+    let mut reader = reader(payload);
+    skip_spawn_info(&mut reader)?;
+    let (_, sea_level) = var_int_at(payload, &mut reader)?;
+    Ok(splice(payload, sea_level, &[]))
+}
+
+/// 1.21.1 sends the teleport id last and the relative flags as a byte, it has no velocity
+fn player_position(payload: &[u8]) -> Result<Vec<u8>> {
+    let mut reader = reader(payload);
+    let teleport_id = reader.get_var_int()?;
+    let mut position = ByteBuffer::empty();
+    for _ in 0..3 {
+        position.put_f64(reader.get_f64()?);
+    }
+    // the velocity, 1.21.1 keeps the one the player has
+    for _ in 0..3 {
+        reader.get_f64()?;
+    }
+    position.put_f32(reader.get_f32()?);
+    position.put_f32(reader.get_f32()?);
+    // the flags of the position and rotation, the ones of the velocity came with 1.21.2
+    position.put_u8(u8::try_from(reader.get_i32()? & 0x1F).unwrap_or_default());
+    position.put_var_int(&teleport_id);
+    Ok(position.buf().to_vec())
+}
+
+/// Entities of a type 1.21.1 does not have are not sent
+fn add_entity(payload: &[u8]) -> Result<Option<Vec<u8>>> {
+    let mut reader = reader(payload);
+    reader.get_var_int()?;
+    reader.get_uuid()?;
+    let (kind, range) = var_int_at(payload, &mut reader)?;
+    Ok(entity_type(kind).map(|kind| splice(payload, range, &var_int(kind))))
+}
+
+/// Copies a paletted container of a chunk section, remapping its ids. Containers with more bits
+/// per entry than `max_indirect_bits` have no palette but the ids in their data
+fn copy_container(
+    from: &mut ByteBuffer,
+    to: &mut ByteBuffer,
+    max_indirect_bits: u8,
+    remap: fn(i32) -> i32,
+) -> Result<()> {
+    let bits = from.get_u8()?;
+    if bits > 32 {
+        return Err(invalid("Too many bits per entry"));
+    }
+    to.put_u8(bits);
+    if bits <= max_indirect_bits {
+        // the palette, or the one id of the whole container
+        let length = if bits == 0 {
+            1
+        } else {
+            let length = from.get_var_int()?;
+            to.put_var_int(&length);
+            length.0
+        };
+        for _ in 0..length {
+            to.put_var_int(&VarInt(remap(from.get_var_int()?.0)));
+        }
+    }
+    let longs = from.get_var_int()?;
+    to.put_var_int(&longs);
+    for _ in 0..longs.0 {
+        let long = from.get_u64()?;
+        if bits > max_indirect_bits {
+            to.put_u64(remap_packed(long, bits, remap));
+        } else {
+            to.put_u64(long);
+        }
+    }
+    Ok(())
+}
+
+/// Remaps the ids packed into a long of a container without a palette
+fn remap_packed(long: u64, bits: u8, remap: fn(i32) -> i32) -> u64 {
+    let bits = u32::from(bits);
+    let mask = (1 << bits) - 1;
+    (0..64 / bits).fold(0, |packed, i| {
+        let shift = i * bits;
+        let id = remap(i32::try_from((long >> shift) & mask).unwrap_or_default());
+        packed | ((u64::try_from(id).unwrap_or_default() & mask) << shift)
+    })
+}
+
+fn chunk(payload: &[u8]) -> Result<Vec<u8>> {
+    let mut reader = reader(payload);
+    // chunk x and z
+    reader.get_i32()?;
+    reader.get_i32()?;
+    // heightmaps
+    NetworkNbt::deserialize(Deserializer::new(&mut reader))?;
+    let (size, size_range) = var_int_at(payload, &mut reader)?;
+    let size = usize::try_from(size).map_err(|_| invalid("Negative chunk data size"))?;
+    let mut sections = ByteBuffer::new(BytesMut::from(&reader.copy_to_bytes(size)?[..]));
+    let data_end = offset(payload, &mut reader);
+
+    let mut data = ByteBuffer::empty();
+    while !sections.buf().is_empty() {
+        // non-air blocks
+        data.put_i16(sections.get_i16()?);
+        copy_container(&mut sections, &mut data, 8, block_state)?;
+        // biomes did not change
+        copy_container(&mut sections, &mut data, 3, |id| id)?;
+    }
+    let size = i32::try_from(data.buf().len()).map_err(|_| invalid("Chunk data is too big"))?;
+    Ok([
+        &payload[..size_range.start],
+        &var_int(size),
+        data.buf(),
+        &payload[data_end..],
+    ]
+    .concat())
+}
+
+fn block_update(payload: &[u8]) -> Result<Vec<u8>> {
+    let mut reader = reader(payload);
+    // position
+    reader.get_i64()?;
+    let (state, range) = var_int_at(payload, &mut reader)?;
+    Ok(splice(payload, range, &var_int(block_state(state))))
+}
+
+fn section_blocks_update(payload: &[u8]) -> Result<Vec<u8>> {
+    let mut reader = reader(payload);
+    let mut blocks = ByteBuffer::empty();
+    // section position
+    blocks.put_i64(reader.get_i64()?);
+    let count = reader.get_var_int()?;
+    blocks.put_var_int(&count);
+    for _ in 0..count.0 {
+        // the state id follows the position in the section
+        let block = reader.get_var_long()?;
+        let state = block_state(i32::try_from(block >> 12).unwrap_or_default());
+        blocks.put_var_long(&VarLong((i64::from(state) << 12) | (block & 0xFFF)));
+    }
+    Ok(blocks.buf().to_vec())
+}
+
+fn level_event(payload: &[u8]) -> Option<Vec<u8>> {
+    let event = i32::from_be_bytes(payload.get(..4)?.try_into().ok()?);
+    if event != BLOCK_BREAK_EVENT {
+        return Some(payload.to_vec());
+    }
+    // the data follows the position
+    let state = i32::from_be_bytes(payload.get(12..16)?.try_into().ok()?);
+    Some(splice(payload, 12..16, &block_state(state).to_be_bytes()))
+}
+
+/// Sounds are sent by name, sounds 1.21.1 does not have are not played
+fn sound(payload: &[u8]) -> Result<Option<Vec<u8>>> {
+    let mut reader = reader(payload);
+    let (id, range) = var_int_at(payload, &mut reader)?;
+    // zero is a sound which is already sent by name
+    let Some(id) = id.checked_sub(1).filter(|id| *id >= 0) else {
+        return Ok(Some(payload.to_vec()));
+    };
+    let Some(name) = usize::try_from(id)
+        .ok()
+        .and_then(|id| SOUND_NAMES.get(id))
+        .filter(|name| !name.is_empty())
+    else {
+        return Ok(None);
+    };
+    let mut sound = ByteBuffer::empty();
+    sound.put_var_int(&VarInt(0));
+    sound.put_string(name);
+    // no fixed range
+    sound.put_bool(false);
+    Ok(Some(splice(payload, range, sound.buf())))
+}
+
+/// 1.21.1 stops the time if it is negative
+fn set_time(payload: &[u8]) -> Result<Vec<u8>> {
+    let mut reader = reader(payload);
+    let mut time = ByteBuffer::empty();
+    // world age
+    time.put_i64(reader.get_i64()?);
+    let time_of_day = reader.get_i64()?;
+    if reader.get_bool()? {
+        time.put_i64(time_of_day);
+    } else {
+        time.put_i64(-(time_of_day.max(1)));
+    }
+    Ok(time.buf().to_vec())
+}
+
+/// Remaps the blocks and entity types in tags, dropping the ones 1.21.1 does not have
+fn tags(payload: &[u8]) -> Result<Vec<u8>> {
+    let mut reader = reader(payload);
+    let mut tags = ByteBuffer::empty();
+    let registries = reader.get_var_int()?;
+    tags.put_var_int(&registries);
+    for _ in 0..registries.0 {
+        let registry = reader.get_string()?;
+        let remap: fn(i32) -> Option<i32> = match registry.as_str() {
+            "minecraft:block" => block,
+            "minecraft:entity_type" => entity_type,
+            _ => Some,
+        };
+        tags.put_string(&registry);
+        let count = reader.get_var_int()?;
+        tags.put_var_int(&count);
+        for _ in 0..count.0 {
+            tags.put_string(&reader.get_string()?);
+            let mut ids = Vec::new();
+            for _ in 0..reader.get_var_int()?.0 {
+                ids.extend(remap(reader.get_var_int()?.0));
+            }
+            // all boats are one type in 1.21.1
+            ids.sort_unstable();
+            ids.dedup();
+            tags.put_list(&ids, |p, id| p.put_var_int(&VarInt(*id)));
+        }
+    }
+    Ok(tags.buf().to_vec())
+}
+
+/// 1.21.1 sends how far the player moves sideways and forward while riding, plus jumping and
+/// sneaking as flags
+fn player_input(payload: &[u8]) -> Result<Vec<u8>> {
+    let mut reader = reader(payload);
+    let sideways = reader.get_f32()?;
+    let forward = reader.get_f32()?;
+    let flags = reader.get_u8()?;
+    let input = [
+        (forward > 0.0, SPlayerInput::FORWARD),
+        (forward < 0.0, SPlayerInput::BACKWARD),
+        (sideways > 0.0, SPlayerInput::LEFT),
+        (sideways < 0.0, SPlayerInput::RIGHT),
+        (flags & 0x01 != 0, SPlayerInput::JUMP),
+        (flags & 0x02 != 0, SPlayerInput::SNEAK),
+    ]
+    .into_iter()
+    .filter(|(held, _)| *held)
+    .fold(0, |input, (_, flag)| input | flag);
+    Ok(vec![input])
+}
+
+#[cfg(test)]
+mod test {
+    use bytes::BytesMut;
+    use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+    use pumpkin_world::block::block_registry::get_block;
+
+    use crate::{
+        bytebuf::ByteBuffer,
+        client::play::{CBlockUpdate, CRespawn, CSyncPlayerPosition},
+        packet_encoder::PreparedPacket,
+        server::play::SPlayerInput,
+        translation::{PacketTable, ProtocolTranslator},
+        ConnectionState, PositionFlag, RawPacket, VarInt,
+    };
+
+    use super::{block_state, entity_type, translator, PROTOCOL_VERSION};
+
+    fn clientbound(packet: PreparedPacket) -> (i32, Vec<u8>) {
+        let packet = translator()
+            .clientbound(ConnectionState::Play, packet)
+            .unwrap();
+        let (id, payload) = packet.parts();
+        (id, payload.to_vec())
+    }
+
+    fn default_state(name: &str) -> i32 {
+        get_block(name).unwrap().default_state_id.into()
+    }
+
+    fn entity(name: &str) -> Option<i32> {
+        let id = super::ENTITY_TYPES
+            .iter()
+            .position(|entity| *entity == name)?;
+        entity_type(i32::try_from(id).unwrap())
+    }
+
+    #[test]
+    fn removes_the_sea_level() {
+        let packet = CRespawn::new(
+            VarInt(0),
+            "minecraft:overworld",
+            0,
+            0,
+            -1,
+            false,
+            false,
+            None,
+            VarInt(20),
+            VarInt(63),
+            1,
+        );
+        let prepared = PreparedPacket::new(&packet);
+        let mut expected = prepared.parts().1.to_vec();
+        // the sea level is the second to last field
+        expected.remove(expected.len() - 2);
+
+        let (id, payload) = clientbound(PreparedPacket::new(&packet));
+        assert_eq!(id, 0x47);
+        assert_eq!(payload, expected);
+    }
+
+    #[test]
+    fn moves_the_teleport_id() {
+        let (_, payload) = clientbound(PreparedPacket::new(&CSyncPlayerPosition::new(
+            VarInt(7),
+            Vector3::new(1.0, 2.0, 3.0),
+            Vector3::new(0.5, 0.5, 0.5),
+            90.0,
+            10.0,
+            &[PositionFlag::Y, PositionFlag::DeltaX],
+        )));
+        let mut expected = ByteBuffer::empty();
+        expected.put_f64(1.0);
+        expected.put_f64(2.0);
+        expected.put_f64(3.0);
+        expected.put_f32(90.0);
+        expected.put_f32(10.0);
+        expected.put_u8(0x02);
+        expected.put_var_int(&VarInt(7));
+        assert_eq!(payload, expected.buf().to_vec());
+    }
+
+    #[test]
+    fn remaps_block_states() {
+        // before the first block added in 1.21.2
+        assert_eq!(block_state(1), 1);
+        assert_eq!(
+            block_state(default_state("minecraft:pale_oak_planks")),
+            block_state(default_state("minecraft:dark_oak_planks"))
+        );
+        // 1.21.1 has 26684 states
+        assert_eq!(super::OLD_BLOCKS.states.iter().max(), Some(&26683));
+
+        let position = WorldPosition(Vector3::new(0, 64, 0));
+        let state = default_state("minecraft:pale_moss_block");
+        let (_, payload) = clientbound(PreparedPacket::new(&CBlockUpdate::new(
+            &position,
+            VarInt(state),
+        )));
+        assert_eq!(
+            payload[8..],
+            super::var_int(block_state(default_state("minecraft:moss_block")))[..]
+        );
+    }
+
+    #[test]
+    fn remaps_entity_types() {
+        assert_eq!(entity("minecraft:allay"), Some(0));
+        assert_eq!(entity("minecraft:oak_boat"), Some(10));
+        assert_eq!(entity("minecraft:bamboo_chest_raft"), Some(17));
+        assert_eq!(entity("minecraft:player"), Some(128));
+        assert_eq!(entity("minecraft:fishing_bobber"), Some(129));
+        assert_eq!(entity("minecraft:creaking"), None);
+    }
+
+    #[test]
+    fn turns_movement_into_flags() {
+        let packets = PacketTable::shipped(PROTOCOL_VERSION).unwrap();
+        let id = packets
+            .serverbound_id(ConnectionState::Play, "player_input")
+            .unwrap();
+        let mut payload = ByteBuffer::empty();
+        payload.put_f32(-0.98);
+        payload.put_f32(0.98);
+        payload.put_u8(0x01);
+        let packet = translator()
+            .serverbound(
+                ConnectionState::Play,
+                RawPacket {
+                    id: VarInt(id),
+                    bytebuf: ByteBuffer::new(BytesMut::from(&payload.buf()[..])),
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            packet.bytebuf.buf().to_vec(),
+            [SPlayerInput::FORWARD | SPlayerInput::RIGHT | SPlayerInput::JUMP]
+        );
+    }
+}
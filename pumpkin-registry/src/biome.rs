@@ -1,3 +1,4 @@
+use pumpkin_config::dimension_effects::BiomeEffectsOverride;
 use pumpkin_protocol::VarInt;
 use serde::{Deserialize, Serialize};
 
@@ -10,6 +11,30 @@ pub struct Biome {
     downfall: f32,
     effects: BiomeEffects,
 }
+impl Biome {
+    pub fn apply_override(&mut self, effects_override: &BiomeEffectsOverride) {
+        let effects = &mut self.effects;
+        if let Some(fog_color) = effects_override.fog_color {
+            effects.fog_color = fog_color;
+        }
+        if let Some(sky_color) = effects_override.sky_color {
+            effects.sky_color = sky_color;
+        }
+        if let Some(water_color) = effects_override.water_color {
+            effects.water_color = water_color;
+        }
+        if let Some(water_fog_color) = effects_override.water_fog_color {
+            effects.water_fog_color = water_fog_color;
+        }
+        if let Some(foliage_color) = effects_override.foliage_color {
+            effects.foliage_color = Some(foliage_color);
+        }
+        if let Some(grass_color) = effects_override.grass_color {
+            effects.grass_color = Some(grass_color);
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct BiomeEffects {
     fog_color: i32,
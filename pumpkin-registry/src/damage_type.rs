@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::SYNCED_REGISTRIES;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DamageType {
     exhaustion: f32,
@@ -10,3 +12,31 @@ pub struct DamageType {
     message_id: String,
     scaling: String,
 }
+
+impl DamageType {
+    /// How much hunger the damage costs
+    #[must_use]
+    pub const fn exhaustion(&self) -> f32 {
+        self.exhaustion
+    }
+
+    /// The death message is translated from `death.attack.<message_id>`
+    #[must_use]
+    pub fn message_id(&self) -> &str {
+        &self.message_id
+    }
+
+    /// `fall_variants` or `intentional_game_design` if the death message is not the default one
+    #[must_use]
+    pub fn death_message_type(&self) -> Option<&str> {
+        self.death_message_type.as_deref()
+    }
+}
+
+/// The damage type with its network id, e.g. `minecraft:fall`
+#[must_use]
+pub fn get_damage_type(name: &str) -> Option<(u16, &'static DamageType)> {
+    let registry = &SYNCED_REGISTRIES.damage_type;
+    let id = registry.keys().position(|key| key == name)?;
+    Some((id as u16, &registry[name]))
+}
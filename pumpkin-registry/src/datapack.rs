@@ -0,0 +1,436 @@
+//! The datapacks in a world's `datapacks` folder, which of them are enabled and in which order.
+//!
+//! Packs are identified like vanilla does it, `file/<folder name>`. The built-in `vanilla` pack
+//! is always enabled first.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+use serde::{de::DeserializeOwned, Deserialize};
+
+/// The `pack_format` of datapacks made for the Minecraft version Pumpkin supports
+pub const DATAPACK_FORMAT: i32 = 57;
+
+pub const VANILLA_PACK: &str = "vanilla";
+
+/// A datapack found in the `datapacks` folder
+#[derive(Clone, Debug)]
+pub struct Datapack {
+    pub id: String,
+    pub path: PathBuf,
+    pub description: String,
+    /// Whether the pack says it supports [`DATAPACK_FORMAT`], other packs may still work
+    pub compatible: bool,
+}
+
+/// Where a pack goes in the load order when it is enabled, packs later in it override earlier
+/// ones
+pub enum PackPosition<'a> {
+    First,
+    Last,
+    Before(&'a str),
+    After(&'a str),
+}
+
+#[derive(Deserialize)]
+struct PackMcmeta {
+    pack: PackInfo,
+}
+
+#[derive(Deserialize)]
+struct PackInfo {
+    pack_format: i32,
+    #[serde(default)]
+    description: serde_json::Value,
+    supported_formats: Option<SupportedFormats>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SupportedFormats {
+    Single(i32),
+    Range([i32; 2]),
+    Bounds {
+        min_inclusive: i32,
+        max_inclusive: i32,
+    },
+}
+
+impl PackInfo {
+    fn supports(&self, format: i32) -> bool {
+        match self.supported_formats {
+            Some(SupportedFormats::Single(supported)) => supported == format,
+            Some(
+                SupportedFormats::Range([min, max])
+                | SupportedFormats::Bounds {
+                    min_inclusive: min,
+                    max_inclusive: max,
+                },
+            ) => (min..=max).contains(&format),
+            None => self.pack_format == format,
+        }
+    }
+}
+
+/// The plain text of a description, which may be a text component
+fn plain_text(description: &serde_json::Value) -> String {
+    match description {
+        serde_json::Value::String(text) => text.clone(),
+        serde_json::Value::Array(parts) => parts.iter().map(plain_text).collect(),
+        serde_json::Value::Object(component) => {
+            let mut text = component.get("text").map(plain_text).unwrap_or_default();
+            if let Some(extra) = component.get("extra") {
+                text.push_str(&plain_text(extra));
+            }
+            text
+        }
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Reads the `pack.mcmeta` of the pack in the folder
+fn read_pack(path: &Path) -> Result<Datapack, String> {
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or("The folder name is not valid UTF-8")?;
+    let mcmeta = std::fs::read_to_string(path.join("pack.mcmeta"))
+        .map_err(|err| format!("Couldn't read pack.mcmeta: {err}"))?;
+    let mcmeta: PackMcmeta = serde_json::from_str(&mcmeta)
+        .map_err(|err| format!("Couldn't parse pack.mcmeta: {err}"))?;
+    Ok(Datapack {
+        id: format!("file/{name}"),
+        path: path.to_path_buf(),
+        description: plain_text(&mcmeta.pack.description),
+        compatible: mcmeta.pack.supports(DATAPACK_FORMAT),
+    })
+}
+
+/// Reads every JSON file in `data/<namespace>/<directory>` of the pack, including the ones in
+/// sub folders, by their id like `namespace:sub/name`. Broken files are skipped with a warning
+pub(crate) fn read_pack_files<T: DeserializeOwned>(
+    pack: &Path,
+    directory: &str,
+) -> Vec<(String, T)> {
+    fn collect_json_files(dir: &Path, files: &mut Vec<PathBuf>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                collect_json_files(&path, files);
+            } else if path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+            {
+                files.push(path);
+            }
+        }
+    }
+
+    let Ok(namespaces) = std::fs::read_dir(pack.join("data")) else {
+        return Vec::new();
+    };
+    let mut entries = Vec::new();
+    for namespace in namespaces.flatten() {
+        let namespace_name = namespace.file_name().to_string_lossy().into_owned();
+        let dir = namespace.path().join(directory);
+        let mut files = Vec::new();
+        collect_json_files(&dir, &mut files);
+        files.sort();
+        for file in files {
+            let Some(name) = file
+                .strip_prefix(&dir)
+                .ok()
+                .and_then(|path| path.with_extension("").to_str().map(str::to_string))
+            else {
+                continue;
+            };
+            let id = format!("{namespace_name}:{}", name.replace('\\', "/"));
+            let parsed = std::fs::read_to_string(&file)
+                .map_err(|err| err.to_string())
+                .and_then(|content| serde_json::from_str(&content).map_err(|err| err.to_string()));
+            match parsed {
+                Ok(entry) => entries.push((id, entry)),
+                Err(err) => log::warn!("Could not load {}: {err}", file.display()),
+            }
+        }
+    }
+    entries
+}
+
+/// Entries which only come from datapacks, like predicates. A pack overrides the entries of the
+/// packs loaded before it
+pub struct DatapackRegistry<T> {
+    /// The folder in a namespace the entries are in, e.g. `predicate`
+    directory: &'static str,
+    entries: RwLock<HashMap<String, Arc<T>>>,
+}
+
+impl<T: DeserializeOwned> DatapackRegistry<T> {
+    #[must_use]
+    pub fn new(directory: &'static str) -> Self {
+        Self {
+            directory,
+            entries: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Replaces the entries with the ones of the packs, in their load order
+    pub fn load(&self, pack_dirs: &[PathBuf]) {
+        let entries = pack_dirs
+            .iter()
+            .flat_map(|pack| read_pack_files(pack, self.directory))
+            .map(|(id, entry)| (id, Arc::new(entry)))
+            .collect();
+        *self.entries.write().expect("Registry lock is poisoned") = entries;
+    }
+
+    /// The entry with the id, `minecraft:` may be left out
+    #[must_use]
+    pub fn get(&self, id: &str) -> Option<Arc<T>> {
+        let id = if id.contains(':') {
+            id.to_string()
+        } else {
+            format!("minecraft:{id}")
+        };
+        self.entries
+            .read()
+            .expect("Registry lock is poisoned")
+            .get(&id)
+            .cloned()
+    }
+
+    /// Every id, sorted
+    #[must_use]
+    pub fn ids(&self) -> Vec<String> {
+        let mut ids: Vec<_> = self
+            .entries
+            .read()
+            .expect("Registry lock is poisoned")
+            .keys()
+            .cloned()
+            .collect();
+        ids.sort();
+        ids
+    }
+}
+
+/// The available datapacks and the ids of the enabled ones in their load order
+#[derive(Default)]
+pub struct Datapacks {
+    available: Vec<Datapack>,
+    enabled: Vec<String>,
+}
+
+impl Datapacks {
+    /// Finds the packs in the folder. Packs which are in neither of the saved lists are new and
+    /// get enabled, like vanilla does it
+    #[must_use]
+    pub fn scan(folder: &Path, enabled: &[String], disabled: &[String]) -> Self {
+        let mut available = Vec::new();
+        if let Ok(entries) = std::fs::read_dir(folder) {
+            for path in entries.flatten().map(|entry| entry.path()) {
+                if !path.is_dir() {
+                    continue;
+                }
+                match read_pack(&path) {
+                    Ok(pack) => {
+                        if !pack.compatible {
+                            log::warn!(
+                                "Datapack {} was made for another Minecraft version",
+                                pack.id
+                            );
+                        }
+                        available.push(pack);
+                    }
+                    Err(err) => log::warn!("Skipping datapack {}: {err}", path.display()),
+                }
+            }
+        }
+        available.sort_by(|a, b| a.id.cmp(&b.id));
+        Self::from_available(available, enabled, disabled)
+    }
+
+    fn from_available(available: Vec<Datapack>, enabled: &[String], disabled: &[String]) -> Self {
+        let mut packs = Self {
+            available,
+            enabled: vec![VANILLA_PACK.to_string()],
+        };
+        // packs which were removed from the folder are forgotten
+        for id in enabled {
+            if packs.get(id).is_some() && !packs.enabled.contains(id) {
+                packs.enabled.push(id.clone());
+            }
+        }
+        let new: Vec<_> = packs
+            .available
+            .iter()
+            .filter(|pack| !enabled.contains(&pack.id) && !disabled.contains(&pack.id))
+            .map(|pack| pack.id.clone())
+            .collect();
+        packs.enabled.extend(new);
+        packs
+    }
+
+    #[must_use]
+    pub fn get(&self, id: &str) -> Option<&Datapack> {
+        self.available.iter().find(|pack| pack.id == id)
+    }
+
+    /// Every pack that was found, sorted by id
+    #[must_use]
+    pub fn available(&self) -> &[Datapack] {
+        &self.available
+    }
+
+    /// The ids of the enabled packs in the order they load, starting with `vanilla`
+    #[must_use]
+    pub fn enabled(&self) -> &[String] {
+        &self.enabled
+    }
+
+    /// The ids of the packs which were found but are not enabled
+    #[must_use]
+    pub fn disabled(&self) -> Vec<String> {
+        self.available
+            .iter()
+            .filter(|pack| !self.enabled.contains(&pack.id))
+            .map(|pack| pack.id.clone())
+            .collect()
+    }
+
+    /// The folders of the enabled packs in the order they load
+    #[must_use]
+    pub fn enabled_folders(&self) -> Vec<PathBuf> {
+        self.enabled
+            .iter()
+            .filter_map(|id| self.get(id))
+            .map(|pack| pack.path.clone())
+            .collect()
+    }
+
+    pub fn enable(&mut self, id: &str, position: &PackPosition) -> Result<(), String> {
+        if self.get(id).is_none() {
+            return Err(format!("Unknown datapack '{id}'"));
+        }
+        if self.enabled.iter().any(|enabled| enabled == id) {
+            return Err(format!("Datapack '{id}' is already enabled"));
+        }
+        let index_of = |other: &str| {
+            self.enabled
+                .iter()
+                .position(|enabled| enabled == other)
+                .ok_or_else(|| format!("Datapack '{other}' is not enabled"))
+        };
+        let index = match position {
+            // vanilla always loads first
+            PackPosition::First => 1,
+            PackPosition::Last => self.enabled.len(),
+            PackPosition::Before(other) => index_of(other)?.max(1),
+            PackPosition::After(other) => index_of(other)? + 1,
+        };
+        self.enabled.insert(index, id.to_string());
+        Ok(())
+    }
+
+    pub fn disable(&mut self, id: &str) -> Result<(), String> {
+        if id == VANILLA_PACK {
+            return Err("The vanilla datapack can't be disabled".to_string());
+        }
+        let index = self
+            .enabled
+            .iter()
+            .position(|enabled| enabled == id)
+            .ok_or_else(|| format!("Datapack '{id}' is not enabled"))?;
+        self.enabled.remove(index);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use super::{Datapack, Datapacks, PackInfo, PackMcmeta, PackPosition, DATAPACK_FORMAT};
+
+    fn pack(id: &str) -> Datapack {
+        Datapack {
+            id: id.to_string(),
+            path: PathBuf::from(id),
+            description: String::new(),
+            compatible: true,
+        }
+    }
+
+    fn info(json: &str) -> PackInfo {
+        serde_json::from_str::<PackMcmeta>(json).unwrap().pack
+    }
+
+    #[test]
+    fn supported_formats() {
+        assert!(info(&format!(
+            r#"{{"pack": {{"pack_format": {DATAPACK_FORMAT}}}}}"#
+        ))
+        .supports(DATAPACK_FORMAT));
+        assert!(!info(r#"{"pack": {"pack_format": 48}}"#).supports(DATAPACK_FORMAT));
+        assert!(
+            info(r#"{"pack": {"pack_format": 48, "supported_formats": [48, 60]}}"#)
+                .supports(DATAPACK_FORMAT)
+        );
+        assert!(info(
+            r#"{"pack": {"pack_format": 48, "supported_formats": {"min_inclusive": 48, "max_inclusive": 57}}}"#
+        )
+        .supports(DATAPACK_FORMAT));
+    }
+
+    #[test]
+    fn ordering() {
+        let available = vec![
+            pack("file/a"),
+            pack("file/b"),
+            pack("file/c"),
+            pack("file/new"),
+        ];
+        let enabled = [
+            "vanilla".to_string(),
+            "file/b".to_string(),
+            "file/gone".to_string(),
+        ];
+        let disabled = ["file/a".to_string(), "file/c".to_string()];
+        let mut packs = Datapacks::from_available(available, &enabled, &disabled);
+        assert_eq!(packs.enabled(), ["vanilla", "file/b", "file/new"]);
+        assert_eq!(packs.disabled(), ["file/a", "file/c"]);
+
+        packs.enable("file/a", &PackPosition::First).unwrap();
+        packs
+            .enable("file/c", &PackPosition::After("file/b"))
+            .unwrap();
+        assert_eq!(
+            packs.enabled(),
+            ["vanilla", "file/a", "file/b", "file/c", "file/new"]
+        );
+        assert!(packs.enable("file/a", &PackPosition::Last).is_err());
+        assert!(packs.disable("vanilla").is_err());
+
+        packs.disable("file/b").unwrap();
+        packs.disable("file/a").unwrap();
+        packs
+            .enable("file/b", &PackPosition::Before("file/new"))
+            .unwrap();
+        assert_eq!(packs.enabled(), ["vanilla", "file/c", "file/b", "file/new"]);
+        assert_eq!(
+            packs.enabled_folders(),
+            [
+                PathBuf::from("file/c"),
+                PathBuf::from("file/b"),
+                PathBuf::from("file/new")
+            ]
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use pumpkin_config::dimension_effects::DimensionTypeOverride;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +24,29 @@ pub struct Dimension {
     ultrawarm: u8,
 }
 
+impl Dimension {
+    pub fn apply_override(&mut self, dimension_override: &DimensionTypeOverride) {
+        if let Some(effects) = &dimension_override.effects {
+            match DimensionEffects::from_id(effects) {
+                Some(effects) => self.effects = effects,
+                None => log::warn!("Unknown dimension effects {effects}"),
+            }
+        }
+        if let Some(ambient_light) = dimension_override.ambient_light {
+            self.ambient_light = ambient_light;
+        }
+        if let Some(fixed_time) = dimension_override.fixed_time {
+            self.fixed_time = Some(fixed_time);
+        }
+        if let Some(has_skylight) = dimension_override.has_skylight {
+            self.has_skylight = has_skylight.into();
+        }
+        if let Some(has_ceiling) = dimension_override.has_ceiling {
+            self.has_ceiling = has_ceiling.into();
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Default, Debug)]
 pub enum DimensionEffects {
     #[serde(rename = "minecraft:overworld")]
@@ -34,6 +58,17 @@ pub enum DimensionEffects {
     TheEnd,
 }
 
+impl DimensionEffects {
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "minecraft:overworld" => Some(Self::Overworld),
+            "minecraft:the_nether" => Some(Self::TheNether),
+            "minecraft:the_end" => Some(Self::TheEnd),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum MonsterSpawnLightLevel {
@@ -1,7 +0,0 @@
-use serde::{Deserialize, Serialize};
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Enchantment {
-    // TODO: Add things :D
-    //  description: Text<'static>,
-}
@@ -0,0 +1,251 @@
+//! Item modifiers, which datapacks define in `data/<namespace>/item_modifier` and `/item modify`
+//! applies to stacks. Like predicates, files using functions Pumpkin doesn't know are skipped.
+
+use std::{collections::BTreeMap, sync::LazyLock};
+
+use pumpkin_core::nbt::{snbt, Value};
+use pumpkin_world::item::{
+    component::{namespaced, DataComponent, Enchantments},
+    ItemStack,
+};
+use serde::Deserialize;
+
+use crate::{
+    datapack::DatapackRegistry,
+    json_to_nbt,
+    predicate::{LootContext, NumberProvider, Predicate},
+};
+
+pub static ITEM_MODIFIERS: LazyLock<DatapackRegistry<ItemModifier>> =
+    LazyLock::new(|| DatapackRegistry::new("item_modifier"));
+
+/// A function changing a stack, which only runs if all its conditions pass
+#[derive(Deserialize, Clone, Debug)]
+pub struct ItemFunction {
+    #[serde(flatten)]
+    pub kind: ItemFunctionKind,
+    #[serde(default)]
+    pub conditions: Vec<Predicate>,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LoreMode {
+    Append,
+    #[default]
+    ReplaceAll,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(tag = "function")]
+pub enum ItemFunctionKind {
+    #[serde(rename = "minecraft:set_count", alias = "set_count")]
+    SetCount {
+        count: NumberProvider,
+        #[serde(default)]
+        add: bool,
+    },
+    /// Sets the durability left as a fraction of the maximum, only for stacks with a
+    /// `minecraft:max_damage` component
+    #[serde(rename = "minecraft:set_damage", alias = "set_damage")]
+    SetDamage {
+        damage: NumberProvider,
+        #[serde(default)]
+        add: bool,
+    },
+    #[serde(rename = "minecraft:set_name", alias = "set_name")]
+    SetName { name: serde_json::Value },
+    #[serde(rename = "minecraft:set_lore", alias = "set_lore")]
+    SetLore {
+        lore: Vec<serde_json::Value>,
+        #[serde(default)]
+        mode: LoreMode,
+    },
+    /// Sets components by their name, a `!` in front of the name removes the component
+    #[serde(rename = "minecraft:set_components", alias = "set_components")]
+    SetComponents {
+        components: serde_json::Map<String, serde_json::Value>,
+    },
+    /// Sets the `minecraft:custom_data` component, given as SNBT
+    #[serde(rename = "minecraft:set_custom_data", alias = "set_custom_data")]
+    SetCustomData { tag: String },
+    #[serde(rename = "minecraft:set_enchantments", alias = "set_enchantments")]
+    SetEnchantments {
+        enchantments: BTreeMap<String, NumberProvider>,
+        #[serde(default)]
+        add: bool,
+    },
+    #[serde(rename = "minecraft:sequence", alias = "sequence")]
+    Sequence { functions: Vec<ItemFunction> },
+}
+
+impl ItemFunction {
+    pub fn apply(&self, stack: &mut ItemStack, context: &LootContext) {
+        if !self
+            .conditions
+            .iter()
+            .all(|condition| condition.test(context))
+        {
+            return;
+        }
+        match &self.kind {
+            ItemFunctionKind::SetCount { count, add } => {
+                let base = if *add { i32::from(stack.item_count) } else { 0 };
+                stack.item_count = (base + count.get_int()).clamp(0, i32::from(u8::MAX)) as u8;
+            }
+            ItemFunctionKind::SetDamage { damage, add } => {
+                let Some(DataComponent::MaxDamage(max_damage)) =
+                    stack.components.get("minecraft:max_damage")
+                else {
+                    return;
+                };
+                let max_damage = *max_damage as f32;
+                let damage_now = match stack.components.get("minecraft:damage") {
+                    Some(DataComponent::Damage(damage)) => *damage as f32,
+                    _ => 0.0,
+                };
+                let base = if *add {
+                    1.0 - damage_now / max_damage
+                } else {
+                    0.0
+                };
+                let durability = (base + damage.get()).clamp(0.0, 1.0);
+                let damage = ((1.0 - durability) * max_damage).floor() as i32;
+                stack.components.set(DataComponent::Damage(damage));
+            }
+            ItemFunctionKind::SetName { name } => {
+                stack
+                    .components
+                    .set(DataComponent::CustomName(name.to_string()));
+            }
+            ItemFunctionKind::SetLore { lore, mode } => {
+                let mut lines = match (mode, stack.components.get("minecraft:lore")) {
+                    (LoreMode::Append, Some(DataComponent::Lore(lines))) => lines.clone(),
+                    _ => Vec::new(),
+                };
+                lines.extend(lore.iter().map(ToString::to_string));
+                stack.components.set(DataComponent::Lore(lines));
+            }
+            ItemFunctionKind::SetComponents { components } => {
+                for (name, value) in components {
+                    match name.strip_prefix('!') {
+                        Some(name) => stack.components.remove(name),
+                        None => {
+                            let name = namespaced(name);
+                            let component = DataComponent::from_nbt(&name, &json_to_nbt(value));
+                            stack.components.set_named(&name, component);
+                        }
+                    }
+                }
+            }
+            ItemFunctionKind::SetCustomData { tag } => match snbt::parse_compound(tag) {
+                Ok(data) => stack.components.set(DataComponent::CustomData(data)),
+                Err(err) => log::warn!("Invalid custom data {tag}: {err}"),
+            },
+            ItemFunctionKind::SetEnchantments { enchantments, add } => {
+                let mut current = match stack.components.get("minecraft:enchantments") {
+                    Some(DataComponent::Enchantments(current)) => current.clone(),
+                    _ => Enchantments {
+                        levels: BTreeMap::new(),
+                        show_in_tooltip: true,
+                    },
+                };
+                for (enchantment, level) in enchantments {
+                    let level_now = current.levels.entry(namespaced(enchantment)).or_default();
+                    *level_now = if *add { *level_now } else { 0 } + level.get_int();
+                }
+                current.levels.retain(|_, level| *level > 0);
+                stack.components.set(DataComponent::Enchantments(current));
+            }
+            ItemFunctionKind::Sequence { functions } => {
+                for function in functions {
+                    function.apply(stack, context);
+                }
+            }
+        }
+    }
+}
+
+/// An item modifier file, which may have a list of functions that run in order
+#[derive(Clone, Debug)]
+pub struct ItemModifier(pub ItemFunction);
+
+impl ItemModifier {
+    pub fn apply(&self, stack: &mut ItemStack, context: &LootContext) {
+        self.0.apply(stack, context);
+    }
+}
+
+impl<'de> Deserialize<'de> for ItemModifier {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum File {
+            Many(Vec<ItemFunction>),
+            One(ItemFunction),
+        }
+        Ok(Self(match File::deserialize(deserializer)? {
+            File::Many(functions) => ItemFunction {
+                kind: ItemFunctionKind::Sequence { functions },
+                conditions: Vec::new(),
+            },
+            File::One(function) => function,
+        }))
+    }
+}
+
+/// The value of a custom data entry, for checking modifiers in tests and plugins
+#[must_use]
+pub fn custom_data_value<'a>(stack: &'a ItemStack, key: &str) -> Option<&'a Value> {
+    match stack.components.get("minecraft:custom_data")? {
+        DataComponent::CustomData(data) => data.get(key),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_core::nbt::Value;
+    use pumpkin_world::item::{component::DataComponent, item_registry::get_item, ItemStack};
+
+    use super::{custom_data_value, ItemModifier};
+    use crate::predicate::LootContext;
+
+    #[test]
+    fn modify_stack() {
+        let modifier: ItemModifier = serde_json::from_str(
+            r#"[
+                {"function": "minecraft:set_count", "count": 5},
+                {"function": "minecraft:set_count", "count": 2, "add": true},
+                {"function": "set_name", "name": {"text": "Relic"}},
+                {"function": "set_lore", "lore": ["first"], "mode": "append"},
+                {"function": "set_custom_data", "tag": "{relic:1b}"},
+                {"function": "set_enchantments", "enchantments": {"sharpness": 3}},
+                {"function": "set_count", "count": 1,
+                 "conditions": [{"condition": "random_chance", "chance": 0.0}]}
+            ]"#,
+        )
+        .unwrap();
+        let mut stack = ItemStack::new(1, get_item("minecraft:diamond_sword").unwrap().id);
+        modifier.apply(&mut stack, &LootContext::default());
+
+        assert_eq!(stack.item_count, 7);
+        assert_eq!(
+            stack.components.get("minecraft:custom_name"),
+            Some(&DataComponent::CustomName(
+                r#"{"text":"Relic"}"#.to_string()
+            ))
+        );
+        assert_eq!(
+            stack.components.get("minecraft:lore"),
+            Some(&DataComponent::Lore(vec![r#""first""#.to_string()]))
+        );
+        assert_eq!(custom_data_value(&stack, "relic"), Some(&Value::Byte(1)));
+        let Some(DataComponent::Enchantments(enchantments)) =
+            stack.components.get("minecraft:enchantments")
+        else {
+            panic!("The stack should be enchanted");
+        };
+        assert_eq!(enchantments.levels["minecraft:sharpness"], 3);
+    }
+}
@@ -1,40 +1,54 @@
-use std::{collections::HashMap, sync::LazyLock};
+use std::{collections::BTreeMap, path::PathBuf, sync::LazyLock};
 
 use banner_pattern::BannerPattern;
 use biome::Biome;
 use chat_type::ChatType;
-use damage_type::DamageType;
+pub use damage_type::{get_damage_type, DamageType};
+pub use datapack::{
+    Datapack, DatapackRegistry, Datapacks, PackPosition, DATAPACK_FORMAT, VANILLA_PACK,
+};
 use dimension::Dimension;
-use enchantment::Enchantment;
-use fastnbt::SerOpts;
-use instrument::Instrument;
-use jukebox_song::JukeboxSong;
-use paint::Painting;
+use fastnbt::{SerOpts, Value};
+pub use item_modifier::{ItemFunction, ItemFunctionKind, ItemModifier, ITEM_MODIFIERS};
+pub use particle::get_particle_id;
+pub use predicate::{
+    EntityContext, EntityPredicate, ItemPredicate, LootContext, NumberProvider, Predicate,
+    PredicateFile, PREDICATES,
+};
+use pumpkin_config::dimension_effects::DimensionEffectsConfig;
 use pumpkin_protocol::client::config::RegistryEntry;
 pub use recipe::{
-    flatten_3x3, IngredientSlot, IngredientType, Recipe, RecipeResult, RecipeType, RECIPES,
+    flatten_3x3, load_recipes, recipes, IngredientSlot, IngredientType, Recipe, RecipeResult,
+    RecipeType,
 };
-use serde::{Deserialize, Serialize};
-pub use tags::{get_tag_values, TagCategory, TagType};
-use trim_material::TrimMaterial;
-use trim_pattern::TrimPattern;
+use serde::{Deserialize, Deserializer, Serialize};
+pub use sound::get_sound_id;
+pub use tags::{get_tag_values, is_in, load_tags, network_tags, TagCategory, TagType};
 use wolf::WolfVariant;
 
 mod banner_pattern;
 mod biome;
 mod chat_type;
 mod damage_type;
+mod datapack;
 mod dimension;
-mod enchantment;
-mod instrument;
-mod jukebox_song;
-mod paint;
+mod item_modifier;
+mod particle;
+mod predicate;
 mod recipe;
+mod sound;
 mod tags;
-mod trim_material;
-mod trim_pattern;
 mod wolf;
 
+/// Reloads everything datapacks can add from the given datapack directories, in the order they
+/// are enabled
+pub fn load_datapack_content(pack_dirs: &[PathBuf]) {
+    load_tags(pack_dirs);
+    load_recipes(pack_dirs);
+    PREDICATES.load(pack_dirs);
+    ITEM_MODIFIERS.load(pack_dirs);
+}
+
 pub static SYNCED_REGISTRIES: LazyLock<SyncedRegistry> = LazyLock::new(|| {
     serde_json::from_str(include_str!("../../assets/synced_registries.json"))
         .expect("Could not parse synced_registries.json registry.")
@@ -45,124 +59,148 @@ pub struct Registry {
     pub registry_entries: Vec<RegistryEntry<'static>>,
 }
 
+/// An entry which is sent like vanilla reports it, for registries Pumpkin doesn't use itself
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+pub struct RawEntry(Value);
+
+impl<'de> Deserialize<'de> for RawEntry {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        serde_json::Value::deserialize(deserializer).map(|json| Self(json_to_nbt(&json)))
+    }
+}
+
+fn json_to_nbt(json: &serde_json::Value) -> Value {
+    match json {
+        serde_json::Value::Null => Value::Compound(Default::default()),
+        serde_json::Value::Bool(value) => Value::Byte(i8::from(*value)),
+        serde_json::Value::Number(number) => match number.as_i64() {
+            Some(number) => i32::try_from(number).map_or(Value::Long(number), Value::Int),
+            None => Value::Double(number.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::String(value) => Value::String(value.clone()),
+        serde_json::Value::Array(list) => Value::List(list.iter().map(json_to_nbt).collect()),
+        serde_json::Value::Object(object) => Value::Compound(
+            object
+                .iter()
+                .map(|(key, value)| (key.clone(), json_to_nbt(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// Every registry is sorted, which is the order the client gives the entries their ids in. So
+/// the ids are always the same and other crates can know them, see
+/// `pumpkin_macros::registry_entries!`
 #[derive(Serialize, Deserialize)]
 pub struct SyncedRegistry {
     #[serde(rename = "minecraft:worldgen/biome")]
-    biome: HashMap<String, Biome>,
+    biome: BTreeMap<String, Biome>,
     #[serde(rename = "minecraft:chat_type")]
-    chat_type: HashMap<String, ChatType>,
+    chat_type: BTreeMap<String, ChatType>,
     #[serde(rename = "minecraft:trim_pattern")]
-    trim_pattern: HashMap<String, TrimPattern>,
+    trim_pattern: BTreeMap<String, RawEntry>,
     #[serde(rename = "minecraft:trim_material")]
-    trim_material: HashMap<String, TrimMaterial>,
+    trim_material: BTreeMap<String, RawEntry>,
     #[serde(rename = "minecraft:wolf_variant")]
-    wolf_variant: HashMap<String, WolfVariant>,
+    wolf_variant: BTreeMap<String, WolfVariant>,
     #[serde(rename = "minecraft:painting_variant")]
-    painting_variant: HashMap<String, Painting>,
+    painting_variant: BTreeMap<String, RawEntry>,
     #[serde(rename = "minecraft:dimension_type")]
-    dimension_type: HashMap<String, Dimension>,
+    dimension_type: BTreeMap<String, Dimension>,
     #[serde(rename = "minecraft:damage_type")]
-    damage_type: HashMap<String, DamageType>,
+    damage_type: BTreeMap<String, DamageType>,
     #[serde(rename = "minecraft:banner_pattern")]
-    banner_pattern: HashMap<String, BannerPattern>,
+    banner_pattern: BTreeMap<String, BannerPattern>,
     #[serde(rename = "minecraft:enchantment")]
-    enchantment: HashMap<String, Enchantment>,
+    enchantment: BTreeMap<String, RawEntry>,
     #[serde(rename = "minecraft:jukebox_song")]
-    jukebox_song: HashMap<String, JukeboxSong>,
+    jukebox_song: BTreeMap<String, RawEntry>,
     #[serde(rename = "minecraft:instrument")]
-    instrument: HashMap<String, Instrument>,
+    instrument: BTreeMap<String, RawEntry>,
 }
 
-impl Registry {
-    pub fn get_synced() -> Vec<Self> {
-        let registry_entries = SYNCED_REGISTRIES
-            .biome
-            .iter()
-            .map(|s| RegistryEntry {
-                entry_id: s.0,
-                data: fastnbt::to_bytes_with_opts(&s.1, SerOpts::network_nbt()).unwrap(),
-            })
-            .collect();
-        let biome = Registry {
-            registry_id: "minecraft:worldgen/biome".to_string(),
-            registry_entries,
+impl SyncedRegistry {
+    /// The names of the registry's entries, in the order of their ids
+    pub fn entries(&self, registry_id: &str) -> Option<Vec<&str>> {
+        fn names<T>(registry: &BTreeMap<String, T>) -> Vec<&str> {
+            registry.keys().map(String::as_str).collect()
+        }
+        let names = match registry_id {
+            "minecraft:worldgen/biome" => names(&self.biome),
+            "minecraft:chat_type" => names(&self.chat_type),
+            "minecraft:trim_pattern" => names(&self.trim_pattern),
+            "minecraft:trim_material" => names(&self.trim_material),
+            "minecraft:wolf_variant" => names(&self.wolf_variant),
+            "minecraft:painting_variant" => names(&self.painting_variant),
+            "minecraft:dimension_type" => names(&self.dimension_type),
+            "minecraft:damage_type" => names(&self.damage_type),
+            "minecraft:banner_pattern" => names(&self.banner_pattern),
+            "minecraft:enchantment" => names(&self.enchantment),
+            "minecraft:jukebox_song" => names(&self.jukebox_song),
+            "minecraft:instrument" => names(&self.instrument),
+            _ => return None,
         };
+        Some(names)
+    }
+}
 
-        let registry_entries = SYNCED_REGISTRIES
-            .chat_type
+impl Registry {
+    fn new<T: Serialize>(registry_id: &str, entries: &'static BTreeMap<String, T>) -> Self {
+        let registry_entries = entries
             .iter()
             .map(|s| RegistryEntry {
                 entry_id: s.0,
                 data: fastnbt::to_bytes_with_opts(&s.1, SerOpts::network_nbt()).unwrap(),
             })
             .collect();
-        let chat_type = Registry {
-            registry_id: "minecraft:chat_type".to_string(),
+        Self {
+            registry_id: registry_id.to_string(),
             registry_entries,
-        };
-
-        // let registry_entries = SYNCED_REGISTRIES
-        //     .trim_pattern
-        //     .iter()
-        //     .map(|s| RegistryEntry {
-        //         entry_id: s.0,
-        //         data: fastnbt::to_bytes_with_opts(&s.1, SerOpts::network_nbt()).unwrap(),
-        //     })
-        //     .collect();
-        // let trim_pattern = Registry {
-        //     registry_id: "minecraft:trim_pattern".to_string(),
-        //     registry_entries,
-        // };
-
-        // let registry_entries = SYNCED_REGISTRIES
-        //     .trim_material
-        //     .iter()
-        //     .map(|s| RegistryEntry {
-        //         entry_id: s.0,
-        //         data: fastnbt::to_bytes_with_opts(&s.1, SerOpts::network_nbt()).unwrap(),
-        //     })
-        //     .collect();
-        // let trim_material = Registry {
-        //     registry_id: "minecraft:trim_material".to_string(),
-        //     registry_entries,
-        // };
+        }
+    }
 
+    /// Returns the registries synced to clients, with the dimension types and biomes overridden.
+    pub fn get_synced(overrides: &DimensionEffectsConfig) -> Vec<Self> {
         let registry_entries = SYNCED_REGISTRIES
-            .wolf_variant
+            .biome
             .iter()
             .map(|s| {
-                // I present to you, A ugly hack which is done because Mojang developers decited to put is_<biome> instead of just <biome> on 3 wolf varients while all others have just the biome, this causes the client to not find the biome and disconnect
-                let varient = s.1.clone();
+                let data = match overrides.biomes.get(s.0) {
+                    Some(effects_override) => {
+                        let mut biome = s.1.clone();
+                        biome.apply_override(effects_override);
+                        fastnbt::to_bytes_with_opts(&biome, SerOpts::network_nbt())
+                    }
+                    None => fastnbt::to_bytes_with_opts(&s.1, SerOpts::network_nbt()),
+                };
                 RegistryEntry {
                     entry_id: s.0,
-                    data: fastnbt::to_bytes_with_opts(&varient, SerOpts::network_nbt()).unwrap(),
+                    data: data.unwrap(),
                 }
             })
             .collect();
-        let wolf_variant = Registry {
-            registry_id: "minecraft:wolf_variant".to_string(),
-            registry_entries,
-        };
-
-        let registry_entries = SYNCED_REGISTRIES
-            .painting_variant
-            .iter()
-            .map(|s| RegistryEntry {
-                entry_id: s.0,
-                data: fastnbt::to_bytes_with_opts(&s.1, SerOpts::network_nbt()).unwrap(),
-            })
-            .collect();
-        let painting_variant = Registry {
-            registry_id: "minecraft:painting_variant".to_string(),
+        let biome = Registry {
+            registry_id: "minecraft:worldgen/biome".to_string(),
             registry_entries,
         };
 
         let registry_entries = SYNCED_REGISTRIES
             .dimension_type
             .iter()
-            .map(|s| RegistryEntry {
-                entry_id: s.0,
-                data: fastnbt::to_bytes_with_opts(&s.1, SerOpts::network_nbt()).unwrap(),
+            .map(|s| {
+                let data = match overrides.dimension_types.get(s.0) {
+                    Some(dimension_override) => {
+                        let mut dimension = s.1.clone();
+                        dimension.apply_override(dimension_override);
+                        fastnbt::to_bytes_with_opts(&dimension, SerOpts::network_nbt())
+                    }
+                    None => fastnbt::to_bytes_with_opts(&s.1, SerOpts::network_nbt()),
+                };
+                RegistryEntry {
+                    entry_id: s.0,
+                    data: data.unwrap(),
+                }
             })
             .collect();
         let dimension_type = Registry {
@@ -170,85 +208,45 @@ impl Registry {
             registry_entries,
         };
 
-        let registry_entries = SYNCED_REGISTRIES
-            .damage_type
-            .iter()
-            .map(|s| RegistryEntry {
-                entry_id: s.0,
-                data: fastnbt::to_bytes_with_opts(&s.1, SerOpts::network_nbt()).unwrap(),
-            })
-            .collect();
-        let damage_type = Registry {
-            registry_id: "minecraft:damage_type".to_string(),
-            registry_entries,
-        };
-
-        let registry_entries = SYNCED_REGISTRIES
-            .banner_pattern
-            .iter()
-            .map(|s| RegistryEntry {
-                entry_id: s.0,
-                data: fastnbt::to_bytes_with_opts(&s.1, SerOpts::network_nbt()).unwrap(),
-            })
-            .collect();
-        let banner_pattern = Registry {
-            registry_id: "minecraft:banner_pattern".to_string(),
-            registry_entries,
-        };
-
-        // TODO
-        // let registry_entries = SYNCED_REGISTRIES
-        //     .enchantment
-        //     .iter()
-        //     .map(|s| RegistryEntry {
-        //         entry_id: s.0,
-        //         data: fastnbt::to_bytes_with_opts(&s.1, SerOpts::network_nbt()).unwrap(),
-        //     })
-        //     .collect();
-        // let enchantment = Registry {
-        //     registry_id: "minecraft:enchantment".to_string(),
-        //     registry_entries,
-        // };
-
-        // let registry_entries = SYNCED_REGISTRIES
-        //     .jukebox_song
-        //     .iter()
-        //     .map(|s| RegistryEntry {
-        //         entry_id: s.0,
-        //         data: fastnbt::to_bytes_with_opts(&s.1, SerOpts::network_nbt()).unwrap(),
-        //     })
-        //     .collect();
-        // let jukebox_song = Registry {
-        //     registry_id: "minecraft:jukebox_song".to_string(),
-        //     registry_entries,
-        // };
-
-        // let registry_entries = SYNCED_REGISTRIES
-        //     .instrument
-        //     .iter()
-        //     .map(|s| RegistryEntry {
-        //         entry_id: s.0,
-        //         data: fastnbt::to_bytes_with_opts(&s.1, SerOpts::network_nbt()).unwrap(),
-        //     })
-        //     .collect();
-        // let instrument = Registry {
-        //     registry_id: "minecraft:instrument".to_string(),
-        //     registry_entries,
-        // };
-
+        let registries = &*SYNCED_REGISTRIES;
         vec![
             biome,
-            chat_type,
-            // trim_pattern,
-            // trim_material,
-            wolf_variant,
-            painting_variant,
+            Self::new("minecraft:chat_type", &registries.chat_type),
+            Self::new("minecraft:trim_pattern", &registries.trim_pattern),
+            Self::new("minecraft:trim_material", &registries.trim_material),
+            Self::new("minecraft:wolf_variant", &registries.wolf_variant),
+            Self::new("minecraft:painting_variant", &registries.painting_variant),
             dimension_type,
-            damage_type,
-            banner_pattern,
-            // enchantment,
-            // jukebox_song,
-            // instrument,
+            Self::new("minecraft:damage_type", &registries.damage_type),
+            Self::new("minecraft:banner_pattern", &registries.banner_pattern),
+            Self::new("minecraft:enchantment", &registries.enchantment),
+            Self::new("minecraft:jukebox_song", &registries.jukebox_song),
+            Self::new("minecraft:instrument", &registries.instrument),
         ]
     }
 }
+
+#[cfg(test)]
+mod test {
+    use pumpkin_world::item::component::ENCHANTMENTS;
+
+    use super::{get_damage_type, SYNCED_REGISTRIES};
+
+    #[test]
+    fn damage_types_by_name() {
+        let (id, arrow) = get_damage_type("minecraft:arrow").unwrap();
+        assert_eq!(id, 0);
+        assert_eq!(arrow.message_id(), "arrow");
+        let (_, fall) = get_damage_type("minecraft:fall").unwrap();
+        assert_eq!(fall.death_message_type(), Some("fall_variants"));
+        assert!(get_damage_type("fall").is_none());
+    }
+
+    #[test]
+    fn enchantment_ids_match_items() {
+        assert_eq!(
+            SYNCED_REGISTRIES.entries("minecraft:enchantment").unwrap(),
+            ENCHANTMENTS
+        );
+    }
+}
@@ -0,0 +1,16 @@
+use std::{collections::HashMap, sync::LazyLock};
+
+/// The ids of `minecraft:particle_type` by name, for particles only known at runtime like in `/particle`
+static PARTICLES: LazyLock<HashMap<String, u16>> = LazyLock::new(|| {
+    serde_json::from_str(include_str!("../../assets/particles.json"))
+        .expect("Could not parse particles.json registry.")
+});
+
+/// Returns the id of the particle, the `minecraft:` namespace may be omitted.
+pub fn get_particle_id(name: &str) -> Option<u16> {
+    if name.contains(':') {
+        PARTICLES.get(name).copied()
+    } else {
+        PARTICLES.get(&format!("minecraft:{name}")).copied()
+    }
+}
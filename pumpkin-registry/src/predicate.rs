@@ -0,0 +1,367 @@
+//! Predicates, which datapacks define in `data/<namespace>/predicate` and loot tables use as
+//! conditions. Only the conditions Pumpkin can check are supported, files using others are
+//! skipped when they are loaded.
+
+use std::sync::LazyLock;
+
+use pumpkin_core::math::vector3::Vector3;
+use pumpkin_world::item::{component::namespaced, item_registry::get_item_name, ItemStack};
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::{datapack::DatapackRegistry, is_in, TagCategory};
+
+pub static PREDICATES: LazyLock<DatapackRegistry<PredicateFile>> =
+    LazyLock::new(|| DatapackRegistry::new("predicate"));
+
+/// How deep predicates may reference other predicates, so ones referencing themselves end
+const MAX_REFERENCE_DEPTH: usize = 16;
+
+/// What predicates and item modifiers are checked against. Conditions about something the
+/// context doesn't know fail
+#[derive(Clone, Debug, Default)]
+pub struct LootContext {
+    /// The time of day in ticks
+    pub day_time: i64,
+    pub raining: bool,
+    pub thundering: bool,
+    pub origin: Option<Vector3<f64>>,
+    /// The entity the context is about, vanilla's `this`
+    pub this_entity: Option<EntityContext>,
+    /// Whether a player killed [`Self::this_entity`]
+    pub killed_by_player: bool,
+    /// The item used, e.g. to break a block
+    pub tool: Option<ItemStack>,
+    pub explosion_radius: Option<f32>,
+}
+
+#[derive(Clone, Debug)]
+pub struct EntityContext {
+    /// e.g. `minecraft:zombie`
+    pub entity_type: String,
+    pub on_fire: bool,
+    pub sneaking: bool,
+    pub sprinting: bool,
+    pub swimming: bool,
+    pub baby: bool,
+}
+
+/// A number which may be random, like vanilla's number providers
+#[derive(Deserialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum NumberProvider {
+    Constant(f32),
+    Typed(TypedNumberProvider),
+    /// `{min, max}` without a type is uniform
+    Uniform {
+        min: f32,
+        max: f32,
+    },
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(tag = "type")]
+pub enum TypedNumberProvider {
+    #[serde(rename = "minecraft:constant", alias = "constant")]
+    Constant { value: f32 },
+    #[serde(rename = "minecraft:uniform", alias = "uniform")]
+    Uniform { min: f32, max: f32 },
+    #[serde(rename = "minecraft:binomial", alias = "binomial")]
+    Binomial { n: u32, p: f32 },
+}
+
+impl NumberProvider {
+    #[must_use]
+    pub fn get(&self) -> f32 {
+        let uniform = |min: f32, max: f32| {
+            if min < max {
+                rand::thread_rng().gen_range(min..max)
+            } else {
+                min
+            }
+        };
+        match *self {
+            Self::Constant(value) | Self::Typed(TypedNumberProvider::Constant { value }) => value,
+            Self::Uniform { min, max } | Self::Typed(TypedNumberProvider::Uniform { min, max }) => {
+                uniform(min, max)
+            }
+            Self::Typed(TypedNumberProvider::Binomial { n, p }) => {
+                let mut rng = rand::thread_rng();
+                (0..n).filter(|_| rng.gen::<f32>() < p).count() as f32
+            }
+        }
+    }
+
+    /// Rounds like vanilla does for whole numbers
+    #[must_use]
+    pub fn get_int(&self) -> i32 {
+        self.get().round() as i32
+    }
+}
+
+/// An exact value or an inclusive range where both ends are optional
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(untagged)]
+pub enum IntRange {
+    Exact(i64),
+    Range { min: Option<i64>, max: Option<i64> },
+}
+
+impl IntRange {
+    #[must_use]
+    pub fn contains(self, value: i64) -> bool {
+        match self {
+            Self::Exact(exact) => value == exact,
+            Self::Range { min, max } => {
+                min.is_none_or(|min| value >= min) && max.is_none_or(|max| value <= max)
+            }
+        }
+    }
+}
+
+/// An item id, a `#tag` or a list of ids
+#[derive(Deserialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum ItemList {
+    Single(String),
+    Many(Vec<String>),
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct ItemPredicate {
+    pub items: Option<ItemList>,
+    pub count: Option<IntRange>,
+}
+
+impl ItemPredicate {
+    #[must_use]
+    pub fn test(&self, stack: &ItemStack) -> bool {
+        if let Some(items) = &self.items {
+            let Some(name) = get_item_name(stack.item_id) else {
+                return false;
+            };
+            let matches = |item: &String| match item.strip_prefix('#') {
+                Some(tag) => is_in(TagCategory::Item, tag, name),
+                None => namespaced(item) == name,
+            };
+            let matches = match items {
+                ItemList::Single(item) => matches(item),
+                ItemList::Many(items) => items.iter().any(matches),
+            };
+            if !matches {
+                return false;
+            }
+        }
+        self.count
+            .is_none_or(|count| count.contains(i64::from(stack.item_count)))
+    }
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct EntityFlags {
+    pub is_on_fire: Option<bool>,
+    pub is_sneaking: Option<bool>,
+    pub is_sprinting: Option<bool>,
+    pub is_swimming: Option<bool>,
+    pub is_baby: Option<bool>,
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct EntityPredicate {
+    /// An entity type or a `#tag`
+    #[serde(rename = "type")]
+    pub entity_type: Option<String>,
+    pub flags: Option<EntityFlags>,
+}
+
+impl EntityPredicate {
+    #[must_use]
+    pub fn test(&self, entity: &EntityContext) -> bool {
+        if let Some(entity_type) = &self.entity_type {
+            let matches = match entity_type.strip_prefix('#') {
+                Some(tag) => is_in(TagCategory::Entity, tag, &entity.entity_type),
+                None => namespaced(entity_type) == entity.entity_type,
+            };
+            if !matches {
+                return false;
+            }
+        }
+        let Some(flags) = &self.flags else {
+            return true;
+        };
+        [
+            (flags.is_on_fire, entity.on_fire),
+            (flags.is_sneaking, entity.sneaking),
+            (flags.is_sprinting, entity.sprinting),
+            (flags.is_swimming, entity.swimming),
+            (flags.is_baby, entity.baby),
+        ]
+        .into_iter()
+        .all(|(expected, actual)| expected.is_none_or(|expected| expected == actual))
+    }
+}
+
+/// A loot condition
+#[derive(Deserialize, Clone, Debug)]
+#[serde(tag = "condition")]
+pub enum Predicate {
+    #[serde(rename = "minecraft:all_of", alias = "all_of")]
+    AllOf { terms: Vec<Predicate> },
+    #[serde(rename = "minecraft:any_of", alias = "any_of")]
+    AnyOf { terms: Vec<Predicate> },
+    #[serde(rename = "minecraft:inverted", alias = "inverted")]
+    Inverted { term: Box<Predicate> },
+    /// Checks the predicate with the name from a datapack
+    #[serde(rename = "minecraft:reference", alias = "reference")]
+    Reference { name: String },
+    #[serde(rename = "minecraft:random_chance", alias = "random_chance")]
+    RandomChance { chance: NumberProvider },
+    #[serde(rename = "minecraft:weather_check", alias = "weather_check")]
+    WeatherCheck {
+        raining: Option<bool>,
+        thundering: Option<bool>,
+    },
+    /// Checks the time of day, `period` wraps it, e.g. 24000 for days
+    #[serde(rename = "minecraft:time_check", alias = "time_check")]
+    TimeCheck {
+        value: IntRange,
+        period: Option<i64>,
+    },
+    #[serde(rename = "minecraft:killed_by_player", alias = "killed_by_player")]
+    KilledByPlayer,
+    /// Passes with a chance of 1 / explosion radius, always without an explosion
+    #[serde(rename = "minecraft:survives_explosion", alias = "survives_explosion")]
+    SurvivesExplosion,
+    #[serde(rename = "minecraft:entity_properties", alias = "entity_properties")]
+    EntityProperties { predicate: EntityPredicate },
+    #[serde(rename = "minecraft:match_tool", alias = "match_tool")]
+    MatchTool { predicate: ItemPredicate },
+}
+
+impl Predicate {
+    #[must_use]
+    pub fn test(&self, context: &LootContext) -> bool {
+        self.test_nested(context, 0)
+    }
+
+    fn test_nested(&self, context: &LootContext, depth: usize) -> bool {
+        match self {
+            Self::AllOf { terms } => terms.iter().all(|term| term.test_nested(context, depth)),
+            Self::AnyOf { terms } => terms.iter().any(|term| term.test_nested(context, depth)),
+            Self::Inverted { term } => !term.test_nested(context, depth),
+            Self::Reference { name } => {
+                if depth >= MAX_REFERENCE_DEPTH {
+                    log::warn!("Predicate {name} is referenced too deep, it may reference itself");
+                    return false;
+                }
+                PREDICATES
+                    .get(name)
+                    .is_some_and(|predicate| predicate.0.test_nested(context, depth + 1))
+            }
+            Self::RandomChance { chance } => rand::thread_rng().gen::<f32>() < chance.get(),
+            Self::WeatherCheck {
+                raining,
+                thundering,
+            } => {
+                raining.is_none_or(|raining| raining == context.raining)
+                    && thundering.is_none_or(|thundering| thundering == context.thundering)
+            }
+            Self::TimeCheck { value, period } => {
+                let time = match period {
+                    Some(period) if *period > 0 => context.day_time.rem_euclid(*period),
+                    _ => context.day_time,
+                };
+                value.contains(time)
+            }
+            Self::KilledByPlayer => context.killed_by_player,
+            Self::SurvivesExplosion => context
+                .explosion_radius
+                .is_none_or(|radius| rand::thread_rng().gen::<f32>() <= 1.0 / radius),
+            Self::EntityProperties { predicate } => context
+                .this_entity
+                .as_ref()
+                .is_some_and(|entity| predicate.test(entity)),
+            Self::MatchTool { predicate } => context
+                .tool
+                .as_ref()
+                .is_some_and(|tool| predicate.test(tool)),
+        }
+    }
+}
+
+/// A predicate file, which may have a list of predicates that all have to pass
+#[derive(Clone, Debug)]
+pub struct PredicateFile(pub Predicate);
+
+impl<'de> Deserialize<'de> for PredicateFile {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum File {
+            Many(Vec<Predicate>),
+            One(Predicate),
+        }
+        Ok(Self(match File::deserialize(deserializer)? {
+            File::Many(terms) => Predicate::AllOf { terms },
+            File::One(predicate) => predicate,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_world::item::{item_registry::get_item, ItemStack};
+
+    use super::{EntityContext, LootContext, PredicateFile};
+
+    fn predicate(json: &str) -> PredicateFile {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn conditions() {
+        let mut context = LootContext {
+            day_time: 25_000,
+            raining: true,
+            this_entity: Some(EntityContext {
+                entity_type: "minecraft:zombie".to_string(),
+                on_fire: true,
+                sneaking: false,
+                sprinting: false,
+                swimming: false,
+                baby: false,
+            }),
+            ..Default::default()
+        };
+        let night_rain = predicate(
+            r#"[
+                {"condition": "minecraft:weather_check", "raining": true},
+                {"condition": "minecraft:time_check", "value": {"min": 0, "max": 2000}, "period": 24000}
+            ]"#,
+        );
+        assert!(night_rain.0.test(&context));
+        context.raining = false;
+        assert!(!night_rain.0.test(&context));
+
+        let burning_zombie = predicate(
+            r#"{"condition": "entity_properties", "entity": "this",
+                "predicate": {"type": "zombie", "flags": {"is_on_fire": true}}}"#,
+        );
+        assert!(burning_zombie.0.test(&context));
+        let not_burning = predicate(
+            r#"{"condition": "inverted", "term": {"condition": "entity_properties",
+                "predicate": {"flags": {"is_on_fire": true}}}}"#,
+        );
+        assert!(!not_burning.0.test(&context));
+
+        let with_stone = predicate(
+            r#"{"condition": "match_tool", "predicate": {"items": ["minecraft:stone"], "count": {"min": 2}}}"#,
+        );
+        assert!(!with_stone.0.test(&context));
+        context.tool = Some(ItemStack::new(3, get_item("minecraft:stone").unwrap().id));
+        assert!(with_stone.0.test(&context));
+
+        let never = predicate(r#"{"condition": "random_chance", "chance": 0.0}"#);
+        assert!(!never.0.test(&context));
+    }
+}
@@ -4,7 +4,14 @@ mod recipe_formats;
 pub use read::{
     ingredients::IngredientSlot, ingredients::IngredientType, Recipe, RecipeResult, RecipeType,
 };
-use std::sync::LazyLock;
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    sync::{Arc, LazyLock, RwLock},
+};
+
+use crate::datapack::read_pack_files;
+
 pub fn flatten_3x3<T: Clone>(input: [[Option<T>; 3]; 3]) -> [[Option<T>; 3]; 3] {
     let mut final_output = [const { [const { None }; 3] }; 3];
 
@@ -40,13 +47,34 @@ pub fn flatten_3x3<T: Clone>(input: [[Option<T>; 3]; 3]) -> [[Option<T>; 3]; 3]
 
     final_output
 }
-pub static RECIPES: LazyLock<Vec<Recipe>> =
-    LazyLock::new(|| serde_json::from_str(include_str!("../../../assets/recipes.json")).unwrap());
+static RECIPES: LazyLock<RwLock<Arc<Vec<Recipe>>>> =
+    LazyLock::new(|| RwLock::new(Arc::new(vanilla_recipes())));
+
+fn vanilla_recipes() -> Vec<Recipe> {
+    serde_json::from_str(include_str!("../../../assets/recipes.json")).unwrap()
+}
+
+/// The vanilla recipes and the ones of the enabled datapacks
+pub fn recipes() -> Arc<Vec<Recipe>> {
+    RECIPES.read().expect("Recipes lock is poisoned").clone()
+}
+
+/// Replaces the datapack recipes with the ones of the packs. The vanilla recipes have no ids, so
+/// datapacks can only add recipes, a pack overrides the recipes of earlier packs with the same id
+pub fn load_recipes(pack_dirs: &[PathBuf]) {
+    let mut by_id = BTreeMap::new();
+    for pack in pack_dirs {
+        by_id.extend(read_pack_files::<Recipe>(pack, "recipe"));
+    }
+    let mut recipes = vanilla_recipes();
+    recipes.extend(by_id.into_values());
+    *RECIPES.write().expect("Recipes lock is poisoned") = Arc::new(recipes);
+}
 
 #[cfg(test)]
 mod test {
     use super::flatten_3x3;
-    use crate::RECIPES;
+    use crate::recipes;
 
     #[test]
     fn row_flatten() {
@@ -87,6 +115,6 @@ mod test {
     #[test]
     // This makes sure that all recipes are able deserialized properly
     fn check_parsing() {
-        assert!(!RECIPES.is_empty())
+        assert!(!recipes().is_empty())
     }
 }
@@ -0,0 +1,16 @@
+use std::{collections::HashMap, sync::LazyLock};
+
+/// The ids of `minecraft:sound_event` by name, for sounds only known at runtime like in `/playsound`
+static SOUNDS: LazyLock<HashMap<String, u16>> = LazyLock::new(|| {
+    serde_json::from_str(include_str!("../../assets/sounds.json"))
+        .expect("Could not parse sounds.json registry.")
+});
+
+/// Returns the id of the sound, the `minecraft:` namespace may be omitted.
+pub fn get_sound_id(name: &str) -> Option<u16> {
+    if name.contains(':') {
+        SOUNDS.get(name).copied()
+    } else {
+        SOUNDS.get(&format!("minecraft:{name}")).copied()
+    }
+}
@@ -1,12 +1,16 @@
+use pumpkin_macros::registry_entries;
+use pumpkin_protocol::{client::config::RegistryTags, VarInt};
+use pumpkin_world::{block::block_registry::BLOCKS, item::item_registry::ITEMS};
 use serde::de::{Error, Visitor};
 use serde::{Deserialize, Deserializer};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt::Formatter;
-use std::sync::LazyLock;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, RwLock};
 
-use crate::IngredientType;
+use crate::{datapack::read_pack_files, IngredientType, SYNCED_REGISTRIES};
 
-#[derive(Deserialize, Eq, PartialEq, Hash)]
+#[derive(Deserialize, Eq, PartialEq, Hash, Clone, Copy, Debug)]
 pub enum TagCategory {
     #[serde(rename = "minecraft:instrument")]
     Instrument,
@@ -36,22 +40,108 @@ pub enum TagCategory {
     GameEvent,
 }
 
-pub static TAGS: LazyLock<HashMap<TagCategory, HashMap<String, Vec<TagType>>>> =
-    LazyLock::new(|| {
-        let mut map = HashMap::new();
-        let tags_str = include_str!("../../assets/tags.json");
-        let tags: Vec<TagCollection> =
-            serde_json::from_str(tags_str).expect("Valid tag collections");
-        for tag in tags {
-            map.insert(tag.name, tag.values);
+impl TagCategory {
+    pub const ALL: [Self; 13] = [
+        Self::Instrument,
+        Self::WorldGenBiome,
+        Self::PointOfInterest,
+        Self::Entity,
+        Self::DamageType,
+        Self::BannerPattern,
+        Self::Block,
+        Self::Fluid,
+        Self::Enchantment,
+        Self::Cat,
+        Self::Painting,
+        Self::Item,
+        Self::GameEvent,
+    ];
+
+    pub const fn registry_id(self) -> &'static str {
+        match self {
+            Self::Instrument => "minecraft:instrument",
+            Self::WorldGenBiome => "minecraft:worldgen/biome",
+            Self::PointOfInterest => "minecraft:point_of_interest_type",
+            Self::Entity => "minecraft:entity_type",
+            Self::DamageType => "minecraft:damage_type",
+            Self::BannerPattern => "minecraft:banner_pattern",
+            Self::Block => "minecraft:block",
+            Self::Fluid => "minecraft:fluid",
+            Self::Enchantment => "minecraft:enchantment",
+            Self::Cat => "minecraft:cat_variant",
+            Self::Painting => "minecraft:painting_variant",
+            Self::Item => "minecraft:item",
+            Self::GameEvent => "minecraft:game_event",
+        }
+    }
+
+    /// The network ids of the registry entries, `None` if we don't know them
+    fn network_ids(self) -> Option<HashMap<String, i32>> {
+        fn enumerate<'a>(names: impl IntoIterator<Item = &'a str>) -> HashMap<String, i32> {
+            (0..)
+                .zip(names)
+                .map(|(id, name)| (name.to_string(), id))
+                .collect()
         }
-        map
-    });
+        match self {
+            Self::Block => Some(
+                BLOCKS
+                    .blocks
+                    .iter()
+                    .map(|block| (block.name.clone(), i32::from(block.id)))
+                    .collect(),
+            ),
+            Self::Item => Some(
+                ITEMS
+                    .iter()
+                    .map(|(name, item)| (name.clone(), i32::from(item.id)))
+                    .collect(),
+            ),
+            Self::Entity => Some(enumerate(registry_entries!("minecraft:entity_type"))),
+            Self::Fluid => Some(enumerate(registry_entries!("minecraft:fluid"))),
+            _ => SYNCED_REGISTRIES.entries(self.registry_id()).map(enumerate),
+        }
+    }
+}
+
+static TAGS: LazyLock<RwLock<Tags>> = LazyLock::new(|| RwLock::new(Tags::load(&[])));
+
+/// Returns all entries of a tag with nested tags resolved
+pub fn get_tag_values(tag_category: TagCategory, tag: &str) -> Option<Vec<String>> {
+    let tags = TAGS.read().expect("Tags lock is poisoned");
+    tags.resolved
+        .get(&tag_category)?
+        .get(&namespaced(tag))
+        .map(|values| values.iter().cloned().collect())
+}
+
+/// Returns whether `name` is part of the tag, nested tags included
+pub fn is_in(tag_category: TagCategory, tag: &str, name: &str) -> bool {
+    let tags = TAGS.read().expect("Tags lock is poisoned");
+    tags.resolved
+        .get(&tag_category)
+        .and_then(|tags| tags.get(&namespaced(tag)))
+        .is_some_and(|values| values.contains(&namespaced(name)))
+}
+
+/// Reloads all tags from the vanilla tags and the given datapack directories. Later packs
+/// override earlier ones.
+pub fn load_tags(pack_dirs: &[PathBuf]) {
+    let tags = Tags::load(pack_dirs);
+    *TAGS.write().expect("Tags lock is poisoned") = tags;
+}
+
+/// The tags as they are sent to clients
+pub fn network_tags() -> Arc<Vec<RegistryTags>> {
+    TAGS.read().expect("Tags lock is poisoned").network.clone()
+}
 
-pub fn get_tag_values(tag_category: TagCategory, tag: &str) -> Option<&Vec<TagType>> {
-    TAGS.get(&tag_category)
-        .expect("Should deserialize all tag categories")
-        .get(tag)
+fn namespaced(name: &str) -> String {
+    if name.contains(':') {
+        name.to_string()
+    } else {
+        format!("minecraft:{name}")
+    }
 }
 
 #[derive(Deserialize)]
@@ -61,7 +151,7 @@ pub struct TagCollection {
     values: HashMap<String, Vec<TagType>>,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum TagType {
     Item(String),
     Tag(String),
@@ -92,8 +182,8 @@ impl<'de> Deserialize<'de> for TagType {
                 E: Error,
             {
                 match v.strip_prefix('#') {
-                    Some(v) => Ok(TagType::Tag(v.to_string())),
-                    None => Ok(TagType::Item(v.to_string())),
+                    Some(v) => Ok(TagType::Tag(namespaced(v))),
+                    None => Ok(TagType::Item(namespaced(v))),
                 }
             }
         }
@@ -101,13 +191,265 @@ impl<'de> Deserialize<'de> for TagType {
     }
 }
 
+/// A tag file like it is found in datapacks under `data/<namespace>/tags/<registry>/`
+#[derive(Deserialize)]
+struct TagFile {
+    #[serde(default)]
+    replace: bool,
+    values: Vec<TagFileEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TagFileEntry {
+    Plain(TagType),
+    Detailed {
+        id: TagType,
+        #[serde(default = "required")]
+        required: bool,
+    },
+}
+
+const fn required() -> bool {
+    true
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct TagEntry {
+    value: TagType,
+    required: bool,
+}
+
+impl From<TagFileEntry> for TagEntry {
+    fn from(entry: TagFileEntry) -> Self {
+        match entry {
+            TagFileEntry::Plain(value) => Self {
+                value,
+                required: true,
+            },
+            TagFileEntry::Detailed { id, required } => Self {
+                value: id,
+                required,
+            },
+        }
+    }
+}
+
+type Definitions = HashMap<TagCategory, BTreeMap<String, Vec<TagEntry>>>;
+
+struct Tags {
+    resolved: HashMap<TagCategory, HashMap<String, BTreeSet<String>>>,
+    network: Arc<Vec<RegistryTags>>,
+}
+
+impl Tags {
+    fn load(pack_dirs: &[PathBuf]) -> Self {
+        let mut definitions = vanilla_definitions();
+        for pack in pack_dirs {
+            load_pack(&mut definitions, pack);
+        }
+        Self::from_definitions(&definitions)
+    }
+
+    fn from_definitions(definitions: &Definitions) -> Self {
+        let resolved: HashMap<_, _> = definitions
+            .iter()
+            .map(|(category, tags)| (*category, resolve(*category, tags)))
+            .collect();
+        let mut network = Vec::new();
+        for category in TagCategory::ALL {
+            let (Some(tags), Some(ids)) = (resolved.get(&category), category.network_ids()) else {
+                continue;
+            };
+            let mut tags: Vec<_> = tags
+                .iter()
+                .map(|(name, values)| {
+                    let ids = values
+                        .iter()
+                        .filter_map(|value| ids.get(value))
+                        .map(|id| VarInt(*id))
+                        .collect();
+                    (name.clone(), ids)
+                })
+                .collect();
+            tags.sort_by(|(a, _), (b, _)| a.cmp(b));
+            network.push(RegistryTags {
+                registry_id: category.registry_id(),
+                tags,
+            });
+        }
+        Self {
+            resolved,
+            network: Arc::new(network),
+        }
+    }
+}
+
+fn vanilla_definitions() -> Definitions {
+    let tags: Vec<TagCollection> = serde_json::from_str(include_str!("../../assets/tags.json"))
+        .expect("Valid tag collections");
+    tags.into_iter()
+        .map(|collection| {
+            let tags = collection
+                .values
+                .into_iter()
+                .map(|(name, values)| {
+                    let entries = values
+                        .into_iter()
+                        .map(|value| TagEntry {
+                            value,
+                            required: true,
+                        })
+                        .collect();
+                    (name, entries)
+                })
+                .collect();
+            (collection.name, tags)
+        })
+        .collect()
+}
+
+/// Merges a tag file into the definitions, `replace` drops everything earlier packs defined
+fn merge(definitions: &mut Definitions, category: TagCategory, name: String, file: TagFile) {
+    let entries = definitions
+        .entry(category)
+        .or_default()
+        .entry(name)
+        .or_default();
+    if file.replace {
+        entries.clear();
+    }
+    for entry in file.values.into_iter().map(TagEntry::from) {
+        if !entries.contains(&entry) {
+            entries.push(entry);
+        }
+    }
+}
+
+fn load_pack(definitions: &mut Definitions, pack: &Path) {
+    for category in TagCategory::ALL {
+        let registry_path = category.registry_id().trim_start_matches("minecraft:");
+        for (name, tag) in read_pack_files::<TagFile>(pack, &format!("tags/{registry_path}")) {
+            merge(definitions, category, name, tag);
+        }
+    }
+}
+
+/// Resolves nested tags, tags referencing a missing required tag or themselves are dropped
+fn resolve(
+    category: TagCategory,
+    tags: &BTreeMap<String, Vec<TagEntry>>,
+) -> HashMap<String, BTreeSet<String>> {
+    fn resolve_tag(
+        name: &str,
+        tags: &BTreeMap<String, Vec<TagEntry>>,
+        resolved: &mut HashMap<String, Option<BTreeSet<String>>>,
+        visiting: &mut HashSet<String>,
+    ) -> Result<BTreeSet<String>, String> {
+        if let Some(values) = resolved.get(name) {
+            return values
+                .clone()
+                .ok_or_else(|| format!("#{name} could not be loaded"));
+        }
+        let entries = tags
+            .get(name)
+            .ok_or_else(|| format!("#{name} does not exist"))?;
+        if !visiting.insert(name.to_string()) {
+            return Err(format!("#{name} references itself"));
+        }
+        let mut values = BTreeSet::new();
+        let mut result = Ok(());
+        for entry in entries {
+            match &entry.value {
+                TagType::Item(value) => {
+                    values.insert(value.clone());
+                }
+                TagType::Tag(tag) => match resolve_tag(tag, tags, resolved, visiting) {
+                    Ok(nested) => values.extend(nested),
+                    Err(err) if entry.required => {
+                        result = Err(err);
+                        break;
+                    }
+                    Err(_) => (),
+                },
+            }
+        }
+        visiting.remove(name);
+        let values = result.map(|()| values);
+        resolved.insert(name.to_string(), values.clone().ok());
+        values
+    }
+
+    let mut resolved = HashMap::new();
+    for name in tags.keys() {
+        if let Err(err) = resolve_tag(name, tags, &mut resolved, &mut HashSet::new()) {
+            log::warn!(
+                "Could not load tag #{name} of {}: {err}",
+                category.registry_id()
+            );
+        }
+    }
+    resolved
+        .into_iter()
+        .filter_map(|(name, values)| Some((name, values?)))
+        .collect()
+}
+
 #[cfg(test)]
 mod test {
-    use crate::tags::TAGS;
+    use std::collections::HashMap;
+
+    use super::{merge, TagCategory, TagFile, Tags, TAGS};
 
     #[test]
     // This test assures that all tags that exist are loaded into the tags registry
     fn load_tags() {
-        assert!(!TAGS.is_empty())
+        let tags = TAGS.read().unwrap();
+        assert!(!tags.resolved.is_empty());
+        assert!(!tags.network.is_empty());
+    }
+
+    #[test]
+    fn merge_and_resolve() {
+        let file = |json: &str| serde_json::from_str::<TagFile>(json).unwrap();
+        let mut definitions = HashMap::new();
+        let block = TagCategory::Block;
+        let mut add = |name: &str, json: &str| {
+            merge(&mut definitions, block, name.to_string(), file(json));
+        };
+        add("minecraft:logs", r#"{"values": ["oak_log"]}"#);
+        add("minecraft:logs", r#"{"values": ["minecraft:birch_log"]}"#);
+        add("minecraft:stones", r#"{"values": ["minecraft:granite"]}"#);
+        add(
+            "minecraft:stones",
+            r#"{"replace": true, "values": ["minecraft:stone"]}"#,
+        );
+        add(
+            "minecraft:all",
+            r##"{"values": ["#logs", "#minecraft:stones", {"id": "#missing", "required": false}]}"##,
+        );
+        add("minecraft:broken", r##"{"values": ["#missing"]}"##);
+        add("minecraft:cycle", r##"{"values": ["#cycle"]}"##);
+
+        let tags = Tags::from_definitions(&definitions);
+        let resolved = &tags.resolved[&block];
+        let all: Vec<_> = resolved["minecraft:all"]
+            .iter()
+            .map(String::as_str)
+            .collect();
+        assert_eq!(
+            all,
+            [
+                "minecraft:birch_log",
+                "minecraft:oak_log",
+                "minecraft:stone"
+            ]
+        );
+        assert!(!resolved.contains_key("minecraft:broken"));
+        assert!(!resolved.contains_key("minecraft:cycle"));
+        let network = &tags.network[0];
+        assert_eq!(network.registry_id, "minecraft:block");
+        assert_eq!(network.tags[0].0, "minecraft:all");
+        assert_eq!(network.tags[0].1.len(), 3);
     }
 }
@@ -0,0 +1,357 @@
+//! A headless client speaking just enough of the protocol to join a server,
+//! used to drive end-to-end tests of the real networking stack.
+//!
+//! Only offline mode servers without encryption are supported. It can join as a client of an
+//! older version Pumpkin comes with a packet table for, see [`TestClient::join_as`].
+
+use std::{net::SocketAddr, time::Duration};
+
+use pumpkin_config::compression::CompressionInfo;
+use pumpkin_protocol::{
+    bytebuf::packet_id::Packet,
+    client::{
+        config::{CConfigDisconnect, CFinishConfig, CKnownPacks},
+        login::{CEncryptionRequest, CLoginDisconnect, CLoginSuccess, CSetCompression},
+        play::{CKeepAlive, CLogin, CPlayDisconnect, CSyncPlayerPosition},
+    },
+    packet_decoder::PacketDecoder,
+    packet_encoder::{PacketEncoder, PreparedPacket},
+    translation::PacketTable,
+    ClientPacket, ConnectionState, RawPacket, CURRENT_MC_PROTOCOL,
+};
+use thiserror::Error;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+    time::timeout,
+};
+use uuid::Uuid;
+
+pub mod packets;
+
+use packets::{
+    AcknowledgeFinishConfig, ClientInformation, ConfirmTeleport, Handshake, KeepAlive, KnownPacks,
+    LoginAcknowledged, LoginStart, PlayerPosition,
+};
+
+const LOGIN_DISCONNECT: i32 = CLoginDisconnect::PACKET_ID;
+const ENCRYPTION_REQUEST: i32 = CEncryptionRequest::PACKET_ID;
+const SET_COMPRESSION: i32 = CSetCompression::PACKET_ID;
+const LOGIN_SUCCESS: i32 = CLoginSuccess::PACKET_ID;
+const CONFIG_DISCONNECT: i32 = CConfigDisconnect::PACKET_ID;
+const KNOWN_PACKS: i32 = CKnownPacks::PACKET_ID;
+const FINISH_CONFIG: i32 = CFinishConfig::PACKET_ID;
+const PLAY_DISCONNECT: i32 = CPlayDisconnect::PACKET_ID;
+const PLAY_LOGIN: i32 = CLogin::PACKET_ID;
+const KEEP_ALIVE: i32 = CKeepAlive::PACKET_ID;
+const SYNC_POSITION: i32 = CSyncPlayerPosition::PACKET_ID;
+
+#[derive(Error, Debug)]
+pub enum TestClientError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("timed out waiting for a packet")]
+    Timeout,
+    #[error("the server closed the connection")]
+    ConnectionClosed,
+    #[error("failed to decode a packet: {0}")]
+    Decode(String),
+    #[error("failed to encode a packet: {0}")]
+    Encode(String),
+    #[error("malformed packet {0:#04x}: {1}")]
+    Malformed(i32, String),
+    #[error("disconnected by the server: {0}")]
+    Disconnected(String),
+    #[error("the server requested encryption, which is not supported")]
+    EncryptionRequested,
+}
+
+pub struct TestClient {
+    stream: TcpStream,
+    address: SocketAddr,
+    encoder: PacketEncoder,
+    decoder: PacketDecoder,
+    protocol_version: u32,
+    /// The packet table of the client's version if it is older than the server's, the ids of play
+    /// packets are remapped from and to ours by their name
+    packets: Option<PacketTable>,
+    /// How long to wait for a packet before failing
+    pub timeout: Duration,
+    /// The UUID the server assigned during login
+    pub uuid: Option<Uuid>,
+    /// The position the server last synced
+    pub position: (f64, f64, f64),
+}
+
+impl TestClient {
+    pub async fn connect(address: SocketAddr) -> Result<Self, TestClientError> {
+        let stream = TcpStream::connect(address).await?;
+        stream.set_nodelay(true)?;
+        Ok(Self {
+            stream,
+            address,
+            encoder: PacketEncoder::default(),
+            decoder: PacketDecoder::default(),
+            protocol_version: CURRENT_MC_PROTOCOL,
+            packets: None,
+            timeout: Duration::from_secs(10),
+            uuid: None,
+            position: (0.0, 0.0, 0.0),
+        })
+    }
+
+    /// Connects, logs in and configures the client, returning once the server sent the play login.
+    pub async fn join(address: SocketAddr, name: &str) -> Result<Self, TestClientError> {
+        Self::join_as(address, name, CURRENT_MC_PROTOCOL).await
+    }
+
+    /// Joins as a client of the protocol version, which has to be ours or one Pumpkin comes with a
+    /// packet table for.
+    ///
+    /// Login and configuration are the same in those versions, so only play packets are remapped.
+    pub async fn join_as(
+        address: SocketAddr,
+        name: &str,
+        protocol_version: u32,
+    ) -> Result<Self, TestClientError> {
+        let mut client = Self::connect(address).await?;
+        client.protocol_version = protocol_version;
+        client.packets = PacketTable::shipped(protocol_version);
+        client.login(name).await?;
+        client.configure().await?;
+        client.wait_for(PLAY_LOGIN).await?;
+        Ok(client)
+    }
+
+    pub async fn send_packet<P: ClientPacket>(
+        &mut self,
+        packet: &P,
+    ) -> Result<(), TestClientError> {
+        self.encoder
+            .append_packet(packet)
+            .map_err(|err| TestClientError::Encode(err.to_string()))?;
+        let bytes = self.encoder.take();
+        self.stream.write_all(&bytes).await?;
+        Ok(())
+    }
+
+    /// Sends a play packet with the id of the client's version.
+    pub async fn send_play_packet<P: ClientPacket>(
+        &mut self,
+        packet: &P,
+    ) -> Result<(), TestClientError> {
+        let Some(packets) = &self.packets else {
+            return self.send_packet(packet).await;
+        };
+        let prepared = PreparedPacket::new(packet);
+        let (id, payload) = prepared.parts();
+        let id = PacketTable::current()
+            .serverbound_name(ConnectionState::Play, id)
+            .and_then(|name| packets.serverbound_id(ConnectionState::Play, name))
+            .ok_or_else(|| {
+                TestClientError::Encode(format!(
+                    "packet {id:#04x} does not exist in protocol {}",
+                    self.protocol_version
+                ))
+            })?;
+        self.encoder
+            .append_prepared(&PreparedPacket::from_parts(id, payload))
+            .map_err(|err| TestClientError::Encode(err.to_string()))?;
+        let bytes = self.encoder.take();
+        self.stream.write_all(&bytes).await?;
+        Ok(())
+    }
+
+    /// Waits for the next play packet, with our id for it.
+    async fn recv_play_packet(&mut self) -> Result<RawPacket, TestClientError> {
+        let mut packet = self.recv_packet().await?;
+        if let Some(packets) = &self.packets {
+            // packets we do not have are ignored
+            packet.id.0 = packets
+                .clientbound_name(ConnectionState::Play, packet.id.0)
+                .and_then(|name| PacketTable::current().clientbound_id(ConnectionState::Play, name))
+                .unwrap_or(-1);
+        }
+        Ok(packet)
+    }
+
+    /// Waits for the next packet from the server.
+    pub async fn recv_packet(&mut self) -> Result<RawPacket, TestClientError> {
+        let mut buf = [0; 4096];
+        loop {
+            if let Some(packet) = self
+                .decoder
+                .decode()
+                .map_err(|err| TestClientError::Decode(err.to_string()))?
+            {
+                return Ok(packet);
+            }
+            let read = timeout(self.timeout, self.stream.read(&mut buf))
+                .await
+                .map_err(|_| TestClientError::Timeout)??;
+            if read == 0 {
+                return Err(TestClientError::ConnectionClosed);
+            }
+            self.decoder.queue_slice(&buf[..read]);
+        }
+    }
+
+    /// Performs the login, returning the UUID the server assigned.
+    pub async fn login(&mut self, name: &str) -> Result<Uuid, TestClientError> {
+        self.send_packet(&Handshake {
+            protocol_version: self.protocol_version as i32,
+            server_address: &self.address.ip().to_string(),
+            server_port: self.address.port(),
+            next_state: 2,
+        })
+        .await?;
+        self.send_packet(&LoginStart {
+            name,
+            uuid: Uuid::nil(),
+        })
+        .await?;
+
+        loop {
+            let mut packet = self.recv_packet().await?;
+            match packet.id.0 {
+                SET_COMPRESSION => {
+                    let threshold = packet
+                        .bytebuf
+                        .get_var_int()
+                        .map_err(|err| TestClientError::Malformed(packet.id.0, err.to_string()))?;
+                    let threshold = threshold.0 as u32;
+                    self.encoder.set_compression(Some(CompressionInfo {
+                        threshold,
+                        level: 6,
+                        adaptive: false,
+                    }));
+                    self.decoder.set_compression(Some(threshold));
+                }
+                LOGIN_SUCCESS => {
+                    let uuid = packet
+                        .bytebuf
+                        .get_uuid()
+                        .map_err(|err| TestClientError::Malformed(packet.id.0, err.to_string()))?;
+                    self.uuid = Some(uuid);
+                    self.send_packet(&LoginAcknowledged).await?;
+                    return Ok(uuid);
+                }
+                LOGIN_DISCONNECT => {
+                    let reason = packet.bytebuf.get_string().unwrap_or_default();
+                    return Err(TestClientError::Disconnected(reason));
+                }
+                ENCRYPTION_REQUEST => return Err(TestClientError::EncryptionRequested),
+                id => log::debug!("Ignoring login packet {id:#04x}"),
+            }
+        }
+    }
+
+    /// Answers the configuration until the server finishes it.
+    pub async fn configure(&mut self) -> Result<(), TestClientError> {
+        self.send_packet(&ClientInformation {
+            locale: "en_us",
+            view_distance: 2,
+        })
+        .await?;
+
+        loop {
+            let packet = self.recv_packet().await?;
+            match packet.id.0 {
+                KNOWN_PACKS => self.send_packet(&KnownPacks).await?,
+                FINISH_CONFIG => {
+                    self.send_packet(&AcknowledgeFinishConfig).await?;
+                    return Ok(());
+                }
+                // the reason is an NBT text component
+                CONFIG_DISCONNECT => {
+                    return Err(TestClientError::Disconnected(
+                        "during configuration".to_string(),
+                    ))
+                }
+                id => log::debug!("Ignoring configuration packet {id:#04x}"),
+            }
+        }
+    }
+
+    /// Waits for a play packet, answering keep alives and teleports in the meantime.
+    pub async fn wait_for(&mut self, packet_id: i32) -> Result<RawPacket, TestClientError> {
+        loop {
+            let packet = self.recv_play_packet().await?;
+            if packet.id.0 == packet_id {
+                return Ok(packet);
+            }
+            self.handle_play_packet(packet).await?;
+        }
+    }
+
+    /// Handles the packets every client has to answer.
+    pub async fn handle_play_packet(
+        &mut self,
+        mut packet: RawPacket,
+    ) -> Result<(), TestClientError> {
+        let id = packet.id.0;
+        let malformed = |err: pumpkin_protocol::bytebuf::DeserializerError| {
+            TestClientError::Malformed(id, err.to_string())
+        };
+        match id {
+            KEEP_ALIVE => {
+                let keep_alive_id = packet.bytebuf.get_i64().map_err(malformed)?;
+                self.send_play_packet(&KeepAlive { keep_alive_id }).await?;
+            }
+            SYNC_POSITION => {
+                // 1.21.2 moved the teleport id in front of the position
+                let moved_id = self.protocol_version >= 768;
+                let mut teleport_id = 0;
+                if moved_id {
+                    teleport_id = packet.bytebuf.get_var_int().map_err(malformed)?.0;
+                }
+                let x = packet.bytebuf.get_f64().map_err(malformed)?;
+                let y = packet.bytebuf.get_f64().map_err(malformed)?;
+                let z = packet.bytebuf.get_f64().map_err(malformed)?;
+                if !moved_id {
+                    // rotation and relative flags
+                    packet.bytebuf.get_f32().map_err(malformed)?;
+                    packet.bytebuf.get_f32().map_err(malformed)?;
+                    packet.bytebuf.get_u8().map_err(malformed)?;
+                    teleport_id = packet.bytebuf.get_var_int().map_err(malformed)?.0;
+                }
+                self.position = (x, y, z);
+                self.send_play_packet(&ConfirmTeleport { teleport_id })
+                    .await?;
+            }
+            // the reason is an NBT text component
+            PLAY_DISCONNECT => {
+                return Err(TestClientError::Disconnected("during play".to_string()))
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Moves the player, the server may answer with a teleport if it rejects the movement.
+    pub async fn move_to(&mut self, x: f64, y: f64, z: f64) -> Result<(), TestClientError> {
+        self.position = (x, y, z);
+        self.send_play_packet(&PlayerPosition {
+            x,
+            feet_y: y,
+            z,
+            ground: true,
+        })
+        .await
+    }
+
+    /// Handles incoming packets for the given duration, e.g. to stay connected over keep alives.
+    pub async fn idle(&mut self, duration: Duration) -> Result<(), TestClientError> {
+        let deadline = tokio::time::Instant::now() + duration;
+        loop {
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            if remaining.is_zero() {
+                return Ok(());
+            }
+            match timeout(remaining, self.recv_play_packet()).await {
+                Ok(packet) => self.handle_play_packet(packet?).await?,
+                Err(_) => return Ok(()),
+            }
+        }
+    }
+}
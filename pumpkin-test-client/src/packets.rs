@@ -0,0 +1,159 @@
+//! Serverbound packets written by the test client.
+//!
+//! The protocol crate only reads these, so the packet ids are taken from the server's packet structs.
+
+use pumpkin_protocol::{
+    bytebuf::{packet_id::Packet, ByteBuffer},
+    server::{
+        config::{SAcknowledgeFinishConfig, SClientInformationConfig, SKnownPacks},
+        handshake::SHandShake,
+        login::{SLoginAcknowledged, SLoginStart},
+        play::{SConfirmTeleport, SKeepAlive, SPlayerPosition},
+    },
+    ClientPacket, VarInt,
+};
+use uuid::Uuid;
+
+pub struct Handshake<'a> {
+    pub protocol_version: i32,
+    pub server_address: &'a str,
+    pub server_port: u16,
+    /// 1 for status, 2 for login
+    pub next_state: i32,
+}
+
+impl Packet for Handshake<'_> {
+    const PACKET_ID: i32 = SHandShake::PACKET_ID;
+}
+
+impl ClientPacket for Handshake<'_> {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        bytebuf.put_var_int(&VarInt(self.protocol_version));
+        bytebuf.put_string(self.server_address);
+        bytebuf.put_u16(self.server_port);
+        bytebuf.put_var_int(&VarInt(self.next_state));
+    }
+}
+
+pub struct LoginStart<'a> {
+    pub name: &'a str,
+    pub uuid: Uuid,
+}
+
+impl Packet for LoginStart<'_> {
+    const PACKET_ID: i32 = SLoginStart::PACKET_ID;
+}
+
+impl ClientPacket for LoginStart<'_> {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        bytebuf.put_string(self.name);
+        bytebuf.put_uuid(&self.uuid);
+    }
+}
+
+pub struct LoginAcknowledged;
+
+impl Packet for LoginAcknowledged {
+    const PACKET_ID: i32 = SLoginAcknowledged::PACKET_ID;
+}
+
+impl ClientPacket for LoginAcknowledged {
+    fn write(&self, _bytebuf: &mut ByteBuffer) {}
+}
+
+pub struct ClientInformation<'a> {
+    pub locale: &'a str,
+    pub view_distance: i8,
+}
+
+impl Packet for ClientInformation<'_> {
+    const PACKET_ID: i32 = SClientInformationConfig::PACKET_ID;
+}
+
+impl ClientPacket for ClientInformation<'_> {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        bytebuf.put_string(self.locale);
+        bytebuf.put_i8(self.view_distance);
+        // chat enabled with colors
+        bytebuf.put_var_int(&VarInt(0));
+        bytebuf.put_bool(true);
+        // all skin parts
+        bytebuf.put_u8(0x7F);
+        // right hand
+        bytebuf.put_var_int(&VarInt(1));
+        // no text filtering, allow server listing
+        bytebuf.put_bool(false);
+        bytebuf.put_bool(true);
+    }
+}
+
+/// Tells the server the client knows none of its packs
+pub struct KnownPacks;
+
+impl Packet for KnownPacks {
+    const PACKET_ID: i32 = SKnownPacks::PACKET_ID;
+}
+
+impl ClientPacket for KnownPacks {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        bytebuf.put_var_int(&VarInt(0));
+    }
+}
+
+pub struct AcknowledgeFinishConfig;
+
+impl Packet for AcknowledgeFinishConfig {
+    const PACKET_ID: i32 = SAcknowledgeFinishConfig::PACKET_ID;
+}
+
+impl ClientPacket for AcknowledgeFinishConfig {
+    fn write(&self, _bytebuf: &mut ByteBuffer) {}
+}
+
+pub struct KeepAlive {
+    pub keep_alive_id: i64,
+}
+
+impl Packet for KeepAlive {
+    const PACKET_ID: i32 = SKeepAlive::PACKET_ID;
+}
+
+impl ClientPacket for KeepAlive {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        bytebuf.put_i64(self.keep_alive_id);
+    }
+}
+
+pub struct ConfirmTeleport {
+    pub teleport_id: i32,
+}
+
+impl Packet for ConfirmTeleport {
+    const PACKET_ID: i32 = SConfirmTeleport::PACKET_ID;
+}
+
+impl ClientPacket for ConfirmTeleport {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        bytebuf.put_var_int(&VarInt(self.teleport_id));
+    }
+}
+
+pub struct PlayerPosition {
+    pub x: f64,
+    pub feet_y: f64,
+    pub z: f64,
+    pub ground: bool,
+}
+
+impl Packet for PlayerPosition {
+    const PACKET_ID: i32 = SPlayerPosition::PACKET_ID;
+}
+
+impl ClientPacket for PlayerPosition {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        bytebuf.put_f64(self.x);
+        bytebuf.put_f64(self.feet_y);
+        bytebuf.put_f64(self.z);
+        bytebuf.put_bool(self.ground);
+    }
+}
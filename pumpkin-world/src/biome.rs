@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 
 // TODO make this work with the protocol
 // Send by the registry
-#[derive(Serialize, Deserialize, Clone, Copy)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum Biome {
     Plains,
@@ -11,6 +11,23 @@ pub enum Biome {
     // TODO list all Biomes
 }
 
+impl Biome {
+    pub const ALL: [Self; 2] = [Self::Plains, Self::SnowyTiga];
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Plains => "minecraft:plains",
+            Self::SnowyTiga => "minecraft:snowy_taiga",
+        }
+    }
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|biome| biome.name() == name)
+    }
+}
+
 #[derive(Clone)]
 #[enum_dispatch(BiomeSupplierImpl)]
 pub enum BiomeSupplier {
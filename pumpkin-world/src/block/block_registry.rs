@@ -1,5 +1,6 @@
 use std::sync::LazyLock;
 
+use pumpkin_core::math::{boundingbox::BoundingBox, vector3::Vector3};
 use serde::Deserialize;
 
 pub static BLOCKS: LazyLock<TopLevel> = LazyLock::new(|| {
@@ -7,7 +8,7 @@ pub static BLOCKS: LazyLock<TopLevel> = LazyLock::new(|| {
         .expect("Could not parse blocks.json registry.")
 });
 
-pub fn get_block(registry_id: &str) -> Option<&Block> {
+pub fn get_block<'a>(registry_id: &str) -> Option<&'a Block> {
     BLOCKS
         .blocks
         .iter()
@@ -38,10 +39,18 @@ pub fn get_block_and_state_by_state_id<'a>(id: u16) -> Option<(&'a Block, &'a St
     None
 }
 
+/// The id of a block entity type like `minecraft:chest`, see [`State::block_entity_type`]
+pub fn get_block_entity_ident(id: u32) -> Option<&'static str> {
+    BLOCKS
+        .block_entity_types
+        .iter()
+        .find(|kind| kind.id == id)
+        .map(|kind| kind.ident.as_str())
+}
+
 pub fn get_block_by_item<'a>(item_id: u16) -> Option<&'a Block> {
     BLOCKS.blocks.iter().find(|&block| block.item_id == item_id)
 }
-#[expect(dead_code)]
 #[derive(Deserialize, Clone, Debug)]
 pub struct TopLevel {
     pub blocks: Vec<Block>,
@@ -60,6 +69,61 @@ pub struct Block {
     pub default_state_id: u16,
     pub states: Vec<State>,
 }
+impl Block {
+    /// Returns the state id with the given property values, properties which are not given keep the
+    /// value of the default state. Returns `None` if a property or value does not exist on this block.
+    ///
+    /// States are ordered like vanilla: the last property changes the fastest.
+    pub fn state_id_with_properties(&self, properties: &[(&str, &str)]) -> Option<u16> {
+        self.changed_state(self.default_state_id, properties)
+    }
+
+    /// Returns the state with the given property values changed, the other properties keep their
+    /// value in `state_id`. Returns `None` if the state isn't one of this block's or a property or
+    /// value does not exist on it.
+    pub fn changed_state(&self, state_id: u16, properties: &[(&str, &str)]) -> Option<u16> {
+        let mut indices = self.property_indices(state_id)?;
+        for (name, value) in properties {
+            let index = self.properties.iter().position(|p| p.name == *name)?;
+            indices[index] = self.properties[index]
+                .values
+                .iter()
+                .position(|v| v == value)?;
+        }
+
+        let mut offset = 0;
+        for (property, index) in self.properties.iter().zip(indices) {
+            offset = offset * property.values.len() + index;
+        }
+        Some(self.states.first()?.id + offset as u16)
+    }
+
+    /// Returns the value of every property of the given state.
+    pub fn properties_of_state(&self, state_id: u16) -> Option<Vec<(&str, &str)>> {
+        let indices = self.property_indices(state_id)?;
+        Some(
+            self.properties
+                .iter()
+                .zip(indices)
+                .map(|(property, index)| (property.name.as_str(), property.values[index].as_str()))
+                .collect(),
+        )
+    }
+
+    fn property_indices(&self, state_id: u16) -> Option<Vec<usize>> {
+        let mut offset = usize::from(state_id.checked_sub(self.states.first()?.id)?);
+        if offset >= self.states.len() {
+            return None;
+        }
+        let mut indices = vec![0; self.properties.len()];
+        for (i, property) in self.properties.iter().enumerate().rev() {
+            indices[i] = offset % property.values.len();
+            offset /= property.values.len();
+        }
+        Some(indices)
+    }
+}
+
 #[expect(dead_code)]
 #[derive(Deserialize, Clone, Debug)]
 struct BlockEntityKind {
@@ -67,11 +131,10 @@ struct BlockEntityKind {
     ident: String,
     name: String,
 }
-#[expect(dead_code)]
 #[derive(Deserialize, Clone, Debug)]
 pub struct Property {
-    name: String,
-    values: Vec<String>,
+    pub name: String,
+    pub values: Vec<String>,
 }
 #[derive(Deserialize, Clone, Debug)]
 pub struct State {
@@ -84,7 +147,19 @@ pub struct State {
     pub collision_shapes: Vec<u16>,
     pub block_entity_type: Option<u32>,
 }
-#[expect(dead_code)]
+impl State {
+    /// The boxes entities collide with, relative to the block
+    pub fn collision_boxes(&self) -> impl Iterator<Item = BoundingBox> + '_ {
+        self.collision_shapes.iter().filter_map(|shape| {
+            let shape = BLOCKS.shapes.get(usize::from(*shape))?;
+            Some(BoundingBox::new(
+                Vector3::new(shape.min_x, shape.min_y, shape.min_z),
+                Vector3::new(shape.max_x, shape.max_y, shape.max_z),
+            ))
+        })
+    }
+}
+
 #[derive(Deserialize, Clone, Debug)]
 struct Shape {
     min_x: f64,
@@ -94,3 +169,69 @@ struct Shape {
     max_y: f64,
     max_z: f64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{get_block, get_state_by_state_id};
+
+    #[test]
+    fn default_state_properties() {
+        let block = get_block("minecraft:oak_stairs").unwrap();
+        assert_eq!(
+            block.state_id_with_properties(&[]),
+            Some(block.default_state_id)
+        );
+        assert_eq!(
+            block.properties_of_state(block.default_state_id).unwrap(),
+            [
+                ("facing", "north"),
+                ("half", "bottom"),
+                ("shape", "straight"),
+                ("waterlogged", "false")
+            ]
+        );
+    }
+
+    #[test]
+    fn state_with_properties() {
+        let block = get_block("minecraft:oak_log").unwrap();
+        let state = block.state_id_with_properties(&[("axis", "x")]).unwrap();
+        assert_eq!(state, block.states[0].id);
+        assert_eq!(block.properties_of_state(state).unwrap(), [("axis", "x")]);
+
+        assert!(block.state_id_with_properties(&[("axis", "w")]).is_none());
+        assert!(block.state_id_with_properties(&[("facing", "x")]).is_none());
+    }
+
+    #[test]
+    fn changed_state_keeps_properties() {
+        let block = get_block("minecraft:piston").unwrap();
+        let facing_up = block
+            .state_id_with_properties(&[("facing", "up"), ("extended", "false")])
+            .unwrap();
+        let extended = block
+            .changed_state(facing_up, &[("extended", "true")])
+            .unwrap();
+        assert_eq!(
+            block.properties_of_state(extended).unwrap(),
+            [("extended", "true"), ("facing", "up")]
+        );
+        assert!(block.changed_state(0, &[("extended", "true")]).is_none());
+    }
+
+    #[test]
+    fn collision_boxes() {
+        let stone = get_block("minecraft:stone").unwrap();
+        let state = get_state_by_state_id(stone.default_state_id).unwrap();
+        let boxes: Vec<_> = state.collision_boxes().collect();
+        assert_eq!(boxes.len(), 1);
+        assert!((boxes[0].min_y, boxes[0].max_y) == (0.0, 1.0));
+
+        let slab = get_block("minecraft:oak_slab").unwrap();
+        let state = get_state_by_state_id(slab.default_state_id).unwrap();
+        assert!(state.collision_boxes().all(|shape| shape.max_y <= 0.5));
+
+        let air = get_state_by_state_id(0).unwrap();
+        assert_eq!(air.collision_boxes().count(), 0);
+    }
+}
@@ -0,0 +1,131 @@
+//! Block states custom blocks are shown as.
+//!
+//! Clients only know vanilla blocks, so a custom block is a vanilla state which a resource pack
+//! gives another model. Pumpkin only ever places mushroom blocks in their default state, every
+//! other state of them is free for custom blocks. Note blocks change their state when they are
+//! tuned or powered, but they only get the instrument of the block below them, so the states with
+//! a mob head instrument are free.
+
+use super::block_registry::{get_block, Block};
+
+/// The instruments vanilla note blocks play with a mob head on top of them, which Pumpkin doesn't
+const HEAD_INSTRUMENTS: [&str; 7] = [
+    "zombie",
+    "skeleton",
+    "creeper",
+    "dragon",
+    "wither_skeleton",
+    "piglin",
+    "custom_head",
+];
+
+/// A block whose non-default states are used for custom blocks
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CustomStateBlock {
+    /// 350 free states
+    NoteBlock,
+    /// 63 free states each
+    BrownMushroomBlock,
+    RedMushroomBlock,
+    MushroomStem,
+}
+
+impl CustomStateBlock {
+    pub const ALL: [Self; 4] = [
+        Self::NoteBlock,
+        Self::BrownMushroomBlock,
+        Self::RedMushroomBlock,
+        Self::MushroomStem,
+    ];
+
+    pub const MUSHROOM_BLOCKS: [Self; 3] = [
+        Self::BrownMushroomBlock,
+        Self::RedMushroomBlock,
+        Self::MushroomStem,
+    ];
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::NoteBlock => "minecraft:note_block",
+            Self::BrownMushroomBlock => "minecraft:brown_mushroom_block",
+            Self::RedMushroomBlock => "minecraft:red_mushroom_block",
+            Self::MushroomStem => "minecraft:mushroom_stem",
+        }
+    }
+
+    #[must_use]
+    pub fn block(self) -> &'static Block {
+        get_block(self.name()).expect("Custom state blocks are vanilla blocks")
+    }
+
+    /// The model the states which are not custom keep. Mushroom blocks normally pick a model for
+    /// every face, which can't be expressed per state, so they are shown as the full block.
+    #[must_use]
+    pub const fn vanilla_model(self) -> &'static str {
+        match self {
+            Self::NoteBlock => "minecraft:block/note_block",
+            Self::BrownMushroomBlock => "minecraft:block/brown_mushroom_block_inventory",
+            Self::RedMushroomBlock => "minecraft:block/red_mushroom_block_inventory",
+            Self::MushroomStem => "minecraft:block/mushroom_stem_inventory",
+        }
+    }
+
+    /// The states vanilla blocks are never placed in, every state except the default one for
+    /// mushroom blocks and the mob head instruments for note blocks
+    pub fn free_states(self) -> impl Iterator<Item = u16> {
+        let block = self.block();
+        block
+            .states
+            .iter()
+            .map(|state| state.id)
+            .filter(move |id| match self {
+                Self::NoteBlock => block.properties_of_state(*id).is_some_and(|properties| {
+                    properties.iter().any(|(name, value)| {
+                        *name == "instrument" && HEAD_INSTRUMENTS.contains(value)
+                    })
+                }),
+                _ => *id != block.default_state_id,
+            })
+    }
+
+    /// The block a state belongs to, if it is one of the custom state blocks
+    #[must_use]
+    pub fn of_state(state_id: u16) -> Option<Self> {
+        Self::ALL.into_iter().find(|block| {
+            let states = &block.block().states;
+            states
+                .first()
+                .zip(states.last())
+                .is_some_and(|(first, last)| (first.id..=last.id).contains(&state_id))
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::CustomStateBlock;
+
+    #[test]
+    fn free_states() {
+        let note_block = CustomStateBlock::NoteBlock.block();
+        assert_eq!(CustomStateBlock::NoteBlock.free_states().count(), 350);
+        assert!(CustomStateBlock::NoteBlock.free_states().all(|state| {
+            state != note_block.default_state_id
+                && !note_block
+                    .properties_of_state(state)
+                    .unwrap()
+                    .contains(&("instrument", "harp"))
+        }));
+        for block in CustomStateBlock::MUSHROOM_BLOCKS {
+            assert_eq!(block.free_states().count(), 63);
+        }
+
+        let state = CustomStateBlock::MushroomStem.free_states().next().unwrap();
+        assert_eq!(
+            CustomStateBlock::of_state(state),
+            Some(CustomStateBlock::MushroomStem)
+        );
+        assert_eq!(CustomStateBlock::of_state(0), None);
+    }
+}
@@ -2,12 +2,13 @@ use num_derive::FromPrimitive;
 
 pub mod block_registry;
 pub mod block_state;
+pub mod custom_states;
 
 use pumpkin_core::math::vector3::Vector3;
 
 pub use block_state::BlockState;
 
-#[derive(FromPrimitive)]
+#[derive(FromPrimitive, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum BlockFace {
     Bottom = 0,
     Top,
@@ -29,4 +30,40 @@ impl BlockFace {
         }
         .into()
     }
+
+    /// The face of a `facing` block state property, like `up` or `north`
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "down" => Self::Bottom,
+            "up" => Self::Top,
+            "north" => Self::North,
+            "south" => Self::South,
+            "west" => Self::West,
+            "east" => Self::East,
+            _ => return None,
+        })
+    }
+
+    /// The value of a `facing` block state property for the face
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Bottom => "down",
+            Self::Top => "up",
+            Self::North => "north",
+            Self::South => "south",
+            Self::West => "west",
+            Self::East => "east",
+        }
+    }
+
+    pub const fn opposite(self) -> Self {
+        match self {
+            Self::Bottom => Self::Top,
+            Self::Top => Self::Bottom,
+            Self::North => Self::South,
+            Self::South => Self::North,
+            Self::West => Self::East,
+            Self::East => Self::West,
+        }
+    }
 }
@@ -1,30 +1,172 @@
 use std::{
-    fs::OpenOptions,
-    io::{Read, Seek},
+    fs::{self, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::PathBuf,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
+use dashmap::DashMap;
 use flate2::bufread::{GzDecoder, ZlibDecoder};
 use itertools::Itertools;
+use pumpkin_core::math::vector2::Vector2;
+use thiserror::Error;
 
-use crate::level::SaveFile;
+use super::{ChunkReadingError, ChunkStorage, ChunkWritingError, CompressionError};
 
-use super::{ChunkData, ChunkReader, ChunkReadingError, CompressionError};
+const SECTOR_SIZE: usize = 4096;
+/// The location and the timestamp table
+const HEADER_SECTORS: usize = 2;
 
-#[derive(Clone)]
-pub struct AnvilChunkReader {}
+/// The region files vanilla saves chunks in, chunks are written zlib compressed like vanilla does
+pub struct AnvilChunkStorage {
+    region_folder: PathBuf,
+    /// Chunks of the same region are written one after another, they share the header
+    regions: DashMap<(i32, i32), Arc<parking_lot::Mutex<()>>>,
+}
 
-impl Default for AnvilChunkReader {
-    fn default() -> Self {
-        Self::new()
+impl AnvilChunkStorage {
+    pub fn new(region_folder: PathBuf) -> Self {
+        Self {
+            region_folder,
+            regions: DashMap::new(),
+        }
     }
-}
 
-impl AnvilChunkReader {
-    pub fn new() -> Self {
-        Self {}
+    fn region_file(&self, region: (i32, i32)) -> PathBuf {
+        self.region_folder
+            .join(format!("r.{}.{}.mca", region.0, region.1))
+    }
+
+    /// The region files in the region folder with their region
+    fn region_files(&self) -> io::Result<Vec<((i32, i32), PathBuf)>> {
+        let entries = match fs::read_dir(&self.region_folder) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        };
+        let mut regions = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if let Some(region) = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(parse_region_name)
+            {
+                regions.push((region, path));
+            }
+        }
+        Ok(regions)
+    }
+
+    /// Checks that the headers of the region files point to chunks. With `repair` the broken
+    /// entries are removed, so those chunks are generated again instead of failing to load
+    pub fn check_regions(&self, repair: bool) -> io::Result<RegionCheck> {
+        let mut check = RegionCheck::default();
+        for (region, path) in self.region_files()? {
+            check.regions += 1;
+            let lock = self.regions.entry(region).or_default().clone();
+            let _guard = lock.lock();
+            let mut file = OpenOptions::new().read(true).write(repair).open(&path)?;
+            let len = file.metadata()?.len() as usize;
+            if len < HEADER_SECTORS * SECTOR_SIZE {
+                check
+                    .problems
+                    .push((path.clone(), None, HeaderProblem::TruncatedHeader));
+                if repair {
+                    // there is no room for chunks after a header this short
+                    file.set_len(0)?;
+                    file.set_len((HEADER_SECTORS * SECTOR_SIZE) as u64)?;
+                }
+                continue;
+            }
+
+            let mut location_table = [0; SECTOR_SIZE];
+            file.read_exact(&mut location_table)?;
+            let mut entries = location_table
+                .chunks_exact(4)
+                .enumerate()
+                .map(|(i, location)| {
+                    let offset =
+                        u32::from_be_bytes([0, location[0], location[1], location[2]]) as usize;
+                    (i, offset, location[3] as usize)
+                })
+                .filter(|(_, offset, sectors)| *offset != 0 || *sectors != 0)
+                .collect_vec();
+            entries.sort_unstable_by_key(|(_, offset, _)| *offset);
+
+            let mut broken = Vec::new();
+            let mut used_until = HEADER_SECTORS;
+            for (i, offset, sectors) in entries {
+                let chunk = Vector2::new(
+                    region.0 * 32 + (i % 32) as i32,
+                    region.1 * 32 + (i / 32) as i32,
+                );
+                let start = offset * SECTOR_SIZE;
+                let problem = if offset < HEADER_SECTORS || sectors == 0 || start + 5 > len {
+                    Some(HeaderProblem::OutOfBounds)
+                } else if offset < used_until {
+                    Some(HeaderProblem::Overlapping)
+                } else {
+                    let mut header = [0; 5];
+                    file.seek(SeekFrom::Start(start as u64))?;
+                    file.read_exact(&mut header)?;
+                    let length = u32::from_be_bytes(header[..4].try_into().unwrap()) as usize;
+                    // chunks with the highest bit set are in their own file
+                    let known_compression =
+                        header[4] & 0x80 != 0 || Compression::from_byte(header[4]).is_some();
+                    (length == 0
+                        || length + 4 > sectors * SECTOR_SIZE
+                        || start + 4 + length > len
+                        || !known_compression)
+                        .then_some(HeaderProblem::InvalidData)
+                };
+                match problem {
+                    Some(problem) => {
+                        check.problems.push((path.clone(), Some(chunk), problem));
+                        broken.push(i);
+                    }
+                    None => {
+                        check.valid_chunks += 1;
+                        used_until = offset + sectors;
+                    }
+                }
+            }
+            if repair {
+                for i in broken {
+                    file.seek(SeekFrom::Start((i * 4) as u64))?;
+                    file.write_all(&[0; 4])?;
+                    file.seek(SeekFrom::Start((SECTOR_SIZE + i * 4) as u64))?;
+                    file.write_all(&[0; 4])?;
+                }
+            }
+        }
+        Ok(check)
     }
 }
 
+/// What is wrong with a chunk's entry in the header of a region file
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeaderProblem {
+    #[error("The header is cut off")]
+    TruncatedHeader,
+    #[error("The chunk is said to be in the header or after the end of the file")]
+    OutOfBounds,
+    #[error("The chunk shares sectors with another chunk")]
+    Overlapping,
+    #[error("The chunk's length or compression is invalid")]
+    InvalidData,
+}
+
+/// What checking the headers of the region files found
+#[derive(Debug, Default)]
+pub struct RegionCheck {
+    pub regions: usize,
+    pub valid_chunks: usize,
+    /// The region files and the chunks with broken entries, `None` if the whole header is broken
+    pub problems: Vec<(PathBuf, Option<Vector2<i32>>, HeaderProblem)>,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Compression {
     /// GZip Compression
@@ -52,7 +194,10 @@ impl Compression {
         }
     }
 
-    fn decompress_data(&self, compressed_data: Vec<u8>) -> Result<Vec<u8>, CompressionError> {
+    pub(super) fn decompress_data(
+        &self,
+        compressed_data: Vec<u8>,
+    ) -> Result<Vec<u8>, CompressionError> {
         match self {
             Compression::GZip => {
                 let mut decoder = GzDecoder::new(&compressed_data[..]);
@@ -85,21 +230,45 @@ impl Compression {
     }
 }
 
-impl ChunkReader for AnvilChunkReader {
-    fn read_chunk(
-        &self,
-        save_file: &SaveFile,
-        at: &pumpkin_core::math::vector2::Vector2<i32>,
-    ) -> Result<super::ChunkData, ChunkReadingError> {
+/// The region of a file named `r.<x>.<z>.mca`
+fn parse_region_name(name: &str) -> Option<(i32, i32)> {
+    let (x, z) = name
+        .strip_prefix("r.")?
+        .strip_suffix(".mca")?
+        .split_once('.')?;
+    Some((x.parse().ok()?, z.parse().ok()?))
+}
+
+/// Finds `sectors` free sectors after the header. The chunk's old sectors are still used, they are
+/// only freed once the header points to the new ones
+fn find_free_sectors(location_table: &[u8], sectors: usize) -> usize {
+    let mut used = location_table
+        .chunks_exact(4)
+        .map(|location| {
+            let offset = u32::from_be_bytes([0, location[0], location[1], location[2]]) as usize;
+            (offset, offset + location[3] as usize)
+        })
+        .filter(|(offset, _)| *offset != 0)
+        .collect_vec();
+    used.sort_unstable();
+
+    let mut start = HEADER_SECTORS;
+    for (offset, end) in used {
+        if offset >= start + sectors {
+            break;
+        }
+        start = start.max(end);
+    }
+    start
+}
+
+impl ChunkStorage for AnvilChunkStorage {
+    fn read_raw_chunk(&self, at: &Vector2<i32>) -> Result<Vec<u8>, ChunkReadingError> {
         let region = (at.x >> 5, at.z >> 5);
 
         let mut region_file = OpenOptions::new()
             .read(true)
-            .open(
-                save_file
-                    .region_folder
-                    .join(format!("r.{}.{}.mca", region.0, region.1)),
-            )
+            .open(self.region_file(region))
             .map_err(|err| match err.kind() {
                 std::io::ErrorKind::NotFound => ChunkReadingError::ChunkNotExist,
                 kind => ChunkReadingError::IoError(kind),
@@ -152,35 +321,419 @@ impl ChunkReader for AnvilChunkReader {
 
         // size includes the compression scheme byte, so we need to subtract 1
         let chunk_data = file_buf.drain(0..size as usize - 1).collect_vec();
-        let decompressed_chunk = compression
+        compression
             .decompress_data(chunk_data)
-            .map_err(ChunkReadingError::Compression)?;
+            .map_err(ChunkReadingError::Compression)
+    }
+
+    fn write_raw_chunk(&self, nbt: &[u8], at: &Vector2<i32>) -> Result<(), ChunkWritingError> {
+        let mut encoder =
+            flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder
+            .write_all(nbt)
+            .and_then(|()| encoder.flush())
+            .map_err(|err| ChunkWritingError::Compression(CompressionError::ZlibError(err)))?;
+        let compressed = encoder
+            .finish()
+            .map_err(|err| ChunkWritingError::Compression(CompressionError::ZlibError(err)))?;
+
+        // the length counts the compression scheme byte too
+        let mut payload = Vec::with_capacity(compressed.len() + 5);
+        payload.extend_from_slice(&(compressed.len() as u32 + 1).to_be_bytes());
+        payload.push(2);
+        payload.extend_from_slice(&compressed);
+        let sectors = payload.len().div_ceil(SECTOR_SIZE);
+        if sectors > u8::MAX as usize {
+            return Err(ChunkWritingError::ChunkTooLarge);
+        }
+        payload.resize(sectors * SECTOR_SIZE, 0);
 
-        ChunkData::from_bytes(decompressed_chunk, *at).map_err(ChunkReadingError::ParsingError)
+        let region = (at.x >> 5, at.z >> 5);
+        let lock = self.regions.entry(region).or_default().clone();
+        let _guard = lock.lock();
+
+        let io_error = |err: std::io::Error| ChunkWritingError::IoError(err.kind());
+        fs::create_dir_all(&self.region_folder).map_err(io_error)?;
+        let mut region_file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(self.region_file(region))
+            .map_err(io_error)?;
+
+        let mut location_table = [0; SECTOR_SIZE];
+        if region_file.metadata().map_err(io_error)?.len() >= (HEADER_SECTORS * SECTOR_SIZE) as u64
+        {
+            region_file
+                .read_exact(&mut location_table)
+                .map_err(io_error)?;
+        } else {
+            region_file
+                .write_all(&[0; HEADER_SECTORS * SECTOR_SIZE])
+                .map_err(io_error)?;
+        }
+
+        let table_entry = ((at.x.rem_euclid(32) + at.z.rem_euclid(32) * 32) * 4) as usize;
+        let offset = find_free_sectors(&location_table, sectors);
+        region_file
+            .seek(SeekFrom::Start((offset * SECTOR_SIZE) as u64))
+            .and_then(|_| region_file.write_all(&payload))
+            .map_err(io_error)?;
+
+        // the header is written last, so a crash while writing keeps the old chunk
+        let [_, offset_high, offset_middle, offset_low] = (offset as u32).to_be_bytes();
+        let location = [offset_high, offset_middle, offset_low, sectors as u8];
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs() as u32);
+        region_file
+            .seek(SeekFrom::Start(table_entry as u64))
+            .and_then(|_| region_file.write_all(&location))
+            .and_then(|_| region_file.seek(SeekFrom::Start((SECTOR_SIZE + table_entry) as u64)))
+            .and_then(|_| region_file.write_all(&timestamp.to_be_bytes()))
+            .map_err(io_error)?;
+        Ok(())
+    }
+
+    fn saved_chunks(&self) -> Result<Vec<Vector2<i32>>, ChunkReadingError> {
+        let regions = self
+            .region_files()
+            .map_err(|err| ChunkReadingError::IoError(err.kind()))?;
+        let mut chunks = Vec::new();
+        for (region, path) in regions {
+            let mut location_table = [0; SECTOR_SIZE];
+            let read =
+                fs::File::open(&path).and_then(|mut file| file.read_exact(&mut location_table));
+            if let Err(err) = read {
+                log::warn!("Skipping the region file {}: {err}", path.display());
+                continue;
+            }
+            for (i, location) in location_table.chunks_exact(4).enumerate() {
+                if location != [0; 4] {
+                    chunks.push(Vector2::new(
+                        region.0 * 32 + (i % 32) as i32,
+                        region.1 * 32 + (i / 32) as i32,
+                    ));
+                }
+            }
+        }
+        Ok(chunks)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::path::PathBuf;
+    use std::{
+        collections::HashMap,
+        fs::{self, OpenOptions},
+        io::{Seek, SeekFrom, Write},
+        path::{Path, PathBuf},
+        sync::atomic::AtomicBool,
+    };
 
-    use pumpkin_core::math::vector2::Vector2;
+    use fastnbt::{LongArray, Value};
+    use pumpkin_core::{
+        math::{vector2::Vector2, vector3::Vector3},
+        nbt::Compound,
+    };
 
     use crate::{
-        chunk::{anvil::AnvilChunkReader, ChunkReader, ChunkReadingError},
-        level::SaveFile,
+        block::block_registry::get_block,
+        chunk::{
+            anvil::{parse_region_name, AnvilChunkStorage, HeaderProblem, SECTOR_SIZE},
+            light::{ChunkLight, MAX_LIGHT},
+            ChunkBlocks, ChunkData, ChunkReadingError, ChunkStorage,
+        },
     };
 
+    fn compound<const N: usize>(tags: [(&str, Value); N]) -> Value {
+        Value::Compound(
+            tags.into_iter()
+                .map(|(tag, value)| (tag.to_string(), value))
+                .collect(),
+        )
+    }
+
+    /// A chunk like vanilla saves it, with a chest, a sign and a biome Pumpkin doesn't keep
+    fn vanilla_chunk() -> Value {
+        let stone = compound([("Name", Value::String("minecraft:stone".to_string()))]);
+        let chest = compound([
+            ("id", Value::String("minecraft:chest".to_string())),
+            ("x", Value::Int(3)),
+            ("y", Value::Int(-60)),
+            ("z", Value::Int(4)),
+            (
+                "Items",
+                Value::List(vec![compound([
+                    ("Slot", Value::Byte(0)),
+                    ("id", Value::String("minecraft:diamond".to_string())),
+                    ("count", Value::Int(5)),
+                ])]),
+            ),
+        ]);
+        let sign = compound([
+            ("id", Value::String("minecraft:sign".to_string())),
+            ("x", Value::Int(1)),
+            ("y", Value::Int(-60)),
+            ("z", Value::Int(1)),
+            (
+                "front_text",
+                compound([(
+                    "messages",
+                    Value::List(vec![Value::String("\"Hello\"".to_string()); 4]),
+                )]),
+            ),
+        ]);
+        compound([
+            ("DataVersion", Value::Int(3955)),
+            ("xPos", Value::Int(0)),
+            ("zPos", Value::Int(0)),
+            ("yPos", Value::Int(-4)),
+            ("Status", Value::String("minecraft:full".to_string())),
+            ("InhabitedTime", Value::Long(1200)),
+            (
+                "sections",
+                Value::List(vec![compound([
+                    ("Y", Value::Byte(-4)),
+                    (
+                        "block_states",
+                        compound([("palette", Value::List(vec![stone]))]),
+                    ),
+                    (
+                        "biomes",
+                        compound([(
+                            "palette",
+                            Value::List(vec![Value::String("minecraft:cherry_grove".to_string())]),
+                        )]),
+                    ),
+                ])]),
+            ),
+            (
+                "Heightmaps",
+                compound([
+                    (
+                        "MOTION_BLOCKING",
+                        Value::LongArray(LongArray::new(vec![0; 37])),
+                    ),
+                    (
+                        "WORLD_SURFACE",
+                        Value::LongArray(LongArray::new(vec![0; 37])),
+                    ),
+                    ("OCEAN_FLOOR", Value::LongArray(LongArray::new(vec![1; 37]))),
+                ]),
+            ),
+            ("block_entities", Value::List(vec![chest, sign])),
+            ("structures", compound([("starts", compound([]))])),
+            ("block_ticks", Value::List(Vec::new())),
+        ])
+    }
+
+    /// Writes the chunk zlib compressed as the only chunk of a region file, like vanilla does
+    fn write_region(path: &Path, chunk: &Value) {
+        let mut encoder =
+            flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder
+            .write_all(&fastnbt::to_bytes(chunk).unwrap())
+            .unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let mut file = vec![0; 2 * SECTOR_SIZE];
+        file[..4].copy_from_slice(&[0, 0, 2, 1]);
+        file.extend_from_slice(&(compressed.len() as u32 + 1).to_be_bytes());
+        file.push(2);
+        file.extend_from_slice(&compressed);
+        file.resize(file.len().next_multiple_of(SECTOR_SIZE), 0);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, file).unwrap();
+    }
+
     #[test]
     fn not_existing() {
-        let region_path = PathBuf::from("not_existing");
-        let result = AnvilChunkReader::new().read_chunk(
-            &SaveFile {
-                root_folder: PathBuf::from(""),
-                region_folder: region_path,
-            },
-            &Vector2::new(0, 0),
-        );
+        let storage = AnvilChunkStorage::new(PathBuf::from("not_existing"));
+        let result = storage.read_chunk(&Vector2::new(0, 0));
         assert!(matches!(result, Err(ChunkReadingError::ChunkNotExist)));
+        assert!(storage.saved_chunks().unwrap().is_empty());
+    }
+
+    #[test]
+    fn region_names() {
+        assert_eq!(parse_region_name("r.-1.20.mca"), Some((-1, 20)));
+        assert_eq!(parse_region_name("r.0.0.mcc"), None);
+        assert_eq!(parse_region_name("r.a.0.mca"), None);
+    }
+
+    #[test]
+    fn written_chunks_are_read_again() {
+        let folder = std::env::temp_dir().join(format!("pumpkin-anvil-{}", std::process::id()));
+        let storage = AnvilChunkStorage::new(folder.join("region"));
+        let stone = get_block("minecraft:stone").unwrap().default_state_id;
+        let stairs = get_block("minecraft:oak_stairs")
+            .unwrap()
+            .state_id_with_properties(&[("facing", "west"), ("half", "top")])
+            .unwrap();
+
+        let at = Vector2::new(-3, 40);
+        let mut chunk = ChunkData {
+            blocks: ChunkBlocks::default(),
+            position: at,
+            persistent_data: Default::default(),
+            block_entity_data: HashMap::new(),
+            block_entities: HashMap::new(),
+            entities: Vec::new(),
+            light: None,
+            nbt: Default::default(),
+            dirty: AtomicBool::new(true),
+        };
+        for x in 0..16 {
+            for z in 0..16 {
+                for y in -64..0 {
+                    chunk.blocks.set_block(Vector3::new(x, y, z).into(), stone);
+                }
+            }
+        }
+        chunk
+            .blocks
+            .set_block(Vector3::new(5, 70, 9).into(), stairs);
+        chunk.light = Some(ChunkLight::compute(&chunk.blocks, &Default::default()));
+
+        // the second write moves the chunk to new sectors
+        storage.write_chunk(&chunk, &at).unwrap();
+        storage.write_chunk(&chunk, &at).unwrap();
+        let read = storage.read_chunk(&at);
+        let saved = storage.saved_chunks();
+        let _ = std::fs::remove_dir_all(&folder);
+        let read = read.unwrap();
+        assert_eq!(saved.unwrap(), vec![at]);
+
+        assert_eq!(
+            read.blocks.get_block(Vector3::new(2, -30, 7).into()),
+            Some(stone)
+        );
+        assert_eq!(
+            read.blocks.get_block(Vector3::new(5, 70, 9).into()),
+            Some(stairs)
+        );
+        assert_eq!(
+            read.blocks.get_block(Vector3::new(5, 71, 9).into()),
+            Some(0)
+        );
+        let light = read.light.unwrap();
+        assert_eq!(light.sky_light(Vector3::new(2, 10, 7).into()), MAX_LIGHT);
+        assert_eq!(light.sky_light(Vector3::new(2, -30, 7).into()), 0);
+    }
+
+    #[test]
+    fn vanilla_chunks_keep_what_pumpkin_does_not_know() {
+        let folder = std::env::temp_dir().join(format!("pumpkin-vanilla-{}", std::process::id()));
+        let region = folder.join("region/r.0.0.mca");
+        let at = Vector2::new(0, 0);
+        let vanilla = vanilla_chunk();
+        write_region(&region, &vanilla);
+
+        let storage = AnvilChunkStorage::new(folder.join("region"));
+        let mut chunk = storage.read_chunk(&at).unwrap();
+        chunk.blocks.set_block(Vector3::new(0, 100, 0).into(), 1);
+        storage.write_chunk(&chunk, &at).unwrap();
+        let written: Compound = fastnbt::from_bytes(&storage.read_raw_chunk(&at).unwrap()).unwrap();
+        let read_again = storage.read_chunk(&at);
+        let _ = fs::remove_dir_all(&folder);
+
+        let Value::Compound(vanilla) = vanilla else {
+            unreachable!();
+        };
+        for tag in ["InhabitedTime", "structures", "block_ticks"] {
+            assert_eq!(written.get(tag), vanilla.get(tag), "{tag}");
+        }
+        let Some(Value::Compound(heightmaps)) = written.get("Heightmaps") else {
+            panic!("the heightmaps are missing");
+        };
+        assert!(heightmaps.contains_key("OCEAN_FLOOR"));
+
+        let Some(Value::List(block_entities)) = written.get("block_entities") else {
+            panic!("the block entities are missing");
+        };
+        let Some(Value::List(kept)) = vanilla.get("block_entities") else {
+            unreachable!();
+        };
+        assert_eq!(block_entities.len(), 2);
+        for block_entity in kept {
+            assert!(block_entities.contains(block_entity));
+        }
+
+        let Some(Value::List(sections)) = written.get("sections") else {
+            panic!("the sections are missing");
+        };
+        let bottom = sections
+            .iter()
+            .find_map(|section| match section {
+                Value::Compound(section) if section.get("Y") == Some(&Value::Byte(-4)) => {
+                    Some(section)
+                }
+                _ => None,
+            })
+            .unwrap();
+        let Some(Value::List(bottom_sections)) = vanilla.get("sections") else {
+            unreachable!();
+        };
+        let Value::Compound(vanilla_bottom) = &bottom_sections[0] else {
+            unreachable!();
+        };
+        assert_eq!(bottom.get("biomes"), vanilla_bottom.get("biomes"));
+
+        let read_again = read_again.unwrap();
+        let stone = get_block("minecraft:stone").unwrap().default_state_id;
+        assert_eq!(
+            read_again.blocks.get_block(Vector3::new(5, -60, 5).into()),
+            Some(stone)
+        );
+        assert_eq!(
+            read_again.blocks.get_block(Vector3::new(0, 100, 0).into()),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn broken_headers_are_repaired() {
+        let folder = std::env::temp_dir().join(format!("pumpkin-repair-{}", std::process::id()));
+        let storage = AnvilChunkStorage::new(folder.join("region"));
+        let at = Vector2::new(1, 0);
+        let chunk = ChunkData {
+            blocks: ChunkBlocks::default(),
+            position: at,
+            persistent_data: Default::default(),
+            block_entity_data: HashMap::new(),
+            block_entities: HashMap::new(),
+            entities: Vec::new(),
+            light: None,
+            nbt: Default::default(),
+            dirty: AtomicBool::new(true),
+        };
+        storage.write_chunk(&chunk, &at).unwrap();
+        // the entry of chunk 0, 0 points far after the end of the file
+        OpenOptions::new()
+            .write(true)
+            .open(folder.join("region/r.0.0.mca"))
+            .and_then(|mut file| {
+                file.seek(SeekFrom::Start(0))?;
+                file.write_all(&[0, 1, 0, 1])
+            })
+            .unwrap();
+
+        let found = storage.check_regions(true);
+        let checked_again = storage.check_regions(false);
+        let saved = storage.saved_chunks();
+        let read = storage.read_chunk(&at);
+        let _ = std::fs::remove_dir_all(&folder);
+
+        let found = found.unwrap();
+        assert_eq!((found.regions, found.valid_chunks), (1, 1));
+        assert_eq!(found.problems.len(), 1);
+        assert_eq!(found.problems[0].1, Some(Vector2::new(0, 0)));
+        assert_eq!(found.problems[0].2, HeaderProblem::OutOfBounds);
+        assert!(checked_again.unwrap().problems.is_empty());
+        assert_eq!(saved.unwrap(), vec![at]);
+        // chunks which were not lit are lit again when they are read
+        assert!(read.unwrap().light.is_none());
     }
 }
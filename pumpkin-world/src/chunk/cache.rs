@@ -0,0 +1,145 @@
+//! Keeps chunks no player watches anymore in memory, so walking back and forth doesn't read them
+//! again. Once the loaded chunks take more memory than the budget, the least recently used of
+//! these idle chunks are evicted.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use parking_lot::Mutex;
+use pumpkin_core::math::vector2::Vector2;
+
+/// How often chunks were found in memory, read or generated and evicted
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct ChunkCacheStats {
+    pub hits: usize,
+    pub misses: usize,
+    pub evictions: usize,
+}
+
+#[derive(Default)]
+struct IdleChunks {
+    /// Counts up with every use, so a smaller age was used earlier
+    clock: u64,
+    ages: HashMap<Vector2<i32>, u64>,
+    by_age: BTreeMap<u64, Vector2<i32>>,
+}
+
+#[derive(Default)]
+pub struct ChunkCache {
+    idle: Mutex<IdleChunks>,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+    evictions: AtomicUsize,
+}
+
+impl ChunkCache {
+    /// The chunk was loaded already, it is evicted last if it is idle
+    pub fn hit(&self, chunk: Vector2<i32>) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        let mut idle = self.idle.lock();
+        if idle.ages.contains_key(&chunk) {
+            idle.touch(chunk);
+        }
+    }
+
+    /// The chunk had to be read or generated
+    pub fn miss(&self) {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// No player watches the chunk anymore, so it may be evicted
+    pub fn release(&self, chunk: Vector2<i32>) {
+        self.idle.lock().touch(chunk);
+    }
+
+    /// A player watches the chunk again, so it is not evicted
+    pub fn retain(&self, chunk: Vector2<i32>) {
+        let mut idle = self.idle.lock();
+        if let Some(age) = idle.ages.remove(&chunk) {
+            idle.by_age.remove(&age);
+        }
+    }
+
+    /// Number of chunks which may be evicted
+    pub fn idle_count(&self) -> usize {
+        self.idle.lock().ages.len()
+    }
+
+    /// Takes the least recently used idle chunks out until `loaded` chunks of `chunk_memory` bytes
+    /// each fit into the budget. The caller unloads them
+    pub fn evict(&self, loaded: usize, chunk_memory: usize, budget: usize) -> Vec<Vector2<i32>> {
+        let keep = budget / chunk_memory.max(1);
+        let mut idle = self.idle.lock();
+        let count = loaded.saturating_sub(keep).min(idle.ages.len());
+        let mut evicted = Vec::with_capacity(count);
+        for _ in 0..count {
+            let Some((_, chunk)) = idle.by_age.pop_first() else {
+                break;
+            };
+            idle.ages.remove(&chunk);
+            evicted.push(chunk);
+        }
+        evicted
+    }
+
+    /// Counts a chunk which was unloaded after it was evicted
+    pub fn record_eviction(&self) {
+        self.evictions.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn stats(&self) -> ChunkCacheStats {
+        ChunkCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl IdleChunks {
+    /// Makes the chunk idle if it wasn't, as the most recently used one
+    fn touch(&mut self, chunk: Vector2<i32>) {
+        self.clock += 1;
+        let age = self.clock;
+        if let Some(old) = self.ages.insert(chunk, age) {
+            self.by_age.remove(&old);
+        }
+        self.by_age.insert(age, chunk);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_core::math::vector2::Vector2;
+
+    use super::ChunkCache;
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let cache = ChunkCache::default();
+        for x in 0..4 {
+            cache.release(Vector2::new(x, 0));
+        }
+        cache.hit(Vector2::new(0, 0));
+        cache.retain(Vector2::new(1, 0));
+
+        // 6 loaded chunks of which 4 fit
+        let evicted = cache.evict(6, 10, 40);
+        assert_eq!(evicted, vec![Vector2::new(2, 0), Vector2::new(3, 0)]);
+        assert_eq!(cache.idle_count(), 1);
+        assert_eq!(cache.evict(6, 10, 40).len(), 1);
+        assert!(cache.evict(6, 10, 40).is_empty());
+    }
+
+    #[test]
+    fn only_idle_chunks_are_touched() {
+        let cache = ChunkCache::default();
+        cache.hit(Vector2::new(0, 0));
+        cache.miss();
+        assert_eq!(cache.idle_count(), 0);
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.evictions), (1, 1, 0));
+    }
+}
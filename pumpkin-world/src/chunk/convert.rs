@@ -0,0 +1,129 @@
+//! Copies the saved chunks of a world from one storage to another, `pumpkin convert` runs it. The
+//! chunks are copied as they were saved, so converting loses nothing vanilla or Pumpkin saved.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use pumpkin_core::math::vector2::Vector2;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+use super::{ChunkReadingError, ChunkStorage};
+
+/// What happened to the chunks while converting
+#[derive(Debug, Default)]
+pub struct ConversionStats {
+    pub converted: usize,
+    /// The chunks which couldn't be read or written and why
+    pub failed: Vec<(Vector2<i32>, String)>,
+}
+
+/// Reads every chunk saved in `from` and writes it to `to`, the chunks in `from` are kept.
+/// `progress` is called from the converting threads with how many chunks are done of how many
+pub fn convert(
+    from: &dyn ChunkStorage,
+    to: &dyn ChunkStorage,
+    progress: impl Fn(usize, usize) + Sync,
+) -> Result<ConversionStats, ChunkReadingError> {
+    let chunks = from.saved_chunks()?;
+    let done = AtomicUsize::new(0);
+    let results = chunks
+        .par_iter()
+        .map(|at| {
+            let result = match from.read_raw_chunk(at) {
+                Ok(nbt) => to.write_raw_chunk(&nbt, at).map_err(|err| err.to_string()),
+                Err(err) => Err(err.to_string()),
+            };
+            progress(done.fetch_add(1, Ordering::Relaxed) + 1, chunks.len());
+            (*at, result)
+        })
+        .collect::<Vec<_>>();
+
+    let mut stats = ConversionStats::default();
+    for (at, result) in results {
+        match result {
+            Ok(()) => stats.converted += 1,
+            Err(err) => stats.failed.push((at, err)),
+        }
+    }
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashMap, sync::atomic::AtomicBool};
+
+    use fastnbt::Value;
+    use pumpkin_core::math::{vector2::Vector2, vector3::Vector3};
+
+    use super::convert;
+    use crate::chunk::{
+        anvil::AnvilChunkStorage, key_value::KeyValueChunkStorage, ChunkBlocks, ChunkData,
+        ChunkStorage,
+    };
+
+    #[test]
+    fn anvil_to_key_value_and_back() {
+        let folder = std::env::temp_dir().join(format!("pumpkin-convert-{}", std::process::id()));
+        let anvil = AnvilChunkStorage::new(folder.join("region"));
+        let key_value = KeyValueChunkStorage::new(folder.join("chunks.kv"));
+        let back = AnvilChunkStorage::new(folder.join("region_back"));
+
+        let chunks = [
+            Vector2::new(0, 0),
+            Vector2::new(-33, 5),
+            Vector2::new(31, 64),
+        ];
+        for at in chunks {
+            let mut chunk = ChunkData {
+                blocks: ChunkBlocks::default(),
+                position: at,
+                persistent_data: Default::default(),
+                block_entity_data: HashMap::new(),
+                block_entities: HashMap::new(),
+                entities: Vec::new(),
+                light: None,
+                nbt: Default::default(),
+                dirty: AtomicBool::new(true),
+            };
+            chunk.blocks.set_block(Vector3::new(0, 0, 0).into(), 1);
+            anvil.write_chunk(&chunk, &at).unwrap();
+        }
+
+        // a chunk vanilla didn't finish generating, with tags Pumpkin doesn't know
+        let unfinished = Vector2::new(2, -1);
+        let nbt = fastnbt::to_bytes(&HashMap::from([
+            ("Status", Value::String("minecraft:noise".to_string())),
+            ("blending_data", Value::Compound(HashMap::new())),
+            ("InhabitedTime", Value::Long(20)),
+        ]))
+        .unwrap();
+        anvil.write_raw_chunk(&nbt, &unfinished).unwrap();
+
+        let to_key_value = convert(&anvil, &key_value, |_, _| {});
+        let to_anvil = convert(&key_value, &back, |_, _| {});
+        let read = back.read_chunk(&Vector2::new(-33, 5));
+        let copies = chunks
+            .into_iter()
+            .chain([unfinished])
+            .map(|at| {
+                (
+                    anvil.read_raw_chunk(&at).ok(),
+                    back.read_raw_chunk(&at).ok(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let _ = std::fs::remove_dir_all(&folder);
+
+        assert_eq!(to_key_value.unwrap().converted, 4);
+        let to_anvil = to_anvil.unwrap();
+        assert_eq!(to_anvil.converted, 4);
+        assert!(to_anvil.failed.is_empty());
+        for (original, copy) in copies {
+            assert!(original.is_some());
+            assert_eq!(original, copy);
+        }
+        assert_eq!(
+            read.unwrap().blocks.get_block(Vector3::new(0, 0, 0).into()),
+            Some(1)
+        );
+    }
+}
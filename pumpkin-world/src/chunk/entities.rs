@@ -0,0 +1,373 @@
+//! The entities saved with their chunk, in the chunk's `Entities` list. Which of them are saved
+//! when the chunk unloads and how many are spawned again when it loads is set by
+//! `entity_persistence`.
+
+use std::collections::HashMap;
+
+use fastnbt::IntArray;
+use pumpkin_config::{EntityOverflowStrategy, EntityPersistenceConfig};
+use pumpkin_core::math::vector2::Vector2;
+use pumpkin_core::math::vector3::Vector3;
+use pumpkin_core::nbt::Compound;
+use pumpkin_core::persistent_data::PersistentDataContainer;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::item::ItemStack;
+
+/// An entity as it is stored with its chunk.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChunkEntity {
+    /// The entity type, e.g. `minecraft:zombie`
+    pub id: String,
+    pub position: Vector3<f64>,
+    pub persistence_required: bool,
+    pub custom_name: Option<String>,
+    /// The player who tamed the entity
+    pub owner: Option<Uuid>,
+    /// How many entities this one represents, e.g. the item count or the value of an experience orb
+    pub amount: u32,
+    /// The stack of a dropped item
+    pub item: Option<ItemStack>,
+    /// Custom data of plugins
+    pub persistent_data: PersistentDataContainer,
+}
+
+/// How an entity is written to the chunk's NBT
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub(super) struct EntityNbt {
+    id: String,
+    pos: Vec<f64>,
+    #[serde(default)]
+    persistence_required: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    custom_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    owner: Option<IntArray>,
+    #[serde(default = "one")]
+    count: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    item: Option<Compound>,
+    #[serde(
+        rename = "PublicBukkitValues",
+        default,
+        skip_serializing_if = "PersistentDataContainer::is_empty"
+    )]
+    persistent_data: PersistentDataContainer,
+}
+
+const fn one() -> u32 {
+    1
+}
+
+impl From<&ChunkEntity> for EntityNbt {
+    fn from(entity: &ChunkEntity) -> Self {
+        Self {
+            id: entity.id.clone(),
+            pos: vec![entity.position.x, entity.position.y, entity.position.z],
+            persistence_required: entity.persistence_required,
+            custom_name: entity.custom_name.clone(),
+            // vanilla stores UUIDs as four ints, the most significant first
+            owner: entity.owner.map(|owner| {
+                let owner = owner.as_u128();
+                IntArray::new(
+                    (0..4)
+                        .map(|i| (owner >> (96 - i * 32)) as u32 as i32)
+                        .collect(),
+                )
+            }),
+            count: entity.amount,
+            item: entity.item.as_ref().and_then(ItemStack::to_nbt),
+            persistent_data: entity.persistent_data.clone(),
+        }
+    }
+}
+
+impl EntityNbt {
+    /// The entity, `None` if its position is broken
+    pub(super) fn into_entity(self) -> Option<ChunkEntity> {
+        let [x, y, z] = self.pos[..] else {
+            return None;
+        };
+        let owner = self.owner.and_then(|owner| match owner[..] {
+            [a, b, c, d] => Some(Uuid::from_u128(
+                [a, b, c, d]
+                    .into_iter()
+                    .fold(0, |uuid, int| uuid << 32 | u128::from(int as u32)),
+            )),
+            _ => None,
+        });
+        Some(ChunkEntity {
+            id: self.id,
+            position: Vector3::new(x, y, z),
+            persistence_required: self.persistence_required,
+            custom_name: self.custom_name,
+            owner,
+            amount: self.count,
+            item: self.item.as_ref().and_then(ItemStack::from_nbt),
+            persistent_data: self.persistent_data,
+        })
+    }
+}
+
+impl ChunkEntity {
+    /// Protected entities are never merged or culled, which includes entities plugins stored data on.
+    #[must_use]
+    pub fn is_protected(&self) -> bool {
+        self.persistence_required
+            || self.custom_name.is_some()
+            || self.owner.is_some()
+            || !self.persistent_data.is_empty()
+    }
+
+    /// Whether the entity should be saved when its chunk is unloaded.
+    #[must_use]
+    pub fn should_persist(&self, config: &EntityPersistenceConfig) -> bool {
+        config.persist_all
+            || (config.persist_persistence_required && self.persistence_required)
+            || (config.persist_name_tagged && self.custom_name.is_some())
+            || (config.persist_tamed && self.owner.is_some())
+    }
+
+    fn block_position(&self) -> (i32, i32, i32) {
+        (
+            self.position.x.floor() as i32,
+            self.position.y.floor() as i32,
+            self.position.z.floor() as i32,
+        )
+    }
+}
+
+/// Removes the entities which should not be saved with an unloading chunk, returns how many
+pub fn retain_persistent(
+    entities: &mut Vec<ChunkEntity>,
+    config: &EntityPersistenceConfig,
+) -> usize {
+    let before = entities.len();
+    entities.retain(|entity| entity.should_persist(config));
+    before - entities.len()
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OverflowReport {
+    pub merged: usize,
+    pub culled: usize,
+}
+
+/// Enforces the entity limits on the entities of a freshly loaded chunk, so a chunk full of stacked
+/// mobs can not bring down the server.
+pub fn apply_load_caps(
+    chunk: Vector2<i32>,
+    entities: &mut Vec<ChunkEntity>,
+    config: &EntityPersistenceConfig,
+) -> OverflowReport {
+    let mut report = OverflowReport::default();
+    if !exceeds_caps(entities, config) {
+        return report;
+    }
+    let loaded = entities.len();
+
+    if config.overflow_strategy == EntityOverflowStrategy::Merge {
+        report.merged = merge(entities);
+    }
+    if exceeds_caps(entities, config) {
+        report.culled = cull(entities, config);
+    }
+
+    if config.log_overflow {
+        log::warn!(
+            "Chunk {:?} loaded with {} entities, merged {} and culled {}",
+            chunk,
+            loaded,
+            report.merged,
+            report.culled
+        );
+    }
+    report
+}
+
+fn exceeds_caps(entities: &[ChunkEntity], config: &EntityPersistenceConfig) -> bool {
+    if config.max_entities_per_chunk != 0 && entities.len() > config.max_entities_per_chunk as usize
+    {
+        return true;
+    }
+    if config.max_entities_per_type == 0 {
+        return false;
+    }
+    let mut per_type: HashMap<&str, usize> = HashMap::new();
+    entities.iter().any(|entity| {
+        let count = per_type.entry(&entity.id).or_default();
+        *count += 1;
+        *count > config.max_entities_per_type as usize
+    })
+}
+
+/// Merges unprotected entities of the same type within the same block into one, returning how many were removed.
+/// Dropped items are only merged with the same items
+fn merge(entities: &mut Vec<ChunkEntity>) -> usize {
+    let before = entities.len();
+    let mut merged: Vec<ChunkEntity> = Vec::with_capacity(before);
+    let mut targets: HashMap<(String, (i32, i32, i32)), usize> = HashMap::new();
+
+    for entity in entities.drain(..) {
+        if entity.is_protected() {
+            merged.push(entity);
+            continue;
+        }
+        let key = (entity.id.clone(), entity.block_position());
+        match targets.get(&key) {
+            Some(&target) if merged[target].item == entity.item => {
+                let target = &mut merged[target];
+                target.amount = target.amount.saturating_add(entity.amount);
+            }
+            _ => {
+                targets.insert(key, merged.len());
+                merged.push(entity);
+            }
+        }
+    }
+
+    *entities = merged;
+    before - entities.len()
+}
+
+/// Removes unprotected entities exceeding the limits, keeping the ones loaded first.
+fn cull(entities: &mut Vec<ChunkEntity>, config: &EntityPersistenceConfig) -> usize {
+    let before = entities.len();
+    let max_per_chunk = match config.max_entities_per_chunk {
+        0 => usize::MAX,
+        max => max as usize,
+    };
+    let max_per_type = match config.max_entities_per_type {
+        0 => usize::MAX,
+        max => max as usize,
+    };
+
+    // protected entities take up their share of the limits first
+    let mut total = 0;
+    let mut per_type: HashMap<String, usize> = HashMap::new();
+    for entity in entities.iter().filter(|entity| entity.is_protected()) {
+        total += 1;
+        *per_type.entry(entity.id.clone()).or_default() += 1;
+    }
+
+    entities.retain(|entity| {
+        if entity.is_protected() {
+            return true;
+        }
+        let count = per_type.entry(entity.id.clone()).or_default();
+        if total >= max_per_chunk || *count >= max_per_type {
+            return false;
+        }
+        total += 1;
+        *count += 1;
+        true
+    });
+    before - entities.len()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn entity(id: &str, x: f64) -> ChunkEntity {
+        ChunkEntity {
+            id: id.to_string(),
+            position: Vector3::new(x, 64.0, 0.5),
+            persistence_required: false,
+            custom_name: None,
+            owner: None,
+            amount: 1,
+            item: None,
+            persistent_data: PersistentDataContainer::default(),
+        }
+    }
+
+    fn config(strategy: EntityOverflowStrategy) -> EntityPersistenceConfig {
+        EntityPersistenceConfig {
+            max_entities_per_chunk: 10,
+            max_entities_per_type: 5,
+            overflow_strategy: strategy,
+            log_overflow: false,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn persistence_filters() {
+        let config = EntityPersistenceConfig {
+            persist_all: false,
+            persist_tamed: false,
+            ..Default::default()
+        };
+        let mut named = entity("minecraft:zombie", 0.0);
+        named.custom_name = Some("Bob".to_string());
+        let mut tamed = entity("minecraft:wolf", 0.0);
+        tamed.owner = Some(Uuid::new_v4());
+        let mut entities = vec![entity("minecraft:zombie", 0.0), named.clone(), tamed];
+
+        assert_eq!(retain_persistent(&mut entities, &config), 2);
+        assert_eq!(entities, vec![named]);
+    }
+
+    #[test]
+    fn nbt_round_trip() {
+        let mut tamed = entity("minecraft:wolf", 3.5);
+        tamed.owner = Some(Uuid::from_u128(0x0123_4567_89ab_cdef_fedc_ba98_7654_3210));
+        tamed.custom_name = Some("Rex".to_string());
+
+        let bytes = fastnbt::to_bytes(&EntityNbt::from(&tamed)).unwrap();
+        let read = fastnbt::from_bytes::<EntityNbt>(&bytes)
+            .unwrap()
+            .into_entity();
+        assert_eq!(read, Some(tamed));
+    }
+
+    #[test]
+    fn merge_stacked_mobs() {
+        let mut entities: Vec<_> = (0..1000).map(|_| entity("minecraft:cow", 0.5)).collect();
+        let mut named = entity("minecraft:cow", 0.5);
+        named.custom_name = Some("Bessie".to_string());
+        entities.push(named);
+
+        let report = apply_load_caps(
+            Vector2::new(0, 0),
+            &mut entities,
+            &config(EntityOverflowStrategy::Merge),
+        );
+        assert_eq!(report.merged, 999);
+        assert_eq!(report.culled, 0);
+        assert_eq!(entities.len(), 2);
+        assert_eq!(entities[0].amount, 1000);
+    }
+
+    #[test]
+    fn cull_keeps_protected() {
+        let mut entities: Vec<_> = (0..20)
+            .map(|i| entity("minecraft:zombie", f64::from(i)))
+            .collect();
+        entities[15].persistence_required = true;
+
+        let report = apply_load_caps(
+            Vector2::new(0, 0),
+            &mut entities,
+            &config(EntityOverflowStrategy::Cull),
+        );
+        assert_eq!(report.culled, 15);
+        assert_eq!(entities.len(), 5);
+        assert!(entities.iter().any(|entity| entity.persistence_required));
+    }
+
+    #[test]
+    fn under_caps_untouched() {
+        let mut entities: Vec<_> = (0..5).map(|_| entity("minecraft:pig", 0.0)).collect();
+        let report = apply_load_caps(
+            Vector2::new(0, 0),
+            &mut entities,
+            &config(EntityOverflowStrategy::Merge),
+        );
+        assert_eq!(report, OverflowReport::default());
+        assert_eq!(entities.len(), 5);
+    }
+}
@@ -0,0 +1,280 @@
+//! An experimental chunk storage which writes faster than region files: every written chunk is
+//! appended to one file, and an index in memory points to the latest copy of every chunk. The old
+//! copies are dropped when the file is opened while they take more space than the latest ones.
+//! The file is synced to the disk once a batch of chunks was written.
+//!
+//! A record is the chunk's x and z, the length of its data and the LZ4 compressed chunk NBT, the
+//! numbers are big endian.
+
+use std::{
+    collections::HashMap,
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+use pumpkin_core::math::vector2::Vector2;
+
+use super::{
+    anvil::Compression, ChunkReadingError, ChunkStorage, ChunkWritingError, CompressionError,
+};
+
+const RECORD_HEADER: u64 = 12;
+
+struct Log {
+    file: File,
+    /// Where the data of every chunk starts and how long it is
+    index: HashMap<Vector2<i32>, (u64, u32)>,
+    len: u64,
+}
+
+pub struct KeyValueChunkStorage {
+    path: PathBuf,
+    /// Opened when it is used first
+    log: parking_lot::Mutex<Option<Log>>,
+}
+
+impl KeyValueChunkStorage {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            log: parking_lot::Mutex::new(None),
+        }
+    }
+
+    fn with_log<T>(&self, f: impl FnOnce(&mut Log) -> io::Result<T>) -> io::Result<T> {
+        let mut log = self.log.lock();
+        let log = match &mut *log {
+            Some(log) => log,
+            None => log.insert(Log::open(&self.path)?),
+        };
+        f(log)
+    }
+}
+
+/// Only called with data whose length fits into the record's u32, see [`Log::append`]
+fn write_record(to: &mut impl Write, at: Vector2<i32>, data: &[u8], length: u32) -> io::Result<()> {
+    let mut header = [0; RECORD_HEADER as usize];
+    header[..4].copy_from_slice(&at.x.to_be_bytes());
+    header[4..8].copy_from_slice(&at.z.to_be_bytes());
+    header[8..].copy_from_slice(&length.to_be_bytes());
+    to.write_all(&header)?;
+    to.write_all(data)
+}
+
+impl Log {
+    fn open(path: &Path) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        let file_len = file.metadata()?.len();
+
+        let mut index = HashMap::new();
+        let mut len = 0;
+        let mut reader = BufReader::new(&file);
+        let mut header = [0; RECORD_HEADER as usize];
+        while reader.read_exact(&mut header).is_ok() {
+            let x = i32::from_be_bytes(header[..4].try_into().unwrap());
+            let z = i32::from_be_bytes(header[4..8].try_into().unwrap());
+            let length = u32::from_be_bytes(header[8..].try_into().unwrap());
+            let end = len + RECORD_HEADER + u64::from(length);
+            if end > file_len {
+                break;
+            }
+            reader.seek_relative(i64::from(length))?;
+            index.insert(Vector2::new(x, z), (len + RECORD_HEADER, length));
+            len = end;
+        }
+        if len < file_len {
+            // the server stopped while the chunk was written, the previous copy is still there
+            log::warn!(
+                "Dropping the incomplete chunk at the end of {}",
+                path.display()
+            );
+            file.set_len(len)?;
+        }
+
+        let log = Self { file, index, len };
+        let live = log
+            .index
+            .values()
+            .map(|(_, length)| RECORD_HEADER + u64::from(*length))
+            .sum::<u64>();
+        if len - live > live {
+            return log.compact(path);
+        }
+        Ok(log)
+    }
+
+    /// Writes the latest copy of every chunk to a new file which replaces the log
+    fn compact(mut self, path: &Path) -> io::Result<Self> {
+        let new_path = path.with_extension("kv_new");
+        let mut chunks = self
+            .index
+            .iter()
+            .map(|(at, (offset, length))| (*at, *offset, *length))
+            .collect::<Vec<_>>();
+        chunks.sort_unstable_by_key(|(_, offset, _)| *offset);
+
+        let mut writer = BufWriter::new(File::create(&new_path)?);
+        let mut index = HashMap::with_capacity(chunks.len());
+        let mut len = 0;
+        for (at, offset, length) in chunks {
+            let data = self.read(offset, length)?;
+            write_record(&mut writer, at, &data, length)?;
+            index.insert(at, (len + RECORD_HEADER, length));
+            len += RECORD_HEADER + u64::from(length);
+        }
+        writer
+            .into_inner()
+            .map_err(io::IntoInnerError::into_error)?
+            .sync_all()?;
+        // the old file has to be closed before it can be replaced on Windows
+        drop(self);
+        fs::rename(&new_path, path)?;
+
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        Ok(Self { file, index, len })
+    }
+
+    fn read(&mut self, offset: u64, length: u32) -> io::Result<Vec<u8>> {
+        let mut data = vec![0; length as usize];
+        self.file.seek(SeekFrom::Start(offset))?;
+        self.file.read_exact(&mut data)?;
+        Ok(data)
+    }
+
+    fn append(&mut self, at: Vector2<i32>, data: &[u8], length: u32) -> io::Result<()> {
+        let mut record = Vec::with_capacity(RECORD_HEADER as usize + data.len());
+        write_record(&mut record, at, data, length)?;
+        self.file.seek(SeekFrom::Start(self.len))?;
+        self.file.write_all(&record)?;
+        self.index.insert(at, (self.len + RECORD_HEADER, length));
+        self.len += record.len() as u64;
+        Ok(())
+    }
+}
+
+impl ChunkStorage for KeyValueChunkStorage {
+    fn read_raw_chunk(&self, at: &Vector2<i32>) -> Result<Vec<u8>, ChunkReadingError> {
+        let compressed = self
+            .with_log(|log| match log.index.get(at).copied() {
+                Some((offset, length)) => log.read(offset, length).map(Some),
+                None => Ok(None),
+            })
+            .map_err(|err| ChunkReadingError::IoError(err.kind()))?
+            .ok_or(ChunkReadingError::ChunkNotExist)?;
+        Compression::LZ4
+            .decompress_data(compressed)
+            .map_err(ChunkReadingError::Compression)
+    }
+
+    fn write_raw_chunk(&self, nbt: &[u8], at: &Vector2<i32>) -> Result<(), ChunkWritingError> {
+        let compression_error =
+            |err| ChunkWritingError::Compression(CompressionError::LZ4Error(err));
+        let mut encoder = lz4::EncoderBuilder::new()
+            .build(Vec::new())
+            .map_err(compression_error)?;
+        encoder.write_all(nbt).map_err(compression_error)?;
+        let (compressed, result) = encoder.finish();
+        result.map_err(compression_error)?;
+        let length =
+            u32::try_from(compressed.len()).map_err(|_| ChunkWritingError::ChunkTooLarge)?;
+
+        self.with_log(|log| log.append(*at, &compressed, length))
+            .map_err(|err| ChunkWritingError::IoError(err.kind()))
+    }
+
+    fn saved_chunks(&self) -> Result<Vec<Vector2<i32>>, ChunkReadingError> {
+        self.with_log(|log| Ok(log.index.keys().copied().collect()))
+            .map_err(|err| ChunkReadingError::IoError(err.kind()))
+    }
+
+    fn sync(&self) -> Result<(), ChunkWritingError> {
+        // nothing was written if the log was never opened
+        match &*self.log.lock() {
+            Some(log) => log
+                .file
+                .sync_data()
+                .map_err(|err| ChunkWritingError::IoError(err.kind())),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashMap, fs, io::Write, sync::atomic::AtomicBool};
+
+    use pumpkin_core::math::{vector2::Vector2, vector3::Vector3};
+
+    use super::KeyValueChunkStorage;
+    use crate::chunk::{ChunkBlocks, ChunkData, ChunkReadingError, ChunkStorage};
+
+    fn chunk_with_block(at: Vector2<i32>, state_id: u16) -> ChunkData {
+        let mut chunk = ChunkData {
+            blocks: ChunkBlocks::default(),
+            position: at,
+            persistent_data: Default::default(),
+            block_entity_data: HashMap::new(),
+            block_entities: HashMap::new(),
+            entities: Vec::new(),
+            light: None,
+            nbt: Default::default(),
+            dirty: AtomicBool::new(true),
+        };
+        chunk
+            .blocks
+            .set_block(Vector3::new(1, 2, 3).into(), state_id);
+        chunk
+    }
+
+    #[test]
+    fn latest_copies_are_read_after_reopening() {
+        let path = std::env::temp_dir().join(format!("pumpkin-kv-{}.kv", std::process::id()));
+        let first = Vector2::new(-4, 7);
+        let second = Vector2::new(100, -100);
+
+        let storage = KeyValueChunkStorage::new(path.clone());
+        for state_id in 1..=10 {
+            storage
+                .write_chunk(&chunk_with_block(first, state_id), &first)
+                .unwrap();
+        }
+        storage
+            .write_chunk(&chunk_with_block(second, 1), &second)
+            .unwrap();
+        drop(storage);
+        let written = fs::metadata(&path).unwrap().len();
+        // a chunk which was cut off while it was written
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(&[0, 0, 0, 1, 0, 0]))
+            .unwrap();
+
+        let storage = KeyValueChunkStorage::new(path.clone());
+        let read = storage.read_chunk(&first);
+        let missing = storage.read_chunk(&Vector2::new(0, 0));
+        let mut saved = storage.saved_chunks().unwrap();
+        let compacted = fs::metadata(&path).unwrap().len();
+        drop(storage);
+        let _ = fs::remove_file(&path);
+
+        let read = read.unwrap();
+        assert_eq!(
+            read.blocks.get_block(Vector3::new(1, 2, 3).into()),
+            Some(10)
+        );
+        assert!(matches!(missing, Err(ChunkReadingError::ChunkNotExist)));
+        saved.sort_unstable_by_key(|at| at.x);
+        assert_eq!(saved, vec![first, second]);
+        assert!(compacted < written);
+    }
+}
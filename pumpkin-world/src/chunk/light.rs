@@ -0,0 +1,570 @@
+//! Sky and block light of chunks. The [`LightEngine`] lights chunks on the rayon threads.
+//!
+//! Generated chunks, chunks read without valid light, e.g. imported ones or ones written by an
+//! older Pumpkin, and chunks whose blocks changed how light spreads are lit in the background.
+//! Until a chunk is lit, players get it with full sky light.
+//!
+//! Light spreads across chunk borders: a chunk is lit with the blocks of the loaded chunks around
+//! it, and it is lit again once a chunk around it which was missing is loaded. Chunks which are
+//! not loaded block the light.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, LazyLock},
+};
+
+use dashmap::DashMap;
+use fastnbt::ByteArray;
+use pumpkin_core::math::vector2::Vector2;
+use tokio::sync::{oneshot, RwLock};
+
+use crate::{block::block_registry::BLOCKS, coordinates::ChunkRelativeBlockCoordinates};
+
+use super::{ChunkBlocks, ChunkData, CHUNK_AREA, SUBCHUNK_VOLUME};
+
+pub const MAX_LIGHT: u8 = 15;
+/// The light of a subchunk takes half a byte per block
+pub const LIGHT_ARRAY_LEN: usize = SUBCHUNK_VOLUME / 2;
+
+/// The light of a subchunk, in the order of its blocks. Even blocks are in the lower half of a byte
+pub type LightArray = [u8; LIGHT_ARRAY_LEN];
+
+/// Called with the position of a chunk once it was lit
+pub type RelightListener = Arc<dyn Fn(Vector2<i32>) + Send + Sync>;
+
+/// Where the chunks around a chunk are, row by row from the north west. The chunk at the opposite
+/// offset of index `i` is at index `7 - i`
+pub const NEIGHBOUR_OFFSETS: [(i32, i32); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// The blocks of the chunks around a chunk in the order of [`NEIGHBOUR_OFFSETS`], `None` where no
+/// chunk is loaded
+pub type Neighbours = [Option<ChunkBlocks>; 8];
+
+/// How far light reaches, a chunk is lit with the blocks this far around it
+const PADDING: usize = MAX_LIGHT as usize;
+const PADDED_WIDTH: usize = 16 + 2 * PADDING;
+const PADDED_AREA: usize = PADDED_WIDTH * PADDED_WIDTH;
+/// Chunks which are not loaded block the light
+const NOT_LOADED: (u8, u8) = (MAX_LIGHT, 0);
+/// Light read from a save was lit with every chunk around it
+const ALL_NEIGHBOURS: u8 = u8::MAX;
+
+/// How much light every block state takes away and how much it emits, by state id
+static LIGHT_PROPERTIES: LazyLock<Vec<(u8, u8)>> = LazyLock::new(|| {
+    let mut properties = Vec::new();
+    for state in BLOCKS.blocks.iter().flat_map(|block| &block.states) {
+        let id = usize::from(state.id);
+        if properties.len() <= id {
+            properties.resize(id + 1, (0, 0));
+        }
+        // blocks without a fixed opacity, like glass or water, let the light through
+        let opacity = state.opacity.map_or(0, |opacity| {
+            u8::try_from(opacity.min(u32::from(MAX_LIGHT))).unwrap_or(MAX_LIGHT)
+        });
+        properties[id] = (opacity, state.luminance.min(MAX_LIGHT));
+    }
+    properties
+});
+
+/// Subchunks without light and subchunks in full sky light share their light
+static DARK: LazyLock<Arc<LightArray>> = LazyLock::new(|| Arc::new([0; LIGHT_ARRAY_LEN]));
+static FULL: LazyLock<Arc<LightArray>> = LazyLock::new(|| Arc::new([0xFF; LIGHT_ARRAY_LEN]));
+
+fn light_properties(state_id: u16) -> (u8, u8) {
+    LIGHT_PROPERTIES
+        .get(usize::from(state_id))
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Whether replacing the block with the other changes how light spreads, so the chunk has to be
+/// lit again
+#[must_use]
+pub fn changes_light(old: u16, new: u16) -> bool {
+    old != new && light_properties(old) != light_properties(new)
+}
+
+#[derive(Clone)]
+pub struct ChunkLight {
+    /// By subchunk from the bottom up, like the blocks
+    sky: Box<[Arc<LightArray>]>,
+    block: Box<[Arc<LightArray>]>,
+    /// Which chunks around it were loaded when it was lit, bit `i` for [`NEIGHBOUR_OFFSETS`]`[i]`
+    neighbours: u8,
+}
+
+impl ChunkLight {
+    /// Lights the blocks: sky light shines down from the top of the chunks and every block emits
+    /// its luminance. Both lose a level per block they spread, and more through blocks which block
+    /// them. Light from the blocks of the chunks around it spreads into the chunk
+    #[must_use]
+    pub fn compute(blocks: &ChunkBlocks, neighbours: &Neighbours) -> Self {
+        let height = blocks.len() / CHUNK_AREA;
+        let mut properties = vec![NOT_LOADED; PADDED_AREA * height];
+        let mut loaded_columns = vec![false; PADDED_AREA];
+        for z in 0..PADDED_WIDTH {
+            for x in 0..PADDED_WIDTH {
+                // the chunk of the column, 1, 1 is the lit one
+                let (chunk_x, chunk_z) = ((x + 16 - PADDING) / 16, (z + 16 - PADDING) / 16);
+                let chunk = match neighbour_index(chunk_x, chunk_z) {
+                    None => Some(blocks),
+                    Some(index) => neighbours[index].as_ref(),
+                };
+                let Some(chunk) = chunk else {
+                    continue;
+                };
+                let column = z * PADDED_WIDTH + x;
+                loaded_columns[column] = true;
+                let in_chunk = (z + 16 - PADDING) % 16 * 16 + (x + 16 - PADDING) % 16;
+                for (subchunk_y, subchunk) in chunk.iter_subchunks().take(height / 16).enumerate() {
+                    for y in 0..16 {
+                        properties[(subchunk_y * 16 + y) * PADDED_AREA + column] =
+                            light_properties(subchunk[y * CHUNK_AREA + in_chunk]);
+                    }
+                }
+            }
+        }
+
+        let mut sky = vec![0; properties.len()];
+        let mut queue = VecDeque::new();
+        for column in (0..PADDED_AREA).filter(|column| loaded_columns[*column]) {
+            let mut light = MAX_LIGHT;
+            for y in (0..height).rev() {
+                let index = y * PADDED_AREA + column;
+                light = light.saturating_sub(properties[index].0);
+                if light == 0 {
+                    break;
+                }
+                sky[index] = light;
+                queue.push_back(index);
+            }
+        }
+        spread(&mut sky, &properties, queue);
+
+        let mut block = vec![0; properties.len()];
+        let mut queue = VecDeque::new();
+        for (index, (_, luminance)) in properties.iter().enumerate() {
+            if *luminance > 0 {
+                block[index] = *luminance;
+                queue.push_back(index);
+            }
+        }
+        spread(&mut block, &properties, queue);
+
+        let neighbours = neighbours
+            .iter()
+            .enumerate()
+            .filter(|(_, neighbour)| neighbour.is_some())
+            .fold(0, |mask, (i, _)| mask | 1 << i);
+        Self {
+            sky: pack(&inner_chunk(&sky, height)),
+            block: pack(&inner_chunk(&block, height)),
+            neighbours,
+        }
+    }
+
+    /// Reads the light saved with the subchunks, from the bottom up. `None` if a subchunk below
+    /// the sky has no sky light or the light of a subchunk is broken, the chunk has to be lit
+    /// again then. Subchunks without block light have none
+    pub(super) fn from_saved(
+        sky: Vec<Option<ByteArray>>,
+        block: Vec<Option<ByteArray>>,
+    ) -> Option<Self> {
+        let mut open_sky = true;
+        let mut sky = sky
+            .into_iter()
+            .rev()
+            .map(|saved| match saved {
+                Some(saved) => {
+                    open_sky = false;
+                    unpack_saved(&saved.into_inner())
+                }
+                None if open_sky => Some(FULL.clone()),
+                None => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+        sky.reverse();
+        let block = block
+            .into_iter()
+            .map(|saved| {
+                saved.map_or_else(
+                    || Some(DARK.clone()),
+                    |saved| unpack_saved(&saved.into_inner()),
+                )
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self {
+            sky: sky.into_boxed_slice(),
+            block: block.into_boxed_slice(),
+            neighbours: ALL_NEIGHBOURS,
+        })
+    }
+
+    /// Whether the chunk around it at [`NEIGHBOUR_OFFSETS`]`[index]` was loaded when it was lit
+    #[must_use]
+    pub fn has_neighbour(&self, index: usize) -> bool {
+        self.neighbours & 1 << index != 0
+    }
+
+    pub fn sky_sections(&self) -> &[Arc<LightArray>] {
+        &self.sky
+    }
+
+    pub fn block_sections(&self) -> &[Arc<LightArray>] {
+        &self.block
+    }
+
+    pub fn sky_light(&self, position: ChunkRelativeBlockCoordinates) -> u8 {
+        get_light(&self.sky, position)
+    }
+
+    pub fn block_light(&self, position: ChunkRelativeBlockCoordinates) -> u8 {
+        get_light(&self.block, position)
+    }
+}
+
+/// The index of the chunk in [`Neighbours`], `None` for the chunk in the middle
+fn neighbour_index(chunk_x: usize, chunk_z: usize) -> Option<usize> {
+    match chunk_z * 3 + chunk_x {
+        4 => None,
+        index if index > 4 => Some(index - 1),
+        index => Some(index),
+    }
+}
+
+/// The light of the chunk in the middle of the light of the chunks around it, ordered like its
+/// blocks
+fn inner_chunk(light: &[u8], height: usize) -> Vec<u8> {
+    let mut inner = Vec::with_capacity(height * CHUNK_AREA);
+    for y in 0..height {
+        for z in 0..16 {
+            let start = y * PADDED_AREA + (z + PADDING) * PADDED_WIDTH + PADDING;
+            inner.extend_from_slice(&light[start..start + 16]);
+        }
+    }
+    inner
+}
+
+/// Spreads the light of the queued blocks to their neighbours around the chunk
+fn spread(light: &mut [u8], properties: &[(u8, u8)], mut queue: VecDeque<usize>) {
+    let height = light.len() / PADDED_AREA;
+    while let Some(index) = queue.pop_front() {
+        let level = light[index];
+        let (x, z, y) = (
+            index % PADDED_WIDTH,
+            index / PADDED_WIDTH % PADDED_WIDTH,
+            index / PADDED_AREA,
+        );
+        let neighbours = [
+            (x > 0).then(|| index - 1),
+            (x + 1 < PADDED_WIDTH).then(|| index + 1),
+            (z > 0).then(|| index - PADDED_WIDTH),
+            (z + 1 < PADDED_WIDTH).then(|| index + PADDED_WIDTH),
+            (y > 0).then(|| index - PADDED_AREA),
+            (y + 1 < height).then(|| index + PADDED_AREA),
+        ];
+        for neighbour in neighbours.into_iter().flatten() {
+            let spread = level.saturating_sub(properties[neighbour].0.max(1));
+            if spread > light[neighbour] {
+                light[neighbour] = spread;
+                queue.push_back(neighbour);
+            }
+        }
+    }
+}
+
+fn pack(light: &[u8]) -> Box<[Arc<LightArray>]> {
+    light
+        .chunks(SUBCHUNK_VOLUME)
+        .map(|subchunk| {
+            let mut packed = [0; LIGHT_ARRAY_LEN];
+            for (byte, pair) in packed.iter_mut().zip(subchunk.chunks(2)) {
+                *byte = pair[0] | (pair[1] << 4);
+            }
+            share(packed)
+        })
+        .collect()
+}
+
+fn unpack_saved(saved: &[i8]) -> Option<Arc<LightArray>> {
+    let mut light = [0; LIGHT_ARRAY_LEN];
+    if saved.len() != light.len() {
+        return None;
+    }
+    for (byte, saved) in light.iter_mut().zip(saved) {
+        *byte = *saved as u8;
+    }
+    Some(share(light))
+}
+
+fn share(light: LightArray) -> Arc<LightArray> {
+    if light == **DARK {
+        DARK.clone()
+    } else if light == **FULL {
+        FULL.clone()
+    } else {
+        Arc::new(light)
+    }
+}
+
+/// The light of a subchunk like it is saved in the `SkyLight` and `BlockLight` of a section
+pub(super) fn to_saved(light: &LightArray) -> ByteArray {
+    ByteArray::new(light.iter().map(|byte| *byte as i8).collect())
+}
+
+fn get_light(sections: &[Arc<LightArray>], position: ChunkRelativeBlockCoordinates) -> u8 {
+    let index = ChunkBlocks::convert_index(position);
+    sections.get(index / SUBCHUNK_VOLUME).map_or(0, |section| {
+        let byte = section[index % SUBCHUNK_VOLUME / 2];
+        if index % 2 == 0 {
+            byte & 0x0F
+        } else {
+            byte >> 4
+        }
+    })
+}
+
+/// Lights chunks on the rayon threads. A chunk queued again before it is lit is only lit once
+#[derive(Clone)]
+pub struct LightEngine {
+    /// The chunks of the level, the blocks of the chunks around a chunk are lit with it
+    loaded_chunks: Arc<DashMap<Vector2<i32>, Arc<RwLock<ChunkData>>>>,
+    /// The chunks waiting to be lit and who waits for them
+    queued: Arc<parking_lot::Mutex<HashMap<Vector2<i32>, Vec<oneshot::Sender<()>>>>>,
+    listener: Option<RelightListener>,
+}
+
+impl LightEngine {
+    #[must_use]
+    pub fn new(loaded_chunks: Arc<DashMap<Vector2<i32>, Arc<RwLock<ChunkData>>>>) -> Self {
+        Self {
+            loaded_chunks,
+            queued: Arc::default(),
+            listener: None,
+        }
+    }
+
+    pub fn set_listener(&mut self, listener: RelightListener) {
+        self.listener = Some(listener);
+    }
+
+    /// Number of chunks waiting to be lit
+    #[must_use]
+    pub fn queued_count(&self) -> usize {
+        self.queued.lock().len()
+    }
+
+    /// Lights the chunk in the background
+    pub fn queue(&self, position: Vector2<i32>, chunk: Arc<RwLock<ChunkData>>) {
+        self.enqueue(position, chunk, None);
+    }
+
+    /// Lights the chunk in the background, the receiver is notified once it is lit
+    pub fn queue_and_notify(
+        &self,
+        position: Vector2<i32>,
+        chunk: Arc<RwLock<ChunkData>>,
+    ) -> oneshot::Receiver<()> {
+        let (sender, receiver) = oneshot::channel();
+        self.enqueue(position, chunk, Some(sender));
+        receiver
+    }
+
+    /// Lights the chunk and the loaded chunks around it again, after its blocks changed how light
+    /// spreads
+    pub fn queue_around(&self, position: Vector2<i32>, chunk: Arc<RwLock<ChunkData>>) {
+        self.enqueue(position, chunk, None);
+        for (_, neighbour, chunk) in self.loaded_neighbours(position) {
+            self.enqueue(neighbour, chunk, None);
+        }
+    }
+
+    /// Lights the loaded chunks around a chunk which was just loaded again if they were lit
+    /// without it. Generated chunks were not there when the chunks around them were lit
+    pub fn chunk_loaded(&self, position: Vector2<i32>, generated: bool) {
+        for (index, neighbour, chunk) in self.loaded_neighbours(position) {
+            // the loaded chunk is at the opposite offset from the chunk around it
+            let lit_with_it = !generated
+                && chunk.try_read().is_ok_and(|chunk| {
+                    chunk
+                        .light
+                        .as_ref()
+                        .is_some_and(|light| light.has_neighbour(7 - index))
+                });
+            if !lit_with_it {
+                self.enqueue(neighbour, chunk, None);
+            }
+        }
+    }
+
+    /// The loaded chunks around the chunk with their index in [`NEIGHBOUR_OFFSETS`]
+    fn loaded_neighbours(
+        &self,
+        position: Vector2<i32>,
+    ) -> Vec<(usize, Vector2<i32>, Arc<RwLock<ChunkData>>)> {
+        NEIGHBOUR_OFFSETS
+            .iter()
+            .enumerate()
+            .filter_map(|(index, (x, z))| {
+                let neighbour = Vector2::new(position.x + x, position.z + z);
+                let chunk = self.loaded_chunks.get(&neighbour)?.value().clone();
+                Some((index, neighbour, chunk))
+            })
+            .collect()
+    }
+
+    fn enqueue(
+        &self,
+        position: Vector2<i32>,
+        chunk: Arc<RwLock<ChunkData>>,
+        waiter: Option<oneshot::Sender<()>>,
+    ) {
+        {
+            let mut queued = self.queued.lock();
+            if let Some(waiting) = queued.get_mut(&position) {
+                waiting.extend(waiter);
+                return;
+            }
+            queued.insert(position, waiter.into_iter().collect());
+        }
+        let engine = self.clone();
+        rayon::spawn(move || {
+            // blocks changed while the chunk is lit queue it again
+            let waiting = engine.queued.lock().remove(&position).unwrap_or_default();
+            let blocks = chunk.blocking_read().blocks.clone();
+            let neighbours = NEIGHBOUR_OFFSETS.map(|(x, z)| {
+                let neighbour = engine
+                    .loaded_chunks
+                    .get(&Vector2::new(position.x + x, position.z + z))
+                    .map(|entry| entry.value().clone());
+                neighbour.map(|neighbour| neighbour.blocking_read().blocks.clone())
+            });
+            let light = ChunkLight::compute(&blocks, &neighbours);
+            let changed = {
+                let mut chunk = chunk.blocking_write();
+                let changed = chunk
+                    .light
+                    .as_ref()
+                    .is_none_or(|old| old.sky != light.sky || old.block != light.block);
+                chunk.light = Some(light);
+                // chunks lit the same way again don't have to be written or sent again
+                if changed {
+                    chunk.mark_dirty();
+                }
+                changed
+            };
+            if let (true, Some(listener)) = (changed, &engine.listener) {
+                listener(position);
+            }
+            for sender in waiting {
+                let _ = sender.send(());
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use fastnbt::ByteArray;
+    use pumpkin_core::math::vector3::Vector3;
+
+    use super::{ChunkLight, Neighbours, MAX_LIGHT};
+    use crate::{block::block_registry::get_block, chunk::ChunkBlocks};
+
+    fn state(name: &str) -> u16 {
+        get_block(name).unwrap().default_state_id
+    }
+
+    #[test]
+    fn sky_and_block_light_spread() {
+        let stone = state("minecraft:stone");
+        let mut blocks = ChunkBlocks::default();
+        // a stone roof over the chunk with a hole at 0, 0 and a glowstone under it
+        for x in 0..16 {
+            for z in 0..16 {
+                if (x, z) != (0, 0) {
+                    blocks.set_block(Vector3::new(x, 100, z).into(), stone);
+                }
+            }
+        }
+        blocks.set_block(Vector3::new(8, 90, 8).into(), state("minecraft:glowstone"));
+
+        let light = ChunkLight::compute(&blocks, &Neighbours::default());
+        assert_eq!(light.sky_light(Vector3::new(5, 120, 5).into()), MAX_LIGHT);
+        assert_eq!(light.sky_light(Vector3::new(5, 100, 5).into()), 0);
+        // straight down through the hole, then one less per block to the side
+        assert_eq!(light.sky_light(Vector3::new(0, 50, 0).into()), MAX_LIGHT);
+        assert_eq!(
+            light.sky_light(Vector3::new(3, 99, 0).into()),
+            MAX_LIGHT - 3
+        );
+        assert_eq!(light.block_light(Vector3::new(8, 90, 8).into()), 15);
+        assert_eq!(light.block_light(Vector3::new(8, 88, 9).into()), 12);
+        assert_eq!(light.block_light(Vector3::new(8, 101, 8).into()), 0);
+    }
+
+    #[test]
+    fn light_crosses_chunk_borders() {
+        let stone = state("minecraft:stone");
+        // the chunk and the one east of it are roofed, a glowstone is at the east border
+        let mut roofed = ChunkBlocks::default();
+        for x in 0..16 {
+            for z in 0..16 {
+                roofed.set_block(Vector3::new(x, 100, z).into(), stone);
+            }
+        }
+        let mut blocks = roofed.clone();
+        blocks.set_block(Vector3::new(15, 90, 8).into(), state("minecraft:glowstone"));
+        let mut neighbours = Neighbours::default();
+        neighbours[4] = Some(roofed.clone());
+
+        let light = ChunkLight::compute(&blocks, &neighbours);
+        let east = ChunkLight::compute(&roofed, &{
+            let mut neighbours = Neighbours::default();
+            neighbours[3] = Some(blocks.clone());
+            neighbours
+        });
+        assert_eq!(light.block_light(Vector3::new(15, 90, 8).into()), 15);
+        assert_eq!(east.block_light(Vector3::new(0, 90, 8).into()), 14);
+        assert_eq!(east.block_light(Vector3::new(3, 90, 8).into()), 11);
+        assert!(light.has_neighbour(4));
+        assert!(!light.has_neighbour(3));
+        // sky light from the open chunk west of the roofed one shines in under the roof
+        let mut open_west = Neighbours::default();
+        open_west[3] = Some(ChunkBlocks::default());
+        let lit_from_west = ChunkLight::compute(&roofed, &open_west);
+        assert_eq!(
+            lit_from_west.sky_light(Vector3::new(0, 90, 8).into()),
+            MAX_LIGHT - 1
+        );
+        assert_eq!(
+            ChunkLight::compute(&roofed, &Neighbours::default())
+                .sky_light(Vector3::new(0, 90, 8).into()),
+            0
+        );
+    }
+
+    #[test]
+    fn broken_saved_light_is_rejected() {
+        let full = || Some(ByteArray::new(vec![-1; 2048]));
+        let read = ChunkLight::from_saved(vec![full(), None], vec![None, None]).unwrap();
+        assert_eq!(read.sky_light(Vector3::new(0, -64, 0).into()), MAX_LIGHT);
+        // the top subchunk is under the open sky
+        assert_eq!(read.sky_light(Vector3::new(0, -48, 0).into()), MAX_LIGHT);
+        assert_eq!(read.block_light(Vector3::new(0, -64, 0).into()), 0);
+
+        assert!(ChunkLight::from_saved(vec![None, full()], vec![None, None]).is_none());
+        assert!(
+            ChunkLight::from_saved(vec![Some(ByteArray::new(vec![0; 10]))], vec![None]).is_none()
+        );
+    }
+}
@@ -1,31 +1,97 @@
 use std::cmp::max;
 use std::collections::HashMap;
 use std::ops::Index;
+use std::path::Path;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
 
-use fastnbt::LongArray;
-use pumpkin_core::math::vector2::Vector2;
+use anvil::AnvilChunkStorage;
+use entities::{ChunkEntity, EntityNbt};
+use fastnbt::{ByteArray, LongArray, Value};
+use key_value::KeyValueChunkStorage;
+use light::ChunkLight;
+use pumpkin_config::ChunkStorageKind;
+use pumpkin_core::{
+    math::{position::WorldPosition, vector2::Vector2, vector3::Vector3},
+    nbt::Compound,
+    persistent_data::{self, PersistentDataContainer, PersistentDataType},
+};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::{
-    block::BlockState,
+    block::{
+        block_registry::{get_block, get_block_by_state_id},
+        BlockState,
+    },
     coordinates::{ChunkRelativeBlockCoordinates, Height},
-    level::SaveFile,
-    WORLD_HEIGHT,
+    DATA_VERSION, WORLD_HEIGHT, WORLD_LOWEST_Y,
 };
 
 pub mod anvil;
+pub mod cache;
+pub mod convert;
+pub mod entities;
+pub mod key_value;
+pub mod light;
 
 const CHUNK_AREA: usize = 16 * 16;
 const SUBCHUNK_VOLUME: usize = CHUNK_AREA * 16;
 const CHUNK_VOLUME: usize = CHUNK_AREA * WORLD_HEIGHT;
 
-pub trait ChunkReader: Sync + Send {
-    fn read_chunk(
-        &self,
-        save_file: &SaveFile,
-        at: &Vector2<i32>,
-    ) -> Result<ChunkData, ChunkReadingError>;
+/// The tags of a chunk which Pumpkin writes from what it keeps in [`ChunkData`], the others are
+/// written back like they were read
+const OWNED_TAGS: [&str; 9] = [
+    "DataVersion",
+    "xPos",
+    "zPos",
+    "yPos",
+    "Status",
+    "isLightOn",
+    "ChunkBukkitValues",
+    "block_entities",
+    "entities",
+];
+/// The tags of a section which Pumpkin writes, biomes are kept like they were read
+const OWNED_SECTION_TAGS: [&str; 3] = ["block_states", "SkyLight", "BlockLight"];
+
+/// Where the chunks of a world are saved, `WorldConfig::storage` picks the implementation
+pub trait ChunkStorage: Sync + Send {
+    /// The uncompressed NBT of the chunk like it was saved
+    fn read_raw_chunk(&self, at: &Vector2<i32>) -> Result<Vec<u8>, ChunkReadingError>;
+
+    /// Saves the uncompressed NBT of the chunk
+    fn write_raw_chunk(&self, nbt: &[u8], at: &Vector2<i32>) -> Result<(), ChunkWritingError>;
+
+    /// The positions of every saved chunk, e.g. to convert them to another storage
+    fn saved_chunks(&self) -> Result<Vec<Vector2<i32>>, ChunkReadingError>;
+
+    /// Makes sure the chunks written so far survive a crash of the system, called once after a
+    /// batch of chunks was written
+    fn sync(&self) -> Result<(), ChunkWritingError> {
+        Ok(())
+    }
+
+    fn read_chunk(&self, at: &Vector2<i32>) -> Result<ChunkData, ChunkReadingError> {
+        let nbt = self.read_raw_chunk(at)?;
+        ChunkData::from_bytes(nbt, *at).map_err(ChunkReadingError::ParsingError)
+    }
+
+    fn write_chunk(&self, chunk: &ChunkData, at: &Vector2<i32>) -> Result<(), ChunkWritingError> {
+        self.write_raw_chunk(&chunk.to_bytes()?, at)
+    }
+}
+
+/// Opens the chunks saved in the world's folder
+pub fn open_storage(kind: ChunkStorageKind, root_folder: &Path) -> Arc<dyn ChunkStorage> {
+    match kind {
+        ChunkStorageKind::Anvil => Arc::new(AnvilChunkStorage::new(root_folder.join("region"))),
+        ChunkStorageKind::KeyValue => {
+            Arc::new(KeyValueChunkStorage::new(root_folder.join("chunks.kv")))
+        }
+    }
 }
 
 #[derive(Error, Debug)]
@@ -42,6 +108,18 @@ pub enum ChunkReadingError {
     ParsingError(ChunkParsingError),
 }
 
+#[derive(Error, Debug)]
+pub enum ChunkWritingError {
+    #[error("Io error: {0}")]
+    IoError(std::io::ErrorKind),
+    #[error("Compression error {0}")]
+    Compression(CompressionError),
+    #[error("Failed to serialize the chunk: {0}")]
+    ChunkSerializingError(String),
+    #[error("The chunk is too large for the storage")]
+    ChunkTooLarge,
+}
+
 #[derive(Error, Debug)]
 pub enum CompressionError {
     #[error("Compression scheme not recognised")]
@@ -57,27 +135,92 @@ pub enum CompressionError {
 pub struct ChunkData {
     pub blocks: ChunkBlocks,
     pub position: Vector2<i32>,
+    /// Custom data of plugins
+    pub persistent_data: PersistentDataContainer,
+    /// Custom data of plugins on the block entities in the chunk
+    pub block_entity_data: HashMap<WorldPosition, PersistentDataContainer>,
+    /// The NBT of the block entities in the chunk without the custom data of plugins, like the
+    /// items of chests or the text of signs
+    pub block_entities: HashMap<WorldPosition, Compound>,
+    /// The entities saved when the chunk was last unloaded or saved, the live ones are in the world
+    pub entities: Vec<ChunkEntity>,
+    /// `None` until the chunk is lit, see [`light`]
+    pub light: Option<ChunkLight>,
+    /// The tags the chunk was read with which Pumpkin doesn't know, like biomes, structures or
+    /// scheduled ticks. They are written back unchanged, so saving a vanilla chunk loses nothing
+    pub nbt: Arc<Compound>,
+    /// Whether the chunk changed since it was last written, clean chunks are not written again
+    pub dirty: AtomicBool,
 }
-pub struct ChunkBlocks {
-    // TODO make this a Vec that doesn't store the upper layers that only contain air
+/// The blocks of a subchunk, ordering: yzx (y being the most significant)
+type Section = [u16; SUBCHUNK_VOLUME];
 
+/// Cloning the blocks only clones the references to the subchunks, a subchunk is copied when it
+/// is changed while it is shared
+#[derive(Clone)]
+pub struct ChunkBlocks {
     // The packet relies on this ordering -> leave it like this for performance
-    /// Ordering: yzx (y being the most significant)
-    blocks: Box<[u16; CHUNK_VOLUME]>,
+    /// The subchunks from the bottom up, subchunks of air share the same memory until blocks are
+    /// set in them
+    sections: Box<[Arc<Section>]>,
 
     /// See `https://minecraft.wiki/w/Heightmap` for more info
     pub heightmap: ChunkHeightmaps,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "PascalCase")]
 struct PaletteEntry {
     name: String,
-    _properties: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    properties: Option<HashMap<String, String>>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+impl PaletteEntry {
+    fn from_state_id(state_id: u16) -> Self {
+        let Some(block) = get_block_by_state_id(state_id) else {
+            return Self {
+                name: "minecraft:air".to_string(),
+                properties: None,
+            };
+        };
+        let properties = block
+            .properties_of_state(state_id)
+            .filter(|properties| !properties.is_empty())
+            .map(|properties| {
+                properties
+                    .into_iter()
+                    .map(|(name, value)| (name.to_string(), value.to_string()))
+                    .collect()
+            });
+        Self {
+            name: block.name.clone(),
+            properties,
+        }
+    }
+
+    /// Blocks which don't exist in this version become air, properties which don't exist keep
+    /// their default
+    fn state_id(&self) -> u16 {
+        let Some(block) = get_block(&self.name) else {
+            return BlockState::AIR.get_id();
+        };
+        self.properties
+            .as_ref()
+            .and_then(|properties| {
+                let properties = properties
+                    .iter()
+                    .map(|(name, value)| (name.as_str(), value.as_str()))
+                    .collect::<Vec<_>>();
+                block.state_id_with_properties(&properties)
+            })
+            .unwrap_or(block.default_state_id)
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
 struct ChunkSectionBlockStates {
+    #[serde(skip_serializing_if = "Option::is_none")]
     data: Option<LongArray>,
     palette: Vec<PaletteEntry>,
 }
@@ -89,12 +232,19 @@ pub struct ChunkHeightmaps {
     world_surface: LongArray,
 }
 
-#[derive(Deserialize, Debug)]
-#[expect(dead_code)]
+#[derive(Deserialize, Serialize, Debug)]
 struct ChunkSection {
     #[serde(rename = "Y")]
-    y: i32,
+    y: i8,
     block_states: Option<ChunkSectionBlockStates>,
+    #[serde(rename = "SkyLight", default, skip_serializing_if = "Option::is_none")]
+    sky_light: Option<ByteArray>,
+    #[serde(
+        rename = "BlockLight",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    block_light: Option<ByteArray>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -107,6 +257,57 @@ struct ChunkNbt {
     sections: Vec<ChunkSection>,
 
     heightmaps: ChunkHeightmaps,
+
+    /// Whether the light saved with the sections is complete
+    #[serde(rename = "isLightOn", default)]
+    is_light_on: bool,
+
+    #[serde(rename = "ChunkBukkitValues", default)]
+    persistent_data: PersistentDataContainer,
+
+    #[serde(rename = "block_entities", default)]
+    block_entities: Vec<BlockEntityNbt>,
+
+    #[serde(default)]
+    entities: Vec<EntityNbt>,
+}
+
+/// The tags Pumpkin writes, they are merged into the tags the chunk was read with. Chunks which
+/// were not lit yet are saved without light, so they are lit when they are read again
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct ChunkNbtOut<'a> {
+    data_version: i32,
+    #[serde(rename = "xPos")]
+    x_pos: i32,
+    #[serde(rename = "zPos")]
+    z_pos: i32,
+    #[serde(rename = "yPos")]
+    y_pos: i32,
+    status: &'static str,
+    #[serde(rename = "sections")]
+    sections: Vec<ChunkSection>,
+    heightmaps: &'a ChunkHeightmaps,
+    #[serde(rename = "isLightOn")]
+    is_light_on: bool,
+    #[serde(
+        rename = "ChunkBukkitValues",
+        skip_serializing_if = "PersistentDataContainer::is_empty"
+    )]
+    persistent_data: &'a PersistentDataContainer,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    entities: Vec<EntityNbt>,
+}
+
+/// The custom data of plugins on a block entity, the rest of it is kept in
+/// [`ChunkData::block_entities`]
+#[derive(Deserialize, Debug)]
+struct BlockEntityNbt {
+    x: i32,
+    y: i32,
+    z: i32,
+    #[serde(rename = "PublicBukkitValues", default)]
+    persistent_data: PersistentDataContainer,
 }
 
 #[derive(Deserialize, Debug, PartialEq, Eq)]
@@ -151,36 +352,40 @@ impl Default for ChunkHeightmaps {
 
 impl Default for ChunkBlocks {
     fn default() -> Self {
-        Self {
-            blocks: Box::new([0; CHUNK_VOLUME]),
-            heightmap: ChunkHeightmaps::default(),
-        }
+        Self::empty_with_heightmap(ChunkHeightmaps::default())
     }
 }
 
 impl ChunkBlocks {
-    pub const fn len(&self) -> usize {
-        self.blocks.len()
+    /// The memory the blocks of a chunk take at most, in bytes
+    pub const MEMORY: usize = size_of::<[u16; CHUNK_VOLUME]>() + size_of::<Self>();
+
+    pub fn len(&self) -> usize {
+        self.sections.len() * SUBCHUNK_VOLUME
     }
 
-    pub const fn is_empty(&self) -> bool {
-        self.blocks.is_empty()
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
     }
 
-    pub const fn subchunks_len(&self) -> usize {
-        self.blocks.len().div_ceil(SUBCHUNK_VOLUME)
+    pub fn subchunks_len(&self) -> usize {
+        self.sections.len()
     }
 
     pub fn empty_with_heightmap(heightmap: ChunkHeightmaps) -> Self {
+        let air = Arc::new([0; SUBCHUNK_VOLUME]);
         Self {
-            blocks: Box::new([0; CHUNK_VOLUME]),
+            sections: vec![air; CHUNK_VOLUME / SUBCHUNK_VOLUME].into_boxed_slice(),
             heightmap,
         }
     }
 
     /// Gets the given block in the chunk
     pub fn get_block(&self, position: ChunkRelativeBlockCoordinates) -> Option<u16> {
-        self.blocks.get(Self::convert_index(position)).copied()
+        let index = Self::convert_index(position);
+        self.sections
+            .get(index / SUBCHUNK_VOLUME)
+            .map(|section| section[index % SUBCHUNK_VOLUME])
     }
 
     /// Sets the given block in the chunk, returning the old block
@@ -199,13 +404,23 @@ impl ChunkBlocks {
         position: ChunkRelativeBlockCoordinates,
         block: u16,
     ) -> u16 {
-        std::mem::replace(&mut self.blocks[Self::convert_index(position)], block)
+        let index = Self::convert_index(position);
+        let section = &mut self.sections[index / SUBCHUNK_VOLUME];
+        let old = section[index % SUBCHUNK_VOLUME];
+        // a shared subchunk is only copied if the block changes
+        if old != block {
+            Arc::make_mut(section)[index % SUBCHUNK_VOLUME] = block;
+        }
+        old
+    }
+
+    /// Fills the whole subchunk with the block
+    fn fill_subchunk(&mut self, subchunk: usize, block: u16) {
+        self.sections[subchunk] = Arc::new([block; SUBCHUNK_VOLUME]);
     }
 
     pub fn iter_subchunks(&self) -> impl Iterator<Item = &[u16; SUBCHUNK_VOLUME]> {
-        self.blocks
-            .chunks(SUBCHUNK_VOLUME)
-            .map(|subchunk| subchunk.try_into().unwrap())
+        self.sections.iter().map(|section| &**section)
     }
 
     fn convert_index(index: ChunkRelativeBlockCoordinates) -> usize {
@@ -225,11 +440,34 @@ impl Index<ChunkRelativeBlockCoordinates> for ChunkBlocks {
     type Output = u16;
 
     fn index(&self, index: ChunkRelativeBlockCoordinates) -> &Self::Output {
-        &self.blocks[Self::convert_index(index)]
+        let index = Self::convert_index(index);
+        &self.sections[index / SUBCHUNK_VOLUME][index % SUBCHUNK_VOLUME]
     }
 }
 
 impl ChunkData {
+    /// Has to be called whenever the chunk is changed, so it is written again
+    pub fn mark_dirty(&self) {
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    /// A copy of the chunk which shares the subchunks with it until they change, so it can be
+    /// saved or sent without keeping the chunk locked
+    #[must_use]
+    pub fn snapshot(&self) -> Self {
+        Self {
+            blocks: self.blocks.clone(),
+            position: self.position,
+            persistent_data: self.persistent_data.clone(),
+            block_entity_data: self.block_entity_data.clone(),
+            block_entities: self.block_entities.clone(),
+            entities: self.entities.clone(),
+            light: self.light.clone(),
+            nbt: self.nbt.clone(),
+            dirty: AtomicBool::new(false),
+        }
+    }
+
     pub fn from_bytes(chunk_data: Vec<u8>, at: Vector2<i32>) -> Result<Self, ChunkParsingError> {
         if fastnbt::from_bytes::<ChunkStatus>(&chunk_data)
             .map_err(|_| ChunkParsingError::FailedReadStatus)?
@@ -238,14 +476,29 @@ impl ChunkData {
             return Err(ChunkParsingError::ChunkNotGenerated);
         }
 
+        let mut nbt = fastnbt::from_bytes::<Compound>(chunk_data.as_slice())
+            .map_err(|e| ChunkParsingError::ErrorDeserializingChunk(e.to_string()))?;
         let chunk_data = fastnbt::from_bytes::<ChunkNbt>(chunk_data.as_slice())
             .map_err(|e| ChunkParsingError::ErrorDeserializingChunk(e.to_string()))?;
+        let block_entities = take_block_entities(&mut nbt);
+        strip_owned_tags(&mut nbt);
 
         // this needs to be boxed, otherwise it will cause a stack-overflow
         let mut blocks = ChunkBlocks::empty_with_heightmap(chunk_data.heightmaps);
         let mut block_index = 0; // which block we're currently at
 
+        let lowest_section = i32::from(WORLD_LOWEST_Y) / 16;
+        let mut sky_light = vec![None; blocks.subchunks_len()];
+        let mut block_light = vec![None; blocks.subchunks_len()];
         for section in chunk_data.sections.into_iter() {
+            // vanilla also saves the light of the sections below and above the world
+            if let Some(index) = usize::try_from(i32::from(section.y) - lowest_section)
+                .ok()
+                .filter(|index| *index < sky_light.len())
+            {
+                sky_light[index] = section.sky_light;
+                block_light[index] = section.block_light;
+            }
             let block_states = match section.block_states {
                 Some(states) => states,
                 None => continue, // TODO @lukas0008 this should instead fill all blocks with the only element of the palette
@@ -254,17 +507,16 @@ impl ChunkData {
             let palette = block_states
                 .palette
                 .iter()
-                .map(|entry| match BlockState::new(&entry.name) {
-                    // Block not found, Often the case when World has an newer or older version then block registry
-                    None => BlockState::AIR,
-                    Some(state) => state,
-                })
+                .map(PaletteEntry::state_id)
                 .collect::<Vec<_>>();
 
             let block_data = match block_states.data {
                 None => {
-                    // We skipped placing an empty subchunk.
-                    // We need to increase the y coordinate of the next subchunk being placed.
+                    // The whole subchunk is the only block of the palette
+                    let block = palette.first().copied().unwrap_or(0);
+                    if block != 0 {
+                        blocks.fill_subchunk(block_index / SUBCHUNK_VOLUME, block);
+                    }
                     block_index += SUBCHUNK_VOLUME;
                     continue;
                 }
@@ -284,7 +536,7 @@ impl ChunkData {
             'block_loop: for block in block_data.iter() {
                 for i in 0..blocks_in_pallete {
                     let index = (block >> (i * block_bit_size)) & mask;
-                    let block = &palette[index as usize];
+                    let block = palette[index as usize];
 
                     // TODO allow indexing blocks directly so we can just use block_index and save some time?
                     // this is fine because we initalized the heightmap of `blocks`
@@ -295,7 +547,7 @@ impl ChunkData {
                             y: Height::from_absolute((block_index / CHUNK_AREA) as u16),
                             x: (block_index % 16).into(),
                         },
-                        block.get_id(),
+                        block,
                     );
 
                     block_index += 1;
@@ -309,10 +561,220 @@ impl ChunkData {
             }
         }
 
+        let block_entity_data = chunk_data
+            .block_entities
+            .into_iter()
+            .filter(|block_entity| !block_entity.persistent_data.is_empty())
+            .map(|block_entity| {
+                (
+                    WorldPosition(Vector3::new(block_entity.x, block_entity.y, block_entity.z)),
+                    block_entity.persistent_data,
+                )
+            })
+            .collect();
+
+        let light = chunk_data
+            .is_light_on
+            .then(|| ChunkLight::from_saved(sky_light, block_light))
+            .flatten();
+        if light.is_none() {
+            log::debug!("Chunk {at:?} has no valid light, it is lit again");
+        }
+
         Ok(ChunkData {
             blocks,
             position: at,
+            persistent_data: chunk_data.persistent_data,
+            block_entity_data,
+            block_entities,
+            entities: chunk_data
+                .entities
+                .into_iter()
+                .filter_map(EntityNbt::into_entity)
+                .collect(),
+            light,
+            nbt: Arc::new(nbt),
+            dirty: AtomicBool::new(false),
+        })
+    }
+
+    /// The uncompressed NBT of the chunk like it is stored in region files
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ChunkWritingError> {
+        let sections = self
+            .blocks
+            .iter_subchunks()
+            .enumerate()
+            .map(|(index, subchunk)| ChunkSection {
+                y: (i32::from(WORLD_LOWEST_Y) / 16 + index as i32) as i8,
+                block_states: Some(pack_section(subchunk)),
+                sky_light: self
+                    .light
+                    .as_ref()
+                    .map(|light| light::to_saved(&light.sky_sections()[index])),
+                block_light: self
+                    .light
+                    .as_ref()
+                    .map(|light| light::to_saved(&light.block_sections()[index])),
+            })
+            .collect();
+
+        let out = fastnbt::to_value(ChunkNbtOut {
+            data_version: DATA_VERSION,
+            x_pos: self.position.x,
+            z_pos: self.position.z,
+            y_pos: i32::from(WORLD_LOWEST_Y) / 16,
+            status: "minecraft:full",
+            sections,
+            heightmaps: &self.blocks.heightmap,
+            is_light_on: self.light.is_some(),
+            persistent_data: &self.persistent_data,
+            entities: self.entities.iter().map(EntityNbt::from).collect(),
+        })
+        .map_err(|err| ChunkWritingError::ChunkSerializingError(err.to_string()))?;
+        let Value::Compound(out) = out else {
+            unreachable!("chunks are written as compounds");
+        };
+
+        let mut nbt = Compound::clone(&self.nbt);
+        merge_compound(&mut nbt, out);
+        nbt.insert(
+            "block_entities".to_string(),
+            Value::List(self.block_entities_nbt()),
+        );
+        fastnbt::to_bytes(&nbt)
+            .map_err(|err| ChunkWritingError::ChunkSerializingError(err.to_string()))
+    }
+
+    /// The saved block entities with the custom data of plugins on them
+    fn block_entities_nbt(&self) -> Vec<Value> {
+        let mut block_entities = self.block_entities.clone();
+        for (position, data) in &self.block_entity_data {
+            block_entities
+                .entry(*position)
+                .or_default()
+                .insert(persistent_data::NBT_KEY.to_string(), data.clone().to_nbt());
+        }
+        block_entities
+            .into_iter()
+            .map(|(position, mut block_entity)| {
+                block_entity.insert("x".to_string(), Value::Int(position.0.x));
+                block_entity.insert("y".to_string(), Value::Int(position.0.y));
+                block_entity.insert("z".to_string(), Value::Int(position.0.z));
+                Value::Compound(block_entity)
+            })
+            .collect()
+    }
+}
+
+/// Moves the block entities out of the chunk's NBT, by their position. The custom data of plugins
+/// is read into [`ChunkData::block_entity_data`] instead
+fn take_block_entities(nbt: &mut Compound) -> HashMap<WorldPosition, Compound> {
+    let Some(Value::List(block_entities)) = nbt.remove("block_entities") else {
+        return HashMap::new();
+    };
+    block_entities
+        .into_iter()
+        .filter_map(|block_entity| {
+            let Value::Compound(mut block_entity) = block_entity else {
+                return None;
+            };
+            let (Some(Value::Int(x)), Some(Value::Int(y)), Some(Value::Int(z))) = (
+                block_entity.get("x"),
+                block_entity.get("y"),
+                block_entity.get("z"),
+            ) else {
+                return None;
+            };
+            let position = WorldPosition(Vector3::new(*x, *y, *z));
+            block_entity.remove(persistent_data::NBT_KEY);
+            Some((position, block_entity))
+        })
+        .collect()
+}
+
+/// Removes the tags which are kept in [`ChunkData`] from the chunk's NBT, so the NBT only keeps
+/// what Pumpkin doesn't know
+fn strip_owned_tags(nbt: &mut Compound) {
+    for tag in OWNED_TAGS {
+        nbt.remove(tag);
+    }
+    if let Some(Value::List(sections)) = nbt.get_mut("sections") {
+        for section in sections {
+            if let Value::Compound(section) = section {
+                for tag in OWNED_SECTION_TAGS {
+                    section.remove(tag);
+                }
+            }
+        }
+    }
+}
+
+/// Writes the tags of `from` into `into`, compounds in both are merged so the tags only `into`
+/// has are kept. Sections are merged with the section at the same height
+fn merge_compound(into: &mut Compound, from: Compound) {
+    for (tag, value) in from {
+        match (into.get_mut(&tag), value) {
+            (Some(Value::Compound(into)), Value::Compound(from)) => merge_compound(into, from),
+            (Some(Value::List(into)), Value::List(from)) if tag == "sections" => {
+                merge_sections(into, from);
+            }
+            (_, value) => {
+                into.insert(tag, value);
+            }
+        }
+    }
+}
+
+fn merge_sections(into: &mut Vec<Value>, from: Vec<Value>) {
+    for section in from {
+        let Value::Compound(section) = section else {
+            continue;
+        };
+        let kept = into.iter_mut().find_map(|kept| match kept {
+            Value::Compound(kept) if kept.get("Y") == section.get("Y") => Some(kept),
+            _ => None,
+        });
+        match kept {
+            Some(kept) => merge_compound(kept, section),
+            None => into.push(Value::Compound(section)),
+        }
+    }
+}
+
+/// Packs the blocks like `from_bytes` unpacks them, a subchunk of only one block has no data
+fn pack_section(subchunk: &[u16; SUBCHUNK_VOLUME]) -> ChunkSectionBlockStates {
+    let mut palette = Vec::new();
+    let mut palette_indices = HashMap::new();
+    let indices = subchunk
+        .iter()
+        .map(|block| {
+            *palette_indices.entry(*block).or_insert_with(|| {
+                palette.push(*block);
+                palette.len() as u64 - 1
+            })
         })
+        .collect::<Vec<_>>();
+
+    let data = (palette.len() > 1).then(|| {
+        let block_bit_size = max(4, 64 - (palette.len() as u64 - 1).leading_zeros());
+        let blocks_in_pallete = (64 / block_bit_size) as usize;
+        let packed = indices
+            .chunks(blocks_in_pallete)
+            .map(|blocks| {
+                blocks.iter().enumerate().fold(0u64, |long, (i, index)| {
+                    long | index << (i as u32 * block_bit_size)
+                }) as i64
+            })
+            .collect();
+        LongArray::new(packed)
+    });
+
+    ChunkSectionBlockStates {
+        data,
+        palette: palette
+            .into_iter()
+            .map(PaletteEntry::from_state_id)
+            .collect(),
     }
 }
 
@@ -325,3 +787,29 @@ pub enum ChunkParsingError {
     #[error("Error deserializing chunk: {0}")]
     ErrorDeserializingChunk(String),
 }
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use pumpkin_core::math::vector3::Vector3;
+
+    use super::ChunkBlocks;
+
+    #[test]
+    fn snapshots_keep_their_blocks() {
+        let mut blocks = ChunkBlocks::default();
+        blocks.set_block(Vector3::new(1, 10, 2).into(), 1);
+        let snapshot = blocks.clone();
+
+        blocks.set_block(Vector3::new(1, 10, 2).into(), 2);
+        blocks.set_block(Vector3::new(0, -64, 0).into(), 3);
+        assert_eq!(snapshot.get_block(Vector3::new(1, 10, 2).into()), Some(1));
+        assert_eq!(snapshot.get_block(Vector3::new(0, -64, 0).into()), Some(0));
+        assert_eq!(blocks.get_block(Vector3::new(1, 10, 2).into()), Some(2));
+        assert_eq!(blocks.get_block(Vector3::new(0, -64, 0).into()), Some(3));
+        // only the changed subchunks were copied
+        assert!(!Arc::ptr_eq(&blocks.sections[4], &snapshot.sections[4]));
+        assert!(Arc::ptr_eq(&blocks.sections[10], &snapshot.sections[10]));
+    }
+}
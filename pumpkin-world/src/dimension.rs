@@ -1,8 +1,10 @@
 use std::path::PathBuf;
 
+use pumpkin_config::WorldConfig;
+
 use crate::level::Level;
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Dimension {
     OverWorld,
     Nether,
@@ -10,12 +12,12 @@ pub enum Dimension {
 }
 
 impl Dimension {
-    pub fn into_level(&self, mut base_directory: PathBuf) -> Level {
+    pub fn into_level(&self, mut base_directory: PathBuf, config: &WorldConfig) -> Level {
         match self {
             Dimension::OverWorld => {}
             Dimension::Nether => base_directory.push("DIM-1"),
             Dimension::End => base_directory.push("DIM1"),
         }
-        Level::from_root_folder(base_directory)
+        Level::from_root_folder(base_directory, config)
     }
 }
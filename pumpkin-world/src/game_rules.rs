@@ -0,0 +1,529 @@
+use std::{collections::HashMap, fmt, sync::LazyLock};
+
+use parking_lot::RwLock;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+/// The value of a single game rule. Vanilla only knows booleans and integers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameRuleValue {
+    Bool(bool),
+    Int(i32),
+}
+
+impl GameRuleValue {
+    #[must_use]
+    pub const fn kind(&self) -> GameRuleKind {
+        match self {
+            Self::Bool(_) => GameRuleKind::Bool,
+            Self::Int(_) => GameRuleKind::Int,
+        }
+    }
+}
+
+impl fmt::Display for GameRuleValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool(value) => write!(f, "{value}"),
+            Self::Int(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameRuleKind {
+    Bool,
+    Int,
+}
+
+impl GameRuleKind {
+    /// Parses a value the same way `/gamerule` and `level.dat` do.
+    #[must_use]
+    pub fn parse(self, value: &str) -> Option<GameRuleValue> {
+        match self {
+            Self::Bool => value.parse().ok().map(GameRuleValue::Bool),
+            Self::Int => value.parse().ok().map(GameRuleValue::Int),
+        }
+    }
+}
+
+/// Categories as shown in the vanilla "Edit Game Rules" screen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameRuleCategory {
+    Player,
+    Mobs,
+    Spawning,
+    Drops,
+    Updates,
+    Chat,
+    Misc,
+}
+
+/// Untyped description of a game rule, used for lookups by name (commands, persistence).
+#[derive(Debug)]
+pub struct GameRuleDefinition {
+    pub name: &'static str,
+    pub category: GameRuleCategory,
+    pub default: GameRuleValue,
+}
+
+/// Conversion between Rust types and [`GameRuleValue`].
+pub trait GameRuleType: Copy {
+    fn from_value(value: GameRuleValue) -> Option<Self>;
+    fn into_value(self) -> GameRuleValue;
+}
+
+impl GameRuleType for bool {
+    fn from_value(value: GameRuleValue) -> Option<Self> {
+        match value {
+            GameRuleValue::Bool(value) => Some(value),
+            GameRuleValue::Int(_) => None,
+        }
+    }
+
+    fn into_value(self) -> GameRuleValue {
+        GameRuleValue::Bool(self)
+    }
+}
+
+impl GameRuleType for i32 {
+    fn from_value(value: GameRuleValue) -> Option<Self> {
+        match value {
+            GameRuleValue::Int(value) => Some(value),
+            GameRuleValue::Bool(_) => None,
+        }
+    }
+
+    fn into_value(self) -> GameRuleValue {
+        GameRuleValue::Int(self)
+    }
+}
+
+/// A typed handle to a game rule, so subsystems can query values without string lookups.
+pub struct GameRule<T: GameRuleType> {
+    pub name: &'static str,
+    pub category: GameRuleCategory,
+    pub default: T,
+}
+
+macro_rules! game_rule_value {
+    (bool, $value:expr) => {
+        GameRuleValue::Bool($value)
+    };
+    (i32, $value:expr) => {
+        GameRuleValue::Int($value)
+    };
+}
+
+macro_rules! game_rules {
+    ($($ident:ident: $ty:ident = $name:literal, $category:ident, $default:expr;)*) => {
+        $(
+            pub const $ident: GameRule<$ty> = GameRule {
+                name: $name,
+                category: GameRuleCategory::$category,
+                default: $default,
+            };
+        )*
+
+        /// Every game rule known to vanilla, sorted by name.
+        pub static VANILLA_GAME_RULES: &[GameRuleDefinition] = &[
+            $(
+                GameRuleDefinition {
+                    name: $name,
+                    category: GameRuleCategory::$category,
+                    default: game_rule_value!($ty, $default),
+                },
+            )*
+        ];
+    };
+}
+
+game_rules! {
+    ANNOUNCE_ADVANCEMENTS: bool = "announceAdvancements", Chat, true;
+    BLOCK_EXPLOSION_DROP_DECAY: bool = "blockExplosionDropDecay", Drops, true;
+    COMMAND_BLOCK_OUTPUT: bool = "commandBlockOutput", Chat, true;
+    COMMAND_MODIFICATION_BLOCK_LIMIT: i32 = "commandModificationBlockLimit", Misc, 32768;
+    DISABLE_ELYTRA_MOVEMENT_CHECK: bool = "disableElytraMovementCheck", Player, false;
+    DISABLE_PLAYER_MOVEMENT_CHECK: bool = "disablePlayerMovementCheck", Player, false;
+    DISABLE_RAIDS: bool = "disableRaids", Mobs, false;
+    DO_DAYLIGHT_CYCLE: bool = "doDaylightCycle", Updates, true;
+    DO_ENTITY_DROPS: bool = "doEntityDrops", Drops, true;
+    DO_FIRE_TICK: bool = "doFireTick", Updates, true;
+    DO_IMMEDIATE_RESPAWN: bool = "doImmediateRespawn", Player, false;
+    DO_INSOMNIA: bool = "doInsomnia", Spawning, true;
+    DO_LIMITED_CRAFTING: bool = "doLimitedCrafting", Player, false;
+    DO_MOB_LOOT: bool = "doMobLoot", Drops, true;
+    DO_MOB_SPAWNING: bool = "doMobSpawning", Spawning, true;
+    DO_PATROL_SPAWNING: bool = "doPatrolSpawning", Spawning, true;
+    DO_TILE_DROPS: bool = "doTileDrops", Drops, true;
+    DO_TRADER_SPAWNING: bool = "doTraderSpawning", Spawning, true;
+    DO_VINES_SPREAD: bool = "doVinesSpread", Updates, true;
+    DO_WARDEN_SPAWNING: bool = "doWardenSpawning", Spawning, true;
+    DO_WEATHER_CYCLE: bool = "doWeatherCycle", Updates, true;
+    DROWNING_DAMAGE: bool = "drowningDamage", Player, true;
+    ENDER_PEARLS_VANISH_ON_DEATH: bool = "enderPearlsVanishOnDeath", Player, true;
+    FALL_DAMAGE: bool = "fallDamage", Player, true;
+    FIRE_DAMAGE: bool = "fireDamage", Player, true;
+    FORGIVE_DEAD_PLAYERS: bool = "forgiveDeadPlayers", Mobs, true;
+    FREEZE_DAMAGE: bool = "freezeDamage", Player, true;
+    GLOBAL_SOUND_EVENTS: bool = "globalSoundEvents", Misc, true;
+    KEEP_INVENTORY: bool = "keepInventory", Player, false;
+    LAVA_SOURCE_CONVERSION: bool = "lavaSourceConversion", Updates, false;
+    LOG_ADMIN_COMMANDS: bool = "logAdminCommands", Chat, true;
+    MAX_COMMAND_CHAIN_LENGTH: i32 = "maxCommandChainLength", Misc, 65536;
+    MAX_COMMAND_FORK_COUNT: i32 = "maxCommandForkCount", Misc, 65536;
+    MAX_ENTITY_CRAMMING: i32 = "maxEntityCramming", Mobs, 24;
+    MOB_EXPLOSION_DROP_DECAY: bool = "mobExplosionDropDecay", Drops, true;
+    MOB_GRIEFING: bool = "mobGriefing", Mobs, true;
+    NATURAL_REGENERATION: bool = "naturalRegeneration", Player, true;
+    PLAYERS_NETHER_PORTAL_CREATIVE_DELAY: i32 = "playersNetherPortalCreativeDelay", Player, 0;
+    PLAYERS_NETHER_PORTAL_DEFAULT_DELAY: i32 = "playersNetherPortalDefaultDelay", Player, 80;
+    PLAYERS_SLEEPING_PERCENTAGE: i32 = "playersSleepingPercentage", Player, 100;
+    PROJECTILES_CAN_BREAK_BLOCKS: bool = "projectilesCanBreakBlocks", Misc, true;
+    RANDOM_TICK_SPEED: i32 = "randomTickSpeed", Updates, 3;
+    REDUCED_DEBUG_INFO: bool = "reducedDebugInfo", Misc, false;
+    SEND_COMMAND_FEEDBACK: bool = "sendCommandFeedback", Chat, true;
+    SHOW_DEATH_MESSAGES: bool = "showDeathMessages", Chat, true;
+    SNOW_ACCUMULATION_HEIGHT: i32 = "snowAccumulationHeight", Updates, 1;
+    SPAWN_CHUNK_RADIUS: i32 = "spawnChunkRadius", Misc, 2;
+    SPAWN_RADIUS: i32 = "spawnRadius", Player, 10;
+    SPECTATORS_GENERATE_CHUNKS: bool = "spectatorsGenerateChunks", Player, true;
+    TNT_EXPLODES: bool = "tntExplodes", Misc, true;
+    TNT_EXPLOSION_DROP_DECAY: bool = "tntExplosionDropDecay", Drops, false;
+    UNIVERSAL_ANGER: bool = "universalAnger", Mobs, false;
+    WATER_SOURCE_CONVERSION: bool = "waterSourceConversion", Updates, true;
+}
+
+/// Non-vanilla rules registered by subsystems or plugins at runtime. They are never sent to clients.
+static CUSTOM_GAME_RULES: LazyLock<RwLock<Vec<&'static GameRuleDefinition>>> =
+    LazyLock::new(|| RwLock::new(Vec::new()));
+
+/// Game rules which Pumpkin adds on top of vanilla, registered by [`register_pumpkin_game_rules`].
+pub mod pumpkin {
+    use super::{GameRule, GameRuleCategory};
+
+    /// Whether creepers destroy blocks, only has an effect if `mobGriefing` is enabled.
+    pub const CREEPER_GRIEFING: GameRule<bool> = GameRule {
+        name: "creeperGriefing",
+        category: GameRuleCategory::Mobs,
+        default: true,
+    };
+    /// Whether endermen pick up blocks, only has an effect if `mobGriefing` is enabled.
+    pub const ENDERMAN_GRIEFING: GameRule<bool> = GameRule {
+        name: "endermanGriefing",
+        category: GameRuleCategory::Mobs,
+        default: true,
+    };
+    /// Whether ghast fireballs destroy blocks, only has an effect if `mobGriefing` is enabled.
+    pub const GHAST_GRIEFING: GameRule<bool> = GameRule {
+        name: "ghastGriefing",
+        category: GameRuleCategory::Mobs,
+        default: true,
+    };
+    /// Whether ravagers break leaves and crops, only has an effect if `mobGriefing` is enabled.
+    pub const RAVAGER_GRIEFING: GameRule<bool> = GameRule {
+        name: "ravagerGriefing",
+        category: GameRuleCategory::Mobs,
+        default: true,
+    };
+    /// Whether raids can be started in villages, checked in addition to `disableRaids`.
+    pub const VILLAGE_RAIDS: GameRule<bool> = GameRule {
+        name: "villageRaids",
+        category: GameRuleCategory::Spawning,
+        default: true,
+    };
+}
+
+/// Registers the game rules from [`pumpkin`]. Should be called once on startup.
+pub fn register_pumpkin_game_rules() {
+    for result in [
+        register_game_rule(&pumpkin::CREEPER_GRIEFING),
+        register_game_rule(&pumpkin::ENDERMAN_GRIEFING),
+        register_game_rule(&pumpkin::GHAST_GRIEFING),
+        register_game_rule(&pumpkin::RAVAGER_GRIEFING),
+        register_game_rule(&pumpkin::VILLAGE_RAIDS),
+    ] {
+        if let Err(err) = result {
+            log::warn!("Failed to register game rule: {err}");
+        }
+    }
+}
+
+/// Registers a custom game rule, so it can be queried by name and changed using `/gamerule`.
+pub fn register_game_rule<T: GameRuleType>(rule: &GameRule<T>) -> Result<(), GameRuleError> {
+    let mut custom = CUSTOM_GAME_RULES.write();
+    if get_vanilla_game_rule(rule.name).is_some()
+        || custom.iter().any(|custom| custom.name == rule.name)
+    {
+        return Err(GameRuleError::AlreadyRegistered(rule.name));
+    }
+    // registered rules live for the rest of the program
+    custom.push(Box::leak(Box::new(GameRuleDefinition {
+        name: rule.name,
+        category: rule.category,
+        default: rule.default.into_value(),
+    })));
+    Ok(())
+}
+
+fn get_vanilla_game_rule(name: &str) -> Option<&'static GameRuleDefinition> {
+    VANILLA_GAME_RULES
+        .binary_search_by(|rule| rule.name.cmp(name))
+        .ok()
+        .map(|index| &VANILLA_GAME_RULES[index])
+}
+
+/// Looks up a vanilla or registered custom game rule by its (case sensitive) name.
+#[must_use]
+pub fn get_game_rule(name: &str) -> Option<&'static GameRuleDefinition> {
+    get_vanilla_game_rule(name).or_else(|| {
+        CUSTOM_GAME_RULES
+            .read()
+            .iter()
+            .find(|rule| rule.name == name)
+            .copied()
+    })
+}
+
+/// Returns every vanilla game rule followed by all registered custom ones.
+#[must_use]
+pub fn all_game_rules() -> Vec<&'static GameRuleDefinition> {
+    VANILLA_GAME_RULES
+        .iter()
+        .chain(CUSTOM_GAME_RULES.read().iter().copied())
+        .collect()
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum GameRuleError {
+    #[error("Unknown game rule")]
+    UnknownRule,
+    #[error("Expected a {expected:?} value but got {got:?}")]
+    WrongType {
+        expected: GameRuleKind,
+        got: GameRuleKind,
+    },
+    #[error("Game rule {0} is already registered")]
+    AlreadyRegistered(&'static str),
+}
+
+/// The game rules of a single world. Only values differing from the default are stored.
+///
+/// Serializes to the same string map vanilla uses for the `GameRules` compound in `level.dat`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GameRules {
+    values: HashMap<&'static str, GameRuleValue>,
+    /// Values of rules which were not registered yet when loading, e.g. custom rules of plugins.
+    /// Kept so they survive a save and can be resolved once the rule gets registered.
+    unresolved: HashMap<String, String>,
+}
+
+impl GameRules {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn get<T: GameRuleType>(&self, rule: &GameRule<T>) -> T {
+        self.get_value_of(rule.name, rule.default.into_value())
+            .and_then(T::from_value)
+            .unwrap_or(rule.default)
+    }
+
+    pub fn set<T: GameRuleType>(&mut self, rule: &GameRule<T>, value: T) {
+        self.unresolved.remove(rule.name);
+        self.values.insert(rule.name, value.into_value());
+    }
+
+    /// Returns `mobGriefing` combined with a more specific griefing rule like [`pumpkin::CREEPER_GRIEFING`].
+    #[must_use]
+    pub fn allows_griefing(&self, rule: &GameRule<bool>) -> bool {
+        self.get(&MOB_GRIEFING) && self.get(rule)
+    }
+
+    /// Returns the current value of a rule by name, or `None` if no such rule exists.
+    #[must_use]
+    pub fn get_value(&self, name: &str) -> Option<GameRuleValue> {
+        let definition = get_game_rule(name)?;
+        self.get_value_of(definition.name, definition.default)
+    }
+
+    fn get_value_of(&self, name: &str, default: GameRuleValue) -> Option<GameRuleValue> {
+        if let Some(value) = self.values.get(name) {
+            return Some(*value);
+        }
+        match self.unresolved.get(name) {
+            Some(raw) => default.kind().parse(raw).or(Some(default)),
+            None => Some(default),
+        }
+    }
+
+    pub fn set_value(&mut self, name: &str, value: GameRuleValue) -> Result<(), GameRuleError> {
+        let definition = get_game_rule(name).ok_or(GameRuleError::UnknownRule)?;
+        let expected = definition.default.kind();
+        if expected != value.kind() {
+            return Err(GameRuleError::WrongType {
+                expected,
+                got: value.kind(),
+            });
+        }
+        self.unresolved.remove(name);
+        self.values.insert(definition.name, value);
+        Ok(())
+    }
+
+    /// Reads the rules from their names and values as strings, the way `level.dat` stores them.
+    pub fn from_strings(raw: impl IntoIterator<Item = (String, String)>) -> Self {
+        let mut rules = Self::new();
+        for (name, value) in raw {
+            let Some(definition) = get_game_rule(&name) else {
+                log::debug!("Keeping value of unknown game rule {name}");
+                rules.unresolved.insert(name, value);
+                continue;
+            };
+            match definition.default.kind().parse(&value) {
+                Some(value) => rules.values.insert(definition.name, value),
+                None => {
+                    log::warn!("Ignoring invalid value {value} for game rule {name}");
+                    continue;
+                }
+            };
+        }
+        rules
+    }
+
+    /// Every rule with its value as string, see [`Self::from_strings`].
+    #[must_use]
+    pub fn to_strings(&self) -> Vec<(String, String)> {
+        self.iter()
+            .map(|(definition, value)| (definition.name.to_string(), value.to_string()))
+            .chain(
+                self.unresolved
+                    .iter()
+                    .filter(|(name, _)| get_game_rule(name).is_none())
+                    .map(|(name, value)| (name.clone(), value.clone())),
+            )
+            .collect()
+    }
+
+    /// Iterates over every known rule with its current value.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static GameRuleDefinition, GameRuleValue)> + '_ {
+        all_game_rules().into_iter().map(|definition| {
+            let value = self
+                .get_value_of(definition.name, definition.default)
+                .unwrap_or(definition.default);
+            (definition, value)
+        })
+    }
+}
+
+impl Serialize for GameRules {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.to_strings())
+    }
+}
+
+impl<'de> Deserialize<'de> for GameRules {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = HashMap::<String, String>::deserialize(deserializer)?;
+        Ok(Self::from_strings(raw))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn vanilla_rules_are_sorted() {
+        assert!(VANILLA_GAME_RULES
+            .windows(2)
+            .all(|pair| pair[0].name < pair[1].name));
+    }
+
+    #[test]
+    fn typed_access() {
+        let mut rules = GameRules::new();
+        assert!(!rules.get(&KEEP_INVENTORY));
+        assert_eq!(rules.get(&RANDOM_TICK_SPEED), 3);
+
+        rules.set(&KEEP_INVENTORY, true);
+        rules.set(&RANDOM_TICK_SPEED, 10);
+        assert!(rules.get(&KEEP_INVENTORY));
+        assert_eq!(rules.get(&RANDOM_TICK_SPEED), 10);
+        assert_eq!(
+            rules.get_value("randomTickSpeed"),
+            Some(GameRuleValue::Int(10))
+        );
+    }
+
+    #[test]
+    fn set_value_checks_type() {
+        let mut rules = GameRules::new();
+        assert_eq!(
+            rules.set_value("keepInventory", GameRuleValue::Int(1)),
+            Err(GameRuleError::WrongType {
+                expected: GameRuleKind::Bool,
+                got: GameRuleKind::Int
+            })
+        );
+        assert_eq!(
+            rules.set_value("doesNotExist", GameRuleValue::Bool(true)),
+            Err(GameRuleError::UnknownRule)
+        );
+        assert!(rules
+            .set_value("mobGriefing", GameRuleValue::Bool(false))
+            .is_ok());
+        assert!(!rules.get(&MOB_GRIEFING));
+    }
+
+    #[test]
+    fn custom_rules() {
+        const TEST_RULE: GameRule<i32> = GameRule {
+            name: "testCustomRule",
+            category: GameRuleCategory::Misc,
+            default: 5,
+        };
+
+        // values of rules which are registered later are kept
+        let mut rules: GameRules =
+            serde_json::from_str(r#"{"testCustomRule":"7","mobGriefing":"false"}"#).unwrap();
+        assert!(get_game_rule(TEST_RULE.name).is_none());
+        assert!(serde_json::to_string(&rules)
+            .unwrap()
+            .contains(r#""testCustomRule":"7""#));
+
+        register_game_rule(&TEST_RULE).unwrap();
+        assert_eq!(
+            register_game_rule(&TEST_RULE),
+            Err(GameRuleError::AlreadyRegistered(TEST_RULE.name))
+        );
+        assert_eq!(
+            register_game_rule(&KEEP_INVENTORY),
+            Err(GameRuleError::AlreadyRegistered(KEEP_INVENTORY.name))
+        );
+        assert_eq!(rules.get(&TEST_RULE), 7);
+        assert!(rules
+            .set_value(TEST_RULE.name, GameRuleValue::Int(9))
+            .is_ok());
+        assert_eq!(rules.get(&TEST_RULE), 9);
+
+        rules.set(&pumpkin::CREEPER_GRIEFING, true);
+        assert!(!rules.allows_griefing(&pumpkin::CREEPER_GRIEFING));
+    }
+
+    #[test]
+    fn level_data_round_trip() {
+        let mut rules = GameRules::new();
+        rules.set(&DO_DAYLIGHT_CYCLE, false);
+        rules.set(&SPAWN_RADIUS, 0);
+
+        let json = serde_json::to_string(&rules).unwrap();
+        let read: GameRules = serde_json::from_str(&json).unwrap();
+        assert!(!read.get(&DO_DAYLIGHT_CYCLE));
+        assert_eq!(read.get(&SPAWN_RADIUS), 0);
+        assert!(read.get(&MOB_GRIEFING));
+    }
+}
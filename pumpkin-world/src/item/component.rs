@@ -0,0 +1,991 @@
+//! Item components, which is how items keep data like their name, damage or enchantments since
+//! 1.20.5. Items have default components (see [`ItemComponents`](super::item_registry::ItemComponents)),
+//! a stack only keeps how it differs from them in a [`ComponentPatch`].
+
+use std::collections::BTreeMap;
+
+use fastnbt::Value;
+use pumpkin_core::nbt::Compound;
+use pumpkin_macros::registry_entries;
+
+use super::Rarity;
+use crate::block::block_registry::Block;
+
+/// The components in the order of their network ids
+pub const COMPONENTS: &[&str] = &registry_entries!("minecraft:data_component_type");
+
+/// The attributes in the order of their network ids
+pub const ATTRIBUTES: &[&str] = &registry_entries!("minecraft:attribute");
+
+/// The enchantments in the order their registry is sent in
+pub const ENCHANTMENTS: &[&str] = &registry_entries!("minecraft:enchantment");
+
+/// The potions in the order of their network ids
+pub const POTIONS: &[&str] = &registry_entries!("minecraft:potion");
+
+/// The status effects in the order of their network ids
+pub const MOB_EFFECTS: &[&str] = &registry_entries!("minecraft:mob_effect");
+
+/// How attribute modifiers are applied, in the order of their network ids
+pub const MODIFIER_OPERATIONS: [&str; 3] =
+    ["add_value", "add_multiplied_base", "add_multiplied_total"];
+
+/// Where an item has to be for its attribute modifiers to apply, in the order of their network
+/// ids
+pub const SLOT_GROUPS: [&str; 10] = [
+    "any", "mainhand", "offhand", "hand", "feet", "legs", "chest", "head", "armor", "body",
+];
+
+/// The rarities in the order of their network ids
+pub const RARITIES: [Rarity; 4] = [Rarity::Common, Rarity::UnCommon, Rarity::Rare, Rarity::Epic];
+
+/// The network id of the component, enchantment or whatever else is in the list
+#[must_use]
+pub fn network_id(list: &[&str], name: &str) -> Option<i32> {
+    list.iter()
+        .position(|entry| *entry == name)
+        .map(|id| id as i32)
+}
+
+/// Adds `minecraft:` to names without a namespace, the way vanilla reads ids
+#[must_use]
+pub fn namespaced(name: &str) -> String {
+    if name.contains(':') {
+        name.to_string()
+    } else {
+        format!("minecraft:{name}")
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Enchantments {
+    /// The level of each enchantment by its name
+    pub levels: BTreeMap<String, i32>,
+    pub show_in_tooltip: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AttributeModifier {
+    pub attribute: String,
+    /// Tells modifiers of an attribute apart, e.g. `minecraft:base_attack_damage`
+    pub id: String,
+    pub amount: f64,
+    /// One of [`MODIFIER_OPERATIONS`]
+    pub operation: String,
+    /// One of [`SLOT_GROUPS`]
+    pub slot: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Food {
+    pub nutrition: i32,
+    pub saturation: f32,
+    pub can_always_eat: bool,
+}
+
+/// A status effect a potion gives, like `minecraft:speed`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PotionEffect {
+    pub effect: String,
+    /// 0 is level I
+    pub amplifier: i32,
+    /// In ticks, -1 lasts forever
+    pub duration: i32,
+    pub ambient: bool,
+    pub show_particles: bool,
+    pub show_icon: bool,
+}
+
+impl PotionEffect {
+    #[must_use]
+    pub fn new(effect: &str, amplifier: i32, duration: i32) -> Self {
+        Self {
+            effect: namespaced(effect),
+            amplifier,
+            duration,
+            ambient: false,
+            show_particles: true,
+            show_icon: true,
+        }
+    }
+}
+
+/// What is in a potion, tipped arrow or area effect cloud
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PotionContents {
+    /// Like `minecraft:strong_healing`, which decides the name and effects
+    pub potion: Option<String>,
+    pub custom_color: Option<i32>,
+    /// Effects besides the potion's ones
+    pub custom_effects: Vec<PotionEffect>,
+    /// Replaces the potion's name in the item name, e.g. `item.minecraft.potion.effect.<name>`
+    pub custom_name: Option<String>,
+}
+
+impl PotionContents {
+    #[must_use]
+    pub fn of(potion: &str) -> Self {
+        Self {
+            potion: Some(namespaced(potion)),
+            ..Default::default()
+        }
+    }
+}
+
+/// Which blocks a [`BlockPredicate`] matches
+#[derive(Clone, Debug, PartialEq)]
+pub enum BlockSet {
+    /// Block names like `minecraft:stone`
+    Blocks(Vec<String>),
+    /// A block tag, without the `#`
+    Tag(String),
+}
+
+/// A block an item can be placed on or break in adventure mode
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BlockPredicate {
+    /// Any block if `None`
+    pub blocks: Option<BlockSet>,
+    /// Properties the state has to have, only exact values are kept
+    pub state: BTreeMap<String, String>,
+}
+
+impl BlockPredicate {
+    /// Whether the block state matches, `in_tag` tells whether the block is in a tag
+    pub fn test(&self, block: &Block, state_id: u16, in_tag: impl Fn(&str) -> bool) -> bool {
+        let matches = match &self.blocks {
+            None => true,
+            Some(BlockSet::Blocks(names)) => names.iter().any(|name| *name == block.name),
+            Some(BlockSet::Tag(tag)) => in_tag(tag),
+        };
+        if !matches {
+            return false;
+        }
+        if self.state.is_empty() {
+            return true;
+        }
+        block
+            .properties_of_state(state_id)
+            .is_some_and(|properties| {
+                self.state
+                    .iter()
+                    .all(|(name, value)| properties.contains(&(name.as_str(), value.as_str())))
+            })
+    }
+}
+
+/// The blocks of `minecraft:can_place_on` or `minecraft:can_break`
+#[derive(Clone, Debug, PartialEq)]
+pub struct AdventurePredicate {
+    pub predicates: Vec<BlockPredicate>,
+    pub show_in_tooltip: bool,
+}
+
+/// A component Pumpkin knows, names and text are kept as JSON like vanilla saves them
+#[derive(Clone, Debug, PartialEq)]
+pub enum DataComponent {
+    CustomData(Compound),
+    MaxStackSize(i32),
+    MaxDamage(i32),
+    Damage(i32),
+    Unbreakable {
+        show_in_tooltip: bool,
+    },
+    CustomName(String),
+    ItemName(String),
+    ItemModel(String),
+    Lore(Vec<String>),
+    Rarity(Rarity),
+    Enchantments(Enchantments),
+    AttributeModifiers {
+        modifiers: Vec<AttributeModifier>,
+        show_in_tooltip: bool,
+    },
+    CustomModelData(i32),
+    HideAdditionalTooltip,
+    HideTooltip,
+    RepairCost(i32),
+    EnchantmentGlintOverride(bool),
+    Food(Food),
+    StoredEnchantments(Enchantments),
+    DyedColor {
+        rgb: i32,
+        show_in_tooltip: bool,
+    },
+    CanPlaceOn(AdventurePredicate),
+    CanBreak(AdventurePredicate),
+    PotionContents(PotionContents),
+    /// A component Pumpkin doesn't know, as it was saved. It is kept but not sent to clients
+    Other(Value),
+}
+
+impl DataComponent {
+    /// Reads a component as it is saved, unknown components and ones which don't match their
+    /// format become [`Self::Other`]
+    #[must_use]
+    pub fn from_nbt(name: &str, value: &Value) -> Self {
+        Self::read_nbt(name, value).unwrap_or_else(|| Self::Other(value.clone()))
+    }
+
+    fn read_nbt(name: &str, value: &Value) -> Option<Self> {
+        let component = match name {
+            "minecraft:custom_data" => Self::CustomData(as_compound(value)?.clone()),
+            "minecraft:max_stack_size" => Self::MaxStackSize(as_int(value)?),
+            "minecraft:max_damage" => Self::MaxDamage(as_int(value)?),
+            "minecraft:damage" => Self::Damage(as_int(value)?),
+            "minecraft:unbreakable" => Self::Unbreakable {
+                show_in_tooltip: show_in_tooltip(as_compound(value)?),
+            },
+            "minecraft:custom_name" => Self::CustomName(as_string(value)?),
+            "minecraft:item_name" => Self::ItemName(as_string(value)?),
+            "minecraft:item_model" => Self::ItemModel(as_string(value)?),
+            "minecraft:lore" => Self::Lore(
+                as_list(value)?
+                    .iter()
+                    .map(as_string)
+                    .collect::<Option<_>>()?,
+            ),
+            "minecraft:rarity" => {
+                let rarity = as_string(value)?;
+                Self::Rarity(
+                    *RARITIES
+                        .iter()
+                        .find(|candidate| rarity_name(**candidate) == rarity)?,
+                )
+            }
+            "minecraft:enchantments" => Self::Enchantments(enchantments_from_nbt(value)?),
+            "minecraft:stored_enchantments" => {
+                Self::StoredEnchantments(enchantments_from_nbt(value)?)
+            }
+            "minecraft:attribute_modifiers" => attribute_modifiers_from_nbt(value)?,
+            "minecraft:custom_model_data" => Self::CustomModelData(as_int(value)?),
+            "minecraft:hide_additional_tooltip" => Self::HideAdditionalTooltip,
+            "minecraft:hide_tooltip" => Self::HideTooltip,
+            "minecraft:repair_cost" => Self::RepairCost(as_int(value)?),
+            "minecraft:enchantment_glint_override" => {
+                Self::EnchantmentGlintOverride(as_int(value)? != 0)
+            }
+            "minecraft:food" => {
+                let food = as_compound(value)?;
+                Self::Food(Food {
+                    nutrition: as_int(food.get("nutrition")?)?,
+                    saturation: as_float(food.get("saturation")?)?,
+                    can_always_eat: food.get("can_always_eat").and_then(as_int) == Some(1),
+                })
+            }
+            "minecraft:dyed_color" => match value {
+                Value::Compound(color) => Self::DyedColor {
+                    rgb: as_int(color.get("rgb")?)?,
+                    show_in_tooltip: show_in_tooltip(color),
+                },
+                value => Self::DyedColor {
+                    rgb: as_int(value)?,
+                    show_in_tooltip: true,
+                },
+            },
+            "minecraft:can_place_on" => Self::CanPlaceOn(adventure_predicate_from_nbt(value)?),
+            "minecraft:can_break" => Self::CanBreak(adventure_predicate_from_nbt(value)?),
+            "minecraft:potion_contents" => Self::PotionContents(potion_contents_from_nbt(value)?),
+            _ => return None,
+        };
+        Some(component)
+    }
+
+    /// The component as it is saved
+    #[must_use]
+    pub fn to_nbt(&self) -> Value {
+        match self {
+            Self::CustomData(data) => Value::Compound(data.clone()),
+            Self::MaxStackSize(value)
+            | Self::MaxDamage(value)
+            | Self::Damage(value)
+            | Self::CustomModelData(value)
+            | Self::RepairCost(value) => Value::Int(*value),
+            Self::Unbreakable { show_in_tooltip } => {
+                Value::Compound(tooltip_compound(*show_in_tooltip))
+            }
+            Self::CustomName(text) | Self::ItemName(text) | Self::ItemModel(text) => {
+                Value::String(text.clone())
+            }
+            Self::Lore(lines) => Value::List(lines.iter().cloned().map(Value::String).collect()),
+            Self::Rarity(rarity) => Value::String(rarity_name(*rarity).to_string()),
+            Self::Enchantments(enchantments) | Self::StoredEnchantments(enchantments) => {
+                let mut nbt = tooltip_compound(enchantments.show_in_tooltip);
+                let levels = enchantments
+                    .levels
+                    .iter()
+                    .map(|(name, level)| (name.clone(), Value::Int(*level)))
+                    .collect();
+                nbt.insert("levels".to_string(), Value::Compound(levels));
+                Value::Compound(nbt)
+            }
+            Self::AttributeModifiers {
+                modifiers,
+                show_in_tooltip,
+            } => {
+                let mut nbt = tooltip_compound(*show_in_tooltip);
+                let modifiers = modifiers
+                    .iter()
+                    .map(|modifier| {
+                        Value::Compound(Compound::from([
+                            (
+                                "type".to_string(),
+                                Value::String(modifier.attribute.clone()),
+                            ),
+                            ("id".to_string(), Value::String(modifier.id.clone())),
+                            ("amount".to_string(), Value::Double(modifier.amount)),
+                            (
+                                "operation".to_string(),
+                                Value::String(modifier.operation.clone()),
+                            ),
+                            ("slot".to_string(), Value::String(modifier.slot.clone())),
+                        ]))
+                    })
+                    .collect();
+                nbt.insert("modifiers".to_string(), Value::List(modifiers));
+                Value::Compound(nbt)
+            }
+            Self::HideAdditionalTooltip | Self::HideTooltip => Value::Compound(Compound::new()),
+            Self::EnchantmentGlintOverride(glint) => Value::Byte(i8::from(*glint)),
+            Self::Food(food) => {
+                let mut nbt = Compound::from([
+                    ("nutrition".to_string(), Value::Int(food.nutrition)),
+                    ("saturation".to_string(), Value::Float(food.saturation)),
+                ]);
+                if food.can_always_eat {
+                    nbt.insert("can_always_eat".to_string(), Value::Byte(1));
+                }
+                Value::Compound(nbt)
+            }
+            Self::DyedColor {
+                rgb,
+                show_in_tooltip,
+            } => {
+                let mut nbt = tooltip_compound(*show_in_tooltip);
+                nbt.insert("rgb".to_string(), Value::Int(*rgb));
+                Value::Compound(nbt)
+            }
+            Self::CanPlaceOn(predicate) | Self::CanBreak(predicate) => {
+                let mut nbt = tooltip_compound(predicate.show_in_tooltip);
+                let predicates = predicate
+                    .predicates
+                    .iter()
+                    .map(block_predicate_to_nbt)
+                    .collect();
+                nbt.insert("predicates".to_string(), Value::List(predicates));
+                Value::Compound(nbt)
+            }
+            Self::PotionContents(contents) => potion_contents_to_nbt(contents),
+            Self::Other(value) => value.clone(),
+        }
+    }
+}
+
+/// How a stack's components differ from its item's, by the name of the component
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ComponentPatch {
+    /// `None` if the stack doesn't have the item's component
+    changes: BTreeMap<String, Option<DataComponent>>,
+}
+
+impl ComponentPatch {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            changes: BTreeMap::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// The component, if the stack changed it
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&DataComponent> {
+        self.changes.get(name)?.as_ref()
+    }
+
+    /// Whether the stack doesn't have the component, even if its item has it
+    #[must_use]
+    pub fn is_removed(&self, name: &str) -> bool {
+        matches!(self.changes.get(name), Some(None))
+    }
+
+    /// Sets a component Pumpkin knows, see [`Self::set_named`] for the others
+    pub fn set(&mut self, component: DataComponent) {
+        let name = match &component {
+            DataComponent::CustomData(_) => "minecraft:custom_data",
+            DataComponent::MaxStackSize(_) => "minecraft:max_stack_size",
+            DataComponent::MaxDamage(_) => "minecraft:max_damage",
+            DataComponent::Damage(_) => "minecraft:damage",
+            DataComponent::Unbreakable { .. } => "minecraft:unbreakable",
+            DataComponent::CustomName(_) => "minecraft:custom_name",
+            DataComponent::ItemName(_) => "minecraft:item_name",
+            DataComponent::ItemModel(_) => "minecraft:item_model",
+            DataComponent::Lore(_) => "minecraft:lore",
+            DataComponent::Rarity(_) => "minecraft:rarity",
+            DataComponent::Enchantments(_) => "minecraft:enchantments",
+            DataComponent::AttributeModifiers { .. } => "minecraft:attribute_modifiers",
+            DataComponent::CustomModelData(_) => "minecraft:custom_model_data",
+            DataComponent::HideAdditionalTooltip => "minecraft:hide_additional_tooltip",
+            DataComponent::HideTooltip => "minecraft:hide_tooltip",
+            DataComponent::RepairCost(_) => "minecraft:repair_cost",
+            DataComponent::EnchantmentGlintOverride(_) => "minecraft:enchantment_glint_override",
+            DataComponent::Food(_) => "minecraft:food",
+            DataComponent::StoredEnchantments(_) => "minecraft:stored_enchantments",
+            DataComponent::DyedColor { .. } => "minecraft:dyed_color",
+            DataComponent::CanPlaceOn(_) => "minecraft:can_place_on",
+            DataComponent::CanBreak(_) => "minecraft:can_break",
+            DataComponent::PotionContents(_) => "minecraft:potion_contents",
+            DataComponent::Other(_) => {
+                log::warn!("Unknown components have to be set with their name");
+                return;
+            }
+        };
+        self.changes.insert(name.to_string(), Some(component));
+    }
+
+    /// Sets the component with the name, which may be one Pumpkin doesn't know
+    pub fn set_named(&mut self, name: &str, component: DataComponent) {
+        self.changes.insert(namespaced(name), Some(component));
+    }
+
+    /// Removes the component from the stack, even if its item has it
+    pub fn remove(&mut self, name: &str) {
+        self.changes.insert(namespaced(name), None);
+    }
+
+    /// Makes the stack have the item's component again
+    pub fn reset(&mut self, name: &str) {
+        self.changes.remove(&namespaced(name));
+    }
+
+    /// Every changed component by name, `None` if it was removed
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Option<&DataComponent>)> {
+        self.changes
+            .iter()
+            .map(|(name, component)| (name.as_str(), component.as_ref()))
+    }
+
+    /// Reads the `components` compound of a saved stack, removed components are prefixed with `!`
+    #[must_use]
+    pub fn from_nbt(components: &Compound) -> Self {
+        let changes = components
+            .iter()
+            .map(|(name, value)| match name.strip_prefix('!') {
+                Some(name) => (namespaced(name), None),
+                None => {
+                    let name = namespaced(name);
+                    let component = DataComponent::from_nbt(&name, value);
+                    (name, Some(component))
+                }
+            })
+            .collect();
+        Self { changes }
+    }
+
+    /// The `components` compound of a saved stack
+    #[must_use]
+    pub fn to_nbt(&self) -> Compound {
+        self.changes
+            .iter()
+            .map(|(name, component)| match component {
+                Some(component) => (name.clone(), component.to_nbt()),
+                None => (format!("!{name}"), Value::Compound(Compound::new())),
+            })
+            .collect()
+    }
+
+    /// Converts the `tag` of a stack saved before 1.20.5, what has no component stays in
+    /// `minecraft:custom_data`
+    #[must_use]
+    pub fn from_legacy_tag(tag: &Compound) -> Self {
+        let mut patch = Self::default();
+        let mut custom_data = tag.clone();
+        let mut take = |key: &str| custom_data.remove(key);
+
+        if let Some(damage) = take("Damage").as_ref().and_then(as_int) {
+            if damage != 0 {
+                patch.set(DataComponent::Damage(damage));
+            }
+        }
+        if take("Unbreakable").as_ref().and_then(as_int) == Some(1) {
+            patch.set(DataComponent::Unbreakable {
+                show_in_tooltip: true,
+            });
+        }
+        if let Some(data) = take("CustomModelData").as_ref().and_then(as_int) {
+            patch.set(DataComponent::CustomModelData(data));
+        }
+        if let Some(cost) = take("RepairCost").as_ref().and_then(as_int) {
+            patch.set(DataComponent::RepairCost(cost));
+        }
+        if let Some(enchantments) = take("Enchantments").as_ref().and_then(legacy_enchantments) {
+            patch.set(DataComponent::Enchantments(enchantments));
+        }
+        if let Some(enchantments) = take("StoredEnchantments")
+            .as_ref()
+            .and_then(legacy_enchantments)
+        {
+            patch.set(DataComponent::StoredEnchantments(enchantments));
+        }
+        if let Some(Value::Compound(mut display)) = take("display") {
+            if let Some(name) = display.remove("Name").as_ref().and_then(as_string) {
+                patch.set(DataComponent::CustomName(name));
+            }
+            if let Some(Value::List(lore)) = display.remove("Lore") {
+                patch.set(DataComponent::Lore(
+                    lore.iter().filter_map(as_string).collect(),
+                ));
+            }
+            if let Some(rgb) = display.remove("color").as_ref().and_then(as_int) {
+                patch.set(DataComponent::DyedColor {
+                    rgb,
+                    show_in_tooltip: true,
+                });
+            }
+            if !display.is_empty() {
+                custom_data.insert("display".to_string(), Value::Compound(display));
+            }
+        }
+        // only used to hide parts of the tooltip, which components do themselves now
+        custom_data.remove("HideFlags");
+        if !custom_data.is_empty() {
+            patch.set(DataComponent::CustomData(custom_data));
+        }
+        patch
+    }
+}
+
+const fn rarity_name(rarity: Rarity) -> &'static str {
+    match rarity {
+        Rarity::Common => "common",
+        Rarity::UnCommon => "uncommon",
+        Rarity::Rare => "rare",
+        Rarity::Epic => "epic",
+    }
+}
+
+fn as_int(value: &Value) -> Option<i32> {
+    match value {
+        Value::Byte(value) => Some(i32::from(*value)),
+        Value::Short(value) => Some(i32::from(*value)),
+        Value::Int(value) => Some(*value),
+        _ => None,
+    }
+}
+
+fn as_float(value: &Value) -> Option<f32> {
+    match value {
+        Value::Float(value) => Some(*value),
+        Value::Double(value) => Some(*value as f32),
+        value => as_int(value).map(|value| value as f32),
+    }
+}
+
+fn as_double(value: &Value) -> Option<f64> {
+    match value {
+        Value::Float(value) => Some(f64::from(*value)),
+        Value::Double(value) => Some(*value),
+        value => as_int(value).map(f64::from),
+    }
+}
+
+fn as_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(value) => Some(value.clone()),
+        _ => None,
+    }
+}
+
+fn as_list(value: &Value) -> Option<&Vec<Value>> {
+    match value {
+        Value::List(list) => Some(list),
+        _ => None,
+    }
+}
+
+fn as_compound(value: &Value) -> Option<&Compound> {
+    match value {
+        Value::Compound(compound) => Some(compound),
+        _ => None,
+    }
+}
+
+/// `show_in_tooltip` is true unless it was saved as false
+fn show_in_tooltip(compound: &Compound) -> bool {
+    compound.get("show_in_tooltip").and_then(as_int) != Some(0)
+}
+
+/// Only a hidden tooltip is saved, like vanilla does
+fn tooltip_compound(show_in_tooltip: bool) -> Compound {
+    let mut compound = Compound::new();
+    if !show_in_tooltip {
+        compound.insert("show_in_tooltip".to_string(), Value::Byte(0));
+    }
+    compound
+}
+
+fn enchantments_from_nbt(value: &Value) -> Option<Enchantments> {
+    let compound = as_compound(value)?;
+    // enchantments may be saved without the levels compound if the tooltip is shown
+    let levels = match compound.get("levels") {
+        Some(levels) => as_compound(levels)?,
+        None => compound,
+    };
+    Some(Enchantments {
+        levels: levels
+            .iter()
+            .filter(|(name, _)| *name != "show_in_tooltip")
+            .map(|(name, level)| Some((namespaced(name), as_int(level)?)))
+            .collect::<Option<_>>()?,
+        show_in_tooltip: show_in_tooltip(compound),
+    })
+}
+
+fn attribute_modifiers_from_nbt(value: &Value) -> Option<DataComponent> {
+    let (modifiers, show_in_tooltip) = match value {
+        Value::List(modifiers) => (modifiers, true),
+        Value::Compound(compound) => (
+            as_list(compound.get("modifiers")?)?,
+            show_in_tooltip(compound),
+        ),
+        _ => return None,
+    };
+    let modifiers = modifiers
+        .iter()
+        .map(|modifier| {
+            let modifier = as_compound(modifier)?;
+            let string = |key: &str| modifier.get(key).and_then(as_string);
+            Some(AttributeModifier {
+                attribute: namespaced(&string("type")?),
+                id: namespaced(&string("id")?),
+                amount: as_double(modifier.get("amount")?)?,
+                operation: string("operation")?,
+                slot: string("slot").unwrap_or_else(|| "any".to_string()),
+            })
+        })
+        .collect::<Option<_>>()?;
+    Some(DataComponent::AttributeModifiers {
+        modifiers,
+        show_in_tooltip,
+    })
+}
+
+/// Predicates may be saved as one predicate, a list or `{predicates, show_in_tooltip}`
+fn adventure_predicate_from_nbt(value: &Value) -> Option<AdventurePredicate> {
+    let (predicates, show_in_tooltip) = match value {
+        Value::List(predicates) => (predicates.as_slice(), true),
+        Value::Compound(compound) => match compound.get("predicates") {
+            Some(Value::List(predicates)) => (predicates.as_slice(), show_in_tooltip(compound)),
+            Some(predicate) => (std::slice::from_ref(predicate), show_in_tooltip(compound)),
+            None => (std::slice::from_ref(value), true),
+        },
+        _ => return None,
+    };
+    Some(AdventurePredicate {
+        predicates: predicates
+            .iter()
+            .map(block_predicate_from_nbt)
+            .collect::<Option<_>>()?,
+        show_in_tooltip,
+    })
+}
+
+/// Potion contents are saved as a compound, or only as the potion's name
+fn potion_contents_from_nbt(value: &Value) -> Option<PotionContents> {
+    let compound = match value {
+        Value::String(potion) => return Some(PotionContents::of(potion)),
+        Value::Compound(compound) => compound,
+        _ => return None,
+    };
+    let custom_effects = match compound.get("custom_effects") {
+        Some(effects) => as_list(effects)?
+            .iter()
+            .map(potion_effect_from_nbt)
+            .collect::<Option<_>>()?,
+        None => Vec::new(),
+    };
+    Some(PotionContents {
+        potion: match compound.get("potion") {
+            Some(potion) => Some(namespaced(&as_string(potion)?)),
+            None => None,
+        },
+        custom_color: compound.get("custom_color").and_then(as_int),
+        custom_effects,
+        custom_name: compound.get("custom_name").and_then(as_string),
+    })
+}
+
+/// Like vanilla, the fields which have their default value are left out
+fn potion_contents_to_nbt(contents: &PotionContents) -> Value {
+    let mut nbt = Compound::new();
+    if let Some(potion) = &contents.potion {
+        nbt.insert("potion".to_string(), Value::String(potion.clone()));
+    }
+    if let Some(color) = contents.custom_color {
+        nbt.insert("custom_color".to_string(), Value::Int(color));
+    }
+    if !contents.custom_effects.is_empty() {
+        let effects = contents
+            .custom_effects
+            .iter()
+            .map(potion_effect_to_nbt)
+            .collect();
+        nbt.insert("custom_effects".to_string(), Value::List(effects));
+    }
+    if let Some(name) = &contents.custom_name {
+        nbt.insert("custom_name".to_string(), Value::String(name.clone()));
+    }
+    Value::Compound(nbt)
+}
+
+pub(crate) fn potion_effect_from_nbt(value: &Value) -> Option<PotionEffect> {
+    let compound = as_compound(value)?;
+    let flag = |name: &str, default: bool| {
+        compound
+            .get(name)
+            .and_then(as_int)
+            .map_or(default, |value| value != 0)
+    };
+    let show_particles = flag("show_particles", true);
+    Some(PotionEffect {
+        effect: namespaced(&as_string(compound.get("id")?)?),
+        // saved as an unsigned byte
+        amplifier: compound
+            .get("amplifier")
+            .and_then(as_int)
+            .map_or(0, |amplifier| amplifier & 0xFF),
+        duration: compound.get("duration").and_then(as_int).unwrap_or(0),
+        ambient: flag("ambient", false),
+        show_particles,
+        show_icon: flag("show_icon", show_particles),
+    })
+}
+
+pub(crate) fn potion_effect_to_nbt(effect: &PotionEffect) -> Value {
+    let mut nbt = Compound::from([("id".to_string(), Value::String(effect.effect.clone()))]);
+    if effect.amplifier != 0 {
+        nbt.insert(
+            "amplifier".to_string(),
+            Value::Byte(effect.amplifier.clamp(0, 255) as u8 as i8),
+        );
+    }
+    if effect.duration != 0 {
+        nbt.insert("duration".to_string(), Value::Int(effect.duration));
+    }
+    if effect.ambient {
+        nbt.insert("ambient".to_string(), Value::Byte(1));
+    }
+    if !effect.show_particles {
+        nbt.insert("show_particles".to_string(), Value::Byte(0));
+    }
+    if effect.show_icon != effect.show_particles {
+        nbt.insert(
+            "show_icon".to_string(),
+            Value::Byte(i8::from(effect.show_icon)),
+        );
+    }
+    Value::Compound(nbt)
+}
+
+fn block_predicate_from_nbt(value: &Value) -> Option<BlockPredicate> {
+    let predicate = as_compound(value)?;
+    let blocks = match predicate.get("blocks") {
+        None => None,
+        Some(Value::String(blocks)) => Some(match blocks.strip_prefix('#') {
+            Some(tag) => BlockSet::Tag(namespaced(tag)),
+            None => BlockSet::Blocks(vec![namespaced(blocks)]),
+        }),
+        Some(blocks) => Some(BlockSet::Blocks(
+            as_list(blocks)?
+                .iter()
+                .map(|block| as_string(block).map(|block| namespaced(&block)))
+                .collect::<Option<_>>()?,
+        )),
+    };
+    // ranges of properties are dropped
+    let state = match predicate.get("state") {
+        Some(state) => as_compound(state)?
+            .iter()
+            .filter_map(|(name, value)| Some((name.clone(), as_string(value)?)))
+            .collect(),
+        None => BTreeMap::new(),
+    };
+    Some(BlockPredicate { blocks, state })
+}
+
+fn block_predicate_to_nbt(predicate: &BlockPredicate) -> Value {
+    let mut nbt = Compound::new();
+    match &predicate.blocks {
+        Some(BlockSet::Tag(tag)) => {
+            nbt.insert("blocks".to_string(), Value::String(format!("#{tag}")));
+        }
+        Some(BlockSet::Blocks(blocks)) => {
+            let blocks = blocks.iter().cloned().map(Value::String).collect();
+            nbt.insert("blocks".to_string(), Value::List(blocks));
+        }
+        None => {}
+    }
+    if !predicate.state.is_empty() {
+        let state = predicate
+            .state
+            .iter()
+            .map(|(name, value)| (name.clone(), Value::String(value.clone())))
+            .collect();
+        nbt.insert("state".to_string(), Value::Compound(state));
+    }
+    Value::Compound(nbt)
+}
+
+/// Enchantments before 1.20.5 were a list of `{id, lvl}`
+fn legacy_enchantments(value: &Value) -> Option<Enchantments> {
+    let levels = as_list(value)?
+        .iter()
+        .filter_map(|enchantment| {
+            let enchantment = as_compound(enchantment)?;
+            let id = as_string(enchantment.get("id")?)?;
+            let level = as_int(enchantment.get("lvl")?)?;
+            Some((namespaced(&id), level))
+        })
+        .collect();
+    Some(Enchantments {
+        levels,
+        show_in_tooltip: true,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use fastnbt::Value;
+    use pumpkin_core::nbt::{snbt::parse_compound, Compound};
+
+    use super::{
+        AdventurePredicate, BlockPredicate, BlockSet, ComponentPatch, DataComponent, Food,
+        PotionContents, PotionEffect,
+    };
+    use crate::block::block_registry::get_block;
+
+    #[test]
+    fn saved_components_round_trip() {
+        let saved = parse_compound(
+            r#"{"minecraft:damage":5,"minecraft:enchantments":{levels:{"minecraft:sharpness":3}},"minecraft:food":{nutrition:4,saturation:2.4f},"minecraft:trim":{pattern:"minecraft:eye",material:"minecraft:gold"},"!minecraft:lore":{}}"#,
+        )
+        .unwrap();
+        let patch = ComponentPatch::from_nbt(&saved);
+        assert_eq!(
+            patch.get("minecraft:damage"),
+            Some(&DataComponent::Damage(5))
+        );
+        assert_eq!(
+            patch.get("minecraft:food"),
+            Some(&DataComponent::Food(Food {
+                nutrition: 4,
+                saturation: 2.4,
+                can_always_eat: false,
+            }))
+        );
+        assert!(matches!(
+            patch.get("minecraft:trim"),
+            Some(DataComponent::Other(_))
+        ));
+        assert!(patch.is_removed("minecraft:lore"));
+        assert_eq!(patch.to_nbt(), saved);
+    }
+
+    #[test]
+    fn potion_contents() {
+        let saved = parse_compound(
+            r#"{"minecraft:potion_contents":{potion:"minecraft:swiftness",custom_effects:[{id:"minecraft:haste",amplifier:1b,duration:600}]}}"#,
+        )
+        .unwrap();
+        let patch = ComponentPatch::from_nbt(&saved);
+        assert_eq!(
+            patch.get("minecraft:potion_contents"),
+            Some(&DataComponent::PotionContents(PotionContents {
+                custom_effects: vec![PotionEffect::new("haste", 1, 600)],
+                ..PotionContents::of("swiftness")
+            }))
+        );
+        assert_eq!(patch.to_nbt(), saved);
+
+        // only the potion's name is enough
+        let saved = parse_compound(r#"{"minecraft:potion_contents":"minecraft:water"}"#).unwrap();
+        assert_eq!(
+            ComponentPatch::from_nbt(&saved).get("minecraft:potion_contents"),
+            Some(&DataComponent::PotionContents(PotionContents::of("water")))
+        );
+    }
+
+    #[test]
+    fn legacy_tags_are_converted() {
+        let tag = parse_compound(
+            r#"{Damage:12,display:{Name:'{"text":"Blade"}',color:255},Enchantments:[{id:"sharpness",lvl:2s}],HideFlags:1,CustomTag:1b}"#,
+        )
+        .unwrap();
+        let patch = ComponentPatch::from_legacy_tag(&tag);
+        assert_eq!(
+            patch.get("minecraft:damage"),
+            Some(&DataComponent::Damage(12))
+        );
+        assert_eq!(
+            patch.get("minecraft:custom_name"),
+            Some(&DataComponent::CustomName(
+                r#"{"text":"Blade"}"#.to_string()
+            ))
+        );
+        let Some(DataComponent::Enchantments(enchantments)) = patch.get("minecraft:enchantments")
+        else {
+            panic!("The enchantments were not converted");
+        };
+        assert_eq!(enchantments.levels.get("minecraft:sharpness"), Some(&2));
+        assert_eq!(
+            patch.get("minecraft:custom_data"),
+            Some(&DataComponent::CustomData(Compound::from([(
+                "CustomTag".to_string(),
+                Value::Byte(1)
+            )])))
+        );
+    }
+
+    #[test]
+    fn adventure_predicates() {
+        let saved = parse_compound(
+            r##"{"minecraft:can_break":{predicates:[{blocks:["stone"]},{blocks:"#minecraft:logs"}],show_in_tooltip:0b}}"##,
+        )
+        .unwrap();
+        let patch = ComponentPatch::from_nbt(&saved);
+        let Some(DataComponent::CanBreak(can_break)) = patch.get("minecraft:can_break") else {
+            panic!("can_break was not read");
+        };
+        assert_eq!(
+            can_break,
+            &AdventurePredicate {
+                predicates: vec![
+                    BlockPredicate {
+                        blocks: Some(BlockSet::Blocks(vec!["minecraft:stone".to_string()])),
+                        ..Default::default()
+                    },
+                    BlockPredicate {
+                        blocks: Some(BlockSet::Tag("minecraft:logs".to_string())),
+                        ..Default::default()
+                    },
+                ],
+                show_in_tooltip: false,
+            }
+        );
+
+        let stone = get_block("minecraft:stone").unwrap();
+        let dirt = get_block("minecraft:dirt").unwrap();
+        let no_tags = |_: &str| false;
+        assert!(can_break.predicates[0].test(stone, stone.default_state_id, no_tags));
+        assert!(!can_break.predicates[0].test(dirt, dirt.default_state_id, no_tags));
+        assert!(can_break.predicates[1]
+            .test(dirt, dirt.default_state_id, |tag| tag == "minecraft:logs"));
+
+        let written = patch.to_nbt();
+        assert_eq!(ComponentPatch::from_nbt(&written), patch);
+    }
+}
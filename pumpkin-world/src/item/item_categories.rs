@@ -1,16 +1,21 @@
-use crate::item::ItemStack;
+use crate::item::{item_registry::get_item_name, ItemStack};
 
 impl ItemStack {
     pub fn is_sword(&self) -> bool {
-        [
-            818, // Wooden
-            823, // Stone
-            828, // Gold
-            833, // Iron
-            838, // Diamond
-            843, // Netherite
-        ]
-        .contains(&self.item_id)
+        self.name_ends_with("_sword")
+    }
+
+    /// Axes disable the shields they hit
+    pub fn is_axe(&self) -> bool {
+        self.name_ends_with("_axe")
+    }
+
+    pub fn is_shield(&self) -> bool {
+        get_item_name(self.item_id) == Some("minecraft:shield")
+    }
+
+    fn name_ends_with(&self, suffix: &str) -> bool {
+        get_item_name(self.item_id).is_some_and(|name| name.ends_with(suffix))
     }
 
     pub fn is_helmet(&self) -> bool {
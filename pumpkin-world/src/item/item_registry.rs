@@ -8,18 +8,110 @@ pub static ITEMS: LazyLock<HashMap<String, Item>> = LazyLock::new(|| {
     serde_json::from_str(ITEMS_JSON).expect("Could not parse items.json registry.")
 });
 
+static ITEMS_BY_ID: LazyLock<HashMap<u16, &'static Item>> =
+    LazyLock::new(|| ITEMS.values().map(|item| (item.id, item)).collect());
+
+static ITEM_NAMES_BY_ID: LazyLock<HashMap<u16, &'static str>> = LazyLock::new(|| {
+    ITEMS
+        .iter()
+        .map(|(name, item)| (item.id, name.as_str()))
+        .collect()
+});
+
 pub fn get_item(name: &str) -> Option<&Item> {
     ITEMS.get(name)
 }
 
+/// Finds an item by its protocol id, e.g. the one of an [`super::ItemStack`]
+pub fn get_item_by_id(id: u16) -> Option<&'static Item> {
+    ITEMS_BY_ID.get(&id).copied()
+}
+
+/// The name of the item with the protocol id, e.g. `minecraft:stone`
+pub fn get_item_name(id: u16) -> Option<&'static str> {
+    ITEM_NAMES_BY_ID.get(&id).copied()
+}
+
 #[derive(Deserialize, Clone, Debug)]
 pub struct Item {
     pub id: u16,
     pub components: ItemComponents,
 }
 
+impl Item {
+    /// The sum of the item's modifiers adding to the attribute, e.g. `minecraft:knockback_resistance`
+    #[must_use]
+    pub fn attribute_bonus(&self, attribute: &str) -> f64 {
+        self.components
+            .attribute_modifiers
+            .iter()
+            .flat_map(|modifiers| &modifiers.modifiers)
+            .filter(|modifier| modifier.r#type == attribute && modifier.operation == "add_value")
+            .map(|modifier| modifier.amount)
+            .sum()
+    }
+}
+
 #[derive(Deserialize, Clone, Debug)]
 pub struct ItemComponents {
     #[serde(rename = "minecraft:max_stack_size")]
     pub max_stack_size: u8,
+    /// How much the item can be used before it breaks, tools and armor have it
+    #[serde(rename = "minecraft:max_damage")]
+    pub max_damage: Option<i32>,
+    #[serde(rename = "minecraft:attribute_modifiers")]
+    pub attribute_modifiers: Option<AttributeModifiers>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct AttributeModifiers {
+    pub modifiers: Vec<AttributeModifier>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct AttributeModifier {
+    pub r#type: String,
+    pub amount: f64,
+    pub operation: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_item, get_item_by_id};
+    use crate::item::ItemStack;
+
+    #[test]
+    fn knockback_resistance_of_armor() {
+        let helmet = get_item("minecraft:netherite_helmet").unwrap();
+        assert_eq!(get_item_by_id(helmet.id).unwrap().id, helmet.id);
+        assert!((helmet.attribute_bonus("minecraft:knockback_resistance") - 0.1).abs() < 1e-6);
+
+        let diamond = get_item("minecraft:diamond_helmet").unwrap();
+        assert_eq!(
+            diamond.attribute_bonus("minecraft:knockback_resistance"),
+            0.0
+        );
+    }
+
+    #[test]
+    fn weapon_categories() {
+        let stack = |name| ItemStack::new(1, get_item(name).unwrap().id);
+        assert!(stack("minecraft:wooden_sword").is_sword());
+        assert!(stack("minecraft:netherite_axe").is_axe());
+        assert!(!stack("minecraft:netherite_pickaxe").is_axe());
+        assert!(stack("minecraft:shield").is_shield());
+
+        let sword = get_item("minecraft:diamond_sword").unwrap();
+        assert!((sword.attribute_bonus("minecraft:attack_damage") - 6.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn tools_wear_out() {
+        let mut shears = ItemStack::new(1, get_item("minecraft:shears").unwrap().id);
+        assert!(!shears.wear(237));
+        assert!(shears.wear(1));
+
+        let mut stone = ItemStack::new(1, get_item("minecraft:stone").unwrap().id);
+        assert!(!stone.wear(1000));
+    }
 }
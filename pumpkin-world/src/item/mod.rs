@@ -1,7 +1,13 @@
+pub mod component;
 mod item_categories;
 pub mod item_registry;
+pub mod potion;
+use component::{ComponentPatch, DataComponent};
+use fastnbt::Value;
 pub use item_registry::ITEMS;
-#[derive(serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+use item_registry::{get_item, get_item_by_id, get_item_name};
+use pumpkin_core::nbt::Compound;
+#[derive(serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 /// Item Rarity
 pub enum Rarity {
@@ -11,17 +17,19 @@ pub enum Rarity {
     Epic,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub struct ItemStack {
     pub item_count: u8,
     // This ID is the numerical protocol ID, not the usual minecraft::block ID.
     pub item_id: u16,
-    // TODO: Add Item Components
+    /// How the components of the stack differ from the item's
+    pub components: ComponentPatch,
 }
 
+/// Stacks are equal if they can be stacked, so the count is ignored
 impl PartialEq for ItemStack {
     fn eq(&self, other: &Self) -> bool {
-        self.item_id == other.item_id
+        self.item_id == other.item_id && self.components == other.components
     }
 }
 
@@ -30,6 +38,117 @@ impl ItemStack {
         Self {
             item_count,
             item_id,
+            components: ComponentPatch::default(),
         }
     }
+
+    /// Picks the model of custom items, which are told apart from vanilla items by it
+    pub fn custom_model_data(&self) -> Option<i32> {
+        match self.components.get("minecraft:custom_model_data") {
+            Some(DataComponent::CustomModelData(data)) => Some(*data),
+            _ => None,
+        }
+    }
+
+    pub fn set_custom_model_data(&mut self, data: Option<i32>) {
+        match data {
+            Some(data) => self.components.set(DataComponent::CustomModelData(data)),
+            None => self.components.reset("minecraft:custom_model_data"),
+        }
+    }
+
+    /// Reads a stack as it is saved, stacks saved before 1.20.5 with a `Count` and `tag` are
+    /// converted. Returns `None` for unknown items
+    pub fn from_nbt(nbt: &Compound) -> Option<Self> {
+        let Some(Value::String(id)) = nbt.get("id") else {
+            return None;
+        };
+        let count = match (nbt.get("count"), nbt.get("Count")) {
+            (Some(Value::Int(count)), _) => u8::try_from(*count).ok()?,
+            (None, Some(Value::Byte(count))) => u8::try_from(*count).ok()?,
+            (None, None) => 1,
+            _ => return None,
+        };
+        let mut stack = Self::new(count, get_item(id)?.id);
+        if let Some(Value::Compound(components)) = nbt.get("components") {
+            stack.components = ComponentPatch::from_nbt(components);
+        } else if let Some(Value::Compound(tag)) = nbt.get("tag") {
+            stack.components = ComponentPatch::from_legacy_tag(tag);
+        }
+        Some(stack)
+    }
+
+    /// The stack as it is saved, `None` if the item is unknown
+    pub fn to_nbt(&self) -> Option<Compound> {
+        let mut nbt = Compound::from([
+            (
+                "id".to_string(),
+                Value::String(get_item_name(self.item_id)?.to_string()),
+            ),
+            ("count".to_string(), Value::Int(self.item_count.into())),
+        ]);
+        if !self.components.is_empty() {
+            nbt.insert(
+                "components".to_string(),
+                Value::Compound(self.components.to_nbt()),
+            );
+        }
+        Some(nbt)
+    }
+
+    /// Wears the item out like a tool being used, returns true if it broke. Items without
+    /// durability and unbreakable ones don't wear out
+    pub fn wear(&mut self, amount: i32) -> bool {
+        let max_damage = match self.components.get("minecraft:max_damage") {
+            Some(DataComponent::MaxDamage(max_damage)) => Some(*max_damage),
+            _ => get_item_by_id(self.item_id).and_then(|item| item.components.max_damage),
+        };
+        let Some(max_damage) = max_damage else {
+            return false;
+        };
+        if self.components.get("minecraft:unbreakable").is_some() {
+            return false;
+        }
+        let damage = match self.components.get("minecraft:damage") {
+            Some(DataComponent::Damage(damage)) => *damage,
+            _ => 0,
+        } + amount;
+        if damage >= max_damage {
+            return true;
+        }
+        self.components.set(DataComponent::Damage(damage));
+        false
+    }
+}
+
+/// The stacks in a list of items with their `Slot` like vanilla saves inventories, chests and
+/// other containers. Unknown items are skipped
+pub fn slots_from_nbt(items: &Value) -> Vec<(i8, ItemStack)> {
+    let Value::List(items) = items else {
+        return Vec::new();
+    };
+    items.iter().filter_map(slot_from_nbt).collect()
+}
+
+fn slot_from_nbt(item: &Value) -> Option<(i8, ItemStack)> {
+    let Value::Compound(item) = item else {
+        return None;
+    };
+    let Some(Value::Byte(slot)) = item.get("Slot") else {
+        return None;
+    };
+    Some((*slot, ItemStack::from_nbt(item)?))
+}
+
+/// The stacks as a list of items with their `Slot`, see [`slots_from_nbt`]
+pub fn slots_to_nbt<'a>(items: impl IntoIterator<Item = (i8, &'a ItemStack)>) -> Value {
+    let items = items
+        .into_iter()
+        .filter_map(|(slot, stack)| {
+            let mut item = stack.to_nbt()?;
+            item.insert("Slot".to_string(), Value::Byte(slot));
+            Some(Value::Compound(item))
+        })
+        .collect();
+    Value::List(items)
 }
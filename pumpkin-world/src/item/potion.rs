@@ -0,0 +1,327 @@
+//! The effects of potions, their colors and how they are brewed in brewing stands.
+
+use super::{
+    component::{namespaced, DataComponent, PotionContents, PotionEffect},
+    item_registry::{get_item, get_item_name},
+    ItemStack,
+};
+
+/// The color of potions without effects, like water bottles
+pub const WATER_COLOR: i32 = 0x0038_5DC6;
+
+/// The ticks a brewing stand takes to brew
+pub const BREW_TIME: i32 = 400;
+/// How many brews one blaze powder fuels
+pub const FUEL_PER_BLAZE_POWDER: i32 = 20;
+
+/// Brewing the ingredient into a potion of the first kind gives the second, like vanilla's mixes
+const MIXES: &[(&str, &str, &str)] = &[
+    ("water", "glistering_melon_slice", "mundane"),
+    ("water", "ghast_tear", "mundane"),
+    ("water", "rabbit_foot", "mundane"),
+    ("water", "blaze_powder", "mundane"),
+    ("water", "spider_eye", "mundane"),
+    ("water", "sugar", "mundane"),
+    ("water", "magma_cream", "mundane"),
+    ("water", "redstone", "mundane"),
+    ("water", "glowstone_dust", "thick"),
+    ("water", "nether_wart", "awkward"),
+    ("water", "fermented_spider_eye", "weakness"),
+    ("awkward", "breeze_rod", "wind_charged"),
+    ("awkward", "slime_block", "oozing"),
+    ("awkward", "stone", "infested"),
+    ("awkward", "cobweb", "weaving"),
+    ("awkward", "golden_carrot", "night_vision"),
+    ("night_vision", "redstone", "long_night_vision"),
+    ("night_vision", "fermented_spider_eye", "invisibility"),
+    (
+        "long_night_vision",
+        "fermented_spider_eye",
+        "long_invisibility",
+    ),
+    ("invisibility", "redstone", "long_invisibility"),
+    ("awkward", "magma_cream", "fire_resistance"),
+    ("fire_resistance", "redstone", "long_fire_resistance"),
+    ("awkward", "rabbit_foot", "leaping"),
+    ("leaping", "redstone", "long_leaping"),
+    ("leaping", "glowstone_dust", "strong_leaping"),
+    ("leaping", "fermented_spider_eye", "slowness"),
+    ("long_leaping", "fermented_spider_eye", "long_slowness"),
+    ("slowness", "redstone", "long_slowness"),
+    ("slowness", "glowstone_dust", "strong_slowness"),
+    ("awkward", "turtle_helmet", "turtle_master"),
+    ("turtle_master", "redstone", "long_turtle_master"),
+    ("turtle_master", "glowstone_dust", "strong_turtle_master"),
+    ("swiftness", "fermented_spider_eye", "slowness"),
+    ("long_swiftness", "fermented_spider_eye", "long_slowness"),
+    ("awkward", "sugar", "swiftness"),
+    ("swiftness", "redstone", "long_swiftness"),
+    ("swiftness", "glowstone_dust", "strong_swiftness"),
+    ("awkward", "pufferfish", "water_breathing"),
+    ("water_breathing", "redstone", "long_water_breathing"),
+    ("awkward", "glistering_melon_slice", "healing"),
+    ("healing", "glowstone_dust", "strong_healing"),
+    ("healing", "fermented_spider_eye", "harming"),
+    ("strong_healing", "fermented_spider_eye", "strong_harming"),
+    ("harming", "glowstone_dust", "strong_harming"),
+    ("poison", "fermented_spider_eye", "harming"),
+    ("long_poison", "fermented_spider_eye", "harming"),
+    ("strong_poison", "fermented_spider_eye", "strong_harming"),
+    ("awkward", "spider_eye", "poison"),
+    ("poison", "redstone", "long_poison"),
+    ("poison", "glowstone_dust", "strong_poison"),
+    ("awkward", "ghast_tear", "regeneration"),
+    ("regeneration", "redstone", "long_regeneration"),
+    ("regeneration", "glowstone_dust", "strong_regeneration"),
+    ("awkward", "blaze_powder", "strength"),
+    ("strength", "redstone", "long_strength"),
+    ("strength", "glowstone_dust", "strong_strength"),
+    ("weakness", "redstone", "long_weakness"),
+    ("awkward", "phantom_membrane", "slow_falling"),
+    ("slow_falling", "redstone", "long_slow_falling"),
+];
+
+/// Brewing the ingredient into a bottle of the first item turns it into the second, the potion
+/// stays the same
+const CONTAINER_MIXES: &[(&str, &str, &str)] = &[
+    ("potion", "gunpowder", "splash_potion"),
+    ("splash_potion", "dragon_breath", "lingering_potion"),
+];
+
+/// The effects of the potion, as effect, amplifier and duration in ticks
+fn effects_of(potion: &str) -> &'static [(&'static str, i32, i32)] {
+    let potion = potion.strip_prefix("minecraft:").unwrap_or(potion);
+    match potion {
+        "night_vision" => &[("night_vision", 0, 3600)],
+        "long_night_vision" => &[("night_vision", 0, 9600)],
+        "invisibility" => &[("invisibility", 0, 3600)],
+        "long_invisibility" => &[("invisibility", 0, 9600)],
+        "leaping" => &[("jump_boost", 0, 3600)],
+        "long_leaping" => &[("jump_boost", 0, 9600)],
+        "strong_leaping" => &[("jump_boost", 1, 1800)],
+        "fire_resistance" => &[("fire_resistance", 0, 3600)],
+        "long_fire_resistance" => &[("fire_resistance", 0, 9600)],
+        "swiftness" => &[("speed", 0, 3600)],
+        "long_swiftness" => &[("speed", 0, 9600)],
+        "strong_swiftness" => &[("speed", 1, 1800)],
+        "slowness" => &[("slowness", 0, 1800)],
+        "long_slowness" => &[("slowness", 0, 4800)],
+        "strong_slowness" => &[("slowness", 3, 400)],
+        "turtle_master" => &[("slowness", 3, 400), ("resistance", 2, 400)],
+        "long_turtle_master" => &[("slowness", 3, 800), ("resistance", 2, 800)],
+        "strong_turtle_master" => &[("slowness", 5, 400), ("resistance", 3, 400)],
+        "water_breathing" => &[("water_breathing", 0, 3600)],
+        "long_water_breathing" => &[("water_breathing", 0, 9600)],
+        "healing" => &[("instant_health", 0, 1)],
+        "strong_healing" => &[("instant_health", 1, 1)],
+        "harming" => &[("instant_damage", 0, 1)],
+        "strong_harming" => &[("instant_damage", 1, 1)],
+        "poison" => &[("poison", 0, 900)],
+        "long_poison" => &[("poison", 0, 1800)],
+        "strong_poison" => &[("poison", 1, 432)],
+        "regeneration" => &[("regeneration", 0, 900)],
+        "long_regeneration" => &[("regeneration", 0, 1800)],
+        "strong_regeneration" => &[("regeneration", 1, 450)],
+        "strength" => &[("strength", 0, 3600)],
+        "long_strength" => &[("strength", 0, 9600)],
+        "strong_strength" => &[("strength", 1, 1800)],
+        "weakness" => &[("weakness", 0, 1800)],
+        "long_weakness" => &[("weakness", 0, 4800)],
+        "luck" => &[("luck", 0, 6000)],
+        "slow_falling" => &[("slow_falling", 0, 1800)],
+        "long_slow_falling" => &[("slow_falling", 0, 4800)],
+        "wind_charged" => &[("wind_charged", 0, 3600)],
+        "weaving" => &[("weaving", 0, 3600)],
+        "oozing" => &[("oozing", 0, 3600)],
+        "infested" => &[("infested", 0, 3600)],
+        _ => &[],
+    }
+}
+
+/// The color of an effect's particles, which potions mix
+#[must_use]
+pub fn effect_color(effect: &str) -> i32 {
+    let effect = effect.strip_prefix("minecraft:").unwrap_or(effect);
+    match effect {
+        "speed" => 0x0033_EBFF,
+        "slowness" => 0x008B_AFE0,
+        "haste" => 0x00D9_C043,
+        "mining_fatigue" => 0x004A_4217,
+        "strength" => 0x00FF_C700,
+        "instant_health" | "saturation" => 0x00F8_2423,
+        "instant_damage" => 0x00A9_656A,
+        "jump_boost" => 0x00FD_FF84,
+        "nausea" => 0x0055_1D4A,
+        "regeneration" => 0x00CD_5CAB,
+        "resistance" => 0x0091_46F0,
+        "fire_resistance" => 0x00FF_9900,
+        "water_breathing" => 0x0098_DAC0,
+        "invisibility" => 0x00F6_F6F6,
+        "blindness" => 0x001F_1F23,
+        "night_vision" => 0x00C2_FF66,
+        "hunger" => 0x0058_7653,
+        "weakness" => 0x0048_4D48,
+        "poison" => 0x0087_A363,
+        "wither" => 0x0073_6156,
+        "health_boost" => 0x00F8_7D23,
+        "absorption" => 0x0025_52A5,
+        "glowing" => 0x0094_A061,
+        "levitation" => 0x00CE_FFFF,
+        "luck" => 0x0059_C106,
+        "unluck" => 0x00C0_A44D,
+        "slow_falling" => 0x00F3_CFB9,
+        "conduit_power" => 0x001D_C2D1,
+        "dolphins_grace" => 0x0088_A3BE,
+        "bad_omen" => 0x000B_6138,
+        "hero_of_the_village" => 0x0044_FF44,
+        "darkness" => 0x0029_2721,
+        "trial_omen" => 0x0016_A6A6,
+        "raid_omen" => 0x00DE_4058,
+        "wind_charged" => 0x00BD_C9FF,
+        "weaving" => 0x0078_695A,
+        "oozing" => 0x0099_FFA3,
+        "infested" => 0x008C_9B8C,
+        _ => WATER_COLOR,
+    }
+}
+
+impl PotionContents {
+    /// The effects of the potion and the custom ones
+    #[must_use]
+    pub fn effects(&self) -> Vec<PotionEffect> {
+        let potion = self.potion.as_deref().map_or(&[][..], effects_of);
+        potion
+            .iter()
+            .map(|(effect, amplifier, duration)| PotionEffect::new(effect, *amplifier, *duration))
+            .chain(self.custom_effects.iter().cloned())
+            .collect()
+    }
+
+    /// The custom color, or else the colors of the visible effects mixed like vanilla does
+    #[must_use]
+    pub fn color(&self) -> i32 {
+        if let Some(color) = self.custom_color {
+            return color;
+        }
+        let (mut red, mut green, mut blue, mut weight) = (0, 0, 0, 0);
+        for effect in self.effects().iter().filter(|effect| effect.show_particles) {
+            let color = effect_color(&effect.effect);
+            let level = effect.amplifier + 1;
+            red += level * ((color >> 16) & 0xFF);
+            green += level * ((color >> 8) & 0xFF);
+            blue += level * (color & 0xFF);
+            weight += level;
+        }
+        if weight == 0 {
+            return WATER_COLOR;
+        }
+        ((red / weight) << 16) | ((green / weight) << 8) | (blue / weight)
+    }
+}
+
+fn short_name(item_id: u16) -> Option<&'static str> {
+    get_item_name(item_id).map(|name| name.strip_prefix("minecraft:").unwrap_or(name))
+}
+
+impl ItemStack {
+    /// A potion, splash potion, lingering potion or tipped arrow of the potion, e.g.
+    /// `minecraft:strong_healing`. `None` if the item doesn't exist
+    #[must_use]
+    pub fn potion(item: &str, potion: &str) -> Option<Self> {
+        let mut stack = Self::new(1, get_item(&namespaced(item))?.id);
+        stack
+            .components
+            .set(DataComponent::PotionContents(PotionContents::of(potion)));
+        Some(stack)
+    }
+
+    #[must_use]
+    pub fn potion_contents(&self) -> Option<&PotionContents> {
+        match self.components.get("minecraft:potion_contents") {
+            Some(DataComponent::PotionContents(contents)) => Some(contents),
+            _ => None,
+        }
+    }
+
+    /// Whether it is a potion, splash potion or lingering potion, which go into the bottle slots
+    /// of brewing stands
+    #[must_use]
+    pub fn is_potion(&self) -> bool {
+        matches!(
+            short_name(self.item_id),
+            Some("potion" | "splash_potion" | "lingering_potion")
+        )
+    }
+
+    /// Whether brewing stands accept it as ingredient
+    #[must_use]
+    pub fn is_brewing_ingredient(&self) -> bool {
+        short_name(self.item_id).is_some_and(|name| {
+            MIXES.iter().any(|(_, ingredient, _)| *ingredient == name)
+                || CONTAINER_MIXES
+                    .iter()
+                    .any(|(_, ingredient, _)| *ingredient == name)
+        })
+    }
+
+    /// What brewing the ingredient into this potion gives, `None` if nothing is brewed
+    #[must_use]
+    pub fn brew(&self, ingredient: &Self) -> Option<Self> {
+        if !self.is_potion() {
+            return None;
+        }
+        let item = short_name(self.item_id)?;
+        let ingredient = short_name(ingredient.item_id)?;
+        if let Some((_, _, to)) = CONTAINER_MIXES
+            .iter()
+            .find(|(from, with, _)| *from == item && *with == ingredient)
+        {
+            let mut brewed = self.clone();
+            brewed.item_id = get_item(&namespaced(to))?.id;
+            return Some(brewed);
+        }
+        let potion = self.potion_contents()?.potion.as_deref()?;
+        let potion = potion.strip_prefix("minecraft:").unwrap_or(potion);
+        let (_, _, to) = MIXES
+            .iter()
+            .find(|(from, with, _)| *from == potion && *with == ingredient)?;
+        Self::potion(item, to)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::item::{item_registry::get_item, ItemStack};
+
+    fn stack(name: &str) -> ItemStack {
+        ItemStack::new(1, get_item(name).unwrap().id)
+    }
+
+    #[test]
+    fn brewing() {
+        let water = ItemStack::potion("potion", "water").unwrap();
+        let awkward = water.brew(&stack("minecraft:nether_wart")).unwrap();
+        assert_eq!(awkward, ItemStack::potion("potion", "awkward").unwrap());
+        let swiftness = awkward.brew(&stack("minecraft:sugar")).unwrap();
+        let strong = swiftness.brew(&stack("minecraft:glowstone_dust")).unwrap();
+        assert_eq!(
+            strong,
+            ItemStack::potion("potion", "strong_swiftness").unwrap()
+        );
+        let splash = strong.brew(&stack("minecraft:gunpowder")).unwrap();
+        assert_eq!(
+            splash,
+            ItemStack::potion("splash_potion", "strong_swiftness").unwrap()
+        );
+        assert!(water.brew(&stack("minecraft:dirt")).is_none());
+        assert!(stack("minecraft:dragon_breath").is_brewing_ingredient());
+    }
+
+    #[test]
+    fn colors() {
+        let water = ItemStack::potion("potion", "water").unwrap();
+        assert_eq!(water.potion_contents().unwrap().color(), super::WATER_COLOR);
+        let healing = ItemStack::potion("potion", "healing").unwrap();
+        assert_eq!(healing.potion_contents().unwrap().color(), 0x00F8_2423);
+    }
+}
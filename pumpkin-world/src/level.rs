@@ -1,24 +1,49 @@
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    collections::HashMap,
+    fs,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 
 use dashmap::{DashMap, Entry};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
 use num_traits::Zero;
-use pumpkin_config::BASIC_CONFIG;
-use pumpkin_core::math::vector2::Vector2;
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use pumpkin_config::WorldConfig;
+use pumpkin_core::{math::vector2::Vector2, persistent_data::PersistentDataContainer};
+use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use tokio::{
     sync::{mpsc, RwLock},
     task::JoinHandle,
 };
+use uuid::Uuid;
 
 use crate::{
+    biome::Biome,
     chunk::{
-        anvil::AnvilChunkReader, ChunkData, ChunkParsingError, ChunkReader, ChunkReadingError,
+        cache::{ChunkCache, ChunkCacheStats},
+        light::{LightEngine, RelightListener},
+        open_storage, ChunkBlocks, ChunkData, ChunkParsingError, ChunkReadingError, ChunkStorage,
     },
+    coordinates::XZBlockCoordinates,
+    level_data::{LevelData, LevelFile},
+    player_data::PlayerData,
+    structure::{Structure, StructureLocator},
     world_gen::{get_world_gen, Seed, WorldGenerator},
+    DATA_VERSION,
 };
 
 pub type ConcurrentChunkResult = Vec<(Vector2<i32>, JoinHandle<()>)>;
 
+/// Called with the position of a chunk once it was read or generated, whether it was generated
+/// and how long that took
+pub type ChunkLoadListener = Arc<dyn Fn(Vector2<i32>, bool, Duration) + Send + Sync>;
+
 /// The `Level` module provides functionality for working with chunks within or outside a Minecraft world.
 ///
 /// Key features include:
@@ -31,67 +56,422 @@ pub type ConcurrentChunkResult = Vec<(Vector2<i32>, JoinHandle<()>)>;
 pub struct Level {
     pub seed: Seed,
     save_file: Option<SaveFile>,
+    level_data: parking_lot::Mutex<LevelData>,
     loaded_chunks: Arc<DashMap<Vector2<i32>, Arc<RwLock<ChunkData>>>>,
     chunk_watchers: Arc<DashMap<Vector2<i32>, usize>>,
-    chunk_reader: Arc<dyn ChunkReader>,
+    /// Where the chunks are read from and written to, `None` if the world is not saved
+    chunk_storage: Option<Arc<dyn ChunkStorage>>,
     world_gen: Arc<dyn WorldGenerator>,
+    structure_locator: StructureLocator,
+    /// Number of chunks which are currently read or generated
+    pending_chunks: Arc<AtomicUsize>,
+    chunk_load_listener: Option<ChunkLoadListener>,
+    /// Chunks no player watches stay loaded until the loaded chunks take more memory than the
+    /// budget, in bytes
+    chunk_cache: Arc<ChunkCache>,
+    chunk_memory_budget: usize,
+    /// The evicted chunks which are being written, they are unloaded afterwards
+    evicting_chunks: Arc<EvictingChunks>,
+    /// Lights the chunks which were read without light or whose blocks changed how light spreads
+    light_engine: LightEngine,
+}
+
+/// Counts the evicted chunks which are being written, saving the level waits for them
+#[derive(Default)]
+struct EvictingChunks {
+    count: parking_lot::Mutex<usize>,
+    written: parking_lot::Condvar,
+}
+
+impl EvictingChunks {
+    fn count(&self) -> usize {
+        *self.count.lock()
+    }
+
+    fn start(&self) {
+        *self.count.lock() += 1;
+    }
+
+    /// Returns whether it was the last chunk being written
+    fn finish(&self) -> bool {
+        let mut count = self.count.lock();
+        *count -= 1;
+        if *count == 0 {
+            self.written.notify_all();
+        }
+        *count == 0
+    }
+
+    /// Blocks until no chunk is being written
+    fn wait(&self) {
+        let mut count = self.count.lock();
+        self.written.wait_while(&mut count, |count| *count > 0);
+    }
 }
 
 #[derive(Clone)]
 pub struct SaveFile {
     pub root_folder: PathBuf,
-    pub region_folder: PathBuf,
 }
 
-fn get_or_create_seed() -> Seed {
+const LEVEL_DAT: &str = "level.dat";
+
+/// `data/command_storage_<namespace>.dat`, which has the storages of `/data` in a namespace
+#[derive(Serialize, Deserialize, Default)]
+struct CommandStorageFile {
+    data: CommandStorageContents,
+    #[serde(rename = "DataVersion")]
+    data_version: i32,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct CommandStorageContents {
+    /// The compound of every storage by its path
+    contents: HashMap<String, HashMap<String, fastnbt::Value>>,
+}
+
+fn get_or_create_seed(config: &WorldConfig) -> Seed {
     // TODO: if there is a seed in the config (!= 0) use it. Otherwise make a random one
-    Seed::from(BASIC_CONFIG.seed.as_str())
+    Seed::from(config.seed.as_str())
+}
+
+/// Reads a gzipped NBT file, or `None` if it doesn't exist or is broken
+fn read_nbt_file<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    let file = fs::File::open(path).ok()?;
+    let mut bytes = Vec::new();
+    if let Err(err) = GzDecoder::new(file).read_to_end(&mut bytes) {
+        log::error!("Couldn't read {}: {err}", path.display());
+        return None;
+    }
+    fastnbt::from_bytes(&bytes)
+        .inspect_err(|err| log::error!("Couldn't parse {}: {err}", path.display()))
+        .ok()
+}
+
+/// Reads a gzipped NBT file, or the default if it doesn't exist or is broken
+fn read_nbt<T: DeserializeOwned + Default>(path: &Path) -> T {
+    read_nbt_file(path).unwrap_or_default()
+}
+
+/// Writes a gzipped NBT file, creating its folder if needed
+fn write_nbt<T: Serialize>(path: &Path, data: &T) -> Result<(), String> {
+    let bytes = fastnbt::to_bytes(data).map_err(|err| err.to_string())?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    let mut encoder = GzEncoder::new(
+        fs::File::create(path).map_err(|err| err.to_string())?,
+        Compression::default(),
+    );
+    encoder.write_all(&bytes).map_err(|err| err.to_string())?;
+    encoder.finish().map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+/// Writes a gzipped NBT file like vanilla does, next to the old file first so a crash while
+/// writing loses nothing. The old file is kept with `_old` appended to its extension
+fn write_nbt_replacing<T: Serialize>(path: &Path, data: &T) -> Result<(), String> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default();
+    let new_path = path.with_extension(format!("{extension}_new"));
+    write_nbt(&new_path, data)?;
+    if path.exists() {
+        fs::rename(path, path.with_extension(format!("{extension}_old")))
+            .map_err(|err| err.to_string())?;
+    }
+    fs::rename(&new_path, path).map_err(|err| err.to_string())
 }
 
 impl Level {
-    pub fn from_root_folder(root_folder: PathBuf) -> Self {
-        // If we are using an already existing world we want to read the seed from the level.dat, If not we want to check if there is a seed in the config, if not lets create a random one
-        if root_folder.exists() {
-            let region_folder = root_folder.join("region");
-            assert!(
-                region_folder.exists(),
-                "World region folder does not exist, despite there being a root folder."
-            );
-            // TODO: read seed from level.dat
-            let seed = Seed(0);
-            let world_gen = get_world_gen(seed).into(); // TODO Read Seed from config.
-
-            Self {
-                seed,
-                world_gen,
-                save_file: Some(SaveFile {
-                    root_folder,
-                    region_folder,
-                }),
-                chunk_reader: Arc::new(AnvilChunkReader::new()),
-                loaded_chunks: Arc::new(DashMap::new()),
-                chunk_watchers: Arc::new(DashMap::new()),
-            }
-        } else {
-            let seed = get_or_create_seed();
-            let world_gen = get_world_gen(seed).into(); // TODO Read Seed from config.
-            Self {
-                seed,
-                world_gen,
-                save_file: None,
-                chunk_reader: Arc::new(AnvilChunkReader::new()),
-                loaded_chunks: Arc::new(DashMap::new()),
-                chunk_watchers: Arc::new(DashMap::new()),
+    /// The name of the world's folder, or `world` if it is not saved
+    #[must_use]
+    pub fn name(&self) -> &str {
+        self.save_file
+            .as_ref()
+            .and_then(|save_file| save_file.root_folder.file_name())
+            .and_then(|name| name.to_str())
+            .unwrap_or("world")
+    }
+
+    /// The world's folder, `None` if it is not saved
+    #[must_use]
+    pub fn root_folder(&self) -> Option<&Path> {
+        self.save_file
+            .as_ref()
+            .map(|save_file| save_file.root_folder.as_path())
+    }
+
+    /// Opens the world in the folder, or creates it there if there is none yet. New worlds take
+    /// their seed from the config, existing ones keep theirs.
+    pub fn from_root_folder(root_folder: PathBuf, config: &WorldConfig) -> Self {
+        let level_data = read_nbt_file::<LevelFile>(&root_folder.join(LEVEL_DAT))
+            .map(|file| file.data)
+            .unwrap_or_else(|| {
+                let name = root_folder
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .unwrap_or("world");
+                LevelData::new(name, get_or_create_seed(config).0, config.generator)
+            });
+        let seed = level_data
+            .seed()
+            .map_or_else(|| get_or_create_seed(config), Seed);
+        let world_gen = get_world_gen(seed, config.generator).into();
+        let chunk_storage = open_storage(config.storage, &root_folder);
+        let loaded_chunks = Arc::new(DashMap::new());
+
+        Self {
+            seed,
+            world_gen,
+            save_file: Some(SaveFile { root_folder }),
+            level_data: parking_lot::Mutex::new(level_data),
+            chunk_storage: Some(chunk_storage),
+            loaded_chunks: loaded_chunks.clone(),
+            chunk_watchers: Arc::new(DashMap::new()),
+            structure_locator: StructureLocator::new(seed.0),
+            pending_chunks: Arc::new(AtomicUsize::new(0)),
+            chunk_load_listener: None,
+            chunk_cache: Arc::new(ChunkCache::default()),
+            chunk_memory_budget: 0,
+            evicting_chunks: Arc::default(),
+            light_engine: LightEngine::new(loaded_chunks),
+        }
+    }
+
+    /// What is saved about the world in `level.dat`, as it was read or last saved
+    #[must_use]
+    pub fn level_data(&self) -> LevelData {
+        self.level_data.lock().clone()
+    }
+
+    /// Saves the world's `level.dat`, the previous file is kept as `level.dat_old`
+    pub fn write_level_data(&self, mut data: LevelData) {
+        data.set_written();
+        if let Some(save_file) = &self.save_file {
+            let path = save_file.root_folder.join(LEVEL_DAT);
+            let file = LevelFile { data: data.clone() };
+            if let Err(err) = write_nbt_replacing(&path, &file) {
+                log::error!("Couldn't write {}: {err}", path.display());
             }
         }
+        *self.level_data.lock() = data;
+    }
+
+    pub fn set_chunk_load_listener(&mut self, listener: ChunkLoadListener) {
+        self.chunk_load_listener = Some(listener);
+    }
+
+    /// Called with every chunk once it was lit, e.g. to send its new light to the players
+    pub fn set_relight_listener(&mut self, listener: RelightListener) {
+        self.light_engine.set_listener(listener);
+    }
+
+    #[must_use]
+    pub fn light_engine(&self) -> &LightEngine {
+        &self.light_engine
+    }
+
+    /// How much memory the loaded chunks may take before chunks no player watches are unloaded,
+    /// in bytes. 0 unloads them right away
+    pub fn set_chunk_memory_budget(&mut self, budget: usize) {
+        self.chunk_memory_budget = budget;
     }
 
     pub fn get_block() {}
 
+    pub fn get_biome(&self, x: i32, z: i32) -> Biome {
+        self.world_gen.get_biome(XZBlockCoordinates { x, z })
+    }
+
+    /// Searches for the closest `biome` in square rings of `step` blocks around `origin`, up to `radius` blocks away.
+    pub fn locate_biome(
+        &self,
+        origin: Vector2<i32>,
+        biome: Biome,
+        radius: i32,
+        step: i32,
+    ) -> Option<Vector2<i32>> {
+        for ring in 0..=radius / step {
+            let mut closest: Option<(Vector2<i32>, i64)> = None;
+            for dx in -ring..=ring {
+                for dz in -ring..=ring {
+                    if dx.abs() != ring && dz.abs() != ring {
+                        continue;
+                    }
+                    let position = Vector2::new(origin.x + dx * step, origin.z + dz * step);
+                    if self.get_biome(position.x, position.z) != biome {
+                        continue;
+                    }
+                    let distance = i64::from(dx * step).pow(2) + i64::from(dz * step).pow(2);
+                    if closest.is_none_or(|(_, closest)| distance < closest) {
+                        closest = Some((position, distance));
+                    }
+                }
+            }
+            if let Some((position, _)) = closest {
+                return Some(position);
+            }
+        }
+        None
+    }
+
+    /// Returns the block position of the nearest `structure` start within `radius` chunks.
+    pub fn locate_structure(
+        &self,
+        origin: Vector2<i32>,
+        structure: &Structure,
+        radius: i32,
+    ) -> Option<Vector2<i32>> {
+        self.structure_locator.locate(structure, origin, radius)
+    }
+
     pub fn loaded_chunk_count(&self) -> usize {
         self.loaded_chunks.len()
     }
 
+    /// Returns the chunk if it is loaded, without reading or generating it.
+    pub fn get_loaded_chunk(&self, chunk: Vector2<i32>) -> Option<Arc<RwLock<ChunkData>>> {
+        self.loaded_chunks
+            .get(&chunk)
+            .map(|chunk| chunk.value().clone())
+    }
+
+    /// Where custom data of plugins is saved which is not stored with chunks, like the data of the
+    /// world or players, e.g. `world/persistent_data/players/<uuid>.dat`
+    fn persistent_data_path(&self, name: &str) -> Option<PathBuf> {
+        let save_file = self.save_file.as_ref()?;
+        Some(
+            save_file
+                .root_folder
+                .join("persistent_data")
+                .join(format!("{name}.dat")),
+        )
+    }
+
+    /// Reads custom data of plugins, which is empty if it was never written or the world is not
+    /// saved.
+    #[must_use]
+    pub fn read_persistent_data(&self, name: &str) -> PersistentDataContainer {
+        self.persistent_data_path(name)
+            .map(|path| read_nbt(&path))
+            .unwrap_or_default()
+    }
+
+    /// Writes custom data of plugins, an empty container removes the file. Does nothing if the
+    /// world is not saved.
+    pub fn write_persistent_data(&self, name: &str, data: &PersistentDataContainer) {
+        let Some(path) = self.persistent_data_path(name) else {
+            return;
+        };
+        if data.is_empty() {
+            if path.exists() {
+                if let Err(err) = fs::remove_file(&path) {
+                    log::error!("Couldn't remove {}: {err}", path.display());
+                }
+            }
+            return;
+        }
+        if let Err(err) = write_nbt(&path, data) {
+            log::error!("Couldn't write {}: {err}", path.display());
+        }
+    }
+
+    /// Where vanilla saves a player, e.g. `world/playerdata/<uuid>.dat`
+    fn player_data_path(&self, uuid: &Uuid) -> Option<PathBuf> {
+        let save_file = self.save_file.as_ref()?;
+        Some(
+            save_file
+                .root_folder
+                .join("playerdata")
+                .join(format!("{uuid}.dat")),
+        )
+    }
+
+    /// Reads what was saved about a player, which is empty if the player never joined or the world
+    /// is not saved.
+    #[must_use]
+    pub fn read_player_data(&self, uuid: &Uuid) -> PlayerData {
+        self.player_data_path(uuid)
+            .map(|path| read_nbt(&path))
+            .unwrap_or_default()
+    }
+
+    /// Saves a player like vanilla does, the previous file is kept as `<uuid>.dat_old`. Does
+    /// nothing if the world is not saved.
+    pub fn write_player_data(&self, uuid: &Uuid, data: &PlayerData) {
+        let Some(path) = self.player_data_path(uuid) else {
+            return;
+        };
+        if let Err(err) = write_nbt_replacing(&path, data) {
+            log::error!("Couldn't write {}: {err}", path.display());
+        }
+    }
+
+    /// Where vanilla saves the storages of `/data` in the namespace
+    fn command_storage_path(&self, namespace: &str) -> Option<PathBuf> {
+        let save_file = self.save_file.as_ref()?;
+        Some(
+            save_file
+                .root_folder
+                .join("data")
+                .join(format!("command_storage_{namespace}.dat")),
+        )
+    }
+
+    /// Reads the storages of the namespace by their path, which are empty if they were never
+    /// written or the world is not saved.
+    #[must_use]
+    pub fn read_command_storage(
+        &self,
+        namespace: &str,
+    ) -> HashMap<String, HashMap<String, fastnbt::Value>> {
+        self.command_storage_path(namespace)
+            .map(|path| read_nbt::<CommandStorageFile>(&path).data.contents)
+            .unwrap_or_default()
+    }
+
+    /// Saves the storages of the namespace. Does nothing if the world is not saved.
+    pub fn write_command_storage(
+        &self,
+        namespace: &str,
+        contents: HashMap<String, HashMap<String, fastnbt::Value>>,
+    ) {
+        let Some(path) = self.command_storage_path(namespace) else {
+            return;
+        };
+        let file = CommandStorageFile {
+            data: CommandStorageContents { contents },
+            data_version: DATA_VERSION,
+        };
+        if let Err(err) = write_nbt(&path, &file) {
+            log::error!("Couldn't write {}: {err}", path.display());
+        }
+    }
+
+    /// Returns how many chunks are waiting to be read or generated.
+    pub fn pending_chunk_count(&self) -> usize {
+        self.pending_chunks.load(Ordering::Relaxed)
+    }
+
+    /// The approximate memory the blocks of the loaded chunks take, in bytes
+    #[must_use]
+    pub fn loaded_chunk_memory(&self) -> usize {
+        self.loaded_chunks.len() * ChunkBlocks::MEMORY
+    }
+
+    #[must_use]
+    pub fn chunk_cache_stats(&self) -> ChunkCacheStats {
+        self.chunk_cache.stats()
+    }
+
+    /// Number of loaded chunks no player watches
+    #[must_use]
+    pub fn idle_chunk_count(&self) -> usize {
+        self.chunk_cache.idle_count()
+    }
+
     pub fn list_cached(&self) {
         for entry in self.loaded_chunks.iter() {
             log::debug!("In map: {:?}", entry.key());
@@ -99,7 +479,7 @@ impl Level {
     }
 
     /// Marks chunks as "watched" by a unique player. When no players are watching a chunk,
-    /// it is removed from memory. Should only be called on chunks the player was not watching
+    /// it may be removed from memory. Should only be called on chunks the player was not watching
     /// before
     pub fn mark_chunks_as_newly_watched(&self, chunks: &[Vector2<i32>]) {
         chunks.par_iter().for_each(|chunk| {
@@ -107,7 +487,9 @@ impl Level {
         });
     }
 
-    pub fn mark_chunk_as_newly_watched(&self, chunk: Vector2<i32>) {
+    /// Returns whether nobody watched the chunk before
+    pub fn mark_chunk_as_newly_watched(&self, chunk: Vector2<i32>) -> bool {
+        self.chunk_cache.retain(chunk);
         match self.chunk_watchers.entry(chunk) {
             Entry::Occupied(mut occupied) => {
                 let value = occupied.get_mut();
@@ -117,15 +499,17 @@ impl Level {
                 } else {
                     log::error!("Watching overflow on chunk {:?}", chunk);
                 }
+                false
             }
             Entry::Vacant(vacant) => {
                 vacant.insert(1);
+                true
             }
         }
     }
 
     /// Marks chunks no longer "watched" by a unique player. When no players are watching a chunk,
-    /// it is removed from memory. Should only be called on chunks the player was watching before
+    /// it may be removed from memory. Should only be called on chunks the player was watching before
     pub fn mark_chunks_as_not_watched(&self, chunks: &[Vector2<i32>]) -> Vec<Vector2<i32>> {
         chunks
             .par_iter()
@@ -171,41 +555,120 @@ impl Level {
         self.chunk_watchers.get(chunk).is_none()
     }
 
-    pub fn clean_chunks(&self, chunks: &[Vector2<i32>]) {
-        chunks.par_iter().for_each(|chunk_pos| {
-            //log::debug!("Unloading {:?}", chunk_pos);
-            if let Some(data) = self.loaded_chunks.remove(chunk_pos) {
-                self.write_chunk(data);
-            };
-        });
+    /// Hands chunks no player watches anymore to the chunk cache, then unloads the least recently
+    /// used ones if the loaded chunks take more memory than the budget
+    pub fn clean_chunks(self: &Arc<Self>, chunks: &[Vector2<i32>]) {
+        for chunk in chunks {
+            if self.loaded_chunks.contains_key(chunk) {
+                self.chunk_cache.release(*chunk);
+            }
+        }
+        self.evict_chunks();
+    }
+
+    pub fn clean_memory(self: &Arc<Self>, chunks_to_check: &[Vector2<i32>]) {
+        let unwatched = chunks_to_check
+            .par_iter()
+            .filter(|chunk| self.should_pop_chunk(chunk))
+            .copied()
+            .collect::<Vec<_>>();
+        self.clean_chunks(&unwatched);
+        self.loaded_chunks.shrink_to_fit();
+        self.chunk_watchers.shrink_to_fit();
     }
 
-    pub fn clean_memory(&self, chunks_to_check: &[Vector2<i32>]) {
-        chunks_to_check.par_iter().for_each(|chunk| {
-            if let Some(entry) = self.chunk_watchers.get(chunk) {
-                if entry.value().is_zero() {
-                    self.chunk_watchers.remove(chunk);
+    /// Unloads the least recently used chunks no player watches until the loaded chunks fit into
+    /// the memory budget. They are written on the rayon threads and stay loaded until then, so
+    /// the caller doesn't wait for them
+    pub fn evict_chunks(self: &Arc<Self>) {
+        let evicting = self.evicting_chunks.count();
+        let evicted = self.chunk_cache.evict(
+            self.loaded_chunks.len().saturating_sub(evicting),
+            ChunkBlocks::MEMORY,
+            self.chunk_memory_budget,
+        );
+        for chunk in evicted {
+            let Some(data) = self.get_loaded_chunk(chunk) else {
+                continue;
+            };
+            self.evicting_chunks.start();
+            let level = self.clone();
+            rayon::spawn(move || {
+                level.write_chunk((chunk, data.clone()));
+                // a player may have started watching or changed it while it was written
+                let unloaded = level
+                    .loaded_chunks
+                    .remove_if(&chunk, |_, loaded| {
+                        Arc::ptr_eq(loaded, &data)
+                            && !level.chunk_watchers.contains_key(&chunk)
+                            && data
+                                .try_read()
+                                .is_ok_and(|data| !data.dirty.load(Ordering::Relaxed))
+                    })
+                    .is_some();
+                if unloaded {
+                    level.chunk_cache.record_eviction();
+                } else if !level.chunk_watchers.contains_key(&chunk) {
+                    level.chunk_cache.release(chunk);
+                }
+                // the last written chunk of the evicted ones syncs them all
+                if level.evicting_chunks.finish() {
+                    level.sync_chunks();
                 }
+            });
+        }
+    }
+
+    /// Writes the chunk to the storage if it changed since it was last written, must not be
+    /// called from a tokio runtime thread. The chunk is only locked while a snapshot of it is
+    /// taken
+    pub fn write_chunk(&self, (position, chunk): (Vector2<i32>, Arc<RwLock<ChunkData>>)) {
+        let Some(chunk_storage) = &self.chunk_storage else {
+            return;
+        };
+        let snapshot = {
+            let chunk = chunk.blocking_read();
+            if !chunk.dirty.swap(false, Ordering::Relaxed) {
+                return;
             }
+            chunk.snapshot()
+        };
+        if let Err(err) = chunk_storage.write_chunk(&snapshot, &position) {
+            chunk.blocking_read().mark_dirty();
+            log::error!("Failed to write chunk {position:?}: {err}");
+        }
+    }
 
-            if self.chunk_watchers.get(chunk).is_none() {
-                self.loaded_chunks.remove(chunk);
+    /// Makes sure the written chunks survive a crash of the system, called after writing a batch
+    /// of chunks with [`Self::write_chunk`]
+    pub fn sync_chunks(&self) {
+        if let Some(chunk_storage) = &self.chunk_storage {
+            if let Err(err) = chunk_storage.sync() {
+                log::error!("Failed to sync the written chunks: {err}");
             }
-        });
-        self.loaded_chunks.shrink_to_fit();
-        self.chunk_watchers.shrink_to_fit();
+        }
     }
 
-    pub fn write_chunk(&self, _chunk_to_write: (Vector2<i32>, Arc<RwLock<ChunkData>>)) {
-        //TODO
+    /// Writes every changed loaded chunk, must not be called from a tokio runtime thread
+    pub fn save_chunks(&self) {
+        let chunks = self
+            .loaded_chunks
+            .iter()
+            .map(|entry| (*entry.key(), entry.value().clone()))
+            .collect::<Vec<_>>();
+        chunks
+            .into_par_iter()
+            .for_each(|chunk| self.write_chunk(chunk));
+        // evicted chunks which were still being written count as saved already
+        self.evicting_chunks.wait();
+        self.sync_chunks();
     }
 
     fn load_chunk_from_save(
-        chunk_reader: Arc<dyn ChunkReader>,
-        save_file: SaveFile,
+        chunk_storage: &dyn ChunkStorage,
         chunk_pos: Vector2<i32>,
     ) -> Result<Option<Arc<RwLock<ChunkData>>>, ChunkReadingError> {
-        match chunk_reader.read_chunk(&save_file, &chunk_pos) {
+        match chunk_storage.read_chunk(&chunk_pos) {
             Ok(data) => Ok(Some(Arc::new(RwLock::new(data)))),
             Err(
                 ChunkReadingError::ChunkNotExist
@@ -232,37 +695,43 @@ impl Level {
             .map(|at| {
                 let channel = channel.clone();
                 let loaded_chunks = self.loaded_chunks.clone();
-                let chunk_reader = self.chunk_reader.clone();
-                let save_file = self.save_file.clone();
+                let chunk_storage = self.chunk_storage.clone();
                 let world_gen = self.world_gen.clone();
                 let chunk_pos = *at;
+                let pending_chunks = self.pending_chunks.clone();
+                let chunk_load_listener = self.chunk_load_listener.clone();
+                let chunk_cache = self.chunk_cache.clone();
+                let chunk_watchers = self.chunk_watchers.clone();
+                let light_engine = self.light_engine.clone();
+                pending_chunks.fetch_add(1, Ordering::Relaxed);
 
                 let join_handle = tokio::spawn(async move {
                     let chunk = loaded_chunks
                         .get(&chunk_pos)
-                        .map(|entry| entry.value().clone())
+                        .map(|entry| {
+                            chunk_cache.hit(chunk_pos);
+                            entry.value().clone()
+                        })
                         .unwrap_or_else(|| {
-                            let loaded_chunk = save_file
-                                .and_then(|save_file| {
-                                    match Self::load_chunk_from_save(
-                                        chunk_reader,
-                                        save_file,
-                                        chunk_pos,
-                                    ) {
-                                        Ok(chunk) => chunk,
-                                        Err(err) => {
-                                            log::error!(
-                                                "Failed to read chunk (regenerating) {:?}: {:?}",
-                                                chunk_pos,
-                                                err
-                                            );
-                                            None
-                                        }
+                            chunk_cache.miss();
+                            let start = Instant::now();
+                            let saved_chunk = chunk_storage.and_then(|chunk_storage| {
+                                match Self::load_chunk_from_save(&*chunk_storage, chunk_pos) {
+                                    Ok(chunk) => chunk,
+                                    Err(err) => {
+                                        log::error!(
+                                            "Failed to read chunk (regenerating) {:?}: {:?}",
+                                            chunk_pos,
+                                            err
+                                        );
+                                        None
                                     }
-                                })
-                                .unwrap_or_else(|| {
-                                    Arc::new(RwLock::new(world_gen.generate_chunk(chunk_pos)))
-                                });
+                                }
+                            });
+                            let generated = saved_chunk.is_none();
+                            let loaded_chunk = saved_chunk.unwrap_or_else(|| {
+                                Arc::new(RwLock::new(world_gen.generate_chunk(chunk_pos)))
+                            });
 
                             if let Some(data) = loaded_chunks.get(&chunk_pos) {
                                 // Another thread populated in between the previous check and now
@@ -271,9 +740,26 @@ impl Level {
                                 data.value().clone()
                             } else {
                                 loaded_chunks.insert(chunk_pos, loaded_chunk.clone());
+                                // chunks loaded without a player, e.g. to look at a block, can be
+                                // evicted right away
+                                if !chunk_watchers.contains_key(&chunk_pos) {
+                                    chunk_cache.release(chunk_pos);
+                                }
+                                // generated chunks and chunks read without valid light
+                                if loaded_chunk
+                                    .try_read()
+                                    .is_ok_and(|chunk| chunk.light.is_none())
+                                {
+                                    light_engine.queue(chunk_pos, loaded_chunk.clone());
+                                }
+                                light_engine.chunk_loaded(chunk_pos, generated);
+                                if let Some(listener) = chunk_load_listener {
+                                    listener(chunk_pos, generated, start.elapsed());
+                                }
                                 loaded_chunk
                             }
                         });
+                    pending_chunks.fetch_sub(1, Ordering::Relaxed);
 
                     let _ = channel
                         .send(chunk)
@@ -0,0 +1,471 @@
+//! What vanilla saves about a world in `level.dat`, like its seed, spawn and game rules.
+
+use std::{
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use fastnbt::Value;
+use pumpkin_config::GeneratorKind;
+use pumpkin_core::{math::vector3::Vector3, Difficulty};
+use serde::{Deserialize, Serialize};
+
+use crate::{game_rules::GameRules, DATA_VERSION};
+
+/// The name of the version Pumpkin supports, shown by vanilla in the world list
+const VERSION_NAME: &str = "1.21.3";
+
+/// The file `level.dat` is, the world's data is in one compound
+#[derive(Serialize, Deserialize, Default)]
+pub(crate) struct LevelFile {
+    #[serde(rename = "Data")]
+    pub data: LevelData,
+}
+
+/// The world border as saved, sizes are diameters
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BorderData {
+    pub center_x: f64,
+    pub center_z: f64,
+    pub size: f64,
+    /// The size the border moves to
+    pub lerp_target: f64,
+    /// Milliseconds until the border reaches its target
+    pub lerp_time: i64,
+    /// How far players can be outside the border before they take damage
+    pub safe_zone: f64,
+    pub damage_per_block: f64,
+    pub warning_blocks: f64,
+    pub warning_time: f64,
+}
+
+/// The timers of the mobs which come to the world by themselves, in ticks until they try again.
+/// Vanilla only saves the wandering trader's, the others are saved as `PumpkinSpawners`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SpawnerTimers {
+    pub patrol: i32,
+    pub cat: i32,
+    pub phantom: i32,
+    pub wandering_trader: i32,
+    /// The chance in percent that the wandering trader comes next time
+    pub wandering_trader_chance: i32,
+}
+
+/// The `Data` compound of `level.dat`. Like [`PlayerData`](crate::player_data::PlayerData) only
+/// the fields Pumpkin knows are read and written, everything else stays as it was read.
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(transparent)]
+pub struct LevelData(pub(crate) HashMap<String, Value>);
+
+impl LevelData {
+    /// The data of a world which is created right now
+    #[must_use]
+    pub fn new(name: &str, seed: i64, generator: GeneratorKind) -> Self {
+        let mut data = Self::default();
+        data.set("LevelName", Value::String(name.to_string()));
+        data.set("initialized", Value::Byte(1));
+        data.set("allowCommands", Value::Byte(1));
+        data.set("GameType", Value::Int(0));
+        data.set(
+            "WorldGenSettings",
+            compound([
+                ("seed", Value::Long(seed)),
+                ("generate_features", Value::Byte(1)),
+                ("bonus_chest", Value::Byte(0)),
+                ("dimensions", dimensions(seed, generator)),
+            ]),
+        );
+        data.set_spawn(Vector3::new(10, 120, 10), 0.0);
+        data.set_time(0, 0);
+        data
+    }
+
+    fn long(&self, key: &str) -> Option<i64> {
+        match self.0.get(key)? {
+            Value::Long(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn int(&self, key: &str) -> Option<i32> {
+        match self.0.get(key)? {
+            Value::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn double(&self, key: &str) -> Option<f64> {
+        match self.0.get(key)? {
+            Value::Double(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn set(&mut self, key: &str, value: Value) {
+        self.0.insert(key.to_string(), value);
+    }
+
+    /// Marks the data as written by Pumpkin's Minecraft version right now
+    pub fn set_written(&mut self) {
+        self.set("DataVersion", Value::Int(DATA_VERSION));
+        self.set(
+            "Version",
+            compound([
+                ("Id", Value::Int(DATA_VERSION)),
+                ("Name", Value::String(VERSION_NAME.to_string())),
+                ("Series", Value::String("main".to_string())),
+                ("Snapshot", Value::Byte(0)),
+            ]),
+        );
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |now| now.as_millis() as i64);
+        self.set("LastPlayed", Value::Long(now));
+    }
+
+    /// The seed of the world generation, worlds from before 1.16 saved it elsewhere
+    #[must_use]
+    pub fn seed(&self) -> Option<i64> {
+        if let Some(Value::Compound(settings)) = self.0.get("WorldGenSettings") {
+            if let Some(Value::Long(seed)) = settings.get("seed") {
+                return Some(*seed);
+            }
+        }
+        self.long("RandomSeed")
+    }
+
+    /// The block players spawn at and the yaw they look in
+    #[must_use]
+    pub fn spawn(&self) -> Option<(Vector3<i32>, f32)> {
+        let coordinate = |key| match self.0.get(key)? {
+            Value::Int(value) => Some(*value),
+            _ => None,
+        };
+        let position = Vector3::new(
+            coordinate("SpawnX")?,
+            coordinate("SpawnY")?,
+            coordinate("SpawnZ")?,
+        );
+        let angle = match self.0.get("SpawnAngle") {
+            Some(Value::Float(angle)) => *angle,
+            _ => 0.0,
+        };
+        Some((position, angle))
+    }
+
+    pub fn set_spawn(&mut self, position: Vector3<i32>, angle: f32) {
+        self.set("SpawnX", Value::Int(position.x));
+        self.set("SpawnY", Value::Int(position.y));
+        self.set("SpawnZ", Value::Int(position.z));
+        self.set("SpawnAngle", Value::Float(angle));
+    }
+
+    /// The ticks the world ran for and the time of day, which doesn't pass with
+    /// `doDaylightCycle` off
+    #[must_use]
+    pub fn time(&self) -> (i64, i64) {
+        (
+            self.long("Time").unwrap_or_default(),
+            self.long("DayTime").unwrap_or_default(),
+        )
+    }
+
+    pub fn set_time(&mut self, time: i64, day_time: i64) {
+        self.set("Time", Value::Long(time));
+        self.set("DayTime", Value::Long(day_time));
+    }
+
+    /// The game rules, `None` if the world has none saved yet
+    #[must_use]
+    pub fn game_rules(&self) -> Option<GameRules> {
+        let Value::Compound(rules) = self.0.get("GameRules")? else {
+            return None;
+        };
+        Some(GameRules::from_strings(rules.iter().filter_map(
+            |(name, value)| match value {
+                Value::String(value) => Some((name.clone(), value.clone())),
+                _ => None,
+            },
+        )))
+    }
+
+    pub fn set_game_rules(&mut self, rules: &GameRules) {
+        let rules = rules
+            .to_strings()
+            .into_iter()
+            .map(|(name, value)| (name, Value::String(value)))
+            .collect();
+        self.set("GameRules", Value::Compound(rules));
+    }
+
+    #[must_use]
+    pub fn difficulty(&self) -> Option<Difficulty> {
+        match self.0.get("Difficulty")? {
+            Value::Byte(0) => Some(Difficulty::Peaceful),
+            Value::Byte(1) => Some(Difficulty::Easy),
+            Value::Byte(2) => Some(Difficulty::Normal),
+            Value::Byte(3) => Some(Difficulty::Hard),
+            _ => None,
+        }
+    }
+
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.set("Difficulty", Value::Byte(difficulty as i8));
+    }
+
+    /// The world border, `None` if the world has none saved yet
+    #[must_use]
+    pub fn border(&self) -> Option<BorderData> {
+        let size = self.double("BorderSize")?;
+        Some(BorderData {
+            center_x: self.double("BorderCenterX").unwrap_or_default(),
+            center_z: self.double("BorderCenterZ").unwrap_or_default(),
+            size,
+            lerp_target: self.double("BorderSizeLerpTarget").unwrap_or(size),
+            lerp_time: self.long("BorderSizeLerpTime").unwrap_or_default(),
+            safe_zone: self.double("BorderSafeZone").unwrap_or(5.0),
+            damage_per_block: self.double("BorderDamagePerBlock").unwrap_or(0.2),
+            warning_blocks: self.double("BorderWarningBlocks").unwrap_or(5.0),
+            warning_time: self.double("BorderWarningTime").unwrap_or(15.0),
+        })
+    }
+
+    pub fn set_border(&mut self, border: &BorderData) {
+        self.set("BorderCenterX", Value::Double(border.center_x));
+        self.set("BorderCenterZ", Value::Double(border.center_z));
+        self.set("BorderSize", Value::Double(border.size));
+        self.set("BorderSizeLerpTarget", Value::Double(border.lerp_target));
+        self.set("BorderSizeLerpTime", Value::Long(border.lerp_time));
+        self.set("BorderSafeZone", Value::Double(border.safe_zone));
+        self.set(
+            "BorderDamagePerBlock",
+            Value::Double(border.damage_per_block),
+        );
+        self.set("BorderWarningBlocks", Value::Double(border.warning_blocks));
+        self.set("BorderWarningTime", Value::Double(border.warning_time));
+    }
+
+    /// The timers of the spawners, `None` if the world has none saved yet
+    #[must_use]
+    pub fn spawner_timers(&self) -> Option<SpawnerTimers> {
+        let wandering_trader = self.int("WanderingTraderSpawnDelay")?;
+        let timer = |key: &str| match self.0.get("PumpkinSpawners") {
+            Some(Value::Compound(timers)) => match timers.get(key) {
+                Some(Value::Int(timer)) => Some(*timer),
+                _ => None,
+            },
+            _ => None,
+        };
+        Some(SpawnerTimers {
+            patrol: timer("Patrol").unwrap_or_default(),
+            cat: timer("Cat").unwrap_or_default(),
+            phantom: timer("Phantom").unwrap_or_default(),
+            wandering_trader,
+            wandering_trader_chance: self.int("WanderingTraderSpawnChance").unwrap_or_default(),
+        })
+    }
+
+    pub fn set_spawner_timers(&mut self, timers: &SpawnerTimers) {
+        self.set(
+            "WanderingTraderSpawnDelay",
+            Value::Int(timers.wandering_trader),
+        );
+        self.set(
+            "WanderingTraderSpawnChance",
+            Value::Int(timers.wandering_trader_chance),
+        );
+        self.set(
+            "PumpkinSpawners",
+            compound([
+                ("Patrol", Value::Int(timers.patrol)),
+                ("Cat", Value::Int(timers.cat)),
+                ("Phantom", Value::Int(timers.phantom)),
+            ]),
+        );
+    }
+
+    /// The ids of the enabled datapacks in the order they load and the disabled ones, `None`
+    /// if the world has none saved yet
+    #[must_use]
+    pub fn datapacks(&self) -> Option<(Vec<String>, Vec<String>)> {
+        let Value::Compound(packs) = self.0.get("DataPacks")? else {
+            return None;
+        };
+        let ids = |key: &str| match packs.get(key) {
+            Some(Value::List(ids)) => ids
+                .iter()
+                .filter_map(|id| match id {
+                    Value::String(id) => Some(id.clone()),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        Some((ids("Enabled"), ids("Disabled")))
+    }
+
+    pub fn set_datapacks(&mut self, enabled: &[String], disabled: &[String]) {
+        let ids = |ids: &[String]| Value::List(ids.iter().cloned().map(Value::String).collect());
+        self.set(
+            "DataPacks",
+            compound([("Enabled", ids(enabled)), ("Disabled", ids(disabled))]),
+        );
+    }
+}
+
+fn compound<const N: usize>(entries: [(&str, Value); N]) -> Value {
+    Value::Compound(
+        entries
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect(),
+    )
+}
+
+/// How vanilla would generate the dimensions, so it continues the world similar to Pumpkin
+fn dimensions(seed: i64, generator: GeneratorKind) -> Value {
+    let noise = |dimension: &str, biome_source: Value| {
+        compound([
+            ("type", Value::String(format!("minecraft:{dimension}"))),
+            (
+                "generator",
+                compound([
+                    ("type", Value::String("minecraft:noise".to_string())),
+                    ("seed", Value::Long(seed)),
+                    ("settings", Value::String(format!("minecraft:{dimension}"))),
+                    ("biome_source", biome_source),
+                ]),
+            ),
+        ])
+    };
+    let multi_noise = |preset: &str| {
+        compound([
+            ("type", Value::String("minecraft:multi_noise".to_string())),
+            ("preset", Value::String(format!("minecraft:{preset}"))),
+        ])
+    };
+    let overworld = match generator {
+        GeneratorKind::Plains => noise("overworld", multi_noise("overworld")),
+        // The classic flat preset, which is what the superflat generator builds
+        GeneratorKind::Superflat => {
+            let layer = |block: &str, height| {
+                compound([
+                    ("block", Value::String(format!("minecraft:{block}"))),
+                    ("height", Value::Int(height)),
+                ])
+            };
+            compound([
+                ("type", Value::String("minecraft:overworld".to_string())),
+                (
+                    "generator",
+                    compound([
+                        ("type", Value::String("minecraft:flat".to_string())),
+                        (
+                            "settings",
+                            compound([
+                                ("biome", Value::String("minecraft:plains".to_string())),
+                                ("features", Value::Byte(0)),
+                                ("lakes", Value::Byte(0)),
+                                (
+                                    "layers",
+                                    Value::List(vec![
+                                        layer("bedrock", 1),
+                                        layer("dirt", 2),
+                                        layer("grass_block", 1),
+                                    ]),
+                                ),
+                                (
+                                    "structure_overrides",
+                                    Value::String("minecraft:villages".to_string()),
+                                ),
+                            ]),
+                        ),
+                    ]),
+                ),
+            ])
+        }
+    };
+    let the_end = compound([("type", Value::String("minecraft:the_end".to_string()))]);
+    compound([
+        ("minecraft:overworld", overworld),
+        (
+            "minecraft:the_nether",
+            noise("the_nether", multi_noise("nether")),
+        ),
+        ("minecraft:the_end", noise("the_end", the_end)),
+    ])
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_config::GeneratorKind;
+    use pumpkin_core::math::vector3::Vector3;
+
+    use super::{BorderData, LevelData, SpawnerTimers};
+    use crate::game_rules::{GameRules, DO_DAYLIGHT_CYCLE, KEEP_INVENTORY};
+
+    #[test]
+    fn new_worlds_keep_their_seed() {
+        let data = LevelData::new("world", -42, GeneratorKind::Superflat);
+        assert_eq!(data.seed(), Some(-42));
+        assert_eq!(data.spawn(), Some((Vector3::new(10, 120, 10), 0.0)));
+        assert_eq!(data.time(), (0, 0));
+        assert!(data.game_rules().is_none());
+        assert!(data.border().is_none());
+        assert!(data.datapacks().is_none());
+        assert!(data.spawner_timers().is_none());
+    }
+
+    #[test]
+    fn game_rules_and_border_round_trip() {
+        let mut data = LevelData::default();
+        let mut rules = GameRules::new();
+        rules.set(&KEEP_INVENTORY, true);
+        rules.set(&DO_DAYLIGHT_CYCLE, false);
+        data.set_game_rules(&rules);
+        let border = BorderData {
+            center_x: 12.5,
+            center_z: -3.0,
+            size: 1000.0,
+            lerp_target: 500.0,
+            lerp_time: 60_000,
+            safe_zone: 5.0,
+            damage_per_block: 0.2,
+            warning_blocks: 5.0,
+            warning_time: 15.0,
+        };
+        data.set_border(&border);
+
+        let read = data.game_rules().unwrap();
+        assert!(read.get(&KEEP_INVENTORY));
+        assert!(!read.get(&DO_DAYLIGHT_CYCLE));
+        assert_eq!(data.border(), Some(border));
+    }
+
+    #[test]
+    fn datapacks_round_trip() {
+        let mut data = LevelData::default();
+        let enabled = ["vanilla".to_string(), "file/terrain".to_string()];
+        let disabled = ["file/old".to_string()];
+        data.set_datapacks(&enabled, &disabled);
+        assert_eq!(
+            data.datapacks(),
+            Some((enabled.to_vec(), disabled.to_vec()))
+        );
+    }
+
+    #[test]
+    fn spawner_timers_round_trip() {
+        let mut data = LevelData::default();
+        let timers = SpawnerTimers {
+            patrol: 12_345,
+            cat: 1200,
+            phantom: 60,
+            wandering_trader: 24_000,
+            wandering_trader_chance: 50,
+        };
+        data.set_spawner_timers(&timers);
+        assert_eq!(data.spawner_timers(), Some(timers));
+        assert_eq!(data.int("WanderingTraderSpawnDelay"), Some(24_000));
+    }
+}
@@ -4,11 +4,22 @@ pub mod chunk;
 pub mod coordinates;
 pub mod cylindrical_chunk_iterator;
 pub mod dimension;
+pub mod game_rules;
 pub mod item;
 pub mod level;
+pub mod level_data;
+pub mod pathfinding;
+pub mod player_data;
+pub mod resource_pack;
+pub mod structure;
 mod world_gen;
 
+pub use world_gen::{bench, CacheStats, Seed};
+
 pub const WORLD_HEIGHT: usize = 384;
 pub const WORLD_LOWEST_Y: i16 = -64;
 pub const WORLD_MAX_Y: i16 = WORLD_HEIGHT as i16 - WORLD_LOWEST_Y.abs();
 pub const DIRECT_PALETTE_BITS: u32 = 15;
+
+/// The data version of Minecraft 1.21.3, vanilla upgrades saves with an older one
+pub const DATA_VERSION: i32 = 4082;
@@ -0,0 +1,246 @@
+use std::io::Write;
+
+use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+use serde::{Serialize, Serializer};
+
+use crate::block::block_registry::{Block, State};
+
+/// How a mob's pathfinder sees a single block position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PathNodeType {
+    /// Solid, a mob can not stand inside of it.
+    Blocked,
+    /// Passable, but there is nothing to stand on.
+    Open,
+    /// Passable with a solid floor below and enough head room.
+    Walkable,
+    Water,
+    Lava,
+    DamageFire,
+    DamageOther,
+}
+
+impl PathNodeType {
+    /// The vanilla pathfinding malus. Negative values mean the node is never used.
+    #[must_use]
+    pub const fn malus(self) -> f32 {
+        match self {
+            Self::Blocked | Self::Lava | Self::DamageOther => -1.0,
+            Self::Open | Self::Walkable => 0.0,
+            Self::Water => 8.0,
+            Self::DamageFire => 16.0,
+        }
+    }
+
+    /// Color used when rendering a [`CollisionMap`] layer to an image.
+    #[must_use]
+    pub const fn color(self) -> [u8; 3] {
+        match self {
+            Self::Blocked => [64, 64, 64],
+            Self::Open => [255, 255, 255],
+            Self::Walkable => [0, 200, 0],
+            Self::Water => [0, 0, 255],
+            Self::Lava => [255, 128, 0],
+            Self::DamageFire => [255, 0, 0],
+            Self::DamageOther => [255, 0, 255],
+        }
+    }
+
+    /// Classifies a block by itself, without looking at its neighbours.
+    #[must_use]
+    pub fn from_block(block: &Block, state: &State) -> Self {
+        match block.name.as_str() {
+            "minecraft:water" | "minecraft:bubble_column" => Self::Water,
+            "minecraft:lava" => Self::Lava,
+            "minecraft:fire"
+            | "minecraft:soul_fire"
+            | "minecraft:magma_block"
+            | "minecraft:campfire"
+            | "minecraft:soul_campfire" => Self::DamageFire,
+            "minecraft:cactus" | "minecraft:sweet_berry_bush" | "minecraft:wither_rose" => {
+                Self::DamageOther
+            }
+            _ if state.collision_shapes.is_empty() => Self::Open,
+            _ => Self::Blocked,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct PathNode {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    pub node_type: PathNodeType,
+    pub malus: f32,
+}
+
+/// A snapshot of pathfinding nodes in a box around a position, used to debug mob pathing.
+#[derive(Clone, Debug, Serialize)]
+pub struct CollisionMap {
+    #[serde(serialize_with = "serialize_position")]
+    pub origin: Vector3<i32>,
+    pub radius: i32,
+    pub height: i32,
+    pub nodes: Vec<PathNode>,
+}
+
+impl CollisionMap {
+    /// Builds a map reaching `radius` blocks horizontally and `height` blocks vertically from `origin`.
+    ///
+    /// `sample` returns the raw type of a single block, see [`PathNodeType::from_block`]. Positions
+    /// outside of the box (one block below and above) are sampled too, to detect floors and head room.
+    pub fn build(
+        origin: WorldPosition,
+        radius: i32,
+        height: i32,
+        mut sample: impl FnMut(WorldPosition) -> PathNodeType,
+    ) -> Self {
+        let origin = origin.0;
+        let size = 2 * radius + 1;
+        let column_height = 2 * height + 3;
+        let min_y = origin.y - height - 1;
+
+        // sample every block once, columns are stored bottom up
+        let mut raw = Vec::with_capacity((size * size * column_height) as usize);
+        for x in -radius..=radius {
+            for z in -radius..=radius {
+                for y in 0..column_height {
+                    raw.push(sample(WorldPosition(Vector3::new(
+                        origin.x + x,
+                        min_y + y,
+                        origin.z + z,
+                    ))));
+                }
+            }
+        }
+
+        let mut nodes = Vec::with_capacity((size * size * (2 * height + 1)) as usize);
+        for (column_index, column) in raw.chunks_exact(column_height as usize).enumerate() {
+            let x = column_index as i32 / size - radius;
+            let z = column_index as i32 % size - radius;
+            for y in 1..column_height - 1 {
+                let node_type = evaluate(
+                    column[(y - 1) as usize],
+                    column[y as usize],
+                    column[(y + 1) as usize],
+                );
+                nodes.push(PathNode {
+                    x: origin.x + x,
+                    y: min_y + y,
+                    z: origin.z + z,
+                    node_type,
+                    malus: node_type.malus(),
+                });
+            }
+        }
+
+        Self {
+            origin,
+            radius,
+            height,
+            nodes,
+        }
+    }
+
+    #[must_use]
+    pub fn get(&self, position: WorldPosition) -> Option<&PathNode> {
+        let relative = position.0.sub(&self.origin);
+        if relative.x.abs() > self.radius
+            || relative.z.abs() > self.radius
+            || relative.y.abs() > self.height
+        {
+            return None;
+        }
+        let column_height = 2 * self.height + 1;
+        let column = (relative.x + self.radius) * (2 * self.radius + 1) + relative.z + self.radius;
+        self.nodes
+            .get((column * column_height + relative.y + self.height) as usize)
+    }
+
+    /// Writes a top down view of a single layer as a binary PPM image.
+    pub fn write_layer_ppm(&self, y: i32, writer: &mut impl Write) -> std::io::Result<()> {
+        let size = 2 * self.radius + 1;
+        write!(writer, "P6\n{size} {size}\n255\n")?;
+        for z in -self.radius..=self.radius {
+            for x in -self.radius..=self.radius {
+                let color = self
+                    .get(WorldPosition(Vector3::new(
+                        self.origin.x + x,
+                        y,
+                        self.origin.z + z,
+                    )))
+                    .map_or([0, 0, 0], |node| node.node_type.color());
+                writer.write_all(&color)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn serialize_position<S: Serializer>(
+    position: &Vector3<i32>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    [position.x, position.y, position.z].serialize(serializer)
+}
+
+/// Combines a block with the ones below and above it, like the vanilla walk node evaluator.
+fn evaluate(below: PathNodeType, current: PathNodeType, above: PathNodeType) -> PathNodeType {
+    match current {
+        PathNodeType::Open if below == PathNodeType::Blocked && above != PathNodeType::Blocked => {
+            PathNodeType::Walkable
+        }
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A flat floor at y=0 with a wall along x=2 and a lava pool at x=-2.
+    fn sample(position: WorldPosition) -> PathNodeType {
+        let Vector3 { x, y, .. } = position.0;
+        match (x, y) {
+            (_, 0) => PathNodeType::Blocked,
+            (2, 1..=2) => PathNodeType::Blocked,
+            (-2, 1) => PathNodeType::Lava,
+            _ => PathNodeType::Open,
+        }
+    }
+
+    fn node_type(map: &CollisionMap, x: i32, y: i32, z: i32) -> PathNodeType {
+        map.get(WorldPosition(Vector3::new(x, y, z)))
+            .unwrap()
+            .node_type
+    }
+
+    #[test]
+    fn flat_floor() {
+        let map = CollisionMap::build(WorldPosition(Vector3::new(0, 1, 0)), 3, 1, sample);
+        assert_eq!(map.nodes.len(), 7 * 7 * 3);
+        assert_eq!(node_type(&map, 0, 1, 0), PathNodeType::Walkable);
+        assert_eq!(node_type(&map, 0, 2, 0), PathNodeType::Open);
+        assert_eq!(node_type(&map, 0, 0, 0), PathNodeType::Blocked);
+        assert_eq!(node_type(&map, 2, 1, 3), PathNodeType::Blocked);
+        assert_eq!(node_type(&map, 2, 2, -3), PathNodeType::Blocked);
+        assert_eq!(node_type(&map, -2, 1, 1), PathNodeType::Lava);
+        assert!(map.get(WorldPosition(Vector3::new(4, 1, 0))).is_none());
+    }
+
+    #[test]
+    fn ppm_layer() {
+        let map = CollisionMap::build(WorldPosition(Vector3::new(0, 1, 0)), 1, 0, sample);
+        let mut image = Vec::new();
+        map.write_layer_ppm(1, &mut image).unwrap();
+        let header = b"P6\n3 3\n255\n";
+        assert_eq!(&image[..header.len()], header);
+        assert_eq!(image.len(), header.len() + 3 * 3 * 3);
+        assert_eq!(
+            &image[header.len()..header.len() + 3],
+            &PathNodeType::Walkable.color()
+        );
+    }
+}
@@ -0,0 +1,374 @@
+//! What vanilla saves about a player in `playerdata/<uuid>.dat`.
+
+use std::collections::HashMap;
+
+use fastnbt::{IntArray, Value};
+use pumpkin_core::math::vector3::Vector3;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    item::{
+        component::{potion_effect_from_nbt, potion_effect_to_nbt, PotionEffect},
+        slots_from_nbt, slots_to_nbt, ItemStack,
+    },
+    DATA_VERSION,
+};
+
+/// The NBT compound of a player. Only what Pumpkin knows is read and written, everything else, e.g.
+/// recipes and advancements, stays as it was read so it isn't lost when the world is opened in
+/// vanilla again.
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(transparent)]
+pub struct PlayerData(pub(crate) HashMap<String, Value>);
+
+impl PlayerData {
+    fn int(&self, key: &str) -> Option<i32> {
+        match self.0.get(key)? {
+            Value::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn float(&self, key: &str) -> Option<f32> {
+        match self.0.get(key)? {
+            Value::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn set(&mut self, key: &str, value: Value) {
+        self.0.insert(key.to_string(), value);
+    }
+
+    /// Everything which is saved, like `/data get entity` shows it
+    #[must_use]
+    pub const fn nbt(&self) -> &HashMap<String, Value> {
+        &self.0
+    }
+
+    /// Marks the data as written in the format of the Minecraft version Pumpkin supports
+    pub fn set_data_version(&mut self) {
+        self.set("DataVersion", Value::Int(DATA_VERSION));
+    }
+
+    #[must_use]
+    pub fn position(&self) -> Option<Vector3<f64>> {
+        let Value::List(position) = self.0.get("Pos")? else {
+            return None;
+        };
+        let [Value::Double(x), Value::Double(y), Value::Double(z)] = position.as_slice() else {
+            return None;
+        };
+        Some(Vector3::new(*x, *y, *z))
+    }
+
+    pub fn set_position(&mut self, position: Vector3<f64>) {
+        self.set(
+            "Pos",
+            Value::List(vec![
+                Value::Double(position.x),
+                Value::Double(position.y),
+                Value::Double(position.z),
+            ]),
+        );
+    }
+
+    /// The yaw and pitch
+    #[must_use]
+    pub fn rotation(&self) -> Option<(f32, f32)> {
+        let Value::List(rotation) = self.0.get("Rotation")? else {
+            return None;
+        };
+        let [Value::Float(yaw), Value::Float(pitch)] = rotation.as_slice() else {
+            return None;
+        };
+        Some((*yaw, *pitch))
+    }
+
+    pub fn set_rotation(&mut self, yaw: f32, pitch: f32) {
+        self.set(
+            "Rotation",
+            Value::List(vec![Value::Float(yaw), Value::Float(pitch)]),
+        );
+    }
+
+    /// e.g. `minecraft:overworld`
+    #[must_use]
+    pub fn dimension(&self) -> Option<&str> {
+        match self.0.get("Dimension")? {
+            Value::String(dimension) => Some(dimension),
+            _ => None,
+        }
+    }
+
+    pub fn set_dimension(&mut self, dimension: &str) {
+        self.set("Dimension", Value::String(dimension.to_string()));
+    }
+
+    /// The game mode by its id, 0 is survival
+    #[must_use]
+    pub fn game_mode(&self) -> Option<i32> {
+        self.int("playerGameType")
+    }
+
+    pub fn set_game_mode(&mut self, game_mode: i32) {
+        self.set("playerGameType", Value::Int(game_mode));
+    }
+
+    #[must_use]
+    pub fn health(&self) -> Option<f32> {
+        self.float("Health")
+    }
+
+    pub fn set_health(&mut self, health: f32) {
+        self.set("Health", Value::Float(health));
+    }
+
+    /// The food level and saturation
+    #[must_use]
+    pub fn food(&self) -> Option<(i32, f32)> {
+        Some((self.int("foodLevel")?, self.float("foodSaturationLevel")?))
+    }
+
+    pub fn set_food(&mut self, level: i32, saturation: f32) {
+        self.set("foodLevel", Value::Int(level));
+        self.set("foodSaturationLevel", Value::Float(saturation));
+    }
+
+    /// The hotbar slot the player holds, from 0 to 8
+    #[must_use]
+    pub fn selected_slot(&self) -> Option<i32> {
+        self.int("SelectedItemSlot")
+    }
+
+    pub fn set_selected_slot(&mut self, slot: i32) {
+        self.set("SelectedItemSlot", Value::Int(slot));
+    }
+
+    /// The level, the progress towards the next level from 0 to 1 and the points collected since
+    /// the last death
+    #[must_use]
+    pub fn experience(&self) -> Option<(i32, f32, i32)> {
+        Some((
+            self.int("XpLevel")?,
+            self.float("XpP")?,
+            self.int("XpTotal")?,
+        ))
+    }
+
+    pub fn set_experience(&mut self, level: i32, progress: f32, total: i32) {
+        self.set("XpLevel", Value::Int(level));
+        self.set("XpP", Value::Float(progress));
+        self.set("XpTotal", Value::Int(total));
+    }
+
+    fn items(&self, key: &str) -> Vec<(i8, ItemStack)> {
+        self.0.get(key).map(slots_from_nbt).unwrap_or_default()
+    }
+
+    fn set_items<'a>(&mut self, key: &str, items: impl IntoIterator<Item = (i8, &'a ItemStack)>) {
+        self.set(key, slots_to_nbt(items));
+    }
+
+    /// The items by their slot as vanilla numbers them: 0 to 8 is the hotbar, 9 to 35 the rest,
+    /// 100 to 103 the armor from the boots up and -106 the offhand. Unknown items are skipped
+    #[must_use]
+    pub fn inventory(&self) -> Vec<(i8, ItemStack)> {
+        self.items("Inventory")
+    }
+
+    /// Replaces the items, see [`Self::inventory`]
+    pub fn set_inventory<'a>(&mut self, items: impl IntoIterator<Item = (i8, &'a ItemStack)>) {
+        self.set_items("Inventory", items);
+    }
+
+    /// The items in the player's ender chest by their slot, from 0 to 26
+    #[must_use]
+    pub fn ender_items(&self) -> Vec<(i8, ItemStack)> {
+        self.items("EnderItems")
+    }
+
+    pub fn set_ender_items<'a>(&mut self, items: impl IntoIterator<Item = (i8, &'a ItemStack)>) {
+        self.set_items("EnderItems", items);
+    }
+
+    /// The status effects the player has, saved like the effects of potions
+    #[must_use]
+    pub fn active_effects(&self) -> Vec<PotionEffect> {
+        let Some(Value::List(effects)) = self.0.get("active_effects") else {
+            return Vec::new();
+        };
+        effects.iter().filter_map(potion_effect_from_nbt).collect()
+    }
+
+    pub fn set_active_effects<'a>(&mut self, effects: impl IntoIterator<Item = &'a PotionEffect>) {
+        let effects: Vec<_> = effects.into_iter().map(potion_effect_to_nbt).collect();
+        if effects.is_empty() {
+            self.0.remove("active_effects");
+        } else {
+            self.set("active_effects", Value::List(effects));
+        }
+    }
+
+    /// The dimension and block the player died at last
+    #[must_use]
+    pub fn last_death(&self) -> Option<(String, Vector3<i32>)> {
+        let Value::Compound(location) = self.0.get("LastDeathLocation")? else {
+            return None;
+        };
+        let Some(Value::String(dimension)) = location.get("dimension") else {
+            return None;
+        };
+        let Some(Value::IntArray(position)) = location.get("pos") else {
+            return None;
+        };
+        let [x, y, z] = **position else {
+            return None;
+        };
+        Some((dimension.clone(), Vector3::new(x, y, z)))
+    }
+
+    pub fn set_last_death(&mut self, location: Option<(&str, Vector3<i32>)>) {
+        let Some((dimension, position)) = location else {
+            self.0.remove("LastDeathLocation");
+            return;
+        };
+        let location = HashMap::from([
+            (
+                "dimension".to_string(),
+                Value::String(dimension.to_string()),
+            ),
+            (
+                "pos".to_string(),
+                Value::IntArray(IntArray::new(vec![position.x, position.y, position.z])),
+            ),
+        ]);
+        self.set("LastDeathLocation", Value::Compound(location));
+    }
+
+    /// How close sculk shriekers are to summoning a warden for the player: the warning level, the
+    /// ticks since the last warning and the ticks until the next one
+    #[must_use]
+    pub fn warden_spawn_tracker(&self) -> Option<(i32, i32, i32)> {
+        let Value::Compound(tracker) = self.0.get("warden_spawn_tracker")? else {
+            return None;
+        };
+        let int = |key| match tracker.get(key) {
+            Some(Value::Int(value)) => Some(*value),
+            _ => None,
+        };
+        Some((
+            int("warning_level")?,
+            int("ticks_since_last_warning")?,
+            int("cooldown_ticks")?,
+        ))
+    }
+
+    pub fn set_warden_spawn_tracker(
+        &mut self,
+        warning_level: i32,
+        ticks_since_last_warning: i32,
+        cooldown_ticks: i32,
+    ) {
+        let tracker = HashMap::from([
+            ("warning_level".to_string(), Value::Int(warning_level)),
+            (
+                "ticks_since_last_warning".to_string(),
+                Value::Int(ticks_since_last_warning),
+            ),
+            ("cooldown_ticks".to_string(), Value::Int(cooldown_ticks)),
+        ]);
+        self.set("warden_spawn_tracker", Value::Compound(tracker));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use fastnbt::Value;
+    use pumpkin_core::math::vector3::Vector3;
+
+    use pumpkin_core::nbt::snbt::{parse, parse_compound};
+
+    use super::PlayerData;
+    use crate::item::{
+        component::{DataComponent, PotionEffect},
+        item_registry::get_item,
+        ItemStack,
+    };
+
+    #[test]
+    fn unknown_fields_are_kept() {
+        let mut data = PlayerData::default();
+        data.0.insert("XpLevel".to_string(), Value::Int(30));
+        data.set_position(Vector3::new(1.5, 64.0, -3.5));
+        data.set_food(17, 2.5);
+
+        assert_eq!(data.0.get("XpLevel"), Some(&Value::Int(30)));
+        assert_eq!(data.position(), Some(Vector3::new(1.5, 64.0, -3.5)));
+        assert_eq!(data.food(), Some((17, 2.5)));
+    }
+
+    #[test]
+    fn inventory_keeps_components() {
+        let stone = get_item("minecraft:stone").unwrap().id;
+        let dirt = get_item("minecraft:dirt").unwrap().id;
+        let mut custom = ItemStack::new(6, stone);
+        custom.set_custom_model_data(Some(7));
+        custom.components.set(DataComponent::RepairCost(3));
+        custom.components.remove("minecraft:max_stack_size");
+        let mut data = PlayerData::default();
+        data.set_inventory([(0, &custom), (-106, &ItemStack::new(1, dirt))]);
+
+        let inventory = data.inventory();
+        assert_eq!(inventory.len(), 2);
+        assert_eq!(inventory[0].0, 0);
+        assert_eq!(inventory[0].1.item_count, 6);
+        assert_eq!(inventory[0].1.custom_model_data(), Some(7));
+        assert_eq!(inventory[0].1, custom);
+        assert_eq!(inventory[1], (-106, ItemStack::new(1, dirt)));
+    }
+
+    #[test]
+    fn ender_items_and_effects_are_kept() {
+        let stone = get_item("minecraft:stone").unwrap().id;
+        let mut data = PlayerData::default();
+        data.set_ender_items([(26, &ItemStack::new(3, stone))]);
+        let mut speed = PotionEffect::new("minecraft:speed", 1, 600);
+        speed.ambient = true;
+        let forever = PotionEffect::new("minecraft:night_vision", 0, -1);
+        data.set_active_effects([&speed, &forever]);
+
+        assert_eq!(data.ender_items(), vec![(26, ItemStack::new(3, stone))]);
+        assert!(data.inventory().is_empty());
+        assert_eq!(data.active_effects(), vec![speed, forever]);
+
+        data.set_active_effects(std::iter::empty());
+        assert!(!data.0.contains_key("active_effects"));
+    }
+
+    #[test]
+    fn warden_spawn_tracker_is_read_like_vanilla_writes_it() {
+        let nbt = parse_compound(
+            "{warden_spawn_tracker:{warning_level:3,ticks_since_last_warning:1200,cooldown_ticks:40}}",
+        )
+        .unwrap();
+        let mut data = PlayerData(nbt);
+        assert_eq!(data.warden_spawn_tracker(), Some((3, 1200, 40)));
+
+        data.set_warden_spawn_tracker(4, 0, 200);
+        assert_eq!(data.warden_spawn_tracker(), Some((4, 0, 200)));
+    }
+
+    #[test]
+    fn legacy_items_are_converted() {
+        let item =
+            parse(r#"{Slot:3b,id:"minecraft:stone",Count:12b,tag:{CustomModelData:5}}"#).unwrap();
+        let mut data = PlayerData::default();
+        data.set("Inventory", Value::List(vec![item]));
+
+        let inventory = data.inventory();
+        assert_eq!(inventory[0].0, 3);
+        assert_eq!(inventory[0].1.item_count, 12);
+        assert_eq!(inventory[0].1.custom_model_data(), Some(5));
+    }
+}
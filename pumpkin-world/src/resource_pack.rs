@@ -0,0 +1,224 @@
+//! Generates the resource pack which shows custom items and blocks.
+//!
+//! Custom items are vanilla items with custom model data, which the pack maps to their model with
+//! an override of the vanilla item's model. Custom blocks are states of the blocks in
+//! [`CustomStateBlock`], which the pack gives another model.
+
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use serde_json::{json, Value};
+
+use crate::block::custom_states::CustomStateBlock;
+
+/// The pack format of the supported version
+pub const PACK_FORMAT: u32 = 42;
+
+/// Splits an id like `myplugin:ruby` into namespace and path, `minecraft` if there is no namespace
+fn split_id(id: &str) -> (&str, &str) {
+    id.split_once(':').unwrap_or(("minecraft", id))
+}
+
+#[derive(Default)]
+pub struct ResourcePackBuilder {
+    description: String,
+    /// Custom model data and model by vanilla item
+    item_overrides: BTreeMap<String, BTreeMap<i32, String>>,
+    /// Model by state
+    block_states: BTreeMap<u16, String>,
+    /// Files which are written as they are, like textures or models of the plugins
+    files: BTreeMap<String, Vec<u8>>,
+}
+
+impl ResourcePackBuilder {
+    #[must_use]
+    pub fn new(description: impl Into<String>) -> Self {
+        Self {
+            description: description.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Shows the vanilla item `base` with the custom model data as the model `model`, e.g.
+    /// `myplugin:item/ruby`
+    pub fn item_override(&mut self, base: &str, custom_model_data: i32, model: &str) -> &mut Self {
+        let (_, base) = split_id(base);
+        self.item_overrides
+            .entry(base.to_string())
+            .or_default()
+            .insert(custom_model_data, model.to_string());
+        self
+    }
+
+    /// Shows the state, which has to be a free state of a [`CustomStateBlock`], as the model
+    pub fn block_state(&mut self, state_id: u16, model: &str) -> &mut Self {
+        self.block_states.insert(state_id, model.to_string());
+        self
+    }
+
+    /// Adds a model with a single texture, `parent` is e.g. `minecraft:item/generated` with the
+    /// texture `layer0` or `minecraft:block/cube_all` with the texture `all`
+    pub fn model(
+        &mut self,
+        model: &str,
+        parent: &str,
+        texture_key: &str,
+        texture: &str,
+    ) -> &mut Self {
+        let (namespace, path) = split_id(model);
+        self.json(
+            format!("assets/{namespace}/models/{path}.json"),
+            &json!({ "parent": parent, "textures": { texture_key: texture } }),
+        )
+    }
+
+    /// Adds a file, e.g. a texture at `assets/myplugin/textures/item/ruby.png`
+    pub fn file(&mut self, path: impl Into<String>, content: Vec<u8>) -> &mut Self {
+        self.files.insert(path.into(), content);
+        self
+    }
+
+    fn json(&mut self, path: String, value: &Value) -> &mut Self {
+        let content = serde_json::to_vec_pretty(value).expect("JSON values can be serialized");
+        self.file(path, content)
+    }
+
+    /// Every file of the pack by path
+    #[must_use]
+    pub fn build(mut self) -> BTreeMap<String, Vec<u8>> {
+        let description = std::mem::take(&mut self.description);
+        self.json(
+            "pack.mcmeta".to_string(),
+            &json!({ "pack": { "pack_format": PACK_FORMAT, "description": description } }),
+        );
+
+        for (base, overrides) in std::mem::take(&mut self.item_overrides) {
+            // the client picks the last override which matches, so they are sorted ascending
+            let overrides: Vec<_> = overrides
+                .into_iter()
+                .map(|(custom_model_data, model)| {
+                    json!({ "predicate": { "custom_model_data": custom_model_data }, "model": model })
+                })
+                .collect();
+            self.json(
+                format!("assets/minecraft/models/item/{base}.json"),
+                &json!({
+                    "parent": "minecraft:item/generated",
+                    "textures": { "layer0": format!("minecraft:item/{base}") },
+                    "overrides": overrides,
+                }),
+            );
+        }
+
+        let block_states = std::mem::take(&mut self.block_states);
+        for block in CustomStateBlock::ALL {
+            if !block
+                .free_states()
+                .any(|state| block_states.contains_key(&state))
+            {
+                continue;
+            }
+            self.json(
+                format!(
+                    "assets/minecraft/blockstates/{}.json",
+                    split_id(block.name()).1
+                ),
+                &json!({ "variants": Self::variants(block, &block_states) }),
+            );
+        }
+
+        self.files
+    }
+
+    /// A variant for every state, as states without one would have no model
+    fn variants(
+        block: CustomStateBlock,
+        models: &BTreeMap<u16, String>,
+    ) -> serde_json::Map<String, Value> {
+        let block_data = block.block();
+        block_data
+            .states
+            .iter()
+            .filter_map(|state| {
+                let properties = block_data.properties_of_state(state.id)?;
+                let key = properties
+                    .iter()
+                    .map(|(name, value)| format!("{name}={value}"))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                let model = models
+                    .get(&state.id)
+                    .map_or(block.vanilla_model(), String::as_str);
+                Some((key, json!({ "model": model })))
+            })
+            .collect()
+    }
+
+    /// Writes every file of the pack into the folder, replacing what was there before
+    pub fn write(self, folder: &Path) -> io::Result<()> {
+        if folder.exists() {
+            fs::remove_dir_all(folder)?;
+        }
+        for (path, content) in self.build() {
+            let path = folder.join(path);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, content)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+
+    use crate::block::custom_states::CustomStateBlock;
+
+    use super::ResourcePackBuilder;
+
+    fn parse(files: &std::collections::BTreeMap<String, Vec<u8>>, path: &str) -> Value {
+        serde_json::from_slice(&files[path]).unwrap()
+    }
+
+    #[test]
+    fn items_and_blocks() {
+        let state = CustomStateBlock::NoteBlock.free_states().next().unwrap();
+        let mut builder = ResourcePackBuilder::new("Custom content");
+        builder
+            .item_override("minecraft:paper", 2, "myplugin:item/sapphire")
+            .item_override("paper", 1, "myplugin:item/ruby")
+            .model(
+                "myplugin:item/ruby",
+                "minecraft:item/generated",
+                "layer0",
+                "myplugin:item/ruby",
+            )
+            .block_state(state, "myplugin:block/ruby_ore");
+        let files = builder.build();
+
+        assert_eq!(parse(&files, "pack.mcmeta")["pack"]["pack_format"], 42);
+
+        let paper = parse(&files, "assets/minecraft/models/item/paper.json");
+        let overrides = paper["overrides"].as_array().unwrap();
+        assert_eq!(overrides.len(), 2);
+        assert_eq!(overrides[0]["predicate"]["custom_model_data"], 1);
+        assert_eq!(overrides[0]["model"], "myplugin:item/ruby");
+
+        let ruby = parse(&files, "assets/myplugin/models/item/ruby.json");
+        assert_eq!(ruby["textures"]["layer0"], "myplugin:item/ruby");
+
+        let note_block = parse(&files, "assets/minecraft/blockstates/note_block.json");
+        let variants = note_block["variants"].as_object().unwrap();
+        assert_eq!(variants.len(), 1150);
+        assert_eq!(
+            variants["instrument=harp,note=0,powered=true"]["model"],
+            "myplugin:block/ruby_ore"
+        );
+        assert_eq!(
+            variants["instrument=harp,note=0,powered=false"]["model"],
+            "minecraft:block/note_block"
+        );
+        assert!(!files.contains_key("assets/minecraft/blockstates/mushroom_stem.json"));
+    }
+}
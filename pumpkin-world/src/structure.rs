@@ -0,0 +1,474 @@
+use std::{f64::consts::PI, sync::OnceLock};
+
+use pumpkin_core::{
+    math::vector2::Vector2,
+    random::{legacy_rand::LegacyRand, RandomImpl},
+};
+
+use crate::dimension::Dimension;
+
+/// How the offset of a structure inside of its region is picked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpreadType {
+    Linear,
+    /// Prefers the center of the region
+    Triangular,
+}
+
+/// Vanilla's ways to skip some of the potential structure chunks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrequencyReduction {
+    Default,
+    /// Used by pillager outposts
+    LegacyType1,
+    /// Used by buried treasures
+    LegacyType2,
+    /// Used by mineshafts
+    LegacyType3,
+}
+
+/// Places one structure in every `spacing` x `spacing` chunk region, at a random position which keeps
+/// at least `separation` chunks to the next region.
+#[derive(Clone, Copy, Debug)]
+pub struct RandomSpreadPlacement {
+    pub spacing: i32,
+    pub separation: i32,
+    pub salt: i32,
+    pub spread_type: SpreadType,
+    pub frequency: f32,
+    pub frequency_reduction: FrequencyReduction,
+    /// Offset from the chunk start to the position reported by `/locate`
+    pub locate_offset: (i32, i32),
+}
+
+impl RandomSpreadPlacement {
+    const fn new(spacing: i32, separation: i32, salt: i32, spread_type: SpreadType) -> Self {
+        Self {
+            spacing,
+            separation,
+            salt,
+            spread_type,
+            frequency: 1.0,
+            frequency_reduction: FrequencyReduction::Default,
+            locate_offset: (0, 0),
+        }
+    }
+
+    const fn frequency(mut self, frequency: f32, reduction: FrequencyReduction) -> Self {
+        self.frequency = frequency;
+        self.frequency_reduction = reduction;
+        self
+    }
+
+    const fn locate_offset(mut self, x: i32, z: i32) -> Self {
+        self.locate_offset = (x, z);
+        self
+    }
+
+    /// Returns the chunk in the region of the given chunk a structure may start in.
+    #[must_use]
+    pub fn potential_chunk(&self, seed: i64, chunk: Vector2<i32>) -> Vector2<i32> {
+        let region_x = chunk.x.div_euclid(self.spacing);
+        let region_z = chunk.z.div_euclid(self.spacing);
+        let mut random = region_random(seed, region_x, region_z, self.salt);
+        let range = self.spacing - self.separation;
+        let mut offset = || match self.spread_type {
+            SpreadType::Linear => random.next_bounded_i32(range),
+            SpreadType::Triangular => {
+                (random.next_bounded_i32(range) + random.next_bounded_i32(range)) / 2
+            }
+        };
+        let offset_x = offset();
+        let offset_z = offset();
+        Vector2::new(
+            region_x * self.spacing + offset_x,
+            region_z * self.spacing + offset_z,
+        )
+    }
+
+    /// Returns whether a structure may start in the given chunk, without checking biomes.
+    #[must_use]
+    pub fn is_start_chunk(&self, seed: i64, chunk: Vector2<i32>) -> bool {
+        self.potential_chunk(seed, chunk) == chunk && self.passes_frequency(seed, chunk)
+    }
+
+    fn passes_frequency(&self, seed: i64, chunk: Vector2<i32>) -> bool {
+        if self.frequency >= 1.0 {
+            return true;
+        }
+        match self.frequency_reduction {
+            FrequencyReduction::Default => {
+                region_random(seed, chunk.x, chunk.z, self.salt).next_f32() < self.frequency
+            }
+            FrequencyReduction::LegacyType1 => {
+                let x = chunk.x >> 4;
+                let z = chunk.z >> 4;
+                let mut random = LegacyRand::from_seed((i64::from(x ^ (z << 4)) ^ seed) as u64);
+                random.next_i32();
+                random.next_bounded_i32((1.0 / self.frequency) as i32) == 0
+            }
+            FrequencyReduction::LegacyType2 => {
+                region_random(seed, chunk.x, chunk.z, 10_387_320).next_f32() < self.frequency
+            }
+            FrequencyReduction::LegacyType3 => {
+                let mut random = LegacyRand::from_seed(seed as u64);
+                let a = random.next_i64();
+                let b = random.next_i64();
+                let carver_seed =
+                    i64::from(chunk.x).wrapping_mul(a) ^ i64::from(chunk.z).wrapping_mul(b) ^ seed;
+                LegacyRand::from_seed(carver_seed as u64).next_f64() < f64::from(self.frequency)
+            }
+        }
+    }
+}
+
+/// Places structures in rings around the world origin, used by strongholds.
+#[derive(Clone, Copy, Debug)]
+pub struct ConcentricRingsPlacement {
+    pub distance: i32,
+    pub spread: i32,
+    pub count: i32,
+}
+
+impl ConcentricRingsPlacement {
+    /// Calculates the chunk of every structure, like vanilla does before moving them to a nearby
+    /// preferred biome.
+    #[must_use]
+    pub fn positions(&self, seed: i64) -> Vec<Vector2<i32>> {
+        let mut positions = Vec::with_capacity(self.count as usize);
+        let mut random = LegacyRand::from_seed(seed as u64);
+        let distance = f64::from(self.distance);
+        let mut angle = random.next_f64() * PI * 2.0;
+        let mut spread = self.spread;
+        let mut in_ring = 0;
+        let mut ring = 0;
+
+        for i in 0..self.count {
+            let radius = f64::from(4 * self.distance + self.distance * ring * 6)
+                + (random.next_f64() - 0.5) * distance * 2.5;
+            positions.push(Vector2::new(
+                (angle.cos() * radius).round() as i32,
+                (angle.sin() * radius).round() as i32,
+            ));
+            // vanilla uses this split random to search for a preferred biome
+            random.split();
+
+            angle += PI * 2.0 / f64::from(spread);
+            in_ring += 1;
+            if in_ring == spread {
+                ring += 1;
+                in_ring = 0;
+                spread += 2 * spread / (ring + 1);
+                spread = spread.min(self.count - i);
+                angle += random.next_f64() * PI * 2.0;
+            }
+        }
+        positions
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum StructurePlacement {
+    RandomSpread(RandomSpreadPlacement),
+    ConcentricRings(ConcentricRingsPlacement),
+}
+
+#[derive(Debug)]
+pub struct Structure {
+    pub name: &'static str,
+    pub dimension: Dimension,
+    pub placement: StructurePlacement,
+}
+
+macro_rules! structure {
+    ($name:literal, $dimension:ident, $placement:expr) => {
+        Structure {
+            name: $name,
+            dimension: Dimension::$dimension,
+            placement: $placement,
+        }
+    };
+}
+
+const fn spread(spacing: i32, separation: i32, salt: i32) -> StructurePlacement {
+    StructurePlacement::RandomSpread(RandomSpreadPlacement::new(
+        spacing,
+        separation,
+        salt,
+        SpreadType::Linear,
+    ))
+}
+
+const fn triangular(spacing: i32, separation: i32, salt: i32) -> StructurePlacement {
+    StructurePlacement::RandomSpread(RandomSpreadPlacement::new(
+        spacing,
+        separation,
+        salt,
+        SpreadType::Triangular,
+    ))
+}
+
+/// Vanilla structures and the placement of their structure set.
+pub static STRUCTURES: &[Structure] = &[
+    structure!(
+        "minecraft:ancient_city",
+        OverWorld,
+        spread(24, 8, 20_083_232)
+    ),
+    structure!(
+        "minecraft:buried_treasure",
+        OverWorld,
+        StructurePlacement::RandomSpread(
+            RandomSpreadPlacement::new(1, 0, 0, SpreadType::Linear)
+                .frequency(0.01, FrequencyReduction::LegacyType2)
+                .locate_offset(9, 9)
+        )
+    ),
+    structure!(
+        "minecraft:desert_pyramid",
+        OverWorld,
+        spread(32, 8, 14_357_617)
+    ),
+    structure!("minecraft:end_city", End, triangular(20, 11, 10_387_313)),
+    structure!("minecraft:fortress", Nether, spread(27, 4, 30_084_232)),
+    structure!(
+        "minecraft:bastion_remnant",
+        Nether,
+        spread(27, 4, 30_084_232)
+    ),
+    structure!("minecraft:igloo", OverWorld, spread(32, 8, 14_357_618)),
+    structure!(
+        "minecraft:jungle_pyramid",
+        OverWorld,
+        spread(32, 8, 14_357_619)
+    ),
+    structure!(
+        "minecraft:mansion",
+        OverWorld,
+        triangular(80, 20, 10_387_319)
+    ),
+    structure!(
+        "minecraft:mineshaft",
+        OverWorld,
+        StructurePlacement::RandomSpread(
+            RandomSpreadPlacement::new(1, 0, 0, SpreadType::Linear)
+                .frequency(0.004, FrequencyReduction::LegacyType3)
+        )
+    ),
+    structure!(
+        "minecraft:monument",
+        OverWorld,
+        triangular(32, 5, 10_387_313)
+    ),
+    structure!("minecraft:nether_fossil", Nether, spread(2, 1, 14_357_921)),
+    structure!(
+        "minecraft:ocean_ruin_cold",
+        OverWorld,
+        spread(20, 8, 14_357_621)
+    ),
+    structure!("minecraft:pillager_outpost", OverWorld, {
+        StructurePlacement::RandomSpread(
+            RandomSpreadPlacement::new(32, 8, 165_745_296, SpreadType::Linear)
+                .frequency(0.2, FrequencyReduction::LegacyType1),
+        )
+    }),
+    structure!(
+        "minecraft:ruined_portal",
+        OverWorld,
+        spread(40, 15, 34_222_645)
+    ),
+    structure!("minecraft:shipwreck", OverWorld, spread(24, 4, 165_745_295)),
+    structure!(
+        "minecraft:stronghold",
+        OverWorld,
+        StructurePlacement::ConcentricRings(ConcentricRingsPlacement {
+            distance: 32,
+            spread: 3,
+            count: 128,
+        })
+    ),
+    structure!("minecraft:swamp_hut", OverWorld, spread(32, 8, 14_357_620)),
+    structure!(
+        "minecraft:trail_ruins",
+        OverWorld,
+        spread(34, 8, 83_469_867)
+    ),
+    structure!(
+        "minecraft:trial_chambers",
+        OverWorld,
+        spread(34, 12, 94_251_327)
+    ),
+    structure!(
+        "minecraft:village_plains",
+        OverWorld,
+        spread(34, 8, 10_387_312)
+    ),
+];
+
+#[must_use]
+pub fn get_structure(name: &str) -> Option<&'static Structure> {
+    STRUCTURES.iter().find(|structure| structure.name == name)
+}
+
+/// Searches for structures around a position, caching the stronghold rings of a seed.
+pub struct StructureLocator {
+    seed: i64,
+    rings: OnceLock<Vec<Vector2<i32>>>,
+}
+
+impl StructureLocator {
+    #[must_use]
+    pub const fn new(seed: i64) -> Self {
+        Self {
+            seed,
+            rings: OnceLock::new(),
+        }
+    }
+
+    /// Returns the block position (x, z) of the nearest structure start within `radius` chunks.
+    ///
+    /// TODO: Biomes are not checked yet, as world generation only knows a few of them.
+    #[must_use]
+    pub fn locate(
+        &self,
+        structure: &Structure,
+        origin: Vector2<i32>,
+        radius: i32,
+    ) -> Option<Vector2<i32>> {
+        let origin_chunk = Vector2::new(origin.x >> 4, origin.z >> 4);
+        match &structure.placement {
+            StructurePlacement::RandomSpread(placement) => self
+                .locate_random_spread(placement, origin_chunk, radius)
+                .map(|chunk| {
+                    Vector2::new(
+                        chunk.x * 16 + placement.locate_offset.0,
+                        chunk.z * 16 + placement.locate_offset.1,
+                    )
+                }),
+            StructurePlacement::ConcentricRings(placement) => {
+                // the ring positions are computed once, after that only the closest one has to be found
+                let rings = self.rings.get_or_init(|| placement.positions(self.seed));
+                rings
+                    .iter()
+                    .filter(|chunk| {
+                        (chunk.x - origin_chunk.x).abs() <= radius
+                            && (chunk.z - origin_chunk.z).abs() <= radius
+                    })
+                    .min_by_key(|chunk| chunk_distance_squared(**chunk, origin_chunk))
+                    .map(|chunk| Vector2::new(chunk.x * 16, chunk.z * 16))
+            }
+        }
+    }
+
+    /// Walks the regions in rings around the origin, returning the closest start of the first ring
+    /// which contains one.
+    fn locate_random_spread(
+        &self,
+        placement: &RandomSpreadPlacement,
+        origin: Vector2<i32>,
+        radius: i32,
+    ) -> Option<Vector2<i32>> {
+        let region_radius = radius / placement.spacing;
+        let origin_region = Vector2::new(
+            origin.x.div_euclid(placement.spacing),
+            origin.z.div_euclid(placement.spacing),
+        );
+
+        for ring in 0..=region_radius {
+            let mut closest: Option<Vector2<i32>> = None;
+            for dx in -ring..=ring {
+                for dz in -ring..=ring {
+                    if dx.abs() != ring && dz.abs() != ring {
+                        continue;
+                    }
+                    let region_chunk = Vector2::new(
+                        (origin_region.x + dx) * placement.spacing,
+                        (origin_region.z + dz) * placement.spacing,
+                    );
+                    let chunk = placement.potential_chunk(self.seed, region_chunk);
+                    if !placement.passes_frequency(self.seed, chunk) {
+                        continue;
+                    }
+                    if closest.is_none_or(|closest| {
+                        chunk_distance_squared(chunk, origin)
+                            < chunk_distance_squared(closest, origin)
+                    }) {
+                        closest = Some(chunk);
+                    }
+                }
+            }
+            if closest.is_some() {
+                return closest;
+            }
+        }
+        None
+    }
+}
+
+/// Creates the random vanilla uses for a structure region (`setRegionSeed`).
+fn region_random(seed: i64, region_x: i32, region_z: i32, salt: i32) -> LegacyRand {
+    let region_seed = i64::from(region_x)
+        .wrapping_mul(341_873_128_712)
+        .wrapping_add(i64::from(region_z).wrapping_mul(132_897_987_541))
+        .wrapping_add(seed)
+        .wrapping_add(i64::from(salt));
+    LegacyRand::from_seed(region_seed as u64)
+}
+
+fn chunk_distance_squared(a: Vector2<i32>, b: Vector2<i32>) -> i64 {
+    let dx = i64::from(a.x - b.x);
+    let dz = i64::from(a.z - b.z);
+    dx * dx + dz * dz
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_core::math::vector2::Vector2;
+
+    use super::{get_structure, StructureLocator, StructurePlacement};
+
+    #[test]
+    fn random_spread_stays_in_region() {
+        let StructurePlacement::RandomSpread(placement) =
+            get_structure("minecraft:village_plains").unwrap().placement
+        else {
+            panic!("villages use random spread placement");
+        };
+        for (x, z) in [(0, 0), (-1, -1), (100, -50), (-340, 341)] {
+            let chunk = placement.potential_chunk(12345, Vector2::new(x, z));
+            let region_x = x.div_euclid(placement.spacing);
+            let region_z = z.div_euclid(placement.spacing);
+            assert!((0..placement.spacing - placement.separation)
+                .contains(&(chunk.x - region_x * placement.spacing)));
+            assert!((0..placement.spacing - placement.separation)
+                .contains(&(chunk.z - region_z * placement.spacing)));
+            assert!(placement.is_start_chunk(12345, chunk));
+        }
+    }
+
+    #[test]
+    fn stronghold_rings() {
+        let StructurePlacement::ConcentricRings(placement) =
+            get_structure("minecraft:stronghold").unwrap().placement
+        else {
+            panic!("strongholds use concentric rings placement");
+        };
+        let positions = placement.positions(0);
+        assert_eq!(positions.len(), 128);
+        // the first ring is between 1280 and 2816 blocks away from the origin
+        for chunk in &positions[..3] {
+            let distance = f64::from(chunk.x * chunk.x + chunk.z * chunk.z).sqrt();
+            assert!((80.0..=176.0).contains(&distance), "{distance}");
+        }
+
+        let locator = StructureLocator::new(0);
+        let found = locator
+            .locate(
+                get_structure("minecraft:stronghold").unwrap(),
+                Vector2::new(0, 0),
+                1000,
+            )
+            .unwrap();
+        assert!(positions.contains(&Vector2::new(found.x / 16, found.z / 16)));
+    }
+}
@@ -0,0 +1,221 @@
+//! Times the stages of the world generation without a server, `pumpkin bench-worldgen` prints
+//! the report.
+//!
+//! Every stage runs on its own for each chunk, one chunk after another, so the times of a stage
+//! don't depend on how busy the other threads are.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use pumpkin_config::GeneratorKind;
+use pumpkin_core::{
+    math::vector2::Vector2,
+    random::{xoroshiro128::Xoroshiro, RandomGenerator, RandomImpl},
+};
+
+use super::{
+    get_world_gen,
+    noise::{
+        density::{
+            cache::{CacheStats, CacheVisitor},
+            BuiltInNoiseFunctions, DensityFunctionImpl, NoisePos, UnblendedNoisePos, Visitor,
+        },
+        perlin::DoublePerlinNoiseSampler,
+        BuiltInNoiseParams,
+    },
+    Seed,
+};
+use crate::{WORLD_HEIGHT, WORLD_LOWEST_Y};
+
+/// Densities are sampled at the corners of cells 4 blocks wide and 8 blocks high like vanilla
+/// does, biome noises every 4 blocks
+const CELL_WIDTH: usize = 4;
+const CELL_HEIGHT: usize = 8;
+const BIOME_CELL: usize = 4;
+
+/// How long a stage took for each chunk
+pub struct StageTimes {
+    pub name: &'static str,
+    /// Sorted, the fastest first
+    times: Vec<Duration>,
+}
+
+impl StageTimes {
+    fn new(name: &'static str, mut times: Vec<Duration>) -> Self {
+        times.sort_unstable();
+        Self { name, times }
+    }
+
+    pub fn total(&self) -> Duration {
+        self.times.iter().sum()
+    }
+
+    pub fn mean(&self) -> Duration {
+        self.total() / self.times.len().max(1) as u32
+    }
+
+    pub fn min(&self) -> Duration {
+        self.times.first().copied().unwrap_or_default()
+    }
+
+    pub fn max(&self) -> Duration {
+        self.times.last().copied().unwrap_or_default()
+    }
+
+    /// The time `percent` of the chunks took at most
+    pub fn percentile(&self, percent: f64) -> Duration {
+        if self.times.is_empty() {
+            return Duration::ZERO;
+        }
+        let index = ((self.times.len() - 1) as f64 * percent / 100.0).round() as usize;
+        self.times[index.min(self.times.len() - 1)]
+    }
+
+    pub fn chunks_per_second(&self) -> f64 {
+        self.times.len() as f64 / self.total().as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+pub struct BenchReport {
+    pub chunks: usize,
+    /// Building the noise parameters and density functions, which happens once per world
+    pub setup: Duration,
+    pub stages: Vec<StageTimes>,
+    /// The density caches of all chunks together
+    pub cache: CacheStats,
+}
+
+/// The chunks in a square around 0, 0
+fn positions(chunks: usize) -> impl Iterator<Item = Vector2<i32>> {
+    let side = (chunks as f64).sqrt().ceil() as usize;
+    let offset = side as i32 / 2;
+    (0..chunks).map(move |i| Vector2::new((i % side) as i32 - offset, (i / side) as i32 - offset))
+}
+
+fn time_each(positions: &[Vector2<i32>], mut stage: impl FnMut(Vector2<i32>)) -> Vec<Duration> {
+    positions
+        .iter()
+        .map(|at| {
+            let start = Instant::now();
+            stage(*at);
+            start.elapsed()
+        })
+        .collect()
+}
+
+/// Generates `chunks` chunks with the seed and times every stage
+pub fn run(seed: Seed, generator: GeneratorKind, chunks: usize) -> BenchReport {
+    let positions = positions(chunks).collect::<Vec<_>>();
+
+    let start = Instant::now();
+    let noise_params = BuiltInNoiseParams::new();
+    let noise_functions = BuiltInNoiseFunctions::new(&noise_params);
+    let mut random = RandomGenerator::Xoroshiro(Xoroshiro::from_seed(seed.0 as u64));
+    let biome_noises = [
+        noise_params.temperature(),
+        noise_params.vegetation(),
+        noise_params.continentalness(),
+        noise_params.erosion(),
+    ]
+    .map(|params| DoublePerlinNoiseSampler::new(&mut random, params));
+    let world_gen = get_world_gen(seed, generator);
+    let setup = start.elapsed();
+
+    let biome_noise = time_each(&positions, |at| {
+        for x in (0..16).step_by(BIOME_CELL) {
+            for z in (0..16).step_by(BIOME_CELL) {
+                for y in (0..WORLD_HEIGHT).step_by(BIOME_CELL) {
+                    let x = f64::from(at.x * 16 + x);
+                    let y = f64::from(i32::from(WORLD_LOWEST_Y) + y as i32);
+                    let z = f64::from(at.z * 16 + z);
+                    for noise in &biome_noises {
+                        black_box(noise.sample(x / 4.0, y / 4.0, z / 4.0));
+                    }
+                }
+            }
+        }
+    });
+
+    let density = noise_functions.sloped_cheese_overworld();
+    let sample_cells = |at: Vector2<i32>, sample: &dyn Fn(&NoisePos) -> f64| {
+        for x in (0..=16).step_by(CELL_WIDTH) {
+            for z in (0..=16).step_by(CELL_WIDTH) {
+                for y in (0..=WORLD_HEIGHT).step_by(CELL_HEIGHT) {
+                    let pos = NoisePos::Unblended(UnblendedNoisePos::new(
+                        at.x * 16 + x,
+                        i32::from(WORLD_LOWEST_Y) + y as i32,
+                        at.z * 16 + z,
+                    ));
+                    black_box(sample(&pos));
+                }
+            }
+        }
+    };
+    let uncached_density = time_each(&positions, |at| {
+        sample_cells(at, &|pos| density.sample(pos));
+    });
+    // the cache wrappers get their layers for every chunk, like when generating it
+    let mut cache = CacheStats::default();
+    let cached_density = time_each(&positions, |at| {
+        let visitor = Visitor::Cache(CacheVisitor::new(at.x, at.z, CELL_WIDTH, CELL_HEIGHT));
+        let cached = density.apply(&visitor);
+        sample_cells(at, &|pos| cached.sample(pos));
+        if let Visitor::Cache(visitor) = &visitor {
+            cache += visitor.stats();
+        }
+    });
+
+    let mut generated = Vec::with_capacity(positions.len());
+    let generation = time_each(&positions, |at| {
+        generated.push(world_gen.generate_chunk(at));
+    });
+
+    let mut chunks = generated.into_iter();
+    let serialization = time_each(&positions, |_| {
+        if let Some(chunk) = chunks.next() {
+            black_box(chunk.to_bytes().ok());
+        }
+    });
+
+    BenchReport {
+        chunks: positions.len(),
+        setup,
+        stages: vec![
+            StageTimes::new("biome noise", biome_noise),
+            StageTimes::new("density", uncached_density),
+            StageTimes::new("cached density", cached_density),
+            StageTimes::new("generation", generation),
+            StageTimes::new("serialization", serialization),
+        ],
+        cache,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{positions, StageTimes};
+
+    #[test]
+    fn percentiles() {
+        let times = (1..=100).rev().map(Duration::from_millis).collect();
+        let stage = StageTimes::new("test", times);
+
+        assert_eq!(stage.min(), Duration::from_millis(1));
+        assert_eq!(stage.max(), Duration::from_millis(100));
+        assert_eq!(stage.percentile(50.0), Duration::from_millis(51));
+        assert_eq!(stage.percentile(99.0), Duration::from_millis(99));
+        assert_eq!(stage.mean(), Duration::from_micros(50_500));
+    }
+
+    #[test]
+    fn positions_are_a_square() {
+        let positions = positions(9).collect::<Vec<_>>();
+        assert_eq!(positions.len(), 9);
+        assert!(positions.iter().all(|at| (-1..=1).contains(&at.x)));
+        assert!(positions.iter().all(|at| (-1..=1).contains(&at.z)));
+    }
+}
@@ -13,13 +13,15 @@ pub trait GeneratorInit {
 
 pub trait WorldGenerator: Sync + Send {
     fn generate_chunk(&self, at: Vector2<i32>) -> ChunkData;
+
+    /// Returns the biome which would be generated at the given position.
+    fn get_biome(&self, at: XZBlockCoordinates) -> Biome;
 }
 
 pub(crate) trait BiomeGenerator: Sync + Send {
     fn generate_biome(&self, at: XZBlockCoordinates) -> Biome;
 }
 
-#[expect(dead_code)]
 pub(crate) trait TerrainGenerator: Sync + Send {
     fn prepare_chunk(&self, at: &Vector2<i32>);
 
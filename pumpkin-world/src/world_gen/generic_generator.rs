@@ -1,14 +1,22 @@
+use std::{collections::HashMap, sync::atomic::AtomicBool};
+
 use noise::{NoiseFn, Perlin};
-use pumpkin_core::math::vector2::Vector2;
+use pumpkin_core::{math::vector2::Vector2, persistent_data::PersistentDataContainer};
 
 use crate::{
+    biome::Biome,
+    block::block_state::BlockState,
     chunk::{ChunkBlocks, ChunkData},
-    coordinates::{ChunkRelativeBlockCoordinates, ChunkRelativeXZBlockCoordinates},
-    WORLD_LOWEST_Y,
+    coordinates::{
+        ChunkRelativeBlockCoordinates, ChunkRelativeXZBlockCoordinates, XZBlockCoordinates,
+    },
+    WORLD_LOWEST_Y, WORLD_MAX_Y,
 };
 
 use super::{
-    generator::{BiomeGenerator, GeneratorInit, PerlinTerrainGenerator, WorldGenerator},
+    generator::{
+        BiomeGenerator, GeneratorInit, PerlinTerrainGenerator, TerrainGenerator, WorldGenerator,
+    },
     Seed,
 };
 
@@ -75,45 +83,83 @@ impl<B: BiomeGenerator, T: PerlinTerrainGenerator> WorldGenerator for GenericGen
         ChunkData {
             blocks,
             position: at,
+            persistent_data: PersistentDataContainer::default(),
+            block_entity_data: HashMap::new(),
+            block_entities: HashMap::new(),
+            entities: Vec::new(),
+            light: None,
+            nbt: Default::default(),
+            dirty: AtomicBool::new(true),
         }
     }
+
+    fn get_biome(&self, at: XZBlockCoordinates) -> Biome {
+        self.biome_generator.generate_biome(at)
+    }
 }
 
-// TODO: implement static terrain generator
-/*
-fn generate_chunk(&mut self, at: Vector2<i32>) -> ChunkData {
-    let mut blocks = ChunkBlocks::default();
-    self.terrain_generator.prepare_chunk(&at, &self.perlin);
-    for x in 0..16u8 {
-        for z in 0..16u8 {
-            let biome = self.biome_generator.generate_biome(
-                ChunkRelativeXZBlockCoordinates {
-                    x: x.into(),
-                    z: z.into(),
-                }
-                .with_chunk_coordinates(at),
-            );
-
-            // Iterate from the highest block to the lowest, in order to minimize the heightmap updates
-            for y in (WORLD_LOWEST_Y..WORLD_MAX_Y).rev() {
-                let coordinates = ChunkRelativeBlockCoordinates {
-                    x: x.into(),
-                    y: y.into(),
-                    z: z.into(),
-                };
-
-                blocks.set_block(
-                    coordinates,
-                    self.terrain_generator
-                        .generate_block(coordinates.with_chunk_coordinates(at), biome),
+/// Generates chunks whose blocks only depend on their position, like superflat ones
+pub struct StaticGenerator<B: BiomeGenerator, T: TerrainGenerator> {
+    biome_generator: B,
+    terrain_generator: T,
+}
+
+impl<B: BiomeGenerator + GeneratorInit, T: TerrainGenerator + GeneratorInit> GeneratorInit
+    for StaticGenerator<B, T>
+{
+    fn new(seed: Seed) -> Self {
+        Self {
+            biome_generator: B::new(seed),
+            terrain_generator: T::new(seed),
+        }
+    }
+}
+
+impl<B: BiomeGenerator, T: TerrainGenerator> WorldGenerator for StaticGenerator<B, T> {
+    fn generate_chunk(&self, at: Vector2<i32>) -> ChunkData {
+        let mut blocks = ChunkBlocks::default();
+        self.terrain_generator.prepare_chunk(&at);
+        for x in 0..16u8 {
+            for z in 0..16u8 {
+                let biome = self.biome_generator.generate_biome(
+                    ChunkRelativeXZBlockCoordinates {
+                        x: x.into(),
+                        z: z.into(),
+                    }
+                    .with_chunk_coordinates(at),
                 );
+
+                // Iterate from the highest block to the lowest, in order to minimize the heightmap updates
+                for y in (WORLD_LOWEST_Y..WORLD_MAX_Y).rev() {
+                    let coordinates = ChunkRelativeBlockCoordinates {
+                        x: x.into(),
+                        y: y.into(),
+                        z: z.into(),
+                    };
+                    let block = self
+                        .terrain_generator
+                        .generate_block(coordinates.with_chunk_coordinates(at), biome);
+                    if block.state_id != BlockState::AIR.state_id {
+                        blocks.set_block(coordinates, block.state_id);
+                    }
+                }
             }
         }
+
+        ChunkData {
+            blocks,
+            position: at,
+            persistent_data: PersistentDataContainer::default(),
+            block_entity_data: HashMap::new(),
+            block_entities: HashMap::new(),
+            entities: Vec::new(),
+            light: None,
+            nbt: Default::default(),
+            dirty: AtomicBool::new(true),
+        }
     }
 
-    ChunkData {
-        blocks,
-        position: at,
+    fn get_biome(&self, at: XZBlockCoordinates) -> Biome {
+        self.biome_generator.generate_biome(at)
     }
 }
-*/
@@ -6,13 +6,12 @@ use crate::{
     coordinates::{BlockCoordinates, XZBlockCoordinates},
     world_gen::{
         generator::{BiomeGenerator, GeneratorInit, TerrainGenerator},
-        generic_generator::GenericGenerator,
+        generic_generator::StaticGenerator,
         Seed,
     },
 };
 
-#[expect(dead_code)]
-pub type SuperflatGenerator = GenericGenerator<SuperflatBiomeGenerator, SuperflatTerrainGenerator>;
+pub type SuperflatGenerator = StaticGenerator<SuperflatBiomeGenerator, SuperflatTerrainGenerator>;
 
 pub(crate) struct SuperflatBiomeGenerator {}
 
@@ -1,5 +1,6 @@
 #![allow(dead_code)]
 
+pub mod bench;
 mod blender;
 mod generator;
 mod generic_generator;
@@ -12,14 +13,18 @@ mod sampler;
 mod seed;
 
 pub use generator::WorldGenerator;
-use implementation::overworld::biome::plains::PlainsGenerator;
+use implementation::{overworld::biome::plains::PlainsGenerator, superflat::SuperflatGenerator};
+pub use noise::density::cache::CacheStats;
+use pumpkin_config::GeneratorKind;
 pub use seed::Seed;
 
 use generator::GeneratorInit;
 
-pub fn get_world_gen(seed: Seed) -> Box<dyn WorldGenerator> {
-    // TODO decide which WorldGenerator to pick based on config.
-    Box::new(PlainsGenerator::new(seed))
+pub fn get_world_gen(seed: Seed, kind: GeneratorKind) -> Box<dyn WorldGenerator> {
+    match kind {
+        GeneratorKind::Plains => Box::new(PlainsGenerator::new(seed)),
+        GeneratorKind::Superflat => Box::new(SuperflatGenerator::new(seed)),
+    }
 }
 
 pub mod biome_coords {
@@ -0,0 +1,596 @@
+//! The caches behind the cache [`super::WrapperType`]s. [`CacheVisitor`] replaces the wrappers of
+//! a function with them for the chunk it is sampled in.
+//!
+//! Every layer counts its hits and misses and knows how much it holds, so the effect of
+//! a wrapper on generation can be measured instead of guessed.
+
+use std::{
+    mem::size_of,
+    ops::{Add, AddAssign, Deref},
+    sync::Arc,
+};
+
+use parking_lot::Mutex;
+
+use super::{
+    Applier, ApplierImpl, DensityFunction, DensityFunctionImpl, NoisePos, NoisePosImpl,
+    UnblendedNoisePos, Visitor, VisitorImpl, WrapperType,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// How many values the cache holds right now
+    pub entries: usize,
+    /// The memory used by the cached values and their keys
+    pub bytes: usize,
+}
+
+impl CacheStats {
+    /// Returns the share of lookups which were hits, 0 if there were none
+    #[must_use]
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            return 0.0;
+        }
+        self.hits as f64 / total as f64
+    }
+}
+
+impl Add for CacheStats {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            hits: self.hits + other.hits,
+            misses: self.misses + other.misses,
+            entries: self.entries + other.entries,
+            bytes: self.bytes + other.bytes,
+        }
+    }
+}
+
+impl AddAssign for CacheStats {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+pub trait CacheLayer {
+    /// Returns the cached value for the position, calling `sample` on a miss.
+    ///
+    /// `sample` gets the position to sample at, which is not always `pos`, e.g. flat caches sample at y = 0.
+    fn get_or_sample<P, F>(&mut self, pos: &P, sample: F) -> f64
+    where
+        P: NoisePosImpl,
+        F: FnOnce(&UnblendedNoisePos) -> f64;
+
+    /// Drops all cached values, the counters are kept.
+    fn invalidate(&mut self);
+
+    fn stats(&self) -> CacheStats;
+}
+
+/// Remembers the last sampled position, for functions used several times for the same block
+#[derive(Default)]
+pub struct OnceCache {
+    last: Option<((i32, i32, i32), f64)>,
+    hits: u64,
+    misses: u64,
+}
+
+impl CacheLayer for OnceCache {
+    fn get_or_sample<P, F>(&mut self, pos: &P, sample: F) -> f64
+    where
+        P: NoisePosImpl,
+        F: FnOnce(&UnblendedNoisePos) -> f64,
+    {
+        let key = (pos.x(), pos.y(), pos.z());
+        if let Some((last_key, value)) = self.last {
+            if last_key == key {
+                self.hits += 1;
+                return value;
+            }
+        }
+        self.misses += 1;
+        let value = sample(&UnblendedNoisePos::new(key.0, key.1, key.2));
+        self.last = Some((key, value));
+        value
+    }
+
+    fn invalidate(&mut self) {
+        self.last = None;
+    }
+
+    fn stats(&self) -> CacheStats {
+        let entries = usize::from(self.last.is_some());
+        CacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            entries,
+            bytes: entries * size_of::<((i32, i32, i32), f64)>(),
+        }
+    }
+}
+
+/// Remembers the last sampled column, for functions which do not depend on the height
+#[derive(Default)]
+pub struct Column2DCache {
+    last: Option<((i32, i32), f64)>,
+    hits: u64,
+    misses: u64,
+}
+
+impl CacheLayer for Column2DCache {
+    fn get_or_sample<P, F>(&mut self, pos: &P, sample: F) -> f64
+    where
+        P: NoisePosImpl,
+        F: FnOnce(&UnblendedNoisePos) -> f64,
+    {
+        let key = (pos.x(), pos.z());
+        if let Some((last_key, value)) = self.last {
+            if last_key == key {
+                self.hits += 1;
+                return value;
+            }
+        }
+        self.misses += 1;
+        let value = sample(&UnblendedNoisePos::new(pos.x(), pos.y(), pos.z()));
+        self.last = Some((key, value));
+        value
+    }
+
+    fn invalidate(&mut self) {
+        self.last = None;
+    }
+
+    fn stats(&self) -> CacheStats {
+        let entries = usize::from(self.last.is_some());
+        CacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            entries,
+            bytes: entries * size_of::<((i32, i32), f64)>(),
+        }
+    }
+}
+
+/// Holds one value per quart column (4x4 blocks) of a chunk, sampled at y = 0
+///
+/// Includes the columns on the positive edges, like vanilla, so neighbours can be interpolated.
+pub struct FlatCache {
+    /// The quart coordinates of the chunk's first column
+    start_x: i32,
+    start_z: i32,
+    values: [Option<f64>; Self::SIZE * Self::SIZE],
+    hits: u64,
+    misses: u64,
+}
+
+impl FlatCache {
+    const SIZE: usize = 5;
+
+    #[must_use]
+    pub fn new(chunk_x: i32, chunk_z: i32) -> Self {
+        Self {
+            start_x: chunk_x * 4,
+            start_z: chunk_z * 4,
+            values: [None; Self::SIZE * Self::SIZE],
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    fn index(&self, quart_x: i32, quart_z: i32) -> Option<usize> {
+        let x = usize::try_from(quart_x - self.start_x).ok()?;
+        let z = usize::try_from(quart_z - self.start_z).ok()?;
+        (x < Self::SIZE && z < Self::SIZE).then_some(x * Self::SIZE + z)
+    }
+}
+
+impl CacheLayer for FlatCache {
+    fn get_or_sample<P, F>(&mut self, pos: &P, sample: F) -> f64
+    where
+        P: NoisePosImpl,
+        F: FnOnce(&UnblendedNoisePos) -> f64,
+    {
+        let quart_x = pos.x() >> 2;
+        let quart_z = pos.z() >> 2;
+        let sample_pos = UnblendedNoisePos::new(quart_x << 2, 0, quart_z << 2);
+        let Some(index) = self.index(quart_x, quart_z) else {
+            // outside of the chunk, nothing to keep
+            self.misses += 1;
+            return sample(&sample_pos);
+        };
+        if let Some(value) = self.values[index] {
+            self.hits += 1;
+            return value;
+        }
+        self.misses += 1;
+        let value = sample(&sample_pos);
+        self.values[index] = Some(value);
+        value
+    }
+
+    fn invalidate(&mut self) {
+        self.values = [None; Self::SIZE * Self::SIZE];
+    }
+
+    fn stats(&self) -> CacheStats {
+        let entries = self.values.iter().flatten().count();
+        CacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            entries,
+            bytes: size_of::<[Option<f64>; Self::SIZE * Self::SIZE]>(),
+        }
+    }
+}
+
+/// Holds every block of the current interpolation cell, emptied when the next cell starts
+pub struct CellCache {
+    horizontal_size: usize,
+    vertical_size: usize,
+    /// The block coordinates of the cell's lowest corner
+    start: (i32, i32, i32),
+    values: Vec<Option<f64>>,
+    hits: u64,
+    misses: u64,
+}
+
+impl CellCache {
+    #[must_use]
+    pub fn new(horizontal_size: usize, vertical_size: usize) -> Self {
+        Self {
+            horizontal_size,
+            vertical_size,
+            start: (0, 0, 0),
+            values: vec![None; horizontal_size * vertical_size * horizontal_size],
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Moves on to the cell starting at the block, dropping the values of the previous one.
+    pub fn start_cell(&mut self, x: i32, y: i32, z: i32) {
+        self.start = (x, y, z);
+        self.invalidate();
+    }
+
+    fn index(&self, x: i32, y: i32, z: i32) -> Option<usize> {
+        let x = usize::try_from(x - self.start.0).ok()?;
+        let y = usize::try_from(y - self.start.1).ok()?;
+        let z = usize::try_from(z - self.start.2).ok()?;
+        (x < self.horizontal_size && y < self.vertical_size && z < self.horizontal_size)
+            .then_some((y * self.horizontal_size + z) * self.horizontal_size + x)
+    }
+
+    /// Where the cell with the block starts
+    fn cell_start(&self, x: i32, y: i32, z: i32) -> (i32, i32, i32) {
+        let horizontal = i32::try_from(self.horizontal_size).unwrap_or(i32::MAX);
+        let vertical = i32::try_from(self.vertical_size).unwrap_or(i32::MAX);
+        (
+            x.div_euclid(horizontal) * horizontal,
+            y.div_euclid(vertical) * vertical,
+            z.div_euclid(horizontal) * horizontal,
+        )
+    }
+}
+
+impl CacheLayer for CellCache {
+    fn get_or_sample<P, F>(&mut self, pos: &P, sample: F) -> f64
+    where
+        P: NoisePosImpl,
+        F: FnOnce(&UnblendedNoisePos) -> f64,
+    {
+        let sample_pos = UnblendedNoisePos::new(pos.x(), pos.y(), pos.z());
+        let Some(index) = self.index(pos.x(), pos.y(), pos.z()) else {
+            self.misses += 1;
+            return sample(&sample_pos);
+        };
+        if let Some(value) = self.values[index] {
+            self.hits += 1;
+            return value;
+        }
+        self.misses += 1;
+        let value = sample(&sample_pos);
+        self.values[index] = Some(value);
+        value
+    }
+
+    fn invalidate(&mut self) {
+        self.values.fill(None);
+    }
+
+    fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            entries: self.values.iter().flatten().count(),
+            bytes: self.values.len() * size_of::<Option<f64>>(),
+        }
+    }
+}
+
+/// The layer of one cache wrapper
+pub enum DensityCache {
+    Once(OnceCache),
+    Column(Column2DCache),
+    Flat(FlatCache),
+    Cell(CellCache),
+}
+
+impl DensityCache {
+    fn get_or_sample<P, F>(&mut self, pos: &P, sample: F) -> f64
+    where
+        P: NoisePosImpl,
+        F: FnOnce(&UnblendedNoisePos) -> f64,
+    {
+        match self {
+            Self::Once(cache) => cache.get_or_sample(pos, sample),
+            Self::Column(cache) => cache.get_or_sample(pos, sample),
+            Self::Flat(cache) => cache.get_or_sample(pos, sample),
+            Self::Cell(cache) => {
+                // the cells are sampled one after another, the next one starts on the first miss
+                if cache.index(pos.x(), pos.y(), pos.z()).is_none() {
+                    let (x, y, z) = cache.cell_start(pos.x(), pos.y(), pos.z());
+                    cache.start_cell(x, y, z);
+                }
+                cache.get_or_sample(pos, sample)
+            }
+        }
+    }
+
+    fn stats(&self) -> CacheStats {
+        match self {
+            Self::Once(cache) => cache.stats(),
+            Self::Column(cache) => cache.stats(),
+            Self::Flat(cache) => cache.stats(),
+            Self::Cell(cache) => cache.stats(),
+        }
+    }
+}
+
+/// A cache wrapper replaced by its layer, clones share the layer
+#[derive(Clone)]
+pub struct CachedFunction<'a> {
+    input: Arc<DensityFunction<'a>>,
+    cache: Arc<Mutex<DensityCache>>,
+}
+
+impl<'a> DensityFunctionImpl<'a> for CachedFunction<'a> {
+    fn sample(&self, pos: &NoisePos) -> f64 {
+        self.cache.lock().get_or_sample(pos, |at| {
+            self.input
+                .sample(&NoisePos::Unblended(UnblendedNoisePos::new(
+                    at.x(),
+                    at.y(),
+                    at.z(),
+                )))
+        })
+    }
+
+    fn fill(&self, densities: &mut [f64], applier: &Applier<'a>) {
+        applier.fill(densities, &DensityFunction::Cached(self.clone()));
+    }
+
+    fn apply(&self, visitor: &Visitor<'a>) -> Arc<DensityFunction<'a>> {
+        visitor.apply(Arc::new(DensityFunction::Cached(CachedFunction {
+            input: self.input.apply(visitor),
+            cache: self.cache.clone(),
+        })))
+    }
+
+    fn min(&self) -> f64 {
+        self.input.min()
+    }
+
+    fn max(&self) -> f64 {
+        self.input.max()
+    }
+}
+
+/// Replaces the cache wrappers of a function with layers for one chunk, interpolated wrappers are
+/// kept. Functions are applied to a new visitor for every chunk
+pub struct CacheVisitor {
+    chunk_x: i32,
+    chunk_z: i32,
+    cell_width: usize,
+    cell_height: usize,
+    layers: Mutex<Vec<Arc<Mutex<DensityCache>>>>,
+}
+
+impl CacheVisitor {
+    #[must_use]
+    pub fn new(chunk_x: i32, chunk_z: i32, cell_width: usize, cell_height: usize) -> Self {
+        Self {
+            chunk_x,
+            chunk_z,
+            cell_width,
+            cell_height,
+            layers: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// The stats of all layers the visitor created
+    #[must_use]
+    pub fn stats(&self) -> CacheStats {
+        self.layers
+            .lock()
+            .iter()
+            .fold(CacheStats::default(), |stats, layer| {
+                stats + layer.lock().stats()
+            })
+    }
+}
+
+impl<'a> VisitorImpl<'a> for CacheVisitor {
+    fn apply(&self, function: Arc<DensityFunction<'a>>) -> Arc<DensityFunction<'a>> {
+        let DensityFunction::Wrapper(wrapper) = function.deref() else {
+            return function;
+        };
+        let cache = match wrapper.wrapper() {
+            WrapperType::CacheOnce => DensityCache::Once(OnceCache::default()),
+            WrapperType::Cache2D => DensityCache::Column(Column2DCache::default()),
+            WrapperType::CacheFlat => {
+                DensityCache::Flat(FlatCache::new(self.chunk_x, self.chunk_z))
+            }
+            WrapperType::CacheCell => {
+                DensityCache::Cell(CellCache::new(self.cell_width, self.cell_height))
+            }
+            WrapperType::Interpolated => return function,
+        };
+        let cache = Arc::new(Mutex::new(cache));
+        self.layers.lock().push(cache.clone());
+        Arc::new(DensityFunction::Cached(CachedFunction {
+            input: wrapper.wrapped(),
+            cache,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::{
+        CacheLayer, CacheStats, CacheVisitor, CellCache, Column2DCache, FlatCache, NoisePosImpl,
+        OnceCache, UnblendedNoisePos,
+    };
+    use crate::world_gen::noise::density::{
+        DensityFunction, DensityFunctionImpl, NoisePos, Visitor, WrapperFunction, WrapperType,
+        YClampedFunction,
+    };
+
+    fn pos(x: i32, y: i32, z: i32) -> UnblendedNoisePos {
+        UnblendedNoisePos::new(x, y, z)
+    }
+
+    /// Samples the sum of the coordinates, counting the calls
+    fn sample<C: CacheLayer>(cache: &mut C, at: &UnblendedNoisePos, calls: &mut u32) -> f64 {
+        cache.get_or_sample(at, |pos| {
+            *calls += 1;
+            f64::from(pos.x() + pos.y() + pos.z())
+        })
+    }
+
+    #[test]
+    fn once_cache() {
+        let mut cache = OnceCache::default();
+        let mut calls = 0;
+        assert_eq!(sample(&mut cache, &pos(1, 2, 3), &mut calls), 6.0);
+        assert_eq!(sample(&mut cache, &pos(1, 2, 3), &mut calls), 6.0);
+        assert_eq!(calls, 1);
+
+        // any other position replaces the value
+        assert_eq!(sample(&mut cache, &pos(1, 3, 3), &mut calls), 7.0);
+        assert_eq!(sample(&mut cache, &pos(1, 2, 3), &mut calls), 6.0);
+        assert_eq!(calls, 3);
+
+        cache.invalidate();
+        sample(&mut cache, &pos(1, 2, 3), &mut calls);
+        assert_eq!(calls, 4);
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                hits: 1,
+                misses: 4,
+                entries: 1,
+                bytes: 24,
+            }
+        );
+    }
+
+    #[test]
+    fn column_cache_ignores_height() {
+        let mut cache = Column2DCache::default();
+        let mut calls = 0;
+        assert_eq!(sample(&mut cache, &pos(1, 2, 3), &mut calls), 6.0);
+        // the first height sampled is kept for the whole column
+        assert_eq!(sample(&mut cache, &pos(1, 50, 3), &mut calls), 6.0);
+        assert_eq!(calls, 1);
+
+        assert_eq!(sample(&mut cache, &pos(2, 2, 3), &mut calls), 7.0);
+        assert_eq!(calls, 2);
+        assert!((cache.stats().hit_rate() - 1.0 / 3.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn flat_cache_samples_quart_columns() {
+        let mut cache = FlatCache::new(1, 0);
+        let mut calls = 0;
+        // sampled at the quart's corner at y = 0
+        assert_eq!(sample(&mut cache, &pos(17, 60, 2), &mut calls), 16.0);
+        assert_eq!(sample(&mut cache, &pos(19, -20, 3), &mut calls), 16.0);
+        assert_eq!(calls, 1);
+
+        // the positive edge belongs to the chunk, the negative one does not
+        sample(&mut cache, &pos(32, 0, 16), &mut calls);
+        sample(&mut cache, &pos(32, 0, 16), &mut calls);
+        assert_eq!(calls, 2);
+        sample(&mut cache, &pos(15, 0, 0), &mut calls);
+        sample(&mut cache, &pos(15, 0, 0), &mut calls);
+        assert_eq!(calls, 4);
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (2, 4, 2));
+
+        cache.invalidate();
+        assert_eq!(cache.stats().entries, 0);
+        sample(&mut cache, &pos(17, 60, 2), &mut calls);
+        assert_eq!(calls, 5);
+    }
+
+    #[test]
+    fn cell_cache_is_emptied_per_cell() {
+        let mut cache = CellCache::new(4, 8);
+        let mut calls = 0;
+        cache.start_cell(0, 0, 0);
+        for y in 0..8 {
+            sample(&mut cache, &pos(3, y, 0), &mut calls);
+            sample(&mut cache, &pos(3, y, 0), &mut calls);
+        }
+        assert_eq!(calls, 8);
+        assert_eq!(cache.stats().entries, 8);
+
+        // outside of the cell
+        sample(&mut cache, &pos(4, 0, 0), &mut calls);
+        assert_eq!(calls, 9);
+
+        cache.start_cell(4, 0, 0);
+        assert_eq!(cache.stats().entries, 0);
+        sample(&mut cache, &pos(4, 0, 0), &mut calls);
+        sample(&mut cache, &pos(4, 0, 0), &mut calls);
+        assert_eq!(calls, 10);
+        assert_eq!(cache.stats().bytes, 4 * 8 * 4 * 16);
+    }
+
+    #[test]
+    fn visitor_replaces_cache_wrappers() {
+        let y = Arc::new(DensityFunction::ClampedY(YClampedFunction::new(
+            -64, 320, -64.0, 320.0,
+        )));
+        let function = DensityFunction::Wrapper(WrapperFunction::new(
+            Arc::new(DensityFunction::Wrapper(WrapperFunction::new(
+                y,
+                WrapperType::Interpolated,
+            ))),
+            WrapperType::CacheFlat,
+        ));
+        let visitor = Visitor::Cache(CacheVisitor::new(0, 0, 4, 8));
+        let cached = function.apply(&visitor);
+        assert!(matches!(*cached, DensityFunction::Cached(_)));
+
+        let at = |y| NoisePos::Unblended(UnblendedNoisePos::new(1, y, 2));
+        // flat caches sample at y = 0
+        assert_eq!(cached.sample(&at(100)), 0.0);
+        assert_eq!(cached.sample(&at(-20)), 0.0);
+        let Visitor::Cache(visitor) = visitor else {
+            unreachable!()
+        };
+        let stats = visitor.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 1));
+    }
+}
@@ -1,6 +1,7 @@
 use std::{ops::Deref, sync::Arc};
 
 use blend::{BlendAlphaFunction, BlendDensityFunction, BlendOffsetFunction};
+use cache::{CacheVisitor, CachedFunction};
 use derive_getters::Getters;
 use end::EndIslandFunction;
 use enum_dispatch::enum_dispatch;
@@ -17,6 +18,7 @@ use crate::world_gen::blender::Blender;
 use super::{clamped_map, perlin::DoublePerlinNoiseParameters, BuiltInNoiseParams};
 
 pub mod blend;
+pub mod cache;
 mod end;
 mod math;
 pub mod noise;
@@ -792,6 +794,7 @@ pub enum DensityFunction<'a> {
     Wierd(WierdScaledFunction<'a>),
     Range(RangeFunction<'a>),
     Wrapper(WrapperFunction<'a>),
+    Cached(CachedFunction<'a>),
 }
 
 impl<'a> DensityFunction<'a> {
@@ -963,6 +966,7 @@ pub trait ApplierImpl<'a> {
 #[enum_dispatch(VisitorImpl)]
 pub enum Visitor<'a> {
     Unwrap(UnwrapVisitor),
+    Cache(CacheVisitor),
     Todo(Unused<'a>),
 }
 
@@ -0,0 +1,90 @@
+//! `pumpkin bench-worldgen [--seed <seed>] [--chunks <count>] [--generator <kind>]` generates
+//! chunks without starting the server and prints how long every stage of the world generation
+//! took, so regressions show up in CI.
+
+use std::time::Duration;
+
+use pumpkin_config::GeneratorKind;
+use pumpkin_world::{bench, Seed};
+
+const USAGE: &str =
+    "Usage: pumpkin bench-worldgen [--seed <seed>] [--chunks <count>] [--generator plains|superflat]";
+const DEFAULT_CHUNKS: usize = 1024;
+
+fn millis(duration: Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64() * 1000.0)
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{message}\n{USAGE}");
+    std::process::exit(2);
+}
+
+fn parse<T: std::str::FromStr>(value: Option<String>, name: &str) -> T {
+    value
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| fail(&format!("{name} needs a valid value")))
+}
+
+/// Runs the benchmark with the arguments after `bench-worldgen`
+pub fn run(mut args: impl Iterator<Item = String>) {
+    // the same seed a world with this seed in its config gets
+    let mut seed = Seed::from("");
+    let mut seed_name = String::new();
+    let mut chunks = DEFAULT_CHUNKS;
+    let mut generator = GeneratorKind::Plains;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => {
+                seed_name = args.next().unwrap_or_else(|| fail("--seed needs a value"));
+                seed = seed_name
+                    .parse()
+                    .map_or_else(|_| Seed::from(seed_name.as_str()), Seed);
+            }
+            "--chunks" => chunks = parse(args.next(), "--chunks"),
+            "--generator" => {
+                generator = match args.next().as_deref() {
+                    Some("plains") => GeneratorKind::Plains,
+                    Some("superflat") => GeneratorKind::Superflat,
+                    _ => fail("--generator needs plains or superflat"),
+                }
+            }
+            _ => fail(&format!("Unknown argument {arg}")),
+        }
+    }
+    if chunks == 0 {
+        fail("--chunks needs at least one chunk");
+    }
+
+    let report = bench::run(seed, generator, chunks);
+    println!(
+        "Generated {} {generator:?} chunks with the seed \"{seed_name}\", setting up took {} ms",
+        report.chunks,
+        millis(report.setup)
+    );
+    println!(
+        "{:<14} {:>12} {:>10} {:>10} {:>10} {:>10} {:>10} {:>12}",
+        "stage", "total ms", "mean ms", "min ms", "p50 ms", "p99 ms", "max ms", "chunks/s"
+    );
+    for stage in &report.stages {
+        println!(
+            "{:<14} {:>12} {:>10} {:>10} {:>10} {:>10} {:>10} {:>12.1}",
+            stage.name,
+            millis(stage.total()),
+            millis(stage.mean()),
+            millis(stage.min()),
+            millis(stage.percentile(50.0)),
+            millis(stage.percentile(99.0)),
+            millis(stage.max()),
+            stage.chunks_per_second()
+        );
+    }
+    let cache = report.cache;
+    println!(
+        "The density caches were hit {} times and missed {} times ({:.1}% hits), holding {} bytes per chunk",
+        cache.hits,
+        cache.misses,
+        cache.hit_rate() * 100.0,
+        cache.bytes / report.chunks
+    );
+}
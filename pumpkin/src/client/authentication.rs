@@ -5,6 +5,7 @@ use pumpkin_config::{auth::TextureConfig, ADVANCED_CONFIG};
 use pumpkin_core::ProfileAction;
 use pumpkin_protocol::Property;
 use reqwest::{StatusCode, Url};
+use rsa::{pkcs8::DecodePublicKey, RsaPublicKey};
 use serde::Deserialize;
 use sha1::Digest;
 use sha2::Sha256;
@@ -59,12 +60,13 @@ pub async fn authenticate(
 ) -> Result<GameProfile, AuthError> {
     assert!(ADVANCED_CONFIG.authentication.enabled);
     let address = if ADVANCED_CONFIG.authentication.prevent_proxy_connections {
+        // the session server rejects the login if the player joined it from another IP
         ADVANCED_CONFIG
             .authentication
-            .auth_url
+            .prevent_proxy_connection_auth_url
             .replace("{username}", username)
             .replace("{server_hash}", server_hash)
-            .replace("{}", &ip.to_string())
+            .replace("{ip}", &ip.to_string())
     } else {
         ADVANCED_CONFIG
             .authentication
@@ -87,6 +89,98 @@ pub async fn authenticate(
     Ok(profile)
 }
 
+#[derive(Deserialize)]
+struct ProfileLookup {
+    id: Uuid,
+    name: String,
+}
+
+/// Looks up the profile of a username with Mojang's API, used for players which are not online.
+///
+/// Only the UUID and the correctly capitalized name are filled in.
+pub async fn lookup_profile(
+    username: &str,
+    auth_client: &reqwest::Client,
+) -> Result<GameProfile, AuthError> {
+    let address = ADVANCED_CONFIG
+        .authentication
+        .profile_lookup_url
+        .replace("{username}", username);
+
+    let response = auth_client
+        .get(address)
+        .send()
+        .await
+        .map_err(|_| AuthError::FailedResponse)?;
+    match response.status() {
+        StatusCode::OK => {}
+        StatusCode::NO_CONTENT | StatusCode::NOT_FOUND => Err(AuthError::UnverifiedUsername)?,
+        other => Err(AuthError::UnknownStatusCode(other))?,
+    }
+    let lookup: ProfileLookup = response.json().await.map_err(|_| AuthError::FailedParse)?;
+    Ok(GameProfile {
+        id: lookup.id,
+        name: lookup.name,
+        properties: vec![],
+        profile_actions: None,
+    })
+}
+
+/// Fetches a profile with its properties like the skin, from Mojang's session server or a skin source.
+pub async fn fetch_profile(
+    address: &str,
+    auth_client: &reqwest::Client,
+) -> Result<GameProfile, AuthError> {
+    let response = auth_client
+        .get(address)
+        .send()
+        .await
+        .map_err(|_| AuthError::FailedResponse)?;
+    match response.status() {
+        StatusCode::OK => {}
+        StatusCode::NO_CONTENT | StatusCode::NOT_FOUND => Err(AuthError::UnverifiedUsername)?,
+        other => Err(AuthError::UnknownStatusCode(other))?,
+    }
+    response.json().await.map_err(|_| AuthError::FailedParse)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PublicKeys {
+    player_certificate_keys: Vec<PublicKey>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PublicKey {
+    public_key: String,
+}
+
+/// Fetches the keys Mojang signs the profile keys of players with, see [`crate::client::chat_session`].
+pub async fn fetch_player_certificate_keys(
+    auth_client: &reqwest::Client,
+) -> Result<Vec<RsaPublicKey>, AuthError> {
+    let response = auth_client
+        .get(&ADVANCED_CONFIG.authentication.public_keys_url)
+        .send()
+        .await
+        .map_err(|_| AuthError::FailedResponse)?;
+    match response.status() {
+        StatusCode::OK => {}
+        other => Err(AuthError::UnknownStatusCode(other))?,
+    }
+    let keys: PublicKeys = response.json().await.map_err(|_| AuthError::FailedParse)?;
+    keys.player_certificate_keys
+        .iter()
+        .map(|key| {
+            let der = general_purpose::STANDARD
+                .decode(&key.public_key)
+                .map_err(|_| AuthError::FailedParse)?;
+            RsaPublicKey::from_public_key_der(&der).map_err(|_| AuthError::FailedParse)
+        })
+        .collect()
+}
+
 pub fn validate_textures(property: &Property, config: &TextureConfig) -> Result<(), TextureError> {
     let from64 = general_purpose::STANDARD
         .decode(&property.value)
@@ -0,0 +1,38 @@
+//! Starts a packet capture for each connection if enabled, see [`pumpkin_protocol::capture`].
+
+use std::{
+    fs::{self, File},
+    io::BufWriter,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use pumpkin_config::ADVANCED_CONFIG;
+use pumpkin_protocol::capture::{PacketCapture, SharedCapture};
+
+/// Creates the capture file of the connection, named after when it started and the client id.
+pub fn start(client_id: u16) -> Option<SharedCapture> {
+    let config = &ADVANCED_CONFIG.packet_capture;
+    if !config.enabled {
+        return None;
+    }
+    let directory = Path::new(&config.directory);
+    let started = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs());
+    let path = directory.join(format!("{started}-{client_id}.capture"));
+
+    let capture = fs::create_dir_all(directory)
+        .and_then(|()| File::create(&path))
+        .and_then(|file| PacketCapture::new(Box::new(BufWriter::new(file))));
+    match capture {
+        Ok(capture) => {
+            log::debug!("Capturing packets of {client_id} to {}", path.display());
+            Some(capture.shared())
+        }
+        Err(error) => {
+            log::warn!("Failed to start packet capture for {client_id}: {error}");
+            None
+        }
+    }
+}
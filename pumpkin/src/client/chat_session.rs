@@ -0,0 +1,392 @@
+//! Signed chat: the sessions players sign their chat messages with, and the checks every message goes through.
+//!
+//! After joining, a client sends its session with a profile key Mojang signed. Every message is signed
+//! together with its index in the chain of the player's messages and the messages the player saw before,
+//! so messages can neither be forged nor taken out of the conversation they were sent in.
+
+use std::{
+    collections::VecDeque,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bytes::Bytes;
+use pumpkin_config::BASIC_CONFIG;
+use pumpkin_protocol::{
+    client::play::MAX_PREVIOUS_MESSAGES,
+    server::play::{SChatMessage, SChatSessionUpdate},
+};
+use rsa::{pkcs8::DecodePublicKey, Pkcs1v15Sign, RsaPublicKey};
+use sha1::{Digest, Sha1};
+use sha2::Sha256;
+use thiserror::Error;
+use tokio::sync::OnceCell;
+use uuid::Uuid;
+
+use super::authentication::{self, AuthError};
+
+/// The DER `DigestInfo` prefixes of the hashes, as the `sha1` and `sha2` crates don't know their OIDs
+const SHA1_PREFIX: [u8; 15] = [
+    0x30, 0x21, 0x30, 0x09, 0x06, 0x05, 0x2b, 0x0e, 0x03, 0x02, 0x1a, 0x05, 0x00, 0x04, 0x14,
+];
+const SHA256_PREFIX: [u8; 19] = [
+    0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05,
+    0x00, 0x04, 0x20,
+];
+
+/// The most chat messages a player may not have acknowledged yet before being kicked
+const MAX_PENDING_MESSAGES: usize = 4096;
+
+/// Mojang's keys, fetched once the first player sends a session
+static MOJANG_KEYS: OnceCell<Vec<RsaPublicKey>> = OnceCell::const_new();
+
+/// Whether players have to sign their chat messages, which is only possible in online mode
+#[must_use]
+pub fn enforces_secure_chat() -> bool {
+    BASIC_CONFIG.enforce_secure_profile && BASIC_CONFIG.online_mode
+}
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_millis() as i64)
+}
+
+#[derive(Error, Debug)]
+pub enum SessionError {
+    #[error("Expired profile public key. Check that your system time is synchronized, and try restarting your game.")]
+    Expired,
+    #[error("Unable to parse profile public key.")]
+    InvalidKey,
+    #[error("Invalid signature for profile public key.\nTry restarting your game.")]
+    InvalidSignature,
+    #[error("Could not verify the profile public key: {0}")]
+    MojangKeys(AuthError),
+}
+
+/// Why a chat message was rejected, the message is shown to the player
+#[derive(Error, Debug)]
+pub enum ChatError {
+    #[error("Chat disabled due to missing profile public key. Please try reconnecting.")]
+    MissingProfileKey,
+    #[error("Chat disabled due to expired profile public key. Please try reconnecting.")]
+    ExpiredProfileKey,
+    #[error("Chat disabled due to broken chain. Please try reconnecting.")]
+    ChainBroken,
+    #[error("Out-of-order chat packet received. Did your system time change?")]
+    OutOfOrder,
+    #[error("Chat message validation failure")]
+    InvalidSignature,
+    /// The acknowledged messages don't match the messages we sent, the reason is only logged
+    #[error("Chat message validation failure")]
+    LastSeen(String),
+    #[error("Too many unacknowledged chat messages")]
+    TooManyPending,
+}
+
+impl ChatError {
+    /// Whether the player is kicked, otherwise they are only told their message was not sent
+    #[must_use]
+    pub const fn disconnects(&self) -> bool {
+        !matches!(self, Self::MissingProfileKey | Self::ExpiredProfileKey)
+    }
+}
+
+/// The session a player signs their chat messages with
+pub struct ChatSession {
+    pub session_id: Uuid,
+    /// Milliseconds since the unix epoch
+    pub expires_at: i64,
+    key: RsaPublicKey,
+    /// The key in DER, as sent to other players
+    pub public_key: Bytes,
+    /// Mojang's signature of the key
+    pub key_signature: Bytes,
+}
+
+impl ChatSession {
+    /// Checks that Mojang signed the key for the player.
+    pub async fn verify(
+        player: Uuid,
+        update: SChatSessionUpdate,
+        auth_client: &reqwest::Client,
+    ) -> Result<Self, SessionError> {
+        if update.expires_at < now_millis() {
+            return Err(SessionError::Expired);
+        }
+        let key = RsaPublicKey::from_public_key_der(&update.public_key)
+            .map_err(|_| SessionError::InvalidKey)?;
+
+        let mut signed = Vec::with_capacity(24 + update.public_key.len());
+        signed.extend_from_slice(player.as_bytes());
+        signed.extend_from_slice(&update.expires_at.to_be_bytes());
+        signed.extend_from_slice(&update.public_key);
+        let hash = Sha1::digest(&signed);
+
+        let mojang_keys = MOJANG_KEYS
+            .get_or_try_init(|| authentication::fetch_player_certificate_keys(auth_client))
+            .await
+            .map_err(SessionError::MojangKeys)?;
+        let signed_by_mojang = mojang_keys.iter().any(|mojang_key| {
+            mojang_key
+                .verify(
+                    Pkcs1v15Sign {
+                        hash_len: Some(20),
+                        prefix: Box::new(SHA1_PREFIX),
+                    },
+                    &hash,
+                    &update.key_signature,
+                )
+                .is_ok()
+        });
+        if !signed_by_mojang {
+            return Err(SessionError::InvalidSignature);
+        }
+
+        Ok(Self {
+            session_id: update.session_id,
+            expires_at: update.expires_at,
+            key,
+            public_key: update.public_key,
+            key_signature: update.key_signature,
+        })
+    }
+
+    #[must_use]
+    pub fn has_expired(&self) -> bool {
+        self.expires_at < now_millis()
+    }
+
+    /// The session as it is sent in the player info
+    #[must_use]
+    pub fn to_protocol(&self) -> pumpkin_protocol::client::play::ChatSession<'_> {
+        pumpkin_protocol::client::play::ChatSession {
+            session_id: self.session_id,
+            expires_at: self.expires_at,
+            public_key: &self.public_key,
+            key_signature: &self.key_signature,
+        }
+    }
+
+    fn verify_message(
+        &self,
+        sender: Uuid,
+        index: i32,
+        message: &SChatMessage,
+        signature: &[u8],
+        last_seen: &[Bytes],
+    ) -> bool {
+        let mut hasher = Sha256::new();
+        // the version of the signed data
+        hasher.update(1i32.to_be_bytes());
+        hasher.update(sender.as_bytes());
+        hasher.update(self.session_id.as_bytes());
+        hasher.update(index.to_be_bytes());
+        hasher.update(message.salt.to_be_bytes());
+        hasher.update(message.timestamp.div_euclid(1000).to_be_bytes());
+        hasher.update((message.message.len() as i32).to_be_bytes());
+        hasher.update(message.message.as_bytes());
+        hasher.update((last_seen.len() as i32).to_be_bytes());
+        for seen in last_seen {
+            hasher.update(seen);
+        }
+        self.key
+            .verify(
+                Pkcs1v15Sign {
+                    hash_len: Some(32),
+                    prefix: Box::new(SHA256_PREFIX),
+                },
+                &hasher.finalize(),
+                signature,
+            )
+            .is_ok()
+    }
+}
+
+/// A chat message which passed the checks
+pub struct UnpackedMessage {
+    /// The index in the sender's chain, always 0 for unsigned messages
+    pub index: i32,
+    pub signature: Option<Bytes>,
+    /// The signatures of the messages the sender saw before
+    pub last_seen: Vec<Bytes>,
+}
+
+struct TrackedMessage {
+    signature: Bytes,
+    /// Whether the player has not acknowledged it yet
+    pending: bool,
+}
+
+/// Tracks which of the messages we sent the player acknowledged, like vanilla's `LastSeenMessagesValidator`.
+///
+/// The first [`MAX_PREVIOUS_MESSAGES`] entries are the window the player's acknowledgements refer to,
+/// messages after it are still pending.
+struct LastSeenValidator {
+    tracked: VecDeque<Option<TrackedMessage>>,
+    last_pending: Option<Bytes>,
+}
+
+impl Default for LastSeenValidator {
+    fn default() -> Self {
+        Self {
+            tracked: std::iter::repeat_with(|| None)
+                .take(MAX_PREVIOUS_MESSAGES)
+                .collect(),
+            last_pending: None,
+        }
+    }
+}
+
+impl LastSeenValidator {
+    fn add_pending(&mut self, signature: &Bytes) -> Result<(), ChatError> {
+        if self.last_pending.as_ref() == Some(signature) {
+            return Ok(());
+        }
+        if self.tracked.len() - MAX_PREVIOUS_MESSAGES >= MAX_PENDING_MESSAGES {
+            return Err(ChatError::TooManyPending);
+        }
+        self.tracked.push_back(Some(TrackedMessage {
+            signature: signature.clone(),
+            pending: true,
+        }));
+        self.last_pending = Some(signature.clone());
+        Ok(())
+    }
+
+    fn apply_offset(&mut self, offset: i32) -> Result<(), ChatError> {
+        let max = self.tracked.len() - MAX_PREVIOUS_MESSAGES;
+        match usize::try_from(offset) {
+            Ok(offset) if offset <= max => {
+                self.tracked.drain(..offset);
+                Ok(())
+            }
+            _ => Err(ChatError::LastSeen(format!(
+                "advanced the last seen window by {offset} messages, beyond the {max} pending ones"
+            ))),
+        }
+    }
+
+    /// Returns the signatures of the acknowledged messages.
+    fn apply_update(&mut self, offset: i32, acknowledged: &[u8]) -> Result<Vec<Bytes>, ChatError> {
+        self.apply_offset(offset)?;
+        let is_acknowledged = |index: usize| {
+            acknowledged
+                .get(index / 8)
+                .is_some_and(|byte| byte >> (index % 8) & 1 == 1)
+        };
+        if (MAX_PREVIOUS_MESSAGES..acknowledged.len() * 8).any(is_acknowledged) {
+            return Err(ChatError::LastSeen(
+                "acknowledged more messages than fit in the window".to_string(),
+            ));
+        }
+
+        let mut last_seen = Vec::new();
+        for (index, entry) in self
+            .tracked
+            .iter_mut()
+            .take(MAX_PREVIOUS_MESSAGES)
+            .enumerate()
+        {
+            if is_acknowledged(index) {
+                let Some(message) = entry else {
+                    return Err(ChatError::LastSeen(format!(
+                        "acknowledged an unknown or ignored message at {index}"
+                    )));
+                };
+                message.pending = false;
+                last_seen.push(message.signature.clone());
+            } else {
+                if entry.as_ref().is_some_and(|message| !message.pending) {
+                    return Err(ChatError::LastSeen(format!(
+                        "ignored the previously acknowledged message at {index}"
+                    )));
+                }
+                *entry = None;
+            }
+        }
+        Ok(last_seen)
+    }
+}
+
+/// The signed chat state of a player
+#[derive(Default)]
+pub struct ChatState {
+    session: Option<Arc<ChatSession>>,
+    /// The index of the player's next message, None once the chain broke
+    next_index: Option<i32>,
+    /// The timestamp of the last message, in milliseconds
+    last_timestamp: i64,
+    last_seen: LastSeenValidator,
+}
+
+impl ChatState {
+    #[must_use]
+    pub fn session(&self) -> Option<Arc<ChatSession>> {
+        self.session.clone()
+    }
+
+    /// Starts a new chain with the session.
+    pub fn set_session(&mut self, session: Arc<ChatSession>) {
+        self.session = Some(session);
+        self.next_index = Some(0);
+        self.last_timestamp = 0;
+    }
+
+    /// Checks the acknowledged messages and the signature of a message the player sent.
+    pub fn unpack(
+        &mut self,
+        sender: Uuid,
+        message: &SChatMessage,
+    ) -> Result<UnpackedMessage, ChatError> {
+        let last_seen = self
+            .last_seen
+            .apply_update(message.message_count.0, &message.acknowledged)?;
+
+        let Some(session) = &self.session else {
+            if enforces_secure_chat() {
+                return Err(ChatError::MissingProfileKey);
+            }
+            return Ok(UnpackedMessage {
+                index: 0,
+                signature: None,
+                last_seen,
+            });
+        };
+        let Some(signature) = &message.signature else {
+            return Err(ChatError::MissingProfileKey);
+        };
+        if session.has_expired() {
+            return Err(ChatError::ExpiredProfileKey);
+        }
+        let Some(index) = self.next_index else {
+            return Err(ChatError::ChainBroken);
+        };
+        if message.timestamp < self.last_timestamp {
+            self.next_index = None;
+            return Err(ChatError::OutOfOrder);
+        }
+        self.last_timestamp = message.timestamp;
+        if !session.verify_message(sender, index, message, signature, &last_seen) {
+            self.next_index = None;
+            return Err(ChatError::InvalidSignature);
+        }
+        self.next_index = Some(index + 1);
+
+        Ok(UnpackedMessage {
+            index,
+            signature: Some(signature.clone()),
+            last_seen,
+        })
+    }
+
+    /// Remembers a message we send the player, which they have to acknowledge.
+    pub fn add_pending(&mut self, signature: &Bytes) -> Result<(), ChatError> {
+        self.last_seen.add_pending(signature)
+    }
+
+    /// Applies an acknowledgement the player sent without a message.
+    pub fn acknowledge(&mut self, offset: i32) -> Result<(), ChatError> {
+        self.last_seen.apply_offset(offset)
+    }
+}
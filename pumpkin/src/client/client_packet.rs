@@ -1,24 +1,38 @@
 use num_traits::FromPrimitive;
-use pumpkin_config::{ADVANCED_CONFIG, BASIC_CONFIG};
+use pumpkin_config::{runtime_config, ADVANCED_CONFIG, BASIC_CONFIG};
 use pumpkin_core::text::TextComponent;
 use pumpkin_protocol::{
     client::{
-        config::{CConfigAddResourcePack, CFinishConfig, CKnownPacks, CRegistryData},
+        config::{
+            CConfigAddResourcePack, CCustomReportDetails, CFinishConfig, CKnownPacks,
+            CRegistryData, CServerLinks, CUpdateTags, Label, LinkType, ServerLink,
+        },
         login::{CLoginSuccess, CSetCompression},
         status::CPingResponse,
     },
     server::{
-        config::{SClientInformationConfig, SKnownPacks, SPluginMessage},
+        config::{SClientInformationConfig, SKnownPacks},
         handshake::SHandShake,
         login::{SEncryptionResponse, SLoginPluginResponse, SLoginStart},
         status::SStatusPingRequest,
     },
     ConnectionState, KnownPack, CURRENT_MC_PROTOCOL,
 };
+use pumpkin_registry::network_tags;
 use uuid::Uuid;
 
 use crate::{
-    client::authentication::{self, offline_uuid, validate_textures, GameProfile},
+    client::{
+        authentication::{self, offline_uuid, validate_textures, GameProfile},
+        translation,
+    },
+    data::{
+        banned_ip_data::BANNED_IP_LIST,
+        banned_player_data::BANNED_PLAYER_LIST,
+        op_data::OPERATOR_CONFIG,
+        user_cache_data::USER_CACHE_CONFIG,
+        whitelist_data::{is_whitelist_enabled, WHITELIST_CONFIG},
+    },
     entity::player::{ChatMode, Hand},
     proxy::{
         bungeecord,
@@ -27,7 +41,7 @@ use crate::{
     server::{Server, CURRENT_MC_VERSION},
 };
 
-use super::{authentication::AuthError, Client, PlayerConfig};
+use super::{authentication::AuthError, Client, EncryptionError, PlayerConfig};
 
 /// Processes incoming Packets from the Client to the Server
 /// Implements the `Client` Packets
@@ -45,7 +59,15 @@ impl Client {
             let protocol = version;
             match protocol.cmp(&(CURRENT_MC_PROTOCOL as i32)) {
                 std::cmp::Ordering::Less => {
-                    self.kick(&format!("Client outdated ({protocol}), Server uses Minecraft {CURRENT_MC_VERSION}, Protocol {CURRENT_MC_PROTOCOL}")).await;
+                    if let Some(translator) = u32::try_from(protocol)
+                        .ok()
+                        .and_then(translation::translator)
+                    {
+                        log::debug!("Translating packets for protocol {protocol}");
+                        let _ = self.translator.set(translator);
+                    } else {
+                        self.kick(&format!("Client outdated ({protocol}), Server uses Minecraft {CURRENT_MC_VERSION}, Protocol {CURRENT_MC_PROTOCOL}")).await;
+                    }
                 }
                 std::cmp::Ordering::Equal => {}
                 std::cmp::Ordering::Greater => {
@@ -75,13 +97,34 @@ impl Client {
                 .all(|c| c > 32_u8 as char && c < 127_u8 as char)
     }
 
+    /// Counts the login towards the rate limits of the client's IP, behind a proxy it has to be
+    /// forwarded first and its connection is counted now. Kicks the client if it is limited
+    async fn allow_login(&self, server: &Server) -> bool {
+        let just_forwarded = !self
+            .address_forwarded
+            .swap(true, std::sync::atomic::Ordering::Relaxed);
+        let ip = self.address.lock().await.ip();
+        let allowed = (!just_forwarded || server.throttle.allow_connection(ip))
+            && server.throttle.allow_login(ip);
+        if !allowed {
+            self.kick("You are logging in too fast, please try again later")
+                .await;
+        }
+        allowed
+    }
+
     pub async fn handle_login_start(&self, server: &Server, login_start: SLoginStart) {
         log::debug!("login start");
 
+        let proxy = &ADVANCED_CONFIG.proxy;
+        if !proxy.enabled && !self.allow_login(server).await {
+            return;
+        }
+
         // Don't allow new logons when server is full.
         // If max players is set to zero, then there is no max player count enforced.
         // TODO: If client is an operator or otherwise suitable elevated permissions, allow client to bypass this requirement.
-        let max_players = BASIC_CONFIG.max_players;
+        let max_players = runtime_config().max_players;
         if max_players > 0 && server.get_player_count().await >= max_players as usize {
             self.kick("The server is currently full, please try again later")
                 .await;
@@ -95,14 +138,18 @@ impl Client {
         // default game profile, when no online mode
         // TODO: make offline uuid
         let mut gameprofile = self.gameprofile.lock().await;
-        let proxy = &ADVANCED_CONFIG.proxy;
         if proxy.enabled {
             if proxy.velocity.enabled {
-                velocity_login(self).await;
+                let message_id = velocity_login(self).await;
+                self.velocity_message_id.store(Some(message_id));
             } else if proxy.bungeecord.enabled {
                 match bungeecord::bungeecord_login(self, login_start.name).await {
-                    Ok((_ip, profile)) => {
-                        // self.address.lock() = ip;
+                    Ok((ip, profile)) => {
+                        // the address has to be known before finishing the login to check IP bans
+                        self.address.lock().await.set_ip(ip);
+                        if !self.allow_login(server).await {
+                            return;
+                        }
                         self.finish_login(&profile).await;
                         *gameprofile = Some(profile);
                     }
@@ -116,15 +163,19 @@ impl Client {
                 offline_uuid(&login_start.name).expect("This is very not safe and bad")
             };
 
-            let profile = GameProfile {
+            let mut profile = GameProfile {
                 id,
                 name: login_start.name,
                 properties: vec![],
                 profile_actions: None,
             };
+            if !BASIC_CONFIG.online_mode {
+                server.profile_cache.add_offline_skin(&mut profile).await;
+            }
 
             if BASIC_CONFIG.encryption {
                 let verify_token: [u8; 4] = rand::random();
+                self.verify_token.store(Some(verify_token));
                 self.send_packet(
                     &server.encryption_request(&verify_token, BASIC_CONFIG.online_mode),
                 )
@@ -146,7 +197,27 @@ impl Client {
         encryption_response: SEncryptionResponse,
     ) {
         log::debug!("Handling encryption");
-        let shared_secret = server.decrypt(&encryption_response.shared_secret).unwrap();
+        // only answer the request we sent, and only once
+        let Some(verify_token) = self.verify_token.take() else {
+            self.kick("Unexpected encryption response").await;
+            return;
+        };
+        // proves the client encrypted with our public key and did not replay an old response
+        match server.decrypt(&encryption_response.verify_token) {
+            Ok(token) if token == verify_token => {}
+            _ => {
+                self.kick(&EncryptionError::InvalidVerifyToken.to_string())
+                    .await;
+                return;
+            }
+        }
+        let shared_secret = match server.decrypt(&encryption_response.shared_secret) {
+            Ok(shared_secret) => shared_secret,
+            Err(error) => {
+                self.kick(&error.to_string()).await;
+                return;
+            }
+        };
 
         if let Err(error) = self.set_encryption(Some(&shared_secret)).await {
             self.kick(&error.to_string()).await;
@@ -203,10 +274,34 @@ impl Client {
     }
 
     async fn finish_login(&self, profile: &GameProfile) {
+        if let Some(reason) = self.login_rejection(profile).await {
+            self.kick(&reason).await;
+            return;
+        }
+        USER_CACHE_CONFIG.write().await.add(profile);
         let packet = CLoginSuccess::new(&profile.id, &profile.name, &profile.properties);
         self.send_packet(&packet).await;
     }
 
+    /// Checks the ban lists and the whitelist, returns the kick message if the player may not join
+    async fn login_rejection(&self, profile: &GameProfile) -> Option<String> {
+        if let Some(entry) = BANNED_PLAYER_LIST.write().await.get_entry(profile.id) {
+            return Some(entry.kick_message());
+        }
+        let ip = self.address.lock().await.ip();
+        if let Some(entry) = BANNED_IP_LIST.write().await.get_entry(&ip) {
+            return Some(entry.kick_message());
+        }
+        // operators may always join
+        if is_whitelist_enabled()
+            && !WHITELIST_CONFIG.read().await.is_whitelisted(profile.id)
+            && OPERATOR_CONFIG.read().await.get(profile.id).is_none()
+        {
+            return Some("You are not white-listed on this server!".to_string());
+        }
+        None
+    }
+
     async fn authenticate(
         &self,
         server: &Server,
@@ -251,20 +346,31 @@ impl Client {
         Err(AuthError::MissingAuthClient)
     }
 
-    pub async fn handle_plugin_response(&self, plugin_response: SLoginPluginResponse) {
+    pub async fn handle_plugin_response(
+        &self,
+        server: &Server,
+        plugin_response: SLoginPluginResponse,
+    ) {
         log::debug!("Handling plugin");
         let velocity_config = &ADVANCED_CONFIG.proxy.velocity;
         if velocity_config.enabled {
-            let mut address = self.address.lock().await;
+            let port = self.address.lock().await.port();
+            // only answer the request we sent, and only once
+            let expected_id = self.velocity_message_id.take();
             match velocity::receive_velocity_plugin_response(
-                address.port(),
+                port,
                 velocity_config,
+                expected_id,
                 plugin_response,
             ) {
                 Ok((profile, new_address)) => {
+                    // the address has to be known before finishing the login to check IP bans
+                    *self.address.lock().await = new_address;
+                    if !self.allow_login(server).await {
+                        return;
+                    }
                     self.finish_login(&profile).await;
                     *self.gameprofile.lock().await = Some(profile);
-                    *address = new_address;
                 }
                 Err(error) => self.kick(&error.to_string()).await,
             }
@@ -275,6 +381,7 @@ impl Client {
         log::debug!("Handling login acknowledged");
         self.connection_state.store(ConnectionState::Config);
         self.send_packet(&server.get_branding()).await;
+        self.send_registered_channels().await;
 
         let resource_config = &ADVANCED_CONFIG.resource_pack;
         if resource_config.enabled {
@@ -296,6 +403,49 @@ impl Client {
             self.send_packet(&resource_pack).await;
         }
 
+        let links_config = &ADVANCED_CONFIG.server_links;
+        if links_config.enabled {
+            let links = [
+                (LinkType::BugReport, &links_config.bug_report),
+                (
+                    LinkType::CommunityGuidelines,
+                    &links_config.community_guidelines,
+                ),
+                (LinkType::Support, &links_config.support),
+                (LinkType::Status, &links_config.status),
+                (LinkType::Feedback, &links_config.feedback),
+                (LinkType::Community, &links_config.community),
+                (LinkType::Website, &links_config.website),
+                (LinkType::Forums, &links_config.forums),
+                (LinkType::News, &links_config.news),
+                (LinkType::Announcements, &links_config.announcements),
+            ]
+            .into_iter()
+            .map(|(link_type, url)| (Label::BuiltIn(link_type), url))
+            .chain(links_config.custom.iter().map(|(label, url)| {
+                (
+                    Label::Custom(Box::new(TextComponent::translate(label.as_str(), vec![]))),
+                    url,
+                )
+            }))
+            .filter(|(_, url)| !url.is_empty())
+            .map(|(label, url)| ServerLink { label, url })
+            .collect::<Vec<_>>();
+            if !links.is_empty() {
+                self.send_packet(&CServerLinks::new(&links)).await;
+            }
+        }
+
+        let details_config = &ADVANCED_CONFIG.report_details;
+        if details_config.enabled && !details_config.details.is_empty() {
+            let details = details_config
+                .details
+                .iter()
+                .map(|(title, description)| (title.as_str(), description.as_str()))
+                .collect::<Vec<_>>();
+            self.send_packet(&CCustomReportDetails::new(&details)).await;
+        }
+
         // known data packs
         self.send_packet(&CKnownPacks::new(&[KnownPack {
             namespace: "minecraft",
@@ -329,28 +479,21 @@ impl Client {
         }
     }
 
-    pub async fn handle_plugin_message(&self, plugin_message: SPluginMessage) {
-        log::debug!("Handling plugin message");
-        if plugin_message.channel.starts_with("minecraft:brand")
-            || plugin_message.channel.starts_with("MC|Brand")
-        {
-            log::debug!("got a client brand");
-            match String::from_utf8(plugin_message.data) {
-                Ok(brand) => *self.brand.lock().await = Some(brand),
-                Err(e) => self.kick(&e.to_string()).await,
-            }
-        }
-    }
-
     pub async fn handle_known_packs(&self, server: &Server, _config_acknowledged: SKnownPacks) {
         log::debug!("Handling known packs");
-        for registry in &server.cached_registry {
+        self.send_registries_and_finish(server).await;
+    }
+
+    /// Sends the synced registries and finishes the configuration.
+    pub async fn send_registries_and_finish(&self, server: &Server) {
+        for registry in server.cached_registry.read().await.iter() {
             self.send_packet(&CRegistryData::new(
                 &registry.registry_id,
                 &registry.registry_entries,
             ))
             .await;
         }
+        self.send_packet(&CUpdateTags::new(&network_tags())).await;
 
         // We are done with configuring
         log::debug!("finished config");
@@ -1,18 +1,32 @@
-use std::f32::consts::PI;
+use std::{f32::consts::PI, sync::atomic::Ordering};
 
-use pumpkin_core::math::vector3::Vector3;
+use pumpkin_config::KnockbackConfig;
+use pumpkin_core::{
+    math::{boundingbox::BoundingBox, vector3::Vector3},
+    GameMode,
+};
 use pumpkin_macros::{particle, sound};
 use pumpkin_protocol::{
-    client::play::{CEntityVelocity, CParticle},
+    client::play::{CCooldown, CEntityStatus, CEntityVelocity, Particle},
     SoundCategory, VarInt,
 };
-use pumpkin_world::item::ItemStack;
+use pumpkin_world::item::{component::DataComponent, item_registry::get_item_by_id, ItemStack};
 
 use crate::{
-    entity::{player::Player, Entity},
+    entity::{
+        damage::{DamageSource, PLAYER_ATTACK},
+        data_tracker,
+        player::{Hand, Player},
+        Entity,
+    },
     world::World,
 };
 
+/// How long a shield has to be raised before it blocks, in ticks
+const SHIELD_BLOCK_DELAY: u32 = 5;
+/// How long an axe disables the shield it hits, in ticks
+const SHIELD_DISABLE_TICKS: u32 = 100;
+
 #[derive(Debug, Clone, Copy)]
 pub enum AttackType {
     Knockback,
@@ -23,11 +37,15 @@ pub enum AttackType {
 }
 
 impl AttackType {
-    pub async fn new(player: &Player, attack_cooldown_progress: f32) -> Self {
+    /// Attacks while sprinting knock back further, falling ones are critical hits.
+    /// With `legacy` combat sweeping attacks don't exist
+    pub async fn new(player: &Player, attack_cooldown_progress: f32, legacy: bool) -> Self {
         let entity = &player.living_entity.entity;
 
-        let sprinting = entity.sprinting.load(std::sync::atomic::Ordering::Relaxed);
-        let on_ground = entity.on_ground.load(std::sync::atomic::Ordering::Relaxed);
+        let sprinting = entity.sprinting.load(Ordering::Relaxed);
+        let on_ground = entity.on_ground.load(Ordering::Relaxed);
+        let falling = player.living_entity.fall_distance.load() > 0.0;
+        let riding = player.vehicle.lock().await.is_some();
         let sword = player
             .inventory
             .lock()
@@ -40,15 +58,13 @@ impl AttackType {
             return Self::Knockback;
         }
 
-        // TODO: even more checks
-        if is_strong && !on_ground {
-            // !sprinting omitted
+        // TODO: climbing, water and blindness checks
+        if is_strong && falling && !on_ground && !riding {
             return Self::Critical;
         }
 
         // TODO: movement speed check
-        if sword && is_strong {
-            // !is_crit, !is_knockback_hit, on_ground omitted
+        if sword && is_strong && on_ground && !legacy {
             return Self::Sweeping;
         }
 
@@ -60,19 +76,275 @@ impl AttackType {
     }
 }
 
+/// The level of the enchantment on the item the player holds, 0 if it isn't enchanted with it
+pub async fn held_enchantment(player: &Player, enchantment: &str) -> i32 {
+    let inventory = player.inventory.lock().await;
+    match inventory
+        .held_item()
+        .and_then(|stack| stack.components.get("minecraft:enchantments"))
+    {
+        Some(DataComponent::Enchantments(enchantments)) => {
+            enchantments.levels.get(enchantment).copied().unwrap_or(0)
+        }
+        _ => 0,
+    }
+}
+
+/// The bonus of the held item to the attribute, e.g. a sword's `minecraft:attack_damage`
+pub async fn held_attribute_bonus(player: &Player, attribute: &str) -> f64 {
+    let inventory = player.inventory.lock().await;
+    inventory
+        .held_item()
+        .and_then(|stack| get_item_by_id(stack.item_id))
+        .map_or(0.0, |item| item.attribute_bonus(attribute))
+}
+
+/// The damage of an attack like vanilla calculates it. Attacks before the weapon recharged deal
+/// less damage, unless `legacy` combat is used
+#[expect(clippy::cast_precision_loss)]
+pub async fn attack_damage(
+    player: &Player,
+    attack_cooldown_progress: f32,
+    attack_type: AttackType,
+    legacy: bool,
+) -> f32 {
+    let mut damage = 1.0 + held_attribute_bonus(player, "minecraft:attack_damage").await as f32;
+    let sharpness = held_enchantment(player, "minecraft:sharpness").await;
+    let mut enchantment_damage = if sharpness > 0 {
+        0.5 * sharpness as f32 + 0.5
+    } else {
+        0.0
+    };
+    if !legacy {
+        damage *= 0.2 + attack_cooldown_progress * attack_cooldown_progress * 0.8;
+        enchantment_damage *= attack_cooldown_progress;
+    }
+    if matches!(attack_type, AttackType::Critical) {
+        damage *= 1.5;
+    }
+    damage + enchantment_damage
+}
+
+/// Hits the players standing next to the victim, with a part of the damage depending on the
+/// sweeping edge enchantment
+#[expect(clippy::cast_precision_loss)]
+pub async fn sweep(attacker: &Player, victim: &Player, damage: f32) {
+    let world = &attacker.living_entity.entity.world;
+    let level = held_enchantment(attacker, "minecraft:sweeping_edge").await;
+    let ratio = level as f32 / (level as f32 + 1.0);
+    let sweep_damage = ratio.mul_add(damage, 1.0);
+
+    let victim_pos = victim.living_entity.entity.pos.load();
+    let victim_box = BoundingBox::new_from_pos(
+        victim_pos.x,
+        victim_pos.y,
+        victim_pos.z,
+        &victim.living_entity.entity.bounding_box_size.load(),
+    );
+    let area = BoundingBox {
+        min_x: victim_box.min_x - 1.0,
+        min_y: victim_box.min_y - 0.25,
+        min_z: victim_box.min_z - 1.0,
+        max_x: victim_box.max_x + 1.0,
+        max_y: victim_box.max_y + 0.25,
+        max_z: victim_box.max_z + 1.0,
+    };
+    let attacker_pos = attacker.living_entity.entity.pos.load();
+    let yaw = attacker.living_entity.entity.yaw.load().to_radians();
+    for other in world.players().await.iter() {
+        if other.entity_id() == attacker.entity_id()
+            || other.entity_id() == victim.entity_id()
+            || other.living_entity.health.load() <= 0.0
+            || other.gamemode.load() == GameMode::Spectator
+        {
+            continue;
+        }
+        let pos = other.living_entity.entity.pos.load();
+        let other_box = BoundingBox::new_from_pos(
+            pos.x,
+            pos.y,
+            pos.z,
+            &other.living_entity.entity.bounding_box_size.load(),
+        );
+        if !other_box.intersects(&area) || pos.sub(&attacker_pos).length_squared() >= 9.0 {
+            continue;
+        }
+        if blocks_with_shield(other, attacker_pos)
+            || !other.living_entity.check_damage(sweep_damage)
+        {
+            continue;
+        }
+        other
+            .living_entity
+            .damage(
+                sweep_damage,
+                DamageSource::by(PLAYER_ATTACK, attacker.entity_id()),
+            )
+            .await;
+        let entity = &other.living_entity.entity;
+        entity.knockback(
+            0.4,
+            0.4,
+            f64::from(yaw.sin()),
+            f64::from(-yaw.cos()),
+            &KnockbackConfig::default(),
+        );
+        let velocity = entity.velocity.load();
+        other.movement_check.lock().add_velocity(velocity);
+        other
+            .client
+            .send_packet(&CEntityVelocity::new(
+                &VarInt(entity.entity_id),
+                velocity.x as f32,
+                velocity.y as f32,
+                velocity.z as f32,
+            ))
+            .await;
+    }
+}
+
+/// Whether the player holds up a shield, which blocks attacks from the front
+pub fn blocks_with_shield(player: &Player, attacker_pos: Vector3<f64>) -> bool {
+    if !player
+        .raised_shield
+        .load()
+        .is_some_and(|(_, ticks)| ticks >= SHIELD_BLOCK_DELAY)
+    {
+        return false;
+    }
+    let entity = &player.living_entity.entity;
+    let yaw = f64::from(entity.head_yaw.load()).to_radians();
+    let view = Vector3::new(-yaw.sin(), 0.0, yaw.cos());
+    let pos = entity.pos.load();
+    let incoming = Vector3::new(pos.x - attacker_pos.x, 0.0, pos.z - attacker_pos.z);
+    if incoming.length_squared() == 0.0 {
+        return false;
+    }
+    let incoming = incoming.normalize();
+    incoming.x.mul_add(view.x, incoming.z * view.z) < 0.0
+}
+
+impl Player {
+    /// Raises a shield held in the hand, it blocks attacks after a few ticks
+    pub async fn raise_shield(&self, hand: Hand) {
+        if self.shield_cooldown.load(Ordering::Relaxed) > 0 {
+            return;
+        }
+        self.raised_shield.store(Some((hand, 0)));
+        let flags = match hand {
+            Hand::Main => 0x01,
+            Hand::Off => 0x03,
+        };
+        self.living_entity
+            .entity
+            .data_tracker
+            .lock()
+            .set(&data_tracker::LIVING_FLAGS, flags);
+    }
+
+    pub fn lower_shield(&self) {
+        if self.raised_shield.take().is_some() {
+            self.living_entity
+                .entity
+                .data_tracker
+                .lock()
+                .set(&data_tracker::LIVING_FLAGS, 0);
+        }
+    }
+
+    /// Called every tick, counts how long the shield is raised and its cooldown
+    pub fn tick_shield(&self) {
+        if let Some((hand, ticks)) = self.raised_shield.load() {
+            self.raised_shield
+                .store(Some((hand, ticks.saturating_add(1))));
+        }
+        let _ = self
+            .shield_cooldown
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |ticks| {
+                ticks.checked_sub(1)
+            });
+    }
+
+    /// Blocks an attack with the raised shield, an axe disables it for a while
+    pub async fn block_with_shield(&self, attacker: &Player) {
+        let world = &self.living_entity.entity.world;
+        let axe = attacker
+            .inventory
+            .lock()
+            .await
+            .held_item()
+            .is_some_and(ItemStack::is_axe);
+        if axe {
+            self.lower_shield();
+            self.shield_cooldown
+                .store(SHIELD_DISABLE_TICKS, Ordering::Relaxed);
+            self.client
+                .send_packet(&CCooldown::new(
+                    "minecraft:shield",
+                    (SHIELD_DISABLE_TICKS as i32).into(),
+                ))
+                .await;
+            world
+                .broadcast_packet_all(&CEntityStatus::new(self.entity_id(), 30))
+                .await;
+        } else {
+            world
+                .broadcast_packet_all(&CEntityStatus::new(self.entity_id(), 29))
+                .await;
+            // the attacker bounces off the shield
+            handle_knockback(
+                &self.living_entity.entity,
+                attacker,
+                &attacker.living_entity.entity,
+                0,
+                &KnockbackConfig::default(),
+            )
+            .await;
+        }
+    }
+}
+
+/// The knockback resistance of the armor the player wears, from 0 to 1
+pub async fn knockback_resistance(player: &Player) -> f64 {
+    let inventory = player.inventory.lock().await;
+    let resistance: f64 = inventory
+        .armor()
+        .iter()
+        .flatten()
+        .filter_map(|stack| get_item_by_id(stack.item_id))
+        .map(|item| item.attribute_bonus("minecraft:knockback_resistance"))
+        .sum();
+    resistance.clamp(0.0, 1.0)
+}
+
 pub async fn handle_knockback(
     attacker_entity: &Entity,
     victim: &Player,
     victim_entity: &Entity,
-    strength: f64,
+    bonus_levels: u32,
+    profile: &KnockbackConfig,
 ) {
+    let resistance = if profile.apply_knockback_resistance {
+        knockback_resistance(victim).await
+    } else {
+        0.0
+    };
+    let scale = 1.0 - resistance;
+    if scale <= 0.0 {
+        return;
+    }
+    // sprinting and each level of the knockback enchantment add the same
+    let bonus = profile.sprint_bonus * f64::from(bonus_levels);
+
     let yaw = attacker_entity.yaw.load();
 
     let saved_velo = victim_entity.velocity.load();
     victim_entity.knockback(
-        strength * 0.5,
+        (profile.horizontal + bonus) * scale,
+        (profile.vertical + bonus) * scale,
         f64::from((yaw * (PI / 180.0)).sin()),
         f64::from(-(yaw * (PI / 180.0)).cos()),
+        profile,
     );
 
     let entity_id = VarInt(victim_entity.entity_id);
@@ -85,11 +357,14 @@ pub async fn handle_knockback(
         victim_velocity.z as f32,
     );
     let velocity = attacker_entity.velocity.load();
-    attacker_entity
-        .velocity
-        .store(velocity.multiply(0.6, 1.0, 0.6));
+    attacker_entity.velocity.store(velocity.multiply(
+        profile.attacker_slowdown,
+        1.0,
+        profile.attacker_slowdown,
+    ));
 
     victim_entity.velocity.store(saved_velo);
+    victim.movement_check.lock().add_velocity(victim_velocity);
     victim.client.send_packet(packet).await;
 }
 
@@ -102,20 +377,17 @@ pub async fn spawn_sweep_particle(attacker_entity: &Entity, world: &World, pos:
     // TODO: use entity height
     let body_y = pos.y * 2.0 * scale;
 
+    let particle = Particle::simple(particle!("minecraft:sweep_attack"))
+        .expect("sweep attack particles have no options");
     world
-        .broadcast_packet_all(&CParticle::new(
-            false,
-            pos.x + d,
-            body_y,
-            pos.z + e,
-            0.0,
-            0.0,
-            0.0,
+        .spawn_particle(
+            &particle,
+            Vector3::new(pos.x + d, body_y, pos.z + e),
+            Vector3::new(0.0, 0.0, 0.0),
             0.0,
             0,
-            VarInt(i32::from(particle!("minecraft:sweep_attack"))), // sweep
-            &[],
-        ))
+            false,
+        )
         .await;
 }
 
@@ -1,17 +1,20 @@
 use crate::entity::player::Player;
+use crate::plugin::menu::MENUS;
 use crate::server::Server;
 use itertools::Itertools;
 use pumpkin_core::text::TextComponent;
 use pumpkin_core::GameMode;
+use pumpkin_entity::EntityId;
 use pumpkin_inventory::container_click::{
     Click, ClickType, KeyClick, MouseClick, MouseDragState, MouseDragType,
 };
 use pumpkin_inventory::drag_handler::DragHandler;
 use pumpkin_inventory::window_property::{WindowProperty, WindowPropertyTrait};
 use pumpkin_inventory::{container_click, InventoryError, OptionallyCombinedContainer};
-use pumpkin_inventory::{Container, WindowType};
+use pumpkin_inventory::{Container, OpenContainer, WindowType};
 use pumpkin_protocol::client::play::{
-    CCloseContainer, COpenScreen, CSetContainerContent, CSetContainerProperty, CSetContainerSlot,
+    CCloseContainer, CHorseScreenOpen, COpenScreen, CSetContainerContent, CSetContainerProperty,
+    CSetContainerSlot,
 };
 use pumpkin_protocol::server::play::SClickContainer;
 use pumpkin_protocol::slot::Slot;
@@ -19,6 +22,7 @@ use pumpkin_protocol::VarInt;
 use pumpkin_world::item::item_registry::Item;
 use pumpkin_world::item::ItemStack;
 use std::sync::Arc;
+use tokio::sync::Mutex;
 
 impl Player {
     pub async fn open_container(&self, server: &Server, window_type: WindowType) {
@@ -67,7 +71,7 @@ impl Player {
 
         let carried_item = self
             .carried_item
-            .load()
+            .lock()
             .as_ref()
             .map_or_else(Slot::empty, std::convert::Into::into);
 
@@ -106,11 +110,81 @@ impl Player {
             .await;
     }
 
+    /// Sends the window properties of the open container, like the progress of a brewing stand
+    pub async fn send_container_properties(&self, properties: &[(i16, i16)]) {
+        let window_id = self.inventory.lock().await.total_opened_containers;
+        for (id, value) in properties {
+            self.client
+                .send_packet(&CSetContainerProperty::new(window_id.into(), *id, *value))
+                .await;
+        }
+    }
+
+    /// Opens the container of a block entity, which players look into together
+    pub async fn open_block_container(
+        &self,
+        server: &Server,
+        container_id: u64,
+        container: Arc<Mutex<Box<dyn Container>>>,
+    ) {
+        let window_type = *container.lock().await.window_type();
+        server
+            .open_containers
+            .write()
+            .await
+            .entry(container_id)
+            .or_insert_with(|| OpenContainer::new(container.clone()))
+            .add_player(self.entity_id());
+        self.open_container.store(Some(container_id));
+        self.open_container(server, window_type).await;
+        let properties = container.lock().await.properties();
+        self.send_container_properties(&properties).await;
+    }
+
+    /// Opens the inventory of a horse like mob, the client shows it in a screen of its own with
+    /// the columns of the mob's chest
+    pub async fn open_horse_container(
+        &self,
+        server: &Server,
+        container_id: u64,
+        container: Arc<Mutex<Box<dyn Container>>>,
+        columns: u8,
+        entity_id: EntityId,
+    ) {
+        server
+            .open_containers
+            .write()
+            .await
+            .entry(container_id)
+            .or_insert_with(|| OpenContainer::new(container.clone()))
+            .add_player(self.entity_id());
+        self.open_container.store(Some(container_id));
+        let window_id = {
+            let mut inventory = self.inventory.lock().await;
+            inventory.state_id = 0;
+            inventory.total_opened_containers += 1;
+            inventory.total_opened_containers
+        };
+        self.client
+            .send_packet(&CHorseScreenOpen::new(
+                window_id.into(),
+                i32::from(columns).into(),
+                entity_id,
+            ))
+            .await;
+        let mut container = container.lock().await;
+        self.set_container_content(Some(&mut *container)).await;
+    }
+
     pub async fn handle_click_container(
         &self,
         server: &Arc<Server>,
         packet: SClickContainer,
     ) -> Result<(), InventoryError> {
+        if MENUS.is_open(self.gameprofile.id) {
+            MENUS.click(self, &packet).await;
+            return Ok(());
+        }
         let opened_container = self.get_open_container(server).await;
         let mut opened_container = match opened_container.as_ref() {
             Some(container) => Some(container.lock().await),
@@ -279,14 +353,14 @@ impl Player {
         let mut container = OptionallyCombinedContainer::new(&mut inventory, opened_container);
         match slot {
             container_click::Slot::Normal(slot) => {
-                let mut carried_item = self.carried_item.load();
+                let mut carried_item = self.carried_item.lock().take();
                 let res = container.handle_item_change(
                     &mut carried_item,
                     slot,
                     mouse_click,
                     taking_crafted,
                 );
-                self.carried_item.store(carried_item);
+                *self.carried_item.lock() = carried_item;
                 res
             }
             container_click::Slot::OutsideInventory => Ok(()),
@@ -312,9 +386,7 @@ impl Player {
                         // TODO: Check for max item count here
                         match slot {
                             Some(item) => {
-                                if item.item_id == item_in_pressed_slot.item_id
-                                    && item.item_count != 64
-                                {
+                                if *item == item_in_pressed_slot && item.item_count != 64 {
                                     Some(slot_number)
                                 } else {
                                     None
@@ -330,7 +402,7 @@ impl Player {
                         slots.skip(36).rev().find_map(find_condition)
                     };
                     if let Some(slot) = slots {
-                        let mut item_slot = container.all_slots()[slot].map(|i| i);
+                        let mut item_slot = container.all_slots()[slot].clone();
                         container.handle_item_change(
                             &mut item_slot,
                             slot,
@@ -382,7 +454,7 @@ impl Player {
         let mut inventory = self.inventory.lock().await;
         let mut container = OptionallyCombinedContainer::new(&mut inventory, opened_container);
         if let Some(Some(item)) = container.all_slots().get_mut(slot) {
-            self.carried_item.store(Some(item.to_owned()));
+            *self.carried_item.lock() = Some(item.to_owned());
         }
         Ok(())
     }
@@ -399,13 +471,12 @@ impl Player {
         let Some(item) = slots.get_mut(slot) else {
             return Ok(());
         };
-        let Some(mut carried_item) = **item else {
+        let Some(mut carried_item) = item.take() else {
             return Ok(());
         };
-        **item = None;
 
         for slot in slots.iter_mut().filter_map(|slot| slot.as_mut()) {
-            if slot.item_id == carried_item.item_id {
+            if *slot == carried_item {
                 // TODO: Check for max stack size
                 if slot.item_count + carried_item.item_count <= 64 {
                     slot.item_count = 0;
@@ -421,7 +492,7 @@ impl Player {
                 }
             }
         }
-        self.carried_item.store(Some(carried_item));
+        *self.carried_item.lock() = Some(carried_item);
         Ok(())
     }
 
@@ -454,11 +525,11 @@ impl Player {
                 let mut inventory = self.inventory.lock().await;
                 let mut container =
                     OptionallyCombinedContainer::new(&mut inventory, opened_container);
-                let mut carried_item = self.carried_item.load();
+                let mut carried_item = self.carried_item.lock().take();
                 let res = drag_handler
                     .apply_drag(&mut carried_item, &mut container, &container_id, player_id)
                     .await;
-                self.carried_item.store(carried_item);
+                *self.carried_item.lock() = carried_item;
                 res
             }
         }
@@ -551,75 +622,31 @@ impl Player {
         }
     }
 
-    async fn pickup_items(&self, item: &Item, mut amount: u32) {
+    async fn pickup_items(&self, item: &Item, custom_model_data: Option<i32>, mut amount: u32) {
         let max_stack = item.components.max_stack_size;
-        let mut inventory = self.inventory.lock().await;
-        let slots = inventory.slots_with_hotbar_first();
-
-        let matching_slots = slots.filter_map(|slot| {
-            if let Some(item_slot) = slot.as_ref() {
-                if item_slot.item_id == item.id && item_slot.item_count < max_stack {
-                    let item_count = item_slot.item_count;
-                    Some((slot, item_count))
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
-        });
-
-        for (slot, item_count) in matching_slots {
-            if amount == 0 {
-                return;
-            }
-            let amount_to_add = max_stack - item_count;
-            if let Some(amount_left) = amount.checked_sub(u32::from(amount_to_add)) {
-                amount = amount_left;
-                *slot = Some(ItemStack {
-                    item_id: item.id,
-                    item_count: item.components.max_stack_size,
-                });
-            } else {
-                *slot = Some(ItemStack {
-                    item_id: item.id,
-                    item_count: max_stack - (amount_to_add - amount as u8),
-                });
-                return;
-            }
-        }
-
-        let empty_slots = inventory
-            .slots_with_hotbar_first()
-            .filter(|slot| slot.is_none());
-        for slot in empty_slots {
-            if amount == 0 {
-                return;
-            }
-            if let Some(remaining_amount) = amount.checked_sub(u32::from(max_stack)) {
-                amount = remaining_amount;
-                *slot = Some(ItemStack {
-                    item_id: item.id,
-                    item_count: max_stack,
-                });
-            } else {
-                *slot = Some(ItemStack {
-                    item_id: item.id,
-                    item_count: amount as u8,
-                });
-                return;
+        while amount > 0 {
+            let count = amount.min(u32::from(max_stack)) as u8;
+            amount -= u32::from(count);
+            let mut stack = ItemStack::new(count, item.id);
+            stack.set_custom_model_data(custom_model_data);
+            if let Some(left) = self.insert_stack(stack).await {
+                self.drop_item(left, false).await;
             }
         }
-        log::warn!(
-            "{amount} items were discarded because dropping them to the ground is not implemented"
-        );
     }
 
     /// Add items to inventory if there's space, else drop them to the ground.
     ///
     /// This method automatically syncs changes with the client.
     pub async fn give_items(&self, item: &Item, amount: u32) {
-        self.pickup_items(item, amount).await;
+        self.pickup_items(item, None, amount).await;
+        self.set_container_content(None).await;
+    }
+
+    /// Gives a custom item, which is the item with the custom model data
+    pub async fn give_custom_items(&self, item: &Item, custom_model_data: i32, amount: u32) {
+        self.pickup_items(item, Some(custom_model_data), amount)
+            .await;
         self.set_container_content(None).await;
     }
 }
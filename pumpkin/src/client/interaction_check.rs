@@ -0,0 +1,205 @@
+//! Validation of the blocks and entities players interact with, see
+//! [`InteractionCheckConfig`](pumpkin_config::InteractionCheckConfig).
+//!
+//! The client only sends which block or entity it interacts with, so the server checks that it is
+//! within reach, that the player looks at it and that no block's collision is in the way.
+
+use std::sync::atomic::Ordering;
+
+use pumpkin_config::runtime_config;
+use pumpkin_core::math::{boundingbox::BoundingBox, position::WorldPosition, vector3::Vector3};
+use pumpkin_world::block::block_registry::get_block_and_state_by_state_id;
+
+use crate::{
+    entity::{player::Player, Entity},
+    world::World,
+};
+
+use super::movement_check::blocks_in;
+
+/// The eye height of sneaking players
+const SNEAKING_EYE_HEIGHT: f64 = 1.27;
+/// How far an entity may move per tick, like a sprinting player
+const MAX_ENTITY_SPEED: f64 = 0.3;
+/// Boxes the line of sight only touches do not block it
+const RAY_EPSILON: f64 = 1.0E-4;
+
+/// A check an interaction failed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Violation {
+    Reach,
+    Angle,
+    Wall,
+}
+
+impl Violation {
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Reach => "reach",
+            Self::Angle => "angle",
+            Self::Wall => "wall",
+        }
+    }
+}
+
+/// Where the player's eyes are, lower while it sneaks
+pub(crate) fn eye_position(player: &Player) -> Vector3<f64> {
+    let entity = &player.living_entity.entity;
+    let pos = entity.pos.load();
+    let eye_height = if entity.sneaking.load(Ordering::Relaxed) {
+        SNEAKING_EYE_HEIGHT
+    } else {
+        f64::from(entity.standing_eye_height)
+    };
+    Vector3::new(pos.x, pos.y + eye_height, pos.z)
+}
+
+/// The direction the player looks in
+pub(crate) fn look_direction(entity: &Entity) -> Vector3<f64> {
+    let yaw = f64::from(entity.yaw.load()).to_radians();
+    let pitch = f64::from(entity.pitch.load()).to_radians();
+    Vector3::new(
+        -yaw.sin() * pitch.cos(),
+        -pitch.sin(),
+        yaw.cos() * pitch.cos(),
+    )
+}
+
+/// The angle in degrees between where the player looks and the closest point of the box.
+/// Any point of the box the player looks at is within 90 degrees of that
+fn look_angle(eye: Vector3<f64>, look: Vector3<f64>, target: &BoundingBox) -> f64 {
+    let to_target = target.closest_point(eye).sub(&eye);
+    if to_target.length_squared() == 0.0 {
+        // the eye is inside of it
+        return 0.0;
+    }
+    let to_target = to_target.normalize();
+    let cos = look.z.mul_add(
+        to_target.z,
+        look.x.mul_add(to_target.x, look.y * to_target.y),
+    );
+    cos.clamp(-1.0, 1.0).acos().to_degrees()
+}
+
+/// Whether a block's collision is between the points, besides the one at `ignore`
+async fn obstructed(
+    world: &World,
+    from: Vector3<f64>,
+    to: Vector3<f64>,
+    ignore: Option<&WorldPosition>,
+) -> bool {
+    let area = BoundingBox {
+        min_x: from.x.min(to.x),
+        min_y: from.y.min(to.y),
+        min_z: from.z.min(to.z),
+        max_x: from.x.max(to.x),
+        max_y: from.y.max(to.y),
+        max_z: from.z.max(to.z),
+    };
+    for (position, id) in blocks_in(world, &area).await {
+        if ignore.is_some_and(|ignore| ignore.0 == position.0) {
+            continue;
+        }
+        let Some((_, state)) = get_block_and_state_by_state_id(id) else {
+            continue;
+        };
+        let pos = position.0;
+        let blocked = state.collision_boxes().any(|shape| {
+            shape
+                .offset(f64::from(pos.x), f64::from(pos.y), f64::from(pos.z))
+                .expand(-RAY_EPSILON)
+                .ray_intersection(from, to)
+                // a block the player's head is in does not block their view
+                .is_some_and(|enter| enter > 0.0)
+        });
+        if blocked {
+            return true;
+        }
+    }
+    false
+}
+
+/// Checks the player may interact with the block, `cursor` is where on it they clicked if known
+pub async fn check_block(
+    player: &Player,
+    location: &WorldPosition,
+    cursor: Option<Vector3<f64>>,
+) -> Result<(), Violation> {
+    let runtime = runtime_config();
+    let config = &runtime.interaction_check;
+    if !config.enabled {
+        return Ok(());
+    }
+    if config.reach && !player.can_interact_with_block_at(location, config.additional_range) {
+        return Err(Violation::Reach);
+    }
+    let entity = &player.living_entity.entity;
+    let eye = eye_position(player);
+    let block = BoundingBox::from_block(location);
+    if config.angle && look_angle(eye, look_direction(entity), &block) > config.max_angle {
+        return Err(Violation::Angle);
+    }
+    if config.through_walls {
+        let pos = location.0;
+        let targets = cursor
+            .map(|cursor| {
+                Vector3::new(
+                    f64::from(pos.x) + cursor.x,
+                    f64::from(pos.y) + cursor.y,
+                    f64::from(pos.z) + cursor.z,
+                )
+            })
+            .into_iter()
+            .chain([block.closest_point(eye), block.center()]);
+        for target in targets {
+            if !obstructed(&entity.world, eye, target, Some(location)).await {
+                return Ok(());
+            }
+        }
+        return Err(Violation::Wall);
+    }
+    Ok(())
+}
+
+/// Checks the player may attack or interact with the entity.
+///
+/// The entity may have moved since the player's client showed it where it was hit,
+/// so its box grows by how far it could have moved within the player's latency
+pub async fn check_entity(player: &Player, target: &Entity) -> Result<(), Violation> {
+    let runtime = runtime_config();
+    let config = &runtime.interaction_check;
+    if !config.enabled {
+        return Ok(());
+    }
+    let latency = player.latency().min(config.latency_compensation);
+    let target_box = target.bounding_box.load();
+    let compensated = target_box.expand(MAX_ENTITY_SPEED * f64::from(latency) / 50.0);
+    let eye = eye_position(player);
+
+    if config.reach {
+        let range = player.entity_interaction_range() + config.additional_range;
+        if compensated.squared_magnitude(eye) >= range * range {
+            return Err(Violation::Reach);
+        }
+    }
+    let entity = &player.living_entity.entity;
+    if config.angle && look_angle(eye, look_direction(entity), &compensated) > config.max_angle {
+        return Err(Violation::Angle);
+    }
+    if config.through_walls {
+        let center = target_box.center();
+        let targets = [
+            compensated.closest_point(eye),
+            center,
+            Vector3::new(center.x, target_box.max_y, center.z),
+        ];
+        for target in targets {
+            if !obstructed(&entity.world, eye, target, None).await {
+                return Ok(());
+            }
+        }
+        return Err(Violation::Wall);
+    }
+    Ok(())
+}
@@ -1,44 +1,55 @@
 use std::{
-    collections::VecDeque,
-    net::SocketAddr,
+    collections::{HashSet, VecDeque},
+    net::{IpAddr, SocketAddr},
     sync::{
         atomic::{AtomicBool, AtomicI32},
-        Arc,
+        Arc, OnceLock,
     },
+    time::{Duration, Instant},
 };
 
 use crate::{
     entity::player::{ChatMode, Hand},
-    server::Server,
+    server::{metrics::PACKETS, profiler::PROFILER, throttle, Server},
 };
 
 use authentication::GameProfile;
 use crossbeam::atomic::AtomicCell;
-use pumpkin_config::compression::CompressionInfo;
-use pumpkin_core::text::TextComponent;
+use pumpkin_config::{compression::CompressionInfo, ADVANCED_CONFIG};
+use pumpkin_core::{math::vector2::Vector2, text::TextComponent};
 use pumpkin_protocol::{
     bytebuf::{packet_id::Packet, DeserializerError},
     client::{config::CConfigDisconnect, login::CLoginDisconnect, play::CPlayDisconnect},
-    packet_decoder::PacketDecoder,
-    packet_encoder::{PacketEncodeError, PacketEncoder},
+    packet_decoder::{PacketDecodeError, PacketDecoder},
+    packet_encoder::{PacketEncodeError, PacketEncoder, PreparedPacket},
     server::{
         config::{SAcknowledgeFinishConfig, SClientInformationConfig, SKnownPacks, SPluginMessage},
         handshake::SHandShake,
         login::{SEncryptionResponse, SLoginAcknowledged, SLoginPluginResponse, SLoginStart},
         status::{SStatusPingRequest, SStatusRequest},
     },
+    translation::ProtocolTranslator,
     ClientPacket, ConnectionState, RawPacket, ServerPacket,
 };
+use pumpkin_world::chunk::ChunkData;
+use send_queue::{PacketPriority, SendQueue, SendQueueStats};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, Notify, RwLock};
 
 use thiserror::Error;
 
 pub mod authentication;
+mod capture;
+pub mod chat_session;
 mod client_packet;
 pub mod combat;
 mod container;
+pub mod interaction_check;
+pub mod movement_check;
 pub mod player_packet;
+pub mod plugin_channel;
+pub mod send_queue;
+pub mod translation;
 
 /// Represents a player's configuration settings.
 ///
@@ -94,6 +105,8 @@ pub struct Client {
     pub config: Mutex<Option<PlayerConfig>>,
     /// The client's brand or modpack information, Optional.
     pub brand: Mutex<Option<String>>,
+    /// The plugin channels the client listens on
+    pub plugin_channels: Mutex<HashSet<String>>,
     /// The minecraft protocol version used by the client.
     pub protocol_version: AtomicI32,
     /// The Address used to connect to the Server, Send in the Handshake
@@ -104,11 +117,15 @@ pub struct Client {
     pub encryption: AtomicBool,
     /// Indicates if the client connection is closed.
     pub closed: AtomicBool,
+    /// Wakes up reading once the connection is closed
+    close_notify: Notify,
     /// The underlying TCP connection to the client.
     pub connection_reader: Arc<Mutex<tokio::net::tcp::OwnedReadHalf>>,
     pub connection_writer: Arc<Mutex<tokio::net::tcp::OwnedWriteHalf>>,
     /// The client's IP address.
     pub address: Mutex<SocketAddr>,
+    /// Whether the address is the client's own, behind a proxy only once the proxy forwarded it
+    address_forwarded: AtomicBool,
     /// The packet encoder for outgoing packets.
     enc: Arc<Mutex<PacketEncoder>>,
     /// The packet decoder for incoming packets.
@@ -117,34 +134,92 @@ pub struct Client {
     pub client_packets_queue: Arc<Mutex<VecDeque<RawPacket>>>,
     /// Indicates whether the client should be converted into a player.
     pub make_player: AtomicBool,
+    /// The id of the Velocity player info request we are waiting an answer for
+    pub velocity_message_id: AtomicCell<Option<i32>>,
+    /// The token sent in the encryption request, the client has to send it back encrypted
+    pub verify_token: AtomicCell<Option<[u8; 4]>>,
+    /// The packets waiting to be written once the client is in the play state
+    send_queue: Arc<SendQueue>,
+    /// The packets received in the current second, see [`throttle::packet_limit`]
+    packet_rate: parking_lot::Mutex<throttle::RateWindow>,
+    /// Set at the handshake if the client is on an older protocol version
+    translator: Arc<OnceLock<Arc<dyn ProtocolTranslator>>>,
 }
 
 impl Client {
     #[must_use]
     pub fn new(connection: tokio::net::TcpStream, address: SocketAddr, id: u16) -> Self {
         let (connection_reader, connection_writer) = connection.into_split();
+        let connection_writer = Arc::new(Mutex::new(connection_writer));
+        let mut enc = PacketEncoder::default();
+        let mut dec = PacketDecoder::default();
+        if let Some(capture) = capture::start(id) {
+            enc.set_capture(Some(capture.clone()));
+            dec.set_capture(Some(capture));
+        }
+        let enc = Arc::new(Mutex::new(enc));
+        let send_queue = Arc::new(SendQueue::default());
+        let translator = Arc::new(OnceLock::new());
+        tokio::spawn(send_queue::run_writer(
+            send_queue.clone(),
+            enc.clone(),
+            connection_writer.clone(),
+            translator.clone(),
+            id,
+        ));
         Self {
             id,
             protocol_version: AtomicI32::new(0),
             gameprofile: Mutex::new(None),
             config: Mutex::new(None),
             brand: Mutex::new(None),
+            plugin_channels: Mutex::new(HashSet::new()),
             server_address: Mutex::new(String::new()),
             address: Mutex::new(address),
+            address_forwarded: AtomicBool::new(!ADVANCED_CONFIG.proxy.enabled),
             connection_state: AtomicCell::new(ConnectionState::HandShake),
             connection_reader: Arc::new(Mutex::new(connection_reader)),
-            connection_writer: Arc::new(Mutex::new(connection_writer)),
-            enc: Arc::new(Mutex::new(PacketEncoder::default())),
-            dec: Arc::new(Mutex::new(PacketDecoder::default())),
+            connection_writer,
+            enc,
+            dec: Arc::new(Mutex::new(dec)),
             encryption: AtomicBool::new(false),
             closed: AtomicBool::new(false),
+            close_notify: Notify::new(),
             client_packets_queue: Arc::new(Mutex::new(VecDeque::new())),
             make_player: AtomicBool::new(false),
+            velocity_message_id: AtomicCell::new(None),
+            verify_token: AtomicCell::new(None),
+            send_queue,
+            packet_rate: parking_lot::Mutex::new(throttle::RateWindow::new()),
+            translator,
+        }
+    }
+
+    /// The IP to rate limit, None behind a proxy until it forwarded the client's
+    pub async fn throttled_ip(&self) -> Option<IpAddr> {
+        if self
+            .address_forwarded
+            .load(std::sync::atomic::Ordering::Relaxed)
+        {
+            Some(self.address.lock().await.ip())
+        } else {
+            None
         }
     }
 
     /// Adds a Incoming packet to the queue
     pub async fn add_packet(&self, packet: RawPacket) {
+        let packet = match self.translator.get() {
+            Some(translator) => {
+                let Some(packet) = translator.serverbound(self.connection_state.load(), packet)
+                else {
+                    return;
+                };
+                packet
+            }
+            None => packet,
+        };
+        PACKETS.count_received();
         let mut client_packets_queue = self.client_packets_queue.lock().await;
         client_packets_queue.push_back(packet);
     }
@@ -203,10 +278,39 @@ impl Client {
     ///
     /// * `compression`: An optional `CompressionInfo` struct containing the compression threshold and compression level.
     pub async fn set_compression(&self, compression: Option<CompressionInfo>) {
-        self.dec.lock().await.set_compression(compression.is_some());
+        self.dec
+            .lock()
+            .await
+            .set_compression(compression.as_ref().map(|info| info.threshold));
         self.enc.lock().await.set_compression(compression);
     }
 
+    /// Sends a packet which was serialized once for many clients, see [`PreparedPacket`].
+    pub async fn send_prepared_packet(&self, packet: &PreparedPacket) {
+        let priority = PacketPriority::of(packet.parts().0);
+        let Some(packet) = self.translate(packet.clone()) else {
+            return;
+        };
+        if self.connection_state.load() == ConnectionState::Play {
+            self.queue_packet(priority, packet);
+            return;
+        }
+
+        let mut enc = self.enc.lock().await;
+        if let Err(error) = enc.append_prepared(&packet) {
+            if error.kickable() {
+                self.kick(&error.to_string()).await;
+            }
+            return;
+        }
+
+        let mut writer = self.connection_writer.lock().await;
+        if let Err(error) = writer.write_all(&enc.take()).await {
+            log::debug!("{error}");
+        }
+        PACKETS.count_sent(1);
+    }
+
     /// Sends a clientbound packet to the connected client.
     ///
     /// # Arguments
@@ -215,6 +319,13 @@ impl Client {
     pub async fn send_packet<P: ClientPacket>(&self, packet: &P) {
         //log::debug!("Sending packet with id {} to {}", P::PACKET_ID, self.id);
         // assert!(!self.closed);
+        if self.translator.get().is_some() || self.connection_state.load() == ConnectionState::Play
+        {
+            self.send_prepared_packet(&PreparedPacket::new(packet))
+                .await;
+            return;
+        }
+
         let mut enc = self.enc.lock().await;
         if let Err(error) = enc.append_packet(packet) {
             if error.kickable() {
@@ -227,6 +338,7 @@ impl Client {
         if let Err(error) = writer.write_all(&enc.take()).await {
             log::debug!("{}", error.to_string());
         }
+        PACKETS.count_sent(1);
 
         /*
         else if let Err(error) = writer.flush().await {
@@ -239,6 +351,44 @@ impl Client {
         */
     }
 
+    /// Turns the packet into the client's protocol version, returning None if the version lacks it.
+    fn translate(&self, packet: PreparedPacket) -> Option<PreparedPacket> {
+        match self.translator.get() {
+            Some(translator) => translator.clientbound(self.connection_state.load(), packet),
+            None => Some(packet),
+        }
+    }
+
+    /// Queues the packet for the writer, disconnecting the client if it is too far behind.
+    fn queue_packet(&self, priority: PacketPriority, packet: PreparedPacket) {
+        if let Err(error) = self.send_queue.push(priority, packet) {
+            log::warn!("Disconnecting client id {}: {error}", self.id);
+            self.close();
+        }
+    }
+
+    /// Sends the chunk at the rate the connection allows, waiting if too many chunks are queued already.
+    ///
+    /// The chunk is serialized when it is written, so it is sent with the blocks it has by then.
+    pub async fn send_chunk(&self, position: Vector2<i32>, chunk: Arc<RwLock<ChunkData>>) {
+        self.send_queue.push_chunk(position, chunk).await;
+    }
+
+    /// The client received a batch of chunks and wants `chunks_per_tick` chunks from now on.
+    pub fn acknowledge_chunk_batch(&self, chunks_per_tick: f32) {
+        self.send_queue.acknowledge_chunk_batch(chunks_per_tick);
+    }
+
+    /// Drops queued chunks which have not been written yet, e.g. because they got unloaded.
+    pub fn cancel_chunks(&self, positions: &[Vector2<i32>]) {
+        self.send_queue.cancel_chunks(positions);
+    }
+
+    #[must_use]
+    pub fn send_queue_stats(&self) -> SendQueueStats {
+        self.send_queue.stats()
+    }
+
     /// Sends a clientbound packet to the connected client.
     ///
     /// # Arguments
@@ -266,10 +416,17 @@ impl Client {
         */
 
         let mut enc = self.enc.lock().await;
-        enc.append_packet(packet)?;
+        if self.translator.get().is_some() {
+            if let Some(packet) = self.translate(PreparedPacket::new(packet)) {
+                enc.append_prepared(&packet)?;
+            }
+        } else {
+            enc.append_packet(packet)?;
+        }
 
         let mut writer = self.connection_writer.lock().await;
         let _ = writer.write_all(&enc.take()).await;
+        PACKETS.count_sent(1);
 
         /*
         writer
@@ -300,7 +457,13 @@ impl Client {
                 log::debug!("Canceling client packet processing (pre)");
                 return;
             }
-            if let Err(error) = self.handle_packet(server, &mut packet).await {
+            let start = Instant::now();
+            let result = self.handle_packet(server, &mut packet).await;
+            if PROFILER.is_running() {
+                let stack = format!("network;{:?}", self.connection_state.load());
+                PROFILER.record(&stack, start.elapsed());
+            }
+            if let Err(error) = result {
                 let text = format!("Error while reading incoming packet {error}");
                 log::error!(
                     "Failed to read incoming packet with id {}: {}",
@@ -308,7 +471,7 @@ impl Client {
                     error
                 );
                 self.kick(&text).await;
-            };
+            }
         }
     }
 
@@ -427,7 +590,7 @@ impl Client {
                     .await;
             }
             SLoginPluginResponse::PACKET_ID => {
-                self.handle_plugin_response(SLoginPluginResponse::read(bytebuf)?)
+                self.handle_plugin_response(server, SLoginPluginResponse::read(bytebuf)?)
                     .await;
             }
             SLoginAcknowledged::PACKET_ID => {
@@ -457,7 +620,8 @@ impl Client {
                     .await;
             }
             SPluginMessage::PACKET_ID => {
-                self.handle_plugin_message(SPluginMessage::read(bytebuf)?)
+                let message = SPluginMessage::read(bytebuf)?;
+                self.handle_plugin_message(None, &message.channel, &message.data)
                     .await;
             }
             SAcknowledgeFinishConfig::PACKET_ID => {
@@ -481,7 +645,7 @@ impl Client {
     /// Reads the connection until our buffer of len 4096 is full, then decode
     /// Close connection when an error occurs or when the Client closed the connection
     /// Returns if connection is still open
-    pub async fn poll(&self) -> bool {
+    pub async fn poll(&self, server: &Server) -> bool {
         loop {
             if self.closed.load(std::sync::atomic::Ordering::Relaxed) {
                 // If we manually close (like a kick) we dont want to keep reading bytes
@@ -489,20 +653,48 @@ impl Client {
             }
 
             let mut dec = self.dec.lock().await;
+            let state = self.connection_state.load();
+            dec.set_max_packet_size(throttle::max_packet_size(state));
 
             match dec.decode() {
                 Ok(Some(packet)) => {
+                    drop(dec);
+                    if !self
+                        .packet_rate
+                        .lock()
+                        .hit(throttle::packet_limit(state), Duration::from_secs(1))
+                    {
+                        self.kick("Sent too many packets").await;
+                        if let Some(ip) = self.throttled_ip().await {
+                            server.throttle.block(ip);
+                        }
+                        return false;
+                    }
                     self.add_packet(packet).await;
                     return true;
                 }
                 Ok(None) => (), //log::debug!("Waiting for more data to complete packet..."),
-                Err(err) => log::warn!("Failed to decode packet for: {}", err.to_string()),
+                Err(err) => {
+                    // the rest of the stream can not be read anymore
+                    log::warn!("Failed to decode packet for {}: {}", self.id, err);
+                    if matches!(err, PacketDecodeError::OutOfBounds) {
+                        if let Some(ip) = self.throttled_ip().await {
+                            server.throttle.block(ip);
+                        }
+                    }
+                    self.close();
+                    return false;
+                }
             }
 
             dec.reserve(4096);
             let mut buf = dec.take_capacity();
 
-            let bytes_read = self.connection_reader.lock().await.read_buf(&mut buf).await;
+            let bytes_read = tokio::select! {
+                read = async { self.connection_reader.lock().await.read_buf(&mut buf).await } => read,
+                // a client which is gone, e.g. after timing out, may never send anything again
+                () = self.close_notify.notified() => return false,
+            };
             match bytes_read {
                 Ok(cnt) => {
                     //log::debug!("Read {} bytes", cnt);
@@ -570,14 +762,25 @@ impl Client {
     pub fn close(&self) {
         self.closed
             .store(true, std::sync::atomic::Ordering::Relaxed);
+        self.close_notify.notify_one();
+        self.send_queue.close();
         log::debug!("Closed connection for {}", self.id);
     }
 }
 
+impl Drop for Client {
+    fn drop(&mut self) {
+        // the writer only holds the queue, so it would never stop otherwise
+        self.send_queue.close();
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum EncryptionError {
     #[error("failed to decrypt shared secret")]
     FailedDecrypt,
     #[error("shared secret has the wrong length")]
     SharedWrongLength,
+    #[error("invalid verify token")]
+    InvalidVerifyToken,
 }
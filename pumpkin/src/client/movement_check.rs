@@ -0,0 +1,398 @@
+//! Validation of the movement players send, see [`MovementCheckConfig`](pumpkin_config::MovementCheckConfig).
+//!
+//! Movement failing a check is not applied, the player is either sent back to where they were
+//! accepted last or kicked.
+
+use std::{
+    sync::atomic::Ordering,
+    time::{Duration, Instant},
+};
+
+use pumpkin_config::{runtime_config, ViolationAction};
+use pumpkin_core::{
+    math::{boundingbox::BoundingBox, position::WorldPosition, vector3::Vector3},
+    text::TextComponent,
+    GameMode,
+};
+use pumpkin_entity::effect_type::EffectType;
+use pumpkin_world::block::block_registry::get_block_and_state_by_state_id;
+
+use crate::{
+    entity::player::{Abilities, Player},
+    server::throttle::RateWindow,
+    world::World,
+};
+
+/// How far a player may move per tick on foot, sprint jumping with some room for ice
+const MAX_WALKING_SPEED: f64 = 0.7;
+/// How far a player may move per tick when flying at the default fly speed, while sprinting
+const MAX_FLYING_SPEED: f64 = 1.2;
+/// How far a player may move per tick with an elytra, using fireworks
+const MAX_FALL_FLYING_SPEED: f64 = 4.0;
+/// How much higher than a jump players may get before they are flying
+const JUMP_TOLERANCE: f64 = 0.05;
+/// The upwards velocity of a jump, jump boost adds a tenth per level
+const JUMP_VELOCITY: f64 = 0.42;
+/// How much faster speed makes players per level
+const SPEED_PER_LEVEL: f64 = 0.2;
+/// How many ticks of movement one packet may contain, catching up after lag
+const MAX_TICKS_PER_MOVE: f64 = 5.0;
+/// Player and block boxes touching is not colliding
+const COLLISION_EPSILON: f64 = 1.0E-4;
+/// How far below the player a block still counts as ground
+const GROUND_DISTANCE: f64 = 0.05;
+
+/// Blocks in which players can stay in the air or rise without flying
+const SUPPORTING_BLOCKS: [&str; 14] = [
+    "minecraft:water",
+    "minecraft:lava",
+    "minecraft:bubble_column",
+    "minecraft:ladder",
+    "minecraft:vine",
+    "minecraft:scaffolding",
+    "minecraft:cobweb",
+    "minecraft:powder_snow",
+    "minecraft:twisting_vines",
+    "minecraft:twisting_vines_plant",
+    "minecraft:weeping_vines",
+    "minecraft:weeping_vines_plant",
+    "minecraft:cave_vines",
+    "minecraft:cave_vines_plant",
+];
+
+/// A check a movement failed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Violation {
+    Speed,
+    Fly,
+    NoClip,
+}
+
+impl Violation {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Speed => "speed",
+            Self::Fly => "fly",
+            Self::NoClip => "noclip",
+        }
+    }
+}
+
+/// What the checks know about a player's earlier movement
+pub struct MovementCheck {
+    /// Where the player was accepted last
+    last_valid: Vector3<f64>,
+    last_move: Instant,
+    /// The height the player last stood at, they can not get much higher without support
+    ground_y: f64,
+    /// Ticks the player spent in the air without falling
+    air_ticks: u32,
+    /// Whether the player may be bouncing off the block they stood on last
+    bouncy: bool,
+    /// Horizontal knockback which the client may still be moving with
+    velocity: f64,
+    violations: RateWindow,
+}
+
+impl MovementCheck {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            last_valid: Vector3::new(0.0, 0.0, 0.0),
+            last_move: Instant::now(),
+            ground_y: 0.0,
+            air_ticks: 0,
+            bouncy: false,
+            velocity: 0.0,
+            violations: RateWindow::new(),
+        }
+    }
+
+    /// The server moved the player, e.g. by teleporting them
+    pub fn reset(&mut self, position: Vector3<f64>) {
+        self.last_valid = position;
+        self.last_move = Instant::now();
+        self.ground_y = position.y;
+        self.air_ticks = 0;
+        self.bouncy = false;
+    }
+
+    /// The player may stay in the air, e.g. flying or levitating, they can jump again from where
+    /// they are once they can't anymore
+    fn float(&mut self, y: f64) {
+        self.ground_y = y;
+        self.air_ticks = 0;
+    }
+
+    /// The player was knocked back, like a new jump from where they are
+    pub fn add_velocity(&mut self, velocity: Vector3<f64>) {
+        self.velocity += velocity.x.hypot(velocity.z);
+        if velocity.y > 0.0 {
+            self.ground_y = self.last_valid.y.max(self.ground_y) + velocity.y * 10.0;
+            self.air_ticks = 0;
+        }
+    }
+
+    /// The game ticks since the last accepted movement
+    fn ticks_since_move(&self) -> f64 {
+        (self.last_move.elapsed().as_secs_f64() / 0.05).max(1.0)
+    }
+
+    fn update_air(
+        &mut self,
+        from_y: f64,
+        to_y: f64,
+        surroundings: &Surroundings,
+        max_jump_height: f64,
+        max_air_ticks: u32,
+    ) -> Result<(), Violation> {
+        if surroundings.ground || surroundings.supported {
+            self.ground_y = to_y;
+            self.air_ticks = 0;
+            self.bouncy = surroundings.bouncy;
+            return Ok(());
+        }
+        if !self.bouncy && to_y - self.ground_y > max_jump_height {
+            return Err(Violation::Fly);
+        }
+        if to_y < from_y {
+            self.air_ticks = 0;
+            return Ok(());
+        }
+        self.air_ticks = self
+            .air_ticks
+            .saturating_add(self.ticks_since_move() as u32);
+        if self.air_ticks > max_air_ticks {
+            return Err(Violation::Fly);
+        }
+        Ok(())
+    }
+
+    fn accept(&mut self, to: Vector3<f64>) {
+        self.last_valid = to;
+        self.last_move = Instant::now();
+        self.velocity = if self.velocity < 0.01 {
+            0.0
+        } else {
+            self.velocity * 0.91
+        };
+    }
+}
+
+impl Default for MovementCheck {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How high a jump with jump boost of the level (0 without it) gets, the height is simulated with
+/// the client's gravity and drag
+fn jump_height(jump_boost: u8) -> f64 {
+    let mut velocity = JUMP_VELOCITY + 0.1 * f64::from(jump_boost);
+    let mut height = 0.0;
+    while velocity > 0.0 {
+        height += velocity;
+        velocity = (velocity - 0.08) * 0.98;
+    }
+    height + JUMP_TOLERANCE
+}
+
+/// The level of the effect the player has, 0 without it and 1 for level I
+fn effect_level(player: &Player, effect_type: EffectType) -> u8 {
+    player
+        .living_entity
+        .effect(effect_type)
+        .map_or(0, |effect| effect.amplifier.saturating_add(1))
+}
+
+/// The blocks around a player
+#[derive(Default)]
+struct Surroundings {
+    /// Standing on a block with a collision
+    ground: bool,
+    /// In a liquid or on a climbable block
+    supported: bool,
+    /// Standing on a block players bounce off
+    bouncy: bool,
+}
+
+impl Surroundings {
+    async fn of(world: &World, player: &BoundingBox) -> Self {
+        let below = BoundingBox {
+            min_y: player.min_y - GROUND_DISTANCE,
+            max_y: player.min_y,
+            ..*player
+        };
+        let area = BoundingBox {
+            min_y: below.min_y,
+            ..*player
+        };
+        let mut surroundings = Self::default();
+        for (position, id) in blocks_in(world, &area).await {
+            let Some((block, state)) = get_block_and_state_by_state_id(id) else {
+                continue;
+            };
+            let cell = BoundingBox::from_block(&position);
+            if SUPPORTING_BLOCKS.contains(&block.name.as_str()) && cell.intersects(player) {
+                surroundings.supported = true;
+            }
+            let pos = position.0;
+            let on_block = state.collision_boxes().any(|shape| {
+                shape
+                    .offset(f64::from(pos.x), f64::from(pos.y), f64::from(pos.z))
+                    .intersects(&below)
+            });
+            if on_block {
+                surroundings.ground = true;
+                surroundings.bouncy |=
+                    block.name == "minecraft:slime_block" || block.name.ends_with("_bed");
+            }
+        }
+        surroundings
+    }
+}
+
+/// The block state ids of every non air block the box reaches into
+pub(super) async fn blocks_in(world: &World, area: &BoundingBox) -> Vec<(WorldPosition, u16)> {
+    let min = Vector3::new(
+        area.min_x.floor() as i32,
+        area.min_y.floor() as i32,
+        area.min_z.floor() as i32,
+    );
+    let max = Vector3::new(
+        area.max_x.floor() as i32,
+        area.max_y.floor() as i32,
+        area.max_z.floor() as i32,
+    );
+    let mut blocks = world.get_block_state_ids_in(min, max).await;
+    blocks.retain(|(_, id)| *id != 0);
+    blocks
+}
+
+/// Whether the box is inside of a block's collision
+async fn collides(world: &World, area: &BoundingBox) -> bool {
+    let area = area.expand(-COLLISION_EPSILON);
+    for (position, id) in blocks_in(world, &area).await {
+        let Some((_, state)) = get_block_and_state_by_state_id(id) else {
+            continue;
+        };
+        let pos = position.0;
+        if state.collision_boxes().any(|shape| {
+            shape
+                .offset(f64::from(pos.x), f64::from(pos.y), f64::from(pos.z))
+                .intersects(&area)
+        }) {
+            return true;
+        }
+    }
+    false
+}
+
+/// Checks the move of the player to the position, returning whether they are on the ground.
+///
+/// The client's claim of being on the ground is only trusted if there is a block below it.
+pub async fn check_move(
+    player: &Player,
+    to: Vector3<f64>,
+    claimed_ground: bool,
+) -> Result<bool, Violation> {
+    let runtime = runtime_config();
+    let config = &runtime.movement_check;
+    if !config.enabled {
+        return Ok(claimed_ground);
+    }
+    let entity = &player.living_entity.entity;
+    let world = &entity.world;
+    let gamemode = player.gamemode.load();
+    let (flying, allow_flying, fly_speed) = {
+        let abilities = player.abilities.lock().await;
+        (
+            abilities.flying,
+            abilities.allow_flying,
+            abilities.fly_speed,
+        )
+    };
+    let fall_flying = entity.fall_flying.load(Ordering::Relaxed);
+    let (from, ticks, velocity) = {
+        let check = player.movement_check.lock();
+        (check.last_valid, check.ticks_since_move(), check.velocity)
+    };
+    let size = entity.bounding_box_size.load();
+    let to_box = BoundingBox::new_from_pos(to.x, to.y, to.z, &size);
+
+    if config.speed {
+        let max_speed = if fall_flying {
+            MAX_FALL_FLYING_SPEED
+        } else if flying {
+            MAX_FLYING_SPEED * f64::from(fly_speed / Abilities::default().fly_speed)
+        } else {
+            MAX_WALKING_SPEED
+                * SPEED_PER_LEVEL.mul_add(f64::from(effect_level(player, EffectType::Speed)), 1.0)
+        };
+        let allowed =
+            (max_speed + config.speed_tolerance) * ticks.min(MAX_TICKS_PER_MOVE) + velocity;
+        if (to.x - from.x).hypot(to.z - from.z) > allowed {
+            return Err(Violation::Speed);
+        }
+    }
+
+    if config.noclip && gamemode != GameMode::Spectator && to != from {
+        let from_box = BoundingBox::new_from_pos(from.x, from.y, from.z, &size);
+        // players stuck in a block, e.g. one placed into them, may move out of it
+        if collides(world, &to_box).await && !collides(world, &from_box).await {
+            return Err(Violation::NoClip);
+        }
+    }
+
+    // levitating players rise and slow falling ones stay in the air for long
+    let may_fly = flying
+        || allow_flying
+        || fall_flying
+        || matches!(gamemode, GameMode::Creative | GameMode::Spectator)
+        || player.living_entity.has_effect(EffectType::Levitation)
+        || player.living_entity.has_effect(EffectType::SlowFalling);
+    let mut on_ground = claimed_ground;
+    if config.fly && !may_fly {
+        let surroundings = Surroundings::of(world, &to_box).await;
+        on_ground = claimed_ground && surroundings.ground;
+        player.movement_check.lock().update_air(
+            from.y,
+            to.y,
+            &surroundings,
+            jump_height(effect_level(player, EffectType::JumpBoost)),
+            config.max_air_ticks,
+        )?;
+    } else if may_fly {
+        player.movement_check.lock().float(to.y);
+    }
+
+    player.movement_check.lock().accept(to);
+    Ok(on_ground)
+}
+
+/// Rubber bands or kicks the player, depending on the config and how often they failed checks.
+pub async fn punish(player: &Player, violation: Violation) {
+    let runtime = runtime_config();
+    let config = &runtime.movement_check;
+    log::warn!(
+        "{} failed the {} movement check",
+        player.gameprofile.name,
+        violation.name()
+    );
+    let (exceeded, last_valid) = {
+        let mut check = player.movement_check.lock();
+        let exceeded = config.kick_after != 0
+            && !check
+                .violations
+                .hit(config.kick_after, Duration::from_mins(1));
+        (exceeded, check.last_valid)
+    };
+    if exceeded || config.action == ViolationAction::Kick {
+        player.kick(TextComponent::text("Illegal movement")).await;
+        return;
+    }
+    let entity = &player.living_entity.entity;
+    player
+        .teleport(last_valid, entity.yaw.load(), entity.pitch.load())
+        .await;
+}
@@ -2,48 +2,93 @@ use std::sync::Arc;
 
 use crate::{
     command::CommandSender,
-    entity::player::{ChatMode, Hand, Player},
+    entity::{
+        data_tracker,
+        mob::{self, strider},
+        player::{ChatMode, Hand, Player},
+        vehicle::{self, VehicleKind},
+        EntityBase,
+    },
     error::PumpkinError,
+    plugin::{self, content::CONTENT, menu::MENUS},
     server::Server,
-    world::player_chunker,
+    world::{
+        block_entity::{beacon, center, BlockEntityKind},
+        player_chunker,
+        vibration::GameEvent,
+    },
 };
 use num_traits::FromPrimitive;
-use pumpkin_config::ADVANCED_CONFIG;
-use pumpkin_core::math::{boundingbox::BoundingBox, position::WorldPosition};
+use pumpkin_api::{
+    event::{
+        block::BlockPlaceEvent,
+        player::{InteractAction, PlayerChatEvent, PlayerInteractEvent},
+    },
+    CustomItem, Event,
+};
+use pumpkin_config::{runtime_config, ADVANCED_CONFIG, BASIC_CONFIG};
+use pumpkin_core::math::{boundingbox::BoundingBox, position::WorldPosition, vector2::Vector2};
 use pumpkin_core::{
     math::{vector3::Vector3, wrap_degrees},
-    text::TextComponent,
+    text::{color::NamedColor, TextComponent},
     GameMode,
 };
-use pumpkin_inventory::{InventoryError, WindowType};
+use pumpkin_entity::{effect_type::EffectType, entity_type::EntityType};
+use pumpkin_inventory::{window_property, InventoryError, PAYMENT_SLOT};
+use pumpkin_macros::sound;
+use pumpkin_protocol::{
+    bytebuf::packet_id::Packet, server::config::SAcknowledgeFinishConfig, ConnectionState,
+    RawPacket, SoundCategory, VarInt,
+};
 use pumpkin_protocol::{
     client::play::CCommandSuggestions,
-    server::play::{SCloseContainer, SCommandSuggestion, SKeepAlive, SSetPlayerGround, SUseItem},
+    server::play::{
+        SCloseContainer, SCommandSuggestion, SKeepAlive, SRenameItem, SSetBeacon, SSetPlayerGround,
+        SUseItem,
+    },
 };
 use pumpkin_protocol::{
     client::play::{
-        Animation, CAcknowledgeBlockChange, CEntityAnimation, CHeadRot, CPingResponse,
-        CPlayerChatMessage, CUpdateEntityPos, CUpdateEntityPosRot, CUpdateEntityRot, FilterType,
+        Animation, CAcknowledgeBlockChange, CBlockUpdate, CEntityAnimation, CMoveVehicle,
+        CPingResponse, CPlayerChatMessage, CPlayerInfoUpdate, ChatType, FilterType, PlayerAction,
+        PreviousMessage,
     },
     server::play::{
-        Action, ActionType, SChatCommand, SChatMessage, SClientCommand, SClientInformationPlay,
-        SConfirmTeleport, SInteract, SPlayPingRequest, SPlayerAbilities, SPlayerAction,
-        SPlayerCommand, SPlayerPosition, SPlayerPositionRotation, SPlayerRotation,
+        Action, ActionType, SChatAck, SChatCommand, SChatMessage, SChatSessionUpdate,
+        SChunkBatchReceived, SClientCommand, SClientInformationPlay, SConfirmTeleport, SInteract,
+        SMoveVehicle, SPaddleBoat, SPlayPingRequest, SPlayerAbilities, SPlayerAction,
+        SPlayerCommand, SPlayerInput, SPlayerPosition, SPlayerPositionRotation, SPlayerRotation,
         SSetCreativeSlot, SSetHeldItem, SSwingArm, SUseItemOn, Status,
     },
 };
-use pumpkin_world::block::{block_registry::get_block_by_item, BlockFace};
+use pumpkin_registry::{is_in, TagCategory};
+use pumpkin_world::{
+    block::{
+        block_registry::{get_block_and_state_by_state_id, get_block_by_item},
+        BlockFace,
+    },
+    cylindrical_chunk_iterator::Cylindrical,
+    item::{
+        component::DataComponent,
+        item_registry::{get_item_by_id, get_item_name},
+        ItemStack,
+    },
+};
 use thiserror::Error;
 
-use super::PlayerConfig;
+use super::{
+    chat_session::{ChatError, ChatSession},
+    interaction_check, movement_check, PlayerConfig,
+};
 
-fn modulus(a: f32, b: f32) -> f32 {
-    ((a % b) + b) % b
-}
+/// How far players walk per step, which sculk sensors can hear
+const STEP_LENGTH: f64 = 1.6;
 
 #[derive(Debug, Error)]
 pub enum BlockPlacingError {
     BlockOutOfReach,
+    /// The player does not look at the block or it is behind other blocks
+    BlockNotInSight,
     InvalidBlockFace,
 }
 
@@ -56,20 +101,22 @@ impl std::fmt::Display for BlockPlacingError {
 impl PumpkinError for BlockPlacingError {
     fn is_kick(&self) -> bool {
         match self {
-            Self::BlockOutOfReach => false,
+            Self::BlockOutOfReach | Self::BlockNotInSight => false,
             Self::InvalidBlockFace => true,
         }
     }
 
     fn severity(&self) -> log::Level {
         match self {
-            Self::BlockOutOfReach | Self::InvalidBlockFace => log::Level::Warn,
+            Self::BlockOutOfReach | Self::BlockNotInSight | Self::InvalidBlockFace => {
+                log::Level::Warn
+            }
         }
     }
 
     fn client_kick_reason(&self) -> Option<String> {
         match self {
-            Self::BlockOutOfReach => None,
+            Self::BlockOutOfReach | Self::BlockNotInSight => None,
             Self::InvalidBlockFace => Some("Invalid block face".into()),
         }
     }
@@ -85,6 +132,7 @@ impl Player {
                 // we should set the pos now to that we requested in the teleport packet, Is may fixed issues when the client sended position packets while being teleported
                 self.living_entity
                     .set_pos(position.x, position.y, position.z);
+                self.movement_check.lock().reset(*position);
 
                 *awaiting_teleport = None;
             } else {
@@ -106,56 +154,144 @@ impl Player {
         pos.clamp(-2.0E7, 2.0E7)
     }
 
+    /// Validates the move, returning whether the player is on the ground if it may be applied
+    async fn check_move(&self, to: Vector3<f64>, claimed_ground: bool) -> Option<bool> {
+        // like vanilla, movement from before a teleport is ignored until the client confirmed it
+        if runtime_config().movement_check.teleport_confirm
+            && self.awaiting_teleport.lock().await.is_some()
+        {
+            return None;
+        }
+        match movement_check::check_move(self, to, claimed_ground).await {
+            Ok(on_ground) => Some(on_ground),
+            Err(violation) => {
+                movement_check::punish(self, violation).await;
+                None
+            }
+        }
+    }
+
+    /// Counts the steps of the player walking on the ground, sculk sensors hear them unless the
+    /// player sneaks. Stepping on a sculk shrieker makes it shriek
+    async fn count_steps(&self, from: Vector3<f64>, to: Vector3<f64>, on_ground: bool) {
+        let entity = &self.living_entity.entity;
+        if !on_ground
+            || entity.sneaking.load(std::sync::atomic::Ordering::Relaxed)
+            || self.gamemode.load() == GameMode::Spectator
+        {
+            return;
+        }
+        let below = WorldPosition(Vector3::new(
+            to.x.floor() as i32,
+            (to.y - 0.2).floor() as i32,
+            to.z.floor() as i32,
+        ));
+        entity.world.step_on_sculk_shrieker(below, self).await;
+        let distance = self.step_distance.load() + (to.x - from.x).hypot(to.z - from.z);
+        if distance < STEP_LENGTH {
+            self.step_distance.store(distance);
+            return;
+        }
+        self.step_distance.store(distance % STEP_LENGTH);
+        entity
+            .world
+            .emit_game_event_by(GameEvent::Step, to, Some(self.entity_id()))
+            .await;
+    }
+
+    /// Whether the player may break the block, logging it if not
+    async fn can_dig_at(&self, location: &WorldPosition) -> bool {
+        match interaction_check::check_block(self, location, None).await {
+            Ok(()) => true,
+            Err(violation) => {
+                log::warn!(
+                    "Player {} failed the {} interaction check at {location}",
+                    self.gameprofile.name,
+                    violation.name()
+                );
+                false
+            }
+        }
+    }
+
+    /// Whether the player may change the block, otherwise the client sees the block again as
+    /// it already predicted the change
+    async fn can_build_at(&self, location: WorldPosition, sequence: VarInt) -> bool {
+        let world = &self.living_entity.entity.world;
+        if world.can_build(self, location).await {
+            return true;
+        }
+        self.deny_block_change(location, sequence).await;
+        false
+    }
+
+    /// Like [`Self::can_build_at`], but spectators may not break blocks and players in adventure
+    /// mode only with items which list the block in `minecraft:can_break`
+    async fn can_break_at(&self, location: WorldPosition, sequence: VarInt) -> bool {
+        let allowed = match self.gamemode.load() {
+            GameMode::Spectator => false,
+            GameMode::Adventure => {
+                let held_item = self.inventory.lock().await.held_item().cloned();
+                let world = &self.living_entity.entity.world;
+                world
+                    .get_block_state_id(location)
+                    .await
+                    .is_ok_and(|state_id| {
+                        adventure_allows(held_item.as_ref(), state_id, "minecraft:can_break")
+                    })
+            }
+            GameMode::Undefined | GameMode::Survival | GameMode::Creative => true,
+        };
+        if !allowed {
+            self.deny_block_change(location, sequence).await;
+            return false;
+        }
+        self.can_build_at(location, sequence).await
+    }
+
+    /// Confirms the client's block changes up to the sequence. The changed blocks are sent first,
+    /// otherwise the client shows the blocks it predicted the changes of as they were before
+    async fn acknowledge_block_change(&self, sequence: VarInt) {
+        self.living_entity.entity.world.flush_block_changes().await;
+        self.client
+            .send_packet(&CAcknowledgeBlockChange::new(sequence))
+            .await;
+    }
+
+    /// Sends the block again, as the client already predicted the change
+    async fn deny_block_change(&self, location: WorldPosition, sequence: VarInt) {
+        let world = &self.living_entity.entity.world;
+        if let Ok(state_id) = world.get_block_state_id(location).await {
+            self.client
+                .send_packet(&CBlockUpdate::new(&location, i32::from(state_id).into()))
+                .await;
+        }
+        self.acknowledge_block_change(sequence).await;
+    }
+
     pub async fn handle_position(self: &Arc<Self>, position: SPlayerPosition) {
         if position.x.is_nan() || position.feet_y.is_nan() || position.z.is_nan() {
             self.kick(TextComponent::text("Invalid movement")).await;
             return;
         }
 
-        let entity = &self.living_entity.entity;
-        self.living_entity.set_pos(
+        let to = Vector3::new(
             Self::clamp_horizontal(position.x),
             Self::clamp_vertical(position.feet_y),
             Self::clamp_horizontal(position.z),
         );
-
-        let pos = entity.pos.load();
-        let last_pos = self.living_entity.last_pos.load();
+        let Some(on_ground) = self.check_move(to, position.ground).await else {
+            return;
+        };
+        let entity = &self.living_entity.entity;
+        let from = entity.pos.load();
+        self.living_entity.set_pos(to.x, to.y, to.z);
 
         entity
             .on_ground
-            .store(position.ground, std::sync::atomic::Ordering::Relaxed);
-
-        let entity_id = entity.entity_id;
-        let Vector3 { x, y, z } = pos;
-        let (last_x, last_y, last_z) = (last_pos.x, last_pos.y, last_pos.z);
-        let world = &entity.world;
-
-        // let delta = Vector3::new(x - lastx, y - lasty, z - lastz);
-        // let velocity = self.velocity;
-
-        // // Player is falling down fast, we should account for that
-        // let max_speed = if self.fall_flying { 300.0 } else { 100.0 };
-
-        // teleport when more than 8 blocks (i guess 8 blocks)
-        // TODO: REPLACE * 2.0 by movement packets. see vanilla for details
-        // if delta.length_squared() - velocity.length_squared() > max_speed * 2.0 {
-        //     self.teleport(x, y, z, self.entity.yaw, self.entity.pitch);
-        //     return;
-        // }
-        // send new position to all other players
-        world
-            .broadcast_packet_except(
-                &[self.gameprofile.id],
-                &CUpdateEntityPos::new(
-                    entity_id.into(),
-                    x.mul_add(4096.0, -(last_x * 4096.0)) as i16,
-                    y.mul_add(4096.0, -(last_y * 4096.0)) as i16,
-                    z.mul_add(4096.0, -(last_z * 4096.0)) as i16,
-                    position.ground,
-                ),
-            )
-            .await;
+            .store(on_ground, std::sync::atomic::Ordering::Relaxed);
+        self.count_steps(from, to, on_ground).await;
+        // the movement is sent to other players by the entity tracker
         player_chunker::update_position(self).await;
     }
 
@@ -176,69 +312,27 @@ impl Player {
             return;
         }
 
-        let entity = &self.living_entity.entity;
-        self.living_entity.set_pos(
+        let to = Vector3::new(
             Self::clamp_horizontal(position_rotation.x),
             Self::clamp_vertical(position_rotation.feet_y),
             Self::clamp_horizontal(position_rotation.z),
         );
+        let Some(on_ground) = self.check_move(to, position_rotation.ground).await else {
+            return;
+        };
+        let entity = &self.living_entity.entity;
+        let from = entity.pos.load();
+        self.living_entity.set_pos(to.x, to.y, to.z);
 
-        let pos = entity.pos.load();
-        let last_pos = self.living_entity.last_pos.load();
-
-        entity.on_ground.store(
-            position_rotation.ground,
-            std::sync::atomic::Ordering::Relaxed,
-        );
+        entity
+            .on_ground
+            .store(on_ground, std::sync::atomic::Ordering::Relaxed);
+        self.count_steps(from, to, on_ground).await;
 
         entity.set_rotation(
             wrap_degrees(position_rotation.yaw) % 360.0,
             wrap_degrees(position_rotation.pitch).clamp(-90.0, 90.0) % 360.0,
         );
-
-        let entity_id = entity.entity_id;
-        let Vector3 { x, y, z } = pos;
-        let (last_x, last_y, last_z) = (last_pos.x, last_pos.y, last_pos.z);
-
-        let yaw = modulus(entity.yaw.load() * 256.0 / 360.0, 256.0);
-        let pitch = modulus(entity.pitch.load() * 256.0 / 360.0, 256.0);
-        // let head_yaw = (entity.head_yaw * 256.0 / 360.0).floor();
-        let world = &entity.world;
-
-        // let delta = Vector3::new(x - lastx, y - lasty, z - lastz);
-        // let velocity = self.velocity;
-
-        // // Player is falling down fast, we should account for that
-        // let max_speed = if self.fall_flying { 300.0 } else { 100.0 };
-
-        // // teleport when more than 8 blocks (i guess 8 blocks)
-        // // TODO: REPLACE * 2.0 by movement packets. see vanilla for details
-        // if delta.length_squared() - velocity.length_squared() > max_speed * 2.0 {
-        //     self.teleport(x, y, z, yaw, pitch);
-        //     return;
-        // }
-        // send new position to all other players
-
-        world
-            .broadcast_packet_except(
-                &[self.gameprofile.id],
-                &CUpdateEntityPosRot::new(
-                    entity_id.into(),
-                    x.mul_add(4096.0, -(last_x * 4096.0)) as i16,
-                    y.mul_add(4096.0, -(last_y * 4096.0)) as i16,
-                    z.mul_add(4096.0, -(last_z * 4096.0)) as i16,
-                    yaw as u8,
-                    pitch as u8,
-                    position_rotation.ground,
-                ),
-            )
-            .await;
-        world
-            .broadcast_packet_except(
-                &[self.gameprofile.id],
-                &CHeadRot::new(entity_id.into(), yaw as u8),
-            )
-            .await;
         player_chunker::update_position(self).await;
     }
 
@@ -255,22 +349,6 @@ impl Player {
             wrap_degrees(rotation.yaw) % 360.0,
             wrap_degrees(rotation.pitch).clamp(-90.0, 90.0) % 360.0,
         );
-        // send new position to all other players
-        let entity_id = entity.entity_id;
-        let yaw = modulus(entity.yaw.load() * 256.0 / 360.0, 256.0);
-        let pitch = modulus(entity.pitch.load() * 256.0 / 360.0, 256.0);
-        // let head_yaw = modulus(entity.head_yaw * 256.0 / 360.0, 256.0);
-
-        let world = &entity.world;
-        let packet =
-            CUpdateEntityRot::new(entity_id.into(), yaw as u8, pitch as u8, rotation.ground);
-        world
-            .broadcast_packet_except(&[self.gameprofile.id], &packet)
-            .await;
-        let packet = CHeadRot::new(entity_id.into(), yaw as u8);
-        world
-            .broadcast_packet_except(&[self.gameprofile.id], &packet)
-            .await;
     }
 
     pub async fn handle_chat_command(
@@ -278,7 +356,7 @@ impl Player {
         server: &Arc<Server>,
         command: SChatCommand,
     ) {
-        let dispatcher = server.command_dispatcher.clone();
+        let dispatcher = server.command_dispatcher().await;
         dispatcher
             .handle_command(
                 &mut CommandSender::Player(self.clone()),
@@ -302,7 +380,196 @@ impl Player {
             .store(ground.on_ground, std::sync::atomic::Ordering::Relaxed);
     }
 
-    pub async fn handle_player_command(&self, command: SPlayerCommand) {
+    pub async fn handle_player_input(&self, input: SPlayerInput) {
+        let previous = self
+            .input
+            .swap(input.input, std::sync::atomic::Ordering::Relaxed);
+        let entity = &self.living_entity.entity;
+        let sneaking = input.has(SPlayerInput::SNEAK);
+        if entity.sneaking.load(std::sync::atomic::Ordering::Relaxed) != sneaking {
+            entity.set_sneaking(sneaking);
+        }
+        // starting to sneak leaves the vehicle
+        if sneaking && previous & SPlayerInput::SNEAK == 0 {
+            self.dismount().await;
+        }
+    }
+
+    pub async fn handle_move_vehicle(self: &Arc<Self>, move_vehicle: SMoveVehicle) {
+        if !move_vehicle.x.is_finite()
+            || !move_vehicle.y.is_finite()
+            || !move_vehicle.z.is_finite()
+            || !move_vehicle.yaw.is_finite()
+            || !move_vehicle.pitch.is_finite()
+        {
+            self.kick(TextComponent::text("Invalid move vehicle packet received"))
+                .await;
+            return;
+        }
+        let to = Vector3::new(
+            Self::clamp_horizontal(move_vehicle.x),
+            Self::clamp_vertical(move_vehicle.y),
+            Self::clamp_horizontal(move_vehicle.z),
+        );
+
+        let world = &self.living_entity.entity.world;
+        let mut vehicle = self.vehicle.lock().await;
+        // the client may still send movements it made before it was dismounted
+        let Some(riding) = vehicle.as_mut() else {
+            return;
+        };
+        let Some(vehicle_entity) = world.entities.lock().await.get(&riding.vehicle_id).cloned()
+        else {
+            return;
+        };
+        // only the first passenger steers, mobs only once they are saddled and the like
+        let steers = match vehicle_entity.get_mob() {
+            Some(mob) => mob
+                .controller()
+                .await
+                .is_some_and(|controller| controller.entity_id() == self.entity_id()),
+            None => {
+                riding.kind != VehicleKind::Passive
+                    && vehicle_entity.get_entity().passengers.lock().first()
+                        == Some(&self.entity_id())
+            }
+        };
+        if !steers {
+            return;
+        }
+        let valid = riding.is_valid_move(to);
+        if !valid {
+            log::warn!("{} moved their vehicle too quickly", self.gameprofile.name);
+        }
+        if !valid
+            || !vehicle::vehicle_listeners()
+                .iter()
+                .all(|listener| listener.on_vehicle_move(self, riding, to))
+        {
+            // move the vehicle back to where we last accepted it
+            self.client
+                .send_packet(&CMoveVehicle::new(
+                    riding.pos.x,
+                    riding.pos.y,
+                    riding.pos.z,
+                    riding.yaw,
+                    riding.pitch,
+                ))
+                .await;
+            return;
+        }
+
+        riding.pos = to;
+        riding.yaw = wrap_degrees(move_vehicle.yaw);
+        riding.pitch = wrap_degrees(move_vehicle.pitch).clamp(-90.0, 90.0);
+        let (yaw, pitch) = (riding.yaw, riding.pitch);
+        drop(vehicle);
+
+        // the entity tracker shows the other players where the vehicle went
+        let entity = vehicle_entity.get_entity();
+        entity.set_pos(to.x, to.y, to.z);
+        entity.set_rotation(yaw, pitch);
+        vehicle::position_passengers(entity).await;
+    }
+
+    pub async fn handle_paddle_boat(&self, paddle: SPaddleBoat) {
+        if let Some(riding) = self.vehicle.lock().await.as_mut() {
+            if riding.kind == VehicleKind::Boat {
+                riding.paddles = (paddle.left_paddle_turning, paddle.right_paddle_turning);
+            }
+        }
+    }
+
+    pub async fn handle_configuration_acknowledged(self: &Arc<Self>, server: &Server) {
+        if !self
+            .reconfiguring
+            .load(std::sync::atomic::Ordering::Relaxed)
+        {
+            self.kick(TextComponent::text(
+                "Acknowledged a configuration, but we did not start one",
+            ))
+            .await;
+            return;
+        }
+        let world = &self.living_entity.entity.world;
+        // the client dropped its world, so nothing may be sent to it until it joins again
+        world
+            .current_players
+            .lock()
+            .await
+            .remove(&self.gameprofile.id);
+        self.abort_chunks("reconfiguration");
+        let watched = self.watched_section.load();
+        let view_distance = player_chunker::get_view_distance(self);
+        let chunks =
+            Cylindrical::new(Vector2::new(watched.x, watched.z), view_distance).all_chunks_within();
+        let watched_chunks = {
+            let pending_chunks = self.pending_chunks.lock();
+            chunks
+                .into_iter()
+                .filter(|chunk| !pending_chunks.contains_key(chunk))
+                .collect::<Vec<_>>()
+        };
+        // the chunks are watched again once the player joins again
+        world.mark_chunks_as_not_watched(&watched_chunks);
+
+        self.client.connection_state.store(ConnectionState::Config);
+        self.client.send_registries_and_finish(server).await;
+    }
+
+    /// Handles the packets of a client which was sent back into the configuration
+    pub async fn handle_reconfiguration_packet(
+        self: &Arc<Self>,
+        server: &Server,
+        packet: &RawPacket,
+    ) -> Result<(), Box<dyn PumpkinError>> {
+        match packet.id.0 {
+            SAcknowledgeFinishConfig::PACKET_ID => {
+                self.client.connection_state.store(ConnectionState::Play);
+                self.reconfiguring
+                    .store(false, std::sync::atomic::Ordering::Relaxed);
+
+                let entity = &self.living_entity.entity;
+                let (position, yaw, pitch) =
+                    (entity.pos.load(), entity.yaw.load(), entity.pitch.load());
+                let world = &entity.world;
+                world
+                    .current_players
+                    .lock()
+                    .await
+                    .insert(self.gameprofile.id, self.clone());
+                world
+                    .spawn_player(
+                        &BASIC_CONFIG,
+                        self.clone(),
+                        &*server.command_dispatcher().await,
+                    )
+                    .await;
+                self.teleport(position, yaw, pitch).await;
+            }
+            id => log::debug!(
+                "Ignoring configuration packet {id:#04x} of {}",
+                self.gameprofile.name
+            ),
+        }
+        Ok(())
+    }
+
+    /// The entity the player rides
+    async fn vehicle_entity(&self) -> Option<Arc<dyn EntityBase>> {
+        let vehicle_id = self.vehicle.lock().await.as_ref()?.vehicle_id;
+        let world = &self.living_entity.entity.world;
+        world.entities.lock().await.get(&vehicle_id).cloned()
+    }
+
+    /// The mob the player rides, if the player steers it
+    async fn controlled_vehicle(&self) -> Option<Arc<dyn EntityBase>> {
+        let vehicle = self.vehicle_entity().await?;
+        let controller = vehicle.get_mob()?.controller().await?;
+        (controller.entity_id() == self.entity_id()).then_some(vehicle)
+    }
+
+    pub async fn handle_player_command(&self, server: &Server, command: SPlayerCommand) {
         if command.entity_id != self.entity_id().into() {
             return;
         }
@@ -312,30 +579,45 @@ impl Player {
             match action {
                 pumpkin_protocol::server::play::Action::StartSneaking => {
                     if !entity.sneaking.load(std::sync::atomic::Ordering::Relaxed) {
-                        entity.set_sneaking(true).await;
+                        entity.set_sneaking(true);
                     }
                 }
                 pumpkin_protocol::server::play::Action::StopSneaking => {
                     if entity.sneaking.load(std::sync::atomic::Ordering::Relaxed) {
-                        entity.set_sneaking(false).await;
+                        entity.set_sneaking(false);
                     }
                 }
                 pumpkin_protocol::server::play::Action::StartSprinting => {
                     if !entity.sprinting.load(std::sync::atomic::Ordering::Relaxed) {
-                        entity.set_sprinting(true).await;
+                        entity.set_sprinting(true);
                     }
                 }
                 pumpkin_protocol::server::play::Action::StopSprinting => {
                     if entity.sprinting.load(std::sync::atomic::Ordering::Relaxed) {
-                        entity.set_sprinting(false).await;
+                        entity.set_sprinting(false);
                     }
                 }
-                pumpkin_protocol::server::play::Action::LeaveBed
-                | pumpkin_protocol::server::play::Action::StartHorseJump
-                | pumpkin_protocol::server::play::Action::StopHorseJump
-                | pumpkin_protocol::server::play::Action::OpenVehicleInventory => {
+                pumpkin_protocol::server::play::Action::LeaveBed => {
                     log::debug!("todo");
                 }
+                pumpkin_protocol::server::play::Action::StartHorseJump => {
+                    let vehicle = self.controlled_vehicle().await;
+                    if let Some(mob) = vehicle.as_ref().and_then(|vehicle| vehicle.get_mob()) {
+                        mob.jump(command.jump_boost.0).await;
+                    }
+                }
+                // the client already made the jump when it lets go of the key
+                pumpkin_protocol::server::play::Action::StopHorseJump => {}
+                pumpkin_protocol::server::play::Action::OpenVehicleInventory => {
+                    let vehicle = self.vehicle_entity().await;
+                    if let Some(mob) = vehicle.as_ref().and_then(|vehicle| vehicle.get_mob()) {
+                        if let Some(inventory) = mob.inventory() {
+                            inventory
+                                .open(self, server, mob.living_entity.entity.entity_id)
+                                .await;
+                        }
+                    }
+                }
                 pumpkin_protocol::server::play::Action::StartFlyingElytra => {
                     let fall_flying = entity.check_fall_flying();
                     if entity
@@ -343,7 +625,7 @@ impl Player {
                         .load(std::sync::atomic::Ordering::Relaxed)
                         != fall_flying
                     {
-                        entity.set_fall_flying(fall_flying).await;
+                        entity.set_fall_flying(fall_flying);
                     }
                 } // TODO
             }
@@ -376,7 +658,7 @@ impl Player {
     }
 
     pub async fn handle_chat_message(&self, chat_message: SChatMessage) {
-        let message = chat_message.message;
+        let message = &chat_message.message;
         if message.len() > 256 {
             self.kick(TextComponent::text("Oversized message")).await;
             return;
@@ -388,55 +670,179 @@ impl Player {
             return;
         }
 
+        let unpacked = self
+            .chat_state
+            .lock()
+            .unpack(self.gameprofile.id, &chat_message);
+        let unpacked = match unpacked {
+            Ok(unpacked) => unpacked,
+            Err(error) => {
+                self.handle_chat_error(&error).await;
+                return;
+            }
+        };
+
+        let event = plugin::fire(PlayerChatEvent::new(
+            plugin::player_info(self),
+            message.clone(),
+        ));
+        if event.is_cancelled() {
+            return;
+        }
+        // a changed message does not match the signature anymore, so it is shown as unsigned content
+        let unsigned_content =
+            (event.message != *message).then(|| TextComponent::text(&event.message));
+
         let gameprofile = &self.gameprofile;
-        log::info!("<chat>{}: {}", gameprofile.name, message);
+        log::info!("<chat>{}: {}", gameprofile.name, event.message);
+        let display_name = self.display_name();
+
+        let previous_messages: Vec<_> = unpacked
+            .last_seen
+            .iter()
+            .map(|signature| PreviousMessage::Signature(signature))
+            .collect();
+        let packet = CPlayerChatMessage::new(
+            gameprofile.id,
+            unpacked.index.into(),
+            unpacked.signature.as_deref(),
+            message,
+            chat_message.timestamp,
+            chat_message.salt,
+            &previous_messages,
+            unsigned_content,
+            FilterType::PassThrough,
+            ChatType::Chat,
+            TextComponent::text(&display_name),
+            None,
+        );
 
-        let entity = &self.living_entity.entity;
-        let world = &entity.world;
-        world
-            .broadcast_packet_all(&CPlayerChatMessage::new(
-                gameprofile.id,
-                1.into(),
-                chat_message.signature.as_deref(),
-                &message,
-                chat_message.timestamp,
-                chat_message.salt,
-                &[],
-                Some(TextComponent::text(&message)),
-                FilterType::PassThrough,
-                1.into(),
-                TextComponent::text(&gameprofile.name),
-                None,
+        let players: Vec<_> = self
+            .living_entity
+            .entity
+            .world
+            .current_players
+            .lock()
+            .await
+            .values()
+            .cloned()
+            .collect();
+        for player in players {
+            if player.ignores(gameprofile.id) {
+                continue;
+            }
+            if let Some(signature) = &unpacked.signature {
+                // the receiver has to acknowledge the message before signing their next one
+                let pending = player.chat_state.lock().add_pending(signature);
+                if let Err(error) = pending {
+                    player.handle_chat_error(&error).await;
+                    continue;
+                }
+            }
+            player.client.send_packet(&packet).await;
+        }
+    }
+
+    /// Tells the player their message was not sent, or kicks them if it can not have come from a vanilla client.
+    async fn handle_chat_error(&self, error: &ChatError) {
+        log::warn!(
+            "Failed to validate a chat message from {}: {error:?}",
+            self.gameprofile.name
+        );
+        let reason = error.to_string();
+        if error.disconnects() {
+            self.kick(TextComponent::text(&reason)).await;
+        } else {
+            self.send_system_message(&TextComponent::text(&reason).color_named(NamedColor::Red))
+                .await;
+        }
+    }
+
+    pub async fn handle_chat_session_update(&self, server: &Server, session: SChatSessionUpdate) {
+        let Some(auth_client) = &server.auth_client else {
+            log::debug!(
+                "Ignoring the chat session of {}, as the server is in offline mode",
+                self.gameprofile.name
+            );
+            return;
+        };
+        let session = match ChatSession::verify(self.gameprofile.id, session, auth_client).await {
+            Ok(session) => Arc::new(session),
+            Err(error) => {
+                let reason = error.to_string();
+                self.kick(TextComponent::text(&reason)).await;
+                return;
+            }
+        };
+        self.chat_state.lock().set_session(session.clone());
+
+        // everyone needs the key to verify the player's messages
+        self.living_entity
+            .entity
+            .world
+            .broadcast_packet_all(&CPlayerInfoUpdate::new(
+                0x02,
+                &[pumpkin_protocol::client::play::Player {
+                    uuid: self.gameprofile.id,
+                    actions: vec![PlayerAction::InitializeChat(Some(session.to_protocol()))],
+                }],
             ))
             .await;
+    }
 
-        /* server.broadcast_packet(
-            self,
-            &CDisguisedChatMessage::new(
-                TextComponent::from(message.clone()),
-                VarInt(0),
-               gameprofile.name.clone().into(),
-                None,
-            ),
-        ) */
+    pub async fn handle_chat_ack(&self, ack: SChatAck) {
+        let acknowledged = self.chat_state.lock().acknowledge(ack.offset.0);
+        if let Err(error) = acknowledged {
+            self.handle_chat_error(&error).await;
+        }
+    }
+
+    pub fn handle_chunk_batch_received(&self, received: &SChunkBatchReceived) {
+        self.client
+            .acknowledge_chunk_batch(received.chunks_per_tick);
     }
 
-    pub async fn handle_client_information(&self, client_information: SClientInformationPlay) {
+    pub async fn handle_client_information(
+        self: &Arc<Self>,
+        client_information: SClientInformationPlay,
+    ) {
         if let (Some(main_hand), Some(chat_mode)) = (
             Hand::from_i32(client_information.main_hand.into()),
             ChatMode::from_i32(client_information.chat_mode.into()),
         ) {
-            *self.config.lock().await = PlayerConfig {
-                locale: client_information.locale,
-                // A Negative view distance would be impossible and make no sense right ?, Mojang: Lets make is signed :D
-                view_distance: client_information.view_distance as u8,
-                chat_mode,
-                chat_colors: client_information.chat_colors,
-                skin_parts: client_information.skin_parts,
-                main_hand,
-                text_filtering: client_information.text_filtering,
-                server_listing: client_information.server_listing,
-            };
+            {
+                let mut tracker = self.living_entity.entity.data_tracker.lock();
+                tracker.set(
+                    &data_tracker::SKIN_PARTS,
+                    client_information.skin_parts as i8,
+                );
+                tracker.set(&data_tracker::MAIN_HAND, main_hand.clone() as i8);
+            }
+            let view_distance = client_information.view_distance as u8;
+            let old_view_distance = std::mem::replace(
+                &mut *self.config.lock().await,
+                PlayerConfig {
+                    locale: client_information.locale,
+                    // A Negative view distance would be impossible and make no sense right ?, Mojang: Lets make is signed :D
+                    view_distance,
+                    chat_mode,
+                    chat_colors: client_information.chat_colors,
+                    skin_parts: client_information.skin_parts,
+                    main_hand,
+                    text_filtering: client_information.text_filtering,
+                    server_listing: client_information.server_listing,
+                },
+            )
+            .view_distance;
+            // the chunks are sent with the new distance when the player joins a world again
+            if view_distance != old_view_distance
+                && !self
+                    .reconfiguring
+                    .load(std::sync::atomic::Ordering::Relaxed)
+                && player_chunker::get_view_distance(self) != 0
+            {
+                player_chunker::update_distances(self).await;
+            }
         } else {
             self.kick(TextComponent::text("Invalid hand or chat type"))
                 .await;
@@ -463,43 +869,118 @@ impl Player {
         };
     }
 
-    pub async fn handle_interact(&self, interact: SInteract) {
+    pub async fn handle_interact(&self, server: &Server, interact: SInteract) {
         let sneaking = interact.sneaking;
         let entity = &self.living_entity.entity;
         if entity.sneaking.load(std::sync::atomic::Ordering::Relaxed) != sneaking {
-            entity.set_sneaking(sneaking).await;
+            entity.set_sneaking(sneaking);
         }
         let Some(action) = ActionType::from_i32(interact.typ.0) else {
             self.kick(TextComponent::text("Invalid action type")).await;
             return;
         };
 
+        if self.gamemode.load() == GameMode::Spectator {
+            // spectators see through the eyes of the players they attack and use nothing
+            if action == ActionType::Attack {
+                let target = entity
+                    .world
+                    .get_player_by_entityid(interact.entity_id.0)
+                    .await;
+                if let Some(target) = target.filter(|target| target.entity_id() != self.entity_id())
+                {
+                    self.set_camera(Some(&*target)).await;
+                }
+            }
+            return;
+        }
+
         match action {
             ActionType::Attack => {
                 let entity_id = interact.entity_id;
-                let config = &ADVANCED_CONFIG.pvp;
-                // TODO: do validation and stuff
-                if !config.enabled {
-                    return;
-                }
-
                 let world = &entity.world;
                 let victim = world.get_player_by_entityid(entity_id.0).await;
                 let Some(victim) = victim else {
-                    self.kick(TextComponent::text("Interacted with invalid entity id"))
-                        .await;
+                    let victim = world.entities.lock().await.get(&entity_id.0).cloned();
+                    let Some(victim) = victim else {
+                        self.kick(TextComponent::text("Interacted with invalid entity id"))
+                            .await;
+                        return;
+                    };
+                    // items, orbs and projectiles can't be attacked
+                    let alive = victim
+                        .get_living_entity()
+                        .is_some_and(|living| living.health.load() > 0.0);
+                    if alive
+                        && interaction_check::check_entity(self, victim.get_entity())
+                            .await
+                            .is_ok()
+                    {
+                        self.attack_entity(&victim).await;
+                    }
                     return;
                 };
-                if victim.living_entity.health.load() <= 0.0 {
+                let runtime = runtime_config();
+                if !runtime.pvp.enabled {
+                    return;
+                }
+                if victim.living_entity.health.load() <= 0.0
+                    || victim.gamemode.load() == GameMode::Spectator
+                {
                     // you can trigger this from a non-modded / innocent client client,
                     // so we shouldn't kick the player
                     return;
                 }
+                if let Err(violation) =
+                    interaction_check::check_entity(self, &victim.living_entity.entity).await
+                {
+                    log::warn!(
+                        "Player {} failed the {} interaction check attacking {}",
+                        self.gameprofile.name,
+                        violation.name(),
+                        victim.gameprofile.name
+                    );
+                    return;
+                }
                 self.attack(&victim).await;
             }
-            ActionType::Interact | ActionType::InteractAt => {
-                log::debug!("todo");
+            ActionType::Interact => {
+                let hand = interact
+                    .hand
+                    .and_then(|hand| Hand::from_i32(hand.0))
+                    .unwrap_or(Hand::Main);
+                let target = entity
+                    .world
+                    .entities
+                    .lock()
+                    .await
+                    .get(&interact.entity_id.0)
+                    .cloned();
+                let Some(mob) = target.as_ref().and_then(|target| target.get_mob()) else {
+                    return;
+                };
+                if interaction_check::check_entity(self, &mob.living_entity.entity)
+                    .await
+                    .is_err()
+                {
+                    return;
+                }
+                // sneaking players look into the inventory of mounts instead of getting on
+                if sneaking {
+                    if let Some(inventory) = mob.inventory() {
+                        inventory
+                            .open(self, server, mob.living_entity.entity.entity_id)
+                            .await;
+                        return;
+                    }
+                }
+                // the item the mob took or ate is gone
+                if mob.interact(self, hand).await {
+                    self.set_container_content(None).await;
+                }
             }
+            // the client sends where it clicked the entity right before the interaction itself
+            ActionType::InteractAt => {}
         }
     }
 
@@ -507,31 +988,35 @@ impl Player {
         match Status::from_i32(player_action.status.0) {
             Some(status) => match status {
                 Status::StartedDigging => {
-                    if !self.can_interact_with_block_at(&player_action.location, 1.0) {
-                        log::warn!(
-                            "Player {0} tried to interact with block out of reach at {1}",
-                            self.gameprofile.name,
-                            player_action.location
-                        );
+                    if !self.can_dig_at(&player_action.location).await
+                        || !self
+                            .can_break_at(player_action.location, player_action.sequence)
+                            .await
+                    {
+                        return;
+                    }
+                    let event = plugin::fire(PlayerInteractEvent::new(
+                        plugin::player_info(self),
+                        InteractAction::LeftClickBlock,
+                        Some(player_action.location),
+                    ));
+                    if event.is_cancelled() {
                         return;
                     }
                     // TODO: do validation
                     // TODO: Config
+                    let location = player_action.location;
+                    let world = &self.living_entity.entity.world;
                     if self.gamemode.load() == GameMode::Creative {
-                        let location = player_action.location;
                         // Block break & block break sound
-                        let entity = &self.living_entity.entity;
-                        let world = &entity.world;
                         world.break_block(location, Some(self)).await;
+                    } else {
+                        // hitting note blocks plays them
+                        world.play_note_block(location).await;
                     }
                 }
                 Status::CancelledDigging => {
-                    if !self.can_interact_with_block_at(&player_action.location, 1.0) {
-                        log::warn!(
-                            "Player {0} tried to interact with block out of reach at {1}",
-                            self.gameprofile.name,
-                            player_action.location
-                        );
+                    if !self.can_dig_at(&player_action.location).await {
                         return;
                     }
                     self.current_block_destroy_stage
@@ -540,12 +1025,11 @@ impl Player {
                 Status::FinishedDigging => {
                     // TODO: do validation
                     let location = player_action.location;
-                    if !self.can_interact_with_block_at(&location, 1.0) {
-                        log::warn!(
-                            "Player {0} tried to interact with block out of reach at {1}",
-                            self.gameprofile.name,
-                            player_action.location
-                        );
+                    if !self.can_dig_at(&location).await
+                        || !self
+                            .can_break_at(location, player_action.sequence.clone())
+                            .await
+                    {
                         return;
                     }
                     // Block break & block break sound
@@ -553,14 +1037,10 @@ impl Player {
                     let world = &entity.world;
                     world.break_block(location, Some(self)).await;
                     // TODO: Send this every tick
-                    self.client
-                        .send_packet(&CAcknowledgeBlockChange::new(player_action.sequence))
-                        .await;
+                    self.acknowledge_block_change(player_action.sequence).await;
                 }
-                Status::DropItemStack
-                | Status::DropItem
-                | Status::ShootArrowOrFinishEating
-                | Status::SwapItem => {
+                Status::ShootArrowOrFinishEating => self.lower_shield(),
+                Status::DropItemStack | Status::DropItem | Status::SwapItem => {
                     log::debug!("todo");
                 }
             },
@@ -569,18 +1049,12 @@ impl Player {
     }
 
     pub async fn handle_keep_alive(&self, keep_alive: SKeepAlive) {
-        if self
-            .wait_for_keep_alive
-            .load(std::sync::atomic::Ordering::Relaxed)
-            && keep_alive.keep_alive_id
-                == self
-                    .keep_alive_id
-                    .load(std::sync::atomic::Ordering::Relaxed)
-        {
-            self.wait_for_keep_alive
-                .store(false, std::sync::atomic::Ordering::Relaxed);
+        let answered = self.keep_alive.lock().answer(keep_alive.keep_alive_id);
+        if answered {
+            self.broadcast_latency().await;
         } else {
-            self.kick(TextComponent::text("Timeout")).await;
+            self.kick(TextComponent::translate("disconnect.timeout", vec![]))
+                .await;
         }
     }
 
@@ -603,35 +1077,112 @@ impl Player {
 
     pub async fn handle_use_item_on(
         &self,
+        server: &Server,
         use_item_on: SUseItemOn,
     ) -> Result<(), Box<dyn PumpkinError>> {
         let location = use_item_on.location;
 
-        if !self.can_interact_with_block_at(&location, 1.0) {
-            // TODO: maybe log?
-            return Err(BlockPlacingError::BlockOutOfReach.into());
+        let cursor = Vector3::new(
+            f64::from(use_item_on.cursor_pos_x),
+            f64::from(use_item_on.cursor_pos_y),
+            f64::from(use_item_on.cursor_pos_z),
+        );
+        match interaction_check::check_block(self, &location, Some(cursor)).await {
+            Ok(()) => {}
+            Err(interaction_check::Violation::Reach) => {
+                return Err(BlockPlacingError::BlockOutOfReach.into());
+            }
+            Err(_) => return Err(BlockPlacingError::BlockNotInSight.into()),
+        }
+
+        if self.gamemode.load() == GameMode::Spectator {
+            self.acknowledge_block_change(use_item_on.sequence).await;
+            return Ok(());
+        }
+
+        let event = plugin::fire(PlayerInteractEvent::new(
+            plugin::player_info(self),
+            InteractAction::RightClickBlock,
+            Some(location),
+        ));
+        if event.is_cancelled() {
+            self.acknowledge_block_change(use_item_on.sequence).await;
+            return Ok(());
         }
 
         if let Some(face) = BlockFace::from_i32(use_item_on.face.0) {
+            let clicked_world_pos = WorldPosition(location.0);
+            let entity = &self.living_entity.entity;
+            let world = &entity.world;
+            let clicked_state_id = world.get_block_state_id(clicked_world_pos).await?;
             let mut inventory = self.inventory.lock().await;
             let item_slot = inventory.held_item_mut();
-            if let Some(item) = item_slot {
-                let block = get_block_by_item(item.item_id);
-                // check if item is a block, Because Not every item can be placed :D
-                if let Some(block) = block {
-                    let entity = &self.living_entity.entity;
-                    let world = &entity.world;
+            let custom_item = item_slot.as_ref().and_then(|item| CONTENT.item(item));
+            if self.interact_custom(
+                Some((clicked_world_pos, clicked_state_id)),
+                custom_item.as_ref(),
+            ) {
+                self.acknowledge_block_change(use_item_on.sequence).await;
+                return Ok(());
+            }
 
-                    // TODO: Config
-                    // Decrease Block count
-                    if self.gamemode.load() != GameMode::Creative {
+            // sneaking players place blocks against blocks with a container instead of opening it
+            if !entity.sneaking.load(std::sync::atomic::Ordering::Relaxed) {
+                if world.use_block(clicked_world_pos).await {
+                    self.acknowledge_block_change(use_item_on.sequence).await;
+                    return Ok(());
+                }
+                if let Some((id, container)) = world.block_entity(clicked_world_pos).await {
+                    drop(inventory);
+                    self.open_block_container(server, id, container).await;
+                    world
+                        .emit_game_event_by(
+                            GameEvent::ContainerOpen,
+                            center(clicked_world_pos),
+                            Some(self.entity_id()),
+                        )
+                        .await;
+                    self.acknowledge_block_change(use_item_on.sequence).await;
+                    return Ok(());
+                }
+            }
+
+            if let Some(item) = item_slot {
+                // spawn eggs summon their mob in front of the clicked face
+                let spawned = get_item_name(item.item_id)
+                    .and_then(|name| name.strip_suffix("_spawn_egg"))
+                    .and_then(EntityType::from_name);
+                if let Some(entity_type) = spawned.filter(|_| custom_item.is_none()) {
+                    let world_pos = WorldPosition(location.0 + face.to_offset());
+                    let pos = world_pos.0;
+                    let position = Vector3::new(
+                        f64::from(pos.x) + 0.5,
+                        f64::from(pos.y),
+                        f64::from(pos.z) + 0.5,
+                    );
+                    if self.gamemode.load() != GameMode::Adventure
+                        && world.can_build(self, world_pos).await
+                        && mob::summon(world, entity_type, position).await.is_some()
+                        && self.gamemode.load() != GameMode::Creative
+                    {
                         item.item_count -= 1;
                         if item.item_count == 0 {
                             *item_slot = None;
                         }
                     }
-
-                    let clicked_world_pos = WorldPosition(location.0);
+                    self.acknowledge_block_change(use_item_on.sequence).await;
+                    return Ok(());
+                }
+                // custom items only place the custom block they are registered with
+                let state_id = match &custom_item {
+                    Some(custom_item) => custom_item
+                        .block
+                        .as_ref()
+                        .and_then(|block| CONTENT.block_state(block)),
+                    None => get_block_by_item(item.item_id).map(|block| block.default_state_id),
+                };
+                // check if item is a block, Because Not every item can be placed :D
+                if let Some(state_id) = state_id {
                     let clicked_block_state = world.get_block_state(clicked_world_pos).await?;
 
                     let world_pos = if clicked_block_state.replaceable {
@@ -651,14 +1202,53 @@ impl Player {
                     let bounding_box = entity.bounding_box.load();
                     //TODO: Make this check for every entity in that posistion
                     if !bounding_box.intersects(&block_bounding_box) {
-                        world
-                            .set_block_state(world_pos, block.default_state_id)
-                            .await;
+                        // in adventure mode the clicked block has to be in `minecraft:can_place_on`
+                        let may_place = self.gamemode.load() != GameMode::Adventure
+                            || adventure_allows(
+                                Some(&*item),
+                                clicked_state_id,
+                                "minecraft:can_place_on",
+                            );
+                        let placed = if may_place && world.can_build(self, world_pos).await {
+                            let event = plugin::fire(BlockPlaceEvent::new(
+                                plugin::player_info(self),
+                                world_pos,
+                                state_id,
+                            ));
+                            (!event.is_cancelled()).then_some(event.state_id)
+                        } else {
+                            None
+                        };
+                        if let Some(state_id) = placed {
+                            world.set_block_state(world_pos, state_id).await;
+                            world
+                                .emit_game_event_by(
+                                    GameEvent::BlockPlace,
+                                    center(world_pos),
+                                    Some(self.entity_id()),
+                                )
+                                .await;
+                            // TODO: Config
+                            // Decrease Block count
+                            if self.gamemode.load() != GameMode::Creative {
+                                item.item_count -= 1;
+                                if item.item_count == 0 {
+                                    *item_slot = None;
+                                }
+                            }
+                        } else {
+                            // the client already shows the block it predicted
+                            let previous = world.get_block_state_id(world_pos).await?;
+                            self.client
+                                .send_packet(&CBlockUpdate::new(
+                                    &world_pos,
+                                    i32::from(previous).into(),
+                                ))
+                                .await;
+                        }
                     }
                 }
-                self.client
-                    .send_packet(&CAcknowledgeBlockChange::new(use_item_on.sequence))
-                    .await;
+                self.acknowledge_block_change(use_item_on.sequence).await;
             }
 
             Ok(())
@@ -667,7 +1257,67 @@ impl Player {
         }
     }
 
-    pub fn handle_use_item(&self, _use_item: &SUseItem) {
+    /// Calls the handler of the clicked custom block, then the one of the used custom item. Returns
+    /// true if one of them replaces what would happen otherwise
+    fn interact_custom(
+        &self,
+        clicked: Option<(WorldPosition, u16)>,
+        item: Option<&CustomItem>,
+    ) -> bool {
+        let player = plugin::player_info(self);
+        let position = clicked.map(|(position, _)| position);
+        clicked
+            .and_then(|(_, state_id)| CONTENT.block(state_id))
+            .and_then(|block| block.on_interact)
+            .is_some_and(|on_interact| on_interact(&player, position))
+            || item
+                .and_then(|item| item.on_use.as_ref())
+                .is_some_and(|on_use| on_use(&player, position))
+    }
+
+    pub async fn handle_use_item(&self, use_item: &SUseItem) {
+        if self.gamemode.load() == GameMode::Spectator {
+            return;
+        }
+        let event = plugin::fire(PlayerInteractEvent::new(
+            plugin::player_info(self),
+            InteractAction::RightClickAir,
+            None,
+        ));
+        if event.is_cancelled() {
+            return;
+        }
+        let hand = Hand::from_i32(use_item.hand.0).unwrap_or(Hand::Main);
+        let (held_item, used_item) = {
+            let inventory = self.inventory.lock().await;
+            let used_item = match hand {
+                Hand::Main => inventory.held_item(),
+                Hand::Off => inventory.offhand_item(),
+            };
+            (inventory.held_item().cloned(), used_item.cloned())
+        };
+        let custom_item = held_item.and_then(|item| CONTENT.item(&item));
+        if self.interact_custom(None, custom_item.as_ref()) {
+            return;
+        }
+        if self.throw_potion(hand).await {
+            return;
+        }
+        if used_item.as_ref().is_some_and(ItemStack::is_shield) {
+            self.raise_shield(hand).await;
+            return;
+        }
+        if self.held_item_name(hand).await == Some(strider::STEERING_ITEM) {
+            let vehicle = self.controlled_vehicle().await;
+            let Some(mob) = vehicle.as_ref().and_then(|vehicle| vehicle.get_mob()) else {
+                return;
+            };
+            if mob.boost().await {
+                self.wear_held_item(hand, 1, "minecraft:fishing_rod").await;
+                self.set_container_content(None).await;
+            }
+            return;
+        }
         // TODO: handle packet correctly
         log::error!("An item was used(SUseItem), but the packet is not implemented yet");
     }
@@ -679,6 +1329,9 @@ impl Player {
             return;
         }
         self.inventory.lock().await.set_selected(slot as usize);
+        if matches!(self.raised_shield.load(), Some((Hand::Main, _))) {
+            self.lower_shield();
+        }
     }
 
     pub async fn handle_set_creative_slot(
@@ -689,12 +1342,21 @@ impl Player {
             return Err(InventoryError::PermissionError);
         }
         let valid_slot = packet.slot >= 0 && packet.slot <= 45;
+        let item = packet.clicked_item.to_item();
+        if !item.as_ref().map_or(true, is_valid_creative_item) {
+            log::warn!(
+                "Player {} tried to take an invalid item out of the creative inventory",
+                self.gameprofile.name
+            );
+            // the client already shows the item
+            self.set_container_content(None).await;
+            return Ok(());
+        }
         if valid_slot {
-            self.inventory.lock().await.set_slot(
-                packet.slot as usize,
-                packet.clicked_item.to_item(),
-                true,
-            )?;
+            self.inventory
+                .lock()
+                .await
+                .set_slot(packet.slot as usize, item, true)?;
         };
         // TODO: The Item was dropped per drag and drop,
         Ok(())
@@ -703,12 +1365,8 @@ impl Player {
     // TODO:
     // This function will in the future be used to keep track of if the client is in a valid state.
     // But this is not possible yet
-    pub async fn handle_close_container(&self, server: &Server, packet: SCloseContainer) {
-        let Some(_window_type) = WindowType::from_i32(packet.window_id.0) else {
-            self.kick(TextComponent::text("Invalid window ID")).await;
-            return;
-        };
-        // window_id 0 represents both 9x1 Generic AND inventory here
+    pub async fn handle_close_container(&self, server: &Server, _packet: SCloseContainer) {
+        MENUS.closed(self);
         let mut inventory = self.inventory.lock().await;
 
         inventory.state_id = 0;
@@ -718,10 +1376,115 @@ impl Player {
             if let Some(container) = open_containers.get_mut(&id) {
                 container.remove_player(self.entity_id());
             }
+            drop(open_containers);
             self.open_container.store(None);
+            let world = &self.living_entity.entity.world;
+            if let Some(position) = world.block_entity_position(id).await {
+                world
+                    .emit_game_event_by(
+                        GameEvent::ContainerClose,
+                        center(position),
+                        Some(self.entity_id()),
+                    )
+                    .await;
+            }
         }
     }
 
+    pub fn handle_rename_item(&self, packet: SRenameItem) {
+        // the client limits names to 50 characters
+        if packet.item_name.chars().count() > 50 {
+            return;
+        }
+        MENUS.rename(self, packet.item_name);
+    }
+
+    /// Changes the effects of the beacon the player looks into, which costs the payment item
+    pub async fn handle_set_beacon(&self, server: &Server, packet: SSetBeacon) {
+        let Some(container_id) = self.open_container.load() else {
+            return;
+        };
+        let Some(container) = server
+            .try_get_container(self.entity_id(), container_id)
+            .await
+        else {
+            return;
+        };
+        let world = &self.living_entity.entity.world;
+        let Some(position) = world.block_entity_position(container_id).await else {
+            return;
+        };
+        let block_entity = world
+            .block_entities
+            .lock()
+            .await
+            .get(&position)
+            .map(|block_entity| block_entity.kind);
+        let Some(BlockEntityKind::Beacon(state)) = block_entity else {
+            return;
+        };
+        let Some(primary) = packet
+            .primary_effect
+            .and_then(|effect| EffectType::from_id(effect.0))
+        else {
+            return;
+        };
+        let secondary = match packet.secondary_effect {
+            Some(effect) => match EffectType::from_id(effect.0) {
+                Some(effect) => Some(effect),
+                None => return,
+            },
+            None => None,
+        };
+        if !beacon::allows_effects(state.levels, primary, secondary) {
+            return;
+        }
+
+        {
+            let mut container = container.lock().await;
+            let mut slots = container.all_slots();
+            let Some(payment) = slots.get_mut(PAYMENT_SLOT) else {
+                return;
+            };
+            let paid = payment.as_ref().is_some_and(|payment| {
+                get_item_name(payment.item_id).is_some_and(|name| {
+                    is_in(TagCategory::Item, "minecraft:beacon_payment_items", name)
+                })
+            });
+            if !paid {
+                return;
+            }
+            if let Some(stack) = payment {
+                stack.item_count -= 1;
+                if stack.item_count == 0 {
+                    **payment = None;
+                }
+            }
+            drop(slots);
+            container.set_property(
+                window_property::Beacon::FirstPotionEffect as i16,
+                primary as i16 + 1,
+            );
+            container.set_property(
+                window_property::Beacon::SecondPotionEffect as i16,
+                secondary.map_or(0, |secondary| secondary as i16 + 1),
+            );
+            self.set_container_content(Some(&mut *container)).await;
+        }
+        let pos = position.0;
+        world
+            .play_sound(
+                sound!("minecraft:block.beacon.power_select"),
+                SoundCategory::Blocks,
+                &Vector3::new(
+                    f64::from(pos.x) + 0.5,
+                    f64::from(pos.y) + 0.5,
+                    f64::from(pos.z) + 0.5,
+                ),
+            )
+            .await;
+    }
+
     pub async fn handle_command_suggestion(
         self: &Arc<Self>,
         packet: SCommandSuggestion,
@@ -737,10 +1500,8 @@ impl Player {
             return;
         };
 
-        let suggestions = server
-            .command_dispatcher
-            .find_suggestions(&mut src, server, cmd)
-            .await;
+        let dispatcher = server.command_dispatcher().await;
+        let suggestions = dispatcher.find_suggestions(&mut src, server, cmd).await;
 
         let response = CCommandSuggestions::new(
             packet.id,
@@ -752,3 +1513,35 @@ impl Player {
         self.client.send_packet(&response).await;
     }
 }
+
+/// Whether the item's `component`, `minecraft:can_break` or `minecraft:can_place_on`, lists the
+/// block state, which players in adventure mode need to change it
+fn adventure_allows(item: Option<&ItemStack>, state_id: u16, component: &str) -> bool {
+    let Some((block, _)) = get_block_and_state_by_state_id(state_id) else {
+        return false;
+    };
+    let predicate = match item.and_then(|item| item.components.get(component)) {
+        Some(DataComponent::CanBreak(predicate) | DataComponent::CanPlaceOn(predicate)) => {
+            predicate
+        }
+        _ => return false,
+    };
+    predicate.predicates.iter().any(|predicate| {
+        predicate.test(block, state_id, |tag| {
+            is_in(TagCategory::Block, tag, &block.name)
+        })
+    })
+}
+
+/// Whether the client could have made the item in the creative inventory, like in vanilla only
+/// known items in stacks they fit in are
+fn is_valid_creative_item(item: &ItemStack) -> bool {
+    let Some(registered) = get_item_by_id(item.item_id) else {
+        return false;
+    };
+    let max_stack_size = match item.components.get("minecraft:max_stack_size") {
+        Some(DataComponent::MaxStackSize(size)) => *size,
+        _ => i32::from(registered.components.max_stack_size),
+    };
+    item.item_count > 0 && i32::from(item.item_count) <= max_stack_size
+}
@@ -0,0 +1,153 @@
+//! Plugin messaging: custom payloads sent on named channels, which mods and proxies use to talk to the server.
+//!
+//! Clients announce the channels they listen on with `minecraft:register`, we announce ours the same way
+//! during the configuration.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock},
+};
+
+use async_trait::async_trait;
+use bytes::BytesMut;
+use parking_lot::RwLock;
+use pumpkin_protocol::{
+    bytebuf::ByteBuffer,
+    client::{config::CPluginMessage, play::CPlayPluginMessage},
+    ConnectionState,
+};
+
+use crate::entity::player::Player;
+
+use super::Client;
+
+pub const BRAND: &str = "minecraft:brand";
+pub const REGISTER: &str = "minecraft:register";
+pub const UNREGISTER: &str = "minecraft:unregister";
+/// Answers with the name and version of the server
+pub const PUMPKIN_BRAND: &str = "pumpkin:brand";
+/// Used by minimap mods to keep their maps apart per world
+pub const WORLD_ID: &str = "worldinfo:world_id";
+
+/// The most channels a client may register, so it can not grow the set forever
+const MAX_CLIENT_CHANNELS: usize = 128;
+
+static CHANNEL_HANDLERS: LazyLock<RwLock<HashMap<String, Arc<dyn PluginChannelHandler>>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Handles the messages clients send on a channel.
+#[async_trait]
+pub trait PluginChannelHandler: Send + Sync {
+    /// Called for every message on the channel, `player` is None during the configuration.
+    async fn on_message(&self, client: &Client, player: Option<&Arc<Player>>, data: &[u8]);
+}
+
+/// Registers the handler for the channel, replacing the previous one.
+///
+/// Clients joining afterwards are told that the server listens on the channel.
+pub fn register_channel_handler(channel: &str, handler: Arc<dyn PluginChannelHandler>) {
+    CHANNEL_HANDLERS
+        .write()
+        .insert(channel.to_string(), handler);
+}
+
+/// Returns false if no handler was registered for the channel.
+pub fn unregister_channel_handler(channel: &str) -> bool {
+    CHANNEL_HANDLERS.write().remove(channel).is_some()
+}
+
+/// The channels we listen on, as sent in `minecraft:register`
+fn server_channels() -> Vec<u8> {
+    let handlers = CHANNEL_HANDLERS.read();
+    let mut channels = vec![PUMPKIN_BRAND, WORLD_ID];
+    channels.extend(handlers.keys().map(String::as_str));
+    channels.join("\0").into_bytes()
+}
+
+impl Client {
+    /// Sends a custom payload, in the configuration or in play.
+    ///
+    /// Clients ignore channels they don't know, see [`Client::listens_on`].
+    pub async fn send_plugin_message(&self, channel: &str, data: &[u8]) {
+        if self.connection_state.load() == ConnectionState::Config {
+            self.send_packet(&CPluginMessage::new(channel, data)).await;
+        } else {
+            self.send_packet(&CPlayPluginMessage::new(channel, data))
+                .await;
+        }
+    }
+
+    /// Returns whether the client registered the channel.
+    pub async fn listens_on(&self, channel: &str) -> bool {
+        self.plugin_channels.lock().await.contains(channel)
+    }
+
+    /// Tells the client which channels we listen on.
+    pub async fn send_registered_channels(&self) {
+        self.send_plugin_message(REGISTER, &server_channels()).await;
+    }
+
+    /// Handles a custom payload from the client, `player` is None during the configuration.
+    pub async fn handle_plugin_message(
+        &self,
+        player: Option<&Arc<Player>>,
+        channel: &str,
+        data: &[u8],
+    ) {
+        log::debug!("Handling plugin message on {channel}");
+        match channel {
+            BRAND | "MC|Brand" => {
+                let mut buf = ByteBuffer::new(BytesMut::from(data));
+                match buf.get_string() {
+                    Ok(brand) => *self.brand.lock().await = Some(brand),
+                    Err(error) => self.kick(&error.to_string()).await,
+                }
+            }
+            REGISTER => {
+                let mut channels = self.plugin_channels.lock().await;
+                for channel in data.split(|byte| *byte == 0) {
+                    if channels.len() >= MAX_CLIENT_CHANNELS {
+                        log::debug!("Client id {} registered too many channels", self.id);
+                        break;
+                    }
+                    if let Ok(channel) = std::str::from_utf8(channel) {
+                        if !channel.is_empty() {
+                            channels.insert(channel.to_string());
+                        }
+                    }
+                }
+            }
+            UNREGISTER => {
+                let mut channels = self.plugin_channels.lock().await;
+                for channel in data.split(|byte| *byte == 0) {
+                    if let Ok(channel) = std::str::from_utf8(channel) {
+                        channels.remove(channel);
+                    }
+                }
+            }
+            PUMPKIN_BRAND => {
+                let mut buf = ByteBuffer::empty();
+                buf.put_string(&format!("Pumpkin {}", env!("CARGO_PKG_VERSION")));
+                self.send_plugin_message(PUMPKIN_BRAND, buf.buf()).await;
+            }
+            WORLD_ID => {
+                let Some(player) = player else {
+                    return;
+                };
+                // the seed is the same for every player and across restarts
+                let world_id = player.living_entity.entity.world.level.seed.0.to_string();
+                // the request starts with the same two bytes, followed by the id prefixed with its length
+                let mut response = vec![0, 42, world_id.len() as u8];
+                response.extend_from_slice(world_id.as_bytes());
+                self.send_plugin_message(WORLD_ID, &response).await;
+            }
+            _ => {
+                let handler = CHANNEL_HANDLERS.read().get(channel).cloned();
+                match handler {
+                    Some(handler) => handler.on_message(self, player, data).await,
+                    None => log::debug!("No handler for plugin channel {channel}"),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,393 @@
+//! The packets waiting to be written to a connection in the play state.
+//!
+//! A task per connection writes them, so sending never waits on a slow socket. Keep alives
+//! skip the line, chunks are limited to a number per tick and only serialized once they are
+//! written, and a client which falls too far behind is disconnected instead of growing the
+//! queue forever.
+//!
+//! Chunks are sent in batches the client acknowledges with the rate it can take, like vanilla
+//! does, so a slow connection isn't flooded with chunks when joining.
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
+
+use parking_lot::Mutex;
+use pumpkin_config::{ADVANCED_CONFIG, BASIC_CONFIG};
+use pumpkin_core::math::vector2::Vector2;
+use pumpkin_protocol::{
+    bytebuf::packet_id::Packet,
+    client::play::{CChunkBatchFinished, CChunkBatchStart, CChunkData, CKeepAlive, CPingResponse},
+    packet_encoder::{PacketEncoder, PreparedPacket},
+    translation::ProtocolTranslator,
+    ConnectionState, VarInt,
+};
+use pumpkin_world::chunk::ChunkData;
+use thiserror::Error;
+use tokio::{
+    io::AsyncWriteExt,
+    net::tcp::OwnedWriteHalf,
+    sync::{Mutex as AsyncMutex, Notify, RwLock},
+    time::{sleep_until, Instant},
+};
+
+use crate::server::metrics::PACKETS;
+
+/// How many packets are encoded before they are written to the socket together
+const WRITE_BATCH: usize = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PacketPriority {
+    /// Written before everything else, for packets the client has to answer in time
+    Urgent,
+    /// Written in the order they were sent, but never behind chunks, e.g. teleports
+    Normal,
+}
+
+impl PacketPriority {
+    #[must_use]
+    pub const fn of(packet_id: i32) -> Self {
+        if packet_id == CKeepAlive::PACKET_ID || packet_id == CPingResponse::PACKET_ID {
+            Self::Urgent
+        } else {
+            Self::Normal
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SendQueueStats {
+    pub queued_packets: usize,
+    /// The size of the queued packets, chunks are not counted as they are serialized when written
+    pub queued_bytes: usize,
+    pub queued_chunks: usize,
+    /// The most bytes which were queued at once
+    pub peak_queued_bytes: usize,
+    pub sent_packets: u64,
+    pub sent_chunks: u64,
+    /// The bytes written to the socket, after compression
+    pub sent_bytes: u64,
+}
+
+#[derive(Error, Debug)]
+#[error("{queued_bytes} bytes are waiting to be sent, the client is not keeping up")]
+pub struct SendQueueFull {
+    pub queued_bytes: usize,
+}
+
+enum Outgoing {
+    Packet(PreparedPacket),
+    Chunk(Arc<RwLock<ChunkData>>),
+}
+
+/// How many chunks the client gets per tick, following what it asks for in its acknowledgements
+struct ChunkPacing {
+    desired_per_tick: f32,
+    /// How many chunks may be sent, grows by the desired rate every tick
+    quota: f32,
+    /// Batches the client hasn't acknowledged yet
+    unacknowledged: u32,
+    /// Only one batch is sent before the client told its rate the first time
+    max_unacknowledged: u32,
+}
+
+impl Default for ChunkPacing {
+    fn default() -> Self {
+        Self {
+            desired_per_tick: 9.0,
+            quota: 0.0,
+            unacknowledged: 0,
+            max_unacknowledged: 1,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Queued {
+    urgent: VecDeque<PreparedPacket>,
+    normal: VecDeque<PreparedPacket>,
+    chunks: VecDeque<(Vector2<i32>, Arc<RwLock<ChunkData>>)>,
+    pacing: ChunkPacing,
+    stats: SendQueueStats,
+    closed: bool,
+}
+
+#[derive(Default)]
+pub struct SendQueue {
+    queued: Mutex<Queued>,
+    /// Wakes the writer when something was queued
+    queued_notify: Notify,
+    /// Wakes chunk senders waiting for room
+    drained: Notify,
+}
+
+impl SendQueue {
+    /// Queues the packet, failing if too much is already waiting.
+    pub fn push(
+        &self,
+        priority: PacketPriority,
+        packet: PreparedPacket,
+    ) -> Result<(), SendQueueFull> {
+        let mut queued = self.queued.lock();
+        if queued.closed {
+            return Ok(());
+        }
+        let queued_bytes = queued.stats.queued_bytes + packet.len();
+        if queued_bytes > ADVANCED_CONFIG.send_queue.max_queued_bytes {
+            return Err(SendQueueFull { queued_bytes });
+        }
+
+        queued.stats.queued_packets += 1;
+        queued.stats.queued_bytes = queued_bytes;
+        queued.stats.peak_queued_bytes = queued.stats.peak_queued_bytes.max(queued_bytes);
+        match priority {
+            PacketPriority::Urgent => queued.urgent.push_back(packet),
+            PacketPriority::Normal => queued.normal.push_back(packet),
+        }
+        self.queued_notify.notify_one();
+        Ok(())
+    }
+
+    /// Queues the chunk, waiting for room if too many chunks are already queued.
+    pub async fn push_chunk(&self, position: Vector2<i32>, chunk: Arc<RwLock<ChunkData>>) {
+        loop {
+            // registered before checking, so room made in between is not missed
+            let drained = self.drained.notified();
+            tokio::pin!(drained);
+            drained.as_mut().enable();
+            {
+                let mut queued = self.queued.lock();
+                if queued.closed {
+                    return;
+                }
+                if queued.chunks.len() < ADVANCED_CONFIG.send_queue.max_queued_chunks {
+                    queued.chunks.push_back((position, chunk));
+                    queued.stats.queued_chunks += 1;
+                    self.queued_notify.notify_one();
+                    return;
+                }
+            }
+            drained.await;
+        }
+    }
+
+    /// Drops the queued chunks at the positions, e.g. because they were unloaded before being sent.
+    pub fn cancel_chunks(&self, positions: &[Vector2<i32>]) {
+        let mut queued = self.queued.lock();
+        queued
+            .chunks
+            .retain(|(position, _)| !positions.contains(position));
+        queued.stats.queued_chunks = queued.chunks.len();
+        self.drained.notify_waiters();
+    }
+
+    /// Drops everything queued and stops the writer.
+    pub fn close(&self) {
+        let mut queued = self.queued.lock();
+        queued.closed = true;
+        queued.urgent.clear();
+        queued.normal.clear();
+        queued.chunks.clear();
+        queued.stats.queued_packets = 0;
+        queued.stats.queued_bytes = 0;
+        queued.stats.queued_chunks = 0;
+        self.queued_notify.notify_one();
+        self.drained.notify_waiters();
+    }
+
+    /// The client received a batch of chunks and can take `chunks_per_tick` from now on.
+    pub fn acknowledge_chunk_batch(&self, chunks_per_tick: f32) {
+        let mut queued = self.queued.lock();
+        let pacing = &mut queued.pacing;
+        pacing.unacknowledged = pacing.unacknowledged.saturating_sub(1);
+        pacing.desired_per_tick = if chunks_per_tick.is_nan() {
+            0.01
+        } else {
+            chunks_per_tick.clamp(0.01, 64.0)
+        };
+        if pacing.unacknowledged == 0 {
+            pacing.quota = 1.0;
+        }
+        pacing.max_unacknowledged = 10;
+        self.queued_notify.notify_one();
+    }
+
+    #[must_use]
+    pub fn stats(&self) -> SendQueueStats {
+        self.queued.lock().stats
+    }
+
+    fn pop(&self, chunk_budget: &mut u32) -> Option<Outgoing> {
+        let mut queued = self.queued.lock();
+        if let Some(packet) = queued
+            .urgent
+            .pop_front()
+            .or_else(|| queued.normal.pop_front())
+        {
+            queued.stats.queued_packets -= 1;
+            queued.stats.queued_bytes -= packet.len();
+            return Some(Outgoing::Packet(packet));
+        }
+        if *chunk_budget == 0 {
+            return None;
+        }
+        let (_, chunk) = queued.chunks.pop_front()?;
+        *chunk_budget -= 1;
+        queued.stats.queued_chunks -= 1;
+        self.drained.notify_waiters();
+        Some(Outgoing::Chunk(chunk))
+    }
+
+    /// How many chunks may be sent this tick, none while too many batches are unacknowledged
+    fn chunk_budget(&self, chunks_per_tick: u32) -> u32 {
+        let mut queued = self.queued.lock();
+        let pacing = &mut queued.pacing;
+        if pacing.unacknowledged >= pacing.max_unacknowledged {
+            return 0;
+        }
+        pacing.quota =
+            (pacing.quota + pacing.desired_per_tick).min(pacing.desired_per_tick.max(1.0));
+        (pacing.quota as u32).min(chunks_per_tick)
+    }
+
+    fn record_chunk_batch(&self, chunks: u32) {
+        let mut queued = self.queued.lock();
+        let pacing = &mut queued.pacing;
+        pacing.unacknowledged += 1;
+        pacing.quota = (pacing.quota - chunks as f32).max(0.0);
+    }
+
+    fn record_sent(&self, packets: u64, chunks: u64, bytes: usize) {
+        let mut queued = self.queued.lock();
+        queued.stats.sent_packets += packets;
+        queued.stats.sent_chunks += chunks;
+        queued.stats.sent_bytes += bytes as u64;
+    }
+
+    fn is_closed(&self) -> bool {
+        self.queued.lock().closed
+    }
+
+    fn has_chunks(&self) -> bool {
+        !self.queued.lock().chunks.is_empty()
+    }
+}
+
+/// Writes the queued packets to the connection until the queue is closed.
+pub(super) async fn run_writer(
+    queue: Arc<SendQueue>,
+    encoder: Arc<AsyncMutex<PacketEncoder>>,
+    writer: Arc<AsyncMutex<OwnedWriteHalf>>,
+    translator: Arc<OnceLock<Arc<dyn ProtocolTranslator>>>,
+    client_id: u16,
+) {
+    let tick = Duration::from_secs_f32(1.0 / BASIC_CONFIG.tps);
+    let chunks_per_tick = ADVANCED_CONFIG.send_queue.chunks_per_tick;
+    // clients of versions without chunk batches never acknowledge them
+    let mut paced = true;
+    let mut chunk_budget = queue.chunk_budget(chunks_per_tick);
+    let mut next_tick = Instant::now() + tick;
+
+    loop {
+        let now = Instant::now();
+        if now >= next_tick {
+            chunk_budget = if paced {
+                queue.chunk_budget(chunks_per_tick)
+            } else {
+                chunks_per_tick
+            };
+            next_tick = now + tick;
+        }
+
+        let mut batch = Vec::new();
+        while batch.len() < WRITE_BATCH {
+            match queue.pop(&mut chunk_budget) {
+                Some(outgoing) => batch.push(outgoing),
+                None => break,
+            }
+        }
+        if batch.is_empty() {
+            if queue.is_closed() {
+                return;
+            }
+            if queue.has_chunks() {
+                // out of chunks for this tick
+                tokio::select! {
+                    () = sleep_until(next_tick) => {}
+                    () = queue.queued_notify.notified() => {}
+                }
+            } else {
+                queue.queued_notify.notified().await;
+            }
+            continue;
+        }
+
+        let mut packets = Vec::with_capacity(batch.len() + 2);
+        let mut chunks = 0;
+        let mut batch_start = None;
+        for outgoing in batch {
+            match outgoing {
+                Outgoing::Packet(packet) => packets.push(packet),
+                Outgoing::Chunk(chunk) => {
+                    // the chunk is only locked while the snapshot is taken, players can change it
+                    // while it is encoded
+                    let snapshot = chunk.read().await.snapshot();
+                    let Ok(packet) = tokio::task::spawn_blocking(move || {
+                        PreparedPacket::new(&CChunkData(&snapshot))
+                    })
+                    .await
+                    else {
+                        continue;
+                    };
+                    // everything else is translated when queued
+                    let packet = match translator.get() {
+                        Some(translator) => translator.clientbound(ConnectionState::Play, packet),
+                        None => Some(packet),
+                    };
+                    if let Some(packet) = packet {
+                        batch_start.get_or_insert(packets.len());
+                        packets.push(packet);
+                        chunks += 1;
+                    }
+                }
+            }
+        }
+        if let Some(batch_start) = batch_start.filter(|_| paced) {
+            let start = PreparedPacket::new(&CChunkBatchStart::new());
+            let finished = PreparedPacket::new(&CChunkBatchFinished::new(VarInt(chunks as i32)));
+            let translated = match translator.get() {
+                Some(translator) => translator
+                    .clientbound(ConnectionState::Play, start)
+                    .zip(translator.clientbound(ConnectionState::Play, finished)),
+                None => Some((start, finished)),
+            };
+            if let Some((start, finished)) = translated {
+                packets.insert(batch_start, start);
+                packets.push(finished);
+                queue.record_chunk_batch(chunks as u32);
+                // the next batch waits for the next tick
+                chunk_budget = 0;
+            } else {
+                paced = false;
+            }
+        }
+
+        // the encoder stays locked while writing, so packets sent directly are not encrypted out of order
+        let mut enc = encoder.lock().await;
+        for packet in &packets {
+            if let Err(error) = enc.append_prepared(packet) {
+                log::warn!("Failed to encode a packet for client id {client_id}: {error}");
+            }
+        }
+        let bytes = enc.take();
+        if let Err(error) = writer.lock().await.write_all(&bytes).await {
+            log::debug!("Failed to write to client id {client_id}: {error}");
+            queue.close();
+            return;
+        }
+        queue.record_sent(packets.len() as u64 - chunks, chunks, bytes.len());
+        PACKETS.count_sent(packets.len() as u64);
+    }
+}
@@ -0,0 +1,68 @@
+//! Which older protocol versions clients may join with, see [`pumpkin_protocol::translation`].
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::Path,
+    sync::{Arc, LazyLock},
+};
+
+use parking_lot::RwLock;
+use pumpkin_config::{MultiProtocolConfig, ADVANCED_CONFIG};
+use pumpkin_protocol::translation::{MappingTranslator, ProtocolTranslator};
+
+static TRANSLATORS: LazyLock<RwLock<HashMap<u32, Arc<dyn ProtocolTranslator>>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Registers a translator, e.g. one with payload rewriters for the packets which changed.
+///
+/// Clients are only let in if their version is also allowed in the config.
+pub fn register_translator(translator: Arc<dyn ProtocolTranslator>) {
+    TRANSLATORS
+        .write()
+        .insert(translator.protocol_version(), translator);
+}
+
+/// Returns the translator for the version, None if clients on it may not join.
+pub(crate) fn translator(protocol_version: u32) -> Option<Arc<dyn ProtocolTranslator>> {
+    let config = &ADVANCED_CONFIG.multi_protocol;
+    if !config.enabled || !config.allowed_versions.contains(&protocol_version) {
+        return None;
+    }
+    TRANSLATORS.read().get(&protocol_version).cloned()
+}
+
+/// Loads the translators of the allowed versions which have none registered yet, the ones Pumpkin
+/// comes with or else the packet table in the mappings folder.
+///
+/// Versions without a table can not be joined with.
+pub fn load_translators(config: &MultiProtocolConfig) {
+    for &version in &config.allowed_versions {
+        if TRANSLATORS.read().contains_key(&version) {
+            continue;
+        }
+        if let Some(translator) = MappingTranslator::shipped(version) {
+            log::info!("Clients on protocol {version} may join");
+            register_translator(Arc::new(translator));
+            continue;
+        }
+        let path = Path::new(&config.mappings_folder).join(format!("{version}.json"));
+        let translator = fs::read_to_string(&path)
+            .map_err(|error| error.to_string())
+            .and_then(|json| {
+                MappingTranslator::from_json(version, &json).map_err(|error| error.to_string())
+            });
+        match translator {
+            Ok(translator) => {
+                log::info!("Clients on protocol {version} may join");
+                register_translator(Arc::new(translator));
+            }
+            Err(error) => {
+                log::warn!(
+                    "Protocol {version} is allowed, but {} could not be loaded: {error}",
+                    path.display()
+                );
+            }
+        }
+    }
+}
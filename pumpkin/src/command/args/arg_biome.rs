@@ -0,0 +1,88 @@
+use async_trait::async_trait;
+use pumpkin_protocol::client::play::{
+    CommandSuggestion, ProtoCmdArgParser, ProtoCmdArgSuggestionType,
+};
+use pumpkin_world::biome::Biome;
+
+use crate::{
+    command::{
+        args::SplitSingleWhitespaceIncludingEmptyParts, dispatcher::CommandError, tree::RawArgs,
+        CommandSender,
+    },
+    server::Server,
+};
+
+use super::{Arg, ArgumentConsumer, DefaultNameArgConsumer, FindArg, GetClientSideArgParser};
+
+/// Consumes a biome id, the `minecraft:` namespace may be omitted.
+pub(crate) struct BiomeArgumentConsumer;
+
+impl GetClientSideArgParser for BiomeArgumentConsumer {
+    fn get_client_side_parser(&self) -> ProtoCmdArgParser {
+        ProtoCmdArgParser::ResourceLocation
+    }
+
+    fn get_client_side_suggestion_type_override(&self) -> Option<ProtoCmdArgSuggestionType> {
+        Some(ProtoCmdArgSuggestionType::AskServer)
+    }
+}
+
+#[async_trait]
+impl ArgumentConsumer for BiomeArgumentConsumer {
+    async fn consume<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        args: &mut RawArgs<'a>,
+    ) -> Option<Arg<'a>> {
+        let name = args.pop()?;
+        if name.contains(':') {
+            Biome::from_name(name)
+        } else {
+            Biome::from_name(&format!("minecraft:{name}"))
+        }
+        .map(Arg::Biome)
+    }
+
+    async fn suggest<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        input: &'a str,
+    ) -> Result<Option<Vec<CommandSuggestion<'a>>>, CommandError> {
+        let Some(input) = input.split_single_whitespace_including_empty_parts().last() else {
+            return Ok(None);
+        };
+
+        let suggestions = Biome::ALL
+            .into_iter()
+            .map(Biome::name)
+            .filter(|name| {
+                name.starts_with(input) || name.trim_start_matches("minecraft:").starts_with(input)
+            })
+            .map(|name| CommandSuggestion::new(name, None))
+            .collect();
+        Ok(Some(suggestions))
+    }
+}
+
+impl DefaultNameArgConsumer for BiomeArgumentConsumer {
+    fn default_name(&self) -> &'static str {
+        "biome"
+    }
+
+    fn get_argument_consumer(&self) -> &dyn ArgumentConsumer {
+        &BiomeArgumentConsumer
+    }
+}
+
+impl<'a> FindArg<'a> for BiomeArgumentConsumer {
+    type Data = Biome;
+
+    fn find_arg(args: &'a super::ConsumedArgs, name: &'a str) -> Result<Self::Data, CommandError> {
+        match args.get(name) {
+            Some(Arg::Biome(data)) => Ok(*data),
+            _ => Err(CommandError::InvalidConsumption(Some(name.to_string()))),
+        }
+    }
+}
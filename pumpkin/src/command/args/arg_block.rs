@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use pumpkin_core::nbt::{snbt, Compound};
 use pumpkin_protocol::client::play::{
     CommandSuggestion, ProtoCmdArgParser, ProtoCmdArgSuggestionType,
 };
@@ -18,9 +19,7 @@ pub(crate) struct BlockArgumentConsumer;
 
 impl GetClientSideArgParser for BlockArgumentConsumer {
     fn get_client_side_parser(&self) -> ProtoCmdArgParser {
-        ProtoCmdArgParser::Resource {
-            identifier: "block",
-        }
+        ProtoCmdArgParser::BlockState
     }
 
     fn get_client_side_suggestion_type_override(&self) -> Option<ProtoCmdArgSuggestionType> {
@@ -36,15 +35,7 @@ impl ArgumentConsumer for BlockArgumentConsumer {
         _server: &'a Server,
         args: &mut RawArgs<'a>,
     ) -> Option<Arg<'a>> {
-        let s = args.pop()?;
-
-        let name = if s.contains(':') {
-            s.to_string()
-        } else {
-            format!("minecraft:{s}")
-        };
-
-        Some(Arg::Block(name))
+        Some(Arg::Block(args.pop()?.to_string()))
     }
 
     async fn suggest<'a>(
@@ -68,17 +59,125 @@ impl DefaultNameArgConsumer for BlockArgumentConsumer {
 }
 
 impl<'a> FindArg<'a> for BlockArgumentConsumer {
-    type Data = &'a Block;
+    type Data = BlockStateArg<'a>;
 
     fn find_arg(args: &'a super::ConsumedArgs, name: &'a str) -> Result<Self::Data, CommandError> {
         match args.get(name) {
-            Some(Arg::Block(name)) => match block_registry::get_block(name) {
-                Some(block) => Ok(block),
-                None => Err(CommandError::GeneralCommandIssue(format!(
-                    "Block {name} does not exist."
-                ))),
-            },
+            Some(Arg::Block(input)) => {
+                let input = BlockInput::parse(input)?;
+                let block = input.block()?;
+                let state_id = block
+                    .state_id_with_properties(&input.properties)
+                    .ok_or_else(|| {
+                        CommandError::GeneralCommandIssue(format!(
+                            "Block {} does not have the given properties.",
+                            block.name
+                        ))
+                    })?;
+                let nbt = input
+                    .nbt
+                    .map(|nbt| {
+                        snbt::parse_compound(nbt).map_err(|err| {
+                            CommandError::GeneralCommandIssue(format!(
+                                "Invalid block NBT {nbt}: {err}"
+                            ))
+                        })
+                    })
+                    .transpose()?;
+                let has_block_entity = block_registry::get_state_by_state_id(state_id)
+                    .is_some_and(|state| state.block_entity_type.is_some());
+                if nbt.is_some() && !has_block_entity {
+                    return Err(CommandError::GeneralCommandIssue(format!(
+                        "Block {} has no block entity to apply the NBT to.",
+                        block.name
+                    )));
+                }
+                Ok(BlockStateArg {
+                    block,
+                    state_id,
+                    nbt,
+                })
+            }
             _ => Err(CommandError::InvalidConsumption(Some(name.to_string()))),
         }
     }
 }
+
+/// A block with an explicit state, e.g. `oak_log[axis=x]`.
+pub(crate) struct BlockStateArg<'a> {
+    pub block: &'a Block,
+    pub state_id: u16,
+    /// Applied to the block entity of the placed block, only given for blocks which have one
+    pub nbt: Option<Compound>,
+}
+
+/// The parts of a block state or block predicate argument like `minecraft:stairs[facing=east]{...}`.
+pub(crate) struct BlockInput<'a> {
+    /// Namespaced block name, or tag name (without `#`) if [`Self::is_tag`]
+    pub name: String,
+    pub is_tag: bool,
+    pub properties: Vec<(&'a str, &'a str)>,
+    pub nbt: Option<&'a str>,
+}
+
+impl<'a> BlockInput<'a> {
+    pub fn parse(input: &'a str) -> Result<Self, CommandError> {
+        let invalid = || CommandError::GeneralCommandIssue(format!("Invalid block {input}"));
+
+        let (input, is_tag) = match input.strip_prefix('#') {
+            Some(input) => (input, true),
+            None => (input, false),
+        };
+
+        let (rest, nbt) = match input.find('{') {
+            Some(i) if input.ends_with('}') => (&input[..i], Some(&input[i..])),
+            Some(_) => return Err(invalid()),
+            None => (input, None),
+        };
+
+        let (name, properties) = match rest.find('[') {
+            Some(i) => {
+                let properties = rest[i + 1..].strip_suffix(']').ok_or_else(invalid)?;
+                let properties = properties
+                    .split(',')
+                    .filter(|property| !property.is_empty())
+                    .map(|property| {
+                        property
+                            .split_once('=')
+                            .map(|(key, value)| (key.trim(), value.trim()))
+                            .ok_or_else(invalid)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                (&rest[..i], properties)
+            }
+            None => (rest, Vec::new()),
+        };
+
+        if name.is_empty() {
+            return Err(invalid());
+        }
+        let name = if name.contains(':') {
+            name.to_string()
+        } else {
+            format!("minecraft:{name}")
+        };
+
+        Ok(Self {
+            name,
+            is_tag,
+            properties,
+            nbt,
+        })
+    }
+
+    pub fn block(&self) -> Result<&'static Block, CommandError> {
+        if self.is_tag {
+            return Err(CommandError::GeneralCommandIssue(
+                "Tags are not allowed here, only blocks.".into(),
+            ));
+        }
+        block_registry::get_block(&self.name).ok_or_else(|| {
+            CommandError::GeneralCommandIssue(format!("Block {} does not exist.", self.name))
+        })
+    }
+}
@@ -0,0 +1,124 @@
+use async_trait::async_trait;
+use pumpkin_protocol::client::play::{
+    CommandSuggestion, ProtoCmdArgParser, ProtoCmdArgSuggestionType,
+};
+use pumpkin_registry::{get_tag_values, TagCategory};
+use pumpkin_world::block::block_registry::{self, Block};
+
+use crate::{command::dispatcher::CommandError, server::Server};
+
+use super::{
+    super::{
+        args::{ArgumentConsumer, RawArgs},
+        CommandSender,
+    },
+    arg_block::BlockInput,
+    Arg, DefaultNameArgConsumer, FindArg, GetClientSideArgParser,
+};
+
+/// Consumes a block predicate like `stone`, `#minecraft:logs[axis=y]` or `chest{Items:[]}`.
+pub(crate) struct BlockPredicateArgumentConsumer;
+
+impl GetClientSideArgParser for BlockPredicateArgumentConsumer {
+    fn get_client_side_parser(&self) -> ProtoCmdArgParser {
+        ProtoCmdArgParser::BlockPredicate
+    }
+
+    fn get_client_side_suggestion_type_override(&self) -> Option<ProtoCmdArgSuggestionType> {
+        None
+    }
+}
+
+#[async_trait]
+impl ArgumentConsumer for BlockPredicateArgumentConsumer {
+    async fn consume<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        args: &mut RawArgs<'a>,
+    ) -> Option<Arg<'a>> {
+        Some(Arg::BlockPredicate(args.pop()?.to_string()))
+    }
+
+    async fn suggest<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        _input: &'a str,
+    ) -> Result<Option<Vec<CommandSuggestion<'a>>>, CommandError> {
+        Ok(None)
+    }
+}
+
+impl DefaultNameArgConsumer for BlockPredicateArgumentConsumer {
+    fn default_name(&self) -> &'static str {
+        "filter"
+    }
+
+    fn get_argument_consumer(&self) -> &dyn ArgumentConsumer {
+        self
+    }
+}
+
+impl<'a> FindArg<'a> for BlockPredicateArgumentConsumer {
+    type Data = BlockPredicate<'a>;
+
+    fn find_arg(args: &'a super::ConsumedArgs, name: &'a str) -> Result<Self::Data, CommandError> {
+        match args.get(name) {
+            Some(Arg::BlockPredicate(input)) => BlockPredicate::parse(input),
+            _ => Err(CommandError::InvalidConsumption(Some(name.to_string()))),
+        }
+    }
+}
+
+pub(crate) struct BlockPredicate<'a> {
+    /// Block ids matched by this predicate
+    blocks: Vec<u16>,
+    properties: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> BlockPredicate<'a> {
+    fn parse(input: &'a str) -> Result<Self, CommandError> {
+        let input = BlockInput::parse(input)?;
+
+        let blocks = if input.is_tag {
+            let mut blocks = Vec::new();
+            collect_tag_blocks(&input.name, &mut blocks)?;
+            blocks
+        } else {
+            vec![input.block()?.id]
+        };
+
+        // TODO: also match block entity data once block entities are implemented
+        Ok(Self {
+            blocks,
+            properties: input.properties,
+        })
+    }
+
+    /// Returns whether the given block state matches this predicate.
+    pub fn test(&self, block: &Block, state_id: u16) -> bool {
+        if !self.blocks.contains(&block.id) {
+            return false;
+        }
+        if self.properties.is_empty() {
+            return true;
+        }
+        block.properties_of_state(state_id).is_some_and(|state| {
+            self.properties
+                .iter()
+                .all(|property| state.contains(property))
+        })
+    }
+}
+
+fn collect_tag_blocks(tag: &str, blocks: &mut Vec<u16>) -> Result<(), CommandError> {
+    let values = get_tag_values(TagCategory::Block, tag)
+        .ok_or_else(|| CommandError::GeneralCommandIssue(format!("Unknown block tag #{tag}")))?;
+    for name in values {
+        if let Some(block) = block_registry::get_block(&name) {
+            blocks.push(block.id);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use async_trait::async_trait;
+use pumpkin_protocol::client::play::{
+    CommandSuggestion, ProtoCmdArgParser, ProtoCmdArgSuggestionType,
+};
+
+use crate::{command::dispatcher::CommandError, server::Server};
+
+use super::{
+    super::{
+        args::{ArgumentConsumer, RawArgs},
+        CommandSender,
+    },
+    Arg, FindArg, GetClientSideArgParser,
+};
+
+/// Consumes `true` or `false`.
+pub(crate) struct BoolArgConsumer;
+
+impl GetClientSideArgParser for BoolArgConsumer {
+    fn get_client_side_parser(&self) -> ProtoCmdArgParser<'_> {
+        ProtoCmdArgParser::Bool
+    }
+
+    fn get_client_side_suggestion_type_override(&self) -> Option<ProtoCmdArgSuggestionType> {
+        None
+    }
+}
+
+#[async_trait]
+impl ArgumentConsumer for BoolArgConsumer {
+    async fn consume<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        args: &mut RawArgs<'a>,
+    ) -> Option<Arg<'a>> {
+        Some(Arg::Bool(args.pop()?.parse().ok()?))
+    }
+
+    async fn suggest<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        _input: &'a str,
+    ) -> Result<Option<Vec<CommandSuggestion<'a>>>, CommandError> {
+        Ok(None)
+    }
+}
+
+impl<'a> FindArg<'a> for BoolArgConsumer {
+    type Data = bool;
+
+    fn find_arg(args: &'a super::ConsumedArgs, name: &'a str) -> Result<Self::Data, CommandError> {
+        match args.get(name) {
+            Some(Arg::Bool(data)) => Ok(*data),
+            _ => Err(CommandError::InvalidConsumption(Some(name.to_string()))),
+        }
+    }
+}
@@ -37,9 +37,9 @@ impl ArgumentConsumer for CommandTreeArgumentConsumer {
     ) -> Option<Arg<'a>> {
         let s = args.pop()?;
 
-        let dispatcher = &server.command_dispatcher;
+        let dispatcher = server.command_dispatcher().await;
         return match dispatcher.get_tree(s) {
-            Ok(tree) => Some(Arg::CommandTree(tree)),
+            Ok(tree) => Some(Arg::CommandTree(tree.clone())),
             Err(_) => None,
         };
     }
@@ -55,11 +55,12 @@ impl ArgumentConsumer for CommandTreeArgumentConsumer {
         };
 
         let suggestions = server
-            .command_dispatcher
+            .command_dispatcher()
+            .await
             .commands
             .keys()
             .filter(|suggestion| suggestion.starts_with(input))
-            .map(|suggestion| CommandSuggestion::new(suggestion, None))
+            .map(|suggestion| CommandSuggestion::new(suggestion.to_string(), None))
             .collect();
         Ok(Some(suggestions))
     }
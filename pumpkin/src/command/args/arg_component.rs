@@ -0,0 +1,95 @@
+use async_trait::async_trait;
+use pumpkin_core::text::TextComponent;
+use pumpkin_protocol::client::play::{
+    CommandSuggestion, ProtoCmdArgParser, ProtoCmdArgSuggestionType,
+};
+use serde_json::Value;
+
+use crate::{command::dispatcher::CommandError, server::Server};
+
+use super::{
+    super::{
+        args::{ArgumentConsumer, RawArgs},
+        CommandSender,
+    },
+    Arg, ConsumedArgs, DefaultNameArgConsumer, FindArg, GetClientSideArgParser,
+};
+
+/// A text component in JSON like vanilla's: a string, an object or an array, whose first component
+/// is the parent of the others
+fn parse_component(value: Value) -> Option<TextComponent<'static>> {
+    match value {
+        Value::String(text) => Some(TextComponent::text_string(text)),
+        Value::Array(components) => {
+            let mut components = components.into_iter();
+            let first = parse_component(components.next()?)?;
+            components.try_fold(first, |parent, child| {
+                Some(parent.add_child(parse_component(child)?))
+            })
+        }
+        object @ Value::Object(_) => serde_json::from_value(object).ok(),
+        _ => None,
+    }
+}
+
+/// Consumes all remaining words as a JSON text component like `{"text":"Round 2","color":"gold"}`
+pub(crate) struct TextComponentArgConsumer;
+
+impl GetClientSideArgParser for TextComponentArgConsumer {
+    fn get_client_side_parser(&self) -> ProtoCmdArgParser<'_> {
+        ProtoCmdArgParser::Component
+    }
+
+    fn get_client_side_suggestion_type_override(&self) -> Option<ProtoCmdArgSuggestionType> {
+        None
+    }
+}
+
+#[async_trait]
+impl ArgumentConsumer for TextComponentArgConsumer {
+    async fn consume<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        args: &mut RawArgs<'a>,
+    ) -> Option<Arg<'a>> {
+        let mut json = args.pop()?.to_string();
+        while let Some(word) = args.pop() {
+            json.push(' ');
+            json.push_str(word);
+        }
+
+        let value = serde_json::from_str(&json).ok()?;
+        parse_component(value).map(Arg::Component)
+    }
+
+    async fn suggest<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        _input: &'a str,
+    ) -> Result<Option<Vec<CommandSuggestion<'a>>>, CommandError> {
+        Ok(None)
+    }
+}
+
+impl DefaultNameArgConsumer for TextComponentArgConsumer {
+    fn default_name(&self) -> &'static str {
+        "component"
+    }
+
+    fn get_argument_consumer(&self) -> &dyn ArgumentConsumer {
+        self
+    }
+}
+
+impl<'a> FindArg<'a> for TextComponentArgConsumer {
+    type Data = &'a TextComponent<'static>;
+
+    fn find_arg(args: &'a ConsumedArgs, name: &'a str) -> Result<Self::Data, CommandError> {
+        match args.get(name) {
+            Some(Arg::Component(data)) => Ok(data),
+            _ => Err(CommandError::InvalidConsumption(Some(name.to_string()))),
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use async_trait::async_trait;
+use pumpkin_protocol::client::play::{
+    CommandSuggestion, ProtoCmdArgParser, ProtoCmdArgSuggestionType, StringProtoArgBehavior,
+};
+use pumpkin_world::item::component::namespaced;
+
+use crate::{
+    command::{
+        args::SplitSingleWhitespaceIncludingEmptyParts, dispatcher::CommandError, tree::RawArgs,
+        CommandSender,
+    },
+    server::Server,
+};
+
+use super::{Arg, ArgumentConsumer, FindArg, GetClientSideArgParser};
+
+/// Consumes the id of a datapack like `"file/terrain"`, which is quoted as the client doesn't
+/// allow `/` in single words. Suggests the packs which are enabled, or the ones which aren't.
+pub(crate) struct DatapackArgumentConsumer {
+    pub enabled: bool,
+}
+
+impl GetClientSideArgParser for DatapackArgumentConsumer {
+    fn get_client_side_parser(&self) -> ProtoCmdArgParser {
+        ProtoCmdArgParser::String(StringProtoArgBehavior::QuotablePhrase)
+    }
+
+    fn get_client_side_suggestion_type_override(&self) -> Option<ProtoCmdArgSuggestionType> {
+        Some(ProtoCmdArgSuggestionType::AskServer)
+    }
+}
+
+#[async_trait]
+impl ArgumentConsumer for DatapackArgumentConsumer {
+    async fn consume<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        args: &mut RawArgs<'a>,
+    ) -> Option<Arg<'a>> {
+        let id = args.pop()?;
+        let id = id
+            .strip_prefix('"')
+            .and_then(|id| id.strip_suffix('"'))
+            .unwrap_or(id);
+        Some(Arg::Simple(id.to_string()))
+    }
+
+    async fn suggest<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        server: &'a Server,
+        input: &'a str,
+    ) -> Result<Option<Vec<CommandSuggestion<'a>>>, CommandError> {
+        let Some(input) = input.split_single_whitespace_including_empty_parts().last() else {
+            return Ok(None);
+        };
+        let input = input.trim_start_matches('"');
+
+        let datapacks = server.datapacks.lock();
+        let ids = if self.enabled {
+            datapacks.enabled().to_vec()
+        } else {
+            datapacks.disabled()
+        };
+        let suggestions = ids
+            .into_iter()
+            .filter(|id| id.starts_with(input))
+            .map(|id| CommandSuggestion::new(format!("\"{id}\""), None))
+            .collect();
+        Ok(Some(suggestions))
+    }
+}
+
+impl<'a> FindArg<'a> for DatapackArgumentConsumer {
+    type Data = &'a str;
+
+    fn find_arg(args: &'a super::ConsumedArgs, name: &'a str) -> Result<Self::Data, CommandError> {
+        match args.get(name) {
+            Some(Arg::Simple(data)) => Ok(data),
+            _ => Err(CommandError::InvalidConsumption(Some(name.to_string()))),
+        }
+    }
+}
+
+/// Consumes the id of something datapacks define, like a predicate. The `minecraft:` namespace
+/// may be left out. Suggests the ids `ids` returns.
+pub(crate) struct DatapackEntryArgumentConsumer {
+    pub ids: fn() -> Vec<String>,
+}
+
+impl GetClientSideArgParser for DatapackEntryArgumentConsumer {
+    fn get_client_side_parser(&self) -> ProtoCmdArgParser {
+        ProtoCmdArgParser::ResourceLocation
+    }
+
+    fn get_client_side_suggestion_type_override(&self) -> Option<ProtoCmdArgSuggestionType> {
+        Some(ProtoCmdArgSuggestionType::AskServer)
+    }
+}
+
+#[async_trait]
+impl ArgumentConsumer for DatapackEntryArgumentConsumer {
+    async fn consume<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        args: &mut RawArgs<'a>,
+    ) -> Option<Arg<'a>> {
+        Some(Arg::Simple(namespaced(args.pop()?)))
+    }
+
+    async fn suggest<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        input: &'a str,
+    ) -> Result<Option<Vec<CommandSuggestion<'a>>>, CommandError> {
+        let Some(input) = input.split_single_whitespace_including_empty_parts().last() else {
+            return Ok(None);
+        };
+
+        let suggestions = (self.ids)()
+            .into_iter()
+            .filter(|id| {
+                id.starts_with(input) || id.trim_start_matches("minecraft:").starts_with(input)
+            })
+            .map(|id| CommandSuggestion::new(id, None))
+            .collect();
+        Ok(Some(suggestions))
+    }
+}
+
+impl<'a> FindArg<'a> for DatapackEntryArgumentConsumer {
+    type Data = &'a str;
+
+    fn find_arg(args: &'a super::ConsumedArgs, name: &'a str) -> Result<Self::Data, CommandError> {
+        match args.get(name) {
+            Some(Arg::Simple(data)) => Ok(data),
+            _ => Err(CommandError::InvalidConsumption(Some(name.to_string()))),
+        }
+    }
+}
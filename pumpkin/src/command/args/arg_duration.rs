@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use pumpkin_protocol::client::play::{
+    CommandSuggestion, ProtoCmdArgParser, ProtoCmdArgSuggestionType, StringProtoArgBehavior,
+};
+
+use crate::{command::dispatcher::CommandError, server::Server};
+
+use super::{
+    super::{
+        args::{ArgumentConsumer, RawArgs},
+        CommandSender,
+    },
+    Arg, DefaultNameArgConsumer, FindArg, GetClientSideArgParser,
+};
+
+/// A duration like `30m`, `12h` or `7d`. Supported units are `s`, `m`, `h`, `d` and `w`.
+pub(crate) struct DurationArgumentConsumer;
+
+impl GetClientSideArgParser for DurationArgumentConsumer {
+    fn get_client_side_parser(&self) -> ProtoCmdArgParser {
+        ProtoCmdArgParser::String(StringProtoArgBehavior::SingleWord)
+    }
+
+    fn get_client_side_suggestion_type_override(&self) -> Option<ProtoCmdArgSuggestionType> {
+        None
+    }
+}
+
+#[async_trait]
+impl ArgumentConsumer for DurationArgumentConsumer {
+    async fn consume<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        args: &mut RawArgs<'a>,
+    ) -> Option<Arg<'a>> {
+        parse_duration(args.pop()?).map(Arg::Duration)
+    }
+
+    async fn suggest<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        _input: &'a str,
+    ) -> Result<Option<Vec<CommandSuggestion<'a>>>, CommandError> {
+        Ok(None)
+    }
+}
+
+fn parse_duration(s: &str) -> Option<Duration> {
+    let unit_start = s.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = s.split_at(unit_start);
+    let amount: u64 = amount.parse().ok()?;
+    let seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return None,
+    };
+    Some(Duration::from_secs(amount.checked_mul(seconds)?))
+}
+
+impl DefaultNameArgConsumer for DurationArgumentConsumer {
+    fn default_name(&self) -> &'static str {
+        "duration"
+    }
+
+    fn get_argument_consumer(&self) -> &dyn ArgumentConsumer {
+        &DurationArgumentConsumer
+    }
+}
+
+impl<'a> FindArg<'a> for DurationArgumentConsumer {
+    type Data = Duration;
+
+    fn find_arg(args: &'a super::ConsumedArgs, name: &'a str) -> Result<Self::Data, CommandError> {
+        match args.get(name) {
+            Some(Arg::Duration(data)) => Ok(*data),
+            _ => Err(CommandError::InvalidConsumption(Some(name.to_string()))),
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use pumpkin_protocol::client::play::{
+    CommandSuggestion, ProtoCmdArgParser, ProtoCmdArgSuggestionType,
+};
+use tokio::time::timeout;
+
+use crate::client::authentication::GameProfile;
+use crate::command::dispatcher::CommandError;
+use crate::command::tree::RawArgs;
+use crate::command::CommandSender;
+use crate::server::Server;
+
+use super::super::args::ArgumentConsumer;
+use super::{Arg, ConsumedArgs, DefaultNameArgConsumer, GetClientSideArgParser};
+
+/// How long looking up a player who is not online may take
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Select the profiles of online players with a selector, or any player by name, also if they are
+/// not online. Names are only looked up by the executor, see
+/// [`GameProfilesArgumentConsumer::find_profiles`]
+pub(crate) struct GameProfilesArgumentConsumer;
+
+impl GetClientSideArgParser for GameProfilesArgumentConsumer {
+    fn get_client_side_parser(&self) -> ProtoCmdArgParser {
+        ProtoCmdArgParser::GameProfile
+    }
+
+    fn get_client_side_suggestion_type_override(&self) -> Option<ProtoCmdArgSuggestionType> {
+        None
+    }
+}
+
+#[async_trait]
+impl ArgumentConsumer for GameProfilesArgumentConsumer {
+    async fn consume<'a>(
+        &self,
+        src: &CommandSender<'a>,
+        server: &'a Server,
+        args: &mut RawArgs<'a>,
+    ) -> Option<Arg<'a>> {
+        let s = args.pop()?;
+
+        let profiles = match s {
+            "@s" | "@n" | "@p" => match src {
+                CommandSender::Player(p) => vec![p.gameprofile.clone()],
+                _ => return None,
+            },
+            "@r" => server
+                .get_random_player()
+                .await
+                .map(|p| p.gameprofile.clone())
+                .into_iter()
+                .collect(),
+            "@a" | "@e" => server
+                .get_all_players()
+                .await
+                .iter()
+                .map(|p| p.gameprofile.clone())
+                .collect(),
+            name => return Some(Arg::ProfileName(name.to_string())),
+        };
+
+        Some(Arg::GameProfiles(profiles))
+    }
+
+    async fn suggest<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        _input: &'a str,
+    ) -> Result<Option<Vec<CommandSuggestion<'a>>>, CommandError> {
+        Ok(None)
+    }
+}
+
+impl DefaultNameArgConsumer for GameProfilesArgumentConsumer {
+    fn default_name(&self) -> &'static str {
+        "targets"
+    }
+
+    fn get_argument_consumer(&self) -> &dyn ArgumentConsumer {
+        &GameProfilesArgumentConsumer
+    }
+}
+
+impl GameProfilesArgumentConsumer {
+    /// The selected profiles, a player who is not online is looked up by their name
+    pub async fn find_profiles(
+        args: &ConsumedArgs<'_>,
+        name: &str,
+        server: &Server,
+    ) -> Result<Vec<GameProfile>, CommandError> {
+        match args.get(name) {
+            Some(Arg::GameProfiles(profiles)) => Ok(profiles.clone()),
+            Some(Arg::ProfileName(player)) => {
+                match timeout(LOOKUP_TIMEOUT, server.lookup_profile(player)).await {
+                    Ok(Some(profile)) => Ok(vec![profile]),
+                    Ok(None) => Err(CommandError::GeneralCommandIssue(
+                        "That player does not exist".to_string(),
+                    )),
+                    Err(_) => Err(CommandError::GeneralCommandIssue(format!(
+                        "Looking up {player} took too long"
+                    ))),
+                }
+            }
+            _ => Err(CommandError::InvalidConsumption(Some(name.to_string()))),
+        }
+    }
+
+    /// The names of the selected players, without looking up the ones who are not online
+    pub fn find_names(args: &ConsumedArgs<'_>, name: &str) -> Result<Vec<String>, CommandError> {
+        match args.get(name) {
+            Some(Arg::GameProfiles(profiles)) => Ok(profiles
+                .iter()
+                .map(|profile| profile.name.clone())
+                .collect()),
+            Some(Arg::ProfileName(player)) => Ok(vec![player.clone()]),
+            _ => Err(CommandError::InvalidConsumption(Some(name.to_string()))),
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use async_trait::async_trait;
+use pumpkin_protocol::client::play::{
+    CommandSuggestion, ProtoCmdArgParser, ProtoCmdArgSuggestionType, StringProtoArgBehavior,
+};
+use pumpkin_world::game_rules::{all_game_rules, get_game_rule, GameRuleDefinition, GameRuleKind};
+
+use crate::{
+    command::{
+        args::SplitSingleWhitespaceIncludingEmptyParts, dispatcher::CommandError, tree::RawArgs,
+        CommandSender,
+    },
+    server::Server,
+};
+
+use super::{Arg, ArgumentConsumer, DefaultNameArgConsumer, FindArg, GetClientSideArgParser};
+
+/// Consumes the name of a game rule, e.g. `keepInventory`.
+pub(crate) struct GameRuleArgumentConsumer;
+
+impl GetClientSideArgParser for GameRuleArgumentConsumer {
+    fn get_client_side_parser(&self) -> ProtoCmdArgParser {
+        ProtoCmdArgParser::String(StringProtoArgBehavior::SingleWord)
+    }
+
+    fn get_client_side_suggestion_type_override(&self) -> Option<ProtoCmdArgSuggestionType> {
+        Some(ProtoCmdArgSuggestionType::AskServer)
+    }
+}
+
+#[async_trait]
+impl ArgumentConsumer for GameRuleArgumentConsumer {
+    async fn consume<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        args: &mut RawArgs<'a>,
+    ) -> Option<Arg<'a>> {
+        get_game_rule(args.pop()?).map(Arg::GameRule)
+    }
+
+    async fn suggest<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        input: &'a str,
+    ) -> Result<Option<Vec<CommandSuggestion<'a>>>, CommandError> {
+        let Some(input) = input.split_single_whitespace_including_empty_parts().last() else {
+            return Ok(None);
+        };
+
+        let suggestions = all_game_rules()
+            .into_iter()
+            .filter(|rule| rule.name.starts_with(input))
+            .map(|rule| CommandSuggestion::new(rule.name, None))
+            .collect();
+        Ok(Some(suggestions))
+    }
+}
+
+impl DefaultNameArgConsumer for GameRuleArgumentConsumer {
+    fn default_name(&self) -> &'static str {
+        "rule"
+    }
+
+    fn get_argument_consumer(&self) -> &dyn ArgumentConsumer {
+        &GameRuleArgumentConsumer
+    }
+}
+
+impl<'a> FindArg<'a> for GameRuleArgumentConsumer {
+    type Data = &'static GameRuleDefinition;
+
+    fn find_arg(args: &'a super::ConsumedArgs, name: &'a str) -> Result<Self::Data, CommandError> {
+        match args.get(name) {
+            Some(Arg::GameRule(data)) => Ok(data),
+            _ => Err(CommandError::InvalidConsumption(Some(name.to_string()))),
+        }
+    }
+}
+
+/// Consumes the new value of a game rule. The value is validated against the rule type by the executor,
+/// because the rule is not known to this consumer.
+pub(crate) struct GameRuleValueArgumentConsumer;
+
+impl GetClientSideArgParser for GameRuleValueArgumentConsumer {
+    fn get_client_side_parser(&self) -> ProtoCmdArgParser {
+        ProtoCmdArgParser::String(StringProtoArgBehavior::SingleWord)
+    }
+
+    fn get_client_side_suggestion_type_override(&self) -> Option<ProtoCmdArgSuggestionType> {
+        Some(ProtoCmdArgSuggestionType::AskServer)
+    }
+}
+
+#[async_trait]
+impl ArgumentConsumer for GameRuleValueArgumentConsumer {
+    async fn consume<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        args: &mut RawArgs<'a>,
+    ) -> Option<Arg<'a>> {
+        Some(Arg::Simple(args.pop()?.to_string()))
+    }
+
+    async fn suggest<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        input: &'a str,
+    ) -> Result<Option<Vec<CommandSuggestion<'a>>>, CommandError> {
+        let mut parts = input.split_single_whitespace_including_empty_parts();
+        let (Some(rule), Some(input)) = (parts.nth(1).and_then(get_game_rule), parts.last()) else {
+            return Ok(None);
+        };
+
+        if rule.default.kind() != GameRuleKind::Bool {
+            return Ok(None);
+        }
+
+        let suggestions = ["true", "false"]
+            .into_iter()
+            .filter(|suggestion| suggestion.starts_with(input))
+            .map(|suggestion| CommandSuggestion::new(suggestion, None))
+            .collect();
+        Ok(Some(suggestions))
+    }
+}
+
+impl DefaultNameArgConsumer for GameRuleValueArgumentConsumer {
+    fn default_name(&self) -> &'static str {
+        "value"
+    }
+
+    fn get_argument_consumer(&self) -> &dyn ArgumentConsumer {
+        &GameRuleValueArgumentConsumer
+    }
+}
+
+impl<'a> FindArg<'a> for GameRuleValueArgumentConsumer {
+    type Data = &'a str;
+
+    fn find_arg(args: &'a super::ConsumedArgs, name: &'a str) -> Result<Self::Data, CommandError> {
+        match args.get(name) {
+            Some(Arg::Simple(data)) => Ok(data),
+            _ => Err(CommandError::InvalidConsumption(Some(name.to_string()))),
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use async_trait::async_trait;
+use pumpkin_inventory::player::PlayerInventory;
+use pumpkin_protocol::client::play::{
+    CommandSuggestion, ProtoCmdArgParser, ProtoCmdArgSuggestionType,
+};
+
+use crate::{command::dispatcher::CommandError, server::Server};
+
+use super::{
+    super::{
+        args::{ArgumentConsumer, RawArgs},
+        CommandSender,
+    },
+    Arg, DefaultNameArgConsumer, FindArg, GetClientSideArgParser,
+};
+
+/// A slot of a player's inventory like vanilla names them, e.g. `weapon.mainhand` or `hotbar.3`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ItemSlot {
+    /// The selected hotbar slot, which is only known when the command runs
+    MainHand,
+    /// A slot of [`PlayerInventory`]
+    Inventory(usize),
+}
+
+impl ItemSlot {
+    fn from_name(name: &str) -> Option<Self> {
+        let indexed = |prefix: &str, count: usize, first: usize| {
+            let index: usize = name.strip_prefix(prefix)?.parse().ok()?;
+            (index < count).then_some(Self::Inventory(first + index))
+        };
+        match name {
+            "weapon" | "weapon.mainhand" => Some(Self::MainHand),
+            "weapon.offhand" => Some(Self::Inventory(45)),
+            "armor.head" => Some(Self::Inventory(5)),
+            "armor.chest" => Some(Self::Inventory(6)),
+            "armor.legs" => Some(Self::Inventory(7)),
+            "armor.feet" => Some(Self::Inventory(8)),
+            _ => indexed("hotbar.", 9, 36).or_else(|| indexed("inventory.", 27, 9)),
+        }
+    }
+
+    /// The slot in `inventory`, see [`PlayerInventory::get_slot`]
+    pub fn index(self, inventory: &PlayerInventory) -> usize {
+        match self {
+            Self::MainHand => inventory.selected() + 36,
+            Self::Inventory(index) => index,
+        }
+    }
+}
+
+/// Consumes an [`ItemSlot`]
+pub(crate) struct ItemSlotArgumentConsumer;
+
+impl GetClientSideArgParser for ItemSlotArgumentConsumer {
+    fn get_client_side_parser(&self) -> ProtoCmdArgParser {
+        ProtoCmdArgParser::ItemSlot
+    }
+
+    fn get_client_side_suggestion_type_override(&self) -> Option<ProtoCmdArgSuggestionType> {
+        None
+    }
+}
+
+#[async_trait]
+impl ArgumentConsumer for ItemSlotArgumentConsumer {
+    async fn consume<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        args: &mut RawArgs<'a>,
+    ) -> Option<Arg<'a>> {
+        ItemSlot::from_name(args.pop()?).map(Arg::ItemSlot)
+    }
+
+    async fn suggest<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        _input: &'a str,
+    ) -> Result<Option<Vec<CommandSuggestion<'a>>>, CommandError> {
+        Ok(None)
+    }
+}
+
+impl DefaultNameArgConsumer for ItemSlotArgumentConsumer {
+    fn default_name(&self) -> &'static str {
+        "slot"
+    }
+
+    fn get_argument_consumer(&self) -> &dyn ArgumentConsumer {
+        &ItemSlotArgumentConsumer
+    }
+}
+
+impl<'a> FindArg<'a> for ItemSlotArgumentConsumer {
+    type Data = ItemSlot;
+
+    fn find_arg(args: &'a super::ConsumedArgs, name: &'a str) -> Result<Self::Data, CommandError> {
+        match args.get(name) {
+            Some(Arg::ItemSlot(slot)) => Ok(*slot),
+            _ => Err(CommandError::InvalidConsumption(Some(name.to_string()))),
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use async_trait::async_trait;
+use pumpkin_core::{
+    nbt::{snbt, Compound, NbtError, NbtPath, Value},
+    persistent_data::NamespacedKey,
+};
+use pumpkin_protocol::client::play::{
+    CommandSuggestion, ProtoCmdArgParser, ProtoCmdArgSuggestionType,
+};
+
+use crate::{command::dispatcher::CommandError, server::Server};
+
+use super::{
+    super::{
+        args::{ArgumentConsumer, RawArgs},
+        CommandSender,
+    },
+    Arg, ConsumedArgs, DefaultNameArgConsumer, FindArg, GetClientSideArgParser,
+};
+
+/// Parses the next words, SNBT and paths may contain spaces so words are added until they can be
+/// parsed
+fn consume_words<T>(
+    args: &mut RawArgs<'_>,
+    parse: impl Fn(&str) -> Result<T, NbtError>,
+) -> Option<T> {
+    let mut input = args.pop()?.to_string();
+    loop {
+        if let Ok(value) = parse(&input) {
+            return Some(value);
+        }
+        input.push(' ');
+        input.push_str(args.pop()?);
+    }
+}
+
+/// Consumes an SNBT compound like `{CustomName:"Steve"}`
+pub(crate) struct NbtCompoundArgumentConsumer;
+
+impl GetClientSideArgParser for NbtCompoundArgumentConsumer {
+    fn get_client_side_parser(&self) -> ProtoCmdArgParser<'_> {
+        ProtoCmdArgParser::Nbt
+    }
+
+    fn get_client_side_suggestion_type_override(&self) -> Option<ProtoCmdArgSuggestionType> {
+        None
+    }
+}
+
+#[async_trait]
+impl ArgumentConsumer for NbtCompoundArgumentConsumer {
+    async fn consume<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        args: &mut RawArgs<'a>,
+    ) -> Option<Arg<'a>> {
+        consume_words(args, snbt::parse_compound).map(Arg::Nbt)
+    }
+
+    async fn suggest<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        _input: &'a str,
+    ) -> Result<Option<Vec<CommandSuggestion<'a>>>, CommandError> {
+        Ok(None)
+    }
+}
+
+impl DefaultNameArgConsumer for NbtCompoundArgumentConsumer {
+    fn default_name(&self) -> &'static str {
+        "nbt"
+    }
+
+    fn get_argument_consumer(&self) -> &dyn ArgumentConsumer {
+        self
+    }
+}
+
+impl<'a> FindArg<'a> for NbtCompoundArgumentConsumer {
+    type Data = &'a Compound;
+
+    fn find_arg(args: &'a ConsumedArgs, name: &'a str) -> Result<Self::Data, CommandError> {
+        match args.get(name) {
+            Some(Arg::Nbt(data)) => Ok(data),
+            _ => Err(CommandError::InvalidConsumption(Some(name.to_string()))),
+        }
+    }
+}
+
+/// Consumes any SNBT value like `1b`, `"text"` or `[1,2,3]`
+pub(crate) struct NbtTagArgumentConsumer;
+
+impl GetClientSideArgParser for NbtTagArgumentConsumer {
+    fn get_client_side_parser(&self) -> ProtoCmdArgParser<'_> {
+        ProtoCmdArgParser::NbtTag
+    }
+
+    fn get_client_side_suggestion_type_override(&self) -> Option<ProtoCmdArgSuggestionType> {
+        None
+    }
+}
+
+#[async_trait]
+impl ArgumentConsumer for NbtTagArgumentConsumer {
+    async fn consume<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        args: &mut RawArgs<'a>,
+    ) -> Option<Arg<'a>> {
+        consume_words(args, snbt::parse).map(Arg::NbtTag)
+    }
+
+    async fn suggest<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        _input: &'a str,
+    ) -> Result<Option<Vec<CommandSuggestion<'a>>>, CommandError> {
+        Ok(None)
+    }
+}
+
+impl DefaultNameArgConsumer for NbtTagArgumentConsumer {
+    fn default_name(&self) -> &'static str {
+        "value"
+    }
+
+    fn get_argument_consumer(&self) -> &dyn ArgumentConsumer {
+        self
+    }
+}
+
+impl<'a> FindArg<'a> for NbtTagArgumentConsumer {
+    type Data = &'a Value;
+
+    fn find_arg(args: &'a ConsumedArgs, name: &'a str) -> Result<Self::Data, CommandError> {
+        match args.get(name) {
+            Some(Arg::NbtTag(data)) => Ok(data),
+            _ => Err(CommandError::InvalidConsumption(Some(name.to_string()))),
+        }
+    }
+}
+
+/// Consumes a path into NBT like `Inventory[0].id`
+pub(crate) struct NbtPathArgumentConsumer;
+
+impl GetClientSideArgParser for NbtPathArgumentConsumer {
+    fn get_client_side_parser(&self) -> ProtoCmdArgParser<'_> {
+        ProtoCmdArgParser::NbtPath
+    }
+
+    fn get_client_side_suggestion_type_override(&self) -> Option<ProtoCmdArgSuggestionType> {
+        None
+    }
+}
+
+#[async_trait]
+impl ArgumentConsumer for NbtPathArgumentConsumer {
+    async fn consume<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        args: &mut RawArgs<'a>,
+    ) -> Option<Arg<'a>> {
+        consume_words(args, NbtPath::parse).map(Arg::NbtPath)
+    }
+
+    async fn suggest<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        _input: &'a str,
+    ) -> Result<Option<Vec<CommandSuggestion<'a>>>, CommandError> {
+        Ok(None)
+    }
+}
+
+impl DefaultNameArgConsumer for NbtPathArgumentConsumer {
+    fn default_name(&self) -> &'static str {
+        "path"
+    }
+
+    fn get_argument_consumer(&self) -> &dyn ArgumentConsumer {
+        self
+    }
+}
+
+impl<'a> FindArg<'a> for NbtPathArgumentConsumer {
+    type Data = &'a NbtPath;
+
+    fn find_arg(args: &'a ConsumedArgs, name: &'a str) -> Result<Self::Data, CommandError> {
+        match args.get(name) {
+            Some(Arg::NbtPath(data)) => Ok(data),
+            _ => Err(CommandError::InvalidConsumption(Some(name.to_string()))),
+        }
+    }
+}
+
+/// Consumes the id of a `/data` storage like `mypack:state`, the namespace defaults to
+/// `minecraft`
+pub(crate) struct StorageArgumentConsumer;
+
+impl GetClientSideArgParser for StorageArgumentConsumer {
+    fn get_client_side_parser(&self) -> ProtoCmdArgParser<'_> {
+        ProtoCmdArgParser::ResourceLocation
+    }
+
+    fn get_client_side_suggestion_type_override(&self) -> Option<ProtoCmdArgSuggestionType> {
+        None
+    }
+}
+
+#[async_trait]
+impl ArgumentConsumer for StorageArgumentConsumer {
+    async fn consume<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        args: &mut RawArgs<'a>,
+    ) -> Option<Arg<'a>> {
+        let id = args.pop()?;
+        match id.split_once(':') {
+            Some((namespace, path)) => NamespacedKey::new(namespace, path),
+            None => NamespacedKey::new("minecraft", id),
+        }
+        .map(Arg::Storage)
+    }
+
+    async fn suggest<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        _input: &'a str,
+    ) -> Result<Option<Vec<CommandSuggestion<'a>>>, CommandError> {
+        Ok(None)
+    }
+}
+
+impl DefaultNameArgConsumer for StorageArgumentConsumer {
+    fn default_name(&self) -> &'static str {
+        "storage"
+    }
+
+    fn get_argument_consumer(&self) -> &dyn ArgumentConsumer {
+        self
+    }
+}
+
+impl<'a> FindArg<'a> for StorageArgumentConsumer {
+    type Data = &'a NamespacedKey;
+
+    fn find_arg(args: &'a ConsumedArgs, name: &'a str) -> Result<Self::Data, CommandError> {
+        match args.get(name) {
+            Some(Arg::Storage(data)) => Ok(data),
+            _ => Err(CommandError::InvalidConsumption(Some(name.to_string()))),
+        }
+    }
+}
@@ -0,0 +1,408 @@
+use async_trait::async_trait;
+use pumpkin_core::{
+    math::{position::WorldPosition, vector3::Vector3},
+    text::color::RGBColor,
+};
+use pumpkin_protocol::client::play::{
+    CommandSuggestion, Particle, ParticleOptions, ParticleOptionsKind, PositionSource,
+    ProtoCmdArgParser, ProtoCmdArgSuggestionType,
+};
+use pumpkin_registry::get_particle_id;
+use pumpkin_world::{
+    block::block_registry::get_block,
+    item::{item_registry::get_item, ItemStack},
+};
+
+use crate::{command::dispatcher::CommandError, server::Server};
+
+use super::{
+    super::{
+        args::{ArgumentConsumer, RawArgs},
+        CommandSender,
+    },
+    Arg, DefaultNameArgConsumer, FindArg, GetClientSideArgParser,
+};
+
+/// Consumes a particle with its options, e.g. `dust{color:[1.0,0.0,0.0],scale:2.0}`
+pub(crate) struct ParticleArgumentConsumer;
+
+impl GetClientSideArgParser for ParticleArgumentConsumer {
+    fn get_client_side_parser(&self) -> ProtoCmdArgParser<'_> {
+        ProtoCmdArgParser::Particle
+    }
+
+    fn get_client_side_suggestion_type_override(&self) -> Option<ProtoCmdArgSuggestionType> {
+        None
+    }
+}
+
+#[async_trait]
+impl ArgumentConsumer for ParticleArgumentConsumer {
+    async fn consume<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        args: &mut RawArgs<'a>,
+    ) -> Option<Arg<'a>> {
+        let mut input = args.pop()?.to_string();
+        // the options may contain spaces, so the words are joined until all braces are closed
+        while input.matches('{').count() > input.matches('}').count() {
+            input.push(' ');
+            input.push_str(args.pop()?);
+        }
+
+        let (name, options) = match input.find('{') {
+            Some(start) => (&input[..start], Some(&input[start..])),
+            None => (input.as_str(), None),
+        };
+        let id = get_particle_id(name)?;
+        let options = match options {
+            Some(options) => {
+                let Value::Compound(options) = Reader::new(options).read_all()? else {
+                    return None;
+                };
+                parse_options(ParticleOptionsKind::of(id), &options)?
+            }
+            None => ParticleOptions::None,
+        };
+        Particle::new(id, options).map(Arg::Particle)
+    }
+
+    async fn suggest<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        _input: &'a str,
+    ) -> Result<Option<Vec<CommandSuggestion<'a>>>, CommandError> {
+        Ok(None)
+    }
+}
+
+impl DefaultNameArgConsumer for ParticleArgumentConsumer {
+    fn default_name(&self) -> &'static str {
+        "name"
+    }
+
+    fn get_argument_consumer(&self) -> &dyn ArgumentConsumer {
+        self
+    }
+}
+
+impl<'a> FindArg<'a> for ParticleArgumentConsumer {
+    type Data = &'a Particle;
+
+    fn find_arg(args: &'a super::ConsumedArgs, name: &'a str) -> Result<Self::Data, CommandError> {
+        match args.get(name) {
+            Some(Arg::Particle(data)) => Ok(data),
+            _ => Err(CommandError::InvalidConsumption(Some(name.to_string()))),
+        }
+    }
+}
+
+type Compound = Vec<(String, Value)>;
+
+/// The subset of SNBT the options of particles are written in
+enum Value {
+    Compound(Compound),
+    List(Vec<Value>),
+    Number(f64),
+    String(String),
+}
+
+fn get<'a>(compound: &'a Compound, key: &str) -> Option<&'a Value> {
+    compound
+        .iter()
+        .find(|(name, _)| name == key)
+        .map(|(_, value)| value)
+}
+
+impl Value {
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Number(number) => Some(*number),
+            _ => None,
+        }
+    }
+
+    fn as_f32(&self) -> Option<f32> {
+        self.as_f64().map(|number| number as f32)
+    }
+
+    fn as_i32(&self) -> Option<i32> {
+        self.as_f64().map(|number| number as i32)
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    fn as_vec3(&self) -> Option<Vector3<f64>> {
+        match self {
+            Self::List(list) => match list.as_slice() {
+                [x, y, z] => Some(Vector3::new(x.as_f64()?, y.as_f64()?, z.as_f64()?)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Either `0xRRGGBB` as a number or `[r, g, b]` from 0.0 to 1.0
+    fn as_color(&self) -> Option<RGBColor> {
+        let component = |value: f64| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        if let Self::Number(number) = self {
+            let [_, red, green, blue] = (*number as i32).to_be_bytes();
+            return Some(RGBColor::new(red, green, blue));
+        }
+        let color = self.as_vec3()?;
+        Some(RGBColor::new(
+            component(color.x),
+            component(color.y),
+            component(color.z),
+        ))
+    }
+
+    /// Either `0xAARRGGBB` as a number or `[r, g, b, a]` from 0.0 to 1.0
+    fn as_argb(&self) -> Option<(RGBColor, u8)> {
+        let component = |value: &Value| {
+            value
+                .as_f64()
+                .map(|value| (value.clamp(0.0, 1.0) * 255.0).round() as u8)
+        };
+        match self {
+            Self::Number(number) => {
+                let [alpha, red, green, blue] = (*number as i64 as i32).to_be_bytes();
+                Some((RGBColor::new(red, green, blue), alpha))
+            }
+            Self::List(list) => match list.as_slice() {
+                [red, green, blue, alpha] => Some((
+                    RGBColor::new(component(red)?, component(green)?, component(blue)?),
+                    component(alpha)?,
+                )),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+fn parse_options(kind: ParticleOptionsKind, options: &Compound) -> Option<ParticleOptions> {
+    let scale = || get(options, "scale").map_or(Some(1.0), Value::as_f32);
+    Some(match kind {
+        ParticleOptionsKind::None => ParticleOptions::None,
+        ParticleOptionsKind::Block => {
+            ParticleOptions::Block(parse_block_state(get(options, "block_state")?)?)
+        }
+        ParticleOptionsKind::Dust => ParticleOptions::Dust {
+            color: get(options, "color")?.as_color()?,
+            scale: scale()?.clamp(0.01, 4.0),
+        },
+        ParticleOptionsKind::DustColorTransition => ParticleOptions::DustColorTransition {
+            from: get(options, "from_color")?.as_color()?,
+            to: get(options, "to_color")?.as_color()?,
+            scale: scale()?.clamp(0.01, 4.0),
+        },
+        ParticleOptionsKind::Color => {
+            let (color, alpha) = get(options, "color")?.as_argb()?;
+            ParticleOptions::Color { color, alpha }
+        }
+        ParticleOptionsKind::Item => ParticleOptions::Item(parse_item(get(options, "item")?)?),
+        ParticleOptionsKind::Vibration => {
+            let Value::Compound(destination) = get(options, "destination")? else {
+                return None;
+            };
+            // entities are given by UUID, which can not be typed sensibly, so only blocks work
+            let kind = get(destination, "type")?.as_str()?;
+            if kind.trim_start_matches("minecraft:") != "block" {
+                return None;
+            }
+            let pos = get(destination, "pos")?.as_vec3()?;
+            ParticleOptions::Vibration {
+                destination: PositionSource::Block(WorldPosition(Vector3::new(
+                    pos.x as i32,
+                    pos.y as i32,
+                    pos.z as i32,
+                ))),
+                arrival_in_ticks: get(options, "arrival_in_ticks")?.as_i32()?,
+            }
+        }
+        ParticleOptionsKind::Trail => ParticleOptions::Trail {
+            target: get(options, "target")?.as_vec3()?,
+            color: get(options, "color")?.as_color()?,
+        },
+        ParticleOptionsKind::SculkCharge => {
+            ParticleOptions::SculkCharge(get(options, "roll")?.as_f32()?)
+        }
+        ParticleOptionsKind::Shriek => ParticleOptions::Shriek(get(options, "delay")?.as_i32()?),
+    })
+}
+
+/// Either a block name or `{Name:"...",Properties:{...}}`
+fn parse_block_state(value: &Value) -> Option<u16> {
+    let (name, properties) = match value {
+        Value::String(name) => (name.as_str(), None),
+        Value::Compound(compound) => (
+            get(compound, "Name")?.as_str()?,
+            match get(compound, "Properties") {
+                Some(Value::Compound(properties)) => Some(properties),
+                Some(_) => return None,
+                None => None,
+            },
+        ),
+        _ => return None,
+    };
+    let block = get_block(&namespaced(name))?;
+    match properties {
+        Some(properties) => {
+            let properties = properties
+                .iter()
+                .map(|(key, value)| Some((key.as_str(), value.as_str()?)))
+                .collect::<Option<Vec<_>>>()?;
+            block.state_id_with_properties(&properties)
+        }
+        None => Some(block.default_state_id),
+    }
+}
+
+/// Either an item name or `{id:"...",count:1}`
+fn parse_item(value: &Value) -> Option<ItemStack> {
+    let (name, count) = match value {
+        Value::String(name) => (name.as_str(), 1),
+        Value::Compound(compound) => (
+            get(compound, "id")?.as_str()?,
+            get(compound, "count").map_or(Some(1), Value::as_i32)?,
+        ),
+        _ => return None,
+    };
+    let item_id = get_item(&namespaced(name))?.id;
+    Some(ItemStack::new(count.clamp(1, 99) as u8, item_id))
+}
+
+fn namespaced(name: &str) -> String {
+    if name.contains(':') {
+        name.to_string()
+    } else {
+        format!("minecraft:{name}")
+    }
+}
+
+struct Reader<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl<'a> Reader<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            chars: input.chars().peekable(),
+        }
+    }
+
+    /// Reads a single value, failing if anything but whitespace follows it
+    fn read_all(mut self) -> Option<Value> {
+        let value = self.read_value()?;
+        self.skip_whitespace();
+        self.chars.next().is_none().then_some(value)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    fn expect(&mut self, expected: char) -> Option<()> {
+        self.skip_whitespace();
+        self.chars.next_if_eq(&expected).map(|_| ())
+    }
+
+    fn read_value(&mut self) -> Option<Value> {
+        self.skip_whitespace();
+        match *self.chars.peek()? {
+            '{' => self.read_compound().map(Value::Compound),
+            '[' => self.read_list().map(Value::List),
+            '"' | '\'' => self.read_quoted().map(Value::String),
+            _ => {
+                let word = self.read_word()?;
+                Some(parse_number(&word).map_or(Value::String(word), Value::Number))
+            }
+        }
+    }
+
+    fn read_compound(&mut self) -> Option<Compound> {
+        self.expect('{')?;
+        let mut compound = Vec::new();
+        if self.expect('}').is_some() {
+            return Some(compound);
+        }
+        loop {
+            self.skip_whitespace();
+            let key = match self.chars.peek()? {
+                '"' | '\'' => self.read_quoted()?,
+                _ => self.read_word()?,
+            };
+            self.expect(':')?;
+            compound.push((key, self.read_value()?));
+            if self.expect(',').is_none() {
+                self.expect('}')?;
+                return Some(compound);
+            }
+        }
+    }
+
+    fn read_list(&mut self) -> Option<Vec<Value>> {
+        self.expect('[')?;
+        // typed arrays like `[I;1,2,3]` are read as lists
+        let mut lookahead = self.chars.clone();
+        if matches!(lookahead.next(), Some('B' | 'I' | 'L')) && lookahead.next() == Some(';') {
+            self.chars.next();
+            self.chars.next();
+        }
+        let mut list = Vec::new();
+        if self.expect(']').is_some() {
+            return Some(list);
+        }
+        loop {
+            list.push(self.read_value()?);
+            if self.expect(',').is_none() {
+                self.expect(']')?;
+                return Some(list);
+            }
+        }
+    }
+
+    fn read_quoted(&mut self) -> Option<String> {
+        let quote = self.chars.next()?;
+        let mut string = String::new();
+        loop {
+            match self.chars.next()? {
+                '\\' => string.push(self.chars.next()?),
+                c if c == quote => return Some(string),
+                c => string.push(c),
+            }
+        }
+    }
+
+    fn read_word(&mut self) -> Option<String> {
+        let mut word = String::new();
+        while let Some(c) = self
+            .chars
+            .next_if(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '+'))
+        {
+            word.push(c);
+        }
+        (!word.is_empty()).then_some(word)
+    }
+}
+
+/// Reads numbers with an optional type suffix like `1.5f`, and booleans as 0 or 1
+fn parse_number(word: &str) -> Option<f64> {
+    match word {
+        "true" => return Some(1.0),
+        "false" => return Some(0.0),
+        _ => {}
+    }
+    let number = word
+        .strip_suffix(['b', 'B', 's', 'S', 'l', 'L', 'f', 'F', 'd', 'D'])
+        .unwrap_or(word);
+    number.parse().ok()
+}
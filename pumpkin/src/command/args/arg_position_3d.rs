@@ -10,7 +10,7 @@ use crate::command::CommandSender;
 use crate::server::Server;
 
 use super::super::args::ArgumentConsumer;
-use super::coordinate::MaybeRelativeCoordinate;
+use super::coordinate::{LocalCoordinates, MaybeRelativeCoordinate};
 use super::{Arg, DefaultNameArgConsumer, FindArg, GetClientSideArgParser};
 
 /// x, y and z coordinates
@@ -34,9 +34,13 @@ impl ArgumentConsumer for Position3DArgumentConsumer {
         _server: &'a Server,
         args: &mut RawArgs<'a>,
     ) -> Option<Arg<'a>> {
-        let pos = MaybeRelativePosition3D::try_new(args.pop()?, args.pop()?, args.pop()?)?;
+        let (x, y, z) = (args.pop()?, args.pop()?, args.pop()?);
 
-        let vec3 = pos.try_to_absolute(src.position())?;
+        let vec3 = if x.starts_with('^') {
+            LocalCoordinates::try_new(x, y, z)?.into_absolute(src.position()?, src.rotation()?)
+        } else {
+            MaybeRelativePosition3D::try_new(x, y, z)?.try_to_absolute(src.position())?
+        };
 
         Some(Arg::Pos3D(vec3))
     }
@@ -11,6 +11,15 @@ use crate::server::Server;
 use super::super::args::ArgumentConsumer;
 use super::{Arg, DefaultNameArgConsumer, FindArg, GetClientSideArgParser};
 
+/// Parses an angle, which may be relative to the sender's rotation using `~`
+fn parse_angle(s: &str, origin: Option<f32>) -> Option<f32> {
+    match s.strip_prefix('~') {
+        Some("") => origin,
+        Some(offset) => Some(origin? + offset.parse::<f32>().ok()?),
+        None => s.parse().ok(),
+    }
+}
+
 /// yaw and pitch
 pub(crate) struct RotationArgumentConsumer;
 
@@ -28,15 +37,16 @@ impl GetClientSideArgParser for RotationArgumentConsumer {
 impl ArgumentConsumer for RotationArgumentConsumer {
     async fn consume<'a>(
         &self,
-        _src: &CommandSender<'a>,
+        src: &CommandSender<'a>,
         _server: &'a Server,
         args: &mut RawArgs<'a>,
     ) -> Option<Arg<'a>> {
         let yaw = args.pop()?;
         let pitch = args.pop()?;
 
-        let mut yaw = yaw.parse::<f32>().ok()?;
-        let mut pitch = pitch.parse::<f32>().ok()?;
+        let rotation = src.rotation();
+        let mut yaw = parse_angle(yaw, rotation.map(|(yaw, _)| yaw))?;
+        let mut pitch = parse_angle(pitch, rotation.map(|(_, pitch)| pitch))?;
 
         yaw %= 360.0;
         if yaw >= 180.0 {
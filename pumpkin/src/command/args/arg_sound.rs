@@ -0,0 +1,82 @@
+use async_trait::async_trait;
+use pumpkin_protocol::client::play::{
+    CommandSuggestion, ProtoCmdArgParser, ProtoCmdArgSuggestionType,
+};
+
+use crate::{command::dispatcher::CommandError, server::Server};
+
+use super::{
+    super::{
+        args::{ArgumentConsumer, RawArgs},
+        CommandSender,
+    },
+    Arg, DefaultNameArgConsumer, FindArg, GetClientSideArgParser,
+};
+
+/// Consumes a sound name, which does not have to be in the registry as resource packs can add sounds.
+pub(crate) struct SoundArgumentConsumer;
+
+impl GetClientSideArgParser for SoundArgumentConsumer {
+    fn get_client_side_parser(&self) -> ProtoCmdArgParser<'_> {
+        ProtoCmdArgParser::ResourceLocation
+    }
+
+    fn get_client_side_suggestion_type_override(&self) -> Option<ProtoCmdArgSuggestionType> {
+        Some(ProtoCmdArgSuggestionType::AvailableSounds)
+    }
+}
+
+#[async_trait]
+impl ArgumentConsumer for SoundArgumentConsumer {
+    async fn consume<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        args: &mut RawArgs<'a>,
+    ) -> Option<Arg<'a>> {
+        let s = args.pop()?;
+        let valid = s.chars().all(|c| {
+            c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '_' | '-' | '.' | '/' | ':')
+        });
+        if !valid || s.matches(':').count() > 1 {
+            return None;
+        }
+
+        let name = if s.contains(':') {
+            s.to_string()
+        } else {
+            format!("minecraft:{s}")
+        };
+        Some(Arg::Sound(name))
+    }
+
+    async fn suggest<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        _input: &'a str,
+    ) -> Result<Option<Vec<CommandSuggestion<'a>>>, CommandError> {
+        Ok(None)
+    }
+}
+
+impl DefaultNameArgConsumer for SoundArgumentConsumer {
+    fn default_name(&self) -> &'static str {
+        "sound"
+    }
+
+    fn get_argument_consumer(&self) -> &dyn ArgumentConsumer {
+        self
+    }
+}
+
+impl<'a> FindArg<'a> for SoundArgumentConsumer {
+    type Data = &'a str;
+
+    fn find_arg(args: &'a super::ConsumedArgs, name: &'a str) -> Result<Self::Data, CommandError> {
+        match args.get(name) {
+            Some(Arg::Sound(name)) => Ok(name),
+            _ => Err(CommandError::InvalidConsumption(Some(name.to_string()))),
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use async_trait::async_trait;
+use pumpkin_protocol::client::play::{
+    CommandSuggestion, ProtoCmdArgParser, ProtoCmdArgSuggestionType,
+};
+use pumpkin_world::structure::{get_structure, Structure, STRUCTURES};
+
+use crate::{
+    command::{
+        args::SplitSingleWhitespaceIncludingEmptyParts, dispatcher::CommandError, tree::RawArgs,
+        CommandSender,
+    },
+    server::Server,
+};
+
+use super::{Arg, ArgumentConsumer, DefaultNameArgConsumer, FindArg, GetClientSideArgParser};
+
+/// Consumes a structure id, the `minecraft:` namespace may be omitted.
+pub(crate) struct StructureArgumentConsumer;
+
+impl GetClientSideArgParser for StructureArgumentConsumer {
+    fn get_client_side_parser(&self) -> ProtoCmdArgParser {
+        ProtoCmdArgParser::ResourceLocation
+    }
+
+    fn get_client_side_suggestion_type_override(&self) -> Option<ProtoCmdArgSuggestionType> {
+        Some(ProtoCmdArgSuggestionType::AskServer)
+    }
+}
+
+#[async_trait]
+impl ArgumentConsumer for StructureArgumentConsumer {
+    async fn consume<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        args: &mut RawArgs<'a>,
+    ) -> Option<Arg<'a>> {
+        let name = args.pop()?;
+        if name.contains(':') {
+            get_structure(name)
+        } else {
+            get_structure(&format!("minecraft:{name}"))
+        }
+        .map(Arg::Structure)
+    }
+
+    async fn suggest<'a>(
+        &self,
+        _sender: &CommandSender<'a>,
+        _server: &'a Server,
+        input: &'a str,
+    ) -> Result<Option<Vec<CommandSuggestion<'a>>>, CommandError> {
+        let Some(input) = input.split_single_whitespace_including_empty_parts().last() else {
+            return Ok(None);
+        };
+
+        let suggestions = STRUCTURES
+            .iter()
+            .filter(|structure| {
+                structure.name.starts_with(input)
+                    || structure
+                        .name
+                        .trim_start_matches("minecraft:")
+                        .starts_with(input)
+            })
+            .map(|structure| CommandSuggestion::new(structure.name, None))
+            .collect();
+        Ok(Some(suggestions))
+    }
+}
+
+impl DefaultNameArgConsumer for StructureArgumentConsumer {
+    fn default_name(&self) -> &'static str {
+        "structure"
+    }
+
+    fn get_argument_consumer(&self) -> &dyn ArgumentConsumer {
+        &StructureArgumentConsumer
+    }
+}
+
+impl<'a> FindArg<'a> for StructureArgumentConsumer {
+    type Data = &'static Structure;
+
+    fn find_arg(args: &'a super::ConsumedArgs, name: &'a str) -> Result<Self::Data, CommandError> {
+        match args.get(name) {
+            Some(Arg::Structure(data)) => Ok(data),
+            _ => Err(CommandError::InvalidConsumption(Some(name.to_string()))),
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use std::str::FromStr;
 
+use pumpkin_core::math::vector3::Vector3;
 use pumpkin_world::{WORLD_LOWEST_Y, WORLD_MAX_Y};
 
 pub enum MaybeRelativeCoordinate<const IS_Y: bool> {
@@ -36,6 +37,61 @@ impl<const IS_Y: bool> MaybeRelativeCoordinate<IS_Y> {
     }
 }
 
+/// Coordinates relative to the rotation of the sender, written as `^left ^up ^forwards`
+pub struct LocalCoordinates {
+    left: f64,
+    up: f64,
+    forwards: f64,
+}
+
+impl LocalCoordinates {
+    /// Returns None if not all of the coordinates are local
+    pub fn try_new(x: &str, y: &str, z: &str) -> Option<Self> {
+        let parse = |s: &str| -> Option<f64> {
+            let s = s.strip_prefix('^')?;
+            if s.is_empty() {
+                Some(0.0)
+            } else {
+                s.parse().ok()
+            }
+        };
+        Some(Self {
+            left: parse(x)?,
+            up: parse(y)?,
+            forwards: parse(z)?,
+        })
+    }
+
+    pub fn into_absolute(self, origin: Vector3<f64>, (yaw, pitch): (f32, f32)) -> Vector3<f64> {
+        let yaw = f64::from(yaw + 90.0).to_radians();
+        let pitch = f64::from(-pitch).to_radians();
+        let pitch_up = pitch + std::f64::consts::FRAC_PI_2;
+
+        let forwards = Vector3::new(
+            yaw.cos() * pitch.cos(),
+            pitch.sin(),
+            yaw.sin() * pitch.cos(),
+        );
+        let up = Vector3::new(
+            yaw.cos() * pitch_up.cos(),
+            pitch_up.sin(),
+            yaw.sin() * pitch_up.cos(),
+        );
+        // cross product of forwards and up, pointing to the left
+        let left = Vector3::new(
+            -(forwards.y * up.z - forwards.z * up.y),
+            -(forwards.z * up.x - forwards.x * up.z),
+            -(forwards.x * up.y - forwards.y * up.x),
+        );
+
+        Vector3::new(
+            origin.x + forwards.x * self.forwards + up.x * self.up + left.x * self.left,
+            origin.y + forwards.y * self.forwards + up.y * self.up + left.y * self.left,
+            origin.z + forwards.z * self.forwards + up.z * self.up + left.z * self.left,
+        )
+    }
+}
+
 #[derive(Debug)]
 pub enum MaybeRelativeBlockCoordinate<const IS_Y: bool> {
     Absolute(i32),
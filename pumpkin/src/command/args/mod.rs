@@ -1,16 +1,21 @@
-use std::{collections::HashMap, hash::Hash, sync::Arc};
+use std::{collections::HashMap, hash::Hash, sync::Arc, time::Duration};
 
 use arg_bounded_num::{NotInBounds, Number};
+use arg_item_slot::ItemSlot;
 use async_trait::async_trait;
 use pumpkin_core::{
     math::{position::WorldPosition, vector2::Vector2, vector3::Vector3},
+    nbt::{Compound, NbtPath, Value},
+    persistent_data::NamespacedKey,
+    text::TextComponent,
     GameMode,
 };
 use pumpkin_protocol::client::play::{
-    CommandSuggestion, ProtoCmdArgParser, ProtoCmdArgSuggestionType,
+    CommandSuggestion, Particle, ProtoCmdArgParser, ProtoCmdArgSuggestionType,
 };
+use pumpkin_world::{biome::Biome, game_rules::GameRuleDefinition, structure::Structure};
 
-use crate::{entity::player::Player, server::Server};
+use crate::{client::authentication::GameProfile, entity::player::Player, server::Server};
 
 use super::{
     dispatcher::CommandError,
@@ -18,25 +23,38 @@ use super::{
     CommandSender,
 };
 
+pub(crate) mod arg_biome;
 pub(crate) mod arg_block;
+pub(crate) mod arg_block_predicate;
+pub(crate) mod arg_bool;
 pub(crate) mod arg_bounded_num;
 pub(crate) mod arg_command;
+pub(crate) mod arg_component;
+pub(crate) mod arg_datapack;
+pub(crate) mod arg_duration;
 pub(crate) mod arg_entities;
 pub(crate) mod arg_entity;
+pub(crate) mod arg_game_profile;
 pub(crate) mod arg_gamemode;
+pub(crate) mod arg_gamerule;
 pub(crate) mod arg_item;
+pub(crate) mod arg_item_slot;
 pub(crate) mod arg_message;
+pub(crate) mod arg_nbt;
+pub(crate) mod arg_particle;
 pub(crate) mod arg_players;
 pub(crate) mod arg_position_2d;
 pub(crate) mod arg_position_3d;
 pub(crate) mod arg_postition_block;
 pub(crate) mod arg_rotation;
 pub(crate) mod arg_simple;
+pub(crate) mod arg_sound;
+pub(crate) mod arg_structure;
 mod coordinate;
 
 /// see [`crate::commands::tree_builder::argument`]
 #[async_trait]
-pub(crate) trait ArgumentConsumer: Sync + GetClientSideArgParser {
+pub(crate) trait ArgumentConsumer: Send + Sync + GetClientSideArgParser {
     async fn consume<'a>(
         &self,
         sender: &CommandSender<'a>,
@@ -74,15 +92,32 @@ pub(crate) enum Arg<'a> {
     Entities(Vec<Arc<Player>>),
     Entity(Arc<Player>),
     Players(Vec<Arc<Player>>),
+    GameProfiles(Vec<GameProfile>),
+    /// A player who may not be online, looked up when the command runs
+    ProfileName(String),
     BlockPos(WorldPosition),
     Pos3D(Vector3<f64>),
     Pos2D(Vector2<f64>),
     Rotation(f32, f32),
+    Duration(Duration),
     GameMode(GameMode),
-    CommandTree(&'a CommandTree<'a>),
+    GameRule(&'static GameRuleDefinition),
+    Structure(&'static Structure),
+    Biome(Biome),
+    CommandTree(CommandTree<'a>),
     Item(String),
+    ItemSlot(ItemSlot),
     Block(String),
+    BlockPredicate(String),
+    Sound(String),
+    Particle(Particle),
+    Nbt(Compound),
+    NbtTag(Value),
+    NbtPath(NbtPath),
+    Storage(NamespacedKey),
     Msg(String),
+    Component(TextComponent<'static>),
+    Bool(bool),
     Num(Result<Number, NotInBounds>),
     #[allow(unused)]
     Simple(String),
@@ -80,7 +80,7 @@ fn nodes_to_proto_node_builders<'a>(
 
     for i in children {
         let node = &nodes[*i];
-        match node.node_type {
+        match &node.node_type {
             NodeType::Argument { name, consumer } => {
                 let (node_is_executable, node_children) =
                     nodes_to_proto_node_builders(cmd_src, nodes, &node.children);
@@ -0,0 +1,83 @@
+use async_trait::async_trait;
+use pumpkin_core::text::color::NamedColor;
+use pumpkin_core::text::TextComponent;
+
+use crate::command::args::arg_bounded_num::BoundedNumArgumentConsumer;
+use crate::command::args::arg_simple::SimpleArgConsumer;
+use crate::command::args::{ConsumedArgs, FindArg, FindArgDefaultName};
+use crate::command::tree::CommandTree;
+use crate::command::tree_builder::{argument, argument_default_name, require};
+use crate::command::{CommandError, CommandExecutor, CommandSender};
+use crate::entity::player::PermissionLvl;
+use crate::server::Server;
+
+const NAMES: [&str; 1] = ["auditlog"];
+const DESCRIPTION: &str = "Shows the most recent operator actions.";
+
+const ARG_PLAYER: &str = "player";
+
+const DEFAULT_COUNT: i32 = 10;
+
+static COUNT_CONSUMER: BoundedNumArgumentConsumer<i32> = BoundedNumArgumentConsumer::new()
+    .name("count")
+    .min(1)
+    .max(100);
+
+struct AuditLogExecutor;
+
+#[async_trait]
+impl CommandExecutor for AuditLogExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let count = match COUNT_CONSUMER.find_arg_default_name(args) {
+            Err(_) => DEFAULT_COUNT,
+            Ok(Ok(count)) => count,
+            Ok(Err(())) => {
+                sender
+                    .send_message(
+                        TextComponent::text("Count must be between 1 and 100.")
+                            .color_named(NamedColor::Red),
+                    )
+                    .await;
+                return Ok(());
+            }
+        };
+        let player = SimpleArgConsumer::find_arg(args, ARG_PLAYER).ok();
+
+        let entries = server
+            .audit_log
+            .recent(count.unsigned_abs() as usize, player);
+        if entries.is_empty() {
+            sender
+                .send_message(TextComponent::text("No actions were logged"))
+                .await;
+            return Ok(());
+        }
+        // oldest first, so the newest ends up at the bottom of the chat
+        for entry in entries.iter().rev() {
+            sender
+                .send_message(TextComponent::text_string(entry.to_string()))
+                .await;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.auditlog", PermissionLvl::Three))
+            .execute(&AuditLogExecutor)
+            .with_child(
+                argument_default_name(&COUNT_CONSUMER)
+                    .execute(&AuditLogExecutor)
+                    .with_child(
+                        argument(ARG_PLAYER, &SimpleArgConsumer).execute(&AuditLogExecutor),
+                    ),
+            ),
+    )
+}
@@ -0,0 +1,62 @@
+use async_trait::async_trait;
+use pumpkin_config::ADVANCED_CONFIG;
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+
+use crate::command::args::ConsumedArgs;
+use crate::command::tree::CommandTree;
+use crate::command::tree_builder::require;
+use crate::command::{CommandError, CommandExecutor, CommandSender};
+use crate::entity::player::PermissionLvl;
+use crate::server::{backup, Server};
+
+const NAMES: [&str; 1] = ["backup"];
+
+const DESCRIPTION: &str = "Saves the worlds and archives them in the background.";
+
+struct BackupExecutor;
+
+#[async_trait]
+impl CommandExecutor for BackupExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        _args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let Some(task) = backup::start(server, &ADVANCED_CONFIG.backup).await else {
+            return Err(CommandError::GeneralCommandIssue(
+                "A backup is running already".into(),
+            ));
+        };
+        sender
+            .send_message(TextComponent::text(
+                "Saved the worlds, they are archived in the background",
+            ))
+            .await;
+
+        // the console and RCON find the result in the log
+        let Some(player) = sender.as_player() else {
+            return Ok(());
+        };
+        tokio::spawn(async move {
+            let message = match task.await {
+                Ok(Ok(archives)) => TextComponent::text_string(format!(
+                    "Finished the backup of {} worlds",
+                    archives.len()
+                )),
+                Ok(Err(err)) => TextComponent::text_string(format!("The backup failed: {err}"))
+                    .color_named(NamedColor::Red),
+                Err(_) => TextComponent::text("The backup failed").color_named(NamedColor::Red),
+            };
+            player.send_system_message(&message).await;
+        });
+        Ok(())
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.backup", PermissionLvl::Four))
+            .execute(&BackupExecutor),
+    )
+}
@@ -0,0 +1,126 @@
+use async_trait::async_trait;
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+use time::OffsetDateTime;
+
+use crate::command::args::arg_duration::DurationArgumentConsumer;
+use crate::command::args::arg_game_profile::GameProfilesArgumentConsumer;
+use crate::command::args::arg_message::MsgArgConsumer;
+use crate::command::args::{ConsumedArgs, FindArg};
+use crate::command::tree::CommandTree;
+use crate::command::tree_builder::{argument, require};
+use crate::command::{CommandError, CommandExecutor, CommandSender};
+use crate::data::banned_player_data::{BannedPlayerEntry, BANNED_PLAYER_LIST};
+use crate::entity::player::PermissionLvl;
+use crate::server::audit::{AuditAction, AuditSource};
+use crate::server::Server;
+
+const NAMES: [&str; 1] = ["ban"];
+const DESCRIPTION: &str = "Bans a player from the server.";
+
+const TEMPBAN_NAMES: [&str; 1] = ["tempban"];
+const TEMPBAN_DESCRIPTION: &str = "Bans a player from the server for a limited time.";
+
+const ARG_TARGETS: &str = "targets";
+const ARG_DURATION: &str = "duration";
+const ARG_REASON: &str = "reason";
+
+const DEFAULT_REASON: &str = "Banned by an operator.";
+
+struct BanExecutor;
+
+#[async_trait]
+impl CommandExecutor for BanExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let targets =
+            GameProfilesArgumentConsumer::find_profiles(args, ARG_TARGETS, server).await?;
+        let reason = MsgArgConsumer::find_arg(args, ARG_REASON).unwrap_or(DEFAULT_REASON);
+        let expires = match DurationArgumentConsumer::find_arg(args, ARG_DURATION) {
+            Ok(duration) => {
+                let Some(expires) = time::Duration::try_from(duration)
+                    .ok()
+                    .and_then(|duration| OffsetDateTime::now_utc().checked_add(duration))
+                else {
+                    sender
+                        .send_message(
+                            TextComponent::text("The ban duration is too long")
+                                .color_named(NamedColor::Red),
+                        )
+                        .await;
+                    return Ok(());
+                };
+                Some(expires)
+            }
+            Err(_) => None,
+        };
+
+        for profile in &targets {
+            let entry = BannedPlayerEntry::new(
+                profile.id,
+                profile.name.clone(),
+                sender.to_string(),
+                expires,
+                reason.to_string(),
+            );
+            let kick_message = entry.kick_message();
+            if !BANNED_PLAYER_LIST.write().await.add(entry) {
+                sender
+                    .send_message(TextComponent::text(
+                        "Nothing changed. The player is already banned",
+                    ))
+                    .await;
+                continue;
+            }
+
+            server.audit_log.record(
+                AuditSource::from(&*sender),
+                AuditAction::Ban,
+                Some(&profile.name),
+                match expires {
+                    Some(expires) => format!("{reason} (until {expires})"),
+                    None => reason.to_string(),
+                },
+            );
+
+            if let Some(player) = server.get_player_by_uuid(profile.id).await {
+                player.kick(TextComponent::text_string(kick_message)).await;
+            }
+            sender
+                .send_feedback(
+                    server,
+                    TextComponent::text_string(format!("Banned {}: {reason}", profile.name)),
+                )
+                .await;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.ban", PermissionLvl::Three))
+            .with_child(
+                argument(ARG_TARGETS, &GameProfilesArgumentConsumer)
+                    .execute(&BanExecutor)
+                    .with_child(argument(ARG_REASON, &MsgArgConsumer).execute(&BanExecutor)),
+            ),
+    )
+}
+
+pub fn init_tempban_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(TEMPBAN_NAMES, TEMPBAN_DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.tempban", PermissionLvl::Three))
+            .with_child(
+                argument(ARG_TARGETS, &GameProfilesArgumentConsumer).with_child(
+                    argument(ARG_DURATION, &DurationArgumentConsumer)
+                        .execute(&BanExecutor)
+                        .with_child(argument(ARG_REASON, &MsgArgConsumer).execute(&BanExecutor)),
+                ),
+            ),
+    )
+}
@@ -0,0 +1,112 @@
+use std::net::IpAddr;
+
+use async_trait::async_trait;
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+
+use crate::command::args::arg_message::MsgArgConsumer;
+use crate::command::args::arg_simple::SimpleArgConsumer;
+use crate::command::args::{ConsumedArgs, FindArg};
+use crate::command::tree::CommandTree;
+use crate::command::tree_builder::{argument, require};
+use crate::command::{CommandError, CommandExecutor, CommandSender};
+use crate::data::banned_ip_data::{BannedIpEntry, BANNED_IP_LIST};
+use crate::entity::player::PermissionLvl;
+use crate::server::audit::{AuditAction, AuditSource};
+use crate::server::Server;
+
+const NAMES: [&str; 1] = ["ban-ip"];
+const DESCRIPTION: &str = "Bans an IP address, or the IP address of a player, from the server.";
+
+const ARG_TARGET: &str = "target";
+const ARG_REASON: &str = "reason";
+
+const DEFAULT_REASON: &str = "Banned by an operator.";
+
+struct BanIpExecutor;
+
+#[async_trait]
+impl CommandExecutor for BanIpExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let target = SimpleArgConsumer::find_arg(args, ARG_TARGET)?;
+        let reason = MsgArgConsumer::find_arg(args, ARG_REASON).unwrap_or(DEFAULT_REASON);
+
+        let ip = if let Ok(ip) = target.parse::<IpAddr>() {
+            ip
+        } else if let Some(player) = server.get_player_by_name(target).await {
+            player.client.address.lock().await.ip()
+        } else {
+            sender
+                .send_message(
+                    TextComponent::text("Invalid IP address or unknown player")
+                        .color_named(NamedColor::Red),
+                )
+                .await;
+            return Ok(());
+        };
+
+        let entry = BannedIpEntry::new(ip, sender.to_string(), None, reason.to_string());
+        let kick_message = entry.kick_message();
+        if !BANNED_IP_LIST.write().await.add(entry) {
+            sender
+                .send_message(TextComponent::text(
+                    "Nothing changed. That IP is already banned",
+                ))
+                .await;
+            return Ok(());
+        }
+
+        server.audit_log.record(
+            AuditSource::from(&*sender),
+            AuditAction::BanIp,
+            Some(&ip.to_string()),
+            reason,
+        );
+
+        let mut affected = Vec::new();
+        for player in server.get_all_players().await {
+            if player.client.address.lock().await.ip() == ip {
+                affected.push(player.gameprofile.name.clone());
+                player
+                    .kick(TextComponent::text_string(kick_message.clone()))
+                    .await;
+            }
+        }
+
+        sender
+            .send_feedback(
+                server,
+                TextComponent::text_string(format!("Banned IP {ip}: {reason}")),
+            )
+            .await;
+        if !affected.is_empty() {
+            sender
+                .send_feedback(
+                    server,
+                    TextComponent::text_string(format!(
+                        "This ban affects {} player(s): {}",
+                        affected.len(),
+                        affected.join(", ")
+                    )),
+                )
+                .await;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.ban-ip", PermissionLvl::Three))
+            .with_child(
+                argument(ARG_TARGET, &SimpleArgConsumer)
+                    .execute(&BanIpExecutor)
+                    .with_child(argument(ARG_REASON, &MsgArgConsumer).execute(&BanIpExecutor)),
+            ),
+    )
+}
@@ -10,7 +10,7 @@ use crate::command::args::{Arg, ConsumedArgs};
 use crate::command::tree::CommandTree;
 use crate::command::tree_builder::{argument, require};
 use crate::command::{CommandError, CommandExecutor, CommandSender};
-use crate::entity::player::Player;
+use crate::entity::player::{PermissionLvl, Player};
 use CommandError::InvalidConsumption;
 
 const NAMES: [&str; 1] = ["clear"];
@@ -63,7 +63,7 @@ impl CommandExecutor for ClearExecutor {
     async fn execute<'a>(
         &self,
         sender: &mut CommandSender<'a>,
-        _server: &crate::server::Server,
+        server: &crate::server::Server,
         args: &ConsumedArgs<'a>,
     ) -> Result<(), CommandError> {
         let Some(Arg::Entities(targets)) = args.get(&ARG_TARGET) else {
@@ -77,7 +77,7 @@ impl CommandExecutor for ClearExecutor {
 
         let msg = clear_command_text_output(item_count, targets);
 
-        sender.send_message(msg).await;
+        sender.send_feedback(server, msg).await;
 
         Ok(())
     }
@@ -90,7 +90,7 @@ impl CommandExecutor for ClearSelfExecutor {
     async fn execute<'a>(
         &self,
         sender: &mut CommandSender<'a>,
-        _server: &crate::server::Server,
+        server: &crate::server::Server,
         _args: &ConsumedArgs<'a>,
     ) -> Result<(), CommandError> {
         let target = sender.as_player().ok_or(CommandError::InvalidRequirement)?;
@@ -100,7 +100,7 @@ impl CommandExecutor for ClearSelfExecutor {
         let hold_target = [target];
         let msg = clear_command_text_output(item_count, &hold_target);
 
-        sender.send_message(msg).await;
+        sender.send_feedback(server, msg).await;
 
         Ok(())
     }
@@ -108,7 +108,9 @@ impl CommandExecutor for ClearSelfExecutor {
 
 #[allow(clippy::redundant_closure_for_method_calls)] // causes lifetime issues
 pub fn init_command_tree<'a>() -> CommandTree<'a> {
-    CommandTree::new(NAMES, DESCRIPTION)
-        .with_child(argument(ARG_TARGET, &EntitiesArgumentConsumer).execute(&ClearExecutor))
-        .with_child(require(&|sender| sender.is_player()).execute(&ClearSelfExecutor))
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.clear", PermissionLvl::Two))
+            .with_child(argument(ARG_TARGET, &EntitiesArgumentConsumer).execute(&ClearExecutor))
+            .with_child(require(&|sender| sender.is_player()).execute(&ClearSelfExecutor)),
+    )
 }
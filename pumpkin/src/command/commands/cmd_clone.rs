@@ -0,0 +1,204 @@
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use pumpkin_core::math::position::WorldPosition;
+use pumpkin_core::math::vector3::Vector3;
+use pumpkin_core::text::TextComponent;
+use pumpkin_world::block::block_registry::get_block_and_state_by_state_id;
+use pumpkin_world::game_rules::COMMAND_MODIFICATION_BLOCK_LIMIT;
+
+use crate::command::args::arg_block_predicate::BlockPredicateArgumentConsumer;
+use crate::command::args::arg_postition_block::BlockPosArgumentConsumer;
+use crate::command::args::{ConsumedArgs, FindArg};
+use crate::command::tree::CommandTree;
+use crate::command::tree_builder::{argument, literal, require, NonLeafNodeBuilder};
+use crate::command::{CommandError, CommandExecutor, CommandSender};
+use crate::entity::player::PermissionLvl;
+
+const NAMES: [&str; 1] = ["clone"];
+
+const DESCRIPTION: &str = "Copies blocks from one region to another.";
+
+const ARG_BEGIN: &str = "begin";
+const ARG_END: &str = "end";
+const ARG_DESTINATION: &str = "destination";
+const ARG_FILTER: &str = "filter";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MaskMode {
+    /// Copies all blocks
+    Replace,
+    /// Copies only non-air blocks
+    Masked,
+    /// Copies only blocks matching the filter
+    Filtered,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CloneMode {
+    /// Allows the source and destination region to overlap
+    Force,
+    /// Replaces the source region with air after copying
+    Move,
+    Normal,
+}
+
+struct CloneExecutor(MaskMode, CloneMode);
+
+#[async_trait]
+impl CommandExecutor for CloneExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &crate::server::Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let begin = BlockPosArgumentConsumer::find_arg(args, ARG_BEGIN)?;
+        let end = BlockPosArgumentConsumer::find_arg(args, ARG_END)?;
+        let destination = BlockPosArgumentConsumer::find_arg(args, ARG_DESTINATION)?;
+        let filter = match self.0 {
+            MaskMode::Filtered => Some(BlockPredicateArgumentConsumer::find_arg(args, ARG_FILTER)?),
+            MaskMode::Replace | MaskMode::Masked => None,
+        };
+        let world = sender.world().ok_or(CommandError::InvalidRequirement)?;
+
+        let start = Vector3::new(
+            begin.0.x.min(end.0.x),
+            begin.0.y.min(end.0.y),
+            begin.0.z.min(end.0.z),
+        );
+        let size = Vector3::new(
+            (begin.0.x - end.0.x).abs() + 1,
+            (begin.0.y - end.0.y).abs() + 1,
+            (begin.0.z - end.0.z).abs() + 1,
+        );
+        let offset = destination.0.sub(&start);
+
+        let volume = i64::from(size.x) * i64::from(size.y) * i64::from(size.z);
+        let limit = world
+            .game_rules
+            .read()
+            .await
+            .get(&COMMAND_MODIFICATION_BLOCK_LIMIT);
+        if volume > i64::from(limit) {
+            return Err(CommandError::GeneralCommandIssue(format!(
+                "Too many blocks in the specified area (maximum {limit}, specified {volume})"
+            )));
+        }
+
+        let overlaps = (0..3).all(|axis| {
+            let (start, destination, size) = match axis {
+                0 => (start.x, destination.0.x, size.x),
+                1 => (start.y, destination.0.y, size.y),
+                _ => (start.z, destination.0.z, size.z),
+            };
+            destination < start + size && start < destination + size
+        });
+        if overlaps && self.1 != CloneMode::Force {
+            return Err(CommandError::GeneralCommandIssue(
+                "The source and destination can not overlap".into(),
+            ));
+        }
+
+        // read the whole source first, so overlapping regions are copied correctly
+        let mut copied = Vec::new();
+        let mut sources = Vec::new();
+        for x in start.x..start.x + size.x {
+            for y in start.y..start.y + size.y {
+                for z in start.z..start.z + size.z {
+                    let position = WorldPosition(Vector3::new(x, y, z));
+                    let Ok(state_id) = world.get_block_state_id(position).await else {
+                        continue;
+                    };
+                    let Some((block, state)) = get_block_and_state_by_state_id(state_id) else {
+                        continue;
+                    };
+                    let copy = match self.0 {
+                        MaskMode::Replace => true,
+                        MaskMode::Masked => !state.air,
+                        MaskMode::Filtered => filter
+                            .as_ref()
+                            .is_some_and(|filter| filter.test(block, state_id)),
+                    };
+                    if copy {
+                        copied.push((WorldPosition(position.0.add(&offset)), state_id));
+                        sources.push(position);
+                    }
+                }
+            }
+        }
+
+        if self.1 == CloneMode::Move {
+            let destinations: HashSet<_> = copied.iter().map(|(copy, _)| copy.0).collect();
+            let cleared: Vec<_> = sources
+                .into_iter()
+                .filter(|position| !destinations.contains(&position.0))
+                .map(|position| (position, 0))
+                .collect();
+            world.set_block_states(&cleared).await;
+        }
+        world.set_block_states(&copied).await;
+
+        sender
+            .send_feedback(
+                server,
+                TextComponent::text_string(format!(
+                    "Successfully cloned {} block(s)",
+                    copied.len()
+                )),
+            )
+            .await;
+        Ok(())
+    }
+}
+
+static REPLACE_EXECUTORS: [CloneExecutor; 3] = [
+    CloneExecutor(MaskMode::Replace, CloneMode::Normal),
+    CloneExecutor(MaskMode::Replace, CloneMode::Force),
+    CloneExecutor(MaskMode::Replace, CloneMode::Move),
+];
+static MASKED_EXECUTORS: [CloneExecutor; 3] = [
+    CloneExecutor(MaskMode::Masked, CloneMode::Normal),
+    CloneExecutor(MaskMode::Masked, CloneMode::Force),
+    CloneExecutor(MaskMode::Masked, CloneMode::Move),
+];
+static FILTERED_EXECUTORS: [CloneExecutor; 3] = [
+    CloneExecutor(MaskMode::Filtered, CloneMode::Normal),
+    CloneExecutor(MaskMode::Filtered, CloneMode::Force),
+    CloneExecutor(MaskMode::Filtered, CloneMode::Move),
+];
+
+/// Adds the optional `force|move|normal` argument, executors are ordered normal, force, move.
+fn with_clone_modes<'a>(
+    node: NonLeafNodeBuilder<'a>,
+    executors: &'a [CloneExecutor; 3],
+) -> NonLeafNodeBuilder<'a> {
+    let [normal, force, move_] = executors;
+    node.execute(normal)
+        .with_child(literal("force").execute(force))
+        .with_child(literal("move").execute(move_))
+        .with_child(literal("normal").execute(normal))
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| {
+            sender.has_permission("pumpkin.command.clone", PermissionLvl::Two)
+                && sender.world().is_some()
+        })
+        .with_child(
+            argument(ARG_BEGIN, &BlockPosArgumentConsumer).with_child(
+                argument(ARG_END, &BlockPosArgumentConsumer).with_child(
+                    argument(ARG_DESTINATION, &BlockPosArgumentConsumer)
+                        .execute(&REPLACE_EXECUTORS[0])
+                        .with_child(with_clone_modes(literal("replace"), &REPLACE_EXECUTORS))
+                        .with_child(with_clone_modes(literal("masked"), &MASKED_EXECUTORS))
+                        .with_child(literal("filtered").with_child(with_clone_modes(
+                            argument(ARG_FILTER, &BlockPredicateArgumentConsumer),
+                            &FILTERED_EXECUTORS,
+                        ))),
+                ),
+            ),
+        ),
+    )
+}
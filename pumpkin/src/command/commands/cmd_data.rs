@@ -0,0 +1,344 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use pumpkin_core::nbt::{self, snbt, type_name, Compound, NbtError, Value};
+use pumpkin_core::persistent_data::NamespacedKey;
+use pumpkin_core::{math::position::WorldPosition, text::TextComponent};
+
+use crate::command::args::arg_bounded_num::BoundedNumArgumentConsumer;
+use crate::command::args::arg_entity::EntityArgumentConsumer;
+use crate::command::args::arg_nbt::{
+    NbtCompoundArgumentConsumer, NbtPathArgumentConsumer, NbtTagArgumentConsumer,
+    StorageArgumentConsumer,
+};
+use crate::command::args::arg_postition_block::BlockPosArgumentConsumer;
+use crate::command::args::{ConsumedArgs, FindArg, FindArgDefaultName};
+use crate::command::tree::CommandTree;
+use crate::command::tree_builder::{argument, literal, require, NonLeafNodeBuilder};
+use crate::command::{CommandError, CommandExecutor, CommandSender};
+use crate::entity::player::{PermissionLvl, Player};
+use crate::server::Server;
+use crate::world::World;
+
+const NAMES: [&str; 1] = ["data"];
+
+const DESCRIPTION: &str =
+    "Gets, merges, modifies and removes NBT of entities, blocks and storages.";
+
+const ARG_TARGET: &str = "target";
+const ARG_POS: &str = "targetPos";
+const ARG_STORAGE: &str = "storage";
+const ARG_NBT: &str = "nbt";
+const ARG_PATH: &str = "path";
+const ARG_VALUE: &str = "value";
+
+static INDEX_CONSUMER: BoundedNumArgumentConsumer<i32> =
+    BoundedNumArgumentConsumer::new().name("index");
+
+/// What `/data` works on, which is decided by the argument which was given
+enum DataTarget {
+    Entity(Arc<Player>),
+    Block(Arc<World>, WorldPosition),
+    Storage(Arc<World>, NamespacedKey),
+}
+
+fn nbt_error(err: &NbtError) -> CommandError {
+    CommandError::GeneralCommandIssue(err.to_string())
+}
+
+impl DataTarget {
+    fn find(
+        sender: &CommandSender<'_>,
+        server: &Server,
+        args: &ConsumedArgs<'_>,
+    ) -> Result<Self, CommandError> {
+        if let Ok(player) = EntityArgumentConsumer::find_arg(args, ARG_TARGET) {
+            return Ok(Self::Entity(player));
+        }
+        // storages and blocks of the console are in the default world
+        let world = sender
+            .as_player()
+            .map(|player| player.living_entity.entity.world.clone())
+            .or_else(|| server.worlds.first().cloned())
+            .ok_or(CommandError::InvalidRequirement)?;
+        if let Ok(position) = BlockPosArgumentConsumer::find_arg(args, ARG_POS) {
+            return Ok(Self::Block(world, position));
+        }
+        let id = StorageArgumentConsumer::find_arg(args, ARG_STORAGE)?;
+        Ok(Self::Storage(world, id.clone()))
+    }
+
+    async fn get(&self) -> Result<Compound, CommandError> {
+        match self {
+            Self::Entity(player) => Ok(player.nbt().await),
+            Self::Block(world, position) => {
+                world.block_entity_nbt(*position).await.ok_or_else(|| {
+                    CommandError::GeneralCommandIssue(
+                        "The target block is not a block entity".to_string(),
+                    )
+                })
+            }
+            Self::Storage(world, id) => {
+                Ok(world.with_command_storage(id.namespace(), id.key(), |storage| storage.clone()))
+            }
+        }
+    }
+
+    async fn set(&self, nbt: &Compound) -> Result<(), CommandError> {
+        match self {
+            Self::Entity(_) => Err(CommandError::GeneralCommandIssue(
+                "Unable to modify player data".to_string(),
+            )),
+            Self::Block(world, position) => {
+                world.set_block_entity_nbt(*position, nbt).await;
+                Ok(())
+            }
+            Self::Storage(world, id) => {
+                world.with_command_storage(id.namespace(), id.key(), |storage| {
+                    storage.clone_from(nbt);
+                });
+                Ok(())
+            }
+        }
+    }
+
+    /// Gets the NBT, lets `modify` change it and saves it if something changed
+    async fn modify(
+        &self,
+        modify: impl FnOnce(&mut Compound) -> Result<bool, CommandError> + Send,
+    ) -> Result<(), CommandError> {
+        if let Self::Entity(_) = self {
+            return Err(CommandError::GeneralCommandIssue(
+                "Unable to modify player data".to_string(),
+            ));
+        }
+        let mut nbt = self.get().await?;
+        if !modify(&mut nbt)? {
+            return Err(CommandError::GeneralCommandIssue(
+                "Nothing changed. The specified properties already have these values".to_string(),
+            ));
+        }
+        self.set(&nbt).await
+    }
+
+    fn query_message(&self, value: &Value) -> String {
+        let value = snbt::to_snbt(value);
+        match self {
+            Self::Entity(player) => format!(
+                "{} has the following entity data: {value}",
+                player.gameprofile.name
+            ),
+            Self::Block(_, position) => {
+                format!("{position} has the following block data: {value}")
+            }
+            Self::Storage(_, id) => format!("Storage {id} has the following contents: {value}"),
+        }
+    }
+
+    fn modified_message(&self) -> String {
+        match self {
+            Self::Entity(player) => format!("Modified entity data of {}", player.gameprofile.name),
+            Self::Block(_, position) => format!("Modified block data of {position}"),
+            Self::Storage(_, id) => format!("Modified storage {id}"),
+        }
+    }
+}
+
+struct GetExecutor;
+
+#[async_trait]
+impl CommandExecutor for GetExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let target = DataTarget::find(sender, server, args)?;
+        let nbt = target.get().await?;
+        let value = match NbtPathArgumentConsumer::find_arg(args, ARG_PATH) {
+            Ok(path) => {
+                let mut values = path.get(&nbt).map_err(|err| nbt_error(&err))?;
+                if values.len() > 1 {
+                    return Err(CommandError::GeneralCommandIssue(
+                        "This argument accepts a single NBT value".to_string(),
+                    ));
+                }
+                values.remove(0)
+            }
+            Err(_) => Value::Compound(nbt),
+        };
+        sender
+            .send_message(TextComponent::text_string(target.query_message(&value)))
+            .await;
+        Ok(())
+    }
+}
+
+struct MergeExecutor;
+
+#[async_trait]
+impl CommandExecutor for MergeExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let target = DataTarget::find(sender, server, args)?;
+        let merged = NbtCompoundArgumentConsumer::find_arg(args, ARG_NBT)?;
+        target.modify(|nbt| Ok(nbt::merge(nbt, merged))).await?;
+        sender
+            .send_message(TextComponent::text_string(target.modified_message()))
+            .await;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Operation {
+    Set,
+    Merge,
+    Append,
+    Prepend,
+    Insert,
+}
+
+struct ModifyExecutor(Operation);
+
+#[async_trait]
+impl CommandExecutor for ModifyExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let target = DataTarget::find(sender, server, args)?;
+        let path = NbtPathArgumentConsumer::find_arg(args, ARG_PATH)?;
+        let value = NbtTagArgumentConsumer::find_arg(args, ARG_VALUE)?;
+        let index = match self.0 {
+            Operation::Append => -1,
+            Operation::Insert => INDEX_CONSUMER
+                .find_arg_default_name(args)?
+                .map_err(|()| CommandError::InvalidConsumption(Some("index".to_string())))?,
+            Operation::Prepend | Operation::Set | Operation::Merge => 0,
+        };
+        let operation = self.0;
+        target
+            .modify(|nbt| {
+                let changed = match operation {
+                    Operation::Set => path.set(nbt, value),
+                    Operation::Merge => {
+                        let Value::Compound(value) = value else {
+                            return Err(CommandError::GeneralCommandIssue(format!(
+                                "Expected compound, got: {}",
+                                type_name(value)
+                            )));
+                        };
+                        path.merge(nbt, value)
+                    }
+                    Operation::Append | Operation::Prepend | Operation::Insert => {
+                        path.insert(nbt, index, std::slice::from_ref(value))
+                    }
+                };
+                Ok(changed.map_err(|err| nbt_error(&err))? > 0)
+            })
+            .await?;
+        sender
+            .send_message(TextComponent::text_string(target.modified_message()))
+            .await;
+        Ok(())
+    }
+}
+
+struct RemoveExecutor;
+
+#[async_trait]
+impl CommandExecutor for RemoveExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let target = DataTarget::find(sender, server, args)?;
+        let path = NbtPathArgumentConsumer::find_arg(args, ARG_PATH)?;
+        target
+            .modify(|nbt| Ok(path.remove(nbt).map_err(|err| nbt_error(&err))? > 0))
+            .await?;
+        sender
+            .send_message(TextComponent::text_string(target.modified_message()))
+            .await;
+        Ok(())
+    }
+}
+
+/// Adds the entity, block and storage targets to the node, each followed by `then`
+fn with_targets<'a>(
+    node: NonLeafNodeBuilder<'a>,
+    then: &impl Fn(NonLeafNodeBuilder<'a>) -> NonLeafNodeBuilder<'a>,
+) -> NonLeafNodeBuilder<'a> {
+    node.with_child(
+        literal("entity").with_child(then(argument(ARG_TARGET, &EntityArgumentConsumer))),
+    )
+    .with_child(literal("block").with_child(then(argument(ARG_POS, &BlockPosArgumentConsumer))))
+    .with_child(
+        literal("storage").with_child(then(argument(ARG_STORAGE, &StorageArgumentConsumer))),
+    )
+}
+
+fn modify_value<'a>(
+    operation: NonLeafNodeBuilder<'a>,
+    executor: &'a ModifyExecutor,
+) -> NonLeafNodeBuilder<'a> {
+    operation.with_child(
+        literal("value").with_child(argument(ARG_VALUE, &NbtTagArgumentConsumer).execute(executor)),
+    )
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.data", PermissionLvl::Two))
+            .with_child(with_targets(literal("get"), &|target| {
+                target
+                    .with_child(argument(ARG_PATH, &NbtPathArgumentConsumer).execute(&GetExecutor))
+                    .execute(&GetExecutor)
+            }))
+            .with_child(with_targets(literal("merge"), &|target| {
+                target.with_child(
+                    argument(ARG_NBT, &NbtCompoundArgumentConsumer).execute(&MergeExecutor),
+                )
+            }))
+            .with_child(with_targets(literal("modify"), &|target| {
+                target.with_child(
+                    argument(ARG_PATH, &NbtPathArgumentConsumer)
+                        .with_child(modify_value(
+                            literal("set"),
+                            &ModifyExecutor(Operation::Set),
+                        ))
+                        .with_child(modify_value(
+                            literal("merge"),
+                            &ModifyExecutor(Operation::Merge),
+                        ))
+                        .with_child(modify_value(
+                            literal("append"),
+                            &ModifyExecutor(Operation::Append),
+                        ))
+                        .with_child(modify_value(
+                            literal("prepend"),
+                            &ModifyExecutor(Operation::Prepend),
+                        ))
+                        .with_child(literal("insert").with_child(modify_value(
+                            argument("index", &INDEX_CONSUMER),
+                            &ModifyExecutor(Operation::Insert),
+                        ))),
+                )
+            }))
+            .with_child(with_targets(literal("remove"), &|target| {
+                target.with_child(
+                    argument(ARG_PATH, &NbtPathArgumentConsumer).execute(&RemoveExecutor),
+                )
+            })),
+    )
+}
@@ -0,0 +1,196 @@
+use async_trait::async_trait;
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+use pumpkin_registry::PackPosition;
+
+use crate::command::args::arg_datapack::DatapackArgumentConsumer;
+use crate::command::args::{ConsumedArgs, FindArg};
+use crate::command::tree::CommandTree;
+use crate::command::tree_builder::{argument, literal, require};
+use crate::command::{CommandError, CommandExecutor, CommandSender};
+use crate::entity::player::PermissionLvl;
+use crate::server::Server;
+
+const NAMES: [&str; 1] = ["datapack"];
+
+const DESCRIPTION: &str = "Lists, enables or disables datapacks.";
+
+const ARG_NAME: &str = "name";
+const ARG_EXISTING: &str = "existing";
+
+#[derive(Clone, Copy)]
+enum ListKind {
+    All,
+    Available,
+    Enabled,
+}
+
+struct ListExecutor(ListKind);
+
+fn pack_list(ids: &[String]) -> String {
+    ids.iter()
+        .map(|id| format!("[{id}]"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[async_trait]
+impl CommandExecutor for ListExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        _args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let (enabled, disabled) = {
+            let datapacks = server.datapacks.lock();
+            (datapacks.enabled().to_vec(), datapacks.disabled())
+        };
+        let mut messages = Vec::new();
+        if matches!(self.0, ListKind::All | ListKind::Enabled) {
+            messages.push(format!(
+                "There are {} datapacks enabled: {}",
+                enabled.len(),
+                pack_list(&enabled)
+            ));
+        }
+        if matches!(self.0, ListKind::All | ListKind::Available) {
+            messages.push(if disabled.is_empty() {
+                "There are no more datapacks available".to_string()
+            } else {
+                format!(
+                    "There are {} datapacks available: {}",
+                    disabled.len(),
+                    pack_list(&disabled)
+                )
+            });
+        }
+        for message in messages {
+            sender
+                .send_message(TextComponent::text_string(message))
+                .await;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy)]
+enum EnablePosition {
+    First,
+    Last,
+    Before,
+    After,
+}
+
+struct EnableExecutor(EnablePosition);
+
+#[async_trait]
+impl CommandExecutor for EnableExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let id = DatapackArgumentConsumer::find_arg(args, ARG_NAME)?;
+        let position = match self.0 {
+            EnablePosition::First => PackPosition::First,
+            EnablePosition::Last => PackPosition::Last,
+            EnablePosition::Before => {
+                PackPosition::Before(DatapackArgumentConsumer::find_arg(args, ARG_EXISTING)?)
+            }
+            EnablePosition::After => {
+                PackPosition::After(DatapackArgumentConsumer::find_arg(args, ARG_EXISTING)?)
+            }
+        };
+        let mut compatible = true;
+        server
+            .update_datapacks(|datapacks| {
+                compatible = datapacks.get(id).is_none_or(|pack| pack.compatible);
+                datapacks.enable(id, &position)
+            })
+            .await
+            .map_err(CommandError::GeneralCommandIssue)?;
+
+        sender
+            .send_message(TextComponent::text_string(format!(
+                "Enabled datapack [{id}]"
+            )))
+            .await;
+        if !compatible {
+            sender
+                .send_message(
+                    TextComponent::text_string(format!(
+                        "Datapack [{id}] was made for another Minecraft version and may not work"
+                    ))
+                    .color_named(NamedColor::Yellow),
+                )
+                .await;
+        }
+        Ok(())
+    }
+}
+
+struct DisableExecutor;
+
+#[async_trait]
+impl CommandExecutor for DisableExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let id = DatapackArgumentConsumer::find_arg(args, ARG_NAME)?;
+        server
+            .update_datapacks(|datapacks| datapacks.disable(id))
+            .await
+            .map_err(CommandError::GeneralCommandIssue)?;
+
+        sender
+            .send_message(TextComponent::text_string(format!(
+                "Disabled datapack [{id}]"
+            )))
+            .await;
+        Ok(())
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.datapack", PermissionLvl::Two))
+            .with_child(
+                literal("list")
+                    .execute(&ListExecutor(ListKind::All))
+                    .with_child(literal("available").execute(&ListExecutor(ListKind::Available)))
+                    .with_child(literal("enabled").execute(&ListExecutor(ListKind::Enabled))),
+            )
+            .with_child(
+                literal("enable").with_child(
+                    argument(ARG_NAME, &DatapackArgumentConsumer { enabled: false })
+                        .execute(&EnableExecutor(EnablePosition::Last))
+                        .with_child(
+                            literal("first").execute(&EnableExecutor(EnablePosition::First)),
+                        )
+                        .with_child(literal("last").execute(&EnableExecutor(EnablePosition::Last)))
+                        .with_child(
+                            literal("before").with_child(
+                                argument(ARG_EXISTING, &DatapackArgumentConsumer { enabled: true })
+                                    .execute(&EnableExecutor(EnablePosition::Before)),
+                            ),
+                        )
+                        .with_child(
+                            literal("after").with_child(
+                                argument(ARG_EXISTING, &DatapackArgumentConsumer { enabled: true })
+                                    .execute(&EnableExecutor(EnablePosition::After)),
+                            ),
+                        ),
+                ),
+            )
+            .with_child(
+                literal("disable").with_child(
+                    argument(ARG_NAME, &DatapackArgumentConsumer { enabled: true })
+                        .execute(&DisableExecutor),
+                ),
+            ),
+    )
+}
@@ -0,0 +1,70 @@
+use async_trait::async_trait;
+use pumpkin_config::BASIC_CONFIG;
+
+use crate::command::args::arg_gamemode::GamemodeArgumentConsumer;
+use crate::command::args::{Arg, ConsumedArgs, GetCloned};
+use crate::command::dispatcher::CommandError;
+use crate::command::dispatcher::CommandError::InvalidConsumption;
+use crate::command::tree::CommandTree;
+use crate::command::tree_builder::{argument, require};
+use crate::command::{CommandExecutor, CommandSender};
+use crate::entity::player::PermissionLvl;
+use crate::server::audit::{AuditAction, AuditSource};
+use crate::server::Server;
+use crate::TextComponent;
+
+const NAMES: [&str; 1] = ["defaultgamemode"];
+
+const DESCRIPTION: &str = "Sets the game mode new players join in.";
+
+const ARG_GAMEMODE: &str = "gamemode";
+
+struct DefaultGamemodeExecutor;
+
+#[async_trait]
+impl CommandExecutor for DefaultGamemodeExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let Some(Arg::GameMode(gamemode)) = args.get_cloned(&ARG_GAMEMODE) else {
+            return Err(InvalidConsumption(Some(ARG_GAMEMODE.into())));
+        };
+        server.default_gamemode.store(gamemode);
+        server.audit_log.record(
+            AuditSource::from(&*sender),
+            AuditAction::Gamemode,
+            None,
+            format!("default {gamemode:?}"),
+        );
+
+        // like vanilla, a forced game mode applies to everyone online right away
+        if BASIC_CONFIG.force_gamemode {
+            for player in server.get_all_players().await {
+                if player.gamemode.load() != gamemode {
+                    player.set_gamemode(gamemode).await;
+                }
+            }
+        }
+        sender
+            .send_feedback(
+                server,
+                TextComponent::text(&format!("The default game mode is now {gamemode:?}")),
+            )
+            .await;
+        Ok(())
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| {
+            sender.has_permission("pumpkin.command.defaultgamemode", PermissionLvl::Two)
+        })
+        .with_child(
+            argument(ARG_GAMEMODE, &GamemodeArgumentConsumer).execute(&DefaultGamemodeExecutor),
+        ),
+    )
+}
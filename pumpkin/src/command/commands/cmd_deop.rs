@@ -0,0 +1,80 @@
+use async_trait::async_trait;
+use pumpkin_core::text::TextComponent;
+
+use crate::command::args::arg_game_profile::GameProfilesArgumentConsumer;
+use crate::command::args::ConsumedArgs;
+use crate::command::client_cmd_suggestions;
+use crate::command::tree::CommandTree;
+use crate::command::tree_builder::{argument, require};
+use crate::command::{CommandError, CommandExecutor, CommandSender};
+use crate::data::op_data::OPERATOR_CONFIG;
+use crate::entity::player::PermissionLvl;
+use crate::server::audit::{AuditAction, AuditSource};
+use crate::server::Server;
+
+const NAMES: [&str; 1] = ["deop"];
+const DESCRIPTION: &str = "Revokes operator status from a player.";
+
+const ARG_TARGETS: &str = "targets";
+
+struct DeopExecutor;
+
+#[async_trait]
+impl CommandExecutor for DeopExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let targets =
+            GameProfilesArgumentConsumer::find_profiles(args, ARG_TARGETS, server).await?;
+
+        for profile in &targets {
+            if !OPERATOR_CONFIG.write().await.remove(profile.id) {
+                sender
+                    .send_message(TextComponent::text(
+                        "Nothing changed. The player is not an operator",
+                    ))
+                    .await;
+                continue;
+            }
+
+            server.audit_log.record(
+                AuditSource::from(&*sender),
+                AuditAction::Deop,
+                Some(&profile.name),
+                "Removed operator",
+            );
+
+            if let Some(player) = server.get_player_by_uuid(profile.id).await {
+                player.set_permission_lvl(PermissionLvl::Zero).await;
+                client_cmd_suggestions::send_c_commands_packet(
+                    &player,
+                    &*server.command_dispatcher().await,
+                )
+                .await;
+            }
+            sender
+                .send_feedback(
+                    server,
+                    TextComponent::text_string(format!(
+                        "Made {} no longer a server operator",
+                        profile.name
+                    )),
+                )
+                .await;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.deop", PermissionLvl::Three))
+            .with_child(
+                argument(ARG_TARGETS, &GameProfilesArgumentConsumer).execute(&DeopExecutor),
+            ),
+    )
+}
@@ -0,0 +1,156 @@
+use async_trait::async_trait;
+use pumpkin_core::text::TextComponent;
+
+use crate::command::args::arg_bounded_num::BoundedNumArgumentConsumer;
+use crate::command::args::arg_players::PlayersArgumentConsumer;
+use crate::command::args::{Arg, ConsumedArgs, FindArgDefaultName};
+use crate::command::dispatcher::CommandError::InvalidConsumption;
+use crate::command::tree::CommandTree;
+use crate::command::tree_builder::{argument, argument_default_name, literal, require};
+use crate::command::{CommandError, CommandExecutor, CommandSender};
+use crate::entity::player::PermissionLvl;
+use crate::server::Server;
+use crate::world::player_chunker;
+
+const NAMES: [&str; 1] = ["distance"];
+
+const DESCRIPTION: &str =
+    "Shows or overrides how far players see and how far around them the world is simulated.";
+
+const ARG_TARGETS: &str = "targets";
+
+static DISTANCE_CONSUMER: BoundedNumArgumentConsumer<i32> = BoundedNumArgumentConsumer::new()
+    .name("distance")
+    .min(2)
+    .max(32);
+
+#[derive(Clone, Copy)]
+enum Distance {
+    View,
+    Simulation,
+}
+
+impl Distance {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::View => "view distance",
+            Self::Simulation => "simulation distance",
+        }
+    }
+}
+
+struct QueryExecutor;
+
+#[async_trait]
+impl CommandExecutor for QueryExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        _server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let Some(Arg::Players(targets)) = args.get(ARG_TARGETS) else {
+            return Err(InvalidConsumption(Some(ARG_TARGETS.into())));
+        };
+        for target in targets {
+            sender
+                .send_message(TextComponent::text_string(format!(
+                    "{} has a view distance of {} and a simulation distance of {} chunks",
+                    target.gameprofile.name,
+                    player_chunker::get_view_distance(target),
+                    player_chunker::get_simulation_distance(target)
+                )))
+                .await;
+        }
+        Ok(())
+    }
+}
+
+/// Sets the override of the distance, or removes it if `reset`
+struct SetExecutor {
+    distance: Distance,
+    reset: bool,
+}
+
+#[async_trait]
+impl CommandExecutor for SetExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        _server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let Some(Arg::Players(targets)) = args.get(ARG_TARGETS) else {
+            return Err(InvalidConsumption(Some(ARG_TARGETS.into())));
+        };
+        let value = if self.reset {
+            None
+        } else {
+            let Ok(value) = DISTANCE_CONSUMER.find_arg_default_name(args)? else {
+                return Err(CommandError::GeneralCommandIssue(
+                    "The distance must be between 2 and 32 chunks".into(),
+                ));
+            };
+            Some(value as u8)
+        };
+        for target in targets {
+            match self.distance {
+                Distance::View => target.view_distance_override.store(value),
+                Distance::Simulation => target.simulation_distance_override.store(value),
+            }
+            player_chunker::update_distances(target).await;
+        }
+        let message = match value {
+            Some(value) => format!(
+                "Set the {} of {} players to {value} chunks",
+                self.distance.name(),
+                targets.len()
+            ),
+            None => format!(
+                "The {} of {} players is the server's again",
+                self.distance.name(),
+                targets.len()
+            ),
+        };
+        sender
+            .send_message(TextComponent::text_string(message))
+            .await;
+        Ok(())
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.distance", PermissionLvl::Three))
+            .with_child(
+                argument(ARG_TARGETS, &PlayersArgumentConsumer)
+                    .execute(&QueryExecutor)
+                    .with_child(
+                        literal("view")
+                            .with_child(literal("reset").execute(&SetExecutor {
+                                distance: Distance::View,
+                                reset: true,
+                            }))
+                            .with_child(argument_default_name(&DISTANCE_CONSUMER).execute(
+                                &SetExecutor {
+                                    distance: Distance::View,
+                                    reset: false,
+                                },
+                            )),
+                    )
+                    .with_child(
+                        literal("simulation")
+                            .with_child(literal("reset").execute(&SetExecutor {
+                                distance: Distance::Simulation,
+                                reset: true,
+                            }))
+                            .with_child(argument_default_name(&DISTANCE_CONSUMER).execute(
+                                &SetExecutor {
+                                    distance: Distance::Simulation,
+                                    reset: false,
+                                },
+                            )),
+                    ),
+            ),
+    )
+}
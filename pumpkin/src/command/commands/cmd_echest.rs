@@ -1,5 +1,4 @@
 use async_trait::async_trait;
-use pumpkin_inventory::{Chest, OpenContainer};
 
 use crate::command::{
     args::ConsumedArgs, tree::CommandTree, CommandError, CommandExecutor, CommandSender,
@@ -7,8 +6,7 @@ use crate::command::{
 
 const NAMES: [&str; 2] = ["echest", "enderchest"];
 
-const DESCRIPTION: &str =
-    "Show your personal enderchest (this command is used for testing container behaviour)";
+const DESCRIPTION: &str = "Show your personal enderchest";
 
 struct EchestExecutor;
 
@@ -21,19 +19,9 @@ impl CommandExecutor for EchestExecutor {
         _args: &ConsumedArgs<'a>,
     ) -> Result<(), CommandError> {
         if let Some(player) = sender.as_player() {
-            let entity_id = player.entity_id();
-            player.open_container.store(Some(0));
-            {
-                let mut open_containers = server.open_containers.write().await;
-                if let Some(ender_chest) = open_containers.get_mut(&0) {
-                    ender_chest.add_player(entity_id);
-                } else {
-                    let open_container = OpenContainer::new_empty_container::<Chest>(entity_id);
-                    open_containers.insert(0, open_container);
-                }
-            }
+            let (container_id, ender_chest) = &player.ender_chest;
             player
-                .open_container(server, pumpkin_inventory::WindowType::Generic9x3)
+                .open_block_container(server, *container_id, ender_chest.clone())
                 .await;
         }
 
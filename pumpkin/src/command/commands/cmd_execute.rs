@@ -0,0 +1,98 @@
+use async_trait::async_trait;
+use pumpkin_core::text::TextComponent;
+use pumpkin_registry::{LootContext, PREDICATES};
+
+use crate::command::args::arg_datapack::DatapackEntryArgumentConsumer;
+use crate::command::args::arg_message::MsgArgConsumer;
+use crate::command::args::{ConsumedArgs, FindArg};
+use crate::command::tree::CommandTree;
+use crate::command::tree_builder::{argument, literal, require, NonLeafNodeBuilder};
+use crate::command::{CommandError, CommandExecutor, CommandSender};
+use crate::entity::player::PermissionLvl;
+use crate::server::Server;
+
+const NAMES: [&str; 1] = ["execute"];
+
+const DESCRIPTION: &str = "Runs a command if a predicate passes.";
+
+const ARG_PREDICATE: &str = "predicate";
+const ARG_COMMAND: &str = "command";
+
+fn predicate_ids() -> Vec<String> {
+    PREDICATES.ids()
+}
+
+/// Runs the command in [`ARG_COMMAND`] if the predicate passes, or fails when it doesn't.
+/// Without a command the result is reported
+struct PredicateExecutor {
+    expected: bool,
+}
+
+#[async_trait]
+impl CommandExecutor for PredicateExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let id = DatapackEntryArgumentConsumer::find_arg(args, ARG_PREDICATE)?;
+        let Some(predicate) = PREDICATES.get(id) else {
+            return Err(CommandError::GeneralCommandIssue(format!(
+                "Unknown predicate '{id}'"
+            )));
+        };
+        let context = sender
+            .as_player()
+            .map_or_else(LootContext::default, |player| player.loot_context());
+        let passed = predicate.0.test(&context) == self.expected;
+
+        let Ok(command) = MsgArgConsumer::find_arg(args, ARG_COMMAND) else {
+            if !passed {
+                return Err(CommandError::GeneralCommandIssue("Test failed".to_string()));
+            }
+            sender
+                .send_message(TextComponent::text("Test passed"))
+                .await;
+            return Ok(());
+        };
+        if !passed {
+            return Ok(());
+        }
+        // the command runs as the sender, but the sender's borrow lives longer than the server's
+        let mut sender = match sender {
+            CommandSender::Console => CommandSender::Console,
+            CommandSender::Rcon(output) => CommandSender::Rcon(*output),
+            CommandSender::Player(player) => CommandSender::Player(player.clone()),
+        };
+        let dispatcher = server.command_dispatcher().await;
+        dispatcher
+            .handle_command(&mut sender, server, command)
+            .await;
+        Ok(())
+    }
+}
+
+/// `<name> predicate <predicate> [run <command>]`
+fn condition<'a>(name: &'a str, executor: &'a dyn CommandExecutor) -> NonLeafNodeBuilder<'a> {
+    literal(name).with_child(
+        literal("predicate").with_child(
+            argument(
+                ARG_PREDICATE,
+                &DatapackEntryArgumentConsumer { ids: predicate_ids },
+            )
+            .execute(executor)
+            .with_child(
+                literal("run").with_child(argument(ARG_COMMAND, &MsgArgConsumer).execute(executor)),
+            ),
+        ),
+    )
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.execute", PermissionLvl::Two))
+            .with_child(condition("if", &PredicateExecutor { expected: true }))
+            .with_child(condition("unless", &PredicateExecutor { expected: false })),
+    )
+}
@@ -1,4 +1,5 @@
 use crate::command::args::arg_block::BlockArgumentConsumer;
+use crate::command::args::arg_block_predicate::BlockPredicateArgumentConsumer;
 use crate::command::args::arg_postition_block::BlockPosArgumentConsumer;
 use crate::command::args::{ConsumedArgs, FindArg};
 use crate::command::tree::CommandTree;
@@ -9,6 +10,8 @@ use async_trait::async_trait;
 use pumpkin_core::math::position::WorldPosition;
 use pumpkin_core::math::vector3::Vector3;
 use pumpkin_core::text::TextComponent;
+use pumpkin_world::block::block_registry::get_block_and_state_by_state_id;
+use pumpkin_world::game_rules::COMMAND_MODIFICATION_BLOCK_LIMIT;
 
 const NAMES: [&str; 1] = ["fill"];
 
@@ -17,6 +20,7 @@ const DESCRIPTION: &str = "Fills all or parts of a region with a specific block.
 const ARG_BLOCK: &str = "block";
 const ARG_FROM: &str = "from";
 const ARG_TO: &str = "to";
+const ARG_FILTER: &str = "filter";
 
 #[derive(Clone, Copy, Default)]
 enum Mode {
@@ -33,21 +37,24 @@ enum Mode {
     Replace,
 }
 
-struct SetblockExecutor(Mode);
+struct FillExecutor(Mode);
 
-#[expect(clippy::too_many_lines)]
 #[async_trait]
-impl CommandExecutor for SetblockExecutor {
+impl CommandExecutor for FillExecutor {
     async fn execute<'a>(
         &self,
         sender: &mut CommandSender<'a>,
-        _server: &crate::server::Server,
+        server: &crate::server::Server,
         args: &ConsumedArgs<'a>,
     ) -> Result<(), CommandError> {
         let block = BlockArgumentConsumer::find_arg(args, ARG_BLOCK)?;
-        let block_state_id = block.default_state_id;
+        let block_state_id = block.state_id;
         let from = BlockPosArgumentConsumer::find_arg(args, ARG_FROM)?;
         let to = BlockPosArgumentConsumer::find_arg(args, ARG_TO)?;
+        let filter = match args.get(ARG_FILTER) {
+            Some(_) => Some(BlockPredicateArgumentConsumer::find_arg(args, ARG_FILTER)?),
+            None => None,
+        };
         let mode = self.0;
 
         let start_x = from.0.x.min(to.0.x);
@@ -59,95 +66,85 @@ impl CommandExecutor for SetblockExecutor {
         let end_z = from.0.z.max(to.0.z);
 
         let world = sender.world().ok_or(CommandError::InvalidRequirement)?;
-        let mut placed_blocks = 0;
-
-        match mode {
-            Mode::Destroy => {
-                for x in start_x..=end_x {
-                    for y in start_y..=end_y {
-                        for z in start_z..=end_z {
-                            let block_position = WorldPosition(Vector3 { x, y, z });
-                            world.break_block(block_position, None).await;
-                            world.set_block_state(block_position, block_state_id).await;
-                            placed_blocks += 1;
-                        }
-                    }
-                }
-            }
-            Mode::Replace => {
-                for x in start_x..=end_x {
-                    for y in start_y..=end_y {
-                        for z in start_z..=end_z {
-                            let block_position = WorldPosition(Vector3 { x, y, z });
-                            world.set_block_state(block_position, block_state_id).await;
-                            placed_blocks += 1;
+
+        let volume = i64::from(end_x - start_x + 1)
+            * i64::from(end_y - start_y + 1)
+            * i64::from(end_z - start_z + 1);
+        let limit = world
+            .game_rules
+            .read()
+            .await
+            .get(&COMMAND_MODIFICATION_BLOCK_LIMIT);
+        if volume > i64::from(limit) {
+            return Err(CommandError::GeneralCommandIssue(format!(
+                "Too many blocks in the specified area (maximum {limit}, specified {volume})"
+            )));
+        }
+
+        let mut changes = Vec::new();
+        for x in start_x..=end_x {
+            for y in start_y..=end_y {
+                for z in start_z..=end_z {
+                    let block_position = WorldPosition(Vector3::new(x, y, z));
+                    let is_edge = x == start_x
+                        || x == end_x
+                        || y == start_y
+                        || y == end_y
+                        || z == start_z
+                        || z == end_z;
+
+                    let new_state_id = match mode {
+                        Mode::Hollow if !is_edge => 0,
+                        Mode::Outline if !is_edge => continue,
+                        _ => block_state_id,
+                    };
+
+                    if matches!(mode, Mode::Keep) || filter.is_some() {
+                        let Ok(old_state_id) = world.get_block_state_id(block_position).await
+                        else {
+                            continue;
+                        };
+                        let Some((old_block, old_state)) =
+                            get_block_and_state_by_state_id(old_state_id)
+                        else {
+                            continue;
+                        };
+                        if matches!(mode, Mode::Keep) && !old_state.air {
+                            continue;
                         }
-                    }
-                }
-            }
-            Mode::Keep => {
-                for x in start_x..=end_x {
-                    for y in start_y..=end_y {
-                        for z in start_z..=end_z {
-                            let block_position = WorldPosition(Vector3 { x, y, z });
-                            match world.get_block_state(block_position).await {
-                                Ok(old_state) if old_state.air => {
-                                    world.set_block_state(block_position, block_state_id).await;
-                                    placed_blocks += 1;
-                                }
-                                _ => {}
+                        if let Some(filter) = &filter {
+                            if !filter.test(old_block, old_state_id) {
+                                continue;
                             }
                         }
                     }
-                }
-            }
-            Mode::Hollow => {
-                for x in start_x..=end_x {
-                    for y in start_y..=end_y {
-                        for z in start_z..=end_z {
-                            let block_position = WorldPosition(Vector3::new(x, y, z));
-                            let is_edge = x == start_x
-                                || x == end_x
-                                || y == start_y
-                                || y == end_y
-                                || z == start_z
-                                || z == end_z;
-                            if is_edge {
-                                world.set_block_state(block_position, block_state_id).await;
-                            } else {
-                                world.set_block_state(block_position, 0).await;
-                            }
-                            placed_blocks += 1;
-                        }
+
+                    if matches!(mode, Mode::Destroy) {
+                        world.break_block(block_position, None).await;
                     }
+                    changes.push((block_position, new_state_id));
                 }
             }
-            Mode::Outline => {
-                for x in start_x..=end_x {
-                    for y in start_y..=end_y {
-                        for z in start_z..=end_z {
-                            let block_position = WorldPosition(Vector3::new(x, y, z));
-                            let is_edge = x == start_x
-                                || x == end_x
-                                || y == start_y
-                                || y == end_y
-                                || z == start_z
-                                || z == end_z;
-                            if is_edge {
-                                world.set_block_state(block_position, block_state_id).await;
-                                placed_blocks += 1;
-                            }
-                        }
-                    }
+        }
+
+        world.set_block_states(&changes).await;
+        if let Some(nbt) = &block.nbt {
+            for (position, state_id) in &changes {
+                if *state_id == block_state_id {
+                    world.set_block_entity_nbt(*position, nbt).await;
                 }
             }
-        };
+        }
 
         sender
-            .send_message(TextComponent::text_string(format!(
-                "Placed {} blocks of {} from {from} to {to}",
-                placed_blocks, block.name
-            )))
+            .send_feedback(
+                server,
+                TextComponent::text_string(format!(
+                    "Placed {} blocks of {} from {from} to {to}",
+                    changes.len(),
+                    block.block.name
+                )),
+            )
             .await;
 
         Ok(())
@@ -157,18 +154,26 @@ impl CommandExecutor for SetblockExecutor {
 pub fn init_command_tree<'a>() -> CommandTree<'a> {
     CommandTree::new(NAMES, DESCRIPTION).with_child(
         require(&|sender| {
-            sender.has_permission_lvl(PermissionLvl::Two) && sender.world().is_some()
+            sender.has_permission("pumpkin.command.fill", PermissionLvl::Two)
+                && sender.world().is_some()
         })
         .with_child(
             argument(ARG_FROM, &BlockPosArgumentConsumer).with_child(
                 argument(ARG_TO, &BlockPosArgumentConsumer).with_child(
                     argument(ARG_BLOCK, &BlockArgumentConsumer)
-                        .with_child(literal("destroy").execute(&SetblockExecutor(Mode::Destroy)))
-                        .with_child(literal("hollow").execute(&SetblockExecutor(Mode::Hollow)))
-                        .with_child(literal("keep").execute(&SetblockExecutor(Mode::Keep)))
-                        .with_child(literal("outline").execute(&SetblockExecutor(Mode::Outline)))
-                        .with_child(literal("replace").execute(&SetblockExecutor(Mode::Replace)))
-                        .execute(&SetblockExecutor(Mode::Replace)),
+                        .with_child(literal("destroy").execute(&FillExecutor(Mode::Destroy)))
+                        .with_child(literal("hollow").execute(&FillExecutor(Mode::Hollow)))
+                        .with_child(literal("keep").execute(&FillExecutor(Mode::Keep)))
+                        .with_child(literal("outline").execute(&FillExecutor(Mode::Outline)))
+                        .with_child(
+                            literal("replace")
+                                .execute(&FillExecutor(Mode::Replace))
+                                .with_child(
+                                    argument(ARG_FILTER, &BlockPredicateArgumentConsumer)
+                                        .execute(&FillExecutor(Mode::Replace)),
+                                ),
+                        )
+                        .execute(&FillExecutor(Mode::Replace)),
                 ),
             ),
         ),
@@ -15,6 +15,7 @@ use crate::command::tree::CommandTree;
 use crate::command::tree_builder::{argument, require};
 use crate::command::CommandSender::Player;
 use crate::command::{CommandExecutor, CommandSender};
+use crate::server::audit::{AuditAction, AuditSource};
 use crate::server::Server;
 
 const NAMES: [&str; 1] = ["gamemode"];
@@ -31,32 +32,40 @@ impl CommandExecutor for GamemodeTargetSelf {
     async fn execute<'a>(
         &self,
         sender: &mut CommandSender<'a>,
-        _server: &Server,
+        server: &Server,
         args: &ConsumedArgs<'a>,
     ) -> Result<(), CommandError> {
         let Some(Arg::GameMode(gamemode)) = args.get_cloned(&ARG_GAMEMODE) else {
             return Err(InvalidConsumption(Some(ARG_GAMEMODE.into())));
         };
-
-        if let Player(target) = sender {
-            if target.gamemode.load() == gamemode {
-                target
-                    .send_system_message(&TextComponent::text(&format!(
-                        "You already in {gamemode:?} gamemode"
-                    )))
-                    .await;
-            } else {
-                target.set_gamemode(gamemode).await;
-                target
-                    .send_system_message(&TextComponent::text(&format!(
-                        "Game mode was set to {gamemode:?}"
-                    )))
-                    .await;
-            }
-            Ok(())
+        let source = AuditSource::from(&*sender);
+        let Player(target) = &*sender else {
+            return Err(InvalidRequirement);
+        };
+        let target = target.clone();
+
+        if target.gamemode.load() == gamemode {
+            target
+                .send_system_message(&TextComponent::text(&format!(
+                    "You already in {gamemode:?} gamemode"
+                )))
+                .await;
         } else {
-            Err(InvalidRequirement)
+            server.audit_log.record(
+                source,
+                AuditAction::Gamemode,
+                Some(&target.gameprofile.name),
+                format!("{gamemode:?}"),
+            );
+            target.set_gamemode(gamemode).await;
+            sender
+                .send_feedback(
+                    server,
+                    TextComponent::text(&format!("Game mode was set to {gamemode:?}")),
+                )
+                .await;
         }
+        Ok(())
     }
 }
 
@@ -67,7 +76,7 @@ impl CommandExecutor for GamemodeTargetPlayer {
     async fn execute<'a>(
         &self,
         sender: &mut CommandSender<'a>,
-        _server: &Server,
+        server: &Server,
         args: &ConsumedArgs<'a>,
     ) -> Result<(), CommandError> {
         let Some(Arg::GameMode(gamemode)) = args.get_cloned(&ARG_GAMEMODE) else {
@@ -90,13 +99,22 @@ impl CommandExecutor for GamemodeTargetPlayer {
                         .await;
                 }
             } else {
+                server.audit_log.record(
+                    AuditSource::from(&*sender),
+                    AuditAction::Gamemode,
+                    Some(&target.gameprofile.name),
+                    format!("{gamemode:?}"),
+                );
                 target.set_gamemode(gamemode).await;
                 if target_count == 1 {
                     sender
-                        .send_message(TextComponent::text(&format!(
-                            "{}'s Game mode was set to {:?}",
-                            target.gameprofile.name, gamemode
-                        )))
+                        .send_feedback(
+                            server,
+                            TextComponent::text(&format!(
+                                "{}'s Game mode was set to {:?}",
+                                target.gameprofile.name, gamemode
+                            )),
+                        )
                         .await;
                 }
             }
@@ -109,12 +127,22 @@ impl CommandExecutor for GamemodeTargetPlayer {
 #[allow(clippy::redundant_closure_for_method_calls)]
 pub fn init_command_tree<'a>() -> CommandTree<'a> {
     CommandTree::new(NAMES, DESCRIPTION).with_child(
-        require(&|sender| sender.has_permission_lvl(PermissionLvl::Two)).with_child(
-            argument(ARG_GAMEMODE, &GamemodeArgumentConsumer)
-                .with_child(require(&|sender| sender.is_player()).execute(&GamemodeTargetSelf))
-                .with_child(
-                    argument(ARG_TARGET, &PlayersArgumentConsumer).execute(&GamemodeTargetPlayer),
-                ),
-        ),
+        require(&|sender| sender.has_permission("pumpkin.command.gamemode", PermissionLvl::Two))
+            .with_child(
+                argument(ARG_GAMEMODE, &GamemodeArgumentConsumer)
+                    .with_child(require(&|sender| sender.is_player()).execute(&GamemodeTargetSelf))
+                    .with_child(
+                        require(&|sender| {
+                            sender.has_permission(
+                                "pumpkin.command.gamemode.other",
+                                PermissionLvl::Two,
+                            )
+                        })
+                        .with_child(
+                            argument(ARG_TARGET, &PlayersArgumentConsumer)
+                                .execute(&GamemodeTargetPlayer),
+                        ),
+                    ),
+            ),
     )
 }
@@ -0,0 +1,105 @@
+use async_trait::async_trait;
+use pumpkin_core::text::TextComponent;
+
+use crate::command::args::arg_gamerule::{GameRuleArgumentConsumer, GameRuleValueArgumentConsumer};
+use crate::command::args::{ConsumedArgs, FindArg};
+use crate::command::tree::CommandTree;
+use crate::command::tree_builder::{argument, require};
+use crate::command::{CommandError, CommandExecutor, CommandSender};
+use crate::entity::player::PermissionLvl;
+use crate::server::Server;
+use crate::world::World;
+
+const NAMES: [&str; 1] = ["gamerule"];
+
+const DESCRIPTION: &str = "Sets or queries a game rule value.";
+
+const ARG_RULE: &str = "rule";
+const ARG_VALUE: &str = "value";
+
+/// Players change the rules of the world they are in, everyone else changes the default world.
+fn target_world<'a>(sender: &'a CommandSender<'_>, server: &'a Server) -> &'a World {
+    sender.world().unwrap_or_else(|| {
+        server
+            .worlds
+            .first()
+            .expect("There should always be atleast one world")
+    })
+}
+
+struct GameRuleQueryExecutor;
+
+#[async_trait]
+impl CommandExecutor for GameRuleQueryExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let rule = GameRuleArgumentConsumer::find_arg(args, ARG_RULE)?;
+        let value = target_world(sender, server)
+            .game_rules
+            .read()
+            .await
+            .get_value(rule.name)
+            .unwrap_or(rule.default);
+
+        sender
+            .send_message(TextComponent::text(&format!(
+                "Gamerule {} is currently set to: {value}",
+                rule.name
+            )))
+            .await;
+        Ok(())
+    }
+}
+
+struct GameRuleSetExecutor;
+
+#[async_trait]
+impl CommandExecutor for GameRuleSetExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let rule = GameRuleArgumentConsumer::find_arg(args, ARG_RULE)?;
+        let raw_value = GameRuleValueArgumentConsumer::find_arg(args, ARG_VALUE)?;
+
+        let Some(value) = rule.default.kind().parse(raw_value) else {
+            return Err(CommandError::GeneralCommandIssue(format!(
+                "Invalid value '{raw_value}' for gamerule {}",
+                rule.name
+            )));
+        };
+
+        target_world(sender, server)
+            .set_game_rule(rule.name, value)
+            .await
+            .map_err(|err| CommandError::GeneralCommandIssue(err.to_string()))?;
+
+        sender
+            .send_feedback(
+                server,
+                TextComponent::text(&format!("Gamerule {} is now set to: {value}", rule.name)),
+            )
+            .await;
+        Ok(())
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.gamerule", PermissionLvl::Two))
+            .with_child(
+                argument(ARG_RULE, &GameRuleArgumentConsumer)
+                    .execute(&GameRuleQueryExecutor)
+                    .with_child(
+                        argument(ARG_VALUE, &GameRuleValueArgumentConsumer)
+                            .execute(&GameRuleSetExecutor),
+                    ),
+            ),
+    )
+}
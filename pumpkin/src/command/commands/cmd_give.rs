@@ -29,7 +29,7 @@ impl CommandExecutor for GiveExecutor {
     async fn execute<'a>(
         &self,
         sender: &mut CommandSender<'a>,
-        _server: &crate::server::Server,
+        server: &crate::server::Server,
         args: &ConsumedArgs<'a>,
     ) -> Result<(), CommandError> {
         let targets = PlayersArgumentConsumer.find_arg_default_name(args)?;
@@ -55,17 +55,20 @@ impl CommandExecutor for GiveExecutor {
         }
 
         sender
-            .send_message(TextComponent::text_string(match targets {
-                [target] => format!(
-                    "Gave {item_count} {} to {}",
-                    item_name, target.gameprofile.name
-                ),
-                _ => format!(
-                    "Gave {item_count} {} to {} players",
-                    item_name,
-                    targets.len()
-                ),
-            }))
+            .send_feedback(
+                server,
+                TextComponent::text_string(match targets {
+                    [target] => format!(
+                        "Gave {item_count} {} to {}",
+                        item_name, target.gameprofile.name
+                    ),
+                    _ => format!(
+                        "Gave {item_count} {} to {} players",
+                        item_name,
+                        targets.len()
+                    ),
+                }),
+            )
             .await;
 
         Ok(())
@@ -74,12 +77,15 @@ impl CommandExecutor for GiveExecutor {
 
 pub fn init_command_tree<'a>() -> CommandTree<'a> {
     CommandTree::new(NAMES, DESCRIPTION).with_child(
-        require(&|sender| sender.has_permission_lvl(PermissionLvl::Two)).with_child(
-            argument_default_name(&PlayersArgumentConsumer).with_child(
-                argument(ARG_ITEM, &ItemArgumentConsumer)
-                    .execute(&GiveExecutor)
-                    .with_child(argument_default_name(&ITEM_COUNT_CONSUMER).execute(&GiveExecutor)),
+        require(&|sender| sender.has_permission("pumpkin.command.give", PermissionLvl::Two))
+            .with_child(
+                argument_default_name(&PlayersArgumentConsumer).with_child(
+                    argument(ARG_ITEM, &ItemArgumentConsumer)
+                        .execute(&GiveExecutor)
+                        .with_child(
+                            argument_default_name(&ITEM_COUNT_CONSUMER).execute(&GiveExecutor),
+                        ),
+                ),
             ),
-        ),
     )
 }
@@ -53,11 +53,12 @@ impl CommandExecutor for BaseHelpExecutor {
         server: &Server,
         _args: &ConsumedArgs<'a>,
     ) -> Result<(), CommandError> {
-        let mut keys: Vec<&str> = server.command_dispatcher.commands.keys().copied().collect();
+        let dispatcher = server.command_dispatcher().await;
+        let mut keys: Vec<&str> = dispatcher.commands.keys().map(|key| key.as_ref()).collect();
         keys.sort_unstable();
 
         for key in keys {
-            let Command::Tree(tree) = &server.command_dispatcher.commands[key] else {
+            let Command::Tree(tree) = &dispatcher.commands[key] else {
                 continue;
             };
 
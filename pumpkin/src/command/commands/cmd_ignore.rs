@@ -0,0 +1,59 @@
+use async_trait::async_trait;
+use pumpkin_core::text::TextComponent;
+
+use crate::command::args::arg_game_profile::GameProfilesArgumentConsumer;
+use crate::command::args::ConsumedArgs;
+use crate::command::tree::CommandTree;
+use crate::command::tree_builder::{argument, require};
+use crate::command::{CommandError, CommandExecutor, CommandSender};
+use crate::server::Server;
+
+const NAMES: [&str; 1] = ["ignore"];
+
+const DESCRIPTION: &str = "Hides or shows the chat and private messages of players.";
+
+const ARG_TARGETS: &str = "targets";
+
+struct IgnoreExecutor;
+
+#[async_trait]
+impl CommandExecutor for IgnoreExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let targets =
+            GameProfilesArgumentConsumer::find_profiles(args, ARG_TARGETS, server).await?;
+        let Some(player) = sender.as_player() else {
+            return Err(CommandError::InvalidRequirement);
+        };
+
+        for target in &targets {
+            if target.id == player.gameprofile.id {
+                sender
+                    .send_message(TextComponent::text("You can't ignore yourself"))
+                    .await;
+                continue;
+            }
+            let message = if player.toggle_ignored(target.id) {
+                format!("You are now ignoring {}", target.name)
+            } else {
+                format!("You are no longer ignoring {}", target.name)
+            };
+            sender
+                .send_message(TextComponent::text_string(message))
+                .await;
+        }
+        Ok(())
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.is_player()).with_child(
+            argument(ARG_TARGETS, &GameProfilesArgumentConsumer).execute(&IgnoreExecutor),
+        ),
+    )
+}
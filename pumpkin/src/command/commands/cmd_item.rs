@@ -0,0 +1,100 @@
+use async_trait::async_trait;
+use pumpkin_core::text::TextComponent;
+use pumpkin_registry::ITEM_MODIFIERS;
+
+use crate::command::args::arg_datapack::DatapackEntryArgumentConsumer;
+use crate::command::args::arg_entities::EntitiesArgumentConsumer;
+use crate::command::args::arg_item_slot::ItemSlotArgumentConsumer;
+use crate::command::args::{ConsumedArgs, FindArg};
+use crate::command::tree::CommandTree;
+use crate::command::tree_builder::{argument, literal, require};
+use crate::command::{CommandError, CommandExecutor, CommandSender};
+use crate::entity::player::PermissionLvl;
+use crate::server::Server;
+
+const NAMES: [&str; 1] = ["item"];
+
+const DESCRIPTION: &str = "Modifies items in inventories.";
+
+const ARG_TARGETS: &str = "targets";
+const ARG_SLOT: &str = "slot";
+const ARG_MODIFIER: &str = "modifier";
+
+fn item_modifier_ids() -> Vec<String> {
+    ITEM_MODIFIERS.ids()
+}
+
+struct ModifyExecutor;
+
+#[async_trait]
+impl CommandExecutor for ModifyExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let targets = EntitiesArgumentConsumer::find_arg(args, ARG_TARGETS)?;
+        let slot = ItemSlotArgumentConsumer::find_arg(args, ARG_SLOT)?;
+        let id = DatapackEntryArgumentConsumer::find_arg(args, ARG_MODIFIER)?;
+        let Some(modifier) = ITEM_MODIFIERS.get(id) else {
+            return Err(CommandError::GeneralCommandIssue(format!(
+                "Unknown item modifier '{id}'"
+            )));
+        };
+
+        let mut modified = 0;
+        for target in targets {
+            let context = target.loot_context();
+            {
+                let mut inventory = target.inventory.lock().await;
+                let index = slot.index(&inventory);
+                let Ok(Some(stack)) = inventory.get_slot(index) else {
+                    continue;
+                };
+                modifier.apply(stack, &context);
+                if stack.item_count == 0 {
+                    *inventory.get_slot(index).expect("The slot was just read") = None;
+                }
+            }
+            target.set_container_content(None).await;
+            modified += 1;
+        }
+
+        if modified == 0 {
+            return Err(CommandError::GeneralCommandIssue(
+                "None of the targets has an item in the slot".to_string(),
+            ));
+        }
+        sender
+            .send_feedback(
+                server,
+                TextComponent::text_string(format!("Applied modifier to {modified} items")),
+            )
+            .await;
+        Ok(())
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.item", PermissionLvl::Two))
+            .with_child(
+                literal("modify").with_child(
+                    literal("entity").with_child(
+                        argument(ARG_TARGETS, &EntitiesArgumentConsumer).with_child(
+                            argument(ARG_SLOT, &ItemSlotArgumentConsumer).with_child(
+                                argument(
+                                    ARG_MODIFIER,
+                                    &DatapackEntryArgumentConsumer {
+                                        ids: item_modifier_ids,
+                                    },
+                                )
+                                .execute(&ModifyExecutor),
+                            ),
+                        ),
+                    ),
+                ),
+            ),
+    )
+}
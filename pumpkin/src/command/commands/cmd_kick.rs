@@ -1,19 +1,24 @@
 use async_trait::async_trait;
-use pumpkin_core::text::color::NamedColor;
 use pumpkin_core::text::TextComponent;
 
+use crate::command::args::arg_message::MsgArgConsumer;
 use crate::command::args::arg_players::PlayersArgumentConsumer;
-use crate::command::args::{Arg, ConsumedArgs};
+use crate::command::args::{Arg, ConsumedArgs, FindArg};
 use crate::command::tree::CommandTree;
-use crate::command::tree_builder::argument;
+use crate::command::tree_builder::{argument, require};
 use crate::command::CommandError;
 use crate::command::{CommandExecutor, CommandSender};
+use crate::entity::player::PermissionLvl;
+use crate::server::audit::{AuditAction, AuditSource};
 use CommandError::InvalidConsumption;
 
 const NAMES: [&str; 1] = ["kick"];
 const DESCRIPTION: &str = "Kicks the target player from the server.";
 
 const ARG_TARGET: &str = "target";
+const ARG_REASON: &str = "reason";
+
+const DEFAULT_REASON: &str = "Kicked by an operator";
 
 struct KickExecutor;
 
@@ -22,34 +27,47 @@ impl CommandExecutor for KickExecutor {
     async fn execute<'a>(
         &self,
         sender: &mut CommandSender<'a>,
-        _server: &crate::server::Server,
+        server: &crate::server::Server,
         args: &ConsumedArgs<'a>,
     ) -> Result<(), CommandError> {
         let Some(Arg::Players(targets)) = args.get(&ARG_TARGET) else {
             return Err(InvalidConsumption(Some(ARG_TARGET.into())));
         };
 
-        let target_count = targets.len();
+        // operators may format the reason, e.g. `<red>Be nice`
+        let reason = MsgArgConsumer::find_arg(args, ARG_REASON).map_or_else(
+            |_| TextComponent::text(DEFAULT_REASON),
+            TextComponent::from_mini_message,
+        );
 
         for target in targets {
-            target
-                .kick(TextComponent::text("Kicked by an operator"))
+            server.audit_log.record(
+                AuditSource::from(&*sender),
+                AuditAction::Kick,
+                Some(&target.gameprofile.name),
+                reason.clone().to_pretty_console(),
+            );
+            target.kick(reason.clone()).await;
+            sender
+                .send_feedback(
+                    server,
+                    TextComponent::text_string(format!("Kicked {}: ", target.gameprofile.name))
+                        .add_child(reason.clone()),
+                )
                 .await;
         }
 
-        let msg = if target_count == 1 {
-            TextComponent::text("Player has been kicked.")
-        } else {
-            TextComponent::text_string(format!("{target_count} players have been kicked."))
-        };
-
-        sender.send_message(msg.color_named(NamedColor::Blue)).await;
-
         Ok(())
     }
 }
 
 pub fn init_command_tree<'a>() -> CommandTree<'a> {
-    CommandTree::new(NAMES, DESCRIPTION)
-        .with_child(argument(ARG_TARGET, &PlayersArgumentConsumer).execute(&KickExecutor))
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.kick", PermissionLvl::Three))
+            .with_child(
+                argument(ARG_TARGET, &PlayersArgumentConsumer)
+                    .execute(&KickExecutor)
+                    .with_child(argument(ARG_REASON, &MsgArgConsumer).execute(&KickExecutor)),
+            ),
+    )
 }
@@ -1,5 +1,4 @@
 use async_trait::async_trait;
-use pumpkin_core::text::color::NamedColor;
 use pumpkin_core::text::TextComponent;
 
 use crate::command::args::arg_entities::EntitiesArgumentConsumer;
@@ -7,6 +6,7 @@ use crate::command::args::{Arg, ConsumedArgs};
 use crate::command::tree::CommandTree;
 use crate::command::tree_builder::{argument, require};
 use crate::command::{CommandError, CommandExecutor, CommandSender};
+use crate::entity::player::PermissionLvl;
 use CommandError::InvalidConsumption;
 
 const NAMES: [&str; 1] = ["kill"];
@@ -21,7 +21,7 @@ impl CommandExecutor for KillExecutor {
     async fn execute<'a>(
         &self,
         sender: &mut CommandSender<'a>,
-        _server: &crate::server::Server,
+        server: &crate::server::Server,
         args: &ConsumedArgs<'a>,
     ) -> Result<(), CommandError> {
         let Some(Arg::Entities(targets)) = args.get(&ARG_TARGET) else {
@@ -40,7 +40,7 @@ impl CommandExecutor for KillExecutor {
             TextComponent::text_string(format!("{target_count} entities have been killed."))
         };
 
-        sender.send_message(msg.color_named(NamedColor::Blue)).await;
+        sender.send_feedback(server, msg).await;
 
         Ok(())
     }
@@ -66,7 +66,9 @@ impl CommandExecutor for KillSelfExecutor {
 
 #[allow(clippy::redundant_closure_for_method_calls)] // causes lifetime issues
 pub fn init_command_tree<'a>() -> CommandTree<'a> {
-    CommandTree::new(NAMES, DESCRIPTION)
-        .with_child(argument(ARG_TARGET, &EntitiesArgumentConsumer).execute(&KillExecutor))
-        .with_child(require(&|sender| sender.is_player()).execute(&KillSelfExecutor))
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.kill", PermissionLvl::Two))
+            .with_child(argument(ARG_TARGET, &EntitiesArgumentConsumer).execute(&KillExecutor))
+            .with_child(require(&|sender| sender.is_player()).execute(&KillSelfExecutor)),
+    )
 }
@@ -0,0 +1,87 @@
+use async_trait::async_trait;
+use pumpkin_core::text::TextComponent;
+
+use crate::command::args::arg_players::PlayersArgumentConsumer;
+use crate::command::args::{Arg, ConsumedArgs};
+use crate::command::tree::CommandTree;
+use crate::command::tree_builder::{argument, require};
+use crate::command::CommandError;
+use crate::command::{CommandExecutor, CommandSender};
+use crate::entity::player::{PermissionLvl, Player};
+use CommandError::InvalidConsumption;
+
+const NAMES: [&str; 1] = ["lastdeath"];
+const DESCRIPTION: &str = "Shows where a player died last.";
+
+const ARG_TARGET: &str = "target";
+
+fn death_message(target: &Player) -> TextComponent<'static> {
+    match target.last_death_location() {
+        Some(death) => TextComponent::text_string(format!(
+            "{} died last at {}, {}, {} in {}",
+            target.gameprofile.name,
+            death.position.x,
+            death.position.y,
+            death.position.z,
+            death.dimension
+        )),
+        None => TextComponent::text_string(format!("{} has not died yet", target.gameprofile.name)),
+    }
+}
+
+struct LastDeathExecutor;
+
+#[async_trait]
+impl CommandExecutor for LastDeathExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        _server: &crate::server::Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let Some(Arg::Players(targets)) = args.get(&ARG_TARGET) else {
+            return Err(InvalidConsumption(Some(ARG_TARGET.into())));
+        };
+
+        for target in targets {
+            sender.send_message(death_message(target)).await;
+        }
+
+        Ok(())
+    }
+}
+
+struct LastDeathSelfExecutor;
+
+#[async_trait]
+impl CommandExecutor for LastDeathSelfExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        _server: &crate::server::Server,
+        _args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let target = sender.as_player().ok_or(CommandError::InvalidRequirement)?;
+
+        sender.send_message(death_message(&target)).await;
+
+        Ok(())
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION)
+        .with_child(
+            require(&|sender| {
+                sender.has_permission("pumpkin.command.lastdeath.others", PermissionLvl::Two)
+            })
+            .with_child(argument(ARG_TARGET, &PlayersArgumentConsumer).execute(&LastDeathExecutor)),
+        )
+        .with_child(
+            require(&|sender| {
+                sender.is_player()
+                    && sender.has_permission("pumpkin.command.lastdeath", PermissionLvl::Zero)
+            })
+            .execute(&LastDeathSelfExecutor),
+        )
+}
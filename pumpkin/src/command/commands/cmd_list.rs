@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use itertools::Itertools;
-use pumpkin_config::BASIC_CONFIG;
+use pumpkin_config::runtime_config;
 use pumpkin_core::text::TextComponent;
 
 use crate::{
@@ -34,7 +34,7 @@ impl CommandExecutor for ListExecutor {
             &format!(
                 "There are {} of a max of {} players online: {}",
                 players.len(),
-                BASIC_CONFIG.max_players,
+                runtime_config().max_players,
                 players
                     .iter()
                     .map(|player| &player.gameprofile.name)
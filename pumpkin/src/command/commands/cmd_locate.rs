@@ -0,0 +1,163 @@
+use std::borrow::Cow;
+
+use async_trait::async_trait;
+use pumpkin_core::math::vector2::Vector2;
+use pumpkin_core::text::click::ClickEvent;
+use pumpkin_core::text::hover::HoverEvent;
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+use pumpkin_world::dimension::Dimension;
+
+use crate::command::args::arg_biome::BiomeArgumentConsumer;
+use crate::command::args::arg_structure::StructureArgumentConsumer;
+use crate::command::args::{ConsumedArgs, FindArg};
+use crate::command::tree::CommandTree;
+use crate::command::tree_builder::{argument, literal, require};
+use crate::command::{CommandError, CommandExecutor, CommandSender};
+use crate::entity::player::PermissionLvl;
+use crate::server::Server;
+use crate::world::World;
+
+const NAMES: [&str; 1] = ["locate"];
+const LOCATEBIOME_NAMES: [&str; 1] = ["locatebiome"];
+
+const DESCRIPTION: &str = "Locates the closest structure or biome.";
+const LOCATEBIOME_DESCRIPTION: &str = "Locates the closest biome.";
+
+const ARG_STRUCTURE: &str = "structure";
+const ARG_BIOME: &str = "biome";
+
+/// Vanilla searches 100 chunks around the sender for structures.
+const STRUCTURE_SEARCH_RADIUS: i32 = 100;
+const BIOME_SEARCH_RADIUS: i32 = 6400;
+const BIOME_SEARCH_STEP: i32 = 32;
+
+/// Returns the world and the block position to search from.
+fn search_origin<'a>(
+    sender: &'a CommandSender<'_>,
+    server: &'a Server,
+) -> (&'a World, Vector2<i32>) {
+    let world = sender.world().unwrap_or_else(|| {
+        server
+            .worlds
+            .first()
+            .expect("There should always be atleast one world")
+    });
+    let origin = sender.position().map_or(Vector2::new(0, 0), |pos| {
+        Vector2::new(pos.x.floor() as i32, pos.z.floor() as i32)
+    });
+    (world, origin)
+}
+
+async fn send_result(
+    sender: &mut CommandSender<'_>,
+    name: &str,
+    origin: Vector2<i32>,
+    found: Vector2<i32>,
+) {
+    let distance = f64::from(found.sub(&origin).length_squared())
+        .sqrt()
+        .floor();
+    // TODO: only color the coordinates green once text components support children
+    sender
+        .send_message(
+            TextComponent::text_string(format!(
+                "The nearest {name} is at [{}, ~, {}] ({distance} blocks away)",
+                found.x, found.z
+            ))
+            .hover_event(HoverEvent::ShowText(Cow::from("Click to teleport")))
+            .click_event(ClickEvent::SuggestCommand(Cow::from(format!(
+                "/tp @s {} ~ {}",
+                found.x, found.z
+            ))))
+            .color_named(NamedColor::Green),
+        )
+        .await;
+}
+
+struct LocateStructureExecutor;
+
+#[async_trait]
+impl CommandExecutor for LocateStructureExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let structure = StructureArgumentConsumer::find_arg(args, ARG_STRUCTURE)?;
+        let (world, origin) = search_origin(sender, server);
+
+        // TODO: compare with the dimension of the world once more than the overworld is loaded
+        let found = if structure.dimension == Dimension::OverWorld {
+            world
+                .level
+                .locate_structure(origin, structure, STRUCTURE_SEARCH_RADIUS)
+        } else {
+            None
+        };
+        let Some(found) = found else {
+            return Err(CommandError::GeneralCommandIssue(format!(
+                "Could not find a structure of type \"{}\" nearby",
+                structure.name
+            )));
+        };
+
+        send_result(sender, structure.name, origin, found).await;
+        Ok(())
+    }
+}
+
+struct LocateBiomeExecutor;
+
+#[async_trait]
+impl CommandExecutor for LocateBiomeExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let biome = BiomeArgumentConsumer::find_arg(args, ARG_BIOME)?;
+        let (world, origin) = search_origin(sender, server);
+
+        let level = world.level.clone();
+        // the search may sample a lot of positions, so keep it away from the async runtime
+        let found = tokio::task::spawn_blocking(move || {
+            level.locate_biome(origin, biome, BIOME_SEARCH_RADIUS, BIOME_SEARCH_STEP)
+        })
+        .await
+        .ok()
+        .flatten();
+        let Some(found) = found else {
+            return Err(CommandError::GeneralCommandIssue(format!(
+                "Could not find a biome of type \"{}\" within reasonable distance",
+                biome.name()
+            )));
+        };
+
+        send_result(sender, biome.name(), origin, found).await;
+        Ok(())
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.locate", PermissionLvl::Two))
+            .with_child(
+                literal("structure").with_child(
+                    argument(ARG_STRUCTURE, &StructureArgumentConsumer)
+                        .execute(&LocateStructureExecutor),
+                ),
+            )
+            .with_child(literal("biome").with_child(
+                argument(ARG_BIOME, &BiomeArgumentConsumer).execute(&LocateBiomeExecutor),
+            )),
+    )
+}
+
+pub fn init_locatebiome_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(LOCATEBIOME_NAMES, LOCATEBIOME_DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.locatebiome", PermissionLvl::Two))
+            .with_child(argument(ARG_BIOME, &BiomeArgumentConsumer).execute(&LocateBiomeExecutor)),
+    )
+}
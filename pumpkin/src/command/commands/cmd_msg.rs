@@ -0,0 +1,85 @@
+use async_trait::async_trait;
+
+use crate::command::args::arg_message::MsgArgConsumer;
+use crate::command::args::arg_players::PlayersArgumentConsumer;
+use crate::command::args::{ConsumedArgs, FindArg};
+use crate::command::tree::CommandTree;
+use crate::command::tree_builder::{argument, require};
+use crate::command::{CommandError, CommandExecutor, CommandSender};
+use crate::entity::social;
+use crate::server::Server;
+
+const NAMES: [&str; 3] = ["msg", "tell", "w"];
+
+const DESCRIPTION: &str = "Sends a private message to one or more players.";
+
+const REPLY_NAMES: [&str; 2] = ["r", "reply"];
+
+const REPLY_DESCRIPTION: &str = "Answers the last player you whispered with.";
+
+const ARG_TARGETS: &str = "targets";
+const ARG_MESSAGE: &str = "message";
+
+struct MsgExecutor;
+
+#[async_trait]
+impl CommandExecutor for MsgExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        _server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let targets = PlayersArgumentConsumer::find_arg(args, ARG_TARGETS)?;
+        let message = MsgArgConsumer::find_arg(args, ARG_MESSAGE)?;
+
+        for target in targets {
+            social::whisper(sender, target, message).await;
+        }
+        Ok(())
+    }
+}
+
+struct ReplyExecutor;
+
+#[async_trait]
+impl CommandExecutor for ReplyExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let message = MsgArgConsumer::find_arg(args, ARG_MESSAGE)?;
+        let Some(player) = sender.as_player() else {
+            return Err(CommandError::InvalidRequirement);
+        };
+
+        let Some(uuid) = *player.reply_target.lock() else {
+            return Err(CommandError::GeneralCommandIssue(
+                "There is nobody to reply to".to_string(),
+            ));
+        };
+        let Some(target) = server.get_player_by_uuid(uuid) else {
+            return Err(CommandError::GeneralCommandIssue(
+                "That player is not online anymore".to_string(),
+            ));
+        };
+        social::whisper(sender, &target, message).await;
+        Ok(())
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        argument(ARG_TARGETS, &PlayersArgumentConsumer)
+            .with_child(argument(ARG_MESSAGE, &MsgArgConsumer).execute(&MsgExecutor)),
+    )
+}
+
+pub fn init_reply_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(REPLY_NAMES, REPLY_DESCRIPTION).with_child(
+        require(&|sender| sender.is_player())
+            .with_child(argument(ARG_MESSAGE, &MsgArgConsumer).execute(&ReplyExecutor)),
+    )
+}
@@ -0,0 +1,80 @@
+use async_trait::async_trait;
+use pumpkin_core::text::TextComponent;
+
+use crate::command::args::arg_game_profile::GameProfilesArgumentConsumer;
+use crate::command::args::ConsumedArgs;
+use crate::command::client_cmd_suggestions;
+use crate::command::tree::CommandTree;
+use crate::command::tree_builder::{argument, require};
+use crate::command::{CommandError, CommandExecutor, CommandSender};
+use crate::data::op_data::OPERATOR_CONFIG;
+use crate::entity::player::PermissionLvl;
+use crate::server::audit::{AuditAction, AuditSource};
+use crate::server::Server;
+
+const NAMES: [&str; 1] = ["op"];
+const DESCRIPTION: &str = "Grants operator status to a player.";
+
+const ARG_TARGETS: &str = "targets";
+
+struct OpExecutor;
+
+#[async_trait]
+impl CommandExecutor for OpExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let targets =
+            GameProfilesArgumentConsumer::find_profiles(args, ARG_TARGETS, server).await?;
+
+        for profile in &targets {
+            if !OPERATOR_CONFIG
+                .write()
+                .await
+                .add(profile.id, profile.name.clone())
+            {
+                sender
+                    .send_message(TextComponent::text(
+                        "Nothing changed. The player already is an operator",
+                    ))
+                    .await;
+                continue;
+            }
+
+            let lvl = OPERATOR_CONFIG.read().await.permission_lvl(profile.id);
+            server.audit_log.record(
+                AuditSource::from(&*sender),
+                AuditAction::Op,
+                Some(&profile.name),
+                format!("Level {}", lvl as i8),
+            );
+
+            if let Some(player) = server.get_player_by_uuid(profile.id).await {
+                player.set_permission_lvl(lvl).await;
+                client_cmd_suggestions::send_c_commands_packet(
+                    &player,
+                    &*server.command_dispatcher().await,
+                )
+                .await;
+            }
+            sender
+                .send_feedback(
+                    server,
+                    TextComponent::text_string(format!("Made {} a server operator", profile.name)),
+                )
+                .await;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.op", PermissionLvl::Three))
+            .with_child(argument(ARG_TARGETS, &GameProfilesArgumentConsumer).execute(&OpExecutor)),
+    )
+}
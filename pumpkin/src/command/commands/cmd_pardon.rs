@@ -0,0 +1,66 @@
+use async_trait::async_trait;
+use pumpkin_core::text::TextComponent;
+
+use crate::command::args::arg_game_profile::GameProfilesArgumentConsumer;
+use crate::command::args::ConsumedArgs;
+use crate::command::tree::CommandTree;
+use crate::command::tree_builder::{argument, require};
+use crate::command::{CommandError, CommandExecutor, CommandSender};
+use crate::data::banned_player_data::BANNED_PLAYER_LIST;
+use crate::entity::player::PermissionLvl;
+use crate::server::audit::{AuditAction, AuditSource};
+use crate::server::Server;
+
+const NAMES: [&str; 1] = ["pardon"];
+const DESCRIPTION: &str = "Removes a player from the ban list.";
+
+const ARG_TARGETS: &str = "targets";
+
+struct PardonExecutor;
+
+#[async_trait]
+impl CommandExecutor for PardonExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let targets =
+            GameProfilesArgumentConsumer::find_profiles(args, ARG_TARGETS, server).await?;
+
+        for profile in &targets {
+            if BANNED_PLAYER_LIST.write().await.remove(profile.id) {
+                server.audit_log.record(
+                    AuditSource::from(&*sender),
+                    AuditAction::Pardon,
+                    Some(&profile.name),
+                    "Unbanned",
+                );
+                sender
+                    .send_feedback(
+                        server,
+                        TextComponent::text_string(format!("Unbanned {}", profile.name)),
+                    )
+                    .await;
+            } else {
+                sender
+                    .send_message(TextComponent::text(
+                        "Nothing changed. The player isn't banned",
+                    ))
+                    .await;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.pardon", PermissionLvl::Three))
+            .with_child(
+                argument(ARG_TARGETS, &GameProfilesArgumentConsumer).execute(&PardonExecutor),
+            ),
+    )
+}
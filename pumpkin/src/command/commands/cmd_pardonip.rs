@@ -0,0 +1,70 @@
+use std::net::IpAddr;
+
+use async_trait::async_trait;
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+
+use crate::command::args::arg_simple::SimpleArgConsumer;
+use crate::command::args::{ConsumedArgs, FindArg};
+use crate::command::tree::CommandTree;
+use crate::command::tree_builder::{argument, require};
+use crate::command::{CommandError, CommandExecutor, CommandSender};
+use crate::data::banned_ip_data::BANNED_IP_LIST;
+use crate::entity::player::PermissionLvl;
+use crate::server::audit::{AuditAction, AuditSource};
+use crate::server::Server;
+
+const NAMES: [&str; 1] = ["pardon-ip"];
+const DESCRIPTION: &str = "Removes an IP address from the ban list.";
+
+const ARG_TARGET: &str = "target";
+
+struct PardonIpExecutor;
+
+#[async_trait]
+impl CommandExecutor for PardonIpExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let target = SimpleArgConsumer::find_arg(args, ARG_TARGET)?;
+
+        let Ok(ip) = target.parse::<IpAddr>() else {
+            sender
+                .send_message(
+                    TextComponent::text("Invalid IP address").color_named(NamedColor::Red),
+                )
+                .await;
+            return Ok(());
+        };
+
+        if BANNED_IP_LIST.write().await.remove(&ip) {
+            server.audit_log.record(
+                AuditSource::from(&*sender),
+                AuditAction::PardonIp,
+                Some(&ip.to_string()),
+                "Unbanned",
+            );
+            sender
+                .send_feedback(
+                    server,
+                    TextComponent::text_string(format!("Unbanned IP {ip}")),
+                )
+                .await;
+        } else {
+            sender
+                .send_message(TextComponent::text("Nothing changed. That IP isn't banned"))
+                .await;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.pardon-ip", PermissionLvl::Three))
+            .with_child(argument(ARG_TARGET, &SimpleArgConsumer).execute(&PardonIpExecutor)),
+    )
+}
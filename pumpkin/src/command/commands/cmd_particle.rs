@@ -0,0 +1,137 @@
+use async_trait::async_trait;
+use pumpkin_core::{math::vector3::Vector3, text::TextComponent};
+
+use crate::{
+    command::{
+        args::{
+            arg_bounded_num::BoundedNumArgumentConsumer, arg_particle::ParticleArgumentConsumer,
+            arg_players::PlayersArgumentConsumer, arg_position_3d::Position3DArgumentConsumer,
+            ConsumedArgs, DefaultNameArgConsumer, FindArg, FindArgDefaultName,
+        },
+        tree::CommandTree,
+        tree_builder::{argument, argument_default_name, literal, require},
+        CommandError, CommandExecutor, CommandSender,
+    },
+    entity::player::PermissionLvl,
+    server::Server,
+};
+
+const NAMES: [&str; 1] = ["particle"];
+
+const DESCRIPTION: &str = "Spawns particles.";
+
+const ARG_DELTA: &str = "delta";
+const ARG_VIEWERS: &str = "viewers";
+
+static SPEED_CONSUMER: BoundedNumArgumentConsumer<f32> =
+    BoundedNumArgumentConsumer::new().min(0.0).name("speed");
+
+static COUNT_CONSUMER: BoundedNumArgumentConsumer<i32> =
+    BoundedNumArgumentConsumer::new().min(0).name("count");
+
+struct ParticleExecutor {
+    force: bool,
+}
+
+#[async_trait]
+impl CommandExecutor for ParticleExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let particle = ParticleArgumentConsumer.find_arg_default_name(args)?;
+        let position = match Position3DArgumentConsumer.find_arg_default_name(args) {
+            Ok(position) => position,
+            Err(_) => sender.position().ok_or_else(|| {
+                CommandError::GeneralCommandIssue("A position is required here.".into())
+            })?,
+        };
+        // the rest is given all at once
+        let (delta, speed, count) = match Position3DArgumentConsumer::find_arg(args, ARG_DELTA) {
+            Ok(delta) => {
+                let (Ok(speed), Ok(count)) = (
+                    SPEED_CONSUMER.find_arg_default_name(args)?,
+                    COUNT_CONSUMER.find_arg_default_name(args)?,
+                ) else {
+                    return Err(CommandError::GeneralCommandIssue(format!(
+                        "{} or {} is out of bounds.",
+                        SPEED_CONSUMER.default_name(),
+                        COUNT_CONSUMER.default_name()
+                    )));
+                };
+                let delta = Vector3::new(delta.x as f32, delta.y as f32, delta.z as f32);
+                (delta, speed, count)
+            }
+            Err(_) => (Vector3::new(0.0, 0.0, 0.0), 0.0, 0),
+        };
+
+        let shown_to = if let Ok(viewers) = PlayersArgumentConsumer::find_arg(args, ARG_VIEWERS) {
+            let mut shown_to = 0;
+            for viewer in viewers {
+                if viewer
+                    .spawn_particle(particle, position, delta, speed, count, self.force)
+                    .await
+                {
+                    shown_to += 1;
+                }
+            }
+            shown_to
+        } else {
+            let world = sender.world().unwrap_or_else(|| {
+                server
+                    .worlds
+                    .first()
+                    .expect("There should always be atleast one world")
+            });
+            world
+                .spawn_particle(particle, position, delta, speed, count, self.force)
+                .await
+        };
+
+        if shown_to == 0 {
+            return Err(CommandError::GeneralCommandIssue(
+                "The particle was not visible for anybody".into(),
+            ));
+        }
+        let feedback = format!("Displaying particle to {shown_to} player(s)");
+        sender
+            .send_message(TextComponent::text_string(feedback))
+            .await;
+        Ok(())
+    }
+}
+
+static NORMAL: ParticleExecutor = ParticleExecutor { force: false };
+static FORCE: ParticleExecutor = ParticleExecutor { force: true };
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    let mode = |name, executor: &'static ParticleExecutor| {
+        literal(name)
+            .execute(executor)
+            .with_child(argument(ARG_VIEWERS, &PlayersArgumentConsumer).execute(executor))
+    };
+
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.particle", PermissionLvl::Two))
+            .with_child(
+                argument_default_name(&ParticleArgumentConsumer)
+                    .execute(&NORMAL)
+                    .with_child(
+                        argument_default_name(&Position3DArgumentConsumer)
+                            .execute(&NORMAL)
+                            .with_child(
+                                argument(ARG_DELTA, &Position3DArgumentConsumer).with_child(
+                                    argument_default_name(&SPEED_CONSUMER).with_child(
+                                        argument_default_name(&COUNT_CONSUMER)
+                                            .execute(&NORMAL)
+                                            .with_child(mode("force", &FORCE))
+                                            .with_child(mode("normal", &NORMAL)),
+                                    ),
+                                ),
+                            ),
+                    ),
+            ),
+    )
+}
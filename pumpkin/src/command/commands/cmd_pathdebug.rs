@@ -0,0 +1,116 @@
+use std::fs;
+use std::path::Path;
+
+use async_trait::async_trait;
+use pumpkin_core::text::TextComponent;
+
+use crate::command::args::arg_bounded_num::BoundedNumArgumentConsumer;
+use crate::command::args::arg_postition_block::BlockPosArgumentConsumer;
+use crate::command::args::{ConsumedArgs, FindArg};
+use crate::command::tree::CommandTree;
+use crate::command::tree_builder::{argument, literal, require};
+use crate::command::{CommandError, CommandExecutor, CommandSender};
+use crate::entity::player::PermissionLvl;
+use crate::server::Server;
+
+const NAMES: [&str; 1] = ["pathdebug"];
+
+const DESCRIPTION: &str = "Exports the pathfinding node types around a position.";
+
+const ARG_POSITION: &str = "position";
+const ARG_RADIUS: &str = "radius";
+
+const DEFAULT_RADIUS: i32 = 8;
+const HEIGHT: i32 = 4;
+const EXPORT_FOLDER: &str = "debug";
+
+static RADIUS_CONSUMER: BoundedNumArgumentConsumer<i32> =
+    BoundedNumArgumentConsumer::new().min(1).max(64);
+
+#[derive(Clone, Copy)]
+enum Format {
+    Json,
+    /// a top down PPM image of the layer at the given position
+    Image,
+}
+
+struct PathDebugExecutor(Format);
+
+#[async_trait]
+impl CommandExecutor for PathDebugExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let position = BlockPosArgumentConsumer::find_arg(args, ARG_POSITION)?;
+        let radius = match args.get(ARG_RADIUS) {
+            None => DEFAULT_RADIUS,
+            Some(_) => {
+                BoundedNumArgumentConsumer::<i32>::find_arg(args, ARG_RADIUS)?.map_err(|()| {
+                    CommandError::GeneralCommandIssue("Radius must be between 1 and 64".into())
+                })?
+            }
+        };
+        let world = sender.world().unwrap_or_else(|| {
+            server
+                .worlds
+                .first()
+                .expect("There should always be atleast one world")
+        });
+
+        let map = world.collision_map(position, radius, HEIGHT).await;
+
+        let pos = position.0;
+        let (extension, data) = match self.0 {
+            Format::Json => (
+                "json",
+                serde_json::to_vec_pretty(&map)
+                    .map_err(|err| CommandError::GeneralCommandIssue(err.to_string()))?,
+            ),
+            Format::Image => {
+                let mut data = Vec::new();
+                map.write_layer_ppm(pos.y, &mut data)
+                    .map_err(|err| CommandError::GeneralCommandIssue(err.to_string()))?;
+                ("ppm", data)
+            }
+        };
+        let path = Path::new(EXPORT_FOLDER).join(format!(
+            "pathfinding_{}_{}_{}.{extension}",
+            pos.x, pos.y, pos.z
+        ));
+        fs::create_dir_all(EXPORT_FOLDER)
+            .and_then(|()| fs::write(&path, data))
+            .map_err(|err| {
+                CommandError::GeneralCommandIssue(format!("Failed to write pathfinding map: {err}"))
+            })?;
+
+        sender
+            .send_message(TextComponent::text(&format!(
+                "Exported {} pathfinding nodes to {}",
+                map.nodes.len(),
+                path.display()
+            )))
+            .await;
+        Ok(())
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.pathdebug", PermissionLvl::Three))
+            .with_child(
+                argument(ARG_POSITION, &BlockPosArgumentConsumer)
+                    .execute(&PathDebugExecutor(Format::Json))
+                    .with_child(
+                        argument(ARG_RADIUS, &RADIUS_CONSUMER)
+                            .execute(&PathDebugExecutor(Format::Json))
+                            .with_child(literal("json").execute(&PathDebugExecutor(Format::Json)))
+                            .with_child(
+                                literal("image").execute(&PathDebugExecutor(Format::Image)),
+                            ),
+                    ),
+            ),
+    )
+}
@@ -0,0 +1,77 @@
+use async_trait::async_trait;
+use pumpkin_core::text::TextComponent;
+
+use crate::command::args::ConsumedArgs;
+use crate::command::tree::CommandTree;
+use crate::command::tree_builder::{literal, require};
+use crate::command::{CommandError, CommandExecutor, CommandSender};
+use crate::entity::player::PermissionLvl;
+use crate::server::perf_hud::HudMode;
+use crate::server::Server;
+
+const NAMES: [&str; 1] = ["perfhud"];
+
+const DESCRIPTION: &str = "Shows the server performance in the action bar or a boss bar.";
+
+/// Shows the HUD in the given mode, or toggles the action bar HUD if None
+struct PerfHudExecutor(Option<HudMode>);
+
+#[async_trait]
+impl CommandExecutor for PerfHudExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        _args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let Some(player) = sender.as_player() else {
+            return Err(CommandError::GeneralCommandIssue(
+                "Only players can see the performance HUD".into(),
+            ));
+        };
+
+        let msg = match self.0 {
+            None if server.perf_hud.unsubscribe(&player).await => "Performance HUD disabled",
+            None | Some(HudMode::ActionBar) => {
+                server.perf_hud.subscribe(&player, HudMode::ActionBar).await;
+                "Showing the server performance in the action bar"
+            }
+            Some(HudMode::BossBar) => {
+                server.perf_hud.subscribe(&player, HudMode::BossBar).await;
+                "Showing the server performance in a boss bar"
+            }
+        };
+        sender.send_message(TextComponent::text(msg)).await;
+        Ok(())
+    }
+}
+
+struct PerfHudOffExecutor;
+
+#[async_trait]
+impl CommandExecutor for PerfHudOffExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        _args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        if let Some(player) = sender.as_player() {
+            server.perf_hud.unsubscribe(&player).await;
+        }
+        sender
+            .send_message(TextComponent::text("Performance HUD disabled"))
+            .await;
+        Ok(())
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.perfhud", PermissionLvl::Three))
+            .execute(&PerfHudExecutor(None))
+            .with_child(literal("actionbar").execute(&PerfHudExecutor(Some(HudMode::ActionBar))))
+            .with_child(literal("bossbar").execute(&PerfHudExecutor(Some(HudMode::BossBar))))
+            .with_child(literal("off").execute(&PerfHudOffExecutor)),
+    )
+}
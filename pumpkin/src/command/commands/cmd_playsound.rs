@@ -0,0 +1,172 @@
+use async_trait::async_trait;
+use pumpkin_core::text::TextComponent;
+use pumpkin_protocol::{client::play::SoundEvent, SoundCategory};
+use pumpkin_registry::get_sound_id;
+
+use crate::{
+    command::{
+        args::{
+            arg_bounded_num::BoundedNumArgumentConsumer, arg_players::PlayersArgumentConsumer,
+            arg_position_3d::Position3DArgumentConsumer, arg_sound::SoundArgumentConsumer,
+            ConsumedArgs, DefaultNameArgConsumer, FindArg, FindArgDefaultName,
+        },
+        tree::CommandTree,
+        tree_builder::{argument, argument_default_name, literal, require},
+        CommandError, CommandExecutor, CommandSender,
+    },
+    entity::{player::PermissionLvl, sound::sound_range},
+    server::Server,
+};
+
+const NAMES: [&str; 1] = ["playsound"];
+
+const DESCRIPTION: &str = "Plays a sound to players.";
+
+const ARG_TARGETS: &str = "targets";
+
+static VOLUME_CONSUMER: BoundedNumArgumentConsumer<f32> =
+    BoundedNumArgumentConsumer::new().min(0.0).name("volume");
+
+static PITCH_CONSUMER: BoundedNumArgumentConsumer<f32> = BoundedNumArgumentConsumer::new()
+    .min(0.0)
+    .max(2.0)
+    .name("pitch");
+
+static MIN_VOLUME_CONSUMER: BoundedNumArgumentConsumer<f32> = BoundedNumArgumentConsumer::new()
+    .min(0.0)
+    .max(1.0)
+    .name("minVolume");
+
+/// One per source, as the source is a literal
+static EXECUTORS: [PlaySoundExecutor; 10] = [
+    PlaySoundExecutor(SoundCategory::Master),
+    PlaySoundExecutor(SoundCategory::Music),
+    PlaySoundExecutor(SoundCategory::Records),
+    PlaySoundExecutor(SoundCategory::Weather),
+    PlaySoundExecutor(SoundCategory::Blocks),
+    PlaySoundExecutor(SoundCategory::Hostile),
+    PlaySoundExecutor(SoundCategory::Neutral),
+    PlaySoundExecutor(SoundCategory::Players),
+    PlaySoundExecutor(SoundCategory::Ambient),
+    PlaySoundExecutor(SoundCategory::Voice),
+];
+
+/// Returns the number if given, or the default if the argument was left out
+fn find_optional_num(
+    consumer: &BoundedNumArgumentConsumer<f32>,
+    args: &ConsumedArgs,
+    default: f32,
+) -> Result<f32, CommandError> {
+    match consumer.find_arg_default_name(args) {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(())) => Err(CommandError::GeneralCommandIssue(format!(
+            "{} is out of bounds.",
+            consumer.default_name()
+        ))),
+        Err(_) => Ok(default),
+    }
+}
+
+struct PlaySoundExecutor(SoundCategory);
+
+#[async_trait]
+impl CommandExecutor for PlaySoundExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        _server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let name = SoundArgumentConsumer.find_arg_default_name(args)?;
+        let targets = PlayersArgumentConsumer::find_arg(args, ARG_TARGETS)?;
+        let position = match Position3DArgumentConsumer.find_arg_default_name(args) {
+            Ok(position) => position,
+            Err(_) => sender.position().ok_or_else(|| {
+                CommandError::GeneralCommandIssue("A position is required here.".into())
+            })?,
+        };
+        let volume = find_optional_num(&VOLUME_CONSUMER, args, 1.0)?;
+        let pitch = find_optional_num(&PITCH_CONSUMER, args, 1.0)?;
+        let min_volume = find_optional_num(&MIN_VOLUME_CONSUMER, args, 0.0)?;
+
+        // sounds of resource packs are not in the registry, so they are sent by name
+        let sound = get_sound_id(name).map_or(
+            SoundEvent::Custom {
+                name,
+                fixed_range: None,
+            },
+            SoundEvent::Registry,
+        );
+        let range = f64::from(sound_range(&sound, volume));
+
+        let mut heard = 0;
+        for target in targets {
+            let target_position = target.living_entity.entity.pos.load();
+            let offset = position.sub(&target_position);
+            let distance = offset.length();
+            if distance <= range {
+                target
+                    .play_sound(sound, self.0, &position, volume, pitch)
+                    .await;
+            } else if min_volume > 0.0 {
+                // too far away, so it is played quietly right next to the player instead
+                let near = target_position.add(&(offset * (2.0 / distance)));
+                target
+                    .play_sound(sound, self.0, &near, min_volume, pitch)
+                    .await;
+            } else {
+                continue;
+            }
+            heard += 1;
+        }
+
+        if heard == 0 {
+            return Err(CommandError::GeneralCommandIssue(
+                "Target is too far away to hear the sound".into(),
+            ));
+        }
+        let feedback = match targets {
+            [target] => format!("Played sound {name} to {}", target.gameprofile.name),
+            _ => format!("Played sound {name} to {} players", targets.len()),
+        };
+        sender
+            .send_message(TextComponent::text_string(feedback))
+            .await;
+        Ok(())
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    let sources = EXECUTORS.iter().fold(
+        argument_default_name(&SoundArgumentConsumer),
+        |sound, executor| {
+            sound.with_child(
+                literal(executor.0.name()).with_child(
+                    argument(ARG_TARGETS, &PlayersArgumentConsumer)
+                        .execute(executor)
+                        .with_child(
+                            argument_default_name(&Position3DArgumentConsumer)
+                                .execute(executor)
+                                .with_child(
+                                    argument_default_name(&VOLUME_CONSUMER)
+                                        .execute(executor)
+                                        .with_child(
+                                            argument_default_name(&PITCH_CONSUMER)
+                                                .execute(executor)
+                                                .with_child(
+                                                    argument_default_name(&MIN_VOLUME_CONSUMER)
+                                                        .execute(executor),
+                                                ),
+                                        ),
+                                ),
+                        ),
+                ),
+            )
+        },
+    );
+
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.playsound", PermissionLvl::Two))
+            .with_child(sources),
+    )
+}
@@ -0,0 +1,93 @@
+use async_trait::async_trait;
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+
+use crate::command::args::arg_bounded_num::BoundedNumArgumentConsumer;
+use crate::command::args::{ConsumedArgs, FindArgDefaultName};
+use crate::command::tree::CommandTree;
+use crate::command::tree_builder::{argument_default_name, literal, require};
+use crate::command::{CommandError, CommandExecutor, CommandSender};
+use crate::entity::player::PermissionLvl;
+use crate::server::Server;
+use crate::world::pregen::Pregen;
+
+const NAMES: [&str; 1] = ["pregen"];
+
+const DESCRIPTION: &str = "Generates and saves the chunks around the spawn.";
+
+static RADIUS_CONSUMER: BoundedNumArgumentConsumer<i32> = BoundedNumArgumentConsumer::new()
+    .name("radius")
+    .min(0)
+    .max(5000);
+
+struct StartExecutor;
+
+#[async_trait]
+impl CommandExecutor for StartExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let Ok(radius) = RADIUS_CONSUMER.find_arg_default_name(args)? else {
+            return Err(CommandError::GeneralCommandIssue(
+                "The radius must be between 0 and 5000 chunks".into(),
+            ));
+        };
+        let player = sender.as_player();
+        let world = match &player {
+            Some(player) => player.living_entity.entity.world.clone(),
+            None => server
+                .worlds
+                .first()
+                .expect("There should always be atleast one world")
+                .clone(),
+        };
+        let Some(pregen) = Pregen::start(world, radius.unsigned_abs(), player) else {
+            return Err(CommandError::GeneralCommandIssue(
+                "A pregeneration is running already, stop it with /pregen stop".into(),
+            ));
+        };
+        sender
+            .send_message(TextComponent::text_string(format!(
+                "Pregenerating {} chunks",
+                pregen.total()
+            )))
+            .await;
+        Ok(())
+    }
+}
+
+struct StopExecutor;
+
+#[async_trait]
+impl CommandExecutor for StopExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        _server: &Server,
+        _args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let Some(pregen) = Pregen::running() else {
+            return Err(CommandError::GeneralCommandIssue(
+                "No pregeneration is running".into(),
+            ));
+        };
+        pregen.stop();
+        sender
+            .send_message(
+                TextComponent::text("Stopping the pregeneration, it resumes after a restart")
+                    .color_named(NamedColor::Yellow),
+            )
+            .await;
+        Ok(())
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.pregen", PermissionLvl::Four))
+            .with_child(literal("stop").execute(&StopExecutor))
+            .with_child(argument_default_name(&RADIUS_CONSUMER).execute(&StartExecutor)),
+    )
+}
@@ -0,0 +1,97 @@
+use async_trait::async_trait;
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+
+use crate::command::args::ConsumedArgs;
+use crate::command::tree::CommandTree;
+use crate::command::tree_builder::{literal, require};
+use crate::command::{CommandError, CommandExecutor, CommandSender};
+use crate::entity::player::PermissionLvl;
+use crate::server::profiler::PROFILER;
+use crate::server::Server;
+
+const NAMES: [&str; 1] = ["profiler"];
+
+const DESCRIPTION: &str = "Measures where the server spends its time.";
+
+struct StartExecutor;
+
+#[async_trait]
+impl CommandExecutor for StartExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        _server: &Server,
+        _args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        if !PROFILER.start() {
+            return Err(CommandError::GeneralCommandIssue(
+                "The profiler is already running".into(),
+            ));
+        }
+        sender
+            .send_message(TextComponent::text(
+                "Started the profiler, stop it with /profiler stop",
+            ))
+            .await;
+        Ok(())
+    }
+}
+
+struct StopExecutor;
+
+#[async_trait]
+impl CommandExecutor for StopExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        _server: &Server,
+        _args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let Some(report) = PROFILER.stop() else {
+            return Err(CommandError::GeneralCommandIssue(
+                "The profiler is not running".into(),
+            ));
+        };
+
+        sender
+            .send_message(TextComponent::text(&format!(
+                "Profiled {} ticks over {:.1}s: {:.2} mspt on average, {:.2} ms at most",
+                report.ticks,
+                report.duration.as_secs_f64(),
+                report.mspt(),
+                report.longest_tick.as_secs_f64() * 1000.0
+            )))
+            .await;
+        for (section, mspt) in report.tick_sections() {
+            sender
+                .send_message(
+                    TextComponent::text(&format!("  {section}: {mspt:.2} mspt"))
+                        .color_named(NamedColor::Gray),
+                )
+                .await;
+        }
+
+        match report.write() {
+            Ok(path) => {
+                sender
+                    .send_message(TextComponent::text(&format!(
+                        "Saved the folded stacks to {}",
+                        path.display()
+                    )))
+                    .await;
+                Ok(())
+            }
+            Err(err) => Err(CommandError::GeneralCommandIssue(format!(
+                "Failed to save the profile: {err}"
+            ))),
+        }
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.profiler", PermissionLvl::Three))
+            .with_child(literal("start").execute(&StartExecutor))
+            .with_child(literal("stop").execute(&StopExecutor)),
+    )
+}
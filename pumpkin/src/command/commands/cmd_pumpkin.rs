@@ -4,9 +4,13 @@ use pumpkin_protocol::CURRENT_MC_PROTOCOL;
 
 use crate::{
     command::{
-        args::ConsumedArgs, tree::CommandTree, CommandError, CommandExecutor, CommandSender,
+        args::ConsumedArgs,
+        tree::CommandTree,
+        tree_builder::{literal, require},
+        CommandError, CommandExecutor, CommandSender,
     },
-    server::CURRENT_MC_VERSION,
+    entity::player::PermissionLvl,
+    server::{Server, CURRENT_MC_VERSION},
     GIT_VERSION,
 };
 
@@ -37,6 +41,38 @@ impl CommandExecutor for PumpkinExecutor {
     }
 }
 
+struct ReloadExecutor;
+
+#[async_trait]
+impl CommandExecutor for ReloadExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        _args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let changed = server
+            .reload_config()
+            .await
+            .map_err(|err| CommandError::GeneralCommandIssue(format!("Couldn't reload: {err}")))?;
+        let msg = if changed.is_empty() {
+            "Reloaded the configuration and permissions, nothing changed".to_string()
+        } else {
+            format!(
+                "Reloaded the configuration and permissions, changed {}",
+                changed.join(", ")
+            )
+        };
+        sender.send_message(TextComponent::text(&msg)).await;
+        Ok(())
+    }
+}
+
 pub fn init_command_tree<'a>() -> CommandTree<'a> {
-    CommandTree::new(NAMES, DESCRIPTION).execute(&PumpkinExecutor)
+    CommandTree::new(NAMES, DESCRIPTION)
+        .execute(&PumpkinExecutor)
+        .with_child(
+            require(&|sender| sender.has_permission("pumpkin.command.reload", PermissionLvl::Four))
+                .with_child(literal("reload").execute(&ReloadExecutor)),
+        )
 }
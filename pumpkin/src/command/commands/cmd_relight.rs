@@ -0,0 +1,73 @@
+use async_trait::async_trait;
+use pumpkin_core::{math::vector2::Vector2, text::TextComponent};
+
+use crate::command::args::arg_bounded_num::BoundedNumArgumentConsumer;
+use crate::command::args::{ConsumedArgs, FindArgDefaultName};
+use crate::command::tree::CommandTree;
+use crate::command::tree_builder::{argument_default_name, require};
+use crate::command::{CommandError, CommandExecutor, CommandSender};
+use crate::entity::player::PermissionLvl;
+use crate::server::Server;
+
+const NAMES: [&str; 1] = ["relight"];
+
+const DESCRIPTION: &str = "Lights the chunks around you again.";
+
+static RADIUS_CONSUMER: BoundedNumArgumentConsumer<i32> = BoundedNumArgumentConsumer::new()
+    .name("radius")
+    .min(0)
+    .max(32);
+
+struct RelightExecutor;
+
+#[async_trait]
+impl CommandExecutor for RelightExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let Ok(radius) = RADIUS_CONSUMER.find_arg_default_name(args)? else {
+            return Err(CommandError::GeneralCommandIssue(
+                "The radius must be between 0 and 32 chunks".into(),
+            ));
+        };
+        let player = sender.as_player();
+        let (world, center) = match &player {
+            Some(player) => {
+                let entity = &player.living_entity.entity;
+                (entity.world.clone(), entity.chunk_pos.load())
+            }
+            None => {
+                let world = server
+                    .worlds
+                    .first()
+                    .expect("There should always be atleast one world")
+                    .clone();
+                let center = world
+                    .level
+                    .level_data()
+                    .spawn()
+                    .map_or(Vector2::new(0, 0), |(spawn, _)| {
+                        Vector2::new(spawn.x >> 4, spawn.z >> 4)
+                    });
+                (world, center)
+            }
+        };
+        let total = world.relight(center, radius.unsigned_abs(), player);
+        sender
+            .send_message(TextComponent::text_string(format!(
+                "Relighting {total} chunks"
+            )))
+            .await;
+        Ok(())
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.relight", PermissionLvl::Four))
+            .with_child(argument_default_name(&RADIUS_CONSUMER).execute(&RelightExecutor)),
+    )
+}
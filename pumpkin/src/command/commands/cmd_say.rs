@@ -33,11 +33,11 @@ impl CommandExecutor for SayExecutor {
             return Err(InvalidConsumption(Some(ARG_MESSAGE.into())));
         };
 
+        // the message may be formatted with MiniMessage tags, e.g. `<gold>Restarting soon`
+        let prefix = format!("[{sender}] ");
+        let message = TextComponent::text(&prefix).add_child(TextComponent::from_mini_message(msg));
         server
-            .broadcast_packet_all(&CSystemChatMessage::new(
-                &TextComponent::text(&format!("[{sender}] {msg}")),
-                false,
-            ))
+            .broadcast_packet_all(&CSystemChatMessage::new(&message, false))
             .await;
         Ok(())
     }
@@ -45,7 +45,7 @@ impl CommandExecutor for SayExecutor {
 
 pub fn init_command_tree<'a>() -> CommandTree<'a> {
     CommandTree::new(NAMES, DESCRIPTION).with_child(
-        require(&|sender| sender.has_permission_lvl(PermissionLvl::Two))
+        require(&|sender| sender.has_permission("pumpkin.command.say", PermissionLvl::Two))
             .with_child(argument(ARG_MESSAGE, &MsgArgConsumer).execute(&SayExecutor)),
     )
 }
@@ -0,0 +1,75 @@
+use async_trait::async_trait;
+use pumpkin_core::text::color::NamedColor;
+use pumpkin_core::text::TextComponent;
+
+use crate::command::args::ConsumedArgs;
+use crate::command::tree::CommandTree;
+use crate::command::tree_builder::{literal, require};
+use crate::command::{CommandError, CommandExecutor, CommandSender};
+use crate::entity::player::PermissionLvl;
+use crate::server::Server;
+
+const NAMES: [&str; 1] = ["script"];
+const DESCRIPTION: &str = "Reloads or lists the server's scripts.";
+
+struct ReloadExecutor;
+
+#[async_trait]
+impl CommandExecutor for ReloadExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        _args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        // scripts run while they are loaded, which may block on the world
+        let (loaded, failed) = tokio::task::block_in_place(|| server.scripts.reload());
+        sender
+            .send_message(TextComponent::text_string(format!(
+                "Reloaded {loaded} scripts"
+            )))
+            .await;
+        if failed > 0 {
+            sender
+                .send_message(
+                    TextComponent::text_string(format!(
+                        "{failed} scripts failed to load, see the console"
+                    ))
+                    .color_named(NamedColor::Red),
+                )
+                .await;
+        }
+        Ok(())
+    }
+}
+
+struct ListExecutor;
+
+#[async_trait]
+impl CommandExecutor for ListExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        _args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let names = server.scripts.names();
+        let message = if names.is_empty() {
+            "No scripts are loaded".to_string()
+        } else {
+            format!("Scripts ({}): {}", names.len(), names.join(", "))
+        };
+        sender
+            .send_message(TextComponent::text_string(message))
+            .await;
+        Ok(())
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.script", PermissionLvl::Three))
+            .with_child(literal("reload").execute(&ReloadExecutor))
+            .with_child(literal("list").execute(&ListExecutor)),
+    )
+}
@@ -54,7 +54,7 @@ impl CommandExecutor for PumpkinExecutor {
 pub fn init_command_tree<'a>() -> CommandTree<'a> {
     CommandTree::new(NAMES, DESCRIPTION)
         .with_child(require(&|sender| {
-            sender.has_permission_lvl(PermissionLvl::Two)
+            sender.has_permission("pumpkin.command.seed", PermissionLvl::Two)
         }))
         .execute(&PumpkinExecutor)
 }
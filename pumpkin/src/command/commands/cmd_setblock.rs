@@ -36,11 +36,11 @@ impl CommandExecutor for SetblockExecutor {
     async fn execute<'a>(
         &self,
         sender: &mut CommandSender<'a>,
-        _server: &crate::server::Server,
+        server: &crate::server::Server,
         args: &ConsumedArgs<'a>,
     ) -> Result<(), CommandError> {
         let block = BlockArgumentConsumer::find_arg(args, ARG_BLOCK)?;
-        let block_state_id = block.default_state_id;
+        let block_state_id = block.state_id;
         let pos = BlockPosArgumentConsumer::find_arg(args, ARG_BLOCK_POS)?;
         let mode = self.0;
         // TODO: allow console to use the command (seed sender.world)
@@ -66,13 +66,23 @@ impl CommandExecutor for SetblockExecutor {
             },
         };
 
+        if let (true, Some(nbt)) = (success, &block.nbt) {
+            world.set_block_entity_nbt(pos, nbt).await;
+        }
+
         sender
-            .send_message(if success {
-                TextComponent::text_string(format!("Placed block {} at {pos}", block.name,))
-            } else {
-                TextComponent::text_string(format!("Kept block at {pos}"))
-                    .color_named(NamedColor::Red)
-            })
+            .send_feedback(
+                server,
+                if success {
+                    TextComponent::text_string(format!(
+                        "Placed block {} at {pos}",
+                        block.block.name
+                    ))
+                } else {
+                    TextComponent::text_string(format!("Kept block at {pos}"))
+                        .color_named(NamedColor::Red)
+                },
+            )
             .await;
 
         Ok(())
@@ -82,7 +92,8 @@ impl CommandExecutor for SetblockExecutor {
 pub fn init_command_tree<'a>() -> CommandTree<'a> {
     CommandTree::new(NAMES, DESCRIPTION).with_child(
         require(&|sender| {
-            sender.has_permission_lvl(PermissionLvl::Two) && sender.world().is_some()
+            sender.has_permission("pumpkin.command.setblock", PermissionLvl::Two)
+                && sender.world().is_some()
         })
         .with_child(
             argument(ARG_BLOCK_POS, &BlockPosArgumentConsumer).with_child(
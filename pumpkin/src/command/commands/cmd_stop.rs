@@ -26,19 +26,14 @@ impl CommandExecutor for StopExecutor {
             .send_message(TextComponent::text("Stopping Server").color_named(NamedColor::Red))
             .await;
 
-        // TODO: Gracefully stop
-
-        let kick_message = TextComponent::text("Server stopped");
-        for player in server.get_all_players().await {
-            player.kick(kick_message.clone()).await;
-        }
-
-        std::process::exit(0)
+        server.shutdown.request();
+        Ok(())
     }
 }
 
 pub fn init_command_tree<'a>() -> CommandTree<'a> {
     CommandTree::new(NAMES, DESCRIPTION).with_child(
-        require(&|sender| sender.has_permission_lvl(PermissionLvl::Four)).execute(&StopExecutor),
+        require(&|sender| sender.has_permission("pumpkin.command.stop", PermissionLvl::Four))
+            .execute(&StopExecutor),
     )
 }
@@ -0,0 +1,387 @@
+use async_trait::async_trait;
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+use pumpkin_protocol::client::play::{CUpdateTeams, TeamMethod};
+
+use crate::command::args::arg_bool::BoolArgConsumer;
+use crate::command::args::arg_game_profile::GameProfilesArgumentConsumer;
+use crate::command::args::arg_message::MsgArgConsumer;
+use crate::command::args::arg_simple::SimpleArgConsumer;
+use crate::command::args::{ConsumedArgs, FindArg};
+use crate::command::tree::CommandTree;
+use crate::command::tree_builder::{argument, literal, require};
+use crate::command::{CommandError, CommandExecutor, CommandSender};
+use crate::entity::player::PermissionLvl;
+use crate::server::Server;
+use crate::world::scoreboard::Team;
+
+const NAMES: [&str; 1] = ["team"];
+
+const DESCRIPTION: &str = "Manages the teams of the scoreboard.";
+
+const ARG_TEAM: &str = "team";
+const ARG_DISPLAY_NAME: &str = "displayName";
+const ARG_MEMBERS: &str = "members";
+const ARG_VALUE: &str = "value";
+
+/// The names of the members in [`ARG_MEMBERS`], or the sender's name without the argument
+fn member_names(
+    sender: &CommandSender<'_>,
+    args: &ConsumedArgs<'_>,
+) -> Result<Vec<String>, CommandError> {
+    if let Ok(names) = GameProfilesArgumentConsumer::find_names(args, ARG_MEMBERS) {
+        return Ok(names);
+    }
+    sender.as_player().map_or_else(
+        || Err(CommandError::InvalidRequirement),
+        |player| Ok(vec![player.gameprofile.name.clone()]),
+    )
+}
+
+struct AddExecutor;
+
+#[async_trait]
+impl CommandExecutor for AddExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let name = SimpleArgConsumer::find_arg(args, ARG_TEAM)?;
+        let display_name = MsgArgConsumer::find_arg(args, ARG_DISPLAY_NAME).unwrap_or(name);
+
+        let world = &server.worlds[0];
+        {
+            let mut scoreboard = world.scoreboard.lock().await;
+            if !scoreboard.add_team(name.to_string(), display_name.to_string()) {
+                return Err(CommandError::GeneralCommandIssue(
+                    "A team already exists by that name".to_string(),
+                ));
+            }
+            if let Some(team) = scoreboard.team(name) {
+                world
+                    .broadcast_packet_all(&CUpdateTeams::new(
+                        name,
+                        TeamMethod::Create(team.info(), &[]),
+                    ))
+                    .await;
+            }
+        }
+        sender
+            .send_feedback(
+                server,
+                TextComponent::text_string(format!("Created team [{display_name}]")),
+            )
+            .await;
+        Ok(())
+    }
+}
+
+struct RemoveExecutor;
+
+#[async_trait]
+impl CommandExecutor for RemoveExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let name = SimpleArgConsumer::find_arg(args, ARG_TEAM)?;
+
+        let world = &server.worlds[0];
+        let Some(team) = world.scoreboard.lock().await.remove_team(name) else {
+            return Err(CommandError::GeneralCommandIssue(format!(
+                "Unknown team '{name}'"
+            )));
+        };
+        world
+            .broadcast_packet_all(&CUpdateTeams::new(name, TeamMethod::Remove))
+            .await;
+        sender
+            .send_feedback(
+                server,
+                TextComponent::text_string(format!("Removed team [{}]", team.display_name)),
+            )
+            .await;
+        Ok(())
+    }
+}
+
+struct JoinExecutor;
+
+#[async_trait]
+impl CommandExecutor for JoinExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let name = SimpleArgConsumer::find_arg(args, ARG_TEAM)?;
+        let members = member_names(sender, args)?;
+
+        let world = &server.worlds[0];
+        let display_name = {
+            let mut scoreboard = world.scoreboard.lock().await;
+            for member in &members {
+                if !scoreboard.join_team(name, member) {
+                    return Err(CommandError::GeneralCommandIssue(format!(
+                        "Unknown team '{name}'"
+                    )));
+                }
+            }
+            scoreboard
+                .team(name)
+                .map(|team| team.display_name.clone())
+                .unwrap_or_default()
+        };
+        // clients take the members off the team they were on before themselves
+        let names: Vec<_> = members.iter().map(String::as_str).collect();
+        world
+            .broadcast_packet_all(&CUpdateTeams::new(name, TeamMethod::AddMembers(&names)))
+            .await;
+        let message = if let [member] = members.as_slice() {
+            format!("Added {member} to team [{display_name}]")
+        } else {
+            format!("Added {} entities to team [{display_name}]", members.len())
+        };
+        sender
+            .send_feedback(server, TextComponent::text_string(message))
+            .await;
+        Ok(())
+    }
+}
+
+struct LeaveExecutor;
+
+#[async_trait]
+impl CommandExecutor for LeaveExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let members = member_names(sender, args)?;
+
+        let world = &server.worlds[0];
+        let left: Vec<_> = {
+            let mut scoreboard = world.scoreboard.lock().await;
+            members
+                .iter()
+                .filter_map(|member| {
+                    let team = scoreboard.team_of(member)?.to_string();
+                    scoreboard.leave_team(member);
+                    Some((team, member.as_str()))
+                })
+                .collect()
+        };
+        for (team, member) in &left {
+            world
+                .broadcast_packet_all(&CUpdateTeams::new(
+                    team,
+                    TeamMethod::RemoveMembers(&[*member]),
+                ))
+                .await;
+        }
+        let left = left.len();
+        if left == 0 {
+            sender
+                .send_message(TextComponent::text(
+                    "Nothing changed. None of them are on a team",
+                ))
+                .await;
+            return Ok(());
+        }
+        sender
+            .send_feedback(
+                server,
+                TextComponent::text_string(format!("Removed {left} members from any team")),
+            )
+            .await;
+        Ok(())
+    }
+}
+
+/// Changes an option of the team and tells the clients about it, returns the team's display name
+async fn modify_team(
+    server: &Server,
+    name: &str,
+    modify: impl FnOnce(&mut Team),
+) -> Result<String, CommandError> {
+    let world = &server.worlds[0];
+    let mut scoreboard = world.scoreboard.lock().await;
+    let Some(team) = scoreboard.team_mut(name) else {
+        return Err(CommandError::GeneralCommandIssue(format!(
+            "Unknown team '{name}'"
+        )));
+    };
+    modify(team);
+    world
+        .broadcast_packet_all(&CUpdateTeams::new(name, TeamMethod::Update(team.info())))
+        .await;
+    Ok(team.display_name.clone())
+}
+
+struct ColorExecutor;
+
+#[async_trait]
+impl CommandExecutor for ColorExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let name = SimpleArgConsumer::find_arg(args, ARG_TEAM)?;
+        let value = SimpleArgConsumer::find_arg(args, ARG_VALUE)?;
+        let color = if value == "reset" {
+            None
+        } else {
+            Some(NamedColor::try_from(value).map_err(|()| {
+                CommandError::GeneralCommandIssue(format!("Unknown color '{value}'"))
+            })?)
+        };
+
+        let display_name = modify_team(server, name, |team| team.color = color).await?;
+        sender
+            .send_feedback(
+                server,
+                TextComponent::text_string(format!(
+                    "Updated the color for team [{display_name}] to {value}"
+                )),
+            )
+            .await;
+        Ok(())
+    }
+}
+
+struct SeeFriendlyInvisiblesExecutor;
+
+#[async_trait]
+impl CommandExecutor for SeeFriendlyInvisiblesExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let name = SimpleArgConsumer::find_arg(args, ARG_TEAM)?;
+        let value = BoolArgConsumer::find_arg(args, ARG_VALUE)?;
+
+        let display_name =
+            modify_team(server, name, |team| team.see_friendly_invisibles = value).await?;
+        let message = if value {
+            format!("Team [{display_name}] can now see invisible teammates")
+        } else {
+            format!("Team [{display_name}] can no longer see invisible teammates")
+        };
+        sender
+            .send_feedback(server, TextComponent::text_string(message))
+            .await;
+        Ok(())
+    }
+}
+
+struct ListExecutor;
+
+#[async_trait]
+impl CommandExecutor for ListExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let message = {
+            let scoreboard = server.worlds[0].scoreboard.lock().await;
+            if let Ok(name) = SimpleArgConsumer::find_arg(args, ARG_TEAM) {
+                let Some(team) = scoreboard.team(name) else {
+                    return Err(CommandError::GeneralCommandIssue(format!(
+                        "Unknown team '{name}'"
+                    )));
+                };
+                let mut members: Vec<_> = team.members().collect();
+                members.sort_unstable();
+                if members.is_empty() {
+                    format!("There are no members on team [{}]", team.display_name)
+                } else {
+                    format!(
+                        "Team [{}] has {} members: {}",
+                        team.display_name,
+                        members.len(),
+                        members.join(", ")
+                    )
+                }
+            } else {
+                let mut teams: Vec<_> = scoreboard
+                    .teams()
+                    .values()
+                    .map(|team| format!("[{}]", team.display_name))
+                    .collect();
+                teams.sort_unstable();
+                if teams.is_empty() {
+                    "There are no teams".to_string()
+                } else {
+                    format!("There are {} teams: {}", teams.len(), teams.join(", "))
+                }
+            }
+        };
+        sender
+            .send_message(TextComponent::text_string(message))
+            .await;
+        Ok(())
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.team", PermissionLvl::Two))
+            .with_child(
+                literal("add").with_child(
+                    argument(ARG_TEAM, &SimpleArgConsumer)
+                        .execute(&AddExecutor)
+                        .with_child(
+                            argument(ARG_DISPLAY_NAME, &MsgArgConsumer).execute(&AddExecutor),
+                        ),
+                ),
+            )
+            .with_child(
+                literal("remove")
+                    .with_child(argument(ARG_TEAM, &SimpleArgConsumer).execute(&RemoveExecutor)),
+            )
+            .with_child(
+                literal("join").with_child(
+                    argument(ARG_TEAM, &SimpleArgConsumer)
+                        .execute(&JoinExecutor)
+                        .with_child(
+                            argument(ARG_MEMBERS, &GameProfilesArgumentConsumer)
+                                .execute(&JoinExecutor),
+                        ),
+                ),
+            )
+            .with_child(literal("leave").execute(&LeaveExecutor).with_child(
+                argument(ARG_MEMBERS, &GameProfilesArgumentConsumer).execute(&LeaveExecutor),
+            ))
+            .with_child(
+                literal("modify").with_child(
+                    argument(ARG_TEAM, &SimpleArgConsumer)
+                        .with_child(literal("color").with_child(
+                            argument(ARG_VALUE, &SimpleArgConsumer).execute(&ColorExecutor),
+                        ))
+                        .with_child(
+                            literal("seeFriendlyInvisibles").with_child(
+                                argument(ARG_VALUE, &BoolArgConsumer)
+                                    .execute(&SeeFriendlyInvisiblesExecutor),
+                            ),
+                        ),
+                ),
+            )
+            .with_child(
+                literal("list")
+                    .execute(&ListExecutor)
+                    .with_child(argument(ARG_TEAM, &SimpleArgConsumer).execute(&ListExecutor)),
+            ),
+    )
+}
@@ -0,0 +1,81 @@
+use async_trait::async_trait;
+use pumpkin_core::text::TextComponent;
+use pumpkin_protocol::client::play::{CDisguisedChatMessage, ChatType};
+
+use crate::command::args::arg_message::MsgArgConsumer;
+use crate::command::args::{ConsumedArgs, FindArg};
+use crate::command::tree::CommandTree;
+use crate::command::tree_builder::{argument, require};
+use crate::command::{CommandError, CommandExecutor, CommandSender};
+use crate::server::Server;
+
+const NAMES: [&str; 2] = ["teammsg", "tm"];
+
+const DESCRIPTION: &str = "Sends a message to the players on your team.";
+
+const ARG_MESSAGE: &str = "message";
+
+struct TeamMsgExecutor;
+
+#[async_trait]
+impl CommandExecutor for TeamMsgExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let message = MsgArgConsumer::find_arg(args, ARG_MESSAGE)?;
+        let Some(player) = sender.as_player() else {
+            return Err(CommandError::InvalidRequirement);
+        };
+
+        let (team_name, members): (String, Vec<String>) = {
+            let scoreboard = server.worlds[0].scoreboard.lock().await;
+            let Some(team) = scoreboard
+                .team_of(&player.gameprofile.name)
+                .and_then(|name| scoreboard.team(name))
+            else {
+                return Err(CommandError::GeneralCommandIssue(
+                    "You must be on a team to message your team".to_string(),
+                ));
+            };
+            (
+                team.display_name.clone(),
+                team.members().map(str::to_string).collect(),
+            )
+        };
+
+        let name = TextComponent::text_string(player.display_name());
+        let team_name = TextComponent::text_string(format!("[{team_name}]"));
+        for member in members {
+            let Some(teammate) = server.get_player_by_name(&member).await else {
+                continue;
+            };
+            let chat_type = if teammate.gameprofile.id == player.gameprofile.id {
+                ChatType::TeamMsgCommandOutgoing
+            } else if teammate.ignores(player.gameprofile.id) {
+                continue;
+            } else {
+                ChatType::TeamMsgCommandIncoming
+            };
+            teammate
+                .client
+                .send_packet(&CDisguisedChatMessage::new(
+                    TextComponent::text(message),
+                    (chat_type as i32 + 1).into(),
+                    name.clone(),
+                    Some(team_name.clone()),
+                ))
+                .await;
+        }
+        Ok(())
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.is_player())
+            .with_child(argument(ARG_MESSAGE, &MsgArgConsumer).execute(&TeamMsgExecutor)),
+    )
+}
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use pumpkin_core::math::vector3::Vector3;
 use pumpkin_core::text::TextComponent;
@@ -12,7 +14,9 @@ use crate::command::tree::CommandTree;
 use crate::command::tree_builder::{argument, literal, require};
 use crate::command::CommandError;
 use crate::command::{CommandExecutor, CommandSender};
-use crate::entity::player::PermissionLvl;
+use crate::entity::player::{PermissionLvl, Player};
+use crate::entity::player_set::PlayerSet;
+use crate::entity::vehicle;
 
 const NAMES: [&str; 2] = ["teleport", "tp"];
 const DESCRIPTION: &str = "Teleports entities, including players."; // todo
@@ -35,6 +39,10 @@ const ARG_FACING_ENTITY: &str = "facingEntity";
 /// position
 const ARG_FACING_LOCATION: &str = "facingLocation";
 
+/// Vanilla refuses to teleport entities further away than this
+const MAX_HORIZONTAL_POSITION: f64 = 30_000_000.0;
+const MAX_VERTICAL_POSITION: f64 = 20_000_000.0;
+
 fn yaw_pitch_facing_position(
     looking_from: &Vector3<f64>,
     looking_towards: &Vector3<f64>,
@@ -50,14 +58,120 @@ fn yaw_pitch_facing_position(
     (yaw_degrees as f32, pitch_degrees as f32)
 }
 
+/// The rotation a target teleported to `pos` needs to look at `looking_towards` with its eyes.
+fn facing_from_eyes(
+    target: &Player,
+    pos: Vector3<f64>,
+    looking_towards: &Vector3<f64>,
+) -> (f32, f32) {
+    let eyes = Vector3::new(
+        pos.x,
+        pos.y + f64::from(target.living_entity.entity.standing_eye_height),
+        pos.z,
+    );
+    yaw_pitch_facing_position(&eyes, looking_towards)
+}
+
+/// Which part of an entity is looked at
+#[derive(Clone, Copy)]
+enum EntityAnchor {
+    Feet,
+    Eyes,
+}
+
+impl EntityAnchor {
+    fn position_of(self, player: &Player) -> Vector3<f64> {
+        let entity = &player.living_entity.entity;
+        let pos = entity.pos.load();
+        match self {
+            Self::Feet => pos,
+            Self::Eyes => Vector3::new(pos.x, pos.y + f64::from(entity.standing_eye_height), pos.z),
+        }
+    }
+}
+
+fn validate_position(pos: &Vector3<f64>) -> Result<(), CommandError> {
+    if pos.x.abs() > MAX_HORIZONTAL_POSITION
+        || pos.z.abs() > MAX_HORIZONTAL_POSITION
+        || pos.y.abs() > MAX_VERTICAL_POSITION
+    {
+        return Err(CommandError::GeneralCommandIssue(
+            "Invalid position for teleport".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Fails if the destination entity is in another world than the target.
+fn validate_same_world(target: &Player, destination: &Player) -> Result<(), CommandError> {
+    // TODO: support teleporting into other worlds once entities can change the world they are in
+    if Arc::ptr_eq(
+        &target.living_entity.entity.world,
+        &destination.living_entity.entity.world,
+    ) {
+        Ok(())
+    } else {
+        Err(CommandError::GeneralCommandIssue(format!(
+            "Can not teleport {} into another dimension",
+            target.gameprofile.name
+        )))
+    }
+}
+
+/// Takes the target off its vehicle first, its passengers come along
+async fn teleport(target: &Player, pos: Vector3<f64>, yaw: f32, pitch: f32) {
+    let entity = &target.living_entity.entity;
+    vehicle::dismount(entity).await;
+    target.teleport(pos, yaw, pitch).await;
+    vehicle::position_passengers(entity).await;
+}
+
+async fn send_location_feedback(
+    sender: &mut CommandSender<'_>,
+    server: &crate::server::Server,
+    targets: &[Arc<Player>],
+    pos: Vector3<f64>,
+) {
+    let location = format!("{:.6}, {:.6}, {:.6}", pos.x, pos.y, pos.z);
+    let msg = match targets {
+        [target] => format!("Teleported {} to {location}", target.gameprofile.name),
+        targets => format!("Teleported {} entities to {location}", targets.len()),
+    };
+    sender
+        .send_feedback(server, TextComponent::text_string(msg))
+        .await;
+}
+
+async fn send_entity_feedback(
+    sender: &mut CommandSender<'_>,
+    server: &crate::server::Server,
+    targets: &[Arc<Player>],
+    destination: &Player,
+) {
+    let msg = match targets {
+        [target] => format!(
+            "Teleported {} to {}",
+            target.gameprofile.name, destination.gameprofile.name
+        ),
+        targets => format!(
+            "Teleported {} entities to {}",
+            targets.len(),
+            destination.gameprofile.name
+        ),
+    };
+    sender
+        .send_feedback(server, TextComponent::text_string(msg))
+        .await;
+}
+
 struct TpEntitiesToEntityExecutor;
 
 #[async_trait]
 impl CommandExecutor for TpEntitiesToEntityExecutor {
     async fn execute<'a>(
         &self,
-        _sender: &mut CommandSender<'a>,
-        _server: &crate::server::Server,
+        sender: &mut CommandSender<'a>,
+        server: &crate::server::Server,
         args: &ConsumedArgs<'a>,
     ) -> Result<(), CommandError> {
         let targets = EntitiesArgumentConsumer::find_arg(args, ARG_TARGETS)?;
@@ -65,12 +179,16 @@ impl CommandExecutor for TpEntitiesToEntityExecutor {
         let destination = EntityArgumentConsumer::find_arg(args, ARG_DESTINATION)?;
         let pos = destination.living_entity.entity.pos.load();
 
+        for target in targets {
+            validate_same_world(target, &destination)?;
+        }
         for target in targets {
             let yaw = target.living_entity.entity.yaw.load();
             let pitch = target.living_entity.entity.pitch.load();
-            target.teleport(pos, yaw, pitch).await;
+            teleport(target, pos, yaw, pitch).await;
         }
 
+        send_entity_feedback(sender, server, targets, &destination).await;
         Ok(())
     }
 }
@@ -81,48 +199,51 @@ struct TpEntitiesToPosFacingPosExecutor;
 impl CommandExecutor for TpEntitiesToPosFacingPosExecutor {
     async fn execute<'a>(
         &self,
-        _sender: &mut CommandSender<'a>,
-        _server: &crate::server::Server,
+        sender: &mut CommandSender<'a>,
+        server: &crate::server::Server,
         args: &ConsumedArgs<'a>,
     ) -> Result<(), CommandError> {
         let targets = EntitiesArgumentConsumer::find_arg(args, ARG_TARGETS)?;
 
         let pos = Position3DArgumentConsumer::find_arg(args, ARG_LOCATION)?;
+        validate_position(&pos)?;
 
         let facing_pos = Position3DArgumentConsumer::find_arg(args, ARG_FACING_LOCATION)?;
-        let (yaw, pitch) = yaw_pitch_facing_position(&pos, &facing_pos);
 
         for target in targets {
-            target.teleport(pos, yaw, pitch).await;
+            let (yaw, pitch) = facing_from_eyes(target, pos, &facing_pos);
+            teleport(target, pos, yaw, pitch).await;
         }
 
+        send_location_feedback(sender, server, targets, pos).await;
         Ok(())
     }
 }
 
-struct TpEntitiesToPosFacingEntityExecutor;
+struct TpEntitiesToPosFacingEntityExecutor(EntityAnchor);
 
 #[async_trait]
 impl CommandExecutor for TpEntitiesToPosFacingEntityExecutor {
     async fn execute<'a>(
         &self,
-        _sender: &mut CommandSender<'a>,
-        _server: &crate::server::Server,
+        sender: &mut CommandSender<'a>,
+        server: &crate::server::Server,
         args: &ConsumedArgs<'a>,
     ) -> Result<(), CommandError> {
         let targets = EntitiesArgumentConsumer::find_arg(args, ARG_TARGETS)?;
 
         let pos = Position3DArgumentConsumer::find_arg(args, ARG_LOCATION)?;
+        validate_position(&pos)?;
 
-        let facing_entity = &EntityArgumentConsumer::find_arg(args, ARG_FACING_ENTITY)?
-            .living_entity
-            .entity;
-        let (yaw, pitch) = yaw_pitch_facing_position(&pos, &facing_entity.pos.load());
+        let facing_entity = EntityArgumentConsumer::find_arg(args, ARG_FACING_ENTITY)?;
+        let facing_pos = self.0.position_of(&facing_entity);
 
         for target in targets {
-            target.teleport(pos, yaw, pitch).await;
+            let (yaw, pitch) = facing_from_eyes(target, pos, &facing_pos);
+            teleport(target, pos, yaw, pitch).await;
         }
 
+        send_location_feedback(sender, server, targets, pos).await;
         Ok(())
     }
 }
@@ -133,20 +254,23 @@ struct TpEntitiesToPosWithRotationExecutor;
 impl CommandExecutor for TpEntitiesToPosWithRotationExecutor {
     async fn execute<'a>(
         &self,
-        _sender: &mut CommandSender<'a>,
-        _server: &crate::server::Server,
+        sender: &mut CommandSender<'a>,
+        server: &crate::server::Server,
         args: &ConsumedArgs<'a>,
     ) -> Result<(), CommandError> {
         let targets = EntitiesArgumentConsumer::find_arg(args, ARG_TARGETS)?;
 
         let pos = Position3DArgumentConsumer::find_arg(args, ARG_LOCATION)?;
+        validate_position(&pos)?;
 
         let (yaw, pitch) = RotationArgumentConsumer::find_arg(args, ARG_ROTATION)?;
 
-        for target in targets {
-            target.teleport(pos, yaw, pitch).await;
-        }
+        // everyone ends up with the same rotation
+        PlayerSet::new(targets.to_vec())
+            .teleport(pos, yaw, pitch)
+            .await;
 
+        send_location_feedback(sender, server, targets, pos).await;
         Ok(())
     }
 }
@@ -157,20 +281,22 @@ struct TpEntitiesToPosExecutor;
 impl CommandExecutor for TpEntitiesToPosExecutor {
     async fn execute<'a>(
         &self,
-        _sender: &mut CommandSender<'a>,
-        _server: &crate::server::Server,
+        sender: &mut CommandSender<'a>,
+        server: &crate::server::Server,
         args: &ConsumedArgs<'a>,
     ) -> Result<(), CommandError> {
         let targets = EntitiesArgumentConsumer::find_arg(args, ARG_TARGETS)?;
 
         let pos = Position3DArgumentConsumer::find_arg(args, ARG_LOCATION)?;
+        validate_position(&pos)?;
 
         for target in targets {
             let yaw = target.living_entity.entity.yaw.load();
             let pitch = target.living_entity.entity.pitch.load();
-            target.teleport(pos, yaw, pitch).await;
+            teleport(target, pos, yaw, pitch).await;
         }
 
+        send_location_feedback(sender, server, targets, pos).await;
         Ok(())
     }
 }
@@ -182,7 +308,7 @@ impl CommandExecutor for TpSelfToEntityExecutor {
     async fn execute<'a>(
         &self,
         sender: &mut CommandSender<'a>,
-        _server: &crate::server::Server,
+        server: &crate::server::Server,
         args: &ConsumedArgs<'a>,
     ) -> Result<(), CommandError> {
         let destination = EntityArgumentConsumer::find_arg(args, ARG_DESTINATION)?;
@@ -190,9 +316,12 @@ impl CommandExecutor for TpSelfToEntityExecutor {
 
         match sender {
             CommandSender::Player(player) => {
+                let player = player.clone();
+                validate_same_world(&player, &destination)?;
                 let yaw = player.living_entity.entity.yaw.load();
                 let pitch = player.living_entity.entity.pitch.load();
-                player.teleport(pos, yaw, pitch).await;
+                teleport(&player, pos, yaw, pitch).await;
+                send_entity_feedback(sender, server, &[player], &destination).await;
             }
             _ => {
                 sender
@@ -214,15 +343,18 @@ impl CommandExecutor for TpSelfToPosExecutor {
     async fn execute<'a>(
         &self,
         sender: &mut CommandSender<'a>,
-        _server: &crate::server::Server,
+        server: &crate::server::Server,
         args: &ConsumedArgs<'a>,
     ) -> Result<(), CommandError> {
         match sender {
             CommandSender::Player(player) => {
+                let player = player.clone();
                 let pos = Position3DArgumentConsumer::find_arg(args, ARG_LOCATION)?;
+                validate_position(&pos)?;
                 let yaw = player.living_entity.entity.yaw.load();
                 let pitch = player.living_entity.entity.pitch.load();
-                player.teleport(pos, yaw, pitch).await;
+                teleport(&player, pos, yaw, pitch).await;
+                send_location_feedback(sender, server, &[player], pos).await;
             }
             _ => {
                 sender
@@ -239,7 +371,7 @@ impl CommandExecutor for TpSelfToPosExecutor {
 
 pub fn init_command_tree<'a>() -> CommandTree<'a> {
     CommandTree::new(NAMES, DESCRIPTION).with_child(
-        require(&|sender| sender.has_permission_lvl(PermissionLvl::Two))
+        require(&|sender| sender.has_permission("pumpkin.command.teleport", PermissionLvl::Two))
             .with_child(
                 argument(ARG_LOCATION, &Position3DArgumentConsumer).execute(&TpSelfToPosExecutor),
             )
@@ -260,7 +392,19 @@ pub fn init_command_tree<'a>() -> CommandTree<'a> {
                                     .with_child(
                                         literal("entity").with_child(
                                             argument(ARG_FACING_ENTITY, &EntityArgumentConsumer)
-                                                .execute(&TpEntitiesToPosFacingEntityExecutor),
+                                                .execute(&TpEntitiesToPosFacingEntityExecutor(
+                                                    EntityAnchor::Feet,
+                                                ))
+                                                .with_child(literal("feet").execute(
+                                                    &TpEntitiesToPosFacingEntityExecutor(
+                                                        EntityAnchor::Feet,
+                                                    ),
+                                                ))
+                                                .with_child(literal("eyes").execute(
+                                                    &TpEntitiesToPosFacingEntityExecutor(
+                                                        EntityAnchor::Eyes,
+                                                    ),
+                                                )),
                                         ),
                                     )
                                     .with_child(
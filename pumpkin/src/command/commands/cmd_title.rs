@@ -0,0 +1,195 @@
+use async_trait::async_trait;
+use pumpkin_core::text::TextComponent;
+
+use crate::{
+    command::{
+        args::{
+            arg_bounded_num::BoundedNumArgumentConsumer, arg_component::TextComponentArgConsumer,
+            arg_players::PlayersArgumentConsumer, ConsumedArgs, DefaultNameArgConsumer, FindArg,
+            FindArgDefaultName,
+        },
+        tree::CommandTree,
+        tree_builder::{argument, argument_default_name, literal, require},
+        CommandError, CommandExecutor, CommandSender,
+    },
+    entity::{player::PermissionLvl, player_set::PlayerSet, title::TitleTimes},
+    server::Server,
+};
+
+const NAMES: [&str; 1] = ["title"];
+
+const DESCRIPTION: &str = "Shows titles or action bar messages to players.";
+
+const ARG_TARGETS: &str = "targets";
+const ARG_TEXT: &str = "title";
+
+static FADE_IN_CONSUMER: BoundedNumArgumentConsumer<i32> =
+    BoundedNumArgumentConsumer::new().min(0).name("fadeIn");
+
+static STAY_CONSUMER: BoundedNumArgumentConsumer<i32> =
+    BoundedNumArgumentConsumer::new().min(0).name("stay");
+
+static FADE_OUT_CONSUMER: BoundedNumArgumentConsumer<i32> =
+    BoundedNumArgumentConsumer::new().min(0).name("fadeOut");
+
+/// `for Steve` or `for 3 players`, as in the feedback of vanilla
+fn describe_targets(targets: &PlayerSet) -> String {
+    match targets.iter().next() {
+        Some(target) if targets.len() == 1 => target.gameprofile.name.clone(),
+        _ => format!("{} players", targets.len()),
+    }
+}
+
+fn find_targets(args: &ConsumedArgs) -> Result<PlayerSet, CommandError> {
+    Ok(PlayerSet::new(
+        PlayersArgumentConsumer::find_arg(args, ARG_TARGETS)?.to_vec(),
+    ))
+}
+
+struct TitleClearExecutor {
+    reset: bool,
+}
+
+#[async_trait]
+impl CommandExecutor for TitleClearExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        _server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let targets = find_targets(args)?;
+        targets.clear_title(self.reset).await;
+
+        let feedback = if self.reset {
+            format!("Reset title options for {}", describe_targets(&targets))
+        } else {
+            format!("Cleared titles for {}", describe_targets(&targets))
+        };
+        sender
+            .send_message(TextComponent::text_string(feedback))
+            .await;
+        Ok(())
+    }
+}
+
+enum TitleTextExecutor {
+    Title,
+    Subtitle,
+    ActionBar,
+}
+
+#[async_trait]
+impl CommandExecutor for TitleTextExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        _server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let targets = find_targets(args)?;
+        let text = TextComponentArgConsumer::find_arg(args, ARG_TEXT)?.clone();
+
+        let feedback = match self {
+            Self::Title => {
+                targets.send_title(text, None, None).await;
+                format!("Showing new title for {}", describe_targets(&targets))
+            }
+            Self::Subtitle => {
+                targets.send_subtitle(text).await;
+                format!("Showing new subtitle for {}", describe_targets(&targets))
+            }
+            Self::ActionBar => {
+                targets.send_action_bar(text).await;
+                format!(
+                    "Showing new actionbar title for {}",
+                    describe_targets(&targets)
+                )
+            }
+        };
+        sender
+            .send_message(TextComponent::text_string(feedback))
+            .await;
+        Ok(())
+    }
+}
+
+struct TitleTimesExecutor;
+
+#[async_trait]
+impl CommandExecutor for TitleTimesExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        _server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let targets = find_targets(args)?;
+        let mut times = [0; 3];
+        for (time, consumer) in
+            times
+                .iter_mut()
+                .zip([&FADE_IN_CONSUMER, &STAY_CONSUMER, &FADE_OUT_CONSUMER])
+        {
+            let Ok(value) = consumer.find_arg_default_name(args)? else {
+                return Err(CommandError::GeneralCommandIssue(format!(
+                    "{} is out of bounds.",
+                    consumer.default_name()
+                )));
+            };
+            *time = value;
+        }
+        let [fade_in, stay, fade_out] = times;
+
+        targets
+            .set_title_times(TitleTimes::new(fade_in, stay, fade_out))
+            .await;
+        let feedback = format!(
+            "Changed title display times for {}",
+            describe_targets(&targets)
+        );
+        sender
+            .send_message(TextComponent::text_string(feedback))
+            .await;
+        Ok(())
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.title", PermissionLvl::Two))
+            .with_child(
+                argument(ARG_TARGETS, &PlayersArgumentConsumer)
+                    .with_child(literal("clear").execute(&TitleClearExecutor { reset: false }))
+                    .with_child(literal("reset").execute(&TitleClearExecutor { reset: true }))
+                    .with_child(
+                        literal("title").with_child(
+                            argument(ARG_TEXT, &TextComponentArgConsumer)
+                                .execute(&TitleTextExecutor::Title),
+                        ),
+                    )
+                    .with_child(
+                        literal("subtitle").with_child(
+                            argument(ARG_TEXT, &TextComponentArgConsumer)
+                                .execute(&TitleTextExecutor::Subtitle),
+                        ),
+                    )
+                    .with_child(
+                        literal("actionbar").with_child(
+                            argument(ARG_TEXT, &TextComponentArgConsumer)
+                                .execute(&TitleTextExecutor::ActionBar),
+                        ),
+                    )
+                    .with_child(
+                        literal("times").with_child(
+                            argument_default_name(&FADE_IN_CONSUMER).with_child(
+                                argument_default_name(&STAY_CONSUMER).with_child(
+                                    argument_default_name(&FADE_OUT_CONSUMER)
+                                        .execute(&TitleTimesExecutor),
+                                ),
+                            ),
+                        ),
+                    ),
+            ),
+    )
+}
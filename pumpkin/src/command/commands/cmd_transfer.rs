@@ -118,19 +118,20 @@ impl CommandExecutor for TransferTargetPlayer {
 
 pub fn init_command_tree<'a>() -> CommandTree<'a> {
     CommandTree::new(NAMES, DESCRIPTION).with_child(
-        require(&|sender| sender.has_permission_lvl(PermissionLvl::Three)).with_child(
-            argument(ARG_HOSTNAME, &SimpleArgConsumer)
-                .with_child(require(&|sender| sender.is_player()).execute(&TransferTargetSelf))
-                .with_child(
-                    argument_default_name(&PORT_CONSUMER)
-                        .with_child(
-                            require(&|sender| sender.is_player()).execute(&TransferTargetSelf),
-                        )
-                        .with_child(
-                            argument(ARG_PLAYERS, &PlayersArgumentConsumer)
-                                .execute(&TransferTargetPlayer),
-                        ),
-                ),
-        ),
+        require(&|sender| sender.has_permission("pumpkin.command.transfer", PermissionLvl::Three))
+            .with_child(
+                argument(ARG_HOSTNAME, &SimpleArgConsumer)
+                    .with_child(require(&|sender| sender.is_player()).execute(&TransferTargetSelf))
+                    .with_child(
+                        argument_default_name(&PORT_CONSUMER)
+                            .with_child(
+                                require(&|sender| sender.is_player()).execute(&TransferTargetSelf),
+                            )
+                            .with_child(
+                                argument(ARG_PLAYERS, &PlayersArgumentConsumer)
+                                    .execute(&TransferTargetPlayer),
+                            ),
+                    ),
+            ),
     )
 }
@@ -0,0 +1,213 @@
+use async_trait::async_trait;
+use pumpkin_core::text::TextComponent;
+
+use crate::command::args::arg_game_profile::GameProfilesArgumentConsumer;
+use crate::command::args::ConsumedArgs;
+use crate::command::tree::CommandTree;
+use crate::command::tree_builder::{argument, literal, require};
+use crate::command::{CommandError, CommandExecutor, CommandSender};
+use crate::data::op_data::OPERATOR_CONFIG;
+use crate::data::whitelist_data::{
+    is_whitelist_enabled, set_whitelist_enabled, WhitelistConfig, WHITELIST_CONFIG,
+};
+use crate::data::LoadJSONConfiguration;
+use crate::entity::player::PermissionLvl;
+use crate::server::Server;
+use pumpkin_config::BASIC_CONFIG;
+
+const NAMES: [&str; 1] = ["whitelist"];
+const DESCRIPTION: &str = "Manages the server whitelist.";
+
+const ARG_TARGETS: &str = "targets";
+
+/// Kicks the online players which are not whitelisted, if the whitelist is enforced
+async fn kick_unlisted_players(server: &Server) {
+    if !BASIC_CONFIG.enforce_whitelist || !is_whitelist_enabled() {
+        return;
+    }
+    let whitelist = WHITELIST_CONFIG.read().await;
+    let ops = OPERATOR_CONFIG.read().await;
+    for player in server.get_all_players().await {
+        let id = player.gameprofile.id;
+        if !whitelist.is_whitelisted(id) && ops.get(id).is_none() {
+            player
+                .kick(TextComponent::text(
+                    "You are not white-listed on this server!",
+                ))
+                .await;
+        }
+    }
+}
+
+struct WhitelistToggleExecutor(bool);
+
+#[async_trait]
+impl CommandExecutor for WhitelistToggleExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        _args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let state = if self.0 { "on" } else { "off" };
+        if is_whitelist_enabled() == self.0 {
+            sender
+                .send_message(TextComponent::text_string(format!(
+                    "Whitelist is already turned {state}"
+                )))
+                .await;
+            return Ok(());
+        }
+
+        set_whitelist_enabled(self.0);
+        sender
+            .send_feedback(
+                server,
+                TextComponent::text_string(format!("Whitelist is now turned {state}")),
+            )
+            .await;
+        kick_unlisted_players(server).await;
+        Ok(())
+    }
+}
+
+struct WhitelistListExecutor;
+
+#[async_trait]
+impl CommandExecutor for WhitelistListExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        _server: &Server,
+        _args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let whitelist = WHITELIST_CONFIG.read().await;
+        let msg = if whitelist.whitelist.is_empty() {
+            TextComponent::text("There are no whitelisted players")
+        } else {
+            let names: Vec<&str> = whitelist
+                .whitelist
+                .iter()
+                .map(|entry| entry.name.as_str())
+                .collect();
+            TextComponent::text_string(format!(
+                "There are {} whitelisted player(s): {}",
+                names.len(),
+                names.join(", ")
+            ))
+        };
+        sender.send_message(msg).await;
+        Ok(())
+    }
+}
+
+struct WhitelistAddExecutor;
+
+#[async_trait]
+impl CommandExecutor for WhitelistAddExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let targets =
+            GameProfilesArgumentConsumer::find_profiles(args, ARG_TARGETS, server).await?;
+
+        for profile in &targets {
+            if WHITELIST_CONFIG
+                .write()
+                .await
+                .add(profile.id, profile.name.clone())
+            {
+                sender
+                    .send_feedback(
+                        server,
+                        TextComponent::text_string(format!(
+                            "Added {} to the whitelist",
+                            profile.name
+                        )),
+                    )
+                    .await;
+            } else {
+                sender
+                    .send_message(TextComponent::text("Player is already whitelisted"))
+                    .await;
+            }
+        }
+        Ok(())
+    }
+}
+
+struct WhitelistRemoveExecutor;
+
+#[async_trait]
+impl CommandExecutor for WhitelistRemoveExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let targets =
+            GameProfilesArgumentConsumer::find_profiles(args, ARG_TARGETS, server).await?;
+
+        for profile in &targets {
+            if WHITELIST_CONFIG.write().await.remove(profile.id) {
+                sender
+                    .send_feedback(
+                        server,
+                        TextComponent::text_string(format!(
+                            "Removed {} from the whitelist",
+                            profile.name
+                        )),
+                    )
+                    .await;
+            } else {
+                sender
+                    .send_message(TextComponent::text("Player is not whitelisted"))
+                    .await;
+            }
+        }
+        kick_unlisted_players(server).await;
+        Ok(())
+    }
+}
+
+struct WhitelistReloadExecutor;
+
+#[async_trait]
+impl CommandExecutor for WhitelistReloadExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        _args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        *WHITELIST_CONFIG.write().await = WhitelistConfig::load();
+        sender
+            .send_feedback(server, TextComponent::text("Reloaded the whitelist"))
+            .await;
+        kick_unlisted_players(server).await;
+        Ok(())
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.whitelist", PermissionLvl::Three))
+            .with_child(literal("on").execute(&WhitelistToggleExecutor(true)))
+            .with_child(literal("off").execute(&WhitelistToggleExecutor(false)))
+            .with_child(literal("list").execute(&WhitelistListExecutor))
+            .with_child(literal("add").with_child(
+                argument(ARG_TARGETS, &GameProfilesArgumentConsumer).execute(&WhitelistAddExecutor),
+            ))
+            .with_child(
+                literal("remove").with_child(
+                    argument(ARG_TARGETS, &GameProfilesArgumentConsumer)
+                        .execute(&WhitelistRemoveExecutor),
+                ),
+            )
+            .with_child(literal("reload").execute(&WhitelistReloadExecutor)),
+    )
+}
@@ -15,9 +15,10 @@ use crate::{
             FindArgDefaultName,
         },
         tree::CommandTree,
-        tree_builder::{argument_default_name, literal},
+        tree_builder::{argument_default_name, literal, require},
         CommandError, CommandExecutor, CommandSender,
     },
+    entity::player::PermissionLvl,
     server::Server,
 };
 
@@ -547,54 +548,64 @@ static WARNING_DISTANCE_CONSUMER: BoundedNumArgumentConsumer<i32> =
     BoundedNumArgumentConsumer::new().min(0).name("distance");
 
 pub fn init_command_tree<'a>() -> CommandTree<'a> {
-    CommandTree::new(NAMES, DESCRIPTION)
-        .with_child(
-            literal("add").with_child(
-                argument_default_name(&DISTANCE_CONSUMER)
-                    .execute(&WorldborderAddExecutor)
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.has_permission("pumpkin.command.worldborder", PermissionLvl::Two))
+            .with_child(
+                literal("add").with_child(
+                    argument_default_name(&DISTANCE_CONSUMER)
+                        .execute(&WorldborderAddExecutor)
+                        .with_child(
+                            argument_default_name(&TIME_CONSUMER)
+                                .execute(&WorldborderAddTimeExecutor),
+                        ),
+                ),
+            )
+            .with_child(
+                literal("center").with_child(
+                    argument_default_name(&Position2DArgumentConsumer)
+                        .execute(&WorldborderCenterExecutor),
+                ),
+            )
+            .with_child(
+                literal("damage")
                     .with_child(
-                        argument_default_name(&TIME_CONSUMER).execute(&WorldborderAddTimeExecutor),
-                    ),
-            ),
-        )
-        .with_child(literal("center").with_child(
-            argument_default_name(&Position2DArgumentConsumer).execute(&WorldborderCenterExecutor),
-        ))
-        .with_child(
-            literal("damage")
-                .with_child(
-                    literal("amount").with_child(
-                        argument_default_name(&DAMAGE_PER_BLOCK_CONSUMER)
-                            .execute(&WorldborderDamageAmountExecutor),
-                    ),
-                )
-                .with_child(
-                    literal("buffer").with_child(
-                        argument_default_name(&DAMAGE_BUFFER_CONSUMER)
-                            .execute(&WorldborderDamageBufferExecutor),
+                        literal("amount").with_child(
+                            argument_default_name(&DAMAGE_PER_BLOCK_CONSUMER)
+                                .execute(&WorldborderDamageAmountExecutor),
+                        ),
+                    )
+                    .with_child(
+                        literal("buffer").with_child(
+                            argument_default_name(&DAMAGE_BUFFER_CONSUMER)
+                                .execute(&WorldborderDamageBufferExecutor),
+                        ),
                     ),
+            )
+            .with_child(literal("get").execute(&WorldborderGetExecutor))
+            .with_child(
+                literal("set").with_child(
+                    argument_default_name(&DISTANCE_CONSUMER)
+                        .execute(&WorldborderSetExecutor)
+                        .with_child(
+                            argument_default_name(&TIME_CONSUMER)
+                                .execute(&WorldborderSetTimeExecutor),
+                        ),
                 ),
-        )
-        .with_child(literal("get").execute(&WorldborderGetExecutor))
-        .with_child(
-            literal("set").with_child(
-                argument_default_name(&DISTANCE_CONSUMER)
-                    .execute(&WorldborderSetExecutor)
+            )
+            .with_child(
+                literal("warning")
+                    .with_child(
+                        literal("distance").with_child(
+                            argument_default_name(&WARNING_DISTANCE_CONSUMER)
+                                .execute(&WorldborderWarningDistanceExecutor),
+                        ),
+                    )
                     .with_child(
-                        argument_default_name(&TIME_CONSUMER).execute(&WorldborderSetTimeExecutor),
+                        literal("time").with_child(
+                            argument_default_name(&TIME_CONSUMER)
+                                .execute(&WorldborderWarningTimeExecutor),
+                        ),
                     ),
             ),
-        )
-        .with_child(
-            literal("warning")
-                .with_child(
-                    literal("distance").with_child(
-                        argument_default_name(&WARNING_DISTANCE_CONSUMER)
-                            .execute(&WorldborderWarningDistanceExecutor),
-                    ),
-                )
-                .with_child(literal("time").with_child(
-                    argument_default_name(&TIME_CONSUMER).execute(&WorldborderWarningTimeExecutor),
-                )),
-        )
+    )
 }
@@ -1,18 +1,50 @@
+pub mod cmd_auditlog;
+pub mod cmd_backup;
+pub mod cmd_ban;
+pub mod cmd_banip;
 pub mod cmd_clear;
+pub mod cmd_clone;
 pub mod cmd_craft;
+pub mod cmd_data;
+pub mod cmd_datapack;
+pub mod cmd_defaultgamemode;
+pub mod cmd_deop;
+pub mod cmd_distance;
 pub mod cmd_echest;
+pub mod cmd_execute;
 pub mod cmd_fill;
 pub mod cmd_gamemode;
+pub mod cmd_gamerule;
 pub mod cmd_give;
 pub mod cmd_help;
+pub mod cmd_ignore;
+pub mod cmd_item;
 pub mod cmd_kick;
 pub mod cmd_kill;
+pub mod cmd_lastdeath;
 pub mod cmd_list;
+pub mod cmd_locate;
+pub mod cmd_msg;
+pub mod cmd_op;
+pub mod cmd_pardon;
+pub mod cmd_pardonip;
+pub mod cmd_particle;
+pub mod cmd_pathdebug;
+pub mod cmd_perfhud;
+pub mod cmd_playsound;
+pub mod cmd_pregen;
+pub mod cmd_profiler;
 pub mod cmd_pumpkin;
+pub mod cmd_relight;
 pub mod cmd_say;
+pub mod cmd_script;
 pub mod cmd_seed;
 pub mod cmd_setblock;
 pub mod cmd_stop;
+pub mod cmd_team;
+pub mod cmd_teammsg;
 pub mod cmd_teleport;
+pub mod cmd_title;
 pub mod cmd_transfer;
+pub mod cmd_whitelist;
 pub mod cmd_worldborder;
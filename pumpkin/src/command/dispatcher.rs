@@ -4,14 +4,18 @@ use pumpkin_protocol::client::play::CommandSuggestion;
 use super::args::ConsumedArgs;
 
 use crate::command::dispatcher::CommandError::{
-    GeneralCommandIssue, InvalidConsumption, InvalidRequirement, OtherPumpkin,
+    GeneralCommandIssue, InvalidConsumption, InvalidRequirement, OtherPumpkin, SyntaxError,
 };
 use crate::command::tree::{Command, CommandTree, NodeType, RawArgs};
 use crate::command::CommandSender;
 use crate::error::PumpkinError;
+use crate::server::audit::AuditAction;
 use crate::server::Server;
 use pumpkin_core::text::color::{Color, NamedColor};
-use std::collections::{HashMap, HashSet};
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use pumpkin_core::text::click::ClickEvent;
 
 #[derive(Debug)]
 #[allow(dead_code)]
@@ -27,6 +31,107 @@ pub(crate) enum CommandError {
     OtherPumpkin(Box<dyn PumpkinError>),
 
     GeneralCommandIssue(String),
+
+    /// The command does not match any path of its [`CommandTree`].
+    SyntaxError(CommandSyntaxError),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SyntaxErrorKind {
+    /// The command does not exist or ended too early
+    UnknownCommand,
+    /// An argument could not be parsed
+    IncorrectArgument,
+    /// There are arguments left after the command was complete
+    TrailingData,
+}
+
+impl SyntaxErrorKind {
+    const fn translation_key(self) -> &'static str {
+        match self {
+            Self::UnknownCommand => "command.unknown.command",
+            Self::IncorrectArgument => "command.unknown.argument",
+            Self::TrailingData => "command.expected.separator",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct CommandSyntaxError {
+    pub kind: SyntaxErrorKind,
+    /// Byte position in the command where parsing failed
+    pub cursor: usize,
+    /// Literals and `<arguments>` which would have been accepted at the cursor
+    pub expected: Vec<String>,
+}
+
+impl CommandSyntaxError {
+    /// How many characters in front of the cursor are shown, like vanilla
+    const CONTEXT_LENGTH: usize = 10;
+
+    /// Renders the error like vanilla: the error message, followed by the end of the command with the
+    /// invalid part underlined.
+    pub fn to_text_component(&self, cmd: &str) -> TextComponent<'static> {
+        let cursor = self.cursor.min(cmd.len());
+        let context_start = cmd[..cursor]
+            .char_indices()
+            .rev()
+            .nth(Self::CONTEXT_LENGTH - 1)
+            .map_or(0, |(i, _)| i);
+
+        let mut context = TextComponent::text("")
+            .color_named(NamedColor::Gray)
+            .click_event(ClickEvent::SuggestCommand(Cow::Owned(format!("/{cmd}"))));
+        if context_start > 0 {
+            context = context.add_child(TextComponent::text("..."));
+        }
+        context = context.add_child(TextComponent::text_string(
+            cmd[context_start..cursor].to_string(),
+        ));
+        if cursor < cmd.len() {
+            context = context.add_child(
+                TextComponent::text_string(cmd[cursor..].to_string())
+                    .color_named(NamedColor::Red)
+                    .underlined(),
+            );
+        }
+        context = context.add_child(
+            TextComponent::translate("command.context.here", vec![])
+                .color_named(NamedColor::Red)
+                .italic(),
+        );
+
+        let mut message = TextComponent::text("")
+            .add_child(
+                TextComponent::translate(self.kind.translation_key(), vec![])
+                    .color_named(NamedColor::Red),
+            )
+            .add_child(TextComponent::text("\n"))
+            .add_child(context);
+        if !self.expected.is_empty() {
+            message = message.add_child(
+                TextComponent::text_string(format!("\nExpected: {}", self.expected.join(", ")))
+                    .color_named(NamedColor::Gray),
+            );
+        }
+        message
+    }
+}
+
+/// How far a path got before it did not match the command
+struct PathFailure {
+    /// Number of arguments which were not consumed
+    remaining: usize,
+    reason: FailureReason,
+}
+
+enum FailureReason {
+    /// The literal or `<argument>` the path expected next
+    Expected(String),
+    /// The path ended before the arguments did
+    TrailingData,
+    /// A requirement was not met, so the sender should not know about the rest of the path
+    Hidden,
 }
 
 impl CommandError {
@@ -41,14 +146,15 @@ impl CommandError {
                 Ok("Internal Error (See logs for details)".into())
             }
             GeneralCommandIssue(s) => Ok(s),
+            SyntaxError(e) => Ok(e.to_text_component(cmd).to_pretty_console()),
             OtherPumpkin(e) => Err(e),
         }
     }
 }
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct CommandDispatcher<'a> {
-    pub(crate) commands: HashMap<&'a str, Command<'a>>,
+    pub(crate) commands: HashMap<Cow<'a, str>, Command<'a>>,
 }
 
 /// Stores registered [`CommandTree`]s and dispatches commands to them.
@@ -59,7 +165,14 @@ impl<'a> CommandDispatcher<'a> {
         server: &'a Server,
         cmd: &'a str,
     ) {
+        server
+            .audit_log
+            .record((&*sender).into(), AuditAction::Command, None, cmd);
         if let Err(e) = self.dispatch(sender, server, cmd).await {
+            if let SyntaxError(err) = e {
+                sender.send_message(err.to_text_component(cmd)).await;
+                return;
+            }
             match e.into_string_or_pumpkin_error(cmd) {
                 Ok(err) => {
                     sender
@@ -129,6 +242,7 @@ impl<'a> CommandDispatcher<'a> {
                     log::error!("Error while parsing command \"{cmd}\": {e}");
                     return Vec::new();
                 }
+                Err(SyntaxError(_)) => return Vec::new(),
                 Ok(Some(new_suggestions)) => {
                     suggestions.extend(new_suggestions);
                 }
@@ -137,10 +251,42 @@ impl<'a> CommandDispatcher<'a> {
         }
 
         let mut suggestions = Vec::from_iter(suggestions);
-        suggestions.sort_by(|a, b| a.suggestion.cmp(b.suggestion));
+        suggestions.sort_by(|a, b| a.suggestion.cmp(&b.suggestion));
         suggestions
     }
 
+    /// The literals which can follow the complete words of the command, for the console which
+    /// doesn't know the tree like clients do. Assumes every argument is a single word
+    pub(crate) fn find_literal_suggestions(&'a self, cmd: &str) -> Vec<&'a str> {
+        let complete = cmd.rfind(char::is_whitespace).map_or("", |end| &cmd[..end]);
+        let mut words = complete.split_whitespace();
+        let Some(Ok(tree)) = words.next().map(|key| self.get_tree(key)) else {
+            return Vec::new();
+        };
+        let words: Vec<&str> = words.collect();
+
+        let mut literals = Vec::new();
+        for path in tree.iter_paths() {
+            let mut consuming = path
+                .iter()
+                .map(|&i| &tree.nodes[i].node_type)
+                .filter(|node| {
+                    matches!(node, NodeType::Literal { .. } | NodeType::Argument { .. })
+                });
+            let fits = words.iter().all(|word| match consuming.next() {
+                Some(NodeType::Literal { string }) => string == word,
+                Some(NodeType::Argument { .. }) => true,
+                _ => false,
+            });
+            if let (true, Some(NodeType::Literal { string })) = (fits, consuming.next()) {
+                literals.push(string.as_ref());
+            }
+        }
+        literals.sort_unstable();
+        literals.dedup();
+        literals
+    }
+
     /// Execute a command using its corresponding [`CommandTree`].
     pub(crate) async fn dispatch(
         &'a self,
@@ -155,20 +301,70 @@ impl<'a> CommandDispatcher<'a> {
             .ok_or(GeneralCommandIssue("Empty Command".to_string()))?;
         let raw_args: Vec<&str> = parts.rev().collect();
 
+        if !self.commands.contains_key(key) {
+            return Err(SyntaxError(CommandSyntaxError {
+                kind: SyntaxErrorKind::UnknownCommand,
+                cursor: offset_in(cmd, key),
+                expected: Vec::new(),
+            }));
+        }
         let tree = self.get_tree(key)?;
 
-        // try paths until fitting path is found
+        // try paths until fitting path is found, otherwise report the paths which got the furthest
+        let mut remaining = raw_args.len();
+        let mut expected = BTreeSet::new();
+        let mut trailing_data = false;
         for path in tree.iter_paths() {
-            if Self::try_is_fitting_path(src, server, &path, tree, raw_args.clone()).await? {
-                return Ok(());
+            let failure = match Self::try_is_fitting_path(
+                src,
+                server,
+                &path,
+                tree,
+                raw_args.clone(),
+            )
+            .await?
+            {
+                Ok(()) => return Ok(()),
+                Err(failure) => failure,
+            };
+            if failure.remaining > remaining {
+                continue;
+            }
+            if failure.remaining < remaining {
+                remaining = failure.remaining;
+                expected.clear();
+                trailing_data = false;
+            }
+            match failure.reason {
+                FailureReason::Expected(token) => {
+                    expected.insert(token);
+                }
+                FailureReason::TrailingData => trailing_data = true,
+                FailureReason::Hidden => {}
             }
         }
-        Err(GeneralCommandIssue(format!(
-            "Invalid Syntax. Usage: {tree}"
-        )))
+
+        let (kind, cursor) = if remaining == 0 {
+            (SyntaxErrorKind::UnknownCommand, cmd.len())
+        } else {
+            let kind = if !expected.is_empty() {
+                SyntaxErrorKind::IncorrectArgument
+            } else if trailing_data {
+                SyntaxErrorKind::TrailingData
+            } else {
+                SyntaxErrorKind::UnknownCommand
+            };
+            // arguments are stored in reverse, so the next one is the last
+            (kind, offset_in(cmd, raw_args[remaining - 1]))
+        };
+        Err(SyntaxError(CommandSyntaxError {
+            kind,
+            cursor,
+            expected: expected.into_iter().collect(),
+        }))
     }
 
-    pub(crate) fn get_tree(&'a self, key: &str) -> Result<&'a CommandTree<'a>, CommandError> {
+    pub(crate) fn get_tree(&self, key: &str) -> Result<&CommandTree<'a>, CommandError> {
         let command = self
             .commands
             .get(key)
@@ -192,69 +388,87 @@ impl<'a> CommandDispatcher<'a> {
         src: &mut CommandSender<'a>,
         server: &'a Server,
         path: &[usize],
-        tree: &CommandTree<'a>,
+        tree: &'a CommandTree<'a>,
         mut raw_args: RawArgs<'a>,
-    ) -> Result<bool, CommandError> {
+    ) -> Result<Result<(), PathFailure>, CommandError> {
         let mut parsed_args: ConsumedArgs = HashMap::new();
 
         for node in path.iter().map(|&i| &tree.nodes[i]) {
-            match node.node_type {
+            let remaining = raw_args.len();
+            match &node.node_type {
                 NodeType::ExecuteLeaf { executor } => {
                     return if raw_args.is_empty() {
                         executor.execute(src, server, &parsed_args).await?;
-                        Ok(true)
+                        Ok(Ok(()))
                     } else {
-                        Ok(false)
+                        Ok(Err(PathFailure {
+                            remaining,
+                            reason: FailureReason::TrailingData,
+                        }))
                     };
                 }
                 NodeType::Literal { string, .. } => {
-                    if raw_args.pop() != Some(string) {
-                        return Ok(false);
+                    if raw_args.pop() != Some(string.as_ref()) {
+                        return Ok(Err(PathFailure {
+                            remaining,
+                            reason: FailureReason::Expected(string.to_string()),
+                        }));
                     }
                 }
                 NodeType::Argument { consumer, name, .. } => {
                     match consumer.consume(src, server, &mut raw_args).await {
                         Some(consumed) => {
-                            parsed_args.insert(name, consumed);
+                            parsed_args.insert(name.as_ref(), consumed);
+                        }
+                        None => {
+                            return Ok(Err(PathFailure {
+                                remaining,
+                                reason: FailureReason::Expected(format!("<{name}>")),
+                            }))
                         }
-                        None => return Ok(false),
                     }
                 }
                 NodeType::Require { predicate, .. } => {
                     if !predicate(src) {
-                        return Ok(false);
+                        return Ok(Err(PathFailure {
+                            remaining,
+                            reason: FailureReason::Hidden,
+                        }));
                     }
                 }
             }
         }
 
-        Ok(false)
+        Ok(Err(PathFailure {
+            remaining: raw_args.len(),
+            reason: FailureReason::TrailingData,
+        }))
     }
 
     async fn try_find_suggestions_on_path(
         src: &mut CommandSender<'a>,
         server: &'a Server,
         path: &[usize],
-        tree: &CommandTree<'a>,
+        tree: &'a CommandTree<'a>,
         mut raw_args: RawArgs<'a>,
         input: &'a str,
     ) -> Result<Option<Vec<CommandSuggestion<'a>>>, CommandError> {
         let mut parsed_args: ConsumedArgs = HashMap::new();
 
         for node in path.iter().map(|&i| &tree.nodes[i]) {
-            match node.node_type {
+            match &node.node_type {
                 NodeType::ExecuteLeaf { .. } => {
                     return Ok(None);
                 }
                 NodeType::Literal { string, .. } => {
-                    if raw_args.pop() != Some(string) {
+                    if raw_args.pop() != Some(string.as_ref()) {
                         return Ok(None);
                     }
                 }
                 NodeType::Argument { consumer, name } => {
                     match consumer.consume(src, server, &mut raw_args).await {
                         Some(consumed) => {
-                            parsed_args.insert(name, consumed);
+                            parsed_args.insert(name.as_ref(), consumed);
                         }
                         None => {
                             return if raw_args.is_empty() {
@@ -281,12 +495,38 @@ impl<'a> CommandDispatcher<'a> {
     pub(crate) fn register(&mut self, tree: CommandTree<'a>) {
         let mut names = tree.names.iter();
 
-        let primary_name = names.next().expect("at least one name must be provided");
+        let primary_name = names
+            .next()
+            .expect("at least one name must be provided")
+            .clone();
 
-        for &name in names {
-            self.commands.insert(name, Command::Alias(primary_name));
+        for name in names {
+            self.commands
+                .insert(name.clone(), Command::Alias(primary_name.clone()));
         }
 
         self.commands.insert(primary_name, Command::Tree(tree));
     }
+
+    /// Removes a command with its aliases, returns false if there is no such command.
+    pub(crate) fn unregister(&mut self, name: &str) -> bool {
+        if !matches!(self.commands.get(name), Some(Command::Tree(_))) {
+            return false;
+        }
+        let Some(Command::Tree(tree)) = self.commands.remove(name) else {
+            return false;
+        };
+        for alias in &tree.names {
+            if matches!(self.commands.get(alias), Some(Command::Alias(primary)) if primary == name)
+            {
+                self.commands.remove(alias);
+            }
+        }
+        true
+    }
+}
+
+/// Byte position of `part` in `cmd`, `part` has to be a slice of `cmd`.
+fn offset_in(cmd: &str, part: &str) -> usize {
+    part.as_ptr() as usize - cmd.as_ptr() as usize
 }
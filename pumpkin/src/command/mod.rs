@@ -5,25 +5,32 @@ use crate::command::commands::cmd_seed;
 use crate::command::commands::cmd_transfer;
 use crate::command::dispatcher::CommandDispatcher;
 use crate::entity::player::{PermissionLvl, Player};
+use crate::permission;
 use crate::server::Server;
 use crate::world::World;
 use args::ConsumedArgs;
 use async_trait::async_trait;
 use commands::{
-    cmd_clear, cmd_craft, cmd_echest, cmd_fill, cmd_gamemode, cmd_give, cmd_help, cmd_kick,
-    cmd_kill, cmd_list, cmd_pumpkin, cmd_say, cmd_setblock, cmd_stop, cmd_teleport,
-    cmd_worldborder,
+    cmd_auditlog, cmd_backup, cmd_ban, cmd_banip, cmd_clear, cmd_clone, cmd_craft, cmd_data,
+    cmd_datapack, cmd_defaultgamemode, cmd_deop, cmd_distance, cmd_echest, cmd_execute, cmd_fill,
+    cmd_gamemode, cmd_gamerule, cmd_give, cmd_help, cmd_ignore, cmd_item, cmd_kick, cmd_kill,
+    cmd_lastdeath, cmd_list, cmd_locate, cmd_msg, cmd_op, cmd_pardon, cmd_pardonip, cmd_particle,
+    cmd_pathdebug, cmd_perfhud, cmd_playsound, cmd_pregen, cmd_profiler, cmd_pumpkin, cmd_relight,
+    cmd_say, cmd_script, cmd_setblock, cmd_stop, cmd_team, cmd_teammsg, cmd_teleport, cmd_title,
+    cmd_whitelist, cmd_worldborder,
 };
 use dispatcher::CommandError;
+use pumpkin_config::ADVANCED_CONFIG;
 use pumpkin_core::math::vector3::Vector3;
-use pumpkin_core::text::TextComponent;
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+use pumpkin_world::game_rules::{LOG_ADMIN_COMMANDS, SEND_COMMAND_FEEDBACK};
 
 pub mod args;
 pub mod client_cmd_suggestions;
 mod commands;
 pub mod dispatcher;
-mod tree;
-mod tree_builder;
+pub(crate) mod tree;
+pub(crate) mod tree_builder;
 mod tree_format;
 
 pub enum CommandSender<'a> {
@@ -55,6 +62,54 @@ impl<'a> CommandSender<'a> {
         }
     }
 
+    /// Tells the sender what the command did and, like vanilla, the other operators too in
+    /// italic gray. Players only see it while `sendCommandFeedback` is on, errors are sent with
+    /// [`Self::send_message`] instead
+    pub async fn send_feedback(&self, server: &Server, text: TextComponent<'a>) {
+        let world = self.world().unwrap_or(&server.worlds[0]);
+        let (send_feedback, log_admin_commands) = {
+            let game_rules = world.game_rules.read().await;
+            (
+                game_rules.get(&SEND_COMMAND_FEEDBACK),
+                game_rules.get(&LOG_ADMIN_COMMANDS),
+            )
+        };
+        if send_feedback || !self.is_player() {
+            self.send_message(text.clone()).await;
+        }
+
+        let commands = &ADVANCED_CONFIG.commands;
+        let informs_admins = match self {
+            CommandSender::Player(_) => true,
+            CommandSender::Console => commands.broadcast_console_to_ops,
+            CommandSender::Rcon(_) => commands.broadcast_rcon_to_ops,
+        };
+        if !informs_admins {
+            return;
+        }
+        let admin_message = TextComponent::translate(
+            "chat.type.admin",
+            vec![TextComponent::text_string(self.to_string()), text],
+        )
+        .color_named(NamedColor::Gray)
+        .italic();
+        if send_feedback {
+            let source = self.as_player();
+            for player in server.get_all_players().await {
+                let is_source = source
+                    .as_ref()
+                    .is_some_and(|source| Arc::ptr_eq(source, &player));
+                if !is_source && !matches!(player.permission_lvl(), PermissionLvl::Zero) {
+                    player.send_system_message(&admin_message).await;
+                }
+            }
+        }
+        // the console already saw its own message
+        if !self.is_console() && log_admin_commands {
+            log::info!("{}", admin_message.to_pretty_console());
+        }
+    }
+
     #[must_use]
     pub const fn is_player(&self) -> bool {
         matches!(self, CommandSender::Player(_))
@@ -81,6 +136,16 @@ impl<'a> CommandSender<'a> {
         }
     }
 
+    /// Whether the sender has the permission node, players without an entry for the node need `default_lvl`.
+    /// See [`crate::permission`]
+    #[must_use]
+    pub fn has_permission(&self, node: &str, default_lvl: PermissionLvl) -> bool {
+        match self {
+            CommandSender::Console | CommandSender::Rcon(_) => true,
+            CommandSender::Player(p) => permission::has_permission(p, node, default_lvl),
+        }
+    }
+
     #[must_use]
     pub fn has_permission_lvl(&self, lvl: PermissionLvl) -> bool {
         match self {
@@ -97,6 +162,18 @@ impl<'a> CommandSender<'a> {
         }
     }
 
+    /// yaw and pitch of the sender
+    #[must_use]
+    pub fn rotation(&self) -> Option<(f32, f32)> {
+        match self {
+            CommandSender::Console | CommandSender::Rcon(..) => None,
+            CommandSender::Player(p) => Some((
+                p.living_entity.entity.yaw.load(),
+                p.living_entity.entity.pitch.load(),
+            )),
+        }
+    }
+
     #[must_use]
     pub fn world(&self) -> Option<&World> {
         match self {
@@ -114,12 +191,14 @@ pub fn default_dispatcher<'a>() -> Arc<CommandDispatcher<'a>> {
     dispatcher.register(cmd_pumpkin::init_command_tree());
     dispatcher.register(cmd_say::init_command_tree());
     dispatcher.register(cmd_gamemode::init_command_tree());
+    dispatcher.register(cmd_defaultgamemode::init_command_tree());
     dispatcher.register(cmd_stop::init_command_tree());
     dispatcher.register(cmd_help::init_command_tree());
     dispatcher.register(cmd_echest::init_command_tree());
     dispatcher.register(cmd_craft::init_command_tree());
     dispatcher.register(cmd_kill::init_command_tree());
     dispatcher.register(cmd_kick::init_command_tree());
+    dispatcher.register(cmd_lastdeath::init_command_tree());
     dispatcher.register(cmd_worldborder::init_command_tree());
     dispatcher.register(cmd_teleport::init_command_tree());
     dispatcher.register(cmd_give::init_command_tree());
@@ -129,12 +208,45 @@ pub fn default_dispatcher<'a>() -> Arc<CommandDispatcher<'a>> {
     dispatcher.register(cmd_seed::init_command_tree());
     dispatcher.register(cmd_transfer::init_command_tree());
     dispatcher.register(cmd_fill::init_command_tree());
+    dispatcher.register(cmd_clone::init_command_tree());
+    dispatcher.register(cmd_gamerule::init_command_tree());
+    dispatcher.register(cmd_pathdebug::init_command_tree());
+    dispatcher.register(cmd_locate::init_command_tree());
+    dispatcher.register(cmd_locate::init_locatebiome_command_tree());
+    dispatcher.register(cmd_perfhud::init_command_tree());
+    dispatcher.register(cmd_profiler::init_command_tree());
+    dispatcher.register(cmd_pregen::init_command_tree());
+    dispatcher.register(cmd_relight::init_command_tree());
+    dispatcher.register(cmd_distance::init_command_tree());
+    dispatcher.register(cmd_whitelist::init_command_tree());
+    dispatcher.register(cmd_ban::init_command_tree());
+    dispatcher.register(cmd_ban::init_tempban_command_tree());
+    dispatcher.register(cmd_banip::init_command_tree());
+    dispatcher.register(cmd_pardon::init_command_tree());
+    dispatcher.register(cmd_pardonip::init_command_tree());
+    dispatcher.register(cmd_op::init_command_tree());
+    dispatcher.register(cmd_deop::init_command_tree());
+    dispatcher.register(cmd_auditlog::init_command_tree());
+    dispatcher.register(cmd_backup::init_command_tree());
+    dispatcher.register(cmd_script::init_command_tree());
+    dispatcher.register(cmd_title::init_command_tree());
+    dispatcher.register(cmd_data::init_command_tree());
+    dispatcher.register(cmd_datapack::init_command_tree());
+    dispatcher.register(cmd_execute::init_command_tree());
+    dispatcher.register(cmd_item::init_command_tree());
+    dispatcher.register(cmd_playsound::init_command_tree());
+    dispatcher.register(cmd_particle::init_command_tree());
+    dispatcher.register(cmd_msg::init_command_tree());
+    dispatcher.register(cmd_msg::init_reply_command_tree());
+    dispatcher.register(cmd_teammsg::init_command_tree());
+    dispatcher.register(cmd_team::init_command_tree());
+    dispatcher.register(cmd_ignore::init_command_tree());
 
     Arc::new(dispatcher)
 }
 
 #[async_trait]
-pub(crate) trait CommandExecutor: Sync {
+pub(crate) trait CommandExecutor: Send + Sync {
     async fn execute<'a>(
         &self,
         sender: &mut CommandSender<'a>,
@@ -1,29 +1,65 @@
 use super::{args::ArgumentConsumer, CommandExecutor};
 use crate::command::CommandSender;
-use std::{collections::VecDeque, fmt::Debug};
+use std::{borrow::Cow, collections::VecDeque, fmt::Debug, ops::Deref, sync::Arc};
 
 /// see [`crate::commands::tree_builder::argument`]
 pub type RawArgs<'a> = Vec<&'a str>;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Node<'a> {
     pub(crate) children: Vec<usize>,
     pub(crate) node_type: NodeType<'a>,
 }
 
+/// A part of a node which is borrowed, like the parts of the built-in commands, or owned by the
+/// tree, like the parts of plugin commands which are dropped again when they are unregistered
+pub enum Shared<'a, T: ?Sized> {
+    Borrowed(&'a T),
+    Owned(Arc<T>),
+}
+
+impl<T: ?Sized> Clone for Shared<'_, T> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Borrowed(value) => Self::Borrowed(value),
+            Self::Owned(value) => Self::Owned(value.clone()),
+        }
+    }
+}
+
+impl<T: ?Sized> Deref for Shared<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        match self {
+            Self::Borrowed(value) => value,
+            Self::Owned(value) => value,
+        }
+    }
+}
+
+impl<'a, T: ?Sized> From<&'a T> for Shared<'a, T> {
+    fn from(value: &'a T) -> Self {
+        Self::Borrowed(value)
+    }
+}
+
+pub type Predicate<'a> = dyn Fn(&CommandSender) -> bool + Send + Sync + 'a;
+
+#[derive(Clone)]
 pub enum NodeType<'a> {
     ExecuteLeaf {
-        executor: &'a dyn CommandExecutor,
+        executor: Shared<'a, dyn CommandExecutor + 'a>,
     },
     Literal {
-        string: &'a str,
+        string: Cow<'a, str>,
     },
     Argument {
-        name: &'a str,
-        consumer: &'a dyn ArgumentConsumer,
+        name: Cow<'a, str>,
+        consumer: Shared<'a, dyn ArgumentConsumer + 'a>,
     },
     Require {
-        predicate: &'a (dyn Fn(&CommandSender) -> bool + Sync),
+        predicate: Shared<'a, Predicate<'a>>,
     },
 }
 
@@ -45,17 +81,18 @@ impl Debug for NodeType<'_> {
     }
 }
 
+#[derive(Clone)]
 pub enum Command<'a> {
     Tree(CommandTree<'a>),
-    Alias(&'a str),
+    Alias(Cow<'a, str>),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CommandTree<'a> {
     pub(crate) nodes: Vec<Node<'a>>,
     pub(crate) children: Vec<usize>,
-    pub(crate) names: Vec<&'a str>,
-    pub(crate) description: &'a str,
+    pub(crate) names: Vec<Cow<'a, str>>,
+    pub(crate) description: Cow<'a, str>,
 }
 
 impl<'a> CommandTree<'a> {
@@ -1,6 +1,8 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
 use crate::command::args::ArgumentConsumer;
-use crate::command::tree::{CommandTree, Node, NodeType};
-use crate::command::CommandSender;
+use crate::command::tree::{CommandTree, Node, NodeType, Predicate, Shared};
 
 use super::args::DefaultNameArgConsumer;
 use super::CommandExecutor;
@@ -24,14 +26,26 @@ impl<'a> CommandTree<'a> {
         let mut names_vec = Vec::with_capacity(NAME_COUNT);
 
         for name in names {
-            names_vec.push(name);
+            names_vec.push(Cow::Borrowed(name));
         }
 
         Self {
             nodes: Vec::new(),
             children: Vec::new(),
             names: names_vec,
-            description,
+            description: Cow::Borrowed(description),
+        }
+    }
+
+    /// Same as [`CommandTree::new`], but the tree owns the names and description
+    pub fn new_owned(names: Vec<String>, description: String) -> Self {
+        assert!(!names.is_empty());
+
+        Self {
+            nodes: Vec::new(),
+            children: Vec::new(),
+            names: names.into_iter().map(Cow::Owned).collect(),
+            description: Cow::Owned(description),
         }
     }
 
@@ -42,7 +56,16 @@ impl<'a> CommandTree<'a> {
     /// desired type.
     ///
     /// Also see [`NonLeafNodeBuilder::execute`].
-    pub fn execute(mut self, executor: &'a dyn CommandExecutor) -> Self {
+    pub fn execute(self, executor: &'a dyn CommandExecutor) -> Self {
+        self.execute_shared(Shared::Borrowed(executor))
+    }
+
+    /// Same as [`CommandTree::execute`], but the tree owns the executor
+    pub fn execute_owned(self, executor: Arc<dyn CommandExecutor + 'a>) -> Self {
+        self.execute_shared(Shared::Owned(executor))
+    }
+
+    fn execute_shared(mut self, executor: Shared<'a, dyn CommandExecutor + 'a>) -> Self {
         let node = Node {
             node_type: NodeType::ExecuteLeaf { executor },
             children: Vec::new(),
@@ -115,7 +138,16 @@ impl<'a> NonLeafNodeBuilder<'a> {
     /// desired type.
     ///
     /// Also see [`CommandTree::execute`].
-    pub fn execute(mut self, executor: &'a dyn CommandExecutor) -> Self {
+    pub fn execute(self, executor: &'a dyn CommandExecutor) -> Self {
+        self.execute_shared(Shared::Borrowed(executor))
+    }
+
+    /// Same as [`NonLeafNodeBuilder::execute`], but the tree owns the executor
+    pub fn execute_owned(self, executor: Arc<dyn CommandExecutor + 'a>) -> Self {
+        self.execute_shared(Shared::Owned(executor))
+    }
+
+    fn execute_shared(mut self, executor: Shared<'a, dyn CommandExecutor + 'a>) -> Self {
         self.leaf_nodes.push(LeafNodeBuilder {
             node_type: NodeType::ExecuteLeaf { executor },
         });
@@ -127,7 +159,20 @@ impl<'a> NonLeafNodeBuilder<'a> {
 /// Matches a sting literal.
 pub const fn literal(string: &str) -> NonLeafNodeBuilder {
     NonLeafNodeBuilder {
-        node_type: NodeType::Literal { string },
+        node_type: NodeType::Literal {
+            string: Cow::Borrowed(string),
+        },
+        child_nodes: Vec::new(),
+        leaf_nodes: Vec::new(),
+    }
+}
+
+/// Same as [`literal`], but the tree owns the string
+pub fn literal_owned<'a>(string: String) -> NonLeafNodeBuilder<'a> {
+    NonLeafNodeBuilder {
+        node_type: NodeType::Literal {
+            string: Cow::Owned(string),
+        },
         child_nodes: Vec::new(),
         leaf_nodes: Vec::new(),
     }
@@ -143,7 +188,25 @@ pub const fn literal(string: &str) -> NonLeafNodeBuilder {
 /// reversed, so [`Vec::pop`] can be used to obtain args in ltr order.
 pub fn argument<'a>(name: &'a str, consumer: &'a dyn ArgumentConsumer) -> NonLeafNodeBuilder<'a> {
     NonLeafNodeBuilder {
-        node_type: NodeType::Argument { name, consumer },
+        node_type: NodeType::Argument {
+            name: Cow::Borrowed(name),
+            consumer: Shared::Borrowed(consumer),
+        },
+        child_nodes: Vec::new(),
+        leaf_nodes: Vec::new(),
+    }
+}
+
+/// Same as [`argument`], but the tree owns the name and consumer
+pub fn argument_owned<'a>(
+    name: String,
+    consumer: Shared<'a, dyn ArgumentConsumer + 'a>,
+) -> NonLeafNodeBuilder<'a> {
+    NonLeafNodeBuilder {
+        node_type: NodeType::Argument {
+            name: Cow::Owned(name),
+            consumer,
+        },
         child_nodes: Vec::new(),
         leaf_nodes: Vec::new(),
     }
@@ -153,8 +216,8 @@ pub fn argument<'a>(name: &'a str, consumer: &'a dyn ArgumentConsumer) -> NonLea
 pub fn argument_default_name(consumer: &dyn DefaultNameArgConsumer) -> NonLeafNodeBuilder<'_> {
     NonLeafNodeBuilder {
         node_type: NodeType::Argument {
-            name: consumer.default_name(),
-            consumer: consumer.get_argument_consumer(),
+            name: Cow::Borrowed(consumer.default_name()),
+            consumer: Shared::Borrowed(consumer.get_argument_consumer()),
         },
         child_nodes: Vec::new(),
         leaf_nodes: Vec::new(),
@@ -163,9 +226,22 @@ pub fn argument_default_name(consumer: &dyn DefaultNameArgConsumer) -> NonLeafNo
 
 /// ```predicate``` should return ```false``` if requirement for reaching following [Node]s is not
 /// met.
-pub fn require(predicate: &(dyn Fn(&CommandSender) -> bool + Sync)) -> NonLeafNodeBuilder {
+pub fn require<'a>(predicate: &'a Predicate<'a>) -> NonLeafNodeBuilder<'a> {
+    NonLeafNodeBuilder {
+        node_type: NodeType::Require {
+            predicate: Shared::Borrowed(predicate),
+        },
+        child_nodes: Vec::new(),
+        leaf_nodes: Vec::new(),
+    }
+}
+
+/// Same as [`require`], but the tree owns the predicate
+pub fn require_owned<'a>(predicate: Arc<Predicate<'a>>) -> NonLeafNodeBuilder<'a> {
     NonLeafNodeBuilder {
-        node_type: NodeType::Require { predicate },
+        node_type: NodeType::Require {
+            predicate: Shared::Owned(predicate),
+        },
         child_nodes: Vec::new(),
         leaf_nodes: Vec::new(),
     }
@@ -18,7 +18,7 @@ impl<'a> IsVisible for Node<'a> {
 
 impl<'a> Display for Node<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self.node_type {
+        match &self.node_type {
             NodeType::Literal { string } => {
                 f.write_str(string)?;
             }
@@ -53,7 +53,7 @@ fn flatten_require_nodes(nodes: &[Node], children: &[usize]) -> Vec<usize> {
 impl<'a> Display for CommandTree<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.write_char('/')?;
-        f.write_str(self.names[0])?;
+        f.write_str(&self.names[0])?;
 
         let mut todo = VecDeque::<&[usize]>::with_capacity(self.children.len());
         todo.push_back(&self.children);
@@ -0,0 +1,119 @@
+//! Logs in the format `simple_logger` used, but above the console's prompt while it is shown, so
+//! the line being typed isn't garbled.
+
+use colored::Colorize;
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+use parking_lot::Mutex;
+use pumpkin_config::logging::{self, LoggingConfig};
+use rustyline::ExternalPrinter;
+use time::{format_description::BorrowedFormatItem, macros::format_description, OffsetDateTime};
+
+const TIMESTAMP_FORMAT: &[BorrowedFormatItem<'static>] =
+    format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
+
+type Printer = Box<dyn ExternalPrinter + Send>;
+
+/// Prints above the console's prompt, if the console runs
+static PRINTER: Mutex<Option<Printer>> = Mutex::new(None);
+
+struct ConsoleLogger {
+    level: LevelFilter,
+    threads: bool,
+    color: bool,
+    timestamp: bool,
+}
+
+impl ConsoleLogger {
+    fn format(&self, record: &Record) -> String {
+        let level = format!("{:<5}", record.level().to_string());
+        let level = if self.color {
+            match record.level() {
+                Level::Error => level.red().to_string(),
+                Level::Warn => level.yellow().to_string(),
+                Level::Info => level.cyan().to_string(),
+                Level::Debug => level.purple().to_string(),
+                Level::Trace => level,
+            }
+        } else {
+            level
+        };
+
+        let target = if record.target().is_empty() {
+            record.module_path().unwrap_or_default()
+        } else {
+            record.target()
+        };
+
+        let thread = if self.threads {
+            format!("@{}", std::thread::current().name().unwrap_or("?"))
+        } else {
+            String::new()
+        };
+
+        let timestamp = if self.timestamp {
+            // The local offset can't always be found while other threads run
+            let now = OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
+            format!("{} ", now.format(TIMESTAMP_FORMAT).unwrap_or_default())
+        } else {
+            String::new()
+        };
+
+        format!("{timestamp}{level} [{target}{thread}] {}", record.args())
+    }
+}
+
+impl Log for ConsoleLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let message = self.format(record);
+        if let Some(printer) = PRINTER.lock().as_mut() {
+            let _ = printer.print(message);
+            return;
+        }
+        println!("{message}");
+    }
+
+    fn flush(&self) {}
+}
+
+const fn convert_level_filter(level: logging::LevelFilter) -> LevelFilter {
+    match level {
+        logging::LevelFilter::Off => LevelFilter::Off,
+        logging::LevelFilter::Error => LevelFilter::Error,
+        logging::LevelFilter::Warn => LevelFilter::Warn,
+        logging::LevelFilter::Info => LevelFilter::Info,
+        logging::LevelFilter::Debug => LevelFilter::Debug,
+        logging::LevelFilter::Trace => LevelFilter::Trace,
+    }
+}
+
+pub fn init(config: &LoggingConfig) -> Result<(), SetLoggerError> {
+    let mut level = convert_level_filter(config.level);
+    if config.env {
+        if let Some(env_level) = std::env::var("RUST_LOG")
+            .ok()
+            .and_then(|level| level.parse().ok())
+        {
+            level = env_level;
+        }
+    }
+    log::set_logger(Box::leak(Box::new(ConsoleLogger {
+        level,
+        threads: config.threads,
+        color: config.color,
+        timestamp: config.timestamp,
+    })))?;
+    log::set_max_level(level);
+    Ok(())
+}
+
+/// Logs through the printer from now on
+pub fn set_printer(printer: impl ExternalPrinter + Send + 'static) {
+    *PRINTER.lock() = Some(Box::new(printer));
+}
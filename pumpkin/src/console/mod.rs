@@ -0,0 +1,154 @@
+//! The server console, a line editor with tab completion from the command tree and a history
+//! which is kept between restarts.
+
+use std::{io::ErrorKind, sync::Arc, thread};
+
+use rustyline::{
+    completion::Completer, error::ReadlineError, highlight::Highlighter, hint::Hinter,
+    history::DefaultHistory, validate::Validator, Config, Context, Editor, Helper,
+};
+use tokio::runtime::Handle;
+
+use crate::{command::CommandSender, server::Server};
+
+pub mod logger;
+
+const PROMPT: &str = "> ";
+const HISTORY_FILE: &str = "console_history.txt";
+const HISTORY_SIZE: usize = 1000;
+
+struct CommandHelper {
+    server: Arc<Server>,
+    runtime: Handle,
+}
+
+impl CommandHelper {
+    /// Everything which could replace the last word of the line
+    async fn candidates(&self, line: &str) -> Vec<String> {
+        let dispatcher = self.server.command_dispatcher().await;
+        if !line.contains(char::is_whitespace) {
+            return dispatcher
+                .commands
+                .keys()
+                .map(ToString::to_string)
+                .collect();
+        }
+
+        let mut candidates: Vec<String> = dispatcher
+            .find_literal_suggestions(line)
+            .into_iter()
+            .map(ToString::to_string)
+            .collect();
+        candidates.extend(
+            dispatcher
+                .find_suggestions(&mut CommandSender::Console, &self.server, line)
+                .await
+                .into_iter()
+                .map(|suggestion| suggestion.suggestion.into_owned()),
+        );
+        if candidates.is_empty() {
+            // Most arguments the tree can't suggest for are players
+            candidates = self
+                .server
+                .get_all_players()
+                .await
+                .iter()
+                .map(|player| player.gameprofile.name.clone())
+                .collect();
+        }
+        candidates
+    }
+}
+
+impl Completer for CommandHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let line = &line[..pos];
+        let start = line.rfind(char::is_whitespace).map_or(0, |end| end + 1);
+        let word = &line[start..];
+        let mut candidates = self.runtime.block_on(self.candidates(line));
+        candidates.retain(|candidate| candidate.starts_with(word));
+        candidates.sort_unstable();
+        candidates.dedup();
+        Ok((start, candidates))
+    }
+}
+
+impl Hinter for CommandHelper {
+    type Hint = String;
+}
+
+impl Highlighter for CommandHelper {}
+
+impl Validator for CommandHelper {}
+
+impl Helper for CommandHelper {}
+
+fn run(server: &Arc<Server>, runtime: &Handle) -> rustyline::Result<()> {
+    let config = Config::builder()
+        .auto_add_history(true)
+        .max_history_size(HISTORY_SIZE)?
+        .build();
+    let mut editor: Editor<CommandHelper, DefaultHistory> = Editor::with_config(config)?;
+    editor.set_helper(Some(CommandHelper {
+        server: server.clone(),
+        runtime: runtime.clone(),
+    }));
+    match editor.load_history(HISTORY_FILE) {
+        Err(ReadlineError::Io(err)) if err.kind() == ErrorKind::NotFound => {}
+        Err(err) => log::warn!("Failed to load the console history: {err}"),
+        Ok(()) => {}
+    }
+    match editor.create_external_printer() {
+        Ok(printer) => logger::set_printer(printer),
+        Err(err) => log::debug!("The console can't print above its prompt: {err}"),
+    }
+
+    let mut interrupted = false;
+    loop {
+        let line = match editor.readline(PROMPT) {
+            Ok(line) => line,
+            // Ctrl-C doesn't send a signal while the line is edited
+            Err(ReadlineError::Interrupted) => {
+                crate::handle_interrupt(server, &mut interrupted);
+                continue;
+            }
+            Err(ReadlineError::Eof) => return Ok(()),
+            Err(err) => return Err(err),
+        };
+        let command = line.trim();
+        if command.is_empty() {
+            continue;
+        }
+        runtime.block_on(async {
+            let dispatcher = server.command_dispatcher().await;
+            dispatcher
+                .handle_command(&mut CommandSender::Console, server, command)
+                .await;
+        });
+        if let Err(err) = editor.append_history(HISTORY_FILE) {
+            log::warn!("Failed to save the console history: {err}");
+        }
+    }
+}
+
+/// Reads commands from the console in its own thread, has to be called inside the tokio runtime
+pub fn start(server: Arc<Server>) {
+    let runtime = Handle::current();
+    let spawned = thread::Builder::new()
+        .name("console".to_string())
+        .spawn(move || {
+            if let Err(err) = run(&server, &runtime) {
+                log::error!("Failed to read the console: {err}");
+            }
+        });
+    if let Err(err) = spawned {
+        log::error!("Failed to start the console: {err}");
+    }
+}
@@ -0,0 +1,156 @@
+//! `pumpkin convert [--to <storage>] [--from <storage>] [--world <folder>] [--check] [--repair]`
+//! imports vanilla worlds to Pumpkin's chunk storage or exports them back without starting the
+//! server. Chunks are copied with their NBT unchanged, so nothing is lost either way. The world
+//! uses the converted chunks once its config's `storage` is changed.
+//!
+//! The headers of the region files are checked before chunks are converted from them, `--repair`
+//! removes the entries pointing to broken chunks so those are generated again.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use pumpkin_config::{world_config, ChunkStorageKind};
+use pumpkin_world::chunk::{anvil::AnvilChunkStorage, convert, open_storage};
+
+const USAGE: &str = "Usage: pumpkin convert [--to anvil|key_value] [--from anvil|key_value] [--world <folder>] [--check] [--repair]";
+/// How many of the chunks which failed to convert are listed
+const LISTED_FAILURES: usize = 20;
+
+fn fail(message: &str) -> ! {
+    eprintln!("{message}\n{USAGE}");
+    std::process::exit(2);
+}
+
+const fn storage_name(storage: ChunkStorageKind) -> &'static str {
+    match storage {
+        ChunkStorageKind::Anvil => "anvil",
+        ChunkStorageKind::KeyValue => "key_value",
+    }
+}
+
+fn parse_storage(value: Option<String>, name: &str) -> ChunkStorageKind {
+    match value.as_deref() {
+        Some("anvil") => ChunkStorageKind::Anvil,
+        Some("key_value") => ChunkStorageKind::KeyValue,
+        _ => fail(&format!("{name} needs anvil or key_value")),
+    }
+}
+
+/// Checks the headers of the world's region files, removing broken entries if `repair`
+fn check_regions(world: &Path, repair: bool) {
+    let check = AnvilChunkStorage::new(world.join("region"))
+        .check_regions(repair)
+        .unwrap_or_else(|err| fail(&format!("Couldn't check the region files: {err}")));
+    for (path, chunk, problem) in &check.problems {
+        match chunk {
+            Some(chunk) => println!(
+                "{} chunk {}, {}: {problem}",
+                path.display(),
+                chunk.x,
+                chunk.z
+            ),
+            None => println!("{}: {problem}", path.display()),
+        }
+    }
+    println!(
+        "Checked {} region files, {} chunks are fine and {} entries are broken",
+        check.regions,
+        check.valid_chunks,
+        check.problems.len()
+    );
+    if !check.problems.is_empty() {
+        if repair {
+            println!("Removed the broken entries, those chunks are generated again");
+        } else {
+            println!("Run with --repair to remove the broken entries");
+        }
+    }
+}
+
+/// Runs the conversion with the arguments after `convert`
+pub fn run(mut args: impl Iterator<Item = String>) {
+    let mut world = PathBuf::from("world");
+    let mut from = None;
+    let mut to = None;
+    let mut check = false;
+    let mut repair = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--world" => {
+                world = args
+                    .next()
+                    .unwrap_or_else(|| fail("--world needs a folder"))
+                    .into();
+            }
+            "--from" => from = Some(parse_storage(args.next(), "--from")),
+            "--to" => to = Some(parse_storage(args.next(), "--to")),
+            "--check" => check = true,
+            "--repair" => repair = true,
+            _ => fail(&format!("Unknown argument {arg}")),
+        }
+    }
+    if to.is_none() && !check && !repair {
+        fail("--to, --check or --repair is needed");
+    }
+    let name = world
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("world")
+        .to_string();
+    // the chunks are where the world's config says by default
+    let from = from.unwrap_or_else(|| world_config(&name).unwrap_or_else(|err| fail(&err)).storage);
+
+    if check || repair || from == ChunkStorageKind::Anvil {
+        check_regions(&world, repair);
+    }
+    let Some(to) = to else {
+        return;
+    };
+    if from == to {
+        fail("--from and --to are the same storage");
+    }
+
+    let start = Instant::now();
+    let stats = convert::convert(
+        &*open_storage(from, &world),
+        &*open_storage(to, &world),
+        |done, total| {
+            if done % 1000 == 0 || done == total {
+                println!("Converted {done} of {total} chunks");
+            }
+        },
+    )
+    .unwrap_or_else(|err| {
+        fail(&format!(
+            "Couldn't list the chunks of {}: {err}",
+            world.display()
+        ))
+    });
+
+    for (at, err) in stats.failed.iter().take(LISTED_FAILURES) {
+        eprintln!("Chunk {}, {}: {err}", at.x, at.z);
+    }
+    let mut errors = BTreeMap::new();
+    for (_, err) in &stats.failed {
+        *errors.entry(err.as_str()).or_insert(0) += 1;
+    }
+    for (err, count) in errors {
+        eprintln!("{count} chunks failed with: {err}");
+    }
+    println!(
+        "Converted {} chunks of {} from {} to {} in {:.1}s, {} failed",
+        stats.converted,
+        world.display(),
+        storage_name(from),
+        storage_name(to),
+        start.elapsed().as_secs_f64(),
+        stats.failed.len()
+    );
+    println!(
+        "Set storage = \"{}\" in worlds/{name}.toml to use them, the old chunks are kept",
+        storage_name(to)
+    );
+}
@@ -0,0 +1,54 @@
+//! Dates in ban lists are stored like Java's `yyyy-MM-dd HH:mm:ss Z`, a ban which never expires as `forever`.
+
+use serde::{Deserialize, Deserializer, Serializer};
+use time::{format_description::BorrowedFormatItem, macros::format_description, OffsetDateTime};
+
+const FORMAT: &[BorrowedFormatItem<'static>] = format_description!(
+    "[year]-[month]-[day] [hour]:[minute]:[second] [offset_hour sign:mandatory][offset_minute]"
+);
+
+const FOREVER: &str = "forever";
+
+pub fn format(date: &OffsetDateTime) -> String {
+    date.format(FORMAT)
+        .unwrap_or_else(|_| date.unix_timestamp().to_string())
+}
+
+pub fn serialize<S: Serializer>(date: &OffsetDateTime, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format(date))
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<OffsetDateTime, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    OffsetDateTime::parse(&s, FORMAT).map_err(serde::de::Error::custom)
+}
+
+pub mod expires {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use time::OffsetDateTime;
+
+    use super::{FOREVER, FORMAT};
+
+    #[expect(clippy::ref_option)]
+    pub fn serialize<S: Serializer>(
+        date: &Option<OffsetDateTime>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match date {
+            Some(date) => super::serialize(date, serializer),
+            None => serializer.serialize_str(FOREVER),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<OffsetDateTime>, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        if s == FOREVER {
+            return Ok(None);
+        }
+        OffsetDateTime::parse(&s, FORMAT)
+            .map(Some)
+            .map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,112 @@
+use std::{net::IpAddr, path::Path, sync::LazyLock};
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use tokio::sync::RwLock;
+
+use super::{ban_date, LoadJSONConfiguration};
+
+pub static BANNED_IP_LIST: LazyLock<RwLock<BannedIpList>> =
+    LazyLock::new(|| RwLock::new(BannedIpList::load()));
+
+#[derive(Deserialize, Serialize, Default)]
+#[serde(transparent)]
+pub struct BannedIpList {
+    pub banned_ips: Vec<BannedIpEntry>,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct BannedIpEntry {
+    pub ip: IpAddr,
+    #[serde(with = "ban_date")]
+    pub created: OffsetDateTime,
+    pub source: String,
+    /// None if the ban never expires
+    #[serde(with = "ban_date::expires")]
+    pub expires: Option<OffsetDateTime>,
+    pub reason: String,
+}
+
+impl BannedIpEntry {
+    #[must_use]
+    pub fn new(
+        ip: IpAddr,
+        source: String,
+        expires: Option<OffsetDateTime>,
+        reason: String,
+    ) -> Self {
+        Self {
+            ip,
+            created: OffsetDateTime::now_utc(),
+            source,
+            expires,
+            reason,
+        }
+    }
+
+    #[must_use]
+    pub fn is_expired(&self) -> bool {
+        self.expires
+            .is_some_and(|expires| expires <= OffsetDateTime::now_utc())
+    }
+
+    /// The message shown to players with this IP when they are kicked or try to join
+    #[must_use]
+    pub fn kick_message(&self) -> String {
+        let mut message = format!(
+            "Your IP address is banned from this server.\nReason: {}",
+            self.reason
+        );
+        if let Some(expires) = &self.expires {
+            message.push_str("\nYour ban will be removed on ");
+            message.push_str(&ban_date::format(expires));
+        }
+        message
+    }
+}
+
+impl BannedIpList {
+    /// Returns the ban of an IP, if it did not expire yet
+    pub fn get_entry(&mut self, ip: &IpAddr) -> Option<&BannedIpEntry> {
+        self.remove_expired();
+        self.banned_ips.iter().find(|entry| entry.ip == *ip)
+    }
+
+    /// Bans an IP, replacing its previous ban. Returns false if the IP was already banned
+    pub fn add(&mut self, entry: BannedIpEntry) -> bool {
+        self.remove_expired();
+        let len = self.banned_ips.len();
+        self.banned_ips.retain(|banned| banned.ip != entry.ip);
+        let newly_banned = self.banned_ips.len() == len;
+        self.banned_ips.push(entry);
+        self.save();
+        newly_banned
+    }
+
+    /// Returns false if the IP was not banned
+    pub fn remove(&mut self, ip: &IpAddr) -> bool {
+        let len = self.banned_ips.len();
+        self.banned_ips.retain(|entry| entry.ip != *ip);
+        if self.banned_ips.len() == len {
+            return false;
+        }
+        self.save();
+        true
+    }
+
+    fn remove_expired(&mut self) {
+        let len = self.banned_ips.len();
+        self.banned_ips.retain(|entry| !entry.is_expired());
+        if self.banned_ips.len() != len {
+            self.save();
+        }
+    }
+}
+
+impl LoadJSONConfiguration for BannedIpList {
+    fn get_path() -> &'static Path {
+        Path::new("banned-ips.json")
+    }
+
+    fn validate(&self) {}
+}
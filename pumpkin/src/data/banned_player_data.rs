@@ -0,0 +1,114 @@
+use std::{path::Path, sync::LazyLock};
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use super::{ban_date, LoadJSONConfiguration};
+
+pub static BANNED_PLAYER_LIST: LazyLock<RwLock<BannedPlayerList>> =
+    LazyLock::new(|| RwLock::new(BannedPlayerList::load()));
+
+#[derive(Deserialize, Serialize, Default)]
+#[serde(transparent)]
+pub struct BannedPlayerList {
+    pub banned_players: Vec<BannedPlayerEntry>,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct BannedPlayerEntry {
+    pub uuid: Uuid,
+    pub name: String,
+    #[serde(with = "ban_date")]
+    pub created: OffsetDateTime,
+    pub source: String,
+    /// None if the ban never expires
+    #[serde(with = "ban_date::expires")]
+    pub expires: Option<OffsetDateTime>,
+    pub reason: String,
+}
+
+impl BannedPlayerEntry {
+    #[must_use]
+    pub fn new(
+        uuid: Uuid,
+        name: String,
+        source: String,
+        expires: Option<OffsetDateTime>,
+        reason: String,
+    ) -> Self {
+        Self {
+            uuid,
+            name,
+            created: OffsetDateTime::now_utc(),
+            source,
+            expires,
+            reason,
+        }
+    }
+
+    #[must_use]
+    pub fn is_expired(&self) -> bool {
+        self.expires
+            .is_some_and(|expires| expires <= OffsetDateTime::now_utc())
+    }
+
+    /// The message shown to the player when they are kicked or try to join
+    #[must_use]
+    pub fn kick_message(&self) -> String {
+        let mut message = format!("You are banned from this server.\nReason: {}", self.reason);
+        if let Some(expires) = &self.expires {
+            message.push_str("\nYour ban will be removed on ");
+            message.push_str(&ban_date::format(expires));
+        }
+        message
+    }
+}
+
+impl BannedPlayerList {
+    /// Returns the ban of a player, if it did not expire yet
+    pub fn get_entry(&mut self, uuid: Uuid) -> Option<&BannedPlayerEntry> {
+        self.remove_expired();
+        self.banned_players.iter().find(|entry| entry.uuid == uuid)
+    }
+
+    /// Bans a player, replacing their previous ban. Returns false if the player was already banned
+    pub fn add(&mut self, entry: BannedPlayerEntry) -> bool {
+        self.remove_expired();
+        let len = self.banned_players.len();
+        self.banned_players
+            .retain(|banned| banned.uuid != entry.uuid);
+        let newly_banned = self.banned_players.len() == len;
+        self.banned_players.push(entry);
+        self.save();
+        newly_banned
+    }
+
+    /// Returns false if the player was not banned
+    pub fn remove(&mut self, uuid: Uuid) -> bool {
+        let len = self.banned_players.len();
+        self.banned_players.retain(|entry| entry.uuid != uuid);
+        if self.banned_players.len() == len {
+            return false;
+        }
+        self.save();
+        true
+    }
+
+    fn remove_expired(&mut self) {
+        let len = self.banned_players.len();
+        self.banned_players.retain(|entry| !entry.is_expired());
+        if self.banned_players.len() != len {
+            self.save();
+        }
+    }
+}
+
+impl LoadJSONConfiguration for BannedPlayerList {
+    fn get_path() -> &'static Path {
+        Path::new("banned-players.json")
+    }
+
+    fn validate(&self) {}
+}
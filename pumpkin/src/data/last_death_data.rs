@@ -0,0 +1,51 @@
+//! Pumpkin used to keep the last deaths in `last_deaths.json`, they are in the player data now
+//! like in vanilla.
+
+use std::{collections::HashMap, fs, path::Path};
+
+use pumpkin_core::math::vector3::Vector3;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::world::World;
+
+const PATH: &str = "last_deaths.json";
+
+#[derive(Deserialize)]
+struct LastDeath {
+    dimension: String,
+    x: i32,
+    y: i32,
+    z: i32,
+}
+
+/// Moves the last deaths from `last_deaths.json` into the player data of the world and removes
+/// the file
+pub fn migrate(world: &World) {
+    let path = Path::new(PATH);
+    let Ok(content) = fs::read_to_string(path) else {
+        return;
+    };
+    let deaths: HashMap<Uuid, LastDeath> = match serde_json::from_str(&content) {
+        Ok(deaths) => deaths,
+        Err(err) => {
+            log::error!("Couldn't parse {PATH}, the last deaths in it are not migrated: {err}");
+            return;
+        }
+    };
+    for (uuid, death) in &deaths {
+        let mut data = world.read_saved_player(*uuid);
+        data.set_last_death(Some((
+            &death.dimension,
+            Vector3::new(death.x, death.y, death.z),
+        )));
+        world.level.write_player_data(uuid, &data);
+    }
+    match fs::remove_file(path) {
+        Ok(()) => log::info!(
+            "Moved the last deaths of {} players from {PATH} into the player data",
+            deaths.len()
+        ),
+        Err(err) => log::error!("Couldn't remove {PATH}: {err}"),
+    }
+}
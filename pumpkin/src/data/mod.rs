@@ -0,0 +1,67 @@
+//! Server data which is changed at runtime and stored in the vanilla JSON files, e.g. `ops.json`.
+
+use std::{fs, path::Path};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+pub mod banned_ip_data;
+pub mod banned_player_data;
+pub mod last_death_data;
+pub mod op_data;
+pub mod user_cache_data;
+pub mod whitelist_data;
+
+mod ban_date;
+
+pub trait LoadJSONConfiguration {
+    #[must_use]
+    fn load() -> Self
+    where
+        Self: Sized + Default + Serialize + DeserializeOwned,
+    {
+        let path = Self::get_path();
+
+        let data = if path.exists() {
+            let file_content = fs::read_to_string(path)
+                .unwrap_or_else(|_| panic!("Couldn't read data file at {}", path.display()));
+
+            serde_json::from_str(&file_content).unwrap_or_else(|err| {
+                panic!("Couldn't parse data file at {}. Reason: {err}. Fix or delete the file and start Pumpkin again", path.display())
+            })
+        } else {
+            let content = Self::default();
+            content.save();
+            content
+        };
+
+        data.validate();
+        data
+    }
+
+    fn get_path() -> &'static Path;
+
+    fn validate(&self);
+
+    fn save(&self)
+    where
+        Self: Serialize,
+    {
+        let path = Self::get_path();
+        let content = match serde_json::to_string_pretty(self) {
+            Ok(content) => content,
+            Err(err) => {
+                log::error!(
+                    "Couldn't serialize data for {}. Reason: {err}",
+                    path.display()
+                );
+                return;
+            }
+        };
+        if let Err(err) = fs::write(path, content) {
+            log::error!(
+                "Couldn't write data file to {}. Reason: {err}",
+                path.display()
+            );
+        }
+    }
+}
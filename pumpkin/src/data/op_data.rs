@@ -0,0 +1,94 @@
+use std::{path::Path, sync::LazyLock};
+
+use num_traits::FromPrimitive;
+use pumpkin_config::BASIC_CONFIG;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::entity::player::PermissionLvl;
+
+use super::LoadJSONConfiguration;
+
+pub static OPERATOR_CONFIG: LazyLock<RwLock<OperatorConfig>> =
+    LazyLock::new(|| RwLock::new(OperatorConfig::load()));
+
+#[derive(Deserialize, Serialize, Default)]
+#[serde(transparent)]
+pub struct OperatorConfig {
+    pub ops: Vec<Op>,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Op {
+    pub uuid: Uuid,
+    pub name: String,
+    pub level: u8,
+    pub bypasses_player_limit: bool,
+}
+
+impl Op {
+    #[must_use]
+    pub fn permission_lvl(&self) -> PermissionLvl {
+        PermissionLvl::from_u8(self.level.min(4)).unwrap_or(PermissionLvl::Zero)
+    }
+}
+
+impl OperatorConfig {
+    #[must_use]
+    pub fn get(&self, uuid: Uuid) -> Option<&Op> {
+        self.ops.iter().find(|op| op.uuid == uuid)
+    }
+
+    /// The permission level of a player, players which are not an operator have level 0
+    #[must_use]
+    pub fn permission_lvl(&self, uuid: Uuid) -> PermissionLvl {
+        self.get(uuid)
+            .map_or(PermissionLvl::Zero, Op::permission_lvl)
+    }
+
+    /// Makes a player an operator with the configured `op_permission_level`, returns false if they already were one
+    pub fn add(&mut self, uuid: Uuid, name: String) -> bool {
+        if self.get(uuid).is_some() {
+            return false;
+        }
+        self.ops.push(Op {
+            uuid,
+            name,
+            level: BASIC_CONFIG.op_permission_level,
+            bypasses_player_limit: false,
+        });
+        self.save();
+        true
+    }
+
+    /// Returns false if the player was not an operator
+    pub fn remove(&mut self, uuid: Uuid) -> bool {
+        let len = self.ops.len();
+        self.ops.retain(|op| op.uuid != uuid);
+        if self.ops.len() == len {
+            return false;
+        }
+        self.save();
+        true
+    }
+}
+
+impl LoadJSONConfiguration for OperatorConfig {
+    fn get_path() -> &'static Path {
+        Path::new("ops.json")
+    }
+
+    fn validate(&self) {
+        for op in &self.ops {
+            if op.level > 4 {
+                log::warn!(
+                    "Operator {} has the invalid permission level {}, using 4 instead",
+                    op.name,
+                    op.level
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use std::{path::Path, sync::LazyLock};
+
+use pumpkin_config::ADVANCED_CONFIG;
+use pumpkin_protocol::Property;
+use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::client::authentication::GameProfile;
+
+use super::LoadJSONConfiguration;
+
+pub static USER_CACHE_CONFIG: LazyLock<RwLock<UserCacheConfig>> =
+    LazyLock::new(|| RwLock::new(UserCacheConfig::load()));
+
+/// The profiles of players which joined or were looked up, the most recently used last
+#[derive(Deserialize, Serialize, Default)]
+#[serde(transparent)]
+pub struct UserCacheConfig {
+    pub entries: Vec<UserCacheEntry>,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UserCacheEntry {
+    pub name: String,
+    pub uuid: Uuid,
+    #[serde(with = "super::ban_date")]
+    pub expires_on: OffsetDateTime,
+    /// Not stored by vanilla, kept for the skins of players in offline mode
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub properties: Vec<Property>,
+}
+
+impl UserCacheEntry {
+    #[must_use]
+    pub fn is_expired(&self) -> bool {
+        self.expires_on < OffsetDateTime::now_utc()
+    }
+
+    #[must_use]
+    pub fn profile(&self) -> GameProfile {
+        GameProfile {
+            id: self.uuid,
+            name: self.name.clone(),
+            properties: self.properties.clone(),
+            profile_actions: None,
+        }
+    }
+}
+
+impl UserCacheConfig {
+    /// The profile of the player with the name, ignoring case, if it did not expire
+    #[must_use]
+    pub fn get_by_name(&self, name: &str) -> Option<&UserCacheEntry> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|entry| entry.name.eq_ignore_ascii_case(name) && !entry.is_expired())
+    }
+
+    #[must_use]
+    pub fn get_by_uuid(&self, uuid: Uuid) -> Option<&UserCacheEntry> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|entry| entry.uuid == uuid && !entry.is_expired())
+    }
+
+    /// Caches the profile, replacing older entries of the same player or name
+    pub fn add(&mut self, profile: &GameProfile) {
+        let config = &ADVANCED_CONFIG.authentication.profile_cache;
+        if !config.enabled {
+            return;
+        }
+        self.entries.retain(|entry| {
+            entry.uuid != profile.id
+                && !entry.name.eq_ignore_ascii_case(&profile.name)
+                && !entry.is_expired()
+        });
+        self.entries.push(UserCacheEntry {
+            name: profile.name.clone(),
+            uuid: profile.id,
+            expires_on: OffsetDateTime::now_utc() + Duration::days(i64::from(config.expiry_days)),
+            properties: profile.properties.clone(),
+        });
+        let overflow = self.entries.len().saturating_sub(config.max_entries);
+        self.entries.drain(..overflow);
+        self.save();
+    }
+}
+
+impl LoadJSONConfiguration for UserCacheConfig {
+    fn get_path() -> &'static Path {
+        Path::new("usercache.json")
+    }
+
+    fn validate(&self) {}
+}
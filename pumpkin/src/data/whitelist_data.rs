@@ -0,0 +1,82 @@
+use std::{
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        LazyLock,
+    },
+};
+
+use pumpkin_config::{save_basic_setting, BASIC_CONFIG};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use super::LoadJSONConfiguration;
+
+pub static WHITELIST_CONFIG: LazyLock<RwLock<WhitelistConfig>> =
+    LazyLock::new(|| RwLock::new(WhitelistConfig::load()));
+
+/// Whether the whitelist is enforced, can be toggled with `/whitelist on|off`
+pub static WHITELIST_ENABLED: LazyLock<AtomicBool> =
+    LazyLock::new(|| AtomicBool::new(BASIC_CONFIG.white_list));
+
+#[derive(Deserialize, Serialize, Default)]
+#[serde(transparent)]
+pub struct WhitelistConfig {
+    pub whitelist: Vec<WhitelistEntry>,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct WhitelistEntry {
+    pub uuid: Uuid,
+    pub name: String,
+}
+
+impl WhitelistConfig {
+    #[must_use]
+    pub fn is_whitelisted(&self, uuid: Uuid) -> bool {
+        self.whitelist.iter().any(|entry| entry.uuid == uuid)
+    }
+
+    /// Returns false if the player was already whitelisted
+    pub fn add(&mut self, uuid: Uuid, name: String) -> bool {
+        if self.is_whitelisted(uuid) {
+            return false;
+        }
+        self.whitelist.push(WhitelistEntry { uuid, name });
+        self.save();
+        true
+    }
+
+    /// Returns false if the player was not whitelisted
+    pub fn remove(&mut self, uuid: Uuid) -> bool {
+        let len = self.whitelist.len();
+        self.whitelist.retain(|entry| entry.uuid != uuid);
+        if self.whitelist.len() == len {
+            return false;
+        }
+        self.save();
+        true
+    }
+}
+
+#[must_use]
+pub fn is_whitelist_enabled() -> bool {
+    WHITELIST_ENABLED.load(Ordering::Relaxed)
+}
+
+/// The state is written to `white_list` of the configuration, so it is kept after restarting
+pub fn set_whitelist_enabled(enabled: bool) {
+    WHITELIST_ENABLED.store(enabled, Ordering::Relaxed);
+    if let Err(err) = save_basic_setting("white_list", enabled.into()) {
+        log::warn!("Couldn't save whether the whitelist is enabled: {err}");
+    }
+}
+
+impl LoadJSONConfiguration for WhitelistConfig {
+    fn get_path() -> &'static Path {
+        Path::new("whitelist.json")
+    }
+
+    fn validate(&self) {}
+}
@@ -0,0 +1,255 @@
+//! Arrows shot by dispensers and skeletons, which hurt the players they fly into. Arrows don't
+//! stick in blocks yet, they drop as an item where they land.
+
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+};
+
+use async_trait::async_trait;
+use crossbeam::atomic::AtomicCell;
+use pumpkin_core::{
+    math::{
+        boundingbox::{BoundingBox, BoundingBoxSize},
+        vector3::Vector3,
+    },
+    GameMode,
+};
+use pumpkin_entity::{effect_type::EffectType, entity_type::EntityType, EntityId};
+use pumpkin_macros::sound;
+use pumpkin_protocol::{client::play::CSpawnEntity, packet_encoder::PreparedPacket, SoundCategory};
+use pumpkin_world::item::{item_registry::get_item_name, ItemStack};
+use rand::Rng;
+use uuid::Uuid;
+
+use crate::world::{vibration::GameEvent, World};
+
+use super::{
+    damage::{DamageSource, ARROW},
+    effect::StatusEffect,
+    item::{ItemEntity, PICKUP_DELAY},
+    new_entity_id,
+    player::Player,
+    Entity, EntityBase,
+};
+
+const GRAVITY: f64 = 0.05;
+const DRAG: f64 = 0.99;
+/// The damage of an arrow flying one block per tick
+const BASE_DAMAGE: f64 = 2.0;
+/// Tipped arrows give their effects for an eighth of the potion's duration
+const TIPPED_DURATION_SCALE: f64 = 0.125;
+const SPECTRAL_GLOWING: u32 = 200;
+/// Arrows which never land are removed after a minute
+const DESPAWN_AGE: u32 = 1200;
+/// Ticks the shooter can't be hit by their own arrow
+const OWNER_IMMUNITY: u32 = 5;
+
+/// Velocity into the direction with some random spread, like vanilla shoots projectiles
+pub fn shoot_velocity(direction: Vector3<f64>, speed: f64, inaccuracy: f64) -> Vector3<f64> {
+    let mut rng = rand::thread_rng();
+    let mut spread = || rng.gen_range(-1.0..1.0) * 0.0075 * inaccuracy;
+    let direction = direction.normalize();
+    Vector3::new(
+        (direction.x + spread()) * speed,
+        (direction.y + spread()) * speed,
+        (direction.z + spread()) * speed,
+    )
+}
+
+pub struct Arrow {
+    pub entity: Entity,
+    uuid: Uuid,
+    /// An arrow, tipped arrow or spectral arrow, dropped again where it lands
+    stack: ItemStack,
+    owner: Option<EntityId>,
+    /// Whether it drops where it lands, the arrows of skeletons don't
+    pickup: bool,
+    age: AtomicU32,
+}
+
+impl Arrow {
+    pub fn new(
+        world: Arc<World>,
+        mut stack: ItemStack,
+        position: Vector3<f64>,
+        velocity: Vector3<f64>,
+        owner: Option<EntityId>,
+    ) -> Self {
+        stack.item_count = 1;
+        let size = BoundingBoxSize {
+            width: 0.5,
+            height: 0.5,
+        };
+        let entity_type = if Self::is_spectral(&stack) {
+            EntityType::SpectralArrow
+        } else {
+            EntityType::Arrow
+        };
+        let entity = Entity::new(
+            new_entity_id(),
+            world,
+            entity_type,
+            0.13,
+            AtomicCell::new(BoundingBox::new_from_pos(
+                position.x, position.y, position.z, &size,
+            )),
+            AtomicCell::new(size),
+        );
+        entity.set_pos(position.x, position.y, position.z);
+        entity.velocity.store(velocity);
+        Self {
+            entity,
+            uuid: Uuid::new_v4(),
+            stack,
+            owner,
+            pickup: true,
+            age: AtomicU32::new(0),
+        }
+    }
+
+    /// The arrow is gone once it lands
+    #[must_use]
+    pub fn without_pickup(mut self) -> Self {
+        self.pickup = false;
+        self
+    }
+
+    fn is_spectral(stack: &ItemStack) -> bool {
+        get_item_name(stack.item_id) == Some("minecraft:spectral_arrow")
+    }
+
+    /// The player the arrow flew into, its shooter only after a few ticks
+    async fn hit_player(&self) -> Option<Arc<Player>> {
+        let entity = &self.entity;
+        let pos = entity.pos.load();
+        let area = BoundingBox::new_from_pos(pos.x, pos.y, pos.z, &entity.bounding_box_size.load());
+        let owner_immune = self.age.load(Ordering::Relaxed) < OWNER_IMMUNITY;
+        entity
+            .world
+            .players()
+            .await
+            .iter()
+            .find(|player| {
+                let living = &player.living_entity;
+                let player_pos = living.entity.pos.load();
+                player.gamemode.load() != GameMode::Spectator
+                    && living.health.load() > 0.0
+                    && !(owner_immune && Some(player.entity_id()) == self.owner)
+                    && BoundingBox::new_from_pos(
+                        player_pos.x,
+                        player_pos.y,
+                        player_pos.z,
+                        &living.entity.bounding_box_size.load(),
+                    )
+                    .intersects(&area)
+            })
+            .cloned()
+    }
+
+    /// Hurts the player, the faster the arrow the more, and gives the effects of tipped arrows
+    async fn hit(&self, player: &Player) {
+        let speed = self.entity.velocity.load().length();
+        let damage = (speed * BASE_DAMAGE).ceil() as f32;
+        let source = self.owner.map_or(DamageSource::new(ARROW), |owner| {
+            DamageSource::by(ARROW, owner)
+        });
+        let living = &player.living_entity;
+        if !player.abilities.lock().await.invulnerable {
+            living.damage(damage, source).await;
+        }
+        if let Some(contents) = self.stack.potion_contents() {
+            living
+                .apply_potion(&contents.effects(), TIPPED_DURATION_SCALE, 1.0, self.owner)
+                .await;
+        }
+        if Self::is_spectral(&self.stack) {
+            living
+                .add_effect(StatusEffect::new(EffectType::Glowing, 0, SPECTRAL_GLOWING))
+                .await;
+        }
+        self.entity
+            .world
+            .play_sound(
+                sound!("minecraft:entity.arrow.hit_player"),
+                SoundCategory::Players,
+                &living.entity.pos.load(),
+            )
+            .await;
+    }
+
+    /// Drops the arrow as an item where it landed, unless it can't be picked up
+    async fn land(&self) {
+        let world = &self.entity.world;
+        let pos = self.entity.pos.load();
+        world
+            .play_sound(
+                sound!("minecraft:entity.arrow.hit"),
+                SoundCategory::Neutral,
+                &pos,
+            )
+            .await;
+        world
+            .emit_game_event_by(GameEvent::ProjectileLand, pos, self.owner)
+            .await;
+        if !self.pickup {
+            return;
+        }
+        let item = ItemEntity::new(
+            world.clone(),
+            self.stack.clone(),
+            pos,
+            Vector3::new(0.0, 0.0, 0.0),
+            PICKUP_DELAY / 4,
+        );
+        world.spawn_entity(Arc::new(item)).await;
+    }
+}
+
+#[async_trait]
+impl EntityBase for Arrow {
+    fn get_entity(&self) -> &Entity {
+        &self.entity
+    }
+
+    async fn tick(&self) -> bool {
+        if self.age.fetch_add(1, Ordering::Relaxed) + 1 >= DESPAWN_AGE {
+            return false;
+        }
+        let entity = &self.entity;
+        let velocity = entity.velocity.load();
+        let moved = entity.move_colliding(velocity).await;
+        if let Some(player) = self.hit_player().await {
+            self.hit(&player).await;
+            return false;
+        }
+        if let Some((block, hit, face)) = entity.hit_block(velocity, moved) {
+            entity.world.hit_target(block, hit, face, true).await;
+            self.land().await;
+            return false;
+        }
+        let mut velocity = velocity.multiply(DRAG, DRAG, DRAG);
+        velocity.y -= GRAVITY;
+        entity.velocity.store(velocity);
+        true
+    }
+
+    fn spawn_packet(&self, position: Vector3<f64>) -> PreparedPacket {
+        let velocity = self.entity.velocity.load();
+        PreparedPacket::new(&CSpawnEntity::new(
+            self.entity.entity_id.into(),
+            self.uuid,
+            (self.entity.entity_type as i32).into(),
+            position.x,
+            position.y,
+            position.z,
+            0.0,
+            0.0,
+            0.0,
+            self.owner.unwrap_or(0).into(),
+            velocity.x as f32,
+            velocity.y as f32,
+            velocity.z as f32,
+        ))
+    }
+}
@@ -0,0 +1,104 @@
+//! Why an entity was hurt, which decides the death message and how the client shows the damage.
+
+use pumpkin_core::text::TextComponent;
+use pumpkin_entity::EntityId;
+use pumpkin_registry::get_damage_type;
+
+/// The damage types of `minecraft:damage_type` Pumpkin deals itself
+pub const GENERIC: &str = "minecraft:generic";
+pub const GENERIC_KILL: &str = "minecraft:generic_kill";
+pub const FALL: &str = "minecraft:fall";
+pub const PLAYER_ATTACK: &str = "minecraft:player_attack";
+pub const MOB_ATTACK: &str = "minecraft:mob_attack";
+pub const SONIC_BOOM: &str = "minecraft:sonic_boom";
+pub const ARROW: &str = "minecraft:arrow";
+pub const DROWN: &str = "minecraft:drown";
+pub const IN_FIRE: &str = "minecraft:in_fire";
+pub const ON_FIRE: &str = "minecraft:on_fire";
+pub const LAVA: &str = "minecraft:lava";
+pub const FREEZE: &str = "minecraft:freeze";
+pub const CACTUS: &str = "minecraft:cactus";
+pub const SWEET_BERRY_BUSH: &str = "minecraft:sweet_berry_bush";
+pub const MAGIC: &str = "minecraft:magic";
+/// Magic of an attacker, like a thrown potion of harming
+pub const INDIRECT_MAGIC: &str = "minecraft:indirect_magic";
+
+/// How long an attacker gets the credit for a kill after their last hit, in ticks
+pub const KILL_CREDIT_TICKS: u32 = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DamageSource {
+    /// An entry of `minecraft:damage_type`, e.g. [`FALL`]
+    pub damage_type: &'static str,
+    /// The entity responsible for the damage, e.g. the player who hit
+    pub attacker: Option<EntityId>,
+}
+
+impl DamageSource {
+    #[must_use]
+    pub const fn new(damage_type: &'static str) -> Self {
+        Self {
+            damage_type,
+            attacker: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn by(damage_type: &'static str, attacker: EntityId) -> Self {
+        Self {
+            damage_type,
+            attacker: Some(attacker),
+        }
+    }
+
+    /// The network id of the damage type, 0 if it is unknown
+    #[must_use]
+    pub fn type_id(&self) -> i32 {
+        get_damage_type(self.damage_type).map_or(0, |(id, _)| i32::from(id))
+    }
+
+    /// What everyone is told when the victim died of this, like vanilla builds it. `killer` is
+    /// the attacker, or whoever hit the victim last if the damage had no attacker
+    #[must_use]
+    pub fn death_message(
+        &self,
+        victim: TextComponent<'static>,
+        killer: Option<TextComponent<'static>>,
+    ) -> TextComponent<'static> {
+        let Some((_, damage_type)) = get_damage_type(self.damage_type) else {
+            return TextComponent::translate("death.attack.generic", vec![victim]);
+        };
+        match (damage_type.death_message_type(), killer) {
+            (Some("fall_variants"), None) => {
+                TextComponent::translate("death.fell.accident.generic", vec![victim])
+            }
+            (Some("fall_variants"), Some(killer)) => {
+                TextComponent::translate("death.fell.assist", vec![victim, killer])
+            }
+            (Some("intentional_game_design"), _) => TextComponent::translate(
+                "death.attack.badRespawnPoint.message",
+                vec![
+                    victim,
+                    TextComponent::text("[")
+                        .add_child(TextComponent::translate(
+                            "death.attack.badRespawnPoint.link",
+                            vec![],
+                        ))
+                        .add_child(TextComponent::text("]")),
+                ],
+            ),
+            (_, Some(killer)) if self.attacker.is_some() => TextComponent::translate(
+                format!("death.attack.{}", damage_type.message_id()),
+                vec![victim, killer],
+            ),
+            (_, Some(killer)) => TextComponent::translate(
+                format!("death.attack.{}.player", damage_type.message_id()),
+                vec![victim, killer],
+            ),
+            (_, None) => TextComponent::translate(
+                format!("death.attack.{}", damage_type.message_id()),
+                vec![victim],
+            ),
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! The metadata of an entity which the client knows about, like its pose or custom name.
+//!
+//! Every entry is defined with its default value when the entity is created. Changed entries are
+//! marked dirty and sent to everyone seeing the entity once per tick, while players starting to see
+//! the entity get every entry which is not at its default.
+
+use pumpkin_core::text::TextComponent;
+use pumpkin_protocol::client::play::{Metadata, MetadataValue, Particle};
+use pumpkin_world::item::ItemStack;
+use uuid::Uuid;
+
+/// An entry of the metadata, the index depends on the entity type
+pub struct TrackedData<T> {
+    pub index: u8,
+    wrap: fn(T) -> MetadataValue,
+}
+
+impl<T> TrackedData<T> {
+    pub const fn new(index: u8, wrap: fn(T) -> MetadataValue) -> Self {
+        Self { index, wrap }
+    }
+}
+
+// Entity
+/// See [`Flag`](super::Flag)
+pub const FLAGS: TrackedData<i8> = TrackedData::new(0, MetadataValue::Byte);
+pub const AIR_SUPPLY: TrackedData<i32> = TrackedData::new(1, MetadataValue::VarInt);
+pub const CUSTOM_NAME: TrackedData<Option<TextComponent<'static>>> =
+    TrackedData::new(2, MetadataValue::OptionalTextComponent);
+pub const CUSTOM_NAME_VISIBLE: TrackedData<bool> = TrackedData::new(3, MetadataValue::Boolean);
+pub const SILENT: TrackedData<bool> = TrackedData::new(4, MetadataValue::Boolean);
+pub const NO_GRAVITY: TrackedData<bool> = TrackedData::new(5, MetadataValue::Boolean);
+pub const POSE: TrackedData<i32> = TrackedData::new(6, MetadataValue::Pose);
+pub const TICKS_FROZEN: TrackedData<i32> = TrackedData::new(7, MetadataValue::VarInt);
+
+// LivingEntity
+pub const LIVING_FLAGS: TrackedData<i8> = TrackedData::new(8, MetadataValue::Byte);
+pub const HEALTH: TrackedData<f32> = TrackedData::new(9, MetadataValue::Float);
+pub const ARROW_COUNT: TrackedData<i32> = TrackedData::new(12, MetadataValue::VarInt);
+pub const STINGER_COUNT: TrackedData<i32> = TrackedData::new(13, MetadataValue::VarInt);
+
+// Mob
+/// 0x04 is aggressive, skeletons raise their bow while they are
+pub const MOB_FLAGS: TrackedData<i8> = TrackedData::new(15, MetadataValue::Byte);
+
+// Player
+pub const ADDITIONAL_HEARTS: TrackedData<f32> = TrackedData::new(15, MetadataValue::Float);
+pub const SCORE: TrackedData<i32> = TrackedData::new(16, MetadataValue::VarInt);
+pub const SKIN_PARTS: TrackedData<i8> = TrackedData::new(17, MetadataValue::Byte);
+/// 0 is left, 1 is right
+pub const MAIN_HAND: TrackedData<i8> = TrackedData::new(18, MetadataValue::Byte);
+
+// Warden
+/// How angry the warden is at its most hated target, makes the heart beat faster
+pub const WARDEN_ANGER: TrackedData<i32> = TrackedData::new(16, MetadataValue::VarInt);
+
+// Spider
+/// 0x01 is climbing
+pub const SPIDER_FLAGS: TrackedData<i8> = TrackedData::new(16, MetadataValue::Byte);
+
+// EnderMan
+/// The state of the block it carries
+pub const ENDERMAN_CARRIED_BLOCK: TrackedData<Option<i32>> =
+    TrackedData::new(16, MetadataValue::OptionalBlockState);
+/// Screaming endermen open their mouth and shake
+pub const ENDERMAN_SCREAMING: TrackedData<bool> = TrackedData::new(17, MetadataValue::Boolean);
+/// Makes the client play the stare sound
+pub const ENDERMAN_STARED_AT: TrackedData<bool> = TrackedData::new(18, MetadataValue::Boolean);
+
+// Phantom
+pub const PHANTOM_SIZE: TrackedData<i32> = TrackedData::new(16, MetadataValue::VarInt);
+
+// AgeableMob and Zombie
+pub const BABY: TrackedData<bool> = TrackedData::new(16, MetadataValue::Boolean);
+
+// TamableAnimal
+/// 0x01 is sitting, 0x04 tamed
+pub const TAMEABLE_FLAGS: TrackedData<i8> = TrackedData::new(17, MetadataValue::Byte);
+pub const TAMEABLE_OWNER: TrackedData<Option<Uuid>> =
+    TrackedData::new(18, MetadataValue::OptionalUuid);
+
+// Wolf
+/// The dye color of the collar
+pub const WOLF_COLLAR: TrackedData<i32> = TrackedData::new(20, MetadataValue::VarInt);
+/// Ticks the wolf stays angry, angry wolves show their teeth
+pub const WOLF_ANGER: TrackedData<i32> = TrackedData::new(21, MetadataValue::VarInt);
+
+// Cat
+pub const CAT_COLLAR: TrackedData<i32> = TrackedData::new(22, MetadataValue::VarInt);
+
+// Parrot
+pub const PARROT_VARIANT: TrackedData<i32> = TrackedData::new(19, MetadataValue::VarInt);
+
+// AbstractHorse
+/// 0x02 is tamed, 0x04 saddled, 0x08 bred and 0x20 rearing
+pub const HORSE_FLAGS: TrackedData<i8> = TrackedData::new(17, MetadataValue::Byte);
+
+// Horse
+/// The color, plus the markings times 256
+pub const HORSE_VARIANT: TrackedData<i32> = TrackedData::new(18, MetadataValue::VarInt);
+
+// Camel
+/// Camels dash forward when the player riding them jumps
+pub const CAMEL_DASH: TrackedData<bool> = TrackedData::new(18, MetadataValue::Boolean);
+
+// Llama
+/// Creamy, white, brown and gray
+pub const LLAMA_VARIANT: TrackedData<i32> = TrackedData::new(21, MetadataValue::VarInt);
+
+// Pillager
+/// Pillagers hold up their crossbow while they charge it
+pub const PILLAGER_CHARGING: TrackedData<bool> = TrackedData::new(17, MetadataValue::Boolean);
+
+// Strider
+/// Ticks a warped fungus on a stick makes it faster
+pub const STRIDER_BOOST_TIME: TrackedData<i32> = TrackedData::new(17, MetadataValue::VarInt);
+/// Striders shiver out of lava
+pub const STRIDER_SUFFOCATING: TrackedData<bool> = TrackedData::new(18, MetadataValue::Boolean);
+pub const STRIDER_SADDLED: TrackedData<bool> = TrackedData::new(19, MetadataValue::Boolean);
+
+// Villager
+/// Ticks the villager keeps shaking its head
+pub const VILLAGER_HEAD_SHAKE: TrackedData<i32> = TrackedData::new(17, MetadataValue::VarInt);
+/// The biome type, profession and level
+pub const VILLAGER_DATA: TrackedData<[i32; 3]> = TrackedData::new(18, MetadataValue::VillagerData);
+
+// ZombieVillager
+/// Makes the client shake the zombie villager while it is cured
+pub const ZOMBIE_VILLAGER_CONVERTING: TrackedData<bool> =
+    TrackedData::new(19, MetadataValue::Boolean);
+pub const ZOMBIE_VILLAGER_DATA: TrackedData<[i32; 3]> =
+    TrackedData::new(20, MetadataValue::VillagerData);
+
+// ItemEntity and ThrownPotion
+/// None is an empty stack
+pub const ITEM: TrackedData<Option<ItemStack>> = TrackedData::new(8, MetadataValue::ItemStack);
+
+// AreaEffectCloud
+pub const CLOUD_RADIUS: TrackedData<f32> = TrackedData::new(8, MetadataValue::Float);
+/// Waiting clouds don't affect entities yet
+pub const CLOUD_WAITING: TrackedData<bool> = TrackedData::new(9, MetadataValue::Boolean);
+pub const CLOUD_PARTICLE: TrackedData<Particle> = TrackedData::new(10, MetadataValue::Particle);
+
+struct Entry {
+    value: MetadataValue,
+    default: MetadataValue,
+    dirty: bool,
+}
+
+/// The entries of an entity's metadata
+#[derive(Default)]
+pub struct DataTracker {
+    /// By index
+    entries: Vec<Option<Entry>>,
+    dirty: bool,
+}
+
+impl DataTracker {
+    /// Adds the entry with its default value, which the client already assumes.
+    pub fn define<T>(&mut self, data: &TrackedData<T>, default: T) {
+        let index = usize::from(data.index);
+        if self.entries.len() <= index {
+            self.entries.resize_with(index + 1, || None);
+        }
+        let default = (data.wrap)(default);
+        self.entries[index] = Some(Entry {
+            value: default.clone(),
+            default,
+            dirty: false,
+        });
+    }
+
+    /// Changes the entry, it is only sent if the value is different.
+    ///
+    /// # Panics
+    /// If the entry was not defined
+    pub fn set<T>(&mut self, data: &TrackedData<T>, value: T) {
+        let entry = self.entries[usize::from(data.index)]
+            .as_mut()
+            .expect("Tracked data has to be defined before it is set");
+        let value = (data.wrap)(value);
+        if entry.value != value {
+            entry.value = value;
+            entry.dirty = true;
+            self.dirty = true;
+        }
+    }
+
+    #[must_use]
+    pub fn get(&self, index: u8) -> Option<&MetadataValue> {
+        self.entries
+            .get(usize::from(index))?
+            .as_ref()
+            .map(|entry| &entry.value)
+    }
+
+    /// The entries which changed since this was last called
+    pub fn take_dirty(&mut self) -> Vec<Metadata> {
+        if !self.dirty {
+            return Vec::new();
+        }
+        self.dirty = false;
+        self.entries
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, entry)| {
+                let entry = entry.as_mut().filter(|entry| entry.dirty)?;
+                entry.dirty = false;
+                Some(Metadata::new(index as u8, entry.value.clone()))
+            })
+            .collect()
+    }
+
+    /// The entries which are not at their default, for players who start seeing the entity
+    #[must_use]
+    pub fn non_default(&self) -> Vec<Metadata> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| {
+                let entry = entry
+                    .as_ref()
+                    .filter(|entry| entry.value != entry.default)?;
+                Some(Metadata::new(index as u8, entry.value.clone()))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,53 @@
+//! Where players died last, shown by recovery compasses and `/lastdeath`.
+
+use std::sync::{Arc, LazyLock};
+
+use parking_lot::RwLock;
+use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+
+use super::player::Player;
+
+static DEATH_LISTENERS: LazyLock<RwLock<Vec<Arc<dyn DeathListener>>>> =
+    LazyLock::new(|| RwLock::new(Vec::new()));
+
+/// Lets plugins react to player deaths, e.g. to place a grave.
+pub trait DeathListener: Send + Sync {
+    /// Called once the player died, before the location is stored as the last death.
+    fn on_death(&self, player: &Player, location: &DeathLocation);
+}
+
+/// Registers a listener which is called for every player death.
+pub fn register_death_listener(listener: Arc<dyn DeathListener>) {
+    DEATH_LISTENERS.write().push(listener);
+}
+
+pub(crate) fn death_listeners() -> Vec<Arc<dyn DeathListener>> {
+    DEATH_LISTENERS.read().clone()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeathLocation {
+    /// e.g. `minecraft:overworld`
+    pub dimension: String,
+    pub position: Vector3<i32>,
+}
+
+impl DeathLocation {
+    /// The block the position is in
+    #[must_use]
+    pub fn new(dimension: String, position: Vector3<f64>) -> Self {
+        Self {
+            dimension,
+            position: Vector3::new(
+                position.x.floor() as i32,
+                position.y.floor() as i32,
+                position.z.floor() as i32,
+            ),
+        }
+    }
+
+    #[must_use]
+    pub fn world_position(&self) -> WorldPosition {
+        WorldPosition(self.position)
+    }
+}
@@ -0,0 +1,301 @@
+//! Status effects of living entities, like the regeneration a totem of undying gives.
+
+use pumpkin_entity::{effect_type::EffectType, entity_type::EntityType, EntityId};
+use pumpkin_protocol::client::play::{CRemoveMobEffect, CUpdateMobEffect};
+use pumpkin_world::item::component::PotionEffect;
+
+use super::{
+    damage::{DamageSource, INDIRECT_MAGIC, MAGIC},
+    data_tracker,
+    living::LivingEntity,
+    Flag,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StatusEffect {
+    pub effect_type: EffectType,
+    /// 0 is level I
+    pub amplifier: u8,
+    /// Ticks left, `None` lasts forever
+    pub duration: Option<u32>,
+    /// Effects from beacons and conduits, their particles are fainter
+    pub ambient: bool,
+    pub show_particles: bool,
+    pub show_icon: bool,
+}
+
+impl StatusEffect {
+    #[must_use]
+    pub const fn new(effect_type: EffectType, amplifier: u8, duration: u32) -> Self {
+        Self {
+            effect_type,
+            amplifier,
+            duration: Some(duration),
+            ambient: false,
+            show_particles: true,
+            show_icon: true,
+        }
+    }
+
+    /// The effect of a potion, `None` if the effect doesn't exist
+    #[must_use]
+    pub fn from_potion(effect: &PotionEffect) -> Option<Self> {
+        Some(Self {
+            effect_type: EffectType::from_name(&effect.effect)?,
+            amplifier: u8::try_from(effect.amplifier.clamp(0, 255)).unwrap_or(u8::MAX),
+            duration: u32::try_from(effect.duration).ok(),
+            ambient: effect.ambient,
+            show_particles: effect.show_particles,
+            show_icon: effect.show_icon,
+        })
+    }
+
+    /// The effect as it is saved, like the effect of a potion
+    #[must_use]
+    pub fn to_potion(&self) -> PotionEffect {
+        PotionEffect {
+            effect: self.effect_type.name().to_string(),
+            amplifier: i32::from(self.amplifier),
+            duration: self
+                .duration
+                .map_or(-1, |duration| i32::try_from(duration).unwrap_or(i32::MAX)),
+            ambient: self.ambient,
+            show_particles: self.show_particles,
+            show_icon: self.show_icon,
+        }
+    }
+
+    /// Whether this effect replaces the other one of the same type, stronger and longer
+    /// effects win like in vanilla
+    fn overrides(&self, other: &Self) -> bool {
+        let longer = match (self.duration, other.duration) {
+            (_, None) => false,
+            (None, Some(_)) => true,
+            (Some(duration), Some(other)) => duration > other,
+        };
+        self.amplifier > other.amplifier || (self.amplifier == other.amplifier && longer)
+    }
+
+    fn flags(&self) -> i8 {
+        i8::from(self.ambient) | i8::from(self.show_particles) << 1 | i8::from(self.show_icon) << 2
+    }
+
+    /// The packet showing the effect to the client
+    #[must_use]
+    pub fn packet(&self, entity_id: i32) -> CUpdateMobEffect {
+        CUpdateMobEffect::new(
+            entity_id.into(),
+            (self.effect_type as i32).into(),
+            i32::from(self.amplifier).into(),
+            self.duration
+                .map_or(-1, |duration| i32::try_from(duration).unwrap_or(i32::MAX))
+                .into(),
+            self.flags(),
+        )
+    }
+
+    /// The extra health absorption gives
+    fn absorption(&self) -> f32 {
+        4.0 * (f32::from(self.amplifier) + 1.0)
+    }
+}
+
+impl LivingEntity {
+    #[must_use]
+    pub fn effect(&self, effect_type: EffectType) -> Option<StatusEffect> {
+        self.effects.lock().get(&effect_type).copied()
+    }
+
+    #[must_use]
+    pub fn has_effect(&self, effect_type: EffectType) -> bool {
+        self.effects.lock().contains_key(&effect_type)
+    }
+
+    /// Gives the entity the effects it was saved with, before anyone sees it
+    pub fn restore_effects(&self, saved: &[PotionEffect]) {
+        for effect in saved.iter().filter_map(StatusEffect::from_potion) {
+            self.effects.lock().insert(effect.effect_type, effect);
+            if effect.effect_type == EffectType::Absorption {
+                self.set_absorption(effect.absorption());
+            }
+            self.set_effect_flag(effect.effect_type, true);
+        }
+    }
+
+    /// The effects as they are saved
+    #[must_use]
+    pub fn saved_effects(&self) -> Vec<PotionEffect> {
+        self.effects
+            .lock()
+            .values()
+            .map(StatusEffect::to_potion)
+            .collect()
+    }
+
+    /// Adds the effect, unless the entity has a stronger one of the same type already.
+    /// Returns whether the effect was added
+    pub async fn add_effect(&self, effect: StatusEffect) -> bool {
+        {
+            let mut effects = self.effects.lock();
+            if effects
+                .get(&effect.effect_type)
+                .is_some_and(|current| !effect.overrides(current))
+            {
+                return false;
+            }
+            effects.insert(effect.effect_type, effect);
+        }
+        if effect.effect_type == EffectType::Absorption {
+            self.set_absorption(self.absorption.load().max(effect.absorption()));
+        }
+        self.set_effect_flag(effect.effect_type, true);
+        self.entity
+            .world
+            .broadcast_packet_all(&effect.packet(self.entity.entity_id))
+            .await;
+        true
+    }
+
+    /// Removes the effect, returns whether the entity had it
+    pub async fn remove_effect(&self, effect_type: EffectType) -> bool {
+        let Some(effect) = self.effects.lock().remove(&effect_type) else {
+            return false;
+        };
+        self.on_effect_removed(&effect).await;
+        true
+    }
+
+    pub async fn clear_effects(&self) {
+        let effects: Vec<_> = self
+            .effects
+            .lock()
+            .drain()
+            .map(|(_, effect)| effect)
+            .collect();
+        for effect in effects {
+            self.on_effect_removed(&effect).await;
+        }
+    }
+
+    async fn on_effect_removed(&self, effect: &StatusEffect) {
+        if effect.effect_type == EffectType::Absorption {
+            self.set_absorption((self.absorption.load() - effect.absorption()).max(0.0));
+        }
+        self.set_effect_flag(effect.effect_type, false);
+        self.entity
+            .world
+            .broadcast_packet_all(&CRemoveMobEffect::new(
+                self.entity.entity_id.into(),
+                (effect.effect_type as i32).into(),
+            ))
+            .await;
+    }
+
+    /// Invisible and glowing entities are drawn that way by the clients
+    fn set_effect_flag(&self, effect_type: EffectType, value: bool) {
+        match effect_type {
+            EffectType::Invisibility => self.entity.set_flag(Flag::Invisible, value),
+            EffectType::Glowing => self.entity.set_flag(Flag::Glowing, value),
+            _ => {}
+        }
+    }
+
+    /// The health above the maximum, which is lost before the health
+    pub fn set_absorption(&self, absorption: f32) {
+        self.absorption.store(absorption);
+        if self.entity.entity_type == EntityType::Player {
+            self.entity
+                .data_tracker
+                .lock()
+                .set(&data_tracker::ADDITIONAL_HEARTS, absorption);
+        }
+    }
+
+    /// Applies the effects which act every few ticks and removes the ones which ran out
+    pub async fn tick_effects(&self) {
+        let (regeneration, expired) = {
+            let mut effects = self.effects.lock();
+            let regeneration = effects
+                .get(&EffectType::Regeneration)
+                .is_some_and(|effect| {
+                    let interval = 50 >> effect.amplifier.min(5);
+                    effect
+                        .duration
+                        .is_none_or(|duration| duration % interval == 0)
+                });
+            let mut expired = Vec::new();
+            effects.retain(|_, effect| {
+                if let Some(duration) = &mut effect.duration {
+                    *duration = duration.saturating_sub(1);
+                    if *duration == 0 {
+                        expired.push(*effect);
+                        return false;
+                    }
+                }
+                true
+            });
+            (regeneration, expired)
+        };
+        if regeneration {
+            self.heal(1.0).await;
+        }
+        for effect in expired {
+            self.on_effect_removed(&effect).await;
+        }
+    }
+
+    /// Applies the effects of a potion. Splash potions scale them by how close the entity is and
+    /// clouds shorten them, `attacker` threw the potion
+    pub async fn apply_potion(
+        &self,
+        effects: &[PotionEffect],
+        duration_scale: f64,
+        instant_scale: f64,
+        attacker: Option<EntityId>,
+    ) {
+        for effect in effects.iter().filter_map(StatusEffect::from_potion) {
+            if effect.effect_type.is_instant() {
+                self.apply_instant(&effect, instant_scale, attacker).await;
+                continue;
+            }
+            let Some(duration) = effect.duration else {
+                self.add_effect(effect).await;
+                continue;
+            };
+            let duration = (f64::from(duration) * duration_scale + 0.5) as u32;
+            if duration > 20 {
+                self.add_effect(StatusEffect {
+                    duration: Some(duration),
+                    ..effect
+                })
+                .await;
+            }
+        }
+    }
+
+    /// Healing heals and harming hurts right away, like vanilla's instant effects
+    async fn apply_instant(&self, effect: &StatusEffect, scale: f64, attacker: Option<EntityId>) {
+        let level = 1 << effect.amplifier.min(28);
+        match effect.effect_type {
+            EffectType::InstantHealth => {
+                self.heal((f64::from(4 * level) * scale) as f32).await;
+            }
+            EffectType::InstantDamage => {
+                let amount = (f64::from(6 * level) * scale) as f32;
+                let source = attacker.map_or(DamageSource::new(MAGIC), |attacker| {
+                    DamageSource::by(INDIRECT_MAGIC, attacker)
+                });
+                self.damage(amount, source).await;
+            }
+            _ => {}
+        }
+    }
+
+    pub async fn heal(&self, amount: f32) {
+        let health = self.health.load();
+        let max_health = self.max_health.load();
+        if health > 0.0 && health < max_health {
+            self.set_health((health + amount).min(max_health)).await;
+        }
+    }
+}
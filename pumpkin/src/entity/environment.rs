@@ -0,0 +1,266 @@
+//! Damage living entities take from the blocks around them: drowning, fire and lava, freezing in
+//! powder snow and touching cacti or berry bushes. Fall damage is dealt when the entity moves.
+
+use pumpkin_core::math::{boundingbox::BoundingBox, position::WorldPosition, vector3::Vector3};
+use pumpkin_macros::sound;
+use pumpkin_protocol::SoundCategory;
+use pumpkin_world::{
+    block::block_registry::get_block_by_state_id,
+    game_rules::{GameRule, DROWNING_DAMAGE, FIRE_DAMAGE, FREEZE_DAMAGE},
+};
+
+use super::{
+    damage::{DamageSource, CACTUS, DROWN, FREEZE, IN_FIRE, LAVA, ON_FIRE, SWEET_BERRY_BUSH},
+    data_tracker,
+    living::LivingEntity,
+    Flag,
+};
+
+/// The air of an entity which isn't under water, in ticks
+pub const MAX_AIR: i32 = 300;
+/// How long an entity has to stand in powder snow until it freezes
+pub const TICKS_TO_FREEZE: i32 = 140;
+/// How long entities keep burning after leaving lava
+const LAVA_FIRE_TICKS: i32 = 300;
+/// How long entities keep burning after leaving fire
+const FIRE_TICKS: i32 = 160;
+
+/// What the blocks around a living entity did to it so far
+pub struct Exposure {
+    /// Ticks the entity keeps burning
+    pub fire_ticks: i32,
+    /// Ticks the entity can stay under water, it drowns below 0
+    pub air: i32,
+    /// Ticks the entity spent in powder snow, it freezes at [`TICKS_TO_FREEZE`]
+    pub frozen_ticks: i32,
+    ticks: u32,
+}
+
+impl Default for Exposure {
+    fn default() -> Self {
+        Self {
+            fire_ticks: 0,
+            air: MAX_AIR,
+            frozen_ticks: 0,
+            ticks: 0,
+        }
+    }
+}
+
+/// The blocks an entity is inside of
+#[derive(Default)]
+struct Surroundings {
+    water: bool,
+    lava: bool,
+    /// The damage of the fire, soul fire burns more
+    fire: Option<f32>,
+    powder_snow: bool,
+    cactus: bool,
+    /// Only grown bushes hurt
+    berry_bush: bool,
+    eyes_in_water: bool,
+}
+
+fn is_water(name: &str, properties: &[(&str, &str)]) -> bool {
+    matches!(name, "minecraft:water" | "minecraft:bubble_column")
+        || properties.contains(&("waterlogged", "true"))
+}
+
+/// Whether the block state is water or a block in water
+pub fn is_water_state(state_id: u16) -> bool {
+    get_block_by_state_id(state_id).is_some_and(|block| {
+        is_water(
+            &block.name,
+            &block.properties_of_state(state_id).unwrap_or_default(),
+        )
+    })
+}
+
+impl LivingEntity {
+    /// Sets the entity on fire for the ticks, unless it already burns longer
+    pub fn set_on_fire(&self, ticks: i32) {
+        let mut exposure = self.exposure.lock();
+        exposure.fire_ticks = exposure.fire_ticks.max(ticks);
+    }
+
+    /// Whether the entity stands in water
+    pub async fn is_in_water(&self) -> bool {
+        let position = self.entity.block_pos.load();
+        self.entity
+            .world
+            .get_block_state_id(position)
+            .await
+            .is_ok_and(is_water_state)
+    }
+
+    async fn surroundings(&self) -> Surroundings {
+        const EPSILON: f64 = 1.0E-3;
+        let entity = &self.entity;
+        let pos = entity.pos.load();
+        let area = BoundingBox::new_from_pos(pos.x, pos.y, pos.z, &entity.bounding_box_size.load());
+        let min = Vector3::new(
+            (area.min_x + EPSILON).floor() as i32,
+            (area.min_y + EPSILON).floor() as i32,
+            (area.min_z + EPSILON).floor() as i32,
+        );
+        let max = Vector3::new(
+            (area.max_x - EPSILON).floor() as i32,
+            (area.max_y - EPSILON).floor() as i32,
+            (area.max_z - EPSILON).floor() as i32,
+        );
+        let eyes = WorldPosition(Vector3::new(
+            pos.x.floor() as i32,
+            (pos.y + f64::from(entity.standing_eye_height)).floor() as i32,
+            pos.z.floor() as i32,
+        ));
+
+        let mut surroundings = Surroundings::default();
+        for (position, state_id) in entity.world.get_block_state_ids_in(min, max).await {
+            let Some(block) = get_block_by_state_id(state_id) else {
+                continue;
+            };
+            let properties = block.properties_of_state(state_id).unwrap_or_default();
+            let water = is_water(&block.name, &properties);
+            surroundings.water |= water;
+            surroundings.eyes_in_water |= water && position == eyes;
+            match block.name.as_str() {
+                "minecraft:lava" => surroundings.lava = true,
+                "minecraft:fire" => surroundings.fire = Some(1.0),
+                "minecraft:soul_fire" => surroundings.fire = Some(2.0),
+                "minecraft:powder_snow" => surroundings.powder_snow = true,
+                "minecraft:cactus" => surroundings.cactus = true,
+                "minecraft:sweet_berry_bush" => {
+                    surroundings.berry_bush |= !properties.contains(&("age", "0"));
+                }
+                _ => {}
+            }
+        }
+        surroundings
+    }
+
+    /// Damages the entity unless the game rule turns the kind of damage off or it is invulnerable
+    /// for a few ticks after the last damage
+    async fn hurt_by(&self, amount: f32, damage_type: &'static str, rule: Option<&GameRule<bool>>) {
+        if let Some(rule) = rule {
+            if !self.entity.world.game_rules.read().await.get(rule) {
+                return;
+            }
+        }
+        if self.check_damage(amount) {
+            self.damage(amount, DamageSource::new(damage_type)).await;
+        }
+    }
+
+    /// Called every tick. Invulnerable entities, like players in creative mode, aren't hurt but
+    /// still burn, freeze and run out of air. Fire immune entities, like striders, neither burn
+    /// nor take damage from fire and lava. Freeze immune entities, e.g. wearing leather armor,
+    /// don't take damage from freezing
+    pub async fn tick_environment(
+        &self,
+        invulnerable: bool,
+        fire_immune: bool,
+        freeze_immune: bool,
+    ) {
+        let surroundings = self.surroundings().await;
+        let entity = &self.entity;
+
+        if surroundings.water || surroundings.lava {
+            self.fall_distance.store(0.0);
+        }
+        if fire_immune {
+            self.exposure.lock().fire_ticks = 0;
+        } else if surroundings.lava {
+            self.set_on_fire(LAVA_FIRE_TICKS);
+        } else if surroundings.fire.is_some() {
+            self.set_on_fire(FIRE_TICKS);
+        }
+
+        let (extinguished, air, frozen_ticks, burning, drowning, freezing) = {
+            let mut exposure = self.exposure.lock();
+            exposure.ticks = exposure.ticks.wrapping_add(1);
+
+            let extinguished =
+                exposure.fire_ticks > 0 && (surroundings.water || surroundings.powder_snow);
+            if extinguished {
+                exposure.fire_ticks = 0;
+            }
+            let burning = exposure.fire_ticks > 0
+                && exposure.fire_ticks % 20 == 0
+                && !surroundings.lava
+                && surroundings.fire.is_none();
+            exposure.fire_ticks = (exposure.fire_ticks - 1).max(0);
+
+            let mut drowning = false;
+            if surroundings.eyes_in_water {
+                exposure.air -= 1;
+                if exposure.air <= -20 {
+                    exposure.air = 0;
+                    drowning = true;
+                }
+            } else {
+                exposure.air = (exposure.air + 4).min(MAX_AIR);
+            }
+
+            exposure.frozen_ticks = if surroundings.powder_snow {
+                (exposure.frozen_ticks + 1).min(TICKS_TO_FREEZE)
+            } else {
+                (exposure.frozen_ticks - 2).max(0)
+            };
+            let freezing = exposure.frozen_ticks >= TICKS_TO_FREEZE && exposure.ticks % 40 == 0;
+
+            (
+                extinguished,
+                exposure.air,
+                exposure.frozen_ticks,
+                burning,
+                drowning,
+                freezing,
+            )
+        };
+
+        {
+            let mut tracker = entity.data_tracker.lock();
+            tracker.set(&data_tracker::AIR_SUPPLY, air);
+            tracker.set(&data_tracker::TICKS_FROZEN, frozen_ticks);
+        }
+        entity.set_flag(Flag::OnFire, self.exposure.lock().fire_ticks > 0);
+        if extinguished {
+            entity
+                .world
+                .play_sound(
+                    sound!("minecraft:entity.generic.extinguish_fire"),
+                    SoundCategory::Neutral,
+                    &entity.pos.load(),
+                )
+                .await;
+        }
+
+        if invulnerable {
+            return;
+        }
+        if surroundings.lava && !fire_immune {
+            self.hurt_by(4.0, LAVA, Some(&FIRE_DAMAGE)).await;
+        }
+        if let Some(damage) = surroundings.fire.filter(|_| !fire_immune) {
+            self.hurt_by(damage, IN_FIRE, Some(&FIRE_DAMAGE)).await;
+        }
+        if burning {
+            self.hurt_by(1.0, ON_FIRE, Some(&FIRE_DAMAGE)).await;
+        }
+        if drowning {
+            self.hurt_by(2.0, DROWN, Some(&DROWNING_DAMAGE)).await;
+        }
+        if freezing && !freeze_immune {
+            self.hurt_by(1.0, FREEZE, Some(&FREEZE_DAMAGE)).await;
+        }
+        if surroundings.cactus {
+            self.hurt_by(1.0, CACTUS, None).await;
+        }
+        if surroundings.berry_bush {
+            let moved = entity.pos.load().sub(&self.last_pos.load());
+            if moved.x.abs() >= 0.003 || moved.z.abs() >= 0.003 {
+                self.hurt_by(1.0, SWEET_BERRY_BUSH, None).await;
+            }
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! The experience of players, collected from orbs and lost when dying.
+
+use pumpkin_protocol::client::play::CSetExperience;
+
+use super::player::Player;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Experience {
+    pub level: i32,
+    /// Towards the next level, from 0 to 1
+    pub progress: f32,
+    /// The points collected since the last death
+    pub total: i32,
+}
+
+impl Experience {
+    /// The points the level needs to reach the next one
+    #[must_use]
+    pub const fn points_for_level(level: i32) -> i32 {
+        if level >= 30 {
+            112 + (level - 30) * 9
+        } else if level >= 15 {
+            37 + (level - 15) * 5
+        } else {
+            7 + level * 2
+        }
+    }
+
+    /// Adds the points, negative points are taken away, like vanilla counts them
+    #[expect(clippy::cast_precision_loss)]
+    pub fn add_points(&mut self, points: i32) {
+        self.progress += points as f32 / Self::points_for_level(self.level) as f32;
+        self.total = self.total.saturating_add(points).max(0);
+        while self.progress < 0.0 {
+            let left = self.progress * Self::points_for_level(self.level) as f32;
+            if self.level > 0 {
+                self.level -= 1;
+                self.progress = 1.0 + left / Self::points_for_level(self.level) as f32;
+            } else {
+                self.progress = 0.0;
+            }
+        }
+        while self.progress >= 1.0 {
+            let left = (self.progress - 1.0) * Self::points_for_level(self.level) as f32;
+            self.level += 1;
+            self.progress = left / Self::points_for_level(self.level) as f32;
+        }
+    }
+
+    /// The points dropped as orbs when the player dies
+    #[must_use]
+    pub fn dropped_on_death(&self) -> i32 {
+        (self.level * 7).min(100)
+    }
+}
+
+impl Player {
+    #[must_use]
+    pub fn experience(&self) -> Experience {
+        *self.experience.lock()
+    }
+
+    pub async fn set_experience(&self, experience: Experience) {
+        *self.experience.lock() = experience;
+        self.send_experience().await;
+    }
+
+    /// Gives the player experience points, or takes them away if negative
+    pub async fn add_experience_points(&self, points: i32) {
+        self.experience.lock().add_points(points);
+        self.send_experience().await;
+    }
+
+    pub async fn send_experience(&self) {
+        let experience = self.experience();
+        self.client
+            .send_packet(&CSetExperience::new(
+                experience.progress,
+                experience.level.into(),
+                experience.total.into(),
+            ))
+            .await;
+    }
+}
@@ -0,0 +1,202 @@
+//! Experience orbs, which fly to the nearest player and give it their points.
+
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+};
+
+use async_trait::async_trait;
+use crossbeam::atomic::AtomicCell;
+use pumpkin_core::{
+    math::{
+        boundingbox::{BoundingBox, BoundingBoxSize},
+        vector3::Vector3,
+    },
+    GameMode,
+};
+use pumpkin_entity::entity_type::EntityType;
+use pumpkin_macros::sound;
+use pumpkin_protocol::{
+    client::play::{CSpawnExperienceOrb, CTakeItemEntity},
+    packet_encoder::PreparedPacket,
+    SoundCategory,
+};
+use pumpkin_world::chunk::entities::ChunkEntity;
+use rand::Rng;
+
+use crate::world::{chunk_entities::saved_entity, World};
+
+use super::{new_entity_id, Entity, EntityBase};
+
+const GRAVITY: f64 = 0.03;
+/// Orbs despawn after 5 minutes
+const DESPAWN_AGE: u32 = 6000;
+/// How far away orbs notice players
+const ATTRACTION_RANGE: f64 = 8.0;
+
+/// The sizes orbs come in, the client picks the texture by the points
+const ORB_SIZES: [i32; 11] = [2477, 1237, 617, 307, 149, 73, 37, 17, 7, 3, 1];
+
+pub struct ExperienceOrb {
+    pub entity: Entity,
+    amount: i32,
+    age: AtomicU32,
+}
+
+impl ExperienceOrb {
+    pub fn new(world: Arc<World>, position: Vector3<f64>, amount: i32) -> Self {
+        let size = BoundingBoxSize {
+            width: 0.5,
+            height: 0.5,
+        };
+        let entity = Entity::new(
+            new_entity_id(),
+            world,
+            EntityType::ExperienceOrb,
+            0.0,
+            AtomicCell::new(BoundingBox::new_from_pos(
+                position.x, position.y, position.z, &size,
+            )),
+            AtomicCell::new(size),
+        );
+        entity.set_pos(position.x, position.y, position.z);
+        Self {
+            entity,
+            amount,
+            age: AtomicU32::new(0),
+        }
+    }
+
+    /// Spawns orbs worth the points at the position, split into the sizes vanilla uses
+    pub async fn spawn(world: &Arc<World>, position: Vector3<f64>, mut points: i32) {
+        while points > 0 {
+            let size = ORB_SIZES
+                .into_iter()
+                .find(|size| *size <= points)
+                .unwrap_or(1);
+            points -= size;
+            let orb = Self::new(world.clone(), position, size);
+            let mut rng = rand::thread_rng();
+            orb.entity.velocity.store(Vector3::new(
+                (rng.gen::<f64>() * 0.2 - 0.1) * 2.0,
+                rng.gen::<f64>() * 0.2 * 2.0,
+                (rng.gen::<f64>() * 0.2 - 0.1) * 2.0,
+            ));
+            world.spawn_entity(Arc::new(orb)).await;
+        }
+    }
+
+    /// Flies towards the nearest player, gives it the points once they touch.
+    /// Returns false once the orb was picked up
+    async fn follow_players(&self) -> bool {
+        let entity = &self.entity;
+        let pos = entity.pos.load();
+        let players = entity.world.players().await;
+        let Some(target) = players
+            .iter()
+            .filter(|player| {
+                player.gamemode.load() != GameMode::Spectator
+                    && player.living_entity.health.load() > 0.0
+            })
+            .map(|player| {
+                let eyes = player.living_entity.entity.pos.load().add(&Vector3::new(
+                    0.0,
+                    f64::from(player.living_entity.entity.standing_eye_height) / 2.0,
+                    0.0,
+                ));
+                (player, eyes.sub(&pos))
+            })
+            .filter(|(_, offset)| offset.length_squared() < ATTRACTION_RANGE * ATTRACTION_RANGE)
+            .min_by(|(_, a), (_, b)| a.length_squared().total_cmp(&b.length_squared()))
+        else {
+            return true;
+        };
+        let (player, offset) = target;
+
+        let player_pos = player.living_entity.entity.pos.load();
+        let player_box = BoundingBox::new_from_pos(
+            player_pos.x,
+            player_pos.y,
+            player_pos.z,
+            &player.living_entity.entity.bounding_box_size.load(),
+        );
+        let area = BoundingBox::new_from_pos(pos.x, pos.y, pos.z, &entity.bounding_box_size.load());
+        if player_box.intersects(&area) {
+            entity
+                .world
+                .broadcast_packet_viewers(
+                    player.entity_id(),
+                    &CTakeItemEntity::new(
+                        entity.entity_id.into(),
+                        player.entity_id().into(),
+                        1.into(),
+                    ),
+                )
+                .await;
+            entity
+                .world
+                .play_sound(
+                    sound!("minecraft:entity.experience_orb.pickup"),
+                    SoundCategory::Players,
+                    &player_pos,
+                )
+                .await;
+            player.add_experience_points(self.amount).await;
+            return false;
+        }
+
+        let closeness = 1.0 - offset.length() / ATTRACTION_RANGE;
+        let pull = offset.normalize() * (closeness * closeness * 0.1);
+        entity.velocity.store(entity.velocity.load().add(&pull));
+        true
+    }
+}
+
+#[async_trait]
+impl EntityBase for ExperienceOrb {
+    fn get_entity(&self) -> &Entity {
+        &self.entity
+    }
+
+    fn chunk_entity(&self) -> Option<ChunkEntity> {
+        Some(ChunkEntity {
+            amount: self.amount.unsigned_abs(),
+            ..saved_entity(&self.entity)
+        })
+    }
+
+    async fn tick(&self) -> bool {
+        if self.age.fetch_add(1, Ordering::Relaxed) + 1 >= DESPAWN_AGE {
+            return false;
+        }
+        let entity = &self.entity;
+        let mut velocity = entity.velocity.load();
+        velocity.y -= GRAVITY;
+        entity.velocity.store(velocity);
+        if !self.follow_players().await {
+            return false;
+        }
+        let mut velocity = entity.move_colliding(entity.velocity.load()).await;
+        let friction = if entity.on_ground.load(Ordering::Relaxed) {
+            0.6 * 0.98
+        } else {
+            0.98
+        };
+        velocity = velocity.multiply(friction, 0.98, friction);
+        if entity.on_ground.load(Ordering::Relaxed) {
+            velocity.y *= -0.9;
+        }
+        entity.velocity.store(velocity);
+        true
+    }
+
+    fn spawn_packet(&self, position: Vector3<f64>) -> PreparedPacket {
+        PreparedPacket::new(&CSpawnExperienceOrb::new(
+            self.entity.entity_id.into(),
+            position.x,
+            position.y,
+            position.z,
+            i16::try_from(self.amount).unwrap_or(i16::MAX),
+        ))
+    }
+}
@@ -0,0 +1,324 @@
+//! Items lying on the ground, like the inventory a player drops when dying, which players pick up
+//! by walking into them.
+
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+};
+
+use async_trait::async_trait;
+use crossbeam::atomic::AtomicCell;
+use pumpkin_core::{
+    math::{
+        boundingbox::{BoundingBox, BoundingBoxSize},
+        vector3::Vector3,
+    },
+    GameMode,
+};
+use pumpkin_entity::entity_type::EntityType;
+use pumpkin_macros::sound;
+use pumpkin_protocol::{
+    client::play::{CSpawnEntity, CTakeItemEntity},
+    packet_encoder::PreparedPacket,
+    SoundCategory,
+};
+use pumpkin_world::{
+    chunk::entities::ChunkEntity,
+    item::{item_registry::get_item_by_id, ItemStack},
+};
+use rand::Rng;
+use uuid::Uuid;
+
+use crate::world::{chunk_entities::saved_entity, World};
+
+use super::{data_tracker, new_entity_id, player::Player, Entity, EntityBase};
+
+const GRAVITY: f64 = 0.04;
+/// Items despawn after 5 minutes
+const DESPAWN_AGE: u32 = 6000;
+/// How long players can't pick up items they dropped, in ticks
+pub const PICKUP_DELAY: u32 = 40;
+
+pub struct ItemEntity {
+    pub entity: Entity,
+    uuid: Uuid,
+    stack: parking_lot::Mutex<ItemStack>,
+    /// Ticks until players can pick the item up
+    pickup_delay: AtomicU32,
+    age: AtomicU32,
+}
+
+impl ItemEntity {
+    pub fn new(
+        world: Arc<World>,
+        stack: ItemStack,
+        position: Vector3<f64>,
+        velocity: Vector3<f64>,
+        pickup_delay: u32,
+    ) -> Self {
+        let size = BoundingBoxSize {
+            width: 0.25,
+            height: 0.25,
+        };
+        let entity = Entity::new(
+            new_entity_id(),
+            world,
+            EntityType::Item,
+            0.2125,
+            AtomicCell::new(BoundingBox::new_from_pos(
+                position.x, position.y, position.z, &size,
+            )),
+            AtomicCell::new(size),
+        );
+        entity.set_pos(position.x, position.y, position.z);
+        entity.velocity.store(velocity);
+        {
+            let mut tracker = entity.data_tracker.lock();
+            tracker.define(&data_tracker::ITEM, None);
+            tracker.set(&data_tracker::ITEM, Some(stack.clone()));
+        }
+        Self {
+            entity,
+            uuid: Uuid::new_v4(),
+            stack: parking_lot::Mutex::new(stack),
+            pickup_delay: AtomicU32::new(pickup_delay),
+            age: AtomicU32::new(0),
+        }
+    }
+
+    /// Falls, slides and bounces like vanilla items do
+    async fn physics(&self) {
+        let entity = &self.entity;
+        let mut velocity = entity.velocity.load();
+        velocity.y -= GRAVITY;
+        velocity = entity.move_colliding(velocity).await;
+        let on_ground = entity.on_ground.load(Ordering::Relaxed);
+        let friction = if on_ground { 0.6 * 0.98 } else { 0.98 };
+        velocity = velocity.multiply(friction, 0.98, friction);
+        if on_ground && velocity.y < 0.0 {
+            velocity.y *= -0.5;
+        }
+        entity.velocity.store(velocity);
+    }
+
+    /// Gives the stack to a player walking into it, returns false once it was picked up completely
+    async fn try_pickup(&self) -> bool {
+        if self.pickup_delay.load(Ordering::Relaxed) > 0 {
+            return true;
+        }
+        let entity = &self.entity;
+        let pos = entity.pos.load();
+        let area = BoundingBox::new_from_pos(pos.x, pos.y, pos.z, &entity.bounding_box_size.load());
+        let players = entity.world.players().await;
+        for player in players.iter() {
+            if player.gamemode.load() == GameMode::Spectator
+                || player.living_entity.health.load() <= 0.0
+            {
+                continue;
+            }
+            let player_pos = player.living_entity.entity.pos.load();
+            let reach = BoundingBox::new_from_pos(
+                player_pos.x,
+                player_pos.y,
+                player_pos.z,
+                &player.living_entity.entity.bounding_box_size.load(),
+            );
+            let reach = BoundingBox {
+                min_x: reach.min_x - 1.0,
+                min_y: reach.min_y - 0.5,
+                min_z: reach.min_z - 1.0,
+                max_x: reach.max_x + 1.0,
+                max_y: reach.max_y + 0.5,
+                max_z: reach.max_z + 1.0,
+            };
+            if !reach.intersects(&area) {
+                continue;
+            }
+
+            let stack = self.stack.lock().clone();
+            let count = stack.item_count;
+            let left = player.insert_stack(stack).await;
+            let taken = count - left.as_ref().map_or(0, |left| left.item_count);
+            if taken == 0 {
+                continue;
+            }
+            player.set_container_content(None).await;
+            entity
+                .world
+                .broadcast_packet_viewers(
+                    player.entity_id(),
+                    &CTakeItemEntity::new(
+                        entity.entity_id.into(),
+                        player.entity_id().into(),
+                        i32::from(taken).into(),
+                    ),
+                )
+                .await;
+            entity
+                .world
+                .play_sound(
+                    sound!("minecraft:entity.item.pickup"),
+                    SoundCategory::Players,
+                    &player_pos,
+                )
+                .await;
+            let Some(left) = left else {
+                return false;
+            };
+            self.set_stack(left);
+        }
+        true
+    }
+
+    /// Whether nobody can pick the item up yet
+    #[must_use]
+    pub fn pickup_delayed(&self) -> bool {
+        self.pickup_delay.load(Ordering::Relaxed) > 0
+    }
+
+    #[must_use]
+    pub fn stack(&self) -> ItemStack {
+        self.stack.lock().clone()
+    }
+
+    /// Changes the stack, e.g. after a part of it was picked up
+    pub fn set_stack(&self, stack: ItemStack) {
+        let mut tracker = self.entity.data_tracker.lock();
+        // stacks are equal regardless of their count, the entry only changes without one
+        tracker.set(&data_tracker::ITEM, None);
+        tracker.set(&data_tracker::ITEM, Some(stack.clone()));
+        drop(tracker);
+        *self.stack.lock() = stack;
+    }
+}
+
+#[async_trait]
+impl EntityBase for ItemEntity {
+    fn get_entity(&self) -> &Entity {
+        &self.entity
+    }
+
+    fn get_item_entity(&self) -> Option<&ItemEntity> {
+        Some(self)
+    }
+
+    fn chunk_entity(&self) -> Option<ChunkEntity> {
+        let stack = self.stack();
+        Some(ChunkEntity {
+            amount: u32::from(stack.item_count),
+            item: Some(stack),
+            ..saved_entity(&self.entity)
+        })
+    }
+
+    async fn tick(&self) -> bool {
+        if self.age.fetch_add(1, Ordering::Relaxed) + 1 >= DESPAWN_AGE {
+            return false;
+        }
+        let _ = self
+            .pickup_delay
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |delay| {
+                delay.checked_sub(1)
+            });
+        self.physics().await;
+        if !self.try_pickup().await {
+            return false;
+        }
+        self.entity.send_data_changes().await;
+        true
+    }
+
+    fn spawn_packet(&self, position: Vector3<f64>) -> PreparedPacket {
+        let velocity = self.entity.velocity.load();
+        PreparedPacket::new(&CSpawnEntity::new(
+            self.entity.entity_id.into(),
+            self.uuid,
+            (EntityType::Item as i32).into(),
+            position.x,
+            position.y,
+            position.z,
+            0.0,
+            0.0,
+            0.0,
+            0.into(),
+            velocity.x as f32,
+            velocity.y as f32,
+            velocity.z as f32,
+        ))
+    }
+}
+
+impl Player {
+    /// Puts the stack into the inventory, onto stacks of the same item first and the hotbar
+    /// before the other slots. Returns what did not fit, the client is not sent the changes
+    pub async fn insert_stack(&self, mut stack: ItemStack) -> Option<ItemStack> {
+        let max_stack =
+            get_item_by_id(stack.item_id).map_or(64, |item| item.components.max_stack_size);
+        let mut inventory = self.inventory.lock().await;
+        for slot in inventory.slots_with_hotbar_first() {
+            let Some(item) = slot.as_mut().filter(|item| **item == stack) else {
+                continue;
+            };
+            let moved = stack
+                .item_count
+                .min(max_stack.saturating_sub(item.item_count));
+            item.item_count += moved;
+            stack.item_count -= moved;
+            if stack.item_count == 0 {
+                return None;
+            }
+        }
+        for slot in inventory.slots_with_hotbar_first() {
+            if slot.is_some() {
+                continue;
+            }
+            let moved = stack.item_count.min(max_stack);
+            let mut part = stack.clone();
+            part.item_count = moved;
+            *slot = Some(part);
+            stack.item_count -= moved;
+            if stack.item_count == 0 {
+                return None;
+            }
+        }
+        Some(stack)
+    }
+
+    /// Drops the stack from the player's eyes. Thrown items fly where the player looks,
+    /// scattered ones like the inventory on death into a random direction
+    pub async fn drop_item(&self, stack: ItemStack, scatter: bool) {
+        let entity = &self.living_entity.entity;
+        let pos = entity.pos.load();
+        let position = Vector3::new(
+            pos.x,
+            pos.y + f64::from(entity.standing_eye_height) - 0.3,
+            pos.z,
+        );
+        let mut rng = rand::thread_rng();
+        let velocity = if scatter {
+            let strength = rng.gen::<f64>() * 0.5;
+            let angle = rng.gen::<f64>() * std::f64::consts::TAU;
+            Vector3::new(-angle.sin() * strength, 0.2, angle.cos() * strength)
+        } else {
+            let yaw = f64::from(entity.yaw.load()).to_radians();
+            let pitch = f64::from(entity.pitch.load()).to_radians();
+            let spread = rng.gen::<f64>() * std::f64::consts::TAU;
+            let strength = 0.02 * rng.gen::<f64>();
+            Vector3::new(
+                (-yaw.sin() * pitch.cos()).mul_add(0.3, spread.cos() * strength),
+                (-pitch.sin()).mul_add(0.3, 0.1 + (rng.gen::<f64>() - rng.gen::<f64>()) * 0.1),
+                (yaw.cos() * pitch.cos()).mul_add(0.3, spread.sin() * strength),
+            )
+        };
+        let world = entity.world.clone();
+        world
+            .spawn_entity(Arc::new(ItemEntity::new(
+                world.clone(),
+                stack,
+                position,
+                velocity,
+                PICKUP_DELAY,
+            )))
+            .await;
+    }
+}
@@ -0,0 +1,114 @@
+//! Keep alives, which check that a player is still connected and measure their latency.
+//!
+//! The server sends one every [`interval`](pumpkin_config::KeepAliveConfig::interval), and kicks the
+//! player if it is not answered within the [`timeout`](pumpkin_config::KeepAliveConfig::timeout).
+//! The latency is the average round trip of the last answers.
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use pumpkin_config::ADVANCED_CONFIG;
+use pumpkin_core::text::TextComponent;
+use pumpkin_protocol::client::play::CKeepAlive;
+
+use super::player::Player;
+
+/// What to do for the keep alive this tick
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeepAliveTick {
+    Idle,
+    /// Send a keep alive with the id
+    Send(i64),
+    /// The client did not answer the last one in time
+    TimedOut,
+}
+
+pub struct KeepAlive {
+    /// The id of the keep alive the client did not answer yet
+    pending: Option<i64>,
+    last_sent: Instant,
+    /// The most recent round trips, in milliseconds
+    samples: VecDeque<u32>,
+    average: u32,
+}
+
+impl KeepAlive {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            pending: None,
+            last_sent: Instant::now(),
+            samples: VecDeque::new(),
+            average: 0,
+        }
+    }
+
+    pub fn tick(&mut self, now: Instant) -> KeepAliveTick {
+        let config = &ADVANCED_CONFIG.keep_alive;
+        let elapsed = now.duration_since(self.last_sent);
+        if self.pending.is_some() {
+            if elapsed >= Duration::from_secs(config.timeout) {
+                return KeepAliveTick::TimedOut;
+            }
+            return KeepAliveTick::Idle;
+        }
+        if elapsed < Duration::from_secs(config.interval) {
+            return KeepAliveTick::Idle;
+        }
+        let id = rand::random();
+        self.pending = Some(id);
+        self.last_sent = now;
+        KeepAliveTick::Send(id)
+    }
+
+    /// Takes the answer to a keep alive, returns false if it was not the one we wait for
+    pub fn answer(&mut self, id: i64) -> bool {
+        if self.pending != Some(id) {
+            return false;
+        }
+        self.pending = None;
+        let round_trip = u32::try_from(self.last_sent.elapsed().as_millis()).unwrap_or(u32::MAX);
+        self.samples.push_back(round_trip);
+        while self.samples.len() > ADVANCED_CONFIG.keep_alive.average_over.max(1) {
+            self.samples.pop_front();
+        }
+        let sum: u64 = self.samples.iter().copied().map(u64::from).sum();
+        self.average = u32::try_from(sum / self.samples.len() as u64).unwrap_or(u32::MAX);
+        true
+    }
+
+    /// The average round trip of the last answers, in milliseconds
+    #[must_use]
+    pub const fn average(&self) -> u32 {
+        self.average
+    }
+}
+
+impl Default for KeepAlive {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Player {
+    /// The average time the client takes to answer keep alives, in milliseconds
+    #[must_use]
+    pub fn latency(&self) -> u32 {
+        self.keep_alive.lock().average()
+    }
+
+    /// Sends a keep alive if it is time for one, or kicks the player if they did not answer the last one
+    pub(crate) async fn tick_keep_alive(&self, now: Instant) {
+        let tick = self.keep_alive.lock().tick(now);
+        match tick {
+            KeepAliveTick::Idle => {}
+            KeepAliveTick::Send(id) => self.client.send_packet(&CKeepAlive::new(id)).await,
+            KeepAliveTick::TimedOut => {
+                self.kick(TextComponent::translate("disconnect.timeout", vec![]))
+                    .await;
+            }
+        }
+    }
+}
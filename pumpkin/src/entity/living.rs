@@ -1,10 +1,28 @@
-use std::sync::atomic::AtomicI32;
+use std::{collections::HashMap, sync::atomic::AtomicI32};
 
 use crossbeam::atomic::AtomicCell;
+use pumpkin_api::event::{
+    entity::{EntityDamageEvent, EntityDeathEvent},
+    player::PlayerInfo,
+};
+use pumpkin_api::Event;
 use pumpkin_core::math::vector3::Vector3;
-use pumpkin_protocol::client::play::{CDamageEvent, CEntityStatus, CSetEntityMetadata, Metadata};
+use pumpkin_entity::{effect_type::EffectType, EntityId};
+use pumpkin_protocol::client::play::{CDamageEvent, CEntityStatus};
+use pumpkin_world::game_rules::FALL_DAMAGE;
 
-use super::Entity;
+use crate::{
+    plugin::{self, EVENTS},
+    world::vibration::GameEvent,
+};
+
+use super::{
+    damage::{DamageSource, FALL, GENERIC_KILL, IN_FIRE, KILL_CREDIT_TICKS, LAVA, ON_FIRE},
+    data_tracker,
+    effect::StatusEffect,
+    environment::Exposure,
+    Entity,
+};
 
 /// Represents a living entity within the game world.
 ///
@@ -20,19 +38,45 @@ pub struct LivingEntity {
     pub last_damage_taken: AtomicCell<f32>,
     /// The current health level of the entity.
     pub health: AtomicCell<f32>,
+    /// The health the entity heals up to
+    pub max_health: AtomicCell<f32>,
     /// The distance the entity has been falling
     pub fall_distance: AtomicCell<f64>,
+    /// What hurt the entity last, which decides its death message
+    pub last_damage_source: AtomicCell<Option<DamageSource>>,
+    /// The entity which hit this one last, with the ticks it keeps the credit for a kill
+    pub kill_credit: AtomicCell<Option<(EntityId, u32)>>,
+    /// How long the entity burns, its air and how frozen it is
+    pub exposure: parking_lot::Mutex<Exposure>,
+    /// The status effects the entity has, like regeneration
+    pub effects: parking_lot::Mutex<HashMap<EffectType, StatusEffect>>,
+    /// The extra health from absorption, lost before the health
+    pub absorption: AtomicCell<f32>,
 }
 
 impl LivingEntity {
-    pub const fn new(entity: Entity) -> Self {
+    pub fn new(entity: Entity) -> Self {
+        {
+            let mut tracker = entity.data_tracker.lock();
+            tracker.define(&data_tracker::LIVING_FLAGS, 0);
+            tracker.define(&data_tracker::HEALTH, 1.0);
+            tracker.define(&data_tracker::ARROW_COUNT, 0);
+            tracker.define(&data_tracker::STINGER_COUNT, 0);
+            tracker.set(&data_tracker::HEALTH, 20.0);
+        }
         Self {
             entity,
             last_pos: AtomicCell::new(Vector3::new(0.0, 0.0, 0.0)),
             time_until_regen: AtomicI32::new(0),
             last_damage_taken: AtomicCell::new(0.0),
             health: AtomicCell::new(20.0),
+            max_health: AtomicCell::new(20.0),
             fall_distance: AtomicCell::new(0.0),
+            last_damage_source: AtomicCell::new(None),
+            kill_credit: AtomicCell::new(None),
+            exposure: parking_lot::Mutex::new(Exposure::default()),
+            effects: parking_lot::Mutex::new(HashMap::new()),
+            absorption: AtomicCell::new(0.0),
         }
     }
 
@@ -45,6 +89,20 @@ impl LivingEntity {
             self.time_until_regen
                 .fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
         }
+        if let Some((attacker, ticks)) = self.kill_credit.load() {
+            self.kill_credit
+                .store(ticks.checked_sub(1).map(|ticks| (attacker, ticks)));
+        }
+    }
+
+    /// Who gets the credit for killing the entity now, the attacker of the damage or whoever
+    /// hit the entity shortly before
+    #[must_use]
+    pub fn killer(&self) -> Option<EntityId> {
+        self.last_damage_source
+            .load()
+            .and_then(|source| source.attacker)
+            .or_else(|| self.kill_credit.load().map(|(attacker, _)| attacker))
     }
 
     pub fn set_pos(&self, x: f64, y: f64, z: f64) {
@@ -52,35 +110,95 @@ impl LivingEntity {
         self.entity.set_pos(x, y, z);
     }
 
+    /// Sets the health the entity heals up to, and heals it fully. For mobs with more health than
+    /// players, before they are spawned
+    pub fn set_max_health(&self, max_health: f32) {
+        self.max_health.store(max_health);
+        self.health.store(max_health);
+        self.entity
+            .data_tracker
+            .lock()
+            .set(&data_tracker::HEALTH, max_health);
+    }
+
     pub async fn set_health(&self, health: f32) {
         self.health.store(health);
-        // tell everyone entities health changed
         self.entity
+            .data_tracker
+            .lock()
+            .set(&data_tracker::HEALTH, health);
+        // sent right away, so the client knows the health before the death animation
+        self.entity.send_data_changes().await;
+    }
+
+    /// The player, if the entity is one, for events
+    async fn player_info(&self) -> Option<PlayerInfo> {
+        let player = self
+            .entity
             .world
-            .broadcast_packet_all(&CSetEntityMetadata::new(
-                self.entity.entity_id.into(),
-                Metadata::new(9, 3.into(), health),
-            ))
-            .await;
+            .get_player_by_entityid(self.entity.entity_id)
+            .await?;
+        Some(plugin::player_info(&player))
     }
 
-    pub async fn damage(&self, amount: f32) {
+    /// Damages the entity, unless a plugin cancels it or fire resistance protects it. Absorption
+    /// takes the damage first and a totem of undying saves a player from dying
+    pub async fn damage(&self, amount: f32, source: DamageSource) {
+        if matches!(source.damage_type, IN_FIRE | ON_FIRE | LAVA)
+            && self.has_effect(EffectType::FireResistance)
+        {
+            return;
+        }
+        let amount = if EVENTS.has_listeners::<EntityDamageEvent>() {
+            let event = plugin::fire(EntityDamageEvent::new(
+                self.entity.entity_id,
+                self.player_info().await,
+                amount,
+            ));
+            if event.is_cancelled() {
+                return;
+            }
+            event.damage
+        } else {
+            amount
+        };
+
+        self.last_damage_source.store(Some(source));
+        if let Some(attacker) = source.attacker {
+            self.kill_credit.store(Some((attacker, KILL_CREDIT_TICKS)));
+        }
+        let attacker = source.attacker.map(Into::into);
         self.entity
             .world
             .broadcast_packet_all(&CDamageEvent::new(
                 self.entity.entity_id.into(),
-                // TODO add damage_type id
-                0.into(),
-                None,
-                None,
+                source.type_id().into(),
+                attacker,
+                attacker,
                 None,
             ))
             .await;
+        self.entity
+            .world
+            .emit_game_event_by(
+                GameEvent::EntityDamage,
+                self.entity.pos.load(),
+                source.attacker,
+            )
+            .await;
 
+        let absorption = self.absorption.load();
+        if absorption > 0.0 {
+            self.set_absorption((absorption - amount).max(0.0));
+        }
+        let amount = (amount - absorption).max(0.0);
         let new_health = (self.health.load() - amount).max(0.0);
 
         if new_health == 0.0 {
-            self.kill().await;
+            if source.damage_type != GENERIC_KILL && self.use_totem().await {
+                return;
+            }
+            self.die().await;
         } else {
             self.set_health(new_health).await;
         }
@@ -118,9 +236,11 @@ impl LivingEntity {
         // + => falling, - => up
         let y_diff = last_y - y;
 
-        if grounded {
+        if self.has_effect(EffectType::SlowFalling) {
+            self.fall_distance.store(0.0);
+        } else if grounded {
             let fall_distance = self.fall_distance.swap(0.0);
-            if dont_damage {
+            if dont_damage || !self.entity.world.game_rules.read().await.get(&FALL_DAMAGE) {
                 return;
             }
 
@@ -130,7 +250,7 @@ impl LivingEntity {
                 return;
             }
 
-            self.damage(damage).await;
+            self.damage(damage, DamageSource::new(FALL)).await;
         } else if y_diff < 0.0 {
             self.fall_distance.store(0.0);
         } else {
@@ -143,7 +263,19 @@ impl LivingEntity {
     ///
     /// This is similar to `kill` but Spawn Particles, Animation and plays death sound
     pub async fn kill(&self) {
+        self.last_damage_source
+            .store(Some(DamageSource::new(GENERIC_KILL)));
+        self.die().await;
+    }
+
+    async fn die(&self) {
         self.set_health(0.0).await;
+        if EVENTS.has_listeners::<EntityDeathEvent>() {
+            plugin::fire(EntityDeathEvent {
+                entity_id: self.entity.entity_id,
+                player: self.player_info().await,
+            });
+        }
 
         // Spawns death smoke particles
         self.entity
@@ -155,5 +287,13 @@ impl LivingEntity {
             .world
             .broadcast_packet_all(&CEntityStatus::new(self.entity.entity_id, 3))
             .await;
+        self.entity
+            .world
+            .emit_game_event_by(
+                GameEvent::EntityDie,
+                self.entity.pos.load(),
+                Some(self.entity.entity_id),
+            )
+            .await;
     }
 }
@@ -0,0 +1,88 @@
+//! The age of mobs which grow up. Babies count up to zero and become adults, adults which just bred
+//! count down to zero until they can breed again.
+
+use std::{
+    cmp::Ordering as CmpOrdering,
+    sync::atomic::{AtomicI32, Ordering},
+};
+
+use pumpkin_core::math::boundingbox::{BoundingBox, BoundingBoxSize};
+
+use crate::entity::data_tracker;
+
+use super::Mob;
+
+/// Babies take 20 minutes to grow up
+const BABY_AGE: i32 = -24000;
+/// Adults can breed again after 5 minutes
+const BREED_COOLDOWN: i32 = 6000;
+/// Feeding a baby takes this part of the time it has left off
+const GROWTH_PER_FEEDING: i32 = 10;
+
+pub struct Age(AtomicI32);
+
+impl Age {
+    #[must_use]
+    pub const fn new(baby: bool) -> Self {
+        Self(AtomicI32::new(if baby { BABY_AGE } else { 0 }))
+    }
+
+    #[must_use]
+    pub fn is_baby(&self) -> bool {
+        self.0.load(Ordering::Relaxed) < 0
+    }
+
+    /// Adults which didn't breed for a while
+    #[must_use]
+    pub fn can_breed(&self) -> bool {
+        self.0.load(Ordering::Relaxed) == 0
+    }
+
+    pub fn start_breed_cooldown(&self) {
+        self.0.store(BREED_COOLDOWN, Ordering::Relaxed);
+    }
+
+    /// Lets a baby grow up a tenth of the time it has left faster, like when it is fed
+    pub fn grow(&self) {
+        let _ = self
+            .0
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |age| {
+                (age < 0).then_some(age - age / GROWTH_PER_FEEDING)
+            });
+    }
+
+    /// Gets a tick older, returns true if the baby grew up just now
+    pub fn tick(&self) -> bool {
+        let age = self.0.load(Ordering::Relaxed);
+        match age.cmp(&0) {
+            CmpOrdering::Less => {
+                self.0.store(age + 1, Ordering::Relaxed);
+                age + 1 == 0
+            }
+            CmpOrdering::Greater => {
+                self.0.store(age - 1, Ordering::Relaxed);
+                false
+            }
+            CmpOrdering::Equal => false,
+        }
+    }
+}
+
+/// Shows the mob as a baby or an adult of the size, babies are half as big
+pub fn set_baby(mob: &Mob, baby: bool, adult_size: BoundingBoxSize) {
+    let entity = &mob.living_entity.entity;
+    let size = if baby {
+        BoundingBoxSize {
+            width: adult_size.width / 2.0,
+            height: adult_size.height / 2.0,
+        }
+    } else {
+        adult_size
+    };
+    let pos = entity.pos.load();
+    entity.bounding_box_size.store(size);
+    entity
+        .bounding_box
+        .store(BoundingBox::new_from_pos(pos.x, pos.y, pos.z, &size));
+    entity.data_tracker.lock().set(&data_tracker::BABY, baby);
+}
@@ -0,0 +1,506 @@
+//! Animals which players breed. Adults fed their food fall in love for a while, walk up to another
+//! one in love and make a baby together. Feeding a baby lets it grow up faster.
+//!
+//! Cows, pigs, sheep and chickens don't do much besides that, they follow players holding their
+//! food and run away when they are hurt.
+
+use std::{any::Any, sync::Arc};
+
+use async_trait::async_trait;
+use pumpkin_core::{
+    math::{boundingbox::BoundingBoxSize, vector3::Vector3},
+    GameMode,
+};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_macros::sound;
+use rand::{thread_rng, Rng};
+
+use crate::{
+    entity::{
+        data_tracker,
+        experience_orb::ExperienceOrb,
+        player::{Hand, Player},
+        EntityBase,
+    },
+    world::World,
+};
+
+use super::{
+    ageable::{set_baby, Age},
+    attacker_pos, mob_entity, mobs_near, play_sound, show_status, spawn_ageable, Mob, MobAi,
+};
+
+/// Ticks an animal stays in love after it was fed
+const LOVE_TICKS: u32 = 600;
+/// Shows hearts around an animal which fell in love
+const LOVE_STATUS: i8 = 18;
+/// How far animals in love look for a partner
+const PARTNER_RANGE: f64 = 8.0;
+/// Partners have to stay this close for a while to make a baby
+const BREEDING_RANGE: f64 = 3.0;
+const BREEDING_TICKS: u32 = 60;
+const MAX_BREEDING_EXPERIENCE: i32 = 7;
+/// How far animals see the food players hold
+const TEMPT_RANGE: f64 = 10.0;
+/// Tempted animals stop this close to the player
+const TEMPT_DISTANCE: f64 = 2.5;
+const MAX_EXPERIENCE: i32 = 3;
+
+#[derive(Default)]
+struct BreedingState {
+    /// Ticks it stays in love
+    love: u32,
+    /// The animal it breeds with, with the ticks they spent close together
+    partner: Option<(EntityId, u32)>,
+}
+
+/// The age of an animal and whether it is in love
+pub struct Breeding {
+    age: Age,
+    /// Whether it eats the item to fall in love
+    foods: fn(&str) -> bool,
+    adult_size: BoundingBoxSize,
+    state: parking_lot::Mutex<BreedingState>,
+}
+
+impl Breeding {
+    #[must_use]
+    pub fn new(baby: bool, foods: fn(&str) -> bool, adult_size: BoundingBoxSize) -> Self {
+        Self {
+            age: Age::new(baby),
+            foods,
+            adult_size,
+            state: parking_lot::Mutex::new(BreedingState::default()),
+        }
+    }
+
+    #[must_use]
+    pub fn is_baby(&self) -> bool {
+        self.age.is_baby()
+    }
+
+    #[must_use]
+    pub fn is_food(&self, item: &str) -> bool {
+        (self.foods)(item)
+    }
+
+    #[must_use]
+    pub fn in_love(&self) -> bool {
+        self.state.lock().love > 0
+    }
+
+    /// Lets a baby grow up faster
+    pub fn grow(&self) {
+        self.age.grow();
+    }
+
+    /// Feeds the animal the food in the player's hand, babies grow up faster and adults fall in
+    /// love. Returns false if the hand doesn't hold its food or it doesn't want to eat right now
+    pub async fn feed(&self, mob: &Mob, player: &Player, hand: Hand) -> bool {
+        let Some(food) = player
+            .held_item_name(hand)
+            .await
+            .filter(|item| self.is_food(item))
+        else {
+            return false;
+        };
+        if self.is_baby() {
+            if !player.use_held_item(hand, food).await {
+                return false;
+            }
+            self.grow();
+            return true;
+        }
+        if !self.can_fall_in_love() || !player.use_held_item(hand, food).await {
+            return false;
+        }
+        self.fall_in_love(mob).await;
+        true
+    }
+
+    /// Adults which didn't breed for a while, and aren't in love already
+    #[must_use]
+    pub fn can_fall_in_love(&self) -> bool {
+        self.age.can_breed() && !self.in_love()
+    }
+
+    pub async fn fall_in_love(&self, mob: &Mob) {
+        self.state.lock().love = LOVE_TICKS;
+        show_status(mob, LOVE_STATUS).await;
+    }
+
+    /// Gets a tick older and a tick less in love
+    pub fn tick(&self, mob: &Mob) {
+        if self.age.tick() {
+            set_baby(mob, false, self.adult_size);
+        }
+        let mut state = self.state.lock();
+        state.love = state.love.saturating_sub(1);
+        if state.love == 0 {
+            state.partner = None;
+        }
+    }
+
+    /// The partner it breeds with, another animal of its kind in love. Partners settle on each
+    /// other
+    async fn find_partner(&self, mob: &Mob) -> Option<Arc<dyn EntityBase>> {
+        let entity = &mob.living_entity.entity;
+        let id = entity.entity_id;
+        let entity_type = entity.entity_type;
+        let current = self.state.lock().partner.map(|(partner, _)| partner);
+        let candidates = mobs_near(&entity.world, entity.pos.load(), PARTNER_RANGE, |other| {
+            other == entity_type
+        })
+        .await;
+        let partner = candidates.into_iter().find(|other| {
+            let other_id = other.get_entity().entity_id;
+            other_id != id
+                && current.is_none_or(|current| current == other_id)
+                && other
+                    .get_mob()
+                    .and_then(Mob::breeding)
+                    .is_some_and(|breeding| {
+                        breeding.in_love()
+                            && breeding
+                                .state
+                                .lock()
+                                .partner
+                                .is_none_or(|(partner, _)| partner == id)
+                    })
+        })?;
+        let partner_id = partner.get_entity().entity_id;
+        if current.is_none() {
+            self.state.lock().partner = Some((partner_id, 0));
+        }
+        if let Some(breeding) = partner.get_mob().and_then(Mob::breeding) {
+            breeding.state.lock().partner.get_or_insert((id, 0));
+        }
+        Some(partner)
+    }
+
+    /// Walks to its partner while it is in love, the one with the lower id makes the baby once
+    /// they were together long enough. Returns true while it is busy breeding
+    pub async fn breed(&self, mob: &Mob, speed: f64) -> bool {
+        if !self.in_love() {
+            return false;
+        }
+        let Some(partner) = self.find_partner(mob).await else {
+            self.state.lock().partner = None;
+            return false;
+        };
+        let entity = &mob.living_entity.entity;
+        let partner_entity = partner.get_entity();
+        let partner_pos = partner_entity.pos.load();
+        mob.look_at(partner_pos);
+        if partner_pos.sub(&entity.pos.load()).length() > BREEDING_RANGE {
+            mob.walk_to(partner_pos, speed);
+            return true;
+        }
+        mob.stop_walking();
+        let together = {
+            let mut state = self.state.lock();
+            let Some((_, ticks)) = &mut state.partner else {
+                return false;
+            };
+            *ticks += 1;
+            *ticks
+        };
+        if together >= BREEDING_TICKS && entity.entity_id < partner_entity.entity_id {
+            if let Some(partner_mob) = partner.get_mob() {
+                self.finish_breeding(mob, partner_mob).await;
+            }
+        }
+        true
+    }
+
+    /// Makes the baby, which belongs to the owner of tamed parents. Breeding gives experience
+    async fn finish_breeding(&self, mob: &Mob, partner: &Mob) {
+        let Some(partner_breeding) = partner.breeding() else {
+            return;
+        };
+        for breeding in [self, partner_breeding] {
+            *breeding.state.lock() = BreedingState::default();
+            breeding.age.start_breed_cooldown();
+        }
+        let entity = &mob.living_entity.entity;
+        let world = &entity.world;
+        let pos = entity.pos.load();
+        let Some(baby) = spawn_ageable(world, entity.entity_type, pos, true).await else {
+            return;
+        };
+        if let Some(tameable) = mob.tameable() {
+            tameable.pass_on(&baby);
+        }
+        show_status(mob, LOVE_STATUS).await;
+        let experience = thread_rng().gen_range(1..=MAX_BREEDING_EXPERIENCE);
+        ExperienceOrb::spawn(world, pos, experience).await;
+    }
+
+    /// Walks after the nearest player holding its food. Returns true while it follows one
+    pub async fn tempt(&self, mob: &Mob, speed: f64) -> bool {
+        let entity = &mob.living_entity.entity;
+        let pos = entity.pos.load();
+        let mut players: Vec<_> = entity
+            .world
+            .players()
+            .await
+            .iter()
+            .filter(|player| {
+                player.gamemode.load() != GameMode::Spectator
+                    && player.living_entity.entity.pos.load().sub(&pos).length() <= TEMPT_RANGE
+            })
+            .cloned()
+            .collect();
+        players.sort_by(|a, b| {
+            let distance = |player: &Arc<Player>| {
+                player
+                    .living_entity
+                    .entity
+                    .pos
+                    .load()
+                    .sub(&pos)
+                    .length_squared()
+            };
+            distance(a).total_cmp(&distance(b))
+        });
+        for player in players {
+            let holds_food = player
+                .held_item_name(Hand::Main)
+                .await
+                .is_some_and(|item| self.is_food(item))
+                || player
+                    .held_item_name(Hand::Off)
+                    .await
+                    .is_some_and(|item| self.is_food(item));
+            if !holds_food {
+                continue;
+            }
+            let player_entity = &player.living_entity.entity;
+            let player_pos = player_entity.pos.load();
+            mob.look_at(Vector3::new(
+                player_pos.x,
+                player_pos.y + f64::from(player_entity.standing_eye_height),
+                player_pos.z,
+            ));
+            if player_pos.sub(&pos).length() > TEMPT_DISTANCE {
+                mob.walk_to(player_pos, speed);
+            } else {
+                mob.stop_walking();
+            }
+            return true;
+        }
+        false
+    }
+
+    /// The experience animals drop, babies drop none
+    #[must_use]
+    pub fn experience(&self) -> i32 {
+        if self.is_baby() {
+            0
+        } else {
+            thread_rng().gen_range(1..=MAX_EXPERIENCE)
+        }
+    }
+}
+
+/// What tells the kinds of farm animals apart
+struct Kind {
+    max_health: f32,
+    size: BoundingBoxSize,
+    eye_height: f32,
+    foods: fn(&str) -> bool,
+    ambient_sound: u16,
+    hurt_sound: u16,
+    death_sound: u16,
+}
+
+fn is_wheat(item: &str) -> bool {
+    item == "minecraft:wheat"
+}
+
+fn is_root(item: &str) -> bool {
+    matches!(
+        item,
+        "minecraft:carrot" | "minecraft:potato" | "minecraft:beetroot"
+    )
+}
+
+/// The seeds chickens breed with and parrots are tamed with
+pub(super) fn is_seed(item: &str) -> bool {
+    matches!(
+        item,
+        "minecraft:wheat_seeds"
+            | "minecraft:melon_seeds"
+            | "minecraft:pumpkin_seeds"
+            | "minecraft:beetroot_seeds"
+            | "minecraft:torchflower_seeds"
+            | "minecraft:pitcher_pod"
+    )
+}
+
+const COW: Kind = Kind {
+    max_health: 10.0,
+    size: BoundingBoxSize {
+        width: 0.9,
+        height: 1.4,
+    },
+    eye_height: 1.3,
+    foods: is_wheat,
+    ambient_sound: sound!("minecraft:entity.cow.ambient"),
+    hurt_sound: sound!("minecraft:entity.cow.hurt"),
+    death_sound: sound!("minecraft:entity.cow.death"),
+};
+
+const PIG: Kind = Kind {
+    max_health: 10.0,
+    size: BoundingBoxSize {
+        width: 0.9,
+        height: 0.9,
+    },
+    eye_height: 0.765,
+    foods: is_root,
+    ambient_sound: sound!("minecraft:entity.pig.ambient"),
+    hurt_sound: sound!("minecraft:entity.pig.hurt"),
+    death_sound: sound!("minecraft:entity.pig.death"),
+};
+
+const SHEEP: Kind = Kind {
+    max_health: 8.0,
+    size: BoundingBoxSize {
+        width: 0.9,
+        height: 1.3,
+    },
+    eye_height: 1.235,
+    foods: is_wheat,
+    ambient_sound: sound!("minecraft:entity.sheep.ambient"),
+    hurt_sound: sound!("minecraft:entity.sheep.hurt"),
+    death_sound: sound!("minecraft:entity.sheep.death"),
+};
+
+const CHICKEN: Kind = Kind {
+    max_health: 4.0,
+    size: BoundingBoxSize {
+        width: 0.4,
+        height: 0.7,
+    },
+    eye_height: 0.644,
+    foods: is_seed,
+    ambient_sound: sound!("minecraft:entity.chicken.ambient"),
+    hurt_sound: sound!("minecraft:entity.chicken.hurt"),
+    death_sound: sound!("minecraft:entity.chicken.death"),
+};
+
+const fn kind(entity_type: EntityType) -> Option<&'static Kind> {
+    match entity_type {
+        EntityType::Chicken => Some(&CHICKEN),
+        EntityType::Cow => Some(&COW),
+        EntityType::Pig => Some(&PIG),
+        EntityType::Sheep => Some(&SHEEP),
+        _ => None,
+    }
+}
+
+/// Speeds in blocks per tick
+const WALK_SPEED: f64 = 0.08;
+const PANIC_SPEED: f64 = 0.16;
+const WANDER_RANGE: f64 = 10.0;
+const PANIC_TICKS: u32 = 100;
+
+/// A cow, pig, sheep or chicken
+pub struct FarmAnimal {
+    kind: &'static Kind,
+    breeding: Breeding,
+    /// What it runs from, with the ticks it keeps panicking
+    panic: parking_lot::Mutex<Option<(Vector3<f64>, u32)>>,
+}
+
+impl FarmAnimal {
+    /// Spawns the animal at the position, returns `None` if the type isn't a farm animal
+    pub async fn spawn(
+        world: &Arc<World>,
+        entity_type: EntityType,
+        position: Vector3<f64>,
+        baby: bool,
+    ) -> Option<Arc<Mob>> {
+        let kind = kind(entity_type)?;
+        let entity = mob_entity(world, entity_type, position, kind.size, kind.eye_height);
+        entity
+            .data_tracker
+            .lock()
+            .define(&data_tracker::BABY, false);
+        let animal = Self {
+            kind,
+            breeding: Breeding::new(baby, kind.foods, kind.size),
+            panic: parking_lot::Mutex::new(None),
+        };
+        let mob = Mob::new(entity, Box::new(animal));
+        mob.living_entity.set_max_health(kind.max_health);
+        if baby {
+            set_baby(&mob, true, kind.size);
+        }
+        let mob = Arc::new(mob);
+        world.spawn_entity(mob.clone()).await;
+        Some(mob)
+    }
+}
+
+#[async_trait]
+impl MobAi for FarmAnimal {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn tick(&self, mob: &Mob) -> bool {
+        self.breeding.tick(mob);
+        if thread_rng().gen_ratio(1, 120) {
+            play_sound(mob, self.kind.ambient_sound).await;
+        }
+        let panic = {
+            let mut panic = self.panic.lock();
+            if let Some((_, ticks)) = &mut *panic {
+                *ticks -= 1;
+                if *ticks == 0 {
+                    *panic = None;
+                }
+            }
+            *panic
+        };
+        if let Some((threat, _)) = panic {
+            mob.flee_from(threat, PANIC_SPEED);
+            return true;
+        }
+        if !self.breeding.breed(mob, WALK_SPEED).await
+            && !self.breeding.tempt(mob, WALK_SPEED).await
+        {
+            mob.wander(WANDER_RANGE, WALK_SPEED);
+        }
+        true
+    }
+
+    async fn on_hurt(&self, mob: &Mob, attacker: Option<EntityId>) {
+        let entity = &mob.living_entity.entity;
+        let threat = match attacker {
+            Some(attacker) => attacker_pos(&entity.world, attacker).await,
+            None => None,
+        };
+        *self.panic.lock() = Some((threat.unwrap_or_else(|| entity.pos.load()), PANIC_TICKS));
+        let sound = if mob.living_entity.health.load() > 0.0 {
+            self.kind.hurt_sound
+        } else {
+            self.kind.death_sound
+        };
+        play_sound(mob, sound).await;
+    }
+
+    async fn interact(&self, mob: &Mob, player: &Player, hand: Hand) -> bool {
+        self.breeding.feed(mob, player, hand).await
+    }
+
+    fn experience(&self) -> i32 {
+        self.breeding.experience()
+    }
+
+    fn breeding(&self) -> Option<&Breeding> {
+        Some(&self.breeding)
+    }
+}
@@ -0,0 +1,225 @@
+//! Camels: they carry two players, the one in front steers a saddled camel and makes it dash
+//! forward by jumping. Camels don't have to be tamed and breed when they are fed cactus.
+
+use std::{
+    any::Any,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+};
+
+use async_trait::async_trait;
+use pumpkin_core::math::{boundingbox::BoundingBoxSize, vector3::Vector3};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_macros::sound;
+use pumpkin_world::item::{item_registry::get_item, ItemStack};
+use rand::{thread_rng, Rng};
+
+use crate::{
+    entity::{
+        data_tracker,
+        player::{Hand, Player},
+    },
+    world::World,
+};
+
+use super::{
+    ageable::set_baby,
+    animal::Breeding,
+    mob_entity,
+    mount::{MountInventory, SADDLE},
+    play_sound, Mob, MobAi,
+};
+
+const MAX_HEALTH: f32 = 32.0;
+const SIZE: BoundingBoxSize = BoundingBoxSize {
+    width: 1.7,
+    height: 2.375,
+};
+const EYE_HEIGHT: f32 = 2.275;
+/// Speeds in blocks per tick
+const WALK_SPEED: f64 = 0.06;
+const WANDER_RANGE: f64 = 10.0;
+
+const SADDLED_FLAG: i8 = 0x04;
+/// Ticks until a camel dashes again, it dashes for the first few of them
+const DASH_COOLDOWN: u32 = 55;
+const DASH_TICKS: u32 = 5;
+
+fn is_cactus(item: &str) -> bool {
+    item == "minecraft:cactus"
+}
+
+pub struct Camel {
+    breeding: Breeding,
+    inventory: MountInventory,
+    /// Ticks until it dashes again
+    dash_cooldown: AtomicU32,
+}
+
+impl Camel {
+    pub async fn spawn(world: &Arc<World>, position: Vector3<f64>, baby: bool) -> Arc<Mob> {
+        let entity = mob_entity(world, EntityType::Camel, position, SIZE, EYE_HEIGHT);
+        {
+            let mut tracker = entity.data_tracker.lock();
+            tracker.define(&data_tracker::BABY, false);
+            tracker.define(&data_tracker::HORSE_FLAGS, 0);
+            tracker.define(&data_tracker::CAMEL_DASH, false);
+        }
+        let camel = Self {
+            breeding: Breeding::new(baby, is_cactus, SIZE),
+            inventory: MountInventory::new(0, &[]),
+            dash_cooldown: AtomicU32::new(0),
+        };
+        let mob = Mob::new(entity, Box::new(camel));
+        mob.living_entity.set_max_health(MAX_HEALTH);
+        if baby {
+            set_baby(&mob, true, SIZE);
+        }
+        let mob = Arc::new(mob);
+        world.spawn_entity(mob.clone()).await;
+        mob
+    }
+
+    fn update_flags(&self, mob: &Mob) {
+        let flags = if self.inventory.is_saddled() {
+            SADDLED_FLAG
+        } else {
+            0
+        };
+        mob.living_entity
+            .entity
+            .data_tracker
+            .lock()
+            .set(&data_tracker::HORSE_FLAGS, flags);
+    }
+
+    fn set_dashing(mob: &Mob, dashing: bool) {
+        mob.living_entity
+            .entity
+            .data_tracker
+            .lock()
+            .set(&data_tracker::CAMEL_DASH, dashing);
+    }
+
+    /// Counts down the dash cooldown, the camel is ready again once it runs out
+    async fn tick_dash(&self, mob: &Mob) {
+        let cooldown = self.dash_cooldown.load(Ordering::Relaxed);
+        if cooldown == 0 {
+            return;
+        }
+        self.dash_cooldown.store(cooldown - 1, Ordering::Relaxed);
+        if cooldown == DASH_COOLDOWN - DASH_TICKS {
+            Self::set_dashing(mob, false);
+        }
+        if cooldown == 1 {
+            play_sound(mob, sound!("minecraft:entity.camel.dash_ready")).await;
+        }
+    }
+
+    /// Puts the saddle in the player's hand on an adult camel which doesn't wear one yet
+    async fn saddle(&self, mob: &Mob, player: &Player, hand: Hand) -> bool {
+        let Some(saddle) = get_item(SADDLE).map(|item| ItemStack::new(1, item.id)) else {
+            return false;
+        };
+        if self.breeding.is_baby()
+            || !self.inventory.put_saddle(saddle).await
+            || !player.use_held_item(hand, SADDLE).await
+        {
+            return false;
+        }
+        self.update_flags(mob);
+        play_sound(mob, sound!("minecraft:entity.camel.saddle")).await;
+        true
+    }
+}
+
+#[async_trait]
+impl MobAi for Camel {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn tick(&self, mob: &Mob) -> bool {
+        self.breeding.tick(mob);
+        self.tick_dash(mob).await;
+        if self.inventory.update().await.saddle {
+            self.update_flags(mob);
+        }
+        if thread_rng().gen_ratio(1, 120) {
+            play_sound(mob, sound!("minecraft:entity.camel.ambient")).await;
+        }
+        let ridden = !mob.living_entity.entity.passengers.lock().is_empty();
+        if ridden {
+            mob.stop_walking();
+            return true;
+        }
+        if !self.breeding.breed(mob, WALK_SPEED).await
+            && !self.breeding.tempt(mob, WALK_SPEED).await
+        {
+            mob.wander(WANDER_RANGE, WALK_SPEED);
+        }
+        true
+    }
+
+    async fn on_hurt(&self, mob: &Mob, _attacker: Option<EntityId>) {
+        if mob.living_entity.health.load() > 0.0 {
+            play_sound(mob, sound!("minecraft:entity.camel.hurt")).await;
+            return;
+        }
+        let entity = &mob.living_entity.entity;
+        self.inventory.close(&entity.world).await;
+        play_sound(mob, sound!("minecraft:entity.camel.death")).await;
+    }
+
+    /// Camels eat cactus and get saddled, otherwise the player gets on
+    async fn interact(&self, mob: &Mob, player: &Player, hand: Hand) -> bool {
+        if let Some(item) = player.held_item_name(hand).await {
+            if self.breeding.feed(mob, player, hand).await {
+                return true;
+            }
+            if item == SADDLE && self.saddle(mob, player, hand).await {
+                return true;
+            }
+        }
+        if self.breeding.is_baby() || player.vehicle.lock().await.is_some() {
+            return false;
+        }
+        if player.start_riding(&mob.living_entity.entity).await {
+            mob.stop_walking();
+        }
+        // nothing was used up
+        false
+    }
+
+    fn experience(&self) -> i32 {
+        self.breeding.experience()
+    }
+
+    fn drops(&self) -> Vec<ItemStack> {
+        self.inventory.drops()
+    }
+
+    fn breeding(&self) -> Option<&Breeding> {
+        Some(&self.breeding)
+    }
+
+    fn inventory(&self) -> Option<&MountInventory> {
+        (!self.breeding.is_baby()).then_some(&self.inventory)
+    }
+
+    async fn steered_by(&self, _mob: &Mob, _player: &Player) -> bool {
+        self.inventory.is_saddled()
+    }
+
+    /// Dashes forward unless it still rests from the last dash, the client moves it
+    async fn jump(&self, mob: &Mob, _strength: i32) {
+        if self.dash_cooldown.load(Ordering::Relaxed) > 0 {
+            return;
+        }
+        self.dash_cooldown.store(DASH_COOLDOWN, Ordering::Relaxed);
+        Self::set_dashing(mob, true);
+        play_sound(mob, sound!("minecraft:entity.camel.dash")).await;
+    }
+}
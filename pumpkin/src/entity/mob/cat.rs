@@ -0,0 +1,177 @@
+//! Cats: raw cod or salmon tames a stray cat now and then. Tamed cats follow their owner, sit when
+//! it tells them to and breed when they are fed fish.
+
+use std::{any::Any, sync::Arc};
+
+use async_trait::async_trait;
+use pumpkin_core::math::{boundingbox::BoundingBoxSize, vector3::Vector3};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_macros::sound;
+use rand::{thread_rng, Rng};
+
+use crate::{
+    entity::{
+        data_tracker,
+        player::{Hand, Player},
+    },
+    world::World,
+};
+
+use super::{
+    ageable::set_baby,
+    animal::Breeding,
+    mob_entity, play_sound,
+    tameable::{dye_color, Tameable, DEFAULT_COLLAR},
+    Mob, MobAi,
+};
+
+const MAX_HEALTH: f32 = 10.0;
+const SIZE: BoundingBoxSize = BoundingBoxSize {
+    width: 0.6,
+    height: 0.7,
+};
+const EYE_HEIGHT: f32 = 0.35;
+/// Speeds in blocks per tick
+const WALK_SPEED: f64 = 0.1;
+const FOLLOW_SPEED: f64 = 0.2;
+const WANDER_RANGE: f64 = 10.0;
+
+/// Fish tames a cat one in three times
+const TAME_CHANCE: f64 = 1.0 / 3.0;
+/// The health fish gives a tamed cat
+const FISH_HEALTH: f32 = 2.0;
+
+fn is_fish(item: &str) -> bool {
+    matches!(item, "minecraft:cod" | "minecraft:salmon")
+}
+
+pub struct Cat {
+    breeding: Breeding,
+    tameable: Tameable,
+}
+
+impl Cat {
+    pub async fn spawn(world: &Arc<World>, position: Vector3<f64>, baby: bool) -> Arc<Mob> {
+        let entity = mob_entity(world, EntityType::Cat, position, SIZE, EYE_HEIGHT);
+        {
+            let mut tracker = entity.data_tracker.lock();
+            tracker.define(&data_tracker::BABY, false);
+            tracker.define(&data_tracker::CAT_COLLAR, DEFAULT_COLLAR);
+        }
+        let cat = Self {
+            breeding: Breeding::new(baby, is_fish, SIZE),
+            tameable: Tameable::default(),
+        };
+        let mob = Mob::new(entity, Box::new(cat));
+        Tameable::define(&mob);
+        mob.living_entity.set_max_health(MAX_HEALTH);
+        if baby {
+            set_baby(&mob, true, SIZE);
+        }
+        let mob = Arc::new(mob);
+        world.spawn_entity(mob.clone()).await;
+        mob
+    }
+}
+
+#[async_trait]
+impl MobAi for Cat {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn tick(&self, mob: &Mob) -> bool {
+        self.breeding.tick(mob);
+        if self.tameable.is_sitting() {
+            return true;
+        }
+        if thread_rng().gen_ratio(1, 120) {
+            let sound = if self.tameable.is_tamed() {
+                sound!("minecraft:entity.cat.ambient")
+            } else {
+                sound!("minecraft:entity.cat.stray_ambient")
+            };
+            play_sound(mob, sound).await;
+        }
+        if self.breeding.breed(mob, WALK_SPEED).await
+            || self.tameable.follow_owner(mob, FOLLOW_SPEED, &SIZE).await
+            || self.breeding.tempt(mob, WALK_SPEED).await
+        {
+            return true;
+        }
+        mob.wander(WANDER_RANGE, WALK_SPEED);
+        true
+    }
+
+    /// Sitting cats get up when they are hurt
+    async fn on_hurt(&self, mob: &Mob, _attacker: Option<EntityId>) {
+        self.tameable.set_sitting(mob, false);
+        let sound = if mob.living_entity.health.load() > 0.0 {
+            sound!("minecraft:entity.cat.hurt")
+        } else {
+            sound!("minecraft:entity.cat.death")
+        };
+        play_sound(mob, sound).await;
+    }
+
+    /// Stray cats take fish, tamed ones eat it, get their collar dyed by their owner or sit down
+    /// and get up when it tells them to
+    async fn interact(&self, mob: &Mob, player: &Player, hand: Hand) -> bool {
+        let item = player.held_item_name(hand).await;
+        if !self.tameable.is_tamed() {
+            let Some(fish) = item.filter(|item| is_fish(item)) else {
+                return false;
+            };
+            if !player.use_held_item(hand, fish).await {
+                return false;
+            }
+            play_sound(mob, sound!("minecraft:entity.cat.eat")).await;
+            if self.tameable.try_tame(mob, player, TAME_CHANCE).await {
+                self.tameable.set_sitting(mob, true);
+            }
+            return true;
+        }
+        if let Some(item) = item {
+            let living = &mob.living_entity;
+            if is_fish(item) {
+                if living.health.load() < living.max_health.load()
+                    && player.use_held_item(hand, item).await
+                {
+                    living.heal(FISH_HEALTH).await;
+                    play_sound(mob, sound!("minecraft:entity.cat.eat")).await;
+                    return true;
+                }
+                if self.breeding.feed(mob, player, hand).await {
+                    return true;
+                }
+            }
+            if let Some(color) = dye_color(item) {
+                if self.tameable.is_owner(player) && player.use_held_item(hand, item).await {
+                    living
+                        .entity
+                        .data_tracker
+                        .lock()
+                        .set(&data_tracker::CAT_COLLAR, color);
+                    return true;
+                }
+            }
+        }
+        if !self.tameable.is_owner(player) {
+            return false;
+        }
+        self.tameable.set_sitting(mob, !self.tameable.is_sitting());
+        true
+    }
+
+    fn experience(&self) -> i32 {
+        self.breeding.experience()
+    }
+
+    fn breeding(&self) -> Option<&Breeding> {
+        Some(&self.breeding)
+    }
+
+    fn tameable(&self) -> Option<&Tameable> {
+        Some(&self.tameable)
+    }
+}
@@ -0,0 +1,379 @@
+//! Endermen: they leave players alone until one stares at them, unless the player wears a carved
+//! pumpkin. They teleport away from water, sunlight and what hurts them, and toward the players they
+//! chase. Now and then they pick up a block and put it down somewhere else.
+
+use std::{any::Any, sync::Arc};
+
+use async_trait::async_trait;
+use pumpkin_core::math::{boundingbox::BoundingBoxSize, position::WorldPosition, vector3::Vector3};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_macros::sound;
+use pumpkin_registry::{is_in, TagCategory};
+use pumpkin_world::{
+    block::block_registry::{get_block_by_state_id, get_state_by_state_id},
+    game_rules::pumpkin::ENDERMAN_GRIEFING,
+    item::{item_registry::get_item_name, ItemStack},
+};
+use rand::{thread_rng, Rng};
+
+use crate::{
+    client::interaction_check::{eye_position, look_direction},
+    entity::{data_tracker, player::Player},
+    world::{block_entity::center, vibration::GameEvent, World},
+};
+
+use super::{
+    find_spawn_position, mob_entity,
+    monster::{can_see, chase, eyes, in_sunlight, keep_target, nearest_player, random_drop},
+    play_sound, Mob, MobAi, Target,
+};
+
+const MAX_HEALTH: f32 = 40.0;
+const EXPERIENCE: i32 = 5;
+const SIZE: BoundingBoxSize = BoundingBoxSize {
+    width: 0.6,
+    height: 2.9,
+};
+const EYE_HEIGHT: f32 = 2.55;
+/// Speeds in blocks per tick
+const WALK_SPEED: f64 = 0.1;
+const CHASE_SPEED: f64 = 0.25;
+const WANDER_RANGE: f64 = 10.0;
+
+/// How far away players can stare at an enderman
+const STARE_RANGE: f64 = 64.0;
+/// How exactly players have to look at its eyes, closer ones may look a bit further off
+const STARE_PRECISION: f64 = 0.025;
+const CARVED_PUMPKIN: &str = "minecraft:carved_pumpkin";
+const REACH: f64 = 1.5;
+const ATTACK_DAMAGE: f32 = 7.0;
+const ATTACK_COOLDOWN: u32 = 20;
+
+/// Where endermen teleport to around themselves
+const TELEPORT_ATTEMPTS: u32 = 8;
+const TELEPORT_HORIZONTAL: i32 = 32;
+const TELEPORT_VERTICAL: i32 = 16;
+/// One in this many ticks endermen teleport away from water and sunlight
+const TELEPORT_CHANCE: u32 = 20;
+/// Endermen get away from nine in ten hurts nobody caused
+const DODGE_CHANCE: f64 = 0.9;
+/// Targets further away than this make them teleport closer, at most once every 30 ticks
+const TELEPORT_TOWARD_DISTANCE: f64 = 16.0;
+const TELEPORT_TOWARD_COOLDOWN: u32 = 30;
+const TELEPORT_TOWARD_HORIZONTAL: i32 = 4;
+
+/// Endermen pick up a block one in 20 ticks and put it down one in 2000 ticks
+const PICK_UP_CHANCE: u32 = 20;
+const PLACE_CHANCE: u32 = 2000;
+const HOLDABLE_TAG: &str = "minecraft:enderman_holdable";
+
+#[derive(Default)]
+struct EndermanState {
+    target: Option<EntityId>,
+    attack_cooldown: u32,
+    teleport_cooldown: u32,
+    carried: Option<u16>,
+}
+
+pub struct Enderman {
+    state: parking_lot::Mutex<EndermanState>,
+}
+
+impl Enderman {
+    pub async fn spawn(world: &Arc<World>, position: Vector3<f64>) -> Arc<Mob> {
+        let entity = mob_entity(world, EntityType::Enderman, position, SIZE, EYE_HEIGHT);
+        {
+            let mut tracker = entity.data_tracker.lock();
+            tracker.define(&data_tracker::ENDERMAN_CARRIED_BLOCK, None);
+            tracker.define(&data_tracker::ENDERMAN_SCREAMING, false);
+            tracker.define(&data_tracker::ENDERMAN_STARED_AT, false);
+        }
+        let enderman = Self {
+            state: parking_lot::Mutex::new(EndermanState::default()),
+        };
+        let mob = Mob::new(entity, Box::new(enderman));
+        mob.living_entity.set_max_health(MAX_HEALTH);
+        let mob = Arc::new(mob);
+        world.spawn_entity(mob.clone()).await;
+        mob
+    }
+
+    /// Whether the player looks the enderman in the eyes
+    fn stares_at(mob: &Mob, player: &Player) -> bool {
+        let to_eyes = eyes(&mob.living_entity.entity).sub(&eye_position(player));
+        let distance = to_eyes.length();
+        let look = look_direction(&player.living_entity.entity);
+        let dot = look.x * to_eyes.x + look.y * to_eyes.y + look.z * to_eyes.z;
+        dot / distance > 1.0 - STARE_PRECISION / distance
+    }
+
+    /// Screams at the player which stared at it and goes after it
+    async fn provoke(&self, mob: &Mob, target: &Target) {
+        self.state.lock().target = Some(target.entity().entity_id);
+        {
+            let mut tracker = mob.living_entity.entity.data_tracker.lock();
+            tracker.set(&data_tracker::ENDERMAN_SCREAMING, true);
+            tracker.set(&data_tracker::ENDERMAN_STARED_AT, true);
+        }
+        play_sound(mob, sound!("minecraft:entity.enderman.stare")).await;
+    }
+
+    fn calm_down(&self, mob: &Mob) {
+        self.state.lock().target = None;
+        let mut tracker = mob.living_entity.entity.data_tracker.lock();
+        tracker.set(&data_tracker::ENDERMAN_SCREAMING, false);
+        tracker.set(&data_tracker::ENDERMAN_STARED_AT, false);
+    }
+
+    /// The player staring at it, if it can see that player. Carved pumpkins hide their look
+    async fn find_starer(mob: &Mob) -> Option<Target> {
+        let starer =
+            nearest_player(mob, STARE_RANGE, |player| Self::stares_at(mob, player)).await?;
+        let Target::Player(player) = &starer else {
+            return None;
+        };
+        let head = player.inventory.lock().await.armor()[0]
+            .as_ref()
+            .map(|head| head.item_id);
+        if head.and_then(get_item_name) == Some(CARVED_PUMPKIN) {
+            return None;
+        }
+        let world = &mob.living_entity.entity.world;
+        can_see(world, eye_position(player), eyes(&mob.living_entity.entity))
+            .await
+            .then_some(starer)
+    }
+
+    /// Teleports to a free spot on solid ground around the position, returns false if it found
+    /// none
+    async fn teleport(mob: &Mob, around: Vector3<f64>, horizontal: i32) -> bool {
+        let entity = &mob.living_entity.entity;
+        let position = WorldPosition(Vector3::new(
+            around.x.floor() as i32,
+            around.y.floor() as i32,
+            around.z.floor() as i32,
+        ));
+        let Some(destination) = find_spawn_position(
+            &entity.world,
+            position,
+            TELEPORT_ATTEMPTS,
+            horizontal,
+            TELEPORT_VERTICAL,
+            &SIZE,
+        )
+        .await
+        else {
+            return false;
+        };
+        play_sound(mob, sound!("minecraft:entity.enderman.teleport")).await;
+        mob.stop_walking();
+        mob.living_entity
+            .set_pos(destination.x, destination.y, destination.z);
+        entity.velocity.store(Vector3::new(0.0, 0.0, 0.0));
+        play_sound(mob, sound!("minecraft:entity.enderman.teleport")).await;
+        true
+    }
+
+    /// Teleports closer to a target which got far away
+    async fn teleport_toward(&self, mob: &Mob, target: &Target) {
+        let pos = mob.living_entity.entity.pos.load();
+        let offset = target.entity().pos.load().sub(&pos);
+        let distance = offset.length();
+        let ready = {
+            let mut state = self.state.lock();
+            state.teleport_cooldown = state.teleport_cooldown.saturating_sub(1);
+            state.teleport_cooldown == 0
+        };
+        if !ready || distance <= TELEPORT_TOWARD_DISTANCE {
+            return;
+        }
+        let toward = pos.add(&(offset * (TELEPORT_TOWARD_DISTANCE / distance)));
+        if Self::teleport(mob, toward, TELEPORT_TOWARD_HORIZONTAL).await {
+            self.state.lock().teleport_cooldown = TELEPORT_TOWARD_COOLDOWN;
+        }
+    }
+
+    /// Picks up a block nearby it can hold, or puts the one it carries down on solid ground
+    async fn move_blocks(&self, mob: &Mob) {
+        let entity = &mob.living_entity.entity;
+        let world = &entity.world;
+        let carried = self.state.lock().carried;
+        let chance = if carried.is_some() {
+            PLACE_CHANCE
+        } else {
+            PICK_UP_CHANCE
+        };
+        let offset = {
+            let mut rng = thread_rng();
+            if !rng.gen_ratio(1, chance) {
+                return;
+            }
+            let reach = if carried.is_some() { 1 } else { 2 };
+            Vector3::new(
+                rng.gen_range(-reach..=reach),
+                rng.gen_range(0..=reach),
+                rng.gen_range(-reach..=reach),
+            )
+        };
+        if !world
+            .game_rules
+            .read()
+            .await
+            .allows_griefing(&ENDERMAN_GRIEFING)
+        {
+            return;
+        }
+        let pos = entity.pos.load();
+        let position = WorldPosition(Vector3::new(
+            pos.x.floor() as i32 + offset.x,
+            pos.y.floor() as i32 + offset.y,
+            pos.z.floor() as i32 + offset.z,
+        ));
+        let Ok(state_id) = world.get_block_state_id(position).await else {
+            return;
+        };
+        match carried {
+            Some(carried) => {
+                let below =
+                    WorldPosition(Vector3::new(position.0.x, position.0.y - 1, position.0.z));
+                let Ok(below) = world.get_block_state_id(below).await else {
+                    return;
+                };
+                let free = get_state_by_state_id(state_id).is_some_and(|state| state.air);
+                let solid = get_state_by_state_id(below)
+                    .is_some_and(|state| !state.collision_shapes.is_empty());
+                if !free || !solid {
+                    return;
+                }
+                world.set_block_state(position, carried).await;
+                self.set_carried(mob, None);
+                world
+                    .emit_game_event_by(
+                        GameEvent::BlockPlace,
+                        center(position),
+                        Some(entity.entity_id),
+                    )
+                    .await;
+            }
+            None => {
+                let holdable = get_block_by_state_id(state_id)
+                    .is_some_and(|block| is_in(TagCategory::Block, HOLDABLE_TAG, &block.name));
+                if !holdable {
+                    return;
+                }
+                world.set_block_state(position, 0).await;
+                self.set_carried(mob, Some(state_id));
+                world
+                    .emit_game_event_by(
+                        GameEvent::BlockDestroy,
+                        center(position),
+                        Some(entity.entity_id),
+                    )
+                    .await;
+            }
+        }
+    }
+
+    fn set_carried(&self, mob: &Mob, carried: Option<u16>) {
+        self.state.lock().carried = carried;
+        mob.living_entity.entity.data_tracker.lock().set(
+            &data_tracker::ENDERMAN_CARRIED_BLOCK,
+            carried.map(i32::from),
+        );
+    }
+}
+
+#[async_trait]
+impl MobAi for Enderman {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn tick(&self, mob: &Mob) -> bool {
+        let check_sun = thread_rng().gen_ratio(1, TELEPORT_CHANCE);
+        if mob.living_entity.is_in_water().await || (check_sun && in_sunlight(mob).await) {
+            let pos = mob.living_entity.entity.pos.load();
+            Self::teleport(mob, pos, TELEPORT_HORIZONTAL).await;
+        }
+        self.move_blocks(mob).await;
+        if thread_rng().gen_ratio(1, 120) {
+            play_sound(mob, sound!("minecraft:entity.enderman.ambient")).await;
+        }
+
+        let target = {
+            let mut state = self.state.lock();
+            state.attack_cooldown = state.attack_cooldown.saturating_sub(1);
+            state.target
+        };
+        let target = match target {
+            Some(target) => keep_target(mob, target, STARE_RANGE).await,
+            None => None,
+        };
+        let target = match target {
+            Some(target) => target,
+            None => {
+                if let Some(starer) = Self::find_starer(mob).await {
+                    self.provoke(mob, &starer).await;
+                } else {
+                    self.calm_down(mob);
+                    mob.wander(WANDER_RANGE, WALK_SPEED);
+                }
+                return true;
+            }
+        };
+        self.teleport_toward(mob, &target).await;
+        chase(mob, &target, CHASE_SPEED, REACH, ATTACK_DAMAGE, || {
+            let mut state = self.state.lock();
+            let ready = state.attack_cooldown == 0;
+            if ready {
+                state.attack_cooldown = ATTACK_COOLDOWN;
+            }
+            ready
+        })
+        .await;
+        true
+    }
+
+    /// Endermen go after whoever hurt them, from anything else they mostly teleport away
+    async fn on_hurt(&self, mob: &Mob, attacker: Option<EntityId>) {
+        let entity = &mob.living_entity.entity;
+        match attacker {
+            Some(attacker) if attacker != entity.entity_id => {
+                self.state.lock().target = Some(attacker);
+                entity
+                    .data_tracker
+                    .lock()
+                    .set(&data_tracker::ENDERMAN_SCREAMING, true);
+            }
+            _ => {
+                if thread_rng().gen_bool(DODGE_CHANCE) {
+                    Self::teleport(mob, entity.pos.load(), TELEPORT_HORIZONTAL).await;
+                }
+            }
+        }
+        let sound = if mob.living_entity.health.load() > 0.0 {
+            sound!("minecraft:entity.enderman.hurt")
+        } else {
+            sound!("minecraft:entity.enderman.death")
+        };
+        play_sound(mob, sound).await;
+    }
+
+    fn experience(&self) -> i32 {
+        EXPERIENCE
+    }
+
+    fn drops(&self) -> Vec<ItemStack> {
+        let carried = self
+            .state
+            .lock()
+            .carried
+            .and_then(get_block_by_state_id)
+            .filter(|block| block.item_id != 0)
+            .map(|block| ItemStack::new(1, block.item_id));
+        carried
+            .into_iter()
+            .chain(random_drop("minecraft:ender_pearl", 1))
+            .collect()
+    }
+}
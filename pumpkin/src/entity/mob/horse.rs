@@ -0,0 +1,411 @@
+//! Horses: players tame a wild horse by riding it until it stops throwing them off, each try makes
+//! it a bit more willing. Tamed horses wear a saddle and horse armor and breed when they are fed
+//! golden food. Every horse has its own speed and jump strength, which the client of the player
+//! riding a saddled horse moves it with.
+
+use std::{
+    any::Any,
+    sync::{atomic::Ordering, Arc},
+};
+
+use async_trait::async_trait;
+use pumpkin_core::math::{boundingbox::BoundingBoxSize, vector3::Vector3};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_macros::sound;
+use pumpkin_protocol::client::play::EquipmentSlot;
+use pumpkin_world::item::{item_registry::get_item, ItemStack};
+use rand::{thread_rng, Rng};
+
+use crate::{
+    entity::{
+        data_tracker,
+        player::{Hand, Player},
+    },
+    world::World,
+};
+
+use super::{
+    ageable::set_baby,
+    animal::Breeding,
+    mob_entity,
+    mount::{MountInventory, SADDLE},
+    play_sound,
+    tameable::Tameable,
+    Mob, MobAi,
+};
+
+const MIN_HEALTH: u8 = 15;
+const MAX_HEALTH: u8 = 30;
+const SIZE: BoundingBoxSize = BoundingBoxSize {
+    width: 1.396_484_4,
+    height: 1.6,
+};
+const EYE_HEIGHT: f32 = 1.52;
+/// Speeds in blocks per tick
+const WALK_SPEED: f64 = 0.1;
+const WANDER_RANGE: f64 = 10.0;
+
+const TAMED_FLAG: i8 = 0x02;
+const SADDLED_FLAG: i8 = 0x04;
+const REARING_FLAG: i8 = 0x20;
+/// 7 colors and 5 markings
+const COLORS: i32 = 7;
+const MARKINGS: i32 = 5;
+
+/// A horse is tamed once its temper reaches this
+const MAX_TEMPER: i32 = 100;
+/// Every time a horse throws off its rider it gets this much more willing
+const THROWN_OFF_TEMPER: i32 = 5;
+/// A wild horse tries to throw off its rider once every 50 ticks on average
+const BUCK_CHANCE: u32 = 50;
+const REARING_TICKS: u32 = 20;
+
+const HORSE_ARMOR: &[&str] = &[
+    "minecraft:leather_horse_armor",
+    "minecraft:iron_horse_armor",
+    "minecraft:golden_horse_armor",
+    "minecraft:diamond_horse_armor",
+];
+
+/// A random speed in blocks per tick and jump strength, average horses are the likeliest
+fn random_stats() -> (f64, f64) {
+    let mut rng = thread_rng();
+    let mut roll =
+        |min: f64, step: f64| min + (0..3).map(|_| rng.gen_range(0.0..step)).sum::<f64>();
+    let speed = roll(0.45, 0.3) * 0.25;
+    let jump_strength = roll(0.4, 0.2);
+    (speed, jump_strength)
+}
+
+/// Only golden food makes a tamed horse fall in love
+fn is_golden_food(item: &str) -> bool {
+    matches!(
+        item,
+        "minecraft:golden_carrot" | "minecraft:golden_apple" | "minecraft:enchanted_golden_apple"
+    )
+}
+
+/// The temper and health the food gives a horse, `None` if horses don't eat the item
+fn food_value(item: &str) -> Option<(i32, f32)> {
+    match item {
+        "minecraft:sugar" => Some((3, 1.0)),
+        "minecraft:wheat" => Some((3, 2.0)),
+        "minecraft:apple" => Some((3, 3.0)),
+        "minecraft:golden_carrot" => Some((5, 4.0)),
+        "minecraft:golden_apple" | "minecraft:enchanted_golden_apple" => Some((10, 10.0)),
+        "minecraft:hay_block" => Some((0, 20.0)),
+        _ => None,
+    }
+}
+
+#[derive(Default)]
+struct HorseState {
+    temper: i32,
+    /// The player riding the horse
+    rider: Option<EntityId>,
+    /// Ticks the horse keeps rearing up
+    rearing: u32,
+}
+
+pub struct Horse {
+    breeding: Breeding,
+    tameable: Tameable,
+    inventory: MountInventory,
+    /// Blocks per tick
+    speed: f64,
+    jump_strength: f64,
+    state: parking_lot::Mutex<HorseState>,
+}
+
+impl Horse {
+    pub async fn spawn(world: &Arc<World>, position: Vector3<f64>, baby: bool) -> Arc<Mob> {
+        let entity = mob_entity(world, EntityType::Horse, position, SIZE, EYE_HEIGHT);
+        {
+            let mut rng = thread_rng();
+            let mut tracker = entity.data_tracker.lock();
+            tracker.define(&data_tracker::BABY, false);
+            tracker.define(&data_tracker::HORSE_FLAGS, 0);
+            tracker.define(&data_tracker::HORSE_VARIANT, 0);
+            tracker.set(
+                &data_tracker::HORSE_VARIANT,
+                rng.gen_range(0..COLORS) | (rng.gen_range(0..MARKINGS) << 8),
+            );
+        }
+        let (speed, jump_strength) = random_stats();
+        let horse = Self {
+            breeding: Breeding::new(baby, is_golden_food, SIZE),
+            tameable: Tameable::horse(),
+            inventory: MountInventory::new(0, HORSE_ARMOR),
+            speed,
+            jump_strength,
+            state: parking_lot::Mutex::new(HorseState::default()),
+        };
+        let mob = Mob::new(entity, Box::new(horse));
+        let max_health = thread_rng().gen_range(MIN_HEALTH..=MAX_HEALTH);
+        mob.living_entity.set_max_health(f32::from(max_health));
+        if baby {
+            set_baby(&mob, true, SIZE);
+        }
+        let mob = Arc::new(mob);
+        world.spawn_entity(mob.clone()).await;
+        mob
+    }
+
+    fn update_flags(&self, mob: &Mob) {
+        let mut flags = 0;
+        if self.tameable.is_tamed() {
+            flags |= TAMED_FLAG;
+        }
+        if self.inventory.is_saddled() {
+            flags |= SADDLED_FLAG;
+        }
+        if self.state.lock().rearing > 0 {
+            flags |= REARING_FLAG;
+        }
+        mob.living_entity
+            .entity
+            .data_tracker
+            .lock()
+            .set(&data_tracker::HORSE_FLAGS, flags);
+    }
+
+    /// The player riding the horse, forgets riders which got off
+    async fn rider(&self, mob: &Mob) -> Option<Arc<Player>> {
+        let rider = self.state.lock().rider?;
+        let entity = &mob.living_entity.entity;
+        let player = entity.world.get_player_by_entityid(rider).await;
+        let riding = match &player {
+            Some(player) => player
+                .vehicle
+                .lock()
+                .await
+                .as_ref()
+                .is_some_and(|riding| riding.vehicle_id == entity.entity_id),
+            None => false,
+        };
+        if !riding {
+            self.state.lock().rider = None;
+            return None;
+        }
+        player
+    }
+
+    /// Wild horses try to throw off their rider, the more often they did the likelier they give
+    /// in and are tamed
+    async fn buck(&self, mob: &Mob, rider: &Player) {
+        if !thread_rng().gen_ratio(1, BUCK_CHANCE) {
+            return;
+        }
+        let temper = self.state.lock().temper;
+        let chance = f64::from(temper) / f64::from(MAX_TEMPER);
+        if !self.tameable.try_tame(mob, rider, chance).await {
+            {
+                let mut state = self.state.lock();
+                state.temper = (state.temper + THROWN_OFF_TEMPER).min(MAX_TEMPER);
+                state.rider = None;
+                state.rearing = REARING_TICKS;
+            }
+            rider.dismount().await;
+            play_sound(mob, sound!("minecraft:entity.horse.angry")).await;
+        }
+        self.update_flags(mob);
+    }
+
+    /// Eats the food if it heals the horse, lets a foal grow or makes a wild horse more willing.
+    /// Returns false if the horse doesn't want it
+    async fn eat(&self, mob: &Mob, player: &Player, hand: Hand, item: &str) -> bool {
+        let Some((temper, health)) = food_value(item) else {
+            return false;
+        };
+        let living = &mob.living_entity;
+        let hurt = living.health.load() < living.max_health.load();
+        let baby = self.breeding.is_baby();
+        let calms =
+            !self.tameable.is_tamed() && temper > 0 && self.state.lock().temper < MAX_TEMPER;
+        if !(hurt || baby || calms) || !player.use_held_item(hand, item).await {
+            return false;
+        }
+        if hurt {
+            living.heal(health).await;
+        }
+        if baby {
+            self.breeding.grow();
+        }
+        if calms {
+            let mut state = self.state.lock();
+            state.temper = (state.temper + temper).min(MAX_TEMPER);
+        }
+        play_sound(mob, sound!("minecraft:entity.horse.eat")).await;
+        true
+    }
+
+    /// Puts the saddle or horse armor in the player's hand on a tamed horse which doesn't wear one
+    /// yet
+    async fn equip(&self, mob: &Mob, player: &Player, hand: Hand, item: &str) -> bool {
+        if !self.tameable.is_tamed() || self.breeding.is_baby() {
+            return false;
+        }
+        let saddle = item == SADDLE;
+        let Some(stack) = get_item(item).map(|item| ItemStack::new(1, item.id)) else {
+            return false;
+        };
+        let put = if saddle {
+            self.inventory.put_saddle(stack).await
+        } else {
+            self.inventory.put_body(stack).await
+        };
+        if !put || !player.use_held_item(hand, item).await {
+            return false;
+        }
+        if saddle {
+            self.update_flags(mob);
+            play_sound(mob, sound!("minecraft:entity.horse.saddle")).await;
+        } else {
+            play_sound(mob, sound!("minecraft:entity.horse.armor")).await;
+        }
+        true
+    }
+
+    /// Shows what players put on the horse or took off in its inventory
+    async fn update_inventory(&self, mob: &Mob) {
+        let change = self.inventory.update().await;
+        if change.saddle {
+            self.update_flags(mob);
+            if self.inventory.is_saddled() {
+                play_sound(mob, sound!("minecraft:entity.horse.saddle")).await;
+            }
+        }
+        // the entity tracker shows the armor
+        if change.body && self.inventory.body().is_some() {
+            play_sound(mob, sound!("minecraft:entity.horse.armor")).await;
+        }
+    }
+}
+
+#[async_trait]
+impl MobAi for Horse {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn tick(&self, mob: &Mob) -> bool {
+        self.breeding.tick(mob);
+        let rearing = {
+            let mut state = self.state.lock();
+            let rearing = state.rearing > 0;
+            state.rearing = state.rearing.saturating_sub(1);
+            rearing
+        };
+        if rearing {
+            self.update_flags(mob);
+        }
+        self.update_inventory(mob).await;
+        if thread_rng().gen_ratio(1, 120) {
+            play_sound(mob, sound!("minecraft:entity.horse.ambient")).await;
+        }
+        if let Some(rider) = self.rider(mob).await {
+            mob.stop_walking();
+            if !self.tameable.is_tamed() {
+                self.buck(mob, &rider).await;
+            }
+            return true;
+        }
+        if !self.breeding.breed(mob, WALK_SPEED).await
+            && !self.breeding.tempt(mob, WALK_SPEED).await
+        {
+            mob.wander(WANDER_RANGE, WALK_SPEED);
+        }
+        true
+    }
+
+    /// Dead horses throw off their rider
+    async fn on_hurt(&self, mob: &Mob, _attacker: Option<EntityId>) {
+        if mob.living_entity.health.load() > 0.0 {
+            play_sound(mob, sound!("minecraft:entity.horse.hurt")).await;
+            return;
+        }
+        if let Some(rider) = self.rider(mob).await {
+            rider.dismount().await;
+        }
+        let entity = &mob.living_entity.entity;
+        self.inventory.close(&entity.world).await;
+        play_sound(mob, sound!("minecraft:entity.horse.death")).await;
+    }
+
+    /// Horses eat their food and tamed ones get saddled or armored, otherwise the player mounts
+    /// them
+    async fn interact(&self, mob: &Mob, player: &Player, hand: Hand) -> bool {
+        let item = player.held_item_name(hand).await;
+        if let Some(item) = item {
+            if self.tameable.is_tamed()
+                && !self.breeding.is_baby()
+                && self.breeding.feed(mob, player, hand).await
+            {
+                return true;
+            }
+            if self.eat(mob, player, hand, item).await || self.equip(mob, player, hand, item).await
+            {
+                return true;
+            }
+        }
+        let entity = &mob.living_entity.entity;
+        let ridden = self.state.lock().rider.is_some();
+        if ridden
+            || self.breeding.is_baby()
+            || player.living_entity.entity.sneaking.load(Ordering::Relaxed)
+            || player.vehicle.lock().await.is_some()
+        {
+            return false;
+        }
+        if !player.start_riding(entity).await {
+            return false;
+        }
+        mob.stop_walking();
+        mob.send_attributes(player).await;
+        self.state.lock().rider = Some(player.entity_id());
+        // nothing was used up
+        false
+    }
+
+    fn experience(&self) -> i32 {
+        self.breeding.experience()
+    }
+
+    fn drops(&self) -> Vec<ItemStack> {
+        self.inventory.drops()
+    }
+
+    fn equipment(&self) -> Vec<(EquipmentSlot, Option<ItemStack>)> {
+        match self.inventory.body() {
+            Some(armor) => vec![(EquipmentSlot::Body, Some(armor))],
+            None => Vec::new(),
+        }
+    }
+
+    fn breeding(&self) -> Option<&Breeding> {
+        Some(&self.breeding)
+    }
+
+    fn tameable(&self) -> Option<&Tameable> {
+        Some(&self.tameable)
+    }
+
+    fn inventory(&self) -> Option<&MountInventory> {
+        self.tameable.is_tamed().then_some(&self.inventory)
+    }
+
+    async fn steered_by(&self, _mob: &Mob, _player: &Player) -> bool {
+        self.tameable.is_tamed() && self.inventory.is_saddled()
+    }
+
+    async fn jump(&self, mob: &Mob, _strength: i32) {
+        play_sound(mob, sound!("minecraft:entity.horse.jump")).await;
+    }
+
+    fn attributes(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            ("minecraft:movement_speed", self.speed),
+            ("minecraft:jump_strength", self.jump_strength),
+        ]
+    }
+}
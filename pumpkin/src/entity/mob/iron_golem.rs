@@ -0,0 +1,207 @@
+//! Iron golems: villagers call them to protect their village. They go after the monsters nearby,
+//! the players who hurt villagers and whoever hurts them, and throw them into the air.
+
+use std::{any::Any, sync::Arc};
+
+use async_trait::async_trait;
+use pumpkin_core::math::{boundingbox::BoundingBoxSize, position::WorldPosition, vector3::Vector3};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_macros::sound;
+use pumpkin_protocol::{client::play::CEntityStatus, SoundCategory};
+use rand::{thread_rng, Rng};
+
+use crate::world::World;
+
+use super::{
+    find_spawn_position, mob_entity, mobs_near, villager::is_villager, Mob, MobAi, Target,
+};
+
+const MAX_HEALTH: f32 = 100.0;
+const SIZE: BoundingBoxSize = BoundingBoxSize {
+    width: 1.4,
+    height: 2.7,
+};
+const EYE_HEIGHT: f32 = 2.295;
+/// Speeds in blocks per tick
+const WALK_SPEED: f64 = 0.06;
+const CHASE_SPEED: f64 = 0.15;
+const WANDER_RANGE: f64 = 10.0;
+
+/// How far golems look for what to attack
+const TARGET_RANGE: f64 = 16.0;
+/// Golems look for a new target every half second
+const TARGET_INTERVAL: u32 = 10;
+const REACH: f64 = 2.5;
+/// Golems hit for half their attack damage plus up to all of it
+const ATTACK_DAMAGE: f32 = 15.0;
+const ATTACK_KNOCKBACK: f64 = 0.4;
+/// How much golems throw what they hit up
+const ATTACK_LIFT: f64 = 0.4;
+const ATTACK_COOLDOWN: u32 = 20;
+/// Makes the client swing the golem's arms
+const ATTACK_STATUS: i8 = 4;
+
+/// Where villagers try to summon golems around them
+const SUMMON_ATTEMPTS: u32 = 10;
+const SUMMON_HORIZONTAL: i32 = 8;
+const SUMMON_VERTICAL: i32 = 6;
+
+#[derive(Default)]
+struct IronGolemState {
+    target: Option<EntityId>,
+    attack_cooldown: u32,
+}
+
+pub struct IronGolem {
+    state: parking_lot::Mutex<IronGolemState>,
+}
+
+impl IronGolem {
+    pub async fn spawn(world: &Arc<World>, position: Vector3<f64>) -> Arc<Mob> {
+        let entity = mob_entity(world, EntityType::IronGolem, position, SIZE, EYE_HEIGHT);
+        let golem = Self {
+            state: parking_lot::Mutex::new(IronGolemState::default()),
+        };
+        let mob = Mob::new(entity, Box::new(golem));
+        mob.living_entity.set_max_health(MAX_HEALTH);
+        let mob = Arc::new(mob);
+        world.spawn_entity(mob.clone()).await;
+        mob
+    }
+
+    /// Summons a golem on solid ground near the position, returns false if there was no room for
+    /// one
+    pub async fn try_summon(world: &Arc<World>, position: WorldPosition) -> bool {
+        let Some(feet) = find_spawn_position(
+            world,
+            position,
+            SUMMON_ATTEMPTS,
+            SUMMON_HORIZONTAL,
+            SUMMON_VERTICAL,
+            &SIZE,
+        )
+        .await
+        else {
+            return false;
+        };
+        Self::spawn(world, feet).await;
+        true
+    }
+
+    /// A player who hurt a villager nearby lately, or else the nearest monster. Golems leave
+    /// creepers alone
+    async fn find_target(mob: &Mob) -> Option<Target> {
+        let entity = &mob.living_entity.entity;
+        let world = &entity.world;
+        let pos = entity.pos.load();
+        for villager in mobs_near(world, pos, TARGET_RANGE, is_villager).await {
+            let Some((attacker, _)) = villager
+                .get_living_entity()
+                .and_then(|living| living.kill_credit.load())
+            else {
+                continue;
+            };
+            if world.get_player_by_entityid(attacker).await.is_none() {
+                continue;
+            }
+            if let Some(target) = Target::find(world, attacker).await {
+                return Some(target);
+            }
+        }
+        let monster = mobs_near(world, pos, TARGET_RANGE, |entity_type| {
+            entity_type.is_enemy() && entity_type != EntityType::Creeper
+        })
+        .await
+        .into_iter()
+        .next()?;
+        Some(Target::Mob(monster))
+    }
+
+    /// Walks up to the target and hits it once it is in reach
+    async fn fight(&self, mob: &Mob, target: &Target) {
+        let entity = &mob.living_entity.entity;
+        let target_pos = target.entity().pos.load();
+        let offset = target_pos.sub(&entity.pos.load());
+        if offset.x.hypot(offset.z) > REACH || offset.y.abs() > SIZE.height {
+            mob.walk_to(target_pos, CHASE_SPEED);
+            return;
+        }
+        mob.stop_walking();
+        mob.look_at(target_pos);
+        let attack = {
+            let mut state = self.state.lock();
+            let ready = state.attack_cooldown == 0;
+            if ready {
+                state.attack_cooldown = ATTACK_COOLDOWN;
+            }
+            ready
+        };
+        if !attack {
+            return;
+        }
+        let world = &entity.world;
+        world
+            .broadcast_packet_all(&CEntityStatus::new(entity.entity_id, ATTACK_STATUS))
+            .await;
+        let damage =
+            ATTACK_DAMAGE / 2.0 + f32::from(thread_rng().gen_range(0..ATTACK_DAMAGE as u8));
+        mob.melee(target, damage, ATTACK_KNOCKBACK, ATTACK_LIFT)
+            .await;
+        world
+            .play_sound(
+                sound!("minecraft:entity.iron_golem.attack"),
+                SoundCategory::Neutral,
+                &entity.pos.load(),
+            )
+            .await;
+    }
+}
+
+#[async_trait]
+impl MobAi for IronGolem {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn tick(&self, mob: &Mob) -> bool {
+        let world = &mob.living_entity.entity.world;
+        let target = {
+            let mut state = self.state.lock();
+            state.attack_cooldown = state.attack_cooldown.saturating_sub(1);
+            state.target
+        };
+        let mut target = match target {
+            Some(target) => Target::find(world, target).await,
+            None => None,
+        };
+        if target.is_none() && mob.age() % TARGET_INTERVAL == 0 {
+            target = Self::find_target(mob).await;
+        }
+        self.state.lock().target = target.as_ref().map(|target| target.entity().entity_id);
+        match target {
+            Some(target) => self.fight(mob, &target).await,
+            None => mob.wander(WANDER_RANGE, WALK_SPEED),
+        }
+        true
+    }
+
+    async fn on_hurt(&self, mob: &Mob, attacker: Option<EntityId>) {
+        if let Some(attacker) = attacker {
+            self.state.lock().target = Some(attacker);
+        }
+        let sound = if mob.living_entity.health.load() > 0.0 {
+            sound!("minecraft:entity.iron_golem.hurt")
+        } else {
+            sound!("minecraft:entity.iron_golem.death")
+        };
+        let entity = &mob.living_entity.entity;
+        entity
+            .world
+            .play_sound(sound, SoundCategory::Neutral, &entity.pos.load())
+            .await;
+    }
+
+    fn knockback_resistance(&self) -> f64 {
+        1.0
+    }
+}
@@ -0,0 +1,855 @@
+//! Mobs: living entities which move and act by themselves. What a kind of mob does is up to its
+//! [`MobAi`], the mob walks wherever its AI sends it and drops experience when a player killed it.
+
+use std::{
+    any::Any,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc,
+    },
+};
+
+use async_trait::async_trait;
+use crossbeam::atomic::AtomicCell;
+use pumpkin_config::KnockbackConfig;
+use pumpkin_core::{
+    math::{
+        boundingbox::{BoundingBox, BoundingBoxSize},
+        position::WorldPosition,
+        vector3::Vector3,
+    },
+    Difficulty, GameMode,
+};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_protocol::{
+    client::play::{
+        CEntityStatus, CEntityVelocity, CSpawnEntity, CUpdateAttributes, EquipmentSlot,
+    },
+    packet_encoder::PreparedPacket,
+    SoundCategory, VarInt,
+};
+use pumpkin_world::{chunk::entities::ChunkEntity, item::ItemStack};
+use rand::{thread_rng, Rng};
+use uuid::Uuid;
+
+use crate::{
+    client::combat::knockback_resistance,
+    world::{chunk_entities::saved_entity, vibration::GameEvent, World},
+};
+
+use super::{
+    damage::{DamageSource, MOB_ATTACK},
+    experience_orb::ExperienceOrb,
+    item::ItemEntity,
+    living::LivingEntity,
+    new_entity_id,
+    player::{Hand, Player},
+    Entity, EntityBase,
+};
+
+pub mod ageable;
+pub mod animal;
+pub mod camel;
+pub mod cat;
+pub mod enderman;
+pub mod horse;
+pub mod iron_golem;
+mod monster;
+pub mod mount;
+pub mod parrot;
+pub mod phantom;
+pub mod pillager;
+pub mod skeleton;
+pub mod spider;
+pub mod strider;
+pub mod tameable;
+pub mod trader_llama;
+pub mod villager;
+pub mod wandering_trader;
+pub mod warden;
+pub mod wolf;
+pub mod zombie;
+pub mod zombie_villager;
+
+const GRAVITY: f64 = 0.08;
+const DRAG: f64 = 0.98;
+/// How much of the velocity is kept every tick, on most blocks and in the air
+const GROUND_FRICTION: f64 = 0.546;
+const AIR_FRICTION: f64 = 0.91;
+const JUMP_VELOCITY: f64 = 0.42;
+/// How fast climbing mobs go up walls
+const CLIMB_VELOCITY: f64 = 0.2;
+/// How close the mob has to get to where it walks to
+const ARRIVED_DISTANCE: f64 = 0.5;
+/// Ticks the death animation takes until the mob is removed
+const DEATH_TICKS: u32 = 20;
+/// One in this many idle ticks a mob starts wandering somewhere
+const WANDER_CHANCE: u32 = 120;
+/// How far mobs run from what they flee from
+const FLEE_DISTANCE: f64 = 8.0;
+/// Ticks until players can pick up what a mob dropped
+const DROP_PICKUP_DELAY: u32 = 10;
+
+/// How a kind of mob gets around
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Movement {
+    Walk,
+    /// Walks up the walls in its way, like spiders
+    Climb,
+    /// Flies straight to where it goes without falling
+    Fly,
+}
+
+/// What a kind of mob does, the mob calls it every tick and when something happens to it
+#[async_trait]
+pub trait MobAi: Send + Sync {
+    /// The AI as [`Any`], to get at the state of a certain kind of mob
+    fn as_any(&self) -> &dyn Any;
+
+    /// Called every tick while the mob is alive, before it moves. The mob is removed once this
+    /// returns false, without dying
+    async fn tick(&self, mob: &Mob) -> bool;
+
+    /// The mob heard the game event, `cause` is the entity which caused it
+    async fn hear(
+        &self,
+        _mob: &Mob,
+        _event: GameEvent,
+        _position: Vector3<f64>,
+        _cause: Option<EntityId>,
+    ) {
+    }
+
+    /// The mob was hurt, by the attacker if there was one
+    async fn on_hurt(&self, _mob: &Mob, _attacker: Option<EntityId>) {}
+
+    /// The player right clicked the mob with what it holds in the hand. Returns false if nothing
+    /// happened
+    async fn interact(&self, _mob: &Mob, _player: &Player, _hand: Hand) -> bool {
+        false
+    }
+
+    /// How much of the knockback the mob shrugs off, at 1 it isn't knocked back at all
+    fn knockback_resistance(&self) -> f64 {
+        0.0
+    }
+
+    fn movement(&self) -> Movement {
+        Movement::Walk
+    }
+
+    /// Whether nothing can hurt the mob right now
+    fn invulnerable(&self, _mob: &Mob) -> bool {
+        false
+    }
+
+    /// Whether fire and lava don't hurt the mob
+    fn fire_immune(&self) -> bool {
+        false
+    }
+
+    /// The experience the mob drops when a player killed it
+    fn experience(&self) -> i32 {
+        0
+    }
+
+    /// The items the mob drops when it dies, like the saddle of a horse
+    fn drops(&self) -> Vec<ItemStack> {
+        Vec::new()
+    }
+
+    /// The items the mob wears, for players which start seeing it
+    fn equipment(&self) -> Vec<(EquipmentSlot, Option<ItemStack>)> {
+        Vec::new()
+    }
+
+    /// How the mob breeds, for mobs which players breed by feeding them
+    fn breeding(&self) -> Option<&animal::Breeding> {
+        None
+    }
+
+    /// Who the mob belongs to, for mobs which players tame
+    fn tameable(&self) -> Option<&tameable::Tameable> {
+        None
+    }
+
+    /// The data of the spawn packet, some mobs tell the client what they are doing with it
+    fn spawn_data(&self, _mob: &Mob) -> i32 {
+        0
+    }
+
+    /// The inventory players open by sneaking and using the mob or while riding it, `None` if it
+    /// has none or doesn't let players look into it
+    fn inventory(&self) -> Option<&mount::MountInventory> {
+        None
+    }
+
+    /// Whether the player riding the mob in front steers it, like a saddled horse
+    async fn steered_by(&self, _mob: &Mob, _player: &Player) -> bool {
+        false
+    }
+
+    /// The player steering the mob made it jump, with a strength from 0 to 100
+    async fn jump(&self, _mob: &Mob, _strength: i32) {}
+
+    /// The player steering the mob used the item it steers with, like a warped fungus on a stick.
+    /// Returns false if the mob wasn't boosted
+    async fn boost(&self, _mob: &Mob) -> bool {
+        false
+    }
+
+    /// The base values of the attributes a client steering the mob moves it with, like the speed
+    /// of a horse
+    fn attributes(&self) -> Vec<(&'static str, f64)> {
+        Vec::new()
+    }
+}
+
+/// A new entity of the mob type standing at the position, for the AI to be added to
+fn mob_entity(
+    world: &Arc<World>,
+    entity_type: EntityType,
+    position: Vector3<f64>,
+    size: BoundingBoxSize,
+    eye_height: f32,
+) -> Entity {
+    let entity = Entity::new(
+        new_entity_id(),
+        world.clone(),
+        entity_type,
+        eye_height,
+        AtomicCell::new(BoundingBox::new_from_pos(
+            position.x, position.y, position.z, &size,
+        )),
+        AtomicCell::new(size),
+    );
+    entity.set_pos(position.x, position.y, position.z);
+    entity
+}
+
+/// A spot on solid ground near the position with room for a mob of the size. Tries random
+/// columns around the position, each from the top down
+pub(crate) async fn find_spawn_position(
+    world: &World,
+    position: WorldPosition,
+    attempts: u32,
+    horizontal: i32,
+    vertical: i32,
+    size: &BoundingBoxSize,
+) -> Option<Vector3<f64>> {
+    let pos = position.0;
+    for _ in 0..attempts {
+        let (x, z) = {
+            let mut rng = thread_rng();
+            (
+                pos.x + rng.gen_range(-horizontal..=horizontal),
+                pos.z + rng.gen_range(-horizontal..=horizontal),
+            )
+        };
+        for y in (pos.y - vertical..=pos.y + vertical).rev() {
+            let feet = Vector3::new(f64::from(x) + 0.5, f64::from(y), f64::from(z) + 0.5);
+            let body = BoundingBox::new_from_pos(feet.x, feet.y, feet.z, size);
+            let ground = BoundingBox {
+                min_y: feet.y - 0.5,
+                max_y: feet.y,
+                ..body
+            };
+            if !world.collision_boxes(&ground).await.is_empty()
+                && world.collision_boxes(&body).await.is_empty()
+            {
+                return Some(feet);
+            }
+        }
+    }
+    None
+}
+
+/// Spawns a mob of the type at the position, returns `None` if the type isn't a mob we have an AI
+/// for
+pub async fn summon(
+    world: &Arc<World>,
+    entity_type: EntityType,
+    position: Vector3<f64>,
+) -> Option<Arc<Mob>> {
+    let mob = match entity_type {
+        EntityType::Enderman => enderman::Enderman::spawn(world, position).await,
+        EntityType::IronGolem => iron_golem::IronGolem::spawn(world, position).await,
+        EntityType::Parrot => parrot::Parrot::spawn(world, position).await,
+        EntityType::Phantom => phantom::Phantom::spawn(world, position).await,
+        EntityType::Pillager => pillager::Pillager::spawn(world, position, None).await,
+        EntityType::Skeleton => skeleton::Skeleton::spawn(world, position).await,
+        EntityType::Spider => spider::Spider::spawn(world, position).await,
+        EntityType::TraderLlama => trader_llama::TraderLlama::spawn(world, position, None).await,
+        EntityType::WanderingTrader => {
+            wandering_trader::WanderingTrader::spawn(world, position, None, None).await
+        }
+        EntityType::Warden => warden::Warden::spawn(world, position).await,
+        EntityType::Zombie => zombie::Zombie::spawn(world, position).await,
+        _ => return spawn_ageable(world, entity_type, position, false).await,
+    };
+    Some(mob)
+}
+
+/// Spawns a baby or an adult of the mobs which grow up, returns `None` if the type isn't one of
+/// them
+async fn spawn_ageable(
+    world: &Arc<World>,
+    entity_type: EntityType,
+    position: Vector3<f64>,
+    baby: bool,
+) -> Option<Arc<Mob>> {
+    let mob = match entity_type {
+        EntityType::Camel => camel::Camel::spawn(world, position, baby).await,
+        EntityType::Cat => cat::Cat::spawn(world, position, baby).await,
+        EntityType::Horse => horse::Horse::spawn(world, position, baby).await,
+        EntityType::Strider => strider::Strider::spawn(world, position, baby).await,
+        EntityType::Villager => villager::Villager::spawn(world, position, baby).await,
+        EntityType::Wolf => wolf::Wolf::spawn(world, position, baby).await,
+        EntityType::ZombieVillager => {
+            zombie_villager::ZombieVillager::spawn(world, position, baby).await
+        }
+        _ => return animal::FarmAnimal::spawn(world, entity_type, position, baby).await,
+    };
+    Some(mob)
+}
+
+/// The living mobs of the matching types in range of the position, nearest first
+async fn mobs_near(
+    world: &World,
+    position: Vector3<f64>,
+    range: f64,
+    matches: impl Fn(EntityType) -> bool + Send,
+) -> Vec<Arc<dyn EntityBase>> {
+    let mut mobs: Vec<_> = world
+        .entities
+        .lock()
+        .await
+        .values()
+        .filter(|entity| {
+            let inner = entity.get_entity();
+            matches(inner.entity_type)
+                && entity
+                    .get_mob()
+                    .is_some_and(|mob| mob.living_entity.health.load() > 0.0)
+                && inner.pos.load().sub(&position).length() <= range
+        })
+        .cloned()
+        .collect();
+    mobs.sort_by(|a, b| {
+        let distance = |entity: &Arc<dyn EntityBase>| {
+            entity
+                .get_entity()
+                .pos
+                .load()
+                .sub(&position)
+                .length_squared()
+        };
+        distance(a).total_cmp(&distance(b))
+    });
+    mobs
+}
+
+/// The damage of a mob hitting a player, it depends on the difficulty
+fn player_damage(difficulty: Difficulty, damage: f32) -> f32 {
+    match difficulty {
+        Difficulty::Peaceful => 0.0,
+        Difficulty::Easy => (damage / 2.0 + 1.0).min(damage),
+        Difficulty::Normal => damage,
+        Difficulty::Hard => damage * 1.5,
+    }
+}
+
+async fn play_sound(mob: &Mob, sound: u16) {
+    let entity = &mob.living_entity.entity;
+    entity
+        .world
+        .play_sound(sound, SoundCategory::Neutral, &entity.pos.load())
+        .await;
+}
+
+async fn show_status(mob: &Mob, status: i8) {
+    let entity = &mob.living_entity.entity;
+    entity
+        .world
+        .broadcast_packet_all(&CEntityStatus::new(entity.entity_id, status))
+        .await;
+}
+
+/// The position of the attacker, if it is still around
+async fn attacker_pos(world: &World, attacker: EntityId) -> Option<Vector3<f64>> {
+    match world.get_player_by_entityid(attacker).await {
+        Some(player) => Some(player.living_entity.entity.pos.load()),
+        None => world
+            .entities
+            .lock()
+            .await
+            .get(&attacker)
+            .map(|attacker| attacker.get_entity().pos.load()),
+    }
+}
+
+/// Pushes the player, players move themselves so they are told about it
+async fn push(player: &Player) {
+    let entity = &player.living_entity.entity;
+    let velocity = entity.velocity.load();
+    player.movement_check.lock().add_velocity(velocity);
+    player
+        .client
+        .send_packet(&CEntityVelocity::new(
+            &VarInt(entity.entity_id),
+            velocity.x as f32,
+            velocity.y as f32,
+            velocity.z as f32,
+        ))
+        .await;
+}
+
+/// What a mob goes after, players aren't among the other entities of the world
+#[derive(Clone)]
+pub enum Target {
+    Player(Arc<Player>),
+    Mob(Arc<dyn EntityBase>),
+}
+
+impl Target {
+    /// The entity with the id, if it is alive and can be attacked. Mobs leave creative and
+    /// spectator players alone
+    async fn find(world: &World, id: EntityId) -> Option<Self> {
+        let target = match world.get_player_by_entityid(id).await {
+            Some(player) => Self::Player(player),
+            None => Self::Mob(world.entities.lock().await.get(&id).cloned()?),
+        };
+        target.can_be_attacked().then_some(target)
+    }
+
+    fn can_be_attacked(&self) -> bool {
+        match self {
+            Self::Player(player) => {
+                matches!(
+                    player.gamemode.load(),
+                    GameMode::Survival | GameMode::Adventure
+                ) && player.living_entity.health.load() > 0.0
+            }
+            Self::Mob(entity) => entity
+                .get_mob()
+                .is_some_and(|mob| mob.living_entity.health.load() > 0.0),
+        }
+    }
+
+    #[must_use]
+    pub fn entity(&self) -> &Entity {
+        match self {
+            Self::Player(player) => &player.living_entity.entity,
+            Self::Mob(entity) => entity.get_entity(),
+        }
+    }
+}
+
+pub struct Mob {
+    pub living_entity: LivingEntity,
+    uuid: Uuid,
+    ai: Box<dyn MobAi>,
+    /// Where the mob walks to, with its speed in blocks per tick
+    walk_target: AtomicCell<Option<(Vector3<f64>, f64)>>,
+    /// Ticks since it was spawned
+    age: AtomicU32,
+    /// Ticks since it died
+    death_ticks: AtomicU32,
+    /// Whether a wall stopped it last tick
+    against_wall: AtomicBool,
+}
+
+impl Mob {
+    /// The mob doing what the AI says, it still has to be spawned into its world
+    pub fn new(entity: Entity, ai: Box<dyn MobAi>) -> Self {
+        let pos = entity.pos.load();
+        let living_entity = LivingEntity::new(entity);
+        living_entity.last_pos.store(pos);
+        Self {
+            living_entity,
+            uuid: Uuid::new_v4(),
+            ai,
+            walk_target: AtomicCell::new(None),
+            age: AtomicU32::new(0),
+            death_ticks: AtomicU32::new(0),
+            against_wall: AtomicBool::new(false),
+        }
+    }
+
+    #[must_use]
+    pub fn age(&self) -> u32 {
+        self.age.load(Ordering::Relaxed)
+    }
+
+    /// The AI if the mob is that kind of mob
+    #[must_use]
+    pub fn ai<T: MobAi + 'static>(&self) -> Option<&T> {
+        self.ai.as_any().downcast_ref()
+    }
+
+    #[must_use]
+    pub fn is_walking(&self) -> bool {
+        self.walk_target.load().is_some()
+    }
+
+    /// Whether a wall stopped the mob from moving on last tick
+    #[must_use]
+    pub fn is_against_wall(&self) -> bool {
+        self.against_wall.load(Ordering::Relaxed)
+    }
+
+    /// Lets the mob walk to the position, with the speed in blocks per tick
+    pub fn walk_to(&self, target: Vector3<f64>, speed: f64) {
+        self.walk_target.store(Some((target, speed)));
+    }
+
+    pub fn stop_walking(&self) {
+        self.walk_target.store(None);
+    }
+
+    /// Now and then walks to a random spot in range, while it isn't walking anywhere
+    pub fn wander(&self, range: f64, speed: f64) {
+        if self.is_walking() {
+            return;
+        }
+        let mut rng = thread_rng();
+        if !rng.gen_ratio(1, WANDER_CHANCE) {
+            return;
+        }
+        let pos = self.living_entity.entity.pos.load();
+        let target = Vector3::new(
+            pos.x + rng.gen_range(-range..=range),
+            pos.y,
+            pos.z + rng.gen_range(-range..=range),
+        );
+        self.walk_to(target, speed);
+    }
+
+    /// Runs away from the position, in a random direction if it stands right there
+    pub fn flee_from(&self, threat: Vector3<f64>, speed: f64) {
+        let pos = self.living_entity.entity.pos.load();
+        let away = Vector3::new(pos.x - threat.x, 0.0, pos.z - threat.z);
+        let away = if away.length_squared() < 1.0E-4 {
+            let angle = thread_rng().gen_range(0.0..std::f64::consts::TAU);
+            Vector3::new(angle.cos(), 0.0, angle.sin())
+        } else {
+            away.normalize()
+        };
+        self.walk_to(pos.add(&(away * FLEE_DISTANCE)), speed);
+    }
+
+    /// Hits the target in front of the mob, players take more or less depending on the
+    /// difficulty. `lift` throws the target up on top of the knockback. Returns false if the
+    /// target took no damage
+    async fn melee(&self, target: &Target, damage: f32, knockback: f64, lift: f64) -> bool {
+        let entity = &self.living_entity.entity;
+        let source = DamageSource::by(MOB_ATTACK, entity.entity_id);
+        let resistance = match target {
+            Target::Player(player) => {
+                let living = &player.living_entity;
+                let damage = player_damage(entity.world.config.difficulty, damage);
+                if player.abilities.lock().await.invulnerable || !living.check_damage(damage) {
+                    return false;
+                }
+                living.damage(damage, source).await;
+                knockback_resistance(player).await
+            }
+            Target::Mob(victim) => {
+                let Some(living) = victim.get_living_entity() else {
+                    return false;
+                };
+                if !living.check_damage(damage) || !victim.damage(damage, source).await {
+                    return false;
+                }
+                victim.get_mob().map_or(0.0, Mob::knockback_resistance)
+            }
+        };
+        let victim = target.entity();
+        let resistance = 1.0 - resistance;
+        if resistance > 0.0 {
+            let yaw = entity.yaw.load().to_radians();
+            victim.knockback(
+                knockback * resistance,
+                knockback * resistance,
+                f64::from(yaw.sin()),
+                f64::from(-yaw.cos()),
+                &KnockbackConfig::default(),
+            );
+            let mut velocity = victim.velocity.load();
+            velocity.y += lift * resistance;
+            victim.velocity.store(velocity);
+        }
+        if let Target::Player(player) = target {
+            push(player).await;
+        }
+        true
+    }
+
+    /// How much of the knockback of an attack the mob shrugs off
+    #[must_use]
+    pub fn knockback_resistance(&self) -> f64 {
+        self.ai.knockback_resistance()
+    }
+
+    #[must_use]
+    pub fn breeding(&self) -> Option<&animal::Breeding> {
+        self.ai.breeding()
+    }
+
+    #[must_use]
+    pub fn tameable(&self) -> Option<&tameable::Tameable> {
+        self.ai.tameable()
+    }
+
+    #[must_use]
+    pub fn inventory(&self) -> Option<&mount::MountInventory> {
+        self.ai.inventory()
+    }
+
+    /// The player steering the mob, its first passenger if the AI lets it steer
+    pub async fn controller(&self) -> Option<Arc<Player>> {
+        let entity = &self.living_entity.entity;
+        let first = entity.passengers.lock().first().copied()?;
+        let player = entity.world.get_player_by_entityid(first).await?;
+        self.ai.steered_by(self, &player).await.then_some(player)
+    }
+
+    pub async fn jump(&self, strength: i32) {
+        self.ai.jump(self, strength).await;
+    }
+
+    pub async fn boost(&self) -> bool {
+        self.ai.boost(self).await
+    }
+
+    /// Tells the player the attributes its client needs to steer the mob
+    pub async fn send_attributes(&self, player: &Player) {
+        let attributes = self.ai.attributes();
+        if attributes.is_empty() {
+            return;
+        }
+        player
+            .client
+            .send_packet(&CUpdateAttributes::new(
+                self.living_entity.entity.entity_id.into(),
+                &attributes,
+            ))
+            .await;
+    }
+
+    /// Lets the AI handle the player right clicking the mob, dead mobs do nothing
+    pub async fn interact(&self, player: &Player, hand: Hand) -> bool {
+        self.living_entity.health.load() > 0.0 && self.ai.interact(self, player, hand).await
+    }
+
+    /// Turns the mob towards the position
+    pub fn look_at(&self, target: Vector3<f64>) {
+        let entity = &self.living_entity.entity;
+        let eyes = entity.pos.load().y + f64::from(entity.standing_eye_height);
+        let offset = target.sub(&entity.pos.load());
+        let horizontal = offset.x.hypot(offset.z);
+        let yaw = (-offset.x).atan2(offset.z).to_degrees();
+        let pitch = -(target.y - eyes).atan2(horizontal).to_degrees();
+        entity.set_rotation(yaw as f32, pitch as f32);
+    }
+
+    /// The velocity the mob wants to walk with, zero once it arrived. Only flying mobs go up and
+    /// down by themselves
+    fn walk_velocity(&self, flying: bool) -> Vector3<f64> {
+        let Some((target, speed)) = self.walk_target.load() else {
+            return Vector3::new(0.0, 0.0, 0.0);
+        };
+        let mut offset = target.sub(&self.living_entity.entity.pos.load());
+        if !flying {
+            offset.y = 0.0;
+        }
+        let distance = offset.length();
+        if distance < ARRIVED_DISTANCE {
+            self.walk_target.store(None);
+            return Vector3::new(0.0, 0.0, 0.0);
+        }
+        offset * (speed.min(distance) / distance)
+    }
+
+    /// Moves the mob like vanilla does, friction only slows down what it doesn't want to walk.
+    /// Walking mobs jump up blocks in their way and climbing ones walk up walls, flying mobs don't
+    /// fall
+    async fn travel(&self) {
+        let living = &self.living_entity;
+        let entity = &living.entity;
+        let movement = self.ai.movement();
+        let flying = movement == Movement::Fly;
+        let on_ground = entity.on_ground.load(Ordering::Relaxed);
+        let friction = if on_ground && !flying {
+            GROUND_FRICTION
+        } else {
+            AIR_FRICTION
+        };
+        let wanted = self.walk_velocity(flying);
+        let walking = wanted.length_squared() > 0.0;
+        if walking {
+            let yaw = (-wanted.x).atan2(wanted.z).to_degrees();
+            let pitch = if flying {
+                -wanted.y.atan2(wanted.x.hypot(wanted.z)).to_degrees()
+            } else {
+                0.0
+            };
+            entity.set_rotation(yaw as f32, pitch as f32);
+        }
+
+        let mut velocity = entity.velocity.load();
+        velocity.x = wanted.x + (velocity.x - wanted.x) * friction;
+        velocity.z = wanted.z + (velocity.z - wanted.z) * friction;
+        velocity.y = if flying {
+            wanted.y + (velocity.y - wanted.y) * friction
+        } else {
+            (velocity.y - GRAVITY) * DRAG
+        };
+        living.last_pos.store(entity.pos.load());
+        let mut moved = entity.move_colliding(velocity).await;
+        #[expect(clippy::float_cmp)]
+        let blocked = moved.x != velocity.x || moved.z != velocity.z;
+        self.against_wall.store(blocked, Ordering::Relaxed);
+        match movement {
+            Movement::Walk if walking && blocked && entity.on_ground.load(Ordering::Relaxed) => {
+                moved.y = JUMP_VELOCITY;
+            }
+            Movement::Climb if blocked => {
+                moved.y = CLIMB_VELOCITY;
+                living.fall_distance.store(0.0);
+            }
+            _ => {}
+        }
+        entity.velocity.store(moved);
+        if !flying {
+            living.update_fall_distance(false).await;
+        }
+    }
+
+    /// Lets the AI hear the game event, dead mobs hear nothing
+    pub async fn hear(&self, event: GameEvent, position: Vector3<f64>, cause: Option<EntityId>) {
+        if self.living_entity.health.load() > 0.0 {
+            self.ai.hear(self, event, position, cause).await;
+        }
+    }
+
+    /// Waits for the death animation, then drops its items and the experience if a player
+    /// killed the mob. Returns false once the mob is gone
+    async fn tick_death(&self) -> bool {
+        if self.death_ticks.fetch_add(1, Ordering::Relaxed) + 1 < DEATH_TICKS {
+            return true;
+        }
+        let entity = &self.living_entity.entity;
+        let world = &entity.world;
+        for stack in self.ai.drops() {
+            let velocity = {
+                let mut rng = thread_rng();
+                Vector3::new(rng.gen_range(-0.1..0.1), 0.2, rng.gen_range(-0.1..0.1))
+            };
+            let item = ItemEntity::new(
+                world.clone(),
+                stack,
+                entity.pos.load(),
+                velocity,
+                DROP_PICKUP_DELAY,
+            );
+            world.spawn_entity(Arc::new(item)).await;
+        }
+        let killed_by_player = match self.living_entity.killer() {
+            Some(killer) => world.get_player_by_entityid(killer).await.is_some(),
+            None => false,
+        };
+        let experience = self.ai.experience();
+        if killed_by_player && experience > 0 {
+            let pos = entity.pos.load();
+            if !world
+                .absorb_experience(entity.block_pos.load(), experience)
+                .await
+            {
+                ExperienceOrb::spawn(world, pos, experience).await;
+            }
+        }
+        false
+    }
+}
+
+#[async_trait]
+impl EntityBase for Mob {
+    fn get_entity(&self) -> &Entity {
+        &self.living_entity.entity
+    }
+
+    async fn tick(&self) -> bool {
+        let living = &self.living_entity;
+        if living.health.load() <= 0.0 {
+            return self.tick_death().await;
+        }
+        self.age.fetch_add(1, Ordering::Relaxed);
+        living.tick();
+        living
+            .tick_environment(self.ai.invulnerable(self), self.ai.fire_immune(), false)
+            .await;
+        living.tick_effects().await;
+        if !self.ai.tick(self).await {
+            return false;
+        }
+        // the client of the player steering the mob moves it
+        if self.controller().await.is_none() {
+            self.travel().await;
+        }
+        living.entity.send_data_changes().await;
+        true
+    }
+
+    fn spawn_packet(&self, position: Vector3<f64>) -> PreparedPacket {
+        let entity = &self.living_entity.entity;
+        let velocity = entity.velocity.load();
+        PreparedPacket::new(&CSpawnEntity::new(
+            entity.entity_id.into(),
+            self.uuid,
+            (entity.entity_type as i32).into(),
+            position.x,
+            position.y,
+            position.z,
+            entity.pitch.load(),
+            entity.yaw.load(),
+            entity.head_yaw.load(),
+            self.ai.spawn_data(self).into(),
+            velocity.x as f32,
+            velocity.y as f32,
+            velocity.z as f32,
+        ))
+    }
+
+    fn get_living_entity(&self) -> Option<&LivingEntity> {
+        Some(&self.living_entity)
+    }
+
+    fn get_mob(&self) -> Option<&Mob> {
+        Some(self)
+    }
+
+    fn equipment(&self) -> Vec<(EquipmentSlot, Option<ItemStack>)> {
+        self.ai.equipment()
+    }
+
+    fn chunk_entity(&self) -> Option<ChunkEntity> {
+        if self.living_entity.health.load() <= 0.0 {
+            return None;
+        }
+        Some(ChunkEntity {
+            owner: self.tameable().and_then(tameable::Tameable::owner),
+            ..saved_entity(&self.living_entity.entity)
+        })
+    }
+
+    async fn damage(&self, amount: f32, source: DamageSource) -> bool {
+        let living = &self.living_entity;
+        if living.health.load() <= 0.0 || self.ai.invulnerable(self) {
+            return false;
+        }
+        living.damage(amount, source).await;
+        self.ai.on_hurt(self, source.attacker).await;
+        true
+    }
+}
@@ -0,0 +1,180 @@
+//! What monsters have in common: they go after the players near them, chase them down and hit
+//! them. Some of them burn in sunlight.
+
+use std::sync::Arc;
+
+use pumpkin_core::{
+    math::{position::WorldPosition, vector3::Vector3},
+    Difficulty,
+};
+use pumpkin_entity::EntityId;
+use pumpkin_world::{
+    block::block_registry::get_state_by_state_id,
+    item::{item_registry::get_item, ItemStack},
+};
+use rand::{thread_rng, Rng};
+
+use crate::{
+    entity::{
+        arrow::{shoot_velocity, Arrow},
+        player::Player,
+        Entity,
+    },
+    world::{vibration::GameEvent, World},
+};
+
+use super::{Mob, Target};
+
+/// Monsters caught in sunlight burn for 8 seconds
+const SUN_FIRE_TICKS: i32 = 160;
+/// Monsters look up at the sky once a second
+const SUN_CHECK_INTERVAL: u32 = 20;
+const ATTACK_KNOCKBACK: f64 = 0.4;
+
+/// Up to `max` of the item, `None` if none dropped this time
+pub(super) fn random_drop(item: &str, max: u8) -> Option<ItemStack> {
+    let count = thread_rng().gen_range(0..=max);
+    let item = get_item(item)?;
+    (count > 0).then(|| ItemStack::new(count, item.id))
+}
+
+/// Where the eyes of the entity are
+pub(super) fn eyes(entity: &Entity) -> Vector3<f64> {
+    let pos = entity.pos.load();
+    Vector3::new(pos.x, pos.y + f64::from(entity.standing_eye_height), pos.z)
+}
+
+/// Whether the sun shines on the head of the mob
+pub(super) async fn in_sunlight(mob: &Mob) -> bool {
+    let entity = &mob.living_entity.entity;
+    let head = eyes(entity);
+    let head = WorldPosition(Vector3::new(
+        head.x.floor() as i32,
+        head.y.floor() as i32,
+        head.z.floor() as i32,
+    ));
+    !mob.living_entity.is_in_water().await && entity.world.is_in_sunlight(head).await
+}
+
+/// Sets the mob on fire while the sun shines on it
+pub(super) async fn burn_in_sunlight(mob: &Mob) {
+    if mob.age() % SUN_CHECK_INTERVAL == 0 && in_sunlight(mob).await {
+        mob.living_entity.set_on_fire(SUN_FIRE_TICKS);
+    }
+}
+
+/// Whether nothing solid is between the points, the blocks they are in don't count
+pub(super) async fn can_see(world: &World, from: Vector3<f64>, to: Vector3<f64>) -> bool {
+    let block_at = |point: Vector3<f64>| {
+        WorldPosition(Vector3::new(
+            point.x.floor() as i32,
+            point.y.floor() as i32,
+            point.z.floor() as i32,
+        ))
+    };
+    let line = to.sub(&from);
+    let steps = (line.length() * 4.0).ceil() as u32;
+    let (start, end) = (block_at(from), block_at(to));
+    let mut last = start;
+    for step in 1..steps {
+        let block = block_at(from.add(&(line * (f64::from(step) / f64::from(steps)))));
+        if block == last || block == end {
+            continue;
+        }
+        last = block;
+        let solid = world.get_block_state_id(block).await.is_ok_and(|state_id| {
+            get_state_by_state_id(state_id).is_some_and(|state| !state.collision_shapes.is_empty())
+        });
+        if solid {
+            return false;
+        }
+    }
+    true
+}
+
+/// The nearest player in range which mobs attack, of those matching
+pub(super) async fn nearest_player(
+    mob: &Mob,
+    range: f64,
+    matches: impl Fn(&Player) -> bool + Send,
+) -> Option<Target> {
+    let entity = &mob.living_entity.entity;
+    let pos = entity.pos.load();
+    let players = entity.world.players().await;
+    players
+        .iter()
+        .filter(|player| Target::Player(Arc::clone(player)).can_be_attacked() && matches(player))
+        .map(|player| (player, player.living_entity.entity.pos.load().sub(&pos)))
+        .filter(|(_, offset)| offset.length() <= range)
+        .min_by(|(_, a), (_, b)| a.length_squared().total_cmp(&b.length_squared()))
+        .map(|(player, _)| Target::Player(Arc::clone(player)))
+}
+
+/// The target with the id while it can still be attacked and is in range
+pub(super) async fn keep_target(mob: &Mob, id: EntityId, range: f64) -> Option<Target> {
+    let entity = &mob.living_entity.entity;
+    let target = Target::find(&entity.world, id).await?;
+    let distance = target.entity().pos.load().sub(&entity.pos.load()).length();
+    (distance <= range).then_some(target)
+}
+
+/// Runs up to the target and hits it once it is in reach and the attack cooled down, `ready`
+/// starts the cooldown. Returns whether it hit
+pub(super) async fn chase(
+    mob: &Mob,
+    target: &Target,
+    speed: f64,
+    reach: f64,
+    damage: f32,
+    ready: impl FnOnce() -> bool + Send,
+) -> bool {
+    let entity = &mob.living_entity.entity;
+    let target_pos = target.entity().pos.load();
+    mob.look_at(eyes(target.entity()));
+    let offset = target_pos.sub(&entity.pos.load());
+    if offset.x.hypot(offset.z) > reach || offset.y.abs() > reach {
+        mob.walk_to(target_pos, speed);
+        return false;
+    }
+    mob.stop_walking();
+    ready() && mob.melee(target, damage, ATTACK_KNOCKBACK, 0.0).await
+}
+
+/// How far off the arrows of monsters fly, they are better aimed on higher difficulties
+const fn arrow_inaccuracy(difficulty: Difficulty) -> f64 {
+    match difficulty {
+        Difficulty::Peaceful => 14.0,
+        Difficulty::Easy => 10.0,
+        Difficulty::Normal => 6.0,
+        Difficulty::Hard => 2.0,
+    }
+}
+
+/// Shoots an arrow at a third of the target's height, aiming a bit higher the farther away it
+/// is. Nobody can pick up the arrows of monsters
+pub(super) async fn shoot_arrow(mob: &Mob, target: &Target, speed: f64) {
+    let entity = &mob.living_entity.entity;
+    let world = &entity.world;
+    let Some(arrow) = get_item("minecraft:arrow") else {
+        return;
+    };
+    let from = eyes(entity).sub(&Vector3::new(0.0, 0.1, 0.0));
+    let target_entity = target.entity();
+    let target_pos = target_entity.pos.load();
+    let aim = target_pos.y + target_entity.bounding_box_size.load().height / 3.0;
+    let (x, z) = (target_pos.x - from.x, target_pos.z - from.z);
+    let direction = Vector3::new(x, aim - from.y + x.hypot(z) * 0.2, z);
+    let velocity = shoot_velocity(direction, speed, arrow_inaccuracy(world.config.difficulty));
+    let arrow = Arrow::new(
+        world.clone(),
+        ItemStack::new(1, arrow.id),
+        from,
+        velocity,
+        Some(entity.entity_id),
+    )
+    .without_pickup();
+    world.spawn_entity(Arc::new(arrow)).await;
+    world
+        .emit_game_event_by(GameEvent::ProjectileShoot, from, Some(entity.entity_id))
+        .await;
+}
@@ -0,0 +1,166 @@
+//! What the mobs players ride have in common: a saddle and what they wear on their body, which
+//! players put on them by hand or in their inventory screen. The client of the player in front
+//! moves a saddled mount by itself, with the speed and jump strength it was sent.
+
+use std::sync::Arc;
+
+use pumpkin_entity::EntityId;
+use pumpkin_inventory::{
+    horse::{HorseInventory, BODY_SLOT, SADDLE_SLOT},
+    Container,
+};
+use pumpkin_world::item::{item_registry::get_item_name, ItemStack};
+use tokio::sync::Mutex;
+
+use crate::{
+    entity::player::Player,
+    server::Server,
+    world::{block_entity::new_container_id, World},
+};
+
+pub const SADDLE: &str = "minecraft:saddle";
+
+/// What players see of a mount, as of the last time its inventory was looked at
+#[derive(Default)]
+struct Worn {
+    saddled: bool,
+    body: Option<ItemStack>,
+    /// Everything in the inventory, for the mount to drop when it dies
+    items: Vec<ItemStack>,
+}
+
+/// What players changed about a mount in its inventory
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WornChange {
+    pub saddle: bool,
+    pub body: bool,
+}
+
+/// The inventory of a mount, players open it like a chest and the mount wears what they put in
+pub struct MountInventory {
+    container_id: u64,
+    container: Arc<Mutex<Box<dyn Container>>>,
+    /// The columns of three slots its chest has, 0 without one
+    columns: u8,
+    body_items: &'static [&'static str],
+    worn: parking_lot::Mutex<Worn>,
+}
+
+impl MountInventory {
+    /// `body_items` are what fits into the body slot, like horse armor
+    #[must_use]
+    pub fn new(columns: u8, body_items: &'static [&'static str]) -> Self {
+        let inventory = HorseInventory::new(usize::from(columns) * 3, body_items);
+        Self {
+            container_id: new_container_id(),
+            container: Arc::new(Mutex::new(Box::new(inventory))),
+            columns,
+            body_items,
+            worn: parking_lot::Mutex::new(Worn::default()),
+        }
+    }
+
+    #[must_use]
+    pub fn is_saddled(&self) -> bool {
+        self.worn.lock().saddled
+    }
+
+    /// What the mount wears on its body
+    #[must_use]
+    pub fn body(&self) -> Option<ItemStack> {
+        self.worn.lock().body.clone()
+    }
+
+    /// Everything in the inventory
+    #[must_use]
+    pub fn drops(&self) -> Vec<ItemStack> {
+        self.worn.lock().items.clone()
+    }
+
+    /// Puts the saddle on, returns false if the mount wears one already
+    pub async fn put_saddle(&self, saddle: ItemStack) -> bool {
+        self.put(SADDLE_SLOT, saddle).await
+    }
+
+    /// Puts the item on the mount's body, returns false if it doesn't fit there or the slot is
+    /// taken
+    pub async fn put_body(&self, stack: ItemStack) -> bool {
+        self.put(BODY_SLOT, stack).await
+    }
+
+    async fn put(&self, slot: usize, stack: ItemStack) -> bool {
+        {
+            let mut container = self.container.lock().await;
+            if !container.can_place_item(slot, &stack) {
+                return false;
+            }
+            let mut slots = container.all_slots();
+            let Some(target) = slots.get_mut(slot) else {
+                return false;
+            };
+            **target = Some(stack);
+        }
+        self.update().await;
+        true
+    }
+
+    /// Looks at what players put into the inventory or took out of it
+    pub async fn update(&self) -> WornChange {
+        let (saddled, body, items) = {
+            let container = self.container.lock().await;
+            let slots = container.all_slots_ref();
+            let name = |slot: usize| {
+                slots
+                    .get(slot)
+                    .copied()
+                    .flatten()
+                    .and_then(|stack| get_item_name(stack.item_id))
+            };
+            let saddled = name(SADDLE_SLOT) == Some(SADDLE);
+            let body = name(BODY_SLOT)
+                .filter(|name| self.body_items.contains(name))
+                .and_then(|_| slots[BODY_SLOT].cloned());
+            let items: Vec<_> = slots
+                .iter()
+                .flatten()
+                .map(|stack| (*stack).clone())
+                .collect();
+            (saddled, body, items)
+        };
+        let mut worn = self.worn.lock();
+        let change = WornChange {
+            saddle: worn.saddled != saddled,
+            body: worn.body != body,
+        };
+        worn.saddled = saddled;
+        worn.body = body;
+        worn.items = items;
+        change
+    }
+
+    /// Opens the inventory screen of the mount with the id for the player
+    pub async fn open(&self, player: &Player, server: &Server, entity_id: EntityId) {
+        player
+            .open_horse_container(
+                server,
+                self.container_id,
+                self.container.clone(),
+                self.columns,
+                entity_id,
+            )
+            .await;
+    }
+
+    /// Closes the screen for the players looking into it, once the mount is gone
+    pub async fn close(&self, world: &World) {
+        for player in world
+            .players()
+            .await
+            .filter(|player| player.open_container.load() == Some(self.container_id))
+            .iter()
+        {
+            player.open_container.store(None);
+            player.close_container().await;
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! Parrots: seeds tame a wild parrot now and then. Tamed parrots follow their owner and sit when it
+//! tells them to. Cookies are poisonous to parrots.
+
+use std::{any::Any, sync::Arc};
+
+use async_trait::async_trait;
+use pumpkin_core::math::{boundingbox::BoundingBoxSize, vector3::Vector3};
+use pumpkin_entity::{effect_type::EffectType, entity_type::EntityType, EntityId};
+use pumpkin_macros::sound;
+use rand::{thread_rng, Rng};
+
+use crate::{
+    entity::{
+        damage::{DamageSource, PLAYER_ATTACK},
+        data_tracker,
+        effect::StatusEffect,
+        player::{Hand, Player},
+        EntityBase,
+    },
+    world::World,
+};
+
+use super::{animal::is_seed, mob_entity, play_sound, tameable::Tameable, Mob, MobAi};
+
+const MAX_HEALTH: f32 = 6.0;
+const SIZE: BoundingBoxSize = BoundingBoxSize {
+    width: 0.5,
+    height: 0.9,
+};
+const EYE_HEIGHT: f32 = 0.54;
+/// Speeds in blocks per tick
+const WALK_SPEED: f64 = 0.1;
+const FOLLOW_SPEED: f64 = 0.2;
+const WANDER_RANGE: f64 = 10.0;
+
+/// Seeds tame a parrot one in ten times
+const TAME_CHANCE: f64 = 0.1;
+const COOKIE: &str = "minecraft:cookie";
+const POISON_TICKS: u32 = 900;
+/// Red, blue, green, cyan and gray
+const VARIANTS: i32 = 5;
+
+pub struct Parrot {
+    tameable: Tameable,
+}
+
+impl Parrot {
+    pub async fn spawn(world: &Arc<World>, position: Vector3<f64>) -> Arc<Mob> {
+        let entity = mob_entity(world, EntityType::Parrot, position, SIZE, EYE_HEIGHT);
+        {
+            let mut tracker = entity.data_tracker.lock();
+            tracker.define(&data_tracker::BABY, false);
+            tracker.define(&data_tracker::PARROT_VARIANT, 0);
+            tracker.set(
+                &data_tracker::PARROT_VARIANT,
+                thread_rng().gen_range(0..VARIANTS),
+            );
+        }
+        let parrot = Self {
+            tameable: Tameable::default(),
+        };
+        let mob = Mob::new(entity, Box::new(parrot));
+        Tameable::define(&mob);
+        mob.living_entity.set_max_health(MAX_HEALTH);
+        let mob = Arc::new(mob);
+        world.spawn_entity(mob.clone()).await;
+        mob
+    }
+}
+
+#[async_trait]
+impl MobAi for Parrot {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn tick(&self, mob: &Mob) -> bool {
+        if self.tameable.is_sitting() {
+            return true;
+        }
+        if thread_rng().gen_ratio(1, 120) {
+            play_sound(mob, sound!("minecraft:entity.parrot.ambient")).await;
+        }
+        if self.tameable.follow_owner(mob, FOLLOW_SPEED, &SIZE).await {
+            return true;
+        }
+        mob.wander(WANDER_RANGE, WALK_SPEED);
+        true
+    }
+
+    /// Sitting parrots get up when they are hurt
+    async fn on_hurt(&self, mob: &Mob, _attacker: Option<EntityId>) {
+        self.tameable.set_sitting(mob, false);
+        let sound = if mob.living_entity.health.load() > 0.0 {
+            sound!("minecraft:entity.parrot.hurt")
+        } else {
+            sound!("minecraft:entity.parrot.death")
+        };
+        play_sound(mob, sound).await;
+    }
+
+    /// Wild parrots take seeds, a cookie poisons and kills any parrot. The owner makes tamed
+    /// parrots sit down and get up
+    async fn interact(&self, mob: &Mob, player: &Player, hand: Hand) -> bool {
+        let item = player.held_item_name(hand).await;
+        if item == Some(COOKIE) {
+            if !player.use_held_item(hand, COOKIE).await {
+                return false;
+            }
+            mob.living_entity
+                .add_effect(StatusEffect::new(EffectType::Poison, 0, POISON_TICKS))
+                .await;
+            mob.damage(
+                f32::MAX,
+                DamageSource::by(PLAYER_ATTACK, player.entity_id()),
+            )
+            .await;
+            return true;
+        }
+        if !self.tameable.is_tamed() {
+            let Some(seed) = item.filter(|item| is_seed(item)) else {
+                return false;
+            };
+            if !player.use_held_item(hand, seed).await {
+                return false;
+            }
+            play_sound(mob, sound!("minecraft:entity.parrot.eat")).await;
+            if self.tameable.try_tame(mob, player, TAME_CHANCE).await {
+                self.tameable.set_sitting(mob, true);
+            }
+            return true;
+        }
+        if !self.tameable.is_owner(player) {
+            return false;
+        }
+        self.tameable.set_sitting(mob, !self.tameable.is_sitting());
+        true
+    }
+
+    fn tameable(&self) -> Option<&Tameable> {
+        Some(&self.tameable)
+    }
+}
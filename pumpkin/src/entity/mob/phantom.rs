@@ -0,0 +1,204 @@
+//! Phantoms: they haunt players who haven't rested for three days. Phantoms circle high above their
+//! target and now and then swoop down to bite it. Phantoms burn in sunlight.
+
+use std::{any::Any, f64::consts::TAU, sync::Arc};
+
+use async_trait::async_trait;
+use pumpkin_core::math::{boundingbox::BoundingBoxSize, vector3::Vector3};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_macros::sound;
+use pumpkin_world::item::ItemStack;
+use rand::{thread_rng, Rng};
+
+use crate::{entity::data_tracker, world::World};
+
+use super::{
+    mob_entity,
+    monster::{burn_in_sunlight, chase, keep_target, nearest_player, random_drop},
+    play_sound, Mob, MobAi, Movement, Target,
+};
+
+const MAX_HEALTH: f32 = 20.0;
+const EXPERIENCE: i32 = 5;
+const SIZE: BoundingBoxSize = BoundingBoxSize {
+    width: 0.9,
+    height: 0.5,
+};
+const EYE_HEIGHT: f32 = 0.175;
+/// Speeds in blocks per tick
+const CIRCLE_SPEED: f64 = 0.3;
+const SWOOP_SPEED: f64 = 0.5;
+
+/// Players who haven't rested for three days are haunted
+pub const INSOMNIA_TICKS: u32 = 72_000;
+const TARGET_RANGE: f64 = 64.0;
+/// Phantoms circle 5 to 15 blocks around a spot 20 to 28 blocks above their target
+const MIN_CIRCLE_RADIUS: f64 = 5.0;
+const MAX_CIRCLE_RADIUS: f64 = 15.0;
+const MIN_CIRCLE_HEIGHT: f64 = 20.0;
+const MAX_CIRCLE_HEIGHT: f64 = 28.0;
+/// Phantoms swoop down every 8 to 12 seconds
+const MIN_SWOOP_COOLDOWN: u32 = 160;
+const MAX_SWOOP_COOLDOWN: u32 = 240;
+const REACH: f64 = 1.0;
+const ATTACK_DAMAGE: f32 = 6.0;
+
+struct PhantomState {
+    target: Option<EntityId>,
+    /// What it circles around
+    anchor: Option<Vector3<f64>>,
+    /// Where on the circle it is, in radians
+    angle: f64,
+    radius: f64,
+    clockwise: bool,
+    /// Ticks until it swoops down, it is swooping while this is 0
+    swoop_cooldown: u32,
+}
+
+pub struct Phantom {
+    state: parking_lot::Mutex<PhantomState>,
+}
+
+impl Phantom {
+    pub async fn spawn(world: &Arc<World>, position: Vector3<f64>) -> Arc<Mob> {
+        let entity = mob_entity(world, EntityType::Phantom, position, SIZE, EYE_HEIGHT);
+        entity
+            .data_tracker
+            .lock()
+            .define(&data_tracker::PHANTOM_SIZE, 0);
+        let phantom = {
+            let mut rng = thread_rng();
+            Self {
+                state: parking_lot::Mutex::new(PhantomState {
+                    target: None,
+                    anchor: None,
+                    angle: rng.gen_range(0.0..TAU),
+                    radius: rng.gen_range(MIN_CIRCLE_RADIUS..=MAX_CIRCLE_RADIUS),
+                    clockwise: rng.gen(),
+                    swoop_cooldown: MAX_SWOOP_COOLDOWN,
+                }),
+            }
+        };
+        let mob = Mob::new(entity, Box::new(phantom));
+        mob.living_entity.set_max_health(MAX_HEALTH);
+        let mob = Arc::new(mob);
+        world.spawn_entity(mob.clone()).await;
+        mob
+    }
+
+    /// Flies on along its circle, the anchor moves along above the target
+    fn circle(&self, mob: &Mob, target: Option<&Target>) {
+        let pos = mob.living_entity.entity.pos.load();
+        let mut state = self.state.lock();
+        if let Some(target) = target {
+            let target_pos = target.entity().pos.load();
+            let height = state.anchor.map_or_else(
+                || thread_rng().gen_range(MIN_CIRCLE_HEIGHT..=MAX_CIRCLE_HEIGHT),
+                |anchor| (anchor.y - target_pos.y).clamp(MIN_CIRCLE_HEIGHT, MAX_CIRCLE_HEIGHT),
+            );
+            state.anchor = Some(Vector3::new(
+                target_pos.x,
+                target_pos.y + height,
+                target_pos.z,
+            ));
+        }
+        let anchor = *state.anchor.get_or_insert(pos);
+        let step = CIRCLE_SPEED / state.radius;
+        state.angle = (state.angle + if state.clockwise { step } else { -step }).rem_euclid(TAU);
+        let next = Vector3::new(
+            anchor.x + state.angle.cos() * state.radius,
+            anchor.y,
+            anchor.z + state.angle.sin() * state.radius,
+        );
+        drop(state);
+        mob.walk_to(next, CIRCLE_SPEED);
+    }
+
+    /// Goes back to circling for a while, on a new circle
+    fn stop_swooping(&self) {
+        let mut state = self.state.lock();
+        let mut rng = thread_rng();
+        state.swoop_cooldown = rng.gen_range(MIN_SWOOP_COOLDOWN..=MAX_SWOOP_COOLDOWN);
+        state.radius = rng.gen_range(MIN_CIRCLE_RADIUS..=MAX_CIRCLE_RADIUS);
+        if rng.gen_ratio(1, 3) {
+            state.clockwise = !state.clockwise;
+        }
+    }
+}
+
+#[async_trait]
+impl MobAi for Phantom {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn tick(&self, mob: &Mob) -> bool {
+        burn_in_sunlight(mob).await;
+        if thread_rng().gen_ratio(1, 120) {
+            play_sound(mob, sound!("minecraft:entity.phantom.ambient")).await;
+        }
+        let target = self.state.lock().target;
+        let mut target = match target {
+            Some(target) => keep_target(mob, target, TARGET_RANGE).await,
+            None => None,
+        };
+        if target.is_none() {
+            target = nearest_player(mob, TARGET_RANGE, |player| {
+                player.ticks_since_rest() >= INSOMNIA_TICKS
+            })
+            .await;
+        }
+        self.state.lock().target = target.as_ref().map(|target| target.entity().entity_id);
+        let Some(target) = target else {
+            self.circle(mob, None);
+            return true;
+        };
+
+        let swooping = {
+            let mut state = self.state.lock();
+            state.swoop_cooldown = state.swoop_cooldown.saturating_sub(1);
+            state.swoop_cooldown == 0
+        };
+        if !swooping {
+            self.circle(mob, Some(&target));
+            return true;
+        }
+        if mob.age() % 20 == 0 {
+            play_sound(mob, sound!("minecraft:entity.phantom.swoop")).await;
+        }
+        let bit = chase(mob, &target, SWOOP_SPEED, REACH, ATTACK_DAMAGE, || true).await;
+        if bit {
+            play_sound(mob, sound!("minecraft:entity.phantom.bite")).await;
+        }
+        // they give up on a swoop a wall got in the way of
+        if bit || mob.is_against_wall() {
+            self.stop_swooping();
+        }
+        true
+    }
+
+    /// Phantoms fly off when they are hurt
+    async fn on_hurt(&self, mob: &Mob, _attacker: Option<EntityId>) {
+        self.stop_swooping();
+        let sound = if mob.living_entity.health.load() > 0.0 {
+            sound!("minecraft:entity.phantom.hurt")
+        } else {
+            sound!("minecraft:entity.phantom.death")
+        };
+        play_sound(mob, sound).await;
+    }
+
+    fn movement(&self) -> Movement {
+        Movement::Fly
+    }
+
+    fn experience(&self) -> i32 {
+        EXPERIENCE
+    }
+
+    fn drops(&self) -> Vec<ItemStack> {
+        random_drop("minecraft:phantom_membrane", 1)
+            .into_iter()
+            .collect()
+    }
+}
@@ -0,0 +1,306 @@
+//! Pillagers: they shoot their crossbow at players, villagers and iron golems. By day pillagers
+//! roam the world in patrols, which follow a captain wearing a banner.
+
+use std::{any::Any, sync::Arc};
+
+use async_trait::async_trait;
+use pumpkin_core::math::{boundingbox::BoundingBoxSize, vector3::Vector3};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_macros::sound;
+use pumpkin_protocol::client::play::EquipmentSlot;
+use pumpkin_world::item::{item_registry::get_item, ItemStack};
+use rand::{thread_rng, Rng};
+
+use crate::{entity::data_tracker, world::World};
+
+use super::{
+    mob_entity, mobs_near,
+    monster::{can_see, eyes, keep_target, nearest_player, random_drop, shoot_arrow},
+    play_sound,
+    villager::is_villager,
+    Mob, MobAi, Target,
+};
+
+const MAX_HEALTH: f32 = 24.0;
+const EXPERIENCE: i32 = 5;
+const SIZE: BoundingBoxSize = BoundingBoxSize {
+    width: 0.6,
+    height: 1.95,
+};
+const EYE_HEIGHT: f32 = 1.62;
+/// Speeds in blocks per tick
+const WALK_SPEED: f64 = 0.1;
+const PATROL_SPEED: f64 = 0.07;
+const CHASE_SPEED: f64 = 0.12;
+const WANDER_RANGE: f64 = 10.0;
+
+const TARGET_RANGE: f64 = 16.0;
+/// Pillagers look for what to shoot every half second
+const TARGET_INTERVAL: u32 = 10;
+/// Pillagers stop to shoot at targets this close
+const SHOOT_RANGE: f64 = 8.0;
+const CHARGE_TICKS: u32 = 25;
+const MIN_SHOOT_COOLDOWN: u32 = 20;
+const MAX_SHOOT_COOLDOWN: u32 = 40;
+const ARROW_SPEED: f64 = 1.6;
+
+/// Captains lead their patrol to a new spot once they come this close
+const PATROL_ARRIVED: f64 = 10.0;
+/// How far away from where they are captains lead their patrol next
+const PATROL_DISTANCE: f64 = 100.0;
+/// Patrols stay this close to their captain
+const FOLLOW_DISTANCE: f64 = 4.0;
+
+const BANNER: &str = "minecraft:white_banner";
+
+/// The patrol a pillager walks with
+#[derive(Clone, Copy)]
+pub struct Patrol {
+    /// Who leads it, `None` for the captain itself
+    pub captain: Option<EntityId>,
+    /// Where the patrol heads
+    pub destination: Vector3<f64>,
+}
+
+#[derive(Default)]
+struct PillagerState {
+    target: Option<EntityId>,
+    patrol: Option<Patrol>,
+    /// Ticks it has been charging its crossbow
+    charging: Option<u32>,
+    /// Ticks until it charges the crossbow again
+    cooldown: u32,
+}
+
+pub struct Pillager {
+    crossbow: Option<ItemStack>,
+    /// Captains wear a banner
+    banner: Option<ItemStack>,
+    state: parking_lot::Mutex<PillagerState>,
+}
+
+impl Pillager {
+    pub async fn spawn(
+        world: &Arc<World>,
+        position: Vector3<f64>,
+        patrol: Option<Patrol>,
+    ) -> Arc<Mob> {
+        let entity = mob_entity(world, EntityType::Pillager, position, SIZE, EYE_HEIGHT);
+        entity
+            .data_tracker
+            .lock()
+            .define(&data_tracker::PILLAGER_CHARGING, false);
+        let captain = patrol.is_some_and(|patrol| patrol.captain.is_none());
+        let item = |name| get_item(name).map(|item| ItemStack::new(1, item.id));
+        let pillager = Self {
+            crossbow: item("minecraft:crossbow"),
+            banner: if captain { item(BANNER) } else { None },
+            state: parking_lot::Mutex::new(PillagerState {
+                patrol,
+                ..Default::default()
+            }),
+        };
+        let mob = Mob::new(entity, Box::new(pillager));
+        mob.living_entity.set_max_health(MAX_HEALTH);
+        let mob = Arc::new(mob);
+        world.spawn_entity(mob.clone()).await;
+        mob
+    }
+
+    /// The nearest player it can attack, or else the nearest villager or golem
+    async fn find_target(mob: &Mob) -> Option<Target> {
+        if let Some(player) = nearest_player(mob, TARGET_RANGE, |_| true).await {
+            return Some(player);
+        }
+        let entity = &mob.living_entity.entity;
+        mobs_near(
+            &entity.world,
+            entity.pos.load(),
+            TARGET_RANGE,
+            |entity_type| {
+                is_villager(entity_type)
+                    || matches!(
+                        entity_type,
+                        EntityType::WanderingTrader | EntityType::IronGolem
+                    )
+            },
+        )
+        .await
+        .into_iter()
+        .next()
+        .map(Target::Mob)
+    }
+
+    fn set_charging(mob: &Mob, charging: bool) {
+        mob.living_entity
+            .entity
+            .data_tracker
+            .lock()
+            .set(&data_tracker::PILLAGER_CHARGING, charging);
+    }
+
+    /// Charges the crossbow and shoots once it is charged and sees the target, walking up to it
+    /// until it is close enough
+    async fn fight(&self, mob: &Mob, target: &Target) {
+        let entity = &mob.living_entity.entity;
+        let target_entity = target.entity();
+        let target_pos = target_entity.pos.load();
+        let distance = target_pos.sub(&entity.pos.load()).length();
+        let sees = can_see(&entity.world, eyes(entity), eyes(target_entity)).await;
+        if sees && distance <= SHOOT_RANGE {
+            mob.stop_walking();
+        } else {
+            mob.walk_to(target_pos, CHASE_SPEED);
+        }
+        mob.look_at(eyes(target_entity));
+
+        let (started, charged) = {
+            let mut state = self.state.lock();
+            match state.charging {
+                Some(ticks) if ticks >= CHARGE_TICKS => {
+                    if sees && distance <= SHOOT_RANGE {
+                        state.charging = None;
+                        state.cooldown =
+                            thread_rng().gen_range(MIN_SHOOT_COOLDOWN..=MAX_SHOOT_COOLDOWN);
+                        (false, true)
+                    } else {
+                        (false, false)
+                    }
+                }
+                Some(ticks) => {
+                    state.charging = Some(ticks + 1);
+                    (false, false)
+                }
+                None => {
+                    state.cooldown = state.cooldown.saturating_sub(1);
+                    let start = state.cooldown == 0;
+                    if start {
+                        state.charging = Some(0);
+                    }
+                    (start, false)
+                }
+            }
+        };
+        if started {
+            Self::set_charging(mob, true);
+            play_sound(mob, sound!("minecraft:item.crossbow.loading_start")).await;
+        }
+        if charged {
+            Self::set_charging(mob, false);
+            shoot_arrow(mob, target, ARROW_SPEED).await;
+            play_sound(mob, sound!("minecraft:item.crossbow.shoot")).await;
+        }
+    }
+
+    /// Captains lead the patrol on to the next spot, the others follow their captain. Patrols
+    /// whose captain is gone go on to where it led them
+    async fn patrol(&self, mob: &Mob, patrol: Patrol) {
+        let entity = &mob.living_entity.entity;
+        let pos = entity.pos.load();
+        if let Some(captain) = patrol.captain {
+            let captain = entity
+                .world
+                .entities
+                .lock()
+                .await
+                .get(&captain)
+                .map(|captain| captain.get_entity().pos.load());
+            if let Some(captain) = captain {
+                if captain.sub(&pos).length() > FOLLOW_DISTANCE {
+                    mob.walk_to(captain, PATROL_SPEED);
+                }
+                return;
+            }
+        }
+        let offset = patrol.destination.sub(&pos);
+        if offset.x.hypot(offset.z) > PATROL_ARRIVED {
+            mob.walk_to(patrol.destination, PATROL_SPEED);
+            return;
+        }
+        if patrol.captain.is_some() {
+            // the patrol broke up
+            self.state.lock().patrol = None;
+            return;
+        }
+        let angle = thread_rng().gen_range(0.0..std::f64::consts::TAU);
+        let destination = Vector3::new(
+            pos.x + angle.cos() * PATROL_DISTANCE,
+            pos.y,
+            pos.z + angle.sin() * PATROL_DISTANCE,
+        );
+        self.state.lock().patrol = Some(Patrol {
+            captain: None,
+            destination,
+        });
+        mob.walk_to(destination, PATROL_SPEED);
+    }
+}
+
+#[async_trait]
+impl MobAi for Pillager {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn tick(&self, mob: &Mob) -> bool {
+        if thread_rng().gen_ratio(1, 120) {
+            play_sound(mob, sound!("minecraft:entity.pillager.ambient")).await;
+        }
+        let (target, patrol) = {
+            let state = self.state.lock();
+            (state.target, state.patrol)
+        };
+        let mut target = match target {
+            Some(target) => keep_target(mob, target, TARGET_RANGE * 2.0).await,
+            None => None,
+        };
+        if target.is_none() && mob.age() % TARGET_INTERVAL == 0 {
+            target = Self::find_target(mob).await;
+        }
+        self.state.lock().target = target.as_ref().map(|target| target.entity().entity_id);
+        if let Some(target) = target {
+            self.fight(mob, &target).await;
+            return true;
+        }
+        if self.state.lock().charging.take().is_some() {
+            Self::set_charging(mob, false);
+        }
+        match patrol {
+            Some(patrol) => self.patrol(mob, patrol).await,
+            None => mob.wander(WANDER_RANGE, WALK_SPEED),
+        }
+        true
+    }
+
+    async fn on_hurt(&self, mob: &Mob, attacker: Option<EntityId>) {
+        if let Some(attacker) = attacker {
+            if attacker != mob.living_entity.entity.entity_id {
+                self.state.lock().target = Some(attacker);
+            }
+        }
+        let sound = if mob.living_entity.health.load() > 0.0 {
+            sound!("minecraft:entity.pillager.hurt")
+        } else {
+            sound!("minecraft:entity.pillager.death")
+        };
+        play_sound(mob, sound).await;
+    }
+
+    fn experience(&self) -> i32 {
+        EXPERIENCE
+    }
+
+    fn drops(&self) -> Vec<ItemStack> {
+        random_drop("minecraft:arrow", 2)
+            .into_iter()
+            .chain(self.banner.clone())
+            .collect()
+    }
+
+    fn equipment(&self) -> Vec<(EquipmentSlot, Option<ItemStack>)> {
+        vec![
+            (EquipmentSlot::MainHand, self.crossbow.clone()),
+            (EquipmentSlot::Head, self.banner.clone()),
+        ]
+    }
+}
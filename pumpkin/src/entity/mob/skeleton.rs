@@ -0,0 +1,270 @@
+//! Skeletons: they shoot arrows at the players near them, circling around them while they draw
+//! their bow. The higher the difficulty the better they aim and the faster they shoot. Skeletons
+//! burn in sunlight.
+
+use std::{any::Any, sync::Arc};
+
+use async_trait::async_trait;
+use pumpkin_core::{
+    math::{boundingbox::BoundingBoxSize, vector3::Vector3},
+    Difficulty,
+};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_macros::sound;
+use pumpkin_protocol::client::play::EquipmentSlot;
+use pumpkin_world::item::{item_registry::get_item, ItemStack};
+use rand::{thread_rng, Rng};
+
+use crate::{entity::data_tracker, world::World};
+
+use super::{
+    mob_entity,
+    monster::{
+        burn_in_sunlight, can_see, eyes, keep_target, nearest_player, random_drop, shoot_arrow,
+    },
+    play_sound, Mob, MobAi, Target,
+};
+
+const MAX_HEALTH: f32 = 20.0;
+const EXPERIENCE: i32 = 5;
+const SIZE: BoundingBoxSize = BoundingBoxSize {
+    width: 0.6,
+    height: 1.99,
+};
+const EYE_HEIGHT: f32 = 1.74;
+/// Speeds in blocks per tick
+const WALK_SPEED: f64 = 0.1;
+const STRAFE_SPEED: f64 = 0.05;
+const WANDER_RANGE: f64 = 10.0;
+
+const FOLLOW_RANGE: f64 = 16.0;
+const SHOOT_RANGE: f64 = 15.0;
+/// Skeletons have to see their target for a second before they stop and shoot
+const AIM_TICKS: i32 = 20;
+/// And give up drawing once they didn't see it for three seconds
+const LOST_SIGHT_TICKS: i32 = -60;
+const DRAW_TICKS: u32 = 20;
+const HARD_SHOOT_INTERVAL: u32 = 20;
+const SHOOT_INTERVAL: u32 = 40;
+const ARROW_SPEED: f64 = 1.6;
+/// Every second circling skeletons may turn around
+const STRAFE_SWITCH_TICKS: u32 = 20;
+const STRAFE_SWITCH_CHANCE: f64 = 0.3;
+
+const AGGRESSIVE_FLAG: i8 = 0x04;
+const USING_ITEM_FLAG: i8 = 0x01;
+
+#[derive(Default)]
+struct SkeletonState {
+    target: Option<EntityId>,
+    /// Ticks it saw the target for, or didn't see it for below 0
+    see_time: i32,
+    /// Ticks it has been drawing its bow
+    drawing: Option<u32>,
+    /// Ticks until it draws the bow again
+    cooldown: u32,
+    strafe_time: u32,
+    strafe_clockwise: bool,
+    strafe_backwards: bool,
+}
+
+pub struct Skeleton {
+    bow: Option<ItemStack>,
+    state: parking_lot::Mutex<SkeletonState>,
+}
+
+impl Skeleton {
+    pub async fn spawn(world: &Arc<World>, position: Vector3<f64>) -> Arc<Mob> {
+        let entity = mob_entity(world, EntityType::Skeleton, position, SIZE, EYE_HEIGHT);
+        entity
+            .data_tracker
+            .lock()
+            .define(&data_tracker::MOB_FLAGS, 0);
+        let skeleton = Self {
+            bow: get_item("minecraft:bow").map(|bow| ItemStack::new(1, bow.id)),
+            state: parking_lot::Mutex::new(SkeletonState::default()),
+        };
+        let mob = Mob::new(entity, Box::new(skeleton));
+        mob.living_entity.set_max_health(MAX_HEALTH);
+        let mob = Arc::new(mob);
+        world.spawn_entity(mob.clone()).await;
+        mob
+    }
+
+    fn set_flags(mob: &Mob, aggressive: bool, drawing: bool) {
+        let mut tracker = mob.living_entity.entity.data_tracker.lock();
+        tracker.set(
+            &data_tracker::MOB_FLAGS,
+            if aggressive { AGGRESSIVE_FLAG } else { 0 },
+        );
+        tracker.set(
+            &data_tracker::LIVING_FLAGS,
+            if drawing { USING_ITEM_FLAG } else { 0 },
+        );
+    }
+
+    /// Walks up to the target until it is in range, then circles around it. Close targets make
+    /// it back off
+    fn strafe(&self, mob: &Mob, target_pos: Vector3<f64>, distance: f64) {
+        let pos = mob.living_entity.entity.pos.load();
+        let (clockwise, backwards) = {
+            let mut state = self.state.lock();
+            state.strafe_time += 1;
+            if state.strafe_time >= STRAFE_SWITCH_TICKS {
+                let mut rng = thread_rng();
+                if rng.gen_bool(STRAFE_SWITCH_CHANCE) {
+                    state.strafe_clockwise = !state.strafe_clockwise;
+                }
+                if rng.gen_bool(STRAFE_SWITCH_CHANCE) {
+                    state.strafe_backwards = !state.strafe_backwards;
+                }
+                state.strafe_time = 0;
+            }
+            if distance > SHOOT_RANGE * 0.75 {
+                state.strafe_backwards = false;
+            } else if distance < SHOOT_RANGE * 0.25 {
+                state.strafe_backwards = true;
+            }
+            (state.strafe_clockwise, state.strafe_backwards)
+        };
+        let toward = Vector3::new(target_pos.x - pos.x, 0.0, target_pos.z - pos.z).normalize();
+        let forward = if backwards { -0.5 } else { 0.5 };
+        let sideways = if clockwise { 0.5 } else { -0.5 };
+        let step = Vector3::new(
+            toward.x * forward - toward.z * sideways,
+            0.0,
+            toward.z * forward + toward.x * sideways,
+        );
+        mob.walk_to(pos.add(&(step * 2.0)), STRAFE_SPEED);
+    }
+
+    /// Draws the bow once the attack cooled down and shoots when it is drawn far enough, as long
+    /// as it sees the target
+    async fn fight(&self, mob: &Mob, target: &Target) {
+        let entity = &mob.living_entity.entity;
+        let world = &entity.world;
+        let target_entity = target.entity();
+        let target_pos = target_entity.pos.load();
+        let distance = target_pos.sub(&entity.pos.load()).length();
+        let sees = can_see(world, eyes(entity), eyes(target_entity)).await;
+        let see_time = {
+            let mut state = self.state.lock();
+            state.see_time = if sees {
+                state.see_time.max(0) + 1
+            } else {
+                state.see_time.min(0) - 1
+            };
+            state.see_time
+        };
+        if distance <= SHOOT_RANGE && see_time >= AIM_TICKS {
+            self.strafe(mob, target_pos, distance);
+        } else {
+            mob.walk_to(target_pos, WALK_SPEED);
+        }
+        mob.look_at(eyes(target_entity));
+
+        let interval = if world.config.difficulty == Difficulty::Hard {
+            HARD_SHOOT_INTERVAL
+        } else {
+            SHOOT_INTERVAL
+        };
+        let shoot = {
+            let mut state = self.state.lock();
+            match state.drawing {
+                Some(_) if !sees && see_time < LOST_SIGHT_TICKS => {
+                    state.drawing = None;
+                    false
+                }
+                Some(drawn) if sees && drawn >= DRAW_TICKS && distance <= SHOOT_RANGE => {
+                    state.drawing = None;
+                    state.cooldown = interval;
+                    true
+                }
+                Some(drawn) => {
+                    state.drawing = Some(drawn + 1);
+                    false
+                }
+                None => {
+                    state.cooldown = state.cooldown.saturating_sub(1);
+                    if state.cooldown == 0 && see_time >= LOST_SIGHT_TICKS {
+                        state.drawing = Some(0);
+                    }
+                    false
+                }
+            }
+        };
+        if shoot {
+            shoot_arrow(mob, target, ARROW_SPEED).await;
+            play_sound(mob, sound!("minecraft:entity.skeleton.shoot")).await;
+        }
+        Self::set_flags(mob, true, self.state.lock().drawing.is_some());
+    }
+}
+
+#[async_trait]
+impl MobAi for Skeleton {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn tick(&self, mob: &Mob) -> bool {
+        burn_in_sunlight(mob).await;
+        if thread_rng().gen_ratio(1, 120) {
+            play_sound(mob, sound!("minecraft:entity.skeleton.ambient")).await;
+        }
+        let target = self.state.lock().target;
+        let mut target = match target {
+            Some(target) => keep_target(mob, target, FOLLOW_RANGE).await,
+            None => None,
+        };
+        if target.is_none() {
+            target = nearest_player(mob, FOLLOW_RANGE, |_| true).await;
+        }
+        self.state.lock().target = target.as_ref().map(|target| target.entity().entity_id);
+        if let Some(target) = target {
+            self.fight(mob, &target).await;
+            return true;
+        }
+        {
+            let mut state = self.state.lock();
+            state.drawing = None;
+            state.see_time = 0;
+        }
+        Self::set_flags(mob, false, false);
+        mob.wander(WANDER_RANGE, WALK_SPEED);
+        true
+    }
+
+    /// Skeletons shoot back at whoever hurt them
+    async fn on_hurt(&self, mob: &Mob, attacker: Option<EntityId>) {
+        if let Some(attacker) = attacker {
+            if attacker != mob.living_entity.entity.entity_id {
+                self.state.lock().target = Some(attacker);
+            }
+        }
+        let sound = if mob.living_entity.health.load() > 0.0 {
+            sound!("minecraft:entity.skeleton.hurt")
+        } else {
+            sound!("minecraft:entity.skeleton.death")
+        };
+        play_sound(mob, sound).await;
+    }
+
+    fn experience(&self) -> i32 {
+        EXPERIENCE
+    }
+
+    fn drops(&self) -> Vec<ItemStack> {
+        [
+            random_drop("minecraft:bone", 2),
+            random_drop("minecraft:arrow", 2),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    fn equipment(&self) -> Vec<(EquipmentSlot, Option<ItemStack>)> {
+        vec![(EquipmentSlot::MainHand, self.bow.clone())]
+    }
+}
@@ -0,0 +1,197 @@
+//! Spiders: they climb up walls and leap at the players they go after. In daylight they leave
+//! players alone unless they are hurt.
+
+use std::{
+    any::Any,
+    sync::{atomic::Ordering, Arc},
+};
+
+use async_trait::async_trait;
+use pumpkin_core::math::{boundingbox::BoundingBoxSize, vector3::Vector3};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_macros::sound;
+use pumpkin_world::item::{item_registry::get_item, ItemStack};
+use rand::{thread_rng, Rng};
+
+use crate::{entity::data_tracker, world::World};
+
+use super::{
+    mob_entity,
+    monster::{chase, in_sunlight, keep_target, nearest_player, random_drop},
+    play_sound, Mob, MobAi, Movement, Target,
+};
+
+const MAX_HEALTH: f32 = 16.0;
+const EXPERIENCE: i32 = 5;
+const SIZE: BoundingBoxSize = BoundingBoxSize {
+    width: 1.4,
+    height: 0.9,
+};
+const EYE_HEIGHT: f32 = 0.65;
+/// Speeds in blocks per tick
+const WALK_SPEED: f64 = 0.1;
+const CHASE_SPEED: f64 = 0.15;
+const WANDER_RANGE: f64 = 10.0;
+
+const FOLLOW_RANGE: f64 = 16.0;
+const REACH: f64 = 1.5;
+const ATTACK_DAMAGE: f32 = 2.0;
+const ATTACK_COOLDOWN: u32 = 20;
+/// Spiders leap at targets between 2 and 4 blocks away, one in five ticks
+const MIN_LEAP_DISTANCE: f64 = 2.0;
+const MAX_LEAP_DISTANCE: f64 = 4.0;
+const LEAP_CHANCE: u32 = 5;
+const LEAP_VELOCITY: f64 = 0.4;
+/// Spiders calm down in daylight one in a hundred ticks
+const CALM_DOWN_CHANCE: u32 = 100;
+
+const CLIMBING_FLAG: i8 = 0x01;
+
+#[derive(Default)]
+struct SpiderState {
+    target: Option<EntityId>,
+    /// Whether the target hurt the spider, then daylight doesn't calm it down
+    provoked: bool,
+    attack_cooldown: u32,
+}
+
+pub struct Spider {
+    state: parking_lot::Mutex<SpiderState>,
+}
+
+impl Spider {
+    pub async fn spawn(world: &Arc<World>, position: Vector3<f64>) -> Arc<Mob> {
+        let entity = mob_entity(world, EntityType::Spider, position, SIZE, EYE_HEIGHT);
+        entity
+            .data_tracker
+            .lock()
+            .define(&data_tracker::SPIDER_FLAGS, 0);
+        let spider = Self {
+            state: parking_lot::Mutex::new(SpiderState::default()),
+        };
+        let mob = Mob::new(entity, Box::new(spider));
+        mob.living_entity.set_max_health(MAX_HEALTH);
+        let mob = Arc::new(mob);
+        world.spawn_entity(mob.clone()).await;
+        mob
+    }
+
+    /// Jumps at the target when it is a few blocks away
+    fn leap(mob: &Mob, target: &Target) {
+        let entity = &mob.living_entity.entity;
+        if !entity.on_ground.load(Ordering::Relaxed) {
+            return;
+        }
+        let offset = target.entity().pos.load().sub(&entity.pos.load());
+        let distance = offset.x.hypot(offset.z);
+        if !(MIN_LEAP_DISTANCE..=MAX_LEAP_DISTANCE).contains(&distance)
+            || !thread_rng().gen_ratio(1, LEAP_CHANCE)
+        {
+            return;
+        }
+        let mut velocity = entity.velocity.load();
+        velocity.x += offset.x / distance * LEAP_VELOCITY;
+        velocity.z += offset.z / distance * LEAP_VELOCITY;
+        velocity.y = LEAP_VELOCITY;
+        entity.velocity.store(velocity);
+    }
+
+    /// The target it keeps going after, in daylight only the one which hurt it
+    async fn find_target(&self, mob: &Mob) -> Option<Target> {
+        let (target, provoked) = {
+            let state = self.state.lock();
+            (state.target, state.provoked)
+        };
+        let daylight = in_sunlight(mob).await;
+        if let Some(id) = target {
+            let calms_down = daylight && !provoked && thread_rng().gen_ratio(1, CALM_DOWN_CHANCE);
+            if !calms_down {
+                if let Some(target) = keep_target(mob, id, FOLLOW_RANGE).await {
+                    return Some(target);
+                }
+            }
+            *self.state.lock() = SpiderState::default();
+        }
+        if daylight {
+            return None;
+        }
+        nearest_player(mob, FOLLOW_RANGE, |_| true).await
+    }
+}
+
+#[async_trait]
+impl MobAi for Spider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn tick(&self, mob: &Mob) -> bool {
+        mob.living_entity.entity.data_tracker.lock().set(
+            &data_tracker::SPIDER_FLAGS,
+            if mob.is_against_wall() {
+                CLIMBING_FLAG
+            } else {
+                0
+            },
+        );
+        if thread_rng().gen_ratio(1, 120) {
+            play_sound(mob, sound!("minecraft:entity.spider.ambient")).await;
+        }
+        {
+            let mut state = self.state.lock();
+            state.attack_cooldown = state.attack_cooldown.saturating_sub(1);
+        }
+        let target = self.find_target(mob).await;
+        self.state.lock().target = target.as_ref().map(|target| target.entity().entity_id);
+        let Some(target) = target else {
+            mob.wander(WANDER_RANGE, WALK_SPEED);
+            return true;
+        };
+        Self::leap(mob, &target);
+        chase(mob, &target, CHASE_SPEED, REACH, ATTACK_DAMAGE, || {
+            let mut state = self.state.lock();
+            let ready = state.attack_cooldown == 0;
+            if ready {
+                state.attack_cooldown = ATTACK_COOLDOWN;
+            }
+            ready
+        })
+        .await;
+        true
+    }
+
+    /// Spiders go after whoever hurt them, even in daylight
+    async fn on_hurt(&self, mob: &Mob, attacker: Option<EntityId>) {
+        if let Some(attacker) = attacker {
+            let mut state = self.state.lock();
+            state.target = Some(attacker);
+            state.provoked = true;
+        }
+        let sound = if mob.living_entity.health.load() > 0.0 {
+            sound!("minecraft:entity.spider.hurt")
+        } else {
+            sound!("minecraft:entity.spider.death")
+        };
+        play_sound(mob, sound).await;
+    }
+
+    fn movement(&self) -> Movement {
+        Movement::Climb
+    }
+
+    fn experience(&self) -> i32 {
+        EXPERIENCE
+    }
+
+    fn drops(&self) -> Vec<ItemStack> {
+        let eye = thread_rng()
+            .gen_ratio(1, 3)
+            .then(|| get_item("minecraft:spider_eye"))
+            .flatten()
+            .map(|eye| ItemStack::new(1, eye.id));
+        random_drop("minecraft:string", 2)
+            .into_iter()
+            .chain(eye)
+            .collect()
+    }
+}
@@ -0,0 +1,280 @@
+//! Striders: they walk on lava, where they are warm, and shiver and slow down out of it. The
+//! player riding a saddled strider steers it with a warped fungus on a stick, using it boosts the
+//! strider for a while. Striders breed when they are fed warped fungus.
+
+use std::{
+    any::Any,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc,
+    },
+};
+
+use async_trait::async_trait;
+use pumpkin_core::math::{boundingbox::BoundingBoxSize, position::WorldPosition, vector3::Vector3};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_macros::sound;
+use pumpkin_world::{
+    block::block_registry::get_block_by_state_id,
+    item::{item_registry::get_item, ItemStack},
+};
+use rand::{thread_rng, Rng};
+
+use crate::{
+    entity::{
+        data_tracker,
+        player::{Hand, Player},
+        Entity,
+    },
+    world::World,
+};
+
+use super::{
+    ageable::set_baby,
+    animal::Breeding,
+    mob_entity,
+    monster::random_drop,
+    mount::{MountInventory, SADDLE},
+    play_sound, Mob, MobAi, GRAVITY,
+};
+
+const MAX_HEALTH: f32 = 20.0;
+const SIZE: BoundingBoxSize = BoundingBoxSize {
+    width: 0.9,
+    height: 1.7,
+};
+const EYE_HEIGHT: f32 = 1.445;
+/// Speeds in blocks per tick
+const WALK_SPEED: f64 = 0.1;
+const WANDER_RANGE: f64 = 10.0;
+/// How fast striders come up out of lava
+const LAVA_RISE: f64 = 0.12;
+
+/// The speed a client steering a strider moves it with, it is slower when the strider is cold
+const SPEED: f64 = 0.175;
+const COLD_SPEED: f64 = SPEED * 0.66;
+/// A warped fungus on a stick boosts a strider for 140 to 980 ticks
+const MIN_BOOST_TICKS: u32 = 140;
+const MAX_BOOST_TICKS: u32 = 980;
+pub const STEERING_ITEM: &str = "minecraft:warped_fungus_on_a_stick";
+
+fn is_warped_fungus(item: &str) -> bool {
+    item == "minecraft:warped_fungus"
+}
+
+async fn is_lava(world: &World, position: WorldPosition) -> bool {
+    world
+        .get_block_state_id(position)
+        .await
+        .ok()
+        .and_then(get_block_by_state_id)
+        .is_some_and(|block| block.name == "minecraft:lava")
+}
+
+/// Whether the entity is in lava and whether it stands on it
+async fn lava_around(entity: &Entity) -> (bool, bool) {
+    let pos = entity.pos.load();
+    let block = |y: f64| {
+        WorldPosition(Vector3::new(
+            pos.x.floor() as i32,
+            y.floor() as i32,
+            pos.z.floor() as i32,
+        ))
+    };
+    let world = &entity.world;
+    let inside = is_lava(world, block(pos.y)).await;
+    let on = !inside && is_lava(world, block(pos.y - 0.1)).await;
+    (inside, on)
+}
+
+pub struct Strider {
+    breeding: Breeding,
+    /// Striders have no inventory screen, the saddle is kept like the one of horses
+    saddle: MountInventory,
+    cold: AtomicBool,
+    /// Ticks the boost lasts
+    boost: AtomicU32,
+}
+
+impl Strider {
+    pub async fn spawn(world: &Arc<World>, position: Vector3<f64>, baby: bool) -> Arc<Mob> {
+        let entity = mob_entity(world, EntityType::Strider, position, SIZE, EYE_HEIGHT);
+        {
+            let mut tracker = entity.data_tracker.lock();
+            tracker.define(&data_tracker::BABY, false);
+            tracker.define(&data_tracker::STRIDER_BOOST_TIME, 0);
+            tracker.define(&data_tracker::STRIDER_SUFFOCATING, false);
+            tracker.define(&data_tracker::STRIDER_SADDLED, false);
+        }
+        let strider = Self {
+            breeding: Breeding::new(baby, is_warped_fungus, SIZE),
+            saddle: MountInventory::new(0, &[]),
+            cold: AtomicBool::new(false),
+            boost: AtomicU32::new(0),
+        };
+        let mob = Mob::new(entity, Box::new(strider));
+        mob.living_entity.set_max_health(MAX_HEALTH);
+        if baby {
+            set_baby(&mob, true, SIZE);
+        }
+        let mob = Arc::new(mob);
+        world.spawn_entity(mob.clone()).await;
+        mob
+    }
+
+    /// Floats up in lava and stays on top of it, striders shiver everywhere else. The client
+    /// steering a strider is told its new speed
+    async fn tick_lava(&self, mob: &Mob) {
+        let entity = &mob.living_entity.entity;
+        let (inside, on) = lava_around(entity).await;
+        let mut velocity = entity.velocity.load();
+        if inside {
+            velocity.y = velocity.y.max(LAVA_RISE);
+            entity.velocity.store(velocity);
+        } else if on && velocity.y <= 0.0 {
+            // cancels out the gravity of the next move
+            velocity.y = GRAVITY;
+            entity.velocity.store(velocity);
+        }
+        let cold = !inside && !on;
+        if self.cold.swap(cold, Ordering::Relaxed) == cold {
+            return;
+        }
+        entity
+            .data_tracker
+            .lock()
+            .set(&data_tracker::STRIDER_SUFFOCATING, cold);
+        if let Some(controller) = mob.controller().await {
+            mob.send_attributes(&controller).await;
+        }
+    }
+
+    /// Puts the saddle in the player's hand on an adult strider which doesn't wear one yet
+    async fn put_saddle(&self, mob: &Mob, player: &Player, hand: Hand) -> bool {
+        let Some(saddle) = get_item(SADDLE).map(|item| ItemStack::new(1, item.id)) else {
+            return false;
+        };
+        if self.breeding.is_baby()
+            || !self.saddle.put_saddle(saddle).await
+            || !player.use_held_item(hand, SADDLE).await
+        {
+            return false;
+        }
+        mob.living_entity
+            .entity
+            .data_tracker
+            .lock()
+            .set(&data_tracker::STRIDER_SADDLED, true);
+        play_sound(mob, sound!("minecraft:entity.strider.saddle")).await;
+        true
+    }
+}
+
+#[async_trait]
+impl MobAi for Strider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn tick(&self, mob: &Mob) -> bool {
+        self.breeding.tick(mob);
+        self.tick_lava(mob).await;
+        let _ = self
+            .boost
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |ticks| {
+                ticks.checked_sub(1)
+            });
+        if thread_rng().gen_ratio(1, 120) {
+            play_sound(mob, sound!("minecraft:entity.strider.ambient")).await;
+        }
+        let ridden = !mob.living_entity.entity.passengers.lock().is_empty();
+        if ridden {
+            mob.stop_walking();
+            return true;
+        }
+        if !self.breeding.breed(mob, WALK_SPEED).await
+            && !self.breeding.tempt(mob, WALK_SPEED).await
+        {
+            mob.wander(WANDER_RANGE, WALK_SPEED);
+        }
+        true
+    }
+
+    async fn on_hurt(&self, mob: &Mob, _attacker: Option<EntityId>) {
+        let sound = if mob.living_entity.health.load() > 0.0 {
+            sound!("minecraft:entity.strider.hurt")
+        } else {
+            sound!("minecraft:entity.strider.death")
+        };
+        play_sound(mob, sound).await;
+    }
+
+    /// Striders eat warped fungus and get saddled, a saddled one lets the player on
+    async fn interact(&self, mob: &Mob, player: &Player, hand: Hand) -> bool {
+        if let Some(item) = player.held_item_name(hand).await {
+            if self.breeding.feed(mob, player, hand).await {
+                return true;
+            }
+            if item == SADDLE && self.put_saddle(mob, player, hand).await {
+                return true;
+            }
+        }
+        if !self.saddle.is_saddled() || player.vehicle.lock().await.is_some() {
+            return false;
+        }
+        if player.start_riding(&mob.living_entity.entity).await {
+            mob.stop_walking();
+            mob.send_attributes(player).await;
+        }
+        // nothing was used up
+        false
+    }
+
+    fn fire_immune(&self) -> bool {
+        true
+    }
+
+    fn experience(&self) -> i32 {
+        self.breeding.experience()
+    }
+
+    fn drops(&self) -> Vec<ItemStack> {
+        let mut drops = self.saddle.drops();
+        drops.extend(random_drop("minecraft:string", 5));
+        drops
+    }
+
+    fn breeding(&self) -> Option<&Breeding> {
+        Some(&self.breeding)
+    }
+
+    async fn steered_by(&self, _mob: &Mob, player: &Player) -> bool {
+        self.saddle.is_saddled()
+            && (player.held_item_name(Hand::Main).await == Some(STEERING_ITEM)
+                || player.held_item_name(Hand::Off).await == Some(STEERING_ITEM))
+    }
+
+    /// Makes the strider faster for a while, the client reads how long from its metadata
+    async fn boost(&self, mob: &Mob) -> bool {
+        if self.boost.load(Ordering::Relaxed) > 0 {
+            return false;
+        }
+        let ticks = thread_rng().gen_range(MIN_BOOST_TICKS..=MAX_BOOST_TICKS);
+        self.boost.store(ticks, Ordering::Relaxed);
+        mob.living_entity
+            .entity
+            .data_tracker
+            .lock()
+            .set(&data_tracker::STRIDER_BOOST_TIME, ticks as i32);
+        true
+    }
+
+    fn attributes(&self) -> Vec<(&'static str, f64)> {
+        let speed = if self.cold.load(Ordering::Relaxed) {
+            COLD_SPEED
+        } else {
+            SPEED
+        };
+        vec![("minecraft:movement_speed", speed)]
+    }
+}
@@ -0,0 +1,213 @@
+//! Mobs which players tame. A tamed mob belongs to its owner, it follows the owner around and sits
+//! down when the owner tells it to.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crossbeam::atomic::AtomicCell;
+use pumpkin_core::math::boundingbox::BoundingBoxSize;
+use pumpkin_protocol::client::play::CEntityStatus;
+use rand::{thread_rng, Rng};
+use uuid::Uuid;
+
+use crate::entity::{data_tracker, player::Player};
+
+use super::{find_spawn_position, Mob};
+
+/// Entity statuses the client shows smoke or hearts for
+const TAME_FAILED_STATUS: i8 = 6;
+const TAMED_STATUS: i8 = 7;
+const SITTING_FLAG: i8 = 0x01;
+const TAMED_FLAG: i8 = 0x04;
+
+/// Tamed mobs walk after their owner once it is this far away
+const FOLLOW_DISTANCE: f64 = 10.0;
+/// And stop once they are this close again
+const STOP_DISTANCE: f64 = 2.0;
+/// Owners farther away than this are teleported to
+const TELEPORT_DISTANCE: f64 = 12.0;
+const TELEPORT_ATTEMPTS: u32 = 10;
+const TELEPORT_HORIZONTAL: i32 = 3;
+const TELEPORT_VERTICAL: i32 = 1;
+
+/// The dyes in the order of their color ids, which collars are colored with
+const DYES: [&str; 16] = [
+    "minecraft:white_dye",
+    "minecraft:orange_dye",
+    "minecraft:magenta_dye",
+    "minecraft:light_blue_dye",
+    "minecraft:yellow_dye",
+    "minecraft:lime_dye",
+    "minecraft:pink_dye",
+    "minecraft:gray_dye",
+    "minecraft:light_gray_dye",
+    "minecraft:cyan_dye",
+    "minecraft:purple_dye",
+    "minecraft:blue_dye",
+    "minecraft:brown_dye",
+    "minecraft:green_dye",
+    "minecraft:red_dye",
+    "minecraft:black_dye",
+];
+/// Collars are red until they are dyed
+pub const DEFAULT_COLLAR: i32 = 14;
+
+/// The color id of the dye
+pub(super) fn dye_color(item: &str) -> Option<i32> {
+    DYES.iter()
+        .position(|dye| *dye == item)
+        .map(|color| color as i32)
+}
+
+/// A wild mob by default
+#[derive(Default)]
+pub struct Tameable {
+    owner: AtomicCell<Option<Uuid>>,
+    sitting: AtomicBool,
+    /// Whether it is walking after its owner
+    following: AtomicBool,
+    /// Horses show being tamed with their own flags, they don't sit and their foals aren't tamed
+    horse: bool,
+}
+
+impl Tameable {
+    /// A wild horse, which doesn't have the metadata of the other tameable mobs
+    #[must_use]
+    pub fn horse() -> Self {
+        Self {
+            horse: true,
+            ..Self::default()
+        }
+    }
+
+    /// Defines the flags and the owner, for the mobs which aren't horses
+    pub fn define(mob: &Mob) {
+        let mut tracker = mob.living_entity.entity.data_tracker.lock();
+        tracker.define(&data_tracker::TAMEABLE_FLAGS, 0);
+        tracker.define(&data_tracker::TAMEABLE_OWNER, None);
+    }
+
+    #[must_use]
+    pub fn owner(&self) -> Option<Uuid> {
+        self.owner.load()
+    }
+
+    #[must_use]
+    pub fn is_tamed(&self) -> bool {
+        self.owner.load().is_some()
+    }
+
+    #[must_use]
+    pub fn is_owner(&self, player: &Player) -> bool {
+        self.owner.load() == Some(player.gameprofile.id)
+    }
+
+    #[must_use]
+    pub fn is_sitting(&self) -> bool {
+        self.sitting.load(Ordering::Relaxed)
+    }
+
+    /// Makes the mob belong to the owner, without any particles
+    pub fn tame(&self, mob: &Mob, owner: Uuid) {
+        self.owner.store(Some(owner));
+        self.update_tracker(mob);
+    }
+
+    /// Tames the mob for the player with the chance, showing hearts if it worked and smoke if it
+    /// didn't. Returns whether the mob is tamed now
+    pub async fn try_tame(&self, mob: &Mob, player: &Player, chance: f64) -> bool {
+        let tamed = thread_rng().gen_bool(chance);
+        let status = if tamed {
+            self.tame(mob, player.gameprofile.id);
+            mob.stop_walking();
+            TAMED_STATUS
+        } else {
+            TAME_FAILED_STATUS
+        };
+        let entity = &mob.living_entity.entity;
+        entity
+            .world
+            .broadcast_packet_all(&CEntityStatus::new(entity.entity_id, status))
+            .await;
+        tamed
+    }
+
+    /// Lets the baby belong to the owner of its parent, foals are born wild
+    pub fn pass_on(&self, baby: &Mob) {
+        let (Some(owner), Some(tameable)) = (self.owner(), baby.tameable()) else {
+            return;
+        };
+        if !self.horse {
+            tameable.tame(baby, owner);
+        }
+    }
+
+    pub fn set_sitting(&self, mob: &Mob, sitting: bool) {
+        self.sitting.store(sitting, Ordering::Relaxed);
+        if sitting {
+            mob.stop_walking();
+        }
+        self.update_tracker(mob);
+    }
+
+    fn update_tracker(&self, mob: &Mob) {
+        if self.horse {
+            return;
+        }
+        let mut flags = 0;
+        if self.is_sitting() {
+            flags |= SITTING_FLAG;
+        }
+        if self.is_tamed() {
+            flags |= TAMED_FLAG;
+        }
+        let mut tracker = mob.living_entity.entity.data_tracker.lock();
+        tracker.set(&data_tracker::TAMEABLE_FLAGS, flags);
+        tracker.set(&data_tracker::TAMEABLE_OWNER, self.owner());
+    }
+
+    /// Walks after the owner when it gets too far away, or teleports next to it when it is even
+    /// farther. Returns true while it follows its owner
+    pub async fn follow_owner(&self, mob: &Mob, speed: f64, size: &BoundingBoxSize) -> bool {
+        let Some(owner) = self.owner() else {
+            return false;
+        };
+        if self.is_sitting() {
+            return false;
+        }
+        let entity = &mob.living_entity.entity;
+        let world = &entity.world;
+        let Some(player) = world.get_player_by_uuid(owner).await else {
+            return false;
+        };
+        let owner_entity = &player.living_entity.entity;
+        let owner_pos = owner_entity.pos.load();
+        let distance = owner_pos.sub(&entity.pos.load()).length();
+        if distance >= TELEPORT_DISTANCE {
+            if let Some(spot) = find_spawn_position(
+                world,
+                owner_entity.block_pos.load(),
+                TELEPORT_ATTEMPTS,
+                TELEPORT_HORIZONTAL,
+                TELEPORT_VERTICAL,
+                size,
+            )
+            .await
+            {
+                mob.living_entity.set_pos(spot.x, spot.y, spot.z);
+                mob.stop_walking();
+            }
+            return true;
+        }
+        let following = self.following.load(Ordering::Relaxed);
+        if distance >= FOLLOW_DISTANCE || (following && distance > STOP_DISTANCE) {
+            self.following.store(true, Ordering::Relaxed);
+            mob.walk_to(owner_pos, speed);
+            return true;
+        }
+        if following {
+            self.following.store(false, Ordering::Relaxed);
+            mob.stop_walking();
+        }
+        false
+    }
+}
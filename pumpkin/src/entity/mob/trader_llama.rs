@@ -0,0 +1,110 @@
+//! Trader llamas: they follow the wandering trader they came with and leave with it.
+
+use std::{any::Any, sync::Arc};
+
+use async_trait::async_trait;
+use pumpkin_core::math::{boundingbox::BoundingBoxSize, vector3::Vector3};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_macros::sound;
+use pumpkin_world::item::ItemStack;
+use rand::{thread_rng, Rng};
+
+use crate::{entity::data_tracker, world::World};
+
+use super::{mob_entity, monster::random_drop, play_sound, Mob, MobAi};
+
+const MIN_HEALTH: u8 = 15;
+const MAX_HEALTH: u8 = 30;
+const SIZE: BoundingBoxSize = BoundingBoxSize {
+    width: 0.9,
+    height: 1.87,
+};
+const EYE_HEIGHT: f32 = 1.7;
+const VARIANTS: i32 = 4;
+/// Speeds in blocks per tick
+const WALK_SPEED: f64 = 0.1;
+const FOLLOW_SPEED: f64 = 0.15;
+const WANDER_RANGE: f64 = 10.0;
+/// Llamas stay this close to their trader
+const FOLLOW_DISTANCE: f64 = 4.0;
+
+pub struct TraderLlama {
+    /// The wandering trader it follows, it stays around if `None`
+    trader: Option<EntityId>,
+}
+
+impl TraderLlama {
+    pub async fn spawn(
+        world: &Arc<World>,
+        position: Vector3<f64>,
+        trader: Option<EntityId>,
+    ) -> Arc<Mob> {
+        let entity = mob_entity(world, EntityType::TraderLlama, position, SIZE, EYE_HEIGHT);
+        {
+            let mut tracker = entity.data_tracker.lock();
+            tracker.define(&data_tracker::LLAMA_VARIANT, 0);
+            tracker.set(
+                &data_tracker::LLAMA_VARIANT,
+                thread_rng().gen_range(0..VARIANTS),
+            );
+        }
+        let mob = Mob::new(entity, Box::new(Self { trader }));
+        let max_health = thread_rng().gen_range(MIN_HEALTH..=MAX_HEALTH);
+        mob.living_entity.set_max_health(f32::from(max_health));
+        let mob = Arc::new(mob);
+        world.spawn_entity(mob.clone()).await;
+        mob
+    }
+}
+
+#[async_trait]
+impl MobAi for TraderLlama {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    /// Leaves once its trader is gone
+    async fn tick(&self, mob: &Mob) -> bool {
+        if thread_rng().gen_ratio(1, 120) {
+            play_sound(mob, sound!("minecraft:entity.llama.ambient")).await;
+        }
+        let Some(trader) = self.trader else {
+            mob.wander(WANDER_RANGE, WALK_SPEED);
+            return true;
+        };
+        let entity = &mob.living_entity.entity;
+        let trader = entity
+            .world
+            .entities
+            .lock()
+            .await
+            .get(&trader)
+            .map(|trader| trader.get_entity().pos.load());
+        let Some(trader) = trader else {
+            return false;
+        };
+        if trader.sub(&entity.pos.load()).length() > FOLLOW_DISTANCE {
+            mob.walk_to(trader, FOLLOW_SPEED);
+        } else {
+            mob.stop_walking();
+        }
+        true
+    }
+
+    async fn on_hurt(&self, mob: &Mob, _attacker: Option<EntityId>) {
+        let sound = if mob.living_entity.health.load() > 0.0 {
+            sound!("minecraft:entity.llama.hurt")
+        } else {
+            sound!("minecraft:entity.llama.death")
+        };
+        play_sound(mob, sound).await;
+    }
+
+    fn experience(&self) -> i32 {
+        thread_rng().gen_range(1..=3)
+    }
+
+    fn drops(&self) -> Vec<ItemStack> {
+        random_drop("minecraft:leather", 2).into_iter().collect()
+    }
+}
@@ -0,0 +1,528 @@
+//! Villagers: they pick up the food lying around and breed once they have enough of it, as long as
+//! there are more beds around than villagers. Threatened villagers panic and run away. Panicking
+//! or gossiping villagers call an iron golem to protect them when enough of them haven't seen one
+//! in a while.
+
+use std::{any::Any, sync::Arc};
+
+use async_trait::async_trait;
+use pumpkin_core::math::{boundingbox::BoundingBoxSize, vector3::Vector3};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_macros::sound;
+use pumpkin_protocol::client::play::CTakeItemEntity;
+use pumpkin_world::{
+    block::block_registry::get_block_by_state_id, item::item_registry::get_item_name,
+};
+use rand::{thread_rng, Rng};
+
+use crate::{
+    entity::{
+        data_tracker,
+        player::{Hand, Player},
+        EntityBase,
+    },
+    world::World,
+};
+
+use super::{
+    ageable::{set_baby, Age},
+    attacker_pos,
+    iron_golem::IronGolem,
+    mob_entity, mobs_near, play_sound, show_status, Mob, MobAi,
+};
+
+const MAX_HEALTH: f32 = 20.0;
+const SIZE: BoundingBoxSize = BoundingBoxSize {
+    width: 0.6,
+    height: 1.95,
+};
+const EYE_HEIGHT: f32 = 1.62;
+/// Speeds in blocks per tick
+const WALK_SPEED: f64 = 0.1;
+const PANIC_SPEED: f64 = 0.2;
+const WANDER_RANGE: f64 = 10.0;
+/// A plains villager without a profession
+pub const DEFAULT_DATA: [i32; 3] = [2, 0, 1];
+
+/// Food points a villager needs to breed, and loses when it does
+const BREEDING_FOOD: u32 = 12;
+/// Villagers stop picking up food once they carry this much
+const MAX_FOOD: u32 = 48;
+const PICKUP_RANGE: f64 = 1.0;
+/// How far villagers look for a partner
+const PARTNER_RANGE: f64 = 8.0;
+/// Partners have to stay this close for a while to make a baby
+const BREEDING_RANGE: f64 = 2.0;
+const BREEDING_TICKS: u32 = 275;
+/// Where villagers look for beds for their baby
+const BED_HORIZONTAL: i32 = 16;
+const BED_VERTICAL: i32 = 4;
+/// How far around a position the beds of its village are
+const VILLAGE_HORIZONTAL: i32 = 32;
+const VILLAGE_VERTICAL: i32 = 8;
+
+/// Entity statuses the client shows particles for
+const HEARTS_STATUS: i8 = 12;
+const ANGRY_STATUS: i8 = 13;
+const HEAD_SHAKE_TICKS: i32 = 40;
+
+const PANIC_TICKS: u32 = 100;
+/// How close threats have to come to make villagers panic
+const THREAT_RANGE: f64 = 8.0;
+/// Panicking villagers call a golem every few seconds
+const PANIC_GOLEM_INTERVAL: u32 = 100;
+const PANIC_GOLEM_VILLAGERS: usize = 3;
+const GOSSIP_RANGE: f64 = 3.0;
+const GOSSIP_COOLDOWN: u32 = 1200;
+const GOSSIP_GOLEM_VILLAGERS: usize = 5;
+/// Villagers look around for threats and golems once a second
+const SENSE_INTERVAL: u32 = 20;
+const GOLEM_RANGE: f64 = 16.0;
+/// Ticks villagers remember a golem they saw, they don't call another one meanwhile
+const GOLEM_MEMORY: u32 = 600;
+/// The villagers calling a golem together
+const GOLEM_VILLAGER_RANGE: f64 = 10.0;
+
+/// The mobs villagers run from
+pub(super) const fn is_threat(entity_type: EntityType) -> bool {
+    matches!(
+        entity_type,
+        EntityType::Drowned
+            | EntityType::Evoker
+            | EntityType::Husk
+            | EntityType::Illusioner
+            | EntityType::Pillager
+            | EntityType::Ravager
+            | EntityType::Vex
+            | EntityType::Vindicator
+            | EntityType::Zoglin
+            | EntityType::Zombie
+            | EntityType::ZombieVillager
+    )
+}
+
+pub(super) fn is_villager(entity_type: EntityType) -> bool {
+    entity_type == EntityType::Villager
+}
+
+/// The food points of the foods villagers pick up
+fn food_points(item: &str) -> Option<u32> {
+    match item {
+        "minecraft:bread" => Some(4),
+        "minecraft:carrot" | "minecraft:potato" | "minecraft:beetroot" => Some(1),
+        _ => None,
+    }
+}
+
+fn is_bed_head(state_id: u16) -> bool {
+    get_block_by_state_id(state_id).is_some_and(|block| {
+        block.name.ends_with("_bed")
+            && block
+                .properties_of_state(state_id)
+                .unwrap_or_default()
+                .contains(&("part", "head"))
+    })
+}
+
+/// The beds in range of the position
+async fn beds_near(world: &World, position: Vector3<f64>, horizontal: i32, vertical: i32) -> usize {
+    let center = Vector3::new(
+        position.x.floor() as i32,
+        position.y.floor() as i32,
+        position.z.floor() as i32,
+    );
+    let offset = Vector3::new(horizontal, vertical, horizontal);
+    world
+        .get_block_state_ids_in(center.sub(&offset), center.add(&offset))
+        .await
+        .into_iter()
+        .filter(|(_, state_id)| is_bed_head(*state_id))
+        .count()
+}
+
+/// Whether there are more beds around the position than villagers to sleep in them
+async fn has_free_bed(world: &World, position: Vector3<f64>) -> bool {
+    let beds = beds_near(world, position, BED_HORIZONTAL, BED_VERTICAL).await;
+    let villagers = mobs_near(world, position, f64::from(BED_HORIZONTAL), is_villager)
+        .await
+        .len();
+    beds > villagers
+}
+
+/// The beds of the village the position is in, none if it isn't in a village. Villages are where
+/// villagers have their beds
+pub async fn village_beds(world: &World, position: Vector3<f64>) -> usize {
+    beds_near(world, position, VILLAGE_HORIZONTAL, VILLAGE_VERTICAL).await
+}
+
+#[derive(Default)]
+struct VillagerState {
+    food: u32,
+    /// The villager it breeds with, with the ticks they spent close together
+    partner: Option<(EntityId, u32)>,
+    /// What it runs from, with the ticks it keeps panicking
+    panic: Option<(Vector3<f64>, u32)>,
+    gossip_cooldown: u32,
+    /// Ticks it still remembers the last golem it saw
+    golem_seen: u32,
+    head_shake: i32,
+}
+
+pub struct Villager {
+    age: Age,
+    state: parking_lot::Mutex<VillagerState>,
+}
+
+impl Villager {
+    /// Spawns a villager at the position, babies grow up after a while
+    pub async fn spawn(world: &Arc<World>, position: Vector3<f64>, baby: bool) -> Arc<Mob> {
+        let entity = mob_entity(world, EntityType::Villager, position, SIZE, EYE_HEIGHT);
+        {
+            let mut tracker = entity.data_tracker.lock();
+            tracker.define(&data_tracker::BABY, false);
+            tracker.define(&data_tracker::VILLAGER_HEAD_SHAKE, 0);
+            tracker.define(&data_tracker::VILLAGER_DATA, DEFAULT_DATA);
+        }
+        let villager = Self {
+            age: Age::new(baby),
+            state: parking_lot::Mutex::new(VillagerState::default()),
+        };
+        let mob = Mob::new(entity, Box::new(villager));
+        mob.living_entity.set_max_health(MAX_HEALTH);
+        if baby {
+            set_baby(&mob, true, SIZE);
+        }
+        let mob = Arc::new(mob);
+        world.spawn_entity(mob.clone()).await;
+        mob
+    }
+
+    #[must_use]
+    pub fn is_baby(&self) -> bool {
+        self.age.is_baby()
+    }
+
+    /// Adults with enough food which didn't breed for a while
+    fn wants_to_breed(&self) -> bool {
+        self.age.can_breed() && self.state.lock().food >= BREEDING_FOOD
+    }
+
+    /// Panics about the threat for a while
+    fn panic(&self, threat: Vector3<f64>) {
+        self.state.lock().panic = Some((threat, PANIC_TICKS));
+    }
+
+    /// Remembers the golems nearby, and panics about threats coming close
+    async fn sense(&self, mob: &Mob) {
+        let entity = &mob.living_entity.entity;
+        let world = &entity.world;
+        let pos = entity.pos.load();
+        if !mobs_near(world, pos, GOLEM_RANGE, |entity_type| {
+            entity_type == EntityType::IronGolem
+        })
+        .await
+        .is_empty()
+        {
+            self.state.lock().golem_seen = GOLEM_MEMORY;
+        }
+        if let Some(threat) = mobs_near(world, pos, THREAT_RANGE, is_threat).await.first() {
+            self.panic(threat.get_entity().pos.load());
+        }
+    }
+
+    /// Picks up the food lying next to it, as much as it can carry
+    async fn pick_up_food(&self, mob: &Mob) {
+        if self.state.lock().food >= MAX_FOOD {
+            return;
+        }
+        let entity = &mob.living_entity.entity;
+        let world = &entity.world;
+        let pos = entity.pos.load();
+        let items: Vec<_> = world
+            .entities
+            .lock()
+            .await
+            .values()
+            .filter(|other| {
+                other.get_item_entity().is_some_and(|item| {
+                    !item.pickup_delayed()
+                        && get_item_name(item.stack().item_id)
+                            .and_then(food_points)
+                            .is_some()
+                })
+            })
+            .filter(|other| other.get_entity().pos.load().sub(&pos).length() <= PICKUP_RANGE)
+            .cloned()
+            .collect();
+        for item in items {
+            let Some(item) = item.get_item_entity() else {
+                continue;
+            };
+            let stack = item.stack();
+            let Some(points) = get_item_name(stack.item_id).and_then(food_points) else {
+                continue;
+            };
+            let taken = {
+                let mut state = self.state.lock();
+                let wanted = MAX_FOOD.saturating_sub(state.food).div_ceil(points);
+                let taken = u32::from(stack.item_count).min(wanted);
+                state.food += taken * points;
+                taken as u8
+            };
+            if taken == 0 {
+                return;
+            }
+            world
+                .broadcast_packet_all(&CTakeItemEntity::new(
+                    item.entity.entity_id.into(),
+                    entity.entity_id.into(),
+                    i32::from(taken).into(),
+                ))
+                .await;
+            if taken < stack.item_count {
+                let mut left = stack;
+                left.item_count -= taken;
+                item.set_stack(left);
+            } else {
+                world.despawn_entity(&item.entity).await;
+            }
+        }
+    }
+
+    /// The partner it breeds with, once both want to. Partners settle on each other
+    async fn find_partner(&self, mob: &Mob) -> Option<Arc<dyn EntityBase>> {
+        let entity = &mob.living_entity.entity;
+        let id = entity.entity_id;
+        let current = self.state.lock().partner.map(|(partner, _)| partner);
+        let candidates =
+            mobs_near(&entity.world, entity.pos.load(), PARTNER_RANGE, is_villager).await;
+        let partner = candidates.into_iter().find(|other| {
+            let other_id = other.get_entity().entity_id;
+            other_id != id
+                && current.is_none_or(|current| current == other_id)
+                && other
+                    .get_mob()
+                    .and_then(Mob::ai::<Self>)
+                    .is_some_and(|villager| {
+                        villager.wants_to_breed()
+                            && villager
+                                .state
+                                .lock()
+                                .partner
+                                .is_none_or(|(partner, _)| partner == id)
+                    })
+        })?;
+        let partner_id = partner.get_entity().entity_id;
+        if current.is_none() {
+            self.state.lock().partner = Some((partner_id, 0));
+        }
+        if let Some(villager) = partner.get_mob().and_then(Mob::ai::<Self>) {
+            villager.state.lock().partner.get_or_insert((id, 0));
+        }
+        Some(partner)
+    }
+
+    /// Walks to its partner and stays with it, the one with the lower id makes the baby once
+    /// they were together long enough. Returns true while it is busy breeding
+    async fn breed(&self, mob: &Mob) -> bool {
+        if !self.wants_to_breed() {
+            self.state.lock().partner = None;
+            return false;
+        }
+        let Some(partner) = self.find_partner(mob).await else {
+            self.state.lock().partner = None;
+            return false;
+        };
+        let entity = &mob.living_entity.entity;
+        let partner_entity = partner.get_entity();
+        let partner_pos = partner_entity.pos.load();
+        mob.look_at(partner_pos);
+        if partner_pos.sub(&entity.pos.load()).length() > BREEDING_RANGE {
+            mob.walk_to(partner_pos, WALK_SPEED);
+            return true;
+        }
+        mob.stop_walking();
+        let together = {
+            let mut state = self.state.lock();
+            let Some((_, ticks)) = &mut state.partner else {
+                return false;
+            };
+            *ticks += 1;
+            *ticks
+        };
+        if together >= BREEDING_TICKS && entity.entity_id < partner_entity.entity_id {
+            if let Some(partner_mob) = partner.get_mob() {
+                self.finish_breeding(mob, partner_mob).await;
+            }
+        }
+        true
+    }
+
+    /// Makes a baby if there is a bed for it, the parents get angry otherwise
+    async fn finish_breeding(&self, mob: &Mob, partner: &Mob) {
+        let Some(partner_villager) = partner.ai::<Self>() else {
+            return;
+        };
+        let entity = &mob.living_entity.entity;
+        let world = &entity.world;
+        let pos = entity.pos.load();
+        self.state.lock().partner = None;
+        partner_villager.state.lock().partner = None;
+        if !has_free_bed(world, pos).await {
+            show_status(mob, ANGRY_STATUS).await;
+            show_status(partner, ANGRY_STATUS).await;
+            return;
+        }
+        for (parent, villager) in [(mob, self), (partner, partner_villager)] {
+            let mut state = villager.state.lock();
+            state.food = state.food.saturating_sub(BREEDING_FOOD);
+            drop(state);
+            villager.age.start_breed_cooldown();
+            show_status(parent, HEARTS_STATUS).await;
+        }
+        Self::spawn(world, pos, true).await;
+    }
+
+    /// Chats with an adult villager close by now and then, villagers gossiping in a big enough
+    /// village call a golem
+    async fn gossip(&self, mob: &Mob) {
+        if self.state.lock().gossip_cooldown > 0 {
+            return;
+        }
+        let entity = &mob.living_entity.entity;
+        let others = mobs_near(&entity.world, entity.pos.load(), GOSSIP_RANGE, is_villager).await;
+        let Some(other) = others.iter().find(|other| {
+            other.get_entity().entity_id != entity.entity_id
+                && other
+                    .get_mob()
+                    .and_then(Mob::ai::<Self>)
+                    .is_some_and(|villager| {
+                        !villager.is_baby() && villager.state.lock().gossip_cooldown == 0
+                    })
+        }) else {
+            return;
+        };
+        if let Some(villager) = other.get_mob().and_then(Mob::ai::<Self>) {
+            villager.state.lock().gossip_cooldown = GOSSIP_COOLDOWN;
+        }
+        self.state.lock().gossip_cooldown = GOSSIP_COOLDOWN;
+        mob.look_at(other.get_entity().pos.load());
+        self.try_summon_golem(mob, GOSSIP_GOLEM_VILLAGERS).await;
+    }
+
+    /// Calls an iron golem if enough villagers around haven't seen one in a while, they all
+    /// remember the new golem
+    async fn try_summon_golem(&self, mob: &Mob, min_villagers: usize) {
+        if self.state.lock().golem_seen > 0 {
+            return;
+        }
+        let entity = &mob.living_entity.entity;
+        let world = &entity.world;
+        let villagers: Vec<_> =
+            mobs_near(world, entity.pos.load(), GOLEM_VILLAGER_RANGE, is_villager)
+                .await
+                .into_iter()
+                .filter(|villager| {
+                    villager
+                        .get_mob()
+                        .and_then(Mob::ai::<Self>)
+                        .is_some_and(|villager| villager.state.lock().golem_seen == 0)
+                })
+                .collect();
+        if villagers.len() < min_villagers
+            || !IronGolem::try_summon(world, entity.block_pos.load()).await
+        {
+            return;
+        }
+        for villager in &villagers {
+            if let Some(villager) = villager.get_mob().and_then(Mob::ai::<Self>) {
+                villager.state.lock().golem_seen = GOLEM_MEMORY;
+            }
+        }
+    }
+
+    /// Counts down the timers, and lets the villager stop shaking its head
+    fn tick_timers(&self, mob: &Mob) {
+        let head_shake = {
+            let mut state = self.state.lock();
+            state.gossip_cooldown = state.gossip_cooldown.saturating_sub(1);
+            state.golem_seen = state.golem_seen.saturating_sub(1);
+            state.head_shake = (state.head_shake - 1).max(0);
+            if let Some((_, ticks)) = &mut state.panic {
+                *ticks -= 1;
+                if *ticks == 0 {
+                    state.panic = None;
+                }
+            }
+            state.head_shake
+        };
+        mob.living_entity
+            .entity
+            .data_tracker
+            .lock()
+            .set(&data_tracker::VILLAGER_HEAD_SHAKE, head_shake);
+    }
+}
+
+#[async_trait]
+impl MobAi for Villager {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn tick(&self, mob: &Mob) -> bool {
+        if self.age.tick() {
+            set_baby(mob, false, SIZE);
+        }
+        self.tick_timers(mob);
+        if mob.age() % SENSE_INTERVAL == 0 {
+            self.sense(mob).await;
+        }
+        if thread_rng().gen_ratio(1, 120) {
+            play_sound(mob, sound!("minecraft:entity.villager.ambient")).await;
+        }
+        let panic = self.state.lock().panic;
+        if let Some((threat, _)) = panic {
+            self.state.lock().partner = None;
+            mob.flee_from(threat, PANIC_SPEED);
+            if mob.age() % PANIC_GOLEM_INTERVAL == 0 {
+                self.try_summon_golem(mob, PANIC_GOLEM_VILLAGERS).await;
+            }
+            return true;
+        }
+        self.pick_up_food(mob).await;
+        if !self.is_baby() {
+            if self.breed(mob).await {
+                return true;
+            }
+            self.gossip(mob).await;
+        }
+        mob.wander(WANDER_RANGE, WALK_SPEED);
+        true
+    }
+
+    async fn on_hurt(&self, mob: &Mob, attacker: Option<EntityId>) {
+        let entity = &mob.living_entity.entity;
+        let threat = match attacker {
+            Some(attacker) => attacker_pos(&entity.world, attacker).await,
+            None => None,
+        };
+        self.panic(threat.unwrap_or_else(|| entity.pos.load()));
+        let sound = if mob.living_entity.health.load() > 0.0 {
+            sound!("minecraft:entity.villager.hurt")
+        } else {
+            sound!("minecraft:entity.villager.death")
+        };
+        play_sound(mob, sound).await;
+    }
+
+    /// Villagers without a profession have nothing to trade, they shake their heads
+    async fn interact(&self, mob: &Mob, _player: &Player, _hand: Hand) -> bool {
+        if self.is_baby() {
+            return false;
+        }
+        self.state.lock().head_shake = HEAD_SHAKE_TICKS;
+        play_sound(mob, sound!("minecraft:entity.villager.no")).await;
+        true
+    }
+}
@@ -0,0 +1,151 @@
+//! Wandering traders: they show up near players now and then with two llamas, head somewhere
+//! nearby and leave again after a while. Wandering traders run from the same mobs villagers do.
+
+use std::{any::Any, sync::Arc};
+
+use async_trait::async_trait;
+use pumpkin_core::math::{boundingbox::BoundingBoxSize, vector3::Vector3};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_macros::sound;
+use rand::{thread_rng, Rng};
+
+use crate::world::World;
+
+use super::{attacker_pos, mob_entity, mobs_near, play_sound, villager::is_threat, Mob, MobAi};
+
+const MAX_HEALTH: f32 = 20.0;
+const SIZE: BoundingBoxSize = BoundingBoxSize {
+    width: 0.6,
+    height: 1.95,
+};
+const EYE_HEIGHT: f32 = 1.62;
+/// Speeds in blocks per tick
+const WALK_SPEED: f64 = 0.1;
+const PANIC_SPEED: f64 = 0.15;
+const WANDER_RANGE: f64 = 10.0;
+
+/// How close threats have to come to make wandering traders run
+const THREAT_RANGE: f64 = 8.0;
+const PANIC_TICKS: u32 = 100;
+/// They stop heading for their spot once they come this close
+const ARRIVED: f64 = 2.0;
+
+#[derive(Default)]
+struct TraderState {
+    /// Ticks until it leaves, it stays around if `None`
+    despawn_delay: Option<u32>,
+    /// The spot it heads for
+    wander_target: Option<Vector3<f64>>,
+    /// What it runs from, with the ticks it keeps panicking
+    panic: Option<(Vector3<f64>, u32)>,
+}
+
+pub struct WanderingTrader {
+    state: parking_lot::Mutex<TraderState>,
+}
+
+impl WanderingTrader {
+    pub async fn spawn(
+        world: &Arc<World>,
+        position: Vector3<f64>,
+        despawn_delay: Option<u32>,
+        wander_target: Option<Vector3<f64>>,
+    ) -> Arc<Mob> {
+        let entity = mob_entity(
+            world,
+            EntityType::WanderingTrader,
+            position,
+            SIZE,
+            EYE_HEIGHT,
+        );
+        let trader = Self {
+            state: parking_lot::Mutex::new(TraderState {
+                despawn_delay,
+                wander_target,
+                panic: None,
+            }),
+        };
+        let mob = Mob::new(entity, Box::new(trader));
+        mob.living_entity.set_max_health(MAX_HEALTH);
+        let mob = Arc::new(mob);
+        world.spawn_entity(mob.clone()).await;
+        mob
+    }
+
+    /// Counts down the timers, returns false once it is time to leave
+    fn tick_timers(&self) -> bool {
+        let mut state = self.state.lock();
+        if let Some((_, ticks)) = &mut state.panic {
+            *ticks -= 1;
+            if *ticks == 0 {
+                state.panic = None;
+            }
+        }
+        match &mut state.despawn_delay {
+            Some(0) => false,
+            Some(delay) => {
+                *delay -= 1;
+                true
+            }
+            None => true,
+        }
+    }
+}
+
+#[async_trait]
+impl MobAi for WanderingTrader {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn tick(&self, mob: &Mob) -> bool {
+        if !self.tick_timers() {
+            return false;
+        }
+        if thread_rng().gen_ratio(1, 120) {
+            play_sound(mob, sound!("minecraft:entity.wandering_trader.ambient")).await;
+        }
+        let entity = &mob.living_entity.entity;
+        let pos = entity.pos.load();
+        let threat = mobs_near(&entity.world, pos, THREAT_RANGE, is_threat)
+            .await
+            .into_iter()
+            .next();
+        if let Some(threat) = threat {
+            self.state.lock().panic = Some((threat.get_entity().pos.load(), PANIC_TICKS));
+        }
+        let (panic, wander_target) = {
+            let state = self.state.lock();
+            (state.panic, state.wander_target)
+        };
+        if let Some((threat, _)) = panic {
+            mob.flee_from(threat, PANIC_SPEED);
+            return true;
+        }
+        match wander_target {
+            Some(target) if target.sub(&pos).length() > ARRIVED => {
+                mob.walk_to(target, WALK_SPEED);
+            }
+            Some(_) => {
+                self.state.lock().wander_target = None;
+            }
+            None => mob.wander(WANDER_RANGE, WALK_SPEED),
+        }
+        true
+    }
+
+    async fn on_hurt(&self, mob: &Mob, attacker: Option<EntityId>) {
+        let entity = &mob.living_entity.entity;
+        let threat = match attacker {
+            Some(attacker) => attacker_pos(&entity.world, attacker).await,
+            None => None,
+        };
+        self.state.lock().panic = Some((threat.unwrap_or_else(|| entity.pos.load()), PANIC_TICKS));
+        let sound = if mob.living_entity.health.load() > 0.0 {
+            sound!("minecraft:entity.wandering_trader.hurt")
+        } else {
+            sound!("minecraft:entity.wandering_trader.death")
+        };
+        play_sound(mob, sound).await;
+    }
+}
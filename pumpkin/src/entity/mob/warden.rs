@@ -0,0 +1,756 @@
+//! Wardens: blind mobs which emerge from the ground when a sculk shrieker summons them. They find
+//! their way by vibrations and smell, everything they notice makes them angrier at whoever caused
+//! it. Once angry enough they roar and hunt the player down, with sonic booms going through walls
+//! for players out of reach. Left alone for a minute they dig back into the ground.
+
+use std::{any::Any, collections::HashMap, sync::Arc};
+
+use async_trait::async_trait;
+use pumpkin_config::KnockbackConfig;
+use pumpkin_core::{
+    math::{boundingbox::BoundingBoxSize, position::WorldPosition, vector3::Vector3},
+    GameMode,
+};
+use pumpkin_entity::{
+    effect_type::EffectType, entity_type::EntityType, pose::EntityPose, EntityId,
+};
+use pumpkin_macros::{particle, sound};
+use pumpkin_protocol::{
+    client::play::{CEntityStatus, Particle},
+    SoundCategory,
+};
+use rand::{thread_rng, Rng};
+
+use crate::{
+    client::combat::knockback_resistance,
+    entity::{
+        damage::{DamageSource, MOB_ATTACK, SONIC_BOOM},
+        data_tracker,
+        effect::StatusEffect,
+        player::Player,
+        Entity,
+    },
+    world::{vibration::GameEvent, World},
+};
+
+use super::{find_spawn_position, mob_entity, push, Mob, MobAi};
+
+const MAX_HEALTH: f32 = 500.0;
+const EXPERIENCE: i32 = 5;
+const SIZE: BoundingBoxSize = BoundingBoxSize {
+    width: 0.9,
+    height: 2.9,
+};
+const EYE_HEIGHT: f32 = 2.465;
+/// Speeds in blocks per tick
+const WALK_SPEED: f64 = 0.15;
+const CHASE_SPEED: f64 = 0.3;
+
+const MAX_ANGER: i32 = 150;
+/// Wardens are agitated from this anger on, and angry enough to attack from the next
+const AGITATED_ANGER: i32 = 40;
+const ANGRY_ANGER: i32 = 80;
+/// How much angrier wardens get at whoever caused a vibration or was sniffed out
+const VIBRATION_ANGER: i32 = 35;
+/// How much angrier wardens get at who hurt them
+const HURT_ANGER: i32 = 100;
+/// The anger at everyone goes down by one every second
+const ANGER_DECAY_INTERVAL: u32 = 20;
+
+const EMERGE_TICKS: u32 = 134;
+const DIG_TICKS: u32 = 100;
+/// Ticks without being disturbed until the warden digs back down
+const DIG_COOLDOWN: u32 = 1200;
+const ROAR_TICKS: u32 = 84;
+/// Ticks into the roar animation the roar is heard
+const ROAR_SOUND_DELAY: u32 = 25;
+const SNIFF_TICKS: u32 = 34;
+const MIN_SNIFF_COOLDOWN: u32 = 100;
+const MAX_SNIFF_COOLDOWN: u32 = 200;
+/// How far wardens smell players, and how close they have to be to get angry at them
+const SNIFF_RANGE: f64 = 24.0;
+const SNIFF_ANGER_HORIZONTAL: f64 = 6.0;
+const SNIFF_ANGER_VERTICAL: f64 = 20.0;
+
+const VIBRATION_RANGE: f64 = 16.0;
+/// Ticks until a warden hears the next vibration
+const VIBRATION_COOLDOWN: u32 = 40;
+
+const ATTACK_DAMAGE: f32 = 30.0;
+const ATTACK_KNOCKBACK: f64 = 0.75;
+const ATTACK_COOLDOWN: u32 = 18;
+const MELEE_REACH: f64 = 2.0;
+
+const SONIC_BOOM_TICKS: u32 = 60;
+/// Ticks of charging until the sonic boom hits
+const SONIC_BOOM_CHARGE: u32 = 34;
+const SONIC_BOOM_COOLDOWN: u32 = 40;
+const SONIC_BOOM_DAMAGE: f32 = 10.0;
+const SONIC_BOOM_HORIZONTAL: f64 = 15.0;
+const SONIC_BOOM_VERTICAL: f64 = 20.0;
+const SONIC_BOOM_KNOCKBACK: f64 = 2.5;
+const SONIC_BOOM_KNOCKBACK_VERTICAL: f64 = 0.5;
+
+/// Where shriekers try to summon wardens around them
+const SUMMON_ATTEMPTS: u32 = 20;
+const SUMMON_HORIZONTAL: i32 = 5;
+const SUMMON_VERTICAL: i32 = 6;
+
+/// Wardens darken the screens of players near them every few seconds
+const DARKNESS_INTERVAL: u32 = 120;
+const DARKNESS_RANGE: f64 = 20.0;
+const DARKNESS_DURATION: u32 = 260;
+
+/// Entity statuses the client animates
+const ATTACK_STATUS: i8 = 4;
+const TENDRILS_STATUS: i8 = 61;
+const SONIC_BOOM_STATUS: i8 = 62;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum AngerLevel {
+    Calm,
+    Agitated,
+    Angry,
+}
+
+impl AngerLevel {
+    const fn of(anger: i32) -> Self {
+        if anger >= ANGRY_ANGER {
+            Self::Angry
+        } else if anger >= AGITATED_ANGER {
+            Self::Agitated
+        } else {
+            Self::Calm
+        }
+    }
+
+    fn ambient_sound(self) -> u16 {
+        match self {
+            Self::Calm => sound!("minecraft:entity.warden.ambient"),
+            Self::Agitated => sound!("minecraft:entity.warden.agitated"),
+            Self::Angry => sound!("minecraft:entity.warden.angry"),
+        }
+    }
+
+    fn listening_sound(self) -> u16 {
+        match self {
+            Self::Angry => sound!("minecraft:entity.warden.listening_angry"),
+            _ => sound!("minecraft:entity.warden.listening"),
+        }
+    }
+}
+
+/// Something the warden is busy with, it can't do anything else meanwhile
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Action {
+    Emerging,
+    Digging,
+    /// Roaring at the player it attacks afterwards
+    Roaring(EntityId),
+    Sniffing,
+    /// Charging a sonic boom at the player
+    SonicBoom(EntityId),
+}
+
+#[derive(Default)]
+struct WardenState {
+    /// How angry it is at the players, up to 150
+    anger: HashMap<EntityId, i32>,
+    /// What it is busy with, with the ticks left
+    action: Option<(Action, u32)>,
+    /// The player it hunts, once it roared at it
+    target: Option<EntityId>,
+    /// Where it noticed something last, it goes to look there
+    disturbance: Option<Vector3<f64>>,
+    /// Ticks since it was last disturbed
+    idle_ticks: u32,
+    sniff_cooldown: u32,
+    vibration_cooldown: u32,
+    attack_cooldown: u32,
+    sonic_boom_cooldown: u32,
+}
+
+impl WardenState {
+    fn increase_anger(&mut self, player: EntityId, amount: i32) {
+        self.idle_ticks = 0;
+        let anger = self.anger.entry(player).or_insert(0);
+        *anger = (*anger + amount).min(MAX_ANGER);
+    }
+
+    /// The player it is angriest at, with the anger
+    fn angriest(&self) -> Option<(EntityId, i32)> {
+        self.anger
+            .iter()
+            .max_by_key(|(_, anger)| **anger)
+            .map(|(player, anger)| (*player, *anger))
+    }
+
+    fn anger_level(&self) -> AngerLevel {
+        AngerLevel::of(self.angriest().map_or(0, |(_, anger)| anger))
+    }
+
+    fn tick_cooldowns(&mut self) {
+        self.sniff_cooldown = self.sniff_cooldown.saturating_sub(1);
+        self.vibration_cooldown = self.vibration_cooldown.saturating_sub(1);
+        self.attack_cooldown = self.attack_cooldown.saturating_sub(1);
+        self.sonic_boom_cooldown = self.sonic_boom_cooldown.saturating_sub(1);
+        if self.target.is_none() && self.disturbance.is_none() {
+            self.idle_ticks += 1;
+        }
+    }
+}
+
+/// Whether wardens go after the player, they leave creative and spectator players alone
+fn can_target(player: &Player) -> bool {
+    matches!(
+        player.gamemode.load(),
+        GameMode::Survival | GameMode::Adventure
+    ) && player.living_entity.health.load() > 0.0
+}
+
+/// Gives darkness to the players in survival and adventure in range
+pub async fn apply_darkness(world: &World, position: Vector3<f64>, range: f64) {
+    for player in world.players().await.iter() {
+        let entity = &player.living_entity.entity;
+        if !can_target(player) || entity.pos.load().sub(&position).length() > range {
+            continue;
+        }
+        player
+            .living_entity
+            .add_effect(StatusEffect {
+                show_particles: false,
+                ..StatusEffect::new(EffectType::Darkness, 0, DARKNESS_DURATION)
+            })
+            .await;
+    }
+}
+
+pub struct Warden {
+    state: parking_lot::Mutex<WardenState>,
+}
+
+impl Warden {
+    /// Lets a warden emerge from the ground at the position
+    pub async fn spawn(world: &Arc<World>, position: Vector3<f64>) -> Arc<Mob> {
+        let entity = mob_entity(world, EntityType::Warden, position, SIZE, EYE_HEIGHT);
+        entity
+            .data_tracker
+            .lock()
+            .define(&data_tracker::WARDEN_ANGER, 0);
+        entity.set_pose(EntityPose::Emerging);
+        let warden = Self {
+            state: parking_lot::Mutex::new(WardenState {
+                action: Some((Action::Emerging, EMERGE_TICKS)),
+                ..WardenState::default()
+            }),
+        };
+        let mob = Mob::new(entity, Box::new(warden));
+        mob.living_entity.set_max_health(MAX_HEALTH);
+        world
+            .play_sound(
+                sound!("minecraft:entity.warden.emerge"),
+                SoundCategory::Hostile,
+                &position,
+            )
+            .await;
+        let mob = Arc::new(mob);
+        world.spawn_entity(mob.clone()).await;
+        mob
+    }
+
+    /// Lets a warden emerge on solid ground near the position, returns false if there was no room
+    /// for one
+    pub async fn try_summon(world: &Arc<World>, position: WorldPosition) -> bool {
+        let Some(feet) = find_spawn_position(
+            world,
+            position,
+            SUMMON_ATTEMPTS,
+            SUMMON_HORIZONTAL,
+            SUMMON_VERTICAL,
+            &SIZE,
+        )
+        .await
+        else {
+            return false;
+        };
+        Self::spawn(world, feet).await;
+        true
+    }
+
+    /// The player, if the warden can go after it
+    async fn target(world: &World, player: EntityId) -> Option<Arc<Player>> {
+        world
+            .get_player_by_entityid(player)
+            .await
+            .filter(|player| can_target(player))
+    }
+
+    /// Calms down a bit, and forgets players which are gone or can't be attacked anymore
+    async fn decay_anger(&self, world: &World) {
+        let players: Vec<_> = self.state.lock().anger.keys().copied().collect();
+        let mut gone = Vec::new();
+        for player in players {
+            if Self::target(world, player).await.is_none() {
+                gone.push(player);
+            }
+        }
+        let mut state = self.state.lock();
+        state
+            .anger
+            .retain(|player, anger| !gone.contains(player) && *anger > 1);
+        for anger in state.anger.values_mut() {
+            *anger -= 1;
+        }
+    }
+
+    async fn play_sound(mob: &Mob, sound: u16) {
+        let entity = &mob.living_entity.entity;
+        entity
+            .world
+            .play_sound(sound, SoundCategory::Hostile, &entity.pos.load())
+            .await;
+    }
+
+    async fn start(&self, mob: &Mob, action: Action) {
+        let entity = &mob.living_entity.entity;
+        // the roar is only heard a bit into the animation
+        let (pose, ticks, sound) = match action {
+            Action::Emerging => (
+                EntityPose::Emerging,
+                EMERGE_TICKS,
+                Some(sound!("minecraft:entity.warden.emerge")),
+            ),
+            Action::Digging => (
+                EntityPose::Digging,
+                DIG_TICKS,
+                Some(sound!("minecraft:entity.warden.dig")),
+            ),
+            Action::Roaring(_) => (EntityPose::Roaring, ROAR_TICKS, None),
+            Action::Sniffing => (
+                EntityPose::Sniffing,
+                SNIFF_TICKS,
+                Some(sound!("minecraft:entity.warden.sniff")),
+            ),
+            Action::SonicBoom(_) => (
+                EntityPose::Standing,
+                SONIC_BOOM_TICKS,
+                Some(sound!("minecraft:entity.warden.sonic_charge")),
+            ),
+        };
+        mob.stop_walking();
+        entity.set_pose(pose);
+        self.state.lock().action = Some((action, ticks));
+        if matches!(action, Action::SonicBoom(_)) {
+            entity
+                .world
+                .broadcast_packet_all(&CEntityStatus::new(entity.entity_id, SONIC_BOOM_STATUS))
+                .await;
+        }
+        if let Some(sound) = sound {
+            Self::play_sound(mob, sound).await;
+        }
+    }
+
+    /// Goes on with what the warden is busy with, `ticks` are the ticks left. Returns false once
+    /// it dug into the ground
+    async fn continue_action(&self, mob: &Mob, action: Action, ticks: u32) -> bool {
+        let entity = &mob.living_entity.entity;
+        let world = &entity.world;
+        match action {
+            Action::Emerging | Action::Sniffing => {}
+            Action::Digging => return ticks > 0,
+            Action::Roaring(player) => {
+                if ROAR_TICKS - ticks == ROAR_SOUND_DELAY {
+                    Self::play_sound(mob, sound!("minecraft:entity.warden.roar")).await;
+                }
+                if let Some(player) = Self::target(world, player).await {
+                    mob.look_at(player.living_entity.entity.pos.load());
+                }
+            }
+            Action::SonicBoom(player) => {
+                let target = Self::target(world, player).await;
+                if let Some(target) = &target {
+                    mob.look_at(target.living_entity.entity.pos.load());
+                }
+                if SONIC_BOOM_TICKS - ticks == SONIC_BOOM_CHARGE {
+                    if let Some(target) = target.filter(|target| in_sonic_boom_range(mob, target)) {
+                        Self::sonic_boom(mob, &target).await;
+                    }
+                }
+            }
+        }
+        if ticks > 0 {
+            return true;
+        }
+
+        entity.set_pose(EntityPose::Standing);
+        {
+            let mut state = self.state.lock();
+            state.action = None;
+            match action {
+                Action::Roaring(player) => state.target = Some(player),
+                Action::SonicBoom(_) => state.sonic_boom_cooldown = SONIC_BOOM_COOLDOWN,
+                Action::Sniffing => {
+                    state.sniff_cooldown =
+                        thread_rng().gen_range(MIN_SNIFF_COOLDOWN..=MAX_SNIFF_COOLDOWN);
+                }
+                Action::Emerging | Action::Digging => {}
+            }
+        }
+        if action == Action::Sniffing {
+            self.finish_sniffing(mob).await;
+        }
+        true
+    }
+
+    /// Smells the nearest player, gets angry at it if it is close and goes looking for it
+    async fn finish_sniffing(&self, mob: &Mob) {
+        let entity = &mob.living_entity.entity;
+        let pos = entity.pos.load();
+        let players = entity.world.players().await;
+        let Some(player) = players
+            .iter()
+            .filter(|player| can_target(player))
+            .map(|player| (player, player.living_entity.entity.pos.load()))
+            .filter(|(_, player_pos)| player_pos.sub(&pos).length() <= SNIFF_RANGE)
+            .min_by(|(_, a), (_, b)| {
+                a.sub(&pos)
+                    .length_squared()
+                    .total_cmp(&b.sub(&pos).length_squared())
+            })
+        else {
+            return;
+        };
+        let (player, player_pos) = player;
+        let offset = player_pos.sub(&pos);
+        let mut state = self.state.lock();
+        if offset.x.hypot(offset.z) <= SNIFF_ANGER_HORIZONTAL
+            && offset.y.abs() <= SNIFF_ANGER_VERTICAL
+        {
+            state.increase_anger(player.entity_id(), VIBRATION_ANGER);
+        }
+        state.disturbance = Some(player_pos);
+        state.idle_ticks = 0;
+    }
+
+    /// Hits the player in front of it
+    async fn melee(mob: &Mob, player: &Player) {
+        let entity = &mob.living_entity.entity;
+        let world = &entity.world;
+        world
+            .broadcast_packet_all(&CEntityStatus::new(entity.entity_id, ATTACK_STATUS))
+            .await;
+        Self::play_sound(mob, sound!("minecraft:entity.warden.attack_impact")).await;
+        if !player.abilities.lock().await.invulnerable {
+            player
+                .living_entity
+                .damage(
+                    ATTACK_DAMAGE,
+                    DamageSource::by(MOB_ATTACK, entity.entity_id),
+                )
+                .await;
+        }
+        let yaw = entity.yaw.load().to_radians();
+        player.living_entity.entity.knockback(
+            ATTACK_KNOCKBACK,
+            ATTACK_KNOCKBACK,
+            f64::from(yaw.sin()),
+            f64::from(-yaw.cos()),
+            &KnockbackConfig::default(),
+        );
+        push(player).await;
+    }
+
+    /// Blasts the player with a sonic boom, which goes through walls and armor
+    async fn sonic_boom(mob: &Mob, player: &Player) {
+        let entity = &mob.living_entity.entity;
+        let world = &entity.world;
+        let chest = |entity: &Entity| {
+            let pos = entity.pos.load();
+            Vector3::new(pos.x, pos.y + 1.6, pos.z)
+        };
+        let from = chest(entity);
+        let victim = &player.living_entity.entity;
+        let line = chest(victim).sub(&from);
+        let direction = line.normalize();
+        let particle = Particle::simple(particle!("minecraft:sonic_boom"))
+            .expect("sonic boom particles have no options");
+        for step in 1..line.length().floor() as i32 + 7 {
+            world
+                .spawn_particle(
+                    &particle,
+                    from.add(&(direction * f64::from(step))),
+                    Vector3::new(0.0, 0.0, 0.0),
+                    0.0,
+                    1,
+                    false,
+                )
+                .await;
+        }
+        Self::play_sound(mob, sound!("minecraft:entity.warden.sonic_boom")).await;
+        if !player.abilities.lock().await.invulnerable {
+            player
+                .living_entity
+                .damage(
+                    SONIC_BOOM_DAMAGE,
+                    DamageSource::by(SONIC_BOOM, entity.entity_id),
+                )
+                .await;
+        }
+        let resistance = 1.0 - knockback_resistance(player).await;
+        let velocity = victim.velocity.load();
+        victim.velocity.store(velocity.add(&direction.multiply(
+            SONIC_BOOM_KNOCKBACK * resistance,
+            SONIC_BOOM_KNOCKBACK_VERTICAL * resistance,
+            SONIC_BOOM_KNOCKBACK * resistance,
+        )));
+        push(player).await;
+    }
+
+    /// Hunts the player down, hits it when it is close and blasts it when it is out of reach
+    async fn fight(&self, mob: &Mob, player: &Player) {
+        let entity = &mob.living_entity.entity;
+        let player_pos = player.living_entity.entity.pos.load();
+        let offset = player_pos.sub(&entity.pos.load());
+        if offset.x.hypot(offset.z) <= MELEE_REACH && offset.y.abs() <= SIZE.height {
+            mob.stop_walking();
+            mob.look_at(player_pos);
+            let attack = {
+                let mut state = self.state.lock();
+                let ready = state.attack_cooldown == 0;
+                if ready {
+                    state.attack_cooldown = ATTACK_COOLDOWN;
+                }
+                ready
+            };
+            if attack {
+                Self::melee(mob, player).await;
+            }
+            return;
+        }
+        let boom = self.state.lock().sonic_boom_cooldown == 0 && in_sonic_boom_range(mob, player);
+        if boom {
+            self.start(mob, Action::SonicBoom(player.entity_id())).await;
+        } else {
+            mob.walk_to(player_pos, CHASE_SPEED);
+        }
+    }
+
+    /// Picks what to do next when the warden isn't busy. Returns false once it decided to dig
+    /// down, which it does after a while of nothing happening
+    async fn decide(&self, mob: &Mob) -> bool {
+        let world = &mob.living_entity.entity.world;
+        let (target, angriest) = {
+            let state = self.state.lock();
+            (state.target, state.angriest())
+        };
+
+        // it stops hunting players it calmed down about
+        if let Some(player) = target {
+            let angry = self
+                .state
+                .lock()
+                .anger
+                .get(&player)
+                .is_some_and(|anger| *anger >= ANGRY_ANGER);
+            match Self::target(world, player).await.filter(|_| angry) {
+                Some(player) => {
+                    self.fight(mob, &player).await;
+                    return true;
+                }
+                None => self.state.lock().target = None,
+            }
+        }
+        if let Some((player, _)) = angriest.filter(|(_, anger)| *anger >= ANGRY_ANGER) {
+            if Self::target(world, player).await.is_some() {
+                self.start(mob, Action::Roaring(player)).await;
+                return true;
+            }
+            self.state.lock().anger.remove(&player);
+        }
+
+        let (disturbance, idle_ticks, sniff_cooldown, level) = {
+            let state = self.state.lock();
+            (
+                state.disturbance,
+                state.idle_ticks,
+                state.sniff_cooldown,
+                state.anger_level(),
+            )
+        };
+        if thread_rng().gen_ratio(1, 80) {
+            Self::play_sound(mob, level.ambient_sound()).await;
+        }
+        if let Some(disturbance) = disturbance {
+            let offset = disturbance.sub(&mob.living_entity.entity.pos.load());
+            if offset.x.hypot(offset.z) < 1.0 {
+                self.state.lock().disturbance = None;
+            } else {
+                mob.walk_to(disturbance, WALK_SPEED);
+            }
+            return true;
+        }
+        if idle_ticks >= DIG_COOLDOWN {
+            self.start(mob, Action::Digging).await;
+        } else if sniff_cooldown == 0 {
+            self.start(mob, Action::Sniffing).await;
+        }
+        true
+    }
+}
+
+/// Sonic booms reach further than the warden can hit, above all sideways
+fn in_sonic_boom_range(mob: &Mob, player: &Player) -> bool {
+    let offset = player
+        .living_entity
+        .entity
+        .pos
+        .load()
+        .sub(&mob.living_entity.entity.pos.load());
+    offset.x.hypot(offset.z) <= SONIC_BOOM_HORIZONTAL && offset.y.abs() <= SONIC_BOOM_VERTICAL
+}
+
+#[async_trait]
+impl MobAi for Warden {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn tick(&self, mob: &Mob) -> bool {
+        let entity = &mob.living_entity.entity;
+        let world = &entity.world;
+        let age = mob.age();
+        if age % ANGER_DECAY_INTERVAL == 0 {
+            self.decay_anger(world).await;
+        }
+        let anger = self.state.lock().angriest().map_or(0, |(_, anger)| anger);
+        entity
+            .data_tracker
+            .lock()
+            .set(&data_tracker::WARDEN_ANGER, anger);
+        if age % DARKNESS_INTERVAL == 0 {
+            apply_darkness(world, entity.pos.load(), DARKNESS_RANGE).await;
+        }
+
+        let action = {
+            let mut state = self.state.lock();
+            state.tick_cooldowns();
+            if let Some((_, ticks)) = &mut state.action {
+                *ticks = ticks.saturating_sub(1);
+            }
+            state.action
+        };
+        match action {
+            Some((action, ticks)) => self.continue_action(mob, action, ticks).await,
+            None => self.decide(mob).await,
+        }
+    }
+
+    async fn hear(
+        &self,
+        mob: &Mob,
+        event: GameEvent,
+        position: Vector3<f64>,
+        cause: Option<EntityId>,
+    ) {
+        let entity = &mob.living_entity.entity;
+        let world = &entity.world;
+        {
+            let state = self.state.lock();
+            if state.vibration_cooldown > 0
+                || matches!(state.action, Some((Action::Emerging | Action::Digging, _)))
+            {
+                return;
+            }
+        }
+        // wardens don't hear themselves or each other
+        if let Some(cause) = cause {
+            let warden = cause == entity.entity_id
+                || world
+                    .entities
+                    .lock()
+                    .await
+                    .get(&cause)
+                    .is_some_and(|other| other.get_entity().entity_type == EntityType::Warden);
+            if warden {
+                return;
+            }
+        }
+        let pos = entity.pos.load();
+        let ears = Vector3::new(pos.x, pos.y + f64::from(entity.standing_eye_height), pos.z);
+        if ears.sub(&position).length() > VIBRATION_RANGE
+            || (event.frequency().is_some() && world.is_vibration_occluded(position, ears).await)
+        {
+            return;
+        }
+        let player = match cause {
+            Some(cause) => Self::target(world, cause).await,
+            None => None,
+        };
+
+        let level = {
+            let mut state = self.state.lock();
+            state.vibration_cooldown = VIBRATION_COOLDOWN;
+            state.idle_ticks = 0;
+            let hunted = state.angriest().map(|(hunted, _)| hunted);
+            if let Some(player) = &player {
+                state.increase_anger(player.entity_id(), VIBRATION_ANGER);
+            }
+            if state.anger_level() != AngerLevel::Angry && (hunted.is_none() || hunted == cause) {
+                state.disturbance = Some(position);
+            }
+            player.is_some().then(|| state.anger_level())
+        };
+        world
+            .broadcast_packet_all(&CEntityStatus::new(entity.entity_id, TENDRILS_STATUS))
+            .await;
+        Self::play_sound(mob, sound!("minecraft:entity.warden.tendril_clicks")).await;
+        let roaring = matches!(self.state.lock().action, Some((Action::Roaring(_), _)));
+        if let Some(level) = level.filter(|_| !roaring) {
+            Self::play_sound(mob, level.listening_sound()).await;
+        }
+    }
+
+    async fn on_hurt(&self, mob: &Mob, attacker: Option<EntityId>) {
+        let world = &mob.living_entity.entity.world;
+        let Some(attacker) = attacker else {
+            return;
+        };
+        if Self::target(world, attacker).await.is_none() {
+            return;
+        }
+        let mut state = self.state.lock();
+        state.increase_anger(attacker, HURT_ANGER);
+        // it turns on who hurt it right away, without roaring first
+        if state.target.is_none() {
+            state.target = Some(attacker);
+            if matches!(
+                state.action,
+                Some((Action::Sniffing | Action::Roaring(_), _))
+            ) {
+                state.action = None;
+                drop(state);
+                mob.living_entity.entity.set_pose(EntityPose::Standing);
+            }
+        }
+    }
+
+    fn invulnerable(&self, _mob: &Mob) -> bool {
+        matches!(
+            self.state.lock().action,
+            Some((Action::Emerging | Action::Digging, _))
+        )
+    }
+
+    fn experience(&self) -> i32 {
+        EXPERIENCE
+    }
+
+    /// The client lets the warden emerge if it is told so when it starts seeing it
+    fn spawn_data(&self, _mob: &Mob) -> i32 {
+        i32::from(matches!(
+            self.state.lock().action,
+            Some((Action::Emerging, _))
+        ))
+    }
+}
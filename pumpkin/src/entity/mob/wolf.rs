@@ -0,0 +1,309 @@
+//! Wolves: a bone tames a wild wolf now and then. Tamed wolves follow their owner, sit when it
+//! tells them to and go after whoever hurts it, they heal and breed when they are fed meat. Wolves
+//! bite back whoever hurts them.
+
+use std::{any::Any, sync::Arc};
+
+use async_trait::async_trait;
+use pumpkin_core::math::{boundingbox::BoundingBoxSize, vector3::Vector3};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_macros::sound;
+use rand::{thread_rng, Rng};
+
+use crate::{
+    entity::{
+        data_tracker,
+        player::{Hand, Player},
+    },
+    world::World,
+};
+
+use super::{
+    ageable::set_baby,
+    animal::Breeding,
+    mob_entity, play_sound,
+    tameable::{dye_color, Tameable, DEFAULT_COLLAR},
+    Mob, MobAi, Target,
+};
+
+const WILD_HEALTH: f32 = 8.0;
+const TAMED_HEALTH: f32 = 40.0;
+const SIZE: BoundingBoxSize = BoundingBoxSize {
+    width: 0.6,
+    height: 0.85,
+};
+const EYE_HEIGHT: f32 = 0.68;
+/// Speeds in blocks per tick
+const WALK_SPEED: f64 = 0.1;
+const CHASE_SPEED: f64 = 0.2;
+const WANDER_RANGE: f64 = 10.0;
+
+/// A bone tames a wolf one in three times
+const TAME_CHANCE: f64 = 1.0 / 3.0;
+const BONE: &str = "minecraft:bone";
+
+/// Wild wolves stay angry at whoever hurt them for 20 to 39 seconds
+const MIN_ANGER_TICKS: i32 = 400;
+const MAX_ANGER_TICKS: i32 = 780;
+/// Tamed wolves look for who hurt their owner every half second
+const DEFEND_INTERVAL: u32 = 10;
+const REACH: f64 = 1.5;
+const ATTACK_DAMAGE: f32 = 4.0;
+const ATTACK_KNOCKBACK: f64 = 0.4;
+const ATTACK_COOLDOWN: u32 = 20;
+
+/// The health the meat gives a tamed wolf, `None` if the item isn't meat
+fn meat_health(item: &str) -> Option<f32> {
+    match item {
+        "minecraft:chicken" | "minecraft:mutton" => Some(2.0),
+        "minecraft:beef" | "minecraft:porkchop" | "minecraft:rabbit" => Some(3.0),
+        "minecraft:rotten_flesh" => Some(4.0),
+        "minecraft:cooked_rabbit" => Some(5.0),
+        "minecraft:cooked_chicken" | "minecraft:cooked_mutton" => Some(6.0),
+        "minecraft:cooked_beef" | "minecraft:cooked_porkchop" => Some(8.0),
+        _ => None,
+    }
+}
+
+fn is_meat(item: &str) -> bool {
+    meat_health(item).is_some()
+}
+
+#[derive(Default)]
+struct WolfState {
+    target: Option<EntityId>,
+    attack_cooldown: u32,
+    /// Ticks a wild wolf stays angry at its target
+    anger: i32,
+}
+
+pub struct Wolf {
+    breeding: Breeding,
+    tameable: Tameable,
+    state: parking_lot::Mutex<WolfState>,
+}
+
+impl Wolf {
+    pub async fn spawn(world: &Arc<World>, position: Vector3<f64>, baby: bool) -> Arc<Mob> {
+        let entity = mob_entity(world, EntityType::Wolf, position, SIZE, EYE_HEIGHT);
+        {
+            let mut tracker = entity.data_tracker.lock();
+            tracker.define(&data_tracker::BABY, false);
+            tracker.define(&data_tracker::WOLF_COLLAR, DEFAULT_COLLAR);
+            tracker.define(&data_tracker::WOLF_ANGER, 0);
+        }
+        let wolf = Self {
+            breeding: Breeding::new(baby, is_meat, SIZE),
+            tameable: Tameable::default(),
+            state: parking_lot::Mutex::new(WolfState::default()),
+        };
+        let mob = Mob::new(entity, Box::new(wolf));
+        Tameable::define(&mob);
+        mob.living_entity.set_max_health(WILD_HEALTH);
+        if baby {
+            set_baby(&mob, true, SIZE);
+        }
+        let mob = Arc::new(mob);
+        world.spawn_entity(mob.clone()).await;
+        mob
+    }
+
+    /// Whoever hurt the owner lately, unless it is another of the owner's pets
+    async fn find_owner_attacker(&self, mob: &Mob) -> Option<Target> {
+        let owner = self.tameable.owner()?;
+        let world = &mob.living_entity.entity.world;
+        let player = world.get_player_by_uuid(owner).await?;
+        let (attacker, _) = player.living_entity.kill_credit.load()?;
+        let target = Target::find(world, attacker).await?;
+        if let Target::Mob(other) = &target {
+            if other
+                .get_mob()
+                .and_then(Mob::tameable)
+                .is_some_and(|tameable| tameable.owner() == Some(owner))
+            {
+                return None;
+            }
+        }
+        Some(target)
+    }
+
+    /// Runs up to the target and bites it once it is in reach
+    async fn fight(&self, mob: &Mob, target: &Target) {
+        let entity = &mob.living_entity.entity;
+        let target_pos = target.entity().pos.load();
+        let offset = target_pos.sub(&entity.pos.load());
+        if offset.x.hypot(offset.z) > REACH || offset.y.abs() > SIZE.height {
+            mob.walk_to(target_pos, CHASE_SPEED);
+            return;
+        }
+        mob.stop_walking();
+        mob.look_at(target_pos);
+        let attack = {
+            let mut state = self.state.lock();
+            let ready = state.attack_cooldown == 0;
+            if ready {
+                state.attack_cooldown = ATTACK_COOLDOWN;
+            }
+            ready
+        };
+        if attack {
+            mob.melee(target, ATTACK_DAMAGE, ATTACK_KNOCKBACK, 0.0)
+                .await;
+        }
+    }
+
+    /// Counts down the timers, wild wolves calm down once their anger is gone
+    fn tick_timers(&self, mob: &Mob) {
+        let anger = {
+            let mut state = self.state.lock();
+            state.attack_cooldown = state.attack_cooldown.saturating_sub(1);
+            if state.anger > 0 {
+                state.anger -= 1;
+                if state.anger == 0 {
+                    state.target = None;
+                }
+            }
+            state.anger
+        };
+        mob.living_entity
+            .entity
+            .data_tracker
+            .lock()
+            .set(&data_tracker::WOLF_ANGER, anger);
+    }
+
+    /// Tames the wolf one in three times, it sits down right away
+    async fn try_tame(&self, mob: &Mob, player: &Player) {
+        if !self.tameable.try_tame(mob, player, TAME_CHANCE).await {
+            return;
+        }
+        mob.living_entity.set_max_health(TAMED_HEALTH);
+        self.tameable.set_sitting(mob, true);
+        let mut state = self.state.lock();
+        state.target = None;
+        state.anger = 0;
+    }
+}
+
+#[async_trait]
+impl MobAi for Wolf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn tick(&self, mob: &Mob) -> bool {
+        let world = &mob.living_entity.entity.world;
+        self.breeding.tick(mob);
+        self.tick_timers(mob);
+        if self.tameable.is_sitting() {
+            return true;
+        }
+        if thread_rng().gen_ratio(1, 120) {
+            play_sound(mob, sound!("minecraft:entity.wolf.ambient")).await;
+        }
+        let target = self.state.lock().target;
+        let mut target = match target {
+            Some(target) => Target::find(world, target).await,
+            None => None,
+        };
+        if target.is_none() && mob.age() % DEFEND_INTERVAL == 0 {
+            target = self.find_owner_attacker(mob).await;
+        }
+        self.state.lock().target = target.as_ref().map(|target| target.entity().entity_id);
+        if let Some(target) = target {
+            self.fight(mob, &target).await;
+            return true;
+        }
+        if self.breeding.breed(mob, WALK_SPEED).await
+            || self.tameable.follow_owner(mob, CHASE_SPEED, &SIZE).await
+        {
+            return true;
+        }
+        mob.wander(WANDER_RANGE, WALK_SPEED);
+        true
+    }
+
+    /// Wolves go after whoever hurt them, except tamed wolves hurt by their owner. Sitting
+    /// wolves get up
+    async fn on_hurt(&self, mob: &Mob, attacker: Option<EntityId>) {
+        let world = &mob.living_entity.entity.world;
+        self.tameable.set_sitting(mob, false);
+        if let Some(attacker) = attacker {
+            let by_owner = match world.get_player_by_entityid(attacker).await {
+                Some(player) => self.tameable.is_owner(&player),
+                None => false,
+            };
+            if !by_owner {
+                let mut state = self.state.lock();
+                state.target = Some(attacker);
+                if !self.tameable.is_tamed() {
+                    state.anger = thread_rng().gen_range(MIN_ANGER_TICKS..=MAX_ANGER_TICKS);
+                }
+            }
+        }
+        let sound = if mob.living_entity.health.load() > 0.0 {
+            sound!("minecraft:entity.wolf.hurt")
+        } else {
+            sound!("minecraft:entity.wolf.death")
+        };
+        play_sound(mob, sound).await;
+    }
+
+    /// Wild wolves take bones, tamed ones eat meat, get their collar dyed by their owner or sit
+    /// down and get up when it tells them to
+    async fn interact(&self, mob: &Mob, player: &Player, hand: Hand) -> bool {
+        let item = player.held_item_name(hand).await;
+        if !self.tameable.is_tamed() {
+            if item != Some(BONE)
+                || self.state.lock().anger > 0
+                || !player.use_held_item(hand, BONE).await
+            {
+                return false;
+            }
+            self.try_tame(mob, player).await;
+            return true;
+        }
+        if let Some(item) = item {
+            let living = &mob.living_entity;
+            if let Some(health) = meat_health(item) {
+                if living.health.load() < living.max_health.load()
+                    && player.use_held_item(hand, item).await
+                {
+                    living.heal(health).await;
+                    return true;
+                }
+                if self.breeding.feed(mob, player, hand).await {
+                    return true;
+                }
+            }
+            if let Some(color) = dye_color(item) {
+                if self.tameable.is_owner(player) && player.use_held_item(hand, item).await {
+                    living
+                        .entity
+                        .data_tracker
+                        .lock()
+                        .set(&data_tracker::WOLF_COLLAR, color);
+                    return true;
+                }
+            }
+        }
+        if !self.tameable.is_owner(player) {
+            return false;
+        }
+        self.tameable.set_sitting(mob, !self.tameable.is_sitting());
+        self.state.lock().target = None;
+        true
+    }
+
+    fn experience(&self) -> i32 {
+        self.breeding.experience()
+    }
+
+    fn breeding(&self) -> Option<&Breeding> {
+        Some(&self.breeding)
+    }
+
+    fn tameable(&self) -> Option<&Tameable> {
+        Some(&self.tameable)
+    }
+}
@@ -0,0 +1,134 @@
+//! Zombies: they hunt players, villagers and iron golems and burn in sunlight. The villagers they
+//! kill may rise again as zombie villagers. At night zombies now and then besiege a village.
+
+use std::{any::Any, sync::Arc};
+
+use async_trait::async_trait;
+use pumpkin_core::math::{boundingbox::BoundingBoxSize, vector3::Vector3};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_macros::sound;
+use pumpkin_world::item::ItemStack;
+use rand::{thread_rng, Rng};
+
+use crate::{entity::data_tracker, world::World};
+
+use super::{
+    mob_entity,
+    monster::{burn_in_sunlight, chase, random_drop},
+    play_sound,
+    zombie_villager::ZombieVillager,
+    Mob, MobAi, Target,
+};
+
+const MAX_HEALTH: f32 = 20.0;
+const EXPERIENCE: i32 = 5;
+const SIZE: BoundingBoxSize = BoundingBoxSize {
+    width: 0.6,
+    height: 1.95,
+};
+const EYE_HEIGHT: f32 = 1.74;
+/// Speeds in blocks per tick
+const WALK_SPEED: f64 = 0.07;
+const CHASE_SPEED: f64 = 0.18;
+const WANDER_RANGE: f64 = 10.0;
+
+/// Zombies look for what to hunt every half second
+const TARGET_INTERVAL: u32 = 10;
+const REACH: f64 = 1.5;
+const ATTACK_DAMAGE: f32 = 3.0;
+const ATTACK_COOLDOWN: u32 = 20;
+
+#[derive(Default)]
+struct ZombieState {
+    target: Option<EntityId>,
+    attack_cooldown: u32,
+}
+
+pub struct Zombie {
+    state: parking_lot::Mutex<ZombieState>,
+}
+
+impl Zombie {
+    pub async fn spawn(world: &Arc<World>, position: Vector3<f64>) -> Arc<Mob> {
+        let entity = mob_entity(world, EntityType::Zombie, position, SIZE, EYE_HEIGHT);
+        entity
+            .data_tracker
+            .lock()
+            .define(&data_tracker::BABY, false);
+        let zombie = Self {
+            state: parking_lot::Mutex::new(ZombieState::default()),
+        };
+        let mob = Mob::new(entity, Box::new(zombie));
+        mob.living_entity.set_max_health(MAX_HEALTH);
+        let mob = Arc::new(mob);
+        world.spawn_entity(mob.clone()).await;
+        mob
+    }
+}
+
+#[async_trait]
+impl MobAi for Zombie {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn tick(&self, mob: &Mob) -> bool {
+        burn_in_sunlight(mob).await;
+        if thread_rng().gen_ratio(1, 120) {
+            play_sound(mob, sound!("minecraft:entity.zombie.ambient")).await;
+        }
+        let target = {
+            let mut state = self.state.lock();
+            state.attack_cooldown = state.attack_cooldown.saturating_sub(1);
+            state.target
+        };
+        let world = &mob.living_entity.entity.world;
+        let mut target = match target {
+            Some(target) => Target::find(world, target).await,
+            None => None,
+        };
+        if target.is_none() && mob.age() % TARGET_INTERVAL == 0 {
+            target = ZombieVillager::find_target(mob).await;
+        }
+        self.state.lock().target = target.as_ref().map(|target| target.entity().entity_id);
+        let Some(target) = target else {
+            mob.wander(WANDER_RANGE, WALK_SPEED);
+            return true;
+        };
+        let hit = chase(mob, &target, CHASE_SPEED, REACH, ATTACK_DAMAGE, || {
+            let mut state = self.state.lock();
+            let ready = state.attack_cooldown == 0;
+            if ready {
+                state.attack_cooldown = ATTACK_COOLDOWN;
+            }
+            ready
+        })
+        .await;
+        if let (true, Target::Mob(victim)) = (hit, &target) {
+            ZombieVillager::try_infect(mob, victim.get_mob()).await;
+        }
+        true
+    }
+
+    async fn on_hurt(&self, mob: &Mob, attacker: Option<EntityId>) {
+        if let Some(attacker) = attacker {
+            self.state.lock().target = Some(attacker);
+        }
+        let sound = if mob.living_entity.health.load() > 0.0 {
+            sound!("minecraft:entity.zombie.hurt")
+        } else {
+            sound!("minecraft:entity.zombie.death")
+        };
+        play_sound(mob, sound).await;
+    }
+
+    fn experience(&self) -> i32 {
+        EXPERIENCE
+    }
+
+    fn drops(&self) -> Vec<ItemStack> {
+        random_drop("minecraft:rotten_flesh", 2)
+            .into_iter()
+            .collect()
+    }
+}
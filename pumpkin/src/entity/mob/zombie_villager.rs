@@ -0,0 +1,336 @@
+//! Zombie villagers: they hunt players, villagers and iron golems, and on harder difficulties the
+//! villagers they kill rise again as zombie villagers. Feeding a golden apple to a weakened one
+//! cures it, after a few minutes it turns back into a villager.
+
+use std::{any::Any, sync::Arc};
+
+use async_trait::async_trait;
+use pumpkin_core::{
+    math::{boundingbox::BoundingBoxSize, vector3::Vector3},
+    Difficulty,
+};
+use pumpkin_entity::{effect_type::EffectType, entity_type::EntityType, EntityId};
+use pumpkin_macros::sound;
+use pumpkin_protocol::{
+    client::play::{Animation, CEntityAnimation, CEntityStatus, CWorldEvent},
+    SoundCategory,
+};
+use rand::{thread_rng, Rng};
+
+use crate::{
+    entity::{
+        data_tracker,
+        effect::StatusEffect,
+        player::{Hand, Player},
+    },
+    world::World,
+};
+
+use super::{
+    ageable::set_baby,
+    mob_entity, mobs_near,
+    villager::{is_villager, Villager, DEFAULT_DATA},
+    Mob, MobAi, Target,
+};
+
+const MAX_HEALTH: f32 = 20.0;
+const SIZE: BoundingBoxSize = BoundingBoxSize {
+    width: 0.6,
+    height: 1.95,
+};
+const EYE_HEIGHT: f32 = 1.74;
+const EXPERIENCE: i32 = 5;
+const BABY_EXPERIENCE: i32 = 12;
+/// Speeds in blocks per tick
+const WALK_SPEED: f64 = 0.07;
+const CHASE_SPEED: f64 = 0.18;
+const WANDER_RANGE: f64 = 10.0;
+
+/// How far zombie villagers look for what to hunt
+const TARGET_RANGE: f64 = 35.0;
+const TARGET_INTERVAL: u32 = 10;
+const REACH: f64 = 1.5;
+const ATTACK_DAMAGE: f32 = 3.0;
+const ATTACK_KNOCKBACK: f64 = 0.4;
+const ATTACK_COOLDOWN: u32 = 20;
+
+/// Ticks a cure takes
+const MIN_CONVERSION_TICKS: u32 = 3600;
+const MAX_CONVERSION_TICKS: u32 = 6000;
+/// Makes the client play the cure sound
+const CURE_STATUS: i8 = 16;
+const INFECT_EVENT: i32 = 1026;
+const CONVERTED_EVENT: i32 = 1027;
+/// Cured villagers are dizzy for a while
+const NAUSEA_TICKS: u32 = 200;
+
+#[derive(Default)]
+struct ZombieVillagerState {
+    target: Option<EntityId>,
+    attack_cooldown: u32,
+    /// Ticks until it is cured, once it got its golden apple
+    conversion: Option<u32>,
+}
+
+pub struct ZombieVillager {
+    /// Baby zombie villagers never grow up
+    baby: bool,
+    state: parking_lot::Mutex<ZombieVillagerState>,
+}
+
+impl ZombieVillager {
+    pub async fn spawn(world: &Arc<World>, position: Vector3<f64>, baby: bool) -> Arc<Mob> {
+        let entity = mob_entity(
+            world,
+            EntityType::ZombieVillager,
+            position,
+            SIZE,
+            EYE_HEIGHT,
+        );
+        {
+            let mut tracker = entity.data_tracker.lock();
+            tracker.define(&data_tracker::BABY, false);
+            tracker.define(&data_tracker::ZOMBIE_VILLAGER_CONVERTING, false);
+            tracker.define(&data_tracker::ZOMBIE_VILLAGER_DATA, DEFAULT_DATA);
+        }
+        let zombie_villager = Self {
+            baby,
+            state: parking_lot::Mutex::new(ZombieVillagerState::default()),
+        };
+        let mob = Mob::new(entity, Box::new(zombie_villager));
+        mob.living_entity.set_max_health(MAX_HEALTH);
+        if baby {
+            set_baby(&mob, true, SIZE);
+        }
+        let mob = Arc::new(mob);
+        world.spawn_entity(mob.clone()).await;
+        mob
+    }
+
+    /// The nearest player it can attack, or else the nearest villager or golem. Zombies hunt the
+    /// same
+    pub(super) async fn find_target(mob: &Mob) -> Option<Target> {
+        let entity = &mob.living_entity.entity;
+        let world = &entity.world;
+        let pos = entity.pos.load();
+        let players: Vec<_> = world.players().await.iter().cloned().collect();
+        let player = players
+            .into_iter()
+            .map(Target::Player)
+            .filter(|player| {
+                player.can_be_attacked()
+                    && player.entity().pos.load().sub(&pos).length() <= TARGET_RANGE
+            })
+            .min_by(|a, b| {
+                let distance = |target: &Target| target.entity().pos.load().sub(&pos).length();
+                distance(a).total_cmp(&distance(b))
+            });
+        if player.is_some() {
+            return player;
+        }
+        let prey = mobs_near(world, pos, TARGET_RANGE, |entity_type| {
+            is_villager(entity_type)
+                || matches!(
+                    entity_type,
+                    EntityType::WanderingTrader | EntityType::IronGolem
+                )
+        })
+        .await;
+        let villager = prey
+            .iter()
+            .find(|prey| prey.get_entity().entity_type != EntityType::IronGolem);
+        villager.or(prey.first()).cloned().map(Target::Mob)
+    }
+
+    /// Walks up to the target and hits it once it is in reach
+    async fn fight(&self, mob: &Mob, target: &Target) {
+        let entity = &mob.living_entity.entity;
+        let target_pos = target.entity().pos.load();
+        let offset = target_pos.sub(&entity.pos.load());
+        if offset.x.hypot(offset.z) > REACH || offset.y.abs() > SIZE.height {
+            mob.walk_to(target_pos, CHASE_SPEED);
+            return;
+        }
+        mob.stop_walking();
+        mob.look_at(target_pos);
+        let attack = {
+            let mut state = self.state.lock();
+            let ready = state.attack_cooldown == 0;
+            if ready {
+                state.attack_cooldown = ATTACK_COOLDOWN;
+            }
+            ready
+        };
+        if !attack {
+            return;
+        }
+        entity
+            .world
+            .broadcast_packet_all(&CEntityAnimation::new(
+                entity.entity_id.into(),
+                Animation::SwingMainArm as u8,
+            ))
+            .await;
+        if mob
+            .melee(target, ATTACK_DAMAGE, ATTACK_KNOCKBACK, 0.0)
+            .await
+        {
+            if let Target::Mob(victim) = target {
+                Self::try_infect(mob, victim.get_mob()).await;
+            }
+        }
+    }
+
+    /// Lets the villager it killed rise as a zombie villager, sometimes on normal difficulty and
+    /// always on hard
+    pub(super) async fn try_infect(mob: &Mob, victim: Option<&Mob>) {
+        let Some(victim) = victim else {
+            return;
+        };
+        let Some(villager) = victim.ai::<Villager>() else {
+            return;
+        };
+        let victim_entity = &victim.living_entity.entity;
+        let world = &victim_entity.world;
+        let infected = victim.living_entity.health.load() <= 0.0
+            && match world.config.difficulty {
+                Difficulty::Hard => true,
+                Difficulty::Normal => thread_rng().gen_bool(0.5),
+                Difficulty::Peaceful | Difficulty::Easy => false,
+            };
+        if !infected {
+            return;
+        }
+        world.despawn_entity(victim_entity).await;
+        Self::spawn(world, victim_entity.pos.load(), villager.is_baby()).await;
+        world
+            .broadcast_packet_all(&CWorldEvent::new(
+                INFECT_EVENT,
+                &mob.living_entity.entity.block_pos.load(),
+                0,
+                false,
+            ))
+            .await;
+    }
+
+    /// Starts curing, the zombie villager gets strength until it turns back into a villager
+    async fn start_conversion(&self, mob: &Mob) {
+        let living = &mob.living_entity;
+        let entity = &living.entity;
+        let ticks = thread_rng().gen_range(MIN_CONVERSION_TICKS..=MAX_CONVERSION_TICKS);
+        self.state.lock().conversion = Some(ticks);
+        living.remove_effect(EffectType::Weakness).await;
+        living
+            .add_effect(StatusEffect::new(EffectType::Strength, 0, ticks))
+            .await;
+        entity
+            .data_tracker
+            .lock()
+            .set(&data_tracker::ZOMBIE_VILLAGER_CONVERTING, true);
+        entity
+            .world
+            .broadcast_packet_all(&CEntityStatus::new(entity.entity_id, CURE_STATUS))
+            .await;
+    }
+
+    /// Turns back into a dizzy villager
+    async fn finish_conversion(&self, mob: &Mob) {
+        let entity = &mob.living_entity.entity;
+        let world = &entity.world;
+        let villager = Villager::spawn(world, entity.pos.load(), self.baby).await;
+        villager
+            .living_entity
+            .add_effect(StatusEffect::new(EffectType::Nausea, 0, NAUSEA_TICKS))
+            .await;
+        world
+            .broadcast_packet_all(&CWorldEvent::new(
+                CONVERTED_EVENT,
+                &entity.block_pos.load(),
+                0,
+                false,
+            ))
+            .await;
+    }
+}
+
+#[async_trait]
+impl MobAi for ZombieVillager {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn tick(&self, mob: &Mob) -> bool {
+        let world = &mob.living_entity.entity.world;
+        let (target, conversion) = {
+            let mut state = self.state.lock();
+            state.attack_cooldown = state.attack_cooldown.saturating_sub(1);
+            if let Some(ticks) = &mut state.conversion {
+                *ticks = ticks.saturating_sub(1);
+            }
+            (state.target, state.conversion)
+        };
+        if conversion == Some(0) {
+            self.finish_conversion(mob).await;
+            return false;
+        }
+        if thread_rng().gen_ratio(1, 120) {
+            let entity = &mob.living_entity.entity;
+            world
+                .play_sound(
+                    sound!("minecraft:entity.zombie_villager.ambient"),
+                    SoundCategory::Hostile,
+                    &entity.pos.load(),
+                )
+                .await;
+        }
+        let mut target = match target {
+            Some(target) => Target::find(world, target).await,
+            None => None,
+        };
+        if target.is_none() && mob.age() % TARGET_INTERVAL == 0 {
+            target = Self::find_target(mob).await;
+        }
+        self.state.lock().target = target.as_ref().map(|target| target.entity().entity_id);
+        match target {
+            Some(target) => self.fight(mob, &target).await,
+            None => mob.wander(WANDER_RANGE, WALK_SPEED),
+        }
+        true
+    }
+
+    async fn on_hurt(&self, mob: &Mob, attacker: Option<EntityId>) {
+        if let Some(attacker) = attacker {
+            self.state.lock().target = Some(attacker);
+        }
+        let sound = if mob.living_entity.health.load() > 0.0 {
+            sound!("minecraft:entity.zombie_villager.hurt")
+        } else {
+            sound!("minecraft:entity.zombie_villager.death")
+        };
+        let entity = &mob.living_entity.entity;
+        entity
+            .world
+            .play_sound(sound, SoundCategory::Hostile, &entity.pos.load())
+            .await;
+    }
+
+    /// Weakened zombie villagers are cured with a golden apple
+    async fn interact(&self, mob: &Mob, player: &Player, hand: Hand) -> bool {
+        if self.state.lock().conversion.is_some()
+            || !mob.living_entity.has_effect(EffectType::Weakness)
+            || !player.use_held_item(hand, "minecraft:golden_apple").await
+        {
+            return false;
+        }
+        self.start_conversion(mob).await;
+        true
+    }
+
+    fn experience(&self) -> i32 {
+        if self.baby {
+            BABY_EXPERIENCE
+        } else {
+            EXPERIENCE
+        }
+    }
+}
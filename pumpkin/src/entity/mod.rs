@@ -1,7 +1,12 @@
-use std::sync::{atomic::AtomicBool, Arc};
+use std::sync::{
+    atomic::{AtomicBool, AtomicI32},
+    Arc,
+};
 
+use async_trait::async_trait;
 use crossbeam::atomic::AtomicCell;
 use num_derive::FromPrimitive;
+use pumpkin_config::KnockbackConfig;
 use pumpkin_core::math::{
     boundingbox::{BoundingBox, BoundingBoxSize},
     get_section_cord,
@@ -9,16 +14,101 @@ use pumpkin_core::math::{
     vector2::Vector2,
     vector3::Vector3,
 };
+use pumpkin_core::persistent_data::PersistentDataContainer;
+use pumpkin_core::text::TextComponent;
 use pumpkin_entity::{entity_type::EntityType, pose::EntityPose, EntityId};
 use pumpkin_protocol::{
-    client::play::{CSetEntityMetadata, Metadata},
-    VarInt,
+    client::play::{CSetEntityMetadata, EquipmentSlot, Metadata, MetadataValue},
+    packet_encoder::PreparedPacket,
 };
+use pumpkin_world::{block::BlockFace, chunk::entities::ChunkEntity, item::ItemStack};
 
 use crate::world::World;
+use damage::DamageSource;
+use data_tracker::DataTracker;
+use item::ItemEntity;
+use living::LivingEntity;
+use mob::Mob;
 
+pub mod arrow;
+pub mod damage;
+pub mod data_tracker;
+pub mod death;
+pub mod effect;
+pub mod environment;
+pub mod experience;
+pub mod experience_orb;
+pub mod item;
+pub mod keep_alive;
 pub mod living;
+pub mod mob;
 pub mod player;
+pub mod player_set;
+pub mod potion;
+pub mod social;
+pub mod sound;
+pub mod tab_list;
+pub mod title;
+pub mod totem;
+pub mod vehicle;
+
+static NEXT_ENTITY_ID: AtomicI32 = AtomicI32::new(2);
+
+/// Generates a new entity id, unique among the entities of every world
+pub fn new_entity_id() -> EntityId {
+    NEXT_ENTITY_ID.fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+}
+
+/// An entity which is not a player, its world ticks it and shows it to the players near it
+#[async_trait]
+pub trait EntityBase: Send + Sync {
+    fn get_entity(&self) -> &Entity;
+
+    /// Called once per tick, the entity is removed from its world once this returns false
+    async fn tick(&self) -> bool;
+
+    /// The packet spawning the entity for a player which starts seeing it at the position
+    fn spawn_packet(&self, position: Vector3<f64>) -> PreparedPacket;
+
+    /// The living part of the entity, `None` for entities like dropped items which can't be hurt
+    fn get_living_entity(&self) -> Option<&LivingEntity> {
+        None
+    }
+
+    /// The dropped item, for hoppers picking items up
+    fn get_item_entity(&self) -> Option<&ItemEntity> {
+        None
+    }
+
+    /// The mob, for entities which act by themselves like wardens
+    fn get_mob(&self) -> Option<&Mob> {
+        None
+    }
+
+    /// The items the entity wears, for players which start seeing it
+    fn equipment(&self) -> Vec<(EquipmentSlot, Option<ItemStack>)> {
+        Vec::new()
+    }
+
+    /// The entity as it is saved with its chunk, `None` for entities which aren't saved
+    fn chunk_entity(&self) -> Option<ChunkEntity> {
+        None
+    }
+
+    /// Hurts the entity, returns false if it can't be hurt
+    async fn damage(&self, amount: f32, source: DamageSource) -> bool {
+        let Some(living) = self.get_living_entity() else {
+            return false;
+        };
+        living.damage(amount, source).await;
+        true
+    }
+
+    /// Shears the entity, like a sheep losing its wool. Returns false if it can't be sheared
+    async fn shear(&self) -> bool {
+        false
+    }
+}
 
 /// Represents a not living Entity (e.g. Item, Egg, Snowball...)
 pub struct Entity {
@@ -58,6 +148,14 @@ pub struct Entity {
     pub bounding_box: AtomicCell<BoundingBox>,
     ///The size (width and height) of the bounding box
     pub bounding_box_size: AtomicCell<BoundingBoxSize>,
+    /// The metadata the client knows about, changes are sent every tick
+    pub data_tracker: parking_lot::Mutex<DataTracker>,
+    /// Custom data of plugins
+    pub persistent_data: parking_lot::Mutex<PersistentDataContainer>,
+    /// The entities riding this one, the first one steers it
+    pub passengers: parking_lot::Mutex<Vec<EntityId>>,
+    /// The entity this one rides
+    pub vehicle_id: AtomicCell<Option<EntityId>>,
 }
 
 impl Entity {
@@ -69,6 +167,15 @@ impl Entity {
         bounding_box: AtomicCell<BoundingBox>,
         bounding_box_size: AtomicCell<BoundingBoxSize>,
     ) -> Self {
+        let mut tracker = DataTracker::default();
+        tracker.define(&data_tracker::FLAGS, 0);
+        tracker.define(&data_tracker::AIR_SUPPLY, 300);
+        tracker.define(&data_tracker::CUSTOM_NAME, None);
+        tracker.define(&data_tracker::CUSTOM_NAME_VISIBLE, false);
+        tracker.define(&data_tracker::SILENT, false);
+        tracker.define(&data_tracker::NO_GRAVITY, false);
+        tracker.define(&data_tracker::POSE, EntityPose::Standing as i32);
+        tracker.define(&data_tracker::TICKS_FROZEN, 0);
         Self {
             entity_id,
             entity_type,
@@ -89,6 +196,10 @@ impl Entity {
             pose: AtomicCell::new(EntityPose::Standing),
             bounding_box,
             bounding_box_size,
+            data_tracker: parking_lot::Mutex::new(tracker),
+            persistent_data: parking_lot::Mutex::new(PersistentDataContainer::default()),
+            passengers: parking_lot::Mutex::new(Vec::new()),
+            vehicle_id: AtomicCell::new(None),
         }
     }
 
@@ -134,10 +245,92 @@ impl Entity {
         }
     }
 
+    /// Moves the entity by the velocity, stopping at the blocks it would run into like vanilla does,
+    /// one axis after the other. Returns the velocity which was left, with the blocked axes
+    /// zeroed, and updates whether the entity is on the ground
+    pub async fn move_colliding(&self, velocity: Vector3<f64>) -> Vector3<f64> {
+        let pos = self.pos.load();
+        let size = self.bounding_box_size.load();
+        let mut area = BoundingBox::new_from_pos(pos.x, pos.y, pos.z, &size);
+        let reach = BoundingBox {
+            min_x: area.min_x + velocity.x.min(0.0),
+            min_y: area.min_y + velocity.y.min(0.0),
+            min_z: area.min_z + velocity.z.min(0.0),
+            max_x: area.max_x + velocity.x.max(0.0),
+            max_y: area.max_y + velocity.y.max(0.0),
+            max_z: area.max_z + velocity.z.max(0.0),
+        };
+        let blocks = self.world.collision_boxes(&reach).await;
+
+        let dy = clip(&blocks, &area, velocity.y, Axis::Y);
+        area = area.offset(0.0, dy, 0.0);
+        let dx = clip(&blocks, &area, velocity.x, Axis::X);
+        area = area.offset(dx, 0.0, 0.0);
+        let dz = clip(&blocks, &area, velocity.z, Axis::Z);
+
+        #[expect(clippy::float_cmp)]
+        let blocked = (dx != velocity.x, dy != velocity.y, dz != velocity.z);
+        self.on_ground.store(
+            blocked.1 && velocity.y < 0.0,
+            std::sync::atomic::Ordering::Relaxed,
+        );
+        self.set_pos(pos.x + dx, pos.y + dy, pos.z + dz);
+        Vector3::new(
+            if blocked.0 { 0.0 } else { velocity.x },
+            if blocked.1 { 0.0 } else { velocity.y },
+            if blocked.2 { 0.0 } else { velocity.z },
+        )
+    }
+
+    /// The block the entity ran into, with the point on the face it hit. `moved` is what
+    /// `move_colliding` left of the velocity, `None` if nothing was in the way
+    #[expect(clippy::float_cmp)]
+    pub fn hit_block(
+        &self,
+        velocity: Vector3<f64>,
+        moved: Vector3<f64>,
+    ) -> Option<(WorldPosition, Vector3<f64>, BlockFace)> {
+        // just past the side of the bounding box which ran into the block
+        const MARGIN: f64 = 1.0E-3;
+        let pos = self.pos.load();
+        let size = self.bounding_box_size.load();
+        let reach = size.width / 2.0 + MARGIN;
+        let middle = pos.y + size.height / 2.0;
+        let (hit, face) = if moved.y != velocity.y {
+            if velocity.y < 0.0 {
+                (Vector3::new(pos.x, pos.y - MARGIN, pos.z), BlockFace::Up)
+            } else {
+                let top = pos.y + size.height + MARGIN;
+                (Vector3::new(pos.x, top, pos.z), BlockFace::Down)
+            }
+        } else if moved.x != velocity.x {
+            if velocity.x < 0.0 {
+                (Vector3::new(pos.x - reach, middle, pos.z), BlockFace::East)
+            } else {
+                (Vector3::new(pos.x + reach, middle, pos.z), BlockFace::West)
+            }
+        } else if moved.z != velocity.z {
+            if velocity.z < 0.0 {
+                (Vector3::new(pos.x, middle, pos.z - reach), BlockFace::South)
+            } else {
+                (Vector3::new(pos.x, middle, pos.z + reach), BlockFace::North)
+            }
+        } else {
+            return None;
+        };
+        let block = WorldPosition(Vector3::new(
+            hit.x.floor() as i32,
+            hit.y.floor() as i32,
+            hit.z.floor() as i32,
+        ));
+        Some((block, hit, face))
+    }
+
     /// Sets the Entity yaw & pitch Rotation
     pub fn set_rotation(&self, yaw: f32, pitch: f32) {
-        // TODO
+        // TODO: the head can turn without the body
         self.yaw.store(yaw);
+        self.head_yaw.store(yaw);
         self.pitch.store(pitch);
     }
 
@@ -149,7 +342,14 @@ impl Entity {
     /// Applies knockback to the entity, following vanilla Minecraft's mechanics.
     ///
     /// This function calculates the entity's new velocity based on the specified knockback strength and direction.
-    pub fn knockback(&self, strength: f64, x: f64, z: f64) {
+    pub fn knockback(
+        &self,
+        horizontal: f64,
+        vertical: f64,
+        x: f64,
+        z: f64,
+        profile: &KnockbackConfig,
+    ) {
         // This has some vanilla magic
         let mut x = x;
         let mut z = z;
@@ -158,70 +358,168 @@ impl Entity {
             z = (rand::random::<f64>() - rand::random::<f64>()) * 0.01;
         }
 
-        let var8 = Vector3::new(x, 0.0, z).normalize() * strength;
+        let var8 = Vector3::new(x, 0.0, z).normalize() * horizontal;
         let velocity = self.velocity.load();
         self.velocity.store(Vector3::new(
-            velocity.x / 2.0 - var8.x,
+            velocity.x * profile.friction - var8.x,
             if self.on_ground.load(std::sync::atomic::Ordering::Relaxed) {
-                (velocity.y / 2.0 + strength).min(0.4)
+                velocity
+                    .y
+                    .mul_add(profile.friction, vertical)
+                    .min(profile.vertical_limit)
             } else {
                 velocity.y
             },
-            velocity.z / 2.0 - var8.z,
+            velocity.z * profile.friction - var8.z,
         ));
     }
 
-    pub async fn set_sneaking(&self, sneaking: bool) {
+    pub fn set_sneaking(&self, sneaking: bool) {
         assert!(self.sneaking.load(std::sync::atomic::Ordering::Relaxed) != sneaking);
         self.sneaking
             .store(sneaking, std::sync::atomic::Ordering::Relaxed);
-        self.set_flag(Flag::Sneaking, sneaking).await;
+        self.set_flag(Flag::Sneaking, sneaking);
         // if sneaking {
-        //     self.set_pose(EntityPose::Crouching).await;
+        //     self.set_pose(EntityPose::Crouching);
         // } else {
-        //     self.set_pose(EntityPose::Standing).await;
+        //     self.set_pose(EntityPose::Standing);
         // }
     }
 
-    pub async fn set_sprinting(&self, sprinting: bool) {
+    pub fn set_sprinting(&self, sprinting: bool) {
         assert!(self.sprinting.load(std::sync::atomic::Ordering::Relaxed) != sprinting);
         self.sprinting
             .store(sprinting, std::sync::atomic::Ordering::Relaxed);
-        self.set_flag(Flag::Sprinting, sprinting).await;
+        self.set_flag(Flag::Sprinting, sprinting);
     }
 
     pub fn check_fall_flying(&self) -> bool {
         !self.on_ground.load(std::sync::atomic::Ordering::Relaxed)
     }
 
-    pub async fn set_fall_flying(&self, fall_flying: bool) {
+    pub fn set_fall_flying(&self, fall_flying: bool) {
         assert!(self.fall_flying.load(std::sync::atomic::Ordering::Relaxed) != fall_flying);
         self.fall_flying
             .store(fall_flying, std::sync::atomic::Ordering::Relaxed);
-        self.set_flag(Flag::FallFlying, fall_flying).await;
+        self.set_flag(Flag::FallFlying, fall_flying);
     }
 
-    async fn set_flag(&self, flag: Flag, value: bool) {
-        let index = flag as u8;
-        let mut b = 0i8;
+    /// Sets one of the flags, keeping the others
+    pub fn set_flag(&self, flag: Flag, value: bool) {
+        let mut tracker = self.data_tracker.lock();
+        let mut flags = match tracker.get(data_tracker::FLAGS.index) {
+            Some(MetadataValue::Byte(flags)) => *flags,
+            _ => 0,
+        };
+        let mask = 1 << flag as u8;
         if value {
-            b |= 1 << index;
+            flags |= mask;
         } else {
-            b &= !(1 << index);
+            flags &= !mask;
+        }
+        tracker.set(&data_tracker::FLAGS, flags);
+    }
+
+    #[must_use]
+    pub fn has_flag(&self, flag: Flag) -> bool {
+        match self.data_tracker.lock().get(data_tracker::FLAGS.index) {
+            Some(MetadataValue::Byte(flags)) => flags & (1 << flag as u8) != 0,
+            _ => false,
         }
-        let packet = CSetEntityMetadata::new(self.entity_id.into(), Metadata::new(0, 0.into(), b));
-        self.world.broadcast_packet_all(&packet).await;
     }
 
-    pub async fn set_pose(&self, pose: EntityPose) {
+    pub fn set_pose(&self, pose: EntityPose) {
         self.pose.store(pose);
-        let pose = pose as i32;
-        let packet = CSetEntityMetadata::<VarInt>::new(
-            self.entity_id.into(),
-            Metadata::new(6, 20.into(), (pose).into()),
-        );
-        self.world.broadcast_packet_all(&packet).await;
+        self.data_tracker
+            .lock()
+            .set(&data_tracker::POSE, pose as i32);
+    }
+
+    /// Shows the name above the entity, it is only shown when looking at the entity unless visible
+    pub fn set_custom_name(&self, name: Option<TextComponent<'static>>, visible: bool) {
+        let mut tracker = self.data_tracker.lock();
+        tracker.set(&data_tracker::CUSTOM_NAME, name);
+        tracker.set(&data_tracker::CUSTOM_NAME_VISIBLE, visible);
+    }
+
+    #[must_use]
+    pub fn custom_name(&self) -> Option<TextComponent<'static>> {
+        match self
+            .data_tracker
+            .lock()
+            .get(data_tracker::CUSTOM_NAME.index)
+        {
+            Some(MetadataValue::OptionalTextComponent(name)) => name.clone(),
+            _ => None,
+        }
+    }
+
+    /// Every entry of the metadata which a new viewer has to know about
+    pub fn metadata(&self) -> Vec<Metadata> {
+        self.data_tracker.lock().non_default()
+    }
+
+    /// Sends the changed metadata to everyone seeing the entity, called every tick.
+    pub async fn send_data_changes(&self) {
+        let changes = self.data_tracker.lock().take_dirty();
+        if changes.is_empty() {
+            return;
+        }
+        self.world
+            .broadcast_packet_viewers(
+                self.entity_id,
+                &CSetEntityMetadata::new(self.entity_id.into(), &changes),
+            )
+            .await;
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+/// How far the area can move along the axis until it touches one of the blocks
+fn clip(blocks: &[BoundingBox], area: &BoundingBox, mut offset: f64, axis: Axis) -> f64 {
+    const EPSILON: f64 = 1.0E-7;
+    // the bounds on the axis and whether the boxes overlap on the other two
+    let bounds = |block: &BoundingBox| match axis {
+        Axis::X => (
+            (block.min_x, block.max_x, area.min_x, area.max_x),
+            block.max_y > area.min_y + EPSILON
+                && block.min_y < area.max_y - EPSILON
+                && block.max_z > area.min_z + EPSILON
+                && block.min_z < area.max_z - EPSILON,
+        ),
+        Axis::Y => (
+            (block.min_y, block.max_y, area.min_y, area.max_y),
+            block.max_x > area.min_x + EPSILON
+                && block.min_x < area.max_x - EPSILON
+                && block.max_z > area.min_z + EPSILON
+                && block.min_z < area.max_z - EPSILON,
+        ),
+        Axis::Z => (
+            (block.min_z, block.max_z, area.min_z, area.max_z),
+            block.max_x > area.min_x + EPSILON
+                && block.min_x < area.max_x - EPSILON
+                && block.max_y > area.min_y + EPSILON
+                && block.min_y < area.max_y - EPSILON,
+        ),
+    };
+    for block in blocks {
+        let ((block_min, block_max, area_min, area_max), overlaps) = bounds(block);
+        if !overlaps {
+            continue;
+        }
+        if offset > 0.0 && block_min >= area_max - EPSILON {
+            offset = offset.min(block_min - area_max);
+        } else if offset < 0.0 && block_max <= area_min + EPSILON {
+            offset = offset.max(block_max - area_min);
+        }
     }
+    offset
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive)]
@@ -1,16 +1,17 @@
 use std::{
     collections::{HashMap, VecDeque},
     sync::{
-        atomic::{AtomicBool, AtomicI32, AtomicI64, AtomicU32, AtomicU8},
+        atomic::{AtomicBool, AtomicI32, AtomicU32, AtomicU8},
         Arc,
     },
-    time::{Duration, Instant},
+    time::Instant,
 };
 
 use crossbeam::atomic::AtomicCell;
 use itertools::Itertools;
 use num_derive::{FromPrimitive, ToPrimitive};
-use pumpkin_config::ADVANCED_CONFIG;
+use num_traits::FromPrimitive as _;
+use pumpkin_config::{runtime_config, BASIC_CONFIG};
 use pumpkin_core::{
     math::{
         boundingbox::{BoundingBox, BoundingBoxSize},
@@ -18,46 +19,80 @@ use pumpkin_core::{
         vector2::Vector2,
         vector3::Vector3,
     },
+    nbt::{Compound, IntArray, Value},
+    persistent_data::{self, PersistentDataType},
     text::TextComponent,
     GameMode,
 };
-use pumpkin_entity::{entity_type::EntityType, EntityId};
-use pumpkin_inventory::player::PlayerInventory;
+use pumpkin_entity::{entity_type::EntityType, pose::EntityPose, EntityId};
+use pumpkin_inventory::{player::PlayerInventory, Chest, Container};
 use pumpkin_macros::sound;
-use pumpkin_protocol::client::play::{CSetEntityMetadata, Metadata};
+use pumpkin_protocol::client::play::{CSetEntityMetadata, EquipmentSlot};
 use pumpkin_protocol::server::play::{SClickContainer, SKeepAlive};
 use pumpkin_protocol::{
     bytebuf::packet_id::Packet,
     client::play::{
-        CCombatDeath, CEntityStatus, CGameEvent, CHurtAnimation, CKeepAlive, CPlayDisconnect,
-        CPlayerAbilities, CPlayerInfoUpdate, CRespawn, CSetHealth, CSpawnEntity,
-        CSyncPlayerPosition, CSystemChatMessage, GameEvent, PlayerAction,
+        Animation, CChangeDifficulty, CCombatDeath, CEntityAnimation, CEntityStatus, CGameEvent,
+        CHurtAnimation, CPlayDisconnect, CPlayerAbilities, CRespawn, CSetCamera, CSetHealth,
+        CStartConfiguration, CSyncPlayerPosition, CSystemChatMessage, GameEvent,
     },
     server::play::{
-        SChatCommand, SChatMessage, SClientCommand, SClientInformationPlay, SClientTickEnd,
-        SCommandSuggestion, SConfirmTeleport, SInteract, SPlayerAbilities, SPlayerAction,
-        SPlayerCommand, SPlayerInput, SPlayerPosition, SPlayerPositionRotation, SPlayerRotation,
-        SSetCreativeSlot, SSetHeldItem, SSetPlayerGround, SSwingArm, SUseItem, SUseItemOn,
+        SChatAck, SChatCommand, SChatMessage, SChatSessionUpdate, SChunkBatchReceived,
+        SClientCommand, SClientInformationPlay, SClientTickEnd, SCloseContainer,
+        SCommandSuggestion, SConfigurationAcknowledged, SConfirmTeleport, SInteract, SMoveVehicle,
+        SPaddleBoat, SPlayPluginMessage, SPlayerAbilities, SPlayerAction, SPlayerCommand,
+        SPlayerInput, SPlayerPosition, SPlayerPositionRotation, SPlayerRotation, SRenameItem,
+        SSetBeacon, SSetCreativeSlot, SSetHeldItem, SSetPlayerGround, SSwingArm, SUseItem,
+        SUseItemOn,
     },
-    RawPacket, ServerPacket, SoundCategory, VarInt,
+    ConnectionState, RawPacket, ServerPacket, SoundCategory, VarInt,
+};
+use pumpkin_registry::{is_in, EntityContext, LootContext, TagCategory};
+use pumpkin_world::{
+    cylindrical_chunk_iterator::Cylindrical,
+    game_rules::{KEEP_INVENTORY, SHOW_DEATH_MESSAGES},
+    item::{
+        component::DataComponent,
+        item_registry::{get_item, get_item_name},
+        ItemStack,
+    },
+    player_data::PlayerData,
 };
-use pumpkin_world::{cylindrical_chunk_iterator::Cylindrical, item::ItemStack};
 use tokio::sync::{Mutex, Notify};
 use tokio::task::JoinHandle;
 
-use super::Entity;
+use super::{
+    damage::{self, DamageSource, PLAYER_ATTACK},
+    data_tracker,
+    death::{death_listeners, DeathLocation},
+    environment::Exposure,
+    experience::Experience,
+    experience_orb::ExperienceOrb,
+    keep_alive::KeepAlive,
+    mob::Mob,
+    totem::OFFHAND_SLOT,
+    Entity, EntityBase,
+};
 use crate::{
     client::{
         authentication::GameProfile,
+        chat_session::ChatState,
         combat::{self, player_attack_sound, AttackType},
+        movement_check::MovementCheck,
         Client, PlayerConfig,
     },
+    data::op_data::OPERATOR_CONFIG,
     server::Server,
-    world::{player_chunker, World},
+    world::{
+        block_entity::{new_container_id, sculk_shrieker::WardenSpawnTracker},
+        player_chunker, World,
+    },
 };
 use crate::{error::PumpkinError, world::player_chunker::get_view_distance};
 
 use super::living::LivingEntity;
+use super::tab_list::TabListEntry;
+use super::vehicle::{self, Riding, VehicleKind};
 
 pub struct ChunkHandleWrapper {
     handle: Option<JoinHandle<()>>,
@@ -95,6 +130,45 @@ impl ChunkHandleWrapper {
 pub type PlayerPendingChunks =
     Arc<parking_lot::Mutex<HashMap<Vector2<i32>, VecDeque<ChunkHandleWrapper>>>>;
 
+/// The inventory as it was saved in the player's file
+fn saved_inventory(saved: &PlayerData) -> PlayerInventory {
+    let mut inventory = PlayerInventory::new();
+    for (slot, item) in saved.inventory() {
+        if let Some(slot) = PlayerInventory::from_saved_slot(slot) {
+            let _ = inventory.set_slot(slot, Some(item), true);
+        }
+    }
+    if let Some(selected) = saved.selected_slot() {
+        if (0..9).contains(&selected) {
+            inventory.set_selected(selected as usize);
+        }
+    }
+    inventory
+}
+
+fn saved_ender_chest(saved: &PlayerData) -> Box<dyn Container> {
+    let mut chest = Chest::new();
+    let mut slots = chest.all_slots();
+    for (slot, item) in saved.ender_items() {
+        if let Some(slot) = usize::try_from(slot)
+            .ok()
+            .and_then(|slot| slots.get_mut(slot))
+        {
+            **slot = Some(item);
+        }
+    }
+    Box::new(chest)
+}
+
+/// Items with the curse of vanishing are destroyed instead of dropped on death
+fn has_curse_of_vanishing(item: &ItemStack) -> bool {
+    matches!(
+        item.components.get("minecraft:enchantments"),
+        Some(DataComponent::Enchantments(enchantments))
+            if enchantments.levels.contains_key("minecraft:vanishing_curse")
+    )
+}
+
 /// Represents a Minecraft player entity.
 ///
 /// A `Player` is a special type of entity that represents a human player connected to the server.
@@ -113,12 +187,14 @@ pub struct Player {
     pub food: AtomicI32,
     /// The player's food saturation level.
     pub food_saturation: AtomicCell<f32>,
+    /// The player's experience level and points, see [`Player::experience`]
+    pub experience: parking_lot::Mutex<Experience>,
     /// The player's inventory, containing items and equipment.
     pub inventory: Mutex<PlayerInventory>,
     /// The ID of the currently open container (if any).
     pub open_container: AtomicCell<Option<u64>>,
     /// The item currently being held by the player.
-    pub carried_item: AtomicCell<Option<ItemStack>>,
+    pub carried_item: parking_lot::Mutex<Option<ItemStack>>,
 
     /// send `send_abilties_update` when changed
     /// The player's abilities and special powers.
@@ -136,14 +212,22 @@ pub struct Player {
     pub awaiting_teleport: Mutex<Option<(VarInt, Vector3<f64>)>>,
     /// The coordinates of the chunk section the player is currently watching.
     pub watched_section: AtomicCell<Vector3<i32>>,
-    /// Did we send a keep alive Packet and wait for the response?
-    pub wait_for_keep_alive: AtomicBool,
-    /// Whats the keep alive packet payload we send, The client should responde with the same id
-    pub keep_alive_id: AtomicI64,
-    /// Last time we send a keep alive
-    pub last_keep_alive_time: AtomicCell<Instant>,
+    /// The view distance the chunks around the player were sent with
+    pub view_distance: AtomicU8,
+    /// Replaces the server's view distance for this player, set by `/distance` or plugins
+    pub view_distance_override: AtomicCell<Option<u8>>,
+    /// Replaces the server's simulation distance for this player
+    pub simulation_distance_override: AtomicCell<Option<u8>>,
+    /// The keep alive the client has to answer, and its latency
+    pub keep_alive: parking_lot::Mutex<KeepAlive>,
     /// Amount of ticks since last attack
     pub last_attacked_ticks: AtomicU32,
+    /// The hand raising a shield and for how many ticks it is raised
+    pub raised_shield: AtomicCell<Option<(Hand, u32)>>,
+    /// Ticks until a shield can be raised again, after an axe disabled it
+    pub shield_cooldown: AtomicU32,
+    /// How far the player walked on the ground since their last step
+    pub step_distance: AtomicCell<f64>,
 
     //TODO: Is there a way to consolidate these two?
     //Need to lookup by chunk, but also would be need to contain all the stuff
@@ -158,7 +242,36 @@ pub struct Player {
     cancel_tasks: Notify,
 
     /// the players op permission level
-    permission_lvl: PermissionLvl,
+    permission_lvl: AtomicCell<PermissionLvl>,
+    /// The vehicle the player is riding, if any
+    pub vehicle: Mutex<Option<Riding>>,
+    /// The movement keys the player is holding, see [`SPlayerInput`]
+    pub input: AtomicU8,
+    /// Whether the player was sent back into the configuration and is not in the world right now
+    pub reconfiguring: AtomicBool,
+    /// Where the player died last, sent to the client for recovery compasses
+    last_death: parking_lot::Mutex<Option<DeathLocation>>,
+    /// Whether the player died and did not respawn yet
+    dead: AtomicBool,
+    /// The session the player signs chat messages with, and which messages they saw
+    pub chat_state: parking_lot::Mutex<ChatState>,
+    /// How the player is shown in the player list
+    pub tab_list: parking_lot::Mutex<TabListEntry>,
+    /// What the anti cheat knows about the player's earlier movement
+    pub movement_check: parking_lot::Mutex<MovementCheck>,
+    /// What was read from the player's file, so what Pumpkin doesn't know is saved again
+    pub saved: parking_lot::Mutex<PlayerData>,
+    /// The entity a spectator sees through the eyes of, `None` while it sees through its own
+    pub camera: AtomicCell<Option<EntityId>>,
+    /// Who `/r` answers, the last player this player whispered with
+    pub reply_target: parking_lot::Mutex<Option<uuid::Uuid>>,
+    /// How close the sculk shriekers nearby are to summoning a warden
+    pub warden_spawn_tracker: parking_lot::Mutex<WardenSpawnTracker>,
+    /// The items in the player's ender chest and the id it is opened with
+    pub ender_chest: (u64, Arc<Mutex<Box<dyn Container>>>),
+    /// Ticks since the player last rested, phantoms go after players who stay up too long.
+    /// Players can't sleep yet, so only dying resets it
+    ticks_since_rest: AtomicU32,
 }
 
 impl Player {
@@ -181,44 +294,113 @@ impl Player {
             |profile| profile,
         );
         let config = client.config.lock().await.clone().unwrap_or_default();
+        let permission_lvl = OPERATOR_CONFIG.read().await.permission_lvl(gameprofile.id);
+        let saved = world.read_saved_player(gameprofile.id);
+        let last_death = saved
+            .last_death()
+            .map(|(dimension, position)| DeathLocation {
+                dimension,
+                position,
+            });
+        let gamemode = saved
+            .game_mode()
+            .and_then(GameMode::from_i32)
+            .filter(|gamemode| *gamemode != GameMode::Undefined && !BASIC_CONFIG.force_gamemode)
+            .unwrap_or(gamemode);
+        let (food, food_saturation) = saved.food().unwrap_or((20, 20.0));
+        let experience = saved
+            .experience()
+            .map(|(level, progress, total)| Experience {
+                level,
+                progress,
+                total,
+            })
+            .unwrap_or_default();
+        let inventory = saved_inventory(&saved);
+        let ender_chest = saved_ender_chest(&saved);
+        let warden_spawn_tracker = saved
+            .warden_spawn_tracker()
+            .map(WardenSpawnTracker::from_saved)
+            .unwrap_or_default();
+        let mut abilities = Abilities::default();
+        abilities.set_gamemode(gamemode);
         let bounding_box_size = BoundingBoxSize {
             width: 0.6,
             height: 1.8,
         };
 
+        let living_entity = LivingEntity::new(Entity::new(
+            entity_id,
+            world,
+            EntityType::Player,
+            1.62,
+            AtomicCell::new(BoundingBox::new_default(&bounding_box_size)),
+            AtomicCell::new(bounding_box_size),
+        ));
+        {
+            let mut tracker = living_entity.entity.data_tracker.lock();
+            tracker.define(&data_tracker::ADDITIONAL_HEARTS, 0.0);
+            tracker.define(&data_tracker::SCORE, 0);
+            tracker.define(&data_tracker::SKIN_PARTS, 0);
+            tracker.define(&data_tracker::MAIN_HAND, 1);
+            tracker.set(&data_tracker::SKIN_PARTS, config.skin_parts as i8);
+            tracker.set(&data_tracker::MAIN_HAND, config.main_hand.clone() as i8);
+        }
+        if let Some(health) = saved.health() {
+            living_entity.health.store(health);
+            living_entity
+                .entity
+                .data_tracker
+                .lock()
+                .set(&data_tracker::HEALTH, health);
+        }
+        living_entity.restore_effects(&saved.active_effects());
+        *living_entity.entity.persistent_data.lock() =
+            living_entity.entity.world.read_player_data(gameprofile.id);
+
         Self {
-            living_entity: LivingEntity::new(Entity::new(
-                entity_id,
-                world,
-                EntityType::Player,
-                1.62,
-                AtomicCell::new(BoundingBox::new_default(&bounding_box_size)),
-                AtomicCell::new(bounding_box_size),
-            )),
+            living_entity,
             config: Mutex::new(config),
             gameprofile,
             client,
             awaiting_teleport: Mutex::new(None),
-            // TODO: Load this from previous instance
-            food: AtomicI32::new(20),
-            food_saturation: AtomicCell::new(20.0),
+            food: AtomicI32::new(food),
+            food_saturation: AtomicCell::new(food_saturation),
+            experience: parking_lot::Mutex::new(experience),
             current_block_destroy_stage: AtomicU8::new(0),
-            inventory: Mutex::new(PlayerInventory::new()),
+            inventory: Mutex::new(inventory),
             open_container: AtomicCell::new(None),
-            carried_item: AtomicCell::new(None),
+            carried_item: parking_lot::Mutex::new(None),
             teleport_id_count: AtomicI32::new(0),
-            abilities: Mutex::new(Abilities::default()),
+            abilities: Mutex::new(abilities),
             gamemode: AtomicCell::new(gamemode),
             watched_section: AtomicCell::new(Vector3::new(0, 0, 0)),
-            wait_for_keep_alive: AtomicBool::new(false),
-            keep_alive_id: AtomicI64::new(0),
-            last_keep_alive_time: AtomicCell::new(std::time::Instant::now()),
+            view_distance: AtomicU8::new(0),
+            view_distance_override: AtomicCell::new(None),
+            simulation_distance_override: AtomicCell::new(None),
+            keep_alive: parking_lot::Mutex::new(KeepAlive::new()),
             last_attacked_ticks: AtomicU32::new(0),
+            raised_shield: AtomicCell::new(None),
+            shield_cooldown: AtomicU32::new(0),
+            step_distance: AtomicCell::new(0.0),
             pending_chunks: Arc::new(parking_lot::Mutex::new(HashMap::new())),
             pending_chunk_batch: parking_lot::Mutex::new(HashMap::new()),
             cancel_tasks: Notify::new(),
-            // TODO: change this
-            permission_lvl: PermissionLvl::Four,
+            permission_lvl: AtomicCell::new(permission_lvl),
+            vehicle: Mutex::new(None),
+            input: AtomicU8::new(0),
+            reconfiguring: AtomicBool::new(false),
+            last_death: parking_lot::Mutex::new(last_death),
+            dead: AtomicBool::new(false),
+            chat_state: parking_lot::Mutex::new(ChatState::default()),
+            tab_list: parking_lot::Mutex::new(TabListEntry::default()),
+            movement_check: parking_lot::Mutex::new(MovementCheck::new()),
+            saved: parking_lot::Mutex::new(saved),
+            camera: AtomicCell::new(None),
+            reply_target: parking_lot::Mutex::new(None),
+            warden_spawn_tracker: parking_lot::Mutex::new(warden_spawn_tracker),
+            ender_chest: (new_container_id(), Arc::new(Mutex::new(ender_chest))),
+            ticks_since_rest: AtomicU32::new(0),
         }
     }
 
@@ -234,7 +416,7 @@ impl Player {
         world.remove_player(self).await;
 
         let watched = self.watched_section.load();
-        let view_distance = get_view_distance(self).await;
+        let view_distance = get_view_distance(self);
         let cylindrical = Cylindrical::new(Vector2::new(watched.x, watched.z), view_distance);
 
         // NOTE: This all must be synchronous to make sense! The chunks are handled asynhrously.
@@ -334,10 +516,10 @@ impl Player {
         let chunks_to_clean = world.mark_chunks_as_not_watched(&watched_chunks);
 
         // Remove chunks with no watchers from the cache
-        world.clean_chunks(&chunks_to_clean);
+        world.clean_chunks(&chunks_to_clean).await;
 
         // Remove left over entries from all possiblily loaded chunks
-        world.clean_memory(&radial_chunks);
+        world.clean_memory(&radial_chunks).await;
 
         log::debug!(
             "Removed player id {} ({}) ({} chunks remain cached)",
@@ -353,16 +535,29 @@ impl Player {
         let world = &self.living_entity.entity.world;
         let victim_entity = &victim.living_entity.entity;
         let attacker_entity = &self.living_entity.entity;
-        let config = &ADVANCED_CONFIG.pvp;
+        let runtime = runtime_config();
+        let config = &runtime.pvp;
 
         let pos = victim_entity.pos.load();
 
-        let attack_cooldown_progress = self.get_attack_cooldown_progress(0.5);
+        let legacy = config.legacy_combat;
+        let attack_cooldown_progress = if legacy {
+            1.0
+        } else {
+            let attack_speed = combat::held_attribute_bonus(self, "minecraft:attack_speed").await;
+            self.get_attack_cooldown_progress(0.5, 4.0 + attack_speed as f32)
+        };
         self.last_attacked_ticks
             .store(0, std::sync::atomic::Ordering::Relaxed);
 
-        // TODO: attack damage attribute and deal damage
-        let mut damage = 1.0;
+        let attack_type = AttackType::new(self, attack_cooldown_progress, legacy).await;
+        let damage =
+            combat::attack_damage(self, attack_cooldown_progress, attack_type, legacy).await;
+
+        if combat::blocks_with_shield(victim, attacker_entity.pos.load()) {
+            victim.block_with_shield(self).await;
+            return;
+        }
         if (config.protect_creative && victim.gamemode.load() == GameMode::Creative)
             || !victim.living_entity.check_damage(damage)
         {
@@ -384,28 +579,39 @@ impl Player {
             )
             .await;
 
-        let attack_type = AttackType::new(self, attack_cooldown_progress).await;
-
         player_attack_sound(&pos, world, attack_type).await;
 
         if matches!(attack_type, AttackType::Critical) {
-            damage *= 1.5;
+            world
+                .broadcast_packet_all(&CEntityAnimation::new(
+                    victim_entity.entity_id.into(),
+                    Animation::CriticalEffect as u8,
+                ))
+                .await;
         }
 
-        victim.living_entity.damage(damage).await;
+        victim
+            .living_entity
+            .damage(damage, DamageSource::by(PLAYER_ATTACK, self.entity_id()))
+            .await;
 
-        let mut knockback_strength = 1.0;
-        match attack_type {
-            AttackType::Knockback => knockback_strength += 1.0,
-            AttackType::Sweeping => {
-                combat::spawn_sweep_particle(attacker_entity, world, &pos).await;
-            }
-            _ => {}
-        };
+        if matches!(attack_type, AttackType::Sweeping) {
+            combat::sweep(self, victim, damage).await;
+            combat::spawn_sweep_particle(attacker_entity, world, &pos).await;
+        }
 
         if config.knockback {
-            combat::handle_knockback(attacker_entity, victim, victim_entity, knockback_strength)
-                .await;
+            let knockback = combat::held_enchantment(self, "minecraft:knockback").await;
+            let bonus_levels = u32::from(matches!(attack_type, AttackType::Knockback))
+                + u32::try_from(knockback).unwrap_or(0);
+            combat::handle_knockback(
+                attacker_entity,
+                victim,
+                victim_entity,
+                bonus_levels,
+                &config.knockback_profile,
+            )
+            .await;
         }
 
         if config.hurt_animation {
@@ -418,11 +624,156 @@ impl Player {
         if config.swing {}
     }
 
+    /// Attacks a mob or another living entity which isn't a player
+    pub async fn attack_entity(&self, victim: &Arc<dyn EntityBase>) {
+        let Some(victim_living) = victim.get_living_entity() else {
+            return;
+        };
+        let world = &self.living_entity.entity.world;
+        let victim_entity = &victim_living.entity;
+        let attacker_entity = &self.living_entity.entity;
+        let runtime = runtime_config();
+        let config = &runtime.pvp;
+        let pos = victim_entity.pos.load();
+
+        let legacy = config.legacy_combat;
+        let attack_cooldown_progress = if legacy {
+            1.0
+        } else {
+            let attack_speed = combat::held_attribute_bonus(self, "minecraft:attack_speed").await;
+            self.get_attack_cooldown_progress(0.5, 4.0 + attack_speed as f32)
+        };
+        self.last_attacked_ticks
+            .store(0, std::sync::atomic::Ordering::Relaxed);
+
+        let attack_type = AttackType::new(self, attack_cooldown_progress, legacy).await;
+        let damage =
+            combat::attack_damage(self, attack_cooldown_progress, attack_type, legacy).await;
+        if !victim_living.check_damage(damage)
+            || !victim
+                .damage(damage, DamageSource::by(PLAYER_ATTACK, self.entity_id()))
+                .await
+        {
+            world
+                .play_sound(
+                    sound!("minecraft:entity.player.attack.nodamage"),
+                    SoundCategory::Players,
+                    &pos,
+                )
+                .await;
+            return;
+        }
+        player_attack_sound(&pos, world, attack_type).await;
+
+        if matches!(attack_type, AttackType::Critical) {
+            world
+                .broadcast_packet_all(&CEntityAnimation::new(
+                    victim_entity.entity_id.into(),
+                    Animation::CriticalEffect as u8,
+                ))
+                .await;
+        }
+
+        if config.knockback {
+            let knockback = combat::held_enchantment(self, "minecraft:knockback").await;
+            let bonus_levels = u32::from(matches!(attack_type, AttackType::Knockback))
+                + u32::try_from(knockback).unwrap_or(0);
+            let profile = &config.knockback_profile;
+            let bonus = profile.sprint_bonus * f64::from(bonus_levels);
+            let yaw = attacker_entity.yaw.load().to_radians();
+            let resistance = 1.0 - victim.get_mob().map_or(0.0, Mob::knockback_resistance);
+            if resistance > 0.0 {
+                victim_entity.knockback(
+                    (profile.horizontal + bonus) * resistance,
+                    (profile.vertical + bonus) * resistance,
+                    f64::from(yaw.sin()),
+                    f64::from(-yaw.cos()),
+                    profile,
+                );
+            }
+        }
+
+        if config.hurt_animation {
+            let entity_id = VarInt(victim_entity.entity_id);
+            world
+                .broadcast_packet_all(&CHurtAnimation::new(&entity_id, attacker_entity.yaw.load()))
+                .await;
+        }
+    }
+
+    /// The items in the hands and the armor, which the other players see
+    pub async fn worn_equipment(&self) -> Vec<(EquipmentSlot, Option<ItemStack>)> {
+        let inventory = self.inventory.lock().await;
+        let [head, chest, legs, feet] = inventory.armor().clone();
+        vec![
+            (EquipmentSlot::MainHand, inventory.held_item().cloned()),
+            (EquipmentSlot::OffHand, inventory.offhand_item().cloned()),
+            (EquipmentSlot::Head, head),
+            (EquipmentSlot::Chest, chest),
+            (EquipmentSlot::Legs, legs),
+            (EquipmentSlot::Feet, feet),
+        ]
+    }
+
+    /// The name of the item in the hand, `None` if it is empty
+    pub async fn held_item_name(&self, hand: Hand) -> Option<&'static str> {
+        let inventory = self.inventory.lock().await;
+        let stack = match hand {
+            Hand::Main => inventory.held_item(),
+            Hand::Off => inventory.offhand_item(),
+        }?;
+        get_item_name(stack.item_id)
+    }
+
+    /// Takes one of the item from the hand if it holds it, creative players keep it. Returns
+    /// false if the hand holds something else
+    pub async fn use_held_item(&self, hand: Hand, item: &str) -> bool {
+        let mut inventory = self.inventory.lock().await;
+        let slot = match hand {
+            Hand::Main => inventory.held_item_mut(),
+            Hand::Off => match inventory.get_slot(OFFHAND_SLOT) {
+                Ok(slot) => slot,
+                Err(_) => return false,
+            },
+        };
+        let Some(stack) = slot
+            .as_mut()
+            .filter(|stack| get_item_name(stack.item_id) == Some(item))
+        else {
+            return false;
+        };
+        if self.gamemode.load() != GameMode::Creative {
+            stack.item_count -= 1;
+            if stack.item_count == 0 {
+                *slot = None;
+            }
+        }
+        true
+    }
+
+    /// Wears the item in the hand down, it turns into `broken` once it breaks
+    pub async fn wear_held_item(&self, hand: Hand, amount: i32, broken: &str) {
+        if self.gamemode.load() == GameMode::Creative {
+            return;
+        }
+        let mut inventory = self.inventory.lock().await;
+        let slot = match hand {
+            Hand::Main => inventory.held_item_mut(),
+            Hand::Off => match inventory.get_slot(OFFHAND_SLOT) {
+                Ok(slot) => slot,
+                Err(_) => return,
+            },
+        };
+        if slot.as_mut().is_some_and(|stack| stack.wear(amount)) {
+            *slot = get_item(broken).map(|item| ItemStack::new(1, item.id));
+        }
+    }
+
     pub async fn await_cancel(&self) {
         self.cancel_tasks.notified().await;
     }
 
-    pub async fn tick(&self) {
+    pub async fn tick(self: &Arc<Self>) {
         if self
             .client
             .closed
@@ -435,40 +786,108 @@ impl Player {
             .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
 
         self.living_entity.tick();
+        self.tick_shield();
+        self.warden_spawn_tracker.lock().tick();
+        if self.living_entity.health.load() > 0.0 {
+            self.ticks_since_rest
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            let gamemode = self.gamemode.load();
+            self.living_entity
+                .tick_environment(
+                    matches!(gamemode, GameMode::Creative | GameMode::Spectator),
+                    false,
+                    self.wears_freeze_immune_armor().await,
+                )
+                .await;
+            self.living_entity.tick_effects().await;
+        }
+
+        if self.living_entity.health.load() <= 0.0
+            && !self.dead.swap(true, std::sync::atomic::Ordering::Relaxed)
+        {
+            self.on_death().await;
+        }
+
+        self.living_entity.entity.send_data_changes().await;
+
+        self.tick_camera().await;
+        self.tick_keep_alive(now).await;
+    }
 
-        if now.duration_since(self.last_keep_alive_time.load()) >= Duration::from_secs(15) {
-            // We never got a response from our last keep alive we send
-            if self
-                .wait_for_keep_alive
-                .load(std::sync::atomic::Ordering::Relaxed)
+    /// Lets a spectator see through the eyes of the player, or through its own again with `None`
+    pub async fn set_camera(&self, target: Option<&Self>) {
+        let camera = target.map(Self::entity_id);
+        if self.camera.swap(camera) == camera {
+            return;
+        }
+        if let Some(target) = target {
+            let entity = &target.living_entity.entity;
+            self.teleport(entity.pos.load(), entity.yaw.load(), entity.pitch.load())
+                .await;
+        }
+        self.client
+            .send_packet(&CSetCamera::new(
+                camera.unwrap_or_else(|| self.entity_id()).into(),
+            ))
+            .await;
+    }
+
+    /// Like in vanilla a spectator moves with its camera, so the chunks around it are loaded,
+    /// and leaves it when sneaking
+    async fn tick_camera(self: &Arc<Self>) {
+        let Some(camera) = self.camera.load() else {
+            return;
+        };
+        let entity = &self.living_entity.entity;
+        let target = entity.world.get_player_by_entityid(camera).await;
+        match target {
+            Some(target)
+                if self.gamemode.load() == GameMode::Spectator
+                    && !entity.sneaking.load(std::sync::atomic::Ordering::Relaxed) =>
             {
-                self.kick(TextComponent::text("Timeout")).await;
-                return;
+                let position = target.living_entity.entity.pos.load();
+                self.living_entity
+                    .set_pos(position.x, position.y, position.z);
+                self.movement_check.lock().reset(position);
+                player_chunker::update_position(self).await;
             }
-            self.wait_for_keep_alive
-                .store(true, std::sync::atomic::Ordering::Relaxed);
-            self.last_keep_alive_time.store(now);
-            let id = now.elapsed().as_millis() as i64;
-            self.keep_alive_id
-                .store(id, std::sync::atomic::Ordering::Relaxed);
-            self.client.send_packet(&CKeepAlive::new(id)).await;
+            _ => self.set_camera(None).await,
         }
     }
 
-    pub fn get_attack_cooldown_progress(&self, base_time: f32) -> f32 {
+    /// How far the weapon recharged since the last attack, from 0 to 1. The attack speed is
+    /// how many attacks per second the held item allows
+    pub fn get_attack_cooldown_progress(&self, base_time: f32, attack_speed: f32) -> f32 {
         #[allow(clippy::cast_precision_loss)]
         let x = self
             .last_attacked_ticks
             .load(std::sync::atomic::Ordering::Acquire) as f32
             + base_time;
-        // TODO attack speed attribute
-        let attack_speed = 4.0;
         let progress_per_tick = 1.0 / attack_speed * 20.0;
 
         let progress = x / progress_per_tick;
         progress.clamp(0.0, 1.0)
     }
 
+    /// Leather armor keeps the player from freezing in powder snow
+    pub async fn wears_freeze_immune_armor(&self) -> bool {
+        self.inventory
+            .lock()
+            .await
+            .armor()
+            .iter()
+            .flatten()
+            .filter_map(|stack| get_item_name(stack.item_id))
+            .any(|name| is_in(TagCategory::Item, "minecraft:freeze_immune_wearables", name))
+    }
+
+    /// The insomnia of the player, in ticks since it last rested
+    #[must_use]
+    pub fn ticks_since_rest(&self) -> u32 {
+        self.ticks_since_rest
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }
+
     pub const fn entity_id(&self) -> EntityId {
         self.living_entity.entity.entity_id
     }
@@ -504,30 +923,224 @@ impl Player {
         self.client
             .send_packet(&CEntityStatus::new(
                 self.entity_id(),
-                24 + self.permission_lvl as i8,
+                24 + self.permission_lvl.load() as i8,
             ))
             .await;
     }
 
     /// sets the players permission level and syncs it with the client
-    pub async fn set_permission_lvl(&mut self, lvl: PermissionLvl) {
-        self.permission_lvl = lvl;
+    pub async fn set_permission_lvl(&self, lvl: PermissionLvl) {
+        self.permission_lvl.store(lvl);
         self.send_permission_lvl_update().await;
     }
 
     /// get the players permission level
     pub fn permission_lvl(&self) -> PermissionLvl {
-        self.permission_lvl
+        self.permission_lvl.load()
     }
 
-    pub async fn respawn(self: &Arc<Self>, alive: bool) {
-        let last_pos = self.living_entity.last_pos.load();
-        let death_location = WorldPosition(Vector3::new(
-            last_pos.x.round() as i32,
-            last_pos.y.round() as i32,
-            last_pos.z.round() as i32,
-        ));
+    /// Tells everyone how the player died and shows the death screen, drops the inventory and
+    /// experience unless `keepInventory` is on. Remembers where the player died, so the client can
+    /// point recovery compasses to it.
+    async fn on_death(&self) {
+        let world = &self.living_entity.entity.world;
+        let location = DeathLocation::new(
+            "minecraft:overworld".to_string(),
+            self.living_entity.entity.pos.load(),
+        );
+        for listener in death_listeners() {
+            listener.on_death(self, &location);
+        }
+        self.set_last_death_location(Some(location));
+        self.ticks_since_rest
+            .store(0, std::sync::atomic::Ordering::Relaxed);
+
+        let (show_death_messages, keep_inventory) = {
+            let game_rules = world.game_rules.read().await;
+            (
+                game_rules.get(&SHOW_DEATH_MESSAGES),
+                game_rules.get(&KEEP_INVENTORY),
+            )
+        };
+        let message = if show_death_messages {
+            let killer = match self.living_entity.killer() {
+                Some(id) => match world.get_player_by_entityid(id).await {
+                    Some(killer) => Some(TextComponent::text_string(killer.display_name())),
+                    // mobs are called by the name of their kind
+                    None => world.entities.lock().await.get(&id).map(|killer| {
+                        let name = killer.get_entity().entity_type.name();
+                        TextComponent::translate(
+                            format!("entity.{}", name.replace(':', ".")),
+                            vec![],
+                        )
+                    }),
+                },
+                None => None,
+            };
+            let source = self
+                .living_entity
+                .last_damage_source
+                .load()
+                .unwrap_or(DamageSource::new(damage::GENERIC));
+            let message =
+                source.death_message(TextComponent::text_string(self.display_name()), killer);
+            log::info!("{}", message.clone().to_pretty_console());
+            world
+                .broadcast_packet_all(&CSystemChatMessage::new(&message, false))
+                .await;
+            message
+        } else {
+            TextComponent::text("")
+        };
+        self.client
+            .send_packet(&CCombatDeath::new(self.entity_id().into(), message))
+            .await;
+        self.living_entity.last_damage_source.store(None);
+        self.living_entity.kill_credit.store(None);
+        self.living_entity.clear_effects().await;
+
+        if keep_inventory || self.gamemode.load() == GameMode::Spectator {
+            return;
+        }
+        let items: Vec<_> = self
+            .inventory
+            .lock()
+            .await
+            .slots_mut()
+            .into_iter()
+            .filter_map(Option::take)
+            .collect();
+        for item in items {
+            if !has_curse_of_vanishing(&item) {
+                self.drop_item(item, true).await;
+            }
+        }
+        self.set_container_content(None).await;
+
+        let experience = self.experience().dropped_on_death();
+        let entity = &self.living_entity.entity;
+        if !world
+            .absorb_experience(entity.block_pos.load(), experience)
+            .await
+        {
+            ExperienceOrb::spawn(world, entity.pos.load(), experience).await;
+        }
+        self.set_experience(Experience::default()).await;
+    }
+
+    /// What is saved about the player in their file, like `/data get entity` shows it
+    pub async fn saved_data(&self) -> PlayerData {
+        let entity = &self.living_entity.entity;
+        let mut data = self.saved.lock().clone();
+        data.set_data_version();
+        data.set_position(entity.pos.load());
+        data.set_rotation(entity.yaw.load(), entity.pitch.load());
+        data.set_dimension("minecraft:overworld");
+        data.set_game_mode(self.gamemode.load() as i32);
+        data.set_health(self.living_entity.health.load());
+        data.set_food(
+            self.food.load(std::sync::atomic::Ordering::Relaxed),
+            self.food_saturation.load(),
+        );
+        let experience = self.experience();
+        data.set_experience(experience.level, experience.progress, experience.total);
+        {
+            let inventory = self.inventory.lock().await;
+            data.set_selected_slot(inventory.selected() as i32);
+            data.set_inventory(
+                inventory
+                    .slots()
+                    .into_iter()
+                    .enumerate()
+                    .filter_map(|(slot, item)| Some((PlayerInventory::saved_slot(slot)?, item?))),
+            );
+        }
+        data.set_ender_items(
+            self.ender_chest
+                .1
+                .lock()
+                .await
+                .all_slots_ref()
+                .into_iter()
+                .enumerate()
+                .filter_map(|(slot, item)| Some((i8::try_from(slot).ok()?, item?))),
+        );
+        data.set_active_effects(&self.living_entity.saved_effects());
+        let last_death = self.last_death_location();
+        data.set_last_death(
+            last_death
+                .as_ref()
+                .map(|death| (death.dimension.as_str(), death.position)),
+        );
+        let (warning_level, ticks_since_last_warning, cooldown_ticks) =
+            self.warden_spawn_tracker.lock().saved();
+        data.set_warden_spawn_tracker(warning_level, ticks_since_last_warning, cooldown_ticks);
+        data
+    }
+
+    /// The player's NBT like `/data get entity` shows it
+    pub async fn nbt(&self) -> Compound {
+        let mut nbt = self.saved_data().await.nbt().clone();
+        let uuid = self.gameprofile.id.as_u128();
+        let uuid = (0..4)
+            .rev()
+            .map(|i| (uuid >> (i * 32)) as u32 as i32)
+            .collect();
+        nbt.insert("UUID".to_string(), Value::IntArray(IntArray::new(uuid)));
+        let data = self.living_entity.entity.persistent_data.lock().clone();
+        if !data.is_empty() {
+            nbt.insert(persistent_data::NBT_KEY.to_string(), data.to_nbt());
+        }
+        nbt
+    }
+
+    #[must_use]
+    pub fn last_death_location(&self) -> Option<DeathLocation> {
+        self.last_death.lock().clone()
+    }
+
+    /// What predicates and item modifiers about the player are checked against
+    #[must_use]
+    pub fn loot_context(&self) -> LootContext {
+        let entity = &self.living_entity.entity;
+        LootContext {
+            origin: Some(entity.pos.load()),
+            this_entity: Some(EntityContext {
+                entity_type: "minecraft:player".to_string(),
+                on_fire: false,
+                sneaking: entity.sneaking.load(std::sync::atomic::Ordering::Relaxed),
+                sprinting: entity.sprinting.load(std::sync::atomic::Ordering::Relaxed),
+                swimming: matches!(entity.pose.load(), EntityPose::Swimming),
+                baby: false,
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// Overrides where the player died last, the client learns about it on the next respawn or join.
+    pub fn set_last_death_location(&self, location: Option<DeathLocation>) {
+        *self.last_death.lock() = location;
+    }
+
+    /// Shows the player the effects they have, after they join or respawn
+    pub async fn send_effects(&self) {
+        let effects: Vec<_> = self
+            .living_entity
+            .effects
+            .lock()
+            .values()
+            .copied()
+            .collect();
+        for effect in effects {
+            self.client
+                .send_packet(&effect.packet(self.entity_id()))
+                .await;
+        }
+    }
 
+    pub async fn respawn(self: &Arc<Self>, alive: bool) {
+        self.dead.store(false, std::sync::atomic::Ordering::Relaxed);
+        let last_death = self.last_death_location();
         let data_kept = u8::from(alive);
 
         self.client
@@ -539,7 +1152,9 @@ impl Player {
                 self.gamemode.load() as i8,
                 false,
                 false,
-                Some(("minecraft:overworld", death_location)),
+                last_death
+                    .as_ref()
+                    .map(|death| (death.dimension.as_str(), death.world_position())),
                 0.into(),
                 0.into(),
                 data_kept,
@@ -566,15 +1181,25 @@ impl Player {
 
         self.living_entity.last_pos.store(position);
 
-        // TODO: difficulty, exp bar, status effect
-
         let world = &self.living_entity.entity.world;
+        self.client
+            .send_packet(&CChangeDifficulty::new(
+                world.config.difficulty as u8,
+                false,
+            ))
+            .await;
+
+        self.set_container_content(None).await;
+        self.send_experience().await;
+        self.send_effects().await;
+
         world
             .worldborder
             .lock()
             .await
             .init_client(&self.client)
             .await;
+        world.send_time(self).await;
 
         // TODO: world spawn (compass stuff)
 
@@ -582,39 +1207,20 @@ impl Player {
             .send_packet(&CGameEvent::new(GameEvent::StartWaitingChunks, 0.0))
             .await;
 
+        // the client dropped every entity, they are spawned again by the entity tracker,
+        // like this player for everyone else, at the new position
+        world.entity_tracker.forget_viewer(self.gameprofile.id);
         let entity = &self.living_entity.entity;
-        let entity_id = entity.entity_id;
-
-        let skin_parts = self.config.lock().await.skin_parts;
-        let entity_metadata_packet =
-            CSetEntityMetadata::new(entity_id.into(), Metadata::new(17, VarInt(0), &skin_parts));
-
-        world
-            .broadcast_packet_except(
-                &[self.gameprofile.id],
-                // TODO: add velo
-                &CSpawnEntity::new(
-                    entity.entity_id.into(),
-                    self.gameprofile.id,
-                    (EntityType::Player as i32).into(),
-                    position.x,
-                    position.y,
-                    position.z,
-                    pitch,
-                    yaw,
-                    yaw,
-                    0.into(),
-                    0.0,
-                    0.0,
-                    0.0,
-                ),
-            )
-            .await;
+        world.remove_entity(entity).await;
 
         player_chunker::player_join(world, self.clone()).await;
-        world.broadcast_packet_all(&entity_metadata_packet).await;
+        let metadata = entity.metadata();
+        self.client
+            .send_packet(&CSetEntityMetadata::new(entity.entity_id.into(), &metadata))
+            .await;
         // update commands
 
+        *self.living_entity.exposure.lock() = Exposure::default();
         self.set_health(20.0, 20, 20.0).await;
     }
 
@@ -634,6 +1240,7 @@ impl Player {
             .set_pos(position.x, position.y, position.z);
         let entity = &self.living_entity.entity;
         entity.set_rotation(yaw, pitch);
+        self.movement_check.lock().reset(position);
         *self.awaiting_teleport.lock().await = Some((teleport_id.into(), position));
         self.client
             .send_packet(&CSyncPlayerPosition::new(
@@ -647,6 +1254,18 @@ impl Player {
             .await;
     }
 
+    /// Puts the player on the vehicle, returns false if players can't ride the entity or it is
+    /// taken.
+    pub async fn start_riding(&self, vehicle: &Entity) -> bool {
+        VehicleKind::from_entity_type(&vehicle.entity_type).is_some()
+            && vehicle::mount(&self.living_entity.entity, vehicle).await
+    }
+
+    /// Takes the player off their vehicle and puts them on a safe spot next to it.
+    pub async fn dismount(&self) {
+        vehicle::dismount(&self.living_entity.entity).await;
+    }
+
     pub fn block_interaction_range(&self) -> f64 {
         if self.gamemode.load() == GameMode::Creative {
             5.0
@@ -655,6 +1274,14 @@ impl Player {
         }
     }
 
+    pub fn entity_interaction_range(&self) -> f64 {
+        if self.gamemode.load() == GameMode::Creative {
+            5.0
+        } else {
+            3.0
+        }
+    }
+
     pub fn can_interact_with_block_at(&self, pos: &WorldPosition, additional_range: f64) -> bool {
         let d = self.block_interaction_range() + additional_range;
         let box_pos = BoundingBox::from_block(pos);
@@ -667,6 +1294,19 @@ impl Player {
         }) < d * d
     }
 
+    /// Sends the player back into the configuration, e.g. to sync changed registries.
+    ///
+    /// The player leaves the world once the client acknowledged it and joins again after the configuration.
+    pub async fn start_reconfiguration(&self) {
+        if self
+            .reconfiguring
+            .swap(true, std::sync::atomic::Ordering::Relaxed)
+        {
+            return;
+        }
+        self.client.send_packet(&CStartConfiguration::new()).await;
+    }
+
     /// Kicks the Client with a reason depending on the connection state
     pub async fn kick<'a>(&self, reason: TextComponent<'a>) {
         if self
@@ -703,15 +1343,9 @@ impl Player {
             .await;
     }
 
+    /// Kills the player, the death screen is shown on the next tick
     pub async fn kill(&self) {
         self.living_entity.kill().await;
-
-        self.client
-            .send_packet(&CCombatDeath::new(
-                self.entity_id().into(),
-                TextComponent::text("noob"),
-            ))
-            .await;
     }
 
     pub async fn set_gamemode(&self, gamemode: GameMode) {
@@ -722,20 +1356,13 @@ impl Player {
             "Setting the same gamemode as already is"
         );
         self.gamemode.store(gamemode);
-        self.abilities.lock().await.flying = false;
+        self.abilities.lock().await.set_gamemode(gamemode);
+        if gamemode != GameMode::Spectator {
+            self.set_camera(None).await;
+        }
         // So a little story time. I actually made an abilties_from_gamemode function. I looked at vanilla and they always send the abilties from the gamemode. But the funny thing actually is. That the client
         // does actually use the same method and set the abilties when receiving the CGameEvent gamemode packet. Just Mojang nonsense
-        self.living_entity
-            .entity
-            .world
-            .broadcast_packet_all(&CPlayerInfoUpdate::new(
-                0x04,
-                &[pumpkin_protocol::client::play::Player {
-                    uuid: self.gameprofile.id,
-                    actions: vec![PlayerAction::UpdateGameMode((gamemode as i32).into())],
-                }],
-            ))
-            .await;
+        self.broadcast_gamemode().await;
         #[allow(clippy::cast_precision_loss)]
         self.client
             .send_packet(&CGameEvent::new(
@@ -799,11 +1426,15 @@ impl Player {
         }
     }
 
+    #[expect(clippy::too_many_lines)]
     pub async fn handle_play_packet(
         self: &Arc<Self>,
         server: &Arc<Server>,
         packet: &mut RawPacket,
     ) -> Result<(), Box<dyn PumpkinError>> {
+        if self.client.connection_state.load() == ConnectionState::Config {
+            return self.handle_reconfiguration_packet(server, packet).await;
+        }
         let bytebuf = &mut packet.bytebuf;
         match packet.id.0 {
             SConfirmTeleport::PACKET_ID => {
@@ -817,6 +1448,16 @@ impl Player {
             SChatMessage::PACKET_ID => {
                 self.handle_chat_message(SChatMessage::read(bytebuf)?).await;
             }
+            SChatSessionUpdate::PACKET_ID => {
+                self.handle_chat_session_update(server, SChatSessionUpdate::read(bytebuf)?)
+                    .await;
+            }
+            SChatAck::PACKET_ID => {
+                self.handle_chat_ack(SChatAck::read(bytebuf)?).await;
+            }
+            SChunkBatchReceived::PACKET_ID => {
+                self.handle_chunk_batch_received(&SChunkBatchReceived::read(bytebuf)?);
+            }
             SClientInformationPlay::PACKET_ID => {
                 self.handle_client_information(SClientInformationPlay::read(bytebuf)?)
                     .await;
@@ -825,15 +1466,31 @@ impl Player {
                 self.handle_client_status(SClientCommand::read(bytebuf)?)
                     .await;
             }
+            SConfigurationAcknowledged::PACKET_ID => {
+                self.handle_configuration_acknowledged(server).await;
+            }
             SPlayerInput::PACKET_ID => {
-                // TODO
+                self.handle_player_input(SPlayerInput::read(bytebuf)?).await;
+            }
+            SMoveVehicle::PACKET_ID => {
+                self.handle_move_vehicle(SMoveVehicle::read(bytebuf)?).await;
+            }
+            SPaddleBoat::PACKET_ID => {
+                self.handle_paddle_boat(SPaddleBoat::read(bytebuf)?).await;
             }
             SInteract::PACKET_ID => {
-                self.handle_interact(SInteract::read(bytebuf)?).await;
+                self.handle_interact(server, SInteract::read(bytebuf)?)
+                    .await;
             }
             SKeepAlive::PACKET_ID => {
                 self.handle_keep_alive(SKeepAlive::read(bytebuf)?).await;
             }
+            SPlayPluginMessage::PACKET_ID => {
+                let message = SPlayPluginMessage::read(bytebuf)?;
+                self.client
+                    .handle_plugin_message(Some(self), &message.channel, &message.data)
+                    .await;
+            }
             SClientTickEnd::PACKET_ID => {
                 // TODO
             }
@@ -859,13 +1516,22 @@ impl Player {
                     .await;
             }
             SPlayerCommand::PACKET_ID => {
-                self.handle_player_command(SPlayerCommand::read(bytebuf)?)
+                self.handle_player_command(server, SPlayerCommand::read(bytebuf)?)
                     .await;
             }
             SClickContainer::PACKET_ID => {
                 self.handle_click_container(server, SClickContainer::read(bytebuf)?)
                     .await?;
             }
+            SCloseContainer::PACKET_ID => {
+                self.handle_close_container(server, SCloseContainer::read(bytebuf)?)
+                    .await;
+            }
+            SRenameItem::PACKET_ID => self.handle_rename_item(SRenameItem::read(bytebuf)?),
+            SSetBeacon::PACKET_ID => {
+                self.handle_set_beacon(server, SSetBeacon::read(bytebuf)?)
+                    .await;
+            }
             SSetHeldItem::PACKET_ID => {
                 self.handle_set_held_item(SSetHeldItem::read(bytebuf)?)
                     .await;
@@ -878,9 +1544,10 @@ impl Player {
                 self.handle_swing_arm(SSwingArm::read(bytebuf)?).await;
             }
             SUseItemOn::PACKET_ID => {
-                self.handle_use_item_on(SUseItemOn::read(bytebuf)?).await?;
+                self.handle_use_item_on(server, SUseItemOn::read(bytebuf)?)
+                    .await?;
             }
-            SUseItem::PACKET_ID => self.handle_use_item(&SUseItem::read(bytebuf)?),
+            SUseItem::PACKET_ID => self.handle_use_item(&SUseItem::read(bytebuf)?).await,
             SCommandSuggestion::PACKET_ID => {
                 self.handle_command_suggestion(SCommandSuggestion::read(bytebuf)?, server)
                     .await;
@@ -913,6 +1580,18 @@ pub struct Abilities {
     pub walk_speed_fov: f32,
 }
 
+impl Abilities {
+    /// Gives the abilities vanilla gives every player in the game mode
+    pub fn set_gamemode(&mut self, gamemode: GameMode) {
+        let creative = gamemode == GameMode::Creative;
+        let spectator = gamemode == GameMode::Spectator;
+        self.invulnerable = creative || spectator;
+        self.flying = spectator;
+        self.allow_flying = creative || spectator;
+        self.creative = creative;
+    }
+}
+
 impl Default for Abilities {
     fn default() -> Self {
         Self {
@@ -927,7 +1606,7 @@ impl Default for Abilities {
 }
 
 /// Represents the player's dominant hand.
-#[derive(FromPrimitive, Clone)]
+#[derive(FromPrimitive, Clone, Copy)]
 pub enum Hand {
     /// The player's primary hand (usually the right hand).
     Main,
@@ -0,0 +1,188 @@
+//! Sending the same thing to many players at once, e.g. a countdown to everyone in a lobby.
+//!
+//! Packets are serialized a single time and then written to all connections concurrently,
+//! instead of serializing and awaiting them player by player.
+
+use std::sync::Arc;
+
+use pumpkin_core::{math::vector3::Vector3, text::TextComponent};
+use pumpkin_protocol::{
+    client::play::{
+        CActionBar, CClearTitle, CPlayPluginMessage, CStopSound, CSubtitle, CSystemChatMessage,
+        SoundEvent,
+    },
+    packet_encoder::PreparedPacket,
+    ClientPacket, SoundCategory,
+};
+use tokio::task::JoinSet;
+use uuid::Uuid;
+
+use super::{
+    player::Player,
+    sound::sound_packet,
+    title::{title_packets, TitleTimes},
+};
+
+/// Below this many players spawning tasks costs more than writing one after another
+const PARALLEL_THRESHOLD: usize = 8;
+
+/// A selection of players which actions are applied to in bulk
+#[derive(Clone, Default)]
+pub struct PlayerSet {
+    players: Vec<Arc<Player>>,
+}
+
+impl PlayerSet {
+    #[must_use]
+    pub const fn new(players: Vec<Arc<Player>>) -> Self {
+        Self { players }
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.players.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.players.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<Player>> {
+        self.players.iter()
+    }
+
+    /// Keeps only the players matching the predicate.
+    #[must_use]
+    pub fn filter(mut self, predicate: impl Fn(&Player) -> bool) -> Self {
+        self.players.retain(|player| predicate(player));
+        self
+    }
+
+    /// Removes the players with the given UUIDs.
+    #[must_use]
+    pub fn except(self, except: &[Uuid]) -> Self {
+        self.filter(|player| !except.contains(&player.gameprofile.id))
+    }
+
+    /// Sends the packet to every player, serializing it only once.
+    pub async fn send_packet<P: ClientPacket>(&self, packet: &P) {
+        if self.players.is_empty() {
+            return;
+        }
+        self.send_prepared(PreparedPacket::new(packet)).await;
+    }
+
+    /// Sends an already serialized packet to every player.
+    pub async fn send_prepared(&self, packet: PreparedPacket) {
+        if self.players.len() < PARALLEL_THRESHOLD {
+            for player in &self.players {
+                player.client.send_prepared_packet(&packet).await;
+            }
+            return;
+        }
+
+        let mut tasks = JoinSet::new();
+        for player in &self.players {
+            let player = player.clone();
+            let packet = packet.clone();
+            tasks.spawn(async move { player.client.send_prepared_packet(&packet).await });
+        }
+        while tasks.join_next().await.is_some() {}
+    }
+
+    /// Sends a custom payload on the channel, see [`crate::client::plugin_channel`].
+    pub async fn send_plugin_message(&self, channel: &str, data: &[u8]) {
+        self.send_packet(&CPlayPluginMessage::new(channel, data))
+            .await;
+    }
+
+    pub async fn send_message(&self, text: &TextComponent<'_>) {
+        self.send_packet(&CSystemChatMessage::new(text, false))
+            .await;
+    }
+
+    /// Shows the title, and the subtitle below it if given, see [`Player::send_title`].
+    pub async fn send_title(
+        &self,
+        title: TextComponent<'_>,
+        subtitle: Option<TextComponent<'_>>,
+        times: Option<TitleTimes>,
+    ) {
+        if self.players.is_empty() {
+            return;
+        }
+        for packet in title_packets(title, subtitle, times) {
+            self.send_prepared(packet).await;
+        }
+    }
+
+    /// Sets the subtitle, which is shown with the next title.
+    pub async fn send_subtitle(&self, subtitle: TextComponent<'_>) {
+        self.send_packet(&CSubtitle::new(subtitle)).await;
+    }
+
+    pub async fn set_title_times(&self, times: TitleTimes) {
+        self.send_packet(&times.to_packet()).await;
+    }
+
+    /// Hides the title, resetting also clears the subtitle and sets the times back to the default.
+    pub async fn clear_title(&self, reset: bool) {
+        self.send_packet(&CClearTitle::new(reset)).await;
+    }
+
+    pub async fn send_action_bar(&self, text: TextComponent<'_>) {
+        self.send_packet(&CActionBar::new(text)).await;
+    }
+
+    /// Plays the sound at the position, all players hear it with the same seed.
+    pub async fn play_sound(
+        &self,
+        sound: SoundEvent<'_>,
+        category: SoundCategory,
+        position: &Vector3<f64>,
+        volume: f32,
+        pitch: f32,
+    ) {
+        self.send_packet(&sound_packet(sound, category, position, volume, pitch))
+            .await;
+    }
+
+    /// Stops the sounds of the category with the name, or all of them if None.
+    pub async fn stop_sound(&self, category: Option<SoundCategory>, sound: Option<&str>) {
+        self.send_packet(&CStopSound::new(category, sound)).await;
+    }
+
+    /// Teleports every player to the position, yaw and pitch in degrees.
+    ///
+    /// Teleports carry a per player id, so unlike the other actions the packets are not shared.
+    pub async fn teleport(&self, position: Vector3<f64>, yaw: f32, pitch: f32) {
+        let mut tasks = JoinSet::new();
+        for player in &self.players {
+            let player = player.clone();
+            tasks.spawn(async move { player.teleport(position, yaw, pitch).await });
+        }
+        while tasks.join_next().await.is_some() {}
+    }
+}
+
+impl From<Vec<Arc<Player>>> for PlayerSet {
+    fn from(players: Vec<Arc<Player>>) -> Self {
+        Self::new(players)
+    }
+}
+
+impl FromIterator<Arc<Player>> for PlayerSet {
+    fn from_iter<I: IntoIterator<Item = Arc<Player>>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+impl IntoIterator for PlayerSet {
+    type Item = Arc<Player>;
+    type IntoIter = std::vec::IntoIter<Arc<Player>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.players.into_iter()
+    }
+}
@@ -0,0 +1,538 @@
+//! Thrown splash and lingering potions, and the area effect clouds lingering potions leave behind.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+};
+
+use async_trait::async_trait;
+use crossbeam::atomic::AtomicCell;
+use pumpkin_core::{
+    math::{
+        boundingbox::{BoundingBox, BoundingBoxSize},
+        position::WorldPosition,
+        vector3::Vector3,
+    },
+    text::color::RGBColor,
+    GameMode,
+};
+use pumpkin_entity::{effect_type::EffectType, entity_type::EntityType, EntityId};
+use pumpkin_macros::{particle, sound};
+use pumpkin_protocol::{
+    client::play::{CSpawnEntity, CWorldEvent, Particle, ParticleOptions},
+    packet_encoder::PreparedPacket,
+    SoundCategory,
+};
+use pumpkin_world::item::{
+    component::{PotionContents, PotionEffect},
+    item_registry::get_item_name,
+    ItemStack,
+};
+use uuid::Uuid;
+
+use crate::world::World;
+
+use super::{
+    data_tracker, new_entity_id,
+    player::{Hand, Player},
+    totem::OFFHAND_SLOT,
+    Entity, EntityBase,
+};
+
+const GRAVITY: f64 = 0.05;
+const DRAG: f64 = 0.99;
+/// How fast players throw potions
+const THROW_SPEED: f64 = 0.5;
+/// How far splash potions reach
+const SPLASH_RANGE: f64 = 4.0;
+/// Ticks the thrower can't be hit by their own potion
+const OWNER_IMMUNITY: u32 = 5;
+
+/// The world events of splashing potions, the client shows the particles in the color
+const SPLASH_EVENT: i32 = 2002;
+const INSTANT_SPLASH_EVENT: i32 = 2007;
+
+const CLOUD_RADIUS: f32 = 3.0;
+/// How much smaller the cloud gets every time it affects an entity
+const CLOUD_RADIUS_ON_USE: f32 = -0.5;
+/// Ticks until the cloud starts to affect entities
+const CLOUD_WAIT_TIME: u32 = 10;
+const CLOUD_DURATION: u32 = 600;
+/// Ticks until an entity is affected by the same cloud again
+const CLOUD_REAPPLICATION_DELAY: u32 = 20;
+
+fn potion_contents(stack: &ItemStack) -> PotionContents {
+    stack.potion_contents().cloned().unwrap_or_default()
+}
+
+fn entity_effect(color: i32) -> Particle {
+    let [_, red, green, blue] = color.to_be_bytes();
+    Particle::new(
+        particle!("minecraft:entity_effect"),
+        ParticleOptions::Color {
+            color: RGBColor::new(red, green, blue),
+            alpha: 255,
+        },
+    )
+    .expect("entity_effect takes a color")
+}
+
+/// The players in the area who can be affected by potions
+fn living_in(players: &[Arc<Player>], area: &BoundingBox) -> Vec<Arc<Player>> {
+    players
+        .iter()
+        .filter(|player| {
+            let entity = &player.living_entity.entity;
+            let pos = entity.pos.load();
+            player.gamemode.load() != GameMode::Spectator
+                && player.living_entity.health.load() > 0.0
+                && BoundingBox::new_from_pos(pos.x, pos.y, pos.z, &entity.bounding_box_size.load())
+                    .intersects(area)
+        })
+        .cloned()
+        .collect()
+}
+
+pub struct ThrownPotion {
+    pub entity: Entity,
+    uuid: Uuid,
+    /// A splash or lingering potion
+    stack: ItemStack,
+    /// The player who threw it, who gets the credit for kills
+    owner: Option<EntityId>,
+    age: AtomicU32,
+}
+
+impl ThrownPotion {
+    pub fn new(
+        world: Arc<World>,
+        stack: ItemStack,
+        position: Vector3<f64>,
+        velocity: Vector3<f64>,
+        owner: Option<EntityId>,
+    ) -> Self {
+        let size = BoundingBoxSize {
+            width: 0.25,
+            height: 0.25,
+        };
+        let entity = Entity::new(
+            new_entity_id(),
+            world,
+            EntityType::Potion,
+            0.0,
+            AtomicCell::new(BoundingBox::new_from_pos(
+                position.x, position.y, position.z, &size,
+            )),
+            AtomicCell::new(size),
+        );
+        entity.set_pos(position.x, position.y, position.z);
+        entity.velocity.store(velocity);
+        {
+            let mut tracker = entity.data_tracker.lock();
+            tracker.define(&data_tracker::ITEM, None);
+            tracker.set(&data_tracker::ITEM, Some(stack.clone()));
+        }
+        Self {
+            entity,
+            uuid: Uuid::new_v4(),
+            stack,
+            owner,
+            age: AtomicU32::new(0),
+        }
+    }
+
+    fn is_lingering(&self) -> bool {
+        get_item_name(self.stack.item_id) == Some("minecraft:lingering_potion")
+    }
+
+    /// The player the potion flew into, its thrower only after a few ticks
+    async fn hit_player(&self) -> Option<Arc<Player>> {
+        let entity = &self.entity;
+        let pos = entity.pos.load();
+        let area = BoundingBox::new_from_pos(pos.x, pos.y, pos.z, &entity.bounding_box_size.load());
+        let players: Vec<_> = entity.world.players().await.iter().cloned().collect();
+        let owner_immune = self.age.load(Ordering::Relaxed) < OWNER_IMMUNITY;
+        living_in(&players, &area)
+            .into_iter()
+            .find(|player| !(owner_immune && Some(player.entity_id()) == self.owner))
+    }
+
+    /// Splashes the effects onto the players and mobs around, the closer the stronger, or leaves a
+    /// cloud behind if the potion is lingering
+    async fn splash(&self, hit: Option<&Arc<Player>>) {
+        let entity = &self.entity;
+        let world = &entity.world;
+        let pos = entity.pos.load();
+        let contents = potion_contents(&self.stack);
+        let effects = contents.effects();
+
+        if self.is_lingering() {
+            let cloud = AreaEffectCloud::new(world.clone(), contents.clone(), pos, self.owner);
+            world.spawn_entity(Arc::new(cloud)).await;
+        } else if !effects.is_empty() {
+            let area = BoundingBox {
+                min_x: pos.x - SPLASH_RANGE,
+                min_y: pos.y - SPLASH_RANGE / 2.0,
+                min_z: pos.z - SPLASH_RANGE,
+                max_x: pos.x + SPLASH_RANGE,
+                max_y: pos.y + SPLASH_RANGE / 2.0,
+                max_z: pos.z + SPLASH_RANGE,
+            };
+            let players: Vec<_> = world.players().await.iter().cloned().collect();
+            for player in living_in(&players, &area) {
+                let distance_squared = player
+                    .living_entity
+                    .entity
+                    .pos
+                    .load()
+                    .sub(&pos)
+                    .length_squared();
+                if distance_squared >= SPLASH_RANGE * SPLASH_RANGE {
+                    continue;
+                }
+                let scale = if hit.is_some_and(|hit| hit.entity_id() == player.entity_id()) {
+                    1.0
+                } else {
+                    1.0 - distance_squared.sqrt() / SPLASH_RANGE
+                };
+                player
+                    .living_entity
+                    .apply_potion(&effects, scale, scale, self.owner)
+                    .await;
+            }
+            let mobs: Vec<_> = world
+                .entities
+                .lock()
+                .await
+                .values()
+                .filter(|other| other.get_mob().is_some())
+                .cloned()
+                .collect();
+            for mob in &mobs {
+                let Some(living) = mob.get_living_entity() else {
+                    continue;
+                };
+                let mob_entity = &living.entity;
+                let mob_pos = mob_entity.pos.load();
+                let hitbox = BoundingBox::new_from_pos(
+                    mob_pos.x,
+                    mob_pos.y,
+                    mob_pos.z,
+                    &mob_entity.bounding_box_size.load(),
+                );
+                let distance = mob_pos.sub(&pos).length();
+                if living.health.load() <= 0.0
+                    || !hitbox.intersects(&area)
+                    || distance >= SPLASH_RANGE
+                {
+                    continue;
+                }
+                let scale = 1.0 - distance / SPLASH_RANGE;
+                living
+                    .apply_potion(&effects, scale, scale, self.owner)
+                    .await;
+            }
+        }
+
+        let instant = effects.iter().any(|effect| {
+            EffectType::from_name(&effect.effect).is_some_and(EffectType::is_instant)
+        });
+        let event = if instant {
+            INSTANT_SPLASH_EVENT
+        } else {
+            SPLASH_EVENT
+        };
+        let position = WorldPosition(Vector3::new(
+            pos.x.floor() as i32,
+            pos.y.floor() as i32,
+            pos.z.floor() as i32,
+        ));
+        world
+            .broadcast_packet_all(&CWorldEvent::new(event, &position, contents.color(), false))
+            .await;
+    }
+}
+
+#[async_trait]
+impl EntityBase for ThrownPotion {
+    fn get_entity(&self) -> &Entity {
+        &self.entity
+    }
+
+    async fn tick(&self) -> bool {
+        self.age.fetch_add(1, Ordering::Relaxed);
+        let entity = &self.entity;
+        let velocity = entity.velocity.load();
+        let moved = entity.move_colliding(velocity).await;
+        let hit_block = entity.hit_block(velocity, moved);
+        if let Some((block, hit, face)) = hit_block {
+            entity.world.hit_target(block, hit, face, false).await;
+        }
+        let hit_player = self.hit_player().await;
+        if hit_block.is_some() || hit_player.is_some() {
+            self.splash(hit_player.as_ref()).await;
+            return false;
+        }
+        let mut velocity = velocity.multiply(DRAG, DRAG, DRAG);
+        velocity.y -= GRAVITY;
+        entity.velocity.store(velocity);
+        entity.send_data_changes().await;
+        true
+    }
+
+    fn spawn_packet(&self, position: Vector3<f64>) -> PreparedPacket {
+        let velocity = self.entity.velocity.load();
+        PreparedPacket::new(&CSpawnEntity::new(
+            self.entity.entity_id.into(),
+            self.uuid,
+            (EntityType::Potion as i32).into(),
+            position.x,
+            position.y,
+            position.z,
+            0.0,
+            0.0,
+            0.0,
+            self.owner.unwrap_or(0).into(),
+            velocity.x as f32,
+            velocity.y as f32,
+            velocity.z as f32,
+        ))
+    }
+}
+
+/// The cloud of a lingering potion, it affects entities standing in it every few ticks and gets
+/// smaller until it is gone
+pub struct AreaEffectCloud {
+    pub entity: Entity,
+    uuid: Uuid,
+    effects: Vec<PotionEffect>,
+    owner: Option<EntityId>,
+    radius: AtomicCell<f32>,
+    age: AtomicU32,
+    /// The age at which each entity can be affected again
+    affected: parking_lot::Mutex<HashMap<EntityId, u32>>,
+}
+
+impl AreaEffectCloud {
+    pub fn new(
+        world: Arc<World>,
+        contents: PotionContents,
+        position: Vector3<f64>,
+        owner: Option<EntityId>,
+    ) -> Self {
+        let size = BoundingBoxSize {
+            width: f64::from(CLOUD_RADIUS) * 2.0,
+            height: 0.5,
+        };
+        let entity = Entity::new(
+            new_entity_id(),
+            world,
+            EntityType::AreaEffectCloud,
+            0.0,
+            AtomicCell::new(BoundingBox::new_from_pos(
+                position.x, position.y, position.z, &size,
+            )),
+            AtomicCell::new(size),
+        );
+        entity.set_pos(position.x, position.y, position.z);
+        {
+            let mut tracker = entity.data_tracker.lock();
+            tracker.define(&data_tracker::CLOUD_RADIUS, 3.0);
+            tracker.define(&data_tracker::CLOUD_WAITING, false);
+            tracker.define(&data_tracker::CLOUD_PARTICLE, entity_effect(-1));
+            tracker.set(&data_tracker::CLOUD_RADIUS, CLOUD_RADIUS);
+            tracker.set(&data_tracker::CLOUD_WAITING, true);
+            tracker.set(
+                &data_tracker::CLOUD_PARTICLE,
+                entity_effect(contents.color()),
+            );
+        }
+        Self {
+            entity,
+            uuid: Uuid::new_v4(),
+            effects: contents.effects(),
+            owner,
+            radius: AtomicCell::new(CLOUD_RADIUS),
+            age: AtomicU32::new(0),
+            affected: parking_lot::Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn set_radius(&self, radius: f32) {
+        self.radius.store(radius);
+        self.entity
+            .data_tracker
+            .lock()
+            .set(&data_tracker::CLOUD_RADIUS, radius);
+    }
+
+    /// Applies the effects to the players standing in the cloud who weren't affected recently.
+    /// Lingering effects last a quarter as long and instant ones are half as strong
+    async fn affect_players(&self, age: u32) {
+        let entity = &self.entity;
+        let pos = entity.pos.load();
+        let radius = f64::from(self.radius.load());
+        let area = BoundingBox {
+            min_x: pos.x - radius,
+            min_y: pos.y,
+            min_z: pos.z - radius,
+            max_x: pos.x + radius,
+            max_y: pos.y + 0.5,
+            max_z: pos.z + radius,
+        };
+        let players: Vec<_> = entity.world.players().await.iter().cloned().collect();
+        for player in living_in(&players, &area) {
+            let offset = player.living_entity.entity.pos.load().sub(&pos);
+            if offset.x * offset.x + offset.z * offset.z > radius * radius {
+                continue;
+            }
+            {
+                let mut affected = self.affected.lock();
+                if affected
+                    .get(&player.entity_id())
+                    .is_some_and(|again| age < *again)
+                {
+                    continue;
+                }
+                affected.insert(player.entity_id(), age + CLOUD_REAPPLICATION_DELAY);
+            }
+            player
+                .living_entity
+                .apply_potion(&self.effects, 0.25, 0.5, self.owner)
+                .await;
+            let radius = self.radius.load() + CLOUD_RADIUS_ON_USE;
+            self.set_radius(radius);
+            if radius < 0.5 {
+                return;
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl EntityBase for AreaEffectCloud {
+    fn get_entity(&self) -> &Entity {
+        &self.entity
+    }
+
+    async fn tick(&self) -> bool {
+        let age = self.age.fetch_add(1, Ordering::Relaxed) + 1;
+        if age >= CLOUD_WAIT_TIME + CLOUD_DURATION {
+            return false;
+        }
+        if age < CLOUD_WAIT_TIME {
+            return true;
+        }
+        if age == CLOUD_WAIT_TIME {
+            self.entity
+                .data_tracker
+                .lock()
+                .set(&data_tracker::CLOUD_WAITING, false);
+        }
+        #[expect(clippy::cast_precision_loss)]
+        let shrink = CLOUD_RADIUS / CLOUD_DURATION as f32;
+        self.set_radius(self.radius.load() - shrink);
+        if age % 5 == 0 {
+            self.affected.lock().retain(|_, again| age < *again);
+            if !self.effects.is_empty() {
+                self.affect_players(age).await;
+            }
+        }
+        if self.radius.load() < 0.5 {
+            return false;
+        }
+        self.entity.send_data_changes().await;
+        true
+    }
+
+    fn spawn_packet(&self, position: Vector3<f64>) -> PreparedPacket {
+        PreparedPacket::new(&CSpawnEntity::new(
+            self.entity.entity_id.into(),
+            self.uuid,
+            (EntityType::AreaEffectCloud as i32).into(),
+            position.x,
+            position.y,
+            position.z,
+            0.0,
+            0.0,
+            0.0,
+            0.into(),
+            0.0,
+            0.0,
+            0.0,
+        ))
+    }
+}
+
+impl Player {
+    /// Throws the splash or lingering potion in the hand where the player looks, returns false if
+    /// the hand holds no such potion
+    pub async fn throw_potion(&self, hand: Hand) -> bool {
+        let stack = {
+            let mut inventory = self.inventory.lock().await;
+            let slot = match hand {
+                Hand::Main => inventory.held_item_mut(),
+                Hand::Off => match inventory.get_slot(OFFHAND_SLOT) {
+                    Ok(slot) => slot,
+                    Err(_) => return false,
+                },
+            };
+            let Some(stack) = slot.as_mut().filter(|stack| {
+                matches!(
+                    get_item_name(stack.item_id),
+                    Some("minecraft:splash_potion" | "minecraft:lingering_potion")
+                )
+            }) else {
+                return false;
+            };
+            let mut thrown = stack.clone();
+            thrown.item_count = 1;
+            if self.gamemode.load() != GameMode::Creative {
+                stack.item_count -= 1;
+                if stack.item_count == 0 {
+                    *slot = None;
+                }
+            }
+            thrown
+        };
+
+        let entity = &self.living_entity.entity;
+        let pos = entity.pos.load();
+        let position = Vector3::new(
+            pos.x,
+            pos.y + f64::from(entity.standing_eye_height) - 0.1,
+            pos.z,
+        );
+        // potions are thrown a bit upwards
+        let yaw = f64::from(entity.yaw.load()).to_radians();
+        let pitch = (f64::from(entity.pitch.load()) - 20.0).to_radians();
+        let velocity = Vector3::new(
+            -yaw.sin() * pitch.cos() * THROW_SPEED,
+            -pitch.sin() * THROW_SPEED,
+            yaw.cos() * pitch.cos() * THROW_SPEED,
+        )
+        .add(&entity.velocity.load());
+        let sound = if get_item_name(stack.item_id) == Some("minecraft:lingering_potion") {
+            sound!("minecraft:entity.lingering_potion.throw")
+        } else {
+            sound!("minecraft:entity.splash_potion.throw")
+        };
+        let world = entity.world.clone();
+        world
+            .spawn_entity(Arc::new(ThrownPotion::new(
+                world.clone(),
+                stack,
+                position,
+                velocity,
+                Some(self.entity_id()),
+            )))
+            .await;
+        world
+            .play_sound(sound, SoundCategory::Neutral, &position)
+            .await;
+        true
+    }
+}
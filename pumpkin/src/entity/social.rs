@@ -0,0 +1,125 @@
+//! Private messages between players and the players someone ignores.
+//!
+//! Private messages are sent as disguised chat with the `msg_command` chat types, so clients
+//! decorate them like vanilla does ("Alex whispers to you: ...").
+
+use pumpkin_api::service::ChatFormat;
+use pumpkin_core::{persistent_data::NamespacedKey, text::TextComponent};
+use pumpkin_protocol::client::play::{CDisguisedChatMessage, ChatType};
+use uuid::Uuid;
+
+use crate::{command::CommandSender, plugin, plugin::SERVICES};
+
+use super::player::Player;
+
+/// Where the ignored players are kept in the player's persistent data, as pairs of longs
+fn ignored_key() -> NamespacedKey {
+    NamespacedKey::new("pumpkin", "ignored").expect("The key is valid")
+}
+
+impl Player {
+    /// The name shown in chat, which a [`ChatFormat`] service may change
+    #[must_use]
+    pub fn display_name(&self) -> String {
+        SERVICES.get::<dyn ChatFormat>().map_or_else(
+            || self.gameprofile.name.clone(),
+            |format| format.display_name(&plugin::player_info(self)),
+        )
+    }
+
+    fn ignored(&self) -> Vec<Uuid> {
+        let halves: Vec<i64> = self
+            .living_entity
+            .entity
+            .persistent_data
+            .lock()
+            .get(&ignored_key())
+            .unwrap_or_default();
+        halves
+            .chunks_exact(2)
+            .map(|pair| Uuid::from_u64_pair(pair[0] as u64, pair[1] as u64))
+            .collect()
+    }
+
+    /// Whether the player hides the chat and private messages of `other`
+    #[must_use]
+    pub fn ignores(&self, other: Uuid) -> bool {
+        self.ignored().contains(&other)
+    }
+
+    /// Starts or stops ignoring `other`, returns whether they are ignored now
+    pub fn toggle_ignored(&self, other: Uuid) -> bool {
+        let mut ignored = self.ignored();
+        let now_ignored = match ignored.iter().position(|uuid| *uuid == other) {
+            Some(index) => {
+                ignored.remove(index);
+                false
+            }
+            None => {
+                ignored.push(other);
+                true
+            }
+        };
+        let halves: Vec<i64> = ignored
+            .iter()
+            .flat_map(|uuid| {
+                let (high, low) = uuid.as_u64_pair();
+                [high as i64, low as i64]
+            })
+            .collect();
+        self.living_entity
+            .entity
+            .persistent_data
+            .lock()
+            .set(&ignored_key(), halves);
+        now_ignored
+    }
+}
+
+/// The name a command sender has in chat
+#[must_use]
+pub fn sender_name(sender: &CommandSender<'_>) -> String {
+    match sender {
+        CommandSender::Player(player) => player.display_name(),
+        CommandSender::Console | CommandSender::Rcon(_) => sender.to_string(),
+    }
+}
+
+/// Sends a message only the sender and the receiver see. Both reply to each other with `/r`
+/// afterwards, a receiver ignoring the sender doesn't get the message
+pub async fn whisper(sender: &CommandSender<'_>, receiver: &Player, message: &str) {
+    let name = sender_name(sender);
+    let content = TextComponent::text(message);
+    if let CommandSender::Player(player) = sender {
+        *player.reply_target.lock() = Some(receiver.gameprofile.id);
+        player
+            .client
+            .send_packet(&CDisguisedChatMessage::new(
+                content.clone(),
+                (ChatType::MsgCommandOutgoing as i32 + 1).into(),
+                TextComponent::text(&name),
+                Some(TextComponent::text_string(receiver.display_name())),
+            ))
+            .await;
+        if receiver.ignores(player.gameprofile.id) {
+            return;
+        }
+        *receiver.reply_target.lock() = Some(player.gameprofile.id);
+    } else {
+        sender
+            .send_message(TextComponent::text_string(format!(
+                "You whisper to {}: {message}",
+                receiver.gameprofile.name
+            )))
+            .await;
+    }
+    receiver
+        .client
+        .send_packet(&CDisguisedChatMessage::new(
+            content,
+            (ChatType::MsgCommandIncoming as i32 + 1).into(),
+            TextComponent::text(&name),
+            None,
+        ))
+        .await;
+}
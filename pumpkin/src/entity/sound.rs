@@ -0,0 +1,65 @@
+//! Playing and stopping sounds for a single player, [`sound_packet`] is shared with the ones
+//! played to many.
+
+use pumpkin_core::math::vector3::Vector3;
+use pumpkin_protocol::{
+    client::play::{CSoundEffect, CStopSound, SoundEvent},
+    SoundCategory,
+};
+use rand::{thread_rng, Rng};
+
+use super::player::Player;
+
+/// How far a sound can be heard at a volume of 1.0, louder sounds reach further
+pub const BASE_SOUND_RANGE: f32 = 16.0;
+
+/// The distance a sound can be heard from, like the client computes it
+#[must_use]
+pub fn sound_range(sound: &SoundEvent<'_>, volume: f32) -> f32 {
+    match sound {
+        SoundEvent::Custom {
+            fixed_range: Some(range),
+            ..
+        } => *range,
+        _ => BASE_SOUND_RANGE * volume.max(1.0),
+    }
+}
+
+/// The packet playing the sound at the position with a random seed, which picks the variant of
+/// the sound
+#[must_use]
+pub fn sound_packet<'a>(
+    sound: SoundEvent<'a>,
+    category: SoundCategory,
+    position: &Vector3<f64>,
+    volume: f32,
+    pitch: f32,
+) -> CSoundEffect<'a> {
+    let seed = thread_rng().gen::<i64>();
+    CSoundEffect::new(
+        sound, category, position.x, position.y, position.z, volume, pitch, seed,
+    )
+}
+
+impl Player {
+    /// Plays the sound at the position for this player only, other players do not hear it.
+    pub async fn play_sound(
+        &self,
+        sound: SoundEvent<'_>,
+        category: SoundCategory,
+        position: &Vector3<f64>,
+        volume: f32,
+        pitch: f32,
+    ) {
+        self.client
+            .send_packet(&sound_packet(sound, category, position, volume, pitch))
+            .await;
+    }
+
+    /// Stops the sounds of the category with the name, or all of them if None.
+    pub async fn stop_sound(&self, category: Option<SoundCategory>, sound: Option<&str>) {
+        self.client
+            .send_packet(&CStopSound::new(category, sound))
+            .await;
+    }
+}
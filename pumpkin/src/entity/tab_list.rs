@@ -0,0 +1,217 @@
+//! The player list, shown while holding tab.
+//!
+//! What it shows of a player can be changed: their name, ping, gamemode and position in the list,
+//! or they can be left out of it. Every change is sent to everyone in the player's world, but the
+//! player themself is always shown their real gamemode, which their client relies on.
+
+use pumpkin_config::ADVANCED_CONFIG;
+use pumpkin_core::{text::TextComponent, GameMode};
+use pumpkin_protocol::client::play::{CPlayerInfoUpdate, CTabList, PlayerAction};
+
+use crate::client::chat_session::ChatSession;
+
+use super::player::Player;
+
+/// Every action of the player info update, to send the whole entry
+pub const ALL_ACTIONS: i8 = 0x01 | 0x02 | 0x04 | 0x08 | 0x10 | 0x20 | 0x40;
+const UPDATE_GAMEMODE: i8 = 0x04;
+const UPDATE_LISTED: i8 = 0x08;
+const UPDATE_LATENCY: i8 = 0x10;
+const UPDATE_DISPLAY_NAME: i8 = 0x20;
+const UPDATE_LIST_ORDER: i8 = 0x40;
+
+/// How a player is shown in the list, None shows the real value
+#[derive(Clone)]
+pub struct TabListEntry {
+    pub display_name: Option<TextComponent<'static>>,
+    /// In milliseconds
+    pub latency: Option<i32>,
+    pub gamemode: Option<GameMode>,
+    pub listed: bool,
+    /// Players with a higher order are listed first
+    pub order: i32,
+}
+
+impl Default for TabListEntry {
+    fn default() -> Self {
+        Self {
+            display_name: None,
+            latency: None,
+            gamemode: None,
+            listed: true,
+            order: 0,
+        }
+    }
+}
+
+/// A player's entry, copied so packets can borrow from it
+pub struct ListedPlayer<'a> {
+    player: &'a Player,
+    entry: TabListEntry,
+    session: Option<std::sync::Arc<ChatSession>>,
+}
+
+impl<'a> ListedPlayer<'a> {
+    pub fn new(player: &'a Player) -> Self {
+        Self {
+            player,
+            entry: player.tab_list.lock().clone(),
+            session: player.chat_state.lock().session(),
+        }
+    }
+
+    /// The entry with [`ALL_ACTIONS`], as the other players see it
+    pub fn to_protocol(&self) -> pumpkin_protocol::client::play::Player<'_> {
+        self.with_gamemode(self.entry.gamemode.unwrap_or(self.player.gamemode.load()))
+    }
+
+    /// The entry with [`ALL_ACTIONS`] for the player themself, with their real gamemode
+    pub fn to_own_protocol(&self) -> pumpkin_protocol::client::play::Player<'_> {
+        self.with_gamemode(self.player.gamemode.load())
+    }
+
+    fn with_gamemode(&self, gamemode: GameMode) -> pumpkin_protocol::client::play::Player<'_> {
+        let gameprofile = &self.player.gameprofile;
+        let session = self.session.as_deref().map(ChatSession::to_protocol);
+        pumpkin_protocol::client::play::Player {
+            uuid: gameprofile.id,
+            actions: vec![
+                PlayerAction::AddPlayer {
+                    name: &gameprofile.name,
+                    properties: &gameprofile.properties,
+                },
+                PlayerAction::InitializeChat(session),
+                PlayerAction::UpdateGameMode((gamemode as i32).into()),
+                PlayerAction::UpdateListed(self.entry.listed),
+                PlayerAction::UpdateLatency(self.player.listed_latency().into()),
+                PlayerAction::UpdateDisplayName(self.entry.display_name.clone()),
+                PlayerAction::UpdateListOrder(self.entry.order.into()),
+            ],
+        }
+    }
+}
+
+impl Player {
+    /// Sets the text above and below the list, the config's is sent when joining.
+    pub async fn send_tab_list(&self, header: &TextComponent<'_>, footer: &TextComponent<'_>) {
+        self.client
+            .send_packet(&CTabList::new(header, footer))
+            .await;
+    }
+
+    /// Sends the header and footer of the config, if there are any.
+    pub async fn send_configured_tab_list(&self) {
+        let config = &ADVANCED_CONFIG.tab_list;
+        if config.header.is_empty() && config.footer.is_empty() {
+            return;
+        }
+        let header = TextComponent::from_mini_message(&config.header);
+        let footer = TextComponent::from_mini_message(&config.footer);
+        self.send_tab_list(&header, &footer).await;
+    }
+
+    /// The ping shown in the list, in milliseconds
+    #[must_use]
+    pub fn listed_latency(&self) -> i32 {
+        if let Some(latency) = self.tab_list.lock().latency {
+            return latency;
+        }
+        if !ADVANCED_CONFIG.tab_list.show_latency {
+            return 0;
+        }
+        i32::try_from(self.latency()).unwrap_or(i32::MAX)
+    }
+
+    /// The gamemode shown in the list, spectators are shown greyed out at the bottom
+    #[must_use]
+    pub fn listed_gamemode(&self) -> GameMode {
+        self.tab_list
+            .lock()
+            .gamemode
+            .unwrap_or_else(|| self.gamemode.load())
+    }
+
+    /// Shows the text instead of the player's name, None shows the name again.
+    pub async fn set_display_name(&self, display_name: Option<TextComponent<'static>>) {
+        self.tab_list.lock().display_name.clone_from(&display_name);
+        self.broadcast_list_update(
+            UPDATE_DISPLAY_NAME,
+            PlayerAction::UpdateDisplayName(display_name),
+        )
+        .await;
+    }
+
+    /// Shows the ping instead of the measured one, None shows the measured one again.
+    pub async fn set_listed_latency(&self, latency: Option<i32>) {
+        self.tab_list.lock().latency = latency;
+        self.broadcast_latency().await;
+    }
+
+    /// Shows the gamemode to the other players instead of the real one, None shows the real one
+    /// again.
+    ///
+    /// The player's own client still knows their real gamemode.
+    pub async fn set_listed_gamemode(&self, gamemode: Option<GameMode>) {
+        self.tab_list.lock().gamemode = gamemode;
+        self.broadcast_gamemode().await;
+    }
+
+    /// Tells the other players the listed gamemode and the player their real one
+    pub(crate) async fn broadcast_gamemode(&self) {
+        let entry = |gamemode: GameMode| pumpkin_protocol::client::play::Player {
+            uuid: self.gameprofile.id,
+            actions: vec![PlayerAction::UpdateGameMode((gamemode as i32).into())],
+        };
+        self.living_entity
+            .entity
+            .world
+            .broadcast_packet_except(
+                &[self.gameprofile.id],
+                &CPlayerInfoUpdate::new(UPDATE_GAMEMODE, &[entry(self.listed_gamemode())]),
+            )
+            .await;
+        self.client
+            .send_packet(&CPlayerInfoUpdate::new(
+                UPDATE_GAMEMODE,
+                &[entry(self.gamemode.load())],
+            ))
+            .await;
+    }
+
+    /// Hides the player from the list, they can still be seen in the world.
+    pub async fn set_listed(&self, listed: bool) {
+        self.tab_list.lock().listed = listed;
+        self.broadcast_list_update(UPDATE_LISTED, PlayerAction::UpdateListed(listed))
+            .await;
+    }
+
+    /// Players with a higher order are listed first, the client sorts players with the same order.
+    pub async fn set_list_order(&self, order: i32) {
+        self.tab_list.lock().order = order;
+        self.broadcast_list_update(
+            UPDATE_LIST_ORDER,
+            PlayerAction::UpdateListOrder(order.into()),
+        )
+        .await;
+    }
+
+    pub(crate) async fn broadcast_latency(&self) {
+        let latency = self.listed_latency();
+        self.broadcast_list_update(UPDATE_LATENCY, PlayerAction::UpdateLatency(latency.into()))
+            .await;
+    }
+
+    async fn broadcast_list_update(&self, actions: i8, action: PlayerAction<'_>) {
+        self.living_entity
+            .entity
+            .world
+            .broadcast_packet_all(&CPlayerInfoUpdate::new(
+                actions,
+                &[pumpkin_protocol::client::play::Player {
+                    uuid: self.gameprofile.id,
+                    actions: vec![action],
+                }],
+            ))
+            .await;
+    }
+}
@@ -0,0 +1,97 @@
+//! Titles in the middle of the screen and messages in the action bar above the hotbar.
+
+use pumpkin_core::text::TextComponent;
+use pumpkin_protocol::{
+    client::play::{CActionBar, CClearTitle, CSubtitle, CTitleAnimation, CTitleText},
+    packet_encoder::PreparedPacket,
+};
+
+use super::player::Player;
+
+/// How long a title fades in, stays and fades out, in ticks
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TitleTimes {
+    pub fade_in: i32,
+    pub stay: i32,
+    pub fade_out: i32,
+}
+
+impl Default for TitleTimes {
+    /// The timings the client uses until they are changed
+    fn default() -> Self {
+        Self {
+            fade_in: 10,
+            stay: 70,
+            fade_out: 20,
+        }
+    }
+}
+
+impl TitleTimes {
+    #[must_use]
+    pub const fn new(fade_in: i32, stay: i32, fade_out: i32) -> Self {
+        Self {
+            fade_in,
+            stay,
+            fade_out,
+        }
+    }
+
+    pub(crate) fn to_packet(self) -> CTitleAnimation {
+        CTitleAnimation::new(self.fade_in, self.stay, self.fade_out)
+    }
+}
+
+/// The packets showing a title, sent to a single player or to a
+/// [`PlayerSet`](super::player_set::PlayerSet)
+pub(crate) fn title_packets(
+    title: TextComponent<'_>,
+    subtitle: Option<TextComponent<'_>>,
+    times: Option<TitleTimes>,
+) -> Vec<PreparedPacket> {
+    let mut packets = Vec::with_capacity(3);
+    if let Some(times) = times {
+        packets.push(PreparedPacket::new(&times.to_packet()));
+    }
+    // the client only shows a subtitle once the title arrives
+    if let Some(subtitle) = subtitle {
+        packets.push(PreparedPacket::new(&CSubtitle::new(subtitle)));
+    }
+    packets.push(PreparedPacket::new(&CTitleText::new(title)));
+    packets
+}
+
+impl Player {
+    /// Shows the title, and the subtitle below it if given.
+    ///
+    /// Without times the ones sent last are used, the client keeps them until the title is reset.
+    pub async fn send_title(
+        &self,
+        title: TextComponent<'_>,
+        subtitle: Option<TextComponent<'_>>,
+        times: Option<TitleTimes>,
+    ) {
+        for packet in title_packets(title, subtitle, times) {
+            self.client.send_prepared_packet(&packet).await;
+        }
+    }
+
+    /// Sets the subtitle, which is shown with the next title.
+    pub async fn send_subtitle(&self, subtitle: TextComponent<'_>) {
+        self.client.send_packet(&CSubtitle::new(subtitle)).await;
+    }
+
+    pub async fn set_title_times(&self, times: TitleTimes) {
+        self.client.send_packet(&times.to_packet()).await;
+    }
+
+    /// Hides the title, resetting also clears the subtitle and sets the times back to the default.
+    pub async fn clear_title(&self, reset: bool) {
+        self.client.send_packet(&CClearTitle::new(reset)).await;
+    }
+
+    /// Shows the text above the hotbar for a few seconds.
+    pub async fn send_action_bar(&self, text: TextComponent<'_>) {
+        self.client.send_packet(&CActionBar::new(text)).await;
+    }
+}
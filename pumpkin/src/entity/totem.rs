@@ -0,0 +1,68 @@
+//! The totem of undying, which saves a player holding it from dying.
+
+use pumpkin_entity::effect_type::EffectType;
+use pumpkin_inventory::player::PlayerInventory;
+use pumpkin_protocol::client::play::CEntityStatus;
+use pumpkin_world::item::{item_registry::get_item_name, ItemStack};
+
+use super::{effect::StatusEffect, living::LivingEntity};
+
+const TOTEM_OF_UNDYING: &str = "minecraft:totem_of_undying";
+/// The client shows the totem, its particles and plays its sound
+const TOTEM_STATUS: i8 = 35;
+/// The offhand slot of the player inventory
+pub(super) const OFFHAND_SLOT: usize = 45;
+
+fn is_totem(slot: &Option<ItemStack>) -> bool {
+    slot.as_ref()
+        .and_then(|stack| get_item_name(stack.item_id))
+        .is_some_and(|name| name == TOTEM_OF_UNDYING)
+}
+
+/// Uses up a totem in the main hand, or else in the offhand, returns whether there was one
+fn consume_totem(inventory: &mut PlayerInventory) -> bool {
+    let slot = if is_totem(inventory.held_item_mut()) {
+        inventory.held_item_mut()
+    } else {
+        match inventory.get_slot(OFFHAND_SLOT) {
+            Ok(slot) if is_totem(slot) => slot,
+            _ => return false,
+        }
+    };
+    if let Some(stack) = slot {
+        stack.item_count -= 1;
+        if stack.item_count == 0 {
+            *slot = None;
+        }
+    }
+    true
+}
+
+impl LivingEntity {
+    /// Saves the entity from dying if it is a player holding a totem of undying. The totem is used
+    /// up and the player gets back a bit of health with regeneration, absorption and fire
+    /// resistance like in vanilla
+    pub(super) async fn use_totem(&self) -> bool {
+        let world = &self.entity.world;
+        let Some(player) = world.get_player_by_entityid(self.entity.entity_id).await else {
+            return false;
+        };
+        if !consume_totem(&mut *player.inventory.lock().await) {
+            return false;
+        }
+        player.set_container_content(None).await;
+
+        self.set_health(1.0).await;
+        self.clear_effects().await;
+        self.add_effect(StatusEffect::new(EffectType::Regeneration, 1, 900))
+            .await;
+        self.add_effect(StatusEffect::new(EffectType::Absorption, 1, 100))
+            .await;
+        self.add_effect(StatusEffect::new(EffectType::FireResistance, 0, 800))
+            .await;
+        world
+            .broadcast_packet_all(&CEntityStatus::new(self.entity.entity_id, TOTEM_STATUS))
+            .await;
+        true
+    }
+}
@@ -0,0 +1,466 @@
+//! Riding: entities carrying their passengers, validating the movement a client sends for the
+//! vehicle it steers and finding a safe place to put passengers when they get off.
+//!
+//! Every entity can carry passengers, which can carry passengers of their own. The first
+//! passenger steers the vehicle if it is a player and the vehicle can be steered.
+
+use std::sync::{Arc, LazyLock};
+
+use parking_lot::RwLock;
+use pumpkin_api::{
+    event::entity::{EntityDismountEvent, EntityMountEvent},
+    Event,
+};
+use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_protocol::{client::play::CSetPassengers, VarInt};
+use pumpkin_world::pathfinding::PathNodeType;
+
+use crate::{
+    plugin::{self, EVENTS},
+    world::{player_chunker, World},
+};
+
+use super::{player::Player, Entity, EntityBase};
+
+/// The fastest a vehicle may fall per tick, vanilla's terminal velocity with some tolerance
+const MAX_VERTICAL_SPEED: f64 = 4.0;
+/// Passengers sit this far above the position of boats and minecarts
+const LOW_SEAT: f64 = 0.1;
+/// Of two passengers the first sits this far in front of the vehicle's middle, the second behind
+const FRONT_SEAT: f64 = 0.2;
+const BACK_SEAT: f64 = -0.6;
+
+static VEHICLE_LISTENERS: LazyLock<RwLock<Vec<Arc<dyn VehicleListener>>>> =
+    LazyLock::new(|| RwLock::new(Vec::new()));
+
+/// Lets plugins intercept vehicle movement.
+pub trait VehicleListener: Send + Sync {
+    /// Called before a vehicle controlled by the player is moved, returning false reverts the movement.
+    fn on_vehicle_move(&self, _player: &Player, _vehicle: &Riding, _to: Vector3<f64>) -> bool {
+        true
+    }
+
+    /// Called before the player is put at `position` after dismounting, which may be changed.
+    fn on_dismount(&self, _player: &Player, _vehicle: &Riding, _position: &mut Vector3<f64>) {}
+}
+
+/// Registers a listener which is called for every vehicle movement and dismount.
+pub fn register_vehicle_listener(listener: Arc<dyn VehicleListener>) {
+    VEHICLE_LISTENERS.write().push(listener);
+}
+
+pub(crate) fn vehicle_listeners() -> Vec<Arc<dyn VehicleListener>> {
+    VEHICLE_LISTENERS.read().clone()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VehicleKind {
+    Boat,
+    Horse,
+    Minecart,
+    /// Pigs and striders, which are steered with an item
+    Other,
+    /// Entities which only carry their passengers, like mobs ridden by other mobs
+    Passive,
+}
+
+impl VehicleKind {
+    /// Returns the kind of vehicle, or None if players can not ride the entity.
+    #[must_use]
+    pub const fn from_entity_type(entity_type: &EntityType) -> Option<Self> {
+        match entity_type {
+            EntityType::AcaciaBoat
+            | EntityType::AcaciaChestBoat
+            | EntityType::BambooRaft
+            | EntityType::BambooChestRaft
+            | EntityType::BirchBoat
+            | EntityType::BirchChestBoat
+            | EntityType::CherryBoat
+            | EntityType::CherryChestBoat
+            | EntityType::DarkOakBoat
+            | EntityType::DarkOakChestBoat
+            | EntityType::JungleBoat
+            | EntityType::JungleChestBoat
+            | EntityType::MangroveBoat
+            | EntityType::MangroveChestBoat
+            | EntityType::OakBoat
+            | EntityType::OakChestBoat
+            | EntityType::PaleOakBoat
+            | EntityType::PaleOakChestBoat
+            | EntityType::SpruceBoat
+            | EntityType::SpruceChestBoat => Some(Self::Boat),
+            EntityType::Horse
+            | EntityType::Donkey
+            | EntityType::Mule
+            | EntityType::SkeletonHorse
+            | EntityType::ZombieHorse
+            | EntityType::Camel
+            | EntityType::Llama
+            | EntityType::TraderLlama => Some(Self::Horse),
+            EntityType::Minecart
+            | EntityType::ChestMinecart
+            | EntityType::CommandBlockMinecart
+            | EntityType::FurnaceMinecart
+            | EntityType::HopperMinecart
+            | EntityType::SpawnerMinecart
+            | EntityType::TntMinecart => Some(Self::Minecart),
+            EntityType::Pig | EntityType::Strider => Some(Self::Other),
+            _ => None,
+        }
+    }
+
+    /// The most blocks the vehicle may move horizontally in a single tick.
+    ///
+    /// Boats are fastest on blue ice, horses when jumping, all limits leave room for lag.
+    #[must_use]
+    pub const fn max_speed(self) -> f64 {
+        match self {
+            Self::Boat => 4.0,
+            Self::Horse => 1.5,
+            Self::Minecart | Self::Other => 1.0,
+            Self::Passive => 0.0,
+        }
+    }
+}
+
+/// How many passengers fit on the entity, boats without a chest and camels carry two
+#[must_use]
+pub const fn max_passengers(entity_type: &EntityType) -> usize {
+    match entity_type {
+        EntityType::AcaciaBoat
+        | EntityType::BambooRaft
+        | EntityType::BirchBoat
+        | EntityType::CherryBoat
+        | EntityType::DarkOakBoat
+        | EntityType::JungleBoat
+        | EntityType::MangroveBoat
+        | EntityType::OakBoat
+        | EntityType::PaleOakBoat
+        | EntityType::SpruceBoat
+        | EntityType::Camel => 2,
+        _ => 1,
+    }
+}
+
+/// The vehicle a player is riding, vehicles it can't steer are [`VehicleKind::Passive`]
+#[derive(Clone, Debug)]
+pub struct Riding {
+    pub vehicle_id: EntityId,
+    pub kind: VehicleKind,
+    /// The last position of the vehicle we accepted
+    pub pos: Vector3<f64>,
+    pub yaw: f32,
+    pub pitch: f32,
+    /// Whether the left and right paddles of a boat are turning
+    pub paddles: (bool, bool),
+}
+
+impl Riding {
+    #[must_use]
+    pub const fn new(vehicle_id: EntityId, kind: VehicleKind, pos: Vector3<f64>) -> Self {
+        Self {
+            vehicle_id,
+            kind,
+            pos,
+            yaw: 0.0,
+            pitch: 0.0,
+            paddles: (false, false),
+        }
+    }
+
+    /// Returns whether the vehicle could have moved to `to` since the last accepted position.
+    #[must_use]
+    pub fn is_valid_move(&self, to: Vector3<f64>) -> bool {
+        let delta = to.sub(&self.pos);
+        let max_speed = self.kind.max_speed();
+        delta.x.mul_add(delta.x, delta.z * delta.z) <= max_speed * max_speed
+            && delta.y.abs() <= MAX_VERTICAL_SPEED
+    }
+}
+
+/// Finds a free spot with a floor next to the vehicle, like the vanilla dismount helper.
+///
+/// Falls back to the top of the vehicle if it is surrounded by blocks.
+pub async fn find_dismount_position(world: &World, vehicle_pos: Vector3<f64>) -> Vector3<f64> {
+    let origin = WorldPosition(Vector3::new(
+        vehicle_pos.x.floor() as i32,
+        vehicle_pos.y.floor() as i32,
+        vehicle_pos.z.floor() as i32,
+    ));
+    let map = world.collision_map(origin, 1, 1).await;
+
+    // sides first, then the corners, the vehicle's own column last
+    let mut offsets = Vec::with_capacity(27);
+    for (x, z) in [
+        (1, 0),
+        (-1, 0),
+        (0, 1),
+        (0, -1),
+        (1, 1),
+        (1, -1),
+        (-1, 1),
+        (-1, -1),
+        (0, 0),
+    ] {
+        // prefer the same height, then stepping up, then down
+        for y in [0, 1, -1] {
+            offsets.push(Vector3::new(x, y, z));
+        }
+    }
+
+    offsets
+        .into_iter()
+        .map(|offset| WorldPosition(origin.0.add(&offset)))
+        .find(|position| {
+            map.get(*position)
+                .is_some_and(|node| node.node_type == PathNodeType::Walkable)
+        })
+        .map_or_else(
+            || Vector3::new(vehicle_pos.x, vehicle_pos.y + 1.0, vehicle_pos.z),
+            |position| {
+                Vector3::new(
+                    f64::from(position.0.x) + 0.5,
+                    f64::from(position.0.y),
+                    f64::from(position.0.z) + 0.5,
+                )
+            },
+        )
+}
+
+/// A player or another entity of the world
+enum Rider {
+    Player(Arc<Player>),
+    Other(Arc<dyn EntityBase>),
+}
+
+impl Rider {
+    async fn find(world: &World, id: EntityId) -> Option<Self> {
+        match world.get_player_by_entityid(id).await {
+            Some(player) => Some(Self::Player(player)),
+            None => world
+                .entities
+                .lock()
+                .await
+                .get(&id)
+                .cloned()
+                .map(Self::Other),
+        }
+    }
+
+    fn entity(&self) -> &Entity {
+        match self {
+            Self::Player(player) => &player.living_entity.entity,
+            Self::Other(entity) => entity.get_entity(),
+        }
+    }
+}
+
+fn passenger_ids(passengers: &[EntityId]) -> Vec<VarInt> {
+    passengers.iter().map(|id| VarInt(*id)).collect()
+}
+
+/// Where the passenger at the index sits relative to the vehicle's position. Of two passengers
+/// the first sits in front of the other
+#[must_use]
+pub fn passenger_offset(vehicle: &Entity, index: usize, count: usize) -> Vector3<f64> {
+    let height = match VehicleKind::from_entity_type(&vehicle.entity_type) {
+        Some(VehicleKind::Boat | VehicleKind::Minecart) => LOW_SEAT,
+        _ => vehicle.bounding_box_size.load().height,
+    };
+    let forward = match (count, index) {
+        (0 | 1, _) => 0.0,
+        (_, 0) => FRONT_SEAT,
+        _ => BACK_SEAT,
+    };
+    let yaw = f64::from(vehicle.yaw.load()).to_radians();
+    Vector3::new(-yaw.sin() * forward, height, yaw.cos() * forward)
+}
+
+/// Whether the entity rides the vehicle, or something riding it
+async fn rides(world: &World, entity: EntityId, vehicle: &Entity) -> bool {
+    if vehicle.entity_id == entity {
+        return true;
+    }
+    let mut current = vehicle.vehicle_id.load();
+    while let Some(id) = current {
+        if id == entity {
+            return true;
+        }
+        current = match Rider::find(world, id).await {
+            Some(rider) => rider.entity().vehicle_id.load(),
+            None => None,
+        };
+    }
+    false
+}
+
+/// Puts the passenger on the vehicle, behind the passengers already riding it. Returns false if
+/// the vehicle is full, the passenger rides something already, the vehicle rides the passenger or
+/// a plugin cancelled it
+pub async fn mount(passenger: &Entity, vehicle: &Entity) -> bool {
+    let world = &passenger.world;
+    let full = vehicle.passengers.lock().len() >= max_passengers(&vehicle.entity_type);
+    if full
+        || passenger.vehicle_id.load().is_some()
+        || rides(world, passenger.entity_id, vehicle).await
+    {
+        return false;
+    }
+    let player = world.get_player_by_entityid(passenger.entity_id).await;
+    if EVENTS.has_listeners::<EntityMountEvent>() {
+        let event = plugin::fire(EntityMountEvent::new(
+            passenger.entity_id,
+            player.as_deref().map(plugin::player_info),
+            vehicle.entity_id,
+        ));
+        if event.is_cancelled() {
+            return false;
+        }
+    }
+    passenger.vehicle_id.store(Some(vehicle.entity_id));
+    let passengers = {
+        let mut passengers = vehicle.passengers.lock();
+        passengers.push(passenger.entity_id);
+        passenger_ids(&passengers)
+    };
+    if let Some(player) = &player {
+        let kind =
+            VehicleKind::from_entity_type(&vehicle.entity_type).unwrap_or(VehicleKind::Passive);
+        *player.vehicle.lock().await =
+            Some(Riding::new(vehicle.entity_id, kind, vehicle.pos.load()));
+    }
+    world
+        .broadcast_packet_all(&CSetPassengers::new(vehicle.entity_id.into(), &passengers))
+        .await;
+    position_passengers(vehicle).await;
+    true
+}
+
+/// Takes the passenger off its vehicle and puts it on a safe spot next to it. Returns false if it
+/// doesn't ride anything or a plugin cancelled it
+pub async fn dismount(passenger: &Entity) -> bool {
+    leave(passenger, true).await
+}
+
+/// Takes the passenger off its vehicle, `cancellable` whether plugins may keep it on
+async fn leave(passenger: &Entity, cancellable: bool) -> bool {
+    let Some(vehicle_id) = passenger.vehicle_id.load() else {
+        return false;
+    };
+    let world = &passenger.world;
+    let player = world.get_player_by_entityid(passenger.entity_id).await;
+    if cancellable && EVENTS.has_listeners::<EntityDismountEvent>() {
+        let event = plugin::fire(EntityDismountEvent::new(
+            passenger.entity_id,
+            player.as_deref().map(plugin::player_info),
+            vehicle_id,
+        ));
+        if event.is_cancelled() {
+            return false;
+        }
+    }
+    passenger.vehicle_id.store(None);
+    let riding = match &player {
+        Some(player) => player.vehicle.lock().await.take(),
+        None => None,
+    };
+    let vehicle = Rider::find(world, vehicle_id).await;
+    let vehicle_pos = vehicle.as_ref().map_or_else(
+        || passenger.pos.load(),
+        |vehicle| vehicle.entity().pos.load(),
+    );
+    if let Some(vehicle) = &vehicle {
+        let passengers = {
+            let mut passengers = vehicle.entity().passengers.lock();
+            passengers.retain(|id| *id != passenger.entity_id);
+            passenger_ids(&passengers)
+        };
+        world
+            .broadcast_packet_all(&CSetPassengers::new(vehicle_id.into(), &passengers))
+            .await;
+    }
+
+    let mut position = find_dismount_position(world, vehicle_pos).await;
+    match (&player, &riding) {
+        (Some(player), Some(riding)) => {
+            for listener in vehicle_listeners() {
+                listener.on_dismount(player, riding, &mut position);
+            }
+            player
+                .teleport(position, passenger.yaw.load(), passenger.pitch.load())
+                .await;
+        }
+        _ => passenger.set_pos(position.x, position.y, position.z),
+    }
+    position_passengers(passenger).await;
+    true
+}
+
+/// Lets the entity's passengers off and takes it off its vehicle, for entities leaving the world
+pub async fn detach(entity: &Entity) {
+    let passengers = entity.passengers.lock().clone();
+    for passenger in passengers {
+        if let Some(passenger) = Rider::find(&entity.world, passenger).await {
+            leave(passenger.entity(), false).await;
+        }
+    }
+    let Some(vehicle_id) = entity.vehicle_id.take() else {
+        return;
+    };
+    if let Some(player) = entity.world.get_player_by_entityid(entity.entity_id).await {
+        player.vehicle.lock().await.take();
+    }
+    let Some(vehicle) = Rider::find(&entity.world, vehicle_id).await else {
+        return;
+    };
+    let passengers = {
+        let mut passengers = vehicle.entity().passengers.lock();
+        passengers.retain(|id| *id != entity.entity_id);
+        passenger_ids(&passengers)
+    };
+    entity
+        .world
+        .broadcast_packet_all(&CSetPassengers::new(vehicle_id.into(), &passengers))
+        .await;
+}
+
+/// Where the passengers of the vehicle sit
+fn seats(vehicle: &Entity) -> Vec<(EntityId, Vector3<f64>)> {
+    let pos = vehicle.pos.load();
+    let passengers = vehicle.passengers.lock();
+    passengers
+        .iter()
+        .enumerate()
+        .map(|(index, id)| {
+            let offset = passenger_offset(vehicle, index, passengers.len());
+            (*id, pos.add(&offset))
+        })
+        .collect()
+}
+
+/// Moves the passengers of the vehicle onto their seats, and their passengers onto theirs
+pub async fn position_passengers(vehicle: &Entity) {
+    let mut pending = seats(vehicle);
+    while let Some((id, seat)) = pending.pop() {
+        let Some(passenger) = Rider::find(&vehicle.world, id).await else {
+            continue;
+        };
+        match &passenger {
+            Rider::Player(player) => {
+                player.living_entity.set_pos(seat.x, seat.y, seat.z);
+                player.movement_check.lock().reset(seat);
+                player_chunker::update_position(player).await;
+            }
+            Rider::Other(entity) => entity.get_entity().set_pos(seat.x, seat.y, seat.z),
+        }
+        pending.extend(seats(passenger.entity()));
+    }
+}
+
+/// The passengers of the vehicle, for a player which starts seeing it. `None` if nobody rides it
+#[must_use]
+pub fn passengers_of(vehicle: &Entity) -> Option<Vec<VarInt>> {
+    let passengers = vehicle.passengers.lock();
+    (!passengers.is_empty()).then(|| passenger_ids(&passengers))
+}
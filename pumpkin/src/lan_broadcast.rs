@@ -1,4 +1,5 @@
-use pumpkin_config::{ADVANCED_CONFIG, BASIC_CONFIG};
+use pumpkin_config::{runtime_config, ADVANCED_CONFIG};
+use pumpkin_core::text::TextComponent;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::time::Duration;
 use tokio::net::UdpSocket;
@@ -28,7 +29,9 @@ pub async fn start_lan_broadcast(bound_addr: SocketAddr) {
         .unwrap_or_default();
 
     if advanced_motd.is_empty() {
-        motd = BASIC_CONFIG.motd.replace('\n', " ");
+        motd = TextComponent::from_mini_message(&runtime_config().motd)
+            .to_legacy()
+            .replace('\n', " ");
         log::warn!("Using the server MOTD as the LAN broadcast MOTD. Note that the LAN broadcast MOTD does not support multiple lines, RGB colors, or gradients so consider defining it accordingly.");
     } else {
         motd = advanced_motd.clone();
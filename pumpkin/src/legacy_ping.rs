@@ -0,0 +1,47 @@
+//! Answers the server list ping of clients from before 1.7, see [`LegacyPing`].
+
+use std::time::Duration;
+
+use pumpkin_protocol::legacy_ping::{LegacyPing, LEGACY_PING_ID};
+use tokio::{io::AsyncWriteExt, net::TcpStream};
+
+use crate::server::Server;
+
+/// How long to wait for the rest of the ping, as it may come in a later segment
+const PING_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Looks at the first bytes of the connection, without consuming them.
+pub async fn detect(connection: &TcpStream) -> Option<LegacyPing> {
+    let mut buf = [0; 3];
+    let mut read = connection.peek(&mut buf).await.ok()?;
+    if read == 0 || buf[0] != LEGACY_PING_ID {
+        return None;
+    }
+    if read < buf.len() {
+        // 1.6 sends its plugin message right after, older clients send nothing more
+        tokio::time::sleep(PING_TIMEOUT).await;
+        read = connection.peek(&mut buf).await.ok()?;
+    }
+    LegacyPing::parse(&buf[..read])
+}
+
+/// Sends the status and closes the connection, like vanilla does.
+pub async fn respond(mut connection: TcpStream, ping: LegacyPing, server: &Server) {
+    let response = server.get_status().lock().await.get_legacy_status(ping);
+    // closing the socket with unread data would reset the connection instead
+    discard_pending(&connection);
+    if let Err(e) = connection.write_all(&response).await {
+        log::debug!("Failed to answer legacy ping: {e}");
+        return;
+    }
+    let _ = connection.shutdown().await;
+}
+
+fn discard_pending(connection: &TcpStream) {
+    let mut buf = [0; 256];
+    while let Ok(read) = connection.try_read(&mut buf) {
+        if read == 0 {
+            break;
+        }
+    }
+}
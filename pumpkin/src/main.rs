@@ -17,33 +17,42 @@
 #[cfg(target_os = "wasi")]
 compile_error!("Compiling for WASI targets is not supported!");
 
-use log::LevelFilter;
-
 use client::Client;
 use server::{ticker::Ticker, Server};
 use std::io::{self};
-use tokio::io::{AsyncBufReadExt, BufReader};
-#[cfg(not(unix))]
+#[cfg(not(any(unix, windows)))]
 use tokio::signal::ctrl_c;
 #[cfg(unix)]
 use tokio::signal::unix::{signal, SignalKind};
+#[cfg(windows)]
+use tokio::signal::windows::{ctrl_break, ctrl_c, ctrl_close, ctrl_shutdown};
 
 use std::sync::Arc;
 
 use crate::server::CURRENT_MC_VERSION;
+use pumpkin_api::event::player::PlayerJoinEvent;
 use pumpkin_config::{ADVANCED_CONFIG, BASIC_CONFIG};
 use pumpkin_core::text::{color::NamedColor, TextComponent};
 use pumpkin_protocol::CURRENT_MC_PROTOCOL;
 use rcon::RCONServer;
 use std::time::Instant;
 use sysinfo::{CpuRefreshKind, System};
+use world::pregen::Pregen;
 // Setup some tokens to allow us to identify which event is for which socket.
 
+pub mod bench_worldgen;
 pub mod client;
 pub mod command;
+pub mod console;
+pub mod convert;
+pub mod data;
 pub mod entity;
 pub mod error;
 pub mod lan_broadcast;
+pub mod legacy_ping;
+pub mod metrics;
+pub mod permission;
+pub mod plugin;
 pub mod proxy;
 pub mod query;
 pub mod rcon;
@@ -61,41 +70,6 @@ fn scrub_address(ip: &str) -> String {
     }
 }
 
-fn init_logger() {
-    use pumpkin_config::ADVANCED_CONFIG;
-    if ADVANCED_CONFIG.logging.enabled {
-        let mut logger = simple_logger::SimpleLogger::new();
-        logger = logger.with_timestamp_format(time::macros::format_description!(
-            "[year]-[month]-[day] [hour]:[minute]:[second]"
-        ));
-
-        if !ADVANCED_CONFIG.logging.timestamp {
-            logger = logger.without_timestamps();
-        }
-
-        if ADVANCED_CONFIG.logging.env {
-            logger = logger.env();
-        }
-
-        logger = logger.with_level(convert_logger_filter(ADVANCED_CONFIG.logging.level));
-
-        logger = logger.with_colors(ADVANCED_CONFIG.logging.color);
-        logger = logger.with_threads(ADVANCED_CONFIG.logging.threads);
-        logger.init().unwrap();
-    }
-}
-
-const fn convert_logger_filter(level: pumpkin_config::logging::LevelFilter) -> LevelFilter {
-    match level {
-        pumpkin_config::logging::LevelFilter::Off => LevelFilter::Off,
-        pumpkin_config::logging::LevelFilter::Error => LevelFilter::Error,
-        pumpkin_config::logging::LevelFilter::Warn => LevelFilter::Warn,
-        pumpkin_config::logging::LevelFilter::Info => LevelFilter::Info,
-        pumpkin_config::logging::LevelFilter::Debug => LevelFilter::Debug,
-        pumpkin_config::logging::LevelFilter::Trace => LevelFilter::Trace,
-    }
-}
-
 const CARGO_PKG_VERSION: &str = env!("CARGO_PKG_VERSION");
 const GIT_VERSION: &str = env!("GIT_VERSION");
 
@@ -172,22 +146,46 @@ fn log_system_info() {
     }
 }
 
+/// `--pregen <radius>` generates the chunks around the spawn instead of starting the server
+fn pregen_radius() -> Option<u32> {
+    let mut args = std::env::args().skip_while(|arg| arg != "--pregen").skip(1);
+    let radius = args.next()?;
+    let Ok(radius) = radius.parse() else {
+        log::error!("The pregeneration radius {radius} is not a number of chunks");
+        std::process::exit(1);
+    };
+    Some(radius)
+}
+
+/// Pregenerates the first world, or finishes the pregeneration a stopped server left behind
+async fn pregenerate(radius: u32) {
+    let server = Server::new();
+    let world = server
+        .worlds
+        .first()
+        .expect("There should always be atleast one world")
+        .clone();
+    let Some(pregen) =
+        Pregen::resume(world.clone()).or_else(|| Pregen::start(world.clone(), radius, None))
+    else {
+        return;
+    };
+    pregen.wait().await;
+    world.save_persistent_data().await;
+}
+
 #[tokio::main]
 #[expect(clippy::too_many_lines)]
 async fn main() -> io::Result<()> {
-    init_logger();
+    if ADVANCED_CONFIG.logging.enabled {
+        console::logger::init(&ADVANCED_CONFIG.logging).unwrap();
+    }
 
     // let rt = tokio::runtime::Builder::new_multi_thread()
     //     .enable_all()
     //     .build()
     //     .unwrap();
 
-    tokio::spawn(async {
-        setup_sighandler()
-            .await
-            .expect("Unable to setup signal handlers");
-    });
-
     // ensure rayon is built outside of tokio scope
     rayon::ThreadPoolBuilder::new().build_global().unwrap();
     let default_panic = std::panic::take_hook();
@@ -197,6 +195,15 @@ async fn main() -> io::Result<()> {
         std::process::exit(1);
     }));
 
+    if std::env::args().nth(1).as_deref() == Some("bench-worldgen") {
+        bench_worldgen::run(std::env::args().skip(2));
+        return Ok(());
+    }
+    if std::env::args().nth(1).as_deref() == Some("convert") {
+        convert::run(std::env::args().skip(2));
+        return Ok(());
+    }
+
     log::info!("Starting Pumpkin {CARGO_PKG_VERSION} ({GIT_VERSION}) for Minecraft {CURRENT_MC_VERSION} (Protocol {CURRENT_MC_PROTOCOL})",);
 
     log_system_info();
@@ -216,6 +223,11 @@ async fn main() -> io::Result<()> {
     log::info!("Report Issues on https://github.com/Snowiiii/Pumpkin/issues");
     log::info!("Join our Discord for community support https://discord.com/invite/wT8XjrjKkf");
 
+    if let Some(radius) = pregen_radius() {
+        pregenerate(radius).await;
+        return Ok(());
+    }
+
     let time = Instant::now();
 
     // Setup the TCP server socket.
@@ -231,13 +243,28 @@ async fn main() -> io::Result<()> {
     let rcon = ADVANCED_CONFIG.rcon.clone();
 
     let server = Arc::new(Server::new());
+    {
+        let server = server.clone();
+        tokio::spawn(async move {
+            setup_sighandler(&server)
+                .await
+                .expect("Unable to setup signal handlers");
+        });
+    }
+    server.plugins.load_all();
+    for world in &server.worlds {
+        Pregen::resume(world.clone());
+    }
+    if ADVANCED_CONFIG.multi_protocol.enabled {
+        client::translation::load_translators(&ADVANCED_CONFIG.multi_protocol);
+    }
     let mut ticker = Ticker::new(BASIC_CONFIG.tps);
 
     log::info!("Started Server took {}ms", time.elapsed().as_millis());
     log::info!("You now can connect to the server, Listening on {}", addr);
 
     if use_console {
-        setup_console(server.clone());
+        console::start(server.clone());
     }
     if rcon.enabled {
         let server = server.clone();
@@ -256,6 +283,21 @@ async fn main() -> io::Result<()> {
         tokio::spawn(lan_broadcast::start_lan_broadcast(addr));
     }
 
+    if ADVANCED_CONFIG.metrics.enabled {
+        tokio::spawn(metrics::start_metrics_exporter(server.clone()));
+    }
+
+    if ADVANCED_CONFIG.watchdog.enabled {
+        server::watchdog::spawn(server.clone(), &ADVANCED_CONFIG.watchdog);
+    }
+
+    if ADVANCED_CONFIG.backup.enabled {
+        tokio::spawn(server::backup::run_scheduled(
+            server.clone(),
+            &ADVANCED_CONFIG.backup,
+        ));
+    }
+
     {
         let server = server.clone();
         tokio::spawn(async move {
@@ -266,12 +308,24 @@ async fn main() -> io::Result<()> {
     let mut master_client_id: u16 = 0;
     loop {
         // Asynchronously wait for an inbound socket.
-        let (connection, address) = listener.accept().await?;
+        let (connection, address) = tokio::select! {
+            accepted = listener.accept() => accepted?,
+            () = server.shutdown.requested() => break,
+        };
 
         if let Err(e) = connection.set_nodelay(true) {
             log::warn!("failed to set TCP_NODELAY {e}");
         }
 
+        // behind a proxy every connection comes from its IP, the client's is counted once forwarded
+        if !ADVANCED_CONFIG.proxy.enabled && !server.throttle.allow_connection(address.ip()) {
+            log::debug!(
+                "Closed connection from {}, it is rate limited",
+                scrub_address(&format!("{address}"))
+            );
+            continue;
+        }
+
         let id = master_client_id;
         master_client_id = master_client_id.wrapping_add(1);
 
@@ -281,16 +335,22 @@ async fn main() -> io::Result<()> {
             id
         );
 
-        let client = Arc::new(Client::new(connection, addr, id));
-
         let server = server.clone();
         tokio::spawn(async move {
+            let _connection_guard = server.shutdown.track_connection();
+            // clients before 1.7 ping with a different protocol
+            if let Some(ping) = legacy_ping::detect(&connection).await {
+                log::debug!("Legacy ping from {}", scrub_address(&format!("{address}")));
+                legacy_ping::respond(connection, ping, &server).await;
+                return;
+            }
+            let client = Arc::new(Client::new(connection, address, id));
             while !client.closed.load(std::sync::atomic::Ordering::Relaxed)
                 && !client
                     .make_player
                     .load(std::sync::atomic::Ordering::Relaxed)
             {
-                let open = client.poll().await;
+                let open = client.poll(&server).await;
                 if open {
                     client.process_packets(&server).await;
                 };
@@ -301,8 +361,15 @@ async fn main() -> io::Result<()> {
             {
                 let (player, world) = server.add_player(client).await;
                 world
-                    .spawn_player(&BASIC_CONFIG, player.clone(), &server.command_dispatcher)
+                    .spawn_player(
+                        &BASIC_CONFIG,
+                        player.clone(),
+                        &*server.command_dispatcher().await,
+                    )
                     .await;
+                plugin::fire(PlayerJoinEvent {
+                    player: plugin::player_info(&player),
+                });
 
                 // poll Player
                 while !player
@@ -310,75 +377,80 @@ async fn main() -> io::Result<()> {
                     .closed
                     .load(core::sync::atomic::Ordering::Relaxed)
                 {
-                    let open = player.client.poll().await;
+                    let open = player.client.poll(&server).await;
                     if open {
                         player.process_packets(&server).await;
                     };
                 }
                 log::debug!("Cleaning up player for id {}", id);
                 player.remove().await;
-                server.remove_player().await;
+                server.remove_player(&player).await;
             }
         });
     }
+
+    server.stop().await;
+    std::process::exit(0);
 }
 
-fn handle_interrupt() {
+/// The first signal stops the server gracefully, another one right away
+fn handle_interrupt(server: &Server, interrupted: &mut bool) {
+    if *interrupted {
+        log::warn!("Received another interrupt signal; stopping server now");
+        std::process::exit(1);
+    }
+    *interrupted = true;
     log::warn!(
         "{}",
         TextComponent::text("Received interrupt signal; stopping server...")
             .color_named(NamedColor::Red)
             .to_pretty_console()
     );
-    std::process::exit(0);
+    server.shutdown.request();
 }
 
-// Non-UNIX Ctrl-C handling
-#[cfg(not(unix))]
-async fn setup_sighandler() -> io::Result<()> {
-    if ctrl_c().await.is_ok() {
-        handle_interrupt();
+// Windows console events
+#[cfg(windows)]
+async fn setup_sighandler(server: &Server) -> io::Result<()> {
+    let mut ctrl_c = ctrl_c()?;
+    let mut ctrl_break = ctrl_break()?;
+    let mut ctrl_close = ctrl_close()?;
+    let mut ctrl_shutdown = ctrl_shutdown()?;
+    let mut interrupted = false;
+    loop {
+        tokio::select! {
+            _ = ctrl_c.recv() => {}
+            _ = ctrl_break.recv() => {}
+            _ = ctrl_close.recv() => {}
+            _ = ctrl_shutdown.recv() => {}
+        }
+        handle_interrupt(server, &mut interrupted);
     }
-
-    Ok(())
 }
 
-// Unix signal handling
-#[cfg(unix)]
-async fn setup_sighandler() -> io::Result<()> {
-    if signal(SignalKind::interrupt())?.recv().await.is_some() {
-        handle_interrupt();
-    }
-
-    if signal(SignalKind::hangup())?.recv().await.is_some() {
-        handle_interrupt();
-    }
-
-    if signal(SignalKind::terminate())?.recv().await.is_some() {
-        handle_interrupt();
+// Ctrl-C handling on other platforms
+#[cfg(not(any(unix, windows)))]
+async fn setup_sighandler(server: &Server) -> io::Result<()> {
+    let mut interrupted = false;
+    loop {
+        ctrl_c().await?;
+        handle_interrupt(server, &mut interrupted);
     }
-
-    Ok(())
 }
 
-fn setup_console(server: Arc<Server>) {
-    tokio::spawn(async move {
-        let stdin = tokio::io::stdin();
-        let mut reader = BufReader::new(stdin);
-        loop {
-            let mut out = String::new();
-
-            reader
-                .read_line(&mut out)
-                .await
-                .expect("Failed to read console line");
-
-            if !out.is_empty() {
-                let dispatcher = server.command_dispatcher.clone();
-                dispatcher
-                    .handle_command(&mut command::CommandSender::Console, &server, &out)
-                    .await;
-            }
+// Unix signal handling
+#[cfg(unix)]
+async fn setup_sighandler(server: &Server) -> io::Result<()> {
+    let mut interrupt = signal(SignalKind::interrupt())?;
+    let mut hangup = signal(SignalKind::hangup())?;
+    let mut terminate = signal(SignalKind::terminate())?;
+    let mut interrupted = false;
+    loop {
+        tokio::select! {
+            _ = interrupt.recv() => {}
+            _ = hangup.recv() => {}
+            _ = terminate.recv() => {}
         }
-    });
+        handle_interrupt(server, &mut interrupted);
+    }
 }
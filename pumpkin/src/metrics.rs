@@ -0,0 +1,263 @@
+//! Serves the server's health in the Prometheus text format, see
+//! <https://prometheus.io/docs/instrumenting/exposition_formats/>.
+
+use std::{collections::BTreeMap, fmt::Write as _, sync::Arc};
+
+use pumpkin_config::ADVANCED_CONFIG;
+use pumpkin_world::level::Level;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+use crate::server::{
+    metrics::{PACKETS, TICK_BUCKETS},
+    Server,
+};
+
+/// Requests larger than this are not metrics scrapes
+const MAX_REQUEST: usize = 8 * 1024;
+
+pub async fn start_metrics_exporter(server: Arc<Server>) {
+    let config = &ADVANCED_CONFIG.metrics;
+    let listener = match TcpListener::bind(config.address).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            log::error!(
+                "Couldn't start the metrics endpoint on {}: {err}",
+                config.address
+            );
+            return;
+        }
+    };
+    log::info!(
+        "Serving metrics on http://{}{}",
+        config.address,
+        config.path
+    );
+
+    loop {
+        let connection = match listener.accept().await {
+            Ok((connection, _)) => connection,
+            Err(err) => {
+                log::debug!("Failed to accept a metrics connection: {err}");
+                continue;
+            }
+        };
+        let server = server.clone();
+        tokio::spawn(async move {
+            if let Err(err) = handle_connection(connection, &server).await {
+                log::debug!("Failed to serve metrics: {err}");
+            }
+        });
+    }
+}
+
+async fn handle_connection(mut connection: TcpStream, server: &Server) -> std::io::Result<()> {
+    let mut request = Vec::new();
+    let mut buf = [0; 1024];
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        let read = connection.read(&mut buf).await?;
+        if read == 0 || request.len() + read > MAX_REQUEST {
+            return Ok(());
+        }
+        request.extend_from_slice(&buf[..read]);
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let mut request_line = request.lines().next().unwrap_or_default().split(' ');
+    let (method, path) = (request_line.next(), request_line.next());
+    // the query string is ignored, Prometheus doesn't send one by default
+    let path = path.map(|path| path.split('?').next().unwrap_or_default());
+
+    let response = if method != Some("GET") {
+        response(
+            "405 Method Not Allowed",
+            "text/plain",
+            "Only GET is allowed\n",
+        )
+    } else if path == Some(ADVANCED_CONFIG.metrics.path.as_str()) {
+        response("200 OK", "text/plain; version=0.0.4", &render(server).await)
+    } else {
+        response("404 Not Found", "text/plain", "Not found\n")
+    };
+    connection.write_all(response.as_bytes()).await?;
+    connection.shutdown().await
+}
+
+fn response(status: &str, content_type: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+/// Writes metrics in the text format
+#[derive(Default)]
+struct MetricsWriter {
+    out: String,
+}
+
+impl MetricsWriter {
+    fn header(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.out, "# HELP {name} {help}");
+        let _ = writeln!(self.out, "# TYPE {name} {kind}");
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: impl std::fmt::Display) {
+        self.out.push_str(name);
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(label, value)| format!("{label}=\"{}\"", escape(value)))
+                .collect();
+            let _ = write!(self.out, "{{{}}}", labels.join(","));
+        }
+        let _ = writeln!(self.out, " {value}");
+    }
+
+    fn single(&mut self, name: &str, kind: &str, help: &str, value: impl std::fmt::Display) {
+        self.header(name, kind, help);
+        self.sample(name, &[], value);
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+async fn render(server: &Server) -> String {
+    let mut metrics = MetricsWriter::default();
+
+    let histogram = server.tick_metrics.histogram();
+    let name = "pumpkin_tick_duration_seconds";
+    metrics.header(name, "histogram", "How long ticks took");
+    for (bound, count) in TICK_BUCKETS.iter().zip(histogram.buckets) {
+        metrics.sample(
+            &format!("{name}_bucket"),
+            &[("le", &bound.to_string())],
+            count,
+        );
+    }
+    metrics.sample(
+        &format!("{name}_bucket"),
+        &[("le", "+Inf")],
+        histogram.count,
+    );
+    metrics.sample(&format!("{name}_sum"), &[], histogram.sum);
+    metrics.sample(&format!("{name}_count"), &[], histogram.count);
+
+    metrics.single(
+        "pumpkin_mspt",
+        "gauge",
+        "Average milliseconds per tick of the recent ticks",
+        server.tick_metrics.mspt(),
+    );
+    metrics.single(
+        "pumpkin_tps",
+        "gauge",
+        "Average ticks per second of the recent ticks",
+        server.tick_metrics.tps(),
+    );
+    metrics.single(
+        "pumpkin_players_online",
+        "gauge",
+        "Players on the server",
+        server.get_player_count().await,
+    );
+    metrics.single(
+        "pumpkin_packets_received_total",
+        "counter",
+        "Packets received from clients",
+        PACKETS.received(),
+    );
+    metrics.single(
+        "pumpkin_packets_sent_total",
+        "counter",
+        "Packets sent to clients",
+        PACKETS.sent(),
+    );
+
+    render_worlds(server, &mut metrics).await;
+    metrics.out
+}
+
+/// The name, help and value of a metric of every world
+type LevelMetric = (&'static str, &'static str, fn(&Level) -> usize);
+
+async fn render_worlds(server: &Server, metrics: &mut MetricsWriter) {
+    let mut entities = Vec::with_capacity(server.worlds.len());
+    for world in &server.worlds {
+        let mut by_type = BTreeMap::new();
+        by_type.insert(
+            "minecraft:player".to_string(),
+            world.current_players.lock().await.len(),
+        );
+        for entity in world.entities.lock().await.values() {
+            let name = format!("minecraft:{}", entity.get_entity().entity_type.name());
+            *by_type.entry(name).or_insert(0) += 1;
+        }
+        entities.push(by_type);
+    }
+
+    let gauges: [LevelMetric; 4] = [
+        ("pumpkin_loaded_chunks", "Chunks in memory", |level| {
+            level.loaded_chunk_count()
+        }),
+        (
+            "pumpkin_chunk_queue",
+            "Chunks waiting to be read or generated",
+            |level| level.pending_chunk_count(),
+        ),
+        (
+            "pumpkin_chunk_cache_bytes",
+            "Approximate memory of the blocks of the loaded chunks",
+            |level| level.loaded_chunk_memory(),
+        ),
+        (
+            "pumpkin_idle_chunks",
+            "Loaded chunks no player watches, which are unloaded first",
+            |level| level.idle_chunk_count(),
+        ),
+    ];
+    let counters: [LevelMetric; 3] = [
+        (
+            "pumpkin_chunk_cache_hits_total",
+            "Chunks which were requested while they were loaded",
+            |level| level.chunk_cache_stats().hits,
+        ),
+        (
+            "pumpkin_chunk_cache_misses_total",
+            "Chunks which had to be read or generated",
+            |level| level.chunk_cache_stats().misses,
+        ),
+        (
+            "pumpkin_chunk_cache_evictions_total",
+            "Chunks unloaded because the loaded chunks exceeded the memory budget",
+            |level| level.chunk_cache_stats().evictions,
+        ),
+    ];
+    for (kind, level_metrics) in [("gauge", &gauges[..]), ("counter", &counters[..])] {
+        for (name, help, value) in level_metrics {
+            metrics.header(name, kind, help);
+            for world in &server.worlds {
+                metrics.sample(name, &[("world", world.level.name())], value(&world.level));
+            }
+        }
+    }
+
+    let name = "pumpkin_entities";
+    metrics.header(name, "gauge", "Entities by world and type");
+    for (world, by_type) in server.worlds.iter().zip(entities) {
+        for (entity_type, count) in by_type {
+            metrics.sample(
+                name,
+                &[("world", world.level.name()), ("type", &entity_type)],
+                count,
+            );
+        }
+    }
+}
@@ -0,0 +1,38 @@
+//! Permissions are hierarchical nodes like `pumpkin.command.gamemode.other`.
+//!
+//! Nodes are resolved by the [`Permissions`] services, the highest priority first. The server
+//! provides the groups and users of the `permissions.toml` with the lowest priority, plugins
+//! register their own service to decide before it.
+//! When no service mentions a node, the player's op level decides.
+
+use std::sync::Arc;
+
+use pumpkin_api::{service::Permissions, ServicePriority, ServiceRegistry};
+
+use crate::{
+    entity::player::{PermissionLvl, Player},
+    plugin::SERVICES,
+};
+
+pub mod toml_provider;
+
+use toml_provider::TomlPermissionProvider;
+
+const OWNER: &str = "pumpkin";
+
+/// Provides the permissions of the `permissions.toml`, which plugins can replace with a higher
+/// priority
+pub fn register_builtin(services: &ServiceRegistry) -> Arc<TomlPermissionProvider> {
+    let permissions = Arc::new(TomlPermissionProvider::load());
+    services.register::<dyn Permissions>(OWNER, ServicePriority::Lowest, permissions.clone());
+    permissions
+}
+
+/// Returns whether the player has the node, falling back to the op level `default_lvl` if no entry mentions it.
+pub fn has_permission(player: &Player, node: &str, default_lvl: PermissionLvl) -> bool {
+    SERVICES
+        .all::<dyn Permissions>()
+        .iter()
+        .find_map(|permissions| permissions.resolve(player.gameprofile.id, node))
+        .unwrap_or_else(|| player.permission_lvl() as i8 >= default_lvl as i8)
+}
@@ -0,0 +1,204 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use parking_lot::RwLock;
+use pumpkin_api::service;
+use pumpkin_core::permission::PermissionSet;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// The group every player is in
+const DEFAULT_GROUP: &str = "default";
+
+/// The layout of the `permissions.toml`
+///
+/// ```toml
+/// [groups.default]
+/// permissions = ["pumpkin.command.help"]
+///
+/// [groups.moderator]
+/// inherits = ["default"]
+/// permissions = ["pumpkin.command.*", "-pumpkin.command.stop"]
+///
+/// [users.069a79f4-44e9-4726-a5be-fca90e38aaf5]
+/// groups = ["moderator"]
+/// permissions = ["pumpkin.command.stop"]
+/// ```
+#[derive(Deserialize, Serialize, Default)]
+#[serde(default)]
+struct PermissionFile {
+    groups: HashMap<String, GroupEntry>,
+    users: HashMap<Uuid, UserEntry>,
+}
+
+#[derive(Deserialize, Serialize, Default)]
+#[serde(default)]
+struct GroupEntry {
+    inherits: Vec<String>,
+    permissions: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Default)]
+#[serde(default)]
+struct UserEntry {
+    groups: Vec<String>,
+    permissions: Vec<String>,
+}
+
+struct Group {
+    inherits: Vec<String>,
+    permissions: PermissionSet,
+}
+
+struct User {
+    groups: Vec<String>,
+    permissions: PermissionSet,
+}
+
+#[derive(Default)]
+struct Permissions {
+    groups: HashMap<String, Group>,
+    users: HashMap<Uuid, User>,
+}
+
+impl From<PermissionFile> for Permissions {
+    fn from(file: PermissionFile) -> Self {
+        Self {
+            groups: file
+                .groups
+                .into_iter()
+                .map(|(name, group)| {
+                    let permissions =
+                        PermissionSet::new(group.permissions.iter().map(String::as_str));
+                    (
+                        name,
+                        Group {
+                            inherits: group.inherits,
+                            permissions,
+                        },
+                    )
+                })
+                .collect(),
+            users: file
+                .users
+                .into_iter()
+                .map(|(id, user)| {
+                    let permissions =
+                        PermissionSet::new(user.permissions.iter().map(String::as_str));
+                    (
+                        id,
+                        User {
+                            groups: user.groups,
+                            permissions,
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Permissions {
+    fn resolve_group<'a>(
+        &'a self,
+        name: &'a str,
+        node: &str,
+        visited: &mut Vec<&'a str>,
+    ) -> Option<bool> {
+        // inheritance cycles would otherwise never end
+        if visited.contains(&name) {
+            return None;
+        }
+        visited.push(name);
+        let group = self.groups.get(name)?;
+        group.permissions.resolve(node).or_else(|| {
+            group
+                .inherits
+                .iter()
+                .find_map(|parent| self.resolve_group(parent, node, visited))
+        })
+    }
+
+    /// The user's own entries win over their groups, the groups over the default group.
+    fn resolve(&self, id: Uuid, node: &str) -> Option<bool> {
+        let mut visited = Vec::new();
+        if let Some(user) = self.users.get(&id) {
+            if let Some(granted) = user.permissions.resolve(node) {
+                return Some(granted);
+            }
+            if let Some(granted) = user
+                .groups
+                .iter()
+                .find_map(|group| self.resolve_group(group, node, &mut visited))
+            {
+                return Some(granted);
+            }
+        }
+        self.resolve_group(DEFAULT_GROUP, node, &mut visited)
+    }
+}
+
+/// Resolves permissions from groups and users stored in the `permissions.toml`
+pub struct TomlPermissionProvider {
+    permissions: RwLock<Permissions>,
+}
+
+impl TomlPermissionProvider {
+    const PATH: &str = "permissions.toml";
+
+    #[must_use]
+    pub fn load() -> Self {
+        Self {
+            permissions: RwLock::new(Self::read_file().into()),
+        }
+    }
+
+    /// Reads the `permissions.toml` again
+    pub fn reload(&self) {
+        *self.permissions.write() = Self::read_file().into();
+    }
+
+    fn read_file() -> PermissionFile {
+        let path = Path::new(Self::PATH);
+        if !path.exists() {
+            let mut file = PermissionFile::default();
+            file.groups
+                .insert(DEFAULT_GROUP.to_string(), GroupEntry::default());
+            match toml::to_string(&file) {
+                Ok(content) => {
+                    if let Err(err) = fs::write(path, content) {
+                        log::warn!(
+                            "Couldn't write default permissions to {}. Reason: {err}",
+                            path.display()
+                        );
+                    }
+                }
+                Err(err) => log::warn!("Couldn't serialize default permissions. Reason: {err}"),
+            }
+            return file;
+        }
+
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) => {
+                log::error!(
+                    "Couldn't read permissions at {}. Reason: {err}",
+                    path.display()
+                );
+                return PermissionFile::default();
+            }
+        };
+        toml::from_str(&content).unwrap_or_else(|err| {
+            log::error!(
+                "Couldn't parse permissions at {}, only op levels are used. Reason: {err}",
+                path.display()
+            );
+            PermissionFile::default()
+        })
+    }
+}
+
+impl service::Permissions for TomlPermissionProvider {
+    fn resolve(&self, player: Uuid, node: &str) -> Option<bool> {
+        self.permissions.read().resolve(player, node)
+    }
+}
@@ -0,0 +1,347 @@
+//! Commands of native plugins, see [`pumpkin_api::command`].
+//!
+//! Plugins register commands whenever they want, so changes are queued and applied on the next
+//! tick, when the command tree is sent to every player again.
+
+use std::{collections::HashMap, sync::Arc};
+
+use async_trait::async_trait;
+use num_traits::FromPrimitive;
+use parking_lot::Mutex;
+use pumpkin_api::{
+    command::{
+        ArgumentType, ArgumentValue, Command as PluginCommand, CommandContext, CommandHandler,
+        CommandNode, CommandSource, NodeKind, Permission, SuggestionProvider,
+    },
+    CommandRegistry,
+};
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+use pumpkin_protocol::client::play::{
+    CommandSuggestion, ProtoCmdArgParser, ProtoCmdArgSuggestionType,
+};
+
+use crate::{
+    command::{
+        args::{
+            arg_bool::BoolArgConsumer,
+            arg_bounded_num::{BoundedNumArgumentConsumer, Number},
+            arg_message::MsgArgConsumer,
+            arg_players::PlayersArgumentConsumer,
+            arg_postition_block::BlockPosArgumentConsumer,
+            arg_simple::SimpleArgConsumer,
+            Arg, ArgumentConsumer, ConsumedArgs, GetClientSideArgParser,
+            SplitSingleWhitespaceIncludingEmptyParts,
+        },
+        dispatcher::{CommandDispatcher, CommandError},
+        tree::{CommandTree, RawArgs, Shared},
+        tree_builder::{argument_owned, literal_owned, require_owned, NonLeafNodeBuilder},
+        CommandExecutor, CommandSender,
+    },
+    entity::player::PermissionLvl,
+    server::Server,
+};
+
+use super::player_info;
+
+enum CommandChange {
+    Register(String, PluginCommand),
+    Unregister(String, String),
+    UnregisterAll(String),
+}
+
+/// Queues the command changes of plugins until the next tick
+#[derive(Default)]
+pub struct PluginCommandQueue {
+    changes: Mutex<Vec<CommandChange>>,
+    /// The registered commands and the plugin they belong to
+    owners: Mutex<HashMap<String, String>>,
+}
+
+impl CommandRegistry for PluginCommandQueue {
+    fn register(&self, owner: &str, command: PluginCommand) {
+        self.changes
+            .lock()
+            .push(CommandChange::Register(owner.to_string(), command));
+    }
+
+    fn unregister(&self, owner: &str, name: &str) {
+        self.changes.lock().push(CommandChange::Unregister(
+            owner.to_string(),
+            name.to_string(),
+        ));
+    }
+}
+
+impl PluginCommandQueue {
+    /// Unregisters every command of the plugin on the next tick
+    pub fn unregister_all(&self, owner: &str) {
+        self.changes
+            .lock()
+            .push(CommandChange::UnregisterAll(owner.to_string()));
+    }
+
+    /// Applies the queued changes and resends the command tree if anything changed
+    pub async fn apply(&self, server: &Server) {
+        let changes = std::mem::take(&mut *self.changes.lock());
+        if changes.is_empty() {
+            return;
+        }
+        server
+            .update_commands(|dispatcher| {
+                let mut owners = self.owners.lock();
+                for change in changes {
+                    match change {
+                        CommandChange::Register(owner, command) => {
+                            register(dispatcher, &mut owners, owner, command);
+                        }
+                        CommandChange::Unregister(owner, name) => {
+                            if owners.get(&name) == Some(&owner) {
+                                owners.remove(&name);
+                                dispatcher.unregister(&name);
+                            }
+                        }
+                        CommandChange::UnregisterAll(owner) => {
+                            owners.retain(|name, command_owner| {
+                                if *command_owner != owner {
+                                    return true;
+                                }
+                                dispatcher.unregister(name);
+                                false
+                            });
+                        }
+                    }
+                }
+            })
+            .await;
+    }
+}
+
+fn register(
+    dispatcher: &mut CommandDispatcher<'static>,
+    owners: &mut HashMap<String, String>,
+    owner: String,
+    command: PluginCommand,
+) {
+    match owners.get(&command.name) {
+        // plugins replace their own commands
+        Some(command_owner) if *command_owner == owner => {
+            dispatcher.unregister(&command.name);
+        }
+        Some(command_owner) => {
+            log::warn!(
+                "Plugin {owner} can't register /{}, {command_owner} already did",
+                command.name
+            );
+            return;
+        }
+        None if dispatcher.commands.contains_key(command.name.as_str()) => {
+            log::warn!(
+                "Plugin {owner} can't register /{}, it already exists",
+                command.name
+            );
+            return;
+        }
+        None => {}
+    }
+    let name = command.name.clone();
+    dispatcher.register(build_tree(command));
+    owners.insert(name, owner);
+}
+
+fn build_tree(command: PluginCommand) -> CommandTree<'static> {
+    let mut names = vec![command.name];
+    names.extend(command.aliases);
+    let mut tree = CommandTree::new_owned(names, command.description);
+
+    let executor = command
+        .handler
+        .map(|handler| Arc::new(PluginCommandExecutor { handler }));
+    if let Some(permission) = &command.permission {
+        let mut root = requirement(permission);
+        for child in command.children {
+            root = root.with_child(build_node(child));
+        }
+        if let Some(executor) = executor {
+            root = root.execute_owned(executor);
+        }
+        tree = tree.with_child(root);
+    } else {
+        for child in command.children {
+            tree = tree.with_child(build_node(child));
+        }
+        if let Some(executor) = executor {
+            tree = tree.execute_owned(executor);
+        }
+    }
+    tree
+}
+
+fn requirement(permission: &Permission) -> NonLeafNodeBuilder<'static> {
+    let node = permission.node.clone();
+    let level = PermissionLvl::from_u8(permission.level).unwrap_or(PermissionLvl::Four);
+    require_owned(Arc::new(move |sender: &CommandSender| {
+        sender.has_permission(&node, level)
+    }))
+}
+
+fn build_node(node: CommandNode) -> NonLeafNodeBuilder<'static> {
+    let mut builder = match node.kind {
+        NodeKind::Literal(name) => literal_owned(name),
+        NodeKind::Argument {
+            name,
+            argument: argument_type,
+            suggestions,
+        } => {
+            let consumer = consumer(&argument_type);
+            let consumer: Shared<'static, dyn ArgumentConsumer> = match suggestions {
+                Some(provider) => Shared::Owned(Arc::new(SuggestingConsumer {
+                    inner: consumer,
+                    provider,
+                })),
+                None => consumer,
+            };
+            argument_owned(name, consumer)
+        }
+    };
+    for child in node.children {
+        builder = builder.with_child(build_node(child));
+    }
+    if let Some(handler) = node.handler {
+        builder = builder.execute_owned(Arc::new(PluginCommandExecutor { handler }));
+    }
+    match &node.permission {
+        Some(permission) => requirement(permission).with_child(builder),
+        None => builder,
+    }
+}
+
+fn consumer(argument_type: &ArgumentType) -> Shared<'static, dyn ArgumentConsumer> {
+    match *argument_type {
+        ArgumentType::Bool => Shared::Borrowed(&BoolArgConsumer),
+        ArgumentType::Integer { min, max } => {
+            let mut consumer = BoundedNumArgumentConsumer::<i32>::new();
+            if let Some(min) = min {
+                consumer = consumer.min(min);
+            }
+            if let Some(max) = max {
+                consumer = consumer.max(max);
+            }
+            Shared::Owned(Arc::new(consumer))
+        }
+        ArgumentType::Double { min, max } => {
+            let mut consumer = BoundedNumArgumentConsumer::<f64>::new();
+            if let Some(min) = min {
+                consumer = consumer.min(min);
+            }
+            if let Some(max) = max {
+                consumer = consumer.max(max);
+            }
+            Shared::Owned(Arc::new(consumer))
+        }
+        ArgumentType::Word => Shared::Borrowed(&SimpleArgConsumer),
+        ArgumentType::GreedyString => Shared::Borrowed(&MsgArgConsumer),
+        ArgumentType::Players => Shared::Borrowed(&PlayersArgumentConsumer),
+        ArgumentType::BlockPos => Shared::Borrowed(&BlockPosArgumentConsumer),
+    }
+}
+
+/// Parses like another consumer, but asks the plugin for suggestions
+struct SuggestingConsumer {
+    inner: Shared<'static, dyn ArgumentConsumer>,
+    provider: Arc<dyn SuggestionProvider>,
+}
+
+impl GetClientSideArgParser for SuggestingConsumer {
+    fn get_client_side_parser(&self) -> ProtoCmdArgParser<'_> {
+        self.inner.get_client_side_parser()
+    }
+
+    fn get_client_side_suggestion_type_override(&self) -> Option<ProtoCmdArgSuggestionType> {
+        Some(ProtoCmdArgSuggestionType::AskServer)
+    }
+}
+
+#[async_trait]
+impl ArgumentConsumer for SuggestingConsumer {
+    async fn consume<'a>(
+        &self,
+        sender: &CommandSender<'a>,
+        server: &'a Server,
+        args: &mut RawArgs<'a>,
+    ) -> Option<Arg<'a>> {
+        self.inner.consume(sender, server, args).await
+    }
+
+    async fn suggest<'a>(
+        &self,
+        sender: &CommandSender<'a>,
+        _server: &'a Server,
+        input: &'a str,
+    ) -> Result<Option<Vec<CommandSuggestion<'a>>>, CommandError> {
+        let Some(input) = input.split_single_whitespace_including_empty_parts().last() else {
+            return Ok(None);
+        };
+        let suggestions = self
+            .provider
+            .suggest(&command_source(sender), input)
+            .into_iter()
+            .map(|suggestion| CommandSuggestion::new(suggestion, None))
+            .collect();
+        Ok(Some(suggestions))
+    }
+}
+
+fn command_source(sender: &CommandSender) -> CommandSource {
+    CommandSource {
+        name: sender.to_string(),
+        player: sender.as_player().map(|player| player_info(&player)),
+    }
+}
+
+struct PluginCommandExecutor {
+    handler: CommandHandler,
+}
+
+#[async_trait]
+impl CommandExecutor for PluginCommandExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        _server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let mut arguments = HashMap::new();
+        for (&name, arg) in args {
+            let value = match arg {
+                Arg::Bool(value) => ArgumentValue::Bool(*value),
+                Arg::Num(Ok(Number::I32(value))) => ArgumentValue::Integer(*value),
+                Arg::Num(Ok(Number::F64(value))) => ArgumentValue::Double(*value),
+                Arg::Num(Err(())) => {
+                    return Err(CommandError::GeneralCommandIssue(format!(
+                        "{name} is out of bounds"
+                    )))
+                }
+                Arg::Simple(value) | Arg::Msg(value) => ArgumentValue::String(value.clone()),
+                Arg::Players(players) => ArgumentValue::Players(
+                    players.iter().map(|player| player_info(player)).collect(),
+                ),
+                Arg::BlockPos(position) => ArgumentValue::BlockPos(*position),
+                _ => continue,
+            };
+            arguments.insert(name.to_string(), value);
+        }
+
+        let source = command_source(sender);
+        let mut context = CommandContext::new(&source, arguments);
+        let result = (self.handler)(&mut context);
+        for reply in context.into_replies() {
+            sender.send_message(TextComponent::text_string(reply)).await;
+        }
+        if let Err(err) = result {
+            sender
+                .send_message(TextComponent::text_string(err).color_named(NamedColor::Red))
+                .await;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,252 @@
+//! Custom items and blocks of native plugins, see [`pumpkin_api::content`].
+
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, LazyLock,
+    },
+};
+
+use parking_lot::RwLock;
+use pumpkin_api::{
+    content::{BlockAppearance, ContentError},
+    persistent_data::NamespacedKey,
+    ContentRegistry, CustomBlock, CustomItem,
+};
+use pumpkin_config::ADVANCED_CONFIG;
+use pumpkin_world::{
+    block::custom_states::CustomStateBlock,
+    item::{
+        item_registry::{get_item, get_item_by_id},
+        ItemStack,
+    },
+    resource_pack::ResourcePackBuilder,
+};
+use uuid::Uuid;
+
+use super::persistent_data::PERSISTENT_DATA;
+
+/// Handlers are called while packets are handled, so the content is kept where no async lock has
+/// to be taken to find it
+pub static CONTENT: LazyLock<Arc<ContentManager>> = LazyLock::new(Arc::default);
+
+struct RegisteredItem {
+    owner: String,
+    item_id: u16,
+    custom_model_data: i32,
+    item: CustomItem,
+}
+
+struct RegisteredBlock {
+    owner: String,
+    state_id: u16,
+    block: CustomBlock,
+}
+
+#[derive(Default)]
+struct Content {
+    items: HashMap<NamespacedKey, RegisteredItem>,
+    /// Custom items by item id and custom model data
+    item_models: HashMap<(u16, i32), NamespacedKey>,
+    blocks: HashMap<NamespacedKey, RegisteredBlock>,
+    block_states: HashMap<u16, NamespacedKey>,
+}
+
+impl Content {
+    fn free_state(&self, appearance: BlockAppearance) -> Option<u16> {
+        let blocks: &[CustomStateBlock] = match appearance {
+            BlockAppearance::NoteBlock => &[CustomStateBlock::NoteBlock],
+            BlockAppearance::MushroomBlock => &CustomStateBlock::MUSHROOM_BLOCKS,
+        };
+        blocks
+            .iter()
+            .flat_map(|block| block.free_states())
+            .find(|state| !self.block_states.contains_key(state))
+    }
+}
+
+#[derive(Default)]
+pub struct ContentManager {
+    content: RwLock<Content>,
+    /// Whether the resource pack has to be generated again
+    changed: AtomicBool,
+}
+
+impl ContentManager {
+    /// The custom item the stack is, if it is one
+    pub fn item(&self, stack: &ItemStack) -> Option<CustomItem> {
+        let custom_model_data = stack.custom_model_data()?;
+        let content = self.content.read();
+        let id = content
+            .item_models
+            .get(&(stack.item_id, custom_model_data))?;
+        Some(content.items[id].item.clone())
+    }
+
+    /// The custom block the state is, if it is one
+    pub fn block(&self, state_id: u16) -> Option<CustomBlock> {
+        let content = self.content.read();
+        let id = content.block_states.get(&state_id)?;
+        Some(content.blocks[id].block.clone())
+    }
+
+    /// Whether the state is a custom block, vanilla blocks don't act in it
+    pub fn is_custom_block(&self, state_id: u16) -> bool {
+        self.content.read().block_states.contains_key(&state_id)
+    }
+
+    /// The state the custom block is placed as
+    pub fn block_state(&self, id: &NamespacedKey) -> Option<u16> {
+        self.content
+            .read()
+            .blocks
+            .get(id)
+            .map(|block| block.state_id)
+    }
+
+    /// Removes the items and blocks of the plugin
+    pub fn unregister_all(&self, owner: &str) {
+        let mut content = self.content.write();
+        let Content {
+            items,
+            item_models,
+            blocks,
+            block_states,
+        } = &mut *content;
+        let items_before = items.len();
+        let blocks_before = blocks.len();
+        items.retain(|_, item| item.owner != owner);
+        item_models.retain(|_, id| items.contains_key(id));
+        blocks.retain(|_, block| block.owner != owner);
+        block_states.retain(|_, id| blocks.contains_key(id));
+        if items.len() != items_before || blocks.len() != blocks_before {
+            self.changed.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Generates the resource pack again if the content changed since the last tick
+    pub fn tick(&self) {
+        let config = &ADVANCED_CONFIG.content;
+        if !config.generate_resource_pack || !self.changed.swap(false, Ordering::Relaxed) {
+            return;
+        }
+        let builder = self.resource_pack(&config.resource_pack_description);
+        let folder = Path::new(&config.resource_pack_folder);
+        match builder.write(folder) {
+            Ok(()) => log::info!("Generated the resource pack in {}", folder.display()),
+            Err(err) => log::error!("Couldn't write the resource pack: {err}"),
+        }
+    }
+
+    fn resource_pack(&self, description: &str) -> ResourcePackBuilder {
+        let content = self.content.read();
+        let mut builder = ResourcePackBuilder::new(description);
+        for (id, item) in &content.items {
+            let model = format!("{}:item/{}", id.namespace(), id.key());
+            let texture = item.item.texture.as_deref().unwrap_or(&model);
+            builder
+                .model(&model, "minecraft:item/generated", "layer0", texture)
+                .item_override(&item.item.base, item.custom_model_data, &model);
+        }
+        for (id, block) in &content.blocks {
+            let model = format!("{}:block/{}", id.namespace(), id.key());
+            let texture = block.block.texture.as_deref().unwrap_or(&model);
+            builder
+                .model(&model, "minecraft:block/cube_all", "all", texture)
+                .block_state(block.state_id, &model);
+        }
+        builder
+    }
+}
+
+impl ContentRegistry for ContentManager {
+    fn register_item(&self, owner: &str, mut item: CustomItem) -> Result<(), ContentError> {
+        if !item.base.contains(':') {
+            item.base = format!("minecraft:{}", item.base);
+        }
+        let item_id = get_item(&item.base)
+            .ok_or_else(|| ContentError::UnknownItem(item.base.clone()))?
+            .id;
+
+        let mut content = self.content.write();
+        if content.items.contains_key(&item.id) {
+            return Err(ContentError::AlreadyRegistered(item.id));
+        }
+        if let Some(block) = &item.block {
+            if !content.blocks.contains_key(block) {
+                return Err(ContentError::UnknownBlock(block.clone()));
+            }
+        }
+        let custom_model_data = match item.custom_model_data {
+            Some(data) if content.item_models.contains_key(&(item_id, data)) => {
+                return Err(ContentError::CustomModelDataTaken(data));
+            }
+            Some(data) => data,
+            None => (1..=i32::MAX)
+                .find(|data| !content.item_models.contains_key(&(item_id, *data)))
+                .expect("There are fewer custom items than custom model data"),
+        };
+
+        content
+            .item_models
+            .insert((item_id, custom_model_data), item.id.clone());
+        content.items.insert(
+            item.id.clone(),
+            RegisteredItem {
+                owner: owner.to_string(),
+                item_id,
+                custom_model_data,
+                item,
+            },
+        );
+        self.changed.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn register_block(&self, owner: &str, block: CustomBlock) -> Result<(), ContentError> {
+        let mut content = self.content.write();
+        if content.blocks.contains_key(&block.id) {
+            return Err(ContentError::AlreadyRegistered(block.id));
+        }
+        let state_id = content
+            .free_state(block.appearance)
+            .ok_or(ContentError::NoFreeState(block.appearance))?;
+
+        content.block_states.insert(state_id, block.id.clone());
+        content.blocks.insert(
+            block.id.clone(),
+            RegisteredBlock {
+                owner: owner.to_string(),
+                state_id,
+                block,
+            },
+        );
+        self.changed.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn give(&self, player: Uuid, item: &NamespacedKey, count: u32) -> bool {
+        let Some(player) = PERSISTENT_DATA.online_player(player) else {
+            return false;
+        };
+        let Some((item_id, custom_model_data)) = self
+            .content
+            .read()
+            .items
+            .get(item)
+            .map(|item| (item.item_id, item.custom_model_data))
+        else {
+            return false;
+        };
+        let base = get_item_by_id(item_id).expect("Custom items have a vanilla base");
+        // handlers may run while the inventory is locked, so the item is given afterwards
+        tokio::spawn(async move {
+            player
+                .give_custom_items(base, custom_model_data, count)
+                .await;
+        });
+        true
+    }
+}
@@ -0,0 +1,51 @@
+//! View and simulation distances native plugins override, see [`pumpkin_api::distance`].
+
+use std::sync::{Arc, LazyLock};
+
+use pumpkin_api::DistanceRegistry;
+use uuid::Uuid;
+
+use crate::{entity::player::Player, world::player_chunker};
+
+use super::{block_on, persistent_data::PERSISTENT_DATA};
+
+pub static DISTANCES: LazyLock<Arc<DistanceOverrides>> =
+    LazyLock::new(|| Arc::new(DistanceOverrides));
+
+/// The overrides are kept on the players, this only finds them
+pub struct DistanceOverrides;
+
+impl DistanceOverrides {
+    fn update(player: Uuid, set: impl FnOnce(&Player)) -> bool {
+        let Some(player) = PERSISTENT_DATA.online_player(player) else {
+            return false;
+        };
+        set(&player);
+        block_on(player_chunker::update_distances(&player));
+        true
+    }
+}
+
+impl DistanceRegistry for DistanceOverrides {
+    fn set_view_distance(&self, player: Uuid, distance: Option<u8>) -> bool {
+        Self::update(player, |player| {
+            player
+                .view_distance_override
+                .store(distance.map(|distance| distance.min(32)));
+        })
+    }
+
+    fn set_simulation_distance(&self, player: Uuid, distance: Option<u8>) -> bool {
+        Self::update(player, |player| {
+            player.simulation_distance_override.store(distance);
+        })
+    }
+
+    fn distances(&self, player: Uuid) -> Option<(u8, u8)> {
+        let player = PERSISTENT_DATA.online_player(player)?;
+        Some((
+            player_chunker::get_view_distance(&player),
+            player_chunker::get_simulation_distance(&player),
+        ))
+    }
+}
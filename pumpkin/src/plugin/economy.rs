@@ -0,0 +1,120 @@
+//! The built-in [`Economy`], which keeps the balances in a JSON file.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use parking_lot::RwLock;
+use pumpkin_api::{
+    service::{Economy, EconomyError},
+    ServicePriority, ServiceRegistry,
+};
+use pumpkin_config::{EconomyConfig, ADVANCED_CONFIG};
+use uuid::Uuid;
+
+/// The owner of the server's own providers
+const OWNER: &str = "pumpkin";
+
+pub struct SimpleEconomy {
+    balances: RwLock<HashMap<Uuid, f64>>,
+    starting_balance: f64,
+    currency_symbol: String,
+    path: PathBuf,
+    /// Whether the balances changed since they were saved
+    changed: AtomicBool,
+}
+
+impl SimpleEconomy {
+    fn load(config: &EconomyConfig) -> Self {
+        let path = PathBuf::from(&config.file);
+        let balances = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|err| {
+                log::error!(
+                    "Couldn't parse {}, starting without balances: {err}",
+                    path.display()
+                );
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+        Self {
+            balances: RwLock::new(balances),
+            starting_balance: config.starting_balance,
+            currency_symbol: config.currency_symbol.clone(),
+            path,
+            changed: AtomicBool::new(false),
+        }
+    }
+
+    /// Writes the balances if they changed since they were last saved
+    pub fn save(&self) {
+        if !self.changed.swap(false, Ordering::Relaxed) {
+            return;
+        }
+        let content = serde_json::to_string_pretty(&*self.balances.read())
+            .expect("Balances can be serialized");
+        if let Err(err) = fs::write(&self.path, content) {
+            log::error!("Couldn't save the balances: {err}");
+        }
+    }
+
+    fn change(
+        &self,
+        player: Uuid,
+        amount: f64,
+        f: impl FnOnce(f64, f64) -> Result<f64, EconomyError>,
+    ) -> Result<f64, EconomyError> {
+        if !amount.is_finite() || amount < 0.0 {
+            return Err(EconomyError::InvalidAmount);
+        }
+        let mut balances = self.balances.write();
+        let balance = balances.entry(player).or_insert(self.starting_balance);
+        *balance = f(*balance, amount)?;
+        self.changed.store(true, Ordering::Relaxed);
+        Ok(*balance)
+    }
+}
+
+impl Economy for SimpleEconomy {
+    fn balance(&self, player: Uuid) -> f64 {
+        self.balances
+            .read()
+            .get(&player)
+            .copied()
+            .unwrap_or(self.starting_balance)
+    }
+
+    fn deposit(&self, player: Uuid, amount: f64) -> Result<f64, EconomyError> {
+        self.change(player, amount, |balance, amount| Ok(balance + amount))
+    }
+
+    fn withdraw(&self, player: Uuid, amount: f64) -> Result<f64, EconomyError> {
+        self.change(player, amount, |balance, amount| {
+            if balance < amount {
+                return Err(EconomyError::InsufficientFunds);
+            }
+            Ok(balance - amount)
+        })
+    }
+
+    fn format(&self, amount: f64) -> String {
+        format!("{}{amount:.2}", self.currency_symbol)
+    }
+}
+
+/// Provides the server's own services, which plugins can replace with a higher priority
+pub fn register_builtin(services: &ServiceRegistry) -> Option<Arc<SimpleEconomy>> {
+    let config = &ADVANCED_CONFIG.economy;
+    if !config.enabled {
+        return None;
+    }
+    let economy = Arc::new(SimpleEconomy::load(config));
+    services.register::<dyn Economy>(OWNER, ServicePriority::Lowest, economy.clone());
+    Some(economy)
+}
@@ -0,0 +1,297 @@
+//! Menus of native plugins, see [`pumpkin_api::menu`].
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock},
+};
+
+use parking_lot::Mutex;
+use pumpkin_api::{
+    menu::{ClickKind, MenuClick},
+    Menu, MenuAction, MenuItem, MenuRegistry, TextPrompt,
+};
+use pumpkin_core::text::TextComponent;
+use pumpkin_inventory::{Container, WindowType};
+use pumpkin_protocol::{
+    client::play::{CCloseContainer, COpenScreen, CSetContainerContent},
+    server::play::SClickContainer,
+    slot::Slot,
+    VarInt,
+};
+use pumpkin_world::item::{item_registry::get_item, ItemStack};
+use uuid::Uuid;
+
+use crate::entity::player::Player;
+
+use super::{persistent_data::PERSISTENT_DATA, player_info};
+
+/// The anvil slot the result is shown in, clicking it submits the prompt
+const ANVIL_RESULT: usize = 2;
+
+/// Menus are opened from handlers, which can't wait for packets to be sent
+pub static MENUS: LazyLock<Arc<MenuManager>> = LazyLock::new(Arc::default);
+
+enum Screen {
+    Menu(Menu),
+    Prompt { prompt: TextPrompt, text: String },
+}
+
+impl Screen {
+    fn window_type(&self) -> WindowType {
+        match self {
+            Self::Menu(menu) => match menu.rows() {
+                1 => WindowType::Generic9x1,
+                2 => WindowType::Generic9x2,
+                3 => WindowType::Generic9x3,
+                4 => WindowType::Generic9x4,
+                5 => WindowType::Generic9x5,
+                _ => WindowType::Generic9x6,
+            },
+            Self::Prompt { .. } => WindowType::Anvil,
+        }
+    }
+
+    fn title(&self) -> &str {
+        match self {
+            Self::Menu(menu) => &menu.title,
+            Self::Prompt { prompt, .. } => &prompt.title,
+        }
+    }
+
+    /// The items of the screen, without the player's inventory
+    fn items(&self) -> Vec<Option<ItemStack>> {
+        match self {
+            Self::Menu(menu) => menu
+                .items()
+                .iter()
+                .map(|item| item.as_ref().and_then(item_stack))
+                .collect(),
+            Self::Prompt { prompt, .. } => {
+                let item = item_stack(&MenuItem::new(prompt.item.clone()));
+                vec![item.clone(), None, item]
+            }
+        }
+    }
+}
+
+fn item_stack(item: &MenuItem) -> Option<ItemStack> {
+    let mut stack = ItemStack::new(item.count.max(1), get_item(&item.item)?.id);
+    stack.set_custom_model_data(item.custom_model_data);
+    Some(stack)
+}
+
+struct OpenMenu {
+    owner: String,
+    window_id: i32,
+    screen: Screen,
+}
+
+#[derive(Default)]
+pub struct MenuManager {
+    open: Mutex<HashMap<Uuid, OpenMenu>>,
+}
+
+impl MenuManager {
+    pub fn is_open(&self, player: Uuid) -> bool {
+        self.open.lock().contains_key(&player)
+    }
+
+    async fn show(&self, player: &Player, owner: String, screen: Screen) {
+        let mut inventory = player.inventory.lock().await;
+        inventory.total_opened_containers += 1;
+        let window_id = inventory.total_opened_containers;
+        drop(inventory);
+
+        player
+            .client
+            .send_packet(&COpenScreen::new(
+                window_id.into(),
+                VarInt(screen.window_type() as i32),
+                TextComponent::text(screen.title()),
+            ))
+            .await;
+        let items = screen.items();
+        self.open.lock().insert(
+            player.gameprofile.id,
+            OpenMenu {
+                owner,
+                window_id,
+                screen,
+            },
+        );
+        Self::send_content(player, window_id, &items).await;
+    }
+
+    /// Sends every slot, which undoes whatever the client predicted
+    async fn send_content(player: &Player, window_id: i32, items: &[Option<ItemStack>]) {
+        let mut inventory = player.inventory.lock().await;
+        let slots: Vec<Slot> = items
+            .iter()
+            .map(Slot::from)
+            .chain(inventory.all_combinable_slots().into_iter().map(Slot::from))
+            .collect();
+        let carried_item = player
+            .carried_item
+            .lock()
+            .as_ref()
+            .map_or_else(Slot::empty, Slot::from);
+        inventory.state_id += 1;
+        let packet = CSetContainerContent::new(
+            window_id.into(),
+            inventory.state_id.into(),
+            &slots,
+            &carried_item,
+        );
+        drop(inventory);
+        player.client.send_packet(&packet).await;
+    }
+
+    async fn apply(&self, player: &Player, owner: String, action: MenuAction) {
+        match action {
+            MenuAction::Nothing => {}
+            MenuAction::Close => {
+                let open = self.open.lock().remove(&player.gameprofile.id);
+                if let Some(open) = open {
+                    player
+                        .client
+                        .send_packet(&CCloseContainer::new(open.window_id.into()))
+                        .await;
+                }
+            }
+            MenuAction::Open(menu) => self.show(player, owner, Screen::Menu(menu)).await,
+            MenuAction::Prompt(prompt) => {
+                let text = String::new();
+                self.show(player, owner, Screen::Prompt { prompt, text })
+                    .await;
+            }
+        }
+    }
+
+    /// Handles a click while the player has a menu open, items never move
+    pub async fn click(&self, player: &Player, packet: &SClickContainer) {
+        let (owner, window_id, items, handler) = {
+            let open = self.open.lock();
+            let Some(menu) = open.get(&player.gameprofile.id) else {
+                return;
+            };
+            if menu.window_id != packet.window_id.0 {
+                return;
+            }
+            let slot = usize::try_from(packet.slot).ok();
+            let handler = match &menu.screen {
+                Screen::Menu(menu) => slot
+                    .and_then(|slot| menu.items().get(slot)?.as_ref()?.on_click.clone())
+                    .map(Handler::Click),
+                Screen::Prompt { prompt, text } => (slot == Some(ANVIL_RESULT))
+                    .then(|| Handler::Submit(prompt.on_submit.clone(), text.clone())),
+            };
+            (
+                menu.owner.clone(),
+                menu.window_id,
+                menu.screen.items(),
+                handler,
+            )
+        };
+        Self::send_content(player, window_id, &items).await;
+
+        let action = match handler {
+            Some(Handler::Click(on_click)) => on_click(&MenuClick {
+                player: player_info(player),
+                slot: packet.slot as usize,
+                kind: click_kind(packet.mode.0, packet.button),
+            }),
+            Some(Handler::Submit(on_submit, text)) => on_submit(&player_info(player), &text),
+            None => return,
+        };
+        self.apply(player, owner, action).await;
+    }
+
+    /// Keeps the text the player typed into a prompt
+    pub fn rename(&self, player: &Player, name: String) {
+        if let Some(OpenMenu {
+            screen: Screen::Prompt { text, .. },
+            ..
+        }) = self.open.lock().get_mut(&player.gameprofile.id)
+        {
+            *text = name;
+        }
+    }
+
+    /// The player closed the screen
+    pub fn closed(&self, player: &Player) {
+        let Some(open) = self.open.lock().remove(&player.gameprofile.id) else {
+            return;
+        };
+        if let Screen::Menu(Menu {
+            on_close: Some(on_close),
+            ..
+        }) = open.screen
+        {
+            on_close(&player_info(player));
+        }
+    }
+
+    /// Forgets the menu of a player who left
+    pub fn remove_player(&self, player: Uuid) {
+        self.open.lock().remove(&player);
+    }
+
+    /// Forgets the menus of the plugin, whose handlers can't be called anymore
+    pub fn unregister_all(&self, owner: &str) {
+        self.open.lock().retain(|_, menu| menu.owner != owner);
+    }
+
+    fn spawn_show(owner: &str, player: Uuid, screen: Screen) -> bool {
+        let Some(player) = PERSISTENT_DATA.online_player(player) else {
+            return false;
+        };
+        let owner = owner.to_string();
+        tokio::spawn(async move {
+            MENUS.show(&player, owner, screen).await;
+        });
+        true
+    }
+}
+
+enum Handler {
+    Click(pumpkin_api::menu::ClickHandler),
+    Submit(pumpkin_api::menu::SubmitHandler, String),
+}
+
+/// See <https://wiki.vg/Protocol#Click_Container>
+fn click_kind(mode: i32, button: i8) -> ClickKind {
+    match (mode, button) {
+        (0, 0) => ClickKind::Left,
+        (0, 1) => ClickKind::Right,
+        (1, 0) => ClickKind::ShiftLeft,
+        (1, 1) => ClickKind::ShiftRight,
+        (2, button) => ClickKind::Number(button as u8),
+        (4, 0) => ClickKind::Drop,
+        (4, 1) => ClickKind::DropStack,
+        _ => ClickKind::Other,
+    }
+}
+
+impl MenuRegistry for MenuManager {
+    fn open(&self, owner: &str, player: Uuid, menu: Menu) -> bool {
+        Self::spawn_show(owner, player, Screen::Menu(menu))
+    }
+
+    fn prompt(&self, owner: &str, player: Uuid, prompt: TextPrompt) -> bool {
+        let text = String::new();
+        Self::spawn_show(owner, player, Screen::Prompt { prompt, text })
+    }
+
+    fn close(&self, player: Uuid) -> bool {
+        let Some(player) = PERSISTENT_DATA.online_player(player) else {
+            return false;
+        };
+        if !self.is_open(player.gameprofile.id) {
+            return false;
+        }
+        tokio::spawn(async move {
+            MENUS.apply(&player, String::new(), MenuAction::Close).await;
+        });
+        true
+    }
+}
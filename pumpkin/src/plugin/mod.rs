@@ -0,0 +1,242 @@
+//! Loads native plugins from the plugin folder, see [`pumpkin_api`] and
+//! [`PluginsConfig`](pumpkin_config::PluginsConfig).
+//!
+//! Events are fired on the global [`EVENTS`] bus, as they happen in places which do not know
+//! about the server, like entities taking damage or chunks being loaded.
+
+use std::{
+    any::type_name,
+    env::consts::DLL_EXTENSION,
+    fs,
+    future::Future,
+    path::Path,
+    sync::{Arc, LazyLock},
+    time::Instant,
+};
+
+use libloading::Library;
+use parking_lot::Mutex;
+use pumpkin_api::{
+    event::player::PlayerInfo,
+    plugin::{PluginDeclaration, DECLARATION_SYMBOL},
+    Event, EventBus, Plugin, PluginContext, ServiceRegistry,
+};
+use pumpkin_config::ADVANCED_CONFIG;
+use thiserror::Error;
+
+use crate::{
+    command::{
+        args::arg_message::MsgArgConsumer,
+        dispatcher::CommandDispatcher,
+        tree::CommandTree,
+        tree_builder::{argument, require_owned},
+        CommandExecutor, CommandSender,
+    },
+    entity::player::{PermissionLvl, Player},
+    server::profiler::PROFILER,
+};
+use command::PluginCommandQueue;
+use content::CONTENT;
+use distance::DISTANCES;
+use menu::MENUS;
+use persistent_data::PERSISTENT_DATA;
+use visibility::VISIBILITY;
+
+pub mod command;
+pub mod content;
+pub mod distance;
+pub mod economy;
+pub mod menu;
+pub mod named_event;
+pub mod persistent_data;
+pub mod script;
+pub mod visibility;
+pub mod wasm;
+
+pub static EVENTS: LazyLock<EventBus> = LazyLock::new(EventBus::default);
+
+/// The services plugins provide, including the server's own ones
+pub static SERVICES: LazyLock<Arc<ServiceRegistry>> = LazyLock::new(Arc::default);
+
+/// Calls the listeners of the event, returning it to check whether it was cancelled or changed
+pub fn fire<E: Event>(mut event: E) -> E {
+    if PROFILER.is_running() {
+        let start = Instant::now();
+        EVENTS.fire(&mut event);
+        let name = type_name::<E>().rsplit("::").next().unwrap_or_default();
+        PROFILER.record(&format!("plugin_events;{name}"), start.elapsed());
+    } else {
+        EVENTS.fire(&mut event);
+    }
+    event
+}
+
+#[must_use]
+pub fn player_info(player: &Player) -> PlayerInfo {
+    PlayerInfo {
+        uuid: player.gameprofile.id,
+        name: player.gameprofile.name.clone(),
+    }
+}
+
+/// The argument of commands registered by plugins, everything after the command's name
+pub const COMMAND_ARGS: &str = "args";
+
+/// Registers a command of a plugin, which needs the permission `<owner>.command.<command>`.
+/// Returns false if the command already exists.
+pub(crate) fn register_command(
+    dispatcher: &mut CommandDispatcher<'static>,
+    owner: &str,
+    command: &str,
+    description: &str,
+    permission: PermissionLvl,
+    executor: Arc<dyn CommandExecutor>,
+) -> bool {
+    if dispatcher.commands.contains_key(command) {
+        log::warn!("{owner} can't register /{command}, it already exists");
+        return false;
+    }
+    let node = format!("{owner}.command.{command}");
+    dispatcher.register(
+        CommandTree::new_owned(vec![command.to_string()], description.to_string()).with_child(
+            require_owned(Arc::new(move |sender: &CommandSender| {
+                sender.has_permission(&node, permission)
+            }))
+            .execute_owned(executor.clone())
+            .with_child(argument(COMMAND_ARGS, &MsgArgConsumer).execute_owned(executor)),
+        ),
+    );
+    true
+}
+
+/// Runs async world access from within a synchronous plugin call
+pub fn block_on<F: Future>(future: F) -> Option<F::Output> {
+    let handle = tokio::runtime::Handle::try_current().ok()?;
+    Some(tokio::task::block_in_place(|| handle.block_on(future)))
+}
+
+#[derive(Error, Debug)]
+pub enum PluginError {
+    #[error("Failed to load the library: {0}")]
+    Library(#[from] libloading::Error),
+    #[error("The plugin was built for another version of Pumpkin or with another compiler")]
+    Incompatible,
+    #[error("A plugin named {0} is already loaded")]
+    AlreadyLoaded(String),
+}
+
+struct LoadedPlugin {
+    name: String,
+    version: String,
+    /// Declared before the library, so it is dropped while its code is still loaded
+    plugin: Box<dyn Plugin>,
+    _library: Library,
+}
+
+#[derive(Default)]
+pub struct PluginManager {
+    plugins: Mutex<Vec<LoadedPlugin>>,
+    /// Applied every tick, see [`PluginCommandQueue::apply`]
+    pub commands: Arc<PluginCommandQueue>,
+}
+
+impl PluginManager {
+    /// Loads every library in the plugin folder
+    pub fn load_all(&self) {
+        let config = &ADVANCED_CONFIG.plugins;
+        if !config.enabled {
+            return;
+        }
+        let folder = Path::new(&config.folder);
+        if let Err(err) = fs::create_dir_all(folder) {
+            log::error!("Couldn't create the plugin folder: {err}");
+            return;
+        }
+        let entries = match fs::read_dir(folder) {
+            Ok(entries) => entries,
+            Err(err) => {
+                log::error!("Couldn't read the plugin folder: {err}");
+                return;
+            }
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path
+                .extension()
+                .is_none_or(|extension| extension != DLL_EXTENSION)
+            {
+                continue;
+            }
+            match self.load(&path) {
+                Ok(name) => log::info!("Loaded plugin {name}"),
+                Err(err) => log::error!("Failed to load plugin {}: {err}", path.display()),
+            }
+        }
+    }
+
+    /// Loads the plugin and lets it register its listeners, returning its name and version
+    fn load(&self, path: &Path) -> Result<String, PluginError> {
+        // SAFETY: loading runs the library's initialisers, plugins are as trusted as the server
+        let library = unsafe { Library::new(path)? };
+        // SAFETY: the symbol is the address of the declaration static
+        let declaration: &PluginDeclaration =
+            unsafe { &**library.get::<*const PluginDeclaration>(DECLARATION_SYMBOL)? };
+        // SAFETY: declare_plugin! exports the version as a nul terminated string
+        if !unsafe { declaration.is_compatible() } {
+            return Err(PluginError::Incompatible);
+        }
+
+        let mut plugin = (declaration.create)();
+        let metadata = plugin.metadata();
+        let name = metadata.name.to_string();
+        let mut plugins = self.plugins.lock();
+        if plugins.iter().any(|loaded| loaded.name == name) {
+            return Err(PluginError::AlreadyLoaded(name));
+        }
+        plugin.on_load(&PluginContext::new(
+            &name,
+            &EVENTS,
+            self.commands.clone(),
+            PERSISTENT_DATA.clone(),
+            PERSISTENT_DATA.clone(),
+            CONTENT.clone(),
+            MENUS.clone(),
+            SERVICES.clone(),
+            VISIBILITY.clone(),
+            DISTANCES.clone(),
+        ));
+        let version = metadata.version.to_string();
+        let loaded = format!("{name} {version}");
+        plugins.push(LoadedPlugin {
+            name,
+            version,
+            plugin,
+            _library: library,
+        });
+        Ok(loaded)
+    }
+
+    /// The name and version of every loaded plugin, in the order they were loaded
+    pub fn loaded(&self) -> Vec<(String, String)> {
+        self.plugins
+            .lock()
+            .iter()
+            .map(|loaded| (loaded.name.clone(), loaded.version.clone()))
+            .collect()
+    }
+
+    /// Unloads every plugin, the most recently loaded first
+    pub fn unload_all(&self) {
+        let mut plugins = self.plugins.lock();
+        while let Some(mut loaded) = plugins.pop() {
+            loaded.plugin.on_unload();
+            EVENTS.unregister_all(&loaded.name);
+            self.commands.unregister_all(&loaded.name);
+            CONTENT.unregister_all(&loaded.name);
+            MENUS.unregister_all(&loaded.name);
+            SERVICES.unregister_all(&loaded.name);
+            VISIBILITY.unregister_all(&loaded.name);
+            log::info!("Unloaded plugin {}", loaded.name);
+        }
+    }
+}
@@ -0,0 +1,217 @@
+//! Events by name and as JSON, for plugins which are not written in Rust.
+
+use pumpkin_api::{
+    event::{
+        block::{BlockBreakEvent, BlockPlaceEvent},
+        entity::{EntityDamageEvent, EntityDeathEvent, EntityDismountEvent, EntityMountEvent},
+        player::{
+            PlayerChatEvent, PlayerInfo, PlayerInteractEvent, PlayerJoinEvent, PlayerQuitEvent,
+        },
+        world::ChunkLoadEvent,
+    },
+    Cancellable, Event, EventPriority,
+};
+use pumpkin_core::math::position::WorldPosition;
+use serde_json::{json, Value};
+
+use super::EVENTS;
+
+/// An event scripts and WebAssembly plugins subscribe to by name
+pub trait NamedEvent: Event {
+    const NAME: &'static str;
+
+    fn to_json(&self) -> Value;
+
+    fn cancel(&mut self) {}
+}
+
+fn player_json(player: &PlayerInfo) -> Value {
+    json!({ "uuid": player.uuid, "name": player.name })
+}
+
+fn position_json(position: &WorldPosition) -> Value {
+    json!({ "x": position.0.x, "y": position.0.y, "z": position.0.z })
+}
+
+impl NamedEvent for PlayerJoinEvent {
+    const NAME: &'static str = "player_join";
+
+    fn to_json(&self) -> Value {
+        json!({ "player": player_json(&self.player) })
+    }
+}
+
+impl NamedEvent for PlayerQuitEvent {
+    const NAME: &'static str = "player_quit";
+
+    fn to_json(&self) -> Value {
+        json!({ "player": player_json(&self.player) })
+    }
+}
+
+impl NamedEvent for PlayerChatEvent {
+    const NAME: &'static str = "player_chat";
+
+    fn to_json(&self) -> Value {
+        json!({ "player": player_json(&self.player), "message": self.message })
+    }
+
+    fn cancel(&mut self) {
+        self.set_cancelled(true);
+    }
+}
+
+impl NamedEvent for PlayerInteractEvent {
+    const NAME: &'static str = "player_interact";
+
+    fn to_json(&self) -> Value {
+        json!({
+            "player": player_json(&self.player),
+            "action": format!("{:?}", self.action),
+            "position": self.position.as_ref().map(position_json),
+        })
+    }
+
+    fn cancel(&mut self) {
+        self.set_cancelled(true);
+    }
+}
+
+impl NamedEvent for BlockBreakEvent {
+    const NAME: &'static str = "block_break";
+
+    fn to_json(&self) -> Value {
+        json!({
+            "player": self.player.as_ref().map(player_json),
+            "position": position_json(&self.position),
+            "state_id": self.state_id,
+        })
+    }
+
+    fn cancel(&mut self) {
+        self.set_cancelled(true);
+    }
+}
+
+impl NamedEvent for BlockPlaceEvent {
+    const NAME: &'static str = "block_place";
+
+    fn to_json(&self) -> Value {
+        json!({
+            "player": player_json(&self.player),
+            "position": position_json(&self.position),
+            "state_id": self.state_id,
+        })
+    }
+
+    fn cancel(&mut self) {
+        self.set_cancelled(true);
+    }
+}
+
+impl NamedEvent for EntityDamageEvent {
+    const NAME: &'static str = "entity_damage";
+
+    fn to_json(&self) -> Value {
+        json!({
+            "entity_id": self.entity_id,
+            "player": self.player.as_ref().map(player_json),
+            "damage": self.damage,
+        })
+    }
+
+    fn cancel(&mut self) {
+        self.set_cancelled(true);
+    }
+}
+
+impl NamedEvent for EntityDeathEvent {
+    const NAME: &'static str = "entity_death";
+
+    fn to_json(&self) -> Value {
+        json!({
+            "entity_id": self.entity_id,
+            "player": self.player.as_ref().map(player_json),
+        })
+    }
+}
+
+impl NamedEvent for EntityMountEvent {
+    const NAME: &'static str = "entity_mount";
+
+    fn to_json(&self) -> Value {
+        json!({
+            "entity_id": self.entity_id,
+            "player": self.player.as_ref().map(player_json),
+            "vehicle_id": self.vehicle_id,
+        })
+    }
+
+    fn cancel(&mut self) {
+        self.set_cancelled(true);
+    }
+}
+
+impl NamedEvent for EntityDismountEvent {
+    const NAME: &'static str = "entity_dismount";
+
+    fn to_json(&self) -> Value {
+        json!({
+            "entity_id": self.entity_id,
+            "player": self.player.as_ref().map(player_json),
+            "vehicle_id": self.vehicle_id,
+        })
+    }
+
+    fn cancel(&mut self) {
+        self.set_cancelled(true);
+    }
+}
+
+impl NamedEvent for ChunkLoadEvent {
+    const NAME: &'static str = "chunk_load";
+
+    fn to_json(&self) -> Value {
+        json!({
+            "world": self.world,
+            "x": self.position.x,
+            "z": self.position.z,
+            "generated": self.generated,
+        })
+    }
+}
+
+fn register<E: NamedEvent>(
+    owner: &str,
+    handler: impl Fn(&'static str, Value) -> bool + Send + Sync + 'static,
+) {
+    EVENTS.register(owner, EventPriority::Normal, false, move |event: &mut E| {
+        if handler(E::NAME, event.to_json()) {
+            event.cancel();
+        }
+    });
+}
+
+/// Registers a listener of the event named `event`, which gets its name and data and returns
+/// whether to cancel it. Returns false if there is no such event
+pub fn listen(
+    event: &str,
+    owner: &str,
+    handler: impl Fn(&'static str, Value) -> bool + Send + Sync + 'static,
+) -> bool {
+    match event {
+        PlayerJoinEvent::NAME => register::<PlayerJoinEvent>(owner, handler),
+        PlayerQuitEvent::NAME => register::<PlayerQuitEvent>(owner, handler),
+        PlayerChatEvent::NAME => register::<PlayerChatEvent>(owner, handler),
+        PlayerInteractEvent::NAME => register::<PlayerInteractEvent>(owner, handler),
+        BlockBreakEvent::NAME => register::<BlockBreakEvent>(owner, handler),
+        BlockPlaceEvent::NAME => register::<BlockPlaceEvent>(owner, handler),
+        EntityDamageEvent::NAME => register::<EntityDamageEvent>(owner, handler),
+        EntityDeathEvent::NAME => register::<EntityDeathEvent>(owner, handler),
+        EntityMountEvent::NAME => register::<EntityMountEvent>(owner, handler),
+        EntityDismountEvent::NAME => register::<EntityDismountEvent>(owner, handler),
+        ChunkLoadEvent::NAME => register::<ChunkLoadEvent>(owner, handler),
+        _ => return false,
+    }
+    true
+}
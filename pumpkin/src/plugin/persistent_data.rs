@@ -0,0 +1,150 @@
+//! Persistent data and NBT of native plugins, see [`pumpkin_api::persistent_data`] and
+//! [`pumpkin_api::nbt`].
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock},
+};
+
+use parking_lot::RwLock;
+use pumpkin_api::{nbt::Compound, NbtStore, PersistentDataContainer, PersistentDataStore};
+use pumpkin_core::math::{position::WorldPosition, vector2::Vector2};
+use uuid::Uuid;
+
+use crate::{entity::player::Player, world::World};
+
+use super::block_on;
+
+/// Plugins access data synchronously, so the worlds and online players are kept where no async
+/// lock has to be taken to find them
+pub static PERSISTENT_DATA: LazyLock<Arc<PersistentDataIndex>> = LazyLock::new(Arc::default);
+
+#[derive(Default)]
+pub struct PersistentDataIndex {
+    worlds: RwLock<HashMap<String, Arc<World>>>,
+    /// The first world, which is the server's default world and has the `/data` storages
+    default_world: RwLock<Option<Arc<World>>>,
+    players: RwLock<HashMap<Uuid, Arc<Player>>>,
+}
+
+impl PersistentDataIndex {
+    pub fn add_world(&self, world: Arc<World>) {
+        self.default_world
+            .write()
+            .get_or_insert_with(|| world.clone());
+        self.worlds
+            .write()
+            .insert(world.level.name().to_string(), world);
+    }
+
+    pub fn add_player(&self, player: Arc<Player>) {
+        self.players.write().insert(player.gameprofile.id, player);
+    }
+
+    /// Saves the data of the player, once plugins handled them leaving
+    pub fn remove_player(&self, player: &Player) {
+        self.players.write().remove(&player.gameprofile.id);
+        player.living_entity.entity.world.write_player_data(player);
+    }
+
+    /// The online player with the UUID
+    pub fn online_player(&self, uuid: Uuid) -> Option<Arc<Player>> {
+        self.players.read().get(&uuid).cloned()
+    }
+
+    fn with_chunk<T>(
+        &self,
+        world: &str,
+        chunk: Vector2<i32>,
+        f: impl FnOnce(&mut pumpkin_world::chunk::ChunkData) -> T,
+    ) -> Option<T> {
+        let chunk = self
+            .worlds
+            .read()
+            .get(world)?
+            .level
+            .get_loaded_chunk(chunk)?;
+        block_on(async move {
+            let mut chunk = chunk.write().await;
+            chunk.mark_dirty();
+            f(&mut chunk)
+        })
+    }
+}
+
+impl PersistentDataStore for PersistentDataIndex {
+    fn player(&self, uuid: Uuid, f: &mut dyn FnMut(&mut PersistentDataContainer)) -> bool {
+        let Some(player) = self.online_player(uuid) else {
+            return false;
+        };
+        f(&mut player.living_entity.entity.persistent_data.lock());
+        true
+    }
+
+    fn world(&self, world: &str, f: &mut dyn FnMut(&mut PersistentDataContainer)) -> bool {
+        let Some(world) = self.worlds.read().get(world).cloned() else {
+            return false;
+        };
+        f(&mut world.persistent_data.lock());
+        true
+    }
+
+    fn chunk(
+        &self,
+        world: &str,
+        chunk: Vector2<i32>,
+        f: &mut dyn FnMut(&mut PersistentDataContainer),
+    ) -> bool {
+        self.with_chunk(world, chunk, |chunk| f(&mut chunk.persistent_data))
+            .is_some()
+    }
+
+    fn block(
+        &self,
+        world: &str,
+        position: WorldPosition,
+        f: &mut dyn FnMut(&mut PersistentDataContainer),
+    ) -> bool {
+        let (chunk, _) = position.chunk_and_chunk_relative_position();
+        self.with_chunk(world, chunk, |chunk| {
+            let data = chunk.block_entity_data.entry(position).or_default();
+            f(data);
+            if data.is_empty() {
+                chunk.block_entity_data.remove(&position);
+            }
+        })
+        .is_some()
+    }
+}
+
+impl NbtStore for PersistentDataIndex {
+    fn player(&self, uuid: Uuid) -> Option<Compound> {
+        let player = self.online_player(uuid)?;
+        block_on(async move { player.nbt().await })
+    }
+
+    fn block(
+        &self,
+        world: &str,
+        position: WorldPosition,
+        f: &mut dyn FnMut(&mut Compound),
+    ) -> bool {
+        let Some(world) = self.worlds.read().get(world).cloned() else {
+            return false;
+        };
+        block_on(async move {
+            let Some(mut nbt) = world.block_entity_nbt(position).await else {
+                return false;
+            };
+            f(&mut nbt);
+            world.set_block_entity_nbt(position, &nbt).await
+        })
+        .unwrap_or(false)
+    }
+
+    fn storage(&self, namespace: &str, path: &str, f: &mut dyn FnMut(&mut Compound)) {
+        if let Some(world) = self.default_world.read().as_ref() {
+            world.with_command_storage(namespace, path, f);
+        }
+    }
+}
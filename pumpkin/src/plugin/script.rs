@@ -0,0 +1,395 @@
+//! Rhai scripts, see [`ScriptingConfig`](pumpkin_config::ScriptingConfig).
+//!
+//! Every `.rhai` file in the script folder is run once when it is loaded, which is when it can
+//! register listeners and commands:
+//!
+//! ```rhai
+//! on("player_join", |event| broadcast(`Welcome ${event.player.name}!`));
+//! // returning true cancels the event
+//! on("block_break", |event| event.position.y < 0);
+//! command("spawnstone", 2, |sender, args| {
+//!     set_block(0, 64, 0, 1);
+//!     "Placed stone at spawn"
+//! });
+//! ```
+//!
+//! Command handlers get the name of the sender and everything after the command, returning a
+//! string replies with it. Scripts can also use `get_block(x, y, z)`, which returns the block
+//! state id or -1, and `set_block(x, y, z, state)`.
+//!
+//! Commands can only be registered on startup, a reloaded script keeps the commands it had.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use num_traits::FromPrimitive;
+use parking_lot::{Mutex, RwLock};
+use pumpkin_config::ADVANCED_CONFIG;
+use pumpkin_core::{
+    math::{position::WorldPosition, vector3::Vector3},
+    text::{color::NamedColor, TextComponent},
+};
+use pumpkin_protocol::client::play::CSystemChatMessage;
+use pumpkin_world::block::block_registry::get_state_by_state_id;
+use rhai::{Dynamic, Engine, EvalAltResult, FnPtr, FuncArgs, AST};
+
+use crate::{
+    command::{
+        args::{arg_message::MsgArgConsumer, ConsumedArgs, FindArg},
+        dispatcher::{CommandDispatcher, CommandError},
+        CommandExecutor, CommandSender,
+    },
+    entity::player::PermissionLvl,
+    server::Server,
+    world::World,
+};
+
+use super::{block_on, named_event, register_command, COMMAND_ARGS, EVENTS};
+
+/// What a script registers while it is loaded
+#[derive(Default)]
+struct Registrations {
+    listeners: Vec<(String, FnPtr)>,
+    commands: Vec<(String, PermissionLvl, FnPtr)>,
+}
+
+type SharedRegistrations = Arc<Mutex<Option<Registrations>>>;
+
+fn registering(
+    registrations: &SharedRegistrations,
+    register: impl FnOnce(&mut Registrations),
+) -> Result<(), Box<EvalAltResult>> {
+    let mut registrations = registrations.lock();
+    let registrations = registrations
+        .as_mut()
+        .ok_or("Listeners and commands can only be registered while the script loads")?;
+    register(registrations);
+    Ok(())
+}
+
+fn create_engine(name: &str, world: &Arc<World>, registrations: &SharedRegistrations) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(ADVANCED_CONFIG.scripting.max_operations);
+
+    let script = name.to_string();
+    engine.on_print(move |message| log::info!("[{script}] {message}"));
+    let script = name.to_string();
+    engine.on_debug(move |message, _, position| {
+        log::debug!("[{script}] {position:?} {message}");
+    });
+
+    let shared = registrations.clone();
+    engine.register_fn(
+        "on",
+        move |event: &str, handler: FnPtr| -> Result<(), Box<EvalAltResult>> {
+            registering(&shared, |registrations| {
+                registrations.listeners.push((event.to_string(), handler));
+            })
+        },
+    );
+    let shared = registrations.clone();
+    engine.register_fn(
+        "command",
+        move |name: &str, handler: FnPtr| -> Result<(), Box<EvalAltResult>> {
+            registering(&shared, |registrations| {
+                registrations
+                    .commands
+                    .push((name.to_string(), PermissionLvl::Zero, handler));
+            })
+        },
+    );
+    let shared = registrations.clone();
+    engine.register_fn(
+        "command",
+        move |name: &str, permission: i64, handler: FnPtr| -> Result<(), Box<EvalAltResult>> {
+            let permission = i8::try_from(permission)
+                .ok()
+                .and_then(PermissionLvl::from_i8)
+                .ok_or("The permission level has to be between 0 and 4")?;
+            registering(&shared, |registrations| {
+                registrations
+                    .commands
+                    .push((name.to_string(), permission, handler));
+            })
+        },
+    );
+
+    let world_ref = world.clone();
+    engine.register_fn("get_block", move |x: i64, y: i64, z: i64| -> i64 {
+        let Some(position) = position(x, y, z) else {
+            return -1;
+        };
+        block_on(world_ref.get_block_state_id(position))
+            .and_then(Result::ok)
+            .map_or(-1, i64::from)
+    });
+    let world_ref = world.clone();
+    engine.register_fn("set_block", move |x: i64, y: i64, z: i64, state: i64| {
+        let (Some(position), Some(state)) = (
+            position(x, y, z),
+            u16::try_from(state)
+                .ok()
+                .filter(|state| get_state_by_state_id(*state).is_some()),
+        ) else {
+            return false;
+        };
+        let world = world_ref.clone();
+        tokio::spawn(async move {
+            world.set_block_state(position, state).await;
+        });
+        true
+    });
+    let world_ref = world.clone();
+    engine.register_fn("broadcast", move |message: &str| {
+        let world = world_ref.clone();
+        let message = TextComponent::text_string(message.to_string());
+        tokio::spawn(async move {
+            world
+                .broadcast_packet_all(&CSystemChatMessage::new(&message, false))
+                .await;
+        });
+    });
+    engine
+}
+
+fn position(x: i64, y: i64, z: i64) -> Option<WorldPosition> {
+    Some(WorldPosition(Vector3::new(
+        i32::try_from(x).ok()?,
+        i32::try_from(y).ok()?,
+        i32::try_from(z).ok()?,
+    )))
+}
+
+pub struct Script {
+    name: String,
+    engine: Engine,
+    ast: AST,
+    /// Command handlers by command name
+    commands: HashMap<String, FnPtr>,
+}
+
+impl Script {
+    /// The owner of the script's listeners
+    fn owner(&self) -> String {
+        format!("script:{}", self.name)
+    }
+
+    /// Calls the handler, logging errors like running too many operations
+    fn call(&self, handler: &FnPtr, args: impl FuncArgs) -> Option<Dynamic> {
+        handler
+            .call::<Dynamic>(&self.engine, &self.ast, args)
+            .inspect_err(|err| log::error!("Script {} failed: {err}", self.name))
+            .ok()
+    }
+}
+
+struct ScriptCommandExecutor {
+    script: String,
+    command: String,
+}
+
+#[async_trait]
+impl CommandExecutor for ScriptCommandExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let args = MsgArgConsumer::find_arg(args, COMMAND_ARGS).unwrap_or_default();
+        let script = server.scripts.scripts.read().get(&self.script).cloned();
+        let Some((script, handler)) = script.and_then(|script| {
+            let handler = script.commands.get(&self.command)?.clone();
+            Some((script, handler))
+        }) else {
+            sender
+                .send_message(
+                    TextComponent::text_string(format!(
+                        "{} no longer has this command",
+                        self.script
+                    ))
+                    .color_named(NamedColor::Red),
+                )
+                .await;
+            return Ok(());
+        };
+
+        let reply = tokio::task::block_in_place(|| {
+            script.call(&handler, (sender.to_string(), args.to_string()))
+        });
+        if let Some(reply) = reply.and_then(|reply| reply.into_string().ok()) {
+            sender.send_message(TextComponent::text_string(reply)).await;
+        }
+        Ok(())
+    }
+}
+
+pub struct ScriptHost {
+    /// Scripts can access the default world
+    world: Arc<World>,
+    scripts: RwLock<HashMap<String, Arc<Script>>>,
+    /// The commands in the dispatcher and the script they belong to
+    commands: Mutex<HashMap<String, String>>,
+}
+
+impl ScriptHost {
+    #[must_use]
+    pub fn new(world: Arc<World>) -> Self {
+        Self {
+            world,
+            scripts: RwLock::new(HashMap::new()),
+            commands: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The `.rhai` files in the script folder by script name
+    fn script_files() -> Vec<(String, PathBuf)> {
+        let Ok(entries) = fs::read_dir(&ADVANCED_CONFIG.scripting.folder) else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == "rhai")
+            })
+            .filter_map(|path| {
+                let name = path.file_stem()?.to_str()?.to_string();
+                Some((name, path))
+            })
+            .collect()
+    }
+
+    /// Runs the script, registering its listeners and returning the commands it registered
+    fn load(
+        &self,
+        name: &str,
+        path: &Path,
+    ) -> Result<Vec<(String, PermissionLvl)>, Box<EvalAltResult>> {
+        let registrations = Arc::new(Mutex::new(Some(Registrations::default())));
+        let engine = create_engine(name, &self.world, &registrations);
+        let ast = engine.compile_file(path.to_path_buf())?;
+        engine.run_ast(&ast)?;
+        let registrations = registrations.lock().take().unwrap_or_default();
+
+        let script = Arc::new(Script {
+            name: name.to_string(),
+            engine,
+            ast,
+            commands: registrations
+                .commands
+                .iter()
+                .map(|(command, _, handler)| (command.clone(), handler.clone()))
+                .collect(),
+        });
+        for (event, handler) in registrations.listeners {
+            let listener = script.clone();
+            let subscribed = named_event::listen(&event, &script.owner(), move |_, data| {
+                let Ok(data) = rhai::serde::to_dynamic(data) else {
+                    return false;
+                };
+                listener
+                    .call(&handler, (data,))
+                    .and_then(|cancel| cancel.as_bool().ok())
+                    .unwrap_or(false)
+            });
+            if !subscribed {
+                log::warn!("Script {name} listens to unknown event {event}");
+            }
+        }
+        self.scripts.write().insert(name.to_string(), script);
+        Ok(registrations
+            .commands
+            .into_iter()
+            .map(|(command, permission, _)| (command, permission))
+            .collect())
+    }
+
+    /// Loads every script and registers their commands, which is only possible before the
+    /// dispatcher is shared
+    pub fn load_all(&self, dispatcher: &mut CommandDispatcher<'static>) {
+        let config = &ADVANCED_CONFIG.scripting;
+        if !config.enabled {
+            return;
+        }
+        if let Err(err) = fs::create_dir_all(&config.folder) {
+            log::error!("Couldn't create the script folder: {err}");
+            return;
+        }
+        for (name, path) in Self::script_files() {
+            let commands = match self.load(&name, &path) {
+                Ok(commands) => commands,
+                Err(err) => {
+                    log::error!("Failed to load script {name}: {err}");
+                    continue;
+                }
+            };
+            for (command, permission) in commands {
+                let executor = Arc::new(ScriptCommandExecutor {
+                    script: name.clone(),
+                    command: command.clone(),
+                });
+                if register_command(
+                    dispatcher,
+                    &format!("script.{name}"),
+                    &command,
+                    "A command of a script.",
+                    permission,
+                    executor,
+                ) {
+                    self.commands.lock().insert(command, name.clone());
+                }
+            }
+            log::info!("Loaded script {name}");
+        }
+    }
+
+    /// Unloads every script and loads the script folder again, returning how many scripts were
+    /// loaded and how many failed to
+    pub fn reload(&self) -> (usize, usize) {
+        self.unload_all();
+        if !ADVANCED_CONFIG.scripting.enabled {
+            return (0, 0);
+        }
+        let (mut loaded, mut failed) = (0, 0);
+        for (name, path) in Self::script_files() {
+            match self.load(&name, &path) {
+                Ok(commands) => {
+                    let registered = self.commands.lock();
+                    for (command, _) in commands {
+                        if registered.get(&command) != Some(&name) {
+                            log::warn!(
+                                "Script {name} registered /{command}, which needs a restart"
+                            );
+                        }
+                    }
+                    loaded += 1;
+                }
+                Err(err) => {
+                    log::error!("Failed to load script {name}: {err}");
+                    failed += 1;
+                }
+            }
+        }
+        (loaded, failed)
+    }
+
+    /// The names of the loaded scripts, sorted
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<_> = self.scripts.read().keys().cloned().collect();
+        names.sort_unstable();
+        names
+    }
+
+    pub fn unload_all(&self) {
+        for (_, script) in self.scripts.write().drain() {
+            EVENTS.unregister_all(&script.owner());
+        }
+    }
+}
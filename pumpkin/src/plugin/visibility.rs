@@ -0,0 +1,83 @@
+//! Entities native plugins hide from players, see [`pumpkin_api::visibility`].
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, LazyLock},
+};
+
+use parking_lot::Mutex;
+use pumpkin_api::VisibilityRegistry;
+use pumpkin_entity::EntityId;
+use uuid::Uuid;
+
+/// The entity tracker looks the hidden entities up every tick
+pub static VISIBILITY: LazyLock<Arc<VisibilityManager>> = LazyLock::new(Arc::default);
+
+#[derive(Default)]
+pub struct VisibilityManager {
+    /// The plugins hiding each entity from each player
+    hidden: Mutex<HashMap<Uuid, HashMap<EntityId, HashSet<String>>>>,
+}
+
+impl VisibilityManager {
+    /// The entities hidden from the player
+    pub fn hidden_from(&self, player: Uuid) -> HashSet<EntityId> {
+        self.hidden
+            .lock()
+            .get(&player)
+            .map(|hidden| hidden.keys().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Forgets what was hidden from a player who left
+    pub fn remove_player(&self, player: Uuid) {
+        self.hidden.lock().remove(&player);
+    }
+
+    /// Shows everything the plugin hid again
+    pub fn unregister_all(&self, owner: &str) {
+        let mut hidden = self.hidden.lock();
+        for entities in hidden.values_mut() {
+            entities.retain(|_, owners| {
+                owners.remove(owner);
+                !owners.is_empty()
+            });
+        }
+        hidden.retain(|_, entities| !entities.is_empty());
+    }
+}
+
+impl VisibilityRegistry for VisibilityManager {
+    fn hide(&self, owner: &str, player: Uuid, entity_id: i32) {
+        self.hidden
+            .lock()
+            .entry(player)
+            .or_default()
+            .entry(entity_id)
+            .or_default()
+            .insert(owner.to_string());
+    }
+
+    fn show(&self, owner: &str, player: Uuid, entity_id: i32) {
+        let mut hidden = self.hidden.lock();
+        let Some(entities) = hidden.get_mut(&player) else {
+            return;
+        };
+        if let Some(owners) = entities.get_mut(&entity_id) {
+            owners.remove(owner);
+            if owners.is_empty() {
+                entities.remove(&entity_id);
+            }
+        }
+        if entities.is_empty() {
+            hidden.remove(&player);
+        }
+    }
+
+    fn is_hidden(&self, player: Uuid, entity_id: i32) -> bool {
+        self.hidden
+            .lock()
+            .get(&player)
+            .is_some_and(|entities| entities.contains_key(&entity_id))
+    }
+}
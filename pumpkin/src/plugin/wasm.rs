@@ -0,0 +1,614 @@
+//! WebAssembly plugins, see [`WasmPluginsConfig`](pumpkin_config::WasmPluginsConfig).
+//!
+//! Plugins are `.wasm` modules in the plugin folder. They run sandboxed and only reach the server
+//! through the functions they import from the `pumpkin` module, each of which needs a
+//! [`WasmCapability`]. Strings cross the boundary as UTF-8, events and commands as JSON, so plugins
+//! can be written in any language which compiles to WebAssembly.
+//!
+//! A plugin exports its `memory` and `pumpkin_alloc(len) -> ptr`, which the server uses to pass
+//! strings, and optionally `on_load()`, `on_unload()`, `on_event(ptr, len) -> cancel`,
+//! `on_command(ptr, len)` and `on_task(id)`.
+//!
+//! Commands can only be registered on startup, a reloaded plugin keeps the commands it had.
+
+use std::{
+    collections::HashMap,
+    fs, mem,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::SystemTime,
+};
+
+use async_trait::async_trait;
+use num_traits::FromPrimitive;
+use parking_lot::Mutex;
+use pumpkin_config::{WasmCapability, ADVANCED_CONFIG};
+use pumpkin_core::{
+    math::{position::WorldPosition, vector3::Vector3},
+    text::{color::NamedColor, TextComponent},
+};
+use pumpkin_world::{
+    block::block_registry::get_state_by_state_id, coordinates::ChunkRelativeBlockCoordinates,
+};
+use serde_json::json;
+use wasmtime::{
+    Caller, Config, Engine, Extern, Instance, Linker, Module, Store, StoreLimits,
+    StoreLimitsBuilder, WasmParams, WasmResults,
+};
+
+use crate::{
+    command::{
+        args::{arg_message::MsgArgConsumer, ConsumedArgs, FindArg},
+        dispatcher::{CommandDispatcher, CommandError},
+        CommandExecutor, CommandSender,
+    },
+    entity::player::PermissionLvl,
+    server::Server,
+    world::World,
+};
+
+use super::{block_on, named_event, register_command, COMMAND_ARGS, EVENTS};
+
+/// How often plugin files are checked for changes
+const RELOAD_CHECK_INTERVAL: u64 = 20;
+
+struct ScheduledTask {
+    id: i32,
+    next_tick: u64,
+    /// Ticks between runs, `None` if it only runs once
+    period: Option<u64>,
+}
+
+/// What the functions imported by a plugin have access to
+struct HostState {
+    name: String,
+    capabilities: Vec<WasmCapability>,
+    limits: StoreLimits,
+    world: Arc<World>,
+    current_tick: Arc<AtomicU64>,
+    /// Events the plugin subscribed to while loading
+    subscriptions: Vec<String>,
+    /// Commands the plugin registered while loading
+    commands: Vec<(String, PermissionLvl)>,
+    /// Replies to the command being executed
+    replies: Vec<String>,
+    tasks: Vec<ScheduledTask>,
+    next_task_id: i32,
+}
+
+impl HostState {
+    fn require(&self, capability: WasmCapability) -> wasmtime::Result<()> {
+        if self.capabilities.contains(&capability) {
+            Ok(())
+        } else {
+            Err(wasmtime::Error::msg(format!(
+                "{} lacks the {} capability",
+                self.name,
+                capability.name()
+            )))
+        }
+    }
+}
+
+fn read_string(caller: &mut Caller<'_, HostState>, ptr: i32, len: i32) -> wasmtime::Result<String> {
+    let memory = caller
+        .get_export("memory")
+        .and_then(Extern::into_memory)
+        .ok_or_else(|| wasmtime::Error::msg("the plugin exports no memory"))?;
+    let start = usize::try_from(ptr)?;
+    let end = start + usize::try_from(len)?;
+    let bytes = memory
+        .data(&caller)
+        .get(start..end)
+        .ok_or_else(|| wasmtime::Error::msg("the string is out of bounds"))?;
+    Ok(String::from_utf8(bytes.to_vec())?)
+}
+
+#[expect(clippy::too_many_lines)]
+fn create_linker(engine: &Engine) -> wasmtime::Result<Linker<HostState>> {
+    let mut linker = Linker::new(engine);
+    linker.func_wrap(
+        "pumpkin",
+        "log",
+        |mut caller: Caller<'_, HostState>, level: i32, ptr: i32, len: i32| {
+            let message = read_string(&mut caller, ptr, len)?;
+            let level = match level {
+                0 => log::Level::Error,
+                1 => log::Level::Warn,
+                2 => log::Level::Info,
+                _ => log::Level::Debug,
+            };
+            log::log!(level, "[{}] {message}", caller.data().name);
+            Ok(())
+        },
+    )?;
+    linker.func_wrap(
+        "pumpkin",
+        "subscribe",
+        |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+            caller.data().require(WasmCapability::Events)?;
+            let event = read_string(&mut caller, ptr, len)?;
+            caller.data_mut().subscriptions.push(event);
+            Ok(())
+        },
+    )?;
+    linker.func_wrap(
+        "pumpkin",
+        "register_command",
+        |mut caller: Caller<'_, HostState>, ptr: i32, len: i32, permission: i32| {
+            caller.data().require(WasmCapability::Commands)?;
+            let name = read_string(&mut caller, ptr, len)?;
+            let permission = PermissionLvl::from_i32(permission)
+                .ok_or_else(|| wasmtime::Error::msg("invalid permission level"))?;
+            caller.data_mut().commands.push((name, permission));
+            Ok(())
+        },
+    )?;
+    linker.func_wrap(
+        "pumpkin",
+        "reply",
+        |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+            caller.data().require(WasmCapability::Commands)?;
+            let reply = read_string(&mut caller, ptr, len)?;
+            caller.data_mut().replies.push(reply);
+            Ok(())
+        },
+    )?;
+    linker.func_wrap(
+        "pumpkin",
+        "get_block",
+        |caller: Caller<'_, HostState>, x: i32, y: i32, z: i32| {
+            caller.data().require(WasmCapability::WorldRead)?;
+            // loading the chunk would fire events to the plugin, which is busy
+            let (chunk, relative) =
+                WorldPosition(Vector3::new(x, y, z)).chunk_and_chunk_relative_position();
+            let Some(chunk) = caller.data().world.level.get_loaded_chunk(chunk) else {
+                return Ok(-1);
+            };
+            Ok(block_on(chunk.read())
+                .and_then(|chunk| {
+                    chunk
+                        .blocks
+                        .get_block(ChunkRelativeBlockCoordinates::from(relative))
+                })
+                .map_or(-1, i32::from))
+        },
+    )?;
+    linker.func_wrap(
+        "pumpkin",
+        "set_block",
+        |caller: Caller<'_, HostState>, x: i32, y: i32, z: i32, state: i32| {
+            caller.data().require(WasmCapability::WorldWrite)?;
+            let Some(state) = u16::try_from(state)
+                .ok()
+                .filter(|state| get_state_by_state_id(*state).is_some())
+            else {
+                return Ok(0);
+            };
+            let position = WorldPosition(Vector3::new(x, y, z));
+            let world = caller.data().world.clone();
+            // applied after the call, the plugin does not wait for the chunk
+            tokio::spawn(async move {
+                world.set_block_state(position, state).await;
+            });
+            Ok(1)
+        },
+    )?;
+    linker.func_wrap(
+        "pumpkin",
+        "schedule",
+        |mut caller: Caller<'_, HostState>, delay: i32, period: i32| {
+            caller.data().require(WasmCapability::Scheduler)?;
+            let state = caller.data_mut();
+            let id = state.next_task_id;
+            state.next_task_id += 1;
+            let delay = u64::try_from(delay.max(1))?;
+            state.tasks.push(ScheduledTask {
+                id,
+                next_tick: state.current_tick.load(Ordering::Relaxed) + delay,
+                period: u64::try_from(period).ok().filter(|period| *period > 0),
+            });
+            Ok(id)
+        },
+    )?;
+    linker.func_wrap(
+        "pumpkin",
+        "cancel_task",
+        |mut caller: Caller<'_, HostState>, id: i32| {
+            caller.data().require(WasmCapability::Scheduler)?;
+            caller.data_mut().tasks.retain(|task| task.id != id);
+            Ok(())
+        },
+    )?;
+    Ok(linker)
+}
+
+struct LoadedModule {
+    store: Store<HostState>,
+    instance: Instance,
+}
+
+impl LoadedModule {
+    /// Calls the export if the plugin has it, logging traps like running out of fuel
+    fn call<P: WasmParams, R: WasmResults>(&mut self, export: &str, params: P) -> Option<R> {
+        let func = self
+            .instance
+            .get_typed_func::<P, R>(&mut self.store, export)
+            .ok()?;
+        let name = &self.store.data().name;
+        let fuel = ADVANCED_CONFIG.plugins.wasm.fuel(name);
+        self.store.set_fuel(fuel).ok()?;
+        func.call(&mut self.store, params)
+            .inspect_err(|err| {
+                log::error!(
+                    "Plugin {} failed in {export}: {err}",
+                    self.store.data().name
+                );
+            })
+            .ok()
+    }
+
+    /// Copies the string into the plugin's memory and calls the export with it
+    fn call_with_string<R: WasmResults>(&mut self, export: &str, value: &str) -> Option<R> {
+        let len = i32::try_from(value.len()).ok()?;
+        let ptr: i32 = self.call("pumpkin_alloc", len)?;
+        let memory = self.instance.get_memory(&mut self.store, "memory")?;
+        memory
+            .write(
+                &mut self.store,
+                usize::try_from(ptr).ok()?,
+                value.as_bytes(),
+            )
+            .inspect_err(|err| {
+                log::error!(
+                    "Plugin {} allocated invalid memory: {err}",
+                    self.store.data().name
+                );
+            })
+            .ok()?;
+        self.call(export, (ptr, len))
+    }
+
+    fn run_tasks(&mut self, tick: u64) {
+        let mut due = Vec::new();
+        self.store.data_mut().tasks.retain_mut(|task| {
+            if task.next_tick > tick {
+                return true;
+            }
+            due.push(task.id);
+            match task.period {
+                Some(period) => {
+                    task.next_tick = tick + period;
+                    true
+                }
+                None => false,
+            }
+        });
+        for id in due {
+            self.call::<i32, ()>("on_task", id);
+        }
+    }
+}
+
+pub struct WasmPlugin {
+    name: String,
+    path: PathBuf,
+    modified: Mutex<Option<SystemTime>>,
+    /// `None` while the plugin is unloaded, e.g. because it failed to reload
+    module: Mutex<Option<LoadedModule>>,
+}
+
+impl WasmPlugin {
+    /// The owner of the plugin's listeners
+    fn owner(&self) -> String {
+        format!("wasm:{}", self.name)
+    }
+
+    /// Returns whether the plugin cancelled the event
+    fn on_event(&self, payload: &str) -> bool {
+        let mut module = self.module.lock();
+        module
+            .as_mut()
+            .and_then(|module| module.call_with_string::<i32>("on_event", payload))
+            == Some(1)
+    }
+
+    /// Runs the command, returning the plugin's replies or `None` if it is not loaded
+    fn on_command(&self, payload: &str) -> Option<Vec<String>> {
+        let mut module = self.module.lock();
+        let module = module.as_mut()?;
+        module.call_with_string::<()>("on_command", payload);
+        Some(mem::take(&mut module.store.data_mut().replies))
+    }
+
+    fn unload(&self) {
+        if let Some(mut module) = self.module.lock().take() {
+            module.call::<(), ()>("on_unload", ());
+        }
+        EVENTS.unregister_all(&self.owner());
+    }
+}
+
+/// Returns false if there is no such event
+fn subscribe(plugin: &Arc<WasmPlugin>, event: &str) -> bool {
+    let listener = plugin.clone();
+    named_event::listen(event, &plugin.owner(), move |name, data| {
+        listener.on_event(&json!({ "event": name, "data": data }).to_string())
+    })
+}
+
+struct WasmCommandExecutor {
+    plugin: String,
+    command: String,
+}
+
+#[async_trait]
+impl CommandExecutor for WasmCommandExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender<'a>,
+        server: &Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let args = MsgArgConsumer::find_arg(args, COMMAND_ARGS).unwrap_or_default();
+        let payload = json!({
+            "command": self.command,
+            "args": args,
+            "sender": sender.to_string(),
+            "player": sender.as_player().map(|player| player.gameprofile.id),
+        })
+        .to_string();
+
+        let plugin = server
+            .wasm_plugins
+            .plugins
+            .lock()
+            .get(&self.plugin)
+            .cloned();
+        match plugin.and_then(|plugin| plugin.on_command(&payload)) {
+            Some(replies) => {
+                for reply in replies {
+                    sender.send_message(TextComponent::text_string(reply)).await;
+                }
+            }
+            None => {
+                sender
+                    .send_message(
+                        TextComponent::text_string(format!("{} is not loaded", self.plugin))
+                            .color_named(NamedColor::Red),
+                    )
+                    .await;
+            }
+        }
+        Ok(())
+    }
+}
+
+struct Runtime {
+    engine: Engine,
+    linker: Linker<HostState>,
+}
+
+impl Runtime {
+    fn new() -> wasmtime::Result<Self> {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config)?;
+        let linker = create_linker(&engine)?;
+        Ok(Self { engine, linker })
+    }
+}
+
+pub struct WasmPluginHost {
+    /// `None` if WebAssembly plugins are disabled
+    runtime: Option<Runtime>,
+    /// Plugins can access the default world
+    world: Arc<World>,
+    plugins: Mutex<HashMap<String, Arc<WasmPlugin>>>,
+    /// The commands in the dispatcher and the plugin they belong to
+    commands: Mutex<HashMap<String, String>>,
+    current_tick: Arc<AtomicU64>,
+}
+
+impl WasmPluginHost {
+    #[must_use]
+    pub fn new(world: Arc<World>) -> Self {
+        let config = &ADVANCED_CONFIG.plugins;
+        let runtime = if config.enabled && config.wasm.enabled {
+            Runtime::new()
+                .inspect_err(|err| log::error!("Failed to start the WebAssembly runtime: {err}"))
+                .ok()
+        } else {
+            None
+        };
+        Self {
+            runtime,
+            world,
+            plugins: Mutex::new(HashMap::new()),
+            commands: Mutex::new(HashMap::new()),
+            current_tick: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// The `.wasm` files in the plugin folder by plugin name
+    fn plugin_files() -> HashMap<String, PathBuf> {
+        let folder = Path::new(&ADVANCED_CONFIG.plugins.folder);
+        let Ok(entries) = fs::read_dir(folder) else {
+            return HashMap::new();
+        };
+        entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == "wasm")
+            })
+            .filter_map(|path| {
+                let name = path.file_stem()?.to_str()?.to_string();
+                Some((name, path))
+            })
+            .collect()
+    }
+
+    /// Loads every plugin and registers their commands, which is only possible before the
+    /// dispatcher is shared
+    pub fn load_all(&self, dispatcher: &mut CommandDispatcher<'static>) {
+        if self.runtime.is_none() {
+            return;
+        }
+        for (name, path) in Self::plugin_files() {
+            let plugin = Arc::new(WasmPlugin {
+                name: name.clone(),
+                path,
+                modified: Mutex::new(None),
+                module: Mutex::new(None),
+            });
+            match self.instantiate(&plugin) {
+                Ok(commands) => {
+                    for (command, permission) in commands {
+                        let executor = Arc::new(WasmCommandExecutor {
+                            plugin: name.clone(),
+                            command: command.clone(),
+                        });
+                        if register_command(
+                            dispatcher,
+                            &name,
+                            &command,
+                            "A command of a WebAssembly plugin.",
+                            permission,
+                            executor,
+                        ) {
+                            self.commands.lock().insert(command, name.clone());
+                        }
+                    }
+                    log::info!("Loaded WebAssembly plugin {name}");
+                }
+                Err(err) => log::error!("Failed to load WebAssembly plugin {name}: {err}"),
+            }
+            self.plugins.lock().insert(name, plugin);
+        }
+    }
+
+    /// Loads the plugin's module and lets it subscribe to events, returning the commands it registered
+    fn instantiate(
+        &self,
+        plugin: &Arc<WasmPlugin>,
+    ) -> wasmtime::Result<Vec<(String, PermissionLvl)>> {
+        let Some(runtime) = &self.runtime else {
+            return Ok(Vec::new());
+        };
+        let config = &ADVANCED_CONFIG.plugins.wasm;
+        *plugin.modified.lock() = fs::metadata(&plugin.path)?.modified().ok();
+        let module = Module::from_file(&runtime.engine, &plugin.path)?;
+
+        let state = HostState {
+            name: plugin.name.clone(),
+            capabilities: config.capabilities(&plugin.name).to_vec(),
+            limits: StoreLimitsBuilder::new()
+                .memory_size(config.max_memory(&plugin.name))
+                .instances(1)
+                .build(),
+            world: self.world.clone(),
+            current_tick: self.current_tick.clone(),
+            subscriptions: Vec::new(),
+            commands: Vec::new(),
+            replies: Vec::new(),
+            tasks: Vec::new(),
+            next_task_id: 0,
+        };
+        let mut store = Store::new(&runtime.engine, state);
+        store.limiter(|state| &mut state.limits);
+        store.set_fuel(config.fuel(&plugin.name))?;
+        let instance = runtime.linker.instantiate(&mut store, &module)?;
+
+        let mut module = LoadedModule { store, instance };
+        module.call::<(), ()>("on_load", ());
+        let state = module.store.data_mut();
+        let subscriptions = mem::take(&mut state.subscriptions);
+        let commands = mem::take(&mut state.commands);
+        *plugin.module.lock() = Some(module);
+
+        for event in subscriptions {
+            if !subscribe(plugin, &event) {
+                log::warn!("Plugin {} subscribed to unknown event {event}", plugin.name);
+            }
+        }
+        Ok(commands)
+    }
+
+    fn reload(&self, plugin: &Arc<WasmPlugin>) {
+        plugin.unload();
+        match self.instantiate(plugin) {
+            Ok(commands) => {
+                let registered = self.commands.lock();
+                for (command, _) in commands {
+                    if registered.get(&command) != Some(&plugin.name) {
+                        log::warn!(
+                            "Plugin {} registered /{command}, which needs a restart",
+                            plugin.name
+                        );
+                    }
+                }
+                log::info!("Reloaded WebAssembly plugin {}", plugin.name);
+            }
+            Err(err) => log::error!("Failed to reload WebAssembly plugin {}: {err}", plugin.name),
+        }
+    }
+
+    /// Reloads plugins whose file changed, loads new ones and unloads removed ones
+    fn reload_changed(&self) {
+        let mut files = Self::plugin_files();
+        let plugins: Vec<_> = self.plugins.lock().values().cloned().collect();
+        for plugin in plugins {
+            let Some(path) = files.remove(&plugin.name) else {
+                if plugin.module.lock().is_some() {
+                    plugin.unload();
+                    log::info!("Unloaded WebAssembly plugin {}", plugin.name);
+                }
+                continue;
+            };
+            let modified = fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .ok();
+            if modified != *plugin.modified.lock() {
+                self.reload(&plugin);
+            }
+        }
+        for (name, path) in files {
+            let plugin = Arc::new(WasmPlugin {
+                name: name.clone(),
+                path,
+                modified: Mutex::new(None),
+                module: Mutex::new(None),
+            });
+            self.reload(&plugin);
+            self.plugins.lock().insert(name, plugin);
+        }
+    }
+
+    /// Runs the plugins' due tasks and reloads changed plugins
+    pub fn tick(&self) {
+        if self.runtime.is_none() {
+            return;
+        }
+        let tick = self.current_tick.fetch_add(1, Ordering::Relaxed) + 1;
+        if ADVANCED_CONFIG.plugins.wasm.hot_reload && tick.is_multiple_of(RELOAD_CHECK_INTERVAL) {
+            self.reload_changed();
+        }
+        let plugins: Vec<_> = self.plugins.lock().values().cloned().collect();
+        for plugin in plugins {
+            if let Some(module) = plugin.module.lock().as_mut() {
+                module.run_tasks(tick);
+            }
+        }
+    }
+
+    pub fn unload_all(&self) {
+        for plugin in self.plugins.lock().drain().map(|(_, plugin)| plugin) {
+            plugin.unload();
+        }
+    }
+}
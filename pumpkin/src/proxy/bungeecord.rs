@@ -2,11 +2,9 @@ use std::net::IpAddr;
 
 use pumpkin_protocol::Property;
 use thiserror::Error;
+use uuid::Uuid;
 
-use crate::{
-    client::authentication::{offline_uuid, GameProfile},
-    Client,
-};
+use crate::{client::authentication::GameProfile, Client};
 
 #[derive(Error, Debug)]
 pub enum BungeeCordError {
@@ -16,8 +14,10 @@ pub enum BungeeCordError {
     FailedParseUUID,
     #[error("Failed to parse Properties")]
     FailedParseProperties,
-    #[error("Failed to make offline UUID")]
-    FailedMakeOfflineUUID,
+    #[error(
+        "If you wish to use IP forwarding, please enable it in your BungeeCord config as well!"
+    )]
+    MissingForwardingData,
 }
 
 pub async fn bungeecord_login(
@@ -25,23 +25,19 @@ pub async fn bungeecord_login(
     username: String,
 ) -> Result<(IpAddr, GameProfile), BungeeCordError> {
     let server_address = client.server_address.lock().await;
+    // hostname\0ip\0uuid\0properties
     let data = server_address.split('\0').take(4).collect::<Vec<_>>();
 
-    // Ip of player, only given if ip_forward on bungee is true
-    let ip = match data.get(1) {
-        Some(ip) => ip
-            .parse()
-            .map_err(|_| BungeeCordError::FailedParseAddress)?,
-        None => client.address.lock().await.ip(),
-    };
-
-    // Uuid of player, only given if ip_forward on bungee is true
-    let id = match data.get(2) {
-        Some(uuid) => uuid.parse().map_err(|_| BungeeCordError::FailedParseUUID)?,
-        None => {
-            offline_uuid(username.as_str()).map_err(|_| BungeeCordError::FailedMakeOfflineUUID)?
-        }
+    // Ip and uuid of player, only given if ip_forward on bungee is true.
+    // Without them anyone could join with any name, bypassing the proxy
+    let (Some(ip), Some(uuid)) = (data.get(1), data.get(2)) else {
+        return Err(BungeeCordError::MissingForwardingData);
     };
+    let ip = ip
+        .parse()
+        .map_err(|_| BungeeCordError::FailedParseAddress)?;
+    // Bungee sends the uuid without hyphens, which parsing accepts as well
+    let id: Uuid = uuid.parse().map_err(|_| BungeeCordError::FailedParseUUID)?;
 
     // Read properties and get textures
     // Properties of player's game profile, only given if ip_forward and online_mode
@@ -19,19 +19,26 @@ use crate::client::{authentication::GameProfile, Client};
 
 type HmacSha256 = Hmac<Sha256>;
 
+const MIN_FORWARDING_VERSION: u8 = 1;
 const MAX_SUPPORTED_FORWARDING_VERSION: u8 = 4;
+/// The length of the HMAC-SHA256 signature in front of the forwarded data
+const SIGNATURE_LENGTH: usize = 32;
 const PLAYER_INFO_CHANNEL: &str = "velocity:player_info";
 
 #[derive(Error, Debug)]
 pub enum VelocityError {
-    #[error("No response data received")]
+    #[error("This server requires you to connect with Velocity.")]
     NoData,
+    #[error("Received an unexpected player info response")]
+    UnexpectedMessageId,
+    #[error("Forwarded player info is too short")]
+    MissingSignature,
     #[error("Unable to verify player details")]
     FailedVerifyIntegrity,
     #[error("Failed to read forward version")]
     FailedReadForwardVersion,
-    #[error("Unsupported forwarding version {0}. Maximum supported version is {1}")]
-    UnsupportedForwardVersion(u8, u8),
+    #[error("Unsupported forwarding version {0}. Supported versions are {1} to {2}")]
+    UnsupportedForwardVersion(u8, u8, u8),
     #[error("Failed to read address")]
     FailedReadAddress,
     #[error("Failed to parse address")]
@@ -44,8 +51,8 @@ pub enum VelocityError {
     FailedReadProfileProperties,
 }
 
-pub async fn velocity_login(client: &Client) {
-    // TODO: validate packet transaction id from plugin response with this
+/// Asks the proxy for the player info, returning the message id the response has to use.
+pub async fn velocity_login(client: &Client) -> i32 {
     let velocity_message_id: i32 = rand::thread_rng().gen();
 
     let mut buf = BytesMut::new();
@@ -57,6 +64,7 @@ pub async fn velocity_login(client: &Client) {
             &buf,
         ))
         .await;
+    velocity_message_id
 }
 
 #[must_use]
@@ -101,11 +109,18 @@ fn read_game_profile(buf: &mut ByteBuffer) -> Result<GameProfile, VelocityError>
 pub fn receive_velocity_plugin_response(
     port: u16,
     config: &VelocityConfig,
+    expected_message_id: Option<i32>,
     response: SLoginPluginResponse,
 ) -> Result<(GameProfile, SocketAddr), VelocityError> {
     log::debug!("received velocity response");
+    if expected_message_id != Some(response.message_id.0) {
+        return Err(VelocityError::UnexpectedMessageId);
+    }
     if let Some(data) = response.data {
-        let (signature, data_without_signature) = data.split_at(32);
+        if data.len() < SIGNATURE_LENGTH {
+            return Err(VelocityError::MissingSignature);
+        }
+        let (signature, data_without_signature) = data.split_at(SIGNATURE_LENGTH);
 
         if !check_integrity((signature, data_without_signature), &config.secret) {
             return Err(VelocityError::FailedVerifyIntegrity);
@@ -117,10 +132,12 @@ pub fn receive_velocity_plugin_response(
         let version = buf
             .get_var_int()
             .map_err(|_| VelocityError::FailedReadForwardVersion)?;
-        let version = version.0 as u8;
-        if version > MAX_SUPPORTED_FORWARDING_VERSION {
+        let version =
+            u8::try_from(version.0).map_err(|_| VelocityError::FailedReadForwardVersion)?;
+        if !(MIN_FORWARDING_VERSION..=MAX_SUPPORTED_FORWARDING_VERSION).contains(&version) {
             return Err(VelocityError::UnsupportedForwardVersion(
                 version,
+                MIN_FORWARDING_VERSION,
                 MAX_SUPPORTED_FORWARDING_VERSION,
             ));
         }
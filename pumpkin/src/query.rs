@@ -6,7 +6,8 @@ use std::{
     time::Duration,
 };
 
-use pumpkin_config::{ADVANCED_CONFIG, BASIC_CONFIG};
+use pumpkin_config::{runtime_config, ADVANCED_CONFIG};
+use pumpkin_core::text::TextComponent;
 use pumpkin_protocol::query::{
     CBasicStatus, CFullStatus, CHandshake, PacketType, RawQueryPacket, SHandshake, SStatusRequest,
 };
@@ -15,6 +16,8 @@ use tokio::{net::UdpSocket, sync::RwLock, time};
 
 use crate::server::{Server, CURRENT_MC_VERSION};
 
+const CARGO_PKG_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 pub async fn start_query_handler(server: Arc<Server>, bound_addr: SocketAddr) {
     let mut query_addr = bound_addr;
     if let Some(port) = ADVANCED_CONFIG.query.port {
@@ -52,7 +55,13 @@ pub async fn start_query_handler(server: Arc<Server>, bound_addr: SocketAddr) {
         let valid_challange_tokens = valid_challange_tokens.clone();
         let server = server.clone();
         let mut buf = vec![0; 1024];
-        let (_, addr) = socket.recv_from(&mut buf).await.unwrap();
+        let addr = match socket.recv_from(&mut buf).await {
+            Ok((_, addr)) => addr,
+            Err(err) => {
+                log::debug!("Failed to receive query packet: {err}");
+                continue;
+            }
+        };
 
         tokio::spawn(async move {
             if let Err(err) = handle_packet(
@@ -71,6 +80,34 @@ pub async fn start_query_handler(server: Arc<Server>, bound_addr: SocketAddr) {
     }
 }
 
+/// The MOTD with legacy formatting codes, as query clients don't know text components
+fn motd() -> String {
+    TextComponent::from_mini_message(&runtime_config().motd).to_legacy()
+}
+
+/// The plugins field of the full status, formatted like GameSpy4's `<server mod>: <plugin>; <plugin>`
+/// where every plugin is its name and version
+fn plugin_list(plugins: &[(String, String)]) -> String {
+    let server_mod = format!("Pumpkin {CARGO_PKG_VERSION}");
+    if plugins.is_empty() {
+        return server_mod;
+    }
+    let plugins = plugins
+        .iter()
+        .map(|(name, version)| format!("{name} {version}"))
+        .collect::<Vec<_>>()
+        .join("; ");
+    format!("{server_mod}: {plugins}")
+}
+
+/// The map is the name of the main world, like the `level-name` in vanilla
+fn map_name(server: &Server) -> &str {
+    server
+        .worlds
+        .first()
+        .map_or("world", |world| world.level.name())
+}
+
 // Errors of packets that don't meet the format aren't returned since we won't handle them anyway
 // The only errors that are thrown are because of a null terminator in a CString
 // since those errors need to be corrected by server owner
@@ -111,36 +148,26 @@ async fn handle_packet(
                         .is_some_and(|token_bound_ip: &SocketAddr| token_bound_ip == &addr)
                     {
                         if packet.is_full_request {
-                            // Get 4 players
+                            // vanilla lists every player, names which can not be encoded are skipped
                             let mut players: Vec<CString> = Vec::new();
                             for world in &server.worlds {
-                                let mut world_players = world
-                                    .current_players
-                                    .lock()
-                                    .await
-                                    // Although there is no documented limit, we will limit to 4 players
-                                    .values()
-                                    .take(4 - players.len())
-                                    .map(|player| {
-                                        CString::new(player.gameprofile.name.as_str()).unwrap()
-                                    })
-                                    .collect::<Vec<_>>();
-
-                                players.append(&mut world_players); // Append players from this world
-
-                                if players.len() >= 4 {
-                                    break; // Stop if we've collected 4 players
-                                }
+                                players.extend(
+                                    world.current_players.lock().await.values().filter_map(
+                                        |player| {
+                                            CString::new(player.gameprofile.name.as_str()).ok()
+                                        },
+                                    ),
+                                );
                             }
 
                             let response = CFullStatus {
                                 session_id: packet.session_id,
-                                hostname: CString::new(BASIC_CONFIG.motd.as_str())?,
+                                hostname: CString::new(motd())?,
                                 version: CString::new(CURRENT_MC_VERSION)?,
-                                plugins: CString::new("Pumpkin on 1.21.3")?, // TODO: Fill this with plugins when plugins are working
-                                map: CString::new("world")?, // TODO: Get actual world name
+                                plugins: CString::new(plugin_list(&server.plugins.loaded()))?,
+                                map: CString::new(map_name(&server))?,
                                 num_players: server.get_player_count().await,
-                                max_players: BASIC_CONFIG.max_players as usize,
+                                max_players: runtime_config().max_players as usize,
                                 host_port: bound_addr.port(),
                                 host_ip: CString::new(bound_addr.ip().to_string())?,
                                 players,
@@ -152,10 +179,10 @@ async fn handle_packet(
                         } else {
                             let resposne = CBasicStatus {
                                 session_id: packet.session_id,
-                                motd: CString::new(BASIC_CONFIG.motd.as_str())?,
-                                map: CString::new("world")?,
+                                motd: CString::new(motd())?,
+                                map: CString::new(map_name(&server))?,
                                 num_players: server.get_player_count().await,
-                                max_players: BASIC_CONFIG.max_players as usize,
+                                max_players: runtime_config().max_players as usize,
                                 host_port: bound_addr.port(),
                                 host_ip: CString::new(bound_addr.ip().to_string())?,
                             };
@@ -171,3 +198,44 @@ async fn handle_packet(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use std::ffi::CString;
+
+    use pumpkin_protocol::query::CFullStatus;
+
+    use super::{plugin_list, CARGO_PKG_VERSION};
+
+    #[test]
+    fn lists_no_plugins() {
+        assert_eq!(plugin_list(&[]), format!("Pumpkin {CARGO_PKG_VERSION}"));
+    }
+
+    #[tokio::test]
+    async fn full_status_lists_plugins() {
+        let plugins = [
+            ("Plugin1".to_string(), "1.0".to_string()),
+            ("Plugin2".to_string(), "2.1".to_string()),
+        ];
+        let packet = CFullStatus {
+            session_id: 1,
+            hostname: CString::new("A Minecraft Server").unwrap(),
+            version: CString::new("1.21.3").unwrap(),
+            plugins: CString::new(plugin_list(&plugins)).unwrap(),
+            map: CString::new("world").unwrap(),
+            num_players: 0,
+            max_players: 20,
+            host_port: 25565,
+            host_ip: CString::new("127.0.0.1").unwrap(),
+            players: Vec::new(),
+        };
+
+        let expected =
+            format!("\0plugins\0Pumpkin {CARGO_PKG_VERSION}: Plugin1 1.0; Plugin2 2.1\0map\0");
+        let encoded = packet.encode().await;
+        assert!(encoded
+            .windows(expected.len())
+            .any(|window| window == expected.as_bytes()));
+    }
+}
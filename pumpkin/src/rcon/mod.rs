@@ -1,4 +1,7 @@
-use std::net::SocketAddr;
+use std::{
+    net::SocketAddr,
+    sync::atomic::{AtomicU32, Ordering},
+};
 
 use packet::{ClientboundPacket, Packet, PacketError, ServerboundPacket};
 use pumpkin_config::{RCONConfig, ADVANCED_CONFIG};
@@ -14,27 +17,34 @@ pub struct RCONServer;
 impl RCONServer {
     pub async fn new(config: &RCONConfig, server: Arc<Server>) -> Result<Self, std::io::Error> {
         assert!(config.enabled, "RCON is not enabled");
-        let listener = tokio::net::TcpListener::bind(config.address).await.unwrap();
+        let listener = tokio::net::TcpListener::bind(config.address).await?;
 
         let password = Arc::new(config.password.clone());
 
-        let mut connections = 0;
+        let connections = Arc::new(AtomicU32::new(0));
         loop {
             // Asynchronously wait for an inbound socket.
             let (connection, address) = listener.accept().await?;
 
-            if config.max_connections != 0 && connections >= config.max_connections {
+            if config.max_connections != 0
+                && connections.load(Ordering::Relaxed) >= config.max_connections
+            {
                 continue;
             }
 
-            connections += 1;
+            connections.fetch_add(1, Ordering::Relaxed);
             let mut client = RCONClient::new(connection, address);
 
             let password = password.clone();
             let server = server.clone();
-            tokio::spawn(async move { while !client.handle(&server, &password).await {} });
-            log::debug!("closed RCON connection");
-            connections -= 1;
+            let connections = connections.clone();
+            tokio::spawn(async move {
+                while !client.handle(&server, &password).await {}
+                if ADVANCED_CONFIG.rcon.logging.log_quit {
+                    log::info!("RCON ({}): Client closed connection", client.address);
+                }
+                connections.fetch_sub(1, Ordering::Relaxed);
+            });
         }
     }
 }
@@ -72,17 +82,26 @@ impl RCONClient {
                 }
             }
             // If we get a close here, we might have a reply, which we still want to write.
-            let _ = self.poll(server, password).await.map_err(|e| {
-                log::error!("RCON error: {e}");
-                self.closed = true;
-            });
+            // A single read may contain several packets
+            loop {
+                match self.poll(server, password).await {
+                    Ok(true) if !self.closed => {}
+                    Ok(_) => break,
+                    Err(e) => {
+                        log::error!("RCON error: {e}");
+                        self.closed = true;
+                        break;
+                    }
+                }
+            }
         }
         self.closed
     }
 
-    async fn poll(&mut self, server: &Arc<Server>, password: &str) -> Result<(), PacketError> {
+    /// Handles the next received packet, returns false if none was received completely yet
+    async fn poll(&mut self, server: &Arc<Server>, password: &str) -> Result<bool, PacketError> {
         let Some(packet) = self.receive_packet().await? else {
-            return Ok(());
+            return Ok(false);
         };
         let config = &ADVANCED_CONFIG.rcon;
         match packet.get_type() {
@@ -105,7 +124,7 @@ impl RCONClient {
             ServerboundPacket::ExecCommand => {
                 if self.logged_in {
                     let output = tokio::sync::Mutex::new(Vec::new());
-                    let dispatcher = server.command_dispatcher.clone();
+                    let dispatcher = server.command_dispatcher().await;
                     dispatcher
                         .handle_command(
                             &mut crate::command::CommandSender::Rcon(&output),
@@ -114,17 +133,31 @@ impl RCONClient {
                         )
                         .await;
                     let output = output.lock().await;
-                    for line in output.iter() {
-                        if config.logging.log_commands {
+                    if config.logging.log_commands {
+                        for line in output.iter() {
                             log::info!("RCON ({}): {}", self.address, line);
                         }
-                        self.send(ClientboundPacket::Output, packet.get_id(), line)
+                    }
+                    let output = output.join("\n");
+                    // an empty response still has to be answered
+                    let mut chunks = split_response(&output);
+                    if chunks.is_empty() {
+                        chunks.push("");
+                    }
+                    for chunk in chunks {
+                        self.send(ClientboundPacket::Output, packet.get_id(), chunk)
                             .await?;
                     }
                 }
             }
+            ServerboundPacket::ResponseValue => {
+                if self.logged_in {
+                    self.send(ClientboundPacket::Output, packet.get_id(), "")
+                        .await?;
+                }
+            }
         }
-        Ok(())
+        Ok(true)
     }
 
     async fn read_bytes(&mut self) -> std::io::Result<bool> {
@@ -145,7 +178,7 @@ impl RCONClient {
     ) -> Result<(), PacketError> {
         let buf = packet.write_buf(id, body);
         self.connection
-            .write(&buf)
+            .write_all(&buf)
             .await
             .map_err(PacketError::FailedSend)?;
         Ok(())
@@ -155,3 +188,18 @@ impl RCONClient {
         Packet::deserialize(&mut self.incoming).await
     }
 }
+
+/// Splits a response into bodies fitting into single packets, without splitting characters.
+fn split_response(mut output: &str) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    while !output.is_empty() {
+        let mut end = output.len().min(ClientboundPacket::MAX_BODY_LEN);
+        while !output.is_char_boundary(end) {
+            end -= 1;
+        }
+        let (chunk, rest) = output.split_at(end);
+        chunks.push(chunk);
+        output = rest;
+    }
+    chunks
+}
@@ -12,18 +12,22 @@ use tokio::io::AsyncReadExt;
 #[repr(i32)]
 pub enum ServerboundPacket {
     /// Typically, the first packet sent by the client, which is used to authenticate the connection with the server.
-    Auth = 2,
+    Auth = 3,
     /// This packet type represents a command issued to the server by a client. This can be a `ConCommand` such as kill <player> or weather clear.
     /// The response will vary depending on the command issued.
-    ExecCommand = 3,
+    ExecCommand = 2,
+    /// Clients send an empty response value after a command, the server mirrors it so the client
+    /// knows a response split over multiple packets is complete.
+    ResponseValue = 0,
 }
 
 impl ServerboundPacket {
-    pub const fn from_i32(n: i32) -> Self {
+    pub const fn from_i32(n: i32) -> Option<Self> {
         match n {
-            //  3 => Self::Auth,
-            2 => Self::ExecCommand,
-            _ => Self::Auth,
+            3 => Some(Self::Auth),
+            2 => Some(Self::ExecCommand),
+            0 => Some(Self::ResponseValue),
+            _ => None,
         }
     }
 }
@@ -40,6 +44,9 @@ pub enum ClientboundPacket {
 }
 
 impl ClientboundPacket {
+    /// The longest body a single response packet may carry, longer responses are split.
+    pub const MAX_BODY_LEN: usize = 4096;
+
     pub fn write_buf(self, id: i32, body: &str) -> BytesMut {
         // let len = outgoing.len() as u64;
         let mut buf = BytesMut::new();
@@ -65,6 +72,8 @@ pub enum PacketError {
     FailedSend(std::io::Error),
     #[error("invalid Packet String body")]
     InvalidBody(FromUtf8Error),
+    #[error("unknown packet type {0}")]
+    UnknownType(i32),
 }
 
 #[derive(Debug)]
@@ -82,9 +91,14 @@ impl Packet {
         }
         let mut buf = Cursor::new(&incoming);
         let len = buf.read_i32_le().await.map_err(PacketError::FailedRead)? + 4;
-        if !(0..=1460).contains(&len) {
+        // 14 is the length of an empty packet, including the length itself
+        if !(14..=1460).contains(&len) {
             return Err(PacketError::InvalidLength);
         }
+        // the rest of the packet has not arrived yet
+        if incoming.len() < len as usize {
+            return Ok(None);
+        }
         let id = buf.read_i32_le().await.map_err(PacketError::FailedRead)?;
         let ty = buf.read_i32_le().await.map_err(PacketError::FailedRead)?;
         let mut payload = vec![];
@@ -100,7 +114,7 @@ impl Packet {
 
         let packet = Self {
             id,
-            ptype: ServerboundPacket::from_i32(ty),
+            ptype: ServerboundPacket::from_i32(ty).ok_or(PacketError::UnknownType(ty))?,
             body: String::from_utf8(payload).map_err(PacketError::InvalidBody)?,
         };
 
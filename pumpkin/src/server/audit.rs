@@ -0,0 +1,221 @@
+//! The audit log, recording what operators do, see [`AuditLogConfig`](pumpkin_config::AuditLogConfig).
+//!
+//! Every entry is one JSON object per line, so the file can be processed with common tools.
+//! Once the file gets too large it is rotated to `<path>.1`, moving older files up by one.
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::PathBuf,
+};
+
+use parking_lot::Mutex;
+use pumpkin_config::ADVANCED_CONFIG;
+use serde::{Deserialize, Serialize};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use uuid::Uuid;
+
+use crate::command::CommandSender;
+
+/// Who did something
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AuditSource {
+    Console,
+    Rcon,
+    Player { name: String, uuid: Uuid },
+    Plugin { name: String },
+}
+
+impl AuditSource {
+    #[must_use]
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Console => "Server",
+            Self::Rcon => "Rcon",
+            Self::Player { name, .. } | Self::Plugin { name } => name,
+        }
+    }
+}
+
+impl From<&CommandSender<'_>> for AuditSource {
+    fn from(sender: &CommandSender<'_>) -> Self {
+        match sender {
+            CommandSender::Console => Self::Console,
+            CommandSender::Rcon(_) => Self::Rcon,
+            CommandSender::Player(player) => Self::Player {
+                name: player.gameprofile.name.clone(),
+                uuid: player.gameprofile.id,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Command,
+    Gamemode,
+    Kick,
+    Ban,
+    Pardon,
+    BanIp,
+    PardonIp,
+    Op,
+    Deop,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AuditEntry {
+    /// RFC 3339
+    pub time: String,
+    pub source: AuditSource,
+    pub action: AuditAction,
+    /// The player or IP the action was done to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    pub details: String,
+}
+
+impl AuditEntry {
+    /// Whether the player did the action or it was done to them
+    #[must_use]
+    pub fn involves(&self, name: &str) -> bool {
+        self.source.name().eq_ignore_ascii_case(name)
+            || self
+                .target
+                .as_ref()
+                .is_some_and(|target| target.eq_ignore_ascii_case(name))
+    }
+}
+
+impl std::fmt::Display for AuditEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "[{}] {} {:?}",
+            self.time,
+            self.source.name(),
+            self.action
+        )?;
+        if let Some(target) = &self.target {
+            write!(f, " {target}")?;
+        }
+        write!(f, ": {}", self.details)
+    }
+}
+
+/// Appends entries to the audit log file
+#[derive(Default)]
+pub struct AuditLog {
+    /// Opened on the first entry
+    file: Mutex<Option<File>>,
+}
+
+impl AuditLog {
+    fn path() -> PathBuf {
+        PathBuf::from(&ADVANCED_CONFIG.audit_log.path)
+    }
+
+    /// The `index`th rotated file, 0 is the current one
+    fn rotated_path(index: u32) -> PathBuf {
+        let path = Self::path();
+        if index == 0 {
+            return path;
+        }
+        let mut name = path.into_os_string();
+        name.push(format!(".{index}"));
+        name.into()
+    }
+
+    /// Records that the source did the action, if the audit log is enabled
+    pub fn record(
+        &self,
+        source: AuditSource,
+        action: AuditAction,
+        target: Option<&str>,
+        details: impl Into<String>,
+    ) {
+        let config = &ADVANCED_CONFIG.audit_log;
+        if !config.enabled || (action == AuditAction::Command && !config.commands) {
+            return;
+        }
+        let entry = AuditEntry {
+            time: OffsetDateTime::now_utc()
+                .format(&Rfc3339)
+                .unwrap_or_default(),
+            source,
+            action,
+            target: target.map(str::to_string),
+            details: details.into(),
+        };
+        if let Err(err) = self.write(&entry) {
+            log::error!("Couldn't write to the audit log: {err}");
+        }
+    }
+
+    fn write(&self, entry: &AuditEntry) -> std::io::Result<()> {
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+        let mut file = self.file.lock();
+        let open = match file.take() {
+            Some(open) => file.insert(open),
+            None => file.insert(Self::open()?),
+        };
+        open.write_all(line.as_bytes())?;
+
+        let max_size = ADVANCED_CONFIG.audit_log.max_file_size;
+        if max_size != 0 && open.metadata()?.len() >= max_size {
+            *file = None;
+            Self::rotate()?;
+        }
+        Ok(())
+    }
+
+    fn open() -> std::io::Result<File> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(Self::path())
+    }
+
+    /// Moves every file up by one, dropping the oldest
+    fn rotate() -> std::io::Result<()> {
+        let max_files = ADVANCED_CONFIG.audit_log.max_files;
+        if max_files == 0 {
+            return fs::remove_file(Self::path());
+        }
+        for index in (0..max_files).rev() {
+            let from = Self::rotated_path(index);
+            if from.exists() {
+                fs::rename(from, Self::rotated_path(index + 1))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The most recent entries, newest first, optionally only those involving a player
+    pub fn recent(&self, count: usize, player: Option<&str>) -> Vec<AuditEntry> {
+        // nothing may be half written while reading
+        let _file = self.file.lock();
+        let mut entries = Vec::new();
+        for index in 0..=ADVANCED_CONFIG.audit_log.max_files {
+            let Ok(file) = File::open(Self::rotated_path(index)) else {
+                break;
+            };
+            let mut from_file: Vec<AuditEntry> = BufReader::new(file)
+                .lines()
+                .map_while(Result::ok)
+                .filter_map(|line| serde_json::from_str(&line).ok())
+                .filter(|entry: &AuditEntry| player.is_none_or(|player| entry.involves(player)))
+                .collect();
+            from_file.reverse();
+            entries.extend(from_file);
+            if entries.len() >= count {
+                break;
+            }
+        }
+        entries.truncate(count);
+        entries
+    }
+}
@@ -0,0 +1,191 @@
+//! Online backups of the worlds.
+//!
+//! A backup saves the worlds, then hard links or copies their files into a staging folder, which
+//! is quick enough not to be noticed. Compressing the staged files into a `.tar.zst` archive and
+//! deleting old archives happens on the blocking pool, while the server keeps running.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime},
+};
+
+use pumpkin_config::BackupConfig;
+use time::{
+    format_description::BorrowedFormatItem, macros::format_description, OffsetDateTime,
+    PrimitiveDateTime,
+};
+use tokio::task::JoinHandle;
+
+use super::Server;
+
+/// Sorts like the time it stands for, so the newest archive has the greatest name
+const TIME_FORMAT: &[BorrowedFormatItem] =
+    format_description!("[year]-[month]-[day]_[hour]-[minute]-[second]");
+const EXTENSION: &str = ".tar.zst";
+/// The file of a running server, which isn't worth restoring
+const SESSION_LOCK: &str = "session.lock";
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Lets the next backup start once the running one is done or failed
+struct RunningGuard;
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        RUNNING.store(false, Ordering::Release);
+    }
+}
+
+/// Saves the worlds and archives them in the background. The task returns the written archives,
+/// `None` is returned if a backup is running already
+pub async fn start(
+    server: &Server,
+    config: &'static BackupConfig,
+) -> Option<JoinHandle<io::Result<Vec<PathBuf>>>> {
+    if RUNNING.swap(true, Ordering::AcqRel) {
+        return None;
+    }
+    let guard = RunningGuard;
+    let mut worlds = Vec::new();
+    for world in &server.worlds {
+        world.save_persistent_data().await;
+        if let Some(root) = world.level.root_folder() {
+            worlds.push((world.level.name().to_string(), root.to_path_buf()));
+        }
+    }
+    let time = OffsetDateTime::now_utc()
+        .format(TIME_FORMAT)
+        .unwrap_or_default();
+    Some(tokio::task::spawn_blocking(move || {
+        let _guard = guard;
+        let folder = PathBuf::from(&config.folder);
+        fs::create_dir_all(&folder)?;
+        let mut archives = Vec::new();
+        for (name, root) in worlds {
+            let archive = backup_world(&folder, &name, &root, &time, config)?;
+            log::info!("Backed up {name} to {}", archive.display());
+            archives.push(archive);
+            if let Err(err) = prune(&folder, &name, config) {
+                log::warn!("Failed to delete old backups of {name}: {err}");
+            }
+        }
+        Ok(archives)
+    }))
+}
+
+/// Makes a backup every `interval_minutes` until the server stops
+pub async fn run_scheduled(server: Arc<Server>, config: &'static BackupConfig) {
+    let period = Duration::from_secs(config.interval_minutes.max(1) * 60);
+    let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            () = server.shutdown.requested() => return,
+        }
+        let Some(task) = start(&server, config).await else {
+            log::warn!("Skipped a scheduled backup, the last one is still running");
+            continue;
+        };
+        match task.await {
+            Ok(Ok(_)) => {}
+            Ok(Err(err)) => log::error!("Failed to back up the worlds: {err}"),
+            Err(err) => log::error!("The backup task failed: {err}"),
+        }
+    }
+}
+
+fn backup_world(
+    folder: &Path,
+    name: &str,
+    root: &Path,
+    time: &str,
+    config: &BackupConfig,
+) -> io::Result<PathBuf> {
+    let staging = folder.join(format!(".staging-{name}-{time}"));
+    let result = stage(root, &staging, config.hard_link)
+        .and_then(|()| archive(folder, name, &staging, time, config.level));
+    let _ = fs::remove_dir_all(&staging);
+    result
+}
+
+/// Links or copies the world's files, they can't change while they are compressed then
+fn stage(from: &Path, to: &Path, hard_link: bool) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let path = entry.path();
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            stage(&path, &target, hard_link)?;
+        } else if entry.file_name() != SESSION_LOCK {
+            // links don't work across file systems
+            if !hard_link || fs::hard_link(&path, &target).is_err() {
+                fs::copy(&path, &target)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Writes the archive under a temporary name first, so half written ones are never pruned or
+/// restored
+fn archive(
+    folder: &Path,
+    name: &str,
+    staging: &Path,
+    time: &str,
+    level: i32,
+) -> io::Result<PathBuf> {
+    let path = folder.join(format!("{name}-{time}{EXTENSION}"));
+    let partial = folder.join(format!("{name}-{time}{EXTENSION}.part"));
+    let encoder = zstd::Encoder::new(fs::File::create(&partial)?, level)?;
+    let mut builder = tar::Builder::new(encoder);
+    builder.append_dir_all(name, staging)?;
+    builder.into_inner()?.finish()?.sync_all()?;
+    fs::rename(&partial, &path)?;
+    Ok(path)
+}
+
+/// Deletes the archives of the world which are too old or more than `keep_last`
+fn prune(folder: &Path, name: &str, config: &BackupConfig) -> io::Result<()> {
+    let mut archives = Vec::new();
+    for entry in fs::read_dir(folder)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let Some(time) = file_name
+            .to_str()
+            .and_then(|file_name| file_name.strip_prefix(name))
+            .and_then(|rest| rest.strip_prefix('-'))
+            .and_then(|rest| rest.strip_suffix(EXTENSION))
+        else {
+            continue;
+        };
+        // other worlds can start with the name too, e.g. `world-2` with `world`
+        if PrimitiveDateTime::parse(time, TIME_FORMAT).is_ok() {
+            archives.push(entry.path());
+        }
+    }
+    archives.sort();
+    let max_age = Duration::from_secs(config.max_age_days * 24 * 60 * 60);
+    let now = SystemTime::now();
+    let count = archives.len();
+    for (index, archive) in archives.into_iter().enumerate() {
+        let too_many = config.keep_last > 0 && index + config.keep_last < count;
+        let too_old = config.max_age_days > 0
+            && fs::metadata(&archive)?
+                .modified()
+                .ok()
+                .and_then(|modified| now.duration_since(modified).ok())
+                .is_some_and(|age| age > max_age);
+        if too_many || too_old {
+            fs::remove_file(&archive)?;
+            log::info!("Deleted the old backup {}", archive.display());
+        }
+    }
+    Ok(())
+}
@@ -6,13 +6,18 @@ use std::{
 };
 
 use base64::{engine::general_purpose, Engine as _};
-use pumpkin_config::{BasicConfiguration, BASIC_CONFIG};
+use pumpkin_config::{BasicConfiguration, RuntimeConfig, ADVANCED_CONFIG, BASIC_CONFIG};
+use pumpkin_core::text::TextComponent;
 use pumpkin_protocol::{
     client::{config::CPluginMessage, status::CStatusResponse},
-    Players, StatusResponse, VarInt, Version, CURRENT_MC_PROTOCOL,
+    legacy_ping::{CLegacyStatus, LegacyPing},
+    Players, Sample, StatusResponse, VarInt, Version, CURRENT_MC_PROTOCOL,
 };
+use rand::{seq::SliceRandom, thread_rng};
+use uuid::Uuid;
 
 use super::CURRENT_MC_VERSION;
+use crate::client::{authentication::GameProfile, chat_session};
 
 const DEFAULT_ICON: &[u8] = include_bytes!("../../../assets/default_icon.png");
 
@@ -27,8 +32,13 @@ fn load_icon_from_bytes(png_data: &[u8]) -> Result<String, Box<dyn error::Error>
     let icon = png::Decoder::new(Cursor::new(&png_data));
     let reader = icon.read_info()?;
     let info = reader.info();
-    assert!(info.width == 64, "Icon width must be 64");
-    assert!(info.height == 64, "Icon height must be 64");
+    if info.width != 64 || info.height != 64 {
+        return Err(format!(
+            "the icon must be 64x64 pixels, but is {}x{}",
+            info.width, info.height
+        )
+        .into());
+    }
 
     // Reader consumes the image. Once we verify dimensions, we want to encode the entire raw image
     let mut result = "data:image/png;base64,".to_owned();
@@ -41,6 +51,8 @@ pub struct CachedStatus {
     // We cache the json response here so we don't parse it every time someone makes a Status request.
     // Keep in mind that we must parse this again, when the StatusResponse changes which usually happen when a player joins or leaves
     status_response_json: String,
+    /// The online players who allow being shown in the server list
+    listed_players: Vec<Sample>,
 }
 
 pub struct CachedBranding {
@@ -76,6 +88,7 @@ impl CachedStatus {
         Self {
             status_response,
             status_response_json,
+            listed_players: Vec::new(),
         }
     }
 
@@ -83,24 +96,68 @@ impl CachedStatus {
         CStatusResponse::new(&self.status_response_json)
     }
 
-    // TODO: Player samples
-    pub fn add_player(&mut self) {
-        let status_response = &mut self.status_response;
-        if let Some(players) = &mut status_response.players {
+    /// The status for clients from before the Netty rewrite, see [`LegacyPing`]
+    pub fn get_legacy_status(&self, ping: LegacyPing) -> Vec<u8> {
+        // old clients only show a single line
+        let motd = self
+            .status_response
+            .description
+            .to_legacy()
+            .replace('\n', " ");
+        let (online, max) = self
+            .status_response
+            .players
+            .as_ref()
+            .map_or((0, 0), |players| (players.online, players.max));
+        CLegacyStatus::new(CURRENT_MC_VERSION, &motd, online, max).encode(ping)
+    }
+
+    /// Counts the player, who is only shown in the sample if `listed`, as the client can opt out of that.
+    pub fn add_player(&mut self, profile: &GameProfile, listed: bool) {
+        if let Some(players) = &mut self.status_response.players {
             players.online += 1;
         }
+        if listed {
+            self.listed_players.push(Sample {
+                name: profile.name.clone(),
+                id: profile.id.to_string(),
+            });
+        }
+        self.update();
+    }
 
-        self.status_response_json = serde_json::to_string(&status_response)
-            .expect("Failed to parse Status response into JSON");
+    pub fn remove_player(&mut self, profile: &GameProfile) {
+        if let Some(players) = &mut self.status_response.players {
+            players.online = players.online.saturating_sub(1);
+        }
+        let id = profile.id.to_string();
+        self.listed_players.retain(|sample| sample.id != id);
+        self.update();
     }
 
-    pub fn remove_player(&mut self) {
-        let status_response = &mut self.status_response;
-        if let Some(players) = &mut status_response.players {
-            players.online -= 1;
+    /// Shows the reloaded MOTD and max players
+    pub fn apply_runtime_config(&mut self, config: &RuntimeConfig) {
+        self.status_response.description = TextComponent::from_mini_message(&config.motd);
+        if let Some(players) = &mut self.status_response.players {
+            players.max = config.max_players;
+        }
+        self.update();
+    }
+
+    fn update(&mut self) {
+        let config = &ADVANCED_CONFIG.server_list;
+        if let Some(players) = &mut self.status_response.players {
+            // a configured sample is kept, otherwise a random selection like in vanilla
+            if config.custom_sample.is_empty() {
+                players.sample = self
+                    .listed_players
+                    .choose_multiple(&mut thread_rng(), config.sample_size)
+                    .cloned()
+                    .collect();
+            }
         }
 
-        self.status_response_json = serde_json::to_string(&status_response)
+        self.status_response_json = serde_json::to_string(&self.status_response)
             .expect("Failed to parse Status response into JSON");
     }
 
@@ -139,19 +196,30 @@ impl CachedStatus {
             None
         };
 
+        let server_list = &ADVANCED_CONFIG.server_list;
+        let players = (!server_list.hide_player_count).then(|| Players {
+            max: config.max_players,
+            online: 0,
+            // the client shows the names as they are, so the lines are converted to legacy codes
+            sample: server_list
+                .custom_sample
+                .iter()
+                .map(|line| Sample {
+                    name: TextComponent::from_mini_message(line).to_legacy(),
+                    id: Uuid::nil().to_string(),
+                })
+                .collect(),
+        });
+
         StatusResponse {
             version: Some(Version {
                 name: CURRENT_MC_VERSION.into(),
                 protocol: CURRENT_MC_PROTOCOL,
             }),
-            players: Some(Players {
-                max: config.max_players,
-                online: 0,
-                sample: vec![],
-            }),
-            description: config.motd.clone(),
+            players,
+            description: TextComponent::from_mini_message(&config.motd),
             favicon: icon,
-            enforce_secure_chat: false,
+            enforce_secure_chat: chat_session::enforces_secure_chat(),
         }
     }
 }
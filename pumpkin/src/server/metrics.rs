@@ -0,0 +1,124 @@
+use std::{
+    collections::VecDeque,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+
+/// How many ticks are kept to calculate the averages, 5 seconds at 20 TPS
+const SAMPLE_COUNT: usize = 100;
+
+struct TickSample {
+    start: Instant,
+    duration: Duration,
+}
+
+/// The upper bounds of the tick duration histogram buckets, in seconds
+pub const TICK_BUCKETS: [f64; 8] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0];
+
+/// The durations of every tick since the server started
+#[derive(Clone, Default)]
+pub struct TickHistogram {
+    /// How many ticks took at most the bound of the bucket with the same index
+    pub buckets: [u64; TICK_BUCKETS.len()],
+    pub count: u64,
+    /// The total duration in seconds
+    pub sum: f64,
+}
+
+impl TickHistogram {
+    fn record(&mut self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        for (bucket, bound) in self.buckets.iter_mut().zip(TICK_BUCKETS) {
+            if seconds <= bound {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += seconds;
+    }
+}
+
+/// Keeps track of the duration of recent ticks.
+pub struct TickMetrics {
+    samples: Mutex<VecDeque<TickSample>>,
+    histogram: Mutex<TickHistogram>,
+}
+
+impl Default for TickMetrics {
+    fn default() -> Self {
+        Self {
+            samples: Mutex::new(VecDeque::with_capacity(SAMPLE_COUNT)),
+            histogram: Mutex::new(TickHistogram::default()),
+        }
+    }
+}
+
+impl TickMetrics {
+    pub fn record_tick(&self, start: Instant, duration: Duration) {
+        let mut samples = self.samples.lock();
+        if samples.len() == SAMPLE_COUNT {
+            samples.pop_front();
+        }
+        samples.push_back(TickSample { start, duration });
+        drop(samples);
+        self.histogram.lock().record(duration);
+    }
+
+    pub fn histogram(&self) -> TickHistogram {
+        self.histogram.lock().clone()
+    }
+
+    /// Average milliseconds per tick
+    pub fn mspt(&self) -> f64 {
+        let samples = self.samples.lock();
+        if samples.is_empty() {
+            return 0.0;
+        }
+        let total: Duration = samples.iter().map(|sample| sample.duration).sum();
+        total.as_secs_f64() * 1000.0 / f64::from(samples.len() as u32)
+    }
+
+    /// Average ticks per second, measured between the start of the recorded ticks
+    pub fn tps(&self) -> f64 {
+        let samples = self.samples.lock();
+        let (Some(first), Some(last)) = (samples.front(), samples.back()) else {
+            return 0.0;
+        };
+        let elapsed = last.start.duration_since(first.start).as_secs_f64();
+        if elapsed == 0.0 {
+            return 0.0;
+        }
+        f64::from(samples.len() as u32 - 1) / elapsed
+    }
+}
+
+/// Counts the packets of every client since the server started
+pub struct PacketCounters {
+    received: AtomicU64,
+    sent: AtomicU64,
+}
+
+pub static PACKETS: PacketCounters = PacketCounters {
+    received: AtomicU64::new(0),
+    sent: AtomicU64::new(0),
+};
+
+impl PacketCounters {
+    pub fn count_received(&self) {
+        self.received.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn count_sent(&self, count: u64) {
+        self.sent.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn received(&self) -> u64 {
+        self.received.load(Ordering::Relaxed)
+    }
+
+    pub fn sent(&self) -> u64 {
+        self.sent.load(Ordering::Relaxed)
+    }
+}
@@ -1,36 +1,65 @@
 use connection_cache::{CachedBranding, CachedStatus};
+use crossbeam::atomic::AtomicCell;
 use key_store::KeyStore;
-use pumpkin_config::BASIC_CONFIG;
-use pumpkin_core::GameMode;
+use pumpkin_api::event::{player::PlayerQuitEvent, server::ConfigReloadedEvent};
+use pumpkin_config::{
+    dimension_effects::DimensionEffectsConfig, world_config, ADVANCED_CONFIG, BASIC_CONFIG,
+};
+use pumpkin_core::{text::TextComponent, GameMode};
 use pumpkin_entity::EntityId;
 use pumpkin_inventory::drag_handler::DragHandler;
 use pumpkin_inventory::{Container, OpenContainer};
 use pumpkin_protocol::client::login::CEncryptionRequest;
+use pumpkin_protocol::client::play::CPlayUpdateTags;
 use pumpkin_protocol::{client::config::CPluginMessage, ClientPacket};
-use pumpkin_registry::Registry;
+use pumpkin_registry::{load_datapack_content, network_tags, Datapacks, Registry};
 use pumpkin_world::dimension::Dimension;
+use pumpkin_world::game_rules::register_pumpkin_game_rules;
 use rand::prelude::SliceRandom;
 use std::collections::HashMap;
-use std::{
-    sync::{
-        atomic::{AtomicI32, Ordering},
-        Arc,
-    },
-    time::Duration,
-};
+use std::{sync::Arc, time::Duration};
 use tokio::sync::{Mutex, RwLock};
 
+use crate::client::authentication::GameProfile;
 use crate::client::EncryptionError;
 use crate::{
     client::Client,
-    command::{default_dispatcher, dispatcher::CommandDispatcher},
-    entity::player::Player,
-    world::World,
+    command::{client_cmd_suggestions, default_dispatcher, dispatcher::CommandDispatcher},
+    data::last_death_data,
+    entity::{self, player::Player, player_set::PlayerSet},
+    permission::{self, toml_provider::TomlPermissionProvider},
+    plugin::{
+        self,
+        economy::{self, SimpleEconomy},
+        menu::MENUS,
+        persistent_data::PERSISTENT_DATA,
+        script::ScriptHost,
+        visibility::VISIBILITY,
+        wasm::WasmPluginHost,
+        PluginManager, SERVICES,
+    },
+    world::{player_chunker, World},
 };
-
+use audit::AuditLog;
+use metrics::TickMetrics;
+use perf_hud::PerfHud;
+use profile_cache::ProfileCache;
+use profiler::PROFILER;
+use shutdown::Shutdown;
+use throttle::ConnectionThrottle;
+
+pub mod audit;
+pub mod backup;
 mod connection_cache;
 mod key_store;
+pub mod metrics;
+pub mod perf_hud;
+pub mod profile_cache;
+pub mod profiler;
+pub mod shutdown;
+pub mod throttle;
 pub mod ticker;
+pub mod watchdog;
 
 pub const CURRENT_MC_VERSION: &str = "1.21.3";
 
@@ -43,18 +72,43 @@ pub struct Server {
     /// Saves server branding information.
     server_branding: CachedBranding,
     /// Saves and Dispatches commands to appropriate handlers.
-    pub command_dispatcher: Arc<CommandDispatcher<'static>>,
+    /// Replaced as a whole when commands change, so commands being run keep their dispatcher.
+    command_dispatcher: RwLock<Arc<CommandDispatcher<'static>>>,
     /// Manages multiple worlds within the server.
     pub worlds: Vec<Arc<World>>,
     /// Caches game registries for efficient access.
-    pub cached_registry: Vec<Registry>,
+    pub cached_registry: RwLock<Vec<Registry>>,
     /// Tracks open containers used for item interactions.
     pub open_containers: RwLock<HashMap<u64, OpenContainer>>,
     pub drag_handler: DragHandler,
-    /// Assigns unique IDs to entities.
-    entity_id: AtomicI32,
     /// Manages authentication with a authentication server, if enabled.
     pub auth_client: Option<reqwest::Client>,
+    /// Durations of the most recent ticks.
+    pub tick_metrics: TickMetrics,
+    /// Players which see the server performance in their HUD.
+    pub perf_hud: PerfHud,
+    /// Rate limits connections and logins per IP.
+    pub throttle: ConnectionThrottle,
+    /// Looks up the profiles of players which are not online.
+    pub profile_cache: ProfileCache,
+    /// Records what operators do.
+    pub audit_log: AuditLog,
+    /// Native plugins, which listen to events.
+    pub plugins: PluginManager,
+    /// Sandboxed WebAssembly plugins.
+    pub wasm_plugins: WasmPluginHost,
+    /// Rhai scripts in the script folder.
+    pub scripts: ScriptHost,
+    /// The built-in economy, if it is enabled.
+    pub economy: Option<Arc<SimpleEconomy>>,
+    /// The built-in permissions of the `permissions.toml`.
+    pub permissions: Arc<TomlPermissionProvider>,
+    /// Requests stopping the server and keeps track of the connections to wait for.
+    pub shutdown: Shutdown,
+    /// The datapacks of the default world and their load order.
+    pub datapacks: parking_lot::Mutex<Datapacks>,
+    /// The game mode new players join in, changed by `/defaultgamemode` until the server stops.
+    pub default_gamemode: AtomicCell<GameMode>,
 }
 
 impl Server {
@@ -75,24 +129,55 @@ impl Server {
         };
 
         // First register default command, after that plugins can put in their own
-        let command_dispatcher = default_dispatcher();
-
-        let world = World::load(Dimension::OverWorld.into_level(
-            // TODO: load form config
-            "./world".parse().unwrap(),
+        let mut command_dispatcher = default_dispatcher();
+        // Same for game rules, has to happen before any world loads its rules
+        register_pumpkin_game_rules();
+
+        let world_config = world_config("world").unwrap_or_else(|err| panic!("{err}"));
+        let world = Arc::new(World::load(
+            Dimension::OverWorld.into_level(
+                // TODO: load form config
+                "./world".parse().unwrap(),
+                &world_config,
+            ),
+            world_config,
         ));
+        PERSISTENT_DATA.add_world(world.clone());
+        last_death_data::migrate(&world);
+        let datapacks = load_datapacks(&world);
+        let dispatcher = Arc::get_mut(&mut command_dispatcher)
+            .expect("The command dispatcher is not shared yet");
+        let wasm_plugins = WasmPluginHost::new(world.clone());
+        wasm_plugins.load_all(dispatcher);
+        let scripts = ScriptHost::new(world.clone());
+        scripts.load_all(dispatcher);
         Self {
-            cached_registry: Registry::get_synced(),
+            cached_registry: RwLock::new(Registry::get_synced(&ADVANCED_CONFIG.dimension_effects)),
             open_containers: RwLock::new(HashMap::new()),
             drag_handler: DragHandler::new(),
             // 0 is invalid
-            entity_id: 2.into(),
-            worlds: vec![Arc::new(world)],
-            command_dispatcher,
+            worlds: vec![world],
+            command_dispatcher: RwLock::new(command_dispatcher),
             auth_client,
             key_store: KeyStore::new(),
             server_listing: Mutex::new(CachedStatus::new()),
             server_branding: CachedBranding::new(),
+            tick_metrics: TickMetrics::default(),
+            perf_hud: PerfHud::default(),
+            throttle: ConnectionThrottle::default(),
+            profile_cache: ProfileCache::new(),
+            audit_log: AuditLog::default(),
+            plugins: PluginManager::default(),
+            wasm_plugins,
+            scripts,
+            economy: economy::register_builtin(&SERVICES),
+            permissions: permission::register_builtin(&SERVICES),
+            shutdown: Shutdown::default(),
+            datapacks: parking_lot::Mutex::new(datapacks),
+            default_gamemode: AtomicCell::new(match BASIC_CONFIG.default_gamemode {
+                GameMode::Undefined => GameMode::Survival,
+                game_mode => game_mode,
+            }),
         }
     }
 
@@ -123,10 +208,7 @@ impl Server {
     /// You still have to spawn the Player in the World to make then to let them Join and make them Visible
     pub async fn add_player(&self, client: Arc<Client>) -> (Arc<Player>, Arc<World>) {
         let entity_id = self.new_entity_id();
-        let gamemode = match BASIC_CONFIG.default_gamemode {
-            GameMode::Undefined => GameMode::Survival,
-            game_mode => game_mode,
-        };
+        let gamemode = self.default_gamemode.load();
         // Basically the default world
         // TODO: select default from config
         let world = &self.worlds[0];
@@ -135,20 +217,131 @@ impl Server {
         world
             .add_player(player.gameprofile.id, player.clone())
             .await;
-        // TODO: Config if we want increase online
-        if let Some(config) = player.client.config.lock().await.as_ref() {
-            // TODO: Config so we can also just ignore this hehe
-            if config.server_listing {
-                self.server_listing.lock().await.add_player();
+        PERSISTENT_DATA.add_player(player.clone());
+        let listed = player
+            .client
+            .config
+            .lock()
+            .await
+            .as_ref()
+            .is_some_and(|config| config.server_listing);
+        self.server_listing
+            .lock()
+            .await
+            .add_player(&player.gameprofile, listed);
+
+        (player, world.clone())
+    }
+
+    /// Replaces the dimension type and biome overrides of every world and syncs the registries to
+    /// every player again, when the reloaded config changed them.
+    ///
+    /// Players are sent back into the configuration for that, which briefly shows a loading screen.
+    async fn set_dimension_effects(&self, overrides: &DimensionEffectsConfig) {
+        *self.cached_registry.write().await = Registry::get_synced(overrides);
+        for world in &self.worlds {
+            let players = world
+                .current_players
+                .lock()
+                .await
+                .values()
+                .cloned()
+                .collect::<Vec<_>>();
+            for player in players {
+                player.start_reconfiguration().await;
             }
         }
+    }
+
+    /// Changes which datapacks are enabled, saves that in `level.dat` and loads the content of the
+    /// enabled packs again, which is then sent to every player.
+    pub async fn update_datapacks(
+        &self,
+        update: impl FnOnce(&mut Datapacks) -> Result<(), String>,
+    ) -> Result<(), String> {
+        let folders = {
+            let mut datapacks = self.datapacks.lock();
+            update(&mut datapacks)?;
+            save_datapacks(&self.worlds[0], &datapacks);
+            datapacks.enabled_folders()
+        };
+        // packs are read from disk, which may take a while
+        tokio::task::block_in_place(|| load_datapack_content(&folders));
+        self.broadcast_packet_all(&CPlayUpdateTags::new(&network_tags()))
+            .await;
+        Ok(())
+    }
+
+    /// Kicks the players, waits for their connections, disables the plugins and saves.
+    /// Only call this once, after the shutdown was requested, and exit afterwards
+    pub async fn stop(&self) {
+        log::info!("Stopping the server");
+        let kick_message = TextComponent::text("Server closed");
+        for player in self.get_all_players().await {
+            player.kick(kick_message.clone()).await;
+        }
+        let open = self.shutdown.drain().await;
+        if open > 0 {
+            log::warn!("Stopping without waiting for {open} connections");
+        }
+        self.plugins.unload_all();
+        self.wasm_plugins.unload_all();
+        self.scripts.unload_all();
+        for world in &self.worlds {
+            world.save_persistent_data().await;
+        }
+        if let Some(economy) = &self.economy {
+            economy.save();
+        }
+        log::info!("Stopped the server");
+    }
 
-        (player, world.clone())
+    /// Reads the configuration files and the permissions again and applies the settings which can
+    /// change while the server runs, returning the names of the changed ones
+    pub async fn reload_config(&self) -> Result<Vec<&'static str>, String> {
+        // the permissions are reloaded even if the config is broken
+        self.permissions.reload();
+        let reload = pumpkin_config::reload()?;
+        let changed = reload.changes();
+        if changed.contains(&"motd") || changed.contains(&"max_players") {
+            self.server_listing
+                .lock()
+                .await
+                .apply_runtime_config(&reload.new);
+        }
+        if changed.contains(&"dimension_effects") {
+            self.set_dimension_effects(&reload.new.dimension_effects)
+                .await;
+        }
+        if reload.old.view_distance != reload.new.view_distance {
+            for player in self.get_all_players().await {
+                player_chunker::update_distances(&player).await;
+            }
+        }
+        plugin::fire(ConfigReloadedEvent {
+            changed: changed.iter().map(ToString::to_string).collect(),
+        });
+        Ok(changed)
     }
 
-    pub async fn remove_player(&self) {
-        // TODO: Config if we want decrease online
-        self.server_listing.lock().await.remove_player();
+    /// Cleans up after a player disconnected, once they left their world.
+    pub async fn remove_player(&self, player: &Player) {
+        if let Some(id) = player.open_container.load() {
+            if let Some(container) = self.open_containers.write().await.get_mut(&id) {
+                container.remove_player(player.entity_id());
+            }
+        }
+        self.server_listing
+            .lock()
+            .await
+            .remove_player(&player.gameprofile);
+        plugin::fire(PlayerQuitEvent {
+            player: plugin::player_info(player),
+        });
+        player.living_entity.entity.world.save_player(player).await;
+        PERSISTENT_DATA.remove_player(player);
+        MENUS.remove_player(player.gameprofile.id);
+        VISIBILITY.remove_player(player.gameprofile.id);
     }
 
     pub async fn try_get_container(
@@ -174,9 +367,7 @@ impl Server {
     where
         P: ClientPacket,
     {
-        for world in &self.worlds {
-            world.broadcast_packet_all(packet).await;
-        }
+        self.players().await.send_packet(packet).await;
     }
 
     /// Searches for a player by their username across all worlds.
@@ -200,6 +391,23 @@ impl Server {
         None
     }
 
+    /// Returns the current command dispatcher.
+    pub async fn command_dispatcher(&self) -> Arc<CommandDispatcher<'static>> {
+        self.command_dispatcher.read().await.clone()
+    }
+
+    /// Changes the registered commands and sends the new command tree to every player.
+    pub async fn update_commands(&self, update: impl FnOnce(&mut CommandDispatcher<'static>)) {
+        let dispatcher = {
+            let mut dispatcher = self.command_dispatcher.write().await;
+            update(Arc::make_mut(&mut dispatcher));
+            dispatcher.clone()
+        };
+        for player in self.get_all_players().await {
+            client_cmd_suggestions::send_c_commands_packet(&player, &dispatcher).await;
+        }
+    }
+
     /// Returns all players from all worlds.
     pub async fn get_all_players(&self) -> Vec<Arc<Player>> {
         let mut players = Vec::<Arc<Player>>::new();
@@ -213,6 +421,11 @@ impl Server {
         players
     }
 
+    /// Returns all players from all worlds, for applying actions to them in bulk.
+    pub async fn players(&self) -> PlayerSet {
+        self.get_all_players().await.into()
+    }
+
     /// Returns a random player from any of the worlds or None if all worlds are empty.
     pub async fn get_random_player(&self) -> Option<Arc<Player>> {
         let players = self.get_all_players().await;
@@ -241,6 +454,19 @@ impl Server {
         None
     }
 
+    /// Finds the profile of a player by name, also if they are not online.
+    ///
+    /// Profiles are cached, otherwise online mode servers ask Mojang for the UUID of offline players
+    /// and other servers use the offline UUID.
+    pub async fn lookup_profile(&self, name: &str) -> Option<GameProfile> {
+        if let Some(player) = self.get_player_by_name(name).await {
+            return Some(player.gameprofile.clone());
+        }
+        self.profile_cache
+            .lookup(name, self.auth_client.as_ref())
+            .await
+    }
+
     /// Counts the total number of players across all worlds.
     ///
     /// This function iterates through each world and sums up the number of players currently connected to that world.
@@ -268,10 +494,9 @@ impl Server {
         false
     }
 
-    /// Generates a new entity id
-    /// This should be global
+    /// Generates a new entity id, see [`entity::new_entity_id`]
     pub fn new_entity_id(&self) -> EntityId {
-        self.entity_id.fetch_add(1, Ordering::SeqCst)
+        entity::new_entity_id()
     }
 
     pub fn get_branding(&self) -> CPluginMessage<'_> {
@@ -300,8 +525,46 @@ impl Server {
     }
 
     async fn tick(&self) {
-        for world in &self.worlds {
-            world.tick().await;
+        PROFILER
+            .time("tick;worlds", async {
+                for world in &self.worlds {
+                    world.tick().await;
+                }
+            })
+            .await;
+        PROFILER
+            .time("tick;plugin_commands", self.plugins.commands.apply(self))
+            .await;
+        PROFILER.time_sync("tick;content", || plugin::content::CONTENT.tick());
+        if let Some(economy) = &self.economy {
+            PROFILER.time_sync("tick;economy", || economy.save());
         }
+        PROFILER.time_sync("tick;wasm_plugins", || self.wasm_plugins.tick());
+        PROFILER
+            .time("tick;perf_hud", self.perf_hud.tick(self))
+            .await;
     }
 }
+
+/// Finds the datapacks of the world and loads the enabled ones
+fn load_datapacks(world: &World) -> Datapacks {
+    let (enabled, disabled) = world.level.level_data().datapacks().unwrap_or_default();
+    let folder = world
+        .level
+        .root_folder()
+        .map(|root| root.join("datapacks"))
+        .unwrap_or_default();
+    let datapacks = Datapacks::scan(&folder, &enabled, &disabled);
+    // new packs were found or old ones are gone
+    if datapacks.enabled() != enabled || datapacks.disabled() != disabled {
+        save_datapacks(world, &datapacks);
+    }
+    load_datapack_content(&datapacks.enabled_folders());
+    datapacks
+}
+
+fn save_datapacks(world: &World, datapacks: &Datapacks) {
+    let mut data = world.level.level_data();
+    data.set_datapacks(datapacks.enabled(), &datapacks.disabled());
+    world.level.write_level_data(data);
+}
@@ -0,0 +1,154 @@
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use pumpkin_config::BASIC_CONFIG;
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+use pumpkin_protocol::client::play::{
+    BossBarColor, BossBarDivision, BossEventAction, CActionBar, CBossEvent,
+};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use super::Server;
+use crate::entity::player::Player;
+
+/// Every player sees their own boss bar, so the same id can be used for everyone
+const BOSS_BAR_ID: Uuid = Uuid::from_u128(0x7075_6d70_6b69_6e2d_7065_7266_2d68_7564);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum HudMode {
+    ActionBar,
+    BossBar,
+}
+
+/// Sends the TPS, MSPT, chunk queue depth and the player's own send queue to subscribed players once per second.
+#[derive(Default)]
+pub struct PerfHud {
+    subscribers: Mutex<HashMap<Uuid, HudMode>>,
+    ticks: AtomicU32,
+}
+
+impl PerfHud {
+    pub async fn subscribe(&self, player: &Player, mode: HudMode) {
+        let previous = self
+            .subscribers
+            .lock()
+            .await
+            .insert(player.gameprofile.id, mode);
+        if previous == Some(HudMode::BossBar) && mode != HudMode::BossBar {
+            remove_boss_bar(player).await;
+        }
+        if previous != Some(HudMode::BossBar) && mode == HudMode::BossBar {
+            player
+                .client
+                .send_packet(&CBossEvent::new(
+                    &BOSS_BAR_ID,
+                    BossEventAction::Add {
+                        title: TextComponent::text("Performance"),
+                        health: 0.0,
+                        color: BossBarColor::Green,
+                        division: BossBarDivision::NoDivision,
+                        flags: 0,
+                    },
+                ))
+                .await;
+        }
+    }
+
+    /// Returns false if the player was not subscribed
+    pub async fn unsubscribe(&self, player: &Player) -> bool {
+        match self.subscribers.lock().await.remove(&player.gameprofile.id) {
+            Some(HudMode::BossBar) => {
+                remove_boss_bar(player).await;
+                true
+            }
+            Some(HudMode::ActionBar) => true,
+            None => false,
+        }
+    }
+
+    pub async fn tick(&self, server: &Server) {
+        let ticks = self.ticks.fetch_add(1, Ordering::Relaxed) + 1;
+        if ticks < BASIC_CONFIG.tps as u32 {
+            return;
+        }
+        self.ticks.store(0, Ordering::Relaxed);
+
+        let mut subscribers = self.subscribers.lock().await;
+        if subscribers.is_empty() {
+            return;
+        }
+
+        let tps = server.tick_metrics.tps();
+        let mspt = server.tick_metrics.mspt();
+        let pending_chunks: usize = server
+            .worlds
+            .iter()
+            .map(|world| world.level.pending_chunk_count())
+            .sum();
+        let text = format!("TPS: {tps:.1} MSPT: {mspt:.2} Chunk queue: {pending_chunks}");
+        let (color, bar_color) = if tps >= f64::from(BASIC_CONFIG.tps) * 0.95 {
+            (NamedColor::Green, BossBarColor::Green)
+        } else if tps >= f64::from(BASIC_CONFIG.tps) * 0.75 {
+            (NamedColor::Yellow, BossBarColor::Yellow)
+        } else {
+            (NamedColor::Red, BossBarColor::Red)
+        };
+        // how much of the tick budget is used
+        let load = (mspt * f64::from(BASIC_CONFIG.tps) / 1000.0).clamp(0.0, 1.0) as f32;
+
+        let mut disconnected = Vec::new();
+        for (id, mode) in subscribers.iter() {
+            let Some(player) = server.get_player_by_uuid(*id).await else {
+                disconnected.push(*id);
+                continue;
+            };
+            let queue = player.client.send_queue_stats();
+            let text = format!(
+                "{text} Send queue: {} chunks {}KB",
+                queue.queued_chunks,
+                queue.queued_bytes / 1024
+            );
+            let title = TextComponent::text(&text).color_named(color);
+            match mode {
+                HudMode::ActionBar => player.client.send_packet(&CActionBar::new(title)).await,
+                HudMode::BossBar => {
+                    let client = &player.client;
+                    client
+                        .send_packet(&CBossEvent::new(
+                            &BOSS_BAR_ID,
+                            BossEventAction::UpdateTitle(title),
+                        ))
+                        .await;
+                    client
+                        .send_packet(&CBossEvent::new(
+                            &BOSS_BAR_ID,
+                            BossEventAction::UpdateHealth(load),
+                        ))
+                        .await;
+                    client
+                        .send_packet(&CBossEvent::new(
+                            &BOSS_BAR_ID,
+                            BossEventAction::UpdateStyle {
+                                color: bar_color,
+                                division: BossBarDivision::NoDivision,
+                            },
+                        ))
+                        .await;
+                }
+            }
+        }
+        for id in disconnected {
+            subscribers.remove(&id);
+        }
+    }
+}
+
+async fn remove_boss_bar(player: &Player) {
+    player
+        .client
+        .send_packet(&CBossEvent::new(&BOSS_BAR_ID, BossEventAction::Remove))
+        .await;
+}
@@ -0,0 +1,146 @@
+//! Finding the profiles of players which are not online, and the skins of players in offline mode.
+//!
+//! Profiles are cached in `usercache.json`, so names can also be resolved when Mojang's API is not
+//! reachable. Requests to Mojang are rate limited, as it limits them per IP itself.
+
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use pumpkin_config::{auth::SkinSource, ADVANCED_CONFIG, BASIC_CONFIG};
+use pumpkin_protocol::Property;
+
+use crate::{
+    client::authentication::{self, offline_uuid, validate_textures, GameProfile},
+    data::user_cache_data::USER_CACHE_CONFIG,
+};
+
+use super::throttle::RateWindow;
+
+pub struct ProfileCache {
+    /// Requests to Mojang within the current minute
+    lookups: Mutex<RateWindow>,
+    /// Fetches the skins of players in offline mode, if a skin source is configured
+    skin_client: Option<reqwest::Client>,
+}
+
+impl ProfileCache {
+    #[must_use]
+    pub fn new() -> Self {
+        let skins = &ADVANCED_CONFIG.authentication.offline_skins;
+        let skin_client =
+            (!BASIC_CONFIG.online_mode && skins.source != SkinSource::None).then(|| {
+                reqwest::Client::builder()
+                    .timeout(Duration::from_secs(5))
+                    .build()
+                    .expect("Failed to to make reqwest client")
+            });
+        Self {
+            lookups: Mutex::new(RateWindow::new()),
+            skin_client,
+        }
+    }
+
+    /// Whether another request to Mojang may be sent now
+    fn may_request(&self) -> bool {
+        let limit = ADVANCED_CONFIG
+            .authentication
+            .profile_cache
+            .lookups_per_minute;
+        self.lookups.lock().hit(limit, Duration::from_mins(1))
+    }
+
+    /// Finds the profile of a player by name from the cache, Mojang's API in online mode or as an offline UUID
+    pub async fn lookup(
+        &self,
+        name: &str,
+        auth_client: Option<&reqwest::Client>,
+    ) -> Option<GameProfile> {
+        if let Some(entry) = USER_CACHE_CONFIG.read().await.get_by_name(name) {
+            return Some(entry.profile());
+        }
+        let profile = match auth_client {
+            Some(auth_client) => {
+                if !self.may_request() {
+                    log::warn!("Not looking up the profile of {name}, too many lookups");
+                    return None;
+                }
+                authentication::lookup_profile(name, auth_client)
+                    .await
+                    .inspect_err(|err| log::debug!("Failed to look up profile of {name}: {err}"))
+                    .ok()?
+            }
+            None => GameProfile {
+                id: offline_uuid(name).ok()?,
+                name: name.to_string(),
+                properties: vec![],
+                profile_actions: None,
+            },
+        };
+        USER_CACHE_CONFIG.write().await.add(&profile);
+        Some(profile)
+    }
+
+    /// Adds the skin from the configured source to the profile of a player in offline mode
+    pub async fn add_offline_skin(&self, profile: &mut GameProfile) {
+        let Some(client) = &self.skin_client else {
+            return;
+        };
+        if !ADVANCED_CONFIG.authentication.textures.enabled {
+            return;
+        }
+        let cached = USER_CACHE_CONFIG
+            .read()
+            .await
+            .get_by_uuid(profile.id)
+            .map(|entry| entry.properties.clone());
+        let properties = match cached {
+            Some(properties) if !properties.is_empty() => properties,
+            _ => match self.fetch_skin(client, &profile.name).await {
+                Some(properties) => properties,
+                None => return,
+            },
+        };
+        let textures = &ADVANCED_CONFIG.authentication.textures;
+        profile.properties = properties
+            .into_iter()
+            .filter(|property| {
+                property.name != "textures" || validate_textures(property, textures).is_ok()
+            })
+            .collect();
+    }
+
+    async fn fetch_skin(&self, client: &reqwest::Client, name: &str) -> Option<Vec<Property>> {
+        let config = &ADVANCED_CONFIG.authentication;
+        let address = match config.offline_skins.source {
+            SkinSource::None => return None,
+            SkinSource::Mojang => {
+                if !self.may_request() {
+                    return None;
+                }
+                let premium = authentication::lookup_profile(name, client).await.ok()?;
+                if !self.may_request() {
+                    return None;
+                }
+                config
+                    .profile_url
+                    .replace("{uuid}", &premium.id.simple().to_string())
+            }
+            SkinSource::Url => config
+                .offline_skins
+                .url
+                .replace("{username}", name)
+                .replace("{uuid}", &offline_uuid(name).ok()?.simple().to_string()),
+        };
+        authentication::fetch_profile(&address, client)
+            .await
+            .inspect_err(|err| log::debug!("Failed to fetch the skin of {name}: {err}"))
+            .ok()
+            .map(|profile| profile.properties)
+    }
+}
+
+impl Default for ProfileCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,204 @@
+//! Measures where the server spends its time while `/profiler` runs.
+//!
+//! Sections are named by their stack, e.g. `tick;worlds;entity_tracker`, which is the folded
+//! format flamegraph tools like `inferno` and `flamegraph.pl` read. Work outside of ticks, like
+//! handling packets or generating chunks, has its own root.
+
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    fs,
+    future::Future,
+    io,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use parking_lot::Mutex;
+
+use super::watchdog::WATCHDOG;
+
+/// The folder the folded stacks are written to
+const OUTPUT_FOLDER: &str = "profiles";
+
+pub static PROFILER: Profiler = Profiler {
+    running: AtomicBool::new(false),
+    session: Mutex::new(None),
+};
+
+struct Session {
+    started: Instant,
+    ticks: u64,
+    longest_tick: Duration,
+    /// The total time of every section, including the sections below it
+    sections: HashMap<String, Duration>,
+}
+
+pub struct Profiler {
+    running: AtomicBool,
+    session: Mutex<Option<Session>>,
+}
+
+impl Profiler {
+    #[must_use]
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    /// Returns false if the profiler already runs
+    pub fn start(&self) -> bool {
+        let mut session = self.session.lock();
+        if session.is_some() {
+            return false;
+        }
+        *session = Some(Session {
+            started: Instant::now(),
+            ticks: 0,
+            longest_tick: Duration::ZERO,
+            sections: HashMap::new(),
+        });
+        self.running.store(true, Ordering::Relaxed);
+        true
+    }
+
+    /// Returns None if the profiler didn't run
+    #[must_use]
+    pub fn stop(&self) -> Option<Report> {
+        self.running.store(false, Ordering::Relaxed);
+        let session = self.session.lock().take()?;
+        let mut sections: Vec<_> = session.sections.into_iter().collect();
+        sections.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        Some(Report {
+            duration: session.started.elapsed(),
+            ticks: session.ticks,
+            longest_tick: session.longest_tick,
+            sections,
+        })
+    }
+
+    /// Adds the time to the section, if the profiler runs
+    pub fn record(&self, stack: &str, duration: Duration) {
+        if !self.is_running() {
+            return;
+        }
+        if let Some(session) = self.session.lock().as_mut() {
+            *session.sections.entry(stack.to_string()).or_default() += duration;
+        }
+    }
+
+    /// Counts a finished tick
+    pub fn record_tick(&self, duration: Duration) {
+        if !self.is_running() {
+            return;
+        }
+        if let Some(session) = self.session.lock().as_mut() {
+            session.ticks += 1;
+            session.longest_tick = session.longest_tick.max(duration);
+            *session.sections.entry("tick".to_string()).or_default() += duration;
+        }
+    }
+
+    /// Runs a section of the tick, recording how long it took. The watchdog tracks the section
+    /// too, to tell where a stuck tick is stuck.
+    pub async fn time<F: Future>(&self, stack: &'static str, future: F) -> F::Output {
+        WATCHDOG.enter(stack);
+        let start = Instant::now();
+        let output = future.await;
+        self.section_done(stack, start.elapsed());
+        output
+    }
+
+    /// Like [`Self::time`], for sections which don't wait
+    pub fn time_sync<T>(&self, stack: &'static str, f: impl FnOnce() -> T) -> T {
+        WATCHDOG.enter(stack);
+        let start = Instant::now();
+        let output = f();
+        self.section_done(stack, start.elapsed());
+        output
+    }
+
+    fn section_done(&self, stack: &'static str, duration: Duration) {
+        WATCHDOG.exit(stack, duration);
+        self.record(stack, duration);
+    }
+}
+
+pub struct Report {
+    pub duration: Duration,
+    pub ticks: u64,
+    pub longest_tick: Duration,
+    /// The total time of every section by its stack, sorted by the stack
+    sections: Vec<(String, Duration)>,
+}
+
+impl Report {
+    /// The sections directly below `tick`, with their average milliseconds per tick, the slowest
+    /// first
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn tick_sections(&self) -> Vec<(&str, f64)> {
+        let ticks = self.ticks.max(1) as f64;
+        let mut sections: Vec<_> = self
+            .sections
+            .iter()
+            .filter_map(|(stack, duration)| {
+                let name = stack.strip_prefix("tick;")?;
+                (!name.contains(';')).then(|| (name, duration.as_secs_f64() * 1000.0 / ticks))
+            })
+            .collect();
+        sections.sort_unstable_by(|a, b| b.1.total_cmp(&a.1));
+        sections
+    }
+
+    /// The average milliseconds per tick
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn mspt(&self) -> f64 {
+        self.total("tick").as_secs_f64() * 1000.0 / self.ticks.max(1) as f64
+    }
+
+    fn total(&self, stack: &str) -> Duration {
+        self.sections
+            .iter()
+            .find(|(section, _)| section == stack)
+            .map_or(Duration::ZERO, |(_, duration)| *duration)
+    }
+
+    /// The stacks with the time spent in them but not in the sections below them, in
+    /// microseconds
+    #[must_use]
+    pub fn folded(&self) -> String {
+        let mut folded = String::new();
+        for (stack, duration) in &self.sections {
+            let children: Duration = self
+                .sections
+                .iter()
+                .filter(|(child, _)| {
+                    child
+                        .strip_prefix(stack.as_str())
+                        .and_then(|rest| rest.strip_prefix(';'))
+                        .is_some_and(|rest| !rest.contains(';'))
+                })
+                .map(|(_, duration)| *duration)
+                .sum();
+            let own = duration.saturating_sub(children).as_micros();
+            if own > 0 {
+                let _ = writeln!(folded, "{stack} {own}");
+            }
+        }
+        folded
+    }
+
+    /// Writes the folded stacks to the profiles folder, returning the file
+    pub fn write(&self) -> io::Result<PathBuf> {
+        fs::create_dir_all(OUTPUT_FOLDER)?;
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let path = PathBuf::from(OUTPUT_FOLDER).join(format!("profile-{time}.folded"));
+        fs::write(&path, self.folded())?;
+        Ok(path)
+    }
+}
@@ -0,0 +1,58 @@
+//! Stopping the server, by `/stop` or a signal, is only requested here. The accept loop then stops
+//! taking connections and runs [`Server::stop`](super::Server::stop), so the connection which
+//! sent `/stop` isn't waited for by itself.
+
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use tokio::sync::Notify;
+
+/// How long the connections get to send their last packets
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Default)]
+pub struct Shutdown {
+    requested: Notify,
+    connections: AtomicUsize,
+}
+
+impl Shutdown {
+    pub fn request(&self) {
+        self.requested.notify_one();
+    }
+
+    /// Waits until stopping the server was requested
+    pub async fn requested(&self) {
+        self.requested.notified().await;
+    }
+
+    /// Counts the connection until the guard is dropped
+    pub fn track_connection(&self) -> ConnectionGuard<'_> {
+        self.connections.fetch_add(1, Ordering::Relaxed);
+        ConnectionGuard(self)
+    }
+
+    /// Waits until every connection was handled, or the timeout passed. Returns the number of
+    /// connections still open
+    pub(super) async fn drain(&self) -> usize {
+        let start = Instant::now();
+        loop {
+            let open = self.connections.load(Ordering::Relaxed);
+            if open == 0 || start.elapsed() >= DRAIN_TIMEOUT {
+                return open;
+            }
+            tokio::time::sleep(DRAIN_POLL_INTERVAL).await;
+        }
+    }
+}
+
+pub struct ConnectionGuard<'a>(&'a Shutdown);
+
+impl Drop for ConnectionGuard<'_> {
+    fn drop(&mut self) {
+        self.0.connections.fetch_sub(1, Ordering::Relaxed);
+    }
+}
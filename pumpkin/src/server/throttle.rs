@@ -0,0 +1,167 @@
+//! Rate limits for connections, logins and packets, see [`RateLimitConfig`](pumpkin_config::RateLimitConfig).
+//!
+//! An IP breaking one of them is blocked for a while, its new connections are closed right away.
+//! Behind a proxy every connection comes from the proxy's IP, so they are throttled on the IP the
+//! proxy forwards once it did, see [`Client::throttled_ip`](crate::client::Client::throttled_ip).
+
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+use pumpkin_config::runtime_config;
+use pumpkin_protocol::ConnectionState;
+
+const MINUTE: Duration = Duration::from_mins(1);
+
+/// Counts events within a fixed window of time
+pub struct RateWindow {
+    start: Instant,
+    count: u32,
+}
+
+impl RateWindow {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            count: 0,
+        }
+    }
+
+    /// Counts one more event, returns false if that is more than the limit in the current window.
+    /// A limit of 0 allows everything
+    pub fn hit(&mut self, limit: u32, window: Duration) -> bool {
+        if self.start.elapsed() >= window {
+            self.start = Instant::now();
+            self.count = 0;
+        }
+        self.count = self.count.saturating_add(1);
+        limit == 0 || self.count <= limit
+    }
+
+    fn is_expired(&self, window: Duration) -> bool {
+        self.start.elapsed() >= window
+    }
+}
+
+impl Default for RateWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Default)]
+struct IpActivity {
+    connections: RateWindow,
+    logins: RateWindow,
+    blocked_until: Option<Instant>,
+}
+
+impl IpActivity {
+    fn is_blocked(&self) -> bool {
+        self.blocked_until
+            .is_some_and(|blocked_until| blocked_until > Instant::now())
+    }
+
+    /// Whether the entry can be forgotten, as it would not block anything anymore
+    fn is_stale(&self) -> bool {
+        !self.is_blocked() && self.connections.is_expired(MINUTE) && self.logins.is_expired(MINUTE)
+    }
+}
+
+/// Tracks what every IP has done recently
+#[derive(Default)]
+pub struct ConnectionThrottle {
+    ips: Mutex<HashMap<IpAddr, IpActivity>>,
+}
+
+impl ConnectionThrottle {
+    /// Counts a new connection, returns false if it has to be closed.
+    pub fn allow_connection(&self, ip: IpAddr) -> bool {
+        let runtime = runtime_config();
+        let config = &runtime.rate_limit;
+        if !config.enabled {
+            return true;
+        }
+        let mut ips = self.ips.lock();
+        ips.retain(|_, activity| !activity.is_stale());
+
+        let activity = ips.entry(ip).or_default();
+        if activity.is_blocked() {
+            return false;
+        }
+        if activity
+            .connections
+            .hit(config.connections_per_minute, MINUTE)
+        {
+            return true;
+        }
+        log::warn!("{ip} opened too many connections");
+        Self::block_activity(activity);
+        false
+    }
+
+    /// Counts a login attempt, returns false if the client has to be kicked.
+    pub fn allow_login(&self, ip: IpAddr) -> bool {
+        let runtime = runtime_config();
+        let config = &runtime.rate_limit;
+        if !config.enabled {
+            return true;
+        }
+        let mut ips = self.ips.lock();
+        let activity = ips.entry(ip).or_default();
+        if activity.is_blocked() {
+            return false;
+        }
+        if activity.logins.hit(config.logins_per_minute, MINUTE) {
+            return true;
+        }
+        log::warn!("{ip} tried to log in too often");
+        Self::block_activity(activity);
+        false
+    }
+
+    /// Blocks the IP for the configured duration.
+    pub fn block(&self, ip: IpAddr) {
+        if !runtime_config().rate_limit.enabled {
+            return;
+        }
+        Self::block_activity(self.ips.lock().entry(ip).or_default());
+    }
+
+    fn block_activity(activity: &mut IpActivity) {
+        let duration = Duration::from_secs(runtime_config().rate_limit.block_duration);
+        activity.blocked_until = Some(Instant::now() + duration);
+    }
+}
+
+/// How many packets a client may send per second in the state, 0 is unlimited
+#[must_use]
+pub fn packet_limit(state: ConnectionState) -> u32 {
+    let runtime = runtime_config();
+    let config = &runtime.rate_limit;
+    if !config.enabled {
+        return 0;
+    }
+    let limits = &config.packets_per_second;
+    match state {
+        ConnectionState::HandShake | ConnectionState::Status => limits.status,
+        ConnectionState::Login | ConnectionState::Transfer => limits.login,
+        ConnectionState::Config => limits.config,
+        ConnectionState::Play => limits.play,
+    }
+}
+
+/// The largest packet a client may send in the state, None only uses the protocol's limit
+#[must_use]
+pub fn max_packet_size(state: ConnectionState) -> Option<i32> {
+    let runtime = runtime_config();
+    let config = &runtime.rate_limit;
+    if !config.enabled || state == ConnectionState::Play {
+        return None;
+    }
+    i32::try_from(config.max_packet_size_before_play).ok()
+}
@@ -2,7 +2,7 @@ use std::time::{Duration, Instant};
 
 use tokio::time::sleep;
 
-use super::Server;
+use super::{profiler::PROFILER, watchdog::WATCHDOG, Server};
 
 pub struct Ticker {
     tick_interval: Duration,
@@ -25,7 +25,12 @@ impl Ticker {
             let elapsed = now - self.last_tick;
 
             if elapsed >= self.tick_interval {
+                WATCHDOG.tick_started();
                 server.tick().await;
+                WATCHDOG.tick_finished();
+                let tick_time = now.elapsed();
+                server.tick_metrics.record_tick(now, tick_time);
+                PROFILER.record_tick(tick_time);
                 self.last_tick = now;
             } else {
                 // Wait for the remaining time until the next tick
@@ -0,0 +1,252 @@
+//! Watches the tick loop from its own thread.
+//!
+//! A stuck tick can't be interrupted, but the watchdog can tell where it is stuck: the tick
+//! sections timed by the profiler are also tracked here, so the crash report names the section
+//! which never finished and the sections which were slow before.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Write as _,
+    fs, io,
+    path::PathBuf,
+    sync::Arc,
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use parking_lot::Mutex;
+use pumpkin_config::{StallAction, WatchdogConfig};
+use pumpkin_protocol::CURRENT_MC_PROTOCOL;
+use tokio::runtime::Handle;
+
+use super::{Server, CURRENT_MC_VERSION};
+
+const CARGO_PKG_VERSION: &str = env!("CARGO_PKG_VERSION");
+const GIT_VERSION: &str = env!("GIT_VERSION");
+
+/// The folder crash reports are written to
+const OUTPUT_FOLDER: &str = "crash-reports";
+/// How often the watchdog checks the tick loop
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+/// How many ticks the slowest sections are taken from, 5 seconds at 20 TPS
+const HISTORY_LENGTH: usize = 100;
+/// How long saving may take, the stuck tick may hold locks the save waits for
+const SAVE_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub static WATCHDOG: Watchdog = Watchdog {
+    tick_start: Mutex::new(None),
+    sections: Mutex::new(Vec::new()),
+    current: Mutex::new(Vec::new()),
+    history: Mutex::new(VecDeque::new()),
+};
+
+type SectionTimes = Vec<(&'static str, Duration)>;
+
+pub struct Watchdog {
+    /// When the running tick started, None between ticks
+    tick_start: Mutex<Option<Instant>>,
+    /// The stacks of the sections the tick is in, the innermost last
+    sections: Mutex<Vec<&'static str>>,
+    /// The sections the running tick finished
+    current: Mutex<SectionTimes>,
+    /// The sections of the recent ticks
+    history: Mutex<VecDeque<SectionTimes>>,
+}
+
+impl Watchdog {
+    pub fn tick_started(&self) {
+        self.current.lock().clear();
+        self.sections.lock().clear();
+        *self.tick_start.lock() = Some(Instant::now());
+    }
+
+    pub fn tick_finished(&self) {
+        *self.tick_start.lock() = None;
+        let sections = std::mem::take(&mut *self.current.lock());
+        let mut history = self.history.lock();
+        if history.len() == HISTORY_LENGTH {
+            history.pop_front();
+        }
+        history.push_back(sections);
+    }
+
+    pub fn enter(&self, section: &'static str) {
+        self.sections.lock().push(section);
+    }
+
+    pub fn exit(&self, section: &'static str, duration: Duration) {
+        self.sections.lock().pop();
+        self.current.lock().push((section, duration));
+    }
+
+    /// How long the running tick has taken so far
+    fn stalled_for(&self) -> Option<Duration> {
+        self.tick_start.lock().map(|start| start.elapsed())
+    }
+
+    /// The innermost section the tick is in
+    fn stuck_in(&self) -> &'static str {
+        self.sections.lock().last().copied().unwrap_or("tick")
+    }
+
+    /// The longest time of every section over the recent ticks, the slowest first
+    fn slowest_sections(&self) -> Vec<(&'static str, Duration)> {
+        let mut slowest: HashMap<&'static str, Duration> = HashMap::new();
+        for (section, duration) in self.history.lock().iter().flatten() {
+            let longest = slowest.entry(section).or_default();
+            *longest = (*longest).max(*duration);
+        }
+        let mut slowest: Vec<_> = slowest.into_iter().collect();
+        slowest.sort_unstable_by_key(|(_, duration)| std::cmp::Reverse(*duration));
+        slowest
+    }
+
+    fn crash_report(&self, server: &Server, stalled: Duration) -> String {
+        let mut report = String::new();
+        let _ = writeln!(report, "---- Pumpkin Crash Report ----");
+        let _ = writeln!(
+            report,
+            "Pumpkin {CARGO_PKG_VERSION} ({GIT_VERSION}) for Minecraft {CURRENT_MC_VERSION} (Protocol {CURRENT_MC_PROTOCOL})"
+        );
+        let _ = writeln!(
+            report,
+            "\nThe tick has not finished for {}s, it is stuck in {}",
+            stalled.as_secs(),
+            self.stuck_in()
+        );
+        let _ = writeln!(
+            report,
+            "Average of the ticks before: {:.2} mspt",
+            server.tick_metrics.mspt()
+        );
+        let _ = writeln!(report, "\nSlowest sections of the recent ticks:");
+        for (section, duration) in self.slowest_sections() {
+            let _ = writeln!(
+                report,
+                "  {section}: {:.2} ms",
+                duration.as_secs_f64() * 1000.0
+            );
+        }
+        let _ = writeln!(report, "\nThreads:\n{}", thread_dump());
+        report
+    }
+}
+
+/// The name, state and what every thread of the process waits on
+#[cfg(target_os = "linux")]
+fn thread_dump() -> String {
+    let Ok(tasks) = fs::read_dir("/proc/self/task") else {
+        return "  Failed to list the threads".to_string();
+    };
+    let mut dump = String::new();
+    for task in tasks.flatten() {
+        let path = task.path();
+        let read = |file| fs::read_to_string(path.join(file)).unwrap_or_default();
+        let name = read("comm");
+        // The state follows the name in parentheses, which may contain spaces
+        let stat = read("stat");
+        let state = stat
+            .rsplit_once(')')
+            .and_then(|(_, rest)| rest.split_whitespace().next())
+            .unwrap_or("?");
+        let _ = writeln!(
+            dump,
+            "  {} {} state {state} waiting in {}",
+            task.file_name().to_string_lossy(),
+            name.trim(),
+            read("wchan")
+        );
+    }
+    dump
+}
+
+#[cfg(not(target_os = "linux"))]
+fn thread_dump() -> String {
+    "  Thread dumps are only supported on Linux".to_string()
+}
+
+fn write_report(report: &str) -> io::Result<PathBuf> {
+    fs::create_dir_all(OUTPUT_FOLDER)?;
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let path = PathBuf::from(OUTPUT_FOLDER).join(format!("crash-{time}-server.txt"));
+    fs::write(&path, report)?;
+    Ok(path)
+}
+
+/// Saves the plugin data of the worlds and players and the economy
+fn force_save(server: &Server, runtime: &Handle) {
+    let save = async {
+        for world in &server.worlds {
+            world.save_persistent_data().await;
+        }
+    };
+    if runtime
+        .block_on(tokio::time::timeout(SAVE_TIMEOUT, save))
+        .is_err()
+    {
+        log::error!("Saving the worlds timed out, they are likely locked by the stuck tick");
+    }
+    if let Some(economy) = &server.economy {
+        economy.save();
+    }
+}
+
+fn watch(server: &Server, config: &WatchdogConfig, runtime: &Handle) {
+    let warning = Duration::from_secs(config.warning_seconds);
+    let timeout = Duration::from_secs(config.timeout_seconds);
+    let mut warned = false;
+    let mut reported = false;
+    loop {
+        thread::sleep(CHECK_INTERVAL);
+        let stalled = WATCHDOG.stalled_for().unwrap_or_default();
+        if stalled < warning {
+            warned = false;
+            reported = false;
+            continue;
+        }
+        if !warned {
+            warned = true;
+            log::warn!(
+                "The tick has not finished for {}s, it is stuck in {}",
+                stalled.as_secs(),
+                WATCHDOG.stuck_in()
+            );
+        }
+        if reported || stalled < timeout {
+            continue;
+        }
+        reported = true;
+        match write_report(&WATCHDOG.crash_report(server, stalled)) {
+            Ok(path) => log::error!(
+                "The server stopped responding, wrote a crash report to {}",
+                path.display()
+            ),
+            Err(err) => {
+                log::error!("The server stopped responding, failed to write a crash report: {err}");
+            }
+        }
+        match config.action {
+            StallAction::Nothing => {}
+            StallAction::Save => force_save(server, runtime),
+            StallAction::Restart => {
+                force_save(server, runtime);
+                log::error!("Stopping the server so it can be restarted");
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+/// Starts the watchdog thread, has to be called inside the tokio runtime
+pub fn spawn(server: Arc<Server>, config: &'static WatchdogConfig) {
+    let runtime = Handle::current();
+    let spawned = thread::Builder::new()
+        .name("watchdog".to_string())
+        .spawn(move || watch(&server, config, &runtime));
+    if let Err(err) = spawned {
+        log::error!("Failed to start the watchdog: {err}");
+    }
+}
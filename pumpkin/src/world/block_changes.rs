@@ -0,0 +1,115 @@
+//! Blocks changed during a tick are sent to the players seeing them when the tick ends, one packet
+//! per chunk section instead of one per block, so explosions and fills don't flood the clients.
+//! A chunk with more changes than `block_changes.chunk_resend_threshold` is sent again as a whole.
+
+use std::collections::HashMap;
+
+use pumpkin_config::ADVANCED_CONFIG;
+use pumpkin_core::math::{position::WorldPosition, vector2::Vector2, vector3::Vector3};
+use pumpkin_protocol::client::play::{CBlockUpdate, CSectionBlocksUpdate};
+use pumpkin_world::cylindrical_chunk_iterator::Cylindrical;
+
+use crate::entity::{player::Player, player_set::PlayerSet};
+
+use super::{player_chunker, World};
+
+/// The latest state of every changed block of a chunk, by section and section relative x, y, z
+type ChunkChanges = HashMap<i32, HashMap<(u8, u8, u8), u16>>;
+
+#[derive(Default)]
+pub struct BlockChanges {
+    chunks: parking_lot::Mutex<HashMap<Vector2<i32>, ChunkChanges>>,
+}
+
+impl BlockChanges {
+    pub fn push(&self, position: WorldPosition, block_state_id: u16) {
+        let (chunk, relative) = position.chunk_and_chunk_relative_position();
+        self.chunks
+            .lock()
+            .entry(chunk)
+            .or_default()
+            .entry(relative.y.div_euclid(16))
+            .or_default()
+            .insert(
+                (
+                    relative.x as u8,
+                    relative.y.rem_euclid(16) as u8,
+                    relative.z as u8,
+                ),
+                block_state_id,
+            );
+    }
+
+    fn take(&self) -> HashMap<Vector2<i32>, ChunkChanges> {
+        std::mem::take(&mut *self.chunks.lock())
+    }
+}
+
+/// Whether the client has the chunk, it ignores changes of chunks it doesn't have
+pub(super) fn sees_chunk(player: &Player, chunk: Vector2<i32>) -> bool {
+    let watched = player.watched_section.load();
+    Cylindrical::new(
+        Vector2::new(watched.x, watched.z),
+        player_chunker::get_view_distance(player),
+    )
+    .is_within_distance(chunk.x, chunk.z)
+        && !player.pending_chunks.lock().contains_key(&chunk)
+}
+
+impl World {
+    /// Sends the blocks changed since the last time to the players seeing them
+    pub async fn flush_block_changes(&self) {
+        let chunks = self.block_changes.take();
+        if chunks.is_empty() {
+            return;
+        }
+        let players = self.players().await;
+        for (chunk, sections) in chunks {
+            let viewers = players
+                .iter()
+                .filter(|player| sees_chunk(player, chunk))
+                .cloned()
+                .collect::<Vec<_>>();
+            if viewers.is_empty() {
+                continue;
+            }
+
+            let changes: usize = sections.values().map(HashMap::len).sum();
+            if changes > ADVANCED_CONFIG.block_changes.chunk_resend_threshold {
+                let chunk_data = self.receive_chunk(chunk).await;
+                for viewer in &viewers {
+                    viewer.client.send_chunk(chunk, chunk_data.clone()).await;
+                }
+                continue;
+            }
+
+            let viewers = PlayerSet::from(viewers);
+            for (section_y, blocks) in sections {
+                let blocks = blocks
+                    .into_iter()
+                    .map(|((x, y, z), block_state_id)| (x, y, z, block_state_id))
+                    .collect::<Vec<_>>();
+                if let [(x, y, z, block_state_id)] = blocks[..] {
+                    let position = WorldPosition(Vector3::new(
+                        chunk.x * 16 + i32::from(x),
+                        section_y * 16 + i32::from(y),
+                        chunk.z * 16 + i32::from(z),
+                    ));
+                    viewers
+                        .send_packet(&CBlockUpdate::new(
+                            &position,
+                            i32::from(block_state_id).into(),
+                        ))
+                        .await;
+                } else {
+                    viewers
+                        .send_packet(&CSectionBlocksUpdate::new(
+                            Vector3::new(chunk.x, section_y, chunk.z),
+                            &blocks,
+                        ))
+                        .await;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//! Beacons: the pyramid below decides their power level, which effects players can choose in their
+//! menu and how far the effects reach.
+
+use std::sync::Arc;
+
+use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+use pumpkin_entity::effect_type::EffectType;
+use pumpkin_inventory::{window_property, Container};
+use pumpkin_macros::sound;
+use pumpkin_protocol::SoundCategory;
+use pumpkin_registry::{is_in, TagCategory};
+use pumpkin_world::{
+    block::block_registry::{get_block_and_state_by_state_id, get_block_by_state_id},
+    WORLD_MAX_Y,
+};
+use tokio::sync::Mutex;
+
+use crate::{entity::effect::StatusEffect, world::World};
+
+use super::center;
+
+/// Beacons update their power level and give their effects every 4 seconds
+pub const UPDATE_INTERVAL: u32 = 80;
+const MAX_LEVELS: u8 = 4;
+
+/// The effects a beacon offers by the power level they need, the last one is only secondary
+const EFFECTS: [&[EffectType]; MAX_LEVELS as usize] = [
+    &[EffectType::Speed, EffectType::Haste],
+    &[EffectType::Resistance, EffectType::JumpBoost],
+    &[EffectType::Strength],
+    &[EffectType::Regeneration],
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BeaconState {
+    /// How many layers of the pyramid below are complete, 0 if the beam is blocked
+    pub levels: u8,
+}
+
+/// Whether a beacon with the power level offers the effects, the secondary one is either
+/// regeneration or the primary one again, which makes it stronger
+#[must_use]
+pub fn allows_effects(levels: u8, primary: EffectType, secondary: Option<EffectType>) -> bool {
+    let offers = |effect: EffectType, levels: u8| {
+        EFFECTS[..usize::from(levels.min(MAX_LEVELS - 1))]
+            .iter()
+            .any(|effects| effects.contains(&effect))
+    };
+    offers(primary, levels)
+        && secondary.is_none_or(|secondary| {
+            levels >= MAX_LEVELS && (secondary == primary || EFFECTS[3].contains(&secondary))
+        })
+}
+
+/// The effects chosen in the beacon's menu
+pub fn chosen_effects(container: &dyn Container) -> (Option<EffectType>, Option<EffectType>) {
+    let properties = container.properties();
+    let effect = |property: window_property::Beacon| {
+        let property = property as i16;
+        properties
+            .iter()
+            .find(|(id, _)| *id == property)
+            .and_then(|(_, value)| EffectType::from_id(i32::from(*value) - 1))
+    };
+    (
+        effect(window_property::Beacon::FirstPotionEffect),
+        effect(window_property::Beacon::SecondPotionEffect),
+    )
+}
+
+impl World {
+    /// Whether the beacon can see the sky, only blocks which let no light through stop the beam
+    async fn beam_blocked(&self, position: WorldPosition) -> bool {
+        let pos = position.0;
+        let min = Vector3::new(pos.x, pos.y + 1, pos.z);
+        let max = Vector3::new(pos.x, i32::from(WORLD_MAX_Y), pos.z);
+        self.get_block_state_ids_in(min, max)
+            .await
+            .into_iter()
+            .any(|(_, state_id)| {
+                // bedrock is the only opaque block the beam shines through
+                get_block_and_state_by_state_id(state_id).is_some_and(|(block, state)| {
+                    block.name != "minecraft:bedrock" && state.opacity.is_some_and(|o| o >= 15)
+                })
+            })
+    }
+
+    /// How many layers of beacon base blocks are complete below the beacon
+    async fn pyramid_levels(&self, position: WorldPosition) -> u8 {
+        let pos = position.0;
+        for level in 1..=MAX_LEVELS {
+            let size = i32::from(level);
+            let min = Vector3::new(pos.x - size, pos.y - size, pos.z - size);
+            let max = Vector3::new(pos.x + size, pos.y - size, pos.z + size);
+            let blocks = self.get_block_state_ids_in(min, max).await;
+            let complete = blocks.len() == ((size * 2 + 1) * (size * 2 + 1)) as usize
+                && blocks.iter().all(|(_, state_id)| {
+                    get_block_by_state_id(*state_id).is_some_and(|block| {
+                        is_in(
+                            TagCategory::Block,
+                            "minecraft:beacon_base_blocks",
+                            &block.name,
+                        )
+                    })
+                });
+            if !complete {
+                return level - 1;
+            }
+        }
+        MAX_LEVELS
+    }
+
+    /// Updates the power level and gives the players in range the chosen effects
+    pub(super) async fn tick_beacon(
+        &self,
+        position: WorldPosition,
+        state: BeaconState,
+        container: &Arc<Mutex<Box<dyn Container>>>,
+    ) -> BeaconState {
+        let levels = if self.beam_blocked(position).await {
+            0
+        } else {
+            self.pyramid_levels(position).await
+        };
+        let (primary, secondary) = {
+            let mut container = container.lock().await;
+            container.set_property(
+                window_property::Beacon::PowerLevel as i16,
+                i16::from(levels),
+            );
+            chosen_effects(container.as_ref())
+        };
+
+        let sound = match (state.levels > 0, levels > 0) {
+            (false, true) => Some(sound!("minecraft:block.beacon.activate")),
+            (true, false) => Some(sound!("minecraft:block.beacon.deactivate")),
+            (true, true) => Some(sound!("minecraft:block.beacon.ambient")),
+            (false, false) => None,
+        };
+        if let Some(sound) = sound {
+            self.play_sound(sound, SoundCategory::Blocks, &center(position))
+                .await;
+        }
+
+        if let Some(primary) = primary.filter(|primary| allows_effects(levels, *primary, secondary))
+        {
+            self.give_beacon_effects(position, levels, primary, secondary)
+                .await;
+        }
+        BeaconState { levels }
+    }
+
+    async fn give_beacon_effects(
+        &self,
+        position: WorldPosition,
+        levels: u8,
+        primary: EffectType,
+        secondary: Option<EffectType>,
+    ) {
+        let range = f64::from(levels) * 10.0 + 10.0;
+        let duration = (9 + u32::from(levels) * 2) * 20;
+        let amplifier = u8::from(secondary == Some(primary));
+        let effect = |effect_type| StatusEffect {
+            ambient: true,
+            ..StatusEffect::new(effect_type, 0, duration)
+        };
+        let beacon = center(position);
+        for player in self.players().await.iter() {
+            // the effects reach up to the top of the world
+            let pos = player.living_entity.entity.pos.load();
+            if (pos.x - beacon.x).abs() > range + 0.5
+                || (pos.z - beacon.z).abs() > range + 0.5
+                || pos.y < beacon.y - range - 0.5
+            {
+                continue;
+            }
+            let living = &player.living_entity;
+            living
+                .add_effect(StatusEffect {
+                    amplifier,
+                    ..effect(primary)
+                })
+                .await;
+            if let Some(secondary) = secondary.filter(|secondary| *secondary != primary) {
+                living.add_effect(effect(secondary)).await;
+            }
+        }
+    }
+}
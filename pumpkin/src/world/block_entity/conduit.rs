@@ -0,0 +1,203 @@
+//! Conduits: surrounded by water and a frame of prismarine they give conduit power to the players
+//! in the water around them, a complete frame also attacks monsters nearby.
+
+use std::sync::Arc;
+
+use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+use pumpkin_entity::{effect_type::EffectType, EntityId};
+use pumpkin_macros::sound;
+use pumpkin_protocol::SoundCategory;
+use pumpkin_world::block::block_registry::get_block_by_state_id;
+
+use crate::{
+    entity::{
+        damage::{DamageSource, MAGIC},
+        effect::StatusEffect,
+        environment::is_water_state,
+        EntityBase,
+    },
+    world::World,
+};
+
+use super::center;
+
+/// Conduits update their frame and give their effects every 2 seconds
+pub const UPDATE_INTERVAL: u32 = 40;
+/// How many frame blocks a conduit needs to activate
+const MIN_FRAME: u32 = 16;
+/// A complete frame, which lets the conduit attack
+const FULL_FRAME: u32 = 42;
+const FRAME_BLOCKS: [&str; 4] = [
+    "minecraft:prismarine",
+    "minecraft:prismarine_bricks",
+    "minecraft:sea_lantern",
+    "minecraft:dark_prismarine",
+];
+const EFFECT_DURATION: u32 = 260;
+const ATTACK_RANGE: f64 = 8.0;
+const ATTACK_DAMAGE: f32 = 4.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ConduitState {
+    pub active: bool,
+    /// The monster the conduit attacks, it keeps attacking it while it is in range
+    pub target: Option<EntityId>,
+}
+
+/// Whether the offset from the conduit is part of its frame: three rings of 5 by 5 around it,
+/// one in each plane through the conduit
+const fn is_frame(x: i32, y: i32, z: i32) -> bool {
+    let (ax, ay, az) = (x.abs(), y.abs(), z.abs());
+    (ax > 1 || ay > 1 || az > 1)
+        && ((x == 0 && (ay == 2 || az == 2))
+            || (y == 0 && (ax == 2 || az == 2))
+            || (z == 0 && (ax == 2 || ay == 2)))
+}
+
+impl World {
+    /// How many frame blocks surround the conduit, 0 if it isn't surrounded by water
+    async fn conduit_frame(&self, position: WorldPosition) -> u32 {
+        let pos = position.0;
+        let blocks = self
+            .get_block_state_ids_in(
+                Vector3::new(pos.x - 2, pos.y - 2, pos.z - 2),
+                Vector3::new(pos.x + 2, pos.y + 2, pos.z + 2),
+            )
+            .await;
+        let mut frame = 0;
+        for (block_pos, state_id) in blocks {
+            let offset = block_pos.0.sub(&pos);
+            if offset.x.abs() <= 1 && offset.y.abs() <= 1 && offset.z.abs() <= 1 {
+                if offset != Vector3::new(0, 0, 0) && !is_water_state(state_id) {
+                    return 0;
+                }
+            } else if is_frame(offset.x, offset.y, offset.z)
+                && get_block_by_state_id(state_id)
+                    .is_some_and(|block| FRAME_BLOCKS.contains(&block.name.as_str()))
+            {
+                frame += 1;
+            }
+        }
+        frame
+    }
+
+    /// Checks the frame, gives the players in range conduit power and attacks a monster
+    pub(super) async fn tick_conduit(
+        &self,
+        position: WorldPosition,
+        state: ConduitState,
+        age: u32,
+    ) -> ConduitState {
+        let frame = self.conduit_frame(position).await;
+        let active = frame >= MIN_FRAME;
+        let sound = match (state.active, active) {
+            (false, true) => Some(sound!("minecraft:block.conduit.activate")),
+            (true, false) => Some(sound!("minecraft:block.conduit.deactivate")),
+            (true, true) if age % (UPDATE_INTERVAL * 2) == 0 => {
+                Some(sound!("minecraft:block.conduit.ambient"))
+            }
+            _ => None,
+        };
+        if let Some(sound) = sound {
+            self.play_sound(sound, SoundCategory::Blocks, &center(position))
+                .await;
+        }
+        if !active {
+            return ConduitState::default();
+        }
+
+        self.give_conduit_power(position, frame).await;
+        let target = if frame >= FULL_FRAME {
+            self.conduit_attack(position, state.target).await
+        } else {
+            None
+        };
+        ConduitState { active, target }
+    }
+
+    async fn give_conduit_power(&self, position: WorldPosition, frame: u32) {
+        // every 7 frame blocks reach 16 blocks further
+        let range = f64::from(frame / 7 * 16);
+        let conduit = center(position);
+        for player in self.players().await.iter() {
+            // the effect reaches up to the top of the world
+            let living = &player.living_entity;
+            let pos = living.entity.pos.load();
+            if (pos.x - conduit.x).abs() > range + 0.5
+                || (pos.z - conduit.z).abs() > range + 0.5
+                || pos.y < conduit.y - range - 0.5
+                || !living.is_in_water().await
+            {
+                continue;
+            }
+            living
+                .add_effect(StatusEffect {
+                    ambient: true,
+                    ..StatusEffect::new(EffectType::ConduitPower, 0, EFFECT_DURATION)
+                })
+                .await;
+        }
+    }
+
+    /// Hurts the target, or the closest monster in the water if the target is gone. Returns who
+    /// it attacked
+    async fn conduit_attack(
+        &self,
+        position: WorldPosition,
+        target: Option<EntityId>,
+    ) -> Option<EntityId> {
+        let conduit = center(position);
+        let in_range = |entity: &Arc<dyn EntityBase>| {
+            let pos = entity.get_entity().pos.load();
+            entity.get_entity().entity_type.is_enemy()
+                && entity.get_living_entity().is_some()
+                && (pos.x - conduit.x).abs() <= ATTACK_RANGE
+                && (pos.y - conduit.y).abs() <= ATTACK_RANGE
+                && (pos.z - conduit.z).abs() <= ATTACK_RANGE
+        };
+        let mut candidates: Vec<_> = self
+            .entities
+            .lock()
+            .await
+            .values()
+            .filter(|entity| in_range(*entity))
+            .cloned()
+            .collect();
+        candidates.sort_by(|a, b| {
+            let distance = |entity: &Arc<dyn EntityBase>| {
+                entity
+                    .get_entity()
+                    .pos
+                    .load()
+                    .sub(&conduit)
+                    .length_squared()
+            };
+            distance(a).total_cmp(&distance(b))
+        });
+        if let Some(index) = candidates
+            .iter()
+            .position(|entity| Some(entity.get_entity().entity_id) == target)
+        {
+            candidates.swap(0, index);
+        }
+
+        for entity in candidates {
+            let Some(living) = entity.get_living_entity() else {
+                continue;
+            };
+            if !living.is_in_water().await {
+                continue;
+            }
+            let pos = entity.get_entity().pos.load();
+            self.play_sound(
+                sound!("minecraft:block.conduit.attack.target"),
+                SoundCategory::Blocks,
+                &pos,
+            )
+            .await;
+            living.damage(ATTACK_DAMAGE, DamageSource::new(MAGIC)).await;
+            return Some(entity.get_entity().entity_id);
+        }
+        None
+    }
+}
@@ -0,0 +1,100 @@
+//! Daylight detectors: their signal follows the sky light reaching them and how high the sun is,
+//! inverted ones give a signal at night instead. Players switch them by using them.
+
+use std::f32::consts::PI;
+
+use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+use pumpkin_world::{
+    block::block_registry::{get_block_and_state_by_state_id, get_block_by_state_id},
+    WORLD_MAX_Y,
+};
+
+use crate::world::{
+    time::{celestial_angle, sky_darken},
+    World,
+};
+
+/// Daylight detectors look at the sky every second
+pub const UPDATE_INTERVAL: u32 = 20;
+const MAX_LIGHT: i32 = 15;
+
+/// The signal of a detector which gets the sky light
+fn detector_power(sky_light: i32, angle: f32, inverted: bool) -> i32 {
+    let light = sky_light - sky_darken(angle);
+    let power = if inverted {
+        MAX_LIGHT - light
+    } else if light > 0 {
+        // the signal is weaker while the sun is low
+        let mut sun = angle * PI * 2.0;
+        let noon = if sun < PI { 0.0 } else { PI * 2.0 };
+        sun += (noon - sun) * 0.2;
+        (f64::from(light) * f64::from(sun.cos())).round() as i32
+    } else {
+        light
+    };
+    power.clamp(0, MAX_LIGHT)
+}
+
+impl World {
+    /// The sky light reaching the position, every block above takes away as much as it blocks
+    async fn sky_light(&self, position: WorldPosition) -> i32 {
+        let pos = position.0;
+        let min = Vector3::new(pos.x, pos.y + 1, pos.z);
+        let max = Vector3::new(pos.x, i32::from(WORLD_MAX_Y), pos.z);
+        let blocked: u32 = self
+            .get_block_state_ids_in(min, max)
+            .await
+            .into_iter()
+            .filter_map(|(_, state_id)| get_block_and_state_by_state_id(state_id))
+            .filter_map(|(_, state)| state.opacity)
+            .sum();
+        (MAX_LIGHT - blocked.min(MAX_LIGHT as u32) as i32).max(0)
+    }
+
+    /// Whether nothing blocks the sky above the position
+    pub async fn sees_sky(&self, position: WorldPosition) -> bool {
+        self.sky_light(position).await == MAX_LIGHT
+    }
+
+    /// Whether the sun shines on the position right now
+    pub async fn is_in_sunlight(&self, position: WorldPosition) -> bool {
+        self.is_day() && self.sees_sky(position).await
+    }
+
+    /// Updates the signal of the daylight detector to the light it gets now
+    pub(super) async fn tick_daylight_detector(&self, position: WorldPosition, state_id: u16) {
+        let Some(block) = get_block_by_state_id(state_id) else {
+            return;
+        };
+        let properties = block.properties_of_state(state_id).unwrap_or_default();
+        let inverted = properties.contains(&("inverted", "true"));
+        let angle = celestial_angle(self.time.day_time());
+        let power = detector_power(self.sky_light(position).await, angle, inverted).to_string();
+        if let Some(new_state) = block.changed_state(state_id, &[("power", power.as_str())]) {
+            if new_state != state_id {
+                self.set_block_state(position, new_state).await;
+            }
+        }
+    }
+
+    /// Switches the daylight detector between day and night mode
+    pub async fn toggle_daylight_detector(&self, position: WorldPosition, state_id: u16) {
+        let Some(block) = get_block_by_state_id(state_id) else {
+            return;
+        };
+        let properties = block.properties_of_state(state_id).unwrap_or_default();
+        let inverted = if properties.contains(&("inverted", "true")) {
+            "false"
+        } else {
+            "true"
+        };
+        if let Some(new_state) = block.changed_state(state_id, &[("inverted", inverted)]) {
+            self.set_block_state(position, new_state).await;
+        }
+        self.tick_daylight_detector(
+            position,
+            self.get_block_state_id(position).await.unwrap_or(state_id),
+        )
+        .await;
+    }
+}
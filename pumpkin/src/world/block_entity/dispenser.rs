@@ -0,0 +1,566 @@
+//! Dispensers and droppers: once they get powered they fire an item out of a random slot. Droppers
+//! put it into the container they face or drop it, dispensers use the [`DispenseBehavior`]
+//! registered for the item, like shooting arrows or placing water.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock},
+};
+
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use pumpkin_core::math::{boundingbox::BoundingBox, position::WorldPosition, vector3::Vector3};
+use pumpkin_inventory::{
+    transfer::{insert_stack, remove_one},
+    Container,
+};
+use pumpkin_protocol::client::play::CWorldEvent;
+use pumpkin_world::{
+    block::{
+        block_registry::{get_block, get_block_by_state_id},
+        BlockFace,
+    },
+    item::{
+        item_registry::{get_item, get_item_name},
+        ItemStack,
+    },
+};
+use rand::Rng;
+use tokio::sync::Mutex;
+
+use crate::{
+    entity::{
+        arrow::{shoot_velocity, Arrow},
+        item::{ItemEntity, PICKUP_DELAY},
+        potion::ThrownPotion,
+    },
+    world::{vibration::GameEvent, World},
+};
+
+/// Ticks between getting powered and firing
+const FIRE_DELAY: u32 = 4;
+/// The world events of dispensers, the client plays their sounds and shows the smoke
+pub const DISPENSE_EVENT: i32 = 1000;
+pub const FAIL_EVENT: i32 = 1001;
+pub const LAUNCH_EVENT: i32 = 1002;
+const SMOKE_EVENT: i32 = 2000;
+
+static BEHAVIORS: LazyLock<RwLock<HashMap<String, Arc<dyn DispenseBehavior>>>> =
+    LazyLock::new(|| RwLock::new(default_behaviors()));
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DispenserState {
+    /// Ticks until it fires, 0 if it isn't about to
+    pub delay: u32,
+}
+
+/// The dispenser an item is dispensed from
+pub struct DispenseSource {
+    pub position: WorldPosition,
+    pub facing: BlockFace,
+}
+
+impl DispenseSource {
+    /// The block the dispenser faces
+    #[must_use]
+    pub fn front(&self) -> WorldPosition {
+        WorldPosition(self.position.0.add(&self.facing.to_offset()))
+    }
+
+    /// The direction the dispenser faces as a unit vector
+    #[must_use]
+    pub fn direction(&self) -> Vector3<f64> {
+        let offset = self.facing.to_offset();
+        Vector3::new(
+            f64::from(offset.x),
+            f64::from(offset.y),
+            f64::from(offset.z),
+        )
+    }
+
+    /// Where items come out of the dispenser, just in front of its center
+    #[must_use]
+    pub fn output(&self) -> Vector3<f64> {
+        let pos = self.position.0;
+        let direction = self.direction();
+        Vector3::new(
+            f64::from(pos.x) + 0.5 + direction.x * 0.7,
+            f64::from(pos.y) + 0.5 + direction.y * 0.7,
+            f64::from(pos.z) + 0.5 + direction.z * 0.7,
+        )
+    }
+}
+
+/// What a dispenser did with a stack
+pub struct Dispensed {
+    /// What is left of the stack, put back into its slot
+    pub rest: Option<ItemStack>,
+    /// An item the dispenser gets besides, like the water bucket after taking up water. It drops
+    /// if the dispenser is full
+    pub extra: Option<ItemStack>,
+    /// The world event of the sound the dispenser makes, e.g. [`LAUNCH_EVENT`] for projectiles.
+    /// `None` if nothing was dispensed, the dispenser clicks instead
+    pub event: Option<i32>,
+}
+
+impl Dispensed {
+    /// Nothing happened, the stack stays as it was
+    #[must_use]
+    pub const fn failed(stack: ItemStack) -> Self {
+        Self {
+            rest: Some(stack),
+            extra: None,
+            event: None,
+        }
+    }
+
+    /// One item of the stack was used up
+    #[must_use]
+    pub fn used_one(stack: ItemStack, event: i32) -> Self {
+        Self {
+            rest: shrink(stack),
+            extra: None,
+            event: Some(event),
+        }
+    }
+
+    /// One item of the stack turned into another one, like a water bucket into a bucket
+    #[must_use]
+    pub fn replaced_one(stack: ItemStack, replacement: ItemStack) -> Self {
+        if stack.item_count <= 1 {
+            return Self {
+                rest: Some(replacement),
+                extra: None,
+                event: Some(DISPENSE_EVENT),
+            };
+        }
+        Self {
+            rest: shrink(stack),
+            extra: Some(replacement),
+            event: Some(DISPENSE_EVENT),
+        }
+    }
+}
+
+/// The stack with one item less, `None` if it was the last one
+fn shrink(mut stack: ItemStack) -> Option<ItemStack> {
+    stack.item_count -= 1;
+    (stack.item_count > 0).then_some(stack)
+}
+
+fn single(name: &str) -> Option<ItemStack> {
+    get_item(name).map(|item| ItemStack::new(1, item.id))
+}
+
+/// What a dispenser does with the items of a kind. Plugins can register their own with
+/// [`register_dispense_behavior`]
+#[async_trait]
+pub trait DispenseBehavior: Send + Sync {
+    /// Dispenses one item of the stack
+    async fn dispense(
+        &self,
+        world: &Arc<World>,
+        source: &DispenseSource,
+        stack: ItemStack,
+    ) -> Dispensed;
+}
+
+/// Registers what dispensers do with the item, replacing the previous behavior
+pub fn register_dispense_behavior(item: &str, behavior: Arc<dyn DispenseBehavior>) {
+    BEHAVIORS.write().insert(item.to_string(), behavior);
+}
+
+fn dispense_behavior(stack: &ItemStack) -> Arc<dyn DispenseBehavior> {
+    get_item_name(stack.item_id)
+        .and_then(|name| BEHAVIORS.read().get(name).cloned())
+        .unwrap_or_else(|| Arc::new(DropItem))
+}
+
+fn default_behaviors() -> HashMap<String, Arc<dyn DispenseBehavior>> {
+    let mut behaviors: HashMap<String, Arc<dyn DispenseBehavior>> = HashMap::new();
+    for arrow in [
+        "minecraft:arrow",
+        "minecraft:tipped_arrow",
+        "minecraft:spectral_arrow",
+    ] {
+        behaviors.insert(arrow.to_string(), Arc::new(ShootArrow));
+    }
+    for potion in ["minecraft:splash_potion", "minecraft:lingering_potion"] {
+        behaviors.insert(potion.to_string(), Arc::new(ThrowPotion));
+    }
+    for (bucket, fluid) in [
+        ("minecraft:water_bucket", "minecraft:water"),
+        ("minecraft:lava_bucket", "minecraft:lava"),
+    ] {
+        behaviors.insert(bucket.to_string(), Arc::new(EmptyBucket { fluid }));
+    }
+    behaviors.insert("minecraft:bucket".to_string(), Arc::new(FillBucket));
+    behaviors.insert("minecraft:shears".to_string(), Arc::new(Shear));
+    behaviors
+}
+
+/// Drops the item in front of the dispenser, what droppers always do
+pub struct DropItem;
+
+#[async_trait]
+impl DispenseBehavior for DropItem {
+    async fn dispense(
+        &self,
+        world: &Arc<World>,
+        source: &DispenseSource,
+        stack: ItemStack,
+    ) -> Dispensed {
+        let mut item = stack.clone();
+        item.item_count = 1;
+        drop_from(world, source, item).await;
+        Dispensed::used_one(stack, DISPENSE_EVENT)
+    }
+}
+
+async fn drop_from(world: &Arc<World>, source: &DispenseSource, stack: ItemStack) {
+    let mut position = source.output();
+    position.y -= if source.facing == BlockFace::Top || source.facing == BlockFace::Bottom {
+        0.125
+    } else {
+        0.15625
+    };
+    let direction = source.direction();
+    let velocity = {
+        let mut rng = rand::thread_rng();
+        let speed = rng.gen::<f64>() * 0.1 + 0.2;
+        let mut spread = || rng.gen_range(-1.0..1.0) * 0.0172275 * 6.0;
+        Vector3::new(
+            direction.x * speed + spread(),
+            0.2 + spread(),
+            direction.z * speed + spread(),
+        )
+    };
+    let item = ItemEntity::new(world.clone(), stack, position, velocity, PICKUP_DELAY);
+    world.spawn_entity(Arc::new(item)).await;
+}
+
+struct ShootArrow;
+
+#[async_trait]
+impl DispenseBehavior for ShootArrow {
+    async fn dispense(
+        &self,
+        world: &Arc<World>,
+        source: &DispenseSource,
+        stack: ItemStack,
+    ) -> Dispensed {
+        let mut direction = source.direction();
+        direction.y += 0.1;
+        let velocity = shoot_velocity(direction, 1.1, 6.0);
+        let arrow = Arrow::new(
+            world.clone(),
+            stack.clone(),
+            source.output(),
+            velocity,
+            None,
+        );
+        world.spawn_entity(Arc::new(arrow)).await;
+        world
+            .emit_game_event(GameEvent::ProjectileShoot, source.output())
+            .await;
+        Dispensed::used_one(stack, LAUNCH_EVENT)
+    }
+}
+
+struct ThrowPotion;
+
+#[async_trait]
+impl DispenseBehavior for ThrowPotion {
+    async fn dispense(
+        &self,
+        world: &Arc<World>,
+        source: &DispenseSource,
+        stack: ItemStack,
+    ) -> Dispensed {
+        let mut direction = source.direction();
+        direction.y += 0.1;
+        let velocity = shoot_velocity(direction, 1.375, 3.0);
+        let mut potion = stack.clone();
+        potion.item_count = 1;
+        let potion = ThrownPotion::new(world.clone(), potion, source.output(), velocity, None);
+        world.spawn_entity(Arc::new(potion)).await;
+        world
+            .emit_game_event(GameEvent::ProjectileShoot, source.output())
+            .await;
+        Dispensed::used_one(stack, LAUNCH_EVENT)
+    }
+}
+
+/// Places the fluid of a bucket in front of the dispenser, the bucket stays
+struct EmptyBucket {
+    fluid: &'static str,
+}
+
+#[async_trait]
+impl DispenseBehavior for EmptyBucket {
+    async fn dispense(
+        &self,
+        world: &Arc<World>,
+        source: &DispenseSource,
+        stack: ItemStack,
+    ) -> Dispensed {
+        let front = source.front();
+        let replaceable = world
+            .get_block_state(front)
+            .await
+            .is_ok_and(|state| state.replaceable);
+        let (Some(fluid), Some(bucket)) = (get_block(self.fluid), single("minecraft:bucket"))
+        else {
+            return Dispensed::failed(stack);
+        };
+        if !replaceable {
+            return DropItem.dispense(world, source, stack).await;
+        }
+        world.set_block_state(front, fluid.default_state_id).await;
+        Dispensed::replaced_one(stack, bucket)
+    }
+}
+
+/// Takes up the water or lava source in front of the dispenser
+struct FillBucket;
+
+#[async_trait]
+impl DispenseBehavior for FillBucket {
+    async fn dispense(
+        &self,
+        world: &Arc<World>,
+        source: &DispenseSource,
+        stack: ItemStack,
+    ) -> Dispensed {
+        let front = source.front();
+        let Ok(state_id) = world.get_block_state_id(front).await else {
+            return Dispensed::failed(stack);
+        };
+        let filled = get_block_by_state_id(state_id).and_then(|block| {
+            let source_block = block
+                .properties_of_state(state_id)
+                .is_some_and(|properties| properties.contains(&("level", "0")));
+            match block.name.as_str() {
+                "minecraft:water" if source_block => single("minecraft:water_bucket"),
+                "minecraft:lava" if source_block => single("minecraft:lava_bucket"),
+                _ => None,
+            }
+        });
+        let (Some(filled), Some(air)) = (filled, get_block("minecraft:air")) else {
+            return DropItem.dispense(world, source, stack).await;
+        };
+        world.set_block_state(front, air.default_state_id).await;
+        Dispensed::replaced_one(stack, filled)
+    }
+}
+
+/// Shears the first entity in front of the dispenser which can be sheared
+struct Shear;
+
+#[async_trait]
+impl DispenseBehavior for Shear {
+    async fn dispense(
+        &self,
+        world: &Arc<World>,
+        source: &DispenseSource,
+        mut stack: ItemStack,
+    ) -> Dispensed {
+        let front = source.front().0;
+        let area = BoundingBox {
+            min_x: f64::from(front.x),
+            min_y: f64::from(front.y),
+            min_z: f64::from(front.z),
+            max_x: f64::from(front.x) + 1.0,
+            max_y: f64::from(front.y) + 1.0,
+            max_z: f64::from(front.z) + 1.0,
+        };
+        let entities: Vec<_> = world
+            .entities
+            .lock()
+            .await
+            .values()
+            .filter(|entity| {
+                let entity = entity.get_entity();
+                let pos = entity.pos.load();
+                BoundingBox::new_from_pos(pos.x, pos.y, pos.z, &entity.bounding_box_size.load())
+                    .intersects(&area)
+            })
+            .cloned()
+            .collect();
+        for entity in entities {
+            if entity.shear().await {
+                let broke = stack.wear(1);
+                return Dispensed {
+                    rest: (!broke).then_some(stack),
+                    extra: None,
+                    event: Some(DISPENSE_EVENT),
+                };
+            }
+        }
+        Dispensed::failed(stack)
+    }
+}
+
+impl World {
+    /// Fires the dispenser once it got powered, the block state remembers whether it is powered
+    pub(super) async fn tick_dispenser(
+        self: &Arc<Self>,
+        position: WorldPosition,
+        state_id: u16,
+        state: DispenserState,
+        container_id: u64,
+        container: &Arc<Mutex<Box<dyn Container>>>,
+    ) -> DispenserState {
+        let Some(block) = get_block_by_state_id(state_id) else {
+            return state;
+        };
+        let properties = block.properties_of_state(state_id).unwrap_or_default();
+        let triggered = properties.contains(&("triggered", "true"));
+        let powered = self.is_receiving_power_or_above(position).await;
+        let mut delay = state.delay;
+        if powered != triggered {
+            let triggered = if powered { "true" } else { "false" };
+            if let Some(new_state) = block.changed_state(state_id, &[("triggered", triggered)]) {
+                self.set_block_state(position, new_state).await;
+            }
+            if powered {
+                delay = FIRE_DELAY;
+            }
+        }
+        if delay == 0 {
+            return DispenserState { delay };
+        }
+        delay -= 1;
+        if delay == 0 {
+            let facing = properties
+                .iter()
+                .find(|(name, _)| *name == "facing")
+                .and_then(|(_, value)| BlockFace::from_name(value))
+                .unwrap_or(BlockFace::North);
+            let source = DispenseSource { position, facing };
+            let dropper = block.name == "minecraft:dropper";
+            self.fire_dispenser(&source, dropper, container_id, container)
+                .await;
+        }
+        DispenserState { delay }
+    }
+
+    async fn fire_dispenser(
+        self: &Arc<Self>,
+        source: &DispenseSource,
+        dropper: bool,
+        container_id: u64,
+        container: &Arc<Mutex<Box<dyn Container>>>,
+    ) {
+        let position = source.position;
+        let slot = {
+            let container = container.lock().await;
+            let filled: Vec<_> = container
+                .all_slots_ref()
+                .iter()
+                .enumerate()
+                .filter(|(_, slot)| slot.is_some())
+                .map(|(slot, _)| slot)
+                .collect();
+            (!filled.is_empty()).then(|| filled[rand::thread_rng().gen_range(0..filled.len())])
+        };
+        let Some(slot) = slot else {
+            self.broadcast_packet_all(&CWorldEvent::new(FAIL_EVENT, &position, 0, false))
+                .await;
+            return;
+        };
+
+        let event = if dropper {
+            self.fire_dropper(source, slot, container).await
+        } else {
+            self.fire_behavior(source, slot, container).await
+        };
+        self.send_container_content(container_id, container).await;
+        match event {
+            Some(event) => {
+                self.broadcast_packet_all(&CWorldEvent::new(event, &position, 0, false))
+                    .await;
+                self.broadcast_packet_all(&CWorldEvent::new(
+                    SMOKE_EVENT,
+                    &position,
+                    source.facing as i32,
+                    false,
+                ))
+                .await;
+            }
+            None => {
+                self.broadcast_packet_all(&CWorldEvent::new(FAIL_EVENT, &position, 0, false))
+                    .await;
+            }
+        }
+    }
+
+    /// Puts an item of the slot into the container in front of the dropper, or drops it
+    async fn fire_dropper(
+        self: &Arc<Self>,
+        source: &DispenseSource,
+        slot: usize,
+        container: &Arc<Mutex<Box<dyn Container>>>,
+    ) -> Option<i32> {
+        let Some((target_id, target)) = self.block_entity(source.front()).await else {
+            let item = remove_one(container.lock().await.as_mut(), slot)?;
+            drop_from(self, source, item).await;
+            return Some(DISPENSE_EVENT);
+        };
+        let inserted = {
+            let mut dropper = container.lock().await;
+            let mut one = dropper
+                .all_slots_ref()
+                .get(slot)
+                .copied()
+                .flatten()?
+                .clone();
+            one.item_count = 1;
+            let inserted =
+                insert_stack(target.lock().await.as_mut(), one, source.facing.opposite()).is_none();
+            if inserted {
+                remove_one(dropper.as_mut(), slot);
+            }
+            inserted
+        };
+        // droppers in front of a full container don't drop their items
+        if !inserted {
+            return None;
+        }
+        self.send_container_content(target_id, &target).await;
+        Some(DISPENSE_EVENT)
+    }
+
+    /// Dispenses the stack of the slot with the behavior of its item
+    async fn fire_behavior(
+        self: &Arc<Self>,
+        source: &DispenseSource,
+        slot: usize,
+        container: &Arc<Mutex<Box<dyn Container>>>,
+    ) -> Option<i32> {
+        let stack = container
+            .lock()
+            .await
+            .all_slots_ref()
+            .get(slot)
+            .copied()
+            .flatten()?
+            .clone();
+        let dispensed = dispense_behavior(&stack)
+            .dispense(self, source, stack)
+            .await;
+        let leftover = {
+            let mut container = container.lock().await;
+            if let Some(slot) = container.all_slots().get_mut(slot) {
+                **slot = dispensed.rest;
+            }
+            dispensed
+                .extra
+                .and_then(|extra| insert_stack(container.as_mut(), extra, source.facing.opposite()))
+        };
+        if let Some(leftover) = leftover {
+            drop_from(self, source, leftover).await;
+        }
+        dispensed.event
+    }
+}
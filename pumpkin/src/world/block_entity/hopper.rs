@@ -0,0 +1,165 @@
+//! Hoppers: they push an item into the container they face and pull one out of the container
+//! above them, or pick up the items lying on them. Powered hoppers are locked.
+
+use std::sync::Arc;
+
+use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+use pumpkin_inventory::{
+    transfer::{insert_stack, move_one},
+    Container,
+};
+use pumpkin_world::block::{block_registry::get_block_by_state_id, BlockFace};
+use tokio::sync::Mutex;
+
+use crate::world::World;
+
+/// Ticks a hopper waits after moving items
+const TRANSFER_COOLDOWN: u32 = 8;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HopperState {
+    /// Ticks until the hopper moves items again
+    pub cooldown: u32,
+}
+
+impl World {
+    /// Moves items through the hopper once its cooldown is over, unless it is locked
+    pub(super) async fn tick_hopper(
+        &self,
+        position: WorldPosition,
+        state_id: u16,
+        state: HopperState,
+        container_id: u64,
+        container: &Arc<Mutex<Box<dyn Container>>>,
+    ) -> HopperState {
+        let cooldown = state.cooldown.saturating_sub(1);
+        let Some(block) = get_block_by_state_id(state_id) else {
+            return HopperState { cooldown };
+        };
+        let properties = block.properties_of_state(state_id).unwrap_or_default();
+        let enabled = !self.is_receiving_power(position).await;
+        // the block shows whether the hopper is locked
+        let enabled_name = if enabled { "true" } else { "false" };
+        if !properties.contains(&("enabled", enabled_name)) {
+            if let Some(new_state) = block.changed_state(state_id, &[("enabled", enabled_name)]) {
+                self.set_block_state(position, new_state).await;
+            }
+        }
+        if cooldown > 0 || !enabled {
+            return HopperState { cooldown };
+        }
+
+        let facing = properties
+            .iter()
+            .find(|(name, _)| *name == "facing")
+            .and_then(|(_, value)| BlockFace::from_name(value))
+            .unwrap_or(BlockFace::Bottom);
+        let pushed = self
+            .hopper_push(position, facing, container_id, container)
+            .await;
+        let pulled = self.hopper_pull(position, container_id, container).await;
+        HopperState {
+            cooldown: if pushed || pulled {
+                TRANSFER_COOLDOWN
+            } else {
+                0
+            },
+        }
+    }
+
+    /// Moves an item into the container the hopper faces
+    async fn hopper_push(
+        &self,
+        position: WorldPosition,
+        facing: BlockFace,
+        container_id: u64,
+        container: &Arc<Mutex<Box<dyn Container>>>,
+    ) -> bool {
+        let target = WorldPosition(position.0.add(&facing.to_offset()));
+        let Some((target_id, target)) = self.block_entity(target).await else {
+            return false;
+        };
+        let moved = {
+            let mut hopper = container.lock().await;
+            let mut target = target.lock().await;
+            move_one(hopper.as_mut(), facing, target.as_mut(), facing.opposite())
+        };
+        if moved {
+            self.send_container_content(container_id, container).await;
+            self.send_container_content(target_id, &target).await;
+        }
+        moved
+    }
+
+    /// Moves an item out of the container above the hopper, or picks up items lying on it
+    async fn hopper_pull(
+        &self,
+        position: WorldPosition,
+        container_id: u64,
+        container: &Arc<Mutex<Box<dyn Container>>>,
+    ) -> bool {
+        let pos = position.0;
+        let above = WorldPosition(Vector3::new(pos.x, pos.y + 1, pos.z));
+        let moved = if let Some((source_id, source)) = self.block_entity(above).await {
+            let moved = {
+                let mut source = source.lock().await;
+                let mut hopper = container.lock().await;
+                move_one(
+                    source.as_mut(),
+                    BlockFace::Bottom,
+                    hopper.as_mut(),
+                    BlockFace::Top,
+                )
+            };
+            if moved {
+                self.send_container_content(source_id, &source).await;
+            }
+            moved
+        } else {
+            self.hopper_pick_up(position, container).await
+        };
+        if moved {
+            self.send_container_content(container_id, container).await;
+        }
+        moved
+    }
+
+    /// Puts the first item lying in the hopper's bowl or on top of it into the hopper
+    async fn hopper_pick_up(
+        &self,
+        position: WorldPosition,
+        container: &Arc<Mutex<Box<dyn Container>>>,
+    ) -> bool {
+        let pos = position.0;
+        let (x, y, z) = (f64::from(pos.x), f64::from(pos.y), f64::from(pos.z));
+        let items: Vec<_> = self
+            .entities
+            .lock()
+            .await
+            .values()
+            .filter(|entity| {
+                let item_pos = entity.get_entity().pos.load();
+                entity.get_item_entity().is_some()
+                    && (x..x + 1.0).contains(&item_pos.x)
+                    && (y + 0.6875..y + 2.0).contains(&item_pos.y)
+                    && (z..z + 1.0).contains(&item_pos.z)
+            })
+            .cloned()
+            .collect();
+        for entity in items {
+            let Some(item) = entity.get_item_entity() else {
+                continue;
+            };
+            let stack = item.stack();
+            let count = stack.item_count;
+            let left = insert_stack(container.lock().await.as_mut(), stack, BlockFace::Top);
+            match left {
+                None => self.despawn_entity(&item.entity).await,
+                Some(left) if left.item_count < count => item.set_stack(left),
+                Some(_) => continue,
+            }
+            return true;
+        }
+        false
+    }
+}
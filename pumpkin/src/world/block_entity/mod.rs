@@ -0,0 +1,507 @@
+//! Blocks which work by themselves, like brewing stands brewing their potions, beacons giving
+//! effects, hoppers moving items, pistons pushing blocks or sculk spreading from catalysts. They
+//! are saved with their chunk, see [`saved`].
+
+use std::{
+    collections::HashMap,
+    mem::discriminant,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use beacon::BeaconState;
+use conduit::ConduitState;
+use dispenser::DispenserState;
+use hopper::HopperState;
+use observer::ObserverState;
+use piston::{MovingPistonState, PistonState};
+use pulse::PulseState;
+use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+use pumpkin_inventory::{Beacon, BrewingStand, Chest, Container, Dispenser, Hopper};
+use pumpkin_macros::sound;
+use pumpkin_protocol::SoundCategory;
+use pumpkin_world::block::block_registry::get_block_by_state_id;
+use rand::Rng;
+use sculk_catalyst::SculkCatalystState;
+use sculk_sensor::SculkSensorState;
+use sculk_shrieker::SculkShriekerState;
+use tokio::sync::Mutex;
+
+use crate::{entity::item::ItemEntity, plugin::content::CONTENT};
+
+use super::{player_chunker::SimulationArea, World};
+
+pub mod beacon;
+pub mod conduit;
+pub mod daylight_detector;
+pub mod dispenser;
+pub mod hopper;
+pub mod note_block;
+pub mod observer;
+pub mod piston;
+pub mod pulse;
+pub mod saved;
+pub mod sculk_catalyst;
+pub mod sculk_sensor;
+pub mod sculk_shrieker;
+
+/// 0 and 1 are the containers of `/echest` and `/craft`
+static NEXT_CONTAINER_ID: AtomicU64 = AtomicU64::new(2);
+/// Ticks until players can pick up the items of a broken block
+const DROP_PICKUP_DELAY: u32 = 10;
+
+/// What a block entity does, with what it keeps track of besides its container
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlockEntityKind {
+    Chest,
+    BrewingStand,
+    Beacon(BeaconState),
+    Conduit(ConduitState),
+    Hopper(HopperState),
+    Dispenser(DispenserState),
+    Dropper(DispenserState),
+    Piston(PistonState),
+    MovingPiston(MovingPistonState),
+    Observer(ObserverState),
+    NoteBlock,
+    Target(PulseState),
+    LightningRod(PulseState),
+    SculkSensor(SculkSensorState),
+    SculkShrieker(SculkShriekerState),
+    SculkCatalyst(SculkCatalystState),
+    DaylightDetector,
+}
+
+impl BlockEntityKind {
+    /// The block entity of the block, `None` if it has none which works by itself
+    #[must_use]
+    pub fn of_block(name: &str) -> Option<Self> {
+        match name {
+            "minecraft:chest" | "minecraft:trapped_chest" => Some(Self::Chest),
+            "minecraft:brewing_stand" => Some(Self::BrewingStand),
+            "minecraft:beacon" => Some(Self::Beacon(BeaconState::default())),
+            "minecraft:conduit" => Some(Self::Conduit(ConduitState::default())),
+            "minecraft:hopper" => Some(Self::Hopper(HopperState::default())),
+            "minecraft:dispenser" => Some(Self::Dispenser(DispenserState::default())),
+            "minecraft:dropper" => Some(Self::Dropper(DispenserState::default())),
+            "minecraft:piston" | "minecraft:sticky_piston" => {
+                Some(Self::Piston(PistonState::default()))
+            }
+            "minecraft:moving_piston" => Some(Self::MovingPiston(MovingPistonState::default())),
+            "minecraft:observer" => Some(Self::Observer(ObserverState::default())),
+            "minecraft:note_block" => Some(Self::NoteBlock),
+            "minecraft:target" => Some(Self::Target(PulseState::default())),
+            "minecraft:lightning_rod" => Some(Self::LightningRod(PulseState::default())),
+            "minecraft:sculk_sensor" | "minecraft:calibrated_sculk_sensor" => {
+                Some(Self::SculkSensor(SculkSensorState::default()))
+            }
+            "minecraft:sculk_shrieker" => Some(Self::SculkShrieker(SculkShriekerState::default())),
+            "minecraft:sculk_catalyst" => Some(Self::SculkCatalyst(SculkCatalystState::default())),
+            "minecraft:daylight_detector" => Some(Self::DaylightDetector),
+            _ => None,
+        }
+    }
+
+    fn container(self) -> Option<Box<dyn Container>> {
+        match self {
+            Self::Chest => Some(Box::new(Chest::new())),
+            Self::BrewingStand => Some(Box::<BrewingStand>::default()),
+            Self::Beacon(_) => Some(Box::<Beacon>::default()),
+            Self::Conduit(_)
+            | Self::Piston(_)
+            | Self::MovingPiston(_)
+            | Self::Observer(_)
+            | Self::NoteBlock
+            | Self::Target(_)
+            | Self::LightningRod(_)
+            | Self::SculkSensor(_)
+            | Self::SculkShrieker(_)
+            | Self::SculkCatalyst(_)
+            | Self::DaylightDetector => None,
+            Self::Hopper(_) => Some(Box::<Hopper>::default()),
+            Self::Dispenser(_) => Some(Box::<Dispenser>::default()),
+            Self::Dropper(_) => Some(Box::new(Dispenser::dropper())),
+        }
+    }
+}
+
+pub struct BlockEntity {
+    pub kind: BlockEntityKind,
+    /// The container players open with its id in the server's open containers, most block entities
+    /// without items like conduits and pistons have none
+    pub container: Option<(u64, Arc<Mutex<Box<dyn Container>>>)>,
+    /// The window properties the players looking into the container were sent last
+    properties: Vec<(i16, i16)>,
+    /// Ticks since it was created, some block entities only work every few ticks
+    age: u32,
+}
+
+impl BlockEntity {
+    fn new(kind: BlockEntityKind) -> Self {
+        Self {
+            kind,
+            container: kind
+                .container()
+                .map(|container| (new_container_id(), Arc::new(Mutex::new(container)))),
+            properties: Vec::new(),
+            age: 0,
+        }
+    }
+}
+
+/// An id for a container in the server's open containers, which no other container has
+pub(crate) fn new_container_id() -> u64 {
+    NEXT_CONTAINER_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// The middle of the block at the position
+pub(crate) fn center(position: WorldPosition) -> Vector3<f64> {
+    let pos = position.0;
+    Vector3::new(
+        f64::from(pos.x) + 0.5,
+        f64::from(pos.y) + 0.5,
+        f64::from(pos.z) + 0.5,
+    )
+}
+
+impl World {
+    /// Creates the block entity of a block which was just set, if it has one. What its chunk saved
+    /// of it is restored
+    pub(super) async fn create_block_entity(&self, position: WorldPosition, state_id: u16) {
+        let Some(kind) = get_block_by_state_id(state_id)
+            .and_then(|block| BlockEntityKind::of_block(&block.name))
+        else {
+            return;
+        };
+        let missing = |block_entities: &HashMap<WorldPosition, BlockEntity>| {
+            block_entities
+                .get(&position)
+                .is_none_or(|block_entity| discriminant(&block_entity.kind) != discriminant(&kind))
+        };
+        if !missing(&*self.block_entities.lock().await) {
+            return;
+        }
+        let block_entity = self.restore_block_entity(position, kind).await;
+        let mut block_entities = self.block_entities.lock().await;
+        if missing(&block_entities) {
+            block_entities.insert(position, block_entity);
+        }
+    }
+
+    /// The id and container of the block entity at the position, created the first time it is
+    /// used. `None` if the block has no block entity with a container
+    pub async fn block_entity(
+        &self,
+        position: WorldPosition,
+    ) -> Option<(u64, Arc<Mutex<Box<dyn Container>>>)> {
+        let state_id = self.get_block_state_id(position).await.ok()?;
+        self.create_block_entity(position, state_id).await;
+        let block_entities = self.block_entities.lock().await;
+        let (id, container) = block_entities.get(&position)?.container.as_ref()?;
+        Some((*id, container.clone()))
+    }
+
+    /// Where the block entity with the container is
+    pub async fn block_entity_position(&self, container_id: u64) -> Option<WorldPosition> {
+        self.block_entities
+            .lock()
+            .await
+            .iter()
+            .find(|(_, block_entity)| {
+                block_entity
+                    .container
+                    .as_ref()
+                    .is_some_and(|(id, _)| *id == container_id)
+            })
+            .map(|(position, _)| *position)
+    }
+
+    /// Ticks the block entities in the simulated chunks and shows the players looking into their
+    /// containers what changed. Block entities whose block is gone drop their items
+    pub(super) async fn tick_block_entities(self: &Arc<Self>, simulated: &SimulationArea) {
+        let block_entities: Vec<_> = self
+            .block_entities
+            .lock()
+            .await
+            .iter_mut()
+            .filter(|(position, _)| {
+                simulated.contains(position.chunk_and_chunk_relative_position().0)
+            })
+            .map(|(position, block_entity)| {
+                block_entity.age = block_entity.age.wrapping_add(1);
+                (
+                    *position,
+                    block_entity.kind,
+                    block_entity.container.clone(),
+                    block_entity.age,
+                )
+            })
+            .collect();
+        for (position, kind, container, age) in block_entities {
+            // game events during the tick may have changed it since
+            let kind = self
+                .block_entities
+                .lock()
+                .await
+                .get(&position)
+                .map_or(kind, |block_entity| block_entity.kind);
+            let Ok(state_id) = self.get_block_state_id(position).await else {
+                continue;
+            };
+            let current = get_block_by_state_id(state_id)
+                .and_then(|block| BlockEntityKind::of_block(&block.name));
+            if current.is_none_or(|current| discriminant(&current) != discriminant(&kind)) {
+                self.remove_block_entity(position).await;
+                continue;
+            }
+            if let Some((id, container)) = &container {
+                self.tick_container(position, state_id, kind, *id, container)
+                    .await;
+            }
+            let kind = match kind {
+                BlockEntityKind::Beacon(state) if age % beacon::UPDATE_INTERVAL == 0 => {
+                    let Some((_, container)) = &container else {
+                        continue;
+                    };
+                    BlockEntityKind::Beacon(self.tick_beacon(position, state, container).await)
+                }
+                BlockEntityKind::Conduit(state) if age % conduit::UPDATE_INTERVAL == 0 => {
+                    BlockEntityKind::Conduit(self.tick_conduit(position, state, age).await)
+                }
+                BlockEntityKind::Hopper(state) => {
+                    let Some((id, container)) = &container else {
+                        continue;
+                    };
+                    BlockEntityKind::Hopper(
+                        self.tick_hopper(position, state_id, state, *id, container)
+                            .await,
+                    )
+                }
+                BlockEntityKind::Dispenser(state) | BlockEntityKind::Dropper(state) => {
+                    let Some((id, container)) = &container else {
+                        continue;
+                    };
+                    let state = self
+                        .tick_dispenser(position, state_id, state, *id, container)
+                        .await;
+                    if matches!(kind, BlockEntityKind::Dropper(_)) {
+                        BlockEntityKind::Dropper(state)
+                    } else {
+                        BlockEntityKind::Dispenser(state)
+                    }
+                }
+                BlockEntityKind::Piston(state) => {
+                    BlockEntityKind::Piston(self.tick_piston(position, state_id, state).await)
+                }
+                BlockEntityKind::MovingPiston(state) => {
+                    BlockEntityKind::MovingPiston(self.tick_moving_piston(position, state).await)
+                }
+                BlockEntityKind::Observer(state) => {
+                    BlockEntityKind::Observer(self.tick_observer(position, state_id, state).await)
+                }
+                BlockEntityKind::NoteBlock => {
+                    self.tick_note_block(position, state_id).await;
+                    kind
+                }
+                BlockEntityKind::Target(state) => {
+                    BlockEntityKind::Target(self.tick_pulse(position, state_id, state).await)
+                }
+                BlockEntityKind::LightningRod(state) => {
+                    BlockEntityKind::LightningRod(self.tick_pulse(position, state_id, state).await)
+                }
+                BlockEntityKind::SculkSensor(state) => BlockEntityKind::SculkSensor(
+                    self.tick_sculk_sensor(position, state_id, state).await,
+                ),
+                BlockEntityKind::SculkShrieker(state) => BlockEntityKind::SculkShrieker(
+                    self.tick_sculk_shrieker(position, state_id, state).await,
+                ),
+                BlockEntityKind::SculkCatalyst(state) => BlockEntityKind::SculkCatalyst(
+                    self.tick_sculk_catalyst(position, state_id, state, age)
+                        .await,
+                ),
+                BlockEntityKind::DaylightDetector
+                    if age % daylight_detector::UPDATE_INTERVAL == 0 =>
+                {
+                    self.tick_daylight_detector(position, state_id).await;
+                    kind
+                }
+                kind => kind,
+            };
+            if let Some(block_entity) = self.block_entities.lock().await.get_mut(&position) {
+                // a piston may have moved another block entity here
+                if discriminant(&block_entity.kind) == discriminant(&kind) {
+                    block_entity.kind = kind;
+                }
+            }
+        }
+    }
+
+    /// Uses the block, like tuning a note block or switching a daylight detector. Returns whether
+    /// it was one which does something when used
+    pub async fn use_block(&self, position: WorldPosition) -> bool {
+        let Ok(state_id) = self.get_block_state_id(position).await else {
+            return false;
+        };
+        match get_block_by_state_id(state_id).map(|block| block.name.as_str()) {
+            Some("minecraft:note_block") if !CONTENT.is_custom_block(state_id) => {
+                self.tune_note_block(position, state_id).await;
+            }
+            Some("minecraft:daylight_detector") => {
+                self.toggle_daylight_detector(position, state_id).await;
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Shows the players looking into the container its items, after they changed by themselves
+    async fn send_container_content(
+        &self,
+        container_id: u64,
+        container: &Arc<Mutex<Box<dyn Container>>>,
+    ) {
+        let viewers = self
+            .players()
+            .await
+            .filter(|player| player.open_container.load() == Some(container_id));
+        for player in viewers.iter() {
+            let mut container = container.lock().await;
+            player.set_container_content(Some(&mut *container)).await;
+        }
+    }
+
+    /// Ticks the container and sends the players looking into it the changes
+    async fn tick_container(
+        &self,
+        position: WorldPosition,
+        state_id: u16,
+        kind: BlockEntityKind,
+        container_id: u64,
+        container: &Arc<Mutex<Box<dyn Container>>>,
+    ) {
+        let (tick, properties, bottles) = {
+            let mut container = container.lock().await;
+            let tick = container.tick();
+            let slots = container.all_slots_ref();
+            let bottles = [0, 1, 2].map(|slot| slots.get(slot).is_some_and(Option::is_some));
+            drop(slots);
+            (tick, container.properties(), bottles)
+        };
+        let properties_changed = {
+            let mut block_entities = self.block_entities.lock().await;
+            let Some(block_entity) = block_entities.get_mut(&position) else {
+                return;
+            };
+            let changed = block_entity.properties != properties;
+            block_entity.properties.clone_from(&properties);
+            changed
+        };
+
+        if tick.slots_changed {
+            self.send_container_content(container_id, container).await;
+        }
+        if properties_changed {
+            let viewers = self
+                .players()
+                .await
+                .filter(|player| player.open_container.load() == Some(container_id));
+            for player in viewers.iter() {
+                player.send_container_properties(&properties).await;
+            }
+        }
+
+        if kind != BlockEntityKind::BrewingStand {
+            return;
+        }
+        if tick.finished {
+            self.play_sound(
+                sound!("minecraft:block.brewing_stand.brew"),
+                SoundCategory::Blocks,
+                &center(position),
+            )
+            .await;
+        }
+        // the block shows which bottle slots have a potion
+        let Some(block) = get_block_by_state_id(state_id) else {
+            return;
+        };
+        let names = ["has_bottle_0", "has_bottle_1", "has_bottle_2"];
+        let properties: Vec<_> = names
+            .iter()
+            .zip(bottles)
+            .map(|(name, bottle)| (*name, if bottle { "true" } else { "false" }))
+            .collect();
+        if let Some(new_state) = block.changed_state(state_id, &properties) {
+            if new_state != state_id {
+                self.set_block_state(position, new_state).await;
+            }
+        }
+    }
+
+    /// Closes the container for the players looking into it
+    async fn close_container_for_viewers(&self, container_id: u64) {
+        for player in self
+            .players()
+            .await
+            .filter(|player| player.open_container.load() == Some(container_id))
+            .iter()
+        {
+            player.open_container.store(None);
+            player.close_container().await;
+        }
+    }
+
+    /// Removes the block entity, the items of its container drop where the block was
+    async fn remove_block_entity(self: &Arc<Self>, position: WorldPosition) {
+        let Some(block_entity) = self.block_entities.lock().await.remove(&position) else {
+            return;
+        };
+        let deactivate = match block_entity.kind {
+            BlockEntityKind::Beacon(state) if state.levels > 0 => {
+                Some(sound!("minecraft:block.beacon.deactivate"))
+            }
+            BlockEntityKind::Conduit(state) if state.active => {
+                Some(sound!("minecraft:block.conduit.deactivate"))
+            }
+            _ => None,
+        };
+        if let Some(sound) = deactivate {
+            self.play_sound(sound, SoundCategory::Blocks, &center(position))
+                .await;
+        }
+        if let BlockEntityKind::Piston(PistonState {
+            extended: Some(facing),
+        }) = block_entity.kind
+        {
+            self.remove_piston_head(position, facing).await;
+        }
+        let Some((container_id, container)) = block_entity.container else {
+            return;
+        };
+        self.close_container_for_viewers(container_id).await;
+        let stacks: Vec<_> = container
+            .lock()
+            .await
+            .all_slots()
+            .into_iter()
+            .filter_map(Option::take)
+            .collect();
+        let pos = position.0;
+        for stack in stacks {
+            let item = {
+                let mut rng = rand::thread_rng();
+                let position = Vector3::new(
+                    f64::from(pos.x) + rng.gen_range(0.25..0.75),
+                    f64::from(pos.y) + rng.gen_range(0.25..0.75),
+                    f64::from(pos.z) + rng.gen_range(0.25..0.75),
+                );
+                let velocity =
+                    Vector3::new(rng.gen_range(-0.05..0.05), 0.2, rng.gen_range(-0.05..0.05));
+                ItemEntity::new(self.clone(), stack, position, velocity, DROP_PICKUP_DELAY)
+            };
+            self.spawn_entity(Arc::new(item)).await;
+        }
+    }
+}
@@ -0,0 +1,224 @@
+//! Note blocks: they play their note when they get powered or are hit, players tune them by using
+//! them. The block below decides which instrument they play.
+
+use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+use pumpkin_macros::sound;
+use pumpkin_protocol::{
+    client::play::{CBlockEvent, SoundEvent},
+    SoundCategory,
+};
+use pumpkin_registry::{is_in, TagCategory};
+use pumpkin_world::block::block_registry::{get_block, get_block_by_state_id};
+
+use crate::{
+    entity::sound::sound_packet,
+    plugin::content::CONTENT,
+    world::{vibration::GameEvent, World},
+};
+
+use super::center;
+
+/// The highest note, notes go around to 0 when tuning further
+const HIGHEST_NOTE: u8 = 24;
+const VOLUME: f32 = 3.0;
+
+/// Wooden blocks make note blocks play the bass
+const WOODEN_TAGS: [&str; 6] = [
+    "minecraft:logs",
+    "minecraft:planks",
+    "minecraft:wooden_slabs",
+    "minecraft:wooden_stairs",
+    "minecraft:wooden_fences",
+    "minecraft:fence_gates",
+];
+/// Parts of the names of stone like blocks, which make note blocks play the bass drum
+const STONE_NAMES: [&str; 18] = [
+    "stone",
+    "deepslate",
+    "_ore",
+    "brick",
+    "obsidian",
+    "netherrack",
+    "basalt",
+    "terracotta",
+    "concrete",
+    "andesite",
+    "diorite",
+    "granite",
+    "tuff",
+    "calcite",
+    "prismarine",
+    "purpur",
+    "quartz",
+    "bedrock",
+];
+
+/// The instrument a note block plays on top of the block
+fn instrument_on(state_id: u16) -> &'static str {
+    let Some(block) = get_block_by_state_id(state_id) else {
+        return "harp";
+    };
+    let name = block.name.trim_start_matches("minecraft:");
+    match name {
+        "gold_block" => return "bell",
+        "clay" => return "flute",
+        "packed_ice" => return "chime",
+        "bone_block" => return "xylophone",
+        "iron_block" => return "iron_xylophone",
+        "soul_sand" => return "cow_bell",
+        "pumpkin" => return "didgeridoo",
+        "emerald_block" => return "bit",
+        "hay_block" => return "banjo",
+        "glowstone" => return "pling",
+        "sand" | "red_sand" | "gravel" | "suspicious_sand" | "suspicious_gravel" => return "snare",
+        "sea_lantern" | "beacon" => return "hat",
+        "note_block" | "bookshelf" | "chest" | "trapped_chest" | "crafting_table" | "jukebox"
+        | "barrel" => return "bass",
+        _ => {}
+    }
+    if name.ends_with("_wool") {
+        "guitar"
+    } else if name.ends_with("_concrete_powder") {
+        "snare"
+    } else if name.contains("glass") {
+        "hat"
+    } else if WOODEN_TAGS
+        .iter()
+        .any(|tag| is_in(TagCategory::Block, tag, &block.name))
+    {
+        "bass"
+    } else if STONE_NAMES.iter().any(|part| name.contains(part)) {
+        "basedrum"
+    } else {
+        "harp"
+    }
+}
+
+fn instrument_sound(instrument: &str) -> u16 {
+    match instrument {
+        "basedrum" => sound!("minecraft:block.note_block.basedrum"),
+        "snare" => sound!("minecraft:block.note_block.snare"),
+        "hat" => sound!("minecraft:block.note_block.hat"),
+        "bass" => sound!("minecraft:block.note_block.bass"),
+        "flute" => sound!("minecraft:block.note_block.flute"),
+        "bell" => sound!("minecraft:block.note_block.bell"),
+        "guitar" => sound!("minecraft:block.note_block.guitar"),
+        "chime" => sound!("minecraft:block.note_block.chime"),
+        "xylophone" => sound!("minecraft:block.note_block.xylophone"),
+        "iron_xylophone" => sound!("minecraft:block.note_block.iron_xylophone"),
+        "cow_bell" => sound!("minecraft:block.note_block.cow_bell"),
+        "didgeridoo" => sound!("minecraft:block.note_block.didgeridoo"),
+        "bit" => sound!("minecraft:block.note_block.bit"),
+        "banjo" => sound!("minecraft:block.note_block.banjo"),
+        "pling" => sound!("minecraft:block.note_block.pling"),
+        _ => sound!("minecraft:block.note_block.harp"),
+    }
+}
+
+/// How much higher than normal the note plays, an octave down to an octave up
+fn note_pitch(note: u8) -> f32 {
+    2f32.powf((f32::from(note) - 12.0) / 12.0)
+}
+
+fn note_of(properties: &[(&str, &str)]) -> u8 {
+    properties
+        .iter()
+        .find(|(name, _)| *name == "note")
+        .and_then(|(_, note)| note.parse().ok())
+        .unwrap_or(0)
+}
+
+impl World {
+    /// Keeps the instrument of the note block up to date and plays it when it gets powered
+    pub(super) async fn tick_note_block(&self, position: WorldPosition, state_id: u16) {
+        if CONTENT.is_custom_block(state_id) {
+            return;
+        }
+        let Some(block) = get_block_by_state_id(state_id) else {
+            return;
+        };
+        let properties = block.properties_of_state(state_id).unwrap_or_default();
+        let pos = position.0;
+        let below = WorldPosition(Vector3::new(pos.x, pos.y - 1, pos.z));
+        let instrument = self
+            .get_block_state_id(below)
+            .await
+            .map_or("harp", instrument_on);
+        let powered = self.is_receiving_power(position).await;
+        let was_powered = properties.contains(&("powered", "true"));
+        if properties.contains(&("instrument", instrument)) && powered == was_powered {
+            return;
+        }
+        let powered_name = if powered { "true" } else { "false" };
+        if let Some(new_state) = block.changed_state(
+            state_id,
+            &[("instrument", instrument), ("powered", powered_name)],
+        ) {
+            self.set_block_state(position, new_state).await;
+        }
+        if powered && !was_powered {
+            self.play_note_block(position).await;
+        }
+    }
+
+    /// Plays the note of the note block, if the block above doesn't muffle it
+    pub async fn play_note_block(&self, position: WorldPosition) {
+        let Ok(state_id) = self.get_block_state_id(position).await else {
+            return;
+        };
+        let Some(block) =
+            get_block_by_state_id(state_id).filter(|block| block.name == "minecraft:note_block")
+        else {
+            return;
+        };
+        if CONTENT.is_custom_block(state_id) {
+            return;
+        }
+        let pos = position.0;
+        let above = WorldPosition(Vector3::new(pos.x, pos.y + 1, pos.z));
+        if !self
+            .get_block_state(above)
+            .await
+            .is_ok_and(|state| state.air)
+        {
+            return;
+        }
+        let properties = block.properties_of_state(state_id).unwrap_or_default();
+        let instrument = properties
+            .iter()
+            .find(|(name, _)| *name == "instrument")
+            .map_or("harp", |(_, instrument)| *instrument);
+        let sound_position = center(position);
+        // the client shows the note particle
+        self.broadcast_packet_all(&CBlockEvent::new(
+            &position,
+            0,
+            0,
+            i32::from(block.id).into(),
+        ))
+        .await;
+        self.broadcast_packet_all(&sound_packet(
+            SoundEvent::Registry(instrument_sound(instrument)),
+            SoundCategory::Records,
+            &sound_position,
+            VOLUME,
+            note_pitch(note_of(&properties)),
+        ))
+        .await;
+        self.emit_game_event(GameEvent::NoteBlockPlay, sound_position)
+            .await;
+    }
+
+    /// Tunes the note block a note higher and plays it
+    pub async fn tune_note_block(&self, position: WorldPosition, state_id: u16) {
+        let Some(block) = get_block("minecraft:note_block") else {
+            return;
+        };
+        let properties = block.properties_of_state(state_id).unwrap_or_default();
+        let note = (note_of(&properties) + 1) % (HIGHEST_NOTE + 1);
+        if let Some(new_state) = block.changed_state(state_id, &[("note", &note.to_string())]) {
+            self.set_block_state(position, new_state).await;
+        }
+        self.play_note_block(position).await;
+    }
+}
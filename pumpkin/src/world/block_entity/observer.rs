@@ -0,0 +1,72 @@
+//! Observers: when the block they face changes they give a short redstone pulse out of their back.
+
+use pumpkin_core::math::position::WorldPosition;
+use pumpkin_world::block::{block_registry::get_block_by_state_id, BlockFace};
+
+use crate::world::World;
+
+/// Ticks between seeing a change and powering
+const PULSE_DELAY: u8 = 2;
+/// Ticks the observer stays powered
+const PULSE_LENGTH: u8 = 2;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ObserverState {
+    /// The state of the block it faces when it last looked, `None` until it looked once
+    pub watched: Option<u16>,
+    /// Ticks until it powers
+    pub delay: u8,
+    /// Ticks until it stops powering
+    pub pulse: u8,
+}
+
+impl World {
+    /// Compares the block the observer faces with what it saw last tick and pulses on changes
+    pub(super) async fn tick_observer(
+        &self,
+        position: WorldPosition,
+        state_id: u16,
+        state: ObserverState,
+    ) -> ObserverState {
+        let Some(block) = get_block_by_state_id(state_id) else {
+            return state;
+        };
+        let facing = block
+            .properties_of_state(state_id)
+            .unwrap_or_default()
+            .iter()
+            .find(|(name, _)| *name == "facing")
+            .and_then(|(_, value)| BlockFace::from_name(value))
+            .unwrap_or(BlockFace::North);
+        let front = WorldPosition(position.0.add(&facing.to_offset()));
+        let watched = self.get_block_state_id(front).await.ok();
+        let changed = state.watched.is_some() && watched != state.watched;
+
+        let (mut delay, mut pulse) = (state.delay, state.pulse);
+        let mut powered = None;
+        if delay > 0 {
+            delay -= 1;
+            if delay == 0 {
+                powered = Some("true");
+                pulse = PULSE_LENGTH;
+            }
+        } else if pulse > 0 {
+            pulse -= 1;
+            if pulse == 0 {
+                powered = Some("false");
+            }
+        } else if changed {
+            delay = PULSE_DELAY;
+        }
+        if let Some(new_state) =
+            powered.and_then(|powered| block.changed_state(state_id, &[("powered", powered)]))
+        {
+            self.set_block_state(position, new_state).await;
+        }
+        ObserverState {
+            watched,
+            delay,
+            pulse,
+        }
+    }
+}
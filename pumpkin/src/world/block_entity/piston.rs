@@ -0,0 +1,864 @@
+//! Pistons: once powered they push up to 12 blocks in front of them, sticky pistons pull the block
+//! in front of their head back when they retract. Slime and honey blocks drag the blocks stuck to
+//! them along. Moved blocks are moving pistons for a few ticks before they land where they moved to.
+
+use std::{collections::HashMap, future::Future, pin::Pin, sync::Arc};
+
+use pumpkin_core::math::{boundingbox::BoundingBox, position::WorldPosition, vector3::Vector3};
+use pumpkin_macros::sound;
+use pumpkin_protocol::{
+    client::play::{CBlockEvent, CWorldEvent},
+    SoundCategory,
+};
+use pumpkin_world::{
+    block::{
+        block_registry::{
+            get_block, get_block_and_state_by_state_id, get_block_by_state_id,
+            get_state_by_state_id,
+        },
+        BlockFace,
+    },
+    item::ItemStack,
+    WORLD_LOWEST_Y, WORLD_MAX_Y,
+};
+use rand::Rng;
+
+use crate::{
+    entity::item::ItemEntity,
+    world::{redstone::FACES, vibration::GameEvent, World},
+};
+
+use super::{center, BlockEntity, BlockEntityKind, DROP_PICKUP_DELAY};
+
+/// How many blocks a piston can move at once
+pub const PUSH_LIMIT: usize = 12;
+/// Moving blocks get half a block further each tick
+const PROGRESS_STEPS: u8 = 2;
+/// The block events of pistons, the client moves the blocks itself to animate them
+const EXTEND_EVENT: u8 = 0;
+const RETRACT_EVENT: u8 = 1;
+/// A sticky piston retracting before the block it pushed landed, it leaves the block behind
+const DROP_EVENT: u8 = 2;
+/// The world event of a block breaking, with its particles and sound
+const BREAK_EVENT: i32 = 2001;
+
+/// Blocks which pistons can't move, besides the unbreakable ones and those with a block entity
+const IMMOVABLE: [&str; 5] = [
+    "minecraft:obsidian",
+    "minecraft:crying_obsidian",
+    "minecraft:respawn_anchor",
+    "minecraft:reinforced_deepslate",
+    "minecraft:piston_head",
+];
+/// Solid blocks which break when pushed, blocks without a collision box always do
+const DESTROYED: [&str; 14] = [
+    "minecraft:pumpkin",
+    "minecraft:carved_pumpkin",
+    "minecraft:jack_o_lantern",
+    "minecraft:melon",
+    "minecraft:dragon_egg",
+    "minecraft:cocoa",
+    "minecraft:cake",
+    "minecraft:flower_pot",
+    "minecraft:lantern",
+    "minecraft:soul_lantern",
+    "minecraft:chorus_plant",
+    "minecraft:chorus_flower",
+    "minecraft:lily_pad",
+    "minecraft:turtle_egg",
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PistonState {
+    /// Where the head was pushed out to, `None` while the piston is retracted
+    pub extended: Option<BlockFace>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MovingPistonState {
+    /// The block state which lands once the block stopped moving
+    pub moved: u16,
+    /// The way the piston which moves the block faces
+    pub facing: BlockFace,
+    /// Whether the block moves away from the piston
+    pub extending: bool,
+    /// Whether it is the piston itself or its head, which extend instead of being pushed
+    pub source: bool,
+    /// How far the block got, in [`PROGRESS_STEPS`]
+    pub progress: u8,
+}
+
+impl Default for MovingPistonState {
+    fn default() -> Self {
+        Self {
+            moved: 0,
+            facing: BlockFace::North,
+            extending: true,
+            source: false,
+            progress: 0,
+        }
+    }
+}
+
+impl MovingPistonState {
+    /// Where the block is relative to where it lands, how clients draw it
+    #[must_use]
+    pub fn offset(self) -> Vector3<f64> {
+        let progress = f64::from(self.progress) / f64::from(PROGRESS_STEPS);
+        let distance = if self.extending {
+            progress - 1.0
+        } else {
+            1.0 - progress
+        };
+        let step = self.facing.to_offset();
+        Vector3::new(
+            f64::from(step.x) * distance,
+            f64::from(step.y) * distance,
+            f64::from(step.z) * distance,
+        )
+    }
+}
+
+/// How a block reacts to being pushed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PushReaction {
+    Normal,
+    /// It breaks and drops its item
+    Destroy,
+    /// It stops the piston
+    Block,
+    /// It can be pushed but not pulled, like glazed terracotta
+    PushOnly,
+}
+
+fn push_reaction(state_id: u16) -> PushReaction {
+    let Some((block, state)) = get_block_and_state_by_state_id(state_id) else {
+        return PushReaction::Block;
+    };
+    let name = block.name.as_str();
+    if state.air {
+        PushReaction::Normal
+    } else if IMMOVABLE.contains(&name) || block.hardness < 0.0 {
+        PushReaction::Block
+    } else if name.ends_with("_glazed_terracotta") {
+        PushReaction::PushOnly
+    } else if DESTROYED.contains(&name)
+        || name.ends_with("_bed")
+        || name.ends_with("_door")
+        || name.ends_with("candle_cake")
+        || name.starts_with("minecraft:potted_")
+        || state.collision_shapes.is_empty()
+    {
+        PushReaction::Destroy
+    } else {
+        PushReaction::Normal
+    }
+}
+
+fn block_name(state_id: u16) -> &'static str {
+    get_block_by_state_id(state_id).map_or("", |block| block.name.as_str())
+}
+
+fn is_air(state_id: u16) -> bool {
+    get_state_by_state_id(state_id).is_some_and(|state| state.air)
+}
+
+fn is_piston(state_id: u16) -> bool {
+    matches!(
+        block_name(state_id),
+        "minecraft:piston" | "minecraft:sticky_piston"
+    )
+}
+
+fn is_sticky(state_id: u16) -> bool {
+    matches!(
+        block_name(state_id),
+        "minecraft:slime_block" | "minecraft:honey_block"
+    )
+}
+
+/// Whether a block moving drags the other along, slime and honey don't stick to each other
+fn can_stick(state_id: u16, other: u16) -> bool {
+    let (name, other_name) = (block_name(state_id), block_name(other));
+    let slime_and_honey = (name == "minecraft:slime_block"
+        && other_name == "minecraft:honey_block")
+        || (name == "minecraft:honey_block" && other_name == "minecraft:slime_block");
+    !slime_and_honey && (is_sticky(state_id) || is_sticky(other))
+}
+
+/// Whether a piston facing `piston_facing` can move the block into the direction
+fn is_pushable(
+    state_id: u16,
+    position: WorldPosition,
+    direction: BlockFace,
+    allow_destroy: bool,
+    piston_facing: BlockFace,
+) -> bool {
+    let y = position.0.y;
+    let (lowest, highest) = (i32::from(WORLD_LOWEST_Y), i32::from(WORLD_MAX_Y) - 1);
+    if y < lowest
+        || y > highest
+        || (direction == BlockFace::Bottom && y == lowest)
+        || (direction == BlockFace::Top && y == highest)
+    {
+        return false;
+    }
+    let Some((block, state)) = get_block_and_state_by_state_id(state_id) else {
+        return false;
+    };
+    if is_piston(state_id) {
+        // extended pistons are stuck to their head
+        return block
+            .properties_of_state(state_id)
+            .is_some_and(|properties| properties.contains(&("extended", "false")));
+    }
+    let reaction_allows = match push_reaction(state_id) {
+        PushReaction::Normal => true,
+        PushReaction::Destroy => allow_destroy,
+        PushReaction::Block => false,
+        PushReaction::PushOnly => direction == piston_facing,
+    };
+    reaction_allows && state.block_entity_type.is_none()
+}
+
+fn relative(position: WorldPosition, face: BlockFace, distance: i32) -> WorldPosition {
+    let offset = face.to_offset();
+    WorldPosition(position.0.add(&Vector3::new(
+        offset.x * distance,
+        offset.y * distance,
+        offset.z * distance,
+    )))
+}
+
+fn facing_of(state_id: u16) -> BlockFace {
+    get_block_by_state_id(state_id)
+        .and_then(|block| block.properties_of_state(state_id))
+        .and_then(|properties| {
+            properties
+                .iter()
+                .find(|(name, _)| *name == "facing")
+                .and_then(|(_, value)| BlockFace::from_name(value))
+        })
+        .unwrap_or(BlockFace::North)
+}
+
+fn piston_type(sticky: bool) -> &'static str {
+    if sticky {
+        "sticky"
+    } else {
+        "normal"
+    }
+}
+
+/// The moving piston block, which stands in for moving blocks until they land
+fn moving_piston_state(facing: BlockFace, sticky: bool) -> Option<u16> {
+    get_block("minecraft:moving_piston")?
+        .state_id_with_properties(&[("facing", facing.name()), ("type", piston_type(sticky))])
+}
+
+/// The blocks a piston moves, found like vanilla's piston structure resolver
+struct PistonStructure<'a> {
+    world: &'a World,
+    piston: WorldPosition,
+    facing: BlockFace,
+    extending: bool,
+    /// The way the blocks move, away from the piston when it extends
+    push_direction: BlockFace,
+    /// The blocks to move, the ones furthest in the push direction first
+    to_push: Vec<WorldPosition>,
+    to_destroy: Vec<WorldPosition>,
+}
+
+impl<'a> PistonStructure<'a> {
+    fn new(world: &'a World, piston: WorldPosition, facing: BlockFace, extending: bool) -> Self {
+        Self {
+            world,
+            piston,
+            facing,
+            extending,
+            push_direction: if extending { facing } else { facing.opposite() },
+            to_push: Vec::new(),
+            to_destroy: Vec::new(),
+        }
+    }
+
+    /// The block state at the position, `None` in unloaded chunks which can't be pushed into
+    async fn state(&self, position: WorldPosition) -> Option<u16> {
+        // the head of a retracting piston is gone before the blocks are pulled
+        if !self.extending && position == relative(self.piston, self.facing, 1) {
+            return Some(0);
+        }
+        self.world.get_block_state_id(position).await.ok()
+    }
+
+    /// Finds the blocks to move, `false` if something immovable is in the way or there are
+    /// more than [`PUSH_LIMIT`]
+    async fn resolve(&mut self) -> bool {
+        let distance = if self.extending { 1 } else { 2 };
+        let start = relative(self.piston, self.facing, distance);
+        let Some(state_id) = self.state(start).await else {
+            return false;
+        };
+        if !is_pushable(state_id, start, self.push_direction, false, self.facing) {
+            if self.extending && push_reaction(state_id) == PushReaction::Destroy {
+                self.to_destroy.push(start);
+                return true;
+            }
+            return false;
+        }
+        if !self.add_block_line(start, self.push_direction).await {
+            return false;
+        }
+        let mut i = 0;
+        while i < self.to_push.len() {
+            let position = self.to_push[i];
+            if self.is_sticky_at(position).await && !self.add_branching_blocks(position).await {
+                return false;
+            }
+            i += 1;
+        }
+        true
+    }
+
+    async fn is_sticky_at(&self, position: WorldPosition) -> bool {
+        self.state(position).await.is_some_and(is_sticky)
+    }
+
+    /// Adds the block, the blocks stuck behind it and the blocks it pushes in front of it
+    async fn add_block_line(&mut self, origin: WorldPosition, direction: BlockFace) -> bool {
+        let Some(mut state_id) = self.state(origin).await else {
+            return false;
+        };
+        if is_air(state_id)
+            || !is_pushable(state_id, origin, self.push_direction, false, direction)
+            || origin == self.piston
+            || self.to_push.contains(&origin)
+        {
+            return true;
+        }
+        let back = self.push_direction.opposite();
+        let mut length = 1;
+        if length + self.to_push.len() > PUSH_LIMIT {
+            return false;
+        }
+        // sticky blocks drag the blocks behind them along
+        while is_sticky(state_id) {
+            let behind = relative(origin, back, length as i32);
+            let Some(behind_state) = self.state(behind).await else {
+                break;
+            };
+            if is_air(behind_state)
+                || !can_stick(state_id, behind_state)
+                || !is_pushable(behind_state, behind, self.push_direction, false, back)
+                || behind == self.piston
+            {
+                break;
+            }
+            state_id = behind_state;
+            length += 1;
+            if length + self.to_push.len() > PUSH_LIMIT {
+                return false;
+            }
+        }
+        let mut added = 0;
+        for distance in (0..length).rev() {
+            self.to_push.push(relative(origin, back, distance as i32));
+            added += 1;
+        }
+
+        let mut distance = 1;
+        loop {
+            let ahead = relative(origin, self.push_direction, distance);
+            if let Some(index) = self.to_push.iter().position(|position| *position == ahead) {
+                self.reorder_at_collision(added, index);
+                let mut i = 0;
+                while i <= index + added {
+                    let position = self.to_push[i];
+                    if self.is_sticky_at(position).await
+                        && !self.add_branching_blocks(position).await
+                    {
+                        return false;
+                    }
+                    i += 1;
+                }
+                return true;
+            }
+            let Some(ahead_state) = self.state(ahead).await else {
+                return false;
+            };
+            if is_air(ahead_state) {
+                return true;
+            }
+            if !is_pushable(
+                ahead_state,
+                ahead,
+                self.push_direction,
+                true,
+                self.push_direction,
+            ) || ahead == self.piston
+            {
+                return false;
+            }
+            if push_reaction(ahead_state) == PushReaction::Destroy {
+                self.to_destroy.push(ahead);
+                return true;
+            }
+            if self.to_push.len() >= PUSH_LIMIT {
+                return false;
+            }
+            self.to_push.push(ahead);
+            added += 1;
+            distance += 1;
+        }
+    }
+
+    /// Moves the blocks just added in front of the line they ran into, so blocks are still moved
+    /// front to back
+    fn reorder_at_collision(&mut self, added: usize, index: usize) {
+        let split = self.to_push.len() - added;
+        let mut reordered = self.to_push[..index].to_vec();
+        reordered.extend_from_slice(&self.to_push[split..]);
+        reordered.extend_from_slice(&self.to_push[index..split]);
+        self.to_push = reordered;
+    }
+
+    /// Adds the blocks stuck to the sides of a sticky block
+    fn add_branching_blocks(
+        &mut self,
+        position: WorldPosition,
+    ) -> Pin<Box<dyn Future<Output = bool> + Send + '_>> {
+        Box::pin(async move {
+            let Some(state_id) = self.state(position).await else {
+                return false;
+            };
+            for face in FACES {
+                if face == self.push_direction || face == self.push_direction.opposite() {
+                    continue;
+                }
+                let neighbor = relative(position, face, 1);
+                let Some(neighbor_state) = self.state(neighbor).await else {
+                    continue;
+                };
+                if can_stick(neighbor_state, state_id) && !self.add_block_line(neighbor, face).await
+                {
+                    return false;
+                }
+            }
+            true
+        })
+    }
+}
+
+impl World {
+    /// Extends the piston once it gets powered and retracts it once it doesn't anymore
+    pub(super) async fn tick_piston(
+        self: &Arc<Self>,
+        position: WorldPosition,
+        state_id: u16,
+        state: PistonState,
+    ) -> PistonState {
+        let Some(block) = get_block_by_state_id(state_id) else {
+            return state;
+        };
+        let facing = facing_of(state_id);
+        let sticky = block.name == "minecraft:sticky_piston";
+        let extended = block
+            .properties_of_state(state_id)
+            .is_some_and(|properties| properties.contains(&("extended", "true")));
+        if extended && !self.has_piston_head(position, facing).await {
+            // the head was broken, the piston breaks with it
+            self.break_block(position, None).await;
+            return PistonState::default();
+        }
+
+        let above = WorldPosition(position.0.add(&Vector3::new(0, 1, 0)));
+        // pistons aren't powered through their front
+        let powered = self.is_receiving_power_except(position, facing).await
+            || self.is_receiving_power(above).await;
+        if powered && !extended && self.extend_piston(position, facing, sticky).await {
+            return PistonState {
+                extended: Some(facing),
+            };
+        }
+        if !powered && extended {
+            self.retract_piston(position, facing, sticky).await;
+            return PistonState::default();
+        }
+        PistonState {
+            extended: extended.then_some(facing),
+        }
+    }
+
+    async fn has_piston_head(&self, position: WorldPosition, facing: BlockFace) -> bool {
+        let head = relative(position, facing, 1);
+        let Ok(state_id) = self.get_block_state_id(head).await else {
+            return true;
+        };
+        match block_name(state_id) {
+            "minecraft:piston_head" => facing_of(state_id) == facing,
+            // the head is still moving out
+            "minecraft:moving_piston" => true,
+            _ => false,
+        }
+    }
+
+    /// Removes the head of a piston which was broken
+    pub(super) async fn remove_piston_head(&self, position: WorldPosition, facing: BlockFace) {
+        let head = relative(position, facing, 1);
+        if let Ok(state_id) = self.get_block_state_id(head).await {
+            if block_name(state_id) == "minecraft:piston_head" && facing_of(state_id) == facing {
+                self.set_block_state(head, 0).await;
+            }
+        }
+    }
+
+    async fn broadcast_piston_event(
+        &self,
+        position: WorldPosition,
+        action: u8,
+        facing: BlockFace,
+        sticky: bool,
+    ) {
+        let name = if sticky {
+            "minecraft:sticky_piston"
+        } else {
+            "minecraft:piston"
+        };
+        let Some(block) = get_block(name) else {
+            return;
+        };
+        self.broadcast_packet_all(&CBlockEvent::new(
+            &position,
+            action,
+            facing as u8,
+            i32::from(block.id).into(),
+        ))
+        .await;
+    }
+
+    /// Pushes the blocks in front of the piston and its head out. `false` if they can't be moved
+    async fn extend_piston(
+        self: &Arc<Self>,
+        position: WorldPosition,
+        facing: BlockFace,
+        sticky: bool,
+    ) -> bool {
+        let Ok(piston_state) = self.get_block_state_id(position).await else {
+            return false;
+        };
+        let (Some(piston), Some(head)) = (
+            get_block_by_state_id(piston_state)
+                .and_then(|block| block.changed_state(piston_state, &[("extended", "true")])),
+            get_block("minecraft:piston_head").and_then(|head| {
+                head.state_id_with_properties(&[
+                    ("facing", facing.name()),
+                    ("type", piston_type(sticky)),
+                    ("short", "false"),
+                ])
+            }),
+        ) else {
+            return false;
+        };
+        let mut structure = PistonStructure::new(self, position, facing, true);
+        if !structure.resolve().await {
+            return false;
+        }
+        let (to_push, to_destroy) = (structure.to_push, structure.to_destroy);
+
+        self.broadcast_piston_event(position, EXTEND_EVENT, facing, sticky)
+            .await;
+        let mut changes = self.move_blocks(facing, true, &to_push, &to_destroy).await;
+        let moving_head = MovingPistonState {
+            moved: head,
+            facing,
+            extending: true,
+            source: true,
+            progress: 0,
+        };
+        self.start_moving(
+            &mut changes,
+            relative(position, facing, 1),
+            moving_head,
+            sticky,
+        )
+        .await;
+        changes.insert(position, piston);
+        self.set_block_states(&changes.into_iter().collect::<Vec<_>>())
+            .await;
+        self.play_sound(
+            sound!("minecraft:block.piston.extend"),
+            SoundCategory::Blocks,
+            &center(position),
+        )
+        .await;
+        self.emit_game_event(GameEvent::BlockActivate, center(position))
+            .await;
+        true
+    }
+
+    /// Pulls the head back in, sticky pistons pull the block in front of it along
+    async fn retract_piston(
+        self: &Arc<Self>,
+        position: WorldPosition,
+        facing: BlockFace,
+        sticky: bool,
+    ) {
+        let Ok(piston_state) = self.get_block_state_id(position).await else {
+            return;
+        };
+        let Some(piston) = get_block_by_state_id(piston_state)
+            .and_then(|block| block.changed_state(piston_state, &[("extended", "false")]))
+        else {
+            return;
+        };
+        let head = relative(position, facing, 1);
+        // a head which is still moving out lands right away
+        self.land_moving_piston(head).await;
+
+        let mut action = RETRACT_EVENT;
+        let mut pulled = None;
+        if sticky {
+            let front = relative(position, facing, 2);
+            let pushed_away = matches!(
+                self.moving_piston_at(front).await,
+                Some(moving) if moving.extending && moving.facing == facing && !moving.source
+            );
+            if pushed_away {
+                // the block the piston just pushed is left behind
+                self.land_moving_piston(front).await;
+                action = DROP_EVENT;
+            } else if let Ok(front_state) = self.get_block_state_id(front).await {
+                if !is_air(front_state)
+                    && is_pushable(front_state, front, facing.opposite(), false, facing)
+                    && (push_reaction(front_state) == PushReaction::Normal
+                        || is_piston(front_state))
+                {
+                    let mut structure = PistonStructure::new(self, position, facing, false);
+                    if structure.resolve().await {
+                        pulled = Some((structure.to_push, structure.to_destroy));
+                    }
+                }
+            }
+        }
+
+        self.broadcast_piston_event(position, action, facing, sticky)
+            .await;
+        let mut changes = match pulled {
+            Some((to_push, to_destroy)) => {
+                self.move_blocks(facing, false, &to_push, &to_destroy).await
+            }
+            None => HashMap::new(),
+        };
+        // the head is gone unless a pulled block moves in
+        changes.entry(head).or_insert(0);
+        let moving_piston = MovingPistonState {
+            moved: piston,
+            facing,
+            extending: false,
+            source: true,
+            progress: 0,
+        };
+        self.start_moving(&mut changes, position, moving_piston, sticky)
+            .await;
+        self.set_block_states(&changes.into_iter().collect::<Vec<_>>())
+            .await;
+        self.play_sound(
+            sound!("minecraft:block.piston.contract"),
+            SoundCategory::Blocks,
+            &center(position),
+        )
+        .await;
+        self.emit_game_event(GameEvent::BlockDeactivate, center(position))
+            .await;
+    }
+
+    /// Breaks the destroyed blocks and turns the pushed ones into moving pistons one block
+    /// further. Returns the block changes, set all at once by the piston
+    async fn move_blocks(
+        self: &Arc<Self>,
+        facing: BlockFace,
+        extending: bool,
+        to_push: &[WorldPosition],
+        to_destroy: &[WorldPosition],
+    ) -> HashMap<WorldPosition, u16> {
+        let direction = if extending { facing } else { facing.opposite() };
+        let mut changes = HashMap::new();
+        for position in to_destroy {
+            if let Ok(state_id) = self.get_block_state_id(*position).await {
+                self.destroy_pushed_block(*position, state_id).await;
+            }
+            changes.insert(*position, 0);
+        }
+        let mut moved = Vec::with_capacity(to_push.len());
+        for position in to_push {
+            if let Ok(state_id) = self.get_block_state_id(*position).await {
+                moved.push((*position, state_id));
+            }
+            // the block leaves air behind, unless another block moves in
+            changes.entry(*position).or_insert(0);
+        }
+        for (position, state_id) in moved {
+            let moving = MovingPistonState {
+                moved: state_id,
+                facing,
+                extending,
+                source: false,
+                progress: 0,
+            };
+            self.start_moving(
+                &mut changes,
+                relative(position, direction, 1),
+                moving,
+                false,
+            )
+            .await;
+        }
+        changes
+    }
+
+    /// Puts a moving piston carrying the block at the position, once the changes are set
+    async fn start_moving(
+        &self,
+        changes: &mut HashMap<WorldPosition, u16>,
+        position: WorldPosition,
+        moving: MovingPistonState,
+        sticky: bool,
+    ) {
+        let Some(state_id) = moving_piston_state(moving.facing, sticky) else {
+            return;
+        };
+        changes.insert(position, state_id);
+        // setting the block keeps a block entity of the same kind
+        self.block_entities.lock().await.insert(
+            position,
+            BlockEntity::new(BlockEntityKind::MovingPiston(moving)),
+        );
+    }
+
+    /// Breaks a block in the way of a piston, it drops its item
+    async fn destroy_pushed_block(self: &Arc<Self>, position: WorldPosition, state_id: u16) {
+        self.broadcast_packet_all(&CWorldEvent::new(
+            BREAK_EVENT,
+            &position,
+            state_id.into(),
+            false,
+        ))
+        .await;
+        // without loot tables blocks drop themselves, fluids drop nothing
+        let Some(block) = get_block_by_state_id(state_id).filter(|block| block.item_id != 0) else {
+            return;
+        };
+        let item = {
+            let mut rng = rand::thread_rng();
+            let velocity =
+                Vector3::new(rng.gen_range(-0.05..0.05), 0.2, rng.gen_range(-0.05..0.05));
+            ItemEntity::new(
+                self.clone(),
+                ItemStack::new(1, block.item_id),
+                center(position),
+                velocity,
+                DROP_PICKUP_DELAY,
+            )
+        };
+        self.spawn_entity(Arc::new(item)).await;
+    }
+
+    async fn moving_piston_at(&self, position: WorldPosition) -> Option<MovingPistonState> {
+        match self.block_entities.lock().await.get(&position)?.kind {
+            BlockEntityKind::MovingPiston(moving) => Some(moving),
+            _ => None,
+        }
+    }
+
+    /// Puts the block the moving piston carries in its place right away
+    async fn land_moving_piston(&self, position: WorldPosition) {
+        let moving = {
+            let mut block_entities = self.block_entities.lock().await;
+            match block_entities
+                .get(&position)
+                .map(|block_entity| block_entity.kind)
+            {
+                Some(BlockEntityKind::MovingPiston(moving)) => {
+                    block_entities.remove(&position);
+                    moving
+                }
+                _ => return,
+            }
+        };
+        let still_moving = self
+            .get_block_state_id(position)
+            .await
+            .is_ok_and(|state_id| block_name(state_id) == "minecraft:moving_piston");
+        if still_moving {
+            self.set_block_state(position, moving.moved).await;
+        }
+    }
+
+    /// Moves the block half a block further, it lands the tick after it arrived
+    pub(super) async fn tick_moving_piston(
+        &self,
+        position: WorldPosition,
+        state: MovingPistonState,
+    ) -> MovingPistonState {
+        if state.progress >= PROGRESS_STEPS {
+            self.land_moving_piston(position).await;
+            return state;
+        }
+        let moved = MovingPistonState {
+            progress: state.progress + 1,
+            ..state
+        };
+        self.push_entities(position, state, moved).await;
+        moved
+    }
+
+    /// Moves the entities in the way of the moving block along with it. Players aren't moved,
+    /// their client moves them with the blocks like in vanilla
+    async fn push_entities(
+        &self,
+        position: WorldPosition,
+        before: MovingPistonState,
+        after: MovingPistonState,
+    ) {
+        let Some(state) = get_state_by_state_id(after.moved) else {
+            return;
+        };
+        let pos = position.0;
+        let offset = after.offset();
+        let boxes: Vec<_> = state
+            .collision_boxes()
+            .map(|area| {
+                area.offset(
+                    f64::from(pos.x) + offset.x,
+                    f64::from(pos.y) + offset.y,
+                    f64::from(pos.z) + offset.z,
+                )
+            })
+            .collect();
+        if boxes.is_empty() {
+            return;
+        }
+        let movement = offset.sub(&before.offset());
+        let entities: Vec<_> = self
+            .entities
+            .lock()
+            .await
+            .values()
+            .filter(|entity| {
+                let entity = entity.get_entity();
+                let pos = entity.pos.load();
+                let area = BoundingBox::new_from_pos(
+                    pos.x,
+                    pos.y,
+                    pos.z,
+                    &entity.bounding_box_size.load(),
+                );
+                boxes.iter().any(|block| block.intersects(&area))
+            })
+            .cloned()
+            .collect();
+        for entity in entities {
+            entity.get_entity().move_colliding(movement).await;
+        }
+    }
+}
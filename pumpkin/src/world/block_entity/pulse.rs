@@ -0,0 +1,184 @@
+//! Target blocks and lightning rods: they give a short redstone pulse when a projectile hits the
+//! target or lightning strikes the rod. Targets give a stronger signal the closer the hit was to
+//! the middle of the face.
+
+use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+use pumpkin_entity::entity_type::EntityType;
+use pumpkin_macros::sound;
+use pumpkin_protocol::{client::play::CSpawnEntity, SoundCategory};
+use pumpkin_world::block::{block_registry::get_block_by_state_id, BlockFace};
+use uuid::Uuid;
+
+use crate::{
+    entity::new_entity_id,
+    world::{vibration::GameEvent, World},
+};
+
+use super::{center, BlockEntityKind};
+
+/// Ticks a target stays powered after an arrow hit it
+const ARROW_PULSE: u8 = 20;
+/// Ticks a target stays powered after another projectile hit it
+const PROJECTILE_PULSE: u8 = 8;
+/// Ticks a lightning rod stays powered after it was struck
+const LIGHTNING_PULSE: u8 = 8;
+/// How far lightning rods attract lightning
+const LIGHTNING_ROD_RANGE: f64 = 128.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PulseState {
+    /// Ticks until the pulse ends
+    pub ticks: u8,
+}
+
+/// The signal of a hit on the face of a target, 15 in the middle down to 1 at the edges
+fn target_power(hit: Vector3<f64>, face: BlockFace) -> u8 {
+    let offset = |value: f64| (value - value.floor() - 0.5).abs();
+    let (x, y, z) = (offset(hit.x), offset(hit.y), offset(hit.z));
+    let distance = match face {
+        BlockFace::Down | BlockFace::Up => x.max(z),
+        BlockFace::North | BlockFace::South => x.max(y),
+        BlockFace::West | BlockFace::East => y.max(z),
+    };
+    let accuracy = ((0.5 - distance) / 0.5).clamp(0.0, 1.0);
+    ((15.0 * accuracy).ceil() as u8).max(1)
+}
+
+impl World {
+    /// Ends the pulse of the target or lightning rod once its time is up
+    pub(super) async fn tick_pulse(
+        &self,
+        position: WorldPosition,
+        state_id: u16,
+        state: PulseState,
+    ) -> PulseState {
+        if state.ticks == 0 {
+            return state;
+        }
+        let ticks = state.ticks - 1;
+        if ticks == 0 {
+            let Some(block) = get_block_by_state_id(state_id) else {
+                return PulseState { ticks };
+            };
+            let off = if block.name == "minecraft:target" {
+                ("power", "0")
+            } else {
+                ("powered", "false")
+            };
+            if let Some(new_state) = block.changed_state(state_id, &[off]) {
+                self.set_block_state(position, new_state).await;
+            }
+        }
+        PulseState { ticks }
+    }
+
+    /// Powers the target if the projectile hit one, with a longer pulse for arrows. Returns
+    /// whether it was a target
+    pub async fn hit_target(
+        &self,
+        position: WorldPosition,
+        hit: Vector3<f64>,
+        face: BlockFace,
+        arrow: bool,
+    ) -> bool {
+        let Ok(state_id) = self.get_block_state_id(position).await else {
+            return false;
+        };
+        let Some(block) =
+            get_block_by_state_id(state_id).filter(|block| block.name == "minecraft:target")
+        else {
+            return false;
+        };
+        let power = target_power(hit, face).to_string();
+        if let Some(new_state) = block.changed_state(state_id, &[("power", power.as_str())]) {
+            self.set_block_state(position, new_state).await;
+        }
+        let ticks = if arrow { ARROW_PULSE } else { PROJECTILE_PULSE };
+        if let Some(block_entity) = self.block_entities.lock().await.get_mut(&position) {
+            if let BlockEntityKind::Target(state) = &mut block_entity.kind {
+                state.ticks = ticks;
+            }
+        }
+        true
+    }
+
+    /// Strikes lightning at the position, or at the closest lightning rod in range which then
+    /// gives a redstone pulse
+    // TODO: call this from thunderstorms once there is weather
+    #[expect(dead_code)]
+    pub async fn strike_lightning(&self, position: WorldPosition) {
+        let rod = self
+            .block_entities
+            .lock()
+            .await
+            .iter()
+            .filter(|(_, block_entity)| {
+                matches!(block_entity.kind, BlockEntityKind::LightningRod(_))
+            })
+            .map(|(rod, _)| (*rod, center(*rod).sub(&center(position)).length()))
+            .filter(|(_, distance)| *distance <= LIGHTNING_ROD_RANGE)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(rod, _)| rod);
+        let strike = match rod {
+            Some(rod) => {
+                if let Ok(state_id) = self.get_block_state_id(rod).await {
+                    if let Some(new_state) = get_block_by_state_id(state_id)
+                        .and_then(|block| block.changed_state(state_id, &[("powered", "true")]))
+                    {
+                        self.set_block_state(rod, new_state).await;
+                    }
+                }
+                if let Some(block_entity) = self.block_entities.lock().await.get_mut(&rod) {
+                    if let BlockEntityKind::LightningRod(state) = &mut block_entity.kind {
+                        state.ticks = LIGHTNING_PULSE;
+                    }
+                }
+                let top = rod.0;
+                Vector3::new(
+                    f64::from(top.x) + 0.5,
+                    f64::from(top.y) + 1.0,
+                    f64::from(top.z) + 0.5,
+                )
+            }
+            None => {
+                let pos = position.0;
+                Vector3::new(
+                    f64::from(pos.x) + 0.5,
+                    f64::from(pos.y),
+                    f64::from(pos.z) + 0.5,
+                )
+            }
+        };
+        // the bolt is only shown, the clients remove it by themselves
+        self.broadcast_packet_all(&CSpawnEntity::new(
+            new_entity_id().into(),
+            Uuid::new_v4(),
+            (EntityType::LightningBolt as i32).into(),
+            strike.x,
+            strike.y,
+            strike.z,
+            0.0,
+            0.0,
+            0.0,
+            0.into(),
+            0.0,
+            0.0,
+            0.0,
+        ))
+        .await;
+        self.play_sound(
+            sound!("minecraft:entity.lightning_bolt.thunder"),
+            SoundCategory::Weather,
+            &strike,
+        )
+        .await;
+        self.play_sound(
+            sound!("minecraft:entity.lightning_bolt.impact"),
+            SoundCategory::Weather,
+            &strike,
+        )
+        .await;
+        self.emit_game_event(GameEvent::LightningStrike, strike)
+            .await;
+    }
+}
@@ -0,0 +1,373 @@
+//! Block entities are saved in the `block_entities` of their chunk like vanilla saves them: the
+//! items of their container, the brew of brewing stands, the effects of beacons and the cooldown
+//! of hoppers. They come back when a player watches their chunk or when they are used, and are
+//! stored in the chunk when no player watches it anymore.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use fastnbt::Value;
+use pumpkin_core::{
+    math::{position::WorldPosition, vector2::Vector2},
+    nbt::Compound,
+};
+use pumpkin_inventory::{window_property, Container};
+use pumpkin_world::{
+    block::block_registry::get_block_entity_ident,
+    chunk::ChunkData,
+    item::{component::MOB_EFFECTS, slots_from_nbt, slots_to_nbt},
+};
+use tokio::sync::{Mutex, RwLock};
+
+use crate::world::World;
+
+use super::{beacon::BeaconState, hopper::HopperState, BlockEntity, BlockEntityKind};
+
+/// A block entity taken out of the world's block entities to be stored, so they aren't locked
+/// while its container is
+type Stored = (
+    WorldPosition,
+    BlockEntityKind,
+    Option<(u64, Arc<Mutex<Box<dyn Container>>>)>,
+);
+
+fn property(container: &dyn Container, property: i16) -> Option<i16> {
+    container
+        .properties()
+        .into_iter()
+        .find(|(id, _)| *id == property)
+        .map(|(_, value)| value)
+}
+
+/// The beacon effect of the window property, which is the effect id plus one
+fn effect_name(value: Option<i16>) -> Option<Value> {
+    let id = usize::try_from(value?.checked_sub(1)?).ok()?;
+    MOB_EFFECTS
+        .get(id)
+        .map(|effect| Value::String((*effect).to_string()))
+}
+
+fn effect_property(name: Option<&Value>) -> i16 {
+    let Some(Value::String(name)) = name else {
+        return 0;
+    };
+    MOB_EFFECTS
+        .iter()
+        .position(|effect| effect == name)
+        .and_then(|id| i16::try_from(id + 1).ok())
+        .unwrap_or(0)
+}
+
+fn set_or_remove(nbt: &mut Compound, key: &str, value: Option<Value>) {
+    match value {
+        Some(value) => nbt.insert(key.to_string(), value),
+        None => nbt.remove(key),
+    };
+}
+
+/// Writes what Pumpkin keeps track of into the block entity's NBT, the other tags stay
+pub(crate) fn write_nbt(
+    kind: BlockEntityKind,
+    container: Option<&dyn Container>,
+    nbt: &mut Compound,
+) {
+    if let Some(container) = container {
+        let slots = container.all_slots_ref();
+        let items = slots
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, stack)| Some((i8::try_from(slot).ok()?, (*stack)?)));
+        nbt.insert("Items".to_string(), slots_to_nbt(items));
+    }
+    match (kind, container) {
+        (BlockEntityKind::BrewingStand, Some(container)) => {
+            let brew_time = property(container, window_property::BrewingStand::BrewTime as i16);
+            let fuel = property(container, window_property::BrewingStand::FuelTime as i16);
+            nbt.insert("BrewTime".to_string(), Value::Short(brew_time.unwrap_or(0)));
+            nbt.insert(
+                "Fuel".to_string(),
+                Value::Byte(fuel.and_then(|fuel| i8::try_from(fuel).ok()).unwrap_or(0)),
+            );
+        }
+        (BlockEntityKind::Beacon(state), Some(container)) => {
+            nbt.insert("Levels".to_string(), Value::Int(state.levels.into()));
+            let primary = property(container, window_property::Beacon::FirstPotionEffect as i16);
+            let secondary = property(
+                container,
+                window_property::Beacon::SecondPotionEffect as i16,
+            );
+            set_or_remove(nbt, "primary_effect", effect_name(primary));
+            set_or_remove(nbt, "secondary_effect", effect_name(secondary));
+        }
+        (BlockEntityKind::Hopper(state), _) => {
+            nbt.insert(
+                "TransferCooldown".to_string(),
+                Value::Int(i32::try_from(state.cooldown).unwrap_or(i32::MAX)),
+            );
+        }
+        _ => {}
+    }
+}
+
+/// Reads what [`write_nbt`] wrote back into the container, returns the kind with what it keeps
+/// track of
+pub(crate) fn read_nbt(
+    kind: BlockEntityKind,
+    container: Option<&mut dyn Container>,
+    nbt: &Compound,
+) -> BlockEntityKind {
+    let mut container = container;
+    if let (Some(container), Some(items)) = (container.as_deref_mut(), nbt.get("Items")) {
+        let mut slots = container.all_slots();
+        for (slot, stack) in slots_from_nbt(items) {
+            if let Some(slot) = usize::try_from(slot)
+                .ok()
+                .and_then(|slot| slots.get_mut(slot))
+            {
+                **slot = Some(stack);
+            }
+        }
+    }
+    match (kind, container) {
+        (BlockEntityKind::BrewingStand, Some(container)) => {
+            if let Some(Value::Byte(fuel)) = nbt.get("Fuel") {
+                container.set_property(
+                    window_property::BrewingStand::FuelTime as i16,
+                    i16::from(*fuel),
+                );
+            }
+            if let Some(Value::Short(brew_time)) = nbt.get("BrewTime") {
+                container.set_property(window_property::BrewingStand::BrewTime as i16, *brew_time);
+            }
+            kind
+        }
+        (BlockEntityKind::Beacon(_), container) => {
+            let levels = match nbt.get("Levels") {
+                Some(Value::Int(levels)) => u8::try_from(*levels).unwrap_or(0),
+                _ => 0,
+            };
+            if let Some(container) = container {
+                container.set_property(
+                    window_property::Beacon::PowerLevel as i16,
+                    i16::from(levels),
+                );
+                container.set_property(
+                    window_property::Beacon::FirstPotionEffect as i16,
+                    effect_property(nbt.get("primary_effect")),
+                );
+                container.set_property(
+                    window_property::Beacon::SecondPotionEffect as i16,
+                    effect_property(nbt.get("secondary_effect")),
+                );
+            }
+            BlockEntityKind::Beacon(BeaconState { levels })
+        }
+        (BlockEntityKind::Hopper(_), _) => {
+            let cooldown = match nbt.get("TransferCooldown") {
+                Some(Value::Int(cooldown)) => u32::try_from(*cooldown).unwrap_or(0),
+                _ => 0,
+            };
+            BlockEntityKind::Hopper(HopperState { cooldown })
+        }
+        (kind, _) => kind,
+    }
+}
+
+impl World {
+    /// The block entity of the kind at the position, with what its chunk saved of it
+    pub(super) async fn restore_block_entity(
+        &self,
+        position: WorldPosition,
+        kind: BlockEntityKind,
+    ) -> BlockEntity {
+        let mut block_entity = BlockEntity::new(kind);
+        let (chunk, _) = position.chunk_and_chunk_relative_position();
+        let Some(chunk) = self.level.get_loaded_chunk(chunk) else {
+            return block_entity;
+        };
+        let Some(nbt) = chunk.read().await.block_entities.get(&position).cloned() else {
+            return block_entity;
+        };
+        if let Some((_, container)) = &block_entity.container {
+            let mut container = container.lock().await;
+            block_entity.kind = read_nbt(kind, Some(container.as_mut()), &nbt);
+        } else {
+            block_entity.kind = read_nbt(kind, None, &nbt);
+        }
+        block_entity
+    }
+
+    /// Creates the block entities saved with the chunk when a player starts watching it, so they
+    /// work without being used first
+    pub(in crate::world) async fn load_block_entities(&self, chunk: &Arc<RwLock<ChunkData>>) {
+        let saved: Vec<_> = chunk.read().await.block_entities.keys().copied().collect();
+        for position in saved {
+            if let Ok(state_id) = self.get_block_state_id(position).await {
+                self.create_block_entity(position, state_id).await;
+            }
+        }
+    }
+
+    /// Replaces what the chunk saved of the block entities with what they are now
+    async fn store_block_entities(&self, chunk: Vector2<i32>, block_entities: &[Stored]) {
+        let Some(data) = self.level.get_loaded_chunk(chunk) else {
+            return;
+        };
+        let mut stored = Vec::with_capacity(block_entities.len());
+        for (position, kind, container) in block_entities {
+            let mut nbt = data
+                .read()
+                .await
+                .block_entities
+                .get(position)
+                .cloned()
+                .unwrap_or_default();
+            if !nbt.contains_key("id") {
+                let Some(id) = self
+                    .get_block_state(*position)
+                    .await
+                    .ok()
+                    .and_then(|state| get_block_entity_ident(state.block_entity_type?))
+                else {
+                    continue;
+                };
+                nbt.insert("id".to_string(), Value::String(id.to_string()));
+            }
+            if let Some((_, container)) = container {
+                let container = container.lock().await;
+                write_nbt(*kind, Some(container.as_ref()), &mut nbt);
+            } else {
+                write_nbt(*kind, None, &mut nbt);
+            }
+            stored.push((*position, nbt));
+        }
+        let mut data = data.write().await;
+        for (position, nbt) in stored {
+            if data.block_entities.get(&position) != Some(&nbt) {
+                data.block_entities.insert(position, nbt);
+                data.mark_dirty();
+            }
+        }
+    }
+
+    /// The block entities in the chunks by chunk, removed from the world's block entities if
+    /// `remove` is set
+    async fn block_entities_by_chunk(
+        &self,
+        chunks: Option<&HashSet<Vector2<i32>>>,
+        remove: bool,
+    ) -> HashMap<Vector2<i32>, Vec<Stored>> {
+        let mut by_chunk: HashMap<_, Vec<_>> = HashMap::new();
+        self.block_entities
+            .lock()
+            .await
+            .retain(|position, block_entity| {
+                let (chunk, _) = position.chunk_and_chunk_relative_position();
+                if chunks.is_some_and(|chunks| !chunks.contains(&chunk)) {
+                    return true;
+                }
+                by_chunk.entry(chunk).or_default().push((
+                    *position,
+                    block_entity.kind,
+                    block_entity.container.clone(),
+                ));
+                !remove
+            });
+        by_chunk
+    }
+
+    /// Stores the block entities of the chunks no player watches anymore in them and removes them
+    /// from the world, their items stay in the chunk
+    pub(in crate::world) async fn unload_block_entities(&self, chunks: &[Vector2<i32>]) {
+        let unloading = chunks
+            .iter()
+            .filter(|chunk| self.level.should_pop_chunk(chunk))
+            .copied()
+            .collect::<HashSet<_>>();
+        if unloading.is_empty() {
+            return;
+        }
+        let by_chunk = self.block_entities_by_chunk(Some(&unloading), true).await;
+        for (chunk, block_entities) in by_chunk {
+            for (_, _, container) in &block_entities {
+                if let Some((id, _)) = container {
+                    self.close_container_for_viewers(*id).await;
+                }
+            }
+            self.store_block_entities(chunk, &block_entities).await;
+        }
+    }
+
+    /// Stores the block entities in their chunks, so they are saved with the chunks
+    pub(in crate::world) async fn save_block_entities(&self) {
+        for (chunk, block_entities) in self.block_entities_by_chunk(None, false).await {
+            self.store_block_entities(chunk, &block_entities).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_core::nbt::Compound;
+    use pumpkin_inventory::{window_property, Beacon, BrewingStand, Chest, Container};
+    use pumpkin_world::item::{item_registry::get_item, ItemStack};
+
+    use super::{read_nbt, write_nbt};
+    use crate::world::block_entity::{beacon::BeaconState, hopper::HopperState, BlockEntityKind};
+
+    fn round_trip(
+        kind: BlockEntityKind,
+        container: &dyn Container,
+        into: &mut dyn Container,
+    ) -> BlockEntityKind {
+        let mut nbt = Compound::new();
+        write_nbt(kind, Some(container), &mut nbt);
+        read_nbt(kind, Some(into), &nbt)
+    }
+
+    #[test]
+    fn chest_items_come_back() {
+        let diamond = get_item("minecraft:diamond").unwrap().id;
+        let mut chest = Chest::new();
+        *chest.all_slots()[5] = Some(ItemStack::new(12, diamond));
+        let mut loaded = Chest::new();
+        round_trip(BlockEntityKind::Chest, &chest, &mut loaded);
+        let slots = loaded.all_slots_ref();
+        assert_eq!(
+            slots[5].map(|stack| (stack.item_id, stack.item_count)),
+            Some((diamond, 12))
+        );
+        assert_eq!(slots.iter().flatten().count(), 1);
+    }
+
+    #[test]
+    fn brews_and_beacon_effects_come_back() {
+        let mut stand = BrewingStand::default();
+        stand.set_property(window_property::BrewingStand::FuelTime as i16, 17);
+        stand.set_property(window_property::BrewingStand::BrewTime as i16, 250);
+        let mut loaded = BrewingStand::default();
+        round_trip(BlockEntityKind::BrewingStand, &stand, &mut loaded);
+        assert_eq!(loaded.properties(), stand.properties());
+
+        let mut beacon = Beacon::default();
+        beacon.set_property(window_property::Beacon::FirstPotionEffect as i16, 1);
+        beacon.set_property(window_property::Beacon::SecondPotionEffect as i16, 10);
+        let kind = BlockEntityKind::Beacon(BeaconState { levels: 4 });
+        let mut loaded = Beacon::default();
+        assert_eq!(round_trip(kind, &beacon, &mut loaded), kind);
+        let properties = loaded.properties();
+        assert_eq!(&properties[1..], &beacon.properties()[1..]);
+        assert_eq!(properties[0].1, 4);
+    }
+
+    #[test]
+    fn hopper_cooldown_comes_back() {
+        let kind = BlockEntityKind::Hopper(HopperState { cooldown: 5 });
+        let mut nbt = Compound::new();
+        write_nbt(kind, None, &mut nbt);
+        let loaded = read_nbt(BlockEntityKind::Hopper(HopperState::default()), None, &nbt);
+        assert_eq!(loaded, kind);
+    }
+}
@@ -0,0 +1,417 @@
+//! Sculk catalysts: they soak up the experience of mobs and players dying nearby and spread sculk
+//! with it. The experience becomes the charge of a cursor which wanders over the blocks around,
+//! turning them into sculk, growing sensors and shriekers on it and using up its charge.
+
+use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+use pumpkin_macros::{particle, sound};
+use pumpkin_protocol::{client::play::Particle, SoundCategory};
+use pumpkin_registry::{is_in, TagCategory};
+use pumpkin_world::block::{
+    block_registry::{get_block, get_block_by_state_id},
+    BlockFace,
+};
+use rand::{seq::SliceRandom, thread_rng, Rng};
+
+use crate::world::World;
+
+use super::{center, BlockEntityKind};
+
+/// How far catalysts soak up the experience of dying mobs
+const RANGE: f64 = 8.0;
+/// Cursors spreading at once, more experience adds to their charge instead
+const MAX_CURSORS: usize = 8;
+const MAX_CHARGE: u16 = 1000;
+/// Ticks between the moves of the cursors
+const SPREAD_INTERVAL: u32 = 2;
+/// Ticks the catalyst blooms after it soaked up experience
+const BLOOM_TICKS: u8 = 8;
+/// One in this many moves over sculk grows something on it or costs charge
+const CHARGE_DECAY_RATE: u32 = 10;
+/// One in this many of them costs charge if nothing grows
+const ADDITIONAL_DECAY_RATE: u32 = 5;
+/// Nothing grows this close to the catalyst
+const NO_GROWTH_RADIUS: f64 = 4.0;
+/// Cursors further than this from the catalyst lose their charge the fastest
+const DECAY_RADIUS: f64 = 24.0;
+/// The charge a sensor or shrieker costs
+const GROWTH_COST: u16 = 10;
+/// One in this many growths is a shrieker
+const SHRIEKER_CHANCE: u32 = 11;
+/// No more growths in a spot which has this many around already
+const MAX_GROWTHS_NEARBY: usize = 2;
+
+const FACES: [BlockFace; 6] = [
+    BlockFace::Bottom,
+    BlockFace::Top,
+    BlockFace::North,
+    BlockFace::South,
+    BlockFace::West,
+    BlockFace::East,
+];
+
+/// Where sculk spreads to next, with the experience it has left to spread
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChargeCursor {
+    pub position: WorldPosition,
+    pub charge: u16,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SculkCatalystState {
+    pub cursors: [Option<ChargeCursor>; MAX_CURSORS],
+    /// Ticks until the catalyst stops blooming
+    pub bloom_ticks: u8,
+}
+
+impl SculkCatalystState {
+    /// Adds a cursor with the charge, or adds it to one at the same position or the weakest one
+    /// if there are already too many
+    fn add_cursor(&mut self, position: WorldPosition, charge: u16) {
+        let slot = self
+            .cursors
+            .iter()
+            .position(|cursor| cursor.is_some_and(|cursor| cursor.position == position))
+            .or_else(|| self.cursors.iter().position(Option::is_none));
+        match slot {
+            Some(slot) => {
+                let cursor = self.cursors[slot].get_or_insert(ChargeCursor {
+                    position,
+                    charge: 0,
+                });
+                cursor.charge = (cursor.charge + charge).min(MAX_CHARGE);
+            }
+            None => {
+                if let Some(cursor) = self
+                    .cursors
+                    .iter_mut()
+                    .flatten()
+                    .min_by_key(|cursor| cursor.charge)
+                {
+                    cursor.charge = (cursor.charge + charge).min(MAX_CHARGE);
+                }
+            }
+        }
+    }
+}
+
+fn offset(position: WorldPosition, x: i32, y: i32, z: i32) -> WorldPosition {
+    WorldPosition(position.0.add(&Vector3::new(x, y, z)))
+}
+
+fn distance(a: WorldPosition, b: WorldPosition) -> f64 {
+    center(a).sub(&center(b)).length()
+}
+
+fn is_sculk(name: &str) -> bool {
+    name == "minecraft:sculk"
+}
+
+fn is_replaceable(name: &str) -> bool {
+    is_in(TagCategory::Block, "minecraft:sculk_replaceable", name)
+}
+
+fn is_growth(name: &str) -> bool {
+    matches!(
+        name,
+        "minecraft:sculk_sensor" | "minecraft:sculk_shrieker" | "minecraft:sculk_catalyst"
+    )
+}
+
+/// The charge a move over sculk far from the catalyst costs, more the further it got
+fn decay_penalty(charge: u16, distance: f64) -> u16 {
+    let spread = distance - NO_GROWTH_RADIUS;
+    let range = DECAY_RADIUS - NO_GROWTH_RADIUS;
+    let factor = (spread * spread / (range * range)).min(1.0);
+    ((f64::from(charge) * factor * 0.5) as u16).max(1)
+}
+
+impl World {
+    async fn block_name_at(&self, position: WorldPosition) -> Option<&'static str> {
+        let state_id = self.get_block_state_id(position).await.ok()?;
+        get_block_by_state_id(state_id).map(|block| block.name.as_str())
+    }
+
+    async fn is_air(&self, position: WorldPosition) -> bool {
+        self.get_block_state(position)
+            .await
+            .is_ok_and(|state| state.air)
+    }
+
+    /// Whether a side of the block touches air, sculk only spreads over the surface
+    async fn is_exposed(&self, position: WorldPosition) -> bool {
+        for face in FACES {
+            if self
+                .is_air(WorldPosition(position.0.add(&face.to_offset())))
+                .await
+            {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Covers the sides of the block which touch air with sculk veins
+    async fn place_sculk_veins(&self, position: WorldPosition) {
+        let Some(vein) = get_block("minecraft:sculk_vein") else {
+            return;
+        };
+        for face in FACES {
+            let side = WorldPosition(position.0.add(&face.to_offset()));
+            let Ok(current) = self.get_block_state_id(side).await else {
+                continue;
+            };
+            let state_id = if self.is_air(side).await {
+                vein.default_state_id
+            } else if get_block_by_state_id(current).is_some_and(|block| block.id == vein.id) {
+                current
+            } else {
+                continue;
+            };
+            // the vein sticks to the face pointing back to the block
+            if let Some(new_state) =
+                vein.changed_state(state_id, &[(face.opposite().name(), "true")])
+            {
+                if new_state != state_id {
+                    self.set_block_state(side, new_state).await;
+                }
+            }
+        }
+    }
+
+    /// Whether a sensor or shrieker can grow on top of the sculk, there is only room for a few
+    async fn can_place_growth(&self, position: WorldPosition) -> bool {
+        if !self.is_air(offset(position, 0, 1, 0)).await {
+            return false;
+        }
+        let mut growths = 0;
+        for x in -4..=4 {
+            for z in -4..=4 {
+                for y in 0..=2 {
+                    let nearby = offset(position, x, y, z);
+                    if self.block_name_at(nearby).await.is_some_and(is_growth) {
+                        growths += 1;
+                        if growths > MAX_GROWTHS_NEARBY {
+                            return false;
+                        }
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    /// Grows a sensor or shrieker on top of the sculk. Shriekers grown by catalysts never summon
+    /// wardens, only the ones of ancient cities do
+    async fn place_growth(&self, position: WorldPosition) {
+        let shrieker = thread_rng().gen_range(0..SHRIEKER_CHANCE) == 0;
+        let (name, sound) = if shrieker {
+            (
+                "minecraft:sculk_shrieker",
+                sound!("minecraft:block.sculk_shrieker.place"),
+            )
+        } else {
+            (
+                "minecraft:sculk_sensor",
+                sound!("minecraft:block.sculk_sensor.place"),
+            )
+        };
+        let Some(block) = get_block(name) else {
+            return;
+        };
+        let state_id = if shrieker {
+            block
+                .changed_state(block.default_state_id, &[("can_summon", "false")])
+                .unwrap_or(block.default_state_id)
+        } else {
+            block.default_state_id
+        };
+        self.set_block_state(offset(position, 0, 1, 0), state_id)
+            .await;
+        self.play_sound(sound, SoundCategory::Blocks, &center(position))
+            .await;
+    }
+
+    /// The charge left after the cursor passed over sculk, something may grow on it
+    async fn use_charge_on_sculk(&self, catalyst: WorldPosition, cursor: ChargeCursor) -> u16 {
+        let (decays, growth_roll, additional_decay) = {
+            let mut rng = thread_rng();
+            (
+                rng.gen_range(0..CHARGE_DECAY_RATE) == 0,
+                rng.gen_range(0..GROWTH_COST),
+                rng.gen_range(0..ADDITIONAL_DECAY_RATE) == 0,
+            )
+        };
+        if cursor.charge == 0 || !decays {
+            return cursor.charge;
+        }
+        let distance = distance(cursor.position, catalyst);
+        let close = distance < NO_GROWTH_RADIUS;
+        if !close && self.can_place_growth(cursor.position).await {
+            if growth_roll < cursor.charge {
+                self.place_growth(cursor.position).await;
+            }
+            return cursor.charge.saturating_sub(GROWTH_COST);
+        }
+        if !additional_decay {
+            cursor.charge
+        } else if close {
+            cursor.charge - 1
+        } else {
+            cursor
+                .charge
+                .saturating_sub(decay_penalty(cursor.charge, distance))
+        }
+    }
+
+    /// A random block around the cursor it can spread to next
+    async fn next_cursor_position(&self, position: WorldPosition) -> Option<WorldPosition> {
+        let mut neighbours: Vec<_> = (-1..=1)
+            .flat_map(|x| (-1..=1).flat_map(move |y| (-1..=1).map(move |z| (x, y, z))))
+            .filter(|offset| *offset != (0, 0, 0))
+            .map(|(x, y, z)| offset(position, x, y, z))
+            .collect();
+        neighbours.shuffle(&mut thread_rng());
+        for neighbour in neighbours {
+            let spreads = self
+                .block_name_at(neighbour)
+                .await
+                .is_some_and(|name| is_sculk(name) || is_replaceable(name));
+            if spreads && self.is_exposed(neighbour).await {
+                return Some(neighbour);
+            }
+        }
+        None
+    }
+
+    /// Spreads sculk where the cursor is and moves it on. `None` once it used up its charge
+    async fn update_cursor(
+        &self,
+        catalyst: WorldPosition,
+        cursor: ChargeCursor,
+    ) -> Option<ChargeCursor> {
+        let name = self.block_name_at(cursor.position).await?;
+        let charge = if is_sculk(name) {
+            self.use_charge_on_sculk(catalyst, cursor).await
+        } else if is_replaceable(name) && self.is_exposed(cursor.position).await {
+            if let Some(sculk) = get_block("minecraft:sculk") {
+                self.set_block_state(cursor.position, sculk.default_state_id)
+                    .await;
+                // the blocks around the sculk get covered with veins
+                for face in FACES {
+                    let side = WorldPosition(cursor.position.0.add(&face.to_offset()));
+                    if self.block_name_at(side).await.is_some_and(is_replaceable) {
+                        self.place_sculk_veins(side).await;
+                    }
+                }
+                self.play_sound(
+                    sound!("minecraft:block.sculk.spread"),
+                    SoundCategory::Blocks,
+                    &center(cursor.position),
+                )
+                .await;
+            }
+            cursor.charge - 1
+        } else {
+            cursor.charge
+        };
+        if charge == 0 {
+            return None;
+        }
+        match self.next_cursor_position(cursor.position).await {
+            Some(position) => Some(ChargeCursor { position, charge }),
+            // stuck cursors fade away
+            None => (charge > 1).then_some(ChargeCursor {
+                position: cursor.position,
+                charge: charge - 1,
+            }),
+        }
+    }
+
+    /// Stops the blooming once its time is up and spreads the sculk of the cursors
+    pub(super) async fn tick_sculk_catalyst(
+        &self,
+        position: WorldPosition,
+        state_id: u16,
+        mut state: SculkCatalystState,
+        age: u32,
+    ) -> SculkCatalystState {
+        if state.bloom_ticks > 0 {
+            state.bloom_ticks -= 1;
+            if state.bloom_ticks == 0 {
+                if let Some(new_state) = get_block_by_state_id(state_id)
+                    .and_then(|block| block.changed_state(state_id, &[("bloom", "false")]))
+                {
+                    self.set_block_state(position, new_state).await;
+                }
+            }
+        }
+        if age % SPREAD_INTERVAL != 0 {
+            return state;
+        }
+        for cursor in &mut state.cursors {
+            if let Some(current) = *cursor {
+                *cursor = self.update_cursor(position, current).await;
+            }
+        }
+        state
+    }
+
+    /// Lets the closest catalyst in range soak up the experience of a mob or player which died at
+    /// the position. Returns false if there was none, the experience drops as orbs then
+    pub async fn absorb_experience(&self, position: WorldPosition, points: i32) -> bool {
+        if points <= 0 {
+            return false;
+        }
+        let catalyst = self
+            .block_entities
+            .lock()
+            .await
+            .iter()
+            .filter(|(_, block_entity)| {
+                matches!(block_entity.kind, BlockEntityKind::SculkCatalyst(_))
+            })
+            .map(|(catalyst, _)| (*catalyst, distance(*catalyst, position)))
+            .filter(|(_, distance)| *distance <= RANGE)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(catalyst, _)| catalyst);
+        let Some(catalyst) = catalyst else {
+            return false;
+        };
+        if let Some(block_entity) = self.block_entities.lock().await.get_mut(&catalyst) {
+            if let BlockEntityKind::SculkCatalyst(state) = &mut block_entity.kind {
+                state.add_cursor(position, points.min(i32::from(MAX_CHARGE)) as u16);
+                state.bloom_ticks = BLOOM_TICKS;
+            }
+        }
+        if let Ok(state_id) = self.get_block_state_id(catalyst).await {
+            if let Some(new_state) = get_block_by_state_id(state_id)
+                .and_then(|block| block.changed_state(state_id, &[("bloom", "true")]))
+            {
+                self.set_block_state(catalyst, new_state).await;
+            }
+        }
+        self.play_sound(
+            sound!("minecraft:block.sculk_catalyst.bloom"),
+            SoundCategory::Blocks,
+            &center(catalyst),
+        )
+        .await;
+        let top = catalyst.0;
+        let particle = Particle::simple(particle!("minecraft:sculk_soul"))
+            .expect("sculk souls have no options");
+        self.spawn_particle(
+            &particle,
+            Vector3::new(
+                f64::from(top.x) + 0.5,
+                f64::from(top.y) + 1.15,
+                f64::from(top.z) + 0.5,
+            ),
+            Vector3::new(0.2, 0.0, 0.2),
+            0.0,
+            2,
+            false,
+        )
+        .await;
+        true
+    }
+}
@@ -0,0 +1,220 @@
+//! Sculk sensors: they pick up the vibrations of game events nearby and give a redstone signal,
+//! the stronger the closer the event was. Calibrated sculk sensors hear further, but only the
+//! frequency of the signal going into their back if they get one.
+
+use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+use pumpkin_entity::EntityId;
+use pumpkin_macros::sound;
+use pumpkin_protocol::SoundCategory;
+use pumpkin_world::block::{
+    block_registry::{get_block_by_state_id, Block},
+    BlockFace,
+};
+
+use crate::world::{redstone::emitted_power_to, vibration::GameEvent, World};
+
+use super::{center, BlockEntityKind};
+
+const RANGE: f64 = 8.0;
+const CALIBRATED_RANGE: f64 = 16.0;
+/// Ticks a sensor stays active after it heard a vibration
+const ACTIVE_TICKS: u8 = 30;
+const CALIBRATED_ACTIVE_TICKS: u8 = 10;
+/// Ticks a sensor can't hear anything after it was active
+const COOLDOWN_TICKS: u8 = 10;
+
+/// A vibration on its way to a sensor
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Vibration {
+    /// The signal the sensor gives once the vibration arrived
+    pub power: u8,
+    /// Ticks until it arrives, vibrations travel a block per tick
+    pub delay: u8,
+    /// The entity which caused it, shriekers nearby shriek if it was a player
+    pub cause: Option<EntityId>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SculkSensorState {
+    pub vibration: Option<Vibration>,
+    /// Ticks until the sensor moves on to its next phase
+    pub phase_ticks: u8,
+}
+
+/// The signal of a sensor which heard a vibration from the distance, 15 right next to it
+fn signal_strength(distance: f64, range: f64) -> u8 {
+    (15 - (15.0 / range * distance).floor() as i32).max(1) as u8
+}
+
+fn property<'a>(properties: &[(&str, &'a str)], name: &str) -> Option<&'a str> {
+    properties
+        .iter()
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| *value)
+}
+
+impl World {
+    async fn set_sensor_phase(
+        &self,
+        position: WorldPosition,
+        block: &Block,
+        state_id: u16,
+        phase: &str,
+        power: u8,
+    ) {
+        let power = power.to_string();
+        if let Some(new_state) = block.changed_state(
+            state_id,
+            &[("sculk_sensor_phase", phase), ("power", power.as_str())],
+        ) {
+            self.set_block_state(position, new_state).await;
+        }
+    }
+
+    /// Activates the sensor once the vibration arrived, and lets it cool down again afterwards
+    pub(super) async fn tick_sculk_sensor(
+        &self,
+        position: WorldPosition,
+        state_id: u16,
+        state: SculkSensorState,
+    ) -> SculkSensorState {
+        let Some(block) = get_block_by_state_id(state_id) else {
+            return state;
+        };
+        let properties = block.properties_of_state(state_id).unwrap_or_default();
+        if state.phase_ticks > 0 {
+            let mut phase_ticks = state.phase_ticks - 1;
+            if phase_ticks == 0 {
+                if property(&properties, "sculk_sensor_phase") == Some("active") {
+                    self.set_sensor_phase(position, block, state_id, "cooldown", 0)
+                        .await;
+                    self.play_sound(
+                        sound!("minecraft:block.sculk_sensor.clicking_stop"),
+                        SoundCategory::Blocks,
+                        &center(position),
+                    )
+                    .await;
+                    phase_ticks = COOLDOWN_TICKS;
+                } else {
+                    self.set_sensor_phase(position, block, state_id, "inactive", 0)
+                        .await;
+                }
+            }
+            return SculkSensorState {
+                phase_ticks,
+                ..state
+            };
+        }
+
+        let Some(vibration) = state.vibration else {
+            return state;
+        };
+        if vibration.delay > 0 {
+            return SculkSensorState {
+                vibration: Some(Vibration {
+                    delay: vibration.delay - 1,
+                    ..vibration
+                }),
+                ..state
+            };
+        }
+        self.set_sensor_phase(position, block, state_id, "active", vibration.power)
+            .await;
+        self.play_sound(
+            sound!("minecraft:block.sculk_sensor.clicking"),
+            SoundCategory::Blocks,
+            &center(position),
+        )
+        .await;
+        self.emit_game_event_by(
+            GameEvent::SculkSensorTendrilsClicking,
+            center(position),
+            vibration.cause,
+        )
+        .await;
+        let calibrated = block.name == "minecraft:calibrated_sculk_sensor";
+        SculkSensorState {
+            vibration: None,
+            phase_ticks: if calibrated {
+                CALIBRATED_ACTIVE_TICKS
+            } else {
+                ACTIVE_TICKS
+            },
+        }
+    }
+
+    /// The frequency a calibrated sensor listens to, the signal going into its back. `None` if
+    /// it hears every frequency
+    async fn calibrated_frequency(&self, position: WorldPosition, facing: BlockFace) -> Option<u8> {
+        let back = WorldPosition(position.0.add(&facing.opposite().to_offset()));
+        let state_id = self.get_block_state_id(back).await.ok()?;
+        let power = emitted_power_to(state_id, facing);
+        (power > 0).then_some(power)
+    }
+
+    /// Sends the vibration of the event to the listening sensors in range, unless wool is in the
+    /// way. Sensors only follow one vibration at a time
+    pub(crate) async fn sculk_sensors_hear(
+        &self,
+        event: GameEvent,
+        source: Vector3<f64>,
+        cause: Option<EntityId>,
+    ) {
+        let listening: Vec<_> = self
+            .block_entities
+            .lock()
+            .await
+            .iter()
+            .filter(|(position, block_entity)| {
+                matches!(
+                    block_entity.kind,
+                    BlockEntityKind::SculkSensor(state)
+                        if state.vibration.is_none() && state.phase_ticks == 0
+                ) && center(**position).sub(&source).length() <= CALIBRATED_RANGE
+            })
+            .map(|(position, _)| *position)
+            .collect();
+        let Some(frequency) = event.frequency() else {
+            return;
+        };
+        for position in listening {
+            let Ok(state_id) = self.get_block_state_id(position).await else {
+                continue;
+            };
+            let Some(block) = get_block_by_state_id(state_id) else {
+                continue;
+            };
+            let properties = block.properties_of_state(state_id).unwrap_or_default();
+            if property(&properties, "sculk_sensor_phase") != Some("inactive") {
+                continue;
+            }
+            let range = if block.name == "minecraft:calibrated_sculk_sensor" {
+                let facing = property(&properties, "facing")
+                    .and_then(BlockFace::from_name)
+                    .unwrap_or(BlockFace::North);
+                let listened = self.calibrated_frequency(position, facing).await;
+                if listened.is_some_and(|listened| listened != frequency) {
+                    continue;
+                }
+                CALIBRATED_RANGE
+            } else {
+                RANGE
+            };
+            let sensor = center(position);
+            let distance = sensor.sub(&source).length();
+            if distance > range || self.is_vibration_occluded(source, sensor).await {
+                continue;
+            }
+            let vibration = Vibration {
+                power: signal_strength(distance, range),
+                delay: distance.floor() as u8,
+                cause,
+            };
+            if let Some(block_entity) = self.block_entities.lock().await.get_mut(&position) {
+                if let BlockEntityKind::SculkSensor(state) = &mut block_entity.kind {
+                    state.vibration.get_or_insert(vibration);
+                }
+            }
+        }
+    }
+}
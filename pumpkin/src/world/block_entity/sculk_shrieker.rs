@@ -0,0 +1,347 @@
+//! Sculk shriekers: they shriek when a player steps on them or a sculk sensor nearby heard one.
+//! Every shriek warns the players nearby, the fourth warning within a while lets a warden emerge
+//! from the shriekers of ancient cities.
+
+use std::sync::Arc;
+
+use pumpkin_core::{
+    math::{position::WorldPosition, vector3::Vector3},
+    Difficulty, GameMode,
+};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_macros::sound;
+use pumpkin_protocol::{client::play::CWorldEvent, SoundCategory};
+use pumpkin_world::{block::block_registry::get_block_by_state_id, game_rules::DO_WARDEN_SPAWNING};
+use rand::{thread_rng, Rng};
+
+use crate::{
+    entity::{
+        mob::warden::{apply_darkness, Warden},
+        player::Player,
+    },
+    world::{vibration::GameEvent, World},
+};
+
+use super::{center, BlockEntityKind};
+
+/// How far shriekers hear the sculk sensors which heard a player
+const RANGE: f64 = 8.0;
+/// Ticks a shriek lasts until the shrieker responds
+const SHRIEK_TICKS: u8 = 90;
+const SHRIEK_EVENT: i32 = 3007;
+/// How far from the shrieker the players are warned too
+const WARNING_RANGE: f64 = 16.0;
+/// No warnings while a warden is this close
+const WARDEN_RANGE: f64 = 48.0;
+/// The warning level at which a warden emerges
+const MAX_WARNING_LEVEL: u8 = 4;
+/// Ticks until the players can be warned again
+const WARNING_COOLDOWN: u32 = 200;
+/// Ticks after the last warning until the warning level goes down again
+const WARNING_DECAY: u32 = 12000;
+/// How far from the shrieker the warden answers, when it doesn't emerge
+const REPLY_OFFSET: f64 = 10.0;
+const DARKNESS_RANGE: f64 = 40.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SculkShriekerState {
+    /// The player a sensor heard, with the ticks until the vibration arrives
+    pub vibration: Option<(EntityId, u8)>,
+    /// Ticks until the shriek ends
+    pub shriek_ticks: u8,
+    /// The warning level of the players when the shriek started
+    pub warning_level: u8,
+}
+
+/// How close the shriekers are to summoning a warden for a player, players nearby share it
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WardenSpawnTracker {
+    pub warning_level: u8,
+    pub ticks_since_last_warning: u32,
+    pub cooldown_ticks: u32,
+}
+
+impl WardenSpawnTracker {
+    /// The tracker like it is saved in the player's file
+    #[must_use]
+    pub fn from_saved(
+        (warning_level, ticks_since_last_warning, cooldown_ticks): (i32, i32, i32),
+    ) -> Self {
+        Self {
+            warning_level: warning_level.clamp(0, i32::from(MAX_WARNING_LEVEL)) as u8,
+            ticks_since_last_warning: ticks_since_last_warning.max(0) as u32,
+            cooldown_ticks: cooldown_ticks.max(0) as u32,
+        }
+    }
+
+    #[must_use]
+    pub fn saved(self) -> (i32, i32, i32) {
+        (
+            i32::from(self.warning_level),
+            self.ticks_since_last_warning as i32,
+            self.cooldown_ticks as i32,
+        )
+    }
+
+    /// The warning level goes down by one after a while without warnings
+    pub fn tick(&mut self) {
+        if self.ticks_since_last_warning >= WARNING_DECAY {
+            self.warning_level = self.warning_level.saturating_sub(1);
+            self.ticks_since_last_warning = 0;
+        } else {
+            self.ticks_since_last_warning += 1;
+        }
+        self.cooldown_ticks = self.cooldown_ticks.saturating_sub(1);
+    }
+
+    #[must_use]
+    pub const fn on_cooldown(self) -> bool {
+        self.cooldown_ticks > 0
+    }
+
+    fn warn(&mut self) {
+        if self.on_cooldown() {
+            return;
+        }
+        self.ticks_since_last_warning = 0;
+        self.cooldown_ticks = WARNING_COOLDOWN;
+        self.warning_level = (self.warning_level + 1).min(MAX_WARNING_LEVEL);
+    }
+}
+
+/// The sound of the warden answering a shriek, the closer it is to emerging the closer it sounds
+fn reply_sound(warning_level: u8) -> u16 {
+    match warning_level {
+        1 => sound!("minecraft:entity.warden.nearby_close"),
+        2 => sound!("minecraft:entity.warden.nearby_closer"),
+        3 => sound!("minecraft:entity.warden.nearby_closest"),
+        _ => sound!("minecraft:entity.warden.listening_angry"),
+    }
+}
+
+fn can_be_warned(player: &Player) -> bool {
+    matches!(
+        player.gamemode.load(),
+        GameMode::Survival | GameMode::Adventure
+    ) && player.living_entity.health.load() > 0.0
+}
+
+impl World {
+    /// Whether the shrieker can let a warden emerge, the ones grown by catalysts can't
+    async fn can_respond(&self, state_id: u16) -> bool {
+        let can_summon = get_block_by_state_id(state_id).is_some_and(|block| {
+            block
+                .properties_of_state(state_id)
+                .unwrap_or_default()
+                .contains(&("can_summon", "true"))
+        });
+        can_summon
+            && self.config.difficulty != Difficulty::Peaceful
+            && self.game_rules.read().await.get(&DO_WARDEN_SPAWNING)
+    }
+
+    /// Warns the player and the players around the shrieker, they all end up with the highest
+    /// warning level among them. Returns the new level, `None` if they can't be warned right now
+    async fn try_warn(&self, position: WorldPosition, player: EntityId) -> Option<u8> {
+        let source = center(position);
+        let warden_nearby = self.entities.lock().await.values().any(|entity| {
+            let entity = entity.get_entity();
+            entity.entity_type == EntityType::Warden
+                && entity.pos.load().sub(&source).length() <= WARDEN_RANGE
+        });
+        if warden_nearby {
+            return None;
+        }
+        let players: Vec<_> = self
+            .players()
+            .await
+            .iter()
+            .filter(|nearby| {
+                nearby.entity_id() == player
+                    || (can_be_warned(nearby)
+                        && nearby.living_entity.entity.pos.load().sub(&source).length()
+                            <= WARNING_RANGE)
+            })
+            .cloned()
+            .collect();
+        let trackers: Vec<_> = players
+            .iter()
+            .map(|player| *player.warden_spawn_tracker.lock())
+            .collect();
+        if trackers.iter().any(|tracker| tracker.on_cooldown()) {
+            return None;
+        }
+        let mut tracker = trackers
+            .into_iter()
+            .max_by_key(|tracker| tracker.warning_level)?;
+        tracker.warn();
+        for player in &players {
+            *player.warden_spawn_tracker.lock() = tracker;
+        }
+        Some(tracker.warning_level)
+    }
+
+    async fn sculk_shrieker_state(&self, position: WorldPosition) -> Option<SculkShriekerState> {
+        match self.block_entities.lock().await.get(&position)?.kind {
+            BlockEntityKind::SculkShrieker(state) => Some(state),
+            _ => None,
+        }
+    }
+
+    /// Lets the shrieker shriek because of the player, unless it already does. Shriekers which
+    /// can summon wardens only shriek when they warn the players
+    async fn try_shriek(&self, position: WorldPosition, state_id: u16, player: EntityId) {
+        let shrieking = self
+            .sculk_shrieker_state(position)
+            .await
+            .is_some_and(|state| state.shriek_ticks > 0);
+        if shrieking {
+            return;
+        }
+        let warning_level = if self.can_respond(state_id).await {
+            match self.try_warn(position, player).await {
+                Some(level) => level,
+                None => return,
+            }
+        } else {
+            0
+        };
+        if let Some(new_state) = get_block_by_state_id(state_id)
+            .and_then(|block| block.changed_state(state_id, &[("shrieking", "true")]))
+        {
+            self.set_block_state(position, new_state).await;
+        }
+        if let Some(block_entity) = self.block_entities.lock().await.get_mut(&position) {
+            if let BlockEntityKind::SculkShrieker(state) = &mut block_entity.kind {
+                *state = SculkShriekerState {
+                    vibration: None,
+                    shriek_ticks: SHRIEK_TICKS,
+                    warning_level,
+                };
+            }
+        }
+        // the clients play the shriek and show its particles
+        self.broadcast_packet_all(&CWorldEvent::new(SHRIEK_EVENT, &position, 0, false))
+            .await;
+        self.emit_game_event_by(GameEvent::Shriek, center(position), Some(player))
+            .await;
+    }
+
+    /// Lets the shrieker shriek if the player stepped on one
+    pub async fn step_on_sculk_shrieker(&self, position: WorldPosition, player: &Player) {
+        let Ok(state_id) = self.get_block_state_id(position).await else {
+            return;
+        };
+        if get_block_by_state_id(state_id)
+            .is_some_and(|block| block.name == "minecraft:sculk_shrieker")
+        {
+            self.try_shriek(position, state_id, player.entity_id())
+                .await;
+        }
+    }
+
+    /// Answers the shriek once it is over: a warden emerges at the highest warning level, below it
+    /// only makes itself heard. Either way the players nearby get darkness
+    async fn respond_to_shriek(
+        self: &Arc<Self>,
+        position: WorldPosition,
+        state_id: u16,
+        level: u8,
+    ) {
+        if level == 0 || !self.can_respond(state_id).await {
+            return;
+        }
+        let source = center(position);
+        if level < MAX_WARNING_LEVEL || !Warden::try_summon(self, position).await {
+            let reply = {
+                let mut rng = thread_rng();
+                Vector3::new(
+                    source.x + rng.gen_range(-REPLY_OFFSET..=REPLY_OFFSET),
+                    source.y + rng.gen_range(-REPLY_OFFSET..=REPLY_OFFSET),
+                    source.z + rng.gen_range(-REPLY_OFFSET..=REPLY_OFFSET),
+                )
+            };
+            self.play_sound(reply_sound(level), SoundCategory::Hostile, &reply)
+                .await;
+        }
+        apply_darkness(self, source, DARKNESS_RANGE).await;
+    }
+
+    /// Ends the shriek once its time is up, and shrieks when the vibration of a player arrived
+    pub(super) async fn tick_sculk_shrieker(
+        self: &Arc<Self>,
+        position: WorldPosition,
+        state_id: u16,
+        state: SculkShriekerState,
+    ) -> SculkShriekerState {
+        if state.shriek_ticks > 0 {
+            let shriek_ticks = state.shriek_ticks - 1;
+            if shriek_ticks == 0 {
+                if let Some(new_state) = get_block_by_state_id(state_id)
+                    .and_then(|block| block.changed_state(state_id, &[("shrieking", "false")]))
+                {
+                    self.set_block_state(position, new_state).await;
+                }
+                self.respond_to_shriek(position, state_id, state.warning_level)
+                    .await;
+            }
+            return SculkShriekerState {
+                shriek_ticks,
+                ..state
+            };
+        }
+        match state.vibration {
+            Some((player, delay)) if delay > 0 => SculkShriekerState {
+                vibration: Some((player, delay - 1)),
+                ..state
+            },
+            Some((player, _)) => {
+                self.try_shriek(position, state_id, player).await;
+                // a shriek stored its state meanwhile
+                let state = self.sculk_shrieker_state(position).await.unwrap_or(state);
+                SculkShriekerState {
+                    vibration: None,
+                    ..state
+                }
+            }
+            None => state,
+        }
+    }
+
+    /// Sends the vibration of a sculk sensor which heard a player to the shriekers in range,
+    /// unless wool is in the way
+    pub(crate) async fn sculk_shriekers_hear(&self, source: Vector3<f64>, cause: Option<EntityId>) {
+        let Some(player) = cause else {
+            return;
+        };
+        if self.get_player_by_entityid(player).await.is_none() {
+            return;
+        }
+        let listening: Vec<_> = self
+            .block_entities
+            .lock()
+            .await
+            .iter()
+            .filter(|(position, block_entity)| {
+                matches!(
+                    block_entity.kind,
+                    BlockEntityKind::SculkShrieker(state)
+                        if state.vibration.is_none() && state.shriek_ticks == 0
+                ) && center(**position).sub(&source).length() <= RANGE
+            })
+            .map(|(position, _)| *position)
+            .collect();
+        for position in listening {
+            let shrieker = center(position);
+            if self.is_vibration_occluded(source, shrieker).await {
+                continue;
+            }
+            let delay = shrieker.sub(&source).length().floor() as u8;
+            if let Some(block_entity) = self.block_entities.lock().await.get_mut(&position) {
+                if let BlockEntityKind::SculkShrieker(state) = &mut block_entity.kind {
+                    state.vibration.get_or_insert((player, delay));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! Entities are saved with their chunk. Once no player watches a chunk anymore its mobs, dropped
+//! items and experience orbs are stored in it and despawned, and they are spawned again when a
+//! player watches it. Which of them are stored and how many come back is set by
+//! `entity_persistence`.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use pumpkin_config::ADVANCED_CONFIG;
+use pumpkin_core::{
+    math::{vector2::Vector2, vector3::Vector3},
+    text::TextComponent,
+};
+use pumpkin_entity::entity_type::EntityType;
+use pumpkin_world::{
+    chunk::{
+        entities::{apply_load_caps, retain_persistent, ChunkEntity},
+        ChunkData,
+    },
+    item::item_registry::get_item_by_id,
+};
+use tokio::sync::RwLock;
+
+use crate::entity::{experience_orb::ExperienceOrb, item::ItemEntity, mob, Entity, EntityBase};
+
+use super::World;
+
+/// What every saved entity has, `EntityBase::chunk_entity` adds what belongs to its type. Custom
+/// names are saved without their formatting
+pub fn saved_entity(entity: &Entity) -> ChunkEntity {
+    ChunkEntity {
+        id: format!("minecraft:{}", entity.entity_type.name()),
+        position: entity.pos.load(),
+        persistence_required: false,
+        custom_name: entity.custom_name().map(|name| name.to_plain_text()),
+        owner: None,
+        amount: 1,
+        item: None,
+        persistent_data: entity.persistent_data.lock().clone(),
+    }
+}
+
+/// Spawns an entity saved with its chunk. Merged items come back as full stacks, merged mobs and
+/// orbs as one
+async fn respawn(world: &Arc<World>, saved: &ChunkEntity) {
+    let Some(entity_type) = saved
+        .id
+        .strip_prefix("minecraft:")
+        .and_then(EntityType::from_name)
+    else {
+        log::debug!("Not spawning the saved entity of unknown type {}", saved.id);
+        return;
+    };
+    let spawned: Vec<Arc<dyn EntityBase>> = match entity_type {
+        EntityType::Item => {
+            let Some(stack) = saved.item.clone() else {
+                return;
+            };
+            let max_stack =
+                get_item_by_id(stack.item_id).map_or(64, |item| item.components.max_stack_size);
+            let mut amount = saved.amount;
+            let mut items: Vec<Arc<dyn EntityBase>> = Vec::new();
+            while amount > 0 {
+                let count = u8::try_from(amount)
+                    .unwrap_or(u8::MAX)
+                    .min(max_stack.max(1));
+                amount -= u32::from(count);
+                let mut stack = stack.clone();
+                stack.item_count = count;
+                items.push(Arc::new(ItemEntity::new(
+                    world.clone(),
+                    stack,
+                    saved.position,
+                    Vector3::new(0.0, 0.0, 0.0),
+                    0,
+                )));
+            }
+            items
+        }
+        EntityType::ExperienceOrb => vec![Arc::new(ExperienceOrb::new(
+            world.clone(),
+            saved.position,
+            i32::try_from(saved.amount).unwrap_or(i32::MAX),
+        ))],
+        _ => {
+            let Some(mob) = mob::summon(world, entity_type, saved.position).await else {
+                log::debug!("Not spawning the saved entity {}, it has no AI", saved.id);
+                return;
+            };
+            if let (Some(owner), Some(tameable)) = (saved.owner, mob.tameable()) {
+                tameable.tame(&mob, owner);
+            }
+            restore(&mob.living_entity.entity, saved);
+            return;
+        }
+    };
+    for entity in spawned {
+        restore(entity.get_entity(), saved);
+        world.spawn_entity(entity).await;
+    }
+}
+
+fn restore(entity: &Entity, saved: &ChunkEntity) {
+    if let Some(name) = &saved.custom_name {
+        entity.set_custom_name(Some(TextComponent::text_string(name.clone())), false);
+    }
+    entity
+        .persistent_data
+        .lock()
+        .clone_from(&saved.persistent_data);
+}
+
+impl World {
+    /// The entities which are saved with their chunk, by chunk
+    async fn saved_entities_by_chunk(&self) -> HashMap<Vector2<i32>, Vec<Arc<dyn EntityBase>>> {
+        let mut by_chunk: HashMap<_, Vec<_>> = HashMap::new();
+        for entity in self.entities.lock().await.values() {
+            if entity.chunk_entity().is_some() {
+                by_chunk
+                    .entry(entity.get_entity().chunk_pos.load())
+                    .or_default()
+                    .push(entity.clone());
+            }
+        }
+        by_chunk
+    }
+
+    /// Replaces the entities stored in the chunk with the ones which should be saved of them
+    async fn store_chunk_entities(&self, chunk: Vector2<i32>, entities: &[Arc<dyn EntityBase>]) {
+        let Some(data) = self.level.get_loaded_chunk(chunk) else {
+            return;
+        };
+        let mut saved = entities
+            .iter()
+            .filter_map(|entity| entity.chunk_entity())
+            .collect();
+        let dropped = retain_persistent(&mut saved, &ADVANCED_CONFIG.entity_persistence);
+        if dropped > 0 {
+            log::debug!("Not saving {dropped} entities of chunk {chunk:?}");
+        }
+        let mut data = data.write().await;
+        if data.entities != saved {
+            data.entities = saved;
+            data.mark_dirty();
+        }
+    }
+
+    /// Spawns the entities saved with the chunk when a player starts watching it, unless they are
+    /// still around from the last time
+    pub(super) async fn load_chunk_entities(self: &Arc<Self>, chunk: &Arc<RwLock<ChunkData>>) {
+        let mut live = self.entity_chunks.lock().await;
+        let (position, mut saved) = {
+            let chunk = chunk.read().await;
+            (chunk.position, chunk.entities.clone())
+        };
+        if !live.insert(position) {
+            return;
+        }
+        apply_load_caps(position, &mut saved, &ADVANCED_CONFIG.entity_persistence);
+        for entity in &saved {
+            respawn(self, entity).await;
+        }
+    }
+
+    /// Stores the entities of the chunks no player watches anymore in them and despawns them
+    pub(super) async fn unload_chunk_entities(&self, chunks: &[Vector2<i32>]) {
+        let mut live = self.entity_chunks.lock().await;
+        let unloading = chunks
+            .iter()
+            .filter(|chunk| self.level.should_pop_chunk(chunk) && live.remove(*chunk))
+            .copied()
+            .collect::<HashSet<_>>();
+        if unloading.is_empty() {
+            return;
+        }
+        let mut by_chunk = self.saved_entities_by_chunk().await;
+        for chunk in unloading {
+            let entities = by_chunk.remove(&chunk).unwrap_or_default();
+            self.store_chunk_entities(chunk, &entities).await;
+            for entity in entities {
+                self.despawn_entity(entity.get_entity()).await;
+            }
+        }
+    }
+
+    /// Stores the entities of the watched chunks in them, so they are saved with the chunks
+    pub(super) async fn save_chunk_entities(&self) {
+        let live = self.entity_chunks.lock().await;
+        let mut by_chunk = self.saved_entities_by_chunk().await;
+        for chunk in live.iter() {
+            let entities = by_chunk.remove(chunk).unwrap_or_default();
+            self.store_chunk_entities(*chunk, &entities).await;
+        }
+    }
+}
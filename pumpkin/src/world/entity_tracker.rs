@@ -0,0 +1,529 @@
+//! Which players see which entities, and keeping their position up to date for them.
+//!
+//! Besides the players, these are the entities the world ticks, like items.
+//! An entity is shown to a player when it is within the [`tracking_range`](EntityType::tracking_range)
+//! of its type and in a chunk the player's client has loaded. Movement is only sent every
+//! [`update_interval`](EntityType::update_interval) ticks, as a delta to what the viewers got last,
+//! falling back to a teleport if the entity moved too far or for a while.
+//!
+//! Plugins can hide entities from single players. Invisible entities hide their equipment too,
+//! unless they glow or the viewer is on their team and sees invisible teammates.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{atomic::Ordering, Arc},
+};
+
+use pumpkin_core::math::{vector2::Vector2, vector3::Vector3};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_protocol::{
+    client::play::{
+        CHeadRot, CRemoveEntities, CSetEntityMetadata, CSetEquipment, CSetPassengers, CSpawnEntity,
+        CTeleportEntitiy, CUpdateEntityPos, CUpdateEntityPosRot, CUpdateEntityRot, EquipmentSlot,
+    },
+    packet_encoder::PreparedPacket,
+    ClientPacket, VarInt,
+};
+use pumpkin_world::{cylindrical_chunk_iterator::Cylindrical, item::ItemStack};
+use uuid::Uuid;
+
+use crate::{
+    entity::{player::Player, player_set::PlayerSet, vehicle, Entity, EntityBase, Flag},
+    plugin::visibility::VISIBILITY,
+};
+
+use super::player_chunker;
+
+/// After this many ticks the position is sent absolute, so rounding errors of the deltas do not add up
+const FORCE_TELEPORT_TICKS: u32 = 400;
+
+/// The position and rotation viewers of an entity know about
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct SentState {
+    /// In 1/4096 blocks, like the deltas
+    x: i64,
+    y: i64,
+    z: i64,
+    yaw: u8,
+    pitch: u8,
+    head_yaw: u8,
+    on_ground: bool,
+}
+
+impl SentState {
+    fn of(entity: &Entity) -> Self {
+        let pos = entity.pos.load();
+        Self {
+            x: encode(pos.x),
+            y: encode(pos.y),
+            z: encode(pos.z),
+            yaw: angle(entity.yaw.load()),
+            pitch: angle(entity.pitch.load()),
+            head_yaw: angle(entity.head_yaw.load()),
+            on_ground: entity.on_ground.load(Ordering::Relaxed),
+        }
+    }
+
+    fn position(self) -> Vector3<f64> {
+        Vector3::new(decode(self.x), decode(self.y), decode(self.z))
+    }
+}
+
+fn player_spawn(player: &Player, sent: SentState) -> PreparedPacket {
+    PreparedPacket::new(&CSpawnEntity::new(
+        player.entity_id().into(),
+        player.gameprofile.id,
+        (EntityType::Player as i32).into(),
+        decode(sent.x),
+        decode(sent.y),
+        decode(sent.z),
+        degrees(sent.pitch),
+        degrees(sent.yaw),
+        degrees(sent.head_yaw),
+        0.into(),
+        0.0,
+        0.0,
+        0.0,
+    ))
+}
+
+type Equipment = Vec<(EquipmentSlot, Option<ItemStack>)>;
+
+/// How an entity looks to its viewers
+struct Looks<'a> {
+    equipment: Equipment,
+    /// Invisible entities which don't glow hide their equipment
+    hides_equipment: bool,
+    /// The team of the entity, if its teammates see it while it is invisible
+    team: Option<&'a str>,
+}
+
+impl<'a> Looks<'a> {
+    fn of(entity: &Entity, equipment: Equipment, team: Option<&'a str>) -> Self {
+        Self {
+            equipment,
+            hides_equipment: entity.has_flag(Flag::Invisible) && !entity.has_flag(Flag::Glowing),
+            team,
+        }
+    }
+
+    fn hides_equipment_from(&self, viewer: &Viewer) -> bool {
+        self.hides_equipment && (self.team.is_none() || self.team != viewer.team.as_deref())
+    }
+
+    /// The equipment with every slot emptied
+    fn hidden_equipment(&self) -> Equipment {
+        self.equipment
+            .iter()
+            .map(|(slot, _)| (*slot, None))
+            .collect()
+    }
+}
+
+fn encode(coordinate: f64) -> i64 {
+    (coordinate * 4096.0).round() as i64
+}
+
+// coordinates are clamped to 30 million blocks, which fits into the mantissa
+#[allow(clippy::cast_precision_loss)]
+fn decode(coordinate: i64) -> f64 {
+    coordinate as f64 / 4096.0
+}
+
+fn angle(degrees: f32) -> u8 {
+    (degrees * 256.0 / 360.0).floor() as i32 as u8
+}
+
+fn degrees(angle: u8) -> f32 {
+    f32::from(angle) * 360.0 / 256.0
+}
+
+struct TrackedEntity {
+    viewers: HashSet<Uuid>,
+    /// The viewers the equipment is hidden from
+    hidden_equipment: HashSet<Uuid>,
+    sent: SentState,
+    equipment: Equipment,
+    ticks: u32,
+    ticks_since_teleport: u32,
+}
+
+impl TrackedEntity {
+    fn new(entity: &Entity) -> Self {
+        Self {
+            viewers: HashSet::new(),
+            hidden_equipment: HashSet::new(),
+            sent: SentState::of(entity),
+            equipment: Vec::new(),
+            ticks: 0,
+            ticks_since_teleport: 0,
+        }
+    }
+
+    /// The packets moving the entity from what was sent last to where it is now
+    fn movement(&mut self, entity: &Entity) -> Vec<PreparedPacket> {
+        let now = SentState::of(entity);
+        let old = self.sent;
+        if now == old {
+            return Vec::new();
+        }
+        let id = entity.entity_id;
+        let moved = (now.x, now.y, now.z) != (old.x, old.y, old.z);
+        let rotated = (now.yaw, now.pitch) != (old.yaw, old.pitch);
+        let mut packets = Vec::with_capacity(2);
+
+        let delta = (
+            i16::try_from(now.x - old.x),
+            i16::try_from(now.y - old.y),
+            i16::try_from(now.z - old.z),
+        );
+        match delta {
+            (Ok(dx), Ok(dy), Ok(dz)) if self.ticks_since_teleport < FORCE_TELEPORT_TICKS => {
+                if moved && rotated {
+                    packets.push(PreparedPacket::new(&CUpdateEntityPosRot::new(
+                        id.into(),
+                        dx,
+                        dy,
+                        dz,
+                        now.yaw,
+                        now.pitch,
+                        now.on_ground,
+                    )));
+                } else if moved || now.on_ground != old.on_ground {
+                    packets.push(PreparedPacket::new(&CUpdateEntityPos::new(
+                        id.into(),
+                        dx,
+                        dy,
+                        dz,
+                        now.on_ground,
+                    )));
+                } else if rotated {
+                    packets.push(PreparedPacket::new(&CUpdateEntityRot::new(
+                        id.into(),
+                        now.yaw,
+                        now.pitch,
+                        now.on_ground,
+                    )));
+                }
+            }
+            // moved too far for a delta, or deltas were sent for too long
+            _ => {
+                let pos = entity.pos.load();
+                packets.push(PreparedPacket::new(&CTeleportEntitiy::new(
+                    id.into(),
+                    pos.x,
+                    pos.y,
+                    pos.z,
+                    now.yaw,
+                    now.pitch,
+                    now.on_ground,
+                )));
+                self.ticks_since_teleport = 0;
+            }
+        }
+        if now.head_yaw != old.head_yaw {
+            packets.push(PreparedPacket::new(&CHeadRot::new(id.into(), now.head_yaw)));
+        }
+        self.sent = now;
+        packets
+    }
+}
+
+/// What a player can see, taken once per tick
+struct Viewer {
+    player: Arc<Player>,
+    pos: Vector3<f64>,
+    view: Cylindrical,
+    /// The entities plugins hide from the player
+    hidden: HashSet<EntityId>,
+    /// The team whose invisible members the player sees
+    team: Option<String>,
+}
+
+impl Viewer {
+    fn can_see(&self, entity: &Entity) -> bool {
+        let range = (i32::from(entity.entity_type.tracking_range()) * 16)
+            .min(i32::from(self.view.view_distance.saturating_sub(1)) * 16);
+        let pos = entity.pos.load();
+        let (dx, dz) = (pos.x - self.pos.x, pos.z - self.pos.z);
+        let chunk = entity.chunk_pos.load();
+        dx.mul_add(dx, dz * dz) <= f64::from(range * range)
+            && self.view.is_within_distance(chunk.x, chunk.z)
+            && !self.hidden.contains(&entity.entity_id)
+    }
+}
+
+/// Packets to send after the tracker is unlocked
+#[derive(Default)]
+struct Outbox {
+    packets: Vec<(Vec<Uuid>, PreparedPacket)>,
+    removed: HashMap<Uuid, Vec<VarInt>>,
+}
+
+impl Outbox {
+    fn push<P: ClientPacket>(&mut self, to: Vec<Uuid>, packet: &P) {
+        self.packets.push((to, PreparedPacket::new(packet)));
+    }
+
+    async fn send(self, viewers: &[Viewer]) {
+        let player = |id: &Uuid| {
+            viewers
+                .iter()
+                .find(|viewer| viewer.player.gameprofile.id == *id)
+                .map(|viewer| viewer.player.clone())
+        };
+        for (id, entity_ids) in self.removed {
+            if let Some(player) = player(&id) {
+                player
+                    .client
+                    .send_packet(&CRemoveEntities::new(&entity_ids))
+                    .await;
+            }
+        }
+        for (to, packet) in self.packets {
+            PlayerSet::new(to.iter().filter_map(player).collect())
+                .send_prepared(packet)
+                .await;
+        }
+    }
+}
+
+/// The entities of a world and who sees them
+#[derive(Default)]
+pub struct EntityTracker {
+    entities: parking_lot::Mutex<HashMap<EntityId, TrackedEntity>>,
+}
+
+impl EntityTracker {
+    /// Updates who sees which entity and sends the movement since the last update.
+    /// Players see each other but not themselves. `teams` are the teams of the players whose
+    /// teammates see them while they are invisible
+    pub async fn tick(
+        &self,
+        players: &PlayerSet,
+        others: &[Arc<dyn EntityBase>],
+        teams: &HashMap<String, String>,
+    ) {
+        let mut viewers = Vec::with_capacity(players.len());
+        let mut equipment = Vec::with_capacity(players.len());
+        for player in players.iter() {
+            let watched = player.watched_section.load();
+            let view_distance = player_chunker::get_view_distance(player);
+            viewers.push(Viewer {
+                player: player.clone(),
+                pos: player.living_entity.entity.pos.load(),
+                view: Cylindrical::new(Vector2::new(watched.x, watched.z), view_distance),
+                hidden: VISIBILITY.hidden_from(player.gameprofile.id),
+                team: teams.get(&player.gameprofile.name).cloned(),
+            });
+            equipment.push(player.worn_equipment().await);
+        }
+
+        let mut outbox = Outbox::default();
+        {
+            let mut entities = self.entities.lock();
+            let mut spawned = HashMap::new();
+            // entities which left the world
+            entities.retain(|id, tracked| {
+                let exists = players.iter().any(|player| player.entity_id() == *id)
+                    || others
+                        .iter()
+                        .any(|entity| entity.get_entity().entity_id == *id);
+                if !exists {
+                    for viewer in &tracked.viewers {
+                        outbox.removed.entry(*viewer).or_default().push(VarInt(*id));
+                    }
+                }
+                exists
+            });
+
+            for (player, equipment) in players.iter().zip(equipment) {
+                let entity = &player.living_entity.entity;
+                let team = teams.get(&player.gameprofile.name).map(String::as_str);
+                Self::update(
+                    &mut entities,
+                    &mut outbox,
+                    &mut spawned,
+                    &viewers,
+                    entity,
+                    &Looks::of(entity, equipment, team),
+                    Some(player.gameprofile.id),
+                    |sent| vec![player_spawn(player, sent)],
+                );
+            }
+            for entity in others {
+                Self::update(
+                    &mut entities,
+                    &mut outbox,
+                    &mut spawned,
+                    &viewers,
+                    entity.get_entity(),
+                    &Looks::of(entity.get_entity(), entity.equipment(), None),
+                    None,
+                    |sent| vec![entity.spawn_packet(sent.position())],
+                );
+            }
+
+            let vehicles = players
+                .iter()
+                .map(|player| &player.living_entity.entity)
+                .chain(others.iter().map(|entity| entity.get_entity()));
+            for vehicle in vehicles {
+                Self::seat_passengers(&mut outbox, &spawned, vehicle);
+            }
+        }
+        outbox.send(&viewers).await;
+    }
+
+    /// Tells the players which just started seeing the vehicle or one of its passengers who rides
+    /// it, after all of them were spawned
+    fn seat_passengers(
+        outbox: &mut Outbox,
+        spawned: &HashMap<EntityId, Vec<Uuid>>,
+        vehicle: &Entity,
+    ) {
+        let Some(passengers) = vehicle::passengers_of(vehicle) else {
+            return;
+        };
+        let mut to: Vec<Uuid> = std::iter::once(vehicle.entity_id)
+            .chain(passengers.iter().map(|passenger| passenger.0))
+            .filter_map(|id| spawned.get(&id))
+            .flatten()
+            .copied()
+            .collect();
+        to.sort_unstable();
+        to.dedup();
+        if !to.is_empty() {
+            outbox.push(
+                to,
+                &CSetPassengers::new(vehicle.entity_id.into(), &passengers),
+            );
+        }
+    }
+
+    /// Sends the movement and equipment of the entity and spawns or removes it for the players
+    /// which started or stopped seeing it, `spawn` gives the packets showing it. The player
+    /// `owner` is the entity and never sees it. The new viewers are added to `spawned`
+    #[expect(clippy::too_many_arguments)]
+    fn update(
+        entities: &mut HashMap<EntityId, TrackedEntity>,
+        outbox: &mut Outbox,
+        spawned: &mut HashMap<EntityId, Vec<Uuid>>,
+        viewers: &[Viewer],
+        entity: &Entity,
+        looks: &Looks,
+        owner: Option<Uuid>,
+        spawn: impl Fn(SentState) -> Vec<PreparedPacket>,
+    ) {
+        let tracked = entities
+            .entry(entity.entity_id)
+            .or_insert_with(|| TrackedEntity::new(entity));
+        tracked.ticks = tracked.ticks.wrapping_add(1);
+        tracked.ticks_since_teleport = tracked.ticks_since_teleport.saturating_add(1);
+        // viewers which left are not sent anything anymore
+        tracked.viewers.retain(|id| {
+            viewers
+                .iter()
+                .any(|viewer| viewer.player.gameprofile.id == *id)
+        });
+        let seeing = &tracked.viewers;
+        tracked.hidden_equipment.retain(|id| seeing.contains(id));
+        let equipment_changed = looks.equipment != tracked.equipment;
+        if equipment_changed {
+            tracked.equipment.clone_from(&looks.equipment);
+        }
+
+        // movement goes to the viewers which already see the entity
+        let interval = entity.entity_type.update_interval();
+        if tracked.ticks.is_multiple_of(interval) {
+            let to: Vec<_> = tracked.viewers.iter().copied().collect();
+            for packet in tracked.movement(entity) {
+                if !to.is_empty() {
+                    outbox.packets.push((to.clone(), packet));
+                }
+            }
+        }
+
+        for viewer in viewers {
+            let id = viewer.player.gameprofile.id;
+            if Some(id) == owner {
+                continue;
+            }
+            let sees = viewer.can_see(entity);
+            let hide_equipment = looks.hides_equipment_from(viewer);
+            if sees && tracked.viewers.insert(id) {
+                // spawned where the other viewers see it, so the next movement applies to all of them
+                for packet in spawn(tracked.sent) {
+                    outbox.packets.push((vec![id], packet));
+                }
+                if hide_equipment {
+                    tracked.hidden_equipment.insert(id);
+                } else if !looks.equipment.is_empty() {
+                    outbox.push(
+                        vec![id],
+                        &CSetEquipment::new(entity.entity_id.into(), &looks.equipment),
+                    );
+                }
+                let metadata = entity.metadata();
+                if !metadata.is_empty() {
+                    outbox.push(
+                        vec![id],
+                        &CSetEntityMetadata::new(entity.entity_id.into(), &metadata),
+                    );
+                }
+                spawned.entry(entity.entity_id).or_default().push(id);
+            } else if !sees && tracked.viewers.remove(&id) {
+                tracked.hidden_equipment.remove(&id);
+                outbox
+                    .removed
+                    .entry(id)
+                    .or_default()
+                    .push(VarInt(entity.entity_id));
+            } else if sees {
+                let was_hidden = tracked.hidden_equipment.contains(&id);
+                if hide_equipment != was_hidden || (equipment_changed && !hide_equipment) {
+                    let equipment = if hide_equipment {
+                        tracked.hidden_equipment.insert(id);
+                        looks.hidden_equipment()
+                    } else {
+                        tracked.hidden_equipment.remove(&id);
+                        looks.equipment.clone()
+                    };
+                    if !equipment.is_empty() {
+                        outbox.push(
+                            vec![id],
+                            &CSetEquipment::new(entity.entity_id.into(), &equipment),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// The players seeing the entity
+    pub fn viewers(&self, entity_id: EntityId) -> Vec<Uuid> {
+        self.entities
+            .lock()
+            .get(&entity_id)
+            .map(|tracked| tracked.viewers.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Stops tracking the entity, returning the players which have to remove it
+    pub fn remove(&self, entity_id: EntityId) -> Vec<Uuid> {
+        self.entities
+            .lock()
+            .remove(&entity_id)
+            .map(|tracked| tracked.viewers.into_iter().collect())
+            .unwrap_or_default()
+    }
+
+    /// The client of the player dropped all entities, e.g. when respawning,
+    /// so they are spawned for it again on the next tick
+    pub fn forget_viewer(&self, viewer: Uuid) {
+        for tracked in self.entities.lock().values_mut() {
+            tracked.viewers.remove(&viewer);
+            tracked.hidden_equipment.remove(&viewer);
+        }
+    }
+}
@@ -1,45 +1,85 @@
 use std::{
-    collections::{hash_map::Entry, HashMap, VecDeque},
+    collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
     sync::Arc,
 };
 
+mod block_changes;
+pub mod block_entity;
+pub mod chunk_entities;
+pub mod entity_tracker;
+pub mod particle;
 pub mod player_chunker;
+pub mod pregen;
+pub mod protection;
+pub mod redstone;
+pub mod relight;
+pub mod spawner;
+pub mod time;
+pub mod vibration;
 
 use crate::{
+    client::chat_session,
     command::{client_cmd_suggestions, dispatcher::CommandDispatcher},
     entity::{
         player::{ChunkHandleWrapper, Player},
-        Entity,
+        player_set::PlayerSet,
+        sound::sound_packet,
+        tab_list::{self, ListedPlayer},
+        vehicle, Entity, EntityBase,
     },
     error::PumpkinError,
+    plugin::{self, content::CONTENT, EVENTS},
+    server::profiler::PROFILER,
 };
-use pumpkin_config::BasicConfiguration;
-use pumpkin_core::math::vector2::Vector2;
+use block_changes::BlockChanges;
+use block_entity::BlockEntity;
+use entity_tracker::EntityTracker;
+use player_chunker::SimulationArea;
+use pumpkin_api::{
+    event::{block::BlockBreakEvent, world::ChunkLoadEvent},
+    Event,
+};
+use pumpkin_config::{
+    runtime_config, BasicConfiguration, GameRuleSetting, WorldConfig, ADVANCED_CONFIG, BASIC_CONFIG,
+};
+use pumpkin_core::math::{boundingbox::BoundingBox, get_section_cord, vector2::Vector2};
 use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+use pumpkin_core::nbt::{Compound, Value};
+use pumpkin_core::persistent_data::{self, PersistentDataContainer, PersistentDataType};
 use pumpkin_core::text::{color::NamedColor, TextComponent};
-use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_entity::EntityId;
 use pumpkin_protocol::{
-    client::play::{CBlockUpdate, CSoundEffect, CWorldEvent},
+    client::play::{CBlockUpdate, CEntityStatus, CWorldEvent, SoundEvent},
     SoundCategory,
 };
 use pumpkin_protocol::{
     client::play::{
-        CChunkData, CGameEvent, CLogin, CPlayerInfoUpdate, CRemoveEntities, CRemovePlayerInfo,
-        CSetEntityMetadata, CSpawnEntity, GameEvent, Metadata, PlayerAction,
+        CGameEvent, CLogin, CPlayerInfoUpdate, CRemoveEntities, CRemovePlayerInfo,
+        CSetEntityMetadata, GameEvent,
     },
-    ClientPacket, VarInt,
+    ClientPacket,
+};
+use pumpkin_world::chunk::{light, ChunkData};
+use pumpkin_world::game_rules::{
+    GameRuleError, GameRuleValue, GameRules, DO_IMMEDIATE_RESPAWN, DO_LIMITED_CRAFTING,
+    REDUCED_DEBUG_INFO,
 };
-use pumpkin_world::chunk::ChunkData;
 use pumpkin_world::level::Level;
+use pumpkin_world::pathfinding::{CollisionMap, PathNodeType};
 use pumpkin_world::{
     block::block_registry::{
-        get_block_and_state_by_state_id, get_block_by_state_id, get_state_by_state_id,
+        get_block_and_state_by_state_id, get_block_by_state_id, get_block_entity_ident,
+        get_state_by_state_id,
     },
     coordinates::ChunkRelativeBlockCoordinates,
+    player_data::PlayerData,
 };
-use rand::{thread_rng, Rng};
+use pumpkin_world::{WORLD_LOWEST_Y, WORLD_MAX_Y};
+use relight::RelitChunks;
 use scoreboard::Scoreboard;
+use spawner::SpecialSpawners;
 use thiserror::Error;
+use time::WorldTime;
 use tokio::sync::{mpsc::Receiver, Mutex};
 use tokio::{
     sync::{mpsc, RwLock},
@@ -50,11 +90,21 @@ use worldborder::Worldborder;
 pub mod scoreboard;
 pub mod worldborder;
 
+/// The name the custom data of plugins on the world is saved as, see [`Level::read_persistent_data`]
+const WORLD_DATA: &str = "world";
+
 type ChunkReceiver = (
     Vec<(Vector2<i32>, JoinHandle<()>)>,
     Receiver<Arc<RwLock<ChunkData>>>,
 );
 
+/// Whether both states are of the same block, its block entity is kept when only its state changes
+fn same_block(old: u16, new: u16) -> bool {
+    old == new
+        || get_block_by_state_id(old).map(|block| block.id)
+            == get_block_by_state_id(new).map(|block| block.id)
+}
+
 #[derive(Debug, Error)]
 pub enum GetBlockError {
     BlockOutOfWorldBounds,
@@ -99,48 +149,322 @@ pub struct World {
     pub scoreboard: Mutex<Scoreboard>,
     /// The world's worldborder, defining the playable area and controlling its expansion or contraction.
     pub worldborder: Mutex<Worldborder>,
-    // TODO: entities
+    /// The world's game rules, controlling gameplay behaviour such as `keepInventory` or `mobGriefing`.
+    pub game_rules: RwLock<GameRules>,
+    /// The settings of the world, the global ones with the world's overrides.
+    pub config: WorldConfig,
+    /// Which players see which entities, and syncs their movement.
+    pub entity_tracker: EntityTracker,
+    /// Custom data of plugins, saved when the server stops
+    pub persistent_data: parking_lot::Mutex<PersistentDataContainer>,
+    /// The storages of `/data` by namespace and path, namespaces are read when they are used
+    command_storage: parking_lot::Mutex<HashMap<String, HashMap<String, Compound>>>,
+    /// The entities besides players, like dropped items
+    pub entities: Mutex<HashMap<EntityId, Arc<dyn EntityBase>>>,
+    /// The blocks which work by themselves, like brewing stands
+    pub block_entities: Mutex<HashMap<WorldPosition, BlockEntity>>,
+    /// Patrols, sieges, wandering traders, cats and phantoms
+    spawners: SpecialSpawners,
+    /// The blocks changed this tick, which are sent when it ends
+    block_changes: BlockChanges,
+    /// The chunks lit this tick, whose light is sent when it ends
+    relit_chunks: Arc<RelitChunks>,
+    /// The chunks whose saved entities were spawned, until no player watches them anymore
+    entity_chunks: Mutex<HashSet<Vector2<i32>>>,
+    pub time: WorldTime,
 }
 
 impl World {
     #[must_use]
-    pub fn load(level: Level) -> Self {
+    pub fn load(mut level: Level, config: WorldConfig) -> Self {
+        let world = level.name().to_string();
+        level.set_chunk_memory_budget(ADVANCED_CONFIG.chunk_cache.memory_budget_mb * 1024 * 1024);
+        level.set_chunk_load_listener(Arc::new(move |position, generated, duration| {
+            let stack = if generated {
+                "chunks;generate"
+            } else {
+                "chunks;read"
+            };
+            PROFILER.record(stack, duration);
+            plugin::fire(ChunkLoadEvent {
+                world: world.clone(),
+                position,
+                generated,
+            });
+        }));
+        let relit_chunks = Arc::new(RelitChunks::default());
+        let relit = relit_chunks.clone();
+        level.set_relight_listener(Arc::new(move |position| relit.push(position)));
+        let persistent_data = level.read_persistent_data(WORLD_DATA);
+        let level_data = level.level_data();
+        // the config only sets the rules of new worlds
+        let game_rules = level_data.game_rules().unwrap_or_else(|| {
+            let mut game_rules = GameRules::new();
+            for (name, setting) in &config.game_rules {
+                let value = match *setting {
+                    GameRuleSetting::Bool(value) => GameRuleValue::Bool(value),
+                    GameRuleSetting::Int(value) => GameRuleValue::Int(value),
+                };
+                if let Err(err) = game_rules.set_value(name, value) {
+                    log::warn!("Couldn't set game rule {name} of {}: {err}", level.name());
+                }
+            }
+            game_rules
+        });
+        let worldborder = level_data.border().map_or_else(
+            || Worldborder::new(0.0, 0.0, 29_999_984.0, 0, 0, 0),
+            |border| Worldborder::from_saved(&border),
+        );
+        let spawners = SpecialSpawners::new(level_data.spawner_timers());
+        let (age, day_time) = level_data.time();
         Self {
             level: Arc::new(level),
             current_players: Arc::new(Mutex::new(HashMap::new())),
             scoreboard: Mutex::new(Scoreboard::new()),
-            worldborder: Mutex::new(Worldborder::new(0.0, 0.0, 29_999_984.0, 0, 0, 0)),
+            worldborder: Mutex::new(worldborder),
+            game_rules: RwLock::new(game_rules),
+            config,
+            entity_tracker: EntityTracker::default(),
+            persistent_data: parking_lot::Mutex::new(persistent_data),
+            command_storage: parking_lot::Mutex::default(),
+            entities: Mutex::new(HashMap::new()),
+            block_entities: Mutex::new(HashMap::new()),
+            spawners,
+            block_changes: BlockChanges::default(),
+            relit_chunks,
+            entity_chunks: Mutex::new(HashSet::new()),
+            time: WorldTime::new(age, day_time),
+        }
+    }
+
+    /// Reads the custom data plugins stored on the player
+    #[must_use]
+    pub fn read_player_data(&self, uuid: uuid::Uuid) -> PersistentDataContainer {
+        self.level.read_persistent_data(&format!("players/{uuid}"))
+    }
+
+    pub fn write_player_data(&self, player: &Player) {
+        self.level.write_persistent_data(
+            &format!("players/{}", player.gameprofile.id),
+            &player.living_entity.entity.persistent_data.lock(),
+        );
+    }
+
+    /// Reads what vanilla saves about the player, like their position and inventory
+    #[must_use]
+    pub fn read_saved_player(&self, uuid: uuid::Uuid) -> PlayerData {
+        self.level.read_player_data(&uuid)
+    }
+
+    /// Saves the player in the world's `playerdata` folder, so vanilla can read them too
+    pub async fn save_player(&self, player: &Player) {
+        let data = player.saved_data().await;
+        self.level.write_player_data(&player.gameprofile.id, &data);
+        *player.saved.lock() = data;
+    }
+
+    /// Saves the world's `level.dat` with its current time, game rules, border and spawner timers
+    pub async fn save_level_data(&self) {
+        let mut data = self.level.level_data();
+        data.set_time(self.time.age(), self.time.day_time());
+        data.set_game_rules(&*self.game_rules.read().await);
+        data.set_border(&self.worldborder.lock().await.to_saved());
+        data.set_difficulty(self.config.difficulty);
+        data.set_spawner_timers(&self.spawners.timers());
+        self.level.write_level_data(data);
+    }
+
+    /// Saves the loaded chunks, the custom data plugins stored on the world and its players, and
+    /// the players
+    pub async fn save_persistent_data(&self) {
+        self.save_chunk_entities().await;
+        self.save_block_entities().await;
+        let level = self.level.clone();
+        if let Err(err) = tokio::task::spawn_blocking(move || level.save_chunks()).await {
+            log::error!("Failed to save the chunks: {err}");
+        }
+        self.save_level_data().await;
+        self.level
+            .write_persistent_data(WORLD_DATA, &self.persistent_data.lock());
+        for player in self.current_players.lock().await.values() {
+            self.write_player_data(player);
+            self.save_player(player).await;
+        }
+        for (namespace, contents) in self.command_storage.lock().iter() {
+            self.level
+                .write_command_storage(namespace, contents.clone());
+        }
+    }
+
+    /// Runs `f` with the `/data` storage, which is empty if it doesn't exist yet
+    pub fn with_command_storage<T>(
+        &self,
+        namespace: &str,
+        path: &str,
+        f: impl FnOnce(&mut Compound) -> T,
+    ) -> T {
+        let mut storages = self.command_storage.lock();
+        let namespace = storages
+            .entry(namespace.to_string())
+            .or_insert_with(|| self.level.read_command_storage(namespace));
+        let storage = namespace.entry(path.to_string()).or_default();
+        let result = f(storage);
+        if storage.is_empty() {
+            namespace.remove(path);
+        }
+        result
+    }
+
+    /// The NBT of the block entity at the position like `/data get block` shows it, `None` if the
+    /// block has no block entity. Changes to it only keep the custom data of plugins
+    pub async fn block_entity_nbt(&self, position: WorldPosition) -> Option<Compound> {
+        let state = self.get_block_state(position).await.ok()?;
+        let id = get_block_entity_ident(state.block_entity_type?)?;
+        let (chunk, _) = position.chunk_and_chunk_relative_position();
+        let chunk = self.receive_chunk(chunk).await;
+        let chunk = chunk.read().await;
+        let mut nbt = chunk
+            .block_entities
+            .get(&position)
+            .cloned()
+            .unwrap_or_default();
+        nbt.extend([
+            ("id".to_string(), Value::String(id.to_string())),
+            ("x".to_string(), Value::Int(position.0.x)),
+            ("y".to_string(), Value::Int(position.0.y)),
+            ("z".to_string(), Value::Int(position.0.z)),
+        ]);
+        if let Some(data) = chunk.block_entity_data.get(&position) {
+            nbt.insert(persistent_data::NBT_KEY.to_string(), data.clone().to_nbt());
+        }
+        Some(nbt)
+    }
+
+    /// Applies the NBT to the block entity at the position, of which only the custom data of
+    /// plugins is kept. Returns false if the block has no block entity
+    pub async fn set_block_entity_nbt(&self, position: WorldPosition, nbt: &Compound) -> bool {
+        if self.block_entity_nbt(position).await.is_none() {
+            return false;
+        }
+        let data = nbt
+            .get(persistent_data::NBT_KEY)
+            .and_then(PersistentDataContainer::from_nbt)
+            .unwrap_or_default();
+        let (chunk, _) = position.chunk_and_chunk_relative_position();
+        let chunk = self.receive_chunk(chunk).await;
+        let mut chunk = chunk.write().await;
+        chunk.mark_dirty();
+        if data.is_empty() {
+            chunk.block_entity_data.remove(&position);
+        } else {
+            chunk.block_entity_data.insert(position, data);
+        }
+        true
+    }
+
+    /// The view distance in the world, at most the server's one
+    #[must_use]
+    pub fn view_distance(&self) -> u8 {
+        self.config.view_distance(runtime_config().view_distance)
+    }
+
+    /// How far around players entities and block entities are ticked, at most the view distance
+    #[must_use]
+    pub fn simulation_distance(&self) -> u8 {
+        BASIC_CONFIG.simulation_distance.min(self.view_distance())
+    }
+
+    /// Sets a game rule by name and syncs rules the client cares about to every player in the world.
+    pub async fn set_game_rule(
+        &self,
+        name: &str,
+        value: GameRuleValue,
+    ) -> Result<(), GameRuleError> {
+        self.game_rules.write().await.set_value(name, value)?;
+
+        match (name, value) {
+            (name, GameRuleValue::Bool(value)) if name == DO_IMMEDIATE_RESPAWN.name => {
+                self.broadcast_packet_all(&CGameEvent::new(
+                    GameEvent::EnabledRespawnScreen,
+                    if value { 1.0 } else { 0.0 },
+                ))
+                .await;
+            }
+            (name, GameRuleValue::Bool(value)) if name == DO_LIMITED_CRAFTING.name => {
+                self.broadcast_packet_all(&CGameEvent::new(
+                    GameEvent::LimitedCrafting,
+                    if value { 1.0 } else { 0.0 },
+                ))
+                .await;
+            }
+            (name, GameRuleValue::Bool(value)) if name == REDUCED_DEBUG_INFO.name => {
+                let current_players = self.current_players.lock().await;
+                for player in current_players.values() {
+                    player
+                        .client
+                        .send_packet(&CEntityStatus::new(
+                            player.entity_id(),
+                            if value { 22 } else { 23 },
+                        ))
+                        .await;
+                }
+            }
+            _ => {}
         }
+        Ok(())
+    }
+
+    /// Returns all players currently in the world, for applying actions to them in bulk.
+    pub async fn players(&self) -> PlayerSet {
+        self.current_players
+            .lock()
+            .await
+            .values()
+            .cloned()
+            .collect()
     }
 
     /// Broadcasts a packet to all connected players within the world.
     ///
     /// Sends the specified packet to every player currently logged in to the world.
     ///
-    /// **Note:** This function acquires a lock on the `current_players` map, ensuring thread safety.
+    /// **Note:** The packet is serialized once, the `current_players` lock is released before sending.
     pub async fn broadcast_packet_all<P>(&self, packet: &P)
     where
         P: ClientPacket,
     {
-        let current_players = self.current_players.lock().await;
-        for player in current_players.values() {
-            player.client.send_packet(packet).await;
-        }
+        self.players().await.send_packet(packet).await;
     }
 
     /// Broadcasts a packet to all connected players within the world, excluding the specified players.
     ///
     /// Sends the specified packet to every player currently logged in to the world, excluding the players listed in the `except` parameter.
     ///
-    /// **Note:** This function acquires a lock on the `current_players` map, ensuring thread safety.
+    /// **Note:** The packet is serialized once, the `current_players` lock is released before sending.
     pub async fn broadcast_packet_except<P>(&self, except: &[uuid::Uuid], packet: &P)
     where
         P: ClientPacket,
     {
-        let current_players = self.current_players.lock().await;
-        for (_, player) in current_players.iter().filter(|c| !except.contains(c.0)) {
-            player.client.send_packet(packet).await;
-        }
+        self.players()
+            .await
+            .except(except)
+            .send_packet(packet)
+            .await;
+    }
+
+    /// Sends a packet to every player seeing the entity, including the entity itself if it is a player.
+    pub async fn broadcast_packet_viewers<P>(&self, entity_id: EntityId, packet: &P)
+    where
+        P: ClientPacket,
+    {
+        let viewers = self.entity_tracker.viewers(entity_id);
+        self.players()
+            .await
+            .filter(|player| {
+                player.entity_id() == entity_id || viewers.contains(&player.gameprofile.id)
+            })
+            .send_packet(packet)
+            .await;
     }
 
     pub async fn play_sound(
@@ -149,25 +473,92 @@ impl World {
         category: SoundCategory,
         posistion: &Vector3<f64>,
     ) {
-        let seed = thread_rng().gen::<f64>();
-        self.broadcast_packet_all(&CSoundEffect::new(
-            VarInt(i32::from(sound_id)),
+        self.broadcast_packet_all(&sound_packet(
+            SoundEvent::Registry(sound_id),
             category,
-            posistion.x,
-            posistion.y,
-            posistion.z,
+            posistion,
             1.0,
             1.0,
-            seed,
         ))
         .await;
     }
 
-    pub async fn tick(&self) {
-        let current_players = self.current_players.lock().await;
-        for player in current_players.values() {
-            player.tick().await;
-        }
+    pub async fn tick(self: &Arc<Self>) {
+        self.tick_time().await;
+        let players = self.players().await;
+        PROFILER
+            .time("tick;worlds;players", async {
+                for player in players.iter() {
+                    player.tick().await;
+                }
+            })
+            .await;
+        // only what is within the simulation distance of a player is ticked
+        let simulated = SimulationArea::new(players.iter());
+        let entities: Vec<_> = self
+            .entities
+            .lock()
+            .await
+            .values()
+            .filter(|entity| simulated.contains(entity.get_entity().chunk_pos.load()))
+            .cloned()
+            .collect();
+        PROFILER
+            .time("tick;worlds;entities", async {
+                for entity in &entities {
+                    if !entity.tick().await {
+                        self.despawn_entity(entity.get_entity()).await;
+                    }
+                }
+                // passengers sit where their vehicle moved to
+                let vehicles = players
+                    .iter()
+                    .map(|player| &player.living_entity.entity)
+                    .chain(entities.iter().map(|entity| entity.get_entity()))
+                    .filter(|entity| {
+                        entity.vehicle_id.load().is_none() && !entity.passengers.lock().is_empty()
+                    });
+                for vehicle in vehicles {
+                    vehicle::position_passengers(vehicle).await;
+                }
+            })
+            .await;
+        PROFILER
+            .time(
+                "tick;worlds;block_entities",
+                self.tick_block_entities(&simulated),
+            )
+            .await;
+        PROFILER
+            .time("tick;worlds;spawners", self.spawners.tick(self))
+            .await;
+        let entities: Vec<_> = self.entities.lock().await.values().cloned().collect();
+        let teams = self.scoreboard.lock().await.friendly_invisible_teams();
+        PROFILER
+            .time(
+                "tick;worlds;entity_tracker",
+                self.entity_tracker.tick(&players, &entities, &teams),
+            )
+            .await;
+        PROFILER
+            .time("tick;worlds;block_changes", self.flush_block_changes())
+            .await;
+        PROFILER
+            .time("tick;worlds;light", self.flush_relit_chunks())
+            .await;
+    }
+
+    /// Adds the entity, the entity tracker shows it to the players near it
+    pub async fn spawn_entity(&self, entity: Arc<dyn EntityBase>) {
+        let id = entity.get_entity().entity_id;
+        self.entities.lock().await.insert(id, entity);
+    }
+
+    /// Removes an entity which is not a player, its passengers get off
+    pub async fn despawn_entity(&self, entity: &Entity) {
+        vehicle::detach(entity).await;
+        self.entities.lock().await.remove(&entity.entity_id);
+        self.remove_entity(entity).await;
     }
 
     /// Gets the y position of the first non air block from the top down
@@ -185,7 +576,34 @@ impl World {
         319
     }
 
-    #[expect(clippy::too_many_lines)]
+    /// Where the player left if they were in this world before, or else the spawn
+    async fn spawn_position(&self, player: &Player) -> (Vector3<f64>, f32, f32) {
+        let (position, (yaw, pitch)) = {
+            let saved = player.saved.lock();
+            let in_world = saved
+                .dimension()
+                .is_none_or(|dimension| dimension == "minecraft:overworld");
+            (
+                saved.position().filter(|_| in_world),
+                saved.rotation().unwrap_or((10.0, 10.0)),
+            )
+        };
+        if let Some(position) = position {
+            return (position, yaw, pitch);
+        }
+        let spawn = self
+            .level
+            .level_data()
+            .spawn()
+            .map_or(Vector3::new(10, 120, 10), |(spawn, _)| spawn);
+        let mut position = Vector3::new(f64::from(spawn.x), 120.0, f64::from(spawn.z));
+        let top = self
+            .get_top_block(Vector2::new(position.x as i32, position.z as i32))
+            .await;
+        position.y = f64::from(top + 1);
+        (position, yaw, pitch)
+    }
+
     pub async fn spawn_player(
         &self,
         base_config: &BasicConfiguration,
@@ -201,19 +619,36 @@ impl World {
             entity_id
         );
 
+        let (reduced_debug_info, immediate_respawn, limited_crafting) = {
+            let game_rules = self.game_rules.read().await;
+            (
+                game_rules.get(&REDUCED_DEBUG_INFO),
+                game_rules.get(&DO_IMMEDIATE_RESPAWN),
+                game_rules.get(&DO_LIMITED_CRAFTING),
+            )
+        };
+
+        let last_death = player.last_death_location();
+
+        let view_distance = player_chunker::wanted_view_distance(&player).await;
+        player
+            .view_distance
+            .store(view_distance, std::sync::atomic::Ordering::Relaxed);
+
         // login packet for our new player
+        let runtime = runtime_config();
         player
             .client
             .send_packet(&CLogin::new(
                 entity_id,
                 base_config.hardcore,
                 &["minecraft:overworld"],
-                base_config.max_players.into(),
-                base_config.view_distance.into(), //  TODO: view distance
-                base_config.simulation_distance.into(), // TODO: sim view dinstance
-                false,
-                true,
-                false,
+                runtime.max_players.into(),
+                view_distance.into(),
+                player_chunker::get_simulation_distance(&player).into(),
+                reduced_debug_info,
+                !immediate_respawn,
+                limited_crafting,
                 0.into(),
                 "minecraft:overworld",
                 0, // seed
@@ -221,10 +656,12 @@ impl World {
                 base_config.default_gamemode as i8,
                 false,
                 false,
-                None,
+                last_death
+                    .as_ref()
+                    .map(|death| (death.dimension.as_str(), death.world_position())),
                 0.into(),
                 0.into(),
-                false,
+                chat_session::enforces_secure_chat(),
             ))
             .await;
         // permissions, i. e. the commands a player may use
@@ -232,132 +669,69 @@ impl World {
         client_cmd_suggestions::send_c_commands_packet(&player, command_dispatcher).await;
 
         // teleport
-        let mut position = Vector3::new(10.0, 120.0, 10.0);
-        let yaw = 10.0;
-        let pitch = 10.0;
-
-        let top = self
-            .get_top_block(Vector2::new(position.x as i32, position.z as i32))
-            .await;
-        position.y = f64::from(top + 1);
+        let (position, yaw, pitch) = self.spawn_position(&player).await;
 
         log::debug!("Sending player teleport to {}", player.gameprofile.name);
         player.teleport(position, yaw, pitch).await;
 
         player.living_entity.last_pos.store(position);
 
-        let gameprofile = &player.gameprofile;
+        // what was loaded from the player's file
+        player
+            .set_health(
+                player.living_entity.health.load(),
+                player.food.load(std::sync::atomic::Ordering::Relaxed),
+                player.food_saturation.load(),
+            )
+            .await;
+        player.set_container_content(None).await;
+        player.send_experience().await;
+        player.send_effects().await;
+
         // first send info update to our new player, So he can see his Skin
         // also send his info to everyone else
         log::debug!("Broadcasting player info for {}", player.gameprofile.name);
-        self.broadcast_packet_all(&CPlayerInfoUpdate::new(
-            0x01 | 0x08,
-            &[pumpkin_protocol::client::play::Player {
-                uuid: gameprofile.id,
-                actions: vec![
-                    PlayerAction::AddPlayer {
-                        name: &gameprofile.name,
-                        properties: &gameprofile.properties,
-                    },
-                    PlayerAction::UpdateListed(true),
-                ],
-            }],
-        ))
+        let listed = ListedPlayer::new(&player);
+        self.broadcast_packet_except(
+            &[player.gameprofile.id],
+            &CPlayerInfoUpdate::new(tab_list::ALL_ACTIONS, &[listed.to_protocol()]),
+        )
         .await;
+        player
+            .client
+            .send_packet(&CPlayerInfoUpdate::new(
+                tab_list::ALL_ACTIONS,
+                &[listed.to_own_protocol()],
+            ))
+            .await;
 
         // here we send all the infos of already joined players
-        let mut entries = Vec::new();
         {
             let current_players = self.current_players.lock().await;
-            for (_, playerr) in current_players
+            let others: Vec<_> = current_players
                 .iter()
                 .filter(|(c, _)| **c != player.gameprofile.id)
-            {
-                let gameprofile = &playerr.gameprofile;
-                entries.push(pumpkin_protocol::client::play::Player {
-                    uuid: gameprofile.id,
-                    actions: vec![
-                        PlayerAction::AddPlayer {
-                            name: &gameprofile.name,
-                            properties: &gameprofile.properties,
-                        },
-                        PlayerAction::UpdateListed(true),
-                    ],
-                });
-            }
+                .map(|(_, playerr)| ListedPlayer::new(playerr))
+                .collect();
+            let entries: Vec<_> = others.iter().map(ListedPlayer::to_protocol).collect();
             log::debug!("Sending player info to {}", player.gameprofile.name);
             player
                 .client
-                .send_packet(&CPlayerInfoUpdate::new(0x01 | 0x08, &entries))
+                .send_packet(&CPlayerInfoUpdate::new(tab_list::ALL_ACTIONS, &entries))
                 .await;
         }
-
-        let gameprofile = &player.gameprofile;
-
-        log::debug!("Broadcasting player spawn for {}", player.gameprofile.name);
-        // spawn player for every client
-        self.broadcast_packet_except(
-            &[player.gameprofile.id],
-            // TODO: add velo
-            &CSpawnEntity::new(
-                entity_id.into(),
-                gameprofile.id,
-                (EntityType::Player as i32).into(),
-                position.x,
-                position.y,
-                position.z,
-                pitch,
-                yaw,
-                yaw,
-                0.into(),
-                0.0,
-                0.0,
-                0.0,
-            ),
-        )
-        .await;
-        // spawn players for our client
-        let id = player.gameprofile.id;
-        for (_, existing_player) in self
-            .current_players
-            .lock()
-            .await
-            .iter()
-            .filter(|c| c.0 != &id)
-        {
-            let entity = &existing_player.living_entity.entity;
-            let pos = entity.pos.load();
-            let gameprofile = &existing_player.gameprofile;
-            log::debug!("Sending player entities to {}", player.gameprofile.name);
-            player
-                .client
-                .send_packet(&CSpawnEntity::new(
-                    existing_player.entity_id().into(),
-                    gameprofile.id,
-                    (EntityType::Player as i32).into(),
-                    pos.x,
-                    pos.y,
-                    pos.z,
-                    entity.yaw.load(),
-                    entity.pitch.load(),
-                    entity.head_yaw.load(),
-                    0.into(),
-                    0.0,
-                    0.0,
-                    0.0,
-                ))
-                .await;
-        }
-        // entity meta data
-        // set skin parts
-        if let Some(config) = player.client.config.lock().await.as_ref() {
-            let packet = CSetEntityMetadata::new(
-                entity_id.into(),
-                Metadata::new(17, VarInt(0), config.skin_parts),
-            );
-            log::debug!("Broadcasting skin for {}", player.gameprofile.name);
-            self.broadcast_packet_all(&packet).await;
-        }
+        player.send_configured_tab_list().await;
+        // the teams color the glow and name of their members
+        self.scoreboard.lock().await.send_teams(&player).await;
+
+        // other players are spawned for the client by the entity tracker, it does not know any yet
+        self.entity_tracker.forget_viewer(player.gameprofile.id);
+        // entity meta data, like the skin parts
+        let metadata = player.living_entity.entity.metadata();
+        player
+            .client
+            .send_packet(&CSetEntityMetadata::new(entity_id.into(), &metadata))
+            .await;
 
         // Start waiting for level chunks, Sets the "Loading Terrain" screen
         log::debug!("Sending waiting chunks to {}", player.gameprofile.name);
@@ -371,6 +745,7 @@ impl World {
             .await
             .init_client(&player.client)
             .await;
+        self.send_time(&player).await;
 
         // Spawn in initial chunks
         player_chunker::player_join(self, player.clone()).await;
@@ -380,11 +755,17 @@ impl World {
         self.level.mark_chunks_as_not_watched(chunks)
     }
 
-    pub fn clean_chunks(&self, chunks: &[Vector2<i32>]) {
+    /// Stores the entities and block entities of the chunks no player watches anymore in them,
+    /// then hands the chunks to the chunk cache
+    pub async fn clean_chunks(&self, chunks: &[Vector2<i32>]) {
+        self.unload_chunk_entities(chunks).await;
+        self.unload_block_entities(chunks).await;
         self.level.clean_chunks(chunks);
     }
 
-    pub fn clean_memory(&self, chunks_to_check: &[Vector2<i32>]) {
+    pub async fn clean_memory(&self, chunks_to_check: &[Vector2<i32>]) {
+        self.unload_chunk_entities(chunks_to_check).await;
+        self.unload_block_entities(chunks_to_check).await;
         self.level.clean_memory(chunks_to_check);
     }
 
@@ -443,14 +824,14 @@ impl World {
         let batch_id = id;
 
         let handle = tokio::spawn(async move {
-            while let Some(chunk_data) = receiver.recv().await {
-                let chunk_data = chunk_data.read().await;
-                let packet = CChunkData(&chunk_data);
+            while let Some(chunk) = receiver.recv().await {
+                let chunk_data = chunk.read().await;
+                let position = chunk_data.position;
                 #[cfg(debug_assertions)]
-                if chunk_data.position == (0, 0).into() {
-                    use pumpkin_protocol::bytebuf::ByteBuffer;
+                if position == (0, 0).into() {
+                    use pumpkin_protocol::{bytebuf::ByteBuffer, client::play::CChunkData};
                     let mut test = ByteBuffer::empty();
-                    packet.write(&mut test);
+                    CChunkData(&chunk_data).write(&mut test);
                     let len = test.buf().len();
                     log::debug!(
                         "Chunk packet size: {}B {}KB {}MB",
@@ -460,7 +841,7 @@ impl World {
                     );
                 }
 
-                {
+                let first_watcher = {
                     let mut pending_chunks = pending_chunks.lock();
                     let handlers = pending_chunks
                         .get_mut(&chunk_data.position)
@@ -492,15 +873,21 @@ impl World {
                     }
 
                     // This must be locked with pending
-                    level.mark_chunk_as_newly_watched(chunk_data.position);
+                    level.mark_chunk_as_newly_watched(chunk_data.position)
                 };
+                drop(chunk_data);
 
                 if !player
                     .client
                     .closed
                     .load(std::sync::atomic::Ordering::Relaxed)
                 {
-                    player.client.send_packet(&packet).await;
+                    player.client.send_chunk(position, chunk.clone()).await;
+                }
+                if first_watcher {
+                    let world = &player.living_entity.entity.world;
+                    world.load_chunk_entities(&chunk).await;
+                    world.load_block_entities(&chunk).await;
                 }
             }
 
@@ -601,11 +988,12 @@ impl World {
     /// - This function assumes `broadcast_packet_expect` and `remove_entity` are defined elsewhere.
     /// - The disconnect message sending is currently optional. Consider making it a configurable option.
     pub async fn remove_player(&self, player: &Player) {
+        vehicle::detach(&player.living_entity.entity).await;
+        // players being reconfigured already left the world
         self.current_players
             .lock()
             .await
-            .remove(&player.gameprofile.id)
-            .unwrap();
+            .remove(&player.gameprofile.id);
         let uuid = player.gameprofile.id;
         self.broadcast_packet_except(
             &[player.gameprofile.id],
@@ -625,8 +1013,13 @@ impl World {
         log::info!("{}", disconn_msg_cmp.to_pretty_console());
     }
 
+    /// Removes the entity for every player seeing it.
     pub async fn remove_entity(&self, entity: &Entity) {
-        self.broadcast_packet_all(&CRemoveEntities::new(&[entity.entity_id.into()]))
+        let viewers = self.entity_tracker.remove(entity.entity_id);
+        self.players()
+            .await
+            .filter(|player| viewers.contains(&player.gameprofile.id))
+            .send_packet(&CRemoveEntities::new(&[entity.entity_id.into()]))
             .await;
     }
 
@@ -638,21 +1031,73 @@ impl World {
         let relative = ChunkRelativeBlockCoordinates::from(relative_coordinates);
 
         let chunk = self.receive_chunk(chunk_coordinate).await;
-        let replaced_block_state_id = chunk
-            .write()
-            .await
-            .blocks
-            .set_block(relative, block_state_id);
-
-        self.broadcast_packet_all(&CBlockUpdate::new(
-            &position,
-            i32::from(block_state_id).into(),
-        ))
-        .await;
+        let replaced_block_state_id = {
+            let mut chunk = chunk.write().await;
+            chunk.mark_dirty();
+            let replaced_block_state_id = chunk.blocks.set_block(relative, block_state_id);
+            // the block entity belonged to the replaced block
+            if !same_block(replaced_block_state_id, block_state_id) {
+                chunk.block_entity_data.remove(&position);
+                chunk.block_entities.remove(&position);
+            }
+            replaced_block_state_id
+        };
+        if light::changes_light(replaced_block_state_id, block_state_id) {
+            self.level
+                .light_engine()
+                .queue_around(chunk_coordinate, chunk.clone());
+        }
+        self.create_block_entity(position, block_state_id).await;
+        self.block_changes.push(position, block_state_id);
 
         replaced_block_state_id
     }
 
+    /// Sets many blocks at once, returning the replaced block state ids in the same order.
+    ///
+    /// Chunks are only locked once.
+    pub async fn set_block_states(&self, blocks: &[(WorldPosition, u16)]) -> Vec<u16> {
+        let mut by_chunk: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
+        for (i, (position, _)) in blocks.iter().enumerate() {
+            let (chunk, _) = position.chunk_and_chunk_relative_position();
+            by_chunk.entry((chunk.x, chunk.z)).or_default().push(i);
+        }
+
+        let mut replaced = vec![0; blocks.len()];
+        for ((chunk_x, chunk_z), indices) in by_chunk {
+            let chunk_position = Vector2::new(chunk_x, chunk_z);
+            let chunk_data = self.receive_chunk(chunk_position).await;
+            let mut chunk = chunk_data.write().await;
+            chunk.mark_dirty();
+            let mut relight = false;
+            for i in indices {
+                let (position, block_state_id) = blocks[i];
+                let (_, relative) = position.chunk_and_chunk_relative_position();
+                replaced[i] = chunk.blocks.set_block(
+                    ChunkRelativeBlockCoordinates::from(relative),
+                    block_state_id,
+                );
+                if !same_block(replaced[i], block_state_id) {
+                    chunk.block_entity_data.remove(&position);
+                    chunk.block_entities.remove(&position);
+                }
+                relight |= light::changes_light(replaced[i], block_state_id);
+                self.block_changes.push(position, block_state_id);
+            }
+            drop(chunk);
+            if relight {
+                self.level
+                    .light_engine()
+                    .queue_around(chunk_position, chunk_data);
+            }
+        }
+        for (position, block_state_id) in blocks {
+            self.create_block_entity(*position, *block_state_id).await;
+        }
+
+        replaced
+    }
+
     // Stream the chunks (don't collect them and then do stuff with them)
     pub fn receive_chunks(&self, chunks: &[Vector2<i32>]) -> ChunkReceiver {
         let (sender, receive) = mpsc::channel(chunks.len());
@@ -668,7 +1113,41 @@ impl World {
             .expect("Channel closed for unknown reason")
     }
 
+    /// Breaks the block unless a plugin cancels it, the player then sees the block again
     pub async fn break_block(&self, position: WorldPosition, cause: Option<&Player>) {
+        if let Some(player) = cause {
+            if let Ok(state_id) = self.get_block_state_id(position).await {
+                let on_break = CONTENT.block(state_id).and_then(|block| block.on_break);
+                if on_break
+                    .is_some_and(|on_break| on_break(&plugin::player_info(player), Some(position)))
+                {
+                    player
+                        .client
+                        .send_packet(&CBlockUpdate::new(&position, i32::from(state_id).into()))
+                        .await;
+                    return;
+                }
+            }
+        }
+        if EVENTS.has_listeners::<BlockBreakEvent>() {
+            let Ok(state_id) = self.get_block_state_id(position).await else {
+                return;
+            };
+            let event = plugin::fire(BlockBreakEvent::new(
+                cause.map(plugin::player_info),
+                position,
+                state_id,
+            ));
+            if event.is_cancelled() {
+                if let Some(player) = cause {
+                    player
+                        .client
+                        .send_packet(&CBlockUpdate::new(&position, i32::from(state_id).into()))
+                        .await;
+                }
+                return;
+            }
+        }
         let broken_block_state_id = self.set_block_state(position, 0).await;
 
         let particles_packet =
@@ -681,6 +1160,12 @@ impl World {
             }
             None => self.broadcast_packet_all(&particles_packet).await,
         }
+        self.emit_game_event_by(
+            vibration::GameEvent::BlockDestroy,
+            block_entity::center(position),
+            cause.map(Player::entity_id),
+        )
+        .await;
     }
 
     pub async fn get_block_state_id(&self, position: WorldPosition) -> Result<u16, GetBlockError> {
@@ -696,6 +1181,39 @@ impl World {
         Ok(id)
     }
 
+    /// Gets the block state id of every block between the corners, receiving each chunk only once.
+    /// Blocks outside of the world's height are left out
+    pub async fn get_block_state_ids_in(
+        &self,
+        min: Vector3<i32>,
+        max: Vector3<i32>,
+    ) -> Vec<(WorldPosition, u16)> {
+        let mut blocks = Vec::new();
+        for chunk_x in get_section_cord(min.x)..=get_section_cord(max.x) {
+            for chunk_z in get_section_cord(min.z)..=get_section_cord(max.z) {
+                let chunk = self.receive_chunk(Vector2::new(chunk_x, chunk_z)).await;
+                let chunk = chunk.read().await;
+                for x in min.x.max(chunk_x * 16)..=max.x.min(chunk_x * 16 + 15) {
+                    for z in min.z.max(chunk_z * 16)..=max.z.min(chunk_z * 16 + 15) {
+                        for y in
+                            min.y.max(WORLD_LOWEST_Y.into())..=max.y.min(i32::from(WORLD_MAX_Y) - 1)
+                        {
+                            let position = WorldPosition(Vector3::new(x, y, z));
+                            let (_, relative) = position.chunk_and_chunk_relative_position();
+                            if let Some(id) = chunk
+                                .blocks
+                                .get_block(ChunkRelativeBlockCoordinates::from(relative))
+                            {
+                                blocks.push((position, id));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        blocks
+    }
+
     /// Gets the Block from the Block Registry, Returns None if the Block has not been found
     pub async fn get_block(
         &self,
@@ -714,6 +1232,66 @@ impl World {
         get_state_by_state_id(id).ok_or(GetBlockError::InvalidBlockId)
     }
 
+    /// Samples the blocks around `origin` into a [`CollisionMap`], showing how a mob's pathfinder sees them.
+    pub async fn collision_map(
+        &self,
+        origin: WorldPosition,
+        radius: i32,
+        height: i32,
+    ) -> CollisionMap {
+        let center = origin.0;
+        let mut samples = HashMap::new();
+        for x in center.x - radius..=center.x + radius {
+            for z in center.z - radius..=center.z + radius {
+                for y in center.y - height - 1..=center.y + height + 1 {
+                    let node_type = match self
+                        .get_block_and_block_state(WorldPosition(Vector3::new(x, y, z)))
+                        .await
+                    {
+                        Ok((block, state)) => PathNodeType::from_block(block, state),
+                        Err(_) => PathNodeType::Blocked,
+                    };
+                    samples.insert((x, y, z), node_type);
+                }
+            }
+        }
+
+        CollisionMap::build(origin, radius, height, |position| {
+            let Vector3 { x, y, z } = position.0;
+            samples
+                .get(&(x, y, z))
+                .copied()
+                .unwrap_or(PathNodeType::Blocked)
+        })
+    }
+
+    /// The boxes of the blocks entities collide with inside of the area, in world coordinates
+    pub async fn collision_boxes(&self, area: &BoundingBox) -> Vec<BoundingBox> {
+        let min = Vector3::new(
+            area.min_x.floor() as i32,
+            area.min_y.floor() as i32,
+            area.min_z.floor() as i32,
+        );
+        let max = Vector3::new(
+            area.max_x.floor() as i32,
+            area.max_y.floor() as i32,
+            area.max_z.floor() as i32,
+        );
+        let mut boxes = Vec::new();
+        for (position, id) in self.get_block_state_ids_in(min, max).await {
+            let Some(state) = get_state_by_state_id(id) else {
+                continue;
+            };
+            let pos = position.0;
+            boxes.extend(
+                state.collision_boxes().map(|shape| {
+                    shape.offset(f64::from(pos.x), f64::from(pos.y), f64::from(pos.z))
+                }),
+            );
+        }
+        boxes
+    }
+
     /// Gets the Block + Block state from the Block Registry, Returns None if the Block state has not been found
     pub async fn get_block_and_block_state(
         &self,
@@ -0,0 +1,83 @@
+//! Spawning particles, which are only sent to the players close enough to see them.
+
+use pumpkin_core::math::vector3::Vector3;
+use pumpkin_protocol::client::play::{CParticle, Particle};
+
+use crate::entity::{player::Player, player_set::PlayerSet};
+
+use super::{player_chunker::get_view_distance, World};
+
+/// Players further away than this do not see particles, like in vanilla
+pub const PARTICLE_RANGE: f64 = 32.0;
+/// The range of forced particles, which are also shown with the client's minimal particle setting
+pub const FORCED_PARTICLE_RANGE: f64 = 512.0;
+
+/// Whether the player is close enough to the particles and has them within their view distance.
+pub fn can_see_particles(player: &Player, position: &Vector3<f64>, force: bool) -> bool {
+    let range = if force {
+        FORCED_PARTICLE_RANGE
+    } else {
+        PARTICLE_RANGE
+    };
+    let player_position = player.living_entity.entity.pos.load();
+    if player_position.sub(position).length_squared() > range * range {
+        return false;
+    }
+    let view_distance = f64::from(get_view_distance(player)) * 16.0;
+    (player_position.x - position.x).abs() <= view_distance
+        && (player_position.z - position.z).abs() <= view_distance
+}
+
+impl World {
+    /// Spawns the particles for every player who can see them.
+    ///
+    /// They are spread randomly within the offset, when the count is 0 a single particle is
+    /// spawned which uses the offset and speed for its direction or color instead.
+    /// Returns how many players see them.
+    pub async fn spawn_particle(
+        &self,
+        particle: &Particle,
+        position: Vector3<f64>,
+        offset: Vector3<f32>,
+        speed: f32,
+        count: i32,
+        force: bool,
+    ) -> usize {
+        let mut viewers = Vec::new();
+        for player in self.players().await {
+            if can_see_particles(&player, &position, force) {
+                viewers.push(player);
+            }
+        }
+        let viewers = PlayerSet::new(viewers);
+        viewers
+            .send_packet(&CParticle::new(
+                force, position, offset, speed, count, particle,
+            ))
+            .await;
+        viewers.len()
+    }
+}
+
+impl Player {
+    /// Spawns the particles for this player only, returns false if they can not see them.
+    pub async fn spawn_particle(
+        &self,
+        particle: &Particle,
+        position: Vector3<f64>,
+        offset: Vector3<f32>,
+        speed: f32,
+        count: i32,
+        force: bool,
+    ) -> bool {
+        if !can_see_particles(self, &position, force) {
+            return false;
+        }
+        self.client
+            .send_packet(&CParticle::new(
+                force, position, offset, speed, count, particle,
+            ))
+            .await;
+        true
+    }
+}
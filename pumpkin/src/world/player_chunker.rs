@@ -1,24 +1,71 @@
-use std::sync::Arc;
+use std::sync::{atomic::Ordering, Arc};
 
-use pumpkin_config::BASIC_CONFIG;
 use pumpkin_core::{
     math::{get_section_cord, position::WorldPosition, vector2::Vector2, vector3::Vector3},
     GameMode,
 };
-use pumpkin_protocol::client::play::{CCenterChunk, CUnloadChunk};
+use pumpkin_protocol::client::play::{
+    CCenterChunk, CSetChunkCacheRadius, CSetSimulationDistance, CUnloadChunk,
+};
 use pumpkin_world::cylindrical_chunk_iterator::Cylindrical;
 
 use crate::entity::player::Player;
 
 use super::World;
 
-pub async fn get_view_distance(player: &Player) -> u8 {
+/// The view distance the chunks around the player were sent with
+pub fn get_view_distance(player: &Player) -> u8 {
+    player.view_distance.load(Ordering::Relaxed)
+}
+
+/// What the player's client asks for, at most the world's view distance or the player's override
+pub async fn wanted_view_distance(player: &Player) -> u8 {
+    let max = player
+        .view_distance_override
+        .load()
+        .unwrap_or_else(|| player.living_entity.entity.world.view_distance());
     player
         .config
         .lock()
         .await
         .view_distance
-        .clamp(2, BASIC_CONFIG.view_distance)
+        .clamp(2, max.max(2))
+}
+
+/// How far around the player entities and block entities are ticked, at most its view distance
+pub fn get_simulation_distance(player: &Player) -> u8 {
+    player
+        .simulation_distance_override
+        .load()
+        .unwrap_or_else(|| player.living_entity.entity.world.simulation_distance())
+        .min(get_view_distance(player))
+        .max(2)
+}
+
+/// The chunks within the simulation distance of any player in a world
+pub struct SimulationArea(Vec<Cylindrical>);
+
+impl SimulationArea {
+    pub fn new<'a>(players: impl IntoIterator<Item = &'a Arc<Player>>) -> Self {
+        Self(
+            players
+                .into_iter()
+                .map(|player| {
+                    let watched = player.watched_section.load();
+                    Cylindrical::new(
+                        Vector2::new(watched.x, watched.z),
+                        get_simulation_distance(player),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    pub fn contains(&self, chunk: Vector2<i32>) -> bool {
+        self.0
+            .iter()
+            .any(|area| area.is_within_distance(chunk.x, chunk.z))
+    }
 }
 
 pub async fn player_join(world: &World, player: Arc<Player>) {
@@ -37,7 +84,7 @@ pub async fn player_join(world: &World, player: Arc<Player>) {
             chunk_z: chunk_pos.z.into(),
         })
         .await;
-    let view_distance = get_view_distance(&player).await;
+    let view_distance = get_view_distance(&player);
     log::debug!(
         "Player {} ({}) joined with view distance: {}",
         player.gameprofile.name,
@@ -80,90 +127,127 @@ pub async fn update_position(player: &Arc<Player>) {
             })
             .await;
 
-        let view_distance = get_view_distance(player).await;
+        let view_distance = get_view_distance(player);
         let old_cylindrical = Cylindrical::new(
             Vector2::new(current_watched.x, current_watched.z),
             view_distance,
         );
         let new_cylindrical = Cylindrical::new(chunk_pos, view_distance);
 
-        let mut loading_chunks = Vec::new();
-        let mut unloading_chunks = Vec::new();
-        Cylindrical::for_each_changed_chunk(
-            old_cylindrical,
-            new_cylindrical,
-            |chunk_pos| {
-                loading_chunks.push(chunk_pos);
-            },
-            |chunk_pos| {
-                unloading_chunks.push(chunk_pos);
-            },
-        );
-        if !loading_chunks.is_empty() {
-            //let inst = std::time::Instant::now();
-            entity
-                .world
-                .spawn_world_chunks(player.clone(), &loading_chunks);
-            //log::debug!("Loading chunks took {:?}", inst.elapsed());
-        }
-
-        if !unloading_chunks.is_empty() {
-            // We want to check if this chunk is still pending
-            // if it is -> ignore
-
-            //let inst = std::time::Instant::now();
-
-            let watched_chunks: Vec<_> = {
-                let mut pending_chunks = player.pending_chunks.lock();
-                unloading_chunks
-                    .into_iter()
-                    .filter(|chunk| {
-                        if let Some(handles) = pending_chunks.get_mut(chunk) {
-                            if let Some((count, handle)) = handles
-                                .iter_mut()
-                                .rev()
-                                .enumerate()
-                                .find(|(_, handle)| !handle.aborted())
-                            {
-                                log::debug!("Aborting chunk {:?} ({}) (unload)", chunk, count);
-                                // We want to abort the last queued chunk, that we if a client still
-                                // has a pending request for this chunk, we dont need to do the work
-                                // twice
-                                handle.abort();
-                            } else {
-                                log::warn!(
-                                    "Aborting chunk {:?} but all were already aborted!",
-                                    chunk
-                                );
-                            }
-                            false
+        change_view(player, old_cylindrical, new_cylindrical).await;
+    }
+}
+
+/// Sends the player's view and simulation distance again after the server's, the client's or
+/// the player's own distances changed, loading or unloading the chunks in between
+pub async fn update_distances(player: &Arc<Player>) {
+    let view_distance = wanted_view_distance(player).await;
+    let old_view_distance = player.view_distance.swap(view_distance, Ordering::Relaxed);
+    player
+        .client
+        .send_packet(&CSetChunkCacheRadius {
+            view_distance: view_distance.into(),
+        })
+        .await;
+    player
+        .client
+        .send_packet(&CSetSimulationDistance {
+            simulation_distance: get_simulation_distance(player).into(),
+        })
+        .await;
+    if old_view_distance == view_distance {
+        return;
+    }
+    let watched = player.watched_section.load();
+    let center = Vector2::new(watched.x, watched.z);
+    change_view(
+        player,
+        Cylindrical::new(center, old_view_distance),
+        Cylindrical::new(center, view_distance),
+    )
+    .await;
+}
+
+/// Sends the chunks which are only in the new view and unloads the ones which are only in the old one
+async fn change_view(
+    player: &Arc<Player>,
+    old_cylindrical: Cylindrical,
+    new_cylindrical: Cylindrical,
+) {
+    let world = &player.living_entity.entity.world;
+    let mut loading_chunks = Vec::new();
+    let mut unloading_chunks = Vec::new();
+    Cylindrical::for_each_changed_chunk(
+        old_cylindrical,
+        new_cylindrical,
+        |chunk_pos| {
+            loading_chunks.push(chunk_pos);
+        },
+        |chunk_pos| {
+            unloading_chunks.push(chunk_pos);
+        },
+    );
+    if !loading_chunks.is_empty() {
+        //let inst = std::time::Instant::now();
+        world.spawn_world_chunks(player.clone(), &loading_chunks);
+        //log::debug!("Loading chunks took {:?}", inst.elapsed());
+    }
+
+    if !unloading_chunks.is_empty() {
+        // We want to check if this chunk is still pending
+        // if it is -> ignore
+
+        //let inst = std::time::Instant::now();
+
+        let watched_chunks: Vec<_> = {
+            let mut pending_chunks = player.pending_chunks.lock();
+            unloading_chunks
+                .into_iter()
+                .filter(|chunk| {
+                    if let Some(handles) = pending_chunks.get_mut(chunk) {
+                        if let Some((count, handle)) = handles
+                            .iter_mut()
+                            .rev()
+                            .enumerate()
+                            .find(|(_, handle)| !handle.aborted())
+                        {
+                            log::debug!("Aborting chunk {:?} ({}) (unload)", chunk, count);
+                            // We want to abort the last queued chunk, that we if a client still
+                            // has a pending request for this chunk, we dont need to do the work
+                            // twice
+                            handle.abort();
                         } else {
-                            true
+                            log::warn!("Aborting chunk {:?} but all were already aborted!", chunk);
                         }
-                    })
-                    .collect()
-            };
-
-            //log::debug!("Unloading chunks took {:?} (1)", inst.elapsed());
-            let chunks_to_clean = entity.world.mark_chunks_as_not_watched(&watched_chunks);
-            entity.world.clean_chunks(&chunks_to_clean);
-
-            //log::debug!("Unloading chunks took {:?} (2)", inst.elapsed());
-            // This can take a little if we are sending a bunch of packets, queue it up :p
-            let client = player.client.clone();
-            tokio::spawn(async move {
-                for chunk in watched_chunks {
-                    if client.closed.load(std::sync::atomic::Ordering::Relaxed) {
-                        // We will never un-close a connection
-                        break;
+                        false
+                    } else {
+                        true
                     }
-                    client
-                        .send_packet(&CUnloadChunk::new(chunk.x, chunk.z))
-                        .await;
+                })
+                .collect()
+        };
+
+        //log::debug!("Unloading chunks took {:?} (1)", inst.elapsed());
+        let chunks_to_clean = world.mark_chunks_as_not_watched(&watched_chunks);
+        world.clean_chunks(&chunks_to_clean).await;
+
+        //log::debug!("Unloading chunks took {:?} (2)", inst.elapsed());
+        // Chunks which were not sent yet don't have to be
+        player.client.cancel_chunks(&watched_chunks);
+        // This can take a little if we are sending a bunch of packets, queue it up :p
+        let client = player.client.clone();
+        tokio::spawn(async move {
+            for chunk in watched_chunks {
+                if client.closed.load(std::sync::atomic::Ordering::Relaxed) {
+                    // We will never un-close a connection
+                    break;
                 }
-            });
-            //log::debug!("Unloading chunks took {:?} (3)", inst.elapsed());
-        }
+                client
+                    .send_packet(&CUnloadChunk::new(chunk.x, chunk.z))
+                    .await;
+            }
+        });
+        //log::debug!("Unloading chunks took {:?} (3)", inst.elapsed());
     }
 }
 
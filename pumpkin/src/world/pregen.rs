@@ -0,0 +1,280 @@
+//! Generates and saves the chunks in a square around the spawn ahead of time, so players exploring
+//! it don't wait for the world generation.
+//!
+//! The chunks are generated ring by ring from the center outwards, by the same tasks which load
+//! chunks for players. How far it got is written to `pregen.json` in the world's folder after
+//! every batch, a server started with that file continues where the last one stopped.
+
+use std::{
+    fs,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+use pumpkin_core::{math::vector2::Vector2, text::TextComponent};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+
+use crate::entity::player::Player;
+
+use super::World;
+
+const PROGRESS_FILE: &str = "pregen.json";
+/// Chunks generated at once, more than the generation tasks can work on in parallel
+const BATCH_SIZE: usize = 256;
+const REPORT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The running pregeneration, only one runs at a time
+static RUNNING: Mutex<Option<Arc<Pregen>>> = Mutex::new(None);
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+struct Progress {
+    center_x: i32,
+    center_z: i32,
+    radius: u32,
+    /// Chunks generated, in the order of `positions`
+    done: usize,
+}
+
+pub struct Pregen {
+    world: Arc<World>,
+    progress: Mutex<Progress>,
+    stop: AtomicBool,
+    /// Sees the progress in the action bar, the console always sees it in the log
+    viewer: Mutex<Option<Arc<Player>>>,
+    generated: AtomicUsize,
+    finished: Notify,
+}
+
+/// The chunks in the square, ring by ring from the center outwards
+fn positions(center: Vector2<i32>, radius: u32) -> impl Iterator<Item = Vector2<i32>> {
+    std::iter::once(center).chain((1..=radius as i32).flat_map(move |ring| {
+        let side = -ring..ring;
+        let top = side.clone().map(move |i| (i, -ring));
+        let right = side.clone().map(move |i| (ring, i));
+        let bottom = side.clone().map(move |i| (-i, ring));
+        let left = side.map(move |i| (-ring, -i));
+        top.chain(right)
+            .chain(bottom)
+            .chain(left)
+            .map(move |(x, z)| Vector2::new(center.x + x, center.z + z))
+    }))
+}
+
+fn progress_path(world: &World) -> Option<PathBuf> {
+    world
+        .level
+        .root_folder()
+        .map(|root| root.join(PROGRESS_FILE))
+}
+
+fn format_duration(seconds: u64) -> String {
+    format!(
+        "{}h {:02}m {:02}s",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+impl Pregen {
+    /// Starts generating the chunks in the radius around the world's spawn, `None` if a
+    /// pregeneration is running already
+    pub fn start(world: Arc<World>, radius: u32, viewer: Option<Arc<Player>>) -> Option<Arc<Self>> {
+        let center = world
+            .level
+            .level_data()
+            .spawn()
+            .map_or(Vector2::new(0, 0), |(spawn, _)| {
+                Vector2::new(spawn.x >> 4, spawn.z >> 4)
+            });
+        Self::spawn(
+            world,
+            Progress {
+                center_x: center.x,
+                center_z: center.z,
+                radius,
+                done: 0,
+            },
+            viewer,
+        )
+    }
+
+    /// Continues the pregeneration a stopped server left behind
+    pub fn resume(world: Arc<World>) -> Option<Arc<Self>> {
+        let path = progress_path(&world)?;
+        let progress = fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str::<Progress>(&json).ok())?;
+        log::info!(
+            "Resuming the pregeneration of {} after {} chunks",
+            world.level.name(),
+            progress.done
+        );
+        Self::spawn(world, progress, None)
+    }
+
+    fn spawn(
+        world: Arc<World>,
+        progress: Progress,
+        viewer: Option<Arc<Player>>,
+    ) -> Option<Arc<Self>> {
+        let mut running = RUNNING.lock();
+        if running.is_some() {
+            return None;
+        }
+        let pregen = Arc::new(Self {
+            world,
+            progress: Mutex::new(progress),
+            stop: AtomicBool::new(false),
+            viewer: Mutex::new(viewer),
+            generated: AtomicUsize::new(0),
+            finished: Notify::new(),
+        });
+        *running = Some(pregen.clone());
+        tokio::spawn(pregen.clone().run());
+        Some(pregen)
+    }
+
+    pub fn running() -> Option<Arc<Self>> {
+        RUNNING.lock().clone()
+    }
+
+    /// Stops after the current batch, the progress is kept to be resumed
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    pub fn total(&self) -> usize {
+        let side = self.progress.lock().radius as usize * 2 + 1;
+        side * side
+    }
+
+    pub fn done(&self) -> usize {
+        self.progress.lock().done
+    }
+
+    async fn run(self: Arc<Self>) {
+        let start = Instant::now();
+        let mut last_report = start;
+        let progress = *self.progress.lock();
+        let center = Vector2::new(progress.center_x, progress.center_z);
+        let total = self.total();
+        let remaining = positions(center, progress.radius)
+            .skip(progress.done)
+            .collect::<Vec<_>>();
+
+        for batch in remaining.chunks(BATCH_SIZE) {
+            if self.stop.load(Ordering::Relaxed) {
+                break;
+            }
+            self.generate(batch).await;
+            let done = {
+                let mut progress = self.progress.lock();
+                progress.done += batch.len();
+                progress.done
+            };
+            self.generated.fetch_add(batch.len(), Ordering::Relaxed);
+            self.save_progress();
+            if last_report.elapsed() >= REPORT_INTERVAL {
+                last_report = Instant::now();
+                self.report(done, total, start.elapsed()).await;
+            }
+        }
+
+        let done = self.done();
+        if done >= total {
+            if let Some(path) = progress_path(&self.world) {
+                let _ = fs::remove_file(path);
+            }
+            log::info!(
+                "Pregenerated {total} chunks of {} in {}",
+                self.world.level.name(),
+                format_duration(start.elapsed().as_secs())
+            );
+        } else {
+            log::info!("Stopped the pregeneration after {done} of {total} chunks");
+        }
+        let viewer = self.viewer.lock().take();
+        if let Some(viewer) = viewer {
+            viewer
+                .send_system_message(&TextComponent::text_string(format!(
+                    "The pregeneration finished {done} of {total} chunks"
+                )))
+                .await;
+        }
+        *RUNNING.lock() = None;
+        self.finished.notify_one();
+    }
+
+    /// Waits until the pregeneration finished or was stopped
+    pub async fn wait(&self) {
+        self.finished.notified().await;
+    }
+
+    /// Generates the chunks and saves them, the ones no player sees are left to the chunk cache
+    async fn generate(&self, batch: &[Vector2<i32>]) {
+        let (handles, mut receiver) = self.world.receive_chunks(batch);
+        for _ in &handles {
+            if receiver.recv().await.is_none() {
+                break;
+            }
+        }
+        let level = self.world.level.clone();
+        let batch = batch.to_vec();
+        let saved = tokio::task::spawn_blocking(move || {
+            for chunk in &batch {
+                if let Some(data) = level.get_loaded_chunk(*chunk) {
+                    level.write_chunk((*chunk, data));
+                }
+            }
+            level.sync_chunks();
+            let unwatched = batch
+                .into_iter()
+                .filter(|chunk| level.should_pop_chunk(chunk))
+                .collect::<Vec<_>>();
+            level.clean_chunks(&unwatched);
+        })
+        .await;
+        if let Err(err) = saved {
+            log::error!("Failed to save pregenerated chunks: {err}");
+        }
+    }
+
+    fn save_progress(&self) {
+        let Some(path) = progress_path(&self.world) else {
+            return;
+        };
+        let progress = *self.progress.lock();
+        let written = serde_json::to_string(&progress)
+            .map_err(|err| err.to_string())
+            .and_then(|json| fs::write(path, json).map_err(|err| err.to_string()));
+        if let Err(err) = written {
+            log::warn!("Failed to save the pregeneration progress: {err}");
+        }
+    }
+
+    /// Logs the speed and how long the rest takes, the viewer sees it above their hotbar
+    async fn report(&self, done: usize, total: usize, elapsed: Duration) {
+        let generated = self.generated.load(Ordering::Relaxed);
+        let per_second = generated as f64 / elapsed.as_secs_f64().max(1.0);
+        let eta = ((total - done) as f64 / per_second.max(0.01)) as u64;
+        let message = format!(
+            "Pregenerated {done}/{total} chunks ({:.1}%), {per_second:.1} chunks/s, {} left",
+            done as f64 * 100.0 / total as f64,
+            format_duration(eta)
+        );
+        log::info!("{message}");
+        let viewer = self.viewer.lock().clone();
+        if let Some(viewer) = viewer {
+            viewer
+                .send_action_bar(TextComponent::text_string(message))
+                .await;
+        }
+    }
+}
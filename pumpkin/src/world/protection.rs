@@ -0,0 +1,64 @@
+//! Decides where blocks may be changed: the spawn protection of vanilla and the [`Protection`]
+//! services of plugins.
+
+use pumpkin_api::service::Protection;
+use pumpkin_config::BASIC_CONFIG;
+use pumpkin_core::math::position::WorldPosition;
+
+use crate::{
+    data::op_data::OPERATOR_CONFIG,
+    entity::player::{PermissionLvl, Player},
+    permission,
+    plugin::{self, SERVICES},
+};
+
+use super::World;
+
+impl World {
+    /// Whether the position is too close to the spawn for the player to build, operators may
+    /// build anywhere
+    pub async fn is_spawn_protected(&self, player: &Player, position: WorldPosition) -> bool {
+        let radius = BASIC_CONFIG.spawn_protection;
+        if radius == 0 || OPERATOR_CONFIG.read().await.ops.is_empty() {
+            return false;
+        }
+        if permission::has_permission(
+            player,
+            "pumpkin.spawn_protection.bypass",
+            PermissionLvl::One,
+        ) {
+            return false;
+        }
+        let Some((spawn, _)) = self.level.level_data().spawn() else {
+            return false;
+        };
+        let distance = (i64::from(position.0.x) - i64::from(spawn.x))
+            .abs()
+            .max((i64::from(position.0.z) - i64::from(spawn.z)).abs());
+        distance <= i64::from(radius)
+    }
+
+    /// Whether the player may place or break the block at the position, or use a bucket on it
+    pub async fn can_build(&self, player: &Player, position: WorldPosition) -> bool {
+        if self.is_spawn_protected(player, position).await {
+            return false;
+        }
+        let protections = SERVICES.all::<dyn Protection>();
+        if protections.is_empty() {
+            return true;
+        }
+        let player = plugin::player_info(player);
+        protections
+            .iter()
+            .all(|protection| protection.can_build(&player, position))
+    }
+}
+
+/// Whether explosions may destroy the block at the position
+#[must_use]
+pub fn can_explode(position: WorldPosition) -> bool {
+    SERVICES
+        .all::<dyn Protection>()
+        .iter()
+        .all(|protection| protection.can_explode(position))
+}
@@ -0,0 +1,140 @@
+//! Redstone power as the blocks with a redstone input see it. Power doesn't travel through solid
+//! blocks yet, a block is only powered by the power sources right next to it.
+
+use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+use pumpkin_world::block::{block_registry::get_block_by_state_id, BlockFace};
+
+use super::World;
+
+pub(crate) const FACES: [BlockFace; 6] = [
+    BlockFace::Bottom,
+    BlockFace::Top,
+    BlockFace::North,
+    BlockFace::South,
+    BlockFace::West,
+    BlockFace::East,
+];
+
+/// How strongly the block state powers the blocks next to it, 0 to 15
+#[must_use]
+pub fn emitted_power(state_id: u16) -> u8 {
+    let Some(block) = get_block_by_state_id(state_id) else {
+        return 0;
+    };
+    let properties = block.properties_of_state(state_id).unwrap_or_default();
+    let property = |name: &str| {
+        properties
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| *value)
+    };
+    let name = block.name.as_str();
+    match name {
+        "minecraft:redstone_block" => 15,
+        "minecraft:redstone_torch" | "minecraft:redstone_wall_torch" => {
+            if property("lit") == Some("true") {
+                15
+            } else {
+                0
+            }
+        }
+        "minecraft:lever"
+        | "minecraft:tripwire_hook"
+        | "minecraft:detector_rail"
+        | "minecraft:lightning_rod" => {
+            if property("powered") == Some("true") {
+                15
+            } else {
+                0
+            }
+        }
+        _ if name.ends_with("_button") || name.ends_with("_pressure_plate") => {
+            if property("powered") == Some("true") {
+                15
+            } else {
+                // weighted pressure plates have a power level instead
+                property("power")
+                    .and_then(|power| power.parse().ok())
+                    .unwrap_or(0)
+            }
+        }
+        // redstone wire, daylight detectors, targets and sculk sensors
+        _ => property("power")
+            .and_then(|power| power.parse().ok())
+            .unwrap_or(0),
+    }
+}
+
+/// How strongly the block state powers the block next to it in the direction. Observers only
+/// power the block behind them
+#[must_use]
+pub fn emitted_power_to(state_id: u16, direction: BlockFace) -> u8 {
+    let Some(block) = get_block_by_state_id(state_id) else {
+        return 0;
+    };
+    if block.name != "minecraft:observer" {
+        return emitted_power(state_id);
+    }
+    let properties = block.properties_of_state(state_id).unwrap_or_default();
+    let facing = properties
+        .iter()
+        .find(|(key, _)| *key == "facing")
+        .and_then(|(_, value)| BlockFace::from_name(value));
+    if properties.contains(&("powered", "true"))
+        && facing.map(BlockFace::opposite) == Some(direction)
+    {
+        15
+    } else {
+        0
+    }
+}
+
+impl World {
+    /// The strongest power the block gets from the blocks next to it
+    pub async fn received_power(&self, position: WorldPosition) -> u8 {
+        let pos = position.0;
+        let mut power = 0;
+        for face in FACES {
+            let neighbor = WorldPosition(pos.add(&face.to_offset()));
+            if let Ok(state_id) = self.get_block_state_id(neighbor).await {
+                power = power.max(emitted_power_to(state_id, face.opposite()));
+            }
+        }
+        power
+    }
+
+    pub async fn is_receiving_power(&self, position: WorldPosition) -> bool {
+        self.received_power(position).await > 0
+    }
+
+    /// Whether the block is powered through any face but the given one
+    pub async fn is_receiving_power_except(
+        &self,
+        position: WorldPosition,
+        except: BlockFace,
+    ) -> bool {
+        let pos = position.0;
+        for face in FACES {
+            if face == except {
+                continue;
+            }
+            let neighbor = WorldPosition(pos.add(&face.to_offset()));
+            if let Ok(state_id) = self.get_block_state_id(neighbor).await {
+                if emitted_power_to(state_id, face.opposite()) > 0 {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Whether the block or the one above it is powered. Dispensers, droppers and pistons react
+    /// to both like in vanilla
+    pub async fn is_receiving_power_or_above(&self, position: WorldPosition) -> bool {
+        let pos = position.0;
+        self.is_receiving_power(position).await
+            || self
+                .is_receiving_power(WorldPosition(Vector3::new(pos.x, pos.y + 1, pos.z)))
+                .await
+    }
+}
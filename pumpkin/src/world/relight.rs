@@ -0,0 +1,144 @@
+//! Chunks are lit again on the light engine of their level, see [`pumpkin_world::chunk::light`].
+//! Their new light is sent to the players seeing them when the tick ends, and `/relight` lights
+//! the chunks around a player again, e.g. after their light was broken by an external editor.
+
+use std::{
+    collections::HashSet,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use pumpkin_core::{math::vector2::Vector2, text::TextComponent};
+use pumpkin_protocol::client::play::CLightUpdate;
+
+use crate::entity::{player::Player, player_set::PlayerSet};
+
+use super::{block_changes::sees_chunk, World};
+
+const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The chunks lit since the last tick
+#[derive(Default)]
+pub struct RelitChunks {
+    chunks: parking_lot::Mutex<HashSet<Vector2<i32>>>,
+}
+
+impl RelitChunks {
+    pub fn push(&self, chunk: Vector2<i32>) {
+        self.chunks.lock().insert(chunk);
+    }
+
+    fn take(&self) -> HashSet<Vector2<i32>> {
+        std::mem::take(&mut *self.chunks.lock())
+    }
+}
+
+/// The chunks in the square around the center
+fn positions(center: Vector2<i32>, radius: u32) -> Vec<Vector2<i32>> {
+    let radius = i32::try_from(radius).unwrap_or(i32::MAX);
+    (-radius..=radius)
+        .flat_map(|x| (-radius..=radius).map(move |z| Vector2::new(center.x + x, center.z + z)))
+        .collect()
+}
+
+impl World {
+    /// Sends the light of the chunks lit since the last time to the players seeing them
+    pub async fn flush_relit_chunks(&self) {
+        let chunks = self.relit_chunks.take();
+        if chunks.is_empty() {
+            return;
+        }
+        let players = self.players().await;
+        for chunk in chunks {
+            let viewers = players
+                .iter()
+                .filter(|player| sees_chunk(player, chunk))
+                .cloned()
+                .collect::<PlayerSet>();
+            if viewers.is_empty() {
+                continue;
+            }
+            let Some(data) = self.level.get_loaded_chunk(chunk) else {
+                continue;
+            };
+            let light = data.read().await.light.clone();
+            if let Some(light) = light {
+                viewers.send_packet(&CLightUpdate::new(chunk, &light)).await;
+            }
+        }
+    }
+
+    /// Lights the chunks in the radius around the center again, chunks which are not loaded are
+    /// read for it and saved afterwards. The viewer sees the progress above their hotbar. Returns
+    /// how many chunks are lit
+    #[must_use]
+    pub fn relight(
+        self: &Arc<Self>,
+        center: Vector2<i32>,
+        radius: u32,
+        viewer: Option<Arc<Player>>,
+    ) -> usize {
+        let chunks = positions(center, radius);
+        let total = chunks.len();
+        let world = self.clone();
+        tokio::spawn(async move {
+            let start = Instant::now();
+            let (handles, mut receiver) = world.receive_chunks(&chunks);
+            let mut queued = Vec::with_capacity(handles.len());
+            for _ in &handles {
+                let Some(chunk) = receiver.recv().await else {
+                    break;
+                };
+                let position = chunk.read().await.position;
+                queued.push(world.level.light_engine().queue_and_notify(position, chunk));
+            }
+
+            let mut last_report = Instant::now();
+            for (done, lit) in queued.into_iter().enumerate() {
+                let _ = lit.await;
+                if last_report.elapsed() >= REPORT_INTERVAL {
+                    last_report = Instant::now();
+                    if let Some(viewer) = &viewer {
+                        viewer
+                            .send_action_bar(TextComponent::text_string(format!(
+                                "Relit {}/{total} chunks",
+                                done + 1
+                            )))
+                            .await;
+                    }
+                }
+            }
+
+            let level = world.level.clone();
+            let saved = tokio::task::spawn_blocking(move || {
+                for chunk in &chunks {
+                    if let Some(data) = level.get_loaded_chunk(*chunk) {
+                        level.write_chunk((*chunk, data));
+                    }
+                }
+                level.sync_chunks();
+                let unwatched = chunks
+                    .into_iter()
+                    .filter(|chunk| level.should_pop_chunk(chunk))
+                    .collect::<Vec<_>>();
+                level.clean_chunks(&unwatched);
+            })
+            .await;
+            if let Err(err) = saved {
+                log::error!("Failed to save relit chunks: {err}");
+            }
+
+            let message = format!(
+                "Relit {total} chunks in {:.1}s",
+                start.elapsed().as_secs_f64()
+            );
+            log::info!("{message}");
+            if let Some(viewer) = viewer {
+                viewer
+                    .send_system_message(&TextComponent::text_string(message))
+                    .await;
+            }
+        });
+        total
+    }
+}
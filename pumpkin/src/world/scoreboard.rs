@@ -1,17 +1,22 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use pumpkin_core::text::TextComponent;
+use pumpkin_core::text::{color::NamedColor, TextComponent};
 use pumpkin_protocol::{
-    client::play::{CDisplayObjective, CUpdateObjectives, CUpdateScore, RenderType},
+    client::play::{
+        CDisplayObjective, CUpdateObjectives, CUpdateScore, CUpdateTeams, RenderType, TeamInfo,
+        TeamMethod,
+    },
     NumberFormat, VarInt,
 };
 
+use crate::entity::player::Player;
+
 use super::World;
 
 #[derive(Default)]
 pub struct Scoreboard {
     objectives: HashMap<String, ScoreboardObjective<'static>>,
-    //  teams: HashMap<String, Team>,
+    teams: HashMap<String, Team>,
 }
 
 impl Scoreboard {
@@ -19,6 +24,7 @@ impl Scoreboard {
     pub fn new() -> Self {
         Self {
             objectives: HashMap::new(),
+            teams: HashMap::new(),
         }
     }
 
@@ -67,12 +73,124 @@ impl Scoreboard {
             .await;
     }
 
-    // pub fn add_team(&mut self, name: String) {
-    //     if self.teams.contains_key(&name) {
-    //         // Maybe make this an error ?
-    //         log::warn!("Tried to create Team which does already exist, {}", name);
-    //     }
-    // }
+    /// Creates a team, returns false if there is one with the name already
+    pub fn add_team(&mut self, name: String, display_name: String) -> bool {
+        if self.teams.contains_key(&name) {
+            return false;
+        }
+        self.teams.insert(
+            name,
+            Team {
+                display_name,
+                color: None,
+                see_friendly_invisibles: true,
+                members: HashSet::new(),
+            },
+        );
+        true
+    }
+
+    pub fn remove_team(&mut self, name: &str) -> Option<Team> {
+        self.teams.remove(name)
+    }
+
+    #[must_use]
+    pub fn team(&self, name: &str) -> Option<&Team> {
+        self.teams.get(name)
+    }
+
+    pub fn team_mut(&mut self, name: &str) -> Option<&mut Team> {
+        self.teams.get_mut(name)
+    }
+
+    #[must_use]
+    pub const fn teams(&self) -> &HashMap<String, Team> {
+        &self.teams
+    }
+
+    /// The name of the team the member is on. Members are player names, like in vanilla
+    #[must_use]
+    pub fn team_of(&self, member: &str) -> Option<&str> {
+        self.teams
+            .iter()
+            .find(|(_, team)| team.members.contains(member))
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Puts the member on the team, they leave the team they were on before. Returns false if
+    /// there is no such team
+    pub fn join_team(&mut self, name: &str, member: &str) -> bool {
+        if !self.teams.contains_key(name) {
+            return false;
+        }
+        self.leave_team(member);
+        if let Some(team) = self.teams.get_mut(name) {
+            team.members.insert(member.to_string());
+        }
+        true
+    }
+
+    /// Takes the member off their team, returns false if they were on none
+    pub fn leave_team(&mut self, member: &str) -> bool {
+        self.teams
+            .values_mut()
+            .any(|team| team.members.remove(member))
+    }
+
+    /// The team of each member on a team whose members see their invisible teammates
+    #[must_use]
+    pub fn friendly_invisible_teams(&self) -> HashMap<String, String> {
+        self.teams
+            .iter()
+            .filter(|(_, team)| team.see_friendly_invisibles)
+            .flat_map(|(name, team)| {
+                team.members
+                    .iter()
+                    .map(move |member| (member.clone(), name.clone()))
+            })
+            .collect()
+    }
+
+    /// Sends every team to a player who joined
+    pub async fn send_teams(&self, player: &Player) {
+        for (name, team) in &self.teams {
+            let members: Vec<_> = team.members().collect();
+            player
+                .client
+                .send_packet(&CUpdateTeams::new(
+                    name,
+                    TeamMethod::Create(team.info(), &members),
+                ))
+                .await;
+        }
+    }
+}
+
+pub struct Team {
+    pub display_name: String,
+    /// Members glow and show their name in the color
+    pub color: Option<NamedColor>,
+    pub see_friendly_invisibles: bool,
+    members: HashSet<String>,
+}
+
+impl Team {
+    #[must_use]
+    pub fn members(&self) -> impl Iterator<Item = &str> {
+        self.members.iter().map(String::as_str)
+    }
+
+    /// What the clients have to know about the team
+    #[must_use]
+    pub fn info(&self) -> TeamInfo<'_> {
+        TeamInfo {
+            display_name: TextComponent::text(&self.display_name),
+            // friendly fire isn't prevented
+            friendly_fire: true,
+            see_friendly_invisibles: self.see_friendly_invisibles,
+            color: self.color,
+        }
+    }
 }
 
 pub struct ScoreboardObjective<'a> {
@@ -0,0 +1,90 @@
+//! Stray cats come to villages with enough beds, once a minute one may show up near a player.
+
+use std::sync::{
+    atomic::{AtomicI32, Ordering},
+    Arc,
+};
+
+use async_trait::async_trait;
+use pumpkin_config::SpawnersConfig;
+use pumpkin_entity::entity_type::EntityType;
+use pumpkin_world::level_data::SpawnerTimers;
+
+use crate::{
+    entity::mob::{cat::Cat, villager::village_beds},
+    world::World,
+};
+
+use super::{random_offset, random_player, spawn_spot, spawns_mobs, SpecialSpawner};
+
+const INTERVAL: i32 = 1_200;
+/// Cats show up 8 to 32 blocks away from the player
+const MIN_DISTANCE: f64 = 8.0;
+const MAX_DISTANCE: f64 = 32.0;
+const VERTICAL: i32 = 16;
+/// Villages need five beds for cats, and get no more than five
+const MIN_BEDS: usize = 5;
+const MAX_CATS: usize = 5;
+const CAT_RANGE: f64 = 48.0;
+
+pub(super) struct CatSpawner {
+    /// Ticks until it tries again
+    timer: AtomicI32,
+}
+
+impl CatSpawner {
+    pub(super) fn new(timers: &SpawnerTimers) -> Self {
+        Self {
+            timer: AtomicI32::new(timers.cat),
+        }
+    }
+}
+
+#[async_trait]
+impl SpecialSpawner for CatSpawner {
+    fn enabled(&self, config: &SpawnersConfig) -> bool {
+        config.cats
+    }
+
+    async fn tick(&self, world: &Arc<World>) {
+        if self.timer.fetch_sub(1, Ordering::Relaxed) > 1 {
+            return;
+        }
+        self.timer.store(INTERVAL, Ordering::Relaxed);
+        if !spawns_mobs(world).await {
+            return;
+        }
+        let Some(player) = random_player(world).await else {
+            return;
+        };
+        let spot = random_offset(
+            player.living_entity.entity.pos.load(),
+            MIN_DISTANCE,
+            MAX_DISTANCE,
+        );
+        if village_beds(world, spot).await < MIN_BEDS {
+            return;
+        }
+        let cats = world
+            .entities
+            .lock()
+            .await
+            .values()
+            .map(|entity| entity.get_entity())
+            .filter(|entity| {
+                entity.entity_type == EntityType::Cat
+                    && entity.pos.load().sub(&spot).length() <= CAT_RANGE
+            })
+            .count();
+        if cats >= MAX_CATS {
+            return;
+        }
+        if let Some(position) = spawn_spot(world, spot, 0, VERTICAL).await {
+            Cat::spawn(world, position, false).await;
+        }
+    }
+
+    fn save(&self, timers: &mut SpawnerTimers) {
+        timers.cat = self.timer.load(Ordering::Relaxed);
+    }
+}
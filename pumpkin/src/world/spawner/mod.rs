@@ -0,0 +1,140 @@
+//! The mobs which come to the world by themselves now and then, besides the ones spawning in the
+//! dark: pillager patrols, zombie sieges, wandering traders, village cats and phantoms. Each of
+//! them can be turned off in the world's config, their timers are saved in `level.dat`.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use pumpkin_config::SpawnersConfig;
+use pumpkin_core::{
+    math::{boundingbox::BoundingBoxSize, position::WorldPosition, vector3::Vector3},
+    GameMode,
+};
+use pumpkin_world::{
+    game_rules::{GameRule, DO_MOB_SPAWNING},
+    level_data::SpawnerTimers,
+};
+use rand::{seq::IteratorRandom, thread_rng, Rng};
+
+use crate::entity::{mob::find_spawn_position, player::Player};
+
+use super::World;
+
+mod cat;
+mod patrol;
+mod phantom;
+mod siege;
+mod wandering_trader;
+
+/// Room for the biggest of the mobs spawned here, trader llamas
+const ROOM: BoundingBoxSize = BoundingBoxSize {
+    width: 0.9,
+    height: 1.95,
+};
+/// How many columns are tried to find a spot for a mob
+const SPAWN_ATTEMPTS: u32 = 10;
+
+#[async_trait]
+trait SpecialSpawner: Send + Sync {
+    fn enabled(&self, config: &SpawnersConfig) -> bool;
+
+    async fn tick(&self, world: &Arc<World>);
+
+    /// Writes the timers to keep when the world is saved
+    fn save(&self, _timers: &mut SpawnerTimers) {}
+}
+
+pub struct SpecialSpawners {
+    spawners: Vec<Box<dyn SpecialSpawner>>,
+}
+
+impl SpecialSpawners {
+    /// The spawners with the saved timers, new worlds start with fresh ones
+    #[must_use]
+    pub fn new(saved: Option<SpawnerTimers>) -> Self {
+        let timers = saved.unwrap_or(SpawnerTimers {
+            patrol: 0,
+            cat: 0,
+            phantom: 0,
+            wandering_trader: wandering_trader::SPAWN_DELAY,
+            wandering_trader_chance: wandering_trader::MIN_CHANCE,
+        });
+        Self {
+            spawners: vec![
+                Box::new(patrol::PatrolSpawner::new(&timers)),
+                Box::new(siege::SiegeSpawner::default()),
+                Box::new(wandering_trader::WanderingTraderSpawner::new(&timers)),
+                Box::new(cat::CatSpawner::new(&timers)),
+                Box::new(phantom::PhantomSpawner::new(&timers)),
+            ],
+        }
+    }
+
+    pub async fn tick(&self, world: &Arc<World>) {
+        for spawner in &self.spawners {
+            if spawner.enabled(&world.config.spawners) {
+                spawner.tick(world).await;
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn timers(&self) -> SpawnerTimers {
+        let mut timers = SpawnerTimers {
+            patrol: 0,
+            cat: 0,
+            phantom: 0,
+            wandering_trader: 0,
+            wandering_trader_chance: 0,
+        };
+        for spawner in &self.spawners {
+            spawner.save(&mut timers);
+        }
+        timers
+    }
+}
+
+async fn rule(world: &World, rule: &GameRule<bool>) -> bool {
+    world.game_rules.read().await.get(rule)
+}
+
+async fn spawns_mobs(world: &World) -> bool {
+    rule(world, &DO_MOB_SPAWNING).await
+}
+
+/// A random player of the world which isn't spectating
+async fn random_player(world: &World) -> Option<Arc<Player>> {
+    world
+        .players()
+        .await
+        .into_iter()
+        .filter(|player| player.gamemode.load() != GameMode::Spectator)
+        .choose(&mut thread_rng())
+}
+
+/// A random spot between the distances away from the position, at the same height
+fn random_offset(position: Vector3<f64>, min: f64, max: f64) -> Vector3<f64> {
+    let mut rng = thread_rng();
+    let angle = rng.gen_range(0.0..std::f64::consts::TAU);
+    let distance = rng.gen_range(min..=max);
+    Vector3::new(
+        position.x + angle.cos() * distance,
+        position.y,
+        position.z + angle.sin() * distance,
+    )
+}
+
+/// A spot on the ground near the position, within the blocks horizontally and vertically
+async fn spawn_spot(
+    world: &World,
+    position: Vector3<f64>,
+    horizontal: i32,
+    vertical: i32,
+) -> Option<Vector3<f64>> {
+    let block = WorldPosition(Vector3::new(
+        position.x.floor() as i32,
+        position.y.floor() as i32,
+        position.z.floor() as i32,
+    ));
+    find_spawn_position(world, block, SPAWN_ATTEMPTS, horizontal, vertical, &ROOM).await
+}
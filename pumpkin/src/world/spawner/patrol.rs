@@ -0,0 +1,118 @@
+//! From the fifth day on a pillager patrol now and then shows up by day near a player who isn't
+//! in a village. The harder the difficulty the bigger the patrol.
+
+use std::sync::{
+    atomic::{AtomicI32, Ordering},
+    Arc,
+};
+
+use async_trait::async_trait;
+use pumpkin_config::SpawnersConfig;
+use pumpkin_core::Difficulty;
+use pumpkin_world::{game_rules::DO_PATROL_SPAWNING, level_data::SpawnerTimers};
+use rand::{thread_rng, Rng};
+
+use crate::{
+    entity::mob::{
+        pillager::{Patrol, Pillager},
+        villager::village_beds,
+    },
+    world::{time::DAY_TICKS, World},
+};
+
+use super::{random_offset, random_player, rule, spawn_spot, spawns_mobs, SpecialSpawner};
+
+const INTERVAL: i32 = 12_000;
+const MAX_EXTRA_INTERVAL: i32 = 1_200;
+const FIRST_DAY: i64 = 5;
+/// One in five tries brings a patrol
+const CHANCE: u32 = 5;
+/// Patrols show up 24 to 48 blocks away from the player
+const MIN_DISTANCE: f64 = 24.0;
+const MAX_DISTANCE: f64 = 48.0;
+/// The patrol's pillagers spawn around their captain
+const SPREAD: i32 = 4;
+const VERTICAL: i32 = 16;
+
+pub(super) struct PatrolSpawner {
+    /// Ticks until it tries again
+    timer: AtomicI32,
+}
+
+impl PatrolSpawner {
+    pub(super) fn new(timers: &SpawnerTimers) -> Self {
+        Self {
+            timer: AtomicI32::new(timers.patrol),
+        }
+    }
+
+    /// Spawns the captain and the rest of the patrol around it
+    async fn spawn_patrol(world: &Arc<World>) {
+        let Some(player) = random_player(world).await else {
+            return;
+        };
+        let player_pos = player.living_entity.entity.pos.load();
+        if village_beds(world, player_pos).await > 0 {
+            return;
+        }
+        let center = random_offset(player_pos, MIN_DISTANCE, MAX_DISTANCE);
+        let Some(captain_pos) = spawn_spot(world, center, 0, VERTICAL).await else {
+            return;
+        };
+        let captain = Pillager::spawn(
+            world,
+            captain_pos,
+            Some(Patrol {
+                captain: None,
+                destination: captain_pos,
+            }),
+        )
+        .await;
+        let patrol = Patrol {
+            captain: Some(captain.living_entity.entity.entity_id),
+            destination: captain_pos,
+        };
+        // one more pillager for each difficulty level
+        for _ in 0..world.config.difficulty as u32 {
+            if let Some(position) = spawn_spot(world, captain_pos, SPREAD, VERTICAL).await {
+                Pillager::spawn(world, position, Some(patrol)).await;
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl SpecialSpawner for PatrolSpawner {
+    fn enabled(&self, config: &SpawnersConfig) -> bool {
+        config.patrols
+    }
+
+    async fn tick(&self, world: &Arc<World>) {
+        if self.timer.fetch_sub(1, Ordering::Relaxed) > 1 {
+            return;
+        }
+        let (extra, spawn) = {
+            let mut rng = thread_rng();
+            (
+                rng.gen_range(0..MAX_EXTRA_INTERVAL),
+                rng.gen_ratio(1, CHANCE),
+            )
+        };
+        self.timer.store(INTERVAL + extra, Ordering::Relaxed);
+        let day = world.time.day_time() / DAY_TICKS;
+        if day < FIRST_DAY
+            || !world.is_day()
+            || !spawn
+            || world.config.difficulty == Difficulty::Peaceful
+        {
+            return;
+        }
+        if spawns_mobs(world).await && rule(world, &DO_PATROL_SPAWNING).await {
+            Self::spawn_patrol(world).await;
+        }
+    }
+
+    fn save(&self, timers: &mut SpawnerTimers) {
+        timers.patrol = self.timer.load(Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,104 @@
+//! At night phantoms come for the players under the open sky who haven't rested for three days.
+//! The longer they didn't rest the likelier they come, more of them on harder difficulties.
+
+use std::sync::{
+    atomic::{AtomicI32, Ordering},
+    Arc,
+};
+
+use async_trait::async_trait;
+use pumpkin_config::SpawnersConfig;
+use pumpkin_core::{
+    math::{position::WorldPosition, vector3::Vector3},
+    Difficulty, GameMode,
+};
+use pumpkin_world::{game_rules::DO_INSOMNIA, level_data::SpawnerTimers};
+use rand::{thread_rng, Rng};
+
+use crate::{
+    entity::mob::phantom::{Phantom, INSOMNIA_TICKS},
+    world::World,
+};
+
+use super::{rule, spawns_mobs, SpecialSpawner};
+
+/// Phantoms try again every one to two minutes
+const MIN_INTERVAL: i32 = 1_200;
+const MAX_INTERVAL: i32 = 2_400;
+/// They spawn 20 to 34 blocks above the player and up to 10 blocks to the side
+const MIN_HEIGHT: f64 = 20.0;
+const MAX_HEIGHT: f64 = 34.0;
+const SPREAD: f64 = 10.0;
+
+pub(super) struct PhantomSpawner {
+    /// Ticks until it tries again
+    timer: AtomicI32,
+}
+
+impl PhantomSpawner {
+    pub(super) fn new(timers: &SpawnerTimers) -> Self {
+        Self {
+            timer: AtomicI32::new(timers.phantom),
+        }
+    }
+}
+
+#[async_trait]
+impl SpecialSpawner for PhantomSpawner {
+    fn enabled(&self, config: &SpawnersConfig) -> bool {
+        config.phantoms
+    }
+
+    async fn tick(&self, world: &Arc<World>) {
+        if self.timer.fetch_sub(1, Ordering::Relaxed) > 1 {
+            return;
+        }
+        let interval = thread_rng().gen_range(MIN_INTERVAL..=MAX_INTERVAL);
+        self.timer.store(interval, Ordering::Relaxed);
+        if world.is_day()
+            || world.config.difficulty == Difficulty::Peaceful
+            || !spawns_mobs(world).await
+            || !rule(world, &DO_INSOMNIA).await
+        {
+            return;
+        }
+        for player in world.players().await {
+            if !matches!(
+                player.gamemode.load(),
+                GameMode::Survival | GameMode::Adventure
+            ) {
+                continue;
+            }
+            let insomnia = player.ticks_since_rest();
+            if insomnia < INSOMNIA_TICKS || thread_rng().gen_range(0..insomnia) < INSOMNIA_TICKS {
+                continue;
+            }
+            let pos = player.living_entity.entity.pos.load();
+            let head = WorldPosition(Vector3::new(
+                pos.x.floor() as i32,
+                pos.y.floor() as i32 + 1,
+                pos.z.floor() as i32,
+            ));
+            if !world.sees_sky(head).await {
+                continue;
+            }
+            let (count, position) = {
+                let mut rng = thread_rng();
+                let count = 1 + rng.gen_range(0..=world.config.difficulty as u32);
+                let position = Vector3::new(
+                    pos.x + rng.gen_range(-SPREAD..=SPREAD),
+                    pos.y + rng.gen_range(MIN_HEIGHT..=MAX_HEIGHT),
+                    pos.z + rng.gen_range(-SPREAD..=SPREAD),
+                );
+                (count, position)
+            };
+            for _ in 0..count {
+                Phantom::spawn(world, position).await;
+            }
+        }
+    }
+
+    fn save(&self, timers: &mut SpawnerTimers) {
+        timers.phantom = self.timer.load(Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,93 @@
+//! Once a night there is a small chance that zombies besiege the village a player is in. Twenty of
+//! them come from a spot at the edge of the village, one per tick.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use pumpkin_config::SpawnersConfig;
+use pumpkin_core::{math::vector3::Vector3, Difficulty};
+use rand::{thread_rng, Rng};
+
+use crate::{
+    entity::mob::{villager::village_beds, zombie::Zombie},
+    world::World,
+};
+
+use super::{random_offset, random_player, spawn_spot, spawns_mobs, SpecialSpawner};
+
+/// One in ten nights has a siege
+const CHANCE: u32 = 10;
+const ZOMBIES: u32 = 20;
+/// The zombies come from this far away from the player
+const DISTANCE: f64 = 32.0;
+/// Tries to find a spot in the village to come from
+const ATTEMPTS: u32 = 10;
+const SPREAD: i32 = 2;
+const VERTICAL: i32 = 16;
+
+#[derive(Default)]
+struct SiegeState {
+    /// Whether this night's siege was tried already
+    tried: bool,
+    /// Where the zombies come from, with how many are still to come
+    siege: Option<(Vector3<f64>, u32)>,
+}
+
+#[derive(Default)]
+pub(super) struct SiegeSpawner {
+    state: parking_lot::Mutex<SiegeState>,
+}
+
+impl SiegeSpawner {
+    /// A spot in the village of a random player for the zombies to come from
+    async fn find_siege(world: &World) -> Option<Vector3<f64>> {
+        let player = random_player(world).await?;
+        let player_pos = player.living_entity.entity.pos.load();
+        if village_beds(world, player_pos).await == 0 {
+            return None;
+        }
+        for _ in 0..ATTEMPTS {
+            let spot = random_offset(player_pos, DISTANCE, DISTANCE);
+            if village_beds(world, spot).await > 0 {
+                return Some(spot);
+            }
+        }
+        None
+    }
+}
+
+#[async_trait]
+impl SpecialSpawner for SiegeSpawner {
+    fn enabled(&self, config: &SpawnersConfig) -> bool {
+        config.sieges
+    }
+
+    async fn tick(&self, world: &Arc<World>) {
+        if world.is_day() {
+            *self.state.lock() = SiegeState::default();
+            return;
+        }
+        let (tried, siege) = {
+            let state = self.state.lock();
+            (state.tried, state.siege)
+        };
+        if let Some((spot, remaining)) = siege {
+            self.state.lock().siege = (remaining > 1).then_some((spot, remaining - 1));
+            if let Some(position) = spawn_spot(world, spot, SPREAD, VERTICAL).await {
+                Zombie::spawn(world, position).await;
+            }
+            return;
+        }
+        if tried {
+            return;
+        }
+        self.state.lock().tried = true;
+        let siege = thread_rng().gen_ratio(1, CHANCE);
+        if !siege || world.config.difficulty == Difficulty::Peaceful || !spawns_mobs(world).await {
+            return;
+        }
+        if let Some(spot) = Self::find_siege(world).await {
+            self.state.lock().siege = Some((spot, ZOMBIES));
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! Every day there is a chance that a wandering trader shows up near a player with two llamas.
+//! The chance grows each day none came, the trader leaves again after two days.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use pumpkin_config::SpawnersConfig;
+use pumpkin_world::{game_rules::DO_TRADER_SPAWNING, level_data::SpawnerTimers};
+use rand::{thread_rng, Rng};
+
+use crate::{
+    entity::mob::{trader_llama::TraderLlama, wandering_trader::WanderingTrader},
+    world::World,
+};
+
+use super::{random_player, rule, spawn_spot, SpecialSpawner};
+
+/// Ticks between the tries
+pub(super) const SPAWN_DELAY: i32 = 24_000;
+/// The chance in percent, it starts at the minimum and grows by a step each try until a trader
+/// comes
+pub(super) const MIN_CHANCE: i32 = 25;
+const CHANCE_STEP: i32 = 25;
+const MAX_CHANCE: i32 = 75;
+/// The delay is counted down once a minute
+const CHECK_INTERVAL: i32 = 1_200;
+/// One in ten traders doesn't make it
+const FAIL_CHANCE: u32 = 10;
+const DESPAWN_DELAY: u32 = 48_000;
+const LLAMAS: u32 = 2;
+/// Traders show up within 48 blocks of the player, their llamas within 4 blocks of them
+const HORIZONTAL: i32 = 48;
+const LLAMA_HORIZONTAL: i32 = 4;
+const VERTICAL: i32 = 16;
+
+struct TraderTimers {
+    /// Ticks until the delay is counted down
+    check: i32,
+    delay: i32,
+    chance: i32,
+}
+
+pub(super) struct WanderingTraderSpawner {
+    timers: parking_lot::Mutex<TraderTimers>,
+}
+
+impl WanderingTraderSpawner {
+    pub(super) fn new(timers: &SpawnerTimers) -> Self {
+        Self {
+            timers: parking_lot::Mutex::new(TraderTimers {
+                check: CHECK_INTERVAL,
+                delay: timers.wandering_trader,
+                chance: timers.wandering_trader_chance,
+            }),
+        }
+    }
+
+    /// Spawns a trader heading for a random player, returns whether it came
+    async fn spawn_trader(world: &Arc<World>) -> bool {
+        let Some(player) = random_player(world).await else {
+            return false;
+        };
+        if thread_rng().gen_ratio(1, FAIL_CHANCE) {
+            return false;
+        }
+        let player_pos = player.living_entity.entity.pos.load();
+        let Some(position) = spawn_spot(world, player_pos, HORIZONTAL, VERTICAL).await else {
+            return false;
+        };
+        let trader =
+            WanderingTrader::spawn(world, position, Some(DESPAWN_DELAY), Some(player_pos)).await;
+        let trader_id = trader.living_entity.entity.entity_id;
+        for _ in 0..LLAMAS {
+            if let Some(position) = spawn_spot(world, position, LLAMA_HORIZONTAL, VERTICAL).await {
+                TraderLlama::spawn(world, position, Some(trader_id)).await;
+            }
+        }
+        true
+    }
+}
+
+#[async_trait]
+impl SpecialSpawner for WanderingTraderSpawner {
+    fn enabled(&self, config: &SpawnersConfig) -> bool {
+        config.wandering_traders
+    }
+
+    async fn tick(&self, world: &Arc<World>) {
+        let chance = {
+            let mut timers = self.timers.lock();
+            timers.check -= 1;
+            if timers.check > 0 {
+                return;
+            }
+            timers.check = CHECK_INTERVAL;
+            timers.delay -= CHECK_INTERVAL;
+            if timers.delay > 0 {
+                return;
+            }
+            timers.delay = SPAWN_DELAY;
+            let chance = timers.chance;
+            timers.chance = (timers.chance + CHANCE_STEP).clamp(MIN_CHANCE, MAX_CHANCE);
+            chance
+        };
+        if !rule(world, &DO_TRADER_SPAWNING).await || thread_rng().gen_range(0..100) >= chance {
+            return;
+        }
+        if Self::spawn_trader(world).await {
+            self.timers.lock().chance = MIN_CHANCE;
+        }
+    }
+
+    fn save(&self, timers: &mut SpawnerTimers) {
+        let own = self.timers.lock();
+        timers.wandering_trader = own.delay;
+        timers.wandering_trader_chance = own.chance;
+    }
+}
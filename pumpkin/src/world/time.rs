@@ -0,0 +1,130 @@
+//! The time of the world: how many ticks it ran for and the time of day, which only passes with
+//! `doDaylightCycle` on. Both are saved in `level.dat`, clients are told them every second and keep
+//! the time going in between.
+
+use std::sync::atomic::{AtomicI64, Ordering};
+
+use pumpkin_protocol::client::play::CUpdateTime;
+use pumpkin_world::game_rules::DO_DAYLIGHT_CYCLE;
+
+use crate::entity::player::Player;
+
+use super::World;
+
+pub const DAY_TICKS: i64 = 24_000;
+/// How often clients are told the time
+const SYNC_INTERVAL: i64 = 20;
+/// The sky is darkened less than this while it is day
+const DAY_DARKEN: i32 = 4;
+
+/// How far the sun went around the world, 0 at noon and 0.5 at midnight
+pub(super) fn celestial_angle(day_time: i64) -> f32 {
+    let day = (f64::from(day_time.rem_euclid(DAY_TICKS) as i32) / 24000.0 - 0.25).rem_euclid(1.0);
+    let smoothed = 0.5 - (day * std::f64::consts::PI).cos() / 2.0;
+    ((day * 2.0 + smoothed) / 3.0) as f32
+}
+
+/// How much darker the sky light is than at noon, up to 11 at night
+pub(super) fn sky_darken(angle: f32) -> i32 {
+    let brightness = 1.0 - ((angle * std::f32::consts::PI * 2.0).cos() * 2.0 + 0.5);
+    (brightness.clamp(0.0, 1.0) * 11.0) as i32
+}
+
+pub struct WorldTime {
+    /// Ticks the world ran for
+    age: AtomicI64,
+    day_time: AtomicI64,
+}
+
+impl WorldTime {
+    #[must_use]
+    pub const fn new(age: i64, day_time: i64) -> Self {
+        Self {
+            age: AtomicI64::new(age),
+            day_time: AtomicI64::new(day_time),
+        }
+    }
+
+    pub fn age(&self) -> i64 {
+        self.age.load(Ordering::Relaxed)
+    }
+
+    /// The ticks since the world's first morning, `% DAY_TICKS` is the time of the current day
+    pub fn day_time(&self) -> i64 {
+        self.day_time.load(Ordering::Relaxed)
+    }
+
+    /// Whether the sun is up, mobs like skeletons burn in its light and phantoms come at night
+    pub fn is_day(&self) -> bool {
+        sky_darken(celestial_angle(self.day_time())) < DAY_DARKEN
+    }
+
+    /// Moves the time on by a tick, returns whether the clients have to be told it
+    fn advance(&self, daylight_cycle: bool) -> bool {
+        let age = self.age.fetch_add(1, Ordering::Relaxed) + 1;
+        if daylight_cycle {
+            self.day_time.fetch_add(1, Ordering::Relaxed);
+        }
+        age % SYNC_INTERVAL == 0
+    }
+
+    fn packet(&self, daylight_cycle: bool) -> CUpdateTime {
+        CUpdateTime::new(self.age(), self.day_time(), daylight_cycle)
+    }
+}
+
+impl World {
+    pub(super) async fn tick_time(&self) {
+        let daylight_cycle = self.game_rules.read().await.get(&DO_DAYLIGHT_CYCLE);
+        if self.time.advance(daylight_cycle) {
+            self.broadcast_packet_all(&self.time.packet(daylight_cycle))
+                .await;
+        }
+    }
+
+    /// Tells the player the time when they join the world
+    pub async fn send_time(&self, player: &Player) {
+        let daylight_cycle = self.game_rules.read().await.get(&DO_DAYLIGHT_CYCLE);
+        player
+            .client
+            .send_packet(&self.time.packet(daylight_cycle))
+            .await;
+    }
+
+    /// Whether the sun is up right now
+    pub fn is_day(&self) -> bool {
+        self.time.is_day()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{WorldTime, DAY_TICKS};
+
+    #[test]
+    fn night_falls() {
+        let time = WorldTime::new(0, 0);
+        assert!(time.is_day());
+        for _ in 0..13_000 {
+            time.advance(true);
+        }
+        // phantoms and sieges only come now
+        assert!(!time.is_day());
+        for _ in 13_000..DAY_TICKS {
+            time.advance(true);
+        }
+        assert!(time.is_day());
+        assert_eq!(time.age(), DAY_TICKS);
+        assert_eq!(time.day_time(), DAY_TICKS);
+    }
+
+    #[test]
+    fn day_stops_without_daylight_cycle() {
+        let time = WorldTime::new(0, 6_000);
+        let synced = (0..40).filter(|_| time.advance(false)).count();
+        assert_eq!(synced, 2);
+        assert_eq!(time.age(), 40);
+        assert_eq!(time.day_time(), 6_000);
+        assert!(time.is_day());
+    }
+}
@@ -0,0 +1,128 @@
+//! Vibrations: what happens in the world, like blocks breaking or entities getting hurt, is a game
+//! event which the sculk sensors and wardens nearby pick up. Each kind of event vibrates with its
+//! own frequency, wool blocks in between keep it from getting through.
+
+use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+use pumpkin_entity::EntityId;
+use pumpkin_registry::{is_in, TagCategory};
+use pumpkin_world::block::block_registry::get_block_by_state_id;
+
+use super::World;
+
+/// Something which happened in the world that sculk sensors can hear
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameEvent {
+    Step,
+    ProjectileLand,
+    ProjectileShoot,
+    NoteBlockPlay,
+    EntityDamage,
+    ContainerClose,
+    ContainerOpen,
+    BlockDeactivate,
+    BlockActivate,
+    BlockDestroy,
+    BlockPlace,
+    LightningStrike,
+    EntityDie,
+    /// A sculk sensor heard a player, sculk shriekers nearby shriek
+    SculkSensorTendrilsClicking,
+    Shriek,
+}
+
+impl GameEvent {
+    /// The frequency of the vibration, 1 to 15. Calibrated sculk sensors only hear one. `None`
+    /// for events which don't vibrate, only shriekers and wardens listen to them
+    #[must_use]
+    pub const fn frequency(self) -> Option<u8> {
+        Some(match self {
+            Self::Step => 1,
+            Self::ProjectileLand => 2,
+            Self::ProjectileShoot => 3,
+            Self::EntityDamage => 7,
+            Self::ContainerClose | Self::BlockDeactivate => 9,
+            Self::ContainerOpen | Self::BlockActivate | Self::NoteBlockPlay => 10,
+            Self::BlockDestroy => 12,
+            Self::BlockPlace => 13,
+            Self::LightningStrike => 14,
+            Self::EntityDie => 15,
+            Self::SculkSensorTendrilsClicking | Self::Shriek => return None,
+        })
+    }
+}
+
+fn block_at(point: Vector3<f64>) -> WorldPosition {
+    WorldPosition(Vector3::new(
+        point.x.floor() as i32,
+        point.y.floor() as i32,
+        point.z.floor() as i32,
+    ))
+}
+
+impl World {
+    /// Lets the sculk sensors, shriekers and wardens in range hear the game event
+    pub async fn emit_game_event(&self, event: GameEvent, position: Vector3<f64>) {
+        self.emit_game_event_by(event, position, None).await;
+    }
+
+    /// Like [`Self::emit_game_event`], for an event the entity caused. Shriekers and wardens react
+    /// to who caused it
+    pub async fn emit_game_event_by(
+        &self,
+        event: GameEvent,
+        position: Vector3<f64>,
+        cause: Option<EntityId>,
+    ) {
+        match event {
+            GameEvent::SculkSensorTendrilsClicking => {
+                self.sculk_shriekers_hear(position, cause).await;
+            }
+            _ if event.frequency().is_some() => {
+                self.sculk_sensors_hear(event, position, cause).await;
+            }
+            _ => {}
+        }
+        let mobs: Vec<_> = self
+            .entities
+            .lock()
+            .await
+            .values()
+            .filter(|entity| entity.get_mob().is_some())
+            .cloned()
+            .collect();
+        for entity in mobs {
+            if let Some(mob) = entity.get_mob() {
+                mob.hear(event, position, cause).await;
+            }
+        }
+    }
+
+    /// Whether a wool block between the points keeps vibrations from getting through. The blocks
+    /// the points are in don't count
+    pub async fn is_vibration_occluded(&self, from: Vector3<f64>, to: Vector3<f64>) -> bool {
+        let line = to.sub(&from);
+        let steps = (line.length() * 4.0).ceil() as u32;
+        let (start, end) = (block_at(from), block_at(to));
+        let mut last = start;
+        for step in 1..steps {
+            let block = block_at(from.add(&(line * (f64::from(step) / f64::from(steps)))));
+            if block == last || block == end {
+                continue;
+            }
+            last = block;
+            let occludes = self.get_block_state_id(block).await.is_ok_and(|state_id| {
+                get_block_by_state_id(state_id).is_some_and(|block| {
+                    is_in(
+                        TagCategory::Block,
+                        "minecraft:occludes_vibration_signals",
+                        &block.name,
+                    )
+                })
+            });
+            if occludes {
+                return true;
+            }
+        }
+        false
+    }
+}
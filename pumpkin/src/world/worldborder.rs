@@ -3,6 +3,8 @@ use pumpkin_protocol::client::play::{
     CSetBorderWarningDelay, CSetBorderWarningDistance,
 };
 
+use pumpkin_world::level_data::BorderData;
+
 use crate::client::Client;
 
 use super::World;
@@ -44,6 +46,40 @@ impl Worldborder {
         }
     }
 
+    /// The border as saved in `level.dat`
+    #[must_use]
+    pub fn from_saved(border: &BorderData) -> Self {
+        let mut worldborder = Self::new(
+            border.center_x,
+            border.center_z,
+            border.size,
+            border.lerp_time,
+            border.warning_blocks as i32,
+            border.warning_time as i32,
+        );
+        worldborder.new_diameter = border.lerp_target;
+        worldborder.damage_per_block = border.damage_per_block as f32;
+        worldborder.buffer = border.safe_zone as f32;
+        worldborder
+    }
+
+    /// The border to save in `level.dat`. How far a moving border got isn't known, so it is saved
+    /// at its target
+    #[must_use]
+    pub fn to_saved(&self) -> BorderData {
+        BorderData {
+            center_x: self.center_x,
+            center_z: self.center_z,
+            size: self.new_diameter,
+            lerp_target: self.new_diameter,
+            lerp_time: 0,
+            safe_zone: self.buffer.into(),
+            damage_per_block: self.damage_per_block.into(),
+            warning_blocks: self.warning_blocks.into(),
+            warning_time: self.warning_time.into(),
+        }
+    }
+
     pub async fn init_client(&self, client: &Client) {
         client
             .send_packet(&CInitializeWorldBorder::new(
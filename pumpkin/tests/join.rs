@@ -0,0 +1,124 @@
+//! End-to-end tests starting the real server binary and joining it with the headless test client.
+
+use std::{
+    fs,
+    net::{SocketAddr, TcpListener},
+    path::PathBuf,
+    process::{Child, Command, Stdio},
+    time::Duration,
+};
+
+use pumpkin_protocol::{
+    bytebuf::packet_id::Packet,
+    client::play::{CChunkData, CSyncPlayerPosition},
+};
+use pumpkin_test_client::TestClient;
+
+struct TestServer {
+    process: Child,
+    directory: PathBuf,
+    address: SocketAddr,
+}
+
+impl TestServer {
+    /// Starts the server in its own directory, offline and without encryption so the test client can join.
+    /// `features` is added to its `features.toml`
+    async fn start(name: &str, features: &str) -> Self {
+        let address = TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .expect("Failed to find a free port");
+        let directory = std::env::temp_dir().join(format!("pumpkin-{name}-{}", std::process::id()));
+        fs::create_dir_all(&directory).expect("Failed to create the server directory");
+        fs::write(
+            directory.join("configuration.toml"),
+            format!("server_address = \"{address}\"\nonline_mode = false\nencryption = false\n"),
+        )
+        .unwrap();
+        fs::write(
+            directory.join("features.toml"),
+            format!("[commands]\nuse_console = false\n{features}"),
+        )
+        .unwrap();
+
+        let server = Self {
+            process: Command::new(env!("CARGO_BIN_EXE_pumpkin"))
+                .current_dir(&directory)
+                .stdin(Stdio::null())
+                .spawn()
+                .expect("Failed to start the server"),
+            directory,
+            address,
+        };
+
+        // wait until the server listens, dropping the server kills it if it never does
+        for _ in 0..300 {
+            if tokio::net::TcpStream::connect(address).await.is_ok() {
+                return server;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        panic!("The server did not start listening on {address}");
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+        let _ = fs::remove_dir_all(&self.directory);
+    }
+}
+
+#[tokio::test]
+async fn join_and_move() {
+    let server = TestServer::start("join", "").await;
+
+    let mut client = TestClient::join(server.address, "TestPlayer")
+        .await
+        .expect("Failed to join the server");
+    assert!(client.uuid.is_some());
+
+    // the server teleports the player to the spawn after joining
+    let teleport = client
+        .wait_for(CSyncPlayerPosition::PACKET_ID)
+        .await
+        .expect("The server did not teleport the player");
+    client.handle_play_packet(teleport).await.unwrap();
+    let (x, y, z) = client.position;
+    client.move_to(x + 0.5, y, z).await.unwrap();
+
+    client
+        .idle(Duration::from_secs(2))
+        .await
+        .expect("The client was disconnected while idling");
+}
+
+#[tokio::test]
+async fn join_with_an_older_version() {
+    let server = TestServer::start(
+        "join-767",
+        "[multi_protocol]\nenabled = true\nallowed_versions = [767]\n",
+    )
+    .await;
+
+    let mut client = TestClient::join_as(server.address, "OlderPlayer", 767)
+        .await
+        .expect("Failed to join the server with 1.21.1");
+
+    let teleport = client
+        .wait_for(CSyncPlayerPosition::PACKET_ID)
+        .await
+        .expect("The server did not teleport the player");
+    client.handle_play_packet(teleport).await.unwrap();
+    client
+        .wait_for(CChunkData::PACKET_ID)
+        .await
+        .expect("The server sent no chunks");
+    let (x, y, z) = client.position;
+    client.move_to(x + 0.5, y, z).await.unwrap();
+
+    client
+        .idle(Duration::from_secs(2))
+        .await
+        .expect("The client was disconnected while idling");
+}